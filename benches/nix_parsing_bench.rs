@@ -7,13 +7,27 @@ use codanna::FileId;
 use codanna::parsing::nix::NixParser;
 use codanna::parsing::LanguageParser;
 use codanna::types::SymbolCounter;
-use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
 use std::fs;
 use std::hint::black_box;
+use std::path::Path;
 
 /// Load real Nix files for benchmarking
 fn load_benchmark_files() -> Vec<(String, String)> {
-    let fixtures_dir = std::path::Path::new("tests/fixtures/nix");
+    let mut files = read_nix_files(Path::new("tests/fixtures/nix"));
+
+    // If no files found, create synthetic ones
+    if files.is_empty() {
+        files.push(("synthetic_small.nix".to_string(), generate_nix_code(100)));
+        files.push(("synthetic_medium.nix".to_string(), generate_nix_code(500)));
+        files.push(("synthetic_large.nix".to_string(), generate_nix_code(1000)));
+    }
+
+    files
+}
+
+/// Name and content of the `.nix` files directly in `fixtures_dir`, by name
+fn read_nix_files(fixtures_dir: &Path) -> Vec<(String, String)> {
     let mut files = Vec::new();
 
     if fixtures_dir.exists() {
@@ -30,13 +44,7 @@ fn load_benchmark_files() -> Vec<(String, String)> {
         }
     }
 
-    // If no files found, create synthetic ones
-    if files.is_empty() {
-        files.push(("synthetic_small.nix".to_string(), generate_nix_code(100)));
-        files.push(("synthetic_medium.nix".to_string(), generate_nix_code(500)));
-        files.push(("synthetic_large.nix".to_string(), generate_nix_code(1000)));
-    }
-
+    files.sort();
     files
 }
 
@@ -114,6 +122,43 @@ fn bench_symbol_extraction(c: &mut Criterion) {
     group.finish();
 }
 
+/// Benchmark the nixpkgs idioms: callPackage trees, overlays, NixOS modules
+/// and flake outputs, one file at a time and as a whole
+fn bench_nixpkgs_idioms(c: &mut Criterion) {
+    let files = read_nix_files(Path::new("tests/fixtures/nix/nixpkgs"));
+    if files.is_empty() {
+        return;
+    }
+
+    let mut group = c.benchmark_group("nix_nixpkgs_idioms");
+    group.sample_size(50);
+
+    for (name, content) in &files {
+        group.throughput(Throughput::Bytes(content.len() as u64));
+        group.bench_with_input(BenchmarkId::new("parse", name), content, |b, content| {
+            let mut parser = NixParser::new().unwrap();
+            b.iter(|| {
+                let mut counter = SymbolCounter::new();
+                black_box(parser.parse(content, FileId(1), &mut counter))
+            });
+        });
+    }
+
+    let total_bytes: usize = files.iter().map(|(_, content)| content.len()).sum();
+    group.throughput(Throughput::Bytes(total_bytes as u64));
+    group.bench_function("parse_all", |b| {
+        let mut parser = NixParser::new().unwrap();
+        b.iter(|| {
+            for (_, content) in &files {
+                let mut counter = SymbolCounter::new();
+                black_box(parser.parse(content, FileId(1), &mut counter));
+            }
+        });
+    });
+
+    group.finish();
+}
+
 /// Benchmark parser creation
 fn bench_parser_creation(c: &mut Criterion) {
     c.bench_function("nix_parser_creation", |b| {
@@ -306,6 +351,7 @@ criterion_group!(
     benches,
     bench_parser_creation,
     bench_symbol_extraction,
+    bench_nixpkgs_idioms,
     bench_throughput,
     bench_complex_constructs,
     bench_memory_usage
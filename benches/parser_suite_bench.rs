@@ -0,0 +1,57 @@
+//! Parser Suite Benchmarks
+//!
+//! Runs every language parser over its `examples/<lang>/comprehensive.*`
+//! fixture, so all languages are measured on comparable, real-world input.
+//! Each fixture is reported twice: as symbols/sec (elements) and as
+//! bytes/sec, which is the fairer comparison across languages whose
+//! fixtures differ in symbol density.
+//!
+//! Run a single language with `cargo bench --bench parser_suite_bench -- rust`.
+//! For a quick pass/fail regression gate use the CLI instead:
+//! `codanna benchmark --save-baseline` once, then `codanna benchmark --check`.
+
+use codanna::Settings;
+use codanna::cli::commands::benchmark::{suite_fixtures, suite_parser};
+use codanna::types::{FileId, SymbolCounter};
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use std::hint::black_box;
+use std::path::Path;
+
+fn bench_parser_suite(c: &mut Criterion) {
+    let settings = Settings::default();
+    let fixtures = suite_fixtures(Path::new(env!("CARGO_MANIFEST_DIR")), None);
+
+    for fixture in &fixtures {
+        let language = fixture.language.as_str();
+        let Some(mut parser) = suite_parser(fixture.language, &settings) else {
+            eprintln!("Skipping {language}: parser unavailable");
+            continue;
+        };
+        let file_id = FileId::new(1).expect("Failed to create file ID");
+        let symbol_count = parser
+            .parse(&fixture.code, file_id, &mut SymbolCounter::new())
+            .len();
+
+        let mut group = c.benchmark_group(format!("parser_suite/{language}"));
+        for (unit, throughput) in [
+            ("symbols", Throughput::Elements(symbol_count as u64)),
+            ("bytes", Throughput::Bytes(fixture.code.len() as u64)),
+        ] {
+            group.throughput(throughput);
+            group.bench_with_input(
+                BenchmarkId::new("comprehensive", unit),
+                &fixture.code,
+                |b, code| {
+                    b.iter(|| {
+                        let mut counter = SymbolCounter::new();
+                        black_box(parser.parse(black_box(code), file_id, &mut counter))
+                    });
+                },
+            );
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench_parser_suite);
+criterion_main!(benches);
@@ -0,0 +1,80 @@
+//! Feeds arbitrary source text to every language parser.
+//!
+//! The first input byte picks the language, the rest is the source. Bytes
+//! that aren't valid UTF-8 are replaced, so inputs still exercise multi-byte
+//! characters, which is where `&code[node.byte_range()]` slicing breaks when
+//! a parser computes a range by hand. Besides symbol extraction, the target
+//! runs the relationship and import finders, since they walk the tree with
+//! their own code paths.
+//!
+//! Run from the repository root (needs nightly and `cargo install cargo-fuzz`):
+//!
+//! ```bash
+//! mkdir -p fuzz/corpus/parse_source
+//! cp examples/*/comprehensive.* fuzz/corpus/parse_source/
+//! cargo +nightly fuzz run parse_source
+//! ```
+
+#![no_main]
+
+use codanna::Settings;
+use codanna::parsing::{LanguageParser, get_registry};
+use codanna::types::{FileId, SymbolCounter};
+use libfuzzer_sys::fuzz_target;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+/// Extensions of the languages under test, indexed by the selector byte
+const EXTENSIONS: &[&str] = &[
+    "rs", "py", "ts", "tsx", "js", "php", "go", "c", "cpp", "cs", "java", "gd", "kt", "lua",
+    "swift", "nix",
+];
+
+thread_local! {
+    static PARSERS: RefCell<HashMap<&'static str, Box<dyn LanguageParser>>> =
+        RefCell::new(HashMap::new());
+}
+
+fn create_parser(extension: &str) -> Option<Box<dyn LanguageParser>> {
+    let registry = get_registry().lock().ok()?;
+    registry
+        .get_by_extension(extension)?
+        .create_parser(&Settings::default())
+        .ok()
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&selector, source)) = data.split_first() else {
+        return;
+    };
+    let extension = EXTENSIONS[selector as usize % EXTENSIONS.len()];
+    let code = String::from_utf8_lossy(source);
+
+    PARSERS.with(|parsers| {
+        let mut parsers = parsers.borrow_mut();
+        if !parsers.contains_key(extension) {
+            let Some(parser) = create_parser(extension) else {
+                return;
+            };
+            parsers.insert(extension, parser);
+        }
+        let parser = parsers.get_mut(extension).unwrap();
+
+        let file_id = FileId::new(1).unwrap();
+        let _ = parser.parse(&code, file_id, &mut SymbolCounter::new());
+        let _ = parser.find_calls(&code);
+        let _ = parser.find_method_calls(&code);
+        let _ = parser.find_instantiations(&code);
+        let _ = parser.find_generic_instantiations(&code);
+        let _ = parser.find_operator_calls(&code);
+        let _ = parser.find_throws(&code);
+        let _ = parser.find_catches(&code);
+        let _ = parser.find_implementations(&code);
+        let _ = parser.find_extends(&code);
+        let _ = parser.find_aliases(&code);
+        let _ = parser.find_uses(&code);
+        let _ = parser.find_defines(&code);
+        let _ = parser.find_imports(&code, file_id);
+        let _ = parser.find_variable_types(&code);
+    });
+});
@@ -0,0 +1,246 @@
+//! Churn versus complexity of indexed files.
+//!
+//! Files that change often and are hard to follow are where defects
+//! cluster. [`churn_report`] counts the commits touching each indexed file
+//! from git history and pairs them with an approximate cyclomatic
+//! complexity: one per function or method plus one per branch region the
+//! parse stage would mark conditional (see [`crate::parsing::control_flow`]).
+//! Files above the median on both axes are flagged as hot spots.
+
+use crate::indexing::facade::IndexFacade;
+use crate::indexing::pipeline::stages::parse::{find_conditional_ranges, syntax_tree};
+use crate::io::ExitCode;
+use crate::parsing::LanguageId;
+use crate::{FileId, SymbolKind};
+use git2::{Repository, Sort};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ChurnError {
+    #[error(
+        "No git repository found at {path}\nSuggestion: Run the report from inside a git checkout"
+    )]
+    NoRepository { path: PathBuf },
+
+    #[error("Failed to read git history: {0}")]
+    History(#[from] git2::Error),
+}
+
+impl ChurnError {
+    pub fn exit_code(&self) -> ExitCode {
+        match self {
+            Self::NoRepository { .. } => ExitCode::ConfigError,
+            Self::History(_) => ExitCode::GeneralError,
+        }
+    }
+}
+
+/// Churn and complexity of one file
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FileChurn {
+    pub file_path: String,
+    pub language: Option<String>,
+    /// Commits touching the file
+    pub commits: usize,
+    /// Functions and methods plus branch regions
+    pub complexity: usize,
+    pub functions: usize,
+    /// `commits * complexity`, the ranking key
+    pub score: usize,
+    /// At or above the median on both churn and complexity
+    pub hotspot: bool,
+}
+
+/// Rank indexed files by churn times complexity, riskiest first.
+///
+/// `since` limits history to commits at or after that Unix time. Files
+/// without commits in range are left out.
+pub fn churn_report(
+    facade: &IndexFacade,
+    since: Option<i64>,
+) -> Result<Vec<FileChurn>, ChurnError> {
+    let root = facade
+        .settings()
+        .workspace_root
+        .clone()
+        .unwrap_or_else(|| PathBuf::from("."));
+    let repo =
+        Repository::discover(&root).map_err(|_| ChurnError::NoRepository { path: root.clone() })?;
+    let commits = commit_counts(&repo, since)?;
+
+    let mut files: HashMap<FileId, (String, Option<LanguageId>, usize)> = HashMap::new();
+    let _ = facade.document_index().for_each_symbol(|symbol| {
+        let entry = files
+            .entry(symbol.file_id)
+            .or_insert_with(|| (symbol.file_path.to_string(), symbol.language_id, 0));
+        if matches!(symbol.kind, SymbolKind::Function | SymbolKind::Method) {
+            entry.2 += 1;
+        }
+        Ok(())
+    });
+
+    let root = root.canonicalize().unwrap_or(root);
+    let mut report = Vec::new();
+    for (file_path, language_id, functions) in files.into_values() {
+        let absolute = resolve(&root, &file_path);
+        let Some(&count) = commits.get(&absolute) else {
+            continue;
+        };
+        let branches = match (language_id, std::fs::read_to_string(&absolute)) {
+            (Some(language_id), Ok(source)) => syntax_tree(&source, language_id)
+                .map_or(0, |tree| find_conditional_ranges(&tree, language_id).len()),
+            _ => 0,
+        };
+        report.push(FileChurn {
+            file_path,
+            language: language_id.map(|id| id.as_str().to_string()),
+            commits: count,
+            complexity: functions + branches,
+            functions,
+            score: 0,
+            hotspot: false,
+        });
+    }
+
+    rank(&mut report);
+    Ok(report)
+}
+
+/// Score and flag `files`, then sort them riskiest first
+pub fn rank(files: &mut [FileChurn]) {
+    let churn_median = median(files.iter().map(|file| file.commits).collect());
+    let complexity_median = median(files.iter().map(|file| file.complexity).collect());
+    for file in files.iter_mut() {
+        file.score = file.commits * file.complexity;
+        file.hotspot = file.commits >= churn_median && file.complexity >= complexity_median;
+    }
+    files.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| a.file_path.cmp(&b.file_path))
+    });
+}
+
+/// Render `files` as CSV with a header row
+pub fn to_csv(files: &[FileChurn]) -> String {
+    let mut csv = String::from("file_path,language,commits,complexity,functions,score,hotspot\n");
+    for file in files {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{}\n",
+            csv_field(&file.file_path),
+            csv_field(file.language.as_deref().unwrap_or("")),
+            file.commits,
+            file.complexity,
+            file.functions,
+            file.score,
+            file.hotspot
+        ));
+    }
+    csv
+}
+
+/// Commits per file over the first-parent diffs of non-merge commits
+/// reachable from HEAD, keyed by absolute path
+fn commit_counts(
+    repo: &Repository,
+    since: Option<i64>,
+) -> Result<HashMap<PathBuf, usize>, ChurnError> {
+    let Some(workdir) = repo.workdir() else {
+        return Ok(HashMap::new());
+    };
+    let workdir = workdir
+        .canonicalize()
+        .unwrap_or_else(|_| workdir.to_path_buf());
+    let mut walk = repo.revwalk()?;
+    walk.set_sorting(Sort::TIME)?;
+    walk.push_head()?;
+
+    let mut counts = HashMap::new();
+    for oid in walk {
+        let commit = repo.find_commit(oid?)?;
+        if since.is_some_and(|since| commit.time().seconds() < since) {
+            break;
+        }
+        if commit.parent_count() > 1 {
+            continue;
+        }
+        let parent_tree = match commit.parent(0) {
+            Ok(parent) => Some(parent.tree()?),
+            Err(_) => None,
+        };
+        let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+        for delta in diff.deltas() {
+            if let Some(path) = delta.new_file().path().or(delta.old_file().path()) {
+                *counts.entry(workdir.join(path)).or_insert(0) += 1;
+            }
+        }
+    }
+    Ok(counts)
+}
+
+fn median(mut values: Vec<usize>) -> usize {
+    if values.is_empty() {
+        return 0;
+    }
+    values.sort_unstable();
+    values[values.len() / 2]
+}
+
+pub(crate) fn resolve(root: &Path, path: &str) -> PathBuf {
+    let path = Path::new(path);
+    if path.is_relative() {
+        root.join(path)
+    } else {
+        path.to_path_buf()
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, commits: usize, complexity: usize) -> FileChurn {
+        FileChurn {
+            file_path: path.to_string(),
+            language: Some("rust".to_string()),
+            commits,
+            complexity,
+            functions: complexity,
+            score: 0,
+            hotspot: false,
+        }
+    }
+
+    #[test]
+    fn test_rank_flags_files_high_on_both_axes() {
+        let mut files = vec![
+            file("src/stable.rs", 1, 40),
+            file("src/busy, \"core\".rs", 12, 30),
+            file("src/churny.rs", 15, 2),
+        ];
+        rank(&mut files);
+
+        assert_eq!(files[0].file_path, "src/busy, \"core\".rs");
+        assert_eq!(files[0].score, 360);
+        assert!(files[0].hotspot);
+        assert!(!files[1].hotspot && !files[2].hotspot);
+        assert!(
+            to_csv(&files)
+                .lines()
+                .nth(1)
+                .unwrap()
+                .starts_with("\"src/busy, \"\"core\"\".rs\",rust,12,30,")
+        );
+    }
+}
@@ -0,0 +1,530 @@
+//! Changed code between git revisions.
+//!
+//! [`git_changes`] lists the lines a revision range touches and
+//! [`parse_unified_diff`] those of a patch, such as a pull request's.
+//! [`changed_symbols`] maps them onto the indexed symbols they fall in, and
+//! [`annotate_diff`] adds who calls each, which is what a review bot
+//! comments with. Review routing starts from the same symbols.
+
+use crate::analysis::churn::resolve;
+use crate::indexing::facade::IndexFacade;
+use crate::io::ExitCode;
+use crate::{FileId, Range, Symbol, SymbolId, SymbolKind};
+use git2::{Delta, DiffOptions, Patch, Repository, RevparseMode};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum DiffError {
+    #[error(
+        "No git repository found at {path}\nSuggestion: Run the report from inside a git checkout"
+    )]
+    NoRepository { path: PathBuf },
+
+    #[error("Failed to diff revisions: {0}")]
+    Git(#[from] git2::Error),
+}
+
+impl DiffError {
+    pub fn exit_code(&self) -> ExitCode {
+        match self {
+            Self::NoRepository { .. } => ExitCode::ConfigError,
+            Self::Git(_) => ExitCode::GeneralError,
+        }
+    }
+}
+
+/// The lines a diff touches in one file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChangedFile {
+    /// Path relative to the repository root
+    pub file_path: String,
+    /// Absolute path in the working tree
+    #[serde(skip)]
+    pub path: PathBuf,
+    /// 1-based inclusive line ranges in the new version. A deletion is
+    /// recorded as the line before it, a deleted file as all its lines.
+    pub lines: Vec<(u32, u32)>,
+}
+
+/// Files changed by a revision range
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct GitChanges {
+    /// Root of the working tree
+    #[serde(skip)]
+    pub repository: PathBuf,
+    pub files: Vec<ChangedFile>,
+}
+
+/// A symbol in a diff annotation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DiffSymbol {
+    pub symbol_id: SymbolId,
+    pub name: String,
+    pub kind: SymbolKind,
+    /// Path relative to the repository root
+    pub file_path: String,
+    /// 1-based
+    pub line: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub module_path: Option<String>,
+}
+
+/// A changed symbol and the functions calling it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChangedSymbol {
+    #[serde(flatten)]
+    pub symbol: DiffSymbol,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    pub callers: Vec<DiffSymbol>,
+}
+
+/// The symbols a diff changes, with their callers
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DiffAnnotation {
+    pub changed_files: Vec<ChangedFile>,
+    pub symbols: Vec<ChangedSymbol>,
+}
+
+/// Lines changed by `range` in the repository containing `root`.
+///
+/// A single revision such as `main` compares it with the working tree,
+/// staged changes included. `base..head` compares two revisions and
+/// `base...head` compares `head` with the merge base, as a pull request
+/// would show it.
+pub fn git_changes(root: &Path, range: &str) -> Result<GitChanges, DiffError> {
+    let repo = Repository::discover(root).map_err(|_| DiffError::NoRepository {
+        path: root.to_path_buf(),
+    })?;
+    let Some(workdir) = repo.workdir() else {
+        return Ok(GitChanges::default());
+    };
+    let workdir = workdir
+        .canonicalize()
+        .unwrap_or_else(|_| workdir.to_path_buf());
+
+    let spec = repo.revparse(range)?;
+    let mut options = DiffOptions::new();
+    options.context_lines(0);
+    let diff = match (spec.from(), spec.to()) {
+        (Some(from), Some(to)) => {
+            let base = if spec.mode().contains(RevparseMode::MERGE_BASE) {
+                repo.find_commit(repo.merge_base(from.id(), to.id())?)?
+                    .tree()?
+            } else {
+                from.peel_to_tree()?
+            };
+            repo.diff_tree_to_tree(Some(&base), Some(&to.peel_to_tree()?), Some(&mut options))?
+        }
+        (Some(from), None) => {
+            repo.diff_tree_to_workdir_with_index(Some(&from.peel_to_tree()?), Some(&mut options))?
+        }
+        _ => return Ok(GitChanges::default()),
+    };
+
+    let mut files = Vec::new();
+    for (idx, delta) in diff.deltas().enumerate() {
+        let Some(path) = delta.new_file().path().or(delta.old_file().path()) else {
+            continue;
+        };
+        let lines = if delta.status() == Delta::Deleted {
+            vec![(1, u32::MAX)]
+        } else {
+            let Some(patch) = Patch::from_diff(&diff, idx)? else {
+                continue;
+            };
+            (0..patch.num_hunks())
+                .map(|hunk| {
+                    let (hunk, _) = patch.hunk(hunk)?;
+                    Ok(hunk_lines(hunk.new_start(), hunk.new_lines()))
+                })
+                .collect::<Result<_, git2::Error>>()?
+        };
+        files.push(ChangedFile {
+            file_path: path.to_string_lossy().replace('\\', "/"),
+            path: workdir.join(path),
+            lines,
+        });
+    }
+    files.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+    Ok(GitChanges {
+        repository: workdir,
+        files,
+    })
+}
+
+/// Changed lines of a unified diff, as printed by `git diff` or served as a
+/// pull request patch. Paths are taken relative to `repository`. Context
+/// lines are skipped, so the ranges match [`git_changes`] whatever the
+/// diff's context size.
+pub fn parse_unified_diff(repository: &Path, text: &str) -> Vec<ChangedFile> {
+    let mut parsed: Vec<(String, bool, Vec<u32>)> = Vec::new();
+    let mut old_path = None;
+    let (mut old_left, mut new_left, mut new_line) = (0u32, 0u32, 0u32);
+    // A removed line not replaced by an added one marks the line before it
+    let mut deletion = false;
+    for line in text.lines() {
+        if old_left > 0 || new_left > 0 {
+            let Some((_, _, changed)) = parsed.last_mut() else {
+                break;
+            };
+            match line.as_bytes().first() {
+                Some(b'+') => {
+                    changed.push(new_line);
+                    new_line += 1;
+                    new_left = new_left.saturating_sub(1);
+                    deletion = false;
+                }
+                Some(b'-') => {
+                    old_left = old_left.saturating_sub(1);
+                    deletion = true;
+                }
+                Some(b'\\') => {}
+                _ => {
+                    if deletion {
+                        changed.push(new_line.saturating_sub(1).max(1));
+                        deletion = false;
+                    }
+                    new_line += 1;
+                    old_left = old_left.saturating_sub(1);
+                    new_left = new_left.saturating_sub(1);
+                }
+            }
+            if deletion && old_left == 0 && new_left == 0 {
+                changed.push(new_line.saturating_sub(1).max(1));
+                deletion = false;
+            }
+            continue;
+        }
+
+        if let Some(path) = line.strip_prefix("--- ") {
+            old_path = diff_path(path);
+        } else if let Some(path) = line.strip_prefix("+++ ") {
+            match diff_path(path) {
+                Some(path) => parsed.push((path, false, Vec::new())),
+                None => parsed.extend(old_path.take().map(|path| (path, true, Vec::new()))),
+            }
+        } else if let Some((old_count, new_start, new_count)) =
+            line.strip_prefix("@@ ").and_then(hunk_header)
+        {
+            (old_left, new_left, new_line) = (old_count, new_count, new_start);
+        }
+    }
+
+    let mut files: Vec<ChangedFile> = parsed
+        .into_iter()
+        .map(|(file_path, deleted, mut changed)| {
+            let lines = if deleted {
+                vec![(1, u32::MAX)]
+            } else {
+                changed.sort_unstable();
+                changed.dedup();
+                let mut lines: Vec<(u32, u32)> = Vec::new();
+                for line in changed {
+                    match lines.last_mut() {
+                        Some((_, last)) if *last + 1 == line => *last = line,
+                        _ => lines.push((line, line)),
+                    }
+                }
+                lines
+            };
+            ChangedFile {
+                path: repository.join(&file_path),
+                file_path,
+                lines,
+            }
+        })
+        .collect();
+    files.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+    files
+}
+
+/// Root of the git working tree containing `root`, or `root` itself
+/// outside of one
+pub fn repository_root(root: &Path) -> PathBuf {
+    Repository::discover(root)
+        .ok()
+        .and_then(|repo| repo.workdir().map(Path::to_path_buf))
+        .map(|workdir| workdir.canonicalize().unwrap_or(workdir))
+        .unwrap_or_else(|| root.to_path_buf())
+}
+
+/// The symbols `files` change, with the functions calling each
+pub fn annotate_diff(
+    facade: &IndexFacade,
+    repository: &Path,
+    files: Vec<ChangedFile>,
+) -> DiffAnnotation {
+    let root = workspace_root(facade);
+    let site = |symbol: &Symbol| DiffSymbol {
+        symbol_id: symbol.id,
+        name: symbol.name.to_string(),
+        kind: symbol.kind,
+        file_path: repository_path(&root, repository, &symbol.file_path),
+        line: symbol.range.start_line + 1,
+        module_path: symbol.module_path.as_deref().map(str::to_string),
+    };
+    let symbols = changed_symbols(facade, &files)
+        .iter()
+        .map(|symbol| {
+            let mut callers: Vec<DiffSymbol> = facade
+                .get_calling_functions(symbol.id)
+                .iter()
+                .map(&site)
+                .collect();
+            callers.sort_by(|a, b| {
+                a.file_path
+                    .cmp(&b.file_path)
+                    .then_with(|| a.line.cmp(&b.line))
+            });
+            callers.dedup_by_key(|caller| caller.symbol_id);
+            ChangedSymbol {
+                symbol: site(symbol),
+                signature: symbol.signature.as_deref().map(str::to_string),
+                callers,
+            }
+        })
+        .collect();
+    DiffAnnotation {
+        changed_files: files,
+        symbols,
+    }
+}
+
+/// Indexed symbols the changed lines fall in.
+///
+/// For each changed range, only the innermost symbols overlapping it are
+/// kept: editing a method reports the method, not its class, while an
+/// edit between methods still reports the class.
+pub fn changed_symbols(facade: &IndexFacade, files: &[ChangedFile]) -> Vec<Symbol> {
+    let root = workspace_root(facade);
+    let by_path: HashMap<&Path, &ChangedFile> = files
+        .iter()
+        .map(|file| (file.path.as_path(), file))
+        .collect();
+    let mut candidates: HashMap<FileId, (&ChangedFile, Vec<Symbol>)> = HashMap::new();
+    let _ = facade.document_index().for_each_symbol(|symbol| {
+        let path = resolve(&root, &symbol.file_path);
+        if let Some(&file) = by_path.get(path.as_path()) {
+            if file
+                .lines
+                .iter()
+                .any(|&lines| overlaps(&symbol.range, lines))
+            {
+                candidates
+                    .entry(symbol.file_id)
+                    .or_insert_with(|| (file, Vec::new()))
+                    .1
+                    .push(symbol);
+            }
+        }
+        Ok(())
+    });
+
+    let mut changed: Vec<Symbol> = Vec::new();
+    for (file, symbols) in candidates.into_values() {
+        for &lines in &file.lines {
+            let hits: Vec<&Symbol> = symbols
+                .iter()
+                .filter(|symbol| overlaps(&symbol.range, lines))
+                .collect();
+            for symbol in &hits {
+                let innermost = !hits
+                    .iter()
+                    .any(|other| other.id != symbol.id && contains(&symbol.range, &other.range));
+                if innermost && !changed.iter().any(|seen| seen.id == symbol.id) {
+                    changed.push((*symbol).clone());
+                }
+            }
+        }
+    }
+    changed.sort_by(|a, b| {
+        a.file_path
+            .cmp(&b.file_path)
+            .then_with(|| a.range.start_line.cmp(&b.range.start_line))
+    });
+    changed
+}
+
+/// The workspace root indexed paths are relative to, canonicalized so it
+/// compares with paths from git
+pub(crate) fn workspace_root(facade: &IndexFacade) -> PathBuf {
+    let root = facade
+        .settings()
+        .workspace_root
+        .clone()
+        .unwrap_or_else(|| PathBuf::from("."));
+    root.canonicalize().unwrap_or(root)
+}
+
+/// Path of an indexed file relative to `repository`, with `/` separators
+pub(crate) fn repository_path(root: &Path, repository: &Path, file_path: &str) -> String {
+    let path = resolve(root, file_path);
+    path.strip_prefix(repository)
+        .unwrap_or(&path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+/// Path named on a `---` or `+++` line, without its `a/` or `b/` prefix;
+/// `None` for `/dev/null`
+fn diff_path(field: &str) -> Option<String> {
+    let path = field.split('\t').next()?.trim_end().trim_matches('"');
+    if path == "/dev/null" {
+        return None;
+    }
+    let path = path
+        .strip_prefix("a/")
+        .or_else(|| path.strip_prefix("b/"))
+        .unwrap_or(path);
+    Some(path.to_string())
+}
+
+/// Old line count, new start and new line count of a hunk header, given
+/// without its leading `@@ `
+fn hunk_header(header: &str) -> Option<(u32, u32, u32)> {
+    let mut fields = header.split_whitespace();
+    let old = fields.next()?.strip_prefix('-')?;
+    let new = fields.next()?.strip_prefix('+')?;
+    let span = |range: &str| -> Option<(u32, u32)> {
+        match range.split_once(',') {
+            Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+            None => Some((range.parse().ok()?, 1)),
+        }
+    };
+    let (_, old_count) = span(old)?;
+    let (new_start, new_count) = span(new)?;
+    Some((old_count, new_start, new_count))
+}
+
+/// Inclusive line range of a hunk with `count` new lines from `start`
+fn hunk_lines(start: u32, count: u32) -> (u32, u32) {
+    if count == 0 {
+        let line = start.max(1);
+        (line, line)
+    } else {
+        (start, start + count - 1)
+    }
+}
+
+/// Whether `range` (0-based lines) overlaps the 1-based `lines`
+fn overlaps(range: &Range, (first, last): (u32, u32)) -> bool {
+    range.start_line < last && first <= range.end_line + 1
+}
+
+fn contains(outer: &Range, inner: &Range) -> bool {
+    (outer.start_line, outer.start_column) <= (inner.start_line, inner.start_column)
+        && (inner.end_line, inner.end_column) <= (outer.end_line, outer.end_column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::{IndexAddOption, Signature};
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn commit_all(repo: &Repository, message: &str) {
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"].iter(), IndexAddOption::DEFAULT, None)
+            .unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("Test", "test@example.com").unwrap();
+        let parent = repo.head().ok().map(|head| head.peel_to_commit().unwrap());
+        let parents: Vec<_> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_git_changes() {
+        let temp = TempDir::new().unwrap();
+        let repo = Repository::init(temp.path()).unwrap();
+        fs::write(temp.path().join("lib.rs"), "a\nb\nc\nd\ne\n").unwrap();
+        fs::write(temp.path().join("old.rs"), "x\n").unwrap();
+        commit_all(&repo, "initial");
+
+        fs::write(temp.path().join("lib.rs"), "a\nB\nc\ne\nf\ng\n").unwrap();
+        fs::remove_file(temp.path().join("old.rs")).unwrap();
+        let changes = git_changes(temp.path(), "HEAD").unwrap();
+        let files: Vec<_> = changes
+            .files
+            .iter()
+            .map(|file| (file.file_path.as_str(), file.lines.clone()))
+            .collect();
+        assert_eq!(
+            files,
+            vec![
+                ("lib.rs", vec![(2, 2), (3, 3), (5, 6)]),
+                ("old.rs", vec![(1, u32::MAX)]),
+            ]
+        );
+
+        commit_all(&repo, "edit");
+        let changes = git_changes(temp.path(), "HEAD~1..HEAD").unwrap();
+        assert_eq!(changes.files.len(), 2);
+        assert_eq!(changes.files[0].lines, vec![(2, 2), (3, 3), (5, 6)]);
+        assert_eq!(changes.files[1].lines, vec![(1, u32::MAX)]);
+    }
+
+    #[test]
+    fn test_parse_unified_diff() {
+        let patch = "\
+diff --git a/lib.rs b/lib.rs
+index 9405325..b1e6722 100644
+--- a/lib.rs
++++ b/lib.rs
+@@ -1,5 +1,6 @@
+ a
+-b
++B
+ c
+-d
+ e
++f
++g
+diff --git a/old.rs b/old.rs
+deleted file mode 100644
+--- a/old.rs
++++ /dev/null
+@@ -1 +0,0 @@
+--- x
+";
+        let files: Vec<_> = parse_unified_diff(Path::new("/repo"), patch)
+            .into_iter()
+            .map(|file| (file.file_path, file.path, file.lines))
+            .collect();
+        assert_eq!(
+            files,
+            vec![
+                (
+                    "lib.rs".to_string(),
+                    PathBuf::from("/repo/lib.rs"),
+                    vec![(2, 3), (5, 6)]
+                ),
+                (
+                    "old.rs".to_string(),
+                    PathBuf::from("/repo/old.rs"),
+                    vec![(1, u32::MAX)]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_overlaps_and_contains() {
+        // Lines 3 to 5, 1-based
+        let method = Range::new(2, 4, 4, 5);
+        let class = Range::new(0, 0, 10, 1);
+        assert!(overlaps(&method, (5, 5)));
+        assert!(!overlaps(&method, (6, 8)));
+        assert!(overlaps(&class, (6, 8)));
+        assert!(contains(&class, &method));
+        assert!(!contains(&method, &class));
+    }
+}
@@ -0,0 +1,518 @@
+//! Near-clone detection for functions and methods.
+//!
+//! Each function body is lexed into a normalized token stream: identifiers and
+//! literals collapse to placeholders while keywords and punctuation are kept,
+//! so a copy with renamed variables still matches its original. Overlapping
+//! k-token shingles form a set per function. MinHash signatures bucketed with
+//! LSH banding find candidate pairs without comparing every function against
+//! every other; candidates are then confirmed by the exact Jaccard similarity
+//! of their shingle sets and merged into clusters.
+
+use crate::indexing::facade::IndexFacade;
+use crate::types::LineIndex;
+use crate::{Symbol, SymbolId, SymbolKind};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+/// MinHash values per function
+const SIGNATURE_LEN: usize = 64;
+/// Signature rows per LSH band; 16 bands of 4 catch pairs above ~0.6 reliably
+const BAND_ROWS: usize = 4;
+/// Buckets this crowded hold boilerplate (accessors, trivial wrappers)
+const MAX_BUCKET: usize = 200;
+
+const IDENTIFIER: &str = "$id";
+const LITERAL: &str = "$lit";
+
+/// Words kept verbatim by the normalizer. Structure lives in keywords, so
+/// erasing them would make every short function look alike.
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "case", "catch", "class", "const", "continue", "def",
+    "default", "do", "elif", "else", "except", "false", "finally", "fn", "for", "func", "function",
+    "if", "impl", "in", "let", "loop", "match", "mut", "new", "nil", "None", "null", "pub",
+    "raise", "return", "self", "Self", "static", "struct", "super", "switch", "this", "throw",
+    "true", "try", "var", "while", "with", "yield",
+];
+
+/// Tuning for [`find_duplicates`]
+#[derive(Debug, Clone)]
+pub struct DuplicateOptions {
+    /// Minimum Jaccard similarity of shingle sets, from 0.0 to 1.0
+    pub threshold: f64,
+    /// Functions with fewer normalized tokens are ignored
+    pub min_tokens: usize,
+    /// Tokens per shingle
+    pub shingle_size: usize,
+    /// Restrict to one language (e.g. "rust")
+    pub language: Option<String>,
+}
+
+impl Default for DuplicateOptions {
+    fn default() -> Self {
+        Self {
+            threshold: 0.85,
+            min_tokens: 50,
+            shingle_size: 5,
+            language: None,
+        }
+    }
+}
+
+/// One function in a clone cluster
+#[derive(Debug, Clone, Serialize)]
+pub struct CloneMember {
+    pub symbol_id: SymbolId,
+    pub name: String,
+    pub kind: SymbolKind,
+    pub file_path: String,
+    /// 1-based, inclusive
+    pub start_line: u32,
+    pub end_line: u32,
+    /// Normalized token count of the body
+    pub tokens: usize,
+}
+
+impl CloneMember {
+    fn from_symbol(symbol: &Symbol) -> Self {
+        Self {
+            symbol_id: symbol.id,
+            name: symbol.name.to_string(),
+            kind: symbol.kind,
+            file_path: symbol.file_path.to_string(),
+            start_line: symbol.range.start_line + 1,
+            end_line: symbol.range.end_line + 1,
+            tokens: 0,
+        }
+    }
+
+    fn contains_or_within(&self, other: &CloneMember) -> bool {
+        self.file_path == other.file_path
+            && ((self.start_line <= other.start_line && other.end_line <= self.end_line)
+                || (other.start_line <= self.start_line && self.end_line <= other.end_line))
+    }
+}
+
+/// Functions whose bodies are near-identical
+#[derive(Debug, Clone, Serialize)]
+pub struct CloneCluster {
+    /// Lowest similarity among the pairs that linked the cluster
+    pub similarity: f64,
+    pub members: Vec<CloneMember>,
+}
+
+/// Find clusters of near-identical functions and methods in the index.
+///
+/// Bodies are read from disk, so files changed since indexing are compared
+/// by their indexed line ranges against current content.
+pub fn find_duplicates(facade: &IndexFacade, options: &DuplicateOptions) -> Vec<CloneCluster> {
+    let mut by_file: HashMap<String, Vec<Symbol>> = HashMap::new();
+    let _ = facade.document_index().for_each_symbol(|symbol| {
+        let wanted_kind = matches!(symbol.kind, SymbolKind::Function | SymbolKind::Method);
+        let wanted_lang = options.language.as_deref().is_none_or(|lang| {
+            symbol
+                .language_id
+                .is_some_and(|id| id.as_str().eq_ignore_ascii_case(lang))
+        });
+        if wanted_kind && wanted_lang {
+            by_file
+                .entry(symbol.file_path.to_string())
+                .or_default()
+                .push(symbol);
+        }
+        Ok(())
+    });
+
+    let root = facade.settings().workspace_root.as_deref();
+    let mut units = Vec::new();
+    for (path, symbols) in by_file {
+        let source = match std::fs::read_to_string(resolve(root, &path)) {
+            Ok(source) => source,
+            Err(e) => {
+                tracing::debug!(target: "analysis", "skipping {path}: {e}");
+                continue;
+            }
+        };
+        let lines = LineIndex::new(&source);
+        for symbol in symbols {
+            if let Some(body) = body_lines(&source, &lines, &symbol) {
+                units.push((CloneMember::from_symbol(&symbol), body));
+            }
+        }
+    }
+
+    detect(units, options)
+}
+
+/// Lines of `symbol`'s indexed range in `source`, cut at the end of the
+/// file. `None` when the file has shrunk above the symbol since indexing.
+fn body_lines(source: &str, lines: &LineIndex, symbol: &Symbol) -> Option<String> {
+    // A final line break doesn't start another line
+    let line_count = match source {
+        "" => 0,
+        _ => lines.line_count() - usize::from(source.ends_with('\n')),
+    } as u32;
+    let start = symbol.range.start_line;
+    if start >= line_count {
+        return None;
+    }
+    let end = symbol.range.end_line.min(line_count - 1);
+    (start <= end).then(|| {
+        (start..=end)
+            .filter_map(|row| lines.line(source, row))
+            .collect::<Vec<_>>()
+            .join("\n")
+    })
+}
+
+/// Cluster `(member, body)` pairs by body similarity
+pub fn detect(units: Vec<(CloneMember, String)>, options: &DuplicateOptions) -> Vec<CloneCluster> {
+    let mut members = Vec::new();
+    let mut sets = Vec::new();
+    for (mut member, body) in units {
+        let tokens = normalize_tokens(&body);
+        if tokens.is_empty() || tokens.len() < options.min_tokens {
+            continue;
+        }
+        member.tokens = tokens.len();
+        sets.push(shingles(&tokens, options.shingle_size));
+        members.push(member);
+    }
+
+    let mut buckets: HashMap<(usize, u64), Vec<usize>> = HashMap::new();
+    for (i, set) in sets.iter().enumerate() {
+        for (band, rows) in minhash(set).chunks(BAND_ROWS).enumerate() {
+            buckets.entry((band, hash_of(rows))).or_default().push(i);
+        }
+    }
+
+    let mut parents: Vec<usize> = (0..members.len()).collect();
+    let mut edges = Vec::new();
+    let mut checked = HashSet::new();
+    for bucket in buckets.values() {
+        if bucket.len() < 2 || bucket.len() > MAX_BUCKET {
+            continue;
+        }
+        for (x, &i) in bucket.iter().enumerate() {
+            for &j in &bucket[x + 1..] {
+                if !checked.insert((i, j)) || members[i].contains_or_within(&members[j]) {
+                    continue;
+                }
+                let similarity = jaccard(&sets[i], &sets[j]);
+                if similarity >= options.threshold {
+                    let (a, b) = (find(&mut parents, i), find(&mut parents, j));
+                    parents[a] = b;
+                    edges.push((i, similarity));
+                }
+            }
+        }
+    }
+
+    let mut similarity_by_root: HashMap<usize, f64> = HashMap::new();
+    for (i, similarity) in edges {
+        let root = find(&mut parents, i);
+        let entry = similarity_by_root.entry(root).or_insert(1.0);
+        *entry = entry.min(similarity);
+    }
+
+    let mut grouped: HashMap<usize, Vec<CloneMember>> = HashMap::new();
+    for (i, member) in members.into_iter().enumerate() {
+        let root = find(&mut parents, i);
+        if similarity_by_root.contains_key(&root) {
+            grouped.entry(root).or_default().push(member);
+        }
+    }
+
+    let mut clusters: Vec<CloneCluster> = grouped
+        .into_iter()
+        .map(|(root, mut members)| {
+            members.sort_by(|a, b| {
+                (a.file_path.as_str(), a.start_line).cmp(&(b.file_path.as_str(), b.start_line))
+            });
+            CloneCluster {
+                similarity: similarity_by_root[&root],
+                members,
+            }
+        })
+        .collect();
+    // Most duplicated code first
+    clusters.sort_by(|a, b| {
+        let size = |c: &CloneCluster| c.members.iter().map(|m| m.tokens).sum::<usize>();
+        size(b)
+            .cmp(&size(a))
+            .then(b.similarity.total_cmp(&a.similarity))
+    });
+    clusters
+}
+
+fn resolve(root: Option<&Path>, path: &str) -> PathBuf {
+    let path = Path::new(path);
+    match root {
+        Some(root) if path.is_relative() => root.join(path),
+        _ => path.to_path_buf(),
+    }
+}
+
+fn find(parents: &mut [usize], mut i: usize) -> usize {
+    while parents[i] != i {
+        parents[i] = parents[parents[i]];
+        i = parents[i];
+    }
+    i
+}
+
+/// Lex `source` into normalized tokens, dropping comments and whitespace
+fn normalize_tokens(source: &str) -> Vec<&str> {
+    let bytes = source.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let c = bytes[i];
+        let rest = &source[i..];
+        if c.is_ascii_whitespace() {
+            i += 1;
+        } else if rest.starts_with("//")
+            || (c == b'#' && !matches!(bytes.get(i + 1), Some(b'[' | b'!')))
+        {
+            i += rest.find('\n').unwrap_or(rest.len());
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            i += comment.find("*/").map_or(rest.len(), |end| end + 4);
+        } else if c == b'"' || c == b'`' {
+            i += string_len(&bytes[i..], c);
+            tokens.push(LITERAL);
+        } else if c == b'\'' {
+            // Char literal, or a Rust lifetime / label when unclosed
+            match bytes[i + 1..].iter().take(4).position(|&b| b == b'\'') {
+                Some(end) => {
+                    i += end + 2;
+                    tokens.push(LITERAL);
+                }
+                None => {
+                    i += 1;
+                    tokens.push("'");
+                }
+            }
+        } else if c.is_ascii_digit() {
+            i += word_len(&bytes[i..], |b| {
+                b.is_ascii_alphanumeric() || b == b'_' || b == b'.'
+            });
+            tokens.push(LITERAL);
+        } else if c.is_ascii_alphabetic() || c == b'_' || !c.is_ascii() {
+            let len = word_len(&bytes[i..], |b| {
+                b.is_ascii_alphanumeric() || b == b'_' || !b.is_ascii()
+            });
+            let word = &source[i..i + len];
+            tokens.push(if KEYWORDS.contains(&word) {
+                word
+            } else {
+                IDENTIFIER
+            });
+            i += len;
+        } else {
+            tokens.push(&source[i..i + 1]);
+            i += 1;
+        }
+    }
+    tokens
+}
+
+fn word_len(bytes: &[u8], accept: impl Fn(u8) -> bool) -> usize {
+    bytes
+        .iter()
+        .position(|&b| !accept(b))
+        .unwrap_or(bytes.len())
+}
+
+/// Length of the string literal at the start of `bytes`, quotes included
+fn string_len(bytes: &[u8], quote: u8) -> usize {
+    let mut i = 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b if b == quote => return i + 1,
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+/// Sorted, deduplicated hashes of every `size`-token window
+fn shingles(tokens: &[&str], size: usize) -> Vec<u64> {
+    let size = size.clamp(1, tokens.len());
+    let mut set: Vec<u64> = tokens.windows(size).map(hash_of).collect();
+    set.sort_unstable();
+    set.dedup();
+    set
+}
+
+fn minhash(set: &[u64]) -> [u64; SIGNATURE_LEN] {
+    let mut signature = [u64::MAX; SIGNATURE_LEN];
+    for &shingle in set {
+        for (seed, slot) in signature.iter_mut().enumerate() {
+            *slot = (*slot).min(mix(shingle ^ mix(seed as u64 + 1)));
+        }
+    }
+    signature
+}
+
+/// splitmix64 finalizer, a cheap family of independent-enough hash functions
+fn mix(mut x: u64) -> u64 {
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+fn hash_of<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Jaccard similarity of two sorted sets
+fn jaccard(a: &[u64], b: &[u64]) -> f64 {
+    let (mut i, mut j, mut shared) = (0, 0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                shared += 1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    let union = a.len() + b.len() - shared;
+    if union == 0 {
+        0.0
+    } else {
+        shared as f64 / union as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit(id: u32, file: &str, body: &str) -> (CloneMember, String) {
+        let member = CloneMember {
+            symbol_id: SymbolId::new(id).unwrap(),
+            name: format!("f{id}"),
+            kind: SymbolKind::Function,
+            file_path: file.to_string(),
+            start_line: id * 100,
+            end_line: id * 100 + 10,
+            tokens: 0,
+        };
+        (member, body.to_string())
+    }
+
+    const ORIGINAL: &str = r#"fn total(items: &[Item]) -> u64 {
+        let mut sum = 0;
+        for item in items {
+            if item.active && item.count > 3 {
+                sum += item.price * item.count; // weighted
+            }
+        }
+        println!("total {}", sum);
+        sum
+    }"#;
+
+    #[test]
+    fn test_normalization_ignores_names_comments_and_literals() {
+        let renamed = r#"fn grand(rows: &[Row]) -> u64 {
+            let mut acc = 0;
+            for row in rows {
+                if row.enabled && row.qty > 7 {
+                    acc += row.cost * row.qty;
+                }
+            }
+            println!("sum={}", acc);
+            acc
+        }"#;
+        assert_eq!(normalize_tokens(ORIGINAL), normalize_tokens(renamed));
+        assert_eq!(normalize_tokens("fn f<'a>(x: &'a str)")[3], "'");
+    }
+
+    #[test]
+    fn test_detect_clusters_near_clones() {
+        let tweaked = ORIGINAL.replace("println!(\"total {}\", sum);", "");
+        let unrelated = r#"fn parse(input: &str) -> Result<Config, Error> {
+            let mut config = Config::default();
+            while let Some(line) = lines.next() {
+                match line.split_once('=') {
+                    Some((k, v)) => config.set(k.trim(), v.trim())?,
+                    None => return Err(Error::Syntax(line.to_string())),
+                }
+            }
+            Ok(config)
+        }"#;
+        let units = vec![
+            unit(1, "src/a.rs", ORIGINAL),
+            unit(2, "src/b.rs", &tweaked),
+            unit(3, "src/c.rs", unrelated),
+        ];
+        let options = DuplicateOptions {
+            threshold: 0.7,
+            min_tokens: 20,
+            ..DuplicateOptions::default()
+        };
+
+        let clusters = detect(units, &options);
+        assert_eq!(clusters.len(), 1);
+        let names: Vec<_> = clusters[0]
+            .members
+            .iter()
+            .map(|m| m.name.as_str())
+            .collect();
+        assert_eq!(names, ["f1", "f2"]);
+        assert!(clusters[0].similarity >= 0.7 && clusters[0].similarity < 1.0);
+    }
+
+    #[test]
+    fn test_detect_skips_small_and_nested_functions() {
+        let small = DuplicateOptions {
+            min_tokens: 1000,
+            ..DuplicateOptions::default()
+        };
+        let units = vec![unit(1, "src/a.rs", ORIGINAL), unit(2, "src/b.rs", ORIGINAL)];
+        assert!(detect(units, &small).is_empty());
+
+        let (outer, body) = unit(1, "src/a.rs", ORIGINAL);
+        let mut inner = outer.clone();
+        inner.start_line += 1;
+        let units = vec![(outer, body.clone()), (inner, body)];
+        assert!(detect(units, &DuplicateOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn test_body_lines_tolerates_empty_and_shrunk_files() {
+        use crate::{FileId, Range};
+
+        let symbol = Symbol::new(
+            SymbolId::new(1).unwrap(),
+            "total",
+            SymbolKind::Function,
+            FileId::new(1).unwrap(),
+            Range::new(2, 0, 5, 1),
+        );
+        let body = |text: &str| body_lines(text, &LineIndex::new(text), &symbol);
+        let source: Vec<&str> = ORIGINAL.lines().collect();
+        assert_eq!(
+            body(ORIGINAL).as_deref(),
+            Some(source[2..=5].join("\n").as_str())
+        );
+
+        // Emptied, or cut above the symbol, since indexing
+        assert_eq!(body(""), None);
+        assert_eq!(body(&source[..2].join("\n")), None);
+        assert_eq!(body(&format!("{}\n", source[..2].join("\n"))), None);
+        // Cut inside the symbol: what is left of it
+        assert_eq!(
+            body(&source[..4].join("\n")).as_deref(),
+            Some(source[2..4].join("\n").as_str())
+        );
+    }
+}
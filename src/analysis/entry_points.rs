@@ -0,0 +1,773 @@
+//! Entry-point discovery.
+//!
+//! Finds where execution starts: `main` functions, binaries declared in
+//! `Cargo.toml`, Python `if __name__ == "__main__":` blocks and console
+//! scripts, `bin` and `scripts` of `package.json`, and flake `apps`. Each
+//! [`EntryPoint`] names the indexed symbol it starts at when one can be
+//! found; [`EntryPoints::roots`] is that set, the starting points for
+//! reachability over the call graph.
+//!
+//! Manifests are found by walking the workspace root, honoring ignore files.
+
+use crate::indexing::facade::IndexFacade;
+use crate::navigation::source_path;
+use crate::parsing::parser::check_recursion_depth;
+use crate::types::LineIndex;
+use crate::{Symbol, SymbolId, SymbolKind};
+use ignore::WalkBuilder;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+use std::sync::LazyLock;
+use tree_sitter::Node;
+
+/// How an entry point was declared
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryKind {
+    /// A `main` function
+    Main,
+    /// A Cargo binary target
+    CargoBin,
+    /// Code under `if __name__ == "__main__":`, or a `__main__.py`
+    PythonMain,
+    /// A console or GUI script of `pyproject.toml` or `setup.cfg`
+    ConsoleScript,
+    /// A `bin` of `package.json`
+    PackageBin,
+    /// A `scripts` entry of `package.json`
+    PackageScript,
+    /// An app of `flake.nix`
+    FlakeApp,
+}
+
+/// One place execution starts
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EntryPoint {
+    pub kind: EntryKind,
+    /// Binary, script, or app name; the function name for `main`
+    pub name: String,
+    /// File the entry point runs, relative to the workspace root when
+    /// inside it
+    pub file_path: String,
+    /// 1-based
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol_id: Option<SymbolId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    /// Manifest declaring it, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub declared_in: Option<String>,
+}
+
+/// Entry points of the workspace, ordered by kind, file, and line
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EntryPoints {
+    pub entries: Vec<EntryPoint>,
+}
+
+impl EntryPoints {
+    /// Symbols execution starts at
+    pub fn roots(&self) -> HashSet<SymbolId> {
+        self.entries
+            .iter()
+            .filter_map(|entry| entry.symbol_id)
+            .collect()
+    }
+
+    pub fn is_entry_point(&self, symbol_id: SymbolId) -> bool {
+        self.entries
+            .iter()
+            .any(|entry| entry.symbol_id == Some(symbol_id))
+    }
+}
+
+/// Languages whose programs start at a function named `main`, with the
+/// symbol kind it has
+const MAIN_FUNCTIONS: &[(&str, SymbolKind, &str)] = &[
+    ("rust", SymbolKind::Function, "main"),
+    ("c", SymbolKind::Function, "main"),
+    ("cpp", SymbolKind::Function, "main"),
+    ("go", SymbolKind::Function, "main"),
+    ("kotlin", SymbolKind::Function, "main"),
+    ("swift", SymbolKind::Function, "main"),
+    ("java", SymbolKind::Method, "main"),
+    ("csharp", SymbolKind::Method, "Main"),
+];
+
+/// Directories never searched for manifests and other project files
+pub(crate) const SKIPPED_DIRS: &[&str] = &["node_modules", "target", ".git", ".codanna"];
+
+static MAIN_GUARD: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"^if\s+(__name__\s*==\s*['"]__main__['"]|['"]__main__['"]\s*==\s*__name__)\s*:"#)
+        .unwrap()
+});
+
+static CALLED_NAME: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b([A-Za-z_][A-Za-z0-9_]*)\s*\(").unwrap());
+
+/// Symbols by normalized absolute file path
+struct SymbolsByFile {
+    root: PathBuf,
+    files: HashMap<PathBuf, Vec<Symbol>>,
+}
+
+impl SymbolsByFile {
+    fn in_file(&self, path: &Path) -> &[Symbol] {
+        self.files.get(path).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    fn named<'a>(&'a self, path: &Path, name: &str) -> Option<&'a Symbol> {
+        self.in_file(path).iter().find(|symbol| {
+            &*symbol.name == name
+                && matches!(symbol.kind, SymbolKind::Function | SymbolKind::Method)
+        })
+    }
+
+    /// Path as shown in reports
+    fn display(&self, path: &Path) -> String {
+        path.strip_prefix(&self.root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
+/// Discover the entry points of the indexed workspace
+pub fn find_entry_points(facade: &IndexFacade) -> EntryPoints {
+    let root = facade
+        .settings()
+        .workspace_root
+        .clone()
+        .or_else(|| std::env::current_dir().ok())
+        .unwrap_or_default();
+    let root = normalize(&root);
+
+    let mut files: HashMap<PathBuf, Vec<Symbol>> = HashMap::new();
+    let _ = facade.document_index().for_each_symbol(|symbol| {
+        let path = normalize(&source_path(facade, &symbol.file_path));
+        files.entry(path).or_default().push(symbol);
+        Ok(())
+    });
+    let symbols = SymbolsByFile { root, files };
+
+    let mut entries = Vec::new();
+    let mut claimed = HashSet::new();
+    for manifest in find_manifests(&symbols.root) {
+        let Ok(content) = std::fs::read_to_string(&manifest) else {
+            continue;
+        };
+        let found = match manifest.file_name().and_then(|name| name.to_str()) {
+            Some("Cargo.toml") => cargo_entries(&manifest, &content, &symbols),
+            Some("pyproject.toml") => pyproject_entries(&manifest, &content, &symbols),
+            Some("setup.cfg") => setup_cfg_entries(&manifest, &content, &symbols),
+            Some("package.json") => package_json_entries(&manifest, &content, &symbols),
+            Some("flake.nix") => flake_entries(&manifest, &content, &symbols),
+            _ => Vec::new(),
+        };
+        claimed.extend(found.iter().filter_map(|entry| entry.symbol_id));
+        entries.extend(found);
+    }
+
+    for (path, file_symbols) in &symbols.files {
+        for symbol in file_symbols {
+            let is_main = MAIN_FUNCTIONS.iter().any(|(language, kind, name)| {
+                symbol
+                    .language_id
+                    .is_some_and(|id| id.as_str() == *language)
+                    && symbol.kind == *kind
+                    && &*symbol.name == *name
+            });
+            if is_main && !claimed.contains(&symbol.id) {
+                entries.push(symbol_entry(
+                    EntryKind::Main,
+                    &symbol.name,
+                    symbol,
+                    &symbols,
+                ));
+            }
+        }
+        let is_python = file_symbols
+            .first()
+            .and_then(|symbol| symbol.language_id)
+            .is_some_and(|id| id.as_str() == "python");
+        if is_python {
+            entries.extend(python_main_entries(path, &symbols));
+        }
+    }
+
+    entries.sort_by(|a, b| {
+        (a.kind, &a.file_path, a.line, &a.name).cmp(&(b.kind, &b.file_path, b.line, &b.name))
+    });
+    EntryPoints { entries }
+}
+
+fn symbol_entry(
+    kind: EntryKind,
+    name: &str,
+    symbol: &Symbol,
+    symbols: &SymbolsByFile,
+) -> EntryPoint {
+    EntryPoint {
+        kind,
+        name: name.to_string(),
+        file_path: symbols.display(&normalize(&symbols.root.join(&*symbol.file_path))),
+        line: Some(symbol.range.start_line + 1),
+        symbol_id: Some(symbol.id),
+        symbol: Some(symbol.name.to_string()),
+        declared_in: None,
+    }
+}
+
+/// An entry point running `file`, at `symbol` when known
+fn file_entry(
+    kind: EntryKind,
+    name: &str,
+    file: &Path,
+    symbol: Option<&Symbol>,
+    manifest: &Path,
+    symbols: &SymbolsByFile,
+) -> EntryPoint {
+    EntryPoint {
+        kind,
+        name: name.to_string(),
+        file_path: symbols.display(file),
+        line: symbol.map(|symbol| symbol.range.start_line + 1),
+        symbol_id: symbol.map(|symbol| symbol.id),
+        symbol: symbol.map(|symbol| symbol.name.to_string()),
+        declared_in: Some(symbols.display(manifest)),
+    }
+}
+
+fn find_manifests(root: &Path) -> Vec<PathBuf> {
+    const MANIFESTS: &[&str] = &[
+        "Cargo.toml",
+        "pyproject.toml",
+        "setup.cfg",
+        "package.json",
+        "flake.nix",
+    ];
+    let mut manifests: Vec<PathBuf> = WalkBuilder::new(root)
+        .filter_entry(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .is_none_or(|name| !SKIPPED_DIRS.contains(&name))
+        })
+        .build()
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            entry.file_type().is_some_and(|kind| kind.is_file())
+                && entry
+                    .file_name()
+                    .to_str()
+                    .is_some_and(|name| MANIFESTS.contains(&name))
+        })
+        .map(|entry| normalize(entry.path()))
+        .collect();
+    manifests.sort();
+    manifests
+}
+
+/// Binary targets of a Cargo manifest as `(name, source path)`, relative to
+/// its directory. Explicit `[[bin]]` targets come first, then those Cargo
+/// infers from `src/main.rs` and `src/bin/`.
+pub fn cargo_bin_targets(content: &str, dir: &Path) -> Vec<(String, PathBuf)> {
+    let Ok(manifest) = content.parse::<toml::Table>() else {
+        return Vec::new();
+    };
+    let package = manifest
+        .get("package")
+        .and_then(|package| package.get("name"))
+        .and_then(|name| name.as_str());
+    let mut targets = Vec::new();
+    for bin in manifest
+        .get("bin")
+        .and_then(|bins| bins.as_array())
+        .into_iter()
+        .flatten()
+    {
+        let Some(name) = bin.get("name").and_then(|name| name.as_str()) else {
+            continue;
+        };
+        let path = match bin.get("path").and_then(|path| path.as_str()) {
+            Some(path) => PathBuf::from(path),
+            None if Some(name) == package => PathBuf::from("src/main.rs"),
+            None => PathBuf::from(format!("src/bin/{name}.rs")),
+        };
+        targets.push((name.to_string(), path));
+    }
+
+    let autobins = manifest
+        .get("package")
+        .and_then(|package| package.get("autobins"))
+        .and_then(|autobins| autobins.as_bool())
+        .unwrap_or(true);
+    let Some(package) = package.filter(|_| autobins) else {
+        return targets;
+    };
+    let mut inferred = Vec::new();
+    if dir.join("src/main.rs").is_file() {
+        inferred.push((package.to_string(), PathBuf::from("src/main.rs")));
+    }
+    if let Ok(bins) = std::fs::read_dir(dir.join("src/bin")) {
+        for bin in bins.filter_map(Result::ok) {
+            let path = bin.path();
+            let name = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            if path.extension().is_some_and(|ext| ext == "rs") {
+                inferred.push((name, PathBuf::from("src/bin").join(bin.file_name())));
+            } else if path.join("main.rs").is_file() {
+                inferred.push((
+                    name,
+                    PathBuf::from("src/bin")
+                        .join(bin.file_name())
+                        .join("main.rs"),
+                ));
+            }
+        }
+    }
+    inferred.sort();
+    for (name, path) in inferred {
+        if !targets.iter().any(|(_, existing)| *existing == path) {
+            targets.push((name, path));
+        }
+    }
+    targets
+}
+
+fn cargo_entries(manifest: &Path, content: &str, symbols: &SymbolsByFile) -> Vec<EntryPoint> {
+    let dir = manifest.parent().unwrap_or(Path::new(""));
+    cargo_bin_targets(content, dir)
+        .into_iter()
+        .map(|(name, path)| {
+            let file = normalize(&dir.join(path));
+            let main = symbols.named(&file, "main");
+            file_entry(EntryKind::CargoBin, &name, &file, main, manifest, symbols)
+        })
+        .collect()
+}
+
+fn pyproject_entries(manifest: &Path, content: &str, symbols: &SymbolsByFile) -> Vec<EntryPoint> {
+    let Ok(pyproject) = content.parse::<toml::Table>() else {
+        return Vec::new();
+    };
+    let tables = [
+        pyproject
+            .get("project")
+            .and_then(|project| project.get("scripts")),
+        pyproject
+            .get("project")
+            .and_then(|project| project.get("gui-scripts")),
+        pyproject
+            .get("tool")
+            .and_then(|tool| tool.get("poetry"))
+            .and_then(|poetry| poetry.get("scripts")),
+    ];
+    let scripts: Vec<(String, String)> = tables
+        .into_iter()
+        .flatten()
+        .filter_map(|table| table.as_table())
+        .flatten()
+        .filter_map(|(name, target)| Some((name.clone(), target.as_str()?.to_string())))
+        .collect();
+    console_script_entries(manifest, scripts, symbols)
+}
+
+fn setup_cfg_entries(manifest: &Path, content: &str, symbols: &SymbolsByFile) -> Vec<EntryPoint> {
+    console_script_entries(manifest, setup_cfg_scripts(content), symbols)
+}
+
+/// `console_scripts` and `gui_scripts` of the `[options.entry_points]`
+/// section of a `setup.cfg`, as `(name, "module:function")`
+pub fn setup_cfg_scripts(content: &str) -> Vec<(String, String)> {
+    let mut scripts = Vec::new();
+    let mut in_section = false;
+    let mut in_scripts = false;
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            in_section = trimmed == "[options.entry_points]";
+            in_scripts = false;
+            continue;
+        }
+        if !in_section || trimmed.is_empty() || trimmed.starts_with(['#', ';']) {
+            continue;
+        }
+        let continuation = line.starts_with(char::is_whitespace);
+        if !continuation {
+            let key = trimmed.split('=').next().unwrap_or_default().trim();
+            in_scripts = matches!(key, "console_scripts" | "gui_scripts");
+            continue;
+        }
+        if let (true, Some((name, target))) = (in_scripts, trimmed.split_once('=')) {
+            scripts.push((name.trim().to_string(), target.trim().to_string()));
+        }
+    }
+    scripts
+}
+
+/// Entries for `(name, "package.module:function")` scripts. The module is
+/// looked up relative to the manifest, with or without a `src/` layout.
+fn console_script_entries(
+    manifest: &Path,
+    scripts: Vec<(String, String)>,
+    symbols: &SymbolsByFile,
+) -> Vec<EntryPoint> {
+    let dir = manifest.parent().unwrap_or(Path::new(""));
+    let mut entries = Vec::new();
+    for (name, target) in scripts {
+        let (module, function) = target.split_once(':').unwrap_or((target.as_str(), ""));
+        // Extras such as `[cli]` trail the reference
+        let function = function.split('[').next().unwrap_or_default().trim();
+        let function = function.rsplit('.').next().unwrap_or_default();
+        let module_path = module.trim().replace('.', "/");
+        let candidates = [
+            dir.join(format!("{module_path}.py")),
+            dir.join(&module_path).join("__init__.py"),
+            dir.join("src").join(format!("{module_path}.py")),
+            dir.join("src").join(&module_path).join("__init__.py"),
+        ];
+        let file = candidates
+            .iter()
+            .map(|candidate| normalize(candidate))
+            .find(|candidate| symbols.files.contains_key(candidate) || candidate.is_file())
+            .unwrap_or_else(|| normalize(&candidates[0]));
+        let symbol = symbols.named(&file, function);
+        entries.push(file_entry(
+            EntryKind::ConsoleScript,
+            &name,
+            &file,
+            symbol,
+            manifest,
+            symbols,
+        ));
+    }
+    entries
+}
+
+fn package_json_entries(
+    manifest: &Path,
+    content: &str,
+    symbols: &SymbolsByFile,
+) -> Vec<EntryPoint> {
+    let Ok(package) = serde_json::from_str::<serde_json::Value>(content) else {
+        return Vec::new();
+    };
+    let dir = manifest.parent().unwrap_or(Path::new(""));
+    let package_name = package["name"].as_str().unwrap_or_default();
+    // A scoped package's command is named without the scope
+    let command_name = package_name.rsplit('/').next().unwrap_or_default();
+    let mut entries = Vec::new();
+
+    let bins: Vec<(String, String)> = match &package["bin"] {
+        serde_json::Value::String(path) => vec![(command_name.to_string(), path.clone())],
+        serde_json::Value::Object(bins) => bins
+            .iter()
+            .filter_map(|(name, path)| Some((name.clone(), path.as_str()?.to_string())))
+            .collect(),
+        _ => Vec::new(),
+    };
+    for (name, path) in bins {
+        let file = normalize(&dir.join(path));
+        let main = symbols.named(&file, "main");
+        entries.push(file_entry(
+            EntryKind::PackageBin,
+            &name,
+            &file,
+            main,
+            manifest,
+            symbols,
+        ));
+    }
+
+    if let Some(scripts) = package["scripts"].as_object() {
+        for (name, command) in scripts {
+            // The first source file the command runs, if it names one
+            let file = command
+                .as_str()
+                .unwrap_or_default()
+                .split_whitespace()
+                .filter(|word| {
+                    [".js", ".mjs", ".cjs", ".ts", ".mts"]
+                        .iter()
+                        .any(|ext| word.ends_with(ext))
+                })
+                .map(|word| normalize(&dir.join(word)))
+                .find(|file| symbols.files.contains_key(file) || file.is_file())
+                .unwrap_or_else(|| manifest.to_path_buf());
+            entries.push(file_entry(
+                EntryKind::PackageScript,
+                name,
+                &file,
+                None,
+                manifest,
+                symbols,
+            ));
+        }
+    }
+    entries
+}
+
+fn flake_entries(manifest: &Path, content: &str, symbols: &SymbolsByFile) -> Vec<EntryPoint> {
+    let mut parser = tree_sitter::Parser::new();
+    if parser
+        .set_language(&tree_sitter_nix::LANGUAGE.into())
+        .is_err()
+    {
+        return Vec::new();
+    }
+    let Some(tree) = parser.parse(content, None) else {
+        return Vec::new();
+    };
+    let file_symbols = symbols.in_file(manifest);
+    flake_apps(tree.root_node(), content)
+        .into_iter()
+        .map(|(name, row)| {
+            // The innermost binding holding the app
+            let symbol = file_symbols
+                .iter()
+                .filter(|symbol| symbol.range.start_line <= row && row <= symbol.range.end_line)
+                .min_by_key(|symbol| symbol.range.end_line - symbol.range.start_line);
+            let mut entry = file_entry(
+                EntryKind::FlakeApp,
+                &name,
+                manifest,
+                symbol,
+                manifest,
+                symbols,
+            );
+            entry.line = Some(row + 1);
+            entry
+        })
+        .collect()
+}
+
+/// Apps declared under `apps` in a flake, as `(name, 0-based line)`.
+///
+/// Handles both `apps.<system>.<name> = { type = "app"; ... }` and attribute
+/// sets nested under `apps`, including through functions such as
+/// `forAllSystems (system: { ... })`.
+pub fn flake_apps(root: Node, code: &str) -> Vec<(String, u32)> {
+    let mut apps = Vec::new();
+    visit_flake(root, code, &[], &mut apps, 0);
+    apps
+}
+
+fn visit_flake(
+    node: Node,
+    code: &str,
+    prefix: &[String],
+    apps: &mut Vec<(String, u32)>,
+    depth: usize,
+) {
+    if !check_recursion_depth(depth, node) {
+        return;
+    }
+    if node.kind() != "binding" {
+        for child in node.children(&mut node.walk()) {
+            visit_flake(child, code, prefix, apps, depth + 1);
+        }
+        return;
+    }
+
+    let Some(attrpath) = node.child_by_field_name("attrpath") else {
+        return;
+    };
+    let mut path = prefix.to_vec();
+    for attr in attrpath.children_by_field_name("attr", &mut attrpath.walk()) {
+        let text = attr.utf8_text(code.as_bytes()).unwrap_or_default();
+        path.push(match attr.kind() {
+            "identifier" => text.to_string(),
+            "string_expression" => text.trim_matches('"').to_string(),
+            _ => "*".to_string(),
+        });
+    }
+    let Some(value) = node.child_by_field_name("expression") else {
+        return;
+    };
+    let under_apps = path
+        .iter()
+        .rposition(|segment| segment == "apps")
+        .is_some_and(|apps| apps + 1 < path.len());
+    let is_attrset = matches!(
+        value.kind(),
+        "attrset_expression" | "rec_attrset_expression"
+    );
+    let is_app = under_apps && (!is_attrset || declares_app(value, code));
+    if is_app {
+        if let Some(name) = path.last() {
+            apps.push((name.clone(), node.start_position().row as u32));
+        }
+        return;
+    }
+    visit_flake(value, code, &path, apps, depth + 1);
+}
+
+/// Whether an attribute set has the `type` or `program` of an app
+fn declares_app(attrset: Node, code: &str) -> bool {
+    let Some(bindings) = attrset.named_child(0) else {
+        return false;
+    };
+    bindings
+        .children_by_field_name("binding", &mut bindings.walk())
+        .filter_map(|binding| binding.child_by_field_name("attrpath"))
+        .filter_map(|attrpath| attrpath.child(0))
+        .any(|attr| matches!(attr.utf8_text(code.as_bytes()), Ok("type" | "program")))
+}
+
+/// `__main__` guards and `__main__.py` files of one Python file. The entry
+/// symbols are the file's functions called under the guard.
+fn python_main_entries(path: &Path, symbols: &SymbolsByFile) -> Vec<EntryPoint> {
+    let Ok(source) = std::fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    let module = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let name = if module == "__main__" {
+        path.parent()
+            .and_then(|dir| dir.file_name())
+            .map(|dir| dir.to_string_lossy().into_owned())
+            .unwrap_or(module)
+    } else {
+        module
+    };
+
+    let lines = LineIndex::new(&source);
+    let line = |row: u32| lines.line(&source, row).unwrap_or_default();
+    let line_count = lines.line_count() as u32;
+    let mut entries = Vec::new();
+    for row in 0..line_count {
+        if !MAIN_GUARD.is_match(line(row)) {
+            continue;
+        }
+        let block = (row + 1..line_count)
+            .map(line)
+            .take_while(|line| line.trim().is_empty() || line.starts_with(char::is_whitespace));
+        let mut called = HashSet::new();
+        for line in block {
+            for capture in CALLED_NAME.captures_iter(line) {
+                let Some(function) = symbols.named(path, &capture[1]) else {
+                    continue;
+                };
+                if called.insert(function.id) {
+                    entries.push(symbol_entry(
+                        EntryKind::PythonMain,
+                        &name,
+                        function,
+                        symbols,
+                    ));
+                }
+            }
+        }
+        if called.is_empty() {
+            entries.push(EntryPoint {
+                kind: EntryKind::PythonMain,
+                name: name.clone(),
+                file_path: symbols.display(path),
+                line: Some(row + 1),
+                symbol_id: None,
+                symbol: None,
+                declared_in: None,
+            });
+        }
+    }
+    if entries.is_empty() && path.file_name().is_some_and(|file| file == "__main__.py") {
+        entries.push(EntryPoint {
+            kind: EntryKind::PythonMain,
+            name,
+            file_path: symbols.display(path),
+            line: None,
+            symbol_id: None,
+            symbol: None,
+            declared_in: None,
+        });
+    }
+    entries
+}
+
+/// `path` with `.` and `..` components resolved lexically
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_cargo_targets_explicit_and_inferred() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::create_dir_all(temp_dir.path().join("src/bin")).unwrap();
+        std::fs::write(temp_dir.path().join("src/main.rs"), "fn main() {}").unwrap();
+        std::fs::write(temp_dir.path().join("src/bin/migrate.rs"), "fn main() {}").unwrap();
+        let manifest =
+            "[package]\nname = \"app\"\n\n[[bin]]\nname = \"tool\"\npath = \"tools/tool.rs\"\n";
+
+        assert_eq!(
+            cargo_bin_targets(manifest, temp_dir.path()),
+            vec![
+                ("tool".to_string(), PathBuf::from("tools/tool.rs")),
+                ("app".to_string(), PathBuf::from("src/main.rs")),
+                ("migrate".to_string(), PathBuf::from("src/bin/migrate.rs")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_setup_cfg_console_scripts() {
+        let setup = "[metadata]\nname = tool\n\n[options.entry_points]\nconsole_scripts =\n    tool = tool.cli:main\n    tool-admin = tool.admin:run [admin]\npytest11 =\n    plugin = tool.plugin\n";
+
+        assert_eq!(
+            setup_cfg_scripts(setup),
+            vec![
+                ("tool".to_string(), "tool.cli:main".to_string()),
+                (
+                    "tool-admin".to_string(),
+                    "tool.admin:run [admin]".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_flake_apps_nested_and_dotted() {
+        let flake = r#"{
+  outputs = { self, nixpkgs }: {
+    apps.x86_64-linux.serve = { type = "app"; program = "${self}/bin/serve"; };
+    apps.aarch64-linux = {
+      migrate = { type = "app"; program = "migrate"; };
+    };
+    packages.x86_64-linux.default = self;
+  };
+}"#;
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&tree_sitter_nix::LANGUAGE.into())
+            .unwrap();
+        let tree = parser.parse(flake, None).unwrap();
+
+        assert_eq!(
+            flake_apps(tree.root_node(), flake),
+            vec![("serve".to_string(), 2), ("migrate".to_string(), 4)]
+        );
+    }
+}
@@ -0,0 +1,249 @@
+//! Feature-flag usage inventory.
+//!
+//! Lists every flag the code looks up and where, so stale flags can be
+//! found and removed. Flag keys are string literals passed to SDK calls,
+//! which never resolve to indexed symbols, so indexed files are scanned
+//! with regular expressions instead: built-in patterns for LaunchDarkly and
+//! Unleash, plus project patterns from `[feature_flags]` in settings. Every
+//! pattern's first capture group is the flag name.
+//!
+//! Matching is textual, so a lookup inside a comment counts as a use.
+
+use crate::SymbolKind;
+use crate::analysis::source_scan::for_each_source;
+use crate::config::FeatureFlagsConfig;
+use crate::indexing::facade::IndexFacade;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Built-in patterns: flag system, regex
+const BUILTIN_PATTERNS: &[(&str, &str)] = &[
+    (
+        "launchdarkly",
+        r#"\b(?i:(?:bool|string|number|int|float|double|json)_?)?(?i:variation)(?i:_?detail)?\s*\(\s*["'`]([\w.:/-]+)["'`]"#,
+    ),
+    (
+        "unleash",
+        r#"\b(?:isEnabled|is_enabled|IsEnabled|getVariant|get_variant|GetVariant)\s*\(\s*["'`]([\w.:/-]+)["'`]"#,
+    ),
+];
+
+/// One lookup of a flag
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FlagSite {
+    /// Flag system of the matching pattern
+    pub system: String,
+    pub language: String,
+    pub file_path: String,
+    /// 1-based
+    pub line: u32,
+    /// 1-based
+    pub column: u32,
+    /// Innermost indexed symbol containing the lookup
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol_kind: Option<SymbolKind>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub module_path: Option<String>,
+    /// The source line of the lookup, trimmed
+    pub code: String,
+}
+
+/// A flag and every place it is looked up
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FlagUsage {
+    pub flag: String,
+    /// Flag systems it was looked up through, sorted
+    pub systems: Vec<String>,
+    /// Lookups by file path then position
+    pub sites: Vec<FlagSite>,
+}
+
+/// Compiled flag lookup patterns
+#[derive(Debug, Default)]
+pub struct FlagPatterns {
+    patterns: Vec<(String, Regex)>,
+}
+
+impl FlagPatterns {
+    /// The built-in patterns (unless disabled) followed by the configured
+    /// ones. A configured pattern that doesn't compile or has no capture
+    /// group is skipped with a warning.
+    pub fn from_config(config: &FeatureFlagsConfig) -> Self {
+        let mut patterns = Vec::new();
+        if config.builtin_sdks {
+            for (system, pattern) in BUILTIN_PATTERNS {
+                let regex = Regex::new(pattern).expect("built-in flag pattern compiles");
+                patterns.push((system.to_string(), regex));
+            }
+        }
+        for pattern in &config.patterns {
+            match Regex::new(&pattern.regex) {
+                Ok(regex) if regex.captures_len() > 1 => {
+                    patterns.push((pattern.system.clone(), regex));
+                }
+                Ok(_) => tracing::warn!(
+                    "[config] ignoring flag pattern for {}: '{}' has no capture group",
+                    pattern.system,
+                    pattern.regex
+                ),
+                Err(e) => tracing::warn!(
+                    "[config] ignoring flag pattern for {}: invalid pattern '{}': {e}",
+                    pattern.system,
+                    pattern.regex
+                ),
+            }
+        }
+        Self { patterns }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Flag lookups in `source`: system, flag name and byte offset of the
+    /// match, in source order. Overlapping matches of later patterns are
+    /// dropped, so a lookup is counted once.
+    pub fn scan<'a>(&'a self, source: &'a str) -> Vec<(&'a str, &'a str, usize)> {
+        let mut lookups: Vec<(&str, &str, usize)> = Vec::new();
+        let mut claimed: Vec<(usize, usize)> = Vec::new();
+        for (system, regex) in &self.patterns {
+            for captures in regex.captures_iter(source) {
+                let (Some(whole), Some(flag)) = (captures.get(0), captures.get(1)) else {
+                    continue;
+                };
+                if claimed
+                    .iter()
+                    .any(|&(start, end)| whole.start() < end && start < whole.end())
+                {
+                    continue;
+                }
+                claimed.push((whole.start(), whole.end()));
+                lookups.push((system.as_str(), flag.as_str(), whole.start()));
+            }
+        }
+        lookups.sort_by_key(|&(_, _, offset)| offset);
+        lookups
+    }
+}
+
+/// Flag inventory across the index, sorted by flag name.
+///
+/// `language` restricts the scan to one language (e.g. "typescript").
+pub fn find_flag_usages(
+    facade: &IndexFacade,
+    patterns: &FlagPatterns,
+    language: Option<&str>,
+) -> Vec<FlagUsage> {
+    if patterns.is_empty() {
+        return Vec::new();
+    }
+
+    let mut inventory: BTreeMap<String, Vec<FlagSite>> = BTreeMap::new();
+    for_each_source(facade, language, |file| {
+        for (system, flag, offset) in patterns.scan(file.source) {
+            let location = file.locate(offset);
+            inventory
+                .entry(flag.to_string())
+                .or_default()
+                .push(FlagSite {
+                    system: system.to_string(),
+                    language: file.language.to_string(),
+                    file_path: file.file_path.to_string(),
+                    line: location.line,
+                    column: location.column,
+                    symbol: location.symbol,
+                    symbol_kind: location.symbol_kind,
+                    module_path: location.module_path,
+                    code: location.code,
+                });
+        }
+    });
+
+    inventory
+        .into_iter()
+        .map(|(flag, mut sites)| {
+            sites.sort_by(|a, b| {
+                (a.file_path.as_str(), a.line, a.column).cmp(&(
+                    b.file_path.as_str(),
+                    b.line,
+                    b.column,
+                ))
+            });
+            let mut systems: Vec<String> = sites.iter().map(|site| site.system.clone()).collect();
+            systems.sort();
+            systems.dedup();
+            FlagUsage {
+                flag,
+                systems,
+                sites,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::FlagPattern;
+
+    fn flags<'a>(patterns: &'a FlagPatterns, source: &'a str) -> Vec<(&'a str, &'a str)> {
+        patterns
+            .scan(source)
+            .into_iter()
+            .map(|(system, flag, _)| (system, flag))
+            .collect()
+    }
+
+    #[test]
+    fn test_builtin_sdk_calls() {
+        let patterns = FlagPatterns::from_config(&FeatureFlagsConfig::default());
+        let source = r#"
+const on = client.variation('new-checkout', ctx, false);
+if ld.BoolVariation("dark-mode", ctx, false) {}
+detail = client.variation_detail("beta.search", ctx, None)
+if (unleash.isEnabled("fast-path")) {}
+let v = client.getVariant(flagName);
+"#;
+        assert_eq!(
+            flags(&patterns, source),
+            [
+                ("launchdarkly", "new-checkout"),
+                ("launchdarkly", "dark-mode"),
+                ("launchdarkly", "beta.search"),
+                ("unleash", "fast-path"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_configured_patterns() {
+        let patterns = FlagPatterns::from_config(&FeatureFlagsConfig {
+            builtin_sdks: false,
+            patterns: vec![
+                FlagPattern {
+                    system: "settings".to_string(),
+                    regex: r#"is_enabled\("(\w+)"\)"#.to_string(),
+                },
+                FlagPattern {
+                    system: "broken".to_string(),
+                    regex: r"is_enabled".to_string(),
+                },
+            ],
+        });
+        let source = "if settings.is_enabled(\"legacy_export\") and is_enabled(\"x\"):\n";
+        assert_eq!(
+            flags(&patterns, source),
+            [("settings", "legacy_export"), ("settings", "x")]
+        );
+        assert!(
+            FlagPatterns::from_config(&FeatureFlagsConfig {
+                builtin_sdks: false,
+                patterns: Vec::new(),
+            })
+            .is_empty()
+        );
+    }
+}
@@ -0,0 +1,745 @@
+//! gRPC service linkage.
+//!
+//! Reads the `.proto` files in the workspace (services, rpcs, messages and
+//! enums) and links code generated from them back to the definitions, so
+//! an RPC can be followed from a Python client to a Rust server.
+//!
+//! There is no proto grammar among the parsers, so definitions come from a
+//! small scanner over the proto source. Generated code is recognized by the
+//! names protoc plugins give it:
+//!
+//! - Python (grpcio): `GreeterStub` on the client side, `GreeterServicer`
+//!   and `add_GreeterServicer_to_server` on the server side; messages
+//!   through `helloworld_pb2`
+//! - Rust (tonic/prost): `GreeterClient` and `greeter_client`,
+//!   `GreeterServer` and `greeter_server`; rpcs in snake case
+//! - TypeScript/JavaScript (grpc-js, ts-proto, connect): `GreeterClient`,
+//!   `GreeterService`, `GreeterDefinition` and `IGreeterServer`; rpcs in
+//!   lower camel case
+//!
+//! In a file naming a service's server side, methods named like its rpcs
+//! are implementations. In a file naming its client side, method calls
+//! named like its rpcs are calls. Messages count as used wherever their
+//! name appears in a file that refers to generated code from their proto.
+//!
+//! [`index_grpc_links`] stores the definitions as `proto` symbols and the
+//! links as relationships, so callers, impact analysis and the other graph
+//! queries follow an rpc across languages.
+
+use crate::analysis::source_scan::{SourceFile, for_each_source, workspace_files};
+use crate::indexing::facade::IndexFacade;
+use crate::parsing::LanguageId;
+use crate::relationship::RelationshipMetadata;
+use crate::storage::{DocumentIndex, MetadataKey, StorageResult};
+use crate::{FileId, Range, RelationKind, Relationship, Symbol, SymbolId, SymbolKind};
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::LazyLock;
+
+/// Declarations and block delimiters in comment-free proto source
+static PROTO_ITEM: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"\b(message|enum|service)\s+(\w+)\s*\{|\brpc\s+(\w+)\s*\(\s*(stream\s+)?([\w.]+)\s*\)\s*returns\s*\(\s*(stream\s+)?([\w.]+)\s*\)|\{|\}|\bpackage\s+([\w.]+)\s*;",
+    )
+    .expect("proto pattern compiles")
+});
+
+static METHOD_CALL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\.\s*(\w+)\s*\(").expect("call pattern compiles"));
+
+static WORD: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b\w+\b").expect("word pattern compiles"));
+
+/// A place in indexed code that uses generated gRPC code
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GrpcSite {
+    pub language: String,
+    pub file_path: String,
+    /// 1-based
+    pub line: u32,
+    /// 1-based
+    pub column: u32,
+    /// Innermost indexed symbol containing the site
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    #[serde(skip)]
+    pub(crate) symbol_id: Option<SymbolId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol_kind: Option<SymbolKind>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub module_path: Option<String>,
+    /// The source line, trimmed
+    pub code: String,
+}
+
+impl GrpcSite {
+    fn new(file: &SourceFile, offset: usize) -> Self {
+        let location = file.locate(offset);
+        Self {
+            language: file.language.to_string(),
+            file_path: file.file_path.to_string(),
+            line: location.line,
+            column: location.column,
+            symbol: location.symbol,
+            symbol_id: location.symbol_id,
+            symbol_kind: location.symbol_kind,
+            module_path: location.module_path,
+            code: location.code,
+        }
+    }
+}
+
+/// An rpc and the code implementing and calling it
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ProtoRpc {
+    pub name: String,
+    pub request: String,
+    pub response: String,
+    pub client_streaming: bool,
+    pub server_streaming: bool,
+    /// 1-based line in the proto file
+    pub line: u32,
+    /// Methods implementing the rpc
+    pub implementations: Vec<GrpcSite>,
+    /// Calls through a generated client
+    pub calls: Vec<GrpcSite>,
+}
+
+/// A service and the code referring to its generated client and server
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ProtoService {
+    pub name: String,
+    /// 1-based line in the proto file
+    pub line: u32,
+    pub rpcs: Vec<ProtoRpc>,
+    /// References to the generated server side
+    pub servers: Vec<GrpcSite>,
+    /// References to the generated client
+    pub clients: Vec<GrpcSite>,
+}
+
+/// A message or enum and where generated code for it is used
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProtoType {
+    /// Nested types are qualified by their parents: `Outer.Inner`
+    pub name: String,
+    /// `message` or `enum`
+    pub kind: String,
+    /// 1-based line in the proto file
+    pub line: u32,
+    pub usages: Vec<GrpcSite>,
+}
+
+/// Definitions of one `.proto` file and their uses
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ProtoFile {
+    /// Relative to the workspace root
+    pub file_path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub package: Option<String>,
+    pub services: Vec<ProtoService>,
+    pub types: Vec<ProtoType>,
+}
+
+impl ProtoFile {
+    /// File name without `.proto`, which names the generated modules
+    fn stem(&self) -> &str {
+        let name = self.file_path.rsplit('/').next().unwrap_or(&self.file_path);
+        name.strip_suffix(".proto").unwrap_or(name)
+    }
+}
+
+/// Services and messages of `source`, a `.proto` file
+pub fn parse_proto(file_path: &str, source: &str) -> ProtoFile {
+    let code = strip_comments(source);
+    let line_of = |offset: usize| code[..offset].matches('\n').count() as u32 + 1;
+    let mut proto = ProtoFile {
+        file_path: file_path.to_string(),
+        ..ProtoFile::default()
+    };
+    // Open blocks: the message, enum or service name, or None for others
+    let mut blocks: Vec<Option<(&str, String)>> = Vec::new();
+    for captures in PROTO_ITEM.captures_iter(&code) {
+        let whole = captures.get(0).expect("group 0 always matches");
+        if let (Some(kind), Some(name)) = (captures.get(1), captures.get(2)) {
+            let line = line_of(whole.start());
+            let qualified = match blocks.iter().rev().flatten().next() {
+                Some((parent_kind, parent)) if *parent_kind == "message" => {
+                    format!("{parent}.{}", name.as_str())
+                }
+                _ => name.as_str().to_string(),
+            };
+            if kind.as_str() == "service" {
+                proto.services.push(ProtoService {
+                    name: qualified.clone(),
+                    line,
+                    ..ProtoService::default()
+                });
+            } else {
+                proto.types.push(ProtoType {
+                    name: qualified.clone(),
+                    kind: kind.as_str().to_string(),
+                    line,
+                    usages: Vec::new(),
+                });
+            }
+            blocks.push(Some((kind.as_str(), qualified)));
+        } else if let Some(name) = captures.get(3) {
+            let in_service = matches!(blocks.last(), Some(Some(("service", _))));
+            if let Some(service) = proto.services.last_mut().filter(|_| in_service) {
+                service.rpcs.push(ProtoRpc {
+                    name: name.as_str().to_string(),
+                    request: captures[5].to_string(),
+                    response: captures[7].to_string(),
+                    client_streaming: captures.get(4).is_some(),
+                    server_streaming: captures.get(6).is_some(),
+                    line: line_of(whole.start()),
+                    ..ProtoRpc::default()
+                });
+            }
+        } else if let Some(package) = captures.get(8) {
+            proto.package = Some(package.as_str().to_string());
+        } else if whole.as_str() == "{" {
+            blocks.push(None);
+        } else {
+            blocks.pop();
+        }
+    }
+    proto
+}
+
+/// `source` with comments blanked out, keeping offsets and line breaks
+fn strip_comments(source: &str) -> String {
+    let mut out = String::with_capacity(source.len());
+    let mut chars = source.chars().peekable();
+    let mut quote = None;
+    while let Some(c) = chars.next() {
+        if let Some(q) = quote {
+            if c == q {
+                quote = None;
+            }
+            out.push(c);
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"' | '\'', _) => {
+                quote = Some(c);
+                out.push(c);
+            }
+            ('/', Some('/')) => {
+                out.push(' ');
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        out.push('\n');
+                        break;
+                    }
+                    out.extend(std::iter::repeat_n(' ', next.len_utf8()));
+                }
+            }
+            ('/', Some('*')) => {
+                out.push(' ');
+                let mut previous = ' ';
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        out.push('\n');
+                    } else {
+                        out.extend(std::iter::repeat_n(' ', next.len_utf8()));
+                    }
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// `.proto` files under `root`, parsed, by path
+pub fn find_proto_files(root: &Path) -> Vec<ProtoFile> {
+    workspace_files(root, "proto")
+        .into_iter()
+        .filter_map(|(relative, path)| {
+            let source = std::fs::read_to_string(path).ok()?;
+            Some(parse_proto(&relative, &source))
+        })
+        .collect()
+}
+
+/// Which side of a service generated code stands for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Client,
+    Server,
+}
+
+/// Generated names referring to `service` in `language`, with their side
+fn generated_names(language: &str, service: &str) -> Vec<(String, Side)> {
+    match language {
+        "python" => vec![
+            (format!("{service}Stub"), Side::Client),
+            (format!("{service}Servicer"), Side::Server),
+            (format!("add_{service}Servicer_to_server"), Side::Server),
+        ],
+        "rust" => {
+            let snake = snake_case(service);
+            vec![
+                (format!("{service}Client"), Side::Client),
+                (format!("{snake}_client"), Side::Client),
+                (format!("{service}Server"), Side::Server),
+                (format!("{snake}_server"), Side::Server),
+            ]
+        }
+        "typescript" | "javascript" => vec![
+            (format!("{service}Client"), Side::Client),
+            (format!("{service}Service"), Side::Server),
+            (format!("{service}Definition"), Side::Server),
+            (format!("I{service}Server"), Side::Server),
+        ],
+        _ => Vec::new(),
+    }
+}
+
+/// Names generated code gives `rpc` in `language`
+fn rpc_names(language: &str, rpc: &str) -> Vec<String> {
+    match language {
+        "rust" => vec![snake_case(rpc)],
+        "typescript" | "javascript" => vec![lower_camel_case(rpc), rpc.to_string()],
+        _ => vec![rpc.to_string()],
+    }
+}
+
+/// Whether a `language` file refers to code generated from `proto`
+fn uses_generated_code(file: &SourceFile, proto: &ProtoFile) -> bool {
+    let stem = proto.stem();
+    match file.language {
+        "python" => file.source.contains(&format!("{stem}_pb2")),
+        "rust" => proto.package.as_deref().is_some_and(|package| {
+            file.source
+                .contains(&format!("include_proto!(\"{package}\")"))
+                || file.source.contains(&package.replace('.', "::"))
+        }),
+        "typescript" | "javascript" => {
+            file.source.contains(&format!("{stem}_pb"))
+                || file.source.contains(&format!("{stem}.pb"))
+                || file.source.contains(&format!("/{stem}\""))
+                || file.source.contains(&format!("/{stem}'"))
+        }
+        _ => false,
+    }
+}
+
+/// Proto definitions in the workspace linked to the indexed code using them.
+///
+/// `service` restricts the report to services of that name, with or
+/// without their package.
+pub fn find_grpc_links(facade: &IndexFacade, service: Option<&str>) -> Vec<ProtoFile> {
+    let root = facade
+        .settings()
+        .workspace_root
+        .clone()
+        .or_else(|| std::env::current_dir().ok())
+        .unwrap_or_default();
+    let mut protos = find_proto_files(&root);
+    if let Some(wanted) = service {
+        for proto in &mut protos {
+            let package = proto.package.clone();
+            proto.services.retain(|service| {
+                service.name == wanted
+                    || package
+                        .as_deref()
+                        .is_some_and(|package| format!("{package}.{}", service.name) == wanted)
+            });
+            proto.types.clear();
+        }
+        protos.retain(|proto| !proto.services.is_empty());
+    }
+    if protos.is_empty() {
+        return protos;
+    }
+
+    for_each_source(facade, None, |file| {
+        for proto in &mut protos {
+            link_file(file, proto);
+        }
+    });
+    protos
+}
+
+/// Record the uses `file` makes of `proto`
+fn link_file(file: &SourceFile, proto: &mut ProtoFile) {
+    let mut generated = uses_generated_code(file, proto);
+    for service in &mut proto.services {
+        let names = generated_names(file.language, &service.name);
+        if names.is_empty() {
+            continue;
+        }
+        let mut sides = Vec::new();
+        for word in WORD.find_iter(file.source) {
+            if let Some((_, side)) = names.iter().find(|(name, _)| name == word.as_str()) {
+                let site = GrpcSite::new(file, word.start());
+                match side {
+                    Side::Client => service.clients.push(site),
+                    Side::Server => service.servers.push(site),
+                }
+                sides.push(*side);
+            }
+        }
+        if sides.is_empty() {
+            continue;
+        }
+        generated = true;
+
+        let by_name: HashMap<String, usize> = service
+            .rpcs
+            .iter()
+            .enumerate()
+            .flat_map(|(index, rpc)| {
+                rpc_names(file.language, &rpc.name)
+                    .into_iter()
+                    .map(move |name| (name, index))
+            })
+            .collect();
+        if sides.contains(&Side::Server) {
+            for symbol in file.symbols.iter().filter(|symbol| is_callable(symbol)) {
+                if let Some(&index) = by_name.get(&*symbol.name) {
+                    let offset =
+                        file.offset(symbol.range.start_line, symbol.range.start_column.into());
+                    service.rpcs[index]
+                        .implementations
+                        .push(GrpcSite::new(file, offset));
+                }
+            }
+        }
+        if sides.contains(&Side::Client) {
+            for call in METHOD_CALL.captures_iter(file.source) {
+                let name = call.get(1).expect("method name group");
+                if let Some(&index) = by_name.get(name.as_str()) {
+                    service.rpcs[index]
+                        .calls
+                        .push(GrpcSite::new(file, name.start()));
+                }
+            }
+        }
+    }
+
+    if !generated || proto.types.is_empty() {
+        return;
+    }
+    let by_name: HashMap<String, usize> = proto
+        .types
+        .iter()
+        .enumerate()
+        .map(|(index, ty)| {
+            let name = ty.name.rsplit('.').next().unwrap_or(&ty.name);
+            (name.to_string(), index)
+        })
+        .collect();
+    for word in WORD.find_iter(file.source) {
+        if let Some(&index) = by_name.get(word.as_str()) {
+            proto.types[index]
+                .usages
+                .push(GrpcSite::new(file, word.start()));
+        }
+    }
+}
+
+/// Language id of the symbols stored for proto definitions
+pub const PROTO_LANGUAGE: &str = "proto";
+
+/// Store the workspace's proto definitions as symbols and link the indexed
+/// code using them.
+///
+/// Services become interfaces with their rpcs as methods, and messages and
+/// enums structs and enums, in the proto's package. Rpc implementations
+/// `Implements` their rpc, client calls `Calls` it, and code naming a
+/// generated client, server or message `Uses` the service or message.
+/// Definitions stored by an earlier run are replaced. Returns the number of
+/// relationships stored.
+pub fn index_grpc_links(facade: &IndexFacade) -> StorageResult<usize> {
+    let index = facade.document_index();
+    let mut stale = Vec::new();
+    index.for_each_symbol(|symbol| {
+        if symbol
+            .language_id
+            .is_some_and(|id| id.as_str() == PROTO_LANGUAGE)
+        {
+            stale.push(symbol.id);
+        }
+        Ok(())
+    })?;
+    let protos = find_grpc_links(facade, None);
+    if protos.is_empty() && stale.is_empty() {
+        return Ok(0);
+    }
+
+    index.start_batch()?;
+    for id in stale {
+        index.delete_relationships_for_symbol(id)?;
+        index.delete_symbol(id)?;
+    }
+    let mut next_symbol = index.get_next_symbol_id()?;
+    let mut next_file = index.get_next_file_id()?;
+    let mut linked = 0;
+    for proto in &protos {
+        let file_id = FileId::new(next_file).expect("file ids start at 1");
+        next_file += 1;
+        linked += store_proto(index, proto, file_id, &mut next_symbol)?;
+    }
+    index.store_metadata(MetadataKey::SymbolCounter, u64::from(next_symbol - 1))?;
+    index.store_metadata(MetadataKey::FileCounter, u64::from(next_file - 1))?;
+    index.commit_batch()?;
+    Ok(linked)
+}
+
+/// Store the definitions of `proto` and the relationships of the code
+/// linked to them, in the index's current batch. Returns the number of
+/// relationships stored.
+fn store_proto(
+    index: &DocumentIndex,
+    proto: &ProtoFile,
+    file_id: FileId,
+    next_symbol: &mut u32,
+) -> StorageResult<usize> {
+    let mut define = |name: &str, kind: SymbolKind, line: u32, signature: String| {
+        let id = SymbolId::new(*next_symbol).expect("symbol ids start at 1");
+        *next_symbol += 1;
+        let row = line.saturating_sub(1);
+        let mut symbol = Symbol::new(id, name, kind, file_id, Range::new(row, 0, row, 0))
+            .with_file_path(proto.file_path.as_str())
+            .with_signature(signature)
+            .with_language_id(LanguageId::new(PROTO_LANGUAGE));
+        if let Some(package) = &proto.package {
+            symbol = symbol.with_module_path(package.as_str());
+        }
+        index.index_symbol(&symbol, &proto.file_path).map(|()| id)
+    };
+
+    let mut links = Vec::new();
+    for service in &proto.services {
+        let service_id = define(
+            &service.name,
+            SymbolKind::Interface,
+            service.line,
+            format!("service {}", service.name),
+        )?;
+        links.push((&service.servers, service_id, RelationKind::Uses));
+        links.push((&service.clients, service_id, RelationKind::Uses));
+        for rpc in &service.rpcs {
+            let stream = |streaming: bool| if streaming { "stream " } else { "" };
+            let rpc_id = define(
+                &rpc.name,
+                SymbolKind::Method,
+                rpc.line,
+                format!(
+                    "rpc {}({}{}) returns ({}{})",
+                    rpc.name,
+                    stream(rpc.client_streaming),
+                    rpc.request,
+                    stream(rpc.server_streaming),
+                    rpc.response
+                ),
+            )?;
+            index.store_relationship(
+                service_id,
+                rpc_id,
+                &Relationship::new(RelationKind::Defines),
+            )?;
+            links.push((&rpc.implementations, rpc_id, RelationKind::Implements));
+            links.push((&rpc.calls, rpc_id, RelationKind::Calls));
+        }
+    }
+    for ty in &proto.types {
+        let kind = if ty.kind == "enum" {
+            SymbolKind::Enum
+        } else {
+            SymbolKind::Struct
+        };
+        let id = define(&ty.name, kind, ty.line, format!("{} {}", ty.kind, ty.name))?;
+        links.push((&ty.usages, id, RelationKind::Uses));
+    }
+
+    let mut linked = 0;
+    for (sites, to, kind) in links {
+        for site in sites {
+            // A site outside every indexed symbol has nothing to link from
+            let Some(from) = site.symbol_id else {
+                continue;
+            };
+            let metadata =
+                RelationshipMetadata::new().at_position(site.line - 1, (site.column - 1) as u16);
+            index.store_relationship(from, to, &Relationship::new(kind).with_metadata(metadata))?;
+            linked += 1;
+        }
+    }
+    Ok(linked)
+}
+
+fn is_callable(symbol: &Symbol) -> bool {
+    matches!(symbol.kind, SymbolKind::Function | SymbolKind::Method)
+}
+
+/// `SayHTTPHello` as `say_http_hello`, the way prost and tonic name things
+pub fn snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut out = String::with_capacity(name.len() + 4);
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() {
+            let after_lower =
+                i > 0 && (chars[i - 1].is_lowercase() || chars[i - 1].is_ascii_digit());
+            let ends_acronym = i > 0
+                && chars[i - 1].is_uppercase()
+                && chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+            if after_lower || ends_acronym {
+                out.push('_');
+            }
+            out.extend(c.to_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// `SayHello` as `sayHello`
+pub fn lower_camel_case(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROTO: &str = r#"
+syntax = "proto3";
+package helloworld.v1;
+
+// The greeting service.
+service Greeter {
+  // Sends a greeting { not a block }
+  rpc SayHello (HelloRequest) returns (HelloReply) {}
+  rpc StreamHellos(stream HelloRequest) returns (stream HelloReply) {
+    option deprecated = true;
+  }
+}
+
+message HelloRequest {
+  string name = 1;
+  message Meta { string trace = 1; }
+  enum Kind { PLAIN = 0; }
+}
+
+/* message Commented {} */
+message HelloReply { string message = 1; }
+"#;
+
+    #[test]
+    fn test_parse_proto() {
+        let proto = parse_proto("protos/helloworld.proto", PROTO);
+        assert_eq!(proto.package.as_deref(), Some("helloworld.v1"));
+        assert_eq!(proto.stem(), "helloworld");
+
+        let service = &proto.services[0];
+        assert_eq!((service.name.as_str(), service.line), ("Greeter", 6));
+        let rpcs: Vec<(&str, &str, &str, bool, bool)> = service
+            .rpcs
+            .iter()
+            .map(|rpc| {
+                (
+                    rpc.name.as_str(),
+                    rpc.request.as_str(),
+                    rpc.response.as_str(),
+                    rpc.client_streaming,
+                    rpc.server_streaming,
+                )
+            })
+            .collect();
+        assert_eq!(
+            rpcs,
+            [
+                ("SayHello", "HelloRequest", "HelloReply", false, false),
+                ("StreamHellos", "HelloRequest", "HelloReply", true, true),
+            ]
+        );
+
+        let types: Vec<(&str, &str)> = proto
+            .types
+            .iter()
+            .map(|ty| (ty.name.as_str(), ty.kind.as_str()))
+            .collect();
+        assert_eq!(
+            types,
+            [
+                ("HelloRequest", "message"),
+                ("HelloRequest.Meta", "message"),
+                ("HelloRequest.Kind", "enum"),
+                ("HelloReply", "message"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_store_proto_links_sites_to_definitions() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let settings = crate::config::Settings {
+            index_path: temp.path().join("index"),
+            ..crate::config::Settings::default()
+        };
+        let index = DocumentIndex::new(temp.path().join("index"), &settings).unwrap();
+        let mut proto = parse_proto("protos/helloworld.proto", PROTO);
+        let say_hello = SymbolId::new(7).unwrap();
+        proto.services[0].rpcs[0].implementations.push(GrpcSite {
+            language: "python".to_string(),
+            file_path: "server.py".to_string(),
+            line: 3,
+            column: 5,
+            symbol: Some("SayHello".to_string()),
+            symbol_id: Some(say_hello),
+            symbol_kind: Some(SymbolKind::Method),
+            module_path: None,
+            code: "def SayHello(self, request, context):".to_string(),
+        });
+
+        let mut next_symbol = 100;
+        index.start_batch().unwrap();
+        let linked = store_proto(&index, &proto, FileId::new(9).unwrap(), &mut next_symbol);
+        assert_eq!(linked.unwrap(), 1);
+        index.commit_batch().unwrap();
+
+        let rpc = index
+            .find_symbols_by_name("SayHello", Some(PROTO_LANGUAGE))
+            .unwrap()
+            .remove(0);
+        assert_eq!(rpc.kind, SymbolKind::Method);
+        assert_eq!(rpc.module_path.as_deref(), Some("helloworld.v1"));
+        assert_eq!(
+            rpc.signature.as_deref(),
+            Some("rpc SayHello(HelloRequest) returns (HelloReply)")
+        );
+        let implemented = index
+            .get_relationships_to(rpc.id, RelationKind::Implements)
+            .unwrap();
+        assert_eq!(implemented.len(), 1);
+        assert_eq!(implemented[0].0, say_hello);
+    }
+
+    #[test]
+    fn test_generated_name_casing() {
+        assert_eq!(snake_case("SayHello"), "say_hello");
+        assert_eq!(snake_case("GetHTTPStatus"), "get_http_status");
+        assert_eq!(snake_case("ListV2Items"), "list_v2_items");
+        assert_eq!(lower_camel_case("SayHello"), "sayHello");
+        assert_eq!(
+            generated_names("rust", "UserAccounts")[1],
+            ("user_accounts_client".to_string(), Side::Client)
+        );
+    }
+}
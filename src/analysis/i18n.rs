@@ -0,0 +1,455 @@
+//! Translation key and user-facing string inventory.
+//!
+//! Maps every i18n key to the places the code looks it up, and, given the
+//! locale files from `[i18n]` in settings, to the files defining it. Keys
+//! defined but never looked up are dead; keys looked up but defined nowhere
+//! are missing a translation. Lookups are found with regular expressions
+//! over indexed sources, like flag keys in [`super::flags`].
+//!
+//! On request the report also lists string literals that read like prose
+//! ("Your order has shipped") outside translation calls: text a translator
+//! never sees. That pass is a heuristic and skips comment lines.
+
+use crate::SymbolKind;
+use crate::analysis::source_scan::{SourceFile, for_each_source};
+use crate::config::I18nConfig;
+use crate::indexing::facade::IndexFacade;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::LazyLock;
+
+/// Built-in key lookup patterns
+const BUILTIN_PATTERNS: &[&str] = &[
+    r#"(?m)(?:^|[^\w$])(?:t|\$t|_|__|gettext|translate|trans|trans_choice)\s*\(\s*(?:"([^"\n]+)"|'([^'\n]+)'|`([^`\n]+)`)"#,
+    r#"\bi18nKey\s*=\s*\{?\s*(?:"([^"\n]+)"|'([^'\n]+)'|`([^`\n]+)`)"#,
+];
+
+/// Quoted string literals on one line
+static STRING_LITERAL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#""((?:[^"\\\n]|\\.)*)"|'((?:[^'\\\n]|\\.)*)'"#).expect("literal pattern compiles")
+});
+
+/// One lookup of a key, or one user-facing literal
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct I18nSite {
+    pub language: String,
+    pub file_path: String,
+    /// 1-based
+    pub line: u32,
+    /// 1-based
+    pub column: u32,
+    /// Innermost indexed symbol containing the site
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol_kind: Option<SymbolKind>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub module_path: Option<String>,
+    /// The source line, trimmed
+    pub code: String,
+}
+
+impl I18nSite {
+    fn new(file: &SourceFile, offset: usize) -> Self {
+        let location = file.locate(offset);
+        Self {
+            language: file.language.to_string(),
+            file_path: file.file_path.to_string(),
+            line: location.line,
+            column: location.column,
+            symbol: location.symbol,
+            symbol_kind: location.symbol_kind,
+            module_path: location.module_path,
+            code: location.code,
+        }
+    }
+}
+
+/// A key, where it is defined and where it is looked up
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct I18nKey {
+    pub key: String,
+    /// Locale files defining the key, relative to the workspace root
+    pub defined_in: Vec<String>,
+    /// Lookups by file path then position
+    pub sites: Vec<I18nSite>,
+}
+
+/// A string literal that reads like text shown to users
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct I18nLiteral {
+    pub text: String,
+    #[serde(flatten)]
+    pub site: I18nSite,
+}
+
+/// Keys and, when asked for, untranslated literals across the index
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct I18nReport {
+    /// Locale files read, relative to the workspace root
+    pub locale_files: Vec<String>,
+    /// Keys looked up or defined, sorted
+    pub keys: Vec<I18nKey>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub literals: Vec<I18nLiteral>,
+}
+
+impl I18nKey {
+    /// Defined in a locale file but never looked up
+    pub fn is_unused(&self) -> bool {
+        self.sites.is_empty()
+    }
+}
+
+impl I18nReport {
+    /// Looked up but defined in none of the locale files read
+    pub fn is_missing(&self, key: &I18nKey) -> bool {
+        !self.locale_files.is_empty() && key.defined_in.is_empty()
+    }
+}
+
+/// Compiled key lookup patterns
+#[derive(Debug, Default)]
+pub struct I18nPatterns {
+    patterns: Vec<Regex>,
+}
+
+impl I18nPatterns {
+    /// The built-in patterns (unless disabled) followed by the configured
+    /// ones. A configured pattern that doesn't compile or has no capture
+    /// group is skipped with a warning.
+    pub fn from_config(config: &I18nConfig) -> Self {
+        let mut patterns = Vec::new();
+        if config.builtin_patterns {
+            for pattern in BUILTIN_PATTERNS {
+                patterns.push(Regex::new(pattern).expect("built-in i18n pattern compiles"));
+            }
+        }
+        for pattern in &config.patterns {
+            match Regex::new(pattern) {
+                Ok(regex) if regex.captures_len() > 1 => patterns.push(regex),
+                Ok(_) => {
+                    tracing::warn!("[config] ignoring i18n pattern '{pattern}': no capture group")
+                }
+                Err(e) => tracing::warn!("[config] ignoring i18n pattern '{pattern}': {e}"),
+            }
+        }
+        Self { patterns }
+    }
+
+    /// Key lookups in `source`: key, byte offset of the key and byte range
+    /// of the whole match, in source order. Keys built at runtime from
+    /// template literals (`${...}`) are skipped.
+    pub fn scan<'a>(&self, source: &'a str) -> Vec<(&'a str, usize, (usize, usize))> {
+        let mut lookups: Vec<(&str, usize, (usize, usize))> = Vec::new();
+        for regex in &self.patterns {
+            for captures in regex.captures_iter(source) {
+                let Some(whole) = captures.get(0) else {
+                    continue;
+                };
+                let Some(key) = captures.iter().skip(1).flatten().next() else {
+                    continue;
+                };
+                if key.as_str().contains("${") {
+                    continue;
+                }
+                let overlaps = lookups
+                    .iter()
+                    .any(|&(_, _, (start, end))| whole.start() < end && start < whole.end());
+                if !overlaps {
+                    lookups.push((key.as_str(), key.start(), (whole.start(), whole.end())));
+                }
+            }
+        }
+        lookups.sort_by_key(|&(_, offset, _)| offset);
+        lookups
+    }
+}
+
+/// Key inventory across the index, plus user-facing literals outside
+/// lookups when `literals` is set.
+///
+/// `language` restricts the scan to one language (e.g. "typescript").
+pub fn i18n_report(
+    facade: &IndexFacade,
+    config: &I18nConfig,
+    language: Option<&str>,
+    literals: bool,
+) -> I18nReport {
+    let root = facade
+        .settings()
+        .workspace_root
+        .clone()
+        .or_else(|| std::env::current_dir().ok())
+        .unwrap_or_default();
+    let (locale_files, definitions) = load_locale_keys(&root, &config.locale_files);
+    let patterns = I18nPatterns::from_config(config);
+
+    let mut keys: BTreeMap<String, I18nKey> = definitions
+        .into_iter()
+        .map(|(key, defined_in)| {
+            let entry = I18nKey {
+                key: key.clone(),
+                defined_in,
+                sites: Vec::new(),
+            };
+            (key, entry)
+        })
+        .collect();
+    let mut found_literals = Vec::new();
+    for_each_source(facade, language, |file| {
+        let lookups = patterns.scan(file.source);
+        if literals {
+            for (text, offset) in user_facing_literals(file.source) {
+                let inside_lookup = lookups
+                    .iter()
+                    .any(|&(_, _, (start, end))| (start..end).contains(&offset));
+                if !inside_lookup {
+                    found_literals.push(I18nLiteral {
+                        text: text.to_string(),
+                        site: I18nSite::new(file, offset),
+                    });
+                }
+            }
+        }
+        for (key, offset, _) in lookups {
+            keys.entry(key.to_string())
+                .or_insert_with(|| I18nKey {
+                    key: key.to_string(),
+                    defined_in: Vec::new(),
+                    sites: Vec::new(),
+                })
+                .sites
+                .push(I18nSite::new(file, offset));
+        }
+    });
+
+    let by_position = |a: &I18nSite, b: &I18nSite| {
+        (a.file_path.as_str(), a.line, a.column).cmp(&(b.file_path.as_str(), b.line, b.column))
+    };
+    let mut keys: Vec<I18nKey> = keys.into_values().collect();
+    for key in &mut keys {
+        key.sites.sort_by(by_position);
+    }
+    found_literals.sort_by(|a, b| by_position(&a.site, &b.site));
+    I18nReport {
+        locale_files,
+        keys,
+        literals: found_literals,
+    }
+}
+
+/// Keys defined by the locale files matching `globs` under `root`: the
+/// files read, relative to `root`, and each key with the files defining it
+pub fn load_locale_keys(
+    root: &Path,
+    globs: &[String],
+) -> (Vec<String>, BTreeMap<String, Vec<String>>) {
+    let mut files = Vec::new();
+    let mut keys: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for pattern in globs {
+        let full = root.join(pattern).to_string_lossy().into_owned();
+        let paths = match glob::glob(&full) {
+            Ok(paths) => paths,
+            Err(e) => {
+                tracing::warn!("[config] ignoring locale file glob '{pattern}': {e}");
+                continue;
+            }
+        };
+        for path in paths.flatten() {
+            let relative = path
+                .strip_prefix(root)
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .replace('\\', "/");
+            if files.contains(&relative) {
+                continue;
+            }
+            let Ok(content) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            let defined = match path.extension().and_then(|ext| ext.to_str()) {
+                Some("json") => json_keys(&content),
+                Some("po" | "pot") => po_keys(&content),
+                _ => {
+                    tracing::warn!("[config] unsupported locale file {relative}");
+                    continue;
+                }
+            };
+            for key in defined {
+                let defining = keys.entry(key).or_default();
+                if !defining.contains(&relative) {
+                    defining.push(relative.clone());
+                }
+            }
+            files.push(relative);
+        }
+    }
+    (files, keys)
+}
+
+/// Suffixes i18next appends to plural forms of a key
+const PLURAL_SUFFIXES: &[&str] = &["_zero", "_one", "_two", "_few", "_many", "_other"];
+
+/// Dotted paths of the leaves of a JSON locale file, sorted. Plural forms
+/// like `items_one` also define their base key `items`.
+pub fn json_keys(content: &str) -> Vec<String> {
+    fn walk(value: &serde_json::Value, prefix: &str, keys: &mut Vec<String>) {
+        let serde_json::Value::Object(map) = value else {
+            keys.push(prefix.to_string());
+            if let Some(base) = PLURAL_SUFFIXES
+                .iter()
+                .find_map(|suffix| prefix.strip_suffix(suffix))
+            {
+                keys.push(base.to_string());
+            }
+            return;
+        };
+        for (name, child) in map {
+            let path = if prefix.is_empty() {
+                name.clone()
+            } else {
+                format!("{prefix}.{name}")
+            };
+            walk(child, &path, keys);
+        }
+    }
+
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(content) else {
+        return Vec::new();
+    };
+    let mut keys = Vec::new();
+    if value.is_object() {
+        walk(&value, "", &mut keys);
+    }
+    keys.sort();
+    keys.dedup();
+    keys
+}
+
+/// Message ids of a gettext catalog, written on one line
+pub fn po_keys(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("msgid "))
+        .filter_map(|id| id.trim().strip_prefix('"')?.strip_suffix('"'))
+        .filter(|id| !id.is_empty())
+        .map(|id| id.replace("\\\"", "\"").replace("\\\\", "\\"))
+        .collect()
+}
+
+/// String literals in `source` that read like prose, with their offset
+fn user_facing_literals(source: &str) -> Vec<(&str, usize)> {
+    let mut found = Vec::new();
+    let mut line_start = 0;
+    for line in source.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        let comment = ["//", "/*", "*", "#", "--"]
+            .iter()
+            .any(|marker| trimmed.starts_with(marker));
+        if !comment {
+            for captures in STRING_LITERAL.captures_iter(line) {
+                let Some(text) = captures.iter().skip(1).flatten().next() else {
+                    continue;
+                };
+                if looks_user_facing(text.as_str()) {
+                    found.push((text.as_str(), line_start + text.start()));
+                }
+            }
+        }
+        line_start += line.len();
+    }
+    found
+}
+
+/// Two or more words, starting with a capitalized word, mostly letters.
+/// Identifiers, paths, URLs, format strings and all-caps starts like SQL
+/// keywords don't qualify.
+pub fn looks_user_facing(text: &str) -> bool {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let Some(first) = words.first() else {
+        return false;
+    };
+    if words.len() < 2 || text.contains("://") {
+        return false;
+    }
+    let mut chars = first.chars();
+    if !chars.next().is_some_and(char::is_uppercase) || chars.any(char::is_uppercase) {
+        return false;
+    }
+    let visible = text.chars().filter(|c| !c.is_whitespace()).count();
+    let letters = text.chars().filter(|c| c.is_alphabetic()).count();
+    letters * 4 >= visible * 3
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(patterns: &I18nPatterns, source: &str) -> Vec<String> {
+        patterns
+            .scan(source)
+            .into_iter()
+            .map(|(key, _, _)| key.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_builtin_lookup_patterns() {
+        let patterns = I18nPatterns::from_config(&I18nConfig::default());
+        let source = r#"
+<h1>{t("checkout.title")}</h1>
+<Trans i18nKey="checkout.note" />
+const s = this.$t('cart.empty');
+print(_("Don't panic"))
+echo __('messages.welcome');
+const dynamic = t(`errors.${code}`);
+const total = cart.get("total");
+"#;
+        assert_eq!(
+            keys(&patterns, source),
+            [
+                "checkout.title",
+                "checkout.note",
+                "cart.empty",
+                "Don't panic",
+                "messages.welcome",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_locale_keys() {
+        let json = r#"{"checkout": {"title": "Checkout", "items_one": "1 item", "items_other": "{{count}} items"}, "ok": "OK"}"#;
+        assert_eq!(
+            json_keys(json),
+            [
+                "checkout.items",
+                "checkout.items_one",
+                "checkout.items_other",
+                "checkout.title",
+                "ok",
+            ]
+        );
+
+        let po = "msgid \"\"\nmsgstr \"\"\n\n#: app.py:3\nmsgid \"Say \\\"hi\\\"\"\nmsgstr \"Sag \\\"hallo\\\"\"\n";
+        assert_eq!(po_keys(po), ["Say \"hi\""]);
+    }
+
+    #[test]
+    fn test_user_facing_literals() {
+        assert!(looks_user_facing("Your order has shipped"));
+        assert!(looks_user_facing("A problem occurred, try again."));
+        assert!(!looks_user_facing("Checkout"));
+        assert!(!looks_user_facing("SELECT id FROM users"));
+        assert!(!looks_user_facing("See https://example.com for details"));
+        assert!(!looks_user_facing("content-type application/json"));
+        assert!(!looks_user_facing("Total: {} / {}"));
+
+        let source = "// \"Not this one here\"\nlet msg = \"Payment failed, please retry\";\n";
+        let found = user_facing_literals(source);
+        assert_eq!(found, [("Payment failed, please retry", 34)]);
+    }
+}
@@ -0,0 +1,318 @@
+//! Unused import detection.
+//!
+//! Imports come from the index's import records. One counts as used when a
+//! relationship from its file resolves to a symbol under the imported path,
+//! or when a name it binds appears in the file outside import declarations.
+//! The second check reparses the file with the language's grammar; it is what
+//! keeps imports of external packages, whose symbols are not indexed, from
+//! being reported wholesale.
+//!
+//! Languages take part by declaring their import declaration node kinds (see
+//! [`LanguageBehavior::import_declaration_kinds`]). Globs, side-effect
+//! imports, and `pub use` re-exports bind nothing to check and are never
+//! reported. Rust traits imported only for their methods are, since nothing
+//! in the file names them.
+//!
+//! [`LanguageBehavior::import_declaration_kinds`]: crate::parsing::LanguageBehavior::import_declaration_kinds
+
+use crate::indexing::facade::IndexFacade;
+use crate::navigation::source_path;
+use crate::parsing::parser::check_recursion_depth;
+use crate::parsing::{Import, LanguageBehavior, get_registry};
+use crate::{FileId, RelationKind, SymbolId};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use tree_sitter::Node;
+
+/// Unused imports of one file
+#[derive(Debug, Clone, Serialize)]
+pub struct FileUnusedImports {
+    pub file_path: String,
+    pub language: String,
+    pub imports: Vec<UnusedImport>,
+}
+
+/// An import nothing in its file refers to
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UnusedImport {
+    /// Import path as recorded
+    pub path: String,
+    /// Name the import binds in the file
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+    /// 1-based line of the declaration, when found in the source
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<u32>,
+}
+
+/// Names read from a file's syntax tree
+#[derive(Debug, Default)]
+pub struct SourceNames {
+    /// Names appearing outside import declarations
+    used: HashSet<String>,
+    /// Names inside import declarations, with the first 1-based line
+    declared: HashMap<String, u32>,
+    /// Names inside re-exporting declarations (`pub use`)
+    exported: HashSet<String>,
+}
+
+/// Unused imports across the index, grouped by file in path order.
+///
+/// `language` restricts the check to one language (e.g. "rust").
+pub fn find_unused_imports(facade: &IndexFacade, language: Option<&str>) -> Vec<FileUnusedImports> {
+    let index = facade.document_index();
+
+    // Module path and name of every symbol, for resolved relationships
+    let mut symbols: HashMap<SymbolId, (FileId, Option<String>, String)> = HashMap::new();
+    let _ = index.for_each_symbol(|symbol| {
+        symbols.insert(
+            symbol.id,
+            (
+                symbol.file_id,
+                symbol.module_path.as_deref().map(str::to_string),
+                symbol.name.to_string(),
+            ),
+        );
+        Ok(())
+    });
+    let mut targets: HashMap<FileId, Vec<(Option<String>, String)>> = HashMap::new();
+    let relationships = index.query_relationships().unwrap_or_else(|e| {
+        tracing::warn!(target: "analysis", "failed to read relationships: {e}");
+        Vec::new()
+    });
+    for (from_id, to_id, relationship) in relationships {
+        if matches!(relationship.kind, RelationKind::Defines) {
+            continue;
+        }
+        let (Some((file_id, _, _)), Some((_, module, name))) =
+            (symbols.get(&from_id), symbols.get(&to_id))
+        else {
+            continue;
+        };
+        targets
+            .entry(*file_id)
+            .or_default()
+            .push((module.clone(), name.clone()));
+    }
+
+    let mut report = Vec::new();
+    for (file_id, file_path, _, _) in index.query_file_info().unwrap_or_default() {
+        let Some((language_name, behavior)) = behavior_for(&file_path) else {
+            continue;
+        };
+        if language.is_some_and(|lang| !lang.eq_ignore_ascii_case(&language_name)) {
+            continue;
+        }
+        let kinds = behavior.import_declaration_kinds();
+        if kinds.is_empty() {
+            continue;
+        }
+        let imports = index.get_imports_for_file(file_id).unwrap_or_default();
+        if imports.is_empty() {
+            continue;
+        }
+
+        let source = match std::fs::read_to_string(source_path(facade, &file_path)) {
+            Ok(source) => source,
+            Err(e) => {
+                tracing::debug!(target: "analysis", "skipping {file_path}: {e}");
+                continue;
+            }
+        };
+        let mut parser = tree_sitter::Parser::new();
+        if parser.set_language(&behavior.get_language()).is_err() {
+            continue;
+        }
+        let Some(tree) = parser.parse(&source, None) else {
+            continue;
+        };
+        let names = scan_names(tree.root_node(), &source, kinds);
+
+        let separator = behavior.module_separator();
+        let resolved: Vec<String> = targets
+            .get(&file_id)
+            .into_iter()
+            .flatten()
+            .map(|(module, name)| match module.as_deref() {
+                Some(module) if !module.is_empty() => format!("{module}{separator}{name}"),
+                _ => name.clone(),
+            })
+            .collect();
+
+        let unused = unused_imports(imports, behavior.as_ref(), &names, &resolved);
+        if !unused.is_empty() {
+            report.push(FileUnusedImports {
+                file_path,
+                language: language_name,
+                imports: unused,
+            });
+        }
+    }
+
+    report.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+    report
+}
+
+/// Language name and behavior for a file, by extension
+fn behavior_for(file_path: &str) -> Option<(String, Box<dyn LanguageBehavior>)> {
+    let extension = Path::new(file_path).extension()?.to_str()?;
+    let registry = get_registry().lock().ok()?;
+    let definition = registry.get_by_extension(extension)?;
+    Some((
+        definition.id().as_str().to_string(),
+        definition.create_behavior(),
+    ))
+}
+
+/// Imports of one file that bind names used neither in `names` nor through
+/// a `resolved` relationship target (a qualified symbol path)
+pub fn unused_imports(
+    imports: Vec<Import>,
+    behavior: &dyn LanguageBehavior,
+    names: &SourceNames,
+    resolved: &[String],
+) -> Vec<UnusedImport> {
+    let separator = behavior.module_separator();
+    let mut seen = HashSet::new();
+    let mut unused = Vec::new();
+    for import in imports {
+        let bindings = behavior.import_bindings(&import);
+        let Some(name) = bindings.first() else {
+            continue;
+        };
+        if !seen.insert((import.path.clone(), import.alias.clone())) {
+            continue;
+        }
+        let used = bindings
+            .iter()
+            .any(|b| names.used.contains(b) || names.exported.contains(b))
+            || resolved.iter().any(|target| {
+                target == &import.path
+                    || target
+                        .strip_prefix(import.path.as_str())
+                        .is_some_and(|rest| rest.starts_with(separator))
+            });
+        if !used {
+            unused.push(UnusedImport {
+                line: names.declared.get(name).copied(),
+                name: name.clone(),
+                path: import.path,
+                alias: import.alias,
+            });
+        }
+    }
+    unused.sort_by_key(|import| import.line);
+    unused
+}
+
+/// Sort the named leaves under `root` into uses and import declarations
+pub fn scan_names(root: Node, code: &str, import_kinds: &[&str]) -> SourceNames {
+    let mut names = SourceNames::default();
+    visit(root, code, import_kinds, None, &mut names, 0);
+    names
+}
+
+/// `import` is the enclosing import declaration and whether it re-exports
+fn visit(
+    node: Node,
+    code: &str,
+    import_kinds: &[&str],
+    import: Option<bool>,
+    names: &mut SourceNames,
+    depth: usize,
+) {
+    if !check_recursion_depth(depth, node) {
+        return;
+    }
+    let import = import.or_else(|| {
+        import_kinds.contains(&node.kind()).then(|| {
+            // `pub use` re-exports what it imports
+            node.children(&mut node.walk())
+                .any(|child| child.kind() == "visibility_modifier")
+        })
+    });
+
+    if node.child_count() == 0 {
+        if !node.is_named() {
+            return;
+        }
+        let Ok(text) = node.utf8_text(code.as_bytes()) else {
+            return;
+        };
+        match import {
+            None => {
+                names.used.insert(text.to_string());
+            }
+            Some(exported) => {
+                let line = node.start_position().row as u32 + 1;
+                names.declared.entry(text.to_string()).or_insert(line);
+                if exported {
+                    names.exported.insert(text.to_string());
+                }
+            }
+        }
+        return;
+    }
+    for child in node.children(&mut node.walk()) {
+        visit(child, code, import_kinds, import, names, depth + 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::rust::RustBehavior;
+
+    fn import(path: &str, alias: Option<&str>) -> Import {
+        Import {
+            path: path.to_string(),
+            alias: alias.map(str::to_string),
+            file_id: FileId::new(1).unwrap(),
+            is_glob: false,
+            is_type_only: false,
+            range: None,
+        }
+    }
+
+    #[test]
+    fn test_unused_rust_imports() {
+        let code = "\
+use std::collections::HashMap;
+use std::fmt::Display;
+use crate::a::Helper as Aid;
+pub use crate::b::Public;
+use crate::c::Resolved;
+use crate::d::Unused;
+
+fn run(map: HashMap<String, u32>) {}
+";
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&tree_sitter_rust::LANGUAGE.into())
+            .unwrap();
+        let tree = parser.parse(code, None).unwrap();
+        let behavior = RustBehavior::new();
+        let names = scan_names(tree.root_node(), code, behavior.import_declaration_kinds());
+
+        let imports = vec![
+            import("std::collections::HashMap", None),
+            import("std::fmt::Display", None),
+            import("crate::a::Helper", Some("Aid")),
+            import("crate::b::Public", None),
+            import("crate::c::Resolved", None),
+            import("crate::d::Unused", None),
+            import("crate::e::Ext", Some("_")),
+        ];
+        let resolved = vec!["crate::c::Resolved".to_string()];
+
+        let unused = unused_imports(imports, &behavior, &names, &resolved);
+        let found: Vec<(&str, Option<u32>)> =
+            unused.iter().map(|u| (u.name.as_str(), u.line)).collect();
+        assert_eq!(
+            found,
+            [("Display", Some(2)), ("Aid", Some(3)), ("Unused", Some(6))]
+        );
+    }
+}
@@ -0,0 +1,373 @@
+//! Architecture layering rule checks.
+//!
+//! Rules come from `[[layers]]` in settings (see [`LayerRule`]). Every import
+//! and every call, use, reference, implementation, or extension edge whose
+//! source module matches a rule's `from` pattern and whose target matches one
+//! of its `deny` patterns is reported as a violation.
+
+use crate::config::LayerRule;
+use crate::indexing::facade::IndexFacade;
+use crate::io::ExitCode;
+use crate::{FileId, RelationKind, SymbolId};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum LayerError {
+    #[error(
+        "Invalid module pattern '{pattern}' in layer rule: {reason}\nSuggestion: Use module paths with * wildcards, e.g. crate::domain or crate::*::db"
+    )]
+    InvalidPattern { pattern: String, reason: String },
+
+    #[error(
+        "No layer rules configured\nSuggestion: Add [[layers]] entries with `from` and `deny` to .codanna/settings.toml"
+    )]
+    NoRules,
+}
+
+impl LayerError {
+    pub fn exit_code(&self) -> ExitCode {
+        ExitCode::ConfigError
+    }
+}
+
+/// A dependency that breaks a layer rule
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LayerViolation {
+    /// Rule name, or its `from` pattern when unnamed
+    pub rule: String,
+    /// `import`, or the relationship kind (`calls`, `uses`, ...)
+    pub edge: String,
+    pub from_module: String,
+    /// Target module, or the import path for imports
+    pub to_module: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_symbol: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to_symbol: Option<String>,
+    pub file_path: String,
+    /// 1-based; absent for imports indexed before their position was recorded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<u32>,
+}
+
+#[derive(Debug)]
+enum ModulePattern {
+    /// Matches the module and its descendants
+    Prefix(String),
+    Glob(glob::Pattern),
+}
+
+impl ModulePattern {
+    fn parse(pattern: &str) -> Result<Self, LayerError> {
+        if pattern.contains(['*', '?', '[']) {
+            glob::Pattern::new(pattern)
+                .map(Self::Glob)
+                .map_err(|e| LayerError::InvalidPattern {
+                    pattern: pattern.to_string(),
+                    reason: e.msg.to_string(),
+                })
+        } else if pattern.is_empty() {
+            Err(LayerError::InvalidPattern {
+                pattern: pattern.to_string(),
+                reason: "pattern is empty".to_string(),
+            })
+        } else {
+            Ok(Self::Prefix(pattern.to_string()))
+        }
+    }
+
+    fn matches(&self, module: &str) -> bool {
+        match self {
+            Self::Prefix(prefix) => {
+                module == prefix
+                    || module
+                        .strip_prefix(prefix.as_str())
+                        .is_some_and(|rest| rest.starts_with("::") || rest.starts_with('.'))
+            }
+            Self::Glob(pattern) => pattern.matches(module),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct CompiledRule {
+    name: String,
+    from: ModulePattern,
+    deny: Vec<ModulePattern>,
+}
+
+/// Compiled layer rules
+#[derive(Debug)]
+pub struct LayerChecker {
+    rules: Vec<CompiledRule>,
+}
+
+impl LayerChecker {
+    pub fn new(rules: &[LayerRule]) -> Result<Self, LayerError> {
+        if rules.is_empty() {
+            return Err(LayerError::NoRules);
+        }
+        let rules = rules
+            .iter()
+            .map(|rule| {
+                Ok(CompiledRule {
+                    name: rule.name.clone().unwrap_or_else(|| rule.from.clone()),
+                    from: ModulePattern::parse(&rule.from)?,
+                    deny: rule
+                        .deny
+                        .iter()
+                        .map(|p| ModulePattern::parse(p))
+                        .collect::<Result<_, _>>()?,
+                })
+            })
+            .collect::<Result<_, LayerError>>()?;
+        Ok(Self { rules })
+    }
+
+    /// Name of the first rule forbidding `from` to depend on `to`.
+    ///
+    /// Dependencies within a denied layer are allowed, so `from = "crate"`,
+    /// `deny = ["crate::infra"]` still lets infra modules use each other.
+    pub fn violated_rule(&self, from: &str, to: &str) -> Option<&str> {
+        self.rules
+            .iter()
+            .find(|rule| {
+                rule.from.matches(from)
+                    && rule
+                        .deny
+                        .iter()
+                        .any(|deny| deny.matches(to) && !deny.matches(from))
+            })
+            .map(|rule| rule.name.as_str())
+    }
+}
+
+struct SymbolInfo {
+    name: String,
+    module: Option<String>,
+    file_path: String,
+    line: u32,
+}
+
+/// Check all imports and symbol relationships against `rules`
+pub fn check_layers(
+    facade: &IndexFacade,
+    rules: &[LayerRule],
+) -> Result<Vec<LayerViolation>, LayerError> {
+    let checker = LayerChecker::new(rules)?;
+    let index = facade.document_index();
+
+    let mut symbols: HashMap<SymbolId, SymbolInfo> = HashMap::new();
+    let mut file_modules: HashMap<FileId, String> = HashMap::new();
+    let _ = index.for_each_symbol(|symbol| {
+        if let Some(module) = symbol.module_path.as_deref() {
+            // The shortest module path in a file is the file's own module
+            let entry = file_modules
+                .entry(symbol.file_id)
+                .or_insert_with(|| module.to_string());
+            if module.len() < entry.len() {
+                *entry = module.to_string();
+            }
+        }
+        symbols.insert(
+            symbol.id,
+            SymbolInfo {
+                name: symbol.name.to_string(),
+                module: symbol.module_path.as_deref().map(str::to_string),
+                file_path: symbol.file_path.to_string(),
+                line: symbol.range.start_line + 1,
+            },
+        );
+        Ok(())
+    });
+
+    let mut violations = Vec::new();
+
+    let relationships = index.query_relationships().unwrap_or_else(|e| {
+        tracing::warn!(target: "analysis", "failed to read relationships: {e}");
+        Vec::new()
+    });
+    for (from_id, to_id, relationship) in relationships {
+        if !matches!(
+            relationship.kind,
+            RelationKind::Calls
+                | RelationKind::Uses
+                | RelationKind::References
+                | RelationKind::Implements
+                | RelationKind::Extends
+        ) {
+            continue;
+        }
+        let (Some(from), Some(to)) = (symbols.get(&from_id), symbols.get(&to_id)) else {
+            continue;
+        };
+        let (Some(from_module), Some(to_module)) = (&from.module, &to.module) else {
+            continue;
+        };
+        if let Some(rule) = checker.violated_rule(from_module, to_module) {
+            violations.push(LayerViolation {
+                rule: rule.to_string(),
+                edge: format!("{:?}", relationship.kind).to_lowercase(),
+                from_module: from_module.clone(),
+                to_module: to_module.clone(),
+                from_symbol: Some(from.name.clone()),
+                to_symbol: Some(to.name.clone()),
+                file_path: from.file_path.clone(),
+                line: Some(
+                    relationship
+                        .metadata
+                        .and_then(|m| m.line)
+                        .map_or(from.line, |line| line + 1),
+                ),
+            });
+        }
+    }
+
+    let mut seen_imports = HashSet::new();
+    for (file_id, file_path, _, _) in index.query_file_info().unwrap_or_default() {
+        let Some(module) = file_modules.get(&file_id) else {
+            continue;
+        };
+        for import in index.get_imports_for_file(file_id).unwrap_or_default() {
+            if let Some(rule) = checker.violated_rule(module, &import.path) {
+                if seen_imports.insert((file_id, import.path.clone())) {
+                    violations.push(LayerViolation {
+                        rule: rule.to_string(),
+                        edge: "import".to_string(),
+                        from_module: module.clone(),
+                        to_module: import.path,
+                        from_symbol: None,
+                        to_symbol: None,
+                        file_path: file_path.clone(),
+                        line: import.range.map(|range| range.start_line + 1),
+                    });
+                }
+            }
+        }
+    }
+
+    violations.sort_by(|a, b| (&a.file_path, a.line).cmp(&(&b.file_path, b.line)));
+    Ok(violations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(from: &str, deny: &[&str]) -> LayerRule {
+        LayerRule {
+            name: None,
+            from: from.to_string(),
+            deny: deny.iter().map(|d| d.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_prefix_and_glob_patterns() {
+        let prefix = ModulePattern::parse("crate::domain").unwrap();
+        assert!(prefix.matches("crate::domain"));
+        assert!(prefix.matches("crate::domain::model"));
+        assert!(!prefix.matches("crate::domainx"));
+
+        let glob = ModulePattern::parse("crate::*::db").unwrap();
+        assert!(glob.matches("crate::infra::db"));
+        assert!(!glob.matches("crate::infra::cache"));
+
+        assert!(matches!(
+            ModulePattern::parse("crate::[oops"),
+            Err(LayerError::InvalidPattern { .. })
+        ));
+    }
+
+    #[test]
+    fn test_violated_rule() {
+        let checker = LayerChecker::new(&[
+            LayerRule {
+                name: Some("domain is pure".to_string()),
+                ..rule("crate::domain", &["crate::infra", "crate::*::db"])
+            },
+            rule("crate", &["crate::cli"]),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            checker.violated_rule("crate::domain::order", "crate::infra::http"),
+            Some("domain is pure")
+        );
+        assert_eq!(
+            checker.violated_rule("crate::domain", "crate::storage::db"),
+            Some("domain is pure")
+        );
+        assert_eq!(checker.violated_rule("crate::infra", "crate::domain"), None);
+        assert_eq!(
+            checker.violated_rule("crate::storage", "crate::cli::args"),
+            Some("crate")
+        );
+        // Modules inside a denied layer may depend on each other
+        assert_eq!(
+            checker.violated_rule("crate::cli", "crate::cli::args"),
+            None
+        );
+
+        assert!(matches!(LayerChecker::new(&[]), Err(LayerError::NoRules)));
+    }
+
+    #[test]
+    fn test_import_violation_has_line() {
+        use crate::config::Settings;
+        use crate::indexing::pipeline::FileRegistration;
+        use crate::parsing::{Import, registry::LanguageId};
+        use crate::{Range, Symbol, SymbolKind};
+        use std::path::PathBuf;
+        use std::sync::Arc;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let settings = Settings {
+            index_path: temp_dir.path().join("index"),
+            ..Settings::default()
+        };
+        let facade = IndexFacade::new(Arc::new(settings)).unwrap();
+        let index = facade.document_index();
+        let file_id = FileId::new(1).unwrap();
+        index.start_batch().unwrap();
+        index
+            .store_file_registration(&FileRegistration {
+                path: PathBuf::from("src/domain/order.rs"),
+                file_id,
+                content_hash: "hash".to_string(),
+                language_id: LanguageId::new("rust"),
+                timestamp: 0,
+                mtime: 0,
+            })
+            .unwrap();
+        let symbol = Symbol::new(
+            SymbolId::new(1).unwrap(),
+            "Order",
+            SymbolKind::Struct,
+            file_id,
+            Range::new(4, 0, 6, 1),
+        )
+        .with_module_path("crate::domain::order");
+        index.index_symbol(&symbol, "src/domain/order.rs").unwrap();
+        index
+            .store_import(&Import {
+                path: "crate::infra::db::Pool".to_string(),
+                alias: None,
+                file_id,
+                is_glob: false,
+                is_type_only: false,
+                range: Some(Range::new(2, 0, 2, 28)),
+            })
+            .unwrap();
+        index.commit_batch().unwrap();
+
+        let violations =
+            check_layers(&facade, &[rule("crate::domain", &["crate::infra"])]).unwrap();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].edge, "import");
+        assert_eq!(violations[0].to_module, "crate::infra::db::Pool");
+        assert_eq!(violations[0].line, Some(3));
+    }
+}
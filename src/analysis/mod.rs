@@ -0,0 +1,51 @@
+//! Whole-index analyses over the symbol graph.
+//!
+//! Unlike `retrieve`, which answers questions about one symbol, these passes
+//! scan the full index and produce repository-level reports. They are exposed
+//! through `codanna analyze <report>`.
+
+pub mod churn;
+pub mod diff;
+pub mod duplicates;
+pub mod entry_points;
+pub mod flags;
+pub mod grpc;
+pub mod i18n;
+pub mod imports;
+pub mod layering;
+pub mod module_summary;
+pub mod openapi;
+pub mod orm;
+pub mod owners;
+pub mod project_graph;
+pub mod routes;
+pub mod security;
+mod source_scan;
+pub mod stats;
+pub mod usage;
+
+pub use churn::{ChurnError, FileChurn, churn_report};
+pub use diff::{
+    ChangedFile, ChangedSymbol, DiffAnnotation, DiffError, DiffSymbol, GitChanges, annotate_diff,
+    changed_symbols, git_changes, parse_unified_diff, repository_root,
+};
+pub use duplicates::{CloneCluster, CloneMember, DuplicateOptions, find_duplicates};
+pub use entry_points::{EntryKind, EntryPoint, EntryPoints, find_entry_points};
+pub use flags::{FlagPatterns, FlagSite, FlagUsage, find_flag_usages};
+pub use grpc::{
+    GrpcSite, ProtoFile, ProtoRpc, ProtoService, ProtoType, find_grpc_links, index_grpc_links,
+};
+pub use i18n::{I18nKey, I18nLiteral, I18nPatterns, I18nReport, I18nSite, i18n_report};
+pub use imports::{FileUnusedImports, UnusedImport, find_unused_imports};
+pub use layering::{LayerChecker, LayerError, LayerViolation, check_layers};
+pub use module_summary::{ModuleSummary, summarize_module};
+pub use openapi::{ApiDrift, ApiSpec, MatchedOperation, SpecError, SpecOperation, check_openapi};
+pub use orm::{ModelUsage, Orm, OrmField, OrmModel, find_models};
+pub use owners::{
+    AffectedSymbol, CodeOwners, OwnerRoute, OwnersError, ReviewRouting, review_routing,
+};
+pub use project_graph::{ProjectEdge, ProjectGraph, ProjectNode, project_graph};
+pub use routes::{Route, RouteHandler, find_routes, route_matches};
+pub use security::{RulePack, SecurityFinding, find_security_calls};
+pub use stats::{DiskUsage, IndexSummary, LanguageSummary, ProjectSummary};
+pub use usage::{Hotspot, HotspotReport, SymbolUsage, UsageStats};
@@ -0,0 +1,439 @@
+//! Module summaries.
+//!
+//! A compact picture of one module or directory for a reader with a small
+//! context window: its public symbols with their signatures and the first
+//! line of their docs, the symbols execution or outside code enters it
+//! through, and the modules it depends on. The rendered text is held to a
+//! token budget by listing fewer public symbols, least used from outside
+//! first.
+
+use super::entry_points::find_entry_points;
+use crate::indexing::facade::IndexFacade;
+use crate::indexing::warm::in_module_tree;
+use crate::relationship::RelationKind;
+use crate::{Symbol, SymbolId, SymbolKind, Visibility};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
+
+/// Token budget when the caller does not give one
+pub const DEFAULT_TOKEN_BUDGET: usize = 2000;
+
+/// Most entry points and dependencies listed
+const MAX_LISTED: usize = 10;
+
+/// Relationship kinds counted as dependencies
+const DEPENDENCY_KINDS: [RelationKind; 4] = [
+    RelationKind::Calls,
+    RelationKind::Uses,
+    RelationKind::Implements,
+    RelationKind::Extends,
+];
+
+/// Summary of a module or directory
+#[derive(Debug, Clone, Serialize)]
+pub struct ModuleSummary {
+    /// Module path or directory as requested
+    pub target: String,
+    pub files: usize,
+    pub symbols: usize,
+    /// Public symbols, listed or not
+    pub public_symbols: usize,
+    pub entry_points: Vec<ModuleEntry>,
+    /// Public symbols that fit the budget, most used from outside first
+    pub public_api: Vec<SummarySymbol>,
+    pub dependencies: Vec<Dependency>,
+    pub token_budget: usize,
+}
+
+/// A public symbol of the module
+#[derive(Debug, Clone, Serialize)]
+pub struct SummarySymbol {
+    pub symbol_id: SymbolId,
+    pub name: String,
+    pub kind: SymbolKind,
+    /// First line of the signature
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    /// First line of the doc comment
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doc: Option<String>,
+    pub file_path: String,
+    /// 1-based
+    pub line: u32,
+    /// Callers outside the module
+    pub external_callers: usize,
+}
+
+/// Where execution or outside code enters the module
+#[derive(Debug, Clone, Serialize)]
+pub struct ModuleEntry {
+    pub symbol_id: SymbolId,
+    pub name: String,
+    /// "main", "cargo_bin", ... for workspace entry points, else
+    /// "called from outside"
+    pub reason: String,
+    pub external_callers: usize,
+}
+
+/// A module the summarized code refers to
+#[derive(Debug, Clone, Serialize)]
+pub struct Dependency {
+    /// Module path, or the directory of the file for symbols without one
+    pub module: String,
+    /// Calls, uses, implementations, and extensions pointing there
+    pub references: usize,
+}
+
+impl ModuleSummary {
+    /// Public symbols left out to stay within the budget
+    pub fn omitted(&self) -> usize {
+        self.public_symbols - self.public_api.len()
+    }
+}
+
+impl fmt::Display for ModuleSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{}: {} files, {} symbols, {} public",
+            self.target, self.files, self.symbols, self.public_symbols
+        )?;
+        if !self.entry_points.is_empty() {
+            writeln!(f, "\nEntry points:")?;
+            for entry in &self.entry_points {
+                writeln!(f, "{}", entry_line(entry))?;
+            }
+        }
+        if !self.dependencies.is_empty() {
+            writeln!(f, "\nDepends on:")?;
+            for dependency in &self.dependencies {
+                writeln!(f, "{}", dependency_line(dependency))?;
+            }
+        }
+        if !self.public_api.is_empty() {
+            writeln!(f, "\nPublic API:")?;
+            for symbol in &self.public_api {
+                write!(f, "{}", symbol_lines(symbol))?;
+            }
+        }
+        let omitted = self.omitted();
+        if omitted > 0 {
+            writeln!(
+                f,
+                "\n({omitted} more public symbols left out to fit {} tokens)",
+                self.token_budget
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// What is summarized: a module tree or the files under a directory
+enum Scope {
+    Module(String),
+    Directory(String),
+}
+
+impl Scope {
+    fn contains(&self, symbol: &Symbol) -> bool {
+        match self {
+            Self::Module(module) => {
+                in_module_tree(symbol.module_path.as_deref().unwrap_or_default(), module)
+            }
+            Self::Directory(dir) => Path::new(&*symbol.file_path).starts_with(dir),
+        }
+    }
+}
+
+/// Summary of `target`, a module path (`crate::storage`, `app.models`) or a
+/// directory of indexed files, rendered within about `token_budget` tokens.
+///
+/// Returns `None` when `target` names no indexed module or directory.
+pub fn summarize_module(
+    facade: &IndexFacade,
+    target: &str,
+    token_budget: usize,
+) -> Option<ModuleSummary> {
+    let target = target.trim();
+    if target.is_empty() {
+        return None;
+    }
+    let (scope, symbols) = scoped_symbols(facade, target)?;
+
+    let files: HashSet<&str> = symbols.iter().map(|s| &*s.file_path).collect();
+    let in_scope: HashSet<SymbolId> = symbols.iter().map(|s| s.id).collect();
+    let external_callers = |id: SymbolId| {
+        facade
+            .get_calling_functions_with_metadata(id)
+            .iter()
+            .filter(|(caller, _)| !in_scope.contains(&caller.id))
+            .count()
+    };
+
+    let mut public: Vec<SummarySymbol> = symbols
+        .iter()
+        .filter(|s| s.visibility == Visibility::Public)
+        .map(|s| SummarySymbol {
+            symbol_id: s.id,
+            name: s.name.to_string(),
+            kind: s.kind,
+            signature: first_line(s.signature.as_deref()),
+            doc: first_line(s.doc_comment.as_deref()),
+            file_path: s.file_path.to_string(),
+            line: s.range.start_line + 1,
+            external_callers: external_callers(s.id),
+        })
+        .collect();
+    public.sort_by(|a, b| {
+        b.external_callers
+            .cmp(&a.external_callers)
+            .then_with(|| kind_rank(a.kind).cmp(&kind_rank(b.kind)))
+            .then_with(|| (&a.file_path, a.line).cmp(&(&b.file_path, b.line)))
+    });
+
+    let mut entry_points: Vec<ModuleEntry> = find_entry_points(facade)
+        .entries
+        .into_iter()
+        .filter_map(|entry| {
+            let id = entry.symbol_id.filter(|id| in_scope.contains(id))?;
+            let reason = serde_json::to_value(entry.kind)
+                .ok()
+                .and_then(|kind| kind.as_str().map(str::to_string))
+                .unwrap_or_default();
+            Some(ModuleEntry {
+                symbol_id: id,
+                name: entry.symbol.unwrap_or(entry.name),
+                reason,
+                external_callers: 0,
+            })
+        })
+        .collect();
+    for symbol in public.iter().filter(|s| s.external_callers > 0) {
+        if entry_points.len() >= MAX_LISTED {
+            break;
+        }
+        if entry_points.iter().any(|e| e.symbol_id == symbol.symbol_id) {
+            continue;
+        }
+        entry_points.push(ModuleEntry {
+            symbol_id: symbol.symbol_id,
+            name: symbol.name.clone(),
+            reason: "called from outside".to_string(),
+            external_callers: symbol.external_callers,
+        });
+    }
+    entry_points.truncate(MAX_LISTED);
+
+    let mut summary = ModuleSummary {
+        target: target.to_string(),
+        files: files.len(),
+        symbols: symbols.len(),
+        public_symbols: public.len(),
+        entry_points,
+        public_api: Vec::new(),
+        dependencies: dependencies(facade, &scope, &symbols),
+        token_budget,
+    };
+
+    // The header and the omission note are always shown; public symbols
+    // fill what is left
+    let mut used = estimate_tokens(&summary.to_string());
+    for symbol in public {
+        let cost = estimate_tokens(&symbol_lines(&symbol));
+        if used + cost > token_budget {
+            break;
+        }
+        used += cost;
+        summary.public_api.push(symbol);
+    }
+    Some(summary)
+}
+
+/// Symbols of the module tree named `target`, or else of the indexed files
+/// under the directory `target`
+fn scoped_symbols(facade: &IndexFacade, target: &str) -> Option<(Scope, Vec<Symbol>)> {
+    let symbols = facade.module_symbols(target);
+    if !symbols.is_empty() {
+        return Some((Scope::Module(target.to_string()), symbols));
+    }
+
+    let dir = directory_key(facade, target);
+    let scope = Scope::Directory(dir.clone());
+    let symbols: Vec<Symbol> = facade
+        .get_all_indexed_paths()
+        .iter()
+        .filter(|path| path.starts_with(&dir))
+        .filter_map(|path| facade.get_file_id_for_path(&path.to_string_lossy()))
+        .flat_map(|file_id| facade.get_symbols_by_file(file_id))
+        .collect();
+    (!symbols.is_empty()).then_some((scope, symbols))
+}
+
+/// `target` as indexed paths start: relative to the workspace root, without
+/// `./` or a trailing slash
+fn directory_key(facade: &IndexFacade, target: &str) -> String {
+    let path = Path::new(target);
+    let relative = facade
+        .settings()
+        .workspace_root
+        .as_deref()
+        .and_then(|root| path.strip_prefix(root).ok())
+        .unwrap_or(path);
+    relative
+        .to_string_lossy()
+        .trim_start_matches("./")
+        .trim_end_matches('/')
+        .replace('\\', "/")
+}
+
+/// Modules outside `scope` its symbols refer to, most referenced first
+fn dependencies(facade: &IndexFacade, scope: &Scope, symbols: &[Symbol]) -> Vec<Dependency> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for symbol in symbols {
+        for kind in DEPENDENCY_KINDS {
+            for (target, _) in facade.get_relationship_targets_with_metadata(symbol.id, kind) {
+                if scope.contains(&target) {
+                    continue;
+                }
+                *counts.entry(module_of(&target)).or_default() += 1;
+            }
+        }
+    }
+    let mut dependencies: Vec<Dependency> = counts
+        .into_iter()
+        .map(|(module, references)| Dependency { module, references })
+        .collect();
+    dependencies.sort_by(|a, b| {
+        b.references
+            .cmp(&a.references)
+            .then_with(|| a.module.cmp(&b.module))
+    });
+    dependencies.truncate(MAX_LISTED);
+    dependencies
+}
+
+fn module_of(symbol: &Symbol) -> String {
+    match symbol.module_path.as_deref() {
+        Some(module) if !module.is_empty() => module.to_string(),
+        _ => Path::new(&*symbol.file_path)
+            .parent()
+            .map(|dir| dir.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+    }
+}
+
+/// Types before functions before values, for symbols equally used
+fn kind_rank(kind: SymbolKind) -> u8 {
+    match kind {
+        SymbolKind::Trait | SymbolKind::Interface => 0,
+        SymbolKind::Struct | SymbolKind::Class | SymbolKind::Enum | SymbolKind::TypeAlias => 1,
+        SymbolKind::Function | SymbolKind::Method | SymbolKind::Macro => 2,
+        SymbolKind::Module => 3,
+        _ => 4,
+    }
+}
+
+fn first_line(text: Option<&str>) -> Option<String> {
+    text?
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(str::to_string)
+}
+
+/// Rough token count: about four bytes of code or prose per token
+fn estimate_tokens(text: &str) -> usize {
+    text.len().div_ceil(4)
+}
+
+fn entry_line(entry: &ModuleEntry) -> String {
+    let reason = if entry.external_callers > 0 {
+        format!("{}, {} callers", entry.reason, entry.external_callers)
+    } else {
+        entry.reason.clone()
+    };
+    format!(
+        "  {} ({reason}) [symbol_id:{}]",
+        entry.name,
+        entry.symbol_id.value()
+    )
+}
+
+fn dependency_line(dependency: &Dependency) -> String {
+    format!(
+        "  {} ({} references)",
+        dependency.module, dependency.references
+    )
+}
+
+fn symbol_lines(symbol: &SummarySymbol) -> String {
+    let mut lines = format!("  {:?} {}", symbol.kind, symbol.name);
+    if let Some(doc) = &symbol.doc {
+        lines.push_str(&format!(" - {doc}"));
+    }
+    lines.push_str(&format!(
+        " [{}:{}, symbol_id:{}]\n",
+        symbol.file_path,
+        symbol.line,
+        symbol.symbol_id.value()
+    ));
+    if let Some(signature) = &symbol.signature {
+        lines.push_str(&format!("    {signature}\n"));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbol(name: &str, doc: Option<&str>, callers: usize) -> SummarySymbol {
+        SummarySymbol {
+            symbol_id: SymbolId::new(1).unwrap(),
+            name: name.to_string(),
+            kind: SymbolKind::Function,
+            signature: Some(format!("pub fn {name}()")),
+            doc: doc.map(str::to_string),
+            file_path: "src/storage/mod.rs".to_string(),
+            line: 10,
+            external_callers: callers,
+        }
+    }
+
+    #[test]
+    fn test_summary_text_lists_api_and_what_was_left_out() {
+        let summary = ModuleSummary {
+            target: "crate::storage".to_string(),
+            files: 2,
+            symbols: 9,
+            public_symbols: 3,
+            entry_points: vec![ModuleEntry {
+                symbol_id: SymbolId::new(1).unwrap(),
+                name: "open".to_string(),
+                reason: "called from outside".to_string(),
+                external_callers: 4,
+            }],
+            public_api: vec![symbol("open", Some("Open the index"), 4)],
+            dependencies: vec![Dependency {
+                module: "crate::config".to_string(),
+                references: 2,
+            }],
+            token_budget: 50,
+        };
+        assert_eq!(
+            summary.to_string(),
+            "crate::storage: 2 files, 9 symbols, 3 public\n\
+             \nEntry points:\n  open (called from outside, 4 callers) [symbol_id:1]\n\
+             \nDepends on:\n  crate::config (2 references)\n\
+             \nPublic API:\n  Function open - Open the index [src/storage/mod.rs:10, symbol_id:1]\n    pub fn open()\n\
+             \n(2 more public symbols left out to fit 50 tokens)\n"
+        );
+        assert_eq!(
+            first_line(Some("\n  Opens the index.\n More.")).as_deref(),
+            Some("Opens the index.")
+        );
+        assert_eq!(estimate_tokens("abcde"), 2);
+    }
+}
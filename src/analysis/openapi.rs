@@ -0,0 +1,475 @@
+//! OpenAPI spec drift detection.
+//!
+//! Correlates the operations of an OpenAPI (or Swagger 2.0) document with
+//! the routes [`find_routes`] detects in the code, reporting operations
+//! the code doesn't serve and routes the spec doesn't document. Paths
+//! compare segment by segment with parameters matching each other whatever
+//! they are named, and spec paths are tried both as written and under each
+//! `servers` URL path (or `basePath`).
+//!
+//! JSON specs are read with `serde_json`. YAML specs go through a small
+//! line scanner that understands the block style specs are written in:
+//! `paths`, their method keys and `operationId`, `servers[].url` and
+//! `basePath`. Flow-style (`{...}`) mappings under `paths` are not read.
+
+use crate::analysis::routes::{Route, find_routes, is_parameter};
+use crate::indexing::facade::IndexFacade;
+use crate::io::ExitCode;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+const METHODS: &[&str] = &[
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+#[derive(Error, Debug)]
+pub enum SpecError {
+    #[error("Failed to read spec {path}: {source}")]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Invalid JSON in spec {path}: {source}")]
+    Json {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+
+    #[error(
+        "No operations found in {path}\nSuggestion: Check that the spec has a `paths` section in block style"
+    )]
+    NoOperations { path: PathBuf },
+}
+
+impl SpecError {
+    pub fn exit_code(&self) -> ExitCode {
+        match self {
+            Self::Read { .. } => ExitCode::IoError,
+            Self::Json { .. } | Self::NoOperations { .. } => ExitCode::ParseError,
+        }
+    }
+}
+
+/// An operation declared in the spec
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SpecOperation {
+    /// Upper-case HTTP method
+    pub method: String,
+    /// Path as written in the spec, e.g. `/users/{id}`
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operation_id: Option<String>,
+    /// 1-based line of the method key; unknown for JSON specs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<u32>,
+}
+
+/// The operations of a spec and the path prefixes its servers add
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ApiSpec {
+    /// URL paths of `servers` or `basePath`, e.g. `/v1`
+    pub base_paths: Vec<String>,
+    pub operations: Vec<SpecOperation>,
+}
+
+/// An operation and the routes serving it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MatchedOperation {
+    pub operation: SpecOperation,
+    pub routes: Vec<Route>,
+}
+
+/// How a spec and the code's routes line up
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ApiDrift {
+    pub spec: String,
+    pub matched: Vec<MatchedOperation>,
+    /// Documented but not served
+    pub spec_only: Vec<SpecOperation>,
+    /// Served but not documented
+    pub code_only: Vec<Route>,
+}
+
+impl ApiDrift {
+    pub fn has_drift(&self) -> bool {
+        !self.spec_only.is_empty() || !self.code_only.is_empty()
+    }
+}
+
+/// Correlate the spec at `spec_path` with the routes in the index.
+///
+/// `language` restricts the routes to one language (e.g. "python").
+pub fn check_openapi(
+    facade: &IndexFacade,
+    spec_path: &Path,
+    language: Option<&str>,
+) -> Result<ApiDrift, SpecError> {
+    let content = std::fs::read_to_string(spec_path).map_err(|source| SpecError::Read {
+        path: spec_path.to_path_buf(),
+        source,
+    })?;
+    let is_json = spec_path.extension().is_some_and(|ext| ext == "json")
+        || content.trim_start().starts_with('{');
+    let spec = if is_json {
+        let value = serde_json::from_str(&content).map_err(|source| SpecError::Json {
+            path: spec_path.to_path_buf(),
+            source,
+        })?;
+        parse_json_spec(&value)
+    } else {
+        parse_yaml_spec(&content)
+    };
+    if spec.operations.is_empty() {
+        return Err(SpecError::NoOperations {
+            path: spec_path.to_path_buf(),
+        });
+    }
+    let mut drift = correlate(&spec, find_routes(facade, language));
+    drift.spec = spec_path.display().to_string();
+    Ok(drift)
+}
+
+/// Match spec operations with routes
+pub fn correlate(spec: &ApiSpec, routes: Vec<Route>) -> ApiDrift {
+    let mut claimed = vec![false; routes.len()];
+    let mut drift = ApiDrift::default();
+    for operation in &spec.operations {
+        let templates: Vec<String> = std::iter::once(String::new())
+            .chain(spec.base_paths.iter().cloned())
+            .map(|base| path_shape(&format!("{base}{}", operation.path)))
+            .collect();
+        let serving: Vec<usize> = routes
+            .iter()
+            .enumerate()
+            .filter(|(_, route)| route.method == "ANY" || route.method == operation.method)
+            .filter(|(_, route)| templates.contains(&path_shape(&route.path)))
+            .map(|(index, _)| index)
+            .collect();
+        if serving.is_empty() {
+            drift.spec_only.push(operation.clone());
+            continue;
+        }
+        for &index in &serving {
+            claimed[index] = true;
+        }
+        drift.matched.push(MatchedOperation {
+            operation: operation.clone(),
+            routes: serving.iter().map(|&index| routes[index].clone()).collect(),
+        });
+    }
+    drift.code_only = routes
+        .into_iter()
+        .zip(claimed)
+        .filter(|(_, claimed)| !claimed)
+        .map(|(route, _)| route)
+        .collect();
+    drift
+}
+
+/// `path` with every parameter segment as `{}` and no empty segments
+fn path_shape(path: &str) -> String {
+    path.split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| if is_parameter(segment) { "{}" } else { segment })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// URL path of a server URL: `https://api.example.com/v1/` is `/v1`
+fn url_path(url: &str) -> String {
+    let without_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let path = if url.contains("://") {
+        without_scheme
+            .find('/')
+            .map_or("", |slash| &without_scheme[slash..])
+    } else {
+        without_scheme
+    };
+    path.trim_end_matches('/').to_string()
+}
+
+/// Operations of a JSON spec
+pub fn parse_json_spec(value: &serde_json::Value) -> ApiSpec {
+    let mut spec = ApiSpec::default();
+    if let Some(servers) = value.get("servers").and_then(|servers| servers.as_array()) {
+        spec.base_paths = servers
+            .iter()
+            .filter_map(|server| server.get("url")?.as_str())
+            .map(url_path)
+            .filter(|path| !path.is_empty())
+            .collect();
+    }
+    if let Some(base) = value.get("basePath").and_then(|base| base.as_str()) {
+        spec.base_paths.push(url_path(base));
+    }
+    let Some(paths) = value.get("paths").and_then(|paths| paths.as_object()) else {
+        return spec;
+    };
+    for (path, item) in paths {
+        let Some(item) = item.as_object() else {
+            continue;
+        };
+        for (method, operation) in item {
+            if !METHODS.contains(&method.as_str()) {
+                continue;
+            }
+            spec.operations.push(SpecOperation {
+                method: method.to_ascii_uppercase(),
+                path: path.clone(),
+                operation_id: operation
+                    .get("operationId")
+                    .and_then(|id| id.as_str())
+                    .map(str::to_string),
+                line: None,
+            });
+        }
+    }
+    spec
+}
+
+/// Operations of a block-style YAML spec
+pub fn parse_yaml_spec(content: &str) -> ApiSpec {
+    #[derive(PartialEq)]
+    enum Section {
+        Other,
+        Paths,
+        Servers,
+    }
+
+    let mut spec = ApiSpec::default();
+    let mut section = Section::Other;
+    let mut path_indent = None;
+    let mut current_path: Option<String> = None;
+    let mut method_indent = None;
+    for (row, raw) in content.lines().enumerate() {
+        let line = strip_yaml_comment(raw);
+        if line.trim().is_empty() {
+            continue;
+        }
+        let indent = line.len() - line.trim_start().len();
+        let Some((key, value)) = yaml_entry(line.trim_start()) else {
+            continue;
+        };
+
+        if indent == 0 {
+            section = match key.as_str() {
+                "paths" => Section::Paths,
+                "servers" => Section::Servers,
+                _ => Section::Other,
+            };
+            if key == "basePath" && !value.is_empty() {
+                spec.base_paths.push(url_path(&value));
+            }
+            continue;
+        }
+        match section {
+            Section::Servers if key == "url" || key == "- url" => {
+                let path = url_path(&value);
+                if !path.is_empty() {
+                    spec.base_paths.push(path);
+                }
+            }
+            Section::Paths => {
+                let path_indent = *path_indent.get_or_insert(indent);
+                if indent == path_indent {
+                    current_path = key.starts_with('/').then_some(key);
+                    method_indent = None;
+                    continue;
+                }
+                let Some(path) = &current_path else {
+                    continue;
+                };
+                let method_indent = *method_indent.get_or_insert(indent);
+                if indent == method_indent && METHODS.contains(&key.as_str()) {
+                    spec.operations.push(SpecOperation {
+                        method: key.to_ascii_uppercase(),
+                        path: path.clone(),
+                        operation_id: None,
+                        line: Some(row as u32 + 1),
+                    });
+                } else if indent > method_indent && key == "operationId" {
+                    let operation = spec
+                        .operations
+                        .last_mut()
+                        .filter(|operation| &operation.path == path);
+                    if let Some(operation) = operation.filter(|op| op.operation_id.is_none()) {
+                        operation.operation_id = Some(value);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    spec
+}
+
+/// `line` without a trailing `# comment`
+fn strip_yaml_comment(line: &str) -> &str {
+    if line.trim_start().starts_with('#') {
+        return "";
+    }
+    let mut quote = None;
+    let mut previous = ' ';
+    for (i, c) in line.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '#' && previous.is_whitespace() => return &line[..i],
+            None => {}
+        }
+        previous = c;
+    }
+    line
+}
+
+/// Key and scalar value of a `key: value` line, unquoted. A list item's
+/// first key keeps its `- ` so callers can tell it apart.
+fn yaml_entry(line: &str) -> Option<(String, String)> {
+    let (item, rest) = match line.strip_prefix("- ") {
+        Some(rest) => ("- ", rest.trim_start()),
+        None => ("", line),
+    };
+    let (key, value) = if rest.starts_with(['"', '\'']) {
+        let quote = rest.chars().next()?;
+        let end = rest[1..].find(quote)? + 1;
+        let after = rest[end + 1..].trim_start().strip_prefix(':')?;
+        (&rest[1..end], after)
+    } else {
+        let colon = rest
+            .find(": ")
+            .or_else(|| rest.strip_suffix(':').map(|key| key.len()))?;
+        (&rest[..colon], &rest[colon + 1..])
+    };
+    let value = value.trim().trim_matches(['"', '\'']);
+    Some((format!("{item}{key}"), value.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const YAML: &str = r#"
+openapi: 3.0.3
+servers:
+  - url: https://api.example.com/v1  # production
+paths:
+  /users:
+    get:
+      operationId: listUsers
+      responses:
+        "200":
+          description: ok
+    post:
+      operationId: createUser
+  "/users/{userId}":
+    parameters:
+      - name: userId
+        in: path
+    get:
+      operationId: getUser
+components:
+  schemas:
+    User:
+      type: object
+"#;
+
+    fn operations(spec: &ApiSpec) -> Vec<(&str, &str, Option<&str>)> {
+        spec.operations
+            .iter()
+            .map(|op| {
+                (
+                    op.method.as_str(),
+                    op.path.as_str(),
+                    op.operation_id.as_deref(),
+                )
+            })
+            .collect()
+    }
+
+    fn route(method: &str, path: &str) -> Route {
+        Route {
+            method: method.to_string(),
+            path: path.to_string(),
+            framework: "fastapi".to_string(),
+            language: "python".to_string(),
+            file_path: "app.py".to_string(),
+            line: 1,
+            column: 1,
+            handler: None,
+            code: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_yaml_spec() {
+        let spec = parse_yaml_spec(YAML);
+        assert_eq!(spec.base_paths, ["/v1"]);
+        assert_eq!(
+            operations(&spec),
+            [
+                ("GET", "/users", Some("listUsers")),
+                ("POST", "/users", Some("createUser")),
+                ("GET", "/users/{userId}", Some("getUser")),
+            ]
+        );
+        assert_eq!(spec.operations[0].line, Some(7));
+    }
+
+    #[test]
+    fn test_json_spec() {
+        let value = serde_json::json!({
+            "swagger": "2.0",
+            "basePath": "/api",
+            "paths": {
+                "/pets/{id}": {
+                    "get": {"operationId": "getPet"},
+                    "parameters": []
+                }
+            }
+        });
+        let spec = parse_json_spec(&value);
+        assert_eq!(spec.base_paths, ["/api"]);
+        assert_eq!(operations(&spec), [("GET", "/pets/{id}", Some("getPet"))]);
+    }
+
+    #[test]
+    fn test_correlate_reports_both_sides() {
+        let spec = parse_yaml_spec(YAML);
+        let drift = correlate(
+            &spec,
+            vec![
+                route("GET", "/v1/users"),
+                route("GET", "/users/<int:id>"),
+                route("DELETE", "/users/:id"),
+                route("ANY", "/health"),
+            ],
+        );
+
+        let matched: Vec<(&str, usize)> = drift
+            .matched
+            .iter()
+            .map(|matched| (matched.operation.path.as_str(), matched.routes.len()))
+            .collect();
+        assert_eq!(matched, [("/users", 1), ("/users/{userId}", 1)]);
+        assert_eq!(
+            drift
+                .spec_only
+                .iter()
+                .map(|op| op.method.as_str())
+                .collect::<Vec<_>>(),
+            ["POST"]
+        );
+        assert_eq!(
+            drift
+                .code_only
+                .iter()
+                .map(|route| route.path.as_str())
+                .collect::<Vec<_>>(),
+            ["/users/:id", "/health"]
+        );
+        assert!(drift.has_drift());
+    }
+}
@@ -0,0 +1,619 @@
+//! ORM model inventory.
+//!
+//! Finds the database models an application declares, with their fields,
+//! and the queries reaching each one, so a schema change can be traced to
+//! the code reading and writing the table:
+//!
+//! - Django: classes deriving from `models.Model`, directly or through
+//!   another model; queries through `Model.objects`
+//! - SQLAlchemy: classes with `__tablename__` or `Column`/`mapped_column`
+//!   attributes; queries through `query(Model)`, `select(Model)`,
+//!   `session.get(Model, ...)` and Flask-SQLAlchemy's `Model.query`
+//! - Prisma: `model` blocks of the `.prisma` schemas in the workspace;
+//!   queries through the client, `prisma.user.findMany(...)`
+//! - Diesel: `table!` macros; queries through `users::table`, `users::dsl`
+//!   and column paths like `users::id`
+//!
+//! Python models are indexed classes and carry their symbol id. Prisma
+//! schemas aren't indexed and `table!` expands to no symbol, so those come
+//! from small scanners like the `.proto` one in [`super::grpc`]. Abstract
+//! models (`abstract = True`, `__abstract__ = True`) lend their ORM to
+//! subclasses but aren't reported.
+
+use crate::analysis::source_scan::{SourceFile, for_each_source, workspace_files};
+use crate::indexing::facade::IndexFacade;
+use crate::parsing::signature::group_end;
+use crate::{Symbol, SymbolId, SymbolKind};
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// Base classes in a Python class signature
+static CLASS_BASES: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^class\s+\w+\s*\((.*)\)").expect("class pattern compiles"));
+
+/// Attributes only SQLAlchemy models declare
+static SQLALCHEMY_MARKER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^[ \t]+__tablename__\s*=|\b(?:Column|mapped_column)\s*\(")
+        .expect("marker pattern compiles")
+});
+
+static ABSTRACT_MARKER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^[ \t]+(?:abstract|__abstract__)\s*=\s*True\b")
+        .expect("abstract pattern compiles")
+});
+
+static TABLE_NAME: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?m)^[ \t]+(?:__tablename__|db_table)\s*=\s*["']([^"'\n]+)["']"#)
+        .expect("table pattern compiles")
+});
+
+/// A model attribute: name, annotation, field class and its arguments
+static PYTHON_FIELD: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?m)^[ \t]+(\w+)[ \t]*(?::[ \t]*([^=\n]+?))?[ \t]*=[ \t]*(?:\w+\.)*(\w*Field|\w*ForeignKey|Column|mapped_column|relationship)[ \t]*\(([^\n]*)",
+    )
+    .expect("field pattern compiles")
+});
+
+/// A leading type argument of `Column(...)`, e.g. `String(50)`, the
+/// field type when there is no annotation
+static LEADING_TYPE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*(?:\w+\.)*([A-Z]\w*)").expect("type pattern compiles"));
+
+static DIESEL_TABLE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\btable!\s*\{").expect("table! pattern compiles"));
+
+/// Table name, optional schema and primary key of a `table!` body
+static DIESEL_HEAD: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\A\s*(?:#\[[^\]]*\]\s*)*(?:\w+\.)?(\w+)\s*(?:\([^)]*\))?\s*\{")
+        .expect("table head pattern compiles")
+});
+
+static DIESEL_COLUMN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^\s*(?:#\[[^\]]*\]\s*)*(\w+)\s*->\s*([^,\n]+?)\s*,?\s*$")
+        .expect("column pattern compiles")
+});
+
+static PRISMA_MODEL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^\s*model\s+(\w+)\s*\{").expect("model pattern compiles"));
+
+static PRISMA_FIELD: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(\w+)\s+(\w+(?:\[\])?\??)").expect("field pattern compiles"));
+
+static PRISMA_MAP: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"^@@map\(\s*(?:name\s*:\s*)?"([^"]+)""#).expect("map pattern compiles")
+});
+
+/// Prisma client methods that query a model
+const PRISMA_OPERATIONS: &str = "findMany|findUnique|findUniqueOrThrow|findFirst|findFirstOrThrow|create|createMany|update|updateMany|upsert|delete|deleteMany|count|aggregate|groupBy";
+
+/// The library a model is declared with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Orm {
+    Django,
+    Sqlalchemy,
+    Prisma,
+    Diesel,
+}
+
+impl Orm {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Orm::Django => "django",
+            Orm::Sqlalchemy => "sqlalchemy",
+            Orm::Prisma => "prisma",
+            Orm::Diesel => "diesel",
+        }
+    }
+
+    /// Languages of the code querying models
+    fn query_languages(self) -> &'static [&'static str] {
+        match self {
+            Orm::Django | Orm::Sqlalchemy => &["python"],
+            Orm::Prisma => &["typescript", "javascript"],
+            Orm::Diesel => &["rust"],
+        }
+    }
+}
+
+/// A column or relation of a model
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OrmField {
+    pub name: String,
+    /// Field class (`CharField`), column type (`String`, `Nullable<Text>`)
+    /// or Prisma type (`Post[]`)
+    pub field_type: String,
+    /// 1-based line in the model's file
+    pub line: u32,
+}
+
+/// A query reaching a model
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ModelUsage {
+    pub language: String,
+    pub file_path: String,
+    /// 1-based
+    pub line: u32,
+    /// 1-based
+    pub column: u32,
+    /// Innermost indexed symbol containing the query
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol_kind: Option<SymbolKind>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub module_path: Option<String>,
+    /// The source line, trimmed
+    pub code: String,
+}
+
+impl ModelUsage {
+    fn new(file: &SourceFile, offset: usize) -> Self {
+        let location = file.locate(offset);
+        Self {
+            language: file.language.to_string(),
+            file_path: file.file_path.to_string(),
+            line: location.line,
+            column: location.column,
+            symbol: location.symbol,
+            symbol_kind: location.symbol_kind,
+            module_path: location.module_path,
+            code: location.code,
+        }
+    }
+}
+
+/// A model, its fields and the queries reaching it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OrmModel {
+    /// Class or Prisma model name; the table name for Diesel
+    pub name: String,
+    pub orm: Orm,
+    /// Table name when the model declares one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub table: Option<String>,
+    pub file_path: String,
+    /// 1-based
+    pub line: u32,
+    /// The indexed class, for Python models
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol_id: Option<SymbolId>,
+    pub fields: Vec<OrmField>,
+    /// Queries by file path then position
+    pub usages: Vec<ModelUsage>,
+}
+
+/// A Python class with base classes, a model candidate
+struct PythonClass {
+    symbol: Symbol,
+    /// As written, e.g. `models.Model`
+    bases: Vec<String>,
+    /// Known from the class itself, or inherited once resolved
+    orm: Option<Orm>,
+    is_abstract: bool,
+    table: Option<String>,
+    fields: Vec<OrmField>,
+}
+
+/// Models across the index and the workspace's Prisma schemas, with the
+/// queries reaching them, sorted by file path then line.
+///
+/// `model` keeps only the models with that name or table.
+pub fn find_models(facade: &IndexFacade, model: Option<&str>) -> Vec<OrmModel> {
+    let root = facade
+        .settings()
+        .workspace_root
+        .clone()
+        .or_else(|| std::env::current_dir().ok())
+        .unwrap_or_default();
+    let mut models: Vec<OrmModel> = workspace_files(&root, "prisma")
+        .into_iter()
+        .filter_map(|(relative, path)| {
+            let source = std::fs::read_to_string(path).ok()?;
+            Some(parse_prisma(&relative, &source))
+        })
+        .flatten()
+        .collect();
+
+    let mut classes = Vec::new();
+    for_each_source(facade, None, |file| match file.language {
+        "python" => classes.extend(python_classes(file)),
+        "rust" => models.extend(parse_diesel(file.file_path, file.source)),
+        _ => {}
+    });
+    models.extend(resolve_python_models(classes));
+    if let Some(wanted) = model {
+        models.retain(|model| model.name == wanted || model.table.as_deref() == Some(wanted));
+    }
+    models.sort_by(|a, b| (&a.file_path, a.line).cmp(&(&b.file_path, b.line)));
+    if models.is_empty() {
+        return models;
+    }
+
+    let patterns: Vec<Regex> = models.iter().map(usage_pattern).collect();
+    for_each_source(facade, None, |file| {
+        for (model, pattern) in models.iter_mut().zip(&patterns) {
+            if !model.orm.query_languages().contains(&file.language) {
+                continue;
+            }
+            for captures in pattern.captures_iter(file.source) {
+                if let Some(name) = captures.iter().skip(1).flatten().next() {
+                    model.usages.push(ModelUsage::new(file, name.start()));
+                }
+            }
+        }
+    });
+    for model in &mut models {
+        model.usages.sort_by(|a, b| {
+            (a.file_path.as_str(), a.line, a.column).cmp(&(b.file_path.as_str(), b.line, b.column))
+        });
+    }
+    models
+}
+
+/// Indexed classes of a Python file that have base classes
+fn python_classes(file: &SourceFile) -> Vec<PythonClass> {
+    let mut classes = Vec::new();
+    for symbol in file.symbols.iter().filter(|s| s.kind == SymbolKind::Class) {
+        let Some(bases) = symbol
+            .signature
+            .as_deref()
+            .and_then(|signature| CLASS_BASES.captures(signature))
+        else {
+            continue;
+        };
+        let bases: Vec<String> = bases[1]
+            .split(',')
+            .map(str::trim)
+            .filter(|base| !base.is_empty() && !base.contains('='))
+            .map(str::to_string)
+            .collect();
+        if bases.is_empty() {
+            continue;
+        }
+
+        let start = file.offset(symbol.range.start_line, 0);
+        let end = file.offset(symbol.range.end_line + 1, 0);
+        let body = &file.source[start..end];
+        let orm = if bases.iter().any(|base| base.ends_with("models.Model")) {
+            Some(Orm::Django)
+        } else if SQLALCHEMY_MARKER.is_match(body) {
+            Some(Orm::Sqlalchemy)
+        } else {
+            None
+        };
+        classes.push(PythonClass {
+            symbol: symbol.clone(),
+            bases,
+            orm,
+            is_abstract: ABSTRACT_MARKER.is_match(body),
+            table: TABLE_NAME.captures(body).map(|c| c[1].to_string()),
+            fields: python_fields(body, symbol.range.start_line + 1),
+        });
+    }
+    classes
+}
+
+/// Models among `classes`: those known to be models and, transitively,
+/// the classes deriving from one
+fn resolve_python_models(mut classes: Vec<PythonClass>) -> Vec<OrmModel> {
+    loop {
+        let known: HashMap<String, Orm> = classes
+            .iter()
+            .filter_map(|class| Some((class.symbol.name.to_string(), class.orm?)))
+            .collect();
+        let mut changed = false;
+        for class in classes.iter_mut().filter(|class| class.orm.is_none()) {
+            class.orm = class.bases.iter().find_map(|base| {
+                let name = base.rsplit('.').next().unwrap_or(base);
+                known.get(name).copied()
+            });
+            changed |= class.orm.is_some();
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    classes
+        .into_iter()
+        .filter(|class| !class.is_abstract)
+        .filter_map(|class| {
+            Some(OrmModel {
+                name: class.symbol.name.to_string(),
+                orm: class.orm?,
+                table: class.table,
+                file_path: class.symbol.file_path.to_string(),
+                line: class.symbol.range.start_line + 1,
+                symbol_id: Some(class.symbol.id),
+                fields: class.fields,
+                usages: Vec::new(),
+            })
+        })
+        .collect()
+}
+
+/// Field attributes of a Python class `body` starting on 1-based
+/// `first_line`
+fn python_fields(body: &str, first_line: u32) -> Vec<OrmField> {
+    PYTHON_FIELD
+        .captures_iter(body)
+        .map(|captures| {
+            let name = captures.get(1).expect("field name group");
+            let call = &captures[3];
+            let leading_type = match call {
+                "Column" | "mapped_column" => LEADING_TYPE
+                    .captures(&captures[4])
+                    .map(|ty| ty[1].to_string()),
+                _ => None,
+            };
+            let annotation = captures.get(2).map(|annotation| {
+                let annotation = annotation.as_str().trim();
+                annotation
+                    .strip_prefix("Mapped[")
+                    .and_then(|inner| inner.strip_suffix(']'))
+                    .unwrap_or(annotation)
+                    .to_string()
+            });
+            OrmField {
+                name: name.as_str().to_string(),
+                field_type: annotation
+                    .or(leading_type)
+                    .unwrap_or_else(|| call.to_string()),
+                line: first_line + line_of(body, name.start()) - 1,
+            }
+        })
+        .collect()
+}
+
+/// Tables of the `table!` macros in a Rust file
+fn parse_diesel(file_path: &str, source: &str) -> Vec<OrmModel> {
+    let mut models = Vec::new();
+    for table in DIESEL_TABLE.find_iter(source) {
+        let open = table.end() - 1;
+        let Some(end) = group_end(source, open) else {
+            continue;
+        };
+        let body = &source[open + 1..end - 1];
+        let Some(head) = DIESEL_HEAD.captures(body) else {
+            continue;
+        };
+        let name = head.get(1).expect("table name group");
+        let columns_start = head.get(0).expect("whole match").end();
+        let columns_end = body.rfind('}').unwrap_or(body.len()).max(columns_start);
+        let fields = DIESEL_COLUMN
+            .captures_iter(&body[columns_start..columns_end])
+            .map(|column| {
+                let column_name = column.get(1).expect("column name group");
+                OrmField {
+                    name: column_name.as_str().to_string(),
+                    field_type: column[2].to_string(),
+                    line: line_of(source, open + 1 + columns_start + column_name.start()),
+                }
+            })
+            .collect();
+        models.push(OrmModel {
+            name: name.as_str().to_string(),
+            orm: Orm::Diesel,
+            table: Some(name.as_str().to_string()),
+            file_path: file_path.to_string(),
+            line: line_of(source, open + 1 + name.start()),
+            symbol_id: None,
+            fields,
+            usages: Vec::new(),
+        });
+    }
+    models
+}
+
+/// Models of a Prisma schema
+fn parse_prisma(file_path: &str, source: &str) -> Vec<OrmModel> {
+    let mut models = Vec::new();
+    for model in PRISMA_MODEL.captures_iter(source) {
+        let whole = model.get(0).expect("whole match");
+        let name = model.get(1).expect("model name group");
+        let end = group_end(source, whole.end() - 1).unwrap_or(source.len());
+        let mut table = None;
+        let mut fields = Vec::new();
+        let mut line_start = whole.end();
+        for line in source[whole.end()..end].split_inclusive('\n') {
+            let trimmed = line.trim();
+            if let Some(map) = PRISMA_MAP.captures(trimmed) {
+                table = Some(map[1].to_string());
+            } else if let Some(field) = PRISMA_FIELD.captures(trimmed) {
+                fields.push(OrmField {
+                    name: field[1].to_string(),
+                    field_type: field[2].to_string(),
+                    line: line_of(source, line_start),
+                });
+            }
+            line_start += line.len();
+        }
+        models.push(OrmModel {
+            name: name.as_str().to_string(),
+            orm: Orm::Prisma,
+            table,
+            file_path: file_path.to_string(),
+            line: line_of(source, name.start()),
+            symbol_id: None,
+            fields,
+            usages: Vec::new(),
+        });
+    }
+    models
+}
+
+/// Pattern for the queries reaching `model`; its first participating
+/// group is the model's name at the query site
+fn usage_pattern(model: &OrmModel) -> Regex {
+    let name = regex::escape(&model.name);
+    let pattern = match model.orm {
+        Orm::Django => format!(r"\b({name})\.objects\b"),
+        Orm::Sqlalchemy => format!(
+            r"(?:\.query|\.get|\bselect|\binsert|\bupdate|\bdelete)\s*\(\s*({name})\b|\b({name})\.query\b"
+        ),
+        Orm::Prisma => {
+            let mut chars = model.name.chars();
+            let client: String = chars
+                .next()
+                .map(|first| first.to_lowercase().chain(chars).collect())
+                .unwrap_or_default();
+            let client = regex::escape(&client);
+            format!(r"\.({client})\s*\.\s*(?:{PRISMA_OPERATIONS})\s*\(")
+        }
+        Orm::Diesel => format!(r"(?:^|[^:\w]|\bschema::)({name})::\w"),
+    };
+    Regex::new(&pattern).expect("usage pattern compiles")
+}
+
+/// 1-based line of byte `offset` in `text`
+fn line_of(text: &str, offset: usize) -> u32 {
+    text[..offset].matches('\n').count() as u32 + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(model: &OrmModel) -> Vec<(&str, &str, u32)> {
+        model
+            .fields
+            .iter()
+            .map(|field| (field.name.as_str(), field.field_type.as_str(), field.line))
+            .collect()
+    }
+
+    #[test]
+    fn test_python_fields() {
+        let body = r#"class Order(Base):
+    __tablename__ = "orders"
+
+    id = Column(Integer, primary_key=True)
+    note = sa.Column("note_text", sa.Text)
+    customer_id: Mapped[int] = mapped_column(ForeignKey("customers.id"))
+    customer: Mapped["Customer"] = relationship(back_populates="orders")
+    status = models.CharField(max_length=20)
+    objects = models.Manager()
+
+    def total(self):
+        return sum(item.price for item in self.items)
+"#;
+        let found: Vec<(String, String, u32)> = python_fields(body, 10)
+            .into_iter()
+            .map(|field| (field.name, field.field_type, field.line))
+            .collect();
+        let expected = [
+            ("id", "Integer", 13),
+            ("note", "Column", 14),
+            ("customer_id", "int", 15),
+            ("customer", "\"Customer\"", 16),
+            ("status", "CharField", 17),
+        ];
+        assert_eq!(
+            found,
+            expected.map(|(name, ty, line)| (name.to_string(), ty.to_string(), line))
+        );
+    }
+
+    #[test]
+    fn test_parse_schemas() {
+        let prisma = r#"generator client {
+  provider = "prisma-client-js"
+}
+
+model User {
+  id    Int     @id @default(autoincrement())
+  // contact address
+  email String  @unique
+  posts Post[]
+  bio   String?
+
+  @@map("users")
+}
+"#;
+        let models = parse_prisma("prisma/schema.prisma", prisma);
+        assert_eq!(models.len(), 1);
+        assert_eq!(models[0].name, "User");
+        assert_eq!(models[0].line, 5);
+        assert_eq!(models[0].table.as_deref(), Some("users"));
+        assert_eq!(
+            fields(&models[0]),
+            [
+                ("id", "Int", 6),
+                ("email", "String", 8),
+                ("posts", "Post[]", 9),
+                ("bio", "String?", 10),
+            ]
+        );
+
+        let diesel = r#"// @generated automatically by Diesel CLI.
+
+diesel::table! {
+    posts (id) {
+        id -> Int4,
+        #[max_length = 255]
+        title -> Varchar,
+        body -> Nullable<Text>,
+    }
+}
+"#;
+        let models = parse_diesel("src/schema.rs", diesel);
+        assert_eq!(models.len(), 1);
+        assert_eq!(models[0].name, "posts");
+        assert_eq!(models[0].line, 4);
+        assert_eq!(
+            fields(&models[0]),
+            [
+                ("id", "Int4", 5),
+                ("title", "Varchar", 7),
+                ("body", "Nullable<Text>", 8),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_usage_patterns() {
+        let model = |name: &str, orm| OrmModel {
+            name: name.to_string(),
+            orm,
+            table: None,
+            file_path: String::new(),
+            line: 1,
+            symbol_id: None,
+            fields: Vec::new(),
+            usages: Vec::new(),
+        };
+        let matches = |model: &OrmModel, source: &str| {
+            usage_pattern(model)
+                .captures_iter(source)
+                .filter_map(|captures| captures.iter().skip(1).flatten().next())
+                .map(|name| name.start())
+                .collect::<Vec<_>>()
+        };
+
+        let django = model("Order", Orm::Django);
+        assert_eq!(matches(&django, "Order.objects.filter(id=1)"), [0]);
+        assert!(matches(&django, "OrderItem.objects.all()").is_empty());
+
+        let sqlalchemy = model("Order", Orm::Sqlalchemy);
+        assert_eq!(
+            matches(&sqlalchemy, "session.query(Order).all(); select(Order)"),
+            [14, 35]
+        );
+        assert_eq!(matches(&sqlalchemy, "Order.query.get(1)"), [0]);
+
+        let prisma = model("User", Orm::Prisma);
+        assert_eq!(matches(&prisma, "await prisma.user.findMany()"), [13]);
+        assert!(matches(&prisma, "prisma.userProfile.findMany()").is_empty());
+
+        let diesel = model("posts", Orm::Diesel);
+        assert_eq!(
+            matches(&diesel, "posts::table.filter(posts::id.eq(1))"),
+            [0, 20]
+        );
+        assert!(matches(&diesel, "use crate::posts::service;").is_empty());
+    }
+}
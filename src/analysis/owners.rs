@@ -0,0 +1,265 @@
+//! Review routing from CODEOWNERS.
+//!
+//! [`review_routing`] starts from the symbols a diff changes, follows their
+//! impact radius to the code calling or using them, and looks up the
+//! CODEOWNERS rule of every file reached. Each owner is reported with the
+//! affected files and symbols they own, so a review bot can request the
+//! teams whose code a change can break and not only those whose files it
+//! edits.
+
+use crate::analysis::diff::{
+    ChangedFile, DiffError, changed_symbols, git_changes, repository_path, workspace_root,
+};
+use crate::indexing::facade::IndexFacade;
+use crate::io::ExitCode;
+use crate::{Symbol, SymbolId, SymbolKind};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Where CODEOWNERS files are looked for, in the order GitHub reads them
+pub const CODEOWNERS_LOCATIONS: &[&str] = &[
+    ".github/CODEOWNERS",
+    "CODEOWNERS",
+    "docs/CODEOWNERS",
+    ".gitlab/CODEOWNERS",
+];
+
+#[derive(Error, Debug)]
+pub enum OwnersError {
+    #[error(
+        "No CODEOWNERS file found in {path}\nSuggestion: Add one at .github/CODEOWNERS, CODEOWNERS or docs/CODEOWNERS"
+    )]
+    NoCodeOwners { path: PathBuf },
+
+    #[error(transparent)]
+    Diff(#[from] DiffError),
+}
+
+impl OwnersError {
+    pub fn exit_code(&self) -> ExitCode {
+        match self {
+            Self::NoCodeOwners { .. } => ExitCode::ConfigError,
+            Self::Diff(e) => e.exit_code(),
+        }
+    }
+}
+
+/// Parsed CODEOWNERS rules
+#[derive(Debug)]
+pub struct CodeOwners {
+    rules: Vec<OwnerRule>,
+}
+
+#[derive(Debug)]
+struct OwnerRule {
+    owners: Vec<String>,
+    matcher: Gitignore,
+}
+
+impl CodeOwners {
+    /// The first CODEOWNERS file found in `repository`
+    pub fn load(repository: &Path) -> Option<(PathBuf, Self)> {
+        CODEOWNERS_LOCATIONS.iter().find_map(|location| {
+            let path = repository.join(location);
+            let text = std::fs::read_to_string(&path).ok()?;
+            Some((path, Self::parse(repository, &text)))
+        })
+    }
+
+    /// Rules of a CODEOWNERS file whose patterns are relative to
+    /// `repository`. Lines that are not valid patterns are skipped, as are
+    /// GitLab section headers.
+    pub fn parse(repository: &Path, text: &str) -> Self {
+        let rules = text
+            .lines()
+            .filter_map(|line| {
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') || line.starts_with('[') {
+                    return None;
+                }
+                let mut fields = line
+                    .split_whitespace()
+                    .take_while(|field| !field.starts_with('#'));
+                let pattern = fields.next()?;
+                let mut builder = GitignoreBuilder::new(repository);
+                builder.add_line(None, pattern).ok()?;
+                Some(OwnerRule {
+                    owners: fields.map(str::to_string).collect(),
+                    matcher: builder.build().ok()?,
+                })
+            })
+            .collect();
+        Self { rules }
+    }
+
+    /// Owners of `file_path`, relative to the repository. The last matching
+    /// rule wins, and one listing no owners leaves the file unowned.
+    pub fn owners_of(&self, file_path: &str) -> &[String] {
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| {
+                rule.matcher
+                    .matched_path_or_any_parents(file_path, false)
+                    .is_ignore()
+            })
+            .map(|rule| rule.owners.as_slice())
+            .unwrap_or_default()
+    }
+}
+
+/// An indexed symbol a change reaches
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AffectedSymbol {
+    pub symbol_id: SymbolId,
+    pub name: String,
+    pub kind: SymbolKind,
+    /// Path relative to the repository root
+    pub file_path: String,
+    /// 1-based
+    pub line: u32,
+    /// Changed by the diff itself, rather than depending on changed code
+    pub changed: bool,
+}
+
+/// An owner and the affected code they own
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OwnerRoute {
+    /// Handle as written in CODEOWNERS, e.g. `@acme/payments`
+    pub owner: String,
+    /// Owns changed files, not only code depending on them
+    pub changed: bool,
+    pub files: Vec<String>,
+    pub symbols: Vec<AffectedSymbol>,
+}
+
+/// Owners to request review from for a diff
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReviewRouting {
+    /// The CODEOWNERS file read, relative to the repository root
+    pub codeowners: String,
+    pub changed_files: Vec<ChangedFile>,
+    /// Changed symbols followed by those within the impact radius
+    pub affected: Vec<AffectedSymbol>,
+    pub owners: Vec<OwnerRoute>,
+    /// Affected files no rule assigns an owner
+    pub unowned: Vec<String>,
+}
+
+/// Route the changes of `range` (see [`git_changes`]) to the owners of
+/// every file they affect, following dependents up to `depth` hops.
+pub fn review_routing(
+    facade: &IndexFacade,
+    range: &str,
+    depth: usize,
+) -> Result<ReviewRouting, OwnersError> {
+    let root = workspace_root(facade);
+    let changes = git_changes(&root, range)?;
+    let repository = changes.repository;
+    let (codeowners_path, codeowners) =
+        CodeOwners::load(&repository).ok_or_else(|| OwnersError::NoCodeOwners {
+            path: repository.clone(),
+        })?;
+
+    let affected_symbol = |symbol: &Symbol, changed: bool| AffectedSymbol {
+        symbol_id: symbol.id,
+        name: symbol.name.to_string(),
+        kind: symbol.kind,
+        file_path: repository_path(&root, &repository, &symbol.file_path),
+        line: symbol.range.start_line + 1,
+        changed,
+    };
+
+    let changed = changed_symbols(facade, &changes.files);
+    let mut affected: Vec<AffectedSymbol> = changed
+        .iter()
+        .map(|symbol| affected_symbol(symbol, true))
+        .collect();
+    let mut seen: HashSet<SymbolId> = changed.iter().map(|symbol| symbol.id).collect();
+    let mut dependents = Vec::new();
+    for symbol in &changed {
+        for id in facade.get_impact_radius(symbol.id, Some(depth)) {
+            if !seen.insert(id) {
+                continue;
+            }
+            if let Some(dependent) = facade.get_symbol(id) {
+                dependents.push(affected_symbol(&dependent, false));
+            }
+        }
+    }
+    dependents.sort_by(|a, b| {
+        a.file_path
+            .cmp(&b.file_path)
+            .then_with(|| a.line.cmp(&b.line))
+    });
+    affected.extend(dependents);
+
+    let mut routes: BTreeMap<&str, OwnerRoute> = BTreeMap::new();
+    let mut unowned = BTreeSet::new();
+    let mut route = |file_path: &str, changed: bool, symbol: Option<&AffectedSymbol>| {
+        let owners = codeowners.owners_of(file_path);
+        if owners.is_empty() {
+            unowned.insert(file_path.to_string());
+        }
+        for owner in owners {
+            let entry = routes.entry(owner.as_str()).or_insert_with(|| OwnerRoute {
+                owner: owner.clone(),
+                changed: false,
+                files: Vec::new(),
+                symbols: Vec::new(),
+            });
+            entry.changed |= changed;
+            if !entry.files.iter().any(|file| file == file_path) {
+                entry.files.push(file_path.to_string());
+            }
+            entry.symbols.extend(symbol.cloned());
+        }
+    };
+    for file in &changes.files {
+        route(&file.file_path, true, None);
+    }
+    for symbol in &affected {
+        route(&symbol.file_path, symbol.changed, Some(symbol));
+    }
+
+    Ok(ReviewRouting {
+        codeowners: codeowners_path
+            .strip_prefix(&repository)
+            .unwrap_or(&codeowners_path)
+            .to_string_lossy()
+            .replace('\\', "/"),
+        changed_files: changes.files,
+        affected,
+        owners: routes.into_values().collect(),
+        unowned: unowned.into_iter().collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_owners_of() {
+        let codeowners = CodeOwners::parse(
+            Path::new("/repo"),
+            "# Fallback\n\
+             *       @acme/core\n\
+             *.ts    @acme/web  # frontend\n\
+             /docs/  @acme/docs @alice\n\
+             [Generated]\n\
+             src/gen/\n",
+        );
+        assert_eq!(codeowners.owners_of("src/lib.rs"), ["@acme/core"]);
+        assert_eq!(codeowners.owners_of("web/src/app.ts"), ["@acme/web"]);
+        assert_eq!(
+            codeowners.owners_of("docs/guide/intro.md"),
+            ["@acme/docs", "@alice"]
+        );
+        // A later rule without owners unassigns the files it matches
+        assert!(codeowners.owners_of("src/gen/schema.rs").is_empty());
+    }
+}
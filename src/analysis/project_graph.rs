@@ -0,0 +1,386 @@
+//! Project dependency graph for monorepos.
+//!
+//! Lifts file-level dependencies to the projects detected at index time
+//! (see [`crate::indexing::projects`]): project A depends on project B when
+//! a symbol of A calls, uses, references, implements or extends one of B, or
+//! when a file of A imports B by package name (`acme_core::...`,
+//! `@acme/web/button`, `acme_core.models`). Relative imports stay inside
+//! their project and aren't counted.
+//!
+//! Projects that depend on each other in a loop form a cycle; each cycle is
+//! reported once, as the set of projects it runs through. The graph renders
+//! as Graphviz DOT or Mermaid, with cycle edges highlighted.
+
+use crate::indexing::facade::IndexFacade;
+use crate::{FileId, RelationKind, SymbolId};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+/// A project and its size in the index
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProjectNode {
+    pub name: String,
+    pub files: usize,
+    pub symbols: usize,
+}
+
+/// Project `from` depending on project `to`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProjectEdge {
+    pub from: String,
+    pub to: String,
+    /// Symbol relationships from `from` into `to`
+    pub relationships: usize,
+    /// Imports of `to` by files of `from`
+    pub imports: usize,
+}
+
+/// Projects, the dependencies between them and the cycles those form
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ProjectGraph {
+    /// Sorted by name
+    pub projects: Vec<ProjectNode>,
+    /// Sorted by `from`, then `to`
+    pub edges: Vec<ProjectEdge>,
+    /// Projects of each cycle, sorted
+    pub cycles: Vec<Vec<String>>,
+}
+
+impl ProjectGraph {
+    /// Graph over `projects` with `edges`, finding its cycles
+    pub fn new(mut projects: Vec<ProjectNode>, mut edges: Vec<ProjectEdge>) -> Self {
+        projects.sort_by(|a, b| a.name.cmp(&b.name));
+        edges.sort_by(|a, b| (&a.from, &a.to).cmp(&(&b.from, &b.to)));
+        let cycles = find_cycles(&edges);
+        Self {
+            projects,
+            edges,
+            cycles,
+        }
+    }
+
+    /// Whether the edge belongs to a cycle
+    pub fn in_cycle(&self, edge: &ProjectEdge) -> bool {
+        self.cycles
+            .iter()
+            .any(|cycle| cycle.contains(&edge.from) && cycle.contains(&edge.to))
+    }
+
+    /// Graphviz DOT rendering
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph projects {\n    rankdir=LR;\n    node [shape=box];\n");
+        for project in &self.projects {
+            out.push_str(&format!(
+                "    {} [label=\"{}\\n{} files\"];\n",
+                dot_id(&project.name),
+                project.name.replace('"', "\\\""),
+                project.files
+            ));
+        }
+        for edge in &self.edges {
+            let style = if self.in_cycle(edge) {
+                ", color=red"
+            } else {
+                ""
+            };
+            out.push_str(&format!(
+                "    {} -> {} [label=\"{}\"{style}];\n",
+                dot_id(&edge.from),
+                dot_id(&edge.to),
+                edge.relationships + edge.imports
+            ));
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// Mermaid flowchart rendering
+    pub fn to_mermaid(&self) -> String {
+        let ids: HashMap<&str, String> = self
+            .projects
+            .iter()
+            .enumerate()
+            .map(|(index, project)| (project.name.as_str(), format!("p{index}")))
+            .collect();
+        let mut out = String::from("flowchart LR\n");
+        for project in &self.projects {
+            out.push_str(&format!(
+                "    {}[\"{}\"]\n",
+                ids[project.name.as_str()],
+                project.name.replace('"', "#quot;")
+            ));
+        }
+        let mut cycle_links = Vec::new();
+        for (index, edge) in self.edges.iter().enumerate() {
+            let (Some(from), Some(to)) = (ids.get(edge.from.as_str()), ids.get(edge.to.as_str()))
+            else {
+                continue;
+            };
+            out.push_str(&format!(
+                "    {from} -->|{}| {to}\n",
+                edge.relationships + edge.imports
+            ));
+            if self.in_cycle(edge) {
+                cycle_links.push(index.to_string());
+            }
+        }
+        if !cycle_links.is_empty() {
+            out.push_str(&format!(
+                "    linkStyle {} stroke:red\n",
+                cycle_links.join(",")
+            ));
+        }
+        out
+    }
+}
+
+/// Quoted DOT identifier for a project name
+fn dot_id(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\\\""))
+}
+
+/// Strongly connected groups of more than one project, each sorted
+fn find_cycles(edges: &[ProjectEdge]) -> Vec<Vec<String>> {
+    struct Tarjan<'a> {
+        successors: BTreeMap<&'a str, Vec<&'a str>>,
+        index: HashMap<&'a str, usize>,
+        low: HashMap<&'a str, usize>,
+        stack: Vec<&'a str>,
+        on_stack: BTreeSet<&'a str>,
+        components: Vec<Vec<String>>,
+    }
+
+    impl<'a> Tarjan<'a> {
+        fn visit(&mut self, node: &'a str) {
+            let order = self.index.len();
+            self.index.insert(node, order);
+            self.low.insert(node, order);
+            self.stack.push(node);
+            self.on_stack.insert(node);
+
+            let successors = self.successors.get(node).cloned().unwrap_or_default();
+            for next in successors {
+                if !self.index.contains_key(next) {
+                    self.visit(next);
+                    let low = self.low[node].min(self.low[next]);
+                    self.low.insert(node, low);
+                } else if self.on_stack.contains(next) {
+                    let low = self.low[node].min(self.index[next]);
+                    self.low.insert(node, low);
+                }
+            }
+
+            if self.low[node] == self.index[node] {
+                let mut component = Vec::new();
+                while let Some(member) = self.stack.pop() {
+                    self.on_stack.remove(member);
+                    component.push(member.to_string());
+                    if member == node {
+                        break;
+                    }
+                }
+                if component.len() > 1 {
+                    component.sort();
+                    self.components.push(component);
+                }
+            }
+        }
+    }
+
+    let mut successors: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for edge in edges {
+        successors
+            .entry(edge.from.as_str())
+            .or_default()
+            .push(edge.to.as_str());
+    }
+    let nodes: Vec<&str> = successors.keys().copied().collect();
+    let mut tarjan = Tarjan {
+        successors,
+        index: HashMap::new(),
+        low: HashMap::new(),
+        stack: Vec::new(),
+        on_stack: BTreeSet::new(),
+        components: Vec::new(),
+    };
+    for node in nodes {
+        if !tarjan.index.contains_key(node) {
+            tarjan.visit(node);
+        }
+    }
+    tarjan.components.sort();
+    tarjan.components
+}
+
+/// The project an import path names, if it names one of `projects` other
+/// than `own`
+pub fn imported_project<'a>(path: &str, projects: &'a [String], own: &str) -> Option<&'a str> {
+    let leading = path.split([':', '.', '/']).next().unwrap_or(path);
+    projects
+        .iter()
+        .filter(|project| project.as_str() != own)
+        .find(|project| {
+            // npm names, scoped ones included, are matched whole
+            path == project.as_str()
+                || path
+                    .strip_prefix(project.as_str())
+                    .is_some_and(|rest| rest.starts_with('/'))
+                // Crate and distribution names import with underscores
+                || (!leading.is_empty() && leading == project.replace('-', "_"))
+        })
+        .map(String::as_str)
+}
+
+fn edge_between<'a>(
+    edges: &'a mut BTreeMap<(String, String), ProjectEdge>,
+    from: &str,
+    to: &str,
+) -> &'a mut ProjectEdge {
+    edges
+        .entry((from.to_string(), to.to_string()))
+        .or_insert_with(|| ProjectEdge {
+            from: from.to_string(),
+            to: to.to_string(),
+            relationships: 0,
+            imports: 0,
+        })
+}
+
+/// Project dependency graph of the index
+pub fn project_graph(facade: &IndexFacade) -> ProjectGraph {
+    let index = facade.document_index();
+
+    let mut symbol_projects: HashMap<SymbolId, String> = HashMap::new();
+    let mut file_projects: HashMap<FileId, String> = HashMap::new();
+    let mut nodes: BTreeMap<String, (HashSet<FileId>, usize)> = BTreeMap::new();
+    let _ = index.for_each_symbol(|symbol| {
+        if let Some(project) = symbol.project.as_deref() {
+            let (files, symbols) = nodes.entry(project.to_string()).or_default();
+            files.insert(symbol.file_id);
+            *symbols += 1;
+            symbol_projects.insert(symbol.id, project.to_string());
+            file_projects
+                .entry(symbol.file_id)
+                .or_insert_with(|| project.to_string());
+        }
+        Ok(())
+    });
+
+    let mut edges: BTreeMap<(String, String), ProjectEdge> = BTreeMap::new();
+
+    let relationships = index.query_relationships().unwrap_or_else(|e| {
+        tracing::warn!(target: "analysis", "failed to read relationships: {e}");
+        Vec::new()
+    });
+    for (from_id, to_id, relationship) in relationships {
+        if !matches!(
+            relationship.kind,
+            RelationKind::Calls
+                | RelationKind::Uses
+                | RelationKind::References
+                | RelationKind::Implements
+                | RelationKind::Extends
+        ) {
+            continue;
+        }
+        let (Some(from), Some(to)) = (symbol_projects.get(&from_id), symbol_projects.get(&to_id))
+        else {
+            continue;
+        };
+        if from != to {
+            edge_between(&mut edges, from, to).relationships += 1;
+        }
+    }
+
+    let names: Vec<String> = nodes.keys().cloned().collect();
+    for (file_id, project) in &file_projects {
+        for import in index.get_imports_for_file(*file_id).unwrap_or_default() {
+            if let Some(to) = imported_project(&import.path, &names, project) {
+                edge_between(&mut edges, project, to).imports += 1;
+            }
+        }
+    }
+
+    let projects = nodes
+        .into_iter()
+        .map(|(name, (files, symbols))| ProjectNode {
+            name,
+            files: files.len(),
+            symbols,
+        })
+        .collect();
+    ProjectGraph::new(projects, edges.into_values().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edge(from: &str, to: &str) -> ProjectEdge {
+        ProjectEdge {
+            from: from.to_string(),
+            to: to.to_string(),
+            relationships: 1,
+            imports: 0,
+        }
+    }
+
+    fn node(name: &str) -> ProjectNode {
+        ProjectNode {
+            name: name.to_string(),
+            files: 1,
+            symbols: 1,
+        }
+    }
+
+    #[test]
+    fn test_cycles() {
+        let graph = ProjectGraph::new(
+            ["api", "core", "web", "db", "cli"].map(node).to_vec(),
+            vec![
+                edge("web", "api"),
+                edge("api", "core"),
+                edge("core", "db"),
+                edge("db", "api"),
+                edge("cli", "core"),
+            ],
+        );
+        assert_eq!(graph.cycles, [["api", "core", "db"]]);
+        assert!(graph.in_cycle(&graph.edges[0]));
+        assert!(!graph.in_cycle(&edge("web", "api")));
+        assert!(!graph.in_cycle(&edge("cli", "core")));
+
+        let acyclic = ProjectGraph::new(["a", "b"].map(node).to_vec(), vec![edge("a", "b")]);
+        assert!(acyclic.cycles.is_empty());
+    }
+
+    #[test]
+    fn test_imported_project() {
+        let projects = ["acme-core", "@acme/web", "api"].map(String::from).to_vec();
+        let target = |path: &str, own: &str| imported_project(path, &projects, own);
+        assert_eq!(target("acme_core::parse::Parser", "api"), Some("acme-core"));
+        assert_eq!(
+            target("@acme/web/components/button", "api"),
+            Some("@acme/web")
+        );
+        assert_eq!(target("api.models", "acme-core"), Some("api"));
+        assert_eq!(target("apiary.models", "acme-core"), None);
+        assert_eq!(target("./button", "@acme/web"), None);
+        // A project's imports of itself aren't dependencies
+        assert_eq!(target("api.models", "api"), None);
+    }
+
+    #[test]
+    fn test_renderings() {
+        let graph = ProjectGraph::new(
+            ["a", "b"].map(node).to_vec(),
+            vec![edge("a", "b"), edge("b", "a")],
+        );
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph projects {"));
+        assert!(dot.contains("\"a\" -> \"b\" [label=\"1\", color=red];"));
+        let mermaid = graph.to_mermaid();
+        assert!(mermaid.contains("p0 -->|1| p1"));
+        assert!(mermaid.contains("linkStyle 0,1 stroke:red"));
+    }
+}
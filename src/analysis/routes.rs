@@ -0,0 +1,641 @@
+//! HTTP route inventory.
+//!
+//! Finds route declarations in web framework code and links each to the
+//! indexed symbol handling it, answering "which function serves
+//! /api/users/42". Covered:
+//!
+//! - Python: FastAPI and Flask decorators (`@app.get("/users")`,
+//!   `@bp.route("/users", methods=["POST"])`)
+//! - JavaScript/TypeScript: Express-style `app.get("/users", handler)`
+//! - PHP: Symfony `#[Route]` attributes and `@Route` annotations, Laravel
+//!   `Route::get("/users", [UserController::class, "index"])`
+//! - Rust: actix-web and Rocket attribute macros (`#[get("/users")]`), axum
+//!   `.route("/users", get(list).post(create))`
+//!
+//! Declarations are found textually, so paths are as written: prefixes
+//! added by mounting a router or blueprint elsewhere are not applied.
+
+use crate::analysis::source_scan::{SourceFile, for_each_source};
+use crate::indexing::facade::IndexFacade;
+use crate::parsing::signature::group_end;
+use crate::{Symbol, SymbolId, SymbolKind};
+use regex::Regex;
+use serde::Serialize;
+use std::sync::LazyLock;
+
+/// Lines between a decorator or attribute and the function it applies to
+const MAX_DECORATOR_GAP: u32 = 10;
+
+static PYTHON_DECORATOR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"@\s*[\w.]+\.(get|post|put|delete|patch|head|options|route|api_route|websocket)\(\s*(?:(?:path|rule)\s*=\s*)?(?:"([^"]*)"|'([^']*)')"#)
+        .expect("route pattern compiles")
+});
+
+static EXPRESS_CALL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"\b[\w$]+\.(get|post|put|delete|patch|head|options|all)\(\s*(?:"(/[^"]*)"|'(/[^']*)'|`(/[^`]*)`)"#)
+        .expect("route pattern compiles")
+});
+
+static SYMFONY_ROUTE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?:#\[\s*(?:[\w\\]+\\)?Route|@Route)\(\s*(?:path\s*:\s*)?(?:"([^"]*)"|'([^']*)')"#,
+    )
+    .expect("route pattern compiles")
+});
+
+static LARAVEL_ROUTE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"\bRoute::(get|post|put|patch|delete|options|any)\(\s*(?:"([^"]*)"|'([^']*)')"#)
+        .expect("route pattern compiles")
+});
+
+static RUST_ATTRIBUTE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"#\[\s*(?:\w+::)*(get|post|put|delete|patch|head|options|route)\(\s*"([^"]*)""#)
+        .expect("route pattern compiles")
+});
+
+static AXUM_ROUTE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"\.route\(\s*"([^"]*)"\s*,"#).expect("route pattern compiles"));
+
+static AXUM_METHOD: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b(get|post|put|delete|patch|head|options|any)\(\s*([\w:]+)\s*\)")
+        .expect("route pattern compiles")
+});
+
+/// `methods=[...]`, `methods: [...]`, `methods={...}`, `method = "..."`
+static METHODS_ARG: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"\bmethods?\s*[:=]\s*(?:\[([^\]]*)\]|\{([^}]*)\}|\(([^)]*)\)|["'](\w+)["'])"#)
+        .expect("route pattern compiles")
+});
+
+static WORD: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\w+").expect("word pattern compiles"));
+
+/// The indexed symbol serving a route
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RouteHandler {
+    pub name: String,
+    pub symbol_id: SymbolId,
+    pub kind: SymbolKind,
+    pub file_path: String,
+    /// 1-based
+    pub line: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub module_path: Option<String>,
+}
+
+/// A route declaration
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Route {
+    /// Upper-case HTTP method, or `ANY`
+    pub method: String,
+    /// Path as declared, e.g. `/users/{id}`
+    pub path: String,
+    pub framework: String,
+    pub language: String,
+    pub file_path: String,
+    /// 1-based
+    pub line: u32,
+    /// 1-based
+    pub column: u32,
+    /// Unset when the handler is inline or couldn't be resolved
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub handler: Option<RouteHandler>,
+    /// The source line of the declaration, trimmed
+    pub code: String,
+}
+
+/// How a declaration names its handler
+#[derive(Debug, Clone, PartialEq)]
+enum HandlerRef {
+    /// The function the decorator or attribute applies to
+    Following,
+    /// A function or method passed by name
+    Named(String),
+    /// An inline closure or something not understood
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Declaration {
+    framework: &'static str,
+    methods: Vec<String>,
+    path: String,
+    offset: usize,
+    handler: HandlerRef,
+}
+
+/// Routes declared across the index, by file path then position.
+///
+/// `language` restricts the scan to one language (e.g. "python").
+pub fn find_routes(facade: &IndexFacade, language: Option<&str>) -> Vec<Route> {
+    let mut routes = Vec::new();
+    for_each_source(facade, language, |file| {
+        for declaration in declarations(file.language, file.source) {
+            let location = file.locate(declaration.offset);
+            let handler = match &declaration.handler {
+                HandlerRef::Following => {
+                    following_function(file.symbols, location.line - 1).cloned()
+                }
+                HandlerRef::Named(name) => named_handler(facade, file, name),
+                HandlerRef::Unknown => None,
+            };
+            let handler = handler.map(|symbol| RouteHandler {
+                name: symbol.name.to_string(),
+                symbol_id: symbol.id,
+                kind: symbol.kind,
+                file_path: symbol.file_path.to_string(),
+                line: symbol.range.start_line + 1,
+                module_path: symbol.module_path.as_deref().map(str::to_string),
+            });
+            for method in declaration.methods {
+                routes.push(Route {
+                    method,
+                    path: declaration.path.clone(),
+                    framework: declaration.framework.to_string(),
+                    language: file.language.to_string(),
+                    file_path: file.file_path.to_string(),
+                    line: location.line,
+                    column: location.column,
+                    handler: handler.clone(),
+                    code: location.code.clone(),
+                });
+            }
+        }
+    });
+    routes.sort_by(|a, b| {
+        (a.file_path.as_str(), a.line, a.column, a.method.as_str()).cmp(&(
+            b.file_path.as_str(),
+            b.line,
+            b.column,
+            b.method.as_str(),
+        ))
+    });
+    routes
+}
+
+/// Whether a request for `path` is served by a route declared as
+/// `template`.
+///
+/// Parameter segments (`:id`, `{id}`, `<int:id>`) match any one segment,
+/// and catch-alls (`*`, `{*rest}`, `{rest:path}`, `<path:rest>`) the rest
+/// of the path. A parameter in `path` itself only matches a parameter, so
+/// `/users/:id` finds `/users/{user_id}` but not `/users/me`.
+pub fn route_matches(template: &str, path: &str) -> bool {
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+    let mut wanted = path.split('/').filter(|segment| !segment.is_empty());
+    let mut declared = template.split('/').filter(|segment| !segment.is_empty());
+    loop {
+        match (declared.next(), wanted.next()) {
+            (None, None) => return true,
+            (Some(segment), _) if is_catch_all(segment) => return true,
+            (Some(segment), Some(request)) => {
+                let matches = if is_parameter(request) {
+                    is_parameter(segment)
+                } else {
+                    is_parameter(segment) || segment == request
+                };
+                if !matches {
+                    return false;
+                }
+            }
+            _ => return false,
+        }
+    }
+}
+
+/// Whether a path segment is a parameter: `:id`, `{id}`, `<int:id>`
+pub(crate) fn is_parameter(segment: &str) -> bool {
+    segment.starts_with(':') || segment.contains(['{', '<'])
+}
+
+fn is_catch_all(segment: &str) -> bool {
+    segment.starts_with('*')
+        || segment.starts_with("{*")
+        || segment.ends_with(":path}")
+        || segment.starts_with("<path:")
+        || segment.ends_with("...}")
+}
+
+/// Route declarations in a `language` source file
+fn declarations(language: &str, source: &str) -> Vec<Declaration> {
+    match language {
+        "python" => python_declarations(source),
+        "javascript" | "typescript" => express_declarations(source),
+        "php" => php_declarations(source),
+        "rust" => rust_declarations(source),
+        _ => Vec::new(),
+    }
+}
+
+fn python_declarations(source: &str) -> Vec<Declaration> {
+    PYTHON_DECORATOR
+        .captures_iter(source)
+        .filter_map(|captures| {
+            let verb = captures.get(1)?;
+            let path = captures.get(2).or(captures.get(3))?.as_str();
+            let (framework, methods) = match verb.as_str() {
+                "route" => ("flask", call_methods(source, verb.end(), "GET")),
+                "api_route" => ("fastapi", call_methods(source, verb.end(), "GET")),
+                "websocket" => ("fastapi", vec!["WS".to_string()]),
+                method => ("fastapi", vec![method.to_ascii_uppercase()]),
+            };
+            Some(Declaration {
+                framework,
+                methods,
+                path: path.to_string(),
+                offset: captures.get(0)?.start(),
+                handler: HandlerRef::Following,
+            })
+        })
+        .collect()
+}
+
+fn express_declarations(source: &str) -> Vec<Declaration> {
+    EXPRESS_CALL
+        .captures_iter(source)
+        .filter_map(|captures| {
+            let verb = captures.get(1)?;
+            let path = captures
+                .get(2)
+                .or(captures.get(3))
+                .or(captures.get(4))?
+                .as_str();
+            let method = match verb.as_str() {
+                "all" => "ANY".to_string(),
+                method => method.to_ascii_uppercase(),
+            };
+            let handler = call_arguments(source, verb.end())
+                .and_then(last_argument)
+                .and_then(|argument| {
+                    let valid = !argument.is_empty()
+                        && argument
+                            .chars()
+                            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '$' | '.'));
+                    valid.then(|| argument.rsplit('.').next().unwrap_or(argument))
+                })
+                .map_or(HandlerRef::Unknown, |name| {
+                    HandlerRef::Named(name.to_string())
+                });
+            Some(Declaration {
+                framework: "express",
+                methods: vec![method],
+                path: path.to_string(),
+                offset: captures.get(0)?.start(),
+                handler,
+            })
+        })
+        .collect()
+}
+
+fn php_declarations(source: &str) -> Vec<Declaration> {
+    let symfony = SYMFONY_ROUTE.captures_iter(source).filter_map(|captures| {
+        let whole = captures.get(0)?;
+        let path = captures.get(1).or(captures.get(2))?.as_str();
+        let name_end = whole.start() + whole.as_str().find("Route")? + "Route".len();
+        Some(Declaration {
+            framework: "symfony",
+            methods: call_methods(source, name_end, "ANY"),
+            path: path.to_string(),
+            offset: whole.start(),
+            handler: HandlerRef::Following,
+        })
+    });
+    let laravel = LARAVEL_ROUTE.captures_iter(source).filter_map(|captures| {
+        let verb = captures.get(1)?;
+        let path = captures.get(2).or(captures.get(3))?.as_str();
+        let method = match verb.as_str() {
+            "any" => "ANY".to_string(),
+            method => method.to_ascii_uppercase(),
+        };
+        let handler = call_arguments(source, verb.end())
+            .and_then(last_argument)
+            .and_then(laravel_action)
+            .map_or(HandlerRef::Unknown, |name| {
+                HandlerRef::Named(name.to_string())
+            });
+        Some(Declaration {
+            framework: "laravel",
+            methods: vec![method],
+            path: path.to_string(),
+            offset: captures.get(0)?.start(),
+            handler,
+        })
+    });
+    symfony.chain(laravel).collect()
+}
+
+/// Method named by a Laravel action: `[UserController::class, 'show']`,
+/// `'UserController@show'`, or an invokable `UserController::class`
+fn laravel_action(argument: &str) -> Option<&str> {
+    if let Some(list) = argument.strip_prefix('[') {
+        let method = list.trim_end_matches(']').rsplit(',').next()?;
+        let method = method.trim().trim_matches(['\'', '"']);
+        return (!method.is_empty() && !method.contains("::")).then_some(method);
+    }
+    if let Some((_, method)) = argument.trim_matches(['\'', '"']).split_once('@') {
+        return Some(method);
+    }
+    argument
+        .strip_suffix("::class")
+        .map(|class| class.rsplit('\\').next().unwrap_or(class))
+}
+
+fn rust_declarations(source: &str) -> Vec<Declaration> {
+    let attributes = RUST_ATTRIBUTE.captures_iter(source).filter_map(|captures| {
+        let verb = captures.get(1)?;
+        let methods = match verb.as_str() {
+            "route" => call_methods(source, verb.end(), "ANY"),
+            method => vec![method.to_ascii_uppercase()],
+        };
+        Some(Declaration {
+            framework: "actix",
+            methods,
+            path: captures.get(2)?.as_str().to_string(),
+            offset: captures.get(0)?.start(),
+            handler: HandlerRef::Following,
+        })
+    });
+    let axum = AXUM_ROUTE.captures_iter(source).flat_map(|captures| {
+        let whole = captures.get(0).expect("group 0 always matches");
+        let path = captures.get(1).map_or("", |path| path.as_str());
+        let routers = call_arguments(source, whole.start() + ".route".len()).unwrap_or_default();
+        AXUM_METHOD
+            .captures_iter(routers)
+            .filter_map(|method| {
+                let verb = method.get(1)?.as_str();
+                let handler = method.get(2)?.as_str();
+                Some(Declaration {
+                    framework: "axum",
+                    methods: vec![if verb == "any" {
+                        "ANY".to_string()
+                    } else {
+                        verb.to_ascii_uppercase()
+                    }],
+                    path: path.to_string(),
+                    offset: whole.start() + 1,
+                    handler: HandlerRef::Named(
+                        handler.rsplit("::").next().unwrap_or(handler).to_string(),
+                    ),
+                })
+            })
+            .collect::<Vec<_>>()
+    });
+    attributes.chain(axum).collect()
+}
+
+/// The arguments of the call whose `(` follows `from`, without the
+/// parentheses
+fn call_arguments(source: &str, from: usize) -> Option<&str> {
+    let open = from + source[from..].find('(')?;
+    let end = match group_end(source, open) {
+        Some(end) => end - 1,
+        None => source[open..]
+            .find('\n')
+            .map_or(source.len(), |newline| open + newline),
+    };
+    Some(&source[open + 1..end])
+}
+
+/// HTTP methods listed in the call whose `(` follows `from`, upper-cased,
+/// or just `default` when it lists none
+fn call_methods(source: &str, from: usize, default: &str) -> Vec<String> {
+    let arguments = call_arguments(source, from).unwrap_or_default();
+    let mut methods: Vec<String> = METHODS_ARG
+        .captures_iter(arguments)
+        .filter_map(|captures| captures.iter().skip(1).flatten().next())
+        .flat_map(|list| WORD.find_iter(list.as_str()))
+        .map(|method| method.as_str().to_ascii_uppercase())
+        .collect();
+    methods.dedup();
+    if methods.is_empty() {
+        methods.push(default.to_string());
+    }
+    methods
+}
+
+/// The last top-level argument of a comma-separated list, trimmed
+fn last_argument(arguments: &str) -> Option<&str> {
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut start = 0;
+    for (i, c) in arguments.char_indices() {
+        if let Some(q) = quote {
+            if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' | '`' => quote = Some(c),
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => start = i + 1,
+            _ => {}
+        }
+    }
+    let last = arguments[start..].trim();
+    (!last.is_empty()).then_some(last)
+}
+
+/// The first function or method starting on or shortly after `row`
+fn following_function(symbols: &[Symbol], row: u32) -> Option<&Symbol> {
+    symbols
+        .iter()
+        .filter(|symbol| matches!(symbol.kind, SymbolKind::Function | SymbolKind::Method))
+        .filter(|symbol| {
+            symbol.range.start_line >= row && symbol.range.start_line <= row + MAX_DECORATOR_GAP
+        })
+        .min_by_key(|symbol| symbol.range.start_line)
+}
+
+/// The handler called `name`: a symbol in the declaring file, else the
+/// first match in the index
+fn named_handler(facade: &IndexFacade, file: &SourceFile, name: &str) -> Option<Symbol> {
+    let handler_kind = |symbol: &&Symbol| {
+        matches!(
+            symbol.kind,
+            SymbolKind::Function | SymbolKind::Method | SymbolKind::Class
+        )
+    };
+    if let Some(symbol) = file
+        .symbols
+        .iter()
+        .filter(|symbol| &*symbol.name == name)
+        .find(handler_kind)
+    {
+        return Some(symbol.clone());
+    }
+    facade
+        .find_symbols_by_name(name, Some(file.language))
+        .iter()
+        .find(handler_kind)
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(declarations: Vec<Declaration>) -> Vec<(String, String, &'static str, HandlerRef)> {
+        declarations
+            .into_iter()
+            .flat_map(|declaration| {
+                declaration.methods.into_iter().map(move |method| {
+                    (
+                        method,
+                        declaration.path.clone(),
+                        declaration.framework,
+                        declaration.handler.clone(),
+                    )
+                })
+            })
+            .collect()
+    }
+
+    fn named(name: &str) -> HandlerRef {
+        HandlerRef::Named(name.to_string())
+    }
+
+    #[test]
+    fn test_python_decorators() {
+        let source = r#"
+@app.get("/users/{user_id}")
+async def get_user(user_id: int): ...
+
+@bp.route('/login', methods=["GET", "POST"])
+def login(): ...
+"#;
+        assert_eq!(
+            summary(declarations("python", source)),
+            [
+                (
+                    "GET".to_string(),
+                    "/users/{user_id}".to_string(),
+                    "fastapi",
+                    HandlerRef::Following
+                ),
+                (
+                    "GET".to_string(),
+                    "/login".to_string(),
+                    "flask",
+                    HandlerRef::Following
+                ),
+                (
+                    "POST".to_string(),
+                    "/login".to_string(),
+                    "flask",
+                    HandlerRef::Following
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_express_and_laravel_handlers() {
+        let source = r#"
+router.post('/users', auth, users.create);
+app.get("/health", (req, res) => res.send("ok"));
+cache.get("key");
+"#;
+        assert_eq!(
+            summary(declarations("typescript", source)),
+            [
+                (
+                    "POST".to_string(),
+                    "/users".to_string(),
+                    "express",
+                    named("create")
+                ),
+                (
+                    "GET".to_string(),
+                    "/health".to_string(),
+                    "express",
+                    HandlerRef::Unknown
+                ),
+            ]
+        );
+
+        let source = r#"
+Route::get('/users/{id}', [UserController::class, 'show']);
+Route::post('/users', 'UserController@store');
+
+#[Route('/posts', methods: ['GET', 'HEAD'])]
+public function index(): Response {}
+"#;
+        assert_eq!(
+            summary(declarations("php", source)),
+            [
+                (
+                    "GET".to_string(),
+                    "/posts".to_string(),
+                    "symfony",
+                    HandlerRef::Following
+                ),
+                (
+                    "HEAD".to_string(),
+                    "/posts".to_string(),
+                    "symfony",
+                    HandlerRef::Following
+                ),
+                (
+                    "GET".to_string(),
+                    "/users/{id}".to_string(),
+                    "laravel",
+                    named("show")
+                ),
+                (
+                    "POST".to_string(),
+                    "/users".to_string(),
+                    "laravel",
+                    named("store")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rust_attributes_and_axum() {
+        let source = r#"
+#[get("/items/{id}")]
+async fn item(id: web::Path<u32>) -> impl Responder {}
+
+let app = Router::new()
+    .route("/items", get(handlers::list).post(create_item))
+    .route("/static/{*path}", get_service(ServeDir::new("assets")));
+"#;
+        assert_eq!(
+            summary(declarations("rust", source)),
+            [
+                (
+                    "GET".to_string(),
+                    "/items/{id}".to_string(),
+                    "actix",
+                    HandlerRef::Following
+                ),
+                (
+                    "GET".to_string(),
+                    "/items".to_string(),
+                    "axum",
+                    named("list")
+                ),
+                (
+                    "POST".to_string(),
+                    "/items".to_string(),
+                    "axum",
+                    named("create_item")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_route_matching() {
+        assert!(route_matches("/api/users/{id}", "/api/users/42"));
+        assert!(route_matches("/api/users/:id", "/api/users/42/"));
+        assert!(route_matches("/api/users/<int:id>", "/api/users/:id"));
+        assert!(route_matches("/static/{*path}", "/static/css/site.css"));
+        assert!(route_matches("/api/users/me", "/api/users/me?full=1"));
+        assert!(!route_matches("/api/users/me", "/api/users/:id"));
+        assert!(!route_matches("/api/users/{id}", "/api/users"));
+        assert!(!route_matches("/api/users", "/api/users/42"));
+    }
+}
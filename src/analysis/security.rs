@@ -0,0 +1,344 @@
+//! Security-sensitive call detection.
+//!
+//! Flags calls to APIs that are dangerous with untrusted input: code
+//! evaluation, unsafe deserialization, unbounded string copies, and the like.
+//! Rules name callees per language as written in source; the built-in pack
+//! can be extended or replaced through `[security]` in settings.
+//!
+//! Calls into external APIs never resolve to indexed symbols, so each file is
+//! reparsed and the callee of every call node compared against the rules.
+//! Languages take part by declaring their call node kinds (see
+//! [`LanguageBehavior::call_node_kinds`]). Findings carry the innermost
+//! indexed symbol enclosing the call.
+//!
+//! [`LanguageBehavior::call_node_kinds`]: crate::parsing::LanguageBehavior::call_node_kinds
+
+use crate::config::{SecurityConfig, Severity};
+use crate::indexing::facade::IndexFacade;
+use crate::navigation::source_path;
+use crate::parsing::parser::check_recursion_depth;
+use crate::parsing::{LanguageBehavior, get_registry};
+use crate::types::LineIndex;
+use crate::{FileId, Symbol, SymbolKind};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use tree_sitter::Node;
+
+/// Built-in rules: language, callees, severity, message
+const BUILTIN_RULES: &[(&str, &[&str], Severity, &str)] = &[
+    (
+        "python",
+        &["eval", "exec"],
+        Severity::High,
+        "evaluates a string as code",
+    ),
+    (
+        "python",
+        &[
+            "pickle.load",
+            "pickle.loads",
+            "marshal.load",
+            "marshal.loads",
+        ],
+        Severity::High,
+        "deserialization can run arbitrary code",
+    ),
+    (
+        "python",
+        &["yaml.load"],
+        Severity::Medium,
+        "constructs arbitrary objects unless a safe loader is passed",
+    ),
+    (
+        "php",
+        &["eval", "assert", "create_function"],
+        Severity::High,
+        "evaluates a string as code",
+    ),
+    (
+        "php",
+        &["unserialize"],
+        Severity::High,
+        "deserialization can instantiate arbitrary classes",
+    ),
+    (
+        "php",
+        &["system", "exec", "shell_exec", "passthru", "popen"],
+        Severity::Medium,
+        "runs a shell command",
+    ),
+    (
+        "c",
+        &["strcpy", "strcat", "gets", "sprintf", "vsprintf"],
+        Severity::High,
+        "writes without a bound and can overflow its buffer",
+    ),
+    (
+        "c",
+        &["system", "popen"],
+        Severity::Medium,
+        "runs a shell command",
+    ),
+    (
+        "nix",
+        &["builtins.exec"],
+        Severity::High,
+        "runs a program during evaluation",
+    ),
+    (
+        "nix",
+        &[
+            "builtins.fetchurl",
+            "builtins.fetchTarball",
+            "builtins.fetchGit",
+        ],
+        Severity::Low,
+        "fetches during evaluation, unpinned unless a hash is given",
+    ),
+];
+
+/// A call matched by a rule
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SecurityFinding {
+    /// Callee as matched, e.g. `pickle.load`
+    pub call: String,
+    pub severity: Severity,
+    pub message: String,
+    pub language: String,
+    pub file_path: String,
+    /// 1-based
+    pub line: u32,
+    /// 1-based
+    pub column: u32,
+    /// Innermost indexed symbol containing the call
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol_kind: Option<SymbolKind>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub module_path: Option<String>,
+    /// The source line of the call, trimmed
+    pub code: String,
+}
+
+/// Security rules by language, then callee
+#[derive(Debug, Default)]
+pub struct RulePack {
+    rules: HashMap<String, HashMap<String, (Severity, String)>>,
+}
+
+impl RulePack {
+    /// The built-in rules (unless disabled) followed by the configured ones,
+    /// which override built-ins for the same callee
+    pub fn from_config(config: &SecurityConfig) -> Self {
+        let mut pack = Self::default();
+        if config.builtin_rules {
+            for (language, calls, severity, message) in BUILTIN_RULES {
+                for call in *calls {
+                    pack.insert(language, call, *severity, message);
+                }
+            }
+        }
+        for rule in &config.rules {
+            let message = rule
+                .message
+                .as_deref()
+                .unwrap_or("flagged by security rule");
+            for call in &rule.calls {
+                pack.insert(&rule.language, call, rule.severity, message);
+            }
+        }
+        pack
+    }
+
+    fn insert(&mut self, language: &str, call: &str, severity: Severity, message: &str) {
+        self.rules
+            .entry(language.to_ascii_lowercase())
+            .or_default()
+            .insert(call.to_string(), (severity, message.to_string()));
+    }
+
+    fn language(&self, language: &str) -> Option<&HashMap<String, (Severity, String)>> {
+        self.rules.get(language).filter(|rules| !rules.is_empty())
+    }
+}
+
+/// Security findings across the index, by file path then position.
+///
+/// `language` restricts the check to one language (e.g. "python").
+pub fn find_security_calls(
+    facade: &IndexFacade,
+    pack: &RulePack,
+    language: Option<&str>,
+) -> Vec<SecurityFinding> {
+    let index = facade.document_index();
+    let mut symbols: HashMap<FileId, Vec<Symbol>> = HashMap::new();
+    let _ = index.for_each_symbol(|symbol| {
+        symbols.entry(symbol.file_id).or_default().push(symbol);
+        Ok(())
+    });
+
+    let mut findings = Vec::new();
+    for (file_id, file_path, _, _) in index.query_file_info().unwrap_or_default() {
+        let Some((language_name, behavior)) = behavior_for(&file_path) else {
+            continue;
+        };
+        if language.is_some_and(|lang| !lang.eq_ignore_ascii_case(&language_name)) {
+            continue;
+        }
+        let (Some(rules), kinds) = (pack.language(&language_name), behavior.call_node_kinds())
+        else {
+            continue;
+        };
+        if kinds.is_empty() {
+            continue;
+        }
+
+        let source = match std::fs::read_to_string(source_path(facade, &file_path)) {
+            Ok(source) => source,
+            Err(e) => {
+                tracing::debug!(target: "analysis", "skipping {file_path}: {e}");
+                continue;
+            }
+        };
+        let mut parser = tree_sitter::Parser::new();
+        if parser.set_language(&behavior.get_language()).is_err() {
+            continue;
+        }
+        let Some(tree) = parser.parse(&source, None) else {
+            continue;
+        };
+
+        let file_symbols = symbols.get(&file_id).map(Vec::as_slice).unwrap_or_default();
+        let lines = LineIndex::new(&source);
+        for (call, row, column) in find_calls(tree.root_node(), &source, kinds) {
+            let Some((severity, message)) = rules.get(&call) else {
+                continue;
+            };
+            let enclosing = enclosing_symbol(file_symbols, row, column);
+            findings.push(SecurityFinding {
+                call,
+                severity: *severity,
+                message: message.clone(),
+                language: language_name.clone(),
+                file_path: file_path.clone(),
+                line: row + 1,
+                column: column + 1,
+                symbol: enclosing.map(|symbol| symbol.name.to_string()),
+                symbol_kind: enclosing.map(|symbol| symbol.kind),
+                module_path: enclosing
+                    .and_then(|symbol| symbol.module_path.as_deref())
+                    .map(str::to_string),
+                code: lines
+                    .line(&source, row)
+                    .map(|line| line.trim().to_string())
+                    .unwrap_or_default(),
+            });
+        }
+    }
+
+    findings.sort_by(|a, b| {
+        (a.file_path.as_str(), a.line, a.column).cmp(&(b.file_path.as_str(), b.line, b.column))
+    });
+    findings
+}
+
+/// Callees of the call nodes under `root` as written, with whitespace and a
+/// leading namespace separator removed, and their 0-based position
+pub fn find_calls(root: Node, code: &str, kinds: &[(&str, &str)]) -> Vec<(String, u32, u32)> {
+    let mut calls = Vec::new();
+    visit(root, code, kinds, &mut calls, 0);
+    calls
+}
+
+fn visit(
+    node: Node,
+    code: &str,
+    kinds: &[(&str, &str)],
+    calls: &mut Vec<(String, u32, u32)>,
+    depth: usize,
+) {
+    if !check_recursion_depth(depth, node) {
+        return;
+    }
+    let callee = kinds
+        .iter()
+        .find(|(kind, _)| *kind == node.kind())
+        .and_then(|(_, field)| node.child_by_field_name(field));
+    if let Some(Ok(text)) = callee.map(|callee| callee.utf8_text(code.as_bytes())) {
+        let callee: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+        calls.push((
+            callee.trim_start_matches('\\').to_string(),
+            node.start_position().row as u32,
+            node.start_position().column as u32,
+        ));
+    }
+    for child in node.children(&mut node.walk()) {
+        visit(child, code, kinds, calls, depth + 1);
+    }
+}
+
+/// The smallest symbol in `symbols` whose range holds the position
+pub(crate) fn enclosing_symbol(symbols: &[Symbol], row: u32, column: u32) -> Option<&Symbol> {
+    symbols
+        .iter()
+        .filter(|symbol| symbol.range.contains(row, column as u16))
+        .min_by_key(|symbol| {
+            (
+                symbol.range.end_line - symbol.range.start_line,
+                symbol
+                    .range
+                    .end_column
+                    .saturating_sub(symbol.range.start_column),
+            )
+        })
+}
+
+/// Language name and behavior for a file, by extension
+fn behavior_for(file_path: &str) -> Option<(String, Box<dyn LanguageBehavior>)> {
+    let extension = Path::new(file_path).extension()?.to_str()?;
+    let registry = get_registry().lock().ok()?;
+    let definition = registry.get_by_extension(extension)?;
+    Some((
+        definition.id().as_str().to_string(),
+        definition.create_behavior(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_callees_match_builtin_python_rules() {
+        let code = "import pickle\n\ndef load(path):\n    data = pickle.load(open(path))\n    return evaluate(data) or eval(data)\n";
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&tree_sitter_python::LANGUAGE.into())
+            .unwrap();
+        let tree = parser.parse(code, None).unwrap();
+        let pack = RulePack::from_config(&SecurityConfig::default());
+        let rules = pack.language("python").unwrap();
+
+        let flagged: Vec<(String, u32)> =
+            find_calls(tree.root_node(), code, &[("call", "function")])
+                .into_iter()
+                .filter(|(call, _, _)| rules.contains_key(call))
+                .map(|(call, row, _)| (call, row))
+                .collect();
+
+        assert_eq!(
+            flagged,
+            vec![("pickle.load".to_string(), 3), ("eval".to_string(), 4)]
+        );
+        assert!(
+            RulePack::from_config(&SecurityConfig {
+                builtin_rules: false,
+                rules: Vec::new(),
+            })
+            .language("python")
+            .is_none()
+        );
+    }
+}
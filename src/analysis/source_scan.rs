@@ -0,0 +1,155 @@
+//! Textual scans over indexed source files.
+//!
+//! Reports that look for string literals (flag keys, translation keys)
+//! match source text rather than the symbol graph. This walks the indexed
+//! files of the languages asked for and turns match offsets into positions
+//! with their enclosing symbol, and finds the project files no parser
+//! indexes, such as `.proto` schemas.
+
+use crate::analysis::entry_points::SKIPPED_DIRS;
+use crate::analysis::security::enclosing_symbol;
+use crate::indexing::facade::IndexFacade;
+use crate::navigation::source_path;
+use crate::parsing::get_registry;
+use crate::types::LineIndex;
+use crate::{FileId, Symbol, SymbolId, SymbolKind};
+use ignore::WalkBuilder;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// An indexed file's source
+pub(crate) struct SourceFile<'a> {
+    pub file_path: &'a str,
+    /// Language id, e.g. "typescript"
+    pub language: &'a str,
+    pub source: &'a str,
+    /// Indexed symbols of the file
+    pub symbols: &'a [Symbol],
+    lines: LineIndex,
+}
+
+/// Where a match sits
+pub(crate) struct Location {
+    /// 1-based
+    pub line: u32,
+    /// 1-based
+    pub column: u32,
+    /// Innermost indexed symbol containing the match
+    pub symbol: Option<String>,
+    pub symbol_id: Option<SymbolId>,
+    pub symbol_kind: Option<SymbolKind>,
+    pub module_path: Option<String>,
+    /// The source line of the match, trimmed
+    pub code: String,
+}
+
+impl SourceFile<'_> {
+    /// Position of the byte `offset`
+    pub fn locate(&self, offset: usize) -> Location {
+        let row = self.lines.line_of(offset);
+        let line_start = self.lines.line_start(row).unwrap_or_default();
+        let column = (offset - line_start) as u32;
+        let enclosing = enclosing_symbol(self.symbols, row, column);
+        Location {
+            line: row + 1,
+            column: column + 1,
+            symbol: enclosing.map(|symbol| symbol.name.to_string()),
+            symbol_id: enclosing.map(|symbol| symbol.id),
+            symbol_kind: enclosing.map(|symbol| symbol.kind),
+            module_path: enclosing
+                .and_then(|symbol| symbol.module_path.as_deref())
+                .map(str::to_string),
+            code: self
+                .lines
+                .line(self.source, row)
+                .unwrap_or_default()
+                .trim()
+                .to_string(),
+        }
+    }
+
+    /// Byte offset of a 0-based position, clamped to the file
+    pub fn offset(&self, row: u32, column: u32) -> usize {
+        self.lines
+            .line_start(row)
+            .map_or(self.source.len(), |start| start + column as usize)
+            .min(self.source.len())
+    }
+}
+
+/// Call `visit` with every readable indexed file, optionally only those of
+/// one `language`
+pub(crate) fn for_each_source(
+    facade: &IndexFacade,
+    language: Option<&str>,
+    mut visit: impl FnMut(&SourceFile),
+) {
+    let index = facade.document_index();
+    let mut symbols: HashMap<FileId, Vec<Symbol>> = HashMap::new();
+    let _ = index.for_each_symbol(|symbol| {
+        symbols.entry(symbol.file_id).or_default().push(symbol);
+        Ok(())
+    });
+
+    for (file_id, file_path, _, _) in index.query_file_info().unwrap_or_default() {
+        let Some(language_name) = language_of(&file_path) else {
+            continue;
+        };
+        if language.is_some_and(|lang| !lang.eq_ignore_ascii_case(&language_name)) {
+            continue;
+        }
+        let source = match std::fs::read_to_string(source_path(facade, &file_path)) {
+            Ok(source) => source,
+            Err(e) => {
+                tracing::debug!(target: "analysis", "skipping {file_path}: {e}");
+                continue;
+            }
+        };
+        let lines = LineIndex::new(&source);
+        visit(&SourceFile {
+            file_path: &file_path,
+            language: &language_name,
+            source: &source,
+            symbols: symbols.get(&file_id).map(Vec::as_slice).unwrap_or_default(),
+            lines,
+        });
+    }
+}
+
+/// Files under `root` with `extension`, indexed or not, as their path
+/// relative to `root` and their full path, sorted
+pub(crate) fn workspace_files(root: &Path, extension: &str) -> Vec<(String, PathBuf)> {
+    let mut files: Vec<(String, PathBuf)> = WalkBuilder::new(root)
+        .filter_entry(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .is_none_or(|name| !SKIPPED_DIRS.contains(&name))
+        })
+        .build()
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            entry.file_type().is_some_and(|kind| kind.is_file())
+                && entry.path().extension().is_some_and(|ext| ext == extension)
+        })
+        .map(|entry| {
+            let relative = entry
+                .path()
+                .strip_prefix(root)
+                .unwrap_or(entry.path())
+                .to_string_lossy()
+                .replace('\\', "/");
+            (relative, entry.into_path())
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// Language id of a file, by extension
+fn language_of(file_path: &str) -> Option<String> {
+    let extension = Path::new(file_path).extension()?.to_str()?;
+    let registry = get_registry().lock().ok()?;
+    let definition = registry.get_by_extension(extension)?;
+    Some(definition.id().as_str().to_string())
+}
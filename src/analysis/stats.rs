@@ -0,0 +1,271 @@
+//! Index size and content summary.
+//!
+//! [`IndexSummary`] counts what the index holds: files and symbols per
+//! language and per monorepo project, symbols and relationships per kind,
+//! symbols per `[[tags]]` tag, the bytes it takes on disk, and when it was
+//! last written. It backs `codanna stats`.
+
+use crate::indexing::facade::IndexFacade;
+use crate::parsing::get_registry;
+use crate::storage::IndexMetadata;
+use crate::{FileId, RelationKind, SymbolKind};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+/// Label for files whose language could not be determined
+const UNKNOWN_LANGUAGE: &str = "unknown";
+
+/// Counts for one language
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct LanguageSummary {
+    pub language: String,
+    pub files: usize,
+    pub symbols: usize,
+}
+
+/// Counts for one monorepo project
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ProjectSummary {
+    pub project: String,
+    /// Files with symbols in the project
+    pub files: usize,
+    pub symbols: usize,
+}
+
+/// Bytes used by the index directory
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DiskUsage {
+    pub total_bytes: u64,
+    /// Bytes per top-level entry of the index directory, such as `tantivy`
+    /// or `semantic`
+    pub by_component: BTreeMap<String, u64>,
+}
+
+/// Whole-index counts reported by `codanna stats`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct IndexSummary {
+    pub files: usize,
+    pub symbols: usize,
+    pub relationships: usize,
+    /// Sorted by file count, largest first
+    pub languages: Vec<LanguageSummary>,
+    /// Sorted by file count, largest first; empty outside monorepos
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub projects: Vec<ProjectSummary>,
+    pub symbols_by_kind: BTreeMap<String, usize>,
+    /// Symbols carrying each tag; empty without `[[tags]]` rules
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub symbols_by_tag: BTreeMap<String, usize>,
+    pub relationships_by_kind: BTreeMap<String, usize>,
+    pub disk: DiskUsage,
+    /// Newest per-file index time (Unix seconds)
+    pub last_file_indexed: Option<u64>,
+    /// Last write of the index metadata (Unix seconds)
+    pub last_modified: Option<u64>,
+}
+
+impl IndexSummary {
+    /// Summarize the index behind `facade`
+    pub fn collect(facade: &IndexFacade) -> Self {
+        let index = facade.document_index();
+
+        let mut symbols = Vec::new();
+        let mut symbols_by_tag: BTreeMap<String, usize> = BTreeMap::new();
+        let mut projects: HashMap<String, (HashSet<FileId>, usize)> = HashMap::new();
+        if let Err(e) = index.for_each_symbol(|symbol| {
+            for tag in &symbol.tags {
+                *symbols_by_tag.entry(tag.to_string()).or_default() += 1;
+            }
+            if let Some(project) = &symbol.project {
+                let (files, count) = projects.entry(project.to_string()).or_default();
+                files.insert(symbol.file_id);
+                *count += 1;
+            }
+            symbols.push((
+                symbol.file_id,
+                symbol.kind,
+                symbol.language_id.map(|id| id.as_str().to_string()),
+            ));
+            Ok(())
+        }) {
+            tracing::warn!(target: "analysis", "failed to read symbols: {e}");
+        }
+        let files = index.query_file_info().unwrap_or_else(|e| {
+            tracing::warn!(target: "analysis", "failed to read files: {e}");
+            Vec::new()
+        });
+        let relationships = index.query_relationships().unwrap_or_else(|e| {
+            tracing::warn!(target: "analysis", "failed to read relationships: {e}");
+            Vec::new()
+        });
+
+        let mut summary = Self::from_parts(
+            files
+                .iter()
+                .map(|(file_id, path, _, timestamp)| (*file_id, path.as_str(), *timestamp)),
+            symbols,
+            relationships.iter().map(|(_, _, rel)| rel.kind),
+        );
+        summary.symbols_by_tag = symbols_by_tag;
+        summary.projects = projects
+            .into_iter()
+            .map(|(project, (files, symbols))| ProjectSummary {
+                project,
+                files: files.len(),
+                symbols,
+            })
+            .collect();
+        summary.projects.sort_by(|a, b| {
+            b.files
+                .cmp(&a.files)
+                .then(b.symbols.cmp(&a.symbols))
+                .then_with(|| a.project.cmp(&b.project))
+        });
+        summary.disk = disk_usage(facade.index_base());
+        summary.last_modified = IndexMetadata::load(facade.index_base())
+            .ok()
+            .map(|metadata| metadata.last_modified);
+        summary
+    }
+
+    /// Aggregate `(file_id, path, indexed_at)` files, `(file_id, kind,
+    /// language)` symbols and relationship kinds. A file takes the language
+    /// of its symbols, or else the one its extension maps to.
+    pub fn from_parts<'a>(
+        files: impl IntoIterator<Item = (FileId, &'a str, u64)>,
+        symbols: impl IntoIterator<Item = (FileId, SymbolKind, Option<String>)>,
+        relationships: impl IntoIterator<Item = RelationKind>,
+    ) -> Self {
+        let mut summary = Self::default();
+        let mut file_languages: HashMap<FileId, String> = HashMap::new();
+        let mut languages: HashMap<String, LanguageSummary> = HashMap::new();
+
+        for (file_id, kind, language) in symbols {
+            let language = language.unwrap_or_else(|| UNKNOWN_LANGUAGE.to_string());
+            file_languages
+                .entry(file_id)
+                .or_insert_with(|| language.clone());
+            *summary
+                .symbols_by_kind
+                .entry(format!("{kind:?}"))
+                .or_default() += 1;
+            language_entry(&mut languages, &language).symbols += 1;
+            summary.symbols += 1;
+        }
+
+        for (file_id, path, timestamp) in files {
+            let language = file_languages
+                .remove(&file_id)
+                .unwrap_or_else(|| language_of_path(path));
+            language_entry(&mut languages, &language).files += 1;
+            summary.files += 1;
+            summary.last_file_indexed = summary.last_file_indexed.max(Some(timestamp));
+        }
+
+        for kind in relationships {
+            *summary
+                .relationships_by_kind
+                .entry(format!("{kind:?}"))
+                .or_default() += 1;
+            summary.relationships += 1;
+        }
+
+        summary.languages = languages.into_values().collect();
+        summary.languages.sort_by(|a, b| {
+            b.files
+                .cmp(&a.files)
+                .then(b.symbols.cmp(&a.symbols))
+                .then_with(|| a.language.cmp(&b.language))
+        });
+        summary
+    }
+}
+
+fn language_entry<'a>(
+    languages: &'a mut HashMap<String, LanguageSummary>,
+    language: &str,
+) -> &'a mut LanguageSummary {
+    languages
+        .entry(language.to_string())
+        .or_insert_with(|| LanguageSummary {
+            language: language.to_string(),
+            ..LanguageSummary::default()
+        })
+}
+
+fn language_of_path(path: &str) -> String {
+    let Some(extension) = Path::new(path).extension().and_then(|ext| ext.to_str()) else {
+        return UNKNOWN_LANGUAGE.to_string();
+    };
+    let Ok(registry) = get_registry().lock() else {
+        return UNKNOWN_LANGUAGE.to_string();
+    };
+    registry
+        .get_by_extension(extension)
+        .map(|definition| definition.id().as_str().to_string())
+        .unwrap_or_else(|| UNKNOWN_LANGUAGE.to_string())
+}
+
+/// Sizes of the files under `base`, grouped by their top-level entry
+fn disk_usage(base: &Path) -> DiskUsage {
+    let mut usage = DiskUsage::default();
+    for entry in walkdir::WalkDir::new(base)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+    {
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        let component = entry
+            .path()
+            .strip_prefix(base)
+            .ok()
+            .and_then(|relative| relative.components().next())
+            .map(|first| first.as_os_str().to_string_lossy().into_owned())
+            .unwrap_or_default();
+        *usage.by_component.entry(component).or_default() += metadata.len();
+        usage.total_bytes += metadata.len();
+    }
+    usage
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts_files_and_symbols_per_language() {
+        let rust = FileId::new(1).unwrap();
+        let python = FileId::new(2).unwrap();
+        let empty = FileId::new(3).unwrap();
+        let summary = IndexSummary::from_parts(
+            [
+                (rust, "src/lib.rs", 100),
+                (python, "tools/gen.py", 300),
+                (empty, "src/empty.rs", 200),
+            ],
+            [
+                (rust, SymbolKind::Function, Some("rust".to_string())),
+                (rust, SymbolKind::Struct, Some("rust".to_string())),
+                (python, SymbolKind::Function, Some("python".to_string())),
+            ],
+            [RelationKind::Calls, RelationKind::Calls, RelationKind::Uses],
+        );
+
+        assert_eq!((summary.files, summary.symbols), (3, 3));
+        assert_eq!(
+            summary.languages[0],
+            LanguageSummary {
+                language: "rust".to_string(),
+                files: 2,
+                symbols: 2,
+            }
+        );
+        assert_eq!(summary.languages[1].language, "python");
+        assert_eq!(summary.symbols_by_kind["Function"], 2);
+        assert_eq!(summary.relationships_by_kind["Calls"], 2);
+        assert_eq!(summary.last_file_indexed, Some(300));
+    }
+}
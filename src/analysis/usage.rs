@@ -0,0 +1,249 @@
+//! Symbol usage frequency and hot-spot reports.
+//!
+//! [`UsageStats`] aggregates the relationship table into per-symbol counts:
+//! how often a symbol is called or referenced, and how many distinct symbols
+//! depend on it (fan-in) or it depends on (fan-out). [`UsageStats::hotspots`]
+//! turns those counts into ranked lists.
+
+use crate::indexing::facade::IndexFacade;
+use crate::{RelationKind, Symbol, SymbolId, SymbolKind};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// Usage counts for one symbol
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct SymbolUsage {
+    /// Incoming call edges
+    pub calls: usize,
+    /// Incoming uses, references, implementations, and extensions
+    pub references: usize,
+    /// Distinct symbols that call or reference this one
+    pub fan_in: usize,
+    /// Distinct symbols this one calls or references
+    pub fan_out: usize,
+}
+
+/// Per-symbol usage counts over the whole index
+#[derive(Debug, Default)]
+pub struct UsageStats {
+    usage: HashMap<SymbolId, SymbolUsage>,
+}
+
+/// A ranked symbol in a hot-spot list
+#[derive(Debug, Clone, Serialize)]
+pub struct Hotspot {
+    pub symbol_id: SymbolId,
+    pub name: String,
+    pub kind: SymbolKind,
+    pub file_path: String,
+    /// 1-based
+    pub line: u32,
+    /// The metric this list is ranked by
+    pub count: usize,
+}
+
+/// Hot-spot lists produced by [`UsageStats::hotspots`]
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct HotspotReport {
+    /// Functions and methods by incoming calls
+    pub most_called: Vec<Hotspot>,
+    /// Types by incoming references
+    pub most_referenced_types: Vec<Hotspot>,
+    /// Any symbol by distinct dependents
+    pub highest_fan_in: Vec<Hotspot>,
+    /// Any symbol by distinct dependencies
+    pub highest_fan_out: Vec<Hotspot>,
+}
+
+impl UsageStats {
+    /// Aggregate every relationship in the index
+    pub fn collect(facade: &IndexFacade) -> Self {
+        match facade.document_index().query_relationships() {
+            Ok(relationships) => Self::from_relationships(
+                relationships
+                    .iter()
+                    .map(|(from, to, rel)| (*from, *to, rel.kind)),
+            ),
+            Err(e) => {
+                tracing::warn!(target: "analysis", "failed to read relationships: {e}");
+                Self::default()
+            }
+        }
+    }
+
+    /// Aggregate `(from, to, kind)` edges
+    pub fn from_relationships(
+        edges: impl IntoIterator<Item = (SymbolId, SymbolId, RelationKind)>,
+    ) -> Self {
+        let mut usage: HashMap<SymbolId, SymbolUsage> = HashMap::new();
+        let mut pairs = HashSet::new();
+
+        for (from, to, kind) in edges {
+            // Only forward edges; the reverse kinds mirror them
+            let counted = match kind {
+                RelationKind::Calls => &mut usage.entry(to).or_default().calls,
+                RelationKind::Uses
+                | RelationKind::References
+                | RelationKind::Implements
+                | RelationKind::Extends => &mut usage.entry(to).or_default().references,
+                _ => continue,
+            };
+            *counted += 1;
+
+            if from != to && pairs.insert((from, to)) {
+                usage.entry(to).or_default().fan_in += 1;
+                usage.entry(from).or_default().fan_out += 1;
+            }
+        }
+
+        Self { usage }
+    }
+
+    /// Counts for one symbol; zero if nothing uses it or it uses nothing
+    pub fn get(&self, id: SymbolId) -> SymbolUsage {
+        self.usage.get(&id).copied().unwrap_or_default()
+    }
+
+    /// Symbols with any recorded usage
+    pub fn len(&self) -> usize {
+        self.usage.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.usage.is_empty()
+    }
+
+    /// Rank the top `limit` symbols for each metric
+    pub fn hotspots(&self, facade: &IndexFacade, limit: usize) -> HotspotReport {
+        self.hotspots_with(|id| facade.get_symbol(id), limit)
+    }
+
+    /// [`Self::hotspots`] with a custom symbol lookup
+    pub fn hotspots_with(
+        &self,
+        lookup: impl Fn(SymbolId) -> Option<Symbol>,
+        limit: usize,
+    ) -> HotspotReport {
+        let callable = |kind: SymbolKind| matches!(kind, SymbolKind::Function | SymbolKind::Method);
+        let type_like = |kind: SymbolKind| {
+            matches!(
+                kind,
+                SymbolKind::Struct
+                    | SymbolKind::Enum
+                    | SymbolKind::Trait
+                    | SymbolKind::Interface
+                    | SymbolKind::Class
+                    | SymbolKind::TypeAlias
+            )
+        };
+
+        HotspotReport {
+            most_called: self.rank(&lookup, limit, |u| u.calls, callable),
+            most_referenced_types: self.rank(&lookup, limit, |u| u.references, type_like),
+            highest_fan_in: self.rank(&lookup, limit, |u| u.fan_in, |_| true),
+            highest_fan_out: self.rank(&lookup, limit, |u| u.fan_out, |_| true),
+        }
+    }
+
+    fn rank(
+        &self,
+        lookup: &impl Fn(SymbolId) -> Option<Symbol>,
+        limit: usize,
+        metric: impl Fn(&SymbolUsage) -> usize,
+        kind_filter: impl Fn(SymbolKind) -> bool,
+    ) -> Vec<Hotspot> {
+        let mut ranked: Vec<(SymbolId, usize)> = self
+            .usage
+            .iter()
+            .map(|(id, usage)| (*id, metric(usage)))
+            .filter(|(_, count)| *count > 0)
+            .collect();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.value().cmp(&b.0.value())));
+
+        ranked
+            .into_iter()
+            .filter_map(|(id, count)| {
+                let symbol = lookup(id)?;
+                kind_filter(symbol.kind).then(|| Hotspot {
+                    symbol_id: id,
+                    name: symbol.name.to_string(),
+                    kind: symbol.kind,
+                    file_path: symbol.file_path.to_string(),
+                    line: symbol.range.start_line + 1,
+                    count,
+                })
+            })
+            .take(limit)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FileId, Range};
+
+    fn id(n: u32) -> SymbolId {
+        SymbolId::new(n).unwrap()
+    }
+
+    fn symbol(n: u32, kind: SymbolKind) -> Symbol {
+        Symbol::new(
+            id(n),
+            format!("s{n}"),
+            kind,
+            FileId::new(1).unwrap(),
+            Range::new(n, 0, n + 1, 0),
+        )
+    }
+
+    fn edges() -> Vec<(SymbolId, SymbolId, RelationKind)> {
+        vec![
+            (id(1), id(3), RelationKind::Calls),
+            (id(2), id(3), RelationKind::Calls),
+            (id(2), id(3), RelationKind::Calls),
+            (id(3), id(3), RelationKind::Calls),
+            (id(1), id(4), RelationKind::Calls),
+            (id(1), id(5), RelationKind::Uses),
+            (id(2), id(5), RelationKind::Implements),
+            // Reverse edges are ignored
+            (id(3), id(1), RelationKind::CalledBy),
+        ]
+    }
+
+    #[test]
+    fn test_usage_counts() {
+        let stats = UsageStats::from_relationships(edges());
+        assert_eq!(
+            stats.get(id(3)),
+            SymbolUsage {
+                calls: 4,
+                references: 0,
+                fan_in: 2,
+                fan_out: 0,
+            }
+        );
+        assert_eq!(stats.get(id(5)).references, 2);
+        assert_eq!(stats.get(id(1)).fan_out, 3);
+        assert_eq!(stats.get(id(99)), SymbolUsage::default());
+    }
+
+    #[test]
+    fn test_hotspots_rank_and_filter_by_kind() {
+        let stats = UsageStats::from_relationships(edges());
+        let lookup = |sid: SymbolId| {
+            let kind = match sid.value() {
+                5 => SymbolKind::Struct,
+                _ => SymbolKind::Function,
+            };
+            Some(symbol(sid.value(), kind))
+        };
+
+        let report = stats.hotspots_with(lookup, 2);
+        let names = |list: &[Hotspot]| list.iter().map(|h| h.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names(&report.most_called), ["s3", "s4"]);
+        assert_eq!(report.most_called[0].count, 4);
+        assert_eq!(names(&report.most_referenced_types), ["s5"]);
+        assert_eq!(names(&report.highest_fan_out), ["s1", "s2"]);
+    }
+}
@@ -8,6 +8,8 @@ use clap::{
 };
 use std::path::PathBuf;
 
+use crate::types::PositionEncoding;
+
 fn clap_cargo_style() -> Styles {
     Styles::styled()
         .header(AnsiColor::Cyan.on_default() | Effects::BOLD)
@@ -33,6 +35,7 @@ fn create_custom_help() -> String {
     help.push_str("  $ codanna index src lib            # Index multiple directories\n");
     help.push_str("  $ codanna add-dir tests            # Add tests directory to indexed paths\n");
     help.push_str("  $ codanna list-dirs                # List all indexed directories\n");
+    help.push_str("  $ codanna index verify --repair    # Check and repair index integrity\n");
     help.push_str("  $ codanna serve --http --watch     # HTTP server with OAuth\n");
     help.push_str("  $ codanna serve --https --watch    # HTTPS server with TLS\n");
     help.push_str("  $ codanna documents add-collection docs ./docs  # Add doc collection\n");
@@ -62,6 +65,7 @@ fn create_custom_help() -> String {
     help.push_str("  list-dirs     List all directories that are being indexed\n");
     help.push_str("  retrieve      Query symbols, relationships, and dependencies\n");
     help.push_str("  serve         Start MCP server\n");
+    help.push_str("  daemon        Keep the index loaded for fast queries\n");
     help.push_str("  config        Display active settings\n");
     help.push_str("  mcp-test      Test MCP connection\n");
     help.push_str("  mcp           Execute MCP tools directly\n");
@@ -69,6 +73,7 @@ fn create_custom_help() -> String {
     help.push_str("  parse         Output AST nodes in JSONL format\n");
     help.push_str("  plugin        Manage Claude Code plugins\n");
     help.push_str("  documents     Index and search document collections\n");
+    help.push_str("  export        Export symbols for external analysis\n");
     help.push_str("  help          Print this message or the help of the given subcommand(s)\n\n");
 
     help.push_str("See 'codanna help <command>' for more information on a specific command.\n\n");
@@ -81,6 +86,13 @@ fn create_custom_help() -> String {
     }
     help.push_str("  -c, --config <CONFIG>  Path to custom settings.toml file\n");
     help.push_str("      --info             Show detailed loading information\n");
+    help.push_str("      --output-format <FORMAT>  text, json, yaml or markdown\n");
+    help.push_str(
+        "      --template <TEMPLATE>     One line per result, e.g. \"{symbol.name} {file_path}\"\n",
+    );
+    help.push_str("      --max-tokens <N>   Cut structured output to about N tokens\n");
+    help.push_str("      --max-bytes <N>    Cut structured output to N bytes\n");
+    help.push_str("      --wait[=<SECS>]    Wait for other processes to release the index\n");
     help.push_str("  -h, --help             Print help\n");
     help.push_str("  -V, --version          Print version\n\n");
 
@@ -115,6 +127,32 @@ pub struct Cli {
     #[arg(long, global = true)]
     pub info: bool,
 
+    /// Output format of query and report commands: text, json, yaml or markdown
+    #[arg(long, global = true, value_name = "FORMAT")]
+    pub output_format: Option<String>,
+
+    /// Print one line per result from a template, e.g. "{symbol.name} {file_path}"
+    #[arg(long, global = true, value_name = "TEMPLATE")]
+    pub template: Option<String>,
+
+    /// Cut structured output to about N tokens, dropping the lowest-ranked results first
+    #[arg(long, global = true, value_name = "N")]
+    pub max_tokens: Option<usize>,
+
+    /// Cut structured output to N bytes, dropping the lowest-ranked results first
+    #[arg(long, global = true, value_name = "N")]
+    pub max_bytes: Option<usize>,
+
+    /// Wait for other codanna processes to release the index, forever or up to SECS
+    #[arg(
+        long,
+        global = true,
+        value_name = "SECS",
+        num_args = 0..=1,
+        require_equals = true
+    )]
+    pub wait: Option<Option<u64>>,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -131,7 +169,10 @@ pub enum Commands {
     },
 
     /// Index source files or directories
-    #[command(about = "Build searchable index from codebase")]
+    #[command(
+        about = "Build searchable index from codebase",
+        args_conflicts_with_subcommands = true
+    )]
     Index {
         /// Paths to files or directories to index (multiple paths allowed)
         #[arg(value_name = "PATH")]
@@ -149,6 +190,11 @@ pub enum Commands {
         #[arg(long)]
         no_progress: bool,
 
+        /// Progress output format: `json` replaces the progress bars with
+        /// newline-delimited JSON events on stderr
+        #[arg(long, value_name = "FORMAT")]
+        progress: Option<String>,
+
         /// Dry run - show what would be indexed without indexing
         #[arg(long)]
         dry_run: bool,
@@ -156,6 +202,10 @@ pub enum Commands {
         /// Maximum number of files to index
         #[arg(long)]
         max_files: Option<usize>,
+
+        /// Index maintenance action (omit to build the index)
+        #[command(subcommand)]
+        action: Option<IndexAction>,
     },
 
     /// Add a directory to the indexed paths list
@@ -229,6 +279,24 @@ pub enum Commands {
             help = "Address to bind HTTP/HTTPS server to"
         )]
         bind: String,
+
+        /// Sync the write-ahead log to disk after every index update
+        #[arg(
+            long,
+            help = "Sync index updates to disk (overrides settings.toml fsync)"
+        )]
+        fsync: bool,
+    },
+
+    /// Keep the index loaded in a background process
+    #[command(
+        about = "Run a background daemon that keeps the index loaded for fast queries",
+        long_about = "Run a background daemon that keeps the index loaded, warm, and current with file changes.\n\nWhile it runs, 'codanna mcp <tool>' calls (text output) are answered by the daemon over a unix socket in the index directory instead of loading the index on every invocation.",
+        after_help = "Examples:\n  codanna daemon start\n  codanna mcp find_symbol main\n  codanna daemon status\n  codanna daemon stop"
+    )]
+    Daemon {
+        #[command(subcommand)]
+        action: DaemonAction,
     },
 
     /// Test MCP connection
@@ -296,6 +364,22 @@ pub enum Commands {
         /// Custom file to benchmark
         #[arg(short, long)]
         file: Option<PathBuf>,
+
+        /// Run the fixture suite and fail on throughput regressions
+        #[arg(long)]
+        check: bool,
+
+        /// Run the fixture suite and store its results as the baseline
+        #[arg(long)]
+        save_baseline: bool,
+
+        /// Baseline file for --check and --save-baseline
+        #[arg(long, default_value = "benches/baseline.json")]
+        baseline: PathBuf,
+
+        /// Allowed slowdown against the baseline, in percent
+        #[arg(long, default_value_t = 20.0)]
+        tolerance: f64,
     },
 
     /// Parse a file and output AST nodes in JSONL format
@@ -370,6 +454,115 @@ pub enum Commands {
         #[command(subcommand)]
         action: crate::profiles::commands::ProfileAction,
     },
+
+    /// Export index data for external tools
+    #[command(
+        about = "Export symbols and relationships for external analysis",
+        after_help = "Examples:\n  codanna export symbols --format jsonl > symbols.jsonl\n  codanna export symbols --output symbols.jsonl\n  codanna export symbols | jq -r 'select(.kind == \"Struct\") | .name'"
+    )]
+    Export {
+        #[command(subcommand)]
+        action: ExportAction,
+    },
+
+    /// Repository-wide code analyses
+    #[command(
+        about = "Run repository-wide analyses over the index",
+        after_help = "Examples:\n  codanna analyze duplicates\n  codanna analyze duplicates --threshold 0.9 --min-tokens 80 --lang rust\n  codanna analyze duplicates --json | jq '.data[0].members'\n  codanna analyze hotspots --limit 20\n  codanna analyze layers"
+    )]
+    Analyze {
+        #[command(subcommand)]
+        action: AnalyzeAction,
+    },
+
+    /// Summarize what the index holds
+    #[command(
+        about = "Show file, symbol, and relationship counts and index size",
+        after_help = "Examples:\n  codanna stats\n  codanna stats --json | jq '.data.languages'"
+    )]
+    Stats {
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Browse the code graph in a terminal UI
+    #[command(
+        about = "Terminal UI to find symbols and walk callers, callees, and implementations (requires the tui feature)",
+        after_help = "Keys:\n  type to fuzzy-find, Enter to open a symbol\n  Tab/Left/Right to switch between callers, callees, implementations, outline\n  Backspace to go back, / to search again, q to quit\n\nExample:\n  codanna explore"
+    )]
+    Explore,
+
+    /// Explore the index interactively
+    #[command(
+        about = "Interactive shell with history and symbol name completion",
+        long_about = "Start an interactive shell over the index.\n\nCommands: def, refs, calls, describe, and query. Any other line runs as a query in the saved query syntax (free text plus kind:, lang:, module:, visibility:, exported:, tag:, project:, limit: filters). Tab completes commands and symbol names; history is kept next to the index.",
+        after_help = "Examples:\n  codanna repl\n  echo 'def main' | codanna repl"
+    )]
+    Repl,
+
+    /// Print a shell completion script
+    #[command(
+        about = "Print a completion script for bash, zsh, or fish",
+        long_about = "Print a completion script for bash, zsh, or fish.\n\nBesides subcommands and flags, the script completes symbol names for commands taking a symbol (retrieve symbol, calls, callers, describe, ...) by asking the index for names with the typed prefix.",
+        after_help = "Examples:\n  codanna completions bash > ~/.local/share/bash-completion/completions/codanna\n  codanna completions zsh > \"${fpath[1]}/_codanna\"\n  codanna completions fish > ~/.config/fish/completions/codanna.fish"
+    )]
+    Completions {
+        /// Shell to generate for: bash, zsh, or fish
+        shell: String,
+    },
+
+    /// Symbol names with a prefix, one per line (used by completion scripts)
+    #[command(name = "complete-symbols", hide = true)]
+    CompleteSymbols {
+        /// Typed prefix of the symbol name
+        prefix: String,
+
+        /// Maximum number of names
+        #[arg(long, default_value_t = 50)]
+        limit: usize,
+    },
+
+    /// Manage dependency indexes
+    #[command(
+        about = "Add, list, or remove dependency indexes used to resolve external symbols",
+        after_help = "Examples:\n  codanna deps add nixpkgs-lib\n  codanna deps sync\n  codanna deps list\n  codanna deps remove nixpkgs-lib"
+    )]
+    Deps {
+        #[command(subcommand)]
+        action: DepsAction,
+    },
+}
+
+/// Daemon actions
+#[derive(Subcommand)]
+pub enum DaemonAction {
+    /// Start the daemon
+    #[command(
+        about = "Start the daemon in the background and wait until it serves the index",
+        after_help = "Examples:\n  codanna daemon start\n  codanna daemon start --foreground"
+    )]
+    Start {
+        /// Run in this process instead of detaching, e.g. under a service manager
+        #[arg(long)]
+        foreground: bool,
+
+        /// How often to check for index changes made by other processes, in seconds
+        #[arg(long, default_value_t = 5)]
+        watch_interval: u64,
+    },
+
+    /// Stop the daemon
+    #[command(about = "Stop the daemon serving this index")]
+    Stop,
+
+    /// Show whether a daemon is running
+    #[command(about = "Show the daemon's pid, uptime, and what it holds")]
+    Status {
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
 }
 
 /// Plugin management actions
@@ -474,6 +667,440 @@ pub enum PluginAction {
     },
 }
 
+/// Index maintenance actions
+#[derive(Subcommand)]
+pub enum IndexAction {
+    /// Verify index integrity
+    #[command(
+        about = "Check for dangling relationships, orphaned symbols, and corrupted segments",
+        after_help = "Examples:\n  codanna index verify\n  codanna index verify --repair\n  codanna index verify --json"
+    )]
+    Verify {
+        /// Prune dangling relationships and orphaned symbols
+        #[arg(long)]
+        repair: bool,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Publish the index as a shareable artifact
+    #[command(
+        about = "Package the index into a tarball with a versioned manifest",
+        after_help = "Examples:\n  codanna index publish codanna-index.tar.gz"
+    )]
+    Publish {
+        /// Path of the artifact to write
+        #[arg(value_name = "FILE")]
+        output: PathBuf,
+    },
+
+    /// Mount a published artifact as the index base
+    #[command(
+        about = "Mount a prebuilt index read-only and overlay local edits",
+        after_help = "Examples:\n  codanna index mount ./codanna-index.tar.gz\n  codanna index mount https://ci.example.com/artifacts/codanna-index.tar.gz"
+    )]
+    Mount {
+        /// Local path or http(s) URL of the artifact
+        #[arg(value_name = "SOURCE")]
+        source: String,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+/// Export actions
+#[derive(Subcommand)]
+pub enum ExportAction {
+    /// Export the full symbol table
+    #[command(
+        about = "Export every symbol with kind, range, signature, docs, and module path",
+        after_help = "Examples:\n  codanna export symbols\n  codanna export symbols --format jsonl --output symbols.jsonl"
+    )]
+    Symbols {
+        /// Output format (jsonl)
+        #[arg(long, default_value = "jsonl")]
+        format: String,
+
+        /// Write to file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Export symbols, relationships, and files into a SQLite database
+    #[command(
+        about = "Write the code graph to a SQLite database (requires the sqlite-export feature)",
+        after_help = "Examples:\n  codanna export sqlite --output codanna.db\n  sqlite3 codanna.db \"SELECT name FROM symbols WHERE kind = 'Trait'\""
+    )]
+    Sqlite {
+        /// Database file to create (replaced if it exists)
+        #[arg(short, long, default_value = "codanna.db")]
+        output: PathBuf,
+    },
+}
+
+/// Analysis reports
+#[derive(Subcommand)]
+pub enum AnalyzeAction {
+    /// Find clusters of near-identical functions
+    #[command(
+        about = "Report clusters of near-identical functions and methods",
+        long_about = "Report clusters of near-identical functions and methods.\n\nBodies are compared as token shingles with identifiers and literals normalized, so copies with renamed variables are still found.",
+        after_help = "Examples:\n  codanna analyze duplicates\n  codanna analyze duplicates --threshold 0.95 --limit 10\n  codanna analyze duplicates --lang python --json"
+    )]
+    Duplicates {
+        /// Minimum similarity between clones (0.0-1.0)
+        #[arg(long, default_value_t = 0.85)]
+        threshold: f64,
+
+        /// Ignore functions with fewer normalized tokens
+        #[arg(long, default_value_t = 50)]
+        min_tokens: usize,
+
+        /// Only compare functions in this language
+        #[arg(long)]
+        lang: Option<String>,
+
+        /// Maximum number of clusters to report
+        #[arg(short, long)]
+        limit: Option<usize>,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Rank the most used symbols
+    #[command(
+        about = "Report most-called functions, most-referenced types, and fan-in/fan-out",
+        after_help = "Examples:\n  codanna analyze hotspots\n  codanna analyze hotspots --limit 25\n  codanna analyze hotspots --json | jq '.data.most_called'"
+    )]
+    Hotspots {
+        /// Entries per list
+        #[arg(short, long, default_value_t = 10)]
+        limit: usize,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Check dependencies against layer rules
+    #[command(
+        about = "Check imports and calls against [[layers]] rules in settings",
+        long_about = "Check imports and symbol relationships against architecture layer rules.\n\nExits with code 1 when violations are found, so it can gate CI.",
+        after_help = "Examples:\n  codanna analyze layers\n  codanna analyze layers --json\n\nDefine rules in .codanna/settings.toml:\n  [[layers]]\n  name = \"domain must not import infrastructure\"\n  from = \"crate::domain\"\n  deny = [\"crate::infrastructure\"]"
+    )]
+    Layers {
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Find imports that are never referenced in their file
+    #[command(
+        about = "Report imports never referenced in their file (Rust, Python, TypeScript, PHP)",
+        long_about = "Report imports never referenced in their file.\n\nAn import counts as used when a resolved relationship from the file points into it, or when the name it binds appears outside import declarations. Globs, side-effect imports, and `pub use` re-exports are not reported.",
+        after_help = "Examples:\n  codanna analyze unused-imports\n  codanna analyze unused-imports --lang python\n  codanna analyze unused-imports --json | jq '.data[].file_path'"
+    )]
+    UnusedImports {
+        /// Only check files in this language
+        #[arg(long)]
+        lang: Option<String>,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// List where execution starts
+    #[command(
+        about = "List entry points: main functions, Cargo bins, Python scripts, package.json bins and scripts, flake apps",
+        after_help = "Examples:\n  codanna analyze entry-points\n  codanna analyze entry-points --json | jq '.data.entries[] | select(.symbol_id)'"
+    )]
+    EntryPoints {
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Flag calls to dangerous APIs
+    #[command(
+        about = "Report calls to security-sensitive APIs (eval, unsafe deserialization, unbounded copies)",
+        long_about = "Report calls to security-sensitive APIs.\n\nA built-in rule pack covers Python, PHP, C, and Nix. Exits with code 1 when findings are reported, so it can gate CI.",
+        after_help = "Examples:\n  codanna analyze security\n  codanna analyze security --lang python --min-severity high\n  codanna analyze security --json | jq '.data[] | {call, file_path, line, symbol}'\n\nAdd rules in .codanna/settings.toml:\n  [[security.rules]]\n  language = \"python\"\n  calls = [\"os.system\", \"subprocess.call\"]\n  severity = \"high\"\n  message = \"runs a shell command\""
+    )]
+    Security {
+        /// Only check files in this language
+        #[arg(long)]
+        lang: Option<String>,
+
+        /// Only report findings at or above this severity (low, medium, high)
+        #[arg(long, default_value = "low")]
+        min_severity: String,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Inventory feature-flag lookups
+    #[command(
+        about = "List feature flags the code looks up and where (LaunchDarkly, Unleash, custom patterns)",
+        long_about = "List feature flags the code looks up and where.\n\nBuilt-in patterns match LaunchDarkly `*Variation` and Unleash `isEnabled` / `getVariant` calls with a string literal flag key. Flags with few call sites are the candidates for cleanup.",
+        after_help = "Examples:\n  codanna analyze flags\n  codanna analyze flags --lang typescript\n  codanna analyze flags --flag new-checkout\n  codanna analyze flags --json | jq '.data[] | {flag, sites: (.sites | length)}'\n\nAdd patterns in .codanna/settings.toml (group 1 is the flag name):\n  [[feature_flags.patterns]]\n  system = \"settings\"\n  regex = 'is_enabled\\(\"([\\w.-]+)\"\\)'"
+    )]
+    Flags {
+        /// Only scan files in this language
+        #[arg(long)]
+        lang: Option<String>,
+
+        /// Only report this flag
+        #[arg(long)]
+        flag: Option<String>,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Inventory translation keys and untranslated text
+    #[command(
+        about = "List i18n keys with their lookup sites and locale files; optionally untranslated literals",
+        long_about = "List i18n keys with the places the code looks them up.\n\nBuilt-in patterns match t(), $t(), _(), __(), gettext(), trans() and i18nKey= with a string literal key. With locale files configured, keys defined but never looked up are reported as unused and keys looked up but never defined as missing. --literals adds string literals that read like prose outside translation calls.",
+        after_help = "Examples:\n  codanna analyze i18n\n  codanna analyze i18n --key checkout.title\n  codanna analyze i18n --unused\n  codanna analyze i18n --literals --lang typescript\n  codanna analyze i18n --json | jq '.data.keys[] | select(.sites == [])'\n\nPoint at locale files in .codanna/settings.toml:\n  [i18n]\n  locale_files = [\"locales/en/*.json\", \"po/*.po\"]"
+    )]
+    I18n {
+        /// Only scan files in this language
+        #[arg(long)]
+        lang: Option<String>,
+
+        /// Only report this key
+        #[arg(long)]
+        key: Option<String>,
+
+        /// Only report keys defined in locale files but never looked up
+        #[arg(long, conflicts_with = "missing")]
+        unused: bool,
+
+        /// Only report keys looked up but not defined in any locale file
+        #[arg(long)]
+        missing: bool,
+
+        /// Also list string literals that look user-facing but aren't translated
+        #[arg(long)]
+        literals: bool,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Inventory HTTP routes and their handlers
+    #[command(
+        about = "List HTTP routes with their handlers (FastAPI, Flask, Express, Symfony, Laravel, actix, axum)",
+        long_about = "List HTTP routes declared in the code with the indexed function serving each.\n\nGive a request path to find the routes serving it: parameter segments like {id}, :id and <int:id> match any segment. Paths are as declared; prefixes added when mounting a router or blueprint are not applied.",
+        after_help = "Examples:\n  codanna analyze routes\n  codanna analyze routes /api/users/42\n  codanna analyze routes /api/users/:id --method GET\n  codanna analyze routes --lang python --json | jq '.data[] | {method, path, handler: .handler.name}'"
+    )]
+    Routes {
+        /// Only routes serving this request path
+        path: Option<String>,
+
+        /// Only routes for this HTTP method (ANY routes always match)
+        #[arg(long)]
+        method: Option<String>,
+
+        /// Only scan files in this language
+        #[arg(long)]
+        lang: Option<String>,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Compare an OpenAPI spec with the routes in the code
+    #[command(
+        about = "Report drift between an OpenAPI spec and the routes in the code",
+        long_about = "Report drift between an OpenAPI (or Swagger 2.0) spec and the routes `analyze routes` finds.\n\nOperations without a serving route are spec-only; routes no operation documents are code-only. Spec paths are tried as written and under each server URL path or basePath. Exits with code 1 when there is drift, so it can gate CI.",
+        after_help = "Examples:\n  codanna analyze openapi openapi.yaml\n  codanna analyze openapi docs/swagger.json --lang typescript\n  codanna analyze openapi openapi.yaml --json | jq '.data.spec_only'"
+    )]
+    Openapi {
+        /// Spec file, YAML or JSON
+        spec: PathBuf,
+
+        /// Only compare routes in this language
+        #[arg(long)]
+        lang: Option<String>,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Link .proto services to the generated code using them
+    #[command(
+        about = "Link gRPC services, rpcs and messages in .proto files to their implementations and callers",
+        long_about = "Link gRPC services, rpcs and messages in the workspace's .proto files to the Rust, Python and TypeScript code using their generated stubs.\n\nIn files naming a service's generated server side, methods named like its rpcs are reported as implementations; in files naming its client, calls named like its rpcs are reported as calls.",
+        after_help = "Examples:\n  codanna analyze grpc\n  codanna analyze grpc --service helloworld.Greeter\n  codanna analyze grpc --json | jq '.data[].services[].rpcs[] | {name, implementations: [.implementations[].file_path]}'"
+    )]
+    Grpc {
+        /// Only this service, with or without its package
+        #[arg(long)]
+        service: Option<String>,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// List ORM models with their fields and the queries reaching them
+    #[command(
+        about = "List ORM models (Django, SQLAlchemy, Prisma, Diesel) with their fields and query sites",
+        long_about = "List the database models the code declares, with their fields and the queries reaching them.\n\nDjango and SQLAlchemy models are indexed Python classes; Prisma models come from the workspace's .prisma schemas and Diesel tables from table! macros. Query sites are Model.objects (Django), query(Model), select(Model) and Model.query (SQLAlchemy), prisma.model.findMany(...) and the other client methods (Prisma), and table::... paths (Diesel).",
+        after_help = "Examples:\n  codanna analyze models\n  codanna analyze models Order\n  codanna analyze models users --json | jq '.data[].usages[] | \"\\(.file_path):\\(.line)\"'"
+    )]
+    Models {
+        /// Only the model with this name or table
+        model: Option<String>,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Show which monorepo projects depend on which
+    #[command(
+        about = "Show the dependency graph between monorepo projects, with cycles",
+        long_about = "Show which projects of a monorepo depend on which.\n\nProjects are the Cargo, npm, Python and Nix packages detected at index time. A project depends on another when its symbols call, use or extend the other's, or when its files import the other by package name. Exits with code 1 when projects depend on each other in a cycle, so it can gate CI.",
+        after_help = "Examples:\n  codanna analyze projects\n  codanna analyze projects --dot | dot -Tsvg > projects.svg\n  codanna analyze projects --mermaid > projects.mmd\n  codanna analyze projects --json | jq '.data.cycles'"
+    )]
+    Projects {
+        /// Output as a Graphviz DOT graph
+        #[arg(long, conflicts_with_all = ["mermaid", "json"])]
+        dot: bool,
+
+        /// Output as a Mermaid flowchart
+        #[arg(long, conflicts_with = "json")]
+        mermaid: bool,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Map a diff onto the symbols it changes and their callers
+    #[command(
+        about = "List the symbols a diff changes, with their callers",
+        long_about = "List the symbols a diff changes, with the functions calling each.\n\nThe diff is a git revision range or a unified diff on stdin, such as a pull request patch. Each changed line is mapped to the innermost indexed symbol containing it.\n\nRANGE is a revision compared with the working tree (main), two revisions (main..HEAD), or a branch against its merge base (main...HEAD).",
+        after_help = "Examples:\n  codanna analyze diff\n  codanna analyze diff main...HEAD --json\n  git diff --cached | codanna analyze diff --stdin\n  gh pr diff 42 | codanna analyze diff --stdin --json | jq '.data.symbols[].name'"
+    )]
+    Diff {
+        /// Revision or range to diff
+        #[arg(default_value = "HEAD", conflicts_with = "stdin")]
+        range: String,
+
+        /// Read a unified diff from stdin instead
+        #[arg(long)]
+        stdin: bool,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Route a diff to the CODEOWNERS of the code it affects
+    #[command(
+        about = "List the CODEOWNERS whose code a diff changes or can break",
+        long_about = "List the CODEOWNERS whose code a diff changes or can break.\n\nThe changed lines are mapped to the indexed symbols they fall in, and the impact radius of those symbols (callers, type users, implementors) is followed up to --depth hops. Every affected file is looked up in CODEOWNERS, .github/CODEOWNERS or docs/CODEOWNERS; the last matching rule wins.\n\nRANGE is a revision compared with the working tree (main), two revisions (main..HEAD), or a branch against its merge base (main...HEAD).",
+        after_help = "Examples:\n  codanna analyze owners\n  codanna analyze owners main...HEAD\n  codanna analyze owners origin/main...HEAD --depth 3 --json\n  codanna analyze owners main...HEAD --json | jq -r '.data.owners[].owner'"
+    )]
+    Owners {
+        /// Revision or range to diff
+        #[arg(default_value = "HEAD")]
+        range: String,
+
+        /// Hops of dependents to follow from changed symbols
+        #[arg(long, default_value_t = 2)]
+        depth: usize,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Rank files by git churn times complexity
+    #[command(
+        about = "Report files that change often and are complex (churn vs. complexity)",
+        long_about = "Report files that change often and are complex.\n\nChurn is the number of commits touching a file. Complexity counts its functions and methods plus their branches. Files at or above the median on both are flagged as hot spots.",
+        after_help = "Examples:\n  codanna analyze churn\n  codanna analyze churn --since-days 90 --limit 20\n  codanna analyze churn --csv > churn.csv\n  codanna analyze churn --json | jq '.data[] | select(.hotspot)'"
+    )]
+    Churn {
+        /// Only count commits from the last N days
+        #[arg(long)]
+        since_days: Option<u64>,
+
+        /// Maximum number of files to report
+        #[arg(short, long)]
+        limit: Option<usize>,
+
+        /// Output as CSV for plotting
+        #[arg(long, conflicts_with = "json")]
+        csv: bool,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+/// Dependency index actions
+#[derive(Subcommand)]
+pub enum DepsAction {
+    /// Install a builtin dependency index
+    #[command(
+        about = "Install a builtin dependency index",
+        long_about = "Install a builtin dependency index.\n\nAvailable: nixpkgs-lib (nixpkgs library functions with signatures and docs).",
+        after_help = "Examples:\n  codanna deps add nixpkgs-lib\n  codanna retrieve symbol lib.mkOption"
+    )]
+    Add {
+        /// Dependency name
+        name: String,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Index declarations of installed Python, npm, and Rust packages
+    #[command(
+        about = "Index declarations of packages in the virtualenv, node_modules, and cargo registry",
+        long_about = "Index declarations of installed packages into per-package dependency indexes.\n\nPython packages are read from .venv, venv, env, or $VIRTUAL_ENV (preferring .pyi stubs). npm packages listed in package.json and their @types companions contribute their .d.ts files. Cargo projects get the standard library (requires the rust-src component) and the Cargo.lock versions of direct dependencies from ~/.cargo/registry. Unchanged package versions are skipped, and indexes of uninstalled packages are removed.",
+        after_help = "Examples:\n  codanna deps sync\n  codanna deps sync --json\n  codanna retrieve symbol requests.Session\n  codanna retrieve symbol std::collections::HashMap"
+    )]
+    Sync {
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// List installed dependency indexes
+    List {
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Remove a dependency index
+    Remove {
+        /// Dependency name
+        name: String,
+    },
+}
+
 /// Document collection management actions
 #[derive(Subcommand)]
 pub enum DocumentAction {
@@ -649,9 +1276,143 @@ pub enum RetrieveQuery {
         fields: Option<Vec<String>>,
     },
 
+    /// Find where the type of a variable, field, or function result is defined
+    #[command(
+        after_help = "Examples:\n  codanna retrieve type-definition config\n  codanna retrieve type-definition symbol_id:1771\n  codanna retrieve type-definition settings lang:rust --json"
+    )]
+    TypeDefinition {
+        /// Positional arguments (symbol name and/or key:value pairs)
+        #[arg(num_args = 0..)]
+        args: Vec<String>,
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+        /// Filter output to specific fields (comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        fields: Option<Vec<String>>,
+    },
+
+    /// Show callers or callees of a function as a tree, with call sites
+    #[command(
+        after_help = "Examples:\n  codanna retrieve call-hierarchy process_file\n  codanna retrieve call-hierarchy process_file direction:outgoing depth:2\n  codanna retrieve call-hierarchy symbol_id:1771 --json"
+    )]
+    CallHierarchy {
+        /// Positional arguments (function name and/or key:value pairs such as direction:outgoing depth:2)
+        #[arg(num_args = 0..)]
+        args: Vec<String>,
+        /// Unit of the columns in ranges: byte, char, or utf-16
+        #[arg(long, default_value = "byte")]
+        encoding: PositionEncoding,
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+        /// Filter output to specific fields (comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        fields: Option<Vec<String>>,
+    },
+
+    /// Show the supertypes and subtypes of a class, interface, or trait
+    #[command(
+        after_help = "Examples:\n  codanna retrieve type-hierarchy Parser\n  codanna retrieve type-hierarchy BaseModel depth:1 lang:python\n  codanna retrieve type-hierarchy symbol_id:1771 --json"
+    )]
+    TypeHierarchy {
+        /// Positional arguments (type name and/or key:value pairs such as depth:2)
+        #[arg(num_args = 0..)]
+        args: Vec<String>,
+        /// Unit of the columns in ranges: byte, char, or utf-16
+        #[arg(long, default_value = "byte")]
+        encoding: PositionEncoding,
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+        /// Filter output to specific fields (comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        fields: Option<Vec<String>>,
+    },
+
+    /// Show the commits that touched a symbol
+    #[command(
+        after_help = "Examples:\n  codanna retrieve history parse_config\n  codanna retrieve history symbol_id:1771 limit:5\n  codanna retrieve history Parser lang:rust --json | jq '.data[0].author'"
+    )]
+    History {
+        /// Positional arguments (symbol name and/or key:value pairs such as limit:10)
+        #[arg(num_args = 0..)]
+        args: Vec<String>,
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+        /// Filter output to specific fields (comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        fields: Option<Vec<String>>,
+    },
+
+    /// Show the symbols of a file as a nested outline
+    #[command(
+        after_help = "Examples:\n  codanna retrieve outline src/parser.rs\n  codanna retrieve outline src/parser.rs --json\n  codanna retrieve outline src/parser.rs --encoding utf-16 --json"
+    )]
+    Outline {
+        /// File path, relative to the workspace root or absolute
+        file: String,
+        /// Unit of the columns in ranges: byte, char, or utf-16
+        #[arg(long, default_value = "byte")]
+        encoding: PositionEncoding,
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+        /// Filter output to specific fields (comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        fields: Option<Vec<String>>,
+    },
+
+    /// Show the symbol enclosing a position and the symbol referenced there
+    #[command(
+        after_help = "Examples:\n  codanna retrieve symbol-at src/parser.rs 42 17\n  codanna retrieve symbol-at src/parser.rs 42 17 --json\n  codanna retrieve symbol-at src/parser.rs 42 17 --encoding utf-16"
+    )]
+    SymbolAt {
+        /// File path, relative to the workspace root or absolute
+        file: String,
+        /// Line number (1-based)
+        line: u32,
+        /// Column (1-based, in bytes unless --encoding says otherwise)
+        column: u32,
+        /// Unit of the column: byte, char, or utf-16
+        #[arg(long, default_value = "byte")]
+        encoding: PositionEncoding,
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+        /// Filter output to specific fields (comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        fields: Option<Vec<String>>,
+    },
+
+    /// Search file contents and show the symbol around each match
+    #[command(
+        after_help = "Examples:\n  codanna retrieve grep \"retry\\(\"\n  codanna retrieve grep TODO --limit 100\n  codanna retrieve grep \"unwrap\\(\\)\" -i --json\n\nThe pattern is a regular expression, searched in indexed files only."
+    )]
+    Grep {
+        /// Regular expression to search for
+        pattern: String,
+        /// Maximum number of matching lines
+        #[arg(short, long, default_value = "50")]
+        limit: usize,
+        /// Match regardless of case
+        #[arg(short, long)]
+        ignore_case: bool,
+        /// Unit of the columns in ranges: byte, char, or utf-16
+        #[arg(long, default_value = "byte")]
+        encoding: PositionEncoding,
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+        /// Filter output to specific fields (comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        fields: Option<Vec<String>>,
+    },
+
     /// Search for symbols using full-text search
     #[command(
-        after_help = "Examples:\n  # Traditional flag format\n  codanna retrieve search \"parse\" --limit 5 --kind function\n  \n  # Key:value format (Unix-style)\n  codanna retrieve search query:parse limit:5 kind:function\n  \n  # Mixed format\n  codanna retrieve search \"parse\" limit:5 --json\n  codanna retrieve search \"parse\" --json --fields=name,file_path"
+        after_help = "Examples:\n  # Traditional flag format\n  codanna retrieve search \"parse\" --limit 5 --kind function\n  \n  # Key:value format (Unix-style)\n  codanna retrieve search query:parse limit:5 kind:function\n  \n  # Mixed format\n  codanna retrieve search \"parse\" limit:5 --json\n  codanna retrieve search \"parse\" --json --fields=name,file_path\n  \n  # Rank results near the file you are editing\n  codanna retrieve search \"parse\" context_file:src/io/args.rs\n  \n  # Match anywhere in symbol names, ignoring case\n  codanna retrieve search file_path --substring\n  codanna retrieve search query:file_path match:substring"
     )]
     Search {
         /// Positional arguments (query and/or key:value pairs)
@@ -670,6 +1431,11 @@ pub enum RetrieveQuery {
         #[arg(short, long)]
         module: Option<String>,
 
+        /// Match the query anywhere in symbol names, ignoring case, instead
+        /// of full-text search (key:value format: match:substring)
+        #[arg(long)]
+        substring: bool,
+
         /// Output in JSON format
         #[arg(long)]
         json: bool,
@@ -694,4 +1460,22 @@ pub enum RetrieveQuery {
         #[arg(long, value_delimiter = ',')]
         fields: Option<Vec<String>>,
     },
+
+    /// Run a saved query from [queries] in settings
+    #[command(
+        after_help = "Examples:\n  codanna retrieve query              # list saved queries\n  codanna retrieve query public-api\n  codanna retrieve query handlers term:parse module:crate::io --json\n\nDefine queries in .codanna/settings.toml:\n  [queries]\n  public-api = \"kind:function visibility:public lang:rust\"\n  handlers = \"{term} kind:function module:{module}\""
+    )]
+    Query {
+        /// Saved query name (omit to list saved queries)
+        name: Option<String>,
+        /// Parameters as key:value pairs, substituted into {key} placeholders
+        #[arg(num_args = 0..)]
+        args: Vec<String>,
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+        /// Filter output to specific fields (comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        fields: Option<Vec<String>>,
+    },
 }
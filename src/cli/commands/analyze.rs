@@ -0,0 +1,978 @@
+//! Analyze command - repository-wide reports over the index.
+
+use crate::analysis::churn::to_csv;
+use crate::analysis::diff::workspace_root;
+use crate::analysis::{
+    ApiDrift, CloneCluster, DiffAnnotation, DuplicateOptions, EntryPoints, FileChurn,
+    FileUnusedImports, FlagPatterns, FlagUsage, GrpcSite, Hotspot, HotspotReport, I18nReport,
+    LayerViolation, ModelUsage, OrmModel, ProjectGraph, ProtoFile, ReviewRouting, Route, RulePack,
+    SecurityFinding, UsageStats, annotate_diff, check_layers, check_openapi, churn_report,
+    find_duplicates, find_entry_points, find_flag_usages, find_grpc_links, find_models,
+    find_routes, find_security_calls, find_unused_imports, git_changes, i18n_report,
+    parse_unified_diff, project_graph, repository_root, review_routing, route_matches,
+};
+use crate::cli::AnalyzeAction;
+use crate::config::Severity;
+use crate::indexing::facade::IndexFacade;
+use crate::io::{Envelope, ExitCode, OutputFormat};
+use std::io::Read;
+
+/// Run the analyze command.
+pub fn run(action: AnalyzeAction, indexer: &IndexFacade) -> ExitCode {
+    match action {
+        AnalyzeAction::Duplicates {
+            threshold,
+            min_tokens,
+            lang,
+            limit,
+            json,
+        } => {
+            if !(0.0..=1.0).contains(&threshold) {
+                eprintln!("Error: --threshold must be between 0.0 and 1.0, got {threshold}");
+                return ExitCode::ConfigError;
+            }
+            let options = DuplicateOptions {
+                threshold,
+                min_tokens,
+                language: lang,
+                ..DuplicateOptions::default()
+            };
+            let mut clusters = find_duplicates(indexer, &options);
+            let total = clusters.len();
+            if let Some(limit) = limit {
+                clusters.truncate(limit);
+            }
+            print_duplicates(&clusters, total, OutputFormat::from_json_flag(json));
+            ExitCode::Success
+        }
+        AnalyzeAction::Hotspots { limit, json } => {
+            let report = UsageStats::collect(indexer).hotspots(indexer, limit);
+            print_hotspots(&report, OutputFormat::from_json_flag(json));
+            ExitCode::Success
+        }
+        AnalyzeAction::Layers { json } => match check_layers(indexer, &indexer.settings().layers) {
+            Ok(violations) => {
+                print_layer_violations(&violations, OutputFormat::from_json_flag(json));
+                if violations.is_empty() {
+                    ExitCode::Success
+                } else {
+                    ExitCode::GeneralError
+                }
+            }
+            Err(e) => {
+                eprintln!("Error: {e}");
+                e.exit_code()
+            }
+        },
+        AnalyzeAction::UnusedImports { lang, json } => {
+            let report = find_unused_imports(indexer, lang.as_deref());
+            print_unused_imports(&report, OutputFormat::from_json_flag(json));
+            ExitCode::Success
+        }
+        AnalyzeAction::EntryPoints { json } => {
+            let entry_points = find_entry_points(indexer);
+            print_entry_points(&entry_points, OutputFormat::from_json_flag(json));
+            ExitCode::Success
+        }
+        AnalyzeAction::Security {
+            lang,
+            min_severity,
+            json,
+        } => {
+            let min_severity = match min_severity.to_ascii_lowercase().as_str() {
+                "low" => Severity::Low,
+                "medium" => Severity::Medium,
+                "high" => Severity::High,
+                other => {
+                    eprintln!("Error: --min-severity must be low, medium, or high, got {other}");
+                    return ExitCode::ConfigError;
+                }
+            };
+            let pack = RulePack::from_config(&indexer.settings().security);
+            let mut findings = find_security_calls(indexer, &pack, lang.as_deref());
+            findings.retain(|finding| finding.severity >= min_severity);
+            print_security_findings(&findings, OutputFormat::from_json_flag(json));
+            if findings.is_empty() {
+                ExitCode::Success
+            } else {
+                ExitCode::GeneralError
+            }
+        }
+        AnalyzeAction::Flags { lang, flag, json } => {
+            let patterns = FlagPatterns::from_config(&indexer.settings().feature_flags);
+            let mut usages = find_flag_usages(indexer, &patterns, lang.as_deref());
+            if let Some(flag) = flag {
+                usages.retain(|usage| usage.flag == flag);
+            }
+            print_flag_usages(&usages, OutputFormat::from_json_flag(json));
+            ExitCode::Success
+        }
+        AnalyzeAction::I18n {
+            lang,
+            key,
+            unused,
+            missing,
+            literals,
+            json,
+        } => {
+            let mut report =
+                i18n_report(indexer, &indexer.settings().i18n, lang.as_deref(), literals);
+            let keys = std::mem::take(&mut report.keys);
+            report.keys = keys
+                .into_iter()
+                .filter(|entry| key.as_ref().is_none_or(|key| &entry.key == key))
+                .filter(|entry| !unused || entry.is_unused())
+                .filter(|entry| !missing || report.is_missing(entry))
+                .collect();
+            print_i18n_report(&report, OutputFormat::from_json_flag(json));
+            ExitCode::Success
+        }
+        AnalyzeAction::Routes {
+            path,
+            method,
+            lang,
+            json,
+        } => {
+            let mut routes = find_routes(indexer, lang.as_deref());
+            if let Some(method) = &method {
+                routes.retain(|route| {
+                    route.method == "ANY" || route.method.eq_ignore_ascii_case(method)
+                });
+            }
+            if let Some(path) = &path {
+                routes.retain(|route| route_matches(&route.path, path));
+            }
+            print_routes(&routes, OutputFormat::from_json_flag(json));
+            if path.is_some() && routes.is_empty() {
+                ExitCode::NotFound
+            } else {
+                ExitCode::Success
+            }
+        }
+        AnalyzeAction::Openapi { spec, lang, json } => {
+            match check_openapi(indexer, &spec, lang.as_deref()) {
+                Ok(drift) => {
+                    print_api_drift(&drift, OutputFormat::from_json_flag(json));
+                    if drift.has_drift() {
+                        ExitCode::GeneralError
+                    } else {
+                        ExitCode::Success
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    e.exit_code()
+                }
+            }
+        }
+        AnalyzeAction::Grpc { service, json } => {
+            let protos = find_grpc_links(indexer, service.as_deref());
+            print_grpc_links(&protos, OutputFormat::from_json_flag(json));
+            if service.is_some() && protos.is_empty() {
+                ExitCode::NotFound
+            } else {
+                ExitCode::Success
+            }
+        }
+        AnalyzeAction::Models { model, json } => {
+            let models = find_models(indexer, model.as_deref());
+            print_models(&models, OutputFormat::from_json_flag(json));
+            if model.is_some() && models.is_empty() {
+                ExitCode::NotFound
+            } else {
+                ExitCode::Success
+            }
+        }
+        AnalyzeAction::Projects { dot, mermaid, json } => {
+            let graph = project_graph(indexer);
+            if dot {
+                print!("{}", graph.to_dot());
+            } else if mermaid {
+                print!("{}", graph.to_mermaid());
+            } else {
+                print_project_graph(&graph, OutputFormat::from_json_flag(json));
+            }
+            if graph.cycles.is_empty() {
+                ExitCode::Success
+            } else {
+                ExitCode::GeneralError
+            }
+        }
+        AnalyzeAction::Diff { range, stdin, json } => {
+            let root = workspace_root(indexer);
+            let (files, repository) = if stdin {
+                let mut patch = String::new();
+                if let Err(e) = std::io::stdin().read_to_string(&mut patch) {
+                    eprintln!("Error: Failed to read diff from stdin: {e}");
+                    return ExitCode::IoError;
+                }
+                let repository = repository_root(&root);
+                (parse_unified_diff(&repository, &patch), repository)
+            } else {
+                match git_changes(&root, &range) {
+                    Ok(changes) => (changes.files, changes.repository),
+                    Err(e) => {
+                        eprintln!("Error: {e}");
+                        return e.exit_code();
+                    }
+                }
+            };
+            let annotation = annotate_diff(indexer, &repository, files);
+            print_diff_annotation(&annotation, OutputFormat::from_json_flag(json));
+            ExitCode::Success
+        }
+        AnalyzeAction::Owners { range, depth, json } => {
+            match review_routing(indexer, &range, depth) {
+                Ok(routing) => {
+                    print_review_routing(&routing, OutputFormat::from_json_flag(json));
+                    ExitCode::Success
+                }
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    e.exit_code()
+                }
+            }
+        }
+        AnalyzeAction::Churn {
+            since_days,
+            limit,
+            csv,
+            json,
+        } => {
+            let since = since_days
+                .map(|days| chrono::Utc::now().timestamp() - (days as i64) * 24 * 60 * 60);
+            match churn_report(indexer, since) {
+                Ok(mut files) => {
+                    let total = files.len();
+                    if let Some(limit) = limit {
+                        files.truncate(limit);
+                    }
+                    if csv {
+                        print!("{}", to_csv(&files));
+                    } else {
+                        print_churn(&files, total, OutputFormat::from_json_flag(json));
+                    }
+                    ExitCode::Success
+                }
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    e.exit_code()
+                }
+            }
+        }
+    }
+}
+
+fn print_duplicates(clusters: &[CloneCluster], total: usize, format: OutputFormat) {
+    if format.is_structured() {
+        let envelope = Envelope::success(clusters)
+            .with_count(clusters.len())
+            .with_message(format!("{total} clone clusters"));
+        println!(
+            "{}",
+            envelope.render(format).expect("envelope serialization")
+        );
+        return;
+    }
+
+    if clusters.is_empty() {
+        eprintln!("No duplicate functions found");
+        return;
+    }
+
+    for (i, cluster) in clusters.iter().enumerate() {
+        println!(
+            "Cluster {} ({} functions, {:.0}% similar)",
+            i + 1,
+            cluster.members.len(),
+            cluster.similarity * 100.0
+        );
+        for member in &cluster.members {
+            println!(
+                "  {} ({:?}) at {}:{}-{} [{} tokens]",
+                member.name,
+                member.kind,
+                member.file_path,
+                member.start_line,
+                member.end_line,
+                member.tokens
+            );
+        }
+        println!();
+    }
+    if clusters.len() < total {
+        eprintln!("Showing {} of {total} clusters", clusters.len());
+    }
+}
+
+fn print_hotspots(report: &HotspotReport, format: OutputFormat) {
+    if format.is_structured() {
+        let envelope = Envelope::success(report).with_message("symbol usage hot spots");
+        println!(
+            "{}",
+            envelope.render(format).expect("envelope serialization")
+        );
+        return;
+    }
+
+    let sections: [(&str, &str, &[Hotspot]); 4] = [
+        ("Most called functions", "calls", &report.most_called),
+        (
+            "Most referenced types",
+            "references",
+            &report.most_referenced_types,
+        ),
+        ("Highest fan-in", "dependents", &report.highest_fan_in),
+        ("Highest fan-out", "dependencies", &report.highest_fan_out),
+    ];
+    for (title, unit, entries) in sections {
+        println!("{title}:");
+        if entries.is_empty() {
+            println!("  (none)");
+        }
+        for entry in entries {
+            println!(
+                "  {:>6} {unit:<12} {} ({:?}) at {}:{}",
+                entry.count, entry.name, entry.kind, entry.file_path, entry.line
+            );
+        }
+        println!();
+    }
+}
+
+fn print_layer_violations(violations: &[LayerViolation], format: OutputFormat) {
+    if format.is_structured() {
+        let envelope = Envelope::success(violations)
+            .with_count(violations.len())
+            .with_message(format!("{} layer violations", violations.len()));
+        println!(
+            "{}",
+            envelope.render(format).expect("envelope serialization")
+        );
+        return;
+    }
+
+    if violations.is_empty() {
+        eprintln!("No layer violations");
+        return;
+    }
+
+    for violation in violations {
+        let location = match violation.line {
+            Some(line) => format!("{}:{line}", violation.file_path),
+            None => violation.file_path.clone(),
+        };
+        let from = violation
+            .from_symbol
+            .as_deref()
+            .unwrap_or(&violation.from_module);
+        let to = match &violation.to_symbol {
+            Some(symbol) => format!("{symbol} ({})", violation.to_module),
+            None => violation.to_module.clone(),
+        };
+        println!(
+            "{location}: [{}] {from} {} {to}",
+            violation.rule, violation.edge
+        );
+    }
+    eprintln!("{} layer violations", violations.len());
+}
+
+fn print_unused_imports(report: &[FileUnusedImports], format: OutputFormat) {
+    let total: usize = report.iter().map(|file| file.imports.len()).sum();
+    if format.is_structured() {
+        let envelope = Envelope::success(report)
+            .with_count(report.len())
+            .with_message(format!("{total} unused imports in {} files", report.len()));
+        println!(
+            "{}",
+            envelope.render(format).expect("envelope serialization")
+        );
+        return;
+    }
+
+    if report.is_empty() {
+        eprintln!("No unused imports");
+        return;
+    }
+
+    for file in report {
+        println!("{} ({})", file.file_path, file.language);
+        for import in &file.imports {
+            let line = import
+                .line
+                .map_or_else(|| "?".to_string(), |line| line.to_string());
+            match &import.alias {
+                Some(alias) => println!("  {line:>5}  {} as {alias}", import.path),
+                None => println!("  {line:>5}  {}", import.path),
+            }
+        }
+    }
+    eprintln!("{total} unused imports in {} files", report.len());
+}
+
+fn print_entry_points(entry_points: &EntryPoints, format: OutputFormat) {
+    let entries = &entry_points.entries;
+    if format.is_structured() {
+        let envelope = Envelope::success(entry_points)
+            .with_count(entries.len())
+            .with_message(format!("{} entry points", entries.len()));
+        println!(
+            "{}",
+            envelope.render(format).expect("envelope serialization")
+        );
+        return;
+    }
+
+    if entries.is_empty() {
+        eprintln!("No entry points found");
+        return;
+    }
+
+    for entry in entries {
+        let location = match entry.line {
+            Some(line) => format!("{}:{line}", entry.file_path),
+            None => entry.file_path.clone(),
+        };
+        let symbol = entry
+            .symbol
+            .as_deref()
+            .map(|symbol| format!(" ({symbol})"))
+            .unwrap_or_default();
+        let declared = entry
+            .declared_in
+            .as_deref()
+            .map(|manifest| format!(" [{manifest}]"))
+            .unwrap_or_default();
+        println!(
+            "{:<14} {} at {location}{symbol}{declared}",
+            format!("{:?}", entry.kind),
+            entry.name
+        );
+    }
+    eprintln!("{} entry points", entries.len());
+}
+
+fn print_security_findings(findings: &[SecurityFinding], format: OutputFormat) {
+    if format.is_structured() {
+        let envelope = Envelope::success(findings)
+            .with_count(findings.len())
+            .with_message(format!("{} security findings", findings.len()));
+        println!(
+            "{}",
+            envelope.render(format).expect("envelope serialization")
+        );
+        return;
+    }
+
+    if findings.is_empty() {
+        eprintln!("No security-sensitive calls found");
+        return;
+    }
+
+    for finding in findings {
+        let context = match (&finding.symbol, finding.symbol_kind) {
+            (Some(symbol), Some(kind)) => format!(" in {symbol} ({kind:?})"),
+            _ => String::new(),
+        };
+        println!(
+            "{}:{}:{}: [{:?}] {}{context}: {}",
+            finding.file_path,
+            finding.line,
+            finding.column,
+            finding.severity,
+            finding.call,
+            finding.message
+        );
+        println!("    {}", finding.code);
+    }
+    eprintln!("{} security findings", findings.len());
+}
+
+fn print_flag_usages(usages: &[FlagUsage], format: OutputFormat) {
+    let sites: usize = usages.iter().map(|usage| usage.sites.len()).sum();
+    if format.is_structured() {
+        let envelope = Envelope::success(usages)
+            .with_count(usages.len())
+            .with_message(format!("{} flags, {sites} lookups", usages.len()));
+        println!(
+            "{}",
+            envelope.render(format).expect("envelope serialization")
+        );
+        return;
+    }
+
+    if usages.is_empty() {
+        eprintln!("No feature flag lookups found");
+        return;
+    }
+
+    for usage in usages {
+        println!(
+            "{} ({}) - {} lookups",
+            usage.flag,
+            usage.systems.join(", "),
+            usage.sites.len()
+        );
+        for site in &usage.sites {
+            let context = match (&site.symbol, site.symbol_kind) {
+                (Some(symbol), Some(kind)) => format!(" in {symbol} ({kind:?})"),
+                _ => String::new(),
+            };
+            println!(
+                "    {}:{}:{}{context}: {}",
+                site.file_path, site.line, site.column, site.code
+            );
+        }
+    }
+    eprintln!("{} flags, {sites} lookups", usages.len());
+}
+
+fn print_i18n_report(report: &I18nReport, format: OutputFormat) {
+    if format.is_structured() {
+        let envelope = Envelope::success(report)
+            .with_count(report.keys.len())
+            .with_message(format!(
+                "{} keys, {} untranslated literals",
+                report.keys.len(),
+                report.literals.len()
+            ));
+        println!(
+            "{}",
+            envelope.render(format).expect("envelope serialization")
+        );
+        return;
+    }
+
+    if report.keys.is_empty() && report.literals.is_empty() {
+        eprintln!("No i18n keys found");
+        return;
+    }
+
+    for key in &report.keys {
+        let status = if key.is_unused() {
+            "unused".to_string()
+        } else if report.is_missing(key) {
+            format!("{} lookups, missing", key.sites.len())
+        } else {
+            format!("{} lookups", key.sites.len())
+        };
+        let defined = if key.defined_in.is_empty() {
+            String::new()
+        } else {
+            format!(" [{}]", key.defined_in.join(", "))
+        };
+        println!("{} - {status}{defined}", key.key);
+        for site in &key.sites {
+            let context = match (&site.symbol, site.symbol_kind) {
+                (Some(symbol), Some(kind)) => format!(" in {symbol} ({kind:?})"),
+                _ => String::new(),
+            };
+            println!(
+                "    {}:{}:{}{context}: {}",
+                site.file_path, site.line, site.column, site.code
+            );
+        }
+    }
+    if !report.literals.is_empty() {
+        println!();
+        println!("Untranslated literals:");
+        for literal in &report.literals {
+            println!(
+                "    {}:{}:{}: \"{}\"",
+                literal.site.file_path, literal.site.line, literal.site.column, literal.text
+            );
+        }
+    }
+    eprintln!(
+        "{} keys, {} untranslated literals",
+        report.keys.len(),
+        report.literals.len()
+    );
+}
+
+fn print_routes(routes: &[Route], format: OutputFormat) {
+    if format.is_structured() {
+        let envelope = Envelope::success(routes)
+            .with_count(routes.len())
+            .with_message(format!("{} routes", routes.len()));
+        println!(
+            "{}",
+            envelope.render(format).expect("envelope serialization")
+        );
+        return;
+    }
+
+    if routes.is_empty() {
+        eprintln!("No routes found");
+        return;
+    }
+
+    for route in routes {
+        let handler = match &route.handler {
+            Some(handler) => format!(
+                "{} ({:?}) at {}:{}",
+                handler.name, handler.kind, handler.file_path, handler.line
+            ),
+            None => "unresolved handler".to_string(),
+        };
+        println!(
+            "{:<7} {} -> {handler}  [{} {}:{}]",
+            route.method, route.path, route.framework, route.file_path, route.line
+        );
+    }
+    eprintln!("{} routes", routes.len());
+}
+
+fn print_api_drift(drift: &ApiDrift, format: OutputFormat) {
+    let message = format!(
+        "{} matched, {} spec-only, {} code-only",
+        drift.matched.len(),
+        drift.spec_only.len(),
+        drift.code_only.len()
+    );
+    if format.is_structured() {
+        let envelope = Envelope::success(drift)
+            .with_count(drift.spec_only.len() + drift.code_only.len())
+            .with_message(message);
+        println!(
+            "{}",
+            envelope.render(format).expect("envelope serialization")
+        );
+        return;
+    }
+
+    if !drift.spec_only.is_empty() {
+        println!("Documented in {} but not served:", drift.spec);
+        for operation in &drift.spec_only {
+            let id = operation
+                .operation_id
+                .as_deref()
+                .map(|id| format!(" ({id})"))
+                .unwrap_or_default();
+            let line = operation
+                .line
+                .map(|line| format!("  [{}:{line}]", drift.spec))
+                .unwrap_or_default();
+            println!("  {:<7} {}{id}{line}", operation.method, operation.path);
+        }
+    }
+    if !drift.code_only.is_empty() {
+        println!("Served but not documented:");
+        for route in &drift.code_only {
+            let handler = route
+                .handler
+                .as_ref()
+                .map(|handler| format!(" -> {}", handler.name))
+                .unwrap_or_default();
+            println!(
+                "  {:<7} {}{handler}  [{}:{}]",
+                route.method, route.path, route.file_path, route.line
+            );
+        }
+    }
+    if !drift.has_drift() {
+        println!("Spec and code agree");
+    }
+    eprintln!("{message}");
+}
+
+fn print_grpc_links(protos: &[ProtoFile], format: OutputFormat) {
+    let services: usize = protos.iter().map(|proto| proto.services.len()).sum();
+    if format.is_structured() {
+        let envelope = Envelope::success(protos)
+            .with_count(protos.len())
+            .with_message(format!("{} proto files, {services} services", protos.len()));
+        println!(
+            "{}",
+            envelope.render(format).expect("envelope serialization")
+        );
+        return;
+    }
+
+    if protos.is_empty() {
+        eprintln!("No .proto files found");
+        return;
+    }
+
+    let describe = |site: &GrpcSite| {
+        let context = match (&site.symbol, site.symbol_kind) {
+            (Some(symbol), Some(kind)) => format!(" in {symbol} ({kind:?})"),
+            _ => String::new(),
+        };
+        format!("{}:{}:{}{context}", site.file_path, site.line, site.column)
+    };
+    for proto in protos {
+        let package = proto
+            .package
+            .as_deref()
+            .map(|package| format!("{package}."))
+            .unwrap_or_default();
+        for service in &proto.services {
+            println!(
+                "service {package}{} ({}:{})",
+                service.name, proto.file_path, service.line
+            );
+            for server in &service.servers {
+                println!("  server  {}", describe(server));
+            }
+            for client in &service.clients {
+                println!("  client  {}", describe(client));
+            }
+            for rpc in &service.rpcs {
+                let stream = |streaming: bool| if streaming { "stream " } else { "" };
+                println!(
+                    "  rpc {}({}{}) returns ({}{})",
+                    rpc.name,
+                    stream(rpc.client_streaming),
+                    rpc.request,
+                    stream(rpc.server_streaming),
+                    rpc.response
+                );
+                for implementation in &rpc.implementations {
+                    println!("    impl  {}", describe(implementation));
+                }
+                for call in &rpc.calls {
+                    println!("    call  {}", describe(call));
+                }
+            }
+        }
+        for ty in &proto.types {
+            println!(
+                "{} {package}{} ({}:{}) - {} usages",
+                ty.kind,
+                ty.name,
+                proto.file_path,
+                ty.line,
+                ty.usages.len()
+            );
+        }
+    }
+    eprintln!("{} proto files, {services} services", protos.len());
+}
+
+fn print_models(models: &[OrmModel], format: OutputFormat) {
+    let usages: usize = models.iter().map(|model| model.usages.len()).sum();
+    if format.is_structured() {
+        let envelope = Envelope::success(models)
+            .with_count(models.len())
+            .with_message(format!("{} models, {usages} query sites", models.len()));
+        println!(
+            "{}",
+            envelope.render(format).expect("envelope serialization")
+        );
+        return;
+    }
+
+    if models.is_empty() {
+        eprintln!("No ORM models found");
+        return;
+    }
+
+    let describe = |usage: &ModelUsage| {
+        let context = match (&usage.symbol, usage.symbol_kind) {
+            (Some(symbol), Some(kind)) => format!(" in {symbol} ({kind:?})"),
+            _ => String::new(),
+        };
+        format!(
+            "{}:{}:{}{context}",
+            usage.file_path, usage.line, usage.column
+        )
+    };
+    for model in models {
+        let table = model
+            .table
+            .as_deref()
+            .map(|table| format!(" table {table}"))
+            .unwrap_or_default();
+        println!(
+            "{} {}{table} ({}:{})",
+            model.orm.as_str(),
+            model.name,
+            model.file_path,
+            model.line
+        );
+        for field in &model.fields {
+            println!("  field {}: {}", field.name, field.field_type);
+        }
+        for usage in &model.usages {
+            println!("  query {}", describe(usage));
+        }
+    }
+    eprintln!("{} models, {usages} query sites", models.len());
+}
+
+fn print_project_graph(graph: &ProjectGraph, format: OutputFormat) {
+    let message = format!(
+        "{} projects, {} dependencies, {} cycles",
+        graph.projects.len(),
+        graph.edges.len(),
+        graph.cycles.len()
+    );
+    if format.is_structured() {
+        let envelope = Envelope::success(graph)
+            .with_count(graph.projects.len())
+            .with_message(message);
+        println!(
+            "{}",
+            envelope.render(format).expect("envelope serialization")
+        );
+        return;
+    }
+
+    if graph.projects.is_empty() {
+        eprintln!("No projects detected; reindex to record project boundaries");
+        return;
+    }
+
+    for project in &graph.projects {
+        println!(
+            "{} ({} files, {} symbols)",
+            project.name, project.files, project.symbols
+        );
+        for edge in graph.edges.iter().filter(|edge| edge.from == project.name) {
+            let marker = if graph.in_cycle(edge) {
+                "  [cycle]"
+            } else {
+                ""
+            };
+            println!(
+                "  -> {} ({} relationships, {} imports){marker}",
+                edge.to, edge.relationships, edge.imports
+            );
+        }
+    }
+    for cycle in &graph.cycles {
+        println!("cycle: {}", cycle.join(" <-> "));
+    }
+    eprintln!("{message}");
+}
+
+fn print_diff_annotation(annotation: &DiffAnnotation, format: OutputFormat) {
+    let callers: usize = annotation
+        .symbols
+        .iter()
+        .map(|symbol| symbol.callers.len())
+        .sum();
+    let message = format!(
+        "{} changed symbols in {} files, {callers} callers",
+        annotation.symbols.len(),
+        annotation.changed_files.len()
+    );
+    if format.is_structured() {
+        let envelope = Envelope::success(annotation)
+            .with_count(annotation.symbols.len())
+            .with_message(message);
+        println!(
+            "{}",
+            envelope.render(format).expect("envelope serialization")
+        );
+        return;
+    }
+
+    if annotation.changed_files.is_empty() {
+        eprintln!("No changes");
+        return;
+    }
+
+    for changed in &annotation.symbols {
+        let symbol = &changed.symbol;
+        println!(
+            "{}:{} {:?} {}",
+            symbol.file_path, symbol.line, symbol.kind, symbol.name
+        );
+        for caller in &changed.callers {
+            println!(
+                "  <- {} ({}:{})",
+                caller.name, caller.file_path, caller.line
+            );
+        }
+    }
+    eprintln!("{message}");
+}
+
+fn print_review_routing(routing: &ReviewRouting, format: OutputFormat) {
+    let message = format!(
+        "{} owners for {} changed files and {} affected symbols ({})",
+        routing.owners.len(),
+        routing.changed_files.len(),
+        routing.affected.len(),
+        routing.codeowners
+    );
+    if format.is_structured() {
+        let envelope = Envelope::success(routing)
+            .with_count(routing.owners.len())
+            .with_message(message);
+        println!(
+            "{}",
+            envelope.render(format).expect("envelope serialization")
+        );
+        return;
+    }
+
+    if routing.changed_files.is_empty() {
+        eprintln!("No changes");
+        return;
+    }
+
+    for route in &routing.owners {
+        let reason = if route.changed {
+            "changed"
+        } else {
+            "dependent"
+        };
+        println!("{} ({reason})", route.owner);
+        for file in &route.files {
+            println!("  {file}");
+            for symbol in route
+                .symbols
+                .iter()
+                .filter(|symbol| &symbol.file_path == file)
+            {
+                let marker = if symbol.changed { "*" } else { " " };
+                println!(
+                    "    {marker} {:?} {} :{}",
+                    symbol.kind, symbol.name, symbol.line
+                );
+            }
+        }
+    }
+    if !routing.unowned.is_empty() {
+        println!("unowned");
+        for file in &routing.unowned {
+            println!("  {file}");
+        }
+    }
+    eprintln!("{message}");
+}
+
+fn print_churn(files: &[FileChurn], total: usize, format: OutputFormat) {
+    let hotspots = files.iter().filter(|file| file.hotspot).count();
+    if format.is_structured() {
+        let envelope = Envelope::success(files)
+            .with_count(files.len())
+            .with_message(format!("{total} files with history, {hotspots} hot spots"));
+        println!(
+            "{}",
+            envelope.render(format).expect("envelope serialization")
+        );
+        return;
+    }
+
+    if files.is_empty() {
+        eprintln!("No indexed files with git history");
+        return;
+    }
+
+    println!("{:>7} {:>10} {:>8}  file", "commits", "complexity", "score");
+    for file in files {
+        let marker = if file.hotspot { "  [hot spot]" } else { "" };
+        println!(
+            "{:>7} {:>10} {:>8}  {}{marker}",
+            file.commits, file.complexity, file.score, file.file_path
+        );
+    }
+    if files.len() < total {
+        eprintln!("Showing {} of {total} files", files.len());
+    }
+}
@@ -0,0 +1,76 @@
+//! Index artifact commands - publish and mount shared prebuilt indexes.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::config::Settings;
+use crate::io::{ExitCode, OutputFormat, OutputManager};
+use crate::storage::IndexPersistence;
+use crate::storage::artifact::{mount_artifact, publish_index};
+
+/// Run `codanna index publish`.
+pub fn run_publish(output: &Path, config: &Settings) -> ExitCode {
+    match publish_index(config, output) {
+        Ok(manifest) => {
+            println!(
+                "Published {} ({} symbols, {} files, codanna {})",
+                output.display(),
+                manifest.symbol_count,
+                manifest.file_count,
+                manifest.codanna_version
+            );
+            ExitCode::Success
+        }
+        Err(e) => {
+            eprintln!("Error: {e}");
+            e.exit_code()
+        }
+    }
+}
+
+/// Run `codanna index mount`.
+///
+/// Mounts the artifact as the index base, then re-indexes files edited
+/// locally and drops files that no longer exist so the overlay reflects the
+/// working tree.
+pub fn run_mount(source: &str, json: bool, config: &Settings) -> ExitCode {
+    let report = match mount_artifact(source, config) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return e.exit_code();
+        }
+    };
+
+    if !report.modified.is_empty() || !report.deleted.is_empty() {
+        let persistence = IndexPersistence::new(config.index_path.clone());
+        let mut facade = match persistence.load_facade_lite(Arc::new(config.clone())) {
+            Ok(facade) => facade,
+            Err(e) => {
+                eprintln!("Error: Failed to open mounted index: {e}");
+                return ExitCode::from_error(&e);
+            }
+        };
+
+        let root = config.workspace_root.clone().unwrap_or_default();
+        for path in &report.deleted {
+            if let Err(e) = facade.remove_file(path) {
+                tracing::warn!(target: "cli", "failed to drop {path} from overlay: {e}");
+            }
+        }
+        for path in &report.modified {
+            let absolute: PathBuf = root.join(path);
+            if let Err(e) = facade.index_file(&absolute) {
+                tracing::warn!(target: "cli", "failed to overlay {path}: {e}");
+            }
+        }
+
+        if let Err(e) = persistence.save_facade(&facade) {
+            eprintln!("Error: Failed to save overlay: {e}");
+            return ExitCode::from_error(&e);
+        }
+    }
+
+    let mut output = OutputManager::new(OutputFormat::from_json_flag(json));
+    output.success(report).unwrap_or(ExitCode::GeneralError)
+}
@@ -1,16 +1,221 @@
 //! Benchmark command - parser performance testing.
+//!
+//! Besides the per-language generated benchmarks, `--check` and
+//! `--save-baseline` run the fixture suite: every language's
+//! `examples/<lang>/comprehensive.*` file, measured in symbols/sec and
+//! bytes/sec and compared against a stored baseline.
 
-use std::path::PathBuf;
-use std::time::Instant;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
+use crate::Settings;
 use crate::display::tables::create_benchmark_table;
 use crate::display::theme::Theme;
+use crate::io::ExitCode;
 use crate::parsing::{
-    CSharpParser, GoParser, LanguageParser, LuaParser, PhpParser, PythonParser, RustParser,
-    TypeScriptParser,
+    CSharpParser, GoParser, LanguageId, LanguageParser, LuaParser, PhpParser, PythonParser,
+    RustParser, TypeScriptParser, get_registry,
 };
 use crate::types::{FileId, SymbolCounter};
 use console::style;
+use serde::{Deserialize, Serialize};
+
+/// Timed runs per fixture; the fastest one is reported
+const SUITE_RUNS: usize = 5;
+
+/// Options for the fixture suite
+pub struct SuiteOptions {
+    /// Fail when a language is slower than the baseline allows
+    pub check: bool,
+    /// Write the measured throughput as the new baseline
+    pub save_baseline: bool,
+    /// Baseline file (JSON, keyed by language)
+    pub baseline: PathBuf,
+    /// Allowed slowdown against the baseline, in percent
+    pub tolerance: f64,
+}
+
+/// Parsing throughput of one fixture
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Throughput {
+    pub symbols_per_sec: f64,
+    pub bytes_per_sec: f64,
+}
+
+/// A language's comprehensive example, used as its benchmark fixture
+pub struct SuiteFixture {
+    pub language: LanguageId,
+    pub path: PathBuf,
+    pub code: String,
+}
+
+/// Fixtures of every registered language found under `root/examples`,
+/// sorted by language. `language` restricts the suite to one of them.
+pub fn suite_fixtures(root: &Path, language: Option<&str>) -> Vec<SuiteFixture> {
+    let registry = get_registry();
+    let Ok(registry) = registry.lock() else {
+        return Vec::new();
+    };
+    let Ok(dirs) = std::fs::read_dir(root.join("examples")) else {
+        return Vec::new();
+    };
+
+    let mut fixtures: Vec<SuiteFixture> = dirs
+        .flatten()
+        .filter_map(|dir| std::fs::read_dir(dir.path()).ok())
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.file_stem().is_some_and(|stem| stem == "comprehensive"))
+        .filter_map(|path| {
+            let extension = path.extension()?.to_str()?;
+            let language = registry.get_by_extension(extension)?.id();
+            let code = std::fs::read_to_string(&path).ok()?;
+            Some(SuiteFixture {
+                language,
+                path,
+                code,
+            })
+        })
+        .filter(|fixture| language.is_none_or(|name| fixture.language.as_str() == name))
+        .collect();
+    fixtures.sort_by_key(|fixture| fixture.language.as_str());
+    fixtures
+}
+
+/// Create a parser for `language`, whether or not it is enabled in `settings`
+pub fn suite_parser(language: LanguageId, settings: &Settings) -> Option<Box<dyn LanguageParser>> {
+    let registry = get_registry();
+    let registry = registry.lock().ok()?;
+    registry.get(language)?.create_parser(settings).ok()
+}
+
+/// Parse `code` once to warm up, then `runs` times, returning the symbol
+/// count and the throughput of the fastest run.
+pub fn measure(parser: &mut dyn LanguageParser, code: &str, runs: usize) -> (usize, Throughput) {
+    let file_id = FileId::new(1).expect("Failed to create file ID");
+    let _ = parser.parse(code, file_id, &mut SymbolCounter::new());
+
+    let mut fastest = Duration::MAX;
+    let mut symbols = 0;
+    for _ in 0..runs.max(1) {
+        let start = Instant::now();
+        symbols = parser.parse(code, file_id, &mut SymbolCounter::new()).len();
+        fastest = fastest.min(start.elapsed());
+    }
+
+    let secs = fastest.as_secs_f64().max(f64::EPSILON);
+    let throughput = Throughput {
+        symbols_per_sec: symbols as f64 / secs,
+        bytes_per_sec: code.len() as f64 / secs,
+    };
+    (symbols, throughput)
+}
+
+/// Languages slower than their baseline by more than `tolerance` percent,
+/// described for the report. Languages without a baseline are not checked.
+pub fn regressions(
+    results: &BTreeMap<String, Throughput>,
+    baseline: &BTreeMap<String, Throughput>,
+    tolerance: f64,
+) -> Vec<String> {
+    let floor = 1.0 - tolerance / 100.0;
+    let mut failures = Vec::new();
+    for (language, measured) in results {
+        let Some(expected) = baseline.get(language) else {
+            continue;
+        };
+        for (metric, value, base) in [
+            (
+                "symbols/sec",
+                measured.symbols_per_sec,
+                expected.symbols_per_sec,
+            ),
+            ("bytes/sec", measured.bytes_per_sec, expected.bytes_per_sec),
+        ] {
+            if value < base * floor {
+                failures.push(format!(
+                    "{language}: {value:.0} {metric} is {:.1}% below the baseline of {base:.0}",
+                    (1.0 - value / base) * 100.0
+                ));
+            }
+        }
+    }
+    failures
+}
+
+/// Run the fixture suite and report, save, or check the results.
+pub fn run_suite(language: &str, options: &SuiteOptions, settings: &Settings) -> ExitCode {
+    let filter = (language != "all").then(|| language.to_lowercase());
+    let fixtures = suite_fixtures(Path::new("."), filter.as_deref());
+    if fixtures.is_empty() {
+        eprintln!("No benchmark fixtures found under examples/*/comprehensive.*");
+        return ExitCode::NotFound;
+    }
+
+    println!(
+        "{:<12} {:>8} {:>10} {:>14} {:>14}",
+        "Language", "Symbols", "Bytes", "Symbols/sec", "KiB/sec"
+    );
+    let mut results = BTreeMap::new();
+    for fixture in &fixtures {
+        let Some(mut parser) = suite_parser(fixture.language, settings) else {
+            eprintln!("Skipping {}: parser unavailable", fixture.language.as_str());
+            continue;
+        };
+        let (symbols, throughput) = measure(parser.as_mut(), &fixture.code, SUITE_RUNS);
+        println!(
+            "{:<12} {symbols:>8} {:>10} {:>14.0} {:>14.0}",
+            fixture.language.as_str(),
+            fixture.code.len(),
+            throughput.symbols_per_sec,
+            throughput.bytes_per_sec / 1024.0
+        );
+        results.insert(fixture.language.as_str().to_string(), throughput);
+    }
+
+    if options.save_baseline {
+        let json = serde_json::to_string_pretty(&results).expect("throughput serializes");
+        if let Err(e) = std::fs::write(&options.baseline, json + "\n") {
+            eprintln!("Failed to write {}: {e}", options.baseline.display());
+            return ExitCode::IoError;
+        }
+        println!("\nBaseline saved to {}", options.baseline.display());
+    }
+
+    if !options.check {
+        return ExitCode::Success;
+    }
+
+    let baseline: BTreeMap<String, Throughput> = match std::fs::read_to_string(&options.baseline)
+        .map_err(|e| e.to_string())
+        .and_then(|json| serde_json::from_str(&json).map_err(|e| e.to_string()))
+    {
+        Ok(baseline) => baseline,
+        Err(e) => {
+            eprintln!(
+                "Failed to load baseline {}: {e}",
+                options.baseline.display()
+            );
+            return ExitCode::ConfigError;
+        }
+    };
+
+    let failures = regressions(&results, &baseline, options.tolerance);
+    if failures.is_empty() {
+        println!(
+            "\nAll languages within {}% of the baseline",
+            options.tolerance
+        );
+        return ExitCode::Success;
+    }
+    eprintln!("\nPerformance regressions:");
+    for failure in &failures {
+        eprintln!("  {failure}");
+    }
+    ExitCode::GeneralError
+}
 
 /// Run parser performance benchmarks
 pub fn run(language: &str, custom_file: Option<PathBuf>) {
@@ -761,3 +966,43 @@ fn generate_csharp_benchmark_code() -> String {
 
     code
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_regressions_respect_tolerance() {
+        let rate = |symbols_per_sec, bytes_per_sec| Throughput {
+            symbols_per_sec,
+            bytes_per_sec,
+        };
+        let baseline = BTreeMap::from([
+            ("go".to_string(), rate(100_000.0, 4_000_000.0)),
+            ("rust".to_string(), rate(100_000.0, 4_000_000.0)),
+        ]);
+        let results = BTreeMap::from([
+            // 10% slower: within a 20% tolerance
+            ("go".to_string(), rate(90_000.0, 3_600_000.0)),
+            // Bytes/sec dropped by half
+            ("rust".to_string(), rate(95_000.0, 2_000_000.0)),
+            // No baseline yet
+            ("lua".to_string(), rate(1.0, 1.0)),
+        ]);
+
+        let failures = regressions(&results, &baseline, 20.0);
+        assert_eq!(failures.len(), 1);
+        assert!(failures[0].starts_with("rust: 2000000 bytes/sec is 50.0% below"));
+    }
+
+    #[test]
+    fn test_suite_fixtures_cover_examples() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let fixtures = suite_fixtures(root, None);
+        assert!(fixtures.iter().any(|f| f.language.as_str() == "rust"));
+
+        let only_go = suite_fixtures(root, Some("go"));
+        assert_eq!(only_go.len(), 1);
+        assert!(only_go[0].path.ends_with("examples/go/comprehensive.go"));
+    }
+}
@@ -0,0 +1,278 @@
+//! Completions command - shell completion scripts.
+//!
+//! Scripts are generated from the clap command tree, so subcommands and flags
+//! follow the CLI as it changes. Where a command takes a symbol name, the
+//! script calls the hidden `codanna complete-symbols <prefix>`, which reads
+//! matching names from the index's term dictionary.
+
+use crate::cli::Cli;
+use crate::indexing::facade::IndexFacade;
+use crate::io::ExitCode;
+use clap::{Command, CommandFactory};
+
+/// Commands whose first positional argument is a symbol name
+const SYMBOL_COMMANDS: &[&str] = &[
+    "retrieve symbol",
+    "retrieve calls",
+    "retrieve callers",
+    "retrieve implementations",
+    "retrieve type-definition",
+    "retrieve call-hierarchy",
+    "retrieve type-hierarchy",
+    "retrieve describe",
+    "retrieve history",
+];
+
+const BASH_MAIN: &str = r#"_codanna() {
+    local cur="${COMP_WORDS[COMP_CWORD]}" path="" word i subcommands
+    for ((i = 1; i < COMP_CWORD; i++)); do
+        word="${COMP_WORDS[i]}"
+        if [[ " $(_codanna_subcommands "$path") " == *" $word "* ]]; then
+            path="${path:+$path }$word"
+        fi
+    done
+
+    subcommands="$(_codanna_subcommands "$path")"
+    if [[ "$cur" == -* ]]; then
+        COMPREPLY=($(compgen -W "$(_codanna_flags "$path")" -- "$cur"))
+    elif [[ -n "$subcommands" ]]; then
+        COMPREPLY=($(compgen -W "$subcommands" -- "$cur"))
+    elif _codanna_takes_symbol "$path" && [[ -n "$cur" && "$cur" != *:* ]]; then
+        COMPREPLY=($(codanna complete-symbols "$cur" 2>/dev/null))
+    fi
+}
+
+complete -o default -F _codanna codanna
+"#;
+
+const FISH_MAIN: &str = r#"function __codanna_path
+    set -l path ''
+    for word in (commandline -opc)[2..-1]
+        if contains -- $word (__codanna_subcommands $path)
+            set path (string trim -- "$path $word")
+        end
+    end
+    echo $path
+end
+
+function __codanna_has_subcommands
+    count (__codanna_subcommands (__codanna_path)) >/dev/null
+end
+
+function __codanna_completing_symbol
+    set -l token (commandline -ct)
+    test -n "$token"; and __codanna_takes_symbol (__codanna_path)
+end
+
+complete -c codanna -f -n '__codanna_has_subcommands' -a '(__codanna_subcommands (__codanna_path))'
+complete -c codanna -n 'string match -q -- "-*" (commandline -ct)' -a '(__codanna_flags (__codanna_path))'
+complete -c codanna -f -n '__codanna_completing_symbol' -a '(codanna complete-symbols (commandline -ct) 2>/dev/null)'
+"#;
+
+/// Shells with a completion script
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl Shell {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "bash" => Some(Self::Bash),
+            "zsh" => Some(Self::Zsh),
+            "fish" => Some(Self::Fish),
+            _ => None,
+        }
+    }
+}
+
+/// Run the completions command.
+pub fn run(shell: &str) -> ExitCode {
+    let Some(shell) = Shell::parse(shell) else {
+        eprintln!("Error: Unsupported shell '{shell}'");
+        eprintln!("Suggestion: Use bash, zsh, or fish");
+        return ExitCode::ConfigError;
+    };
+    print!("{}", script(shell, Cli::command()));
+    ExitCode::Success
+}
+
+/// Run the hidden complete-symbols command.
+pub fn run_symbols(prefix: &str, limit: usize, indexer: &IndexFacade) -> ExitCode {
+    if prefix.is_empty() {
+        return ExitCode::Success;
+    }
+    for name in indexer.complete_symbol_names(prefix, limit) {
+        println!("{name}");
+    }
+    ExitCode::Success
+}
+
+/// The completion script for `command` in `shell`
+pub fn script(shell: Shell, mut command: Command) -> String {
+    // Building adds the generated `--help` and `help` and propagates
+    // global flags to subcommands
+    command.build();
+    let nodes = command_paths(&command);
+    match shell {
+        Shell::Bash => bash(&nodes),
+        // zsh runs the bash script through its compatibility layer
+        Shell::Zsh => format!(
+            "#compdef codanna\n\nautoload -U +X bashcompinit && bashcompinit\n\n{}",
+            bash(&nodes)
+        ),
+        Shell::Fish => fish(&nodes),
+    }
+}
+
+/// A command path such as "retrieve symbol" with its visible subcommands and
+/// long flags
+struct CommandPath {
+    path: String,
+    subcommands: Vec<String>,
+    flags: Vec<String>,
+}
+
+fn command_paths(command: &Command) -> Vec<CommandPath> {
+    let mut paths = Vec::new();
+    collect_paths(command, String::new(), &mut paths);
+    paths
+}
+
+fn collect_paths(command: &Command, path: String, paths: &mut Vec<CommandPath>) {
+    let subcommands: Vec<&Command> = command
+        .get_subcommands()
+        .filter(|sub| !sub.is_hide_set())
+        .collect();
+    let flags = command
+        .get_arguments()
+        .filter(|arg| !arg.is_hide_set())
+        .filter_map(|arg| arg.get_long())
+        .map(|long| format!("--{long}"))
+        .collect();
+    paths.push(CommandPath {
+        path: path.clone(),
+        subcommands: subcommands
+            .iter()
+            .map(|sub| sub.get_name().to_string())
+            .collect(),
+        flags,
+    });
+    for sub in subcommands {
+        let sub_path = if path.is_empty() {
+            sub.get_name().to_string()
+        } else {
+            format!("{path} {}", sub.get_name())
+        };
+        collect_paths(sub, sub_path, paths);
+    }
+}
+
+fn bash(paths: &[CommandPath]) -> String {
+    let mut script = String::from("# bash completion for codanna\n\n");
+    script.push_str("_codanna_subcommands() {\n    case \"$1\" in\n");
+    for path in paths.iter().filter(|path| !path.subcommands.is_empty()) {
+        script.push_str(&format!(
+            "        \"{}\") echo \"{}\" ;;\n",
+            path.path,
+            path.subcommands.join(" ")
+        ));
+    }
+    script.push_str("    esac\n}\n\n");
+
+    script.push_str("_codanna_flags() {\n    case \"$1\" in\n");
+    for path in paths.iter().filter(|path| !path.flags.is_empty()) {
+        script.push_str(&format!(
+            "        \"{}\") echo \"{}\" ;;\n",
+            path.path,
+            path.flags.join(" ")
+        ));
+    }
+    script.push_str("    esac\n}\n\n");
+
+    let symbol_paths: Vec<String> = SYMBOL_COMMANDS
+        .iter()
+        .map(|path| format!("\"{path}\""))
+        .collect();
+    script.push_str(&format!(
+        "_codanna_takes_symbol() {{\n    case \"$1\" in\n        {}) return 0 ;;\n    esac\n    return 1\n}}\n\n",
+        symbol_paths.join(" | ")
+    ));
+    script.push_str(BASH_MAIN);
+    script
+}
+
+fn fish(paths: &[CommandPath]) -> String {
+    let mut script = String::from("# fish completion for codanna\n\n");
+    let switch = |name: &str, rows: Vec<(&str, &[String])>| {
+        let mut function = format!("function {name}\n    switch \"$argv[1]\"\n");
+        for (path, words) in rows {
+            function.push_str(&format!(
+                "        case '{path}'\n            string split ' ' -- '{}'\n",
+                words.join(" ")
+            ));
+        }
+        function.push_str("    end\nend\n\n");
+        function
+    };
+    script.push_str(&switch(
+        "__codanna_subcommands",
+        paths
+            .iter()
+            .filter(|path| !path.subcommands.is_empty())
+            .map(|path| (path.path.as_str(), path.subcommands.as_slice()))
+            .collect(),
+    ));
+    script.push_str(&switch(
+        "__codanna_flags",
+        paths
+            .iter()
+            .filter(|path| !path.flags.is_empty())
+            .map(|path| (path.path.as_str(), path.flags.as_slice()))
+            .collect(),
+    ));
+
+    let symbol_paths: Vec<String> = SYMBOL_COMMANDS
+        .iter()
+        .map(|path| format!("'{path}'"))
+        .collect();
+    script.push_str(&format!(
+        "function __codanna_takes_symbol\n    switch \"$argv[1]\"\n        case {}\n            return 0\n    end\n    return 1\nend\n\n",
+        symbol_paths.join(" ")
+    ));
+    script.push_str(FISH_MAIN);
+    script
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_symbol_commands_exist_and_scripts_list_them() {
+        let mut command = Cli::command();
+        command.build();
+        let paths = command_paths(&command);
+        for symbol_command in SYMBOL_COMMANDS {
+            assert!(
+                paths.iter().any(|path| path.path == *symbol_command),
+                "{symbol_command} is not a command"
+            );
+        }
+        assert!(
+            !paths[0]
+                .subcommands
+                .iter()
+                .any(|name| name == "complete-symbols")
+        );
+
+        let bash = script(Shell::Bash, Cli::command());
+        assert!(bash.contains("\"retrieve\") echo \"symbol calls callers"));
+        assert!(bash.contains("\"retrieve symbol\") echo \"--"));
+        assert!(bash.contains("complete -o default -F _codanna codanna"));
+        let fish = script(Shell::Fish, Cli::command());
+        assert!(fish.contains("case 'retrieve describe'"));
+    }
+}
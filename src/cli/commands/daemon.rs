@@ -0,0 +1,260 @@
+//! Daemon command - keep the index loaded for thin CLI clients.
+//!
+//! `start` launches `daemon start --foreground` as a detached process and
+//! returns once it answers; `stop` and `status` talk to it over its socket.
+//! See [`crate::mcp::daemon`] for the protocol.
+
+use crate::cli::commands::mcp::tool_arguments;
+use crate::cli::commands::serve::start_watchers;
+use crate::config::Settings;
+use crate::indexing::facade::IndexFacade;
+use crate::io::{Budget, Envelope, ExitCode, OutputFormat};
+use crate::mcp::CodeIntelligenceServer;
+use crate::mcp::daemon::{self, DaemonReply, DaemonRequest, DaemonStatus, LOG_FILE};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// How long `start` waits for a new daemon to load the index and answer
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(300);
+/// How long `stop` waits for the daemon to let go of its socket
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Start a daemon for the index in the background.
+pub fn start(cli_config: Option<&Path>, index_path: &Path, watch_interval: u64) -> ExitCode {
+    if let Some(status) = status_of(index_path) {
+        eprintln!(
+            "A daemon already serves {} (pid {})",
+            index_path.display(),
+            status.pid
+        );
+        return ExitCode::Success;
+    }
+
+    let log_path = index_path.join(LOG_FILE);
+    let log =
+        match std::fs::create_dir_all(index_path).and_then(|()| std::fs::File::create(&log_path)) {
+            Ok(log) => log,
+            Err(e) => {
+                eprintln!("Error: cannot create {}: {e}", log_path.display());
+                return ExitCode::IoError;
+            }
+        };
+    let exe = match std::env::current_exe() {
+        Ok(exe) => exe,
+        Err(e) => {
+            eprintln!("Error: cannot locate the codanna executable: {e}");
+            return ExitCode::GeneralError;
+        }
+    };
+
+    let mut command = Command::new(exe);
+    if let Some(config) = cli_config {
+        command.arg("--config").arg(config);
+    }
+    command
+        .args(["daemon", "start", "--foreground", "--watch-interval"])
+        .arg(watch_interval.to_string())
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(log);
+    // Its own process group, so Ctrl-C in this terminal leaves it running
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            eprintln!("Error: failed to start the daemon: {e}");
+            return ExitCode::GeneralError;
+        }
+    };
+
+    let deadline = Instant::now() + STARTUP_TIMEOUT;
+    loop {
+        if let Some(status) = status_of(index_path) {
+            eprintln!(
+                "Daemon started (pid {}) with {} symbols from {} files",
+                status.pid, status.symbols, status.files
+            );
+            return ExitCode::Success;
+        }
+        if let Ok(Some(exit)) = child.try_wait() {
+            eprintln!(
+                "Error: the daemon exited during startup ({exit}), see {}",
+                log_path.display()
+            );
+            return ExitCode::GeneralError;
+        }
+        if Instant::now() >= deadline {
+            eprintln!(
+                "Error: the daemon did not answer within {}s, see {}",
+                STARTUP_TIMEOUT.as_secs(),
+                log_path.display()
+            );
+            return ExitCode::GeneralError;
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Run the daemon in this process until it is stopped.
+pub async fn run(
+    config: &Settings,
+    settings: Arc<Settings>,
+    facade: IndexFacade,
+    index_path: &Path,
+    watch_interval: u64,
+) -> ExitCode {
+    // Unlike `serve`, always warm: answering fast is all the daemon is for
+    let server = CodeIntelligenceServer::new(facade);
+    crate::mcp::warm::spawn_warm_up(server.get_facade_arc());
+    let server = match crate::documents::load_from_settings(config) {
+        Some(store) => server.with_document_store_arc(store),
+        None => server,
+    };
+    start_watchers(config, settings, &server, index_path, true, watch_interval);
+
+    eprintln!(
+        "Daemon serving {} on {}",
+        index_path.display(),
+        daemon::socket_path(index_path).display()
+    );
+    match daemon::serve(server, index_path).await {
+        Ok(()) => ExitCode::Success,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            ExitCode::GeneralError
+        }
+    }
+}
+
+/// Ask the daemon of the index to shut down and wait until it has.
+pub fn stop(index_path: &Path) -> ExitCode {
+    match daemon::send(index_path, &DaemonRequest::Shutdown) {
+        None => {
+            eprintln!("No daemon is running for {}", index_path.display());
+            return ExitCode::NotFound;
+        }
+        Some(Err(e)) => {
+            eprintln!("Error: the daemon did not answer: {e}");
+            return ExitCode::GeneralError;
+        }
+        Some(Ok(_)) => {}
+    }
+
+    let deadline = Instant::now() + SHUTDOWN_TIMEOUT;
+    while daemon::send(index_path, &DaemonRequest::Status).is_some() {
+        if Instant::now() >= deadline {
+            eprintln!("Error: the daemon is still running after being asked to stop");
+            return ExitCode::GeneralError;
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+    eprintln!("Daemon stopped");
+    ExitCode::Success
+}
+
+/// Report whether a daemon serves the index, and what it holds.
+pub fn status(index_path: &Path, json: bool) -> ExitCode {
+    let format = OutputFormat::from_json_flag(json);
+    let Some(status) = status_of(index_path) else {
+        let message = format!("No daemon is running for {}", index_path.display());
+        if format.is_structured() {
+            let envelope: Envelope<()> =
+                Envelope::not_found(message).with_hint("Start one with 'codanna daemon start'");
+            println!(
+                "{}",
+                envelope.render(format).expect("envelope serialization")
+            );
+        } else {
+            println!("{message}");
+        }
+        return ExitCode::NotFound;
+    };
+
+    if format.is_structured() {
+        let message = format!("Daemon running (pid {})", status.pid);
+        let envelope = Envelope::success(&status).with_message(message);
+        println!(
+            "{}",
+            envelope.render(format).expect("envelope serialization")
+        );
+    } else {
+        println!("Daemon running (pid {})", status.pid);
+        println!("  Index:    {}", status.index_path.display());
+        println!(
+            "  Started:  {}",
+            crate::mcp::format_relative_time(status.started_at)
+        );
+        println!("  Symbols:  {} in {} files", status.symbols, status.files);
+        println!("  Requests: {}", status.requests);
+    }
+    ExitCode::Success
+}
+
+/// Run `codanna mcp <tool>` on the daemon of the index and print its text.
+///
+/// `None` when no daemon is running, so the caller loads the index itself.
+pub fn forward(
+    index_path: &Path,
+    tool: &str,
+    positional: &[String],
+    args: Option<&str>,
+) -> Option<ExitCode> {
+    let mut arguments = tool_arguments(tool, positional, args).unwrap_or_default();
+    // The daemon applies --max-tokens/--max-bytes as MCP clients ask for it
+    let limit = match Budget::default_budget() {
+        Some(Budget::Tokens(tokens)) => Some(("max_tokens", tokens)),
+        Some(Budget::Bytes(bytes)) => Some(("max_bytes", bytes)),
+        None => None,
+    };
+    if let Some((key, value)) = limit {
+        arguments.insert(key.to_string(), value.into());
+    }
+    let request = DaemonRequest::Tool {
+        tool: tool.to_string(),
+        arguments,
+    };
+
+    match daemon::send(index_path, &request)? {
+        Ok(DaemonReply::Tool {
+            is_error: false,
+            text,
+        }) => {
+            println!("{text}");
+            Some(ExitCode::Success)
+        }
+        Ok(DaemonReply::Tool {
+            is_error: true,
+            text,
+        }) => {
+            eprintln!("Error calling tool: {text}");
+            Some(ExitCode::GeneralError)
+        }
+        Ok(DaemonReply::Invalid { message }) => {
+            eprintln!("Error: the daemon rejected the call: {message}");
+            Some(ExitCode::GeneralError)
+        }
+        Ok(_) => {
+            eprintln!("Error: unexpected reply from the daemon");
+            Some(ExitCode::GeneralError)
+        }
+        // A daemon going away mid-call leaves the index free to load here
+        Err(e) => {
+            tracing::debug!("[daemon] call failed, loading the index instead: {e}");
+            None
+        }
+    }
+}
+
+fn status_of(index_path: &Path) -> Option<DaemonStatus> {
+    match daemon::send(index_path, &DaemonRequest::Status)? {
+        Ok(DaemonReply::Status(status)) => Some(status),
+        _ => None,
+    }
+}
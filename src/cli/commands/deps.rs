@@ -0,0 +1,139 @@
+//! Deps command - manage dependency indexes.
+
+use crate::cli::DepsAction;
+use crate::config::Settings;
+use crate::deps::stubs::{SyncReport, sync};
+use crate::deps::{
+    DependencyManifest, deps_dir, install_builtin, list_dependencies, remove_dependency,
+};
+use crate::io::{Envelope, ExitCode, OutputFormat};
+
+/// Run the deps command.
+pub fn run(action: DepsAction, config: &Settings) -> ExitCode {
+    let root = deps_dir(&config.index_path);
+    match action {
+        DepsAction::Add { name, json } => match install_builtin(&root, config, &name) {
+            Ok(manifest) => {
+                let format = OutputFormat::from_json_flag(json);
+                if format.is_structured() {
+                    let envelope = Envelope::success(&manifest)
+                        .with_count(manifest.symbol_count as usize)
+                        .with_message(format!("Installed {}", manifest.name));
+                    println!(
+                        "{}",
+                        envelope.render(format).expect("envelope serialization")
+                    );
+                } else {
+                    println!(
+                        "Installed {} ({} symbols)",
+                        manifest.name, manifest.symbol_count
+                    );
+                }
+                ExitCode::Success
+            }
+            Err(e) => {
+                eprintln!("Error: {e}");
+                e.exit_code()
+            }
+        },
+        DepsAction::Sync { json } => {
+            let workspace_root = match &config.workspace_root {
+                Some(root) => root.clone(),
+                None => std::env::current_dir().unwrap_or_default(),
+            };
+            let report = sync(&root, config, &workspace_root);
+            print_sync_report(&report, OutputFormat::from_json_flag(json));
+            if report.failed.is_empty() {
+                ExitCode::Success
+            } else {
+                ExitCode::GeneralError
+            }
+        }
+        DepsAction::List { json } => {
+            print_dependencies(
+                &list_dependencies(&root),
+                OutputFormat::from_json_flag(json),
+            );
+            ExitCode::Success
+        }
+        DepsAction::Remove { name } => match remove_dependency(&root, &name) {
+            Ok(()) => {
+                println!("Removed {name}");
+                ExitCode::Success
+            }
+            Err(e) => {
+                eprintln!("Error: {e}");
+                e.exit_code()
+            }
+        },
+    }
+}
+
+fn print_sync_report(report: &SyncReport, format: OutputFormat) {
+    if format.is_structured() {
+        let envelope = Envelope::success(report)
+            .with_count(report.indexed.len())
+            .with_message(format!(
+                "{} indexed, {} up to date, {} removed, {} failed",
+                report.indexed.len(),
+                report.cached.len(),
+                report.removed.len(),
+                report.failed.len()
+            ));
+        println!(
+            "{}",
+            envelope.render(format).expect("envelope serialization")
+        );
+        return;
+    }
+
+    for manifest in &report.indexed {
+        println!(
+            "Indexed {} {} ({} symbols)",
+            manifest.name,
+            manifest.version.as_deref().unwrap_or("-"),
+            manifest.symbol_count
+        );
+    }
+    for name in &report.removed {
+        println!("Removed {name}");
+    }
+    for (name, reason) in &report.failed {
+        eprintln!("Failed {name}: {reason}");
+    }
+    eprintln!(
+        "{} indexed, {} up to date, {} removed",
+        report.indexed.len(),
+        report.cached.len(),
+        report.removed.len()
+    );
+}
+
+fn print_dependencies(manifests: &[DependencyManifest], format: OutputFormat) {
+    if format.is_structured() {
+        let envelope = Envelope::success(manifests).with_count(manifests.len());
+        println!(
+            "{}",
+            envelope.render(format).expect("envelope serialization")
+        );
+        return;
+    }
+
+    if manifests.is_empty() {
+        eprintln!("No dependency indexes installed");
+        eprintln!("Add one with: codanna deps add nixpkgs-lib");
+        return;
+    }
+
+    for manifest in manifests {
+        let version = manifest
+            .version
+            .as_deref()
+            .map(|v| format!(" {v}"))
+            .unwrap_or_default();
+        println!(
+            "{}{version} [{}] {} symbols ({})",
+            manifest.name, manifest.language, manifest.symbol_count, manifest.source
+        );
+    }
+}
@@ -6,9 +6,9 @@ use std::time::Instant;
 use crate::cli::DocumentAction;
 use crate::config::Settings;
 use crate::documents::{CollectionConfig, DocumentStore, IndexProgress, SearchQuery};
-use crate::io::EnvelopeEntityType;
 use crate::io::envelope::{Envelope, ResultCode};
 use crate::io::status_line::StatusLine;
+use crate::io::{EnvelopeEntityType, OutputFormat};
 use crate::io::{ProgressBar, ProgressBarOptions, ProgressBarStyle};
 use crate::vector::{FastEmbedGenerator, VectorDimension};
 
@@ -72,6 +72,8 @@ pub fn run(action: DocumentAction, config: &Settings, cli_config: Option<&PathBu
         } => {
             use crate::io::args::parse_positional_args;
 
+            let format = OutputFormat::from_json_flag(json);
+
             // Parse positional arguments for query and key:value pairs
             let (positional_query, params) = parse_positional_args(&args);
 
@@ -125,7 +127,7 @@ pub fn run(action: DocumentAction, config: &Settings, cli_config: Option<&PathBu
                     let duration_ms = start.elapsed().as_millis() as u64;
                     let count = results.len();
 
-                    if json {
+                    if format.is_structured() {
                         let envelope = if results.is_empty() {
                             Envelope::<Vec<_>>::not_found("No documents matched the query")
                                 .with_query(&query_text)
@@ -144,12 +146,12 @@ pub fn run(action: DocumentAction, config: &Settings, cli_config: Option<&PathBu
                                 )
                         };
                         let output = if let Some(ref field_list) = fields {
-                            envelope.to_json_with_fields(field_list)
+                            envelope.render_with_fields(format, field_list)
                         } else {
-                            envelope.to_json()
+                            envelope.render(format)
                         };
                         match output {
-                            Ok(json) => println!("{json}"),
+                            Ok(rendered) => println!("{rendered}"),
                             Err(e) => {
                                 eprintln!("JSON serialization error: {e}");
                                 std::process::exit(2);
@@ -0,0 +1,30 @@
+//! Explore command - terminal UI over the code graph.
+
+use crate::indexing::facade::IndexFacade;
+use crate::io::ExitCode;
+
+/// Run the explore command.
+#[cfg(feature = "tui")]
+pub fn run(indexer: &IndexFacade) -> ExitCode {
+    use std::io::IsTerminal;
+
+    if !std::io::stdout().is_terminal() {
+        eprintln!("Error: explore needs an interactive terminal");
+        eprintln!("Suggestion: Use `codanna repl` or `codanna retrieve` when piping output");
+        return ExitCode::UnsupportedOperation;
+    }
+    match crate::tui::run(indexer) {
+        Ok(()) => ExitCode::Success,
+        Err(e) => {
+            eprintln!("Error: terminal UI failed: {e}");
+            ExitCode::IoError
+        }
+    }
+}
+
+#[cfg(not(feature = "tui"))]
+pub fn run(_indexer: &IndexFacade) -> ExitCode {
+    eprintln!("Error: The terminal UI is not available in this build");
+    eprintln!("Suggestion: Rebuild with `cargo install codanna --features tui`");
+    ExitCode::UnsupportedOperation
+}
@@ -0,0 +1,87 @@
+//! Export command - stream index data in machine-readable formats.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+use crate::cli::ExportAction;
+use crate::export::{ExportFormat, export_symbols_jsonl};
+use crate::indexing::facade::IndexFacade;
+use crate::io::ExitCode;
+
+/// Run the export command.
+pub fn run(action: ExportAction, indexer: &IndexFacade) -> ExitCode {
+    match action {
+        ExportAction::Symbols { format, output } => {
+            let format = match format.parse::<ExportFormat>() {
+                Ok(format) => format,
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    return ExitCode::ConfigError;
+                }
+            };
+
+            let writer: Box<dyn Write> = match &output {
+                Some(path) => match File::create(path) {
+                    Ok(file) => Box::new(BufWriter::new(file)),
+                    Err(e) => {
+                        eprintln!("Error: Failed to create {}: {e}", path.display());
+                        return ExitCode::IoError;
+                    }
+                },
+                None => Box::new(BufWriter::new(io::stdout().lock())),
+            };
+
+            let result = match format {
+                ExportFormat::Jsonl => export_symbols_jsonl(indexer.document_index(), writer),
+            };
+
+            match result {
+                Ok(count) => {
+                    if let Some(path) = output {
+                        eprintln!("Exported {count} symbols to {}", path.display());
+                    }
+                    ExitCode::Success
+                }
+                // Piping into `head` closes stdout early; that's not a failure
+                Err(crate::storage::StorageError::Io(e))
+                    if e.kind() == io::ErrorKind::BrokenPipe =>
+                {
+                    ExitCode::Success
+                }
+                Err(e) => {
+                    eprintln!("Error: Export failed: {e}");
+                    ExitCode::IoError
+                }
+            }
+        }
+
+        ExportAction::Sqlite { output } => run_sqlite(&output, indexer),
+    }
+}
+
+#[cfg(feature = "sqlite-export")]
+fn run_sqlite(output: &std::path::Path, indexer: &IndexFacade) -> ExitCode {
+    match crate::export::export_sqlite(indexer.document_index(), output) {
+        Ok(stats) => {
+            eprintln!(
+                "Exported {} symbols, {} relationships, {} files to {}",
+                stats.symbols,
+                stats.relationships,
+                stats.files,
+                output.display()
+            );
+            ExitCode::Success
+        }
+        Err(e) => {
+            eprintln!("Error: {e}");
+            ExitCode::IoError
+        }
+    }
+}
+
+#[cfg(not(feature = "sqlite-export"))]
+fn run_sqlite(_output: &std::path::Path, _indexer: &IndexFacade) -> ExitCode {
+    eprintln!("Error: SQLite export is not available in this build");
+    eprintln!("Suggestion: Rebuild with `cargo install codanna --features sqlite-export`");
+    ExitCode::UnsupportedOperation
+}
@@ -1,10 +1,19 @@
 //! Index command - index source code files and directories.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
+use crate::analysis::index_grpc_links;
 use crate::cli::commands::directories::{SkipReason, add_paths_to_settings};
 use crate::config::Settings;
+use crate::deps::deps_dir;
 use crate::indexing::facade::IndexFacade;
+use crate::indexing::pipeline::{UnresolvedRelationship, UnresolvedSink};
+use crate::indexing::shards::{SHARDS_DIR, check_capacity, shard_settings};
+use crate::indexing::{
+    IndexStats, ProgressEvent, ShardManifest, ShardSet, ShardSource, plan_shards,
+};
+use crate::io::ExitCode;
 use crate::storage::IndexPersistence;
 use crate::types::SymbolKind;
 
@@ -13,6 +22,8 @@ pub struct IndexArgs {
     pub paths: Vec<PathBuf>,
     pub force: bool,
     pub progress: bool,
+    /// Write newline-delimited JSON progress events to stderr
+    pub progress_json: bool,
     pub dry_run: bool,
     pub max_files: Option<usize>,
     pub cli_config: Option<PathBuf>,
@@ -33,11 +44,16 @@ pub fn run(
         paths,
         force,
         progress,
+        progress_json,
         dry_run,
         max_files,
         cli_config,
     } = args;
 
+    if progress_json {
+        indexer.set_progress_events(Some(Arc::new(print_event)));
+    }
+
     // Determine paths to index
     let paths_to_index = if !paths.is_empty() {
         // CLI paths provided - add them to settings.toml first
@@ -99,7 +115,7 @@ pub fn run(
                     // Sync added new directories, already indexed - save and return
                     if let Err(e) = persistence.save_facade(indexer) {
                         eprintln!("Error saving index: {e}");
-                        std::process::exit(1);
+                        std::process::exit(ExitCode::from_error(&e) as i32);
                     }
                     return;
                 }
@@ -115,28 +131,233 @@ pub fn run(
     };
 
     // Process each path, tracking total changes
-    let mut total_indexed = 0usize;
-    for path in &paths_to_index {
-        if path.is_file() {
-            if index_single_file(indexer, path, force) {
-                total_indexed += 1;
+    let mut totals = IndexStats::new();
+    if config.indexing.shard_by_directory {
+        index_shards(
+            indexer,
+            config,
+            &paths_to_index,
+            &mut totals,
+            ShardOptions {
+                progress,
+                progress_json,
+                dry_run,
+                force,
+                max_files,
+            },
+        );
+    } else {
+        for path in &paths_to_index {
+            if path.is_file() {
+                if index_single_file(indexer, path, force) {
+                    totals.files_indexed += 1;
+                }
+            } else if path.is_dir() {
+                let stats = index_directory(
+                    indexer,
+                    path,
+                    progress,
+                    progress_json,
+                    dry_run,
+                    force,
+                    max_files,
+                );
+                totals.files_indexed += stats.files_indexed;
+                totals.files_failed += stats.files_failed;
+                totals.symbols_found += stats.symbols_found;
+            } else {
+                eprintln!("Error: Path does not exist: {}", path.display());
+                std::process::exit(1);
             }
-        } else if path.is_dir() {
-            total_indexed += index_directory(indexer, path, progress, dry_run, force, max_files);
-        } else {
-            eprintln!("Error: Path does not exist: {}", path.display());
-            std::process::exit(1);
         }
     }
 
+    totals.stop_timing();
+    if progress_json {
+        print_event(&ProgressEvent::totals(&totals));
+    }
+
     // Only save if changes were made and not in dry-run mode
+    let total_indexed = totals.files_indexed;
     if !dry_run && total_indexed > 0 {
+        // Shards keep their own indexes, which proto links don't span yet
+        if !config.indexing.shard_by_directory {
+            link_grpc(indexer);
+        }
         save_index(indexer, persistence, config);
     } else if !dry_run && total_indexed == 0 {
         tracing::debug!(target: "indexing", "no changes detected, skipping save");
     }
 }
 
+/// Indexing options passed on to every shard
+struct ShardOptions {
+    progress: bool,
+    progress_json: bool,
+    dry_run: bool,
+    force: bool,
+    max_files: Option<usize>,
+}
+
+/// Index `paths` into one shard per top-level directory (see
+/// [`crate::indexing::shards`]). Each shard is loaded, updated and saved on
+/// its own, so only one is held in memory at a time; `indexer` just records
+/// the indexed paths. Relationships a shard leaves unresolved are resolved
+/// against all shards once every shard is saved.
+fn index_shards(
+    indexer: &mut IndexFacade,
+    config: &Settings,
+    paths: &[PathBuf],
+    totals: &mut IndexStats,
+    options: ShardOptions,
+) {
+    let index_base = indexer.index_base().to_path_buf();
+    let shards_dir = index_base.join(SHARDS_DIR);
+    let mut manifest = ShardManifest::load(&shards_dir)
+        .unwrap_or_else(|e| fail(e.to_string(), ExitCode::IndexCorrupted));
+
+    for path in paths {
+        if !path.exists() {
+            fail(
+                format!("Path does not exist: {}", path.display()),
+                ExitCode::GeneralError,
+            );
+        }
+        indexer.add_indexed_path(path);
+    }
+
+    // Leftover relationships of each updated shard, with its position
+    let mut leftovers = Vec::new();
+    for plan in plan_shards(paths) {
+        let position = manifest
+            .record(&plan)
+            .unwrap_or_else(|e| fail(e.to_string(), ExitCode::ConfigError));
+        let settings = Arc::new(shard_settings(config, &index_base, &plan.name));
+        let persistence = IndexPersistence::new(settings.index_path.clone());
+        let loaded = if persistence.exists() && !options.force {
+            persistence.load_facade(settings.clone())
+        } else {
+            IndexFacade::new(settings.clone())
+        };
+        let mut shard = loaded.unwrap_or_else(|e| {
+            fail(
+                format!("shard {}: {e}", plan.name),
+                ExitCode::from_error(&e),
+            )
+        });
+        if options.progress_json {
+            shard.set_progress_events(Some(Arc::new(print_event)));
+        }
+        shard.set_dependency_root(deps_dir(&index_base));
+        let unresolved: UnresolvedSink = Arc::default();
+        shard.set_unresolved_sink(Some(Arc::clone(&unresolved)));
+
+        let mut files_indexed = 0;
+        match &plan.source {
+            ShardSource::Directory(dir) => {
+                let stats = index_directory(
+                    &mut shard,
+                    dir,
+                    options.progress,
+                    options.progress_json,
+                    options.dry_run,
+                    options.force,
+                    options.max_files,
+                );
+                files_indexed += stats.files_indexed;
+                totals.files_failed += stats.files_failed;
+                totals.symbols_found += stats.symbols_found;
+            }
+            ShardSource::Files(files) => {
+                for file in files {
+                    if !options.dry_run && index_single_file(&mut shard, file, options.force) {
+                        files_indexed += 1;
+                    }
+                }
+            }
+        }
+        totals.files_indexed += files_indexed;
+
+        // Ids past the shard's share of the federated id space would alias
+        // symbols of other shards
+        if let Err(e) = check_capacity(shard.document_index()) {
+            fail(format!("shard {}: {e}", plan.name), ExitCode::ConfigError);
+        }
+
+        if !options.dry_run && files_indexed > 0 {
+            if let Err(e) = persistence.save_facade(&shard) {
+                fail(
+                    format!("Could not save shard {}: {e}", plan.name),
+                    ExitCode::from_error(&e),
+                );
+            }
+            let unresolved =
+                std::mem::take(&mut *unresolved.lock().unwrap_or_else(|e| e.into_inner()));
+            if !unresolved.is_empty() {
+                leftovers.push((plan.name, position, unresolved));
+            }
+        }
+    }
+
+    if !options.dry_run {
+        if let Err(e) = manifest.save(&shards_dir) {
+            fail(e.to_string(), ExitCode::IoError);
+        }
+        if manifest.shards.len() > 1 && !leftovers.is_empty() {
+            resolve_across_shards(config, &index_base, leftovers);
+        }
+    }
+}
+
+/// Resolve the relationships shards left unresolved against the symbols of
+/// all shards, storing those that resolve in the shard they start from.
+fn resolve_across_shards(
+    config: &Settings,
+    index_base: &Path,
+    leftovers: Vec<(String, usize, Vec<UnresolvedRelationship>)>,
+) {
+    let shards = ShardSet::open(index_base, config);
+    for (name, position, unresolved) in leftovers {
+        let settings = Arc::new(shard_settings(config, index_base, &name));
+        let persistence = IndexPersistence::new(settings.index_path.clone());
+        let mut shard = persistence
+            .load_facade(settings)
+            .unwrap_or_else(|e| fail(format!("shard {name}: {e}"), ExitCode::from_error(&e)));
+        shard.set_foreign_symbols(Some(Arc::new(shards.foreign_symbols(position))));
+        match shard.resolve_relationships(unresolved) {
+            Ok(stats) => {
+                let resolved = stats.calls_resolved + stats.other_resolved;
+                tracing::debug!(
+                    target: "indexing",
+                    "shard {name}: {resolved} relationships resolved across shards"
+                );
+                if resolved > 0 {
+                    if let Err(e) = persistence.save_facade(&shard) {
+                        fail(
+                            format!("Could not save shard {name}: {e}"),
+                            ExitCode::from_error(&e),
+                        );
+                    }
+                }
+            }
+            Err(e) => fail(format!("shard {name}: {e}"), ExitCode::from_error(&e)),
+        }
+    }
+}
+
+/// Print `message` and exit with `code`.
+fn fail(message: String, code: ExitCode) -> ! {
+    eprintln!("Error: {message}");
+    std::process::exit(code as i32);
+}
+
+/// Write `event` to stderr as one line of JSON.
+fn print_event(event: &ProgressEvent) {
+    if let Ok(line) = serde_json::to_string(event) {
+        eprintln!("{line}");
+    }
+}
+
 /// Index a single file. Returns true if file was indexed (not cached).
 fn index_single_file(indexer: &mut IndexFacade, path: &PathBuf, force: bool) -> bool {
     match indexer.index_file_with_force(path, force) {
@@ -211,25 +432,31 @@ fn index_single_file(indexer: &mut IndexFacade, path: &PathBuf, force: bool) ->
                 }
             }
 
-            std::process::exit(1);
+            std::process::exit(ExitCode::from_error(&e) as i32);
         }
     }
 }
 
-/// Index a directory. Returns the number of files indexed.
+/// Index a directory. Returns the directory's indexing stats.
+///
+/// With `progress_json`, stderr carries only the JSON events, so the
+/// human-readable status lines are left out.
 fn index_directory(
     indexer: &mut IndexFacade,
     path: &PathBuf,
     progress: bool,
+    progress_json: bool,
     dry_run: bool,
     force: bool,
     max_files: Option<usize>,
-) -> usize {
+) -> IndexStats {
     // Visual separator between directory cycles (use stderr to sync with progress bars)
-    eprintln!();
+    if !progress_json {
+        eprintln!();
+    }
 
     // Show pre-indexing message only if we have a file limit (implies actual work)
-    if let Some(max) = max_files {
+    if let (Some(max), false) = (max_files, progress_json) {
         eprintln!(
             "Indexing directory: {} (limited to {} files)",
             path.display(),
@@ -243,10 +470,10 @@ fn index_directory(
     match indexer.index_directory_with_options(path, progress, dry_run, force, max_files) {
         Ok(stats) => {
             // Print message only when no files need indexing (pipeline trace handles the rest)
-            if stats.files_indexed == 0 {
+            if stats.files_indexed == 0 && !progress_json {
                 eprintln!("Index up to date: {}", path.display());
             }
-            stats.files_indexed
+            stats
         }
         Err(e) => {
             eprintln!("Error indexing directory {}: {e}", path.display());
@@ -259,11 +486,21 @@ fn index_directory(
                 }
             }
 
-            std::process::exit(1);
+            std::process::exit(ExitCode::from_error(&e) as i32);
         }
     }
 }
 
+/// Store the workspace's `.proto` definitions and link the code using them
+fn link_grpc(indexer: &IndexFacade) {
+    match index_grpc_links(indexer) {
+        Ok(linked) => {
+            tracing::debug!(target: "indexing", "linked {linked} sites to proto definitions")
+        }
+        Err(e) => eprintln!("Warning: could not link proto definitions: {e}"),
+    }
+}
+
 fn save_index(indexer: &mut IndexFacade, persistence: &IndexPersistence, config: &Settings) {
     // Save the index
     eprintln!(
@@ -277,7 +514,7 @@ fn save_index(indexer: &mut IndexFacade, persistence: &IndexPersistence, config:
         }
         Err(e) => {
             eprintln!("Error: Could not save index: {e}");
-            std::process::exit(1);
+            std::process::exit(ExitCode::from_error(&e) as i32);
         }
     }
 }
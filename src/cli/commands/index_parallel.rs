@@ -77,7 +77,8 @@ pub fn run(args: IndexParallelArgs, settings: &Settings) {
     // Create pipeline
     let settings_arc = Arc::new(settings.clone());
     let config = PipelineConfig::from_settings(&settings_arc);
-    let pipeline = Pipeline::new(Arc::clone(&settings_arc), config);
+    let mut pipeline = Pipeline::new(Arc::clone(&settings_arc), config);
+    pipeline.set_dependency_root(Some(crate::deps::deps_dir(&settings.index_path)));
 
     let mode = if force { "force" } else { "incremental" };
     tracing::debug!(
@@ -133,7 +134,7 @@ fn create_semantic_search(
 
     // Try to load existing embeddings first
     if semantic_path.exists() {
-        match SimpleSemanticSearch::load(semantic_path) {
+        match SimpleSemanticSearch::load_with_config(semantic_path, &settings.semantic_search) {
             Ok(semantic) => {
                 tracing::debug!(target: "pipeline", "Loaded existing embeddings from {}", semantic_path.display());
                 return Some(Arc::new(Mutex::new(semantic)));
@@ -145,7 +146,7 @@ fn create_semantic_search(
     }
 
     // Create new semantic search instance
-    match SimpleSemanticSearch::from_model_name(model) {
+    match SimpleSemanticSearch::from_config(&settings.semantic_search) {
         Ok(semantic) => {
             tracing::debug!(target: "pipeline", "Created new semantic search with model: {model}");
             Some(Arc::new(Mutex::new(semantic)))
@@ -0,0 +1,67 @@
+//! Index verify command - check and repair index integrity.
+
+use crate::config::Settings;
+use crate::io::{ExitCode, OutputFormat, OutputManager};
+use crate::storage::{DocumentIndex, IndexMetadata, verify_index};
+
+/// Run the index verify command.
+///
+/// Opens the Tantivy index directly (no facade, no providers), reports
+/// inconsistencies, and with `repair` prunes what can be pruned. Exits with
+/// `IndexCorrupted` when problems remain.
+pub fn run(repair: bool, json: bool, config: &Settings) -> ExitCode {
+    let format = OutputFormat::from_json_flag(json);
+    let mut output = OutputManager::new(format);
+
+    let tantivy_path = config.index_path.join("tantivy");
+    if !tantivy_path.join("meta.json").exists() {
+        eprintln!(
+            "Error: No index found at {}. Run 'codanna index' first.",
+            config.index_path.display()
+        );
+        return ExitCode::NotFound;
+    }
+
+    let index = match DocumentIndex::new(&tantivy_path, config) {
+        Ok(index) => index,
+        Err(e) => {
+            let error = crate::IndexError::from(e);
+            return output
+                .error(&error)
+                .unwrap_or_else(|_| ExitCode::from_error(&error));
+        }
+    };
+
+    let report = match verify_index(&index, config.workspace_root.as_deref(), repair) {
+        Ok(report) => report,
+        Err(e) => {
+            let error = crate::IndexError::from(e);
+            return output
+                .error(&error)
+                .unwrap_or_else(|_| ExitCode::from_error(&error));
+        }
+    };
+
+    // Keep cached counts in index.meta in line with what repair removed
+    if report.repair.is_some() {
+        if let Ok(mut metadata) = IndexMetadata::load(&config.index_path) {
+            let symbols = index.count_symbols().unwrap_or(0) as u32;
+            let files = index.count_files().unwrap_or(0) as u32;
+            metadata.update_counts(symbols, files);
+            if let Err(e) = metadata.save(&config.index_path) {
+                tracing::warn!(target: "cli", "failed to update index metadata after repair: {e}");
+            }
+        }
+    }
+
+    let unresolved = report.has_unresolved_issues();
+    if let Err(e) = output.success(report) {
+        tracing::debug!(target: "cli", "failed to write verify report: {e}");
+    }
+
+    if unresolved {
+        ExitCode::IndexCorrupted
+    } else {
+        ExitCode::Success
+    }
+}
@@ -113,18 +113,15 @@ impl From<crate::storage::tantivy::SearchResult> for SearchSymbolResult {
     }
 }
 
-/// Run the MCP direct tool invocation command.
-pub async fn run(
-    tool: String,
-    positional: Vec<String>,
-    args: Option<String>,
-    json: bool,
-    fields: Option<Vec<String>>,
-    facade: IndexFacade,
-    config: &Settings,
-) {
+/// Tool arguments from positional `key:value` pairs and the `--args` JSON
+/// object, `None` when there are none. Exits on malformed `--args`.
+pub fn tool_arguments(
+    tool: &str,
+    positional: &[String],
+    args: Option<&str>,
+) -> Option<serde_json::Map<String, serde_json::Value>> {
     // Build arguments from both positional and --args
-    let mut arguments = if let Some(args_str) = &args {
+    let mut arguments = if let Some(args_str) = args {
         // Parse JSON arguments if provided (backward compatibility)
         match serde_json::from_str::<serde_json::Value>(args_str) {
             Ok(serde_json::Value::Object(map)) => Some(map),
@@ -146,11 +143,11 @@ pub async fn run(
     if !positional.is_empty() {
         if let Some(ref mut args_map) = arguments {
             // Use the unified parser from args.rs
-            let (first_positional, params) = parse_positional_args(&positional);
+            let (first_positional, params) = parse_positional_args(positional);
 
             // Handle the first positional argument based on tool type
             if let Some(pos_arg) = first_positional {
-                match tool.as_str() {
+                match tool {
                     "find_symbol" => {
                         args_map.insert(
                             "name".to_string(),
@@ -221,7 +218,20 @@ pub async fn run(
     }
 
     // Convert to Option<Map> only if we have arguments
-    let arguments = arguments.filter(|map| !map.is_empty());
+    arguments.filter(|map| !map.is_empty())
+}
+
+/// Run the MCP direct tool invocation command.
+pub async fn run(
+    tool: String,
+    positional: Vec<String>,
+    args: Option<String>,
+    json: bool,
+    fields: Option<Vec<String>>,
+    facade: IndexFacade,
+    config: &Settings,
+) {
+    let arguments = tool_arguments(&tool, &positional, args.as_deref());
 
     // Collect data for find_symbol if JSON output is requested
     let find_symbol_data = if json && tool == "find_symbol" {
@@ -405,7 +415,7 @@ pub async fn run(
             use std::collections::HashSet;
 
             // Find all functions with this name
-            let symbols = facade.find_symbols_by_name(func_name, language);
+            let symbols = facade.find_symbol_refs_by_name(func_name, language);
             if !symbols.is_empty() {
                 let mut all_callers = Vec::new();
                 let mut seen_ids = HashSet::new();
@@ -479,7 +489,7 @@ pub async fn run(
             use std::collections::HashSet;
 
             // Find ALL symbols with this name (same as MCP handler)
-            let symbols = facade.find_symbols_by_name(sym_name, language);
+            let symbols = facade.find_symbol_refs_by_name(sym_name, language);
 
             if symbols.is_empty() {
                 None // Symbol not found
@@ -881,10 +891,17 @@ pub async fn run(
                 std::process::exit(1);
             }
 
+            let min_confidence = arguments
+                .as_ref()
+                .and_then(|m| m.get("min_confidence"))
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+
             server
                 .get_calls(Parameters(GetCallsRequest {
                     function_name,
                     symbol_id,
+                    min_confidence,
                 }))
                 .await
         }
@@ -909,10 +926,17 @@ pub async fn run(
                 std::process::exit(1);
             }
 
+            let min_confidence = arguments
+                .as_ref()
+                .and_then(|m| m.get("min_confidence"))
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+
             server
                 .find_callers(Parameters(FindCallersRequest {
                     function_name,
                     symbol_id,
+                    min_confidence,
                 }))
                 .await
         }
@@ -942,11 +966,17 @@ pub async fn run(
                 .and_then(|m| m.get("max_depth"))
                 .and_then(|v| v.as_u64())
                 .unwrap_or(3) as u32;
+            let unconditional_only = arguments
+                .as_ref()
+                .and_then(|m| m.get("unconditional_only"))
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
             server
                 .analyze_impact(Parameters(AnalyzeImpactRequest {
                     symbol_name,
                     symbol_id,
                     max_depth,
+                    unconditional_only,
                 }))
                 .await
         }
@@ -986,6 +1016,16 @@ pub async fn run(
                 .and_then(|m| m.get("lang"))
                 .and_then(|v| v.as_str())
                 .map(|s| s.to_string());
+            let context_file = arguments
+                .as_ref()
+                .and_then(|m| m.get("context_file"))
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+            let substring = arguments
+                .as_ref()
+                .and_then(|m| m.get("substring"))
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
             server
                 .search_symbols(Parameters(SearchSymbolsRequest {
                     query: query.to_string(),
@@ -993,6 +1033,8 @@ pub async fn run(
                     kind,
                     module,
                     lang,
+                    context_file,
+                    substring,
                 }))
                 .await
         }
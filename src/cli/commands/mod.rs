@@ -3,15 +3,25 @@
 //! Each command is implemented in its own module.
 //! Commands are progressively migrated from main.rs.
 
+pub mod analyze;
+pub mod artifact;
 pub mod benchmark;
+pub mod completions;
+pub mod daemon;
+pub mod deps;
 pub mod directories;
 pub mod documents;
+pub mod explore;
+pub mod export;
 pub mod index;
 pub mod index_parallel;
+pub mod index_verify;
 pub mod init;
 pub mod mcp;
 pub mod parse;
 pub mod plugin;
 pub mod profile;
+pub mod repl;
 pub mod retrieve;
 pub mod serve;
+pub mod stats;
@@ -0,0 +1,380 @@
+//! Repl command - interactive shell over the index.
+//!
+//! Each line is a command (`def`, `refs`, `calls`, `describe`, `query`) or a
+//! query in the saved query syntax, e.g. `parse kind:function lang:rust`.
+//! Results print as `codanna retrieve` prints them. History is kept next to
+//! the index, and Tab completes commands and symbol names.
+
+use crate::indexing::facade::IndexFacade;
+use crate::io::args::parse_positional_args;
+use crate::io::{ExitCode, OutputFormat};
+use crate::retrieve;
+use console::{Key, Term};
+use std::collections::BTreeSet;
+use std::io::{BufRead, IsTerminal};
+use std::path::PathBuf;
+
+const PROMPT: &str = "codanna> ";
+const MAX_HISTORY: usize = 1000;
+/// Completion candidates listed on a second Tab
+const MAX_LISTED: usize = 40;
+
+/// Commands with their usage and description
+const COMMANDS: &[(&str, &str, &str)] = &[
+    (
+        "def",
+        "<name> [lang:<language>]",
+        "Where a symbol is defined",
+    ),
+    (
+        "refs",
+        "<name> [lang:<language>]",
+        "Functions calling a symbol",
+    ),
+    (
+        "calls",
+        "<name> [lang:<language>]",
+        "Functions a symbol calls",
+    ),
+    (
+        "describe",
+        "<name> [lang:<language>]",
+        "Signature, docs, and relationships",
+    ),
+    (
+        "query",
+        "[<saved query> [key:value ...]]",
+        "Run a saved query, or list them",
+    ),
+    (
+        ":format",
+        "<text|json|yaml|markdown>",
+        "Switch the output format",
+    ),
+    ("help", "", "Show this help"),
+    ("exit", "", "Leave the shell (or Ctrl-D)"),
+];
+
+/// Run the interactive shell until `exit` or end of input.
+pub fn run(indexer: &IndexFacade) -> ExitCode {
+    let history_path = indexer.settings().index_path.with_file_name("repl_history");
+    let mut editor = LineEditor::new(symbol_names(indexer), history_path);
+    let mut format = OutputFormat::from_json_flag(false);
+
+    if editor.interactive {
+        eprintln!("codanna interactive shell. Type `help` for commands, Tab to complete.");
+    }
+    loop {
+        let line = match editor.read_line(PROMPT) {
+            Ok(Some(line)) => line,
+            Ok(None) => break,
+            Err(e) => {
+                eprintln!("Error: failed to read input: {e}");
+                editor.save_history();
+                return ExitCode::GeneralError;
+            }
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        editor.add_history(line);
+        if !execute(indexer, line, &mut format) {
+            break;
+        }
+    }
+    editor.save_history();
+    ExitCode::Success
+}
+
+/// Run one line, returning false when the shell should exit
+fn execute(indexer: &IndexFacade, line: &str, format: &mut OutputFormat) -> bool {
+    let (command, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let args: Vec<String> = rest.split_whitespace().map(str::to_string).collect();
+    let fmt = *format;
+
+    match command {
+        "exit" | "quit" | ":q" => return false,
+        "help" | "?" => print_help(),
+        ":format" => match OutputFormat::parse(args.first().map(String::as_str), None) {
+            Ok(parsed) => *format = parsed,
+            Err(e) => eprintln!("Error: {e}"),
+        },
+        "def" | "refs" | "calls" | "describe" => {
+            let (name, params) = parse_positional_args(&args);
+            let Some(name) =
+                name.or_else(|| params.get("symbol_id").map(|id| format!("symbol_id:{id}")))
+            else {
+                eprintln!("Usage: {command} <name> [lang:<language>]");
+                return true;
+            };
+            let language = params.get("lang").map(String::as_str);
+            let _ = match command {
+                "def" => retrieve::retrieve_symbol(indexer, &name, language, fmt, None),
+                "refs" => retrieve::retrieve_callers(indexer, &name, language, fmt, None),
+                "calls" => retrieve::retrieve_calls(indexer, &name, language, fmt, None),
+                _ => retrieve::retrieve_describe(indexer, &name, language, fmt, None),
+            };
+        }
+        "query" => {
+            let (name, params) = parse_positional_args(&args);
+            let _ = match name {
+                Some(name) => retrieve::retrieve_saved_query(indexer, &name, &params, fmt, None),
+                None => retrieve::list_saved_queries(indexer.settings(), fmt),
+            };
+        }
+        _ => {
+            let _ = retrieve::retrieve_query(indexer, line, fmt, None);
+        }
+    }
+    true
+}
+
+fn print_help() {
+    eprintln!("Commands:");
+    for (name, usage, about) in COMMANDS {
+        let usage = format!("{name} {usage}");
+        eprintln!("  {usage:<42} {about}");
+    }
+    eprintln!();
+    eprintln!("Anything else runs as a query, e.g.");
+    eprintln!("  parse kind:function lang:rust limit:10");
+    eprintln!("  kind:struct module:crate::io");
+}
+
+/// Distinct symbol names in the index, sorted
+fn symbol_names(indexer: &IndexFacade) -> Vec<String> {
+    let mut names = BTreeSet::new();
+    let _ = indexer.document_index().for_each_symbol(|symbol| {
+        if !names.contains(symbol.name.as_ref()) {
+            names.insert(symbol.name.to_string());
+        }
+        Ok(())
+    });
+    names.into_iter().collect()
+}
+
+/// Single-line editor with history and completion.
+///
+/// Falls back to plain line reads when stdin or stdout is not a terminal,
+/// so scripts can be piped in.
+struct LineEditor {
+    term: Term,
+    interactive: bool,
+    history: Vec<String>,
+    history_path: PathBuf,
+    /// Sorted command names
+    commands: Vec<String>,
+    /// Sorted symbol names
+    symbols: Vec<String>,
+}
+
+impl LineEditor {
+    fn new(symbols: Vec<String>, history_path: PathBuf) -> Self {
+        let term = Term::stdout();
+        let interactive = term.is_term() && std::io::stdin().is_terminal();
+        let history = std::fs::read_to_string(&history_path)
+            .map(|text| text.lines().map(str::to_string).collect())
+            .unwrap_or_default();
+        let mut commands: Vec<String> = COMMANDS
+            .iter()
+            .map(|(name, _, _)| name.to_string())
+            .collect();
+        commands.sort();
+        Self {
+            term,
+            interactive,
+            history,
+            history_path,
+            commands,
+            symbols,
+        }
+    }
+
+    fn add_history(&mut self, line: &str) {
+        if self.history.last().map(String::as_str) != Some(line) {
+            self.history.push(line.to_string());
+        }
+    }
+
+    /// Write the history back, unless input was piped
+    fn save_history(&self) {
+        if !self.interactive {
+            return;
+        }
+        let start = self.history.len().saturating_sub(MAX_HISTORY);
+        let mut text = self.history[start..].join("\n");
+        text.push('\n');
+        if let Err(e) = std::fs::write(&self.history_path, text) {
+            tracing::debug!(target: "cli", "failed to save repl history: {e}");
+        }
+    }
+
+    /// The next line, or `None` at end of input
+    fn read_line(&mut self, prompt: &str) -> std::io::Result<Option<String>> {
+        if !self.interactive {
+            let mut line = String::new();
+            let read = std::io::stdin().lock().read_line(&mut line)?;
+            return Ok((read > 0).then_some(line));
+        }
+
+        let mut buffer: Vec<char> = Vec::new();
+        let mut cursor = 0;
+        // Position in history; `history.len()` is the line being typed
+        let mut recall = self.history.len();
+        let mut draft = Vec::new();
+        let mut last_was_tab = false;
+        self.redraw(prompt, &buffer, cursor)?;
+
+        loop {
+            let key = self.term.read_key()?;
+            let is_tab = key == Key::Tab;
+            match key {
+                Key::Enter => {
+                    self.term.write_line("")?;
+                    return Ok(Some(buffer.into_iter().collect()));
+                }
+                // Ctrl-D on an empty line
+                Key::Char('\u{4}') if buffer.is_empty() => {
+                    self.term.write_line("")?;
+                    return Ok(None);
+                }
+                Key::CtrlC => {
+                    self.term.write_line("^C")?;
+                    buffer.clear();
+                    cursor = 0;
+                    recall = self.history.len();
+                }
+                // Ctrl-A and Ctrl-E
+                Key::Home | Key::Char('\u{1}') => cursor = 0,
+                Key::End | Key::Char('\u{5}') => cursor = buffer.len(),
+                Key::Char(c) if !c.is_control() => {
+                    buffer.insert(cursor, c);
+                    cursor += 1;
+                }
+                Key::Backspace if cursor > 0 => {
+                    cursor -= 1;
+                    buffer.remove(cursor);
+                }
+                Key::Del if cursor < buffer.len() => {
+                    buffer.remove(cursor);
+                }
+                Key::ArrowLeft => cursor = cursor.saturating_sub(1),
+                Key::ArrowRight => cursor = (cursor + 1).min(buffer.len()),
+                Key::ArrowUp if recall > 0 => {
+                    if recall == self.history.len() {
+                        draft = buffer.clone();
+                    }
+                    recall -= 1;
+                    buffer = self.history[recall].chars().collect();
+                    cursor = buffer.len();
+                }
+                Key::ArrowDown if recall < self.history.len() => {
+                    recall += 1;
+                    buffer = match self.history.get(recall) {
+                        Some(line) => line.chars().collect(),
+                        None => draft.clone(),
+                    };
+                    cursor = buffer.len();
+                }
+                Key::Tab => {
+                    let before: String = buffer[..cursor].iter().collect();
+                    let (insert, candidates) = complete(&before, &self.commands, &self.symbols);
+                    if !insert.is_empty() {
+                        for c in insert.chars() {
+                            buffer.insert(cursor, c);
+                            cursor += 1;
+                        }
+                    } else if last_was_tab && candidates.len() > 1 {
+                        self.term.write_line("")?;
+                        self.list(candidates)?;
+                    }
+                }
+                _ => {}
+            }
+            last_was_tab = is_tab;
+            self.redraw(prompt, &buffer, cursor)?;
+        }
+    }
+
+    fn redraw(&self, prompt: &str, buffer: &[char], cursor: usize) -> std::io::Result<()> {
+        let line: String = buffer.iter().collect();
+        self.term.clear_line()?;
+        self.term.write_str(&format!("{prompt}{line}"))?;
+        self.term.move_cursor_left(buffer.len() - cursor)?;
+        self.term.flush()
+    }
+
+    fn list(&self, candidates: &[String]) -> std::io::Result<()> {
+        for candidate in candidates.iter().take(MAX_LISTED) {
+            self.term.write_line(candidate)?;
+        }
+        if candidates.len() > MAX_LISTED {
+            self.term
+                .write_line(&format!("... and {} more", candidates.len() - MAX_LISTED))?;
+        }
+        Ok(())
+    }
+}
+
+/// Completion of the word before the cursor: the text to insert and every
+/// candidate. The first word completes against `commands`, later words
+/// against `symbols`; both must be sorted.
+fn complete<'a>(
+    before: &str,
+    commands: &'a [String],
+    symbols: &'a [String],
+) -> (String, &'a [String]) {
+    let prefix = before
+        .rsplit(char::is_whitespace)
+        .next()
+        .unwrap_or_default();
+    let first_word = !before.trim_start().contains(char::is_whitespace);
+    let words = if first_word { commands } else { symbols };
+    // Completing every symbol name from nothing is never useful
+    if prefix.is_empty() && !first_word {
+        return (String::new(), &[]);
+    }
+
+    let from = words.partition_point(|word| word.as_str() < prefix);
+    let len = words[from..].partition_point(|word| word.starts_with(prefix));
+    let candidates = &words[from..from + len];
+    let insert = match candidates {
+        [] => String::new(),
+        [only] => format!("{} ", &only[prefix.len()..]),
+        [first, rest @ ..] => {
+            let common = rest.iter().fold(first.as_str(), |common, word| {
+                let len = common
+                    .char_indices()
+                    .zip(word.chars())
+                    .find(|((_, a), b)| a != b)
+                    .map(|((i, _), _)| i)
+                    .unwrap_or(common.len().min(word.len()));
+                &common[..len]
+            });
+            common[prefix.len()..].to_string()
+        }
+    };
+    (insert, candidates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_complete_extends_to_common_prefix() {
+        let commands: Vec<String> = ["calls", "def", "describe"].map(String::from).to_vec();
+        let symbols: Vec<String> = ["parse", "parse_file", "parse_files", "print"]
+            .map(String::from)
+            .to_vec();
+
+        assert_eq!(complete("ca", &commands, &symbols).0, "lls ");
+        assert_eq!(complete("d", &commands, &symbols).0, "e");
+        assert_eq!(complete("def parse_", &commands, &symbols).0, "file");
+        let (insert, candidates) = complete("def pa", &commands, &symbols);
+        assert_eq!(insert, "rse");
+        assert_eq!(candidates.len(), 3);
+        assert_eq!(complete("def ", &commands, &symbols).1.len(), 0);
+    }
+}
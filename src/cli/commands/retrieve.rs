@@ -4,6 +4,7 @@ use crate::cli::RetrieveQuery;
 use crate::indexing::facade::IndexFacade;
 use crate::io::ExitCode;
 use crate::io::OutputFormat;
+use crate::navigation::CallDirection;
 use crate::retrieve;
 
 /// Run the retrieve command.
@@ -103,12 +104,180 @@ pub fn run(query: RetrieveQuery, indexer: &IndexFacade) -> ExitCode {
             let format = OutputFormat::from_json_flag(json);
             retrieve::retrieve_implementations(indexer, &final_trait, language, format, fields)
         }
+        RetrieveQuery::TypeDefinition { args, json, fields } => {
+            use crate::io::args::parse_positional_args;
+
+            let (positional_name, params) = parse_positional_args(&args);
+
+            let final_name = positional_name
+                .or_else(|| params.get("name").cloned())
+                .or_else(|| params.get("symbol_id").map(|id| format!("symbol_id:{id}")))
+                .unwrap_or_else(|| {
+                    eprintln!("Error: type-definition requires a symbol name or symbol_id");
+                    eprintln!("Usage: codanna retrieve type-definition config");
+                    eprintln!("   or: codanna retrieve type-definition symbol_id:1771");
+                    std::process::exit(1);
+                });
+
+            let language = params.get("lang").map(|s| s.as_str());
+
+            let format = OutputFormat::from_json_flag(json);
+            retrieve::retrieve_type_definition(indexer, &final_name, language, format, fields)
+        }
+        RetrieveQuery::CallHierarchy {
+            args,
+            encoding,
+            json,
+            fields,
+        } => {
+            use crate::io::args::parse_positional_args;
+
+            let (positional_function, params) = parse_positional_args(&args);
+
+            let final_function = positional_function
+                .or_else(|| params.get("function").cloned())
+                .or_else(|| params.get("symbol_id").map(|id| format!("symbol_id:{id}")))
+                .unwrap_or_else(|| {
+                    eprintln!("Error: call-hierarchy requires a function name or symbol_id");
+                    eprintln!("Usage: codanna retrieve call-hierarchy process_file");
+                    eprintln!(
+                        "   or: codanna retrieve call-hierarchy symbol_id:1771 direction:outgoing"
+                    );
+                    std::process::exit(1);
+                });
+
+            let direction = match params.get("direction").map(|d| d.parse()) {
+                None => CallDirection::default(),
+                Some(Ok(direction)) => direction,
+                Some(Err(e)) => {
+                    eprintln!("Error: {e}");
+                    std::process::exit(1);
+                }
+            };
+            let depth = params
+                .get("depth")
+                .and_then(|d| d.parse().ok())
+                .unwrap_or(3);
+
+            let language = params.get("lang").map(|s| s.as_str());
+
+            let format = OutputFormat::from_json_flag(json);
+            retrieve::retrieve_call_hierarchy(
+                indexer,
+                &final_function,
+                direction,
+                depth,
+                language,
+                encoding,
+                format,
+                fields,
+            )
+        }
+        RetrieveQuery::TypeHierarchy {
+            args,
+            encoding,
+            json,
+            fields,
+        } => {
+            use crate::io::args::parse_positional_args;
+
+            let (positional_name, params) = parse_positional_args(&args);
+
+            let final_name = positional_name
+                .or_else(|| params.get("type").cloned())
+                .or_else(|| params.get("symbol_id").map(|id| format!("symbol_id:{id}")))
+                .unwrap_or_else(|| {
+                    eprintln!("Error: type-hierarchy requires a type name or symbol_id");
+                    eprintln!("Usage: codanna retrieve type-hierarchy Parser");
+                    eprintln!("   or: codanna retrieve type-hierarchy symbol_id:1771 depth:2");
+                    std::process::exit(1);
+                });
+
+            let depth = params
+                .get("depth")
+                .and_then(|d| d.parse().ok())
+                .unwrap_or(3);
+
+            let language = params.get("lang").map(|s| s.as_str());
+
+            let format = OutputFormat::from_json_flag(json);
+            retrieve::retrieve_type_hierarchy(
+                indexer,
+                &final_name,
+                depth,
+                language,
+                encoding,
+                format,
+                fields,
+            )
+        }
+        RetrieveQuery::History { args, json, fields } => {
+            use crate::io::args::parse_positional_args;
+
+            let (positional_name, params) = parse_positional_args(&args);
+
+            let final_name = positional_name
+                .or_else(|| params.get("symbol").cloned())
+                .or_else(|| params.get("symbol_id").map(|id| format!("symbol_id:{id}")))
+                .unwrap_or_else(|| {
+                    eprintln!("Error: history requires a symbol name or symbol_id");
+                    eprintln!("Usage: codanna retrieve history parse_config");
+                    eprintln!("   or: codanna retrieve history symbol_id:1771 limit:5");
+                    std::process::exit(1);
+                });
+
+            let limit = params.get("limit").and_then(|l| l.parse().ok());
+            let language = params.get("lang").map(|s| s.as_str());
+
+            let format = OutputFormat::from_json_flag(json);
+            retrieve::retrieve_history(indexer, &final_name, limit, language, format, fields)
+        }
+        RetrieveQuery::Outline {
+            file,
+            encoding,
+            json,
+            fields,
+        } => {
+            let format = OutputFormat::from_json_flag(json);
+            retrieve::retrieve_outline(indexer, &file, encoding, format, fields)
+        }
+        RetrieveQuery::SymbolAt {
+            file,
+            line,
+            column,
+            encoding,
+            json,
+            fields,
+        } => {
+            let format = OutputFormat::from_json_flag(json);
+            retrieve::retrieve_symbol_at(indexer, &file, line, column, encoding, format, fields)
+        }
+        RetrieveQuery::Grep {
+            pattern,
+            limit,
+            ignore_case,
+            encoding,
+            json,
+            fields,
+        } => {
+            let format = OutputFormat::from_json_flag(json);
+            retrieve::retrieve_grep(
+                indexer,
+                &pattern,
+                limit,
+                ignore_case,
+                encoding,
+                format,
+                fields,
+            )
+        }
         RetrieveQuery::Search {
             args,
             limit,
             json,
             kind,
             module,
+            substring,
             fields,
         } => {
             use crate::io::args::parse_positional_args;
@@ -139,6 +308,9 @@ pub fn run(query: RetrieveQuery, indexer: &IndexFacade) -> ExitCode {
 
             // Extract language filter
             let language = params.get("lang").map(|s| s.as_str());
+            let context_file = params.get("context_file").map(|s| s.as_str());
+            let substring =
+                substring || params.get("match").is_some_and(|mode| mode == "substring");
 
             // Call retrieve function with merged parameters
             let format = OutputFormat::from_json_flag(json);
@@ -149,6 +321,8 @@ pub fn run(query: RetrieveQuery, indexer: &IndexFacade) -> ExitCode {
                 final_kind.as_deref(),
                 final_module.as_deref(),
                 language,
+                context_file,
+                substring,
                 format,
                 fields,
             )
@@ -177,5 +351,22 @@ pub fn run(query: RetrieveQuery, indexer: &IndexFacade) -> ExitCode {
             let format = OutputFormat::from_json_flag(json);
             retrieve::retrieve_describe(indexer, &final_symbol, language, format, fields)
         }
+        RetrieveQuery::Query {
+            name,
+            args,
+            json,
+            fields,
+        } => {
+            use crate::io::args::parse_positional_args;
+
+            let format = OutputFormat::from_json_flag(json);
+            match name {
+                Some(name) => {
+                    let (_, params) = parse_positional_args(&args);
+                    retrieve::retrieve_saved_query(indexer, &name, &params, format, fields)
+                }
+                None => retrieve::list_saved_queries(indexer.settings(), format),
+            }
+        }
     }
 }
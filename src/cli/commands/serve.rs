@@ -1,10 +1,11 @@
 //! Serve command - MCP server modes (stdio, HTTP, HTTPS).
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use crate::config::Settings;
 use crate::indexing::facade::IndexFacade;
+use crate::mcp::CodeIntelligenceServer;
 
 /// Arguments for the serve command.
 pub struct ServeArgs {
@@ -155,7 +156,10 @@ async fn run_stdio_server(
         facade.symbol_count(),
         facade.has_semantic_search()
     );
-    let server = crate::mcp::CodeIntelligenceServer::new(facade);
+    let server = CodeIntelligenceServer::new(facade);
+    if config.server.warm_up {
+        crate::mcp::warm::spawn_warm_up(server.get_facade_arc());
+    }
 
     // Load document store and attach to server (shared with watcher later)
     let document_store_arc = crate::documents::load_from_settings(&config);
@@ -166,17 +170,56 @@ async fn run_stdio_server(
         server
     };
 
+    start_watchers(
+        &config,
+        settings,
+        &server,
+        &index_path,
+        watch,
+        actual_watch_interval,
+    );
+
+    // Start server with stdio transport
+    use rmcp::{ServiceExt, transport::stdio};
+    let service = server
+        .serve(stdio())
+        .await
+        .map_err(|e| {
+            eprintln!("Failed to start MCP server: {e}");
+            std::process::exit(1);
+        })
+        .unwrap();
+
+    // Wait for server to complete
+    service
+        .waiting()
+        .await
+        .map_err(|e| {
+            eprintln!("MCP server error: {e}");
+            std::process::exit(1);
+        })
+        .unwrap();
+}
+
+/// Start the watchers of a long-running server: reloading the index when
+/// another process rewrites it (`watch`), and re-indexing changed code,
+/// settings, and documents (`watch` or `file_watch.enabled`).
+pub fn start_watchers(
+    config: &Settings,
+    settings: Arc<Settings>,
+    server: &CodeIntelligenceServer,
+    index_path: &Path,
+    watch: bool,
+    watch_interval: u64,
+) {
     // If watch mode is enabled, start the hot-reload watcher
     if watch {
         use crate::watcher::HotReloadWatcher;
         use std::time::Duration;
 
         let facade_arc = server.get_facade_arc();
-        let watcher = HotReloadWatcher::new(
-            facade_arc,
-            settings.clone(),
-            Duration::from_secs(actual_watch_interval),
-        );
+        let watcher =
+            HotReloadWatcher::new(facade_arc, settings, Duration::from_secs(watch_interval));
 
         // Spawn watcher in background
         tokio::spawn(async move {
@@ -207,7 +250,7 @@ async fn run_stdio_server(
         let mut builder = UnifiedWatcher::builder()
             .broadcaster(broadcaster.clone())
             .indexer(facade_arc.clone())
-            .index_path(index_path.clone())
+            .index_path(index_path.to_path_buf())
             .workspace_root(workspace_root.clone())
             .debounce_ms(debounce_ms);
 
@@ -228,7 +271,7 @@ async fn run_stdio_server(
         }
 
         // Add document handler using shared document store
-        if let Some(store_arc) = document_store_arc {
+        if let Some(store_arc) = server.document_store.clone() {
             tracing::debug!(target: "mcp", "adding document handler to watcher");
             builder = builder
                 .document_store(store_arc.clone())
@@ -265,27 +308,6 @@ async fn run_stdio_server(
             }
         }
     }
-
-    // Start server with stdio transport
-    use rmcp::{ServiceExt, transport::stdio};
-    let service = server
-        .serve(stdio())
-        .await
-        .map_err(|e| {
-            eprintln!("Failed to start MCP server: {e}");
-            std::process::exit(1);
-        })
-        .unwrap();
-
-    // Wait for server to complete
-    service
-        .waiting()
-        .await
-        .map_err(|e| {
-            eprintln!("MCP server error: {e}");
-            std::process::exit(1);
-        })
-        .unwrap();
 }
 
 /// Run the MCP test command.
@@ -0,0 +1,124 @@
+//! Stats command - counts and size of the index.
+
+use crate::analysis::IndexSummary;
+use crate::display::TableBuilder;
+use crate::indexing::facade::IndexFacade;
+use crate::io::{Envelope, ExitCode, OutputFormat};
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+
+/// Run the stats command.
+pub fn run(json: bool, indexer: &IndexFacade) -> ExitCode {
+    let summary = IndexSummary::collect(indexer);
+    let format = OutputFormat::from_json_flag(json);
+    if format.is_structured() {
+        let message = format!(
+            "{} files, {} symbols, {} relationships",
+            summary.files, summary.symbols, summary.relationships
+        );
+        let envelope = Envelope::success(&summary).with_message(message);
+        println!(
+            "{}",
+            envelope.render(format).expect("envelope serialization")
+        );
+        return ExitCode::Success;
+    }
+
+    print_summary(&summary);
+    ExitCode::Success
+}
+
+fn print_summary(summary: &IndexSummary) {
+    let overview = TableBuilder::new()
+        .set_headers(vec!["Index", "Value"])
+        .add_row(vec!["Files".to_string(), summary.files.to_string()])
+        .add_row(vec!["Symbols".to_string(), summary.symbols.to_string()])
+        .add_row(vec![
+            "Relationships".to_string(),
+            summary.relationships.to_string(),
+        ])
+        .add_row(vec![
+            "Size on disk".to_string(),
+            format_bytes(summary.disk.total_bytes),
+        ])
+        .add_row(vec![
+            "Last file indexed".to_string(),
+            format_timestamp(summary.last_file_indexed),
+        ])
+        .add_row(vec![
+            "Last modified".to_string(),
+            format_timestamp(summary.last_modified),
+        ])
+        .build();
+    println!("{overview}");
+
+    let mut languages = TableBuilder::new().set_headers(vec!["Language", "Files", "Symbols"]);
+    for language in &summary.languages {
+        languages = languages.add_row(vec![
+            language.language.clone(),
+            language.files.to_string(),
+            language.symbols.to_string(),
+        ]);
+    }
+    println!("{}", languages.build());
+
+    if !summary.projects.is_empty() {
+        let mut projects = TableBuilder::new().set_headers(vec!["Project", "Files", "Symbols"]);
+        for project in &summary.projects {
+            projects = projects.add_row(vec![
+                project.project.clone(),
+                project.files.to_string(),
+                project.symbols.to_string(),
+            ]);
+        }
+        println!("{}", projects.build());
+    }
+
+    println!("{}", count_table("Symbol kind", &summary.symbols_by_kind));
+    if !summary.symbols_by_tag.is_empty() {
+        println!("{}", count_table("Tag", &summary.symbols_by_tag));
+    }
+    println!(
+        "{}",
+        count_table("Relationship kind", &summary.relationships_by_kind)
+    );
+
+    let mut disk = TableBuilder::new().set_headers(vec!["Component", "Size"]);
+    for (component, bytes) in &summary.disk.by_component {
+        disk = disk.add_row(vec![component.clone(), format_bytes(*bytes)]);
+    }
+    println!("{}", disk.build());
+}
+
+/// Table of `counts`, largest first
+fn count_table(header: &str, counts: &BTreeMap<String, usize>) -> String {
+    let mut rows: Vec<_> = counts.iter().collect();
+    rows.sort_by(|a, b| b.1.cmp(a.1));
+    let mut table = TableBuilder::new().set_headers(vec![header, "Count"]);
+    for (kind, count) in rows {
+        table = table.add_row(vec![kind.clone(), count.to_string()]);
+    }
+    table.build()
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
+
+fn format_timestamp(timestamp: Option<u64>) -> String {
+    timestamp
+        .and_then(|secs| DateTime::<Utc>::from_timestamp(secs as i64, 0))
+        .map(|time| time.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+        .unwrap_or_else(|| "never".to_string())
+}
@@ -6,4 +6,7 @@
 pub mod args;
 pub mod commands;
 
-pub use args::{Cli, Commands, DocumentAction, PluginAction, RetrieveQuery};
+pub use args::{
+    AnalyzeAction, Cli, Commands, DaemonAction, DepsAction, DocumentAction, ExportAction,
+    IndexAction, PluginAction, RetrieveQuery,
+};
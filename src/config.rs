@@ -16,6 +16,8 @@
 //!
 //! For logging, use `RUST_LOG` environment variable directly (standard Rust pattern).
 
+use crate::SymbolKind;
+use crate::parsing::SignatureStyle;
 use figment::{
     Figment,
     providers::{Env, Format, Serialized, Toml},
@@ -78,6 +80,42 @@ pub struct Settings {
     /// Document embedding settings for RAG
     #[serde(default)]
     pub documents: crate::documents::DocumentsConfig,
+
+    /// Saved queries by name, e.g. `public-api = "kind:function visibility:public"`
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub queries: IndexMap<String, String>,
+
+    /// Architecture layering rules checked by `codanna analyze layers`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub layers: Vec<LayerRule>,
+
+    /// Symbol kind overrides applied after parsing
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub kinds: Vec<KindRule>,
+
+    /// Tagging rules applied to symbols at index time
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<TagRule>,
+
+    /// Dangerous-call rules checked by `codanna analyze security`
+    #[serde(default)]
+    pub security: SecurityConfig,
+
+    /// Feature-flag call patterns inventoried by `codanna analyze flags`
+    #[serde(default)]
+    pub feature_flags: FeatureFlagsConfig,
+
+    /// Translation key lookups and locale files read by `codanna analyze i18n`
+    #[serde(default)]
+    pub i18n: I18nConfig,
+
+    /// Generated descriptions of undocumented public symbols
+    #[serde(default)]
+    pub summaries: SummariesConfig,
+
+    /// How symbols are shown in search results and hovers
+    #[serde(default)]
+    pub display: DisplayConfig,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -120,6 +158,12 @@ pub struct IndexingConfig {
     #[serde(default = "default_batches_per_commit")]
     pub batches_per_commit: usize,
 
+    /// Megabytes of uncommitted index batches before committing early, and
+    /// of relationships and symbols held for resolution before they spill
+    /// to disk. Keeps peak memory bounded on small machines; 0 disables the budget
+    #[serde(default)]
+    pub memory_budget_mb: usize,
+
     /// Enable detailed pipeline stage tracing (timing, memory, throughput)
     /// Set logging.modules.pipeline = "info" to see output
     #[serde(default)]
@@ -128,6 +172,42 @@ pub struct IndexingConfig {
     /// Show progress bars during indexing (default: true)
     #[serde(default = "default_true")]
     pub show_progress: bool,
+
+    /// Sync the write-ahead log to disk after every entry (default: false)
+    /// Survives power loss at some cost per update; `serve --fsync` turns it on
+    #[serde(default)]
+    pub fsync: bool,
+
+    /// Give every top-level directory of an indexed path its own index (default: false)
+    /// For very large monorepos; queries fan out over the shards transparently
+    #[serde(default)]
+    pub shard_by_directory: bool,
+
+    /// Extract identifier references with the shared second pass (default: true)
+    /// Parses each file a second time; disable to trade `References` edges for speed
+    #[serde(default = "default_true")]
+    pub reference_pass: bool,
+
+    /// Symbol extraction profile for every language (default: "full")
+    /// Languages can override it with `languages.<name>.profile`
+    #[serde(default)]
+    pub profile: ExtractionProfile,
+
+    /// Languages of files their extension doesn't identify, keyed by file name
+    /// or shebang interpreter, e.g. `Tiltfile = "python"`, `bun = "javascript"`
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub language_overrides: IndexMap<String, String>,
+}
+
+/// How much of each file the indexer keeps
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ExtractionProfile {
+    /// Top-level public symbols only, without the reference pass
+    Light,
+    /// Every symbol, including members, locals, and parameters, plus references
+    #[default]
+    Full,
 }
 
 /// Source layout for project resolution
@@ -179,6 +259,10 @@ pub struct LanguageConfig {
     /// Use when auto-detection fails (e.g., custom build plugins)
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub projects: Vec<ProjectConfig>,
+
+    /// Extraction profile for this language, overriding `indexing.profile`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<ExtractionProfile>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -194,10 +278,22 @@ pub struct SemanticSearchConfig {
     #[serde(default = "default_false")]
     pub enabled: bool,
 
-    /// Model to use for embeddings
+    /// Backend that computes embeddings
+    #[serde(default)]
+    pub provider: EmbeddingProviderKind,
+
+    /// Model to use for embeddings, as named by the provider
     #[serde(default = "default_embedding_model")]
     pub model: String,
 
+    /// Base URL of the provider's API; unset uses its usual address
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+
+    /// Environment variable holding the API key for `openai`
+    #[serde(default = "default_api_key_env")]
+    pub api_key_env: String,
+
     /// Similarity threshold for search results
     #[serde(default = "default_similarity_threshold")]
     pub threshold: f32,
@@ -205,6 +301,14 @@ pub struct SemanticSearchConfig {
     /// Number of parallel embedding model instances
     #[serde(default = "default_embedding_threads")]
     pub embedding_threads: usize,
+
+    /// Most embeddings kept in the cache of unchanged doc comments (0 disables it)
+    #[serde(default = "default_cache_max_entries")]
+    pub cache_max_entries: usize,
+
+    /// Drop cached embeddings unused for this many days (0 keeps them)
+    #[serde(default = "default_cache_max_age_days")]
+    pub cache_max_age_days: u64,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -218,6 +322,316 @@ pub struct FileWatchConfig {
     pub debounce_ms: u64,
 }
 
+/// Dependency rule between groups of modules
+///
+/// ```toml
+/// [[layers]]
+/// name = "domain must not import infrastructure"
+/// from = "crate::domain"
+/// deny = ["crate::infrastructure", "crate::*::db::*"]
+/// ```
+///
+/// Patterns are globs over module paths. A pattern without wildcards matches
+/// that module and everything below it.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct LayerRule {
+    /// Label shown with violations (defaults to the `from` pattern)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// Modules the rule constrains
+    pub from: String,
+
+    /// Modules they must not import, call, or reference
+    pub deny: Vec<String>,
+}
+
+/// Security-sensitive call rules
+///
+/// ```toml
+/// [security]
+/// builtin_rules = true
+///
+/// [[security.rules]]
+/// language = "python"
+/// calls = ["subprocess.call", "os.system"]
+/// severity = "high"
+/// message = "runs a shell command"
+/// ```
+///
+/// Calls match when the callee as written equals an entry, so `pickle.load`
+/// does not match a call through an aliased import.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct SecurityConfig {
+    /// Check the built-in rule pack as well as `rules`
+    #[serde(default = "default_true")]
+    pub builtin_rules: bool,
+
+    /// Additional rules
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<SecurityRule>,
+}
+
+impl Default for SecurityConfig {
+    fn default() -> Self {
+        Self {
+            builtin_rules: true,
+            rules: Vec::new(),
+        }
+    }
+}
+
+/// Feature-flag lookups to inventory
+///
+/// ```toml
+/// [feature_flags]
+/// builtin_sdks = true
+///
+/// [[feature_flags.patterns]]
+/// system = "settings"
+/// regex = 'is_enabled\("([\w.-]+)"\)'
+/// ```
+///
+/// Each pattern's first capture group is the flag name. The built-in
+/// patterns cover LaunchDarkly `*Variation` and Unleash `isEnabled` /
+/// `getVariant` calls whose first argument is a string literal.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct FeatureFlagsConfig {
+    /// Look for the built-in SDK calls as well as `patterns`
+    #[serde(default = "default_true")]
+    pub builtin_sdks: bool,
+
+    /// Additional patterns
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub patterns: Vec<FlagPattern>,
+}
+
+impl Default for FeatureFlagsConfig {
+    fn default() -> Self {
+        Self {
+            builtin_sdks: true,
+            patterns: Vec::new(),
+        }
+    }
+}
+
+/// A regular expression matching flag lookups of one flag system
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct FlagPattern {
+    /// Flag system reported for matches, e.g. "unleash" or "settings"
+    pub system: String,
+
+    /// Pattern whose first capture group is the flag name
+    pub regex: String,
+}
+
+/// Translation keys: how code looks them up and where they are defined
+///
+/// ```toml
+/// [i18n]
+/// builtin_patterns = true
+/// patterns = ['tr\("([\w.]+)"\)']
+/// locale_files = ["locales/en/*.json", "po/*.po"]
+/// ```
+///
+/// Each pattern's first capture group that matched is the key. The built-in
+/// patterns cover `t()`, `$t()`, `_()`, `__()`, `gettext()`, `trans()` and
+/// `i18nKey=` with a string literal key. Locale files are globs relative to
+/// the workspace root: JSON files, whose nested objects give dotted keys,
+/// and gettext `.po` / `.pot` files.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct I18nConfig {
+    /// Look for the built-in lookup calls as well as `patterns`
+    #[serde(default = "default_true")]
+    pub builtin_patterns: bool,
+
+    /// Additional lookup patterns
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub patterns: Vec<String>,
+
+    /// Files defining the keys, for finding unused and missing ones
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub locale_files: Vec<String>,
+}
+
+impl Default for I18nConfig {
+    fn default() -> Self {
+        Self {
+            builtin_patterns: true,
+            patterns: Vec::new(),
+            locale_files: Vec::new(),
+        }
+    }
+}
+
+/// One-line descriptions of undocumented public symbols, written by an LLM
+/// after indexing
+///
+/// ```toml
+/// [summaries]
+/// enabled = true
+/// endpoint = "http://localhost:11434/v1"
+/// model = "llama3.2"
+/// ```
+///
+/// The endpoint speaks the OpenAI chat completions API. Descriptions are
+/// stored apart from doc comments and always shown as generated.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct SummariesConfig {
+    /// Describe undocumented public symbols after indexing
+    #[serde(default = "default_false")]
+    pub enabled: bool,
+
+    /// Base URL of the chat completions API; unset uses OpenAI
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+
+    /// Model that writes the descriptions
+    #[serde(default = "default_summary_model")]
+    pub model: String,
+
+    /// Environment variable holding the API key
+    #[serde(default = "default_api_key_env")]
+    pub api_key_env: String,
+
+    /// Most symbols described per indexing run; the rest follow on later runs
+    #[serde(default = "default_summaries_per_run")]
+    pub max_per_run: usize,
+}
+
+impl Default for SummariesConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: None,
+            model: default_summary_model(),
+            api_key_env: default_api_key_env(),
+            max_per_run: default_summaries_per_run(),
+        }
+    }
+}
+
+/// Presentation of symbols in search results and hovers
+///
+/// ```toml
+/// [display]
+/// elide_signature_defaults = true
+/// ```
+///
+/// Signatures are always normalized by the language's behavior: attributes
+/// and bodies dropped, whitespace collapsed. Default parameter values are
+/// kept unless elided here.
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub struct DisplayConfig {
+    /// Drop default parameter values from displayed signatures
+    #[serde(default = "default_false")]
+    pub elide_signature_defaults: bool,
+}
+
+impl DisplayConfig {
+    /// The signature style these settings ask for
+    pub fn signature_style(&self) -> SignatureStyle {
+        SignatureStyle {
+            elide_defaults: self.elide_signature_defaults,
+        }
+    }
+}
+
+/// Reclassification of symbols whose parser-given kind doesn't fit the
+/// project's conventions
+///
+/// ```toml
+/// [[kinds]]
+/// language = "nix"
+/// name = '^packages\.'
+/// kind = "Module"
+///
+/// [[kinds]]
+/// language = "python"
+/// name = '^[A-Z][A-Z0-9_]*$'
+/// from = "Variable"
+/// kind = "Constant"
+/// ```
+///
+/// The first rule matching a symbol decides its kind.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct KindRule {
+    /// Language id, e.g. "nix"
+    pub language: String,
+
+    /// Regular expression the symbol name must match
+    pub name: String,
+
+    /// Only reclassify symbols the parser gave this kind
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<SymbolKind>,
+
+    /// Kind given to matching symbols
+    pub kind: SymbolKind,
+}
+
+/// A label attached at index time to every symbol matching all of the
+/// rule's conditions
+///
+/// ```toml
+/// [[tags]]
+/// tag = "api-handler"
+/// paths = ["src/api/**"]
+/// name = '^handle_'
+/// kind = "Function"
+///
+/// [[tags]]
+/// tag = "deprecated-zone"
+/// paths = ["src/legacy/**", "src/compat/**"]
+/// ```
+///
+/// A rule needs at least one condition. Symbols can carry several tags;
+/// filter on them with `tag:api-handler` in saved queries.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct TagRule {
+    /// Label given to matching symbols
+    pub tag: String,
+
+    /// Globs over file paths relative to the workspace root; any may match
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub paths: Vec<String>,
+
+    /// Regular expression the symbol name must match
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// Kind the symbol must have
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<SymbolKind>,
+}
+
+/// Calls to flag in one language
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct SecurityRule {
+    /// Language id, e.g. "python"
+    pub language: String,
+
+    /// Callees as written in source, e.g. `eval` or `pickle.load`
+    pub calls: Vec<String>,
+
+    #[serde(default)]
+    pub severity: Severity,
+
+    /// Why the call is dangerous
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// How urgent a security finding is
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Low,
+    #[default]
+    Medium,
+    High,
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct ServerConfig {
     /// Default server mode: "stdio" or "http"
@@ -231,6 +645,16 @@ pub struct ServerConfig {
     /// Watch interval for stdio mode (seconds)
     #[serde(default = "default_watch_interval")]
     pub watch_interval: u64,
+
+    /// Precompute caller and module lookups in the background on start and
+    /// after re-indexing
+    #[serde(default = "default_true")]
+    pub warm_up: bool,
+
+    /// Bearer token for the HTTP REST API; the API is disabled when unset.
+    /// Prefer `CI_SERVER__API_TOKEN` over committing the token to settings.toml.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_token: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -240,16 +664,45 @@ pub struct LoggingConfig {
     #[serde(default = "default_log_level")]
     pub default: String,
 
+    /// Output format: "text" (default) or "json" (one object per line)
+    #[serde(default)]
+    pub format: LogFormat,
+
     /// Per-module log level overrides (IndexMap preserves insertion order)
     /// Example: { "tantivy" = "warn", "watcher" = "debug" }
     #[serde(default)]
     pub modules: IndexMap<String, String>,
 }
 
+/// Where embeddings of doc comments and queries are computed
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum EmbeddingProviderKind {
+    /// Bundled ONNX model run in process; needs no network once downloaded
+    #[default]
+    Local,
+    /// Any endpoint speaking the OpenAI embeddings API
+    OpenAi,
+    /// An Ollama server
+    Ollama,
+}
+
+/// Log output format
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Compact human-readable lines
+    #[default]
+    Text,
+    /// Newline-delimited JSON with span context, for log aggregators
+    Json,
+}
+
 impl Default for LoggingConfig {
     fn default() -> Self {
         Self {
             default: default_log_level(),
+            format: LogFormat::default(),
             modules: default_logging_modules(),
         }
     }
@@ -348,12 +801,27 @@ fn default_max_context_size() -> usize {
 fn default_embedding_model() -> String {
     "AllMiniLML6V2".to_string()
 }
+fn default_api_key_env() -> String {
+    "OPENAI_API_KEY".to_string()
+}
+fn default_summary_model() -> String {
+    "gpt-4o-mini".to_string()
+}
+fn default_summaries_per_run() -> usize {
+    200
+}
 fn default_similarity_threshold() -> f32 {
     0.6
 }
 fn default_embedding_threads() -> usize {
     3
 }
+fn default_cache_max_entries() -> usize {
+    100_000
+}
+fn default_cache_max_age_days() -> u64 {
+    30
+}
 fn default_debounce_ms() -> u64 {
     500
 }
@@ -383,6 +851,15 @@ impl Default for Settings {
             logging: LoggingConfig::default(),
             guidance: GuidanceConfig::default(),
             documents: crate::documents::DocumentsConfig::default(),
+            queries: IndexMap::new(),
+            layers: Vec::new(),
+            kinds: Vec::new(),
+            tags: Vec::new(),
+            security: SecurityConfig::default(),
+            feature_flags: FeatureFlagsConfig::default(),
+            i18n: I18nConfig::default(),
+            summaries: SummariesConfig::default(),
+            display: DisplayConfig::default(),
         }
     }
 }
@@ -403,8 +880,14 @@ impl Default for IndexingConfig {
             indexed_paths: Vec::new(),
             batch_size: default_batch_size(),
             batches_per_commit: default_batches_per_commit(),
+            memory_budget_mb: 0,
             pipeline_tracing: false,
             show_progress: true,
+            fsync: false,
+            shard_by_directory: false,
+            reference_pass: true,
+            profile: ExtractionProfile::default(),
+            language_overrides: IndexMap::new(),
         }
     }
 }
@@ -421,9 +904,14 @@ impl Default for SemanticSearchConfig {
     fn default() -> Self {
         Self {
             enabled: true, // Enabled by default for better code intelligence
+            provider: EmbeddingProviderKind::default(),
             model: default_embedding_model(),
+            endpoint: None,
+            api_key_env: default_api_key_env(),
             threshold: default_similarity_threshold(),
             embedding_threads: default_embedding_threads(),
+            cache_max_entries: default_cache_max_entries(),
+            cache_max_age_days: default_cache_max_age_days(),
         }
     }
 }
@@ -443,6 +931,8 @@ impl Default for ServerConfig {
             mode: default_server_mode(),
             bind: default_bind_address(),
             watch_interval: default_watch_interval(),
+            warm_up: true,
+            api_token: None,
         }
     }
 }
@@ -573,6 +1063,7 @@ fn generate_language_defaults() -> IndexMap<String, LanguageConfig> {
                         parser_options: HashMap::new(),
                         config_files: Vec::new(),
                         projects: Vec::new(),
+                        profile: None,
                     },
                 )
             })
@@ -609,6 +1100,7 @@ fn fallback_minimal_languages() -> IndexMap<String, LanguageConfig> {
             parser_options: HashMap::new(),
             config_files: Vec::new(),
             projects: Vec::new(),
+            profile: None,
         },
     );
 
@@ -849,6 +1341,7 @@ impl Settings {
         );
 
         let mut in_languages_section = false;
+        let mut in_summaries_section = false;
         let mut prev_line_was_section = false;
 
         for line in toml.lines() {
@@ -859,8 +1352,14 @@ impl Settings {
             }
             prev_line_was_section = false;
 
+            if line.starts_with('[') {
+                in_summaries_section = line == "[summaries]";
+            }
+
             // Add section and field comments
-            if line == "version = 1" {
+            if in_summaries_section && !line.starts_with('[') {
+                // summaries fields - comments already added with the section
+            } else if line == "version = 1" {
                 result.push_str("# Version of the configuration schema\n");
             } else if line.starts_with("index_path = ") {
                 result.push_str("\n# Path to the index directory (relative to workspace root)\n");
@@ -893,6 +1392,12 @@ impl Settings {
                 result.push_str("\n# Items per batch before flushing to index (default: 5000)\n");
             } else if line.starts_with("batches_per_commit = ") {
                 result.push_str("\n# Number of batches before committing to disk (default: 10)\n");
+            } else if line.starts_with("memory_budget_mb = ") {
+                result.push_str("\n# Commit early, and spill pending relationships to disk,\n");
+                result.push_str("# once indexing holds this many MB\n");
+                result.push_str(
+                    "# Set to e.g. 256 on CI machines with little RAM (default: 0, no limit)\n",
+                );
             } else if line.starts_with("pipeline_tracing = ") {
                 result.push_str("\n# Enable detailed pipeline stage tracing\n");
                 result.push_str("# Shows timing, throughput, and memory for each stage\n");
@@ -900,6 +1405,16 @@ impl Settings {
             } else if line.starts_with("show_progress = ") {
                 result.push_str("\n# Show progress bars during indexing (default: true)\n");
                 result.push_str("# Use --no-progress CLI flag to override\n");
+            } else if line.starts_with("fsync = ") {
+                result.push_str("\n# Sync the write-ahead log to disk after every index update\n");
+                result.push_str(
+                    "# Turn on for servers that must survive power loss (default: false)\n",
+                );
+            } else if line.starts_with("shard_by_directory = ") {
+                result.push_str("\n# Give every top-level directory its own index shard\n");
+                result.push_str(
+                    "# For very large monorepos; run 'codanna index --force' after changing (default: false)\n",
+                );
             } else if line == "[mcp]" {
                 result.push_str("\n[mcp]\n");
                 prev_line_was_section = true;
@@ -913,6 +1428,17 @@ impl Settings {
                 continue;
             } else if line.starts_with("enabled = ") && !in_languages_section {
                 // enabled field in semantic_search - comment already added above
+            } else if line.starts_with("provider = ") {
+                result.push_str("\n# Where embeddings are computed\n");
+                result.push_str("# - local: bundled ONNX model, works offline (default)\n");
+                result.push_str(
+                    "# - openai: OpenAI or any compatible API, set `endpoint` for self-hosted ones\n",
+                );
+                result.push_str(
+                    "# - ollama: an Ollama server, http://localhost:11434 unless `endpoint` is set\n",
+                );
+            } else if line.starts_with("api_key_env = ") {
+                result.push_str("\n# Environment variable with the API key for openai\n");
             } else if line.starts_with("model = ") {
                 result.push_str("\n# Model to use for embeddings\n");
                 result.push_str(
@@ -928,6 +1454,9 @@ impl Settings {
                 );
                 result.push_str("# - BGESmallZHV15: Chinese-specialized, 512 dimensions\n");
                 result.push_str("# - See documentation for full list of available models\n");
+                result.push_str(
+                    "# - openai/ollama: the provider's model name, e.g. text-embedding-3-small\n",
+                );
             } else if line.starts_with("threshold = ") {
                 result.push_str("\n# Similarity threshold for search results (0.0 to 1.0)\n");
             } else if line.starts_with("embedding_threads = ") {
@@ -935,6 +1464,16 @@ impl Settings {
                 result
                     .push_str("# Each instance uses ~86MB RAM. Higher values = faster indexing.\n");
                 result.push_str("# Set to 1 for low-memory systems, 4-6 for high-end machines.\n");
+            } else if line.starts_with("cache_max_entries = ") {
+                result
+                    .push_str("\n# Embeddings of unchanged doc comments are reused on re-index\n");
+                result.push_str(
+                    "# Most cached embeddings (~1.5KB each at 384 dimensions, 0 disables)\n",
+                );
+            } else if line.starts_with("cache_max_age_days = ") {
+                result.push_str(
+                    "\n# Drop cached embeddings unused for this many days (0 keeps them)\n",
+                );
             } else if line == "[file_watch]" {
                 result.push_str("\n[file_watch]\n");
                 result.push_str("# Enable automatic file watching for indexed files\n");
@@ -962,6 +1501,11 @@ impl Settings {
                 result.push_str("\n# HTTP server bind address (only used when mode = \"http\" or --http flag)\n");
             } else if line.starts_with("watch_interval = ") {
                 result.push_str("\n# Watch interval for stdio mode in seconds (how often to check for file changes)\n");
+            } else if line.starts_with("warm_up = ") {
+                result.push_str(
+                    "\n# Precompute caller and module lookups after start and re-indexing\n",
+                );
+                result.push_str("# Trades a little background CPU for fast first queries\n");
             } else if line == "[logging]" {
                 result.push_str("\n[logging]\n");
                 result.push_str("# Logging configuration\n");
@@ -971,6 +1515,10 @@ impl Settings {
                 continue;
             } else if line.starts_with("default = ") && !in_languages_section {
                 result.push_str("# Default log level (\"warn\" = quiet, \"info\" = normal, \"debug\" = verbose)\n");
+            } else if line.starts_with("format = ") && !in_languages_section {
+                result.push_str(
+                    "\n# Log format: \"text\" (default) or \"json\" for log aggregators\n",
+                );
             } else if line == "[logging.modules]" {
                 result.push_str("\n[logging.modules]\n");
                 result.push_str("# Per-module log level overrides\n");
@@ -986,6 +1534,26 @@ impl Settings {
                 result.push_str("# mcp = \"debug\"       # MCP server operations\n");
                 prev_line_was_section = true;
                 continue;
+            } else if line == "[summaries]" {
+                result.push_str("\n[summaries]\n");
+                result.push_str(
+                    "# One-line descriptions of undocumented public symbols, written by an LLM\n",
+                );
+                result.push_str(
+                    "# after indexing. Shown marked as generated, never as doc comments.\n",
+                );
+                result.push_str("# endpoint: OpenAI-compatible chat API (default: OpenAI), e.g. http://localhost:11434/v1\n");
+                result.push_str("# api_key_env: environment variable with the API key\n");
+                result.push_str("# max_per_run: symbols described per indexing run\n");
+                prev_line_was_section = true;
+                continue;
+            } else if line == "[display]" {
+                result.push_str("\n[display]\n");
+                result.push_str("# How signatures show in search results and hovers\n");
+                prev_line_was_section = true;
+                continue;
+            } else if line.starts_with("elide_signature_defaults = ") {
+                result.push_str("# Drop default parameter values: def f(x=1) shows as def f(x)\n");
             } else if line == "[documents]" {
                 result.push_str("\n[documents]\n");
                 result.push_str("# Document embedding for RAG (Retrieval-Augmented Generation)\n");
@@ -1307,6 +1875,25 @@ __pycache__/
     pub fn get_indexed_paths(&self) -> Vec<PathBuf> {
         self.indexing.indexed_paths.clone()
     }
+
+    /// Extraction profile for a language: its own `profile` if set,
+    /// otherwise `indexing.profile`
+    pub fn extraction_profile(&self, language: &str) -> ExtractionProfile {
+        self.languages
+            .get(language)
+            .and_then(|config| config.profile)
+            .unwrap_or(self.indexing.profile)
+    }
+
+    /// Copy of these settings extracting every language with `profile`
+    pub fn with_extraction_profile(&self, profile: ExtractionProfile) -> Self {
+        let mut settings = self.clone();
+        settings.indexing.profile = profile;
+        for config in settings.languages.values_mut() {
+            config.profile = None;
+        }
+        settings
+    }
 }
 
 #[cfg(test)]
@@ -1531,6 +2118,130 @@ enabled = true
         println!("=== TEST PASSED ===");
     }
 
+    #[test]
+    fn test_logging_format_from_toml() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("settings.toml");
+        fs::write(&config_path, "[logging]\nformat = \"json\"\n").unwrap();
+
+        let settings: Settings = Figment::new()
+            .merge(Serialized::defaults(Settings::default()))
+            .merge(Toml::file(config_path))
+            .extract()
+            .unwrap();
+
+        assert_eq!(settings.logging.format, LogFormat::Json);
+        assert_eq!(settings.logging.default, "warn");
+        assert_eq!(LoggingConfig::default().format, LogFormat::Text);
+    }
+
+    #[test]
+    fn test_layers_from_toml() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("settings.toml");
+        fs::write(
+            &config_path,
+            "[[layers]]\nfrom = \"crate::domain\"\ndeny = [\"crate::infra\"]\n",
+        )
+        .unwrap();
+
+        let settings: Settings = Figment::new()
+            .merge(Serialized::defaults(Settings::default()))
+            .merge(Toml::file(config_path))
+            .extract()
+            .unwrap();
+
+        assert_eq!(
+            settings.layers,
+            vec![LayerRule {
+                name: None,
+                from: "crate::domain".to_string(),
+                deny: vec!["crate::infra".to_string()],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_security_rules_from_toml() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("settings.toml");
+        fs::write(
+            &config_path,
+            "[security]\nbuiltin_rules = false\n\n[[security.rules]]\nlanguage = \"python\"\ncalls = [\"os.system\"]\n",
+        )
+        .unwrap();
+
+        let settings: Settings = Figment::new()
+            .merge(Serialized::defaults(Settings::default()))
+            .merge(Toml::file(config_path))
+            .extract()
+            .unwrap();
+
+        assert!(!settings.security.builtin_rules);
+        assert_eq!(
+            settings.security.rules,
+            vec![SecurityRule {
+                language: "python".to_string(),
+                calls: vec!["os.system".to_string()],
+                severity: Severity::Medium,
+                message: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_feature_flag_patterns_from_toml() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("settings.toml");
+        fs::write(
+            &config_path,
+            "[[feature_flags.patterns]]\nsystem = \"settings\"\nregex = 'flag\\(\"(\\w+)\"\\)'\n",
+        )
+        .unwrap();
+
+        let settings: Settings = Figment::new()
+            .merge(Serialized::defaults(Settings::default()))
+            .merge(Toml::file(config_path))
+            .extract()
+            .unwrap();
+
+        assert!(settings.feature_flags.builtin_sdks);
+        assert_eq!(
+            settings.feature_flags.patterns,
+            vec![FlagPattern {
+                system: "settings".to_string(),
+                regex: r#"flag\("(\w+)"\)"#.to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_extraction_profile_per_language() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("settings.toml");
+        fs::write(
+            &config_path,
+            "[indexing]\nprofile = \"light\"\n\n[languages.rust]\nprofile = \"full\"\n",
+        )
+        .unwrap();
+
+        let settings: Settings = Figment::new()
+            .merge(Serialized::defaults(Settings::default()))
+            .merge(Toml::file(config_path))
+            .extract()
+            .unwrap();
+
+        assert_eq!(settings.extraction_profile("rust"), ExtractionProfile::Full);
+        assert_eq!(
+            settings.extraction_profile("python"),
+            ExtractionProfile::Light
+        );
+        assert!(!settings.languages["rust"].extensions.is_empty());
+
+        let light = settings.with_extraction_profile(ExtractionProfile::Light);
+        assert_eq!(light.extraction_profile("rust"), ExtractionProfile::Light);
+    }
+
     #[test]
     fn test_add_indexed_path() {
         let temp_dir = TempDir::new().unwrap();
@@ -0,0 +1,328 @@
+//! Relationships into dependencies
+//!
+//! Phase 2 resolves what it can among the project's own symbols. A name it
+//! can't place there is looked up in the dependency indexes: in the module
+//! a file imports it from (`import { useState } from "react"`), or for a
+//! call, by its path alone (`lib.mkOption` in a NixOS module). A unique match
+//! is copied into the project index as an external symbol, so the
+//! relationship has a target that callers, impact analysis and the other
+//! graph queries can see.
+//!
+//! External symbols keep the dependency symbol's name, kind, signature and
+//! docs. They are tagged [`EXTERNAL_TAG`], name the dependency as their
+//! `project`, and live at `<dependency>:<file>`, a path no project file has,
+//! so reindexing project files leaves them alone.
+
+use super::{DependencySet, ExternalSymbol, split_qualified};
+use crate::config::Settings;
+use crate::parsing::Import;
+use crate::parsing::registry::LanguageId;
+use crate::storage::{DocumentIndex, StorageResult};
+use crate::{FileId, Symbol, SymbolId};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// Tag of the project symbols that stand for a dependency symbol
+pub const EXTERNAL_TAG: &str = "external";
+
+/// Links relationships to the symbols of the mounted dependencies
+pub struct DependencyLinker {
+    dependencies: DependencySet,
+    index: Arc<DocumentIndex>,
+    state: Mutex<LinkState>,
+}
+
+struct LinkState {
+    /// Project ids of the dependency symbols linked so far
+    linked: HashMap<(String, SymbolId), SymbolId>,
+    /// File ids of the external symbols' files
+    files: HashMap<Box<str>, FileId>,
+    next_symbol: u32,
+    next_file: u32,
+    /// External symbols not yet written to the index
+    pending: Vec<Symbol>,
+}
+
+impl DependencyLinker {
+    /// Linker over the dependencies under `deps_root`, or `None` when no
+    /// dependency is installed
+    pub fn open(deps_root: &Path, settings: &Settings, index: Arc<DocumentIndex>) -> Option<Self> {
+        let dependencies = DependencySet::open(deps_root, settings);
+        if dependencies.is_empty() {
+            return None;
+        }
+        match Self::new(dependencies, index) {
+            Ok(linker) => Some(linker),
+            Err(e) => {
+                tracing::warn!(target: "deps", "cannot link dependency symbols: {e}");
+                None
+            }
+        }
+    }
+
+    pub fn new(dependencies: DependencySet, index: Arc<DocumentIndex>) -> StorageResult<Self> {
+        let next_symbol = index.get_next_symbol_id()?;
+        let next_file = index.get_next_file_id()?;
+        Ok(Self {
+            dependencies,
+            index,
+            state: Mutex::new(LinkState {
+                linked: HashMap::new(),
+                files: HashMap::new(),
+                next_symbol,
+                next_file,
+                pending: Vec::new(),
+            }),
+        })
+    }
+
+    /// Project id of the dependency symbol `name` names in `language`.
+    ///
+    /// `None` unless exactly one dependency symbol matches. The external
+    /// symbol is added on first use and found again in later runs.
+    pub fn link(&self, name: &str, language: LanguageId) -> Option<SymbolId> {
+        let found = self.dependencies.resolve(name, Some(language.as_str()));
+        self.link_unique(&found, language)
+    }
+
+    /// Project id of the dependency symbol `name` refers to through one of
+    /// a file's `imports`.
+    ///
+    /// `name` is either bound by an import, as by `from requests import
+    /// Session` or `import { useState } from "react"`, or reached through a
+    /// module alias, as `np.array` after `import numpy as np`. Only symbols
+    /// of the imported module count; relative imports name project files.
+    pub fn link_import(
+        &self,
+        name: &str,
+        imports: &[Import],
+        language: LanguageId,
+    ) -> Option<SymbolId> {
+        let (qualifier, base) = split_qualified(name);
+        imports
+            .iter()
+            .filter(|import| !import.path.starts_with('.'))
+            .find_map(|import| {
+                let alias = import.alias.as_deref();
+                let module = match qualifier {
+                    Some(qualifier)
+                        if alias == Some(qualifier)
+                            || (alias.is_none() && import.path == qualifier) =>
+                    {
+                        import.path.as_str()
+                    }
+                    Some(_) => return None,
+                    None if alias == Some(base) && !import.is_glob => import.path.as_str(),
+                    None if alias.is_none() => match split_qualified(&import.path) {
+                        (Some(module), last) if last == base => module,
+                        _ => return None,
+                    },
+                    None => return None,
+                };
+                let found = self
+                    .dependencies
+                    .resolve_in(module, base, Some(language.as_str()));
+                self.link_unique(&found, language)
+            })
+    }
+
+    /// Project id for the only symbol in `found`
+    fn link_unique(&self, found: &[ExternalSymbol], language: LanguageId) -> Option<SymbolId> {
+        let [external] = found else {
+            return None;
+        };
+
+        let key = (external.dependency.clone(), external.symbol.id);
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(&id) = state.linked.get(&key) {
+            return Some(id);
+        }
+
+        let path = external_path(external);
+        let id = match self.indexed(external, &path, language) {
+            Some(id) => id,
+            None => {
+                let id = SymbolId::new(state.next_symbol)?;
+                state.next_symbol += 1;
+                let next_file = state.next_file;
+                let file_id = *state
+                    .files
+                    .entry(path.clone())
+                    .or_insert_with(|| FileId::new(next_file).expect("file ids start at 1"));
+                if file_id.value() == next_file {
+                    state.next_file += 1;
+                }
+
+                let mut symbol = external.symbol.clone();
+                symbol.id = id;
+                symbol.file_id = file_id;
+                symbol.file_path = path;
+                symbol.tags = vec![EXTERNAL_TAG.into()];
+                symbol.project = Some(external.dependency.as_str().into());
+                state.pending.push(symbol);
+                id
+            }
+        };
+        state.linked.insert(key, id);
+        Some(id)
+    }
+
+    /// Write the external symbols added since the last flush, in the
+    /// index's current batch
+    pub fn flush(&self) -> StorageResult<usize> {
+        use crate::storage::MetadataKey;
+
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if state.pending.is_empty() {
+            return Ok(0);
+        }
+        self.index.start_batch()?;
+        let pending = std::mem::take(&mut state.pending);
+        for symbol in &pending {
+            self.index.index_symbol(symbol, &symbol.file_path)?;
+        }
+        self.index
+            .store_metadata(MetadataKey::SymbolCounter, u64::from(state.next_symbol - 1))?;
+        self.index
+            .store_metadata(MetadataKey::FileCounter, u64::from(state.next_file - 1))?;
+        Ok(pending.len())
+    }
+
+    /// Id of the external symbol an earlier run added for `external`
+    fn indexed(
+        &self,
+        external: &ExternalSymbol,
+        path: &str,
+        language: LanguageId,
+    ) -> Option<SymbolId> {
+        self.index
+            .find_symbols_by_name(&external.symbol.name, Some(language.as_str()))
+            .unwrap_or_default()
+            .into_iter()
+            .find(|symbol| {
+                &*symbol.file_path == path
+                    && symbol.tags.iter().any(|tag| &**tag == EXTERNAL_TAG)
+                    && symbol.module_path == external.symbol.module_path
+            })
+            .map(|symbol| symbol.id)
+    }
+}
+
+/// Path of an external symbol: its file within the dependency
+fn external_path(external: &ExternalSymbol) -> Box<str> {
+    format!("{}:{}", external.dependency, external.symbol.file_path).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deps::{DependencyManifest, deps_dir, write_dependency_index};
+    use crate::{Range, SymbolKind};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_link_adds_external_symbol_once() {
+        let temp = TempDir::new().unwrap();
+        let settings = Settings {
+            index_path: temp.path().join("index"),
+            ..Settings::default()
+        };
+        let root = deps_dir(&settings.index_path);
+        let mk_option = Symbol::new(
+            SymbolId::new(1).unwrap(),
+            "mkOption",
+            SymbolKind::Function,
+            FileId::new(1).unwrap(),
+            Range::new(0, 0, 0, 0),
+        )
+        .with_file_path("lib/options.nix")
+        .with_module_path("lib.options")
+        .with_language_id(LanguageId::new("nix"));
+        write_dependency_index(
+            &root,
+            &settings,
+            DependencyManifest::new("nixpkgs-lib", "nix", "test"),
+            vec![mk_option],
+        )
+        .unwrap();
+        let index = Arc::new(DocumentIndex::new(temp.path().join("project"), &settings).unwrap());
+
+        let linker = DependencyLinker::open(&root, &settings, Arc::clone(&index)).unwrap();
+        let id = linker.link("lib.mkOption", LanguageId::new("nix")).unwrap();
+        assert_eq!(linker.link("mkOption", LanguageId::new("nix")), Some(id));
+        assert_eq!(linker.link("lib.mkOption", LanguageId::new("rust")), None);
+        assert_eq!(linker.flush().unwrap(), 1);
+        index.commit_batch().unwrap();
+
+        let stored = index.find_symbol_by_id(id).unwrap().unwrap();
+        assert_eq!(&*stored.file_path, "nixpkgs-lib:lib/options.nix");
+        assert_eq!(stored.project.as_deref(), Some("nixpkgs-lib"));
+
+        // A later run finds the symbol instead of adding another
+        let linker = DependencyLinker::open(&root, &settings, Arc::clone(&index)).unwrap();
+        assert_eq!(
+            linker.link("lib.mkOption", LanguageId::new("nix")),
+            Some(id)
+        );
+        assert_eq!(linker.flush().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_link_import_looks_in_the_imported_module() {
+        let temp = TempDir::new().unwrap();
+        let settings = Settings {
+            index_path: temp.path().join("index"),
+            ..Settings::default()
+        };
+        let root = deps_dir(&settings.index_path);
+        let session = |module: &str| {
+            Symbol::new(
+                SymbolId::new(1).unwrap(),
+                "Session",
+                SymbolKind::Class,
+                FileId::new(1).unwrap(),
+                Range::new(0, 0, 0, 0),
+            )
+            .with_file_path(format!("{}.py", module.replace('.', "/")))
+            .with_module_path(module)
+            .with_language_id(LanguageId::new("python"))
+        };
+        write_dependency_index(
+            &root,
+            &settings,
+            DependencyManifest::new("pypi-requests", "python", "test"),
+            vec![session("requests.sessions"), session("httpx.sessions")],
+        )
+        .unwrap();
+        let index = Arc::new(DocumentIndex::new(temp.path().join("project"), &settings).unwrap());
+        let linker = DependencyLinker::open(&root, &settings, index).unwrap();
+        let python = LanguageId::new("python");
+        let import = |path: &str, alias: Option<&str>| Import {
+            path: path.to_string(),
+            alias: alias.map(str::to_string),
+            file_id: FileId::new(1).unwrap(),
+            is_glob: false,
+            is_type_only: false,
+            range: None,
+        };
+
+        // Two dependency symbols share the name; the import tells them apart
+        assert_eq!(linker.link("Session", python), None);
+        let id = linker
+            .link_import("Session", &[import("requests.Session", None)], python)
+            .unwrap();
+        assert_eq!(
+            linker.link_import("rq.Session", &[import("requests", Some("rq"))], python),
+            Some(id)
+        );
+        let other = linker
+            .link_import("Session", &[import("httpx.Session", None)], python)
+            .unwrap();
+        assert_ne!(other, id);
+        assert_eq!(
+            linker.link_import("Session", &[import(".sessions.Session", None)], python),
+            None
+        );
+    }
+}
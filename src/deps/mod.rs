@@ -0,0 +1,524 @@
+//! Dependency indexes
+//!
+//! Read-only symbol indexes for code a project uses but does not own, such as
+//! `nixpkgs.lib`, the Rust standard library, or the packages in a virtualenv,
+//! `node_modules`, or the cargo registry. Each dependency lives in its own
+//! Tantivy index under `<index_path>/deps/<name>/` next to a
+//! `dependency.json` manifest, so adding, refreshing, or removing one never
+//! touches the project index.
+//!
+//! Queries that find nothing in the project fall back to [`DependencySet`],
+//! which answers name lookups across every mounted dependency:
+//!
+//! ```text
+//! codanna deps add nixpkgs-lib
+//! codanna deps sync                       # installed Python/npm/Rust packages
+//! codanna retrieve symbol std::collections::HashMap
+//! codanna retrieve symbol lib.mkOption    # resolves to the nixpkgs-lib entry
+//! ```
+//!
+//! Indexing links the calls the project can't resolve itself to dependency
+//! symbols through [`link::DependencyLinker`].
+
+pub mod link;
+pub mod nixpkgs;
+pub mod rust;
+pub mod stubs;
+
+use crate::config::Settings;
+use crate::indexing::get_utc_timestamp;
+use crate::indexing::pipeline::FileRegistration;
+use crate::io::ExitCode;
+use crate::parsing::registry::LanguageId;
+use crate::storage::{DocumentIndex, StorageError};
+use crate::{FileId, Symbol, SymbolId, SymbolKind};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Directory under the index path that holds dependency indexes
+pub const DEPS_DIR: &str = "deps";
+
+const MANIFEST_FILE: &str = "dependency.json";
+
+/// Errors raised while building or loading dependency indexes
+#[derive(Error, Debug)]
+pub enum DependencyError {
+    #[error("Unknown dependency '{name}'\nSuggestion: Available: {available}")]
+    Unknown { name: String, available: String },
+
+    #[error(
+        "Dependency '{name}' is not installed\nSuggestion: Run 'codanna deps list' to see installed dependencies"
+    )]
+    NotInstalled { name: String },
+
+    #[error(
+        "Invalid dependency manifest at {path}: {reason}\nSuggestion: Remove it with 'codanna deps remove' and add it again"
+    )]
+    InvalidManifest { path: PathBuf, reason: String },
+
+    #[error(
+        "Cannot parse {language} declarations: {reason}\nSuggestion: Enable {language} under [languages] in .codanna/settings.toml"
+    )]
+    Parser { language: String, reason: String },
+
+    #[error("IO error: {0}\nSuggestion: Check file permissions and disk space")]
+    Io(#[from] io::Error),
+
+    #[error("Storage error: {0}")]
+    Storage(#[from] StorageError),
+}
+
+/// Result type for dependency operations
+pub type DependencyResult<T> = Result<T, DependencyError>;
+
+impl DependencyError {
+    pub fn exit_code(&self) -> ExitCode {
+        match self {
+            DependencyError::Unknown { .. } | DependencyError::NotInstalled { .. } => {
+                ExitCode::NotFound
+            }
+            DependencyError::InvalidManifest { .. } => ExitCode::IndexCorrupted,
+            DependencyError::Parser { .. } => ExitCode::ConfigError,
+            DependencyError::Io(_) | DependencyError::Storage(_) => ExitCode::IoError,
+        }
+    }
+}
+
+/// Describes one dependency index
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DependencyManifest {
+    /// Directory name and display name, e.g. `nixpkgs-lib`
+    pub name: String,
+    /// Language of the dependency's symbols
+    pub language: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Where the symbols came from (builtin table, directory, ...)
+    pub source: String,
+    #[serde(default)]
+    pub symbol_count: u32,
+    #[serde(default)]
+    pub created_at: u64,
+}
+
+impl DependencyManifest {
+    pub fn new(
+        name: impl Into<String>,
+        language: impl Into<String>,
+        source: impl Into<String>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            language: language.into(),
+            version: None,
+            source: source.into(),
+            symbol_count: 0,
+            created_at: 0,
+        }
+    }
+
+    pub fn with_version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+}
+
+/// Dependencies that ship with codanna and can be added by name
+pub const BUILTIN_DEPENDENCIES: &[&str] = &[nixpkgs::NAME];
+
+/// Directory holding dependency indexes for `index_path`
+pub fn deps_dir(index_path: &Path) -> PathBuf {
+    index_path.join(DEPS_DIR)
+}
+
+/// Write `symbols` into a fresh dependency index under `deps_root`.
+///
+/// Symbol and file IDs are reassigned; each symbol's `file_path` becomes a
+/// file entry of the dependency. An existing index with the same name is
+/// replaced.
+pub fn write_dependency_index(
+    deps_root: &Path,
+    settings: &Settings,
+    mut manifest: DependencyManifest,
+    symbols: Vec<Symbol>,
+) -> DependencyResult<DependencyManifest> {
+    let dir = deps_root.join(&manifest.name);
+    if dir.exists() {
+        fs::remove_dir_all(&dir)?;
+    }
+    let tantivy_path = dir.join("tantivy");
+    fs::create_dir_all(&tantivy_path)?;
+
+    let index = DocumentIndex::new(&tantivy_path, settings)?;
+    index.start_batch()?;
+
+    let mut files: BTreeMap<String, FileId> = BTreeMap::new();
+    let mut next_symbol = 1;
+    for mut symbol in symbols {
+        let next_file = files.len() as u32 + 1;
+        let file_id = *files
+            .entry(symbol.file_path.to_string())
+            .or_insert_with(|| FileId::new(next_file).expect("file ids start at 1"));
+        if file_id.value() == next_file {
+            index.store_file_registration(&FileRegistration {
+                path: PathBuf::from(symbol.file_path.as_ref()),
+                file_id,
+                content_hash: String::new(),
+                language_id: symbol.language_id.unwrap_or(LanguageId::new("unknown")),
+                timestamp: 0,
+                mtime: 0,
+            })?;
+        }
+
+        symbol.id = SymbolId::new(next_symbol).expect("symbol ids start at 1");
+        symbol.file_id = file_id;
+        let path = symbol.file_path.clone();
+        index.index_symbol(&symbol, &path)?;
+        next_symbol += 1;
+    }
+    index.commit_batch()?;
+
+    manifest.symbol_count = next_symbol - 1;
+    manifest.created_at = get_utc_timestamp();
+    let json =
+        serde_json::to_string_pretty(&manifest).map_err(|e| DependencyError::InvalidManifest {
+            path: dir.join(MANIFEST_FILE),
+            reason: e.to_string(),
+        })?;
+    fs::write(dir.join(MANIFEST_FILE), json)?;
+
+    Ok(manifest)
+}
+
+/// Build the index for one of [`BUILTIN_DEPENDENCIES`]
+pub fn install_builtin(
+    deps_root: &Path,
+    settings: &Settings,
+    name: &str,
+) -> DependencyResult<DependencyManifest> {
+    match name {
+        nixpkgs::NAME => {
+            write_dependency_index(deps_root, settings, nixpkgs::manifest(), nixpkgs::symbols())
+        }
+        _ => Err(DependencyError::Unknown {
+            name: name.to_string(),
+            available: BUILTIN_DEPENDENCIES.join(", "),
+        }),
+    }
+}
+
+/// Manifests of every dependency index under `deps_root`, sorted by name
+pub fn list_dependencies(deps_root: &Path) -> Vec<DependencyManifest> {
+    let Ok(entries) = fs::read_dir(deps_root) else {
+        return Vec::new();
+    };
+    let mut manifests: Vec<_> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| match read_manifest(&entry.path()) {
+            Ok(manifest) => Some(manifest),
+            Err(e) => {
+                tracing::warn!(target: "deps", "skipping {}: {e}", entry.path().display());
+                None
+            }
+        })
+        .collect();
+    manifests.sort_by(|a, b| a.name.cmp(&b.name));
+    manifests
+}
+
+/// Delete the dependency index called `name`
+pub fn remove_dependency(deps_root: &Path, name: &str) -> DependencyResult<()> {
+    let dir = deps_root.join(name);
+    if !dir.join(MANIFEST_FILE).exists() {
+        return Err(DependencyError::NotInstalled {
+            name: name.to_string(),
+        });
+    }
+    fs::remove_dir_all(dir)?;
+    Ok(())
+}
+
+fn read_manifest(dir: &Path) -> DependencyResult<DependencyManifest> {
+    let path = dir.join(MANIFEST_FILE);
+    let json = fs::read_to_string(&path)?;
+    serde_json::from_str(&json).map_err(|e| DependencyError::InvalidManifest {
+        path,
+        reason: e.to_string(),
+    })
+}
+
+/// An opened dependency index
+pub struct DependencyIndex {
+    manifest: DependencyManifest,
+    index: DocumentIndex,
+}
+
+impl DependencyIndex {
+    pub fn open(dir: &Path, settings: &Settings) -> DependencyResult<Self> {
+        let manifest = read_manifest(dir)?;
+        let index = DocumentIndex::new(dir.join("tantivy"), settings)?;
+        Ok(Self { manifest, index })
+    }
+
+    pub fn manifest(&self) -> &DependencyManifest {
+        &self.manifest
+    }
+
+    pub fn document_index(&self) -> &DocumentIndex {
+        &self.index
+    }
+}
+
+/// A symbol found in a dependency index
+#[derive(Debug, Clone, Serialize)]
+pub struct ExternalSymbol {
+    /// Name of the dependency that defines the symbol
+    pub dependency: String,
+    pub symbol: Symbol,
+}
+
+impl fmt::Display for ExternalSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbol = &self.symbol;
+        write!(
+            f,
+            "{} ({:?}) [{}]",
+            symbol.name, symbol.kind, self.dependency
+        )?;
+        if let Some(module) = symbol.module_path.as_deref() {
+            write!(f, " in {module}")?;
+        }
+        if !symbol.file_path.is_empty() {
+            write!(f, " - {}", symbol.file_path)?;
+        }
+        if let Some(signature) = symbol.signature.as_deref() {
+            write!(f, "\n  {signature}")?;
+        }
+        if let Some(doc) = symbol.doc_comment.as_deref() {
+            write!(f, "\n  {doc}")?;
+        }
+        Ok(())
+    }
+}
+
+/// All dependency indexes available to a project
+#[derive(Default)]
+pub struct DependencySet {
+    indexes: Vec<DependencyIndex>,
+}
+
+impl DependencySet {
+    /// Open every dependency under `deps_root`; broken ones are skipped
+    pub fn open(deps_root: &Path, settings: &Settings) -> Self {
+        let indexes = list_dependencies(deps_root)
+            .into_iter()
+            .filter_map(|manifest| {
+                match DependencyIndex::open(&deps_root.join(&manifest.name), settings) {
+                    Ok(index) => Some(index),
+                    Err(e) => {
+                        tracing::warn!(target: "deps", "failed to open {}: {e}", manifest.name);
+                        None
+                    }
+                }
+            })
+            .collect();
+        Self { indexes }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indexes.is_empty()
+    }
+
+    pub fn indexes(&self) -> &[DependencyIndex] {
+        &self.indexes
+    }
+
+    /// Resolve a possibly qualified name such as `lib.mkOption`,
+    /// `lib.strings.optionalString`, or `std::collections::HashMap`.
+    ///
+    /// The last path segment is looked up by name. When the qualifier matches
+    /// some candidates' module paths only those are returned; otherwise every
+    /// candidate is, since callers often reach a symbol through a re-export.
+    pub fn resolve(&self, name: &str, language: Option<&str>) -> Vec<ExternalSymbol> {
+        let (qualifier, base) = split_qualified(name);
+        let found = self.lookup(base, language);
+
+        if let Some(qualifier) = qualifier {
+            let matching: Vec<_> = found
+                .iter()
+                .filter(|ext| {
+                    ext.symbol
+                        .module_path
+                        .as_deref()
+                        .is_some_and(|module| qualifier_matches(qualifier, module))
+                })
+                .cloned()
+                .collect();
+            if !matching.is_empty() {
+                return matching;
+            }
+        }
+        found
+    }
+
+    /// Symbols called `name` that `module` provides, directly or as a
+    /// submodule: what `from module import name` or
+    /// `import { name } from "module"` can bind
+    pub fn resolve_in(
+        &self,
+        module: &str,
+        name: &str,
+        language: Option<&str>,
+    ) -> Vec<ExternalSymbol> {
+        self.lookup(name, language)
+            .into_iter()
+            .filter(|ext| {
+                ext.symbol
+                    .module_path
+                    .as_deref()
+                    .is_some_and(|path| qualifier_matches(module, path))
+            })
+            .collect()
+    }
+
+    /// Symbols called exactly `name` across the dependencies of `language`
+    fn lookup(&self, name: &str, language: Option<&str>) -> Vec<ExternalSymbol> {
+        let mut found = Vec::new();
+        for dependency in &self.indexes {
+            if language.is_some_and(|lang| !dependency.manifest.language.eq_ignore_ascii_case(lang))
+            {
+                continue;
+            }
+            let symbols = dependency
+                .index
+                .find_symbols_by_name(name, None)
+                .unwrap_or_default();
+            found.extend(symbols.into_iter().map(|symbol| ExternalSymbol {
+                dependency: dependency.manifest.name.clone(),
+                symbol,
+            }));
+        }
+        found
+    }
+
+    /// Resolve and keep only symbols of `kind`
+    pub fn resolve_kind(
+        &self,
+        name: &str,
+        kind: SymbolKind,
+        language: Option<&str>,
+    ) -> Vec<ExternalSymbol> {
+        self.resolve(name, language)
+            .into_iter()
+            .filter(|ext| ext.symbol.kind == kind)
+            .collect()
+    }
+}
+
+/// Split `a.b.c` or `a::b::c` into (`Some("a.b")`, `"c"`)
+fn split_qualified(name: &str) -> (Option<&str>, &str) {
+    if let Some((qualifier, base)) = name.rsplit_once("::") {
+        return (Some(qualifier), base);
+    }
+    match name.rsplit_once('.') {
+        Some((qualifier, base)) if !base.is_empty() => (Some(qualifier), base),
+        _ => (None, name),
+    }
+}
+
+/// A qualifier matches a module path when one is a prefix of the other at a
+/// separator, so `lib` matches `lib.strings`, `react` matches
+/// `react/jsx-runtime`, and `std::collections::hash_map` matches
+/// `std::collections`
+fn qualifier_matches(qualifier: &str, module: &str) -> bool {
+    let at_boundary = |long: &str, short: &str| {
+        long == short
+            || long
+                .strip_prefix(short)
+                .is_some_and(|rest| rest.starts_with(['.', '/']) || rest.starts_with("::"))
+    };
+    at_boundary(module, qualifier) || at_boundary(qualifier, module)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Range;
+    use tempfile::TempDir;
+
+    fn symbol(name: &str, module: &str, file: &str) -> Symbol {
+        Symbol::new(
+            SymbolId::new(99).unwrap(),
+            name,
+            SymbolKind::Function,
+            FileId::new(99).unwrap(),
+            Range::new(0, 0, 0, 0),
+        )
+        .with_file_path(file)
+        .with_module_path(module)
+        .with_language_id(LanguageId::new("nix"))
+    }
+
+    #[test]
+    fn test_write_list_resolve_remove() {
+        let temp = TempDir::new().unwrap();
+        let settings = Settings {
+            index_path: temp.path().join("index"),
+            ..Settings::default()
+        };
+        let root = deps_dir(&settings.index_path);
+
+        let manifest = write_dependency_index(
+            &root,
+            &settings,
+            DependencyManifest::new("demo", "nix", "test"),
+            vec![
+                symbol("optionalString", "lib.strings", "lib/strings.nix"),
+                symbol("optional", "lib.lists", "lib/lists.nix"),
+                symbol("concat", "lib.strings", "lib/strings.nix"),
+            ],
+        )
+        .unwrap();
+        assert_eq!(manifest.symbol_count, 3);
+        assert_eq!(list_dependencies(&root), vec![manifest]);
+
+        let deps = DependencySet::open(&root, &settings);
+        let found = deps.resolve("lib.optionalString", Some("nix"));
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].dependency, "demo");
+        assert_eq!(found[0].symbol.module_path.as_deref(), Some("lib.strings"));
+        assert!(deps.resolve("optional", Some("rust")).is_empty());
+        drop(deps);
+
+        remove_dependency(&root, "demo").unwrap();
+        assert!(list_dependencies(&root).is_empty());
+        assert!(matches!(
+            remove_dependency(&root, "demo"),
+            Err(DependencyError::NotInstalled { .. })
+        ));
+    }
+
+    #[test]
+    fn test_qualified_names() {
+        assert_eq!(split_qualified("lib.mkOption"), (Some("lib"), "mkOption"));
+        assert_eq!(
+            split_qualified("std::collections::HashMap"),
+            (Some("std::collections"), "HashMap")
+        );
+        assert_eq!(split_qualified("mkIf"), (None, "mkIf"));
+
+        assert!(qualifier_matches("lib", "lib.strings"));
+        assert!(qualifier_matches("lib.strings", "lib.strings"));
+        assert!(qualifier_matches(
+            "std::collections::hash_map",
+            "std::collections"
+        ));
+        assert!(qualifier_matches("react", "react/jsx-runtime"));
+        assert!(!qualifier_matches("lib.str", "lib.strings"));
+    }
+}
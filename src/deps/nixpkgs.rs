@@ -0,0 +1,1294 @@
+//! Built-in knowledge base for `nixpkgs.lib`
+//!
+//! A curated table of the commonly used library functions with their type
+//! signatures (in the `name :: type` notation of the nixpkgs manual) and a
+//! short description. `codanna deps add nixpkgs-lib` writes it into a
+//! dependency index, so `lib.mkOption`, `lib.strings.optionalString`, or a
+//! bare `mapAttrs` resolve to documented symbols.
+
+use super::DependencyManifest;
+use crate::parsing::registry::LanguageId;
+use crate::{FileId, Range, Symbol, SymbolId, SymbolKind, Visibility};
+
+/// Dependency name used for the nixpkgs library table
+pub const NAME: &str = "nixpkgs-lib";
+
+/// nixpkgs release the signatures were checked against
+pub const VERSION: &str = "24.11";
+
+/// (name, attribute path, source file, signature, description)
+type Entry = (
+    &'static str,
+    &'static str,
+    &'static str,
+    &'static str,
+    &'static str,
+);
+
+const LIB: &[Entry] = &[
+    // lib.options
+    (
+        "mkOption",
+        "lib.options",
+        "lib/options.nix",
+        "mkOption :: { type?, default?, defaultText?, example?, description?, apply?, internal?, visible?, readOnly? } -> Option",
+        "Declare a module option.",
+    ),
+    (
+        "mkEnableOption",
+        "lib.options",
+        "lib/options.nix",
+        "mkEnableOption :: String -> Option",
+        "Boolean option defaulting to false, described as \"Whether to enable <name>.\"",
+    ),
+    (
+        "mkPackageOption",
+        "lib.options",
+        "lib/options.nix",
+        "mkPackageOption :: AttrSet -> (String | [String]) -> AttrSet -> Option",
+        "Option holding a package, defaulting to the named attribute of the package set.",
+    ),
+    (
+        "literalExpression",
+        "lib.options",
+        "lib/options.nix",
+        "literalExpression :: String -> AttrSet",
+        "Render a Nix expression verbatim in option documentation (for defaultText and example).",
+    ),
+    (
+        "literalMD",
+        "lib.options",
+        "lib/options.nix",
+        "literalMD :: String -> AttrSet",
+        "Render Markdown in option documentation for defaultText and example.",
+    ),
+    (
+        "mergeEqualOption",
+        "lib.options",
+        "lib/options.nix",
+        "mergeEqualOption :: [String] -> [AttrSet] -> Any",
+        "Merge function that requires all definitions to be equal.",
+    ),
+    (
+        "showOption",
+        "lib.options",
+        "lib/options.nix",
+        "showOption :: [String] -> String",
+        "Format an option path as a dotted string for messages.",
+    ),
+    // lib.modules
+    (
+        "mkIf",
+        "lib.modules",
+        "lib/modules.nix",
+        "mkIf :: Bool -> a -> Definition",
+        "Apply a definition only when the condition holds; evaluated lazily so it may refer to config.",
+    ),
+    (
+        "mkMerge",
+        "lib.modules",
+        "lib/modules.nix",
+        "mkMerge :: [Definition] -> Definition",
+        "Combine several definitions of the same option.",
+    ),
+    (
+        "mkDefault",
+        "lib.modules",
+        "lib/modules.nix",
+        "mkDefault :: a -> Definition",
+        "Definition with priority 1000, overridden by any plain definition.",
+    ),
+    (
+        "mkForce",
+        "lib.modules",
+        "lib/modules.nix",
+        "mkForce :: a -> Definition",
+        "Definition with priority 50, overriding plain definitions.",
+    ),
+    (
+        "mkOverride",
+        "lib.modules",
+        "lib/modules.nix",
+        "mkOverride :: Int -> a -> Definition",
+        "Definition with an explicit priority; lower numbers win.",
+    ),
+    (
+        "mkOptionDefault",
+        "lib.modules",
+        "lib/modules.nix",
+        "mkOptionDefault :: a -> Definition",
+        "Definition with priority 1500, the priority of option defaults.",
+    ),
+    (
+        "mkBefore",
+        "lib.modules",
+        "lib/modules.nix",
+        "mkBefore :: a -> Definition",
+        "Order a list or lines definition before others (order 500).",
+    ),
+    (
+        "mkAfter",
+        "lib.modules",
+        "lib/modules.nix",
+        "mkAfter :: a -> Definition",
+        "Order a list or lines definition after others (order 1500).",
+    ),
+    (
+        "mkOrder",
+        "lib.modules",
+        "lib/modules.nix",
+        "mkOrder :: Int -> a -> Definition",
+        "Order a list or lines definition explicitly.",
+    ),
+    (
+        "mkRenamedOptionModule",
+        "lib.modules",
+        "lib/modules.nix",
+        "mkRenamedOptionModule :: [String] -> [String] -> Module",
+        "Module forwarding a renamed option with a deprecation warning.",
+    ),
+    (
+        "mkRemovedOptionModule",
+        "lib.modules",
+        "lib/modules.nix",
+        "mkRemovedOptionModule :: [String] -> String -> Module",
+        "Module that fails with the given message when a removed option is set.",
+    ),
+    (
+        "mkAliasOptionModule",
+        "lib.modules",
+        "lib/modules.nix",
+        "mkAliasOptionModule :: [String] -> [String] -> Module",
+        "Module making one option an alias of another.",
+    ),
+    (
+        "evalModules",
+        "lib.modules",
+        "lib/modules.nix",
+        "evalModules :: { modules, specialArgs?, class?, prefix? } -> { config, options, ... }",
+        "Evaluate a list of modules into a configuration.",
+    ),
+    // lib.attrsets
+    (
+        "mapAttrs'",
+        "lib.attrsets",
+        "lib/attrsets.nix",
+        "mapAttrs' :: (String -> Any -> { name :: String; value :: Any; }) -> AttrSet -> AttrSet",
+        "Map names and values; the function returns a nameValuePair.",
+    ),
+    (
+        "mapAttrsToList",
+        "lib.attrsets",
+        "lib/attrsets.nix",
+        "mapAttrsToList :: (String -> a -> b) -> AttrSet -> [b]",
+        "Call a function for each attribute and collect the results in a list.",
+    ),
+    (
+        "mapAttrsRecursive",
+        "lib.attrsets",
+        "lib/attrsets.nix",
+        "mapAttrsRecursive :: ([String] -> a -> b) -> AttrSet -> AttrSet",
+        "Map over leaf values of nested attribute sets, passing the attribute path.",
+    ),
+    (
+        "filterAttrs",
+        "lib.attrsets",
+        "lib/attrsets.nix",
+        "filterAttrs :: (String -> Any -> Bool) -> AttrSet -> AttrSet",
+        "Keep the attributes for which the predicate holds.",
+    ),
+    (
+        "filterAttrsRecursive",
+        "lib.attrsets",
+        "lib/attrsets.nix",
+        "filterAttrsRecursive :: (String -> Any -> Bool) -> AttrSet -> AttrSet",
+        "Filter attributes at every nesting level.",
+    ),
+    (
+        "attrByPath",
+        "lib.attrsets",
+        "lib/attrsets.nix",
+        "attrByPath :: [String] -> Any -> AttrSet -> Any",
+        "Look up a nested attribute path, returning the default when missing.",
+    ),
+    (
+        "hasAttrByPath",
+        "lib.attrsets",
+        "lib/attrsets.nix",
+        "hasAttrByPath :: [String] -> AttrSet -> Bool",
+        "Whether a nested attribute path exists.",
+    ),
+    (
+        "setAttrByPath",
+        "lib.attrsets",
+        "lib/attrsets.nix",
+        "setAttrByPath :: [String] -> Any -> AttrSet",
+        "Create a nested attribute set holding a value at the path.",
+    ),
+    (
+        "getAttrFromPath",
+        "lib.attrsets",
+        "lib/attrsets.nix",
+        "getAttrFromPath :: [String] -> AttrSet -> Any",
+        "Look up a nested attribute path, throwing when missing.",
+    ),
+    (
+        "genAttrs",
+        "lib.attrsets",
+        "lib/attrsets.nix",
+        "genAttrs :: [String] -> (String -> Any) -> AttrSet",
+        "Build an attribute set from names and a function of the name.",
+    ),
+    (
+        "nameValuePair",
+        "lib.attrsets",
+        "lib/attrsets.nix",
+        "nameValuePair :: String -> Any -> { name :: String; value :: Any; }",
+        "Construct a name/value pair for listToAttrs and mapAttrs'.",
+    ),
+    (
+        "recursiveUpdate",
+        "lib.attrsets",
+        "lib/attrsets.nix",
+        "recursiveUpdate :: AttrSet -> AttrSet -> AttrSet",
+        "Merge attribute sets recursively; the right side wins at leaves.",
+    ),
+    (
+        "optionalAttrs",
+        "lib.attrsets",
+        "lib/attrsets.nix",
+        "optionalAttrs :: Bool -> AttrSet -> AttrSet",
+        "Return the attribute set when the condition holds, otherwise {}.",
+    ),
+    (
+        "foldlAttrs",
+        "lib.attrsets",
+        "lib/attrsets.nix",
+        "foldlAttrs :: (Any -> String -> Any -> Any) -> Any -> AttrSet -> Any",
+        "Left fold over attributes in name order.",
+    ),
+    (
+        "zipAttrsWith",
+        "lib.attrsets",
+        "lib/attrsets.nix",
+        "zipAttrsWith :: (String -> [Any] -> Any) -> [AttrSet] -> AttrSet",
+        "Merge a list of attribute sets, combining values per name.",
+    ),
+    (
+        "catAttrs",
+        "lib.attrsets",
+        "lib/attrsets.nix",
+        "catAttrs :: String -> [AttrSet] -> [Any]",
+        "Collect one attribute from each set that has it.",
+    ),
+    (
+        "collect",
+        "lib.attrsets",
+        "lib/attrsets.nix",
+        "collect :: (AttrSet -> Bool) -> AttrSet -> [Any]",
+        "Recursively collect values matching a predicate.",
+    ),
+    (
+        "attrsToList",
+        "lib.attrsets",
+        "lib/attrsets.nix",
+        "attrsToList :: AttrSet -> [{ name :: String; value :: Any; }]",
+        "Convert an attribute set to a list of name/value pairs.",
+    ),
+    (
+        "getAttrs",
+        "lib.attrsets",
+        "lib/attrsets.nix",
+        "getAttrs :: [String] -> AttrSet -> AttrSet",
+        "Select the named attributes.",
+    ),
+    (
+        "isDerivation",
+        "lib.attrsets",
+        "lib/attrsets.nix",
+        "isDerivation :: Any -> Bool",
+        "Whether a value is a derivation.",
+    ),
+    (
+        "getBin",
+        "lib.attrsets",
+        "lib/attrsets.nix",
+        "getBin :: Derivation -> Derivation",
+        "The bin output of a package, falling back to out.",
+    ),
+    (
+        "getLib",
+        "lib.attrsets",
+        "lib/attrsets.nix",
+        "getLib :: Derivation -> Derivation",
+        "The lib output of a package, falling back to out.",
+    ),
+    (
+        "getDev",
+        "lib.attrsets",
+        "lib/attrsets.nix",
+        "getDev :: Derivation -> Derivation",
+        "The dev output of a package, falling back to out.",
+    ),
+    // lib.lists
+    (
+        "optional",
+        "lib.lists",
+        "lib/lists.nix",
+        "optional :: Bool -> a -> [a]",
+        "A singleton list when the condition holds, otherwise [].",
+    ),
+    (
+        "optionals",
+        "lib.lists",
+        "lib/lists.nix",
+        "optionals :: Bool -> [a] -> [a]",
+        "The list when the condition holds, otherwise [].",
+    ),
+    (
+        "flatten",
+        "lib.lists",
+        "lib/lists.nix",
+        "flatten :: [Any] -> [Any]",
+        "Flatten arbitrarily nested lists.",
+    ),
+    (
+        "unique",
+        "lib.lists",
+        "lib/lists.nix",
+        "unique :: [a] -> [a]",
+        "Remove duplicates, keeping the first occurrence.",
+    ),
+    (
+        "foldl'",
+        "lib.lists",
+        "lib/lists.nix",
+        "foldl' :: (acc -> x -> acc) -> acc -> [x] -> acc",
+        "Strict left fold.",
+    ),
+    (
+        "foldr",
+        "lib.lists",
+        "lib/lists.nix",
+        "foldr :: (x -> acc -> acc) -> acc -> [x] -> acc",
+        "Right fold.",
+    ),
+    (
+        "imap0",
+        "lib.lists",
+        "lib/lists.nix",
+        "imap0 :: (Int -> a -> b) -> [a] -> [b]",
+        "Map with a zero-based index.",
+    ),
+    (
+        "imap1",
+        "lib.lists",
+        "lib/lists.nix",
+        "imap1 :: (Int -> a -> b) -> [a] -> [b]",
+        "Map with a one-based index.",
+    ),
+    (
+        "range",
+        "lib.lists",
+        "lib/lists.nix",
+        "range :: Int -> Int -> [Int]",
+        "Integers from first to last, inclusive.",
+    ),
+    (
+        "last",
+        "lib.lists",
+        "lib/lists.nix",
+        "last :: [a] -> a",
+        "The last element; throws on an empty list.",
+    ),
+    (
+        "init",
+        "lib.lists",
+        "lib/lists.nix",
+        "init :: [a] -> [a]",
+        "All elements but the last.",
+    ),
+    (
+        "take",
+        "lib.lists",
+        "lib/lists.nix",
+        "take :: Int -> [a] -> [a]",
+        "The first n elements.",
+    ),
+    (
+        "drop",
+        "lib.lists",
+        "lib/lists.nix",
+        "drop :: Int -> [a] -> [a]",
+        "All but the first n elements.",
+    ),
+    (
+        "findFirst",
+        "lib.lists",
+        "lib/lists.nix",
+        "findFirst :: (a -> Bool) -> a -> [a] -> a",
+        "First element matching the predicate, or the default.",
+    ),
+    (
+        "count",
+        "lib.lists",
+        "lib/lists.nix",
+        "count :: (a -> Bool) -> [a] -> Int",
+        "Number of elements matching the predicate.",
+    ),
+    (
+        "remove",
+        "lib.lists",
+        "lib/lists.nix",
+        "remove :: a -> [a] -> [a]",
+        "Remove every occurrence of a value.",
+    ),
+    (
+        "toList",
+        "lib.lists",
+        "lib/lists.nix",
+        "toList :: a -> [a]",
+        "Wrap a non-list value in a list; lists are returned unchanged.",
+    ),
+    (
+        "singleton",
+        "lib.lists",
+        "lib/lists.nix",
+        "singleton :: a -> [a]",
+        "A one-element list.",
+    ),
+    (
+        "reverseList",
+        "lib.lists",
+        "lib/lists.nix",
+        "reverseList :: [a] -> [a]",
+        "The list in reverse order.",
+    ),
+    (
+        "zipLists",
+        "lib.lists",
+        "lib/lists.nix",
+        "zipLists :: [a] -> [b] -> [{ fst :: a; snd :: b; }]",
+        "Pair up elements of two lists.",
+    ),
+    (
+        "subtractLists",
+        "lib.lists",
+        "lib/lists.nix",
+        "subtractLists :: [a] -> [a] -> [a]",
+        "Elements of the second list not in the first.",
+    ),
+    (
+        "intersectLists",
+        "lib.lists",
+        "lib/lists.nix",
+        "intersectLists :: [a] -> [a] -> [a]",
+        "Elements present in both lists.",
+    ),
+    (
+        "groupBy",
+        "lib.lists",
+        "lib/lists.nix",
+        "groupBy :: (a -> String) -> [a] -> AttrSet",
+        "Group elements into an attribute set keyed by the function result.",
+    ),
+    (
+        "sort",
+        "lib.lists",
+        "lib/lists.nix",
+        "sort :: (a -> a -> Bool) -> [a] -> [a]",
+        "Stable sort with a less-than comparator.",
+    ),
+    (
+        "elemAt",
+        "lib.lists",
+        "lib/lists.nix",
+        "elemAt :: [a] -> Int -> a",
+        "Element at a zero-based index.",
+    ),
+    (
+        "concatMap",
+        "lib.lists",
+        "lib/lists.nix",
+        "concatMap :: (a -> [b]) -> [a] -> [b]",
+        "Map then concatenate the resulting lists.",
+    ),
+    (
+        "partition",
+        "lib.lists",
+        "lib/lists.nix",
+        "partition :: (a -> Bool) -> [a] -> { right :: [a]; wrong :: [a]; }",
+        "Split a list by a predicate.",
+    ),
+    // lib.strings
+    (
+        "optionalString",
+        "lib.strings",
+        "lib/strings.nix",
+        "optionalString :: Bool -> String -> String",
+        "The string when the condition holds, otherwise \"\".",
+    ),
+    (
+        "concatStrings",
+        "lib.strings",
+        "lib/strings.nix",
+        "concatStrings :: [String] -> String",
+        "Concatenate a list of strings.",
+    ),
+    (
+        "concatStringsSep",
+        "lib.strings",
+        "lib/strings.nix",
+        "concatStringsSep :: String -> [String] -> String",
+        "Concatenate strings with a separator.",
+    ),
+    (
+        "concatMapStrings",
+        "lib.strings",
+        "lib/strings.nix",
+        "concatMapStrings :: (a -> String) -> [a] -> String",
+        "Map to strings and concatenate.",
+    ),
+    (
+        "concatMapStringsSep",
+        "lib.strings",
+        "lib/strings.nix",
+        "concatMapStringsSep :: String -> (a -> String) -> [a] -> String",
+        "Map to strings and concatenate with a separator.",
+    ),
+    (
+        "concatLines",
+        "lib.strings",
+        "lib/strings.nix",
+        "concatLines :: [String] -> String",
+        "Join strings with a trailing newline after each.",
+    ),
+    (
+        "splitString",
+        "lib.strings",
+        "lib/strings.nix",
+        "splitString :: String -> String -> [String]",
+        "Split a string on a literal separator.",
+    ),
+    (
+        "hasPrefix",
+        "lib.strings",
+        "lib/strings.nix",
+        "hasPrefix :: String -> String -> Bool",
+        "Whether the second string starts with the first.",
+    ),
+    (
+        "hasSuffix",
+        "lib.strings",
+        "lib/strings.nix",
+        "hasSuffix :: String -> String -> Bool",
+        "Whether the second string ends with the first.",
+    ),
+    (
+        "hasInfix",
+        "lib.strings",
+        "lib/strings.nix",
+        "hasInfix :: String -> String -> Bool",
+        "Whether the second string contains the first.",
+    ),
+    (
+        "removePrefix",
+        "lib.strings",
+        "lib/strings.nix",
+        "removePrefix :: String -> String -> String",
+        "Strip a prefix if present.",
+    ),
+    (
+        "removeSuffix",
+        "lib.strings",
+        "lib/strings.nix",
+        "removeSuffix :: String -> String -> String",
+        "Strip a suffix if present.",
+    ),
+    (
+        "toUpper",
+        "lib.strings",
+        "lib/strings.nix",
+        "toUpper :: String -> String",
+        "Convert ASCII letters to upper case.",
+    ),
+    (
+        "toLower",
+        "lib.strings",
+        "lib/strings.nix",
+        "toLower :: String -> String",
+        "Convert ASCII letters to lower case.",
+    ),
+    (
+        "trim",
+        "lib.strings",
+        "lib/strings.nix",
+        "trim :: String -> String",
+        "Remove leading and trailing whitespace.",
+    ),
+    (
+        "escapeShellArg",
+        "lib.strings",
+        "lib/strings.nix",
+        "escapeShellArg :: String -> String",
+        "Quote a string for safe use as one shell argument.",
+    ),
+    (
+        "escapeShellArgs",
+        "lib.strings",
+        "lib/strings.nix",
+        "escapeShellArgs :: [String] -> String",
+        "Quote each string and join with spaces.",
+    ),
+    (
+        "makeBinPath",
+        "lib.strings",
+        "lib/strings.nix",
+        "makeBinPath :: [Derivation] -> String",
+        "PATH-style string of the packages' bin directories.",
+    ),
+    (
+        "makeLibraryPath",
+        "lib.strings",
+        "lib/strings.nix",
+        "makeLibraryPath :: [Derivation] -> String",
+        "Colon-separated lib directories of the packages.",
+    ),
+    (
+        "makeSearchPath",
+        "lib.strings",
+        "lib/strings.nix",
+        "makeSearchPath :: String -> [String] -> String",
+        "Colon-separated subdirectory of each path.",
+    ),
+    (
+        "fixedWidthString",
+        "lib.strings",
+        "lib/strings.nix",
+        "fixedWidthString :: Int -> String -> String -> String",
+        "Left-pad a string to a width with a filler.",
+    ),
+    (
+        "versionOlder",
+        "lib.strings",
+        "lib/strings.nix",
+        "versionOlder :: String -> String -> Bool",
+        "Whether the first version is older than the second.",
+    ),
+    (
+        "versionAtLeast",
+        "lib.strings",
+        "lib/strings.nix",
+        "versionAtLeast :: String -> String -> Bool",
+        "Whether the first version is at least the second.",
+    ),
+    (
+        "getName",
+        "lib.strings",
+        "lib/strings.nix",
+        "getName :: (String | Derivation) -> String",
+        "Package name without the version.",
+    ),
+    (
+        "getVersion",
+        "lib.strings",
+        "lib/strings.nix",
+        "getVersion :: (String | Derivation) -> String",
+        "Version part of a package name.",
+    ),
+    (
+        "toInt",
+        "lib.strings",
+        "lib/strings.nix",
+        "toInt :: String -> Int",
+        "Parse a decimal integer, throwing on invalid input.",
+    ),
+    (
+        "stringToCharacters",
+        "lib.strings",
+        "lib/strings.nix",
+        "stringToCharacters :: String -> [String]",
+        "Split a string into single characters.",
+    ),
+    (
+        "enableFeature",
+        "lib.strings",
+        "lib/strings.nix",
+        "enableFeature :: Bool -> String -> String",
+        "--enable-<feature> or --disable-<feature> configure flag.",
+    ),
+    (
+        "withFeature",
+        "lib.strings",
+        "lib/strings.nix",
+        "withFeature :: Bool -> String -> String",
+        "--with-<feature> or --without-<feature> configure flag.",
+    ),
+    (
+        "cmakeFeature",
+        "lib.strings",
+        "lib/strings.nix",
+        "cmakeFeature :: String -> String -> String",
+        "-D<name>:STRING=<value> CMake flag.",
+    ),
+    (
+        "cmakeBool",
+        "lib.strings",
+        "lib/strings.nix",
+        "cmakeBool :: String -> Bool -> String",
+        "-D<name>:BOOL=TRUE|FALSE CMake flag.",
+    ),
+    (
+        "mesonOption",
+        "lib.strings",
+        "lib/strings.nix",
+        "mesonOption :: String -> String -> String",
+        "-D<name>=<value> Meson flag.",
+    ),
+    (
+        "mesonBool",
+        "lib.strings",
+        "lib/strings.nix",
+        "mesonBool :: String -> Bool -> String",
+        "-D<name>=true|false Meson flag.",
+    ),
+    // lib.trivial
+    (
+        "id",
+        "lib.trivial",
+        "lib/trivial.nix",
+        "id :: a -> a",
+        "Identity function.",
+    ),
+    (
+        "const",
+        "lib.trivial",
+        "lib/trivial.nix",
+        "const :: a -> b -> a",
+        "Ignore the second argument.",
+    ),
+    (
+        "flip",
+        "lib.trivial",
+        "lib/trivial.nix",
+        "flip :: (a -> b -> c) -> b -> a -> c",
+        "Swap the first two arguments of a function.",
+    ),
+    (
+        "pipe",
+        "lib.trivial",
+        "lib/trivial.nix",
+        "pipe :: a -> [(a -> a)] -> a",
+        "Feed a value through a list of functions in order.",
+    ),
+    (
+        "warn",
+        "lib.trivial",
+        "lib/trivial.nix",
+        "warn :: String -> a -> a",
+        "Print a warning during evaluation and return the value.",
+    ),
+    (
+        "warnIf",
+        "lib.trivial",
+        "lib/trivial.nix",
+        "warnIf :: Bool -> String -> a -> a",
+        "Warn when the condition holds.",
+    ),
+    (
+        "throwIf",
+        "lib.trivial",
+        "lib/trivial.nix",
+        "throwIf :: Bool -> String -> a -> a",
+        "Throw when the condition holds.",
+    ),
+    (
+        "throwIfNot",
+        "lib.trivial",
+        "lib/trivial.nix",
+        "throwIfNot :: Bool -> String -> a -> a",
+        "Throw unless the condition holds.",
+    ),
+    (
+        "boolToString",
+        "lib.trivial",
+        "lib/trivial.nix",
+        "boolToString :: Bool -> String",
+        "\"true\" or \"false\".",
+    ),
+    (
+        "min",
+        "lib.trivial",
+        "lib/trivial.nix",
+        "min :: a -> a -> a",
+        "The smaller of two values.",
+    ),
+    (
+        "max",
+        "lib.trivial",
+        "lib/trivial.nix",
+        "max :: a -> a -> a",
+        "The larger of two values.",
+    ),
+    (
+        "importJSON",
+        "lib.trivial",
+        "lib/trivial.nix",
+        "importJSON :: Path -> Any",
+        "Read and parse a JSON file.",
+    ),
+    (
+        "importTOML",
+        "lib.trivial",
+        "lib/trivial.nix",
+        "importTOML :: Path -> Any",
+        "Read and parse a TOML file.",
+    ),
+    (
+        "functionArgs",
+        "lib.trivial",
+        "lib/trivial.nix",
+        "functionArgs :: (a -> b) -> AttrSet",
+        "Formal arguments of a function, mapping names to whether they have defaults.",
+    ),
+    (
+        "setFunctionArgs",
+        "lib.trivial",
+        "lib/trivial.nix",
+        "setFunctionArgs :: (a -> b) -> AttrSet -> (a -> b)",
+        "Attach formal argument metadata to a function.",
+    ),
+    (
+        "toFunction",
+        "lib.trivial",
+        "lib/trivial.nix",
+        "toFunction :: (a | (b -> a)) -> (b -> a)",
+        "Wrap a non-function value in a constant function.",
+    ),
+    // lib.fixedPoints
+    (
+        "fix",
+        "lib.fixedPoints",
+        "lib/fixed-points.nix",
+        "fix :: (a -> a) -> a",
+        "Fixed point of a function.",
+    ),
+    (
+        "extends",
+        "lib.fixedPoints",
+        "lib/fixed-points.nix",
+        "extends :: (final -> prev -> AttrSet) -> (final -> AttrSet) -> final -> AttrSet",
+        "Apply an overlay to a fixed-point function.",
+    ),
+    (
+        "composeExtensions",
+        "lib.fixedPoints",
+        "lib/fixed-points.nix",
+        "composeExtensions :: Overlay -> Overlay -> Overlay",
+        "Compose two overlays.",
+    ),
+    (
+        "composeManyExtensions",
+        "lib.fixedPoints",
+        "lib/fixed-points.nix",
+        "composeManyExtensions :: [Overlay] -> Overlay",
+        "Compose a list of overlays.",
+    ),
+    (
+        "makeExtensible",
+        "lib.fixedPoints",
+        "lib/fixed-points.nix",
+        "makeExtensible :: (AttrSet -> AttrSet) -> AttrSet",
+        "Fixed point with an extend function for overlays.",
+    ),
+    // lib.customisation
+    (
+        "makeOverridable",
+        "lib.customisation",
+        "lib/customisation.nix",
+        "makeOverridable :: (AttrSet -> a) -> AttrSet -> a",
+        "Add .override to the result of a function.",
+    ),
+    (
+        "callPackageWith",
+        "lib.customisation",
+        "lib/customisation.nix",
+        "callPackageWith :: AttrSet -> (Path | Function) -> AttrSet -> a",
+        "Call a package function, filling arguments from the scope.",
+    ),
+    (
+        "callPackagesWith",
+        "lib.customisation",
+        "lib/customisation.nix",
+        "callPackagesWith :: AttrSet -> (Path | Function) -> AttrSet -> AttrSet",
+        "callPackageWith for functions returning several packages.",
+    ),
+    (
+        "overrideDerivation",
+        "lib.customisation",
+        "lib/customisation.nix",
+        "overrideDerivation :: Derivation -> (AttrSet -> AttrSet) -> Derivation",
+        "Override derivation attributes after mkDerivation; prefer overrideAttrs.",
+    ),
+    (
+        "makeScope",
+        "lib.customisation",
+        "lib/customisation.nix",
+        "makeScope :: (AttrSet -> (Path | Function) -> AttrSet -> a) -> (AttrSet -> AttrSet) -> AttrSet",
+        "Package set with its own callPackage, overrideScope, and packages.",
+    ),
+    // lib.meta
+    (
+        "getExe",
+        "lib.meta",
+        "lib/meta.nix",
+        "getExe :: Derivation -> String",
+        "Path of the package's main program (meta.mainProgram).",
+    ),
+    (
+        "getExe'",
+        "lib.meta",
+        "lib/meta.nix",
+        "getExe' :: Derivation -> String -> String",
+        "Path of a named program in the package's bin directory.",
+    ),
+    (
+        "lowPrio",
+        "lib.meta",
+        "lib/meta.nix",
+        "lowPrio :: Derivation -> Derivation",
+        "Lower the package's install priority.",
+    ),
+    (
+        "hiPrio",
+        "lib.meta",
+        "lib/meta.nix",
+        "hiPrio :: Derivation -> Derivation",
+        "Raise the package's install priority.",
+    ),
+    (
+        "addMetaAttrs",
+        "lib.meta",
+        "lib/meta.nix",
+        "addMetaAttrs :: AttrSet -> Derivation -> Derivation",
+        "Merge attributes into the package's meta.",
+    ),
+    // lib.sources and lib.fileset
+    (
+        "cleanSource",
+        "lib.sources",
+        "lib/sources.nix",
+        "cleanSource :: Path -> Path",
+        "Filter VCS directories, editor backups, and build results from a source.",
+    ),
+    (
+        "cleanSourceWith",
+        "lib.sources",
+        "lib/sources.nix",
+        "cleanSourceWith :: { src, filter?, name? } -> Path",
+        "Filter a source with a custom predicate.",
+    ),
+    (
+        "sourceByRegex",
+        "lib.sources",
+        "lib/sources.nix",
+        "sourceByRegex :: Path -> [String] -> Path",
+        "Keep only files matching one of the regexes.",
+    ),
+    (
+        "toSource",
+        "lib.fileset",
+        "lib/fileset/default.nix",
+        "toSource :: { root :: Path; fileset :: FileSet; } -> SourceLike",
+        "Import a file set into the store as a source.",
+    ),
+    (
+        "unions",
+        "lib.fileset",
+        "lib/fileset/default.nix",
+        "unions :: [FileSet] -> FileSet",
+        "Union of several file sets.",
+    ),
+    (
+        "fileFilter",
+        "lib.fileset",
+        "lib/fileset/default.nix",
+        "fileFilter :: (FileInfo -> Bool) -> Path -> FileSet",
+        "Files under a path matching a predicate.",
+    ),
+    // lib.debug
+    (
+        "traceVal",
+        "lib.debug",
+        "lib/debug.nix",
+        "traceVal :: a -> a",
+        "Trace a value and return it.",
+    ),
+    (
+        "traceSeq",
+        "lib.debug",
+        "lib/debug.nix",
+        "traceSeq :: a -> b -> b",
+        "Deeply evaluate and trace the first value, return the second.",
+    ),
+    (
+        "traceValSeq",
+        "lib.debug",
+        "lib/debug.nix",
+        "traceValSeq :: a -> a",
+        "Deeply evaluate, trace, and return a value.",
+    ),
+    // lib.generators
+    (
+        "toINI",
+        "lib.generators",
+        "lib/generators.nix",
+        "toINI :: AttrSet -> AttrSet -> String",
+        "Render nested attribute sets as an INI file.",
+    ),
+    (
+        "toKeyValue",
+        "lib.generators",
+        "lib/generators.nix",
+        "toKeyValue :: AttrSet -> AttrSet -> String",
+        "Render an attribute set as key=value lines.",
+    ),
+    (
+        "toYAML",
+        "lib.generators",
+        "lib/generators.nix",
+        "toYAML :: AttrSet -> Any -> String",
+        "Render a value as YAML (JSON is valid YAML).",
+    ),
+    (
+        "toPretty",
+        "lib.generators",
+        "lib/generators.nix",
+        "toPretty :: AttrSet -> Any -> String",
+        "Render a value as readable Nix for debugging.",
+    ),
+    (
+        "mkKeyValueDefault",
+        "lib.generators",
+        "lib/generators.nix",
+        "mkKeyValueDefault :: AttrSet -> String -> String -> Any -> String",
+        "Default key/value line formatter for the generators.",
+    ),
+    // lib.versions
+    (
+        "major",
+        "lib.versions",
+        "lib/versions.nix",
+        "major :: String -> String",
+        "Major component of a version string.",
+    ),
+    (
+        "minor",
+        "lib.versions",
+        "lib/versions.nix",
+        "minor :: String -> String",
+        "Minor component of a version string.",
+    ),
+    (
+        "majorMinor",
+        "lib.versions",
+        "lib/versions.nix",
+        "majorMinor :: String -> String",
+        "\"<major>.<minor>\" of a version string.",
+    ),
+    (
+        "splitVersion",
+        "lib.versions",
+        "lib/versions.nix",
+        "splitVersion :: String -> [String]",
+        "Split a version string into components.",
+    ),
+    // lib.types
+    (
+        "listOf",
+        "lib.types",
+        "lib/types.nix",
+        "listOf :: OptionType -> OptionType",
+        "List whose elements have the given type.",
+    ),
+    (
+        "attrsOf",
+        "lib.types",
+        "lib/types.nix",
+        "attrsOf :: OptionType -> OptionType",
+        "Attribute set whose values have the given type.",
+    ),
+    (
+        "lazyAttrsOf",
+        "lib.types",
+        "lib/types.nix",
+        "lazyAttrsOf :: OptionType -> OptionType",
+        "attrsOf with lazily evaluated values; mkIf conditions are not supported.",
+    ),
+    (
+        "nullOr",
+        "lib.types",
+        "lib/types.nix",
+        "nullOr :: OptionType -> OptionType",
+        "null or a value of the given type.",
+    ),
+    (
+        "either",
+        "lib.types",
+        "lib/types.nix",
+        "either :: OptionType -> OptionType -> OptionType",
+        "A value of either type.",
+    ),
+    (
+        "oneOf",
+        "lib.types",
+        "lib/types.nix",
+        "oneOf :: [OptionType] -> OptionType",
+        "A value of any of the types.",
+    ),
+    (
+        "enum",
+        "lib.types",
+        "lib/types.nix",
+        "enum :: [Any] -> OptionType",
+        "One of the listed values.",
+    ),
+    (
+        "submodule",
+        "lib.types",
+        "lib/types.nix",
+        "submodule :: (Module | [Module]) -> OptionType",
+        "Attribute set checked against options declared by modules.",
+    ),
+    (
+        "functionTo",
+        "lib.types",
+        "lib/types.nix",
+        "functionTo :: OptionType -> OptionType",
+        "Function returning a value of the given type.",
+    ),
+    (
+        "strMatching",
+        "lib.types",
+        "lib/types.nix",
+        "strMatching :: String -> OptionType",
+        "String matching a regular expression.",
+    ),
+    (
+        "separatedString",
+        "lib.types",
+        "lib/types.nix",
+        "separatedString :: String -> OptionType",
+        "String; multiple definitions are joined with the separator.",
+    ),
+    (
+        "uniq",
+        "lib.types",
+        "lib/types.nix",
+        "uniq :: OptionType -> OptionType",
+        "Value of the type that may only be defined once.",
+    ),
+];
+
+/// Values in `lib.types`, which are option types rather than functions
+const TYPES: &[(&str, &str)] = &[
+    ("str", "String; multiple definitions are not merged."),
+    (
+        "lines",
+        "String; multiple definitions are joined with newlines.",
+    ),
+    ("bool", "Boolean."),
+    ("int", "Signed integer."),
+    ("float", "Floating point number."),
+    ("number", "Integer or float."),
+    ("port", "Integer between 0 and 65535."),
+    (
+        "path",
+        "Filesystem path (string starting with /, or a path value).",
+    ),
+    ("package", "Derivation or store path."),
+    ("attrs", "Attribute set; definitions are merged shallowly."),
+    (
+        "anything",
+        "Any value; attribute sets are merged recursively.",
+    ),
+    ("raw", "Any value, unmerged and unchecked."),
+];
+
+/// Manifest for the nixpkgs library table
+pub fn manifest() -> DependencyManifest {
+    DependencyManifest::new(NAME, "nix", "builtin:nixpkgs-lib").with_version(VERSION)
+}
+
+/// Symbols for every entry in the table
+pub fn symbols() -> Vec<Symbol> {
+    let nix = LanguageId::new("nix");
+    // IDs are placeholders; write_dependency_index assigns real ones
+    let placeholder_id = SymbolId::new(1).expect("non-zero");
+    let placeholder_file = FileId::new(1).expect("non-zero");
+
+    let functions = LIB.iter().map(|(name, module, file, signature, doc)| {
+        Symbol::new(
+            placeholder_id,
+            *name,
+            SymbolKind::Function,
+            placeholder_file,
+            Range::new(0, 0, 0, 0),
+        )
+        .with_file_path(*file)
+        .with_module_path(*module)
+        .with_signature(*signature)
+        .with_doc(*doc)
+    });
+    let types = TYPES.iter().map(|(name, doc)| {
+        Symbol::new(
+            placeholder_id,
+            *name,
+            SymbolKind::Constant,
+            placeholder_file,
+            Range::new(0, 0, 0, 0),
+        )
+        .with_file_path("lib/types.nix")
+        .with_module_path("lib.types")
+        .with_signature(format!("types.{name} :: OptionType"))
+        .with_doc(*doc)
+    });
+
+    functions
+        .chain(types)
+        .map(|symbol| {
+            symbol
+                .with_visibility(Visibility::Public)
+                .with_language_id(nix)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_table_is_well_formed() {
+        let symbols = symbols();
+        assert_eq!(symbols.len(), LIB.len() + TYPES.len());
+
+        let mut seen = HashSet::new();
+        for (name, module, file, signature, _) in LIB {
+            assert!(module.starts_with("lib."), "{name}: {module}");
+            assert!(
+                file.starts_with("lib/") && file.ends_with(".nix"),
+                "{name}: {file}"
+            );
+            assert!(
+                signature.starts_with(&format!("{name} :: ")),
+                "signature of {name} should start with its name"
+            );
+            assert!(
+                seen.insert((*module, *name)),
+                "duplicate entry {module}.{name}"
+            );
+        }
+    }
+}
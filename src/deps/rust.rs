@@ -0,0 +1,286 @@
+//! Rust standard library and crates.io sources as dependency packages
+//!
+//! The standard library comes from the toolchain's `rust-src` component
+//! (`rustup component add rust-src`), indexed as `rust-std`, `rust-core`, and
+//! `rust-alloc`. Direct dependencies from the workspace `Cargo.toml` are read
+//! from `~/.cargo/registry/src` at the versions pinned in `Cargo.lock`, one
+//! `crate-<name>` index each.
+//!
+//! Sources are parsed rather than rustdoc JSON because the JSON output still
+//! needs a nightly toolchain; the public items and their doc comments are
+//! the same.
+
+use super::stubs::PackageSource;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Dependency name prefix for standard library crates
+pub const RUST_PREFIX: &str = "rust-";
+
+/// Dependency name prefix for crates.io dependencies
+pub const CRATE_PREFIX: &str = "crate-";
+
+/// Standard library crates indexed from rust-src
+const SYSROOT_CRATES: &[&str] = &["std", "core", "alloc"];
+
+/// Standard library and direct dependencies of the Cargo project at
+/// `workspace_root`; empty when there is no `Cargo.toml`
+pub fn discover_rust(workspace_root: &Path) -> Vec<PackageSource> {
+    if !workspace_root.join("Cargo.toml").is_file() {
+        return Vec::new();
+    }
+    let mut packages = discover_sysroot();
+    if let Some(cargo_home) = cargo_home() {
+        packages.extend(discover_crates(workspace_root, &cargo_home));
+    }
+    packages
+}
+
+fn rustc() -> Command {
+    Command::new(std::env::var_os("RUSTC").unwrap_or_else(|| "rustc".into()))
+}
+
+fn cargo_home() -> Option<PathBuf> {
+    std::env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".cargo")))
+}
+
+fn discover_sysroot() -> Vec<PackageSource> {
+    let Some(sysroot) = rustc_output(&["--print", "sysroot"]) else {
+        return Vec::new();
+    };
+    // "rustc 1.85.0 (4d91de4e4 2025-02-17)"
+    let version =
+        rustc_output(&["--version"]).and_then(|v| v.split_whitespace().nth(1).map(str::to_string));
+    let library = Path::new(&sysroot).join("lib/rustlib/src/rust/library");
+    if !library.is_dir() {
+        tracing::debug!(target: "deps", "rust-src not installed under {}", library.display());
+        return Vec::new();
+    }
+
+    SYSROOT_CRATES
+        .iter()
+        .map(|name| (name, library.join(name).join("src")))
+        .filter(|(_, src)| src.is_dir())
+        .map(|(name, src)| PackageSource {
+            dependency: format!("{RUST_PREFIX}{name}"),
+            version: version.clone(),
+            language: "rust",
+            location: src.clone(),
+            roots: vec![(src, name.to_string())],
+        })
+        .collect()
+}
+
+fn rustc_output(args: &[&str]) -> Option<String> {
+    let output = rustc().args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8(output.stdout).ok()?.trim().to_string())
+}
+
+/// Direct dependencies whose sources are in the cargo registry
+pub fn discover_crates(workspace_root: &Path, cargo_home: &Path) -> Vec<PackageSource> {
+    let Some(manifest) = read_toml(&workspace_root.join("Cargo.toml")) else {
+        return Vec::new();
+    };
+    let locked = locked_versions(&workspace_root.join("Cargo.lock"));
+    let registries: Vec<PathBuf> = fs::read_dir(cargo_home.join("registry").join("src"))
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut packages: Vec<_> = direct_dependencies(&manifest)
+        .into_iter()
+        .filter_map(|(crate_name, package)| {
+            let version = locked.get(&package)?;
+            let dir = registries
+                .iter()
+                .map(|registry| registry.join(format!("{package}-{version}")))
+                .find(|dir| dir.is_dir())?;
+            let src = library_dir(&dir)?;
+            Some(PackageSource {
+                dependency: format!("{CRATE_PREFIX}{package}"),
+                version: Some(version.clone()),
+                language: "rust",
+                location: dir,
+                roots: vec![(src, crate_name)],
+            })
+        })
+        .collect();
+    packages.sort_by(|a, b| a.dependency.cmp(&b.dependency));
+    packages.dedup_by(|a, b| a.dependency == b.dependency);
+    packages
+}
+
+fn read_toml(path: &Path) -> Option<toml::Table> {
+    fs::read_to_string(path).ok()?.parse().ok()
+}
+
+/// (crate name used in paths, package name) for every dependency table,
+/// honoring `package = "..."` renames
+fn direct_dependencies(manifest: &toml::Table) -> Vec<(String, String)> {
+    let workspace = manifest.get("workspace").and_then(|w| w.as_table());
+    let tables = ["dependencies", "dev-dependencies", "build-dependencies"]
+        .into_iter()
+        .filter_map(|key| manifest.get(key))
+        .chain(workspace.and_then(|w| w.get("dependencies")))
+        .filter_map(|table| table.as_table());
+
+    let mut deps = Vec::new();
+    for table in tables {
+        for (key, spec) in table {
+            let package = spec
+                .get("package")
+                .and_then(|p| p.as_str())
+                .unwrap_or(key.as_str());
+            deps.push((key.replace('-', "_"), package.to_string()));
+        }
+    }
+    deps
+}
+
+/// Highest locked version of each registry package in `Cargo.lock`
+fn locked_versions(lockfile: &Path) -> HashMap<String, String> {
+    let mut versions: HashMap<String, String> = HashMap::new();
+    let Some(lock) = read_toml(lockfile) else {
+        return versions;
+    };
+    let packages = lock.get("package").and_then(|p| p.as_array());
+    for package in packages.into_iter().flatten() {
+        let field = |key: &str| package.get(key).and_then(|v| v.as_str());
+        let (Some(name), Some(version)) = (field("name"), field("version")) else {
+            continue;
+        };
+        if !field("source").is_some_and(|s| s.starts_with("registry+") || s.starts_with("sparse+"))
+        {
+            continue;
+        }
+        let newer = versions
+            .get(name)
+            .is_none_or(|current| version_key(version) > version_key(current));
+        if newer {
+            versions.insert(name.to_string(), version.to_string());
+        }
+    }
+    versions
+}
+
+fn version_key(version: &str) -> Vec<u64> {
+    version
+        .split(['.', '-', '+'])
+        .map_while(|part| part.parse().ok())
+        .collect()
+}
+
+/// Directory of the crate's library target
+fn library_dir(crate_dir: &Path) -> Option<PathBuf> {
+    let custom = read_toml(&crate_dir.join("Cargo.toml")).and_then(|manifest| {
+        let path = manifest.get("lib")?.get("path")?.as_str()?.to_string();
+        Some(crate_dir.join(path).parent()?.to_path_buf())
+    });
+    custom
+        .or_else(|| Some(crate_dir.join("src")))
+        .filter(|dir| dir.is_dir())
+}
+
+/// `lib.rs` is the crate root, `a/mod.rs` and `a.rs` are `krate::a`
+pub(super) fn rust_module(crate_name: &str, relative: &str) -> Option<String> {
+    let stem = relative.strip_suffix(".rs")?;
+    if relative.starts_with("bin/") {
+        return None;
+    }
+    let stem = stem.strip_suffix("/mod").unwrap_or(stem);
+    if stem == "lib" || stem == "main" {
+        return Some(crate_name.to_string());
+    }
+    Some(format!("{crate_name}::{}", stem.replace('/', "::")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_rust_module() {
+        assert_eq!(rust_module("std", "lib.rs").as_deref(), Some("std"));
+        assert_eq!(
+            rust_module("std", "collections/hash/map.rs").as_deref(),
+            Some("std::collections::hash::map")
+        );
+        assert_eq!(
+            rust_module("serde", "de/mod.rs").as_deref(),
+            Some("serde::de")
+        );
+        assert_eq!(rust_module("serde", "bin/tool.rs"), None);
+        assert_eq!(rust_module("serde", "README.md"), None);
+    }
+
+    #[test]
+    fn test_discover_crates() {
+        let temp = TempDir::new().unwrap();
+        let project = temp.path().join("project");
+        write(
+            &project.join("Cargo.toml"),
+            r#"
+[package]
+name = "app"
+
+[dependencies]
+serde-json = { package = "serde_json", version = "1" }
+anyhow = "1"
+
+[dev-dependencies]
+missing = "0.1"
+"#,
+        );
+        write(
+            &project.join("Cargo.lock"),
+            r#"
+[[package]]
+name = "app"
+version = "0.1.0"
+
+[[package]]
+name = "serde_json"
+version = "1.0.9"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "serde_json"
+version = "1.0.10"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "anyhow"
+version = "1.0.80"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#,
+        );
+        let cargo_home = temp.path().join("cargo");
+        let registry = cargo_home.join("registry/src/index.crates.io-6f17d22bba15001f");
+        write(&registry.join("serde_json-1.0.10/src/lib.rs"), "");
+        write(&registry.join("anyhow-1.0.80/src/lib.rs"), "");
+
+        let packages = discover_crates(&project, &cargo_home);
+        let names: Vec<_> = packages.iter().map(|p| p.dependency.as_str()).collect();
+        assert_eq!(names, ["crate-anyhow", "crate-serde_json"]);
+        assert_eq!(packages[1].version.as_deref(), Some("1.0.10"));
+        assert_eq!(packages[1].roots[0].1, "serde_json");
+        assert!(packages[1].roots[0].0.ends_with("serde_json-1.0.10/src"));
+    }
+}
@@ -0,0 +1,605 @@
+//! Declaration indexes for installed Python, TypeScript, and Rust packages
+//!
+//! Discovers packages in the project's virtualenv (`site-packages`), in
+//! `node_modules`, and in the cargo registry, parses their declarations, and
+//! writes one dependency index per package:
+//!
+//! - Python: every `*.dist-info` in site-packages, using `top_level.txt` to
+//!   find the importable modules. `.pyi` stubs win over `.py` sources, and
+//!   PEP 561 `<module>-stubs` packages index under the real module name.
+//! - TypeScript: packages listed in the project's `package.json`, plus their
+//!   `@types/*` companions. Only `.d.ts` files are read; the one named by
+//!   the package's `types` or `typings` field provides the package itself.
+//! - Rust: the standard library and crates.io dependencies, see
+//!   [`super::rust`].
+//!
+//! Indexes are cached by package version, so a sync after installing one new
+//! package only parses that package. Indexes of uninstalled packages are
+//! removed.
+
+use super::rust::{CRATE_PREFIX, RUST_PREFIX, discover_rust, rust_module};
+use super::{
+    DependencyError, DependencyManifest, DependencyResult, list_dependencies, read_manifest,
+    remove_dependency, write_dependency_index,
+};
+use crate::config::Settings;
+use crate::parsing::get_registry;
+use crate::parsing::registry::LanguageId;
+use crate::types::SymbolCounter;
+use crate::{FileId, ScopeContext, Symbol, SymbolKind, Visibility};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Dependency name prefix for Python packages
+const PYTHON_PREFIX: &str = "pypi-";
+
+/// Dependency name prefix for npm packages
+const NPM_PREFIX: &str = "npm-";
+
+/// Packages with more declaration files are truncated
+const MAX_FILES_PER_PACKAGE: usize = 5000;
+
+/// Generated bundles larger than this are skipped
+const MAX_FILE_SIZE: u64 = 2 * 1024 * 1024;
+
+/// Directories never descended into when collecting files
+const SKIPPED_DIRS: &[&str] = &["__pycache__", "node_modules", "tests", "test", "testing"];
+
+/// An installed package whose declarations can be indexed
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackageSource {
+    /// Dependency index name, e.g. `pypi-requests` or `npm-types__react`
+    pub dependency: String,
+    pub version: Option<String>,
+    /// `python`, `typescript`, or `rust`
+    pub language: &'static str,
+    /// Installation directory recorded in the manifest
+    pub location: PathBuf,
+    /// Importable roots: a file or directory and the module name it provides
+    pub roots: Vec<(PathBuf, String)>,
+}
+
+impl PackageSource {
+    fn manifest(&self) -> DependencyManifest {
+        let manifest = DependencyManifest::new(
+            &self.dependency,
+            self.language,
+            self.location.display().to_string(),
+        );
+        match &self.version {
+            Some(version) => manifest.with_version(version),
+            None => manifest,
+        }
+    }
+
+    /// Whether `deps_root` already holds an index of this exact version
+    fn is_cached(&self, deps_root: &Path) -> bool {
+        read_manifest(&deps_root.join(&self.dependency)).is_ok_and(|existing| {
+            let wanted = self.manifest();
+            existing.version == wanted.version && existing.source == wanted.source
+        })
+    }
+}
+
+/// Outcome of [`sync`]
+#[derive(Debug, Default, Serialize)]
+pub struct SyncReport {
+    /// Packages (re)indexed in this run
+    pub indexed: Vec<DependencyManifest>,
+    /// Packages whose index was already current
+    pub cached: Vec<String>,
+    /// Indexes deleted because the package is no longer installed
+    pub removed: Vec<String>,
+    /// Packages that failed, with the reason
+    pub failed: Vec<(String, String)>,
+}
+
+/// Index the declarations of every package installed in `workspace_root`
+pub fn sync(deps_root: &Path, settings: &Settings, workspace_root: &Path) -> SyncReport {
+    let sources: Vec<_> = discover_python(workspace_root)
+        .into_iter()
+        .chain(discover_node(workspace_root))
+        .chain(discover_rust(workspace_root))
+        .collect();
+    let mut report = SyncReport::default();
+
+    for source in &sources {
+        if source.is_cached(deps_root) {
+            report.cached.push(source.dependency.clone());
+            continue;
+        }
+        let result = extract_declarations(source, settings).and_then(|symbols| {
+            write_dependency_index(deps_root, settings, source.manifest(), symbols)
+        });
+        match result {
+            Ok(manifest) => report.indexed.push(manifest),
+            Err(e) => report
+                .failed
+                .push((source.dependency.clone(), e.to_string())),
+        }
+    }
+
+    let installed: HashSet<&str> = sources.iter().map(|s| s.dependency.as_str()).collect();
+    for manifest in list_dependencies(deps_root) {
+        let managed = [PYTHON_PREFIX, NPM_PREFIX, RUST_PREFIX, CRATE_PREFIX]
+            .iter()
+            .any(|prefix| manifest.name.starts_with(prefix));
+        if managed && !installed.contains(manifest.name.as_str()) {
+            match remove_dependency(deps_root, &manifest.name) {
+                Ok(()) => report.removed.push(manifest.name),
+                Err(e) => report.failed.push((manifest.name, e.to_string())),
+            }
+        }
+    }
+
+    report
+}
+
+/// Packages in the project's virtualenv
+pub fn discover_python(workspace_root: &Path) -> Vec<PackageSource> {
+    let mut venvs: Vec<PathBuf> = [".venv", "venv", "env"]
+        .iter()
+        .map(|dir| workspace_root.join(dir))
+        .collect();
+    // A project-local environment wins over whichever one is activated
+    if let Some(active) = std::env::var_os("VIRTUAL_ENV") {
+        venvs.push(PathBuf::from(active));
+    }
+
+    let Some(site_packages) = venvs.iter().find_map(|venv| find_site_packages(venv)) else {
+        return Vec::new();
+    };
+    let Ok(entries) = fs::read_dir(&site_packages) else {
+        return Vec::new();
+    };
+
+    let mut packages: Vec<_> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| python_package(&site_packages, &entry.path()))
+        .collect();
+    packages.sort_by(|a, b| a.dependency.cmp(&b.dependency));
+    packages
+}
+
+fn find_site_packages(venv: &Path) -> Option<PathBuf> {
+    // Windows layout
+    let windows = venv.join("Lib").join("site-packages");
+    if windows.is_dir() {
+        return Some(windows);
+    }
+    fs::read_dir(venv.join("lib"))
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with("python"))
+        .map(|entry| entry.path().join("site-packages"))
+        .find(|path| path.is_dir())
+}
+
+/// Build a source from a `<name>-<version>.dist-info` directory
+fn python_package(site_packages: &Path, dist_info: &Path) -> Option<PackageSource> {
+    let file_name = dist_info.file_name()?.to_str()?;
+    let (name, version) = file_name.strip_suffix(".dist-info")?.split_once('-')?;
+    let normalized = name.to_lowercase().replace(['-', '.'], "_");
+
+    let top_level = fs::read_to_string(dist_info.join("top_level.txt")).unwrap_or_default();
+    let mut modules: Vec<&str> = top_level
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.contains('/'))
+        .collect();
+    if modules.is_empty() {
+        modules.push(&normalized);
+    }
+
+    let roots: Vec<_> = modules
+        .into_iter()
+        .filter(|module| !module.starts_with('_'))
+        .filter_map(|module| {
+            let import_name = module.strip_suffix("-stubs").unwrap_or(module);
+            [
+                site_packages.join(format!("{import_name}-stubs")),
+                site_packages.join(module),
+                site_packages.join(format!("{module}.pyi")),
+                site_packages.join(format!("{module}.py")),
+            ]
+            .into_iter()
+            .find(|path| path.exists())
+            .map(|path| (path, import_name.to_string()))
+        })
+        .collect();
+    if roots.is_empty() {
+        return None;
+    }
+
+    Some(PackageSource {
+        dependency: format!("{PYTHON_PREFIX}{normalized}"),
+        version: Some(version.to_string()),
+        language: "python",
+        location: dist_info.to_path_buf(),
+        roots,
+    })
+}
+
+/// Direct dependencies from `package.json` that ship type declarations
+pub fn discover_node(workspace_root: &Path) -> Vec<PackageSource> {
+    let node_modules = workspace_root.join("node_modules");
+    let Some(manifest) = read_package_json(&workspace_root.join("package.json")) else {
+        return Vec::new();
+    };
+
+    let mut names: Vec<String> = Vec::new();
+    for field in [
+        "dependencies",
+        "devDependencies",
+        "peerDependencies",
+        "optionalDependencies",
+    ] {
+        if let Some(deps) = manifest.get(field).and_then(|v| v.as_object()) {
+            names.extend(deps.keys().cloned());
+        }
+    }
+    // Typings for untyped packages live in @types even when not listed
+    let companions: Vec<String> = names
+        .iter()
+        .filter(|name| !name.starts_with("@types/"))
+        .map(|name| format!("@types/{}", name.trim_start_matches('@').replace('/', "__")))
+        .collect();
+    names.extend(companions);
+    names.sort();
+    names.dedup();
+
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let dir = node_modules.join(&name);
+            if !dir.is_dir() {
+                return None;
+            }
+            let package = read_package_json(&dir.join("package.json"));
+            let version = package.as_ref().and_then(|pkg| {
+                pkg.get("version")
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string)
+            });
+            let specifier = import_specifier(&name);
+            // The entry point comes first so the directory walk keeps it
+            let mut roots: Vec<_> = package
+                .as_ref()
+                .and_then(|pkg| types_entry(&dir, pkg))
+                .map(|entry| (entry, specifier.clone()))
+                .into_iter()
+                .collect();
+            roots.push((dir.clone(), specifier));
+            Some(PackageSource {
+                dependency: format!("{NPM_PREFIX}{}", name.replace('@', "").replace('/', "__")),
+                version,
+                language: "typescript",
+                location: dir,
+                roots,
+            })
+        })
+        .collect()
+}
+
+fn read_package_json(path: &Path) -> Option<serde_json::Value> {
+    serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+/// Declaration file named by a package's `types` or `typings` field, which
+/// may leave out the extension or name a directory holding `index.d.ts`
+fn types_entry(dir: &Path, package: &serde_json::Value) -> Option<PathBuf> {
+    let entry = ["types", "typings"]
+        .iter()
+        .find_map(|field| package.get(*field)?.as_str())?;
+    let path = dir.join(entry.trim_start_matches("./"));
+    let with_extension = PathBuf::from(format!("{}.d.ts", path.display()));
+    [path.clone(), with_extension, path.join("index.d.ts")]
+        .into_iter()
+        .find(|candidate| candidate.is_file())
+}
+
+/// The specifier code imports a package by: `@types/scope__pkg` declares
+/// `@scope/pkg`, `@types/node` declares `node`
+fn import_specifier(package: &str) -> String {
+    match package.strip_prefix("@types/") {
+        Some(typed) => match typed.split_once("__") {
+            Some((scope, name)) => format!("@{scope}/{name}"),
+            None => typed.to_string(),
+        },
+        None => package.to_string(),
+    }
+}
+
+/// Declaration files of a package with the module each provides
+fn package_files(source: &PackageSource) -> Vec<(PathBuf, String)> {
+    let mut files: BTreeMap<String, PathBuf> = BTreeMap::new();
+
+    let entries: HashSet<&Path> = source
+        .roots
+        .iter()
+        .map(|(root, _)| root.as_path())
+        .filter(|root| root.is_file())
+        .collect();
+
+    for (root, module) in &source.roots {
+        if root.is_file() {
+            files.entry(module.clone()).or_insert_with(|| root.clone());
+            continue;
+        }
+        let walker = WalkDir::new(root)
+            .follow_links(false)
+            .into_iter()
+            .filter_entry(|entry| {
+                entry.depth() == 0
+                    || entry.file_type().is_file()
+                    || !SKIPPED_DIRS.iter().any(|dir| entry.file_name() == *dir)
+            });
+        for entry in walker.filter_map(|entry| entry.ok()) {
+            if !entry.file_type().is_file()
+                || entry.metadata().is_ok_and(|m| m.len() > MAX_FILE_SIZE)
+            {
+                continue;
+            }
+            // An entry point provides its module already
+            if entries.contains(entry.path()) {
+                continue;
+            }
+            let Ok(relative) = entry.path().strip_prefix(root) else {
+                continue;
+            };
+            let relative = relative.to_string_lossy().replace('\\', "/");
+            let Some(file_module) = (match source.language {
+                "python" => python_module(module, &relative),
+                "rust" => rust_module(module, &relative),
+                _ => typescript_module(module, &relative),
+            }) else {
+                continue;
+            };
+
+            // A .pyi stub describes the same module as its .py source
+            let is_stub = relative.ends_with(".pyi");
+            let replace = files.get(&file_module).is_none_or(|existing| {
+                is_stub && existing.extension().is_none_or(|ext| ext != "pyi")
+            });
+            if replace {
+                files.insert(file_module, entry.into_path());
+            }
+        }
+    }
+
+    files
+        .into_iter()
+        .take(MAX_FILES_PER_PACKAGE)
+        .map(|(module, path)| (path, module))
+        .collect()
+}
+
+/// `pkg/sub/__init__.py` provides `pkg.sub`; `pkg/sub/mod.pyi` provides `pkg.sub.mod`
+fn python_module(root_module: &str, relative: &str) -> Option<String> {
+    let stem = relative
+        .strip_suffix(".pyi")
+        .or_else(|| relative.strip_suffix(".py"))?;
+    let stem = stem
+        .strip_suffix("__init__")
+        .unwrap_or(stem)
+        .trim_end_matches('/');
+    if stem.is_empty() {
+        return Some(root_module.to_string());
+    }
+    Some(format!("{root_module}.{}", stem.replace('/', ".")))
+}
+
+/// `index.d.ts` provides the package itself; `client.d.ts` provides
+/// `pkg/client` and `client/index.d.ts` does too
+fn typescript_module(specifier: &str, relative: &str) -> Option<String> {
+    let stem = [".d.ts", ".d.mts", ".d.cts"]
+        .iter()
+        .find_map(|suffix| relative.strip_suffix(suffix))?;
+    let stem = match stem.strip_suffix("index") {
+        Some(dir) if dir.is_empty() || dir.ends_with('/') => dir.trim_end_matches('/'),
+        _ => stem,
+    };
+    if stem.is_empty() {
+        return Some(specifier.to_string());
+    }
+    Some(format!("{specifier}/{stem}"))
+}
+
+/// Parse a package's files, keeping only declarations visible to importers
+fn extract_declarations(
+    source: &PackageSource,
+    settings: &Settings,
+) -> DependencyResult<Vec<Symbol>> {
+    let language_id = LanguageId::new(source.language);
+    let (mut parser, behavior) = get_registry()
+        .lock()
+        .map_err(|e| DependencyError::Parser {
+            language: source.language.to_string(),
+            reason: e.to_string(),
+        })?
+        .create_parser_with_behavior(language_id, settings)
+        .map_err(|e| DependencyError::Parser {
+            language: source.language.to_string(),
+            reason: e.to_string(),
+        })?;
+
+    let placeholder_file = FileId::new(1).expect("non-zero");
+    let mut symbols = Vec::new();
+    for (path, module) in package_files(source) {
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let mut counter = SymbolCounter::new();
+        let file_path = path.display().to_string();
+        symbols.extend(
+            parser
+                .parse(&content, placeholder_file, &mut counter)
+                .into_iter()
+                .filter_map(|mut symbol| {
+                    // Visibility comes from the behavior, as when indexing
+                    behavior.configure_symbol(&mut symbol, Some(&module));
+                    is_declaration(&symbol).then(|| {
+                        symbol
+                            .with_file_path(file_path.as_str())
+                            .with_module_path(module.as_str())
+                            .with_language_id(language_id)
+                    })
+                }),
+        );
+    }
+    Ok(symbols)
+}
+
+/// Public, non-local declarations; Python dunder methods count as public.
+/// Methods are kept regardless of visibility because Rust trait methods and
+/// trait impls carry no `pub`.
+fn is_declaration(symbol: &Symbol) -> bool {
+    if symbol.kind == SymbolKind::Parameter
+        || (symbol.visibility == Visibility::Private && symbol.kind != SymbolKind::Method)
+        || matches!(
+            symbol.scope_context,
+            Some(ScopeContext::Local { .. } | ScopeContext::Parameter)
+        )
+    {
+        return false;
+    }
+    let name = symbol.as_name();
+    !name.starts_with('_') || (name.starts_with("__") && name.ends_with("__"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_discover_python_packages() {
+        let temp = TempDir::new().unwrap();
+        let site = temp.path().join(".venv/lib/python3.12/site-packages");
+        write(
+            &site.join("requests-2.31.0.dist-info/top_level.txt"),
+            "requests\n",
+        );
+        write(&site.join("requests/__init__.py"), "");
+        write(&site.join("requests/sessions.py"), "");
+        write(&site.join("requests/sessions.pyi"), "");
+        write(&site.join("requests/tests/test_x.py"), "");
+        write(&site.join("six-1.16.0.dist-info/RECORD"), "");
+        write(&site.join("six.py"), "");
+        write(
+            &site.join("types_pyyaml-6.0.12.dist-info/top_level.txt"),
+            "yaml-stubs\n",
+        );
+        write(&site.join("yaml-stubs/__init__.pyi"), "");
+
+        let packages = discover_python(temp.path());
+        let names: Vec<_> = packages.iter().map(|p| p.dependency.as_str()).collect();
+        assert_eq!(names, ["pypi-requests", "pypi-six", "pypi-types_pyyaml"]);
+        assert_eq!(packages[0].version.as_deref(), Some("2.31.0"));
+
+        let files = package_files(&packages[0]);
+        let modules: Vec<_> = files.iter().map(|(_, m)| m.as_str()).collect();
+        assert_eq!(modules, ["requests", "requests.sessions"]);
+        assert!(files[1].0.ends_with("sessions.pyi"));
+
+        assert_eq!(package_files(&packages[1])[0].1, "six");
+        assert_eq!(package_files(&packages[2])[0].1, "yaml");
+    }
+
+    #[test]
+    fn test_discover_node_packages() {
+        let temp = TempDir::new().unwrap();
+        write(
+            &temp.path().join("package.json"),
+            r#"{"dependencies": {"react": "^18", "left-pad": "1"}, "devDependencies": {"@scope/lib": "2"}}"#,
+        );
+        let modules = temp.path().join("node_modules");
+        write(
+            &modules.join("@types/react/package.json"),
+            r#"{"version": "18.3.1"}"#,
+        );
+        write(&modules.join("@types/react/index.d.ts"), "");
+        write(&modules.join("@types/react/jsx-runtime.d.ts"), "");
+        write(
+            &modules.join("@scope/lib/package.json"),
+            r#"{"version": "2.0.0", "types": "./dist/index"}"#,
+        );
+        write(&modules.join("@scope/lib/dist/index.d.ts"), "");
+        write(&modules.join("@scope/lib/dist/index.js"), "");
+        write(&modules.join("@scope/lib/dist/reindex.d.ts"), "");
+
+        let packages = discover_node(temp.path());
+        let names: Vec<_> = packages.iter().map(|p| p.dependency.as_str()).collect();
+        assert_eq!(names, ["npm-scope__lib", "npm-types__react"]);
+        assert_eq!(packages[1].version.as_deref(), Some("18.3.1"));
+
+        let modules: Vec<_> = package_files(&packages[1])
+            .into_iter()
+            .map(|(_, m)| m)
+            .collect();
+        assert_eq!(modules, ["react", "react/jsx-runtime"]);
+        let files = package_files(&packages[0]);
+        let modules: Vec<_> = files.iter().map(|(_, m)| m.as_str()).collect();
+        assert_eq!(modules, ["@scope/lib", "@scope/lib/dist/reindex"]);
+        assert!(files[0].0.ends_with("dist/index.d.ts"));
+    }
+
+    #[test]
+    fn test_typescript_module_strips_index_segments_only() {
+        assert_eq!(
+            typescript_module("pkg", "index.d.ts").as_deref(),
+            Some("pkg")
+        );
+        assert_eq!(
+            typescript_module("pkg", "client/index.d.ts").as_deref(),
+            Some("pkg/client")
+        );
+        assert_eq!(
+            typescript_module("pkg", "reindex.d.ts").as_deref(),
+            Some("pkg/reindex")
+        );
+        assert_eq!(typescript_module("pkg", "index.js"), None);
+    }
+
+    #[test]
+    fn test_sync_indexes_and_caches() {
+        let temp = TempDir::new().unwrap();
+        let site = temp.path().join(".venv/lib/python3.12/site-packages");
+        write(&site.join("demo-1.0.dist-info/top_level.txt"), "demo\n");
+        write(
+            &site.join("demo/client.py"),
+            "class Client:\n    def get(self, url):\n        return url\n\ndef _helper():\n    pass\n",
+        );
+        let settings = Settings {
+            index_path: temp.path().join("index"),
+            ..Settings::default()
+        };
+        let deps_root = temp.path().join("deps");
+
+        let report = sync(&deps_root, &settings, temp.path());
+        assert!(report.failed.is_empty(), "{:?}", report.failed);
+        assert_eq!(report.indexed.len(), 1);
+
+        let deps = crate::deps::DependencySet::open(&deps_root, &settings);
+        let found = deps.resolve("demo.client.Client", Some("python"));
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].dependency, "pypi-demo");
+        assert!(deps.resolve("_helper", None).is_empty());
+        drop(deps);
+
+        let report = sync(&deps_root, &settings, temp.path());
+        assert_eq!(report.cached, ["pypi-demo"]);
+
+        fs::remove_dir_all(site.join("demo-1.0.dist-info")).unwrap();
+        let report = sync(&deps_root, &settings, temp.path());
+        assert_eq!(report.removed, ["pypi-demo"]);
+    }
+}
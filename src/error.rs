@@ -3,7 +3,10 @@
 //! This module provides structured error types using thiserror for better
 //! error handling and actionable error messages.
 
+use crate::indexing::pipeline::PipelineError;
+use crate::io::ExitCode;
 use crate::{FileId, SymbolId};
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use thiserror::Error;
 
@@ -86,6 +89,28 @@ pub enum IndexError {
     #[error("Index appears to be corrupted: {reason}")]
     IndexCorrupted { reason: String },
 
+    /// The index is in a format this build cannot read or upgrade
+    #[error(
+        "Index format {found} is not supported (this codanna uses format {supported}): {reason}"
+    )]
+    IncompatibleIndex {
+        found: u32,
+        supported: u32,
+        reason: String,
+    },
+
+    /// Another codanna process holds the index
+    #[error("Index is locked by {holder} (lock file '{path}')")]
+    IndexLocked { path: PathBuf, holder: String },
+
+    /// I/O errors without a more specific context
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+
+    /// A language parser could not be created
+    #[error("Failed to create {language} parser: {reason}")]
+    ParserInit { language: String, reason: String },
+
     /// General errors for cases where we need to preserve existing behavior
     #[error("{0}")]
     General(String),
@@ -116,11 +141,82 @@ impl IndexError {
     pub fn lock_error() -> Self {
         Self::LockError("mutex poisoned".to_string())
     }
+
+    /// Create a parser initialization error
+    pub fn parser_init(language: &str, reason: impl std::fmt::Display) -> Self {
+        Self::ParserInit {
+            language: language.to_string(),
+            reason: reason.to_string(),
+        }
+    }
 }
 
-impl From<std::io::Error> for IndexError {
-    fn from(err: std::io::Error) -> Self {
-        IndexError::General(err.to_string())
+/// Failure category of an [`IndexError`], for scripts that branch on the
+/// kind of failure rather than the message.
+///
+/// The codes returned by [`ErrorCategory::code`] and the process exit codes
+/// from [`ErrorCategory::exit_code`] are stable: new categories may be
+/// added, existing ones are never renumbered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCategory {
+    /// A symbol or file is not in the index
+    NotFound,
+    /// Reading or writing files failed
+    Io,
+    /// Source code or a parser could not be processed
+    Parse,
+    /// The file type or operation is not supported
+    Unsupported,
+    /// Invalid or missing configuration
+    Config,
+    /// The index storage failed (Tantivy, persistence, transactions)
+    Storage,
+    /// The index is corrupted or in an unsupported format and must be rebuilt
+    IndexCorrupted,
+    /// File or symbol IDs are exhausted
+    Capacity,
+    /// A lock could not be acquired or was poisoned
+    Concurrency,
+    /// Semantic search is disabled or failed
+    SemanticSearch,
+    /// Anything else
+    Internal,
+}
+
+impl ErrorCategory {
+    /// Stable machine-readable code, as used in JSON output
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::NotFound => "not_found",
+            Self::Io => "io",
+            Self::Parse => "parse",
+            Self::Unsupported => "unsupported",
+            Self::Config => "config",
+            Self::Storage => "storage",
+            Self::IndexCorrupted => "index_corrupted",
+            Self::Capacity => "capacity",
+            Self::Concurrency => "concurrency",
+            Self::SemanticSearch => "semantic_search",
+            Self::Internal => "internal",
+        }
+    }
+
+    /// Process exit code for failures of this category
+    pub fn exit_code(&self) -> ExitCode {
+        match self {
+            Self::NotFound => ExitCode::NotFound,
+            Self::Io => ExitCode::IoError,
+            Self::Parse => ExitCode::ParseError,
+            Self::Unsupported => ExitCode::UnsupportedOperation,
+            Self::Config => ExitCode::ConfigError,
+            Self::Storage => ExitCode::StorageError,
+            Self::IndexCorrupted => ExitCode::IndexCorrupted,
+            Self::Capacity => ExitCode::BlockingError,
+            Self::Concurrency => ExitCode::LockError,
+            Self::SemanticSearch => ExitCode::SemanticSearchError,
+            Self::Internal => ExitCode::GeneralError,
+        }
     }
 }
 
@@ -152,6 +248,10 @@ impl IndexError {
             Self::TransactionFailed { .. } => "TRANSACTION_FAILED",
             Self::MutexPoisoned => "MUTEX_POISONED",
             Self::IndexCorrupted { .. } => "INDEX_CORRUPTED",
+            Self::IncompatibleIndex { .. } => "INCOMPATIBLE_INDEX",
+            Self::IndexLocked { .. } => "INDEX_LOCKED",
+            Self::Io(_) => "IO_ERROR",
+            Self::ParserInit { .. } => "PARSER_INIT_ERROR",
             Self::General(_) => "GENERAL_ERROR",
             Self::LockError(_) => "LOCK_ERROR",
             Self::SemanticSearchNotEnabled => "SEMANTIC_SEARCH_NOT_ENABLED",
@@ -162,6 +262,45 @@ impl IndexError {
         .to_string()
     }
 
+    /// Failure category of this error
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            Self::FileRead { .. } | Self::FileWrite { .. } | Self::Io(_) => ErrorCategory::Io,
+            Self::ParseError { .. } | Self::ParserInit { .. } => ErrorCategory::Parse,
+            Self::UnsupportedFileType { .. } => ErrorCategory::Unsupported,
+            Self::PersistenceError { .. }
+            | Self::LoadError { .. }
+            | Self::TantivyError { .. }
+            | Self::TransactionFailed { .. }
+            | Self::Storage(_) => ErrorCategory::Storage,
+            Self::SymbolNotFound { .. } | Self::FileNotFound { .. } => ErrorCategory::NotFound,
+            Self::FileIdExhausted | Self::SymbolIdExhausted => ErrorCategory::Capacity,
+            Self::ConfigError { .. } => ErrorCategory::Config,
+            Self::MutexPoisoned | Self::LockError(_) | Self::IndexLocked { .. } => {
+                ErrorCategory::Concurrency
+            }
+            Self::IndexCorrupted { .. } | Self::IncompatibleIndex { .. } => {
+                ErrorCategory::IndexCorrupted
+            }
+            Self::SemanticSearchNotEnabled | Self::SemanticSearch(_) => {
+                ErrorCategory::SemanticSearch
+            }
+            Self::Pipeline(error) => match error.as_ref() {
+                PipelineError::Index(inner) => inner.category(),
+                PipelineError::FileRead { .. } | PipelineError::Spill(_) => ErrorCategory::Io,
+                PipelineError::Parse { .. } | PipelineError::ParserPanic { .. } => {
+                    ErrorCategory::Parse
+                }
+                PipelineError::UnsupportedFileType { .. } => ErrorCategory::Unsupported,
+                PipelineError::Storage(_) => ErrorCategory::Storage,
+                PipelineError::ChannelSend(_) | PipelineError::ChannelRecv(_) => {
+                    ErrorCategory::Internal
+                }
+            },
+            Self::General(_) => ErrorCategory::Internal,
+        }
+    }
+
     /// Get recovery suggestions for this error
     pub fn recovery_suggestions(&self) -> Vec<&'static str> {
         match self {
@@ -181,6 +320,14 @@ impl IndexError {
                 "Run 'codanna index --force' to rebuild from scratch",
                 "Check for disk errors or filesystem corruption",
             ],
+            Self::IncompatibleIndex { .. } => vec![
+                "Run 'codanna index --force' to rebuild the index in the current format",
+                "Or use the codanna release that wrote this index",
+            ],
+            Self::IndexLocked { .. } => vec![
+                "Pass --wait to wait for the other process to finish",
+                "If that process is no longer running, delete the lock file",
+            ],
             Self::LoadError { .. } | Self::PersistenceError { .. } => vec![
                 "The index will be loaded from Tantivy on next start",
                 "Run 'codanna index --force' if you continue to have issues",
@@ -0,0 +1,120 @@
+//! JSON Lines export of the symbol table.
+//!
+//! Each line is a self-contained JSON object, which makes the output easy to
+//! consume with `jq`, DuckDB (`read_json_auto`), or any line-oriented tool.
+
+use crate::storage::{DocumentIndex, StorageError, StorageResult};
+use crate::{Range, Symbol, SymbolKind, Visibility};
+use serde::Serialize;
+use std::io::Write;
+
+/// One exported symbol record
+#[derive(Debug, Serialize)]
+pub struct ExportedSymbol<'a> {
+    pub id: u32,
+    pub name: &'a str,
+    pub kind: SymbolKind,
+    pub file_id: u32,
+    pub file_path: &'a str,
+    pub range: Range,
+    pub signature: Option<&'a str>,
+    pub doc: Option<&'a str>,
+    pub module_path: Option<&'a str>,
+    pub language: Option<&'static str>,
+    pub visibility: Visibility,
+    pub exported: bool,
+    pub tags: &'a [Box<str>],
+    pub project: Option<&'a str>,
+}
+
+impl<'a> From<&'a Symbol> for ExportedSymbol<'a> {
+    fn from(symbol: &'a Symbol) -> Self {
+        Self {
+            id: symbol.id.value(),
+            name: &symbol.name,
+            kind: symbol.kind,
+            file_id: symbol.file_id.value(),
+            file_path: &symbol.file_path,
+            range: symbol.range,
+            signature: symbol.signature.as_deref(),
+            doc: symbol.doc_comment.as_deref(),
+            module_path: symbol.module_path.as_deref(),
+            language: symbol.language_id.map(|id| id.as_str()),
+            visibility: symbol.visibility,
+            exported: symbol.exported,
+            tags: &symbol.tags,
+            project: symbol.project.as_deref(),
+        }
+    }
+}
+
+/// Write every symbol in `index` as one JSON object per line
+///
+/// Returns the number of symbols written.
+pub fn export_symbols_jsonl<W: Write>(
+    index: &DocumentIndex,
+    mut writer: W,
+) -> StorageResult<usize> {
+    let count = index.for_each_symbol(|symbol| {
+        serde_json::to_writer(&mut writer, &ExportedSymbol::from(&symbol))
+            .map_err(|e| StorageError::Serialization(e.to_string()))?;
+        writer.write_all(b"\n")?;
+        Ok(())
+    })?;
+    writer.flush()?;
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::registry::LanguageId;
+    use crate::{FileId, SymbolId};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_export_symbols_jsonl() {
+        let temp_dir = TempDir::new().unwrap();
+        let settings = crate::config::Settings::default();
+        let index = DocumentIndex::new(temp_dir.path(), &settings).unwrap();
+
+        index.start_batch().unwrap();
+        for (id, name) in [(1, "parse"), (2, "Parser")] {
+            let symbol = Symbol::new(
+                SymbolId::new(id).unwrap(),
+                name,
+                SymbolKind::Function,
+                FileId::new(1).unwrap(),
+                Range::new(id * 10, 0, id * 10 + 5, 1),
+            )
+            .with_signature("fn parse(input: &str)")
+            .with_doc("Parses input")
+            .with_module_path("crate::parser")
+            .with_visibility(Visibility::Public)
+            .with_language_id(LanguageId::new("rust"));
+            index.index_symbol(&symbol, "src/parser.rs").unwrap();
+        }
+        index.commit_batch().unwrap();
+
+        let mut out = Vec::new();
+        let count = export_symbols_jsonl(&index, &mut out).unwrap();
+        assert_eq!(count, 2);
+
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<serde_json::Value> = text
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+
+        let first = lines.iter().find(|v| v["name"] == "parse").unwrap();
+        assert_eq!(first["kind"], "Function");
+        assert_eq!(first["file_path"], "src/parser.rs");
+        assert_eq!(first["range"]["start_line"], 10);
+        assert_eq!(first["signature"], "fn parse(input: &str)");
+        assert_eq!(first["doc"], "Parses input");
+        assert_eq!(first["module_path"], "crate::parser");
+        assert_eq!(first["language"], "rust");
+        assert_eq!(first["visibility"], "Public");
+    }
+}
@@ -0,0 +1,54 @@
+//! Structured exports of the index for external analysis tools.
+//!
+//! Exports read directly from the Tantivy [`DocumentIndex`] and stream their
+//! output, so large indexes never have to be materialized in memory.
+//!
+//! [`DocumentIndex`]: crate::storage::DocumentIndex
+
+pub mod jsonl;
+#[cfg(feature = "sqlite-export")]
+pub mod sqlite;
+
+pub use jsonl::{ExportedSymbol, export_symbols_jsonl};
+#[cfg(feature = "sqlite-export")]
+pub use sqlite::{SqliteExportStats, export_sqlite};
+
+use std::str::FromStr;
+
+/// Output formats supported by `codanna export`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// One JSON object per line
+    Jsonl,
+}
+
+impl ExportFormat {
+    /// Names accepted on the command line
+    pub const NAMES: &'static [&'static str] = &["jsonl"];
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "jsonl" | "ndjson" => Ok(ExportFormat::Jsonl),
+            other => Err(format!(
+                "unknown export format '{other}' (expected one of: {})",
+                Self::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_format_from_str() {
+        assert_eq!("jsonl".parse::<ExportFormat>(), Ok(ExportFormat::Jsonl));
+        assert_eq!("NDJSON".parse::<ExportFormat>(), Ok(ExportFormat::Jsonl));
+        assert!("csv".parse::<ExportFormat>().is_err());
+    }
+}
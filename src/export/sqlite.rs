@@ -0,0 +1,307 @@
+//! SQLite export of the code graph.
+//!
+//! Writes symbols, relationships, and files into a standalone database so
+//! downstream tools can query the index with plain SQL. Enabled with the
+//! `sqlite-export` feature.
+//!
+//! # Schema (version 4)
+//!
+//! ```sql
+//! CREATE TABLE meta (
+//!     key   TEXT PRIMARY KEY,      -- schema_version, codanna_version, exported_at
+//!     value TEXT NOT NULL
+//! );
+//! CREATE TABLE files (
+//!     id         INTEGER PRIMARY KEY,
+//!     path       TEXT NOT NULL,    -- workspace-relative
+//!     hash       TEXT NOT NULL,    -- SHA-256 of content at index time
+//!     indexed_at INTEGER NOT NULL  -- UTC seconds
+//! );
+//! CREATE TABLE symbols (
+//!     id           INTEGER PRIMARY KEY,
+//!     name         TEXT NOT NULL,
+//!     kind         TEXT NOT NULL,  -- Function, Method, Struct, ...
+//!     file_id      INTEGER NOT NULL,
+//!     file_path    TEXT NOT NULL,
+//!     start_line   INTEGER NOT NULL,
+//!     start_column INTEGER NOT NULL,
+//!     end_line     INTEGER NOT NULL,
+//!     end_column   INTEGER NOT NULL,
+//!     signature    TEXT,
+//!     doc          TEXT,
+//!     module_path  TEXT,
+//!     language     TEXT,
+//!     visibility   TEXT NOT NULL,  -- Public, Crate, Module, Private, Protected, Package
+//!     exported     INTEGER NOT NULL, -- 1 when part of the module's exported surface
+//!     project      TEXT             -- monorepo project of the file
+//! );
+//! CREATE TABLE symbol_tags (
+//!     symbol_id INTEGER NOT NULL,  -- symbols.id
+//!     tag       TEXT NOT NULL      -- from the [[tags]] rules in settings
+//! );
+//! CREATE TABLE relationships (
+//!     from_id INTEGER NOT NULL,    -- symbols.id
+//!     to_id   INTEGER NOT NULL,    -- symbols.id
+//!     kind    TEXT NOT NULL,       -- Calls, Implements, Uses, ...
+//!     weight  REAL NOT NULL,
+//!     line    INTEGER,             -- call site, when known
+//!     col     INTEGER,
+//!     context TEXT
+//! );
+//! ```
+//!
+//! Indexes cover `symbols(name)`, `symbols(kind)`, `symbols(file_id)`,
+//! `symbols(module_path)`, `symbol_tags(tag)`, `relationships(from_id, kind)`,
+//! and `relationships(to_id, kind)`. Relationships carry no foreign keys so that
+//! edges to unresolved symbols survive the export.
+//!
+//! Example: the ten most-called functions
+//!
+//! ```sql
+//! SELECT s.name, COUNT(*) AS callers
+//! FROM relationships r JOIN symbols s ON s.id = r.to_id
+//! WHERE r.kind = 'Calls'
+//! GROUP BY r.to_id ORDER BY callers DESC LIMIT 10;
+//! ```
+
+use crate::storage::{DocumentIndex, StorageError, StorageResult};
+use rusqlite::{Connection, params};
+use std::path::Path;
+
+/// Version of the exported schema, stored in the `meta` table
+pub const SQLITE_SCHEMA_VERSION: u32 = 4;
+
+const SCHEMA: &str = "
+CREATE TABLE meta (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+CREATE TABLE files (
+    id INTEGER PRIMARY KEY,
+    path TEXT NOT NULL,
+    hash TEXT NOT NULL,
+    indexed_at INTEGER NOT NULL
+);
+CREATE TABLE symbols (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    file_id INTEGER NOT NULL,
+    file_path TEXT NOT NULL,
+    start_line INTEGER NOT NULL,
+    start_column INTEGER NOT NULL,
+    end_line INTEGER NOT NULL,
+    end_column INTEGER NOT NULL,
+    signature TEXT,
+    doc TEXT,
+    module_path TEXT,
+    language TEXT,
+    visibility TEXT NOT NULL,
+    exported INTEGER NOT NULL,
+    project TEXT
+);
+CREATE TABLE symbol_tags (
+    symbol_id INTEGER NOT NULL,
+    tag TEXT NOT NULL
+);
+CREATE TABLE relationships (
+    from_id INTEGER NOT NULL,
+    to_id INTEGER NOT NULL,
+    kind TEXT NOT NULL,
+    weight REAL NOT NULL,
+    line INTEGER,
+    col INTEGER,
+    context TEXT
+);
+";
+
+// Created after the bulk insert, which is considerably faster than
+// maintaining the indexes row by row.
+const INDEXES: &str = "
+CREATE INDEX idx_files_path ON files(path);
+CREATE INDEX idx_symbols_name ON symbols(name);
+CREATE INDEX idx_symbols_kind ON symbols(kind);
+CREATE INDEX idx_symbols_file ON symbols(file_id);
+CREATE INDEX idx_symbols_module ON symbols(module_path);
+CREATE INDEX idx_symbol_tags_tag ON symbol_tags(tag);
+CREATE INDEX idx_relationships_from ON relationships(from_id, kind);
+CREATE INDEX idx_relationships_to ON relationships(to_id, kind);
+";
+
+/// Row counts written by [`export_sqlite`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SqliteExportStats {
+    pub files: usize,
+    pub symbols: usize,
+    pub relationships: usize,
+}
+
+fn sql_error(e: rusqlite::Error) -> StorageError {
+    StorageError::General(format!("SQLite export failed: {e}"))
+}
+
+/// Export the index into a new SQLite database at `path`
+///
+/// An existing file at `path` is replaced.
+pub fn export_sqlite(index: &DocumentIndex, path: &Path) -> StorageResult<SqliteExportStats> {
+    if path.exists() {
+        std::fs::remove_file(path)?;
+    }
+
+    let mut conn = Connection::open(path).map_err(sql_error)?;
+    conn.execute_batch("PRAGMA journal_mode = OFF; PRAGMA synchronous = OFF;")
+        .map_err(sql_error)?;
+    conn.execute_batch(SCHEMA).map_err(sql_error)?;
+
+    let mut stats = SqliteExportStats::default();
+    let tx = conn.transaction().map_err(sql_error)?;
+    {
+        let mut meta = tx
+            .prepare("INSERT INTO meta (key, value) VALUES (?1, ?2)")
+            .map_err(sql_error)?;
+        let exported_at = crate::indexing::get_utc_timestamp().to_string();
+        for (key, value) in [
+            ("schema_version", SQLITE_SCHEMA_VERSION.to_string()),
+            ("codanna_version", env!("CARGO_PKG_VERSION").to_string()),
+            ("exported_at", exported_at),
+        ] {
+            meta.execute(params![key, value]).map_err(sql_error)?;
+        }
+
+        let mut files = tx
+            .prepare("INSERT INTO files (id, path, hash, indexed_at) VALUES (?1, ?2, ?3, ?4)")
+            .map_err(sql_error)?;
+        for (file_id, file_path, hash, timestamp) in index.query_file_info()? {
+            files
+                .execute(params![file_id.value(), file_path, hash, timestamp as i64])
+                .map_err(sql_error)?;
+            stats.files += 1;
+        }
+
+        let mut symbols = tx
+            .prepare(
+                "INSERT OR REPLACE INTO symbols (id, name, kind, file_id, file_path, start_line, \
+                 start_column, end_line, end_column, signature, doc, module_path, language, \
+                 visibility, exported, project) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, \
+                 ?11, ?12, ?13, ?14, ?15, ?16)",
+            )
+            .map_err(sql_error)?;
+        let mut tags = tx
+            .prepare("INSERT INTO symbol_tags (symbol_id, tag) VALUES (?1, ?2)")
+            .map_err(sql_error)?;
+        stats.symbols = index.for_each_symbol(|symbol| {
+            symbols
+                .execute(params![
+                    symbol.id.value(),
+                    symbol.name.as_ref(),
+                    format!("{:?}", symbol.kind),
+                    symbol.file_id.value(),
+                    symbol.file_path.as_ref(),
+                    symbol.range.start_line,
+                    symbol.range.start_column,
+                    symbol.range.end_line,
+                    symbol.range.end_column,
+                    symbol.signature.as_deref(),
+                    symbol.doc_comment.as_deref(),
+                    symbol.module_path.as_deref(),
+                    symbol.language_id.map(|id| id.as_str()),
+                    format!("{:?}", symbol.visibility),
+                    symbol.exported,
+                    symbol.project.as_deref(),
+                ])
+                .map_err(sql_error)?;
+            for tag in &symbol.tags {
+                tags.execute(params![symbol.id.value(), tag.as_ref()])
+                    .map_err(sql_error)?;
+            }
+            Ok(())
+        })?;
+
+        let mut relationships = tx
+            .prepare(
+                "INSERT INTO relationships (from_id, to_id, kind, weight, line, col, context) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            )
+            .map_err(sql_error)?;
+        for (from, to, rel) in index.query_relationships()? {
+            let metadata = rel.metadata.as_ref();
+            relationships
+                .execute(params![
+                    from.value(),
+                    to.value(),
+                    format!("{:?}", rel.kind),
+                    rel.weight as f64,
+                    metadata.and_then(|m| m.line),
+                    metadata.and_then(|m| m.column),
+                    metadata.and_then(|m| m.context.as_deref()),
+                ])
+                .map_err(sql_error)?;
+            stats.relationships += 1;
+        }
+    }
+    tx.commit().map_err(sql_error)?;
+
+    conn.execute_batch(INDEXES).map_err(sql_error)?;
+
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FileId, Range, RelationKind, Relationship, Symbol, SymbolId, SymbolKind};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_export_sqlite_roundtrip() {
+        let temp_dir = TempDir::new().unwrap();
+        let settings = crate::config::Settings::default();
+        let index = DocumentIndex::new(temp_dir.path().join("tantivy"), &settings).unwrap();
+
+        index.start_batch().unwrap();
+        for (id, name) in [(1, "main"), (2, "helper")] {
+            let symbol = Symbol::new(
+                SymbolId::new(id).unwrap(),
+                name,
+                SymbolKind::Function,
+                FileId::new(1).unwrap(),
+                Range::new(id, 0, id + 1, 0),
+            );
+            index.index_symbol(&symbol, "src/main.rs").unwrap();
+        }
+        index
+            .store_relationship(
+                SymbolId::new(1).unwrap(),
+                SymbolId::new(2).unwrap(),
+                &Relationship::new(RelationKind::Calls),
+            )
+            .unwrap();
+        index.commit_batch().unwrap();
+
+        let db_path = temp_dir.path().join("index.db");
+        let stats = export_sqlite(&index, &db_path).unwrap();
+        assert_eq!(stats.symbols, 2);
+        assert_eq!(stats.relationships, 1);
+
+        let conn = Connection::open(&db_path).unwrap();
+        let callee: String = conn
+            .query_row(
+                "SELECT s.name FROM relationships r JOIN symbols s ON s.id = r.to_id \
+                 WHERE r.kind = 'Calls'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(callee, "helper");
+
+        let version: String = conn
+            .query_row(
+                "SELECT value FROM meta WHERE key = 'schema_version'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(version, SQLITE_SCHEMA_VERSION.to_string());
+
+        // Re-exporting replaces the database
+        let again = export_sqlite(&index, &db_path).unwrap();
+        assert_eq!(again, stats);
+    }
+}
@@ -0,0 +1,618 @@
+//! GraphQL schema over the symbol and relationship graph.
+//!
+//! The schema is embeddable: [`build_schema`] returns a ready
+//! `async_graphql::Schema` that can be executed directly or mounted on any
+//! HTTP stack. `codanna serve --http` mounts it at `/graphql` via [`router`].
+//!
+//! Example query:
+//!
+//! ```graphql
+//! {
+//!   symbols(name: "parse", first: 10) {
+//!     edges { cursor node { id name kind filePath calls { name } } }
+//!     pageInfo { hasNextPage endCursor }
+//!   }
+//! }
+//! ```
+
+use crate::indexing::facade::IndexFacade;
+use crate::io::BudgetParams;
+use crate::{RelationKind, Symbol, SymbolId, SymbolKind};
+use async_graphql::connection::{Connection, Edge, query};
+use async_graphql::{Context, EmptyMutation, EmptySubscription, Object, Result, Schema};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Facade handle shared with the rest of the server
+pub type SharedFacade = Arc<RwLock<IndexFacade>>;
+
+/// The complete codanna GraphQL schema
+pub type CodannaSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// Upper bound on page size to keep responses bounded
+const MAX_PAGE_SIZE: usize = 500;
+
+/// Deepest field nesting a query may use; leaves room for the standard
+/// introspection query while stopping `calls { calls { ... } }` chains
+const MAX_QUERY_DEPTH: usize = 16;
+
+/// Most fields a query may select in total
+const MAX_QUERY_COMPLEXITY: usize = 1000;
+
+/// Build the schema backed by a shared facade
+pub fn build_schema(facade: SharedFacade) -> CodannaSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .data(facade)
+        .limit_depth(MAX_QUERY_DEPTH)
+        .limit_complexity(MAX_QUERY_COMPLEXITY)
+        .finish()
+}
+
+/// Schema definition language for client code generation
+pub fn schema_sdl() -> String {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .finish()
+        .sdl()
+}
+
+/// Axum router serving the schema at `POST /graphql`.
+///
+/// `?max_tokens=` or `?max_bytes=` cut the response to size: a root field's
+/// list keeps its leading items and long strings are shortened. The
+/// `budget` response extension reports the cut.
+pub fn router(facade: SharedFacade) -> axum::Router {
+    async fn handler(
+        axum::extract::State(schema): axum::extract::State<CodannaSchema>,
+        axum::extract::Query(params): axum::extract::Query<BudgetParams>,
+        axum::Json(request): axum::Json<async_graphql::Request>,
+    ) -> axum::Json<serde_json::Value> {
+        let start = std::time::Instant::now();
+        let response = schema.execute(request).await;
+        crate::metrics::observe_query("/graphql", start.elapsed());
+
+        let mut body = serde_json::to_value(&response).unwrap_or_default();
+        if let Some(budget) = params.budget() {
+            let omitted = budget.fit_value(&mut body);
+            if !omitted.is_empty() {
+                body["extensions"]["budget"] = serde_json::json!({
+                    "truncated": true,
+                    "omitted": omitted.items,
+                    "trimmed": omitted.trimmed,
+                });
+            }
+        }
+        axum::Json(body)
+    }
+
+    axum::Router::new()
+        .route("/graphql", axum::routing::post(handler))
+        .with_state(build_schema(facade))
+}
+
+/// Offset-cursor pagination over an already filtered list
+async fn paginate<T, N>(
+    items: Vec<T>,
+    after: Option<String>,
+    before: Option<String>,
+    first: Option<i32>,
+    last: Option<i32>,
+    to_node: impl Fn(T) -> N,
+) -> Result<Connection<usize, N>>
+where
+    N: async_graphql::OutputType,
+{
+    query(
+        after,
+        before,
+        first,
+        last,
+        |after, before, first, last| async move {
+            let total = items.len();
+            let mut start = after.map(|a: usize| a + 1).unwrap_or(0).min(total);
+            let mut end = before.unwrap_or(total).min(total).max(start);
+            let first = first.unwrap_or(if last.is_none() { 50 } else { MAX_PAGE_SIZE });
+            end = end.min(start + first.min(MAX_PAGE_SIZE));
+            if let Some(last) = last {
+                start = end.saturating_sub(last.min(MAX_PAGE_SIZE)).max(start);
+            }
+
+            let mut connection = Connection::new(start > 0, end < total);
+            connection.edges.extend(
+                items
+                    .into_iter()
+                    .enumerate()
+                    .skip(start)
+                    .take(end - start)
+                    .map(|(i, item)| Edge::new(i, to_node(item))),
+            );
+            Ok::<_, async_graphql::Error>(connection)
+        },
+    )
+    .await
+}
+
+/// Root query type
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// Look up a symbol by id
+    async fn symbol(&self, ctx: &Context<'_>, id: u32) -> Result<Option<SymbolNode>> {
+        let facade = ctx.data::<SharedFacade>()?.read().await;
+        Ok(SymbolId::new(id)
+            .and_then(|id| facade.get_symbol(id))
+            .map(SymbolNode))
+    }
+
+    /// List symbols, optionally filtered by exact name, kind, language, or module
+    async fn symbols(
+        &self,
+        ctx: &Context<'_>,
+        name: Option<String>,
+        kind: Option<String>,
+        language: Option<String>,
+        module: Option<String>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> Result<Connection<usize, SymbolNode>> {
+        let kind = kind
+            .map(|k| k.parse::<SymbolKind>())
+            .transpose()
+            .map_err(async_graphql::Error::new)?;
+
+        let facade = ctx.data::<SharedFacade>()?.read().await;
+        let mut symbols = match &name {
+            Some(name) => facade.find_symbols_by_name(name, language.as_deref()),
+            None => {
+                let mut all = Vec::new();
+                facade.document_index().for_each_symbol(|symbol| {
+                    all.push(symbol);
+                    Ok(())
+                })?;
+                all
+            }
+        };
+        drop(facade);
+
+        symbols.retain(|s| {
+            kind.is_none_or(|k| s.kind == k)
+                && language
+                    .as_deref()
+                    .is_none_or(|l| s.language_id.is_some_and(|id| id.as_str() == l))
+                && module
+                    .as_deref()
+                    .is_none_or(|m| s.module_path.as_deref() == Some(m))
+        });
+        symbols.sort_by_key(|s| s.id.value());
+
+        paginate(symbols, after, before, first, last, SymbolNode).await
+    }
+
+    /// Full-text symbol search ranked by relevance
+    async fn search(
+        &self,
+        ctx: &Context<'_>,
+        query: String,
+        #[graphql(default = 20)] limit: usize,
+        kind: Option<String>,
+        language: Option<String>,
+    ) -> Result<Vec<SymbolNode>> {
+        let kind = kind
+            .map(|k| k.parse::<SymbolKind>())
+            .transpose()
+            .map_err(async_graphql::Error::new)?;
+        let facade = ctx.data::<SharedFacade>()?.read().await;
+        let results = facade.search(
+            &query,
+            limit.min(MAX_PAGE_SIZE),
+            kind,
+            None,
+            language.as_deref(),
+        )?;
+        Ok(results
+            .into_iter()
+            .filter_map(|r| facade.get_symbol(r.symbol_id))
+            .map(SymbolNode)
+            .collect())
+    }
+
+    /// Indexed files
+    async fn files(
+        &self,
+        ctx: &Context<'_>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> Result<Connection<usize, FileNode>> {
+        let facade = ctx.data::<SharedFacade>()?.read().await;
+        let mut files = facade.document_index().query_file_info()?;
+        drop(facade);
+        files.sort_by_key(|(id, ..)| id.value());
+
+        paginate(files, after, before, first, last, |(id, path, hash, _)| {
+            FileNode {
+                id: id.value(),
+                path,
+                hash,
+            }
+        })
+        .await
+    }
+
+    /// Module paths with the number of symbols declared in each
+    async fn modules(
+        &self,
+        ctx: &Context<'_>,
+        prefix: Option<String>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> Result<Connection<usize, ModuleNode>> {
+        let facade = ctx.data::<SharedFacade>()?.read().await;
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+        facade.document_index().for_each_symbol(|symbol| {
+            if let Some(path) = symbol.module_path.as_deref() {
+                if prefix.as_deref().is_none_or(|p| path.starts_with(p)) {
+                    *counts.entry(path.to_string()).or_default() += 1;
+                }
+            }
+            Ok(())
+        })?;
+        drop(facade);
+
+        let modules: Vec<_> = counts.into_iter().collect();
+        paginate(
+            modules,
+            after,
+            before,
+            first,
+            last,
+            |(path, symbol_count)| ModuleNode { path, symbol_count },
+        )
+        .await
+    }
+
+    /// Index statistics
+    async fn stats(&self, ctx: &Context<'_>) -> Result<IndexStatsNode> {
+        let facade = ctx.data::<SharedFacade>()?.read().await;
+        Ok(IndexStatsNode {
+            symbols: facade.symbol_count(),
+            files: facade.file_count() as usize,
+            relationships: facade.relationship_count(),
+        })
+    }
+}
+
+/// A symbol with lazily resolved relationships
+pub struct SymbolNode(pub Symbol);
+
+#[Object(name = "Symbol")]
+impl SymbolNode {
+    async fn id(&self) -> u32 {
+        self.0.id.value()
+    }
+
+    async fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    async fn kind(&self) -> String {
+        format!("{:?}", self.0.kind)
+    }
+
+    async fn file_path(&self) -> &str {
+        &self.0.file_path
+    }
+
+    async fn start_line(&self) -> u32 {
+        self.0.range.start_line
+    }
+
+    async fn start_column(&self) -> u16 {
+        self.0.range.start_column
+    }
+
+    async fn end_line(&self) -> u32 {
+        self.0.range.end_line
+    }
+
+    async fn end_column(&self) -> u16 {
+        self.0.range.end_column
+    }
+
+    async fn signature(&self) -> Option<&str> {
+        self.0.signature.as_deref()
+    }
+
+    async fn doc(&self) -> Option<&str> {
+        self.0.doc_comment.as_deref()
+    }
+
+    async fn module_path(&self) -> Option<&str> {
+        self.0.module_path.as_deref()
+    }
+
+    async fn language(&self) -> Option<&str> {
+        self.0.language_id.map(|id| id.as_str())
+    }
+
+    async fn visibility(&self) -> String {
+        format!("{:?}", self.0.visibility)
+    }
+
+    /// Whether the symbol is part of its module's exported surface
+    async fn exported(&self) -> bool {
+        self.0.exported
+    }
+
+    /// Labels from the project's tagging rules
+    async fn tags(&self) -> Vec<String> {
+        self.0.tags.iter().map(|tag| tag.to_string()).collect()
+    }
+
+    /// Monorepo project of the symbol's file
+    async fn project(&self) -> Option<String> {
+        self.0.project.as_deref().map(str::to_string)
+    }
+
+    /// Functions this symbol calls
+    async fn calls(&self, ctx: &Context<'_>) -> Result<Vec<SymbolNode>> {
+        let facade = ctx.data::<SharedFacade>()?.read().await;
+        Ok(wrap(facade.get_called_functions(self.0.id)))
+    }
+
+    /// Functions that call this symbol
+    async fn callers(&self, ctx: &Context<'_>) -> Result<Vec<SymbolNode>> {
+        let facade = ctx.data::<SharedFacade>()?.read().await;
+        Ok(wrap(facade.get_calling_functions(self.0.id)))
+    }
+
+    /// Types implementing this trait or interface
+    async fn implementations(&self, ctx: &Context<'_>) -> Result<Vec<SymbolNode>> {
+        let facade = ctx.data::<SharedFacade>()?.read().await;
+        Ok(wrap(facade.get_implementations(self.0.id)))
+    }
+
+    /// Raw relationship edges touching this symbol, optionally filtered by kind
+    async fn relationships(
+        &self,
+        ctx: &Context<'_>,
+        kind: Option<String>,
+    ) -> Result<Vec<RelationshipNode>> {
+        let facade = ctx.data::<SharedFacade>()?.read().await;
+        let edges = facade.get_relationships_for_symbol(self.0.id)?;
+        Ok(edges
+            .into_iter()
+            .filter(|(_, _, rel)| {
+                kind.as_deref()
+                    .is_none_or(|k| format!("{:?}", rel.kind).eq_ignore_ascii_case(k))
+            })
+            .map(|(from, to, rel)| RelationshipNode {
+                from: from.value(),
+                to: to.value(),
+                kind: rel.kind,
+                line: rel.metadata.as_ref().and_then(|m| m.line),
+            })
+            .collect())
+    }
+}
+
+fn wrap(symbols: Vec<Symbol>) -> Vec<SymbolNode> {
+    symbols.into_iter().map(SymbolNode).collect()
+}
+
+/// A directed relationship edge
+pub struct RelationshipNode {
+    from: u32,
+    to: u32,
+    kind: RelationKind,
+    line: Option<u32>,
+}
+
+#[Object(name = "Relationship")]
+impl RelationshipNode {
+    async fn from_id(&self) -> u32 {
+        self.from
+    }
+
+    async fn to_id(&self) -> u32 {
+        self.to
+    }
+
+    async fn kind(&self) -> String {
+        format!("{:?}", self.kind)
+    }
+
+    async fn line(&self) -> Option<u32> {
+        self.line
+    }
+
+    async fn from(&self, ctx: &Context<'_>) -> Result<Option<SymbolNode>> {
+        let facade = ctx.data::<SharedFacade>()?.read().await;
+        Ok(SymbolId::new(self.from)
+            .and_then(|id| facade.get_symbol(id))
+            .map(SymbolNode))
+    }
+
+    async fn to(&self, ctx: &Context<'_>) -> Result<Option<SymbolNode>> {
+        let facade = ctx.data::<SharedFacade>()?.read().await;
+        Ok(SymbolId::new(self.to)
+            .and_then(|id| facade.get_symbol(id))
+            .map(SymbolNode))
+    }
+}
+
+/// An indexed file
+#[derive(async_graphql::SimpleObject)]
+#[graphql(name = "File")]
+pub struct FileNode {
+    pub id: u32,
+    pub path: String,
+    pub hash: String,
+}
+
+/// A module path and its symbol count
+#[derive(async_graphql::SimpleObject)]
+#[graphql(name = "Module")]
+pub struct ModuleNode {
+    pub path: String,
+    pub symbol_count: usize,
+}
+
+/// Aggregate index counts
+#[derive(async_graphql::SimpleObject)]
+#[graphql(name = "IndexStats")]
+pub struct IndexStatsNode {
+    pub symbols: usize,
+    pub files: usize,
+    pub relationships: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Settings;
+    use crate::{FileId, Range, Relationship};
+    use tempfile::TempDir;
+
+    fn facade_with_symbols(temp_dir: &TempDir) -> SharedFacade {
+        let settings = Settings {
+            index_path: temp_dir.path().join("index"),
+            ..Settings::default()
+        };
+        let facade = IndexFacade::new(Arc::new(settings)).unwrap();
+        let index = facade.document_index();
+        index.start_batch().unwrap();
+        for (id, name, module) in [
+            (1, "main", "crate"),
+            (2, "parse", "crate::parser"),
+            (3, "lex", "crate::parser"),
+        ] {
+            let symbol = Symbol::new(
+                SymbolId::new(id).unwrap(),
+                name,
+                SymbolKind::Function,
+                FileId::new(1).unwrap(),
+                Range::new(id, 0, id, 10),
+            )
+            .with_module_path(module);
+            index.index_symbol(&symbol, "src/main.rs").unwrap();
+        }
+        index
+            .store_relationship(
+                SymbolId::new(1).unwrap(),
+                SymbolId::new(2).unwrap(),
+                &Relationship::new(RelationKind::Calls),
+            )
+            .unwrap();
+        index.commit_batch().unwrap();
+        Arc::new(RwLock::new(facade))
+    }
+
+    #[tokio::test]
+    async fn test_symbols_pagination() {
+        let temp_dir = TempDir::new().unwrap();
+        let schema = build_schema(facade_with_symbols(&temp_dir));
+
+        let response = schema
+            .execute("{ symbols(first: 2) { edges { cursor node { name } } pageInfo { hasNextPage endCursor } } }")
+            .await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        let data = response.data.into_json().unwrap();
+        let edges = data["symbols"]["edges"].as_array().unwrap();
+        assert_eq!(edges.len(), 2);
+        assert_eq!(edges[0]["node"]["name"], "main");
+        assert_eq!(data["symbols"]["pageInfo"]["hasNextPage"], true);
+
+        let cursor = data["symbols"]["pageInfo"]["endCursor"].as_str().unwrap();
+        let next = schema
+            .execute(format!(
+                "{{ symbols(first: 2, after: \"{cursor}\") {{ edges {{ node {{ name }} }} pageInfo {{ hasNextPage }} }} }}"
+            ))
+            .await;
+        let data = next.data.into_json().unwrap();
+        assert_eq!(data["symbols"]["edges"][0]["node"]["name"], "lex");
+        assert_eq!(data["symbols"]["pageInfo"]["hasNextPage"], false);
+    }
+
+    #[tokio::test]
+    async fn test_symbol_relationships_and_modules() {
+        let temp_dir = TempDir::new().unwrap();
+        let schema = build_schema(facade_with_symbols(&temp_dir));
+
+        let response = schema
+            .execute("{ symbol(id: 1) { name calls { name } } modules { edges { node { path symbolCount } } } }")
+            .await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        let data = response.data.into_json().unwrap();
+        assert_eq!(data["symbol"]["calls"][0]["name"], "parse");
+
+        let modules = data["modules"]["edges"].as_array().unwrap();
+        assert_eq!(modules.len(), 2);
+        assert_eq!(modules[1]["node"]["path"], "crate::parser");
+        assert_eq!(modules[1]["node"]["symbolCount"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_deeply_nested_query() {
+        let temp_dir = TempDir::new().unwrap();
+        let schema = build_schema(facade_with_symbols(&temp_dir));
+
+        let nested = format!(
+            "{{ symbol(id: 1) {{ {}name{} }} }}",
+            "calls { ".repeat(MAX_QUERY_DEPTH),
+            " }".repeat(MAX_QUERY_DEPTH)
+        );
+        let response = schema.execute(nested).await;
+        assert_eq!(response.errors.len(), 1);
+        assert!(
+            response.errors[0].message.contains("nested too deep"),
+            "{:?}",
+            response.errors
+        );
+    }
+
+    #[tokio::test]
+    async fn test_router_fits_response_to_budget() {
+        use tower::ServiceExt;
+
+        let temp_dir = TempDir::new().unwrap();
+        let request = axum::http::Request::post("/graphql?max_bytes=200")
+            .header("Content-Type", "application/json")
+            .body(axum::body::Body::from(
+                serde_json::json!({ "query": "{ symbols { edges { node { name filePath } } } }" })
+                    .to_string(),
+            ))
+            .unwrap();
+        let response = router(facade_with_symbols(&temp_dir))
+            .oneshot(request)
+            .await
+            .unwrap();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+
+        let edges = body["data"]["symbols"]["edges"].as_array().unwrap();
+        assert_eq!(edges[0]["node"]["name"], "main");
+        let budget = &body["extensions"]["budget"];
+        assert_eq!(budget["truncated"], true);
+        assert_eq!(
+            edges.len() + budget["omitted"].as_u64().unwrap() as usize,
+            3
+        );
+    }
+
+    #[test]
+    fn test_schema_sdl_lists_root_fields() {
+        let sdl = schema_sdl();
+        for field in ["symbols(", "files(", "modules(", "search("] {
+            assert!(sdl.contains(field), "missing {field} in SDL");
+        }
+    }
+}
@@ -23,15 +23,38 @@
 //! let symbols = facade.find_symbols_by_name("main")?;  // Uses DocumentIndex
 //! ```
 
-use crate::config::Settings;
-use crate::indexing::pipeline::Pipeline;
-use crate::semantic::{EmbeddingPool, SimpleSemanticSearch};
-use crate::storage::{DocumentIndex, SearchResult};
+use crate::config::{ExtractionProfile, Settings};
+use crate::deps::{DEPS_DIR, DependencySet, ExternalSymbol};
+use crate::indexing::pipeline::{
+    GeneratedDescription, Phase2Stats, Pipeline, SummaryStore, SymbolLookupCache,
+    UnresolvedRelationship, UnresolvedSink,
+};
+use crate::indexing::shards::{ForeignSymbols, Shard, ShardSet};
+use crate::indexing::trigram::substring_matches;
+use crate::indexing::warm::{WarmCache, WarmStats, in_module_tree};
+use crate::navigation::{
+    BundleLimits, CallDirection, CallHierarchyNode, ContextBundle, GrepMatch, HistoryError, Hover,
+    OutlineNode, SymbolAt, SymbolCommit, TypeDefinition, TypeHierarchy, call_hierarchy,
+    context_bundle, file_outline, find_type_definition, grep, history, hover, symbol_at,
+    type_hierarchy,
+};
+use crate::parsing::instantiation::decode_type_arguments;
+use crate::parsing::{SignatureStyle, display_signature};
+use crate::semantic::{
+    CACHE_DIR, CacheLimits, EmbeddingCache, EmbeddingPool, SimpleSemanticSearch,
+};
+use crate::storage::lock::{WriteGuard, WriteLocks};
+use crate::storage::wal::{IndexUpdate, WriteAheadLog};
+use crate::storage::{DocumentIndex, SearchResult, StorageError};
 use crate::symbol::context::{ContextIncludes, SymbolContext, SymbolRelationships};
-use crate::{FileId, IndexError, RelationKind, Relationship, Symbol, SymbolId, SymbolKind};
+use crate::symbol::{Annotation, QualifiedName};
+use crate::types::PositionEncoding;
+use crate::{
+    FileId, IndexError, RelationKind, Relationship, Symbol, SymbolId, SymbolKind, SymbolRef,
+};
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
 
 /// Result type for facade operations
 pub type FacadeResult<T> = Result<T, IndexError>;
@@ -89,6 +112,24 @@ pub struct IndexFacade {
 
     /// Base path for index storage
     index_base: PathBuf,
+
+    /// Dependency indexes, opened on first lookup
+    dependencies: OnceLock<DependencySet>,
+
+    /// Per-directory index shards, opened on first query
+    shards: OnceLock<ShardSet>,
+
+    /// Generated symbol descriptions, loaded on first lookup
+    summaries: OnceLock<SummaryStore>,
+
+    /// Log of updates in flight, for redoing those a crash interrupted
+    wal: Option<WriteAheadLog>,
+
+    /// This process's claim on the index against other codanna processes
+    write_locks: Arc<WriteLocks>,
+
+    /// Query structures from the last warm-up, see [`Self::warm_up`]
+    warm: RwLock<Option<Arc<WarmCache>>>,
 }
 
 impl IndexFacade {
@@ -108,7 +149,10 @@ impl IndexFacade {
 
         let document_index = Arc::new(DocumentIndex::new(&tantivy_path, &settings)?);
 
-        let pipeline = Pipeline::with_settings(settings.clone());
+        let mut pipeline = Pipeline::with_settings(settings.clone());
+        pipeline.set_dependency_root(Some(index_base.join(DEPS_DIR)));
+        let wal = open_wal(&index_base, &settings);
+        let write_locks = WriteLocks::new(&index_base);
 
         Ok(Self {
             document_index,
@@ -118,13 +162,19 @@ impl IndexFacade {
             settings,
             indexed_paths: HashSet::new(),
             index_base,
+            dependencies: OnceLock::new(),
+            shards: OnceLock::new(),
+            summaries: OnceLock::new(),
+            wal,
+            write_locks,
+            warm: RwLock::new(None),
         })
     }
 
     /// Create facade from existing components (for server integration).
     pub fn from_components(
         document_index: Arc<DocumentIndex>,
-        pipeline: Pipeline,
+        mut pipeline: Pipeline,
         semantic_search: Option<Arc<Mutex<SimpleSemanticSearch>>>,
         settings: Arc<Settings>,
     ) -> Self {
@@ -134,6 +184,10 @@ impl IndexFacade {
             settings.index_path.clone()
         };
 
+        pipeline.set_dependency_root(Some(index_base.join(DEPS_DIR)));
+        let wal = open_wal(&index_base, &settings);
+        let write_locks = WriteLocks::new(&index_base);
+
         Self {
             document_index,
             pipeline,
@@ -142,6 +196,12 @@ impl IndexFacade {
             settings,
             indexed_paths: HashSet::new(),
             index_base,
+            dependencies: OnceLock::new(),
+            shards: OnceLock::new(),
+            summaries: OnceLock::new(),
+            wal,
+            write_locks,
+            warm: RwLock::new(None),
         }
     }
 
@@ -155,6 +215,44 @@ impl IndexFacade {
         &self.pipeline
     }
 
+    /// Send per-file progress events to `events` while indexing.
+    pub fn set_progress_events(&mut self, events: Option<crate::indexing::ProgressEventCallback>) {
+        self.pipeline.set_events(events);
+    }
+
+    /// Resolve relationships that don't resolve within this index against
+    /// the symbols of the other shards; set on the facade of a shard.
+    pub fn set_foreign_symbols(&mut self, foreign: Option<Arc<ForeignSymbols>>) {
+        self.pipeline.set_foreign_symbols(foreign);
+    }
+
+    /// Link the calls that resolve nowhere in the index to the dependencies
+    /// under `root`; set on the facade of a shard, since dependencies are
+    /// installed once for the whole index.
+    pub fn set_dependency_root(&mut self, root: PathBuf) {
+        self.pipeline.set_dependency_root(Some(root));
+    }
+
+    /// Collect the relationships indexing leaves unresolved in `sink`.
+    pub fn set_unresolved_sink(&mut self, sink: Option<UnresolvedSink>) {
+        self.pipeline.set_unresolved_sink(sink);
+    }
+
+    /// Resolve relationships left unresolved by an earlier indexing run,
+    /// such as those collected by [`Self::set_unresolved_sink`], and store
+    /// the ones that resolve now.
+    pub fn resolve_relationships(
+        &mut self,
+        unresolved: Vec<UnresolvedRelationship>,
+    ) -> crate::IndexResult<Phase2Stats> {
+        let _lock = self.lock_for_write()?;
+        let _scope = self.document_index.write_scope();
+        let symbol_cache = Arc::new(SymbolLookupCache::from_index(&self.document_index)?);
+        Ok(self
+            .pipeline
+            .run_phase2(unresolved, symbol_cache, Arc::clone(&self.document_index))?)
+    }
+
     /// Get a reference to the settings.
     pub fn settings(&self) -> &Arc<Settings> {
         &self.settings
@@ -165,6 +263,74 @@ impl IndexFacade {
         &self.index_base
     }
 
+    /// Dependency indexes installed under `<index_path>/deps`.
+    pub fn dependencies(&self) -> &DependencySet {
+        self.dependencies
+            .get_or_init(|| DependencySet::open(&self.index_base.join(DEPS_DIR), &self.settings))
+    }
+
+    /// Index shards under `<index_path>/shards`, empty unless the index was
+    /// built with `indexing.shard_by_directory`.
+    pub fn shards(&self) -> &ShardSet {
+        self.shards
+            .get_or_init(|| ShardSet::open(&self.index_base, &self.settings))
+    }
+
+    /// The shards when the index is sharded. Their ids are federated, and
+    /// the main index holds nothing of its own then.
+    fn sharded(&self) -> Option<&ShardSet> {
+        let shards = self.shards();
+        (!shards.is_empty()).then_some(shards)
+    }
+
+    /// A facade writing to the shard that indexes `path`, if the index is
+    /// sharded, and that shard. Updates logged and locked by it stay with
+    /// the shard; its embeddings are refreshed by the next `codanna index`.
+    fn shard_writer(&self, path: &Path) -> Option<(&Shard, IndexFacade)> {
+        let shards = self.sharded()?;
+        let shard = shards.owner(path)?;
+        let mut pipeline = Pipeline::with_settings(Arc::clone(shard.settings()));
+        pipeline.set_foreign_symbols(Some(Arc::new(shards.foreign_symbols(shard.position()))));
+        let mut writer = IndexFacade::from_components(
+            Arc::clone(shard.document_index()),
+            pipeline,
+            None,
+            Arc::clone(shard.settings()),
+        );
+        writer.set_dependency_root(self.index_base.join(DEPS_DIR));
+        Some((shard, writer))
+    }
+
+    /// Description an LLM wrote for `symbol`, which has no doc comment of
+    /// its own. Only present when `[summaries]` is enabled.
+    pub fn generated_description(&self, symbol: &Symbol) -> Option<&GeneratedDescription> {
+        self.summaries
+            .get_or_init(|| SummaryStore::load(&self.index_base))
+            .get(symbol)
+    }
+
+    /// Describe new undocumented public symbols after indexing. The index
+    /// is complete without them, so a failing endpoint is only logged.
+    fn summarize(&mut self) {
+        if !self.settings.summaries.enabled {
+            return;
+        }
+        let mut store = self
+            .summaries
+            .take()
+            .unwrap_or_else(|| SummaryStore::load(&self.index_base));
+        match self.pipeline.summarize(&self.document_index, &mut store) {
+            Ok(Some(stats)) if stats.described + stats.removed > 0 => {
+                if let Err(e) = store.save() {
+                    tracing::warn!("Failed to save generated descriptions: {e}");
+                }
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Failed to generate symbol descriptions: {e}"),
+        }
+        self.summaries = OnceLock::from(store);
+    }
+
     // =========================================================================
     // Semantic Search Management
     // =========================================================================
@@ -174,17 +340,11 @@ impl IndexFacade {
         let semantic_path = self.index_base.join("semantic");
         std::fs::create_dir_all(&semantic_path)?;
 
-        let model = &self.settings.semantic_search.model;
-
-        let semantic = SimpleSemanticSearch::from_model_name(model)?;
+        let semantic = SimpleSemanticSearch::from_config(&self.settings.semantic_search)?;
         self.semantic_search = Some(Arc::new(Mutex::new(semantic)));
 
         // Create embedding pool for parallel generation
-        let pool_size = self.settings.semantic_search.embedding_threads;
-        let embedding_model = crate::vector::parse_embedding_model(model)
-            .map_err(|e| IndexError::General(format!("Failed to parse embedding model: {e}")))?;
-        let pool = EmbeddingPool::new(pool_size, embedding_model)?;
-        self.embedding_pool = Some(Arc::new(pool));
+        self.embedding_pool = Some(Arc::new(self.create_embedding_pool()?));
 
         Ok(())
     }
@@ -200,6 +360,9 @@ impl IndexFacade {
             let sem = semantic.lock().map_err(|_| IndexError::lock_error())?;
             sem.save(path)?;
         }
+        if let Some(ref pool) = self.embedding_pool {
+            pool.save_cache()?;
+        }
         Ok(())
     }
 
@@ -209,7 +372,7 @@ impl IndexFacade {
     /// Embedding pool for generating new embeddings is initialized lazily.
     pub fn load_semantic_search(&mut self, path: &Path) -> FacadeResult<bool> {
         if path.join("metadata.json").exists() {
-            match SimpleSemanticSearch::load(path) {
+            match SimpleSemanticSearch::load_with_config(path, &self.settings.semantic_search) {
                 Ok(semantic) => {
                     self.semantic_search = Some(Arc::new(Mutex::new(semantic)));
                     // Embedding pool is initialized lazily when needed
@@ -231,16 +394,25 @@ impl IndexFacade {
             return Ok(());
         }
 
-        let model = &self.settings.semantic_search.model;
-        let pool_size = self.settings.semantic_search.embedding_threads;
-        let embedding_model = crate::vector::parse_embedding_model(model)
-            .map_err(|e| IndexError::General(format!("Failed to parse embedding model: {e}")))?;
-        let pool = EmbeddingPool::new(pool_size, embedding_model)?;
-        self.embedding_pool = Some(Arc::new(pool));
+        self.embedding_pool = Some(Arc::new(self.create_embedding_pool()?));
         tracing::debug!("Initialized embedding pool for incremental updates");
         Ok(())
     }
 
+    /// Embedding pool for the configured provider, reusing the embeddings
+    /// cached under the index directory.
+    fn create_embedding_pool(&self) -> FacadeResult<EmbeddingPool> {
+        let config = &self.settings.semantic_search;
+        let pool = EmbeddingPool::from_config(config)?;
+        let cache = EmbeddingCache::open(
+            &self.index_base.join(CACHE_DIR),
+            pool.model_name(),
+            pool.dimensions(),
+            CacheLimits::from_config(config),
+        );
+        Ok(pool.with_cache(cache))
+    }
+
     /// Get semantic search embedding count.
     pub fn semantic_search_embedding_count(&self) -> usize {
         self.semantic_search
@@ -262,28 +434,215 @@ impl IndexFacade {
 
     /// Find a symbol by name.
     pub fn find_symbol(&self, name: &str) -> Option<SymbolId> {
-        self.document_index
-            .find_symbols_by_name(name, None)
-            .ok()
-            .and_then(|symbols| symbols.first().map(|s| s.id))
+        self.find_symbol_refs_by_name(name, None)
+            .first()
+            .map(|symbol| symbol.id)
     }
 
     /// Find all symbols by name with optional language filter.
     pub fn find_symbols_by_name(&self, name: &str, language_filter: Option<&str>) -> Vec<Symbol> {
-        self.document_index
+        let mut symbols = self
+            .document_index
             .find_symbols_by_name(name, language_filter)
-            .unwrap_or_default()
+            .unwrap_or_default();
+        if let Some(shards) = self.sharded() {
+            symbols.extend(shards.find_symbols_by_name(name, language_filter));
+        }
+        symbols
+    }
+
+    /// References to all symbols named `name`, without their signatures,
+    /// doc comments or scopes. [`SymbolRef::hydrate`] loads the rest.
+    pub fn find_symbol_refs_by_name(
+        &self,
+        name: &str,
+        language_filter: Option<&str>,
+    ) -> Vec<SymbolRef> {
+        let mut refs = self
+            .document_index
+            .find_symbol_refs_by_name(name, language_filter)
+            .unwrap_or_default();
+        if let Some(shards) = self.sharded() {
+            refs.extend(shards.find_symbol_refs_by_name(name, language_filter));
+        }
+        refs
+    }
+
+    /// Symbol names starting with `prefix`, sorted, for completion.
+    pub fn complete_symbol_names(&self, prefix: &str, limit: usize) -> Vec<String> {
+        match self.sharded() {
+            Some(shards) => shards.symbol_names_with_prefix(prefix, limit),
+            None => self
+                .document_index
+                .symbol_names_with_prefix(prefix, limit)
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Resolve many names at once, in input order.
+    ///
+    /// Names are looked up with one index search per language; each resolves
+    /// to its first match as [`find_symbol`](Self::find_symbol) would, after
+    /// narrowing by the module part of a qualified name.
+    pub fn resolve_many(&self, names: &[QualifiedName]) -> Vec<Option<SymbolId>> {
+        let mut by_language: HashMap<Option<&str>, Vec<&str>> = HashMap::new();
+        for name in names {
+            let group = by_language.entry(name.language.as_deref()).or_default();
+            if !group.contains(&name.name.as_str()) {
+                group.push(&name.name);
+            }
+        }
+
+        let mut found: HashMap<Option<&str>, HashMap<String, Vec<Symbol>>> = HashMap::new();
+        for (language, group) in by_language {
+            let mut symbols = self
+                .document_index
+                .find_symbols_by_names(&group, language)
+                .unwrap_or_default();
+            if let Some(shards) = self.sharded() {
+                for name in &group {
+                    symbols
+                        .entry(name.to_string())
+                        .or_default()
+                        .extend(shards.find_symbols_by_name(name, language));
+                }
+            }
+            found.insert(language, symbols);
+        }
+
+        names
+            .iter()
+            .map(|name| {
+                found
+                    .get(&name.language.as_deref())?
+                    .get(&name.name)?
+                    .iter()
+                    .find(|symbol| name.matches(symbol))
+                    .map(|symbol| symbol.id)
+            })
+            .collect()
+    }
+
+    /// Find symbols by (possibly qualified) name in dependency indexes.
+    ///
+    /// Used as a fallback when the project itself has no match.
+    pub fn find_external_symbols(
+        &self,
+        name: &str,
+        language_filter: Option<&str>,
+    ) -> Vec<ExternalSymbol> {
+        self.dependencies().resolve(name, language_filter)
+    }
+
+    /// Resolve the type of a variable, field, or parameter (or a function's
+    /// return type) to the symbols that define it.
+    pub fn find_type_definition(&self, symbol_id: SymbolId) -> Option<TypeDefinition> {
+        let symbol = self.get_symbol(symbol_id)?;
+        find_type_definition(self, &symbol)
+    }
+
+    /// Callers or callees of a symbol as a tree `depth` levels deep, with
+    /// the call sites of every edge. Columns are counted in `encoding`.
+    pub fn call_hierarchy(
+        &self,
+        symbol_id: SymbolId,
+        direction: CallDirection,
+        depth: u32,
+        encoding: PositionEncoding,
+    ) -> Option<CallHierarchyNode> {
+        call_hierarchy(self, symbol_id, direction, depth, encoding)
+    }
+
+    /// Supertypes and subtypes of a class, interface, or trait, `depth`
+    /// levels in each direction. Columns are counted in `encoding`.
+    pub fn type_hierarchy(
+        &self,
+        symbol_id: SymbolId,
+        depth: u32,
+        encoding: PositionEncoding,
+    ) -> Option<TypeHierarchy> {
+        type_hierarchy(self, symbol_id, depth, encoding)
+    }
+
+    /// Hover content of a symbol: signature, doc comment as markdown, and
+    /// definition location. Columns are counted in `encoding`.
+    pub fn hover(&self, symbol_id: SymbolId, encoding: PositionEncoding) -> Option<Hover> {
+        let symbol = self.get_symbol(symbol_id)?;
+        Some(hover(self, &symbol, encoding))
+    }
+
+    /// Commits that touched a symbol's lines according to `git log -L`,
+    /// newest first, at most `limit`.
+    pub fn history(
+        &self,
+        symbol: &Symbol,
+        limit: Option<usize>,
+    ) -> Result<Vec<SymbolCommit>, HistoryError> {
+        history(self, symbol, limit)
+    }
+
+    /// Symbols of a file nested by range containment, in source order.
+    /// Columns are counted in `encoding`.
+    ///
+    /// Returns `None` when the file is not indexed.
+    pub fn file_outline(&self, path: &str, encoding: PositionEncoding) -> Option<Vec<OutlineNode>> {
+        file_outline(self, path, encoding)
+    }
+
+    /// Innermost symbol at a zero-based line and column of a file, with the
+    /// indexed symbol the word there refers to. `encoding` is the unit the
+    /// column is counted in.
+    ///
+    /// Returns `None` when the file is not indexed or no symbol contains the
+    /// position.
+    pub fn symbol_at(
+        &self,
+        path: &str,
+        line: u32,
+        character: u32,
+        encoding: PositionEncoding,
+    ) -> Option<SymbolAt> {
+        symbol_at(self, path, line, character, encoding)
+    }
+
+    /// A symbol's source, documentation, direct callers and callees,
+    /// implementation relationships, and file imports, each capped by
+    /// `limits`. Columns are counted in `encoding`.
+    pub fn context_bundle(
+        &self,
+        symbol_id: SymbolId,
+        limits: BundleLimits,
+        encoding: PositionEncoding,
+    ) -> Option<ContextBundle> {
+        context_bundle(self, symbol_id, limits, encoding)
+    }
+
+    /// Lines of indexed files matching `pattern`, each with the symbols
+    /// enclosing it, at most `limit`. Columns are counted in `encoding`.
+    pub fn grep(
+        &self,
+        pattern: &regex::Regex,
+        limit: usize,
+        encoding: PositionEncoding,
+    ) -> Vec<GrepMatch> {
+        grep(self, pattern, limit, encoding)
     }
 
     /// Get a symbol by ID.
     pub fn get_symbol(&self, id: SymbolId) -> Option<Symbol> {
-        self.document_index.find_symbol_by_id(id).ok().flatten()
+        match self.sharded() {
+            Some(shards) => shards.get_symbol(id),
+            None => self.document_index.find_symbol_by_id(id).ok().flatten(),
+        }
     }
 
     /// Get all symbols (with limit).
     ///
     /// Returns empty vec on error for SimpleIndexer API compatibility.
     pub fn get_all_symbols(&self) -> Vec<Symbol> {
+        if let Some(shards) = self.sharded() {
+            return shards.get_all_symbols(10000);
+        }
         self.document_index
             .get_all_symbols(10000)
             .unwrap_or_else(|e| {
@@ -296,20 +655,47 @@ impl IndexFacade {
     ///
     /// Returns empty vec on error for SimpleIndexer API compatibility.
     pub fn get_symbols_by_file(&self, file_id: FileId) -> Vec<Symbol> {
-        self.document_index
-            .find_symbols_by_file(file_id)
-            .unwrap_or_default()
+        match self.sharded() {
+            Some(shards) => shards.find_symbols_by_file(file_id),
+            None => self
+                .document_index
+                .find_symbols_by_file(file_id)
+                .unwrap_or_default(),
+        }
     }
 
     // =========================================================================
     // Relationship Query Methods (delegate to DocumentIndex)
     // =========================================================================
 
+    /// Outgoing relationships of a symbol, from its shard when sharded.
+    fn relationships_from(
+        &self,
+        symbol_id: SymbolId,
+        kind: RelationKind,
+    ) -> Result<Vec<(SymbolId, SymbolId, Relationship)>, StorageError> {
+        match self.sharded() {
+            Some(shards) => Ok(shards.relationships_from(symbol_id, kind)),
+            None => self.document_index.get_relationships_from(symbol_id, kind),
+        }
+    }
+
+    /// Incoming relationships of a symbol, from its shard when sharded.
+    fn relationships_to(
+        &self,
+        symbol_id: SymbolId,
+        kind: RelationKind,
+    ) -> Result<Vec<(SymbolId, SymbolId, Relationship)>, StorageError> {
+        match self.sharded() {
+            Some(shards) => Ok(shards.relationships_to(symbol_id, kind)),
+            None => self.document_index.get_relationships_to(symbol_id, kind),
+        }
+    }
+
     /// Get functions called by a symbol.
     pub fn get_called_functions(&self, symbol_id: SymbolId) -> Vec<Symbol> {
         let relationships = self
-            .document_index
-            .get_relationships_from(symbol_id, RelationKind::Calls)
+            .relationships_from(symbol_id, RelationKind::Calls)
             .unwrap_or_default();
 
         let mut symbols = Vec::new();
@@ -327,8 +713,7 @@ impl IndexFacade {
         symbol_id: SymbolId,
     ) -> Vec<(Symbol, Option<crate::relationship::RelationshipMetadata>)> {
         let relationships = self
-            .document_index
-            .get_relationships_from(symbol_id, RelationKind::Calls)
+            .relationships_from(symbol_id, RelationKind::Calls)
             .unwrap_or_default();
 
         let mut results = Vec::new();
@@ -340,13 +725,281 @@ impl IndexFacade {
         results
     }
 
-    /// Get functions that call a symbol.
-    pub fn get_calling_functions(&self, symbol_id: SymbolId) -> Vec<Symbol> {
+    /// Targets of a symbol's outgoing relationships of one kind, with the
+    /// metadata of each edge.
+    pub fn get_relationship_targets_with_metadata(
+        &self,
+        symbol_id: SymbolId,
+        kind: RelationKind,
+    ) -> Vec<(Symbol, Option<crate::relationship::RelationshipMetadata>)> {
+        let relationships = self.relationships_from(symbol_id, kind).unwrap_or_default();
+
+        let mut results = Vec::new();
+        for (_, to_id, rel) in relationships {
+            if let Some(symbol) = self.get_symbol(to_id) {
+                results.push((symbol, rel.metadata));
+            }
+        }
+        results
+    }
+
+    /// Calls whose metadata puts the callee in `category` (e.g. `"fetcher"`
+    /// for Nix fetches), as (caller, callee, call metadata).
+    pub fn find_calls_by_category(
+        &self,
+        category: &str,
+    ) -> Vec<(Symbol, Symbol, crate::relationship::RelationshipMetadata)> {
+        let relationships = match self.sharded() {
+            Some(shards) => shards.relationships_by_kind(RelationKind::Calls),
+            None => self
+                .document_index
+                .get_all_relationships_by_kind(RelationKind::Calls)
+                .unwrap_or_default(),
+        };
+
+        relationships
+            .into_iter()
+            .filter_map(|(from_id, to_id, rel)| {
+                let metadata = rel.metadata?;
+                if metadata.attribute("category") != Some(category) {
+                    return None;
+                }
+                Some((self.get_symbol(from_id)?, self.get_symbol(to_id)?, metadata))
+            })
+            .collect()
+    }
+
+    /// Where a generic is named with type arguments, as (user, arguments,
+    /// reference metadata), e.g. a function using `Map<string, Session>`.
+    pub fn get_generic_instantiations(
+        &self,
+        generic_id: SymbolId,
+    ) -> Vec<(
+        Symbol,
+        Vec<String>,
+        crate::relationship::RelationshipMetadata,
+    )> {
         let relationships = self
-            .document_index
-            .get_relationships_to(symbol_id, RelationKind::Calls)
+            .relationships_to(generic_id, RelationKind::References)
             .unwrap_or_default();
 
+        relationships
+            .into_iter()
+            .filter_map(|(from_id, _, rel)| {
+                let metadata = rel.metadata?;
+                let arguments = decode_type_arguments(metadata.attribute("type_args")?);
+                Some((self.get_symbol(from_id)?, arguments, metadata))
+            })
+            .collect()
+    }
+
+    /// Errors calling a symbol can surface: what it throws and what its
+    /// callees throw, up to `max_depth` calls down, less the types a caller
+    /// on the way catches. Catches match exact types, not subclasses.
+    /// Returns (thrower, error type) pairs, each error type once.
+    pub fn get_error_surface(
+        &self,
+        symbol_id: SymbolId,
+        max_depth: usize,
+    ) -> Vec<(Symbol, Symbol)> {
+        let mut surfaced = Vec::new();
+        let mut reported = HashSet::new();
+        let mut visited = HashSet::new();
+        let mut pending = vec![(symbol_id, 0, HashSet::new())];
+
+        while let Some((current, depth, caught_above)) = pending.pop() {
+            if !visited.insert(current) {
+                continue;
+            }
+            // (error type, whether `current` catches rather than throws it)
+            let sites: Vec<(SymbolId, bool)> = self
+                .relationships_from(current, RelationKind::Throws)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(_, error_id, rel)| {
+                    let catches = rel
+                        .metadata
+                        .as_ref()
+                        .is_some_and(|metadata| metadata.attribute("caught") == Some("true"));
+                    (error_id, catches)
+                })
+                .collect();
+            for &(error_id, _) in sites.iter().filter(|(_, catches)| !catches) {
+                if caught_above.contains(&error_id) || !reported.insert(error_id) {
+                    continue;
+                }
+                if let (Some(thrower), Some(error)) =
+                    (self.get_symbol(current), self.get_symbol(error_id))
+                {
+                    surfaced.push((thrower, error));
+                }
+            }
+            let mut caught: HashSet<SymbolId> = caught_above;
+            caught.extend(
+                sites
+                    .iter()
+                    .filter(|(_, catches)| *catches)
+                    .map(|(id, _)| *id),
+            );
+
+            if depth < max_depth {
+                for (_, callee, _) in self
+                    .relationships_from(current, RelationKind::Calls)
+                    .unwrap_or_default()
+                {
+                    pending.push((callee, depth + 1, caught.clone()));
+                }
+            }
+        }
+        surfaced
+    }
+
+    /// Failure points (Nix `assert`, `throw`, `abort`) in symbols whose
+    /// module path is `module_prefix` or nested below it, ordered by file
+    /// and line. An empty prefix lists every failure point in the index.
+    pub fn find_failure_points(&self, module_prefix: &str) -> Vec<(Symbol, Annotation)> {
+        let symbols = match self.sharded() {
+            Some(shards) => shards.find_annotated_symbols(),
+            None => self
+                .document_index
+                .find_annotated_symbols()
+                .unwrap_or_else(|e| {
+                    tracing::warn!(target: "facade", "find_annotated_symbols error: {e}");
+                    Vec::new()
+                }),
+        };
+
+        let mut points: Vec<(Symbol, Annotation)> = symbols
+            .into_iter()
+            .filter(|symbol| {
+                in_module_tree(symbol.module_path.as_deref().unwrap_or(""), module_prefix)
+            })
+            .flat_map(|symbol| {
+                symbol
+                    .annotations
+                    .clone()
+                    .into_iter()
+                    .map(move |annotation| (symbol.clone(), annotation))
+            })
+            .collect();
+        points.sort_by(|(a, x), (b, y)| {
+            (&a.file_path, x.line, x.column).cmp(&(&b.file_path, y.line, y.column))
+        });
+        points
+    }
+
+    /// Symbols whose module path is `module` or nested below it. An empty
+    /// `module` lists every symbol.
+    pub fn module_symbols(&self, module: &str) -> Vec<Symbol> {
+        if let Some(warm) = self.warm_cache() {
+            return warm
+                .module_symbols(module)
+                .into_iter()
+                .filter_map(|id| self.get_symbol(id))
+                .collect();
+        }
+        let mut symbols = Vec::new();
+        if let Some(shards) = self.sharded() {
+            shards.for_each_symbol(|symbol| {
+                if in_module_tree(symbol.module_path.as_deref().unwrap_or(""), module) {
+                    symbols.push(symbol);
+                }
+            });
+            return symbols;
+        }
+        let _ = self.document_index.for_each_symbol(|symbol| {
+            if in_module_tree(symbol.module_path.as_deref().unwrap_or(""), module) {
+                symbols.push(symbol);
+            }
+            Ok(())
+        });
+        symbols
+    }
+
+    /// Precompute the reverse call graph, module tree, and name trigrams so
+    /// caller, module, and substring queries skip Tantivy. The result holds
+    /// until the index next changes; warming up again after indexing brings
+    /// it back.
+    pub fn warm_up(&self) -> FacadeResult<WarmStats> {
+        let (cache, stats) = WarmCache::build(&self.document_index)?;
+        if let Ok(mut warm) = self.warm.write() {
+            *warm = Some(Arc::new(cache));
+        }
+        Ok(stats)
+    }
+
+    /// Whether a warm-up reflects the index as it is now.
+    pub fn is_warm(&self) -> bool {
+        self.warm_cache().is_some()
+    }
+
+    fn warm_cache(&self) -> Option<Arc<WarmCache>> {
+        let warm = self.warm.read().ok()?;
+        warm.as_ref()
+            .filter(|cache| cache.is_current(&self.document_index))
+            .cloned()
+    }
+
+    /// The symbol an alias stands for, following `Aliases` edges through
+    /// chains like `type A = B; type B = C;`. A symbol that isn't an alias
+    /// resolves to itself.
+    pub fn resolve_alias(&self, symbol_id: SymbolId) -> SymbolId {
+        let mut seen = HashSet::from([symbol_id]);
+        let mut current = symbol_id;
+        while let Some((_, target, _)) = self
+            .relationships_from(current, RelationKind::Aliases)
+            .unwrap_or_default()
+            .into_iter()
+            .next()
+        {
+            if !seen.insert(target) {
+                break;
+            }
+            current = target;
+        }
+        current
+    }
+
+    /// A symbol followed by every alias that names it, directly or through
+    /// other aliases.
+    pub fn with_aliases(&self, symbol_id: SymbolId) -> Vec<SymbolId> {
+        let mut ids = vec![symbol_id];
+        let mut next = 0;
+        while next < ids.len() {
+            let aliases = self
+                .relationships_to(ids[next], RelationKind::Aliases)
+                .unwrap_or_default();
+            for (alias, _, _) in aliases {
+                if !ids.contains(&alias) {
+                    ids.push(alias);
+                }
+            }
+            next += 1;
+        }
+        ids
+    }
+
+    /// Incoming relationships of `kind` to a symbol and to its aliases, so
+    /// a call through `type Id = NodeId` counts as a use of `NodeId`.
+    fn relationships_to_with_aliases(
+        &self,
+        symbol_id: SymbolId,
+        kind: RelationKind,
+    ) -> Vec<(SymbolId, SymbolId, Relationship)> {
+        let warm = self.warm_cache().filter(|_| kind == RelationKind::Calls);
+        self.with_aliases(symbol_id)
+            .into_iter()
+            .flat_map(|id| match &warm {
+                Some(warm) => warm.callers(id).to_vec(),
+                None => self.relationships_to(id, kind).unwrap_or_default(),
+            })
+            .collect()
+    }
+
+    /// Get functions that call a symbol, directly or through an alias.
+    pub fn get_calling_functions(&self, symbol_id: SymbolId) -> Vec<Symbol> {
+        let relationships = self.relationships_to_with_aliases(symbol_id, RelationKind::Calls);
+
         let mut symbols = Vec::new();
         for (from_id, _, _) in relationships {
             if let Some(symbol) = self.get_symbol(from_id) {
@@ -356,15 +1009,13 @@ impl IndexFacade {
         symbols
     }
 
-    /// Get functions that call a symbol with metadata.
+    /// Get functions that call a symbol, directly or through an alias, with
+    /// metadata.
     pub fn get_calling_functions_with_metadata(
         &self,
         symbol_id: SymbolId,
     ) -> Vec<(Symbol, Option<crate::relationship::RelationshipMetadata>)> {
-        let relationships = self
-            .document_index
-            .get_relationships_to(symbol_id, RelationKind::Calls)
-            .unwrap_or_default();
+        let relationships = self.relationships_to_with_aliases(symbol_id, RelationKind::Calls);
 
         let mut results = Vec::new();
         for (from_id, _, rel) in relationships {
@@ -378,8 +1029,7 @@ impl IndexFacade {
     /// Get implementations of a trait/interface.
     pub fn get_implementations(&self, trait_id: SymbolId) -> Vec<Symbol> {
         let relationships = self
-            .document_index
-            .get_relationships_to(trait_id, RelationKind::Implements)
+            .relationships_to(trait_id, RelationKind::Implements)
             .unwrap_or_default();
 
         let mut symbols = Vec::new();
@@ -394,8 +1044,7 @@ impl IndexFacade {
     /// Get traits implemented by a type.
     pub fn get_implemented_traits(&self, type_id: SymbolId) -> Vec<Symbol> {
         let relationships = self
-            .document_index
-            .get_relationships_from(type_id, RelationKind::Implements)
+            .relationships_from(type_id, RelationKind::Implements)
             .unwrap_or_default();
 
         let mut symbols = Vec::new();
@@ -410,8 +1059,7 @@ impl IndexFacade {
     /// Get classes/types extended by a class.
     pub fn get_extends(&self, class_id: SymbolId) -> Vec<Symbol> {
         let relationships = self
-            .document_index
-            .get_relationships_from(class_id, RelationKind::Extends)
+            .relationships_from(class_id, RelationKind::Extends)
             .unwrap_or_default();
 
         let mut symbols = Vec::new();
@@ -426,8 +1074,7 @@ impl IndexFacade {
     /// Get classes that extend a base class.
     pub fn get_extended_by(&self, base_class_id: SymbolId) -> Vec<Symbol> {
         let relationships = self
-            .document_index
-            .get_relationships_to(base_class_id, RelationKind::Extends)
+            .relationships_to(base_class_id, RelationKind::Extends)
             .unwrap_or_default();
 
         let mut symbols = Vec::new();
@@ -442,8 +1089,7 @@ impl IndexFacade {
     /// Get types/symbols used by a symbol.
     pub fn get_uses(&self, symbol_id: SymbolId) -> Vec<Symbol> {
         let relationships = self
-            .document_index
-            .get_relationships_from(symbol_id, RelationKind::Uses)
+            .relationships_from(symbol_id, RelationKind::Uses)
             .unwrap_or_default();
 
         let mut symbols = Vec::new();
@@ -455,12 +1101,9 @@ impl IndexFacade {
         symbols
     }
 
-    /// Get symbols that use a type.
+    /// Get symbols that use a type, directly or through an alias.
     pub fn get_used_by(&self, type_id: SymbolId) -> Vec<Symbol> {
-        let relationships = self
-            .document_index
-            .get_relationships_to(type_id, RelationKind::Uses)
-            .unwrap_or_default();
+        let relationships = self.relationships_to_with_aliases(type_id, RelationKind::Uses);
 
         let mut symbols = Vec::new();
         for (from_id, _, _) in relationships {
@@ -485,8 +1128,10 @@ impl IndexFacade {
             RelationKind::Implements,
             RelationKind::Extends,
             RelationKind::Defines,
+            RelationKind::Aliases,
+            RelationKind::Throws,
         ] {
-            if let Ok(rels) = self.document_index.get_relationships_from(symbol_id, *kind) {
+            if let Ok(rels) = self.relationships_from(symbol_id, *kind) {
                 all_rels.extend(rels);
             }
         }
@@ -497,8 +1142,10 @@ impl IndexFacade {
             RelationKind::Uses,
             RelationKind::Implements,
             RelationKind::Extends,
+            RelationKind::Aliases,
+            RelationKind::Throws,
         ] {
-            if let Ok(rels) = self.document_index.get_relationships_to(symbol_id, *kind) {
+            if let Ok(rels) = self.relationships_to(symbol_id, *kind) {
                 all_rels.extend(rels);
             }
         }
@@ -518,10 +1165,7 @@ impl IndexFacade {
     ) -> Option<SymbolContext> {
         let symbol = self.get_symbol(symbol_id)?;
         let file_path = self
-            .document_index
             .get_file_path(symbol.file_id)
-            .ok()
-            .flatten()
             .unwrap_or_else(|| symbol.file_path.to_string());
 
         let mut relationships = SymbolRelationships::default();
@@ -539,10 +1183,7 @@ impl IndexFacade {
         }
 
         if include.contains(ContextIncludes::DEFINITIONS) {
-            if let Ok(rels) = self
-                .document_index
-                .get_relationships_from(symbol_id, RelationKind::Defines)
-            {
+            if let Ok(rels) = self.relationships_from(symbol_id, RelationKind::Defines) {
                 let defines: Vec<Symbol> = rels
                     .iter()
                     .filter_map(|(_, to_id, _)| self.get_symbol(*to_id))
@@ -607,8 +1248,7 @@ impl IndexFacade {
             RelationKind::Defines,
         ] {
             let rels = self
-                .document_index
-                .get_relationships_from(symbol_id, *kind)
+                .relationships_from(symbol_id, *kind)
                 .unwrap_or_default();
             let symbols: Vec<Symbol> = rels
                 .iter()
@@ -631,10 +1271,7 @@ impl IndexFacade {
             RelationKind::Uses,
             RelationKind::Implements,
         ] {
-            let rels = self
-                .document_index
-                .get_relationships_to(symbol_id, *kind)
-                .unwrap_or_default();
+            let rels = self.relationships_to(symbol_id, *kind).unwrap_or_default();
             let symbols: Vec<Symbol> = rels
                 .iter()
                 .filter_map(|(from_id, _, _)| self.get_symbol(*from_id))
@@ -652,6 +1289,25 @@ impl IndexFacade {
         &self,
         symbol_id: SymbolId,
         max_depth: Option<usize>,
+    ) -> Vec<SymbolId> {
+        self.impact_radius(symbol_id, max_depth, false)
+    }
+
+    /// Impact radius through calls that always run, skipping callers that
+    /// only reach the symbol behind a branch, loop or closure.
+    pub fn get_unconditional_impact_radius(
+        &self,
+        symbol_id: SymbolId,
+        max_depth: Option<usize>,
+    ) -> Vec<SymbolId> {
+        self.impact_radius(symbol_id, max_depth, true)
+    }
+
+    fn impact_radius(
+        &self,
+        symbol_id: SymbolId,
+        max_depth: Option<usize>,
+        unconditional_only: bool,
     ) -> Vec<SymbolId> {
         let max_depth = max_depth.unwrap_or(2);
         let mut visited = HashSet::new();
@@ -672,8 +1328,16 @@ impl IndexFacade {
                 RelationKind::Implements,
                 RelationKind::Extends,
             ] {
-                if let Ok(rels) = self.document_index.get_relationships_to(current_id, *kind) {
-                    for (from_id, _, _) in rels {
+                if let Ok(rels) = self.relationships_to(current_id, *kind) {
+                    for (from_id, _, rel) in rels {
+                        let conditional = rel
+                            .metadata
+                            .as_ref()
+                            .and_then(|m| m.attribute("conditional"))
+                            .is_some();
+                        if unconditional_only && conditional {
+                            continue;
+                        }
                         if visited.insert(from_id) {
                             queue.push_back((from_id, depth + 1));
                         }
@@ -700,9 +1364,97 @@ impl IndexFacade {
         module_filter: Option<&str>,
         language_filter: Option<&str>,
     ) -> FacadeResult<Vec<SearchResult>> {
-        self.document_index
-            .search(query, limit, kind_filter, module_filter, language_filter)
-            .map_err(Into::into)
+        let mut results = self.document_index.search(
+            query,
+            limit,
+            kind_filter,
+            module_filter,
+            language_filter,
+        )?;
+        if let Some(shards) = self.sharded() {
+            results.extend(shards.search(
+                query,
+                limit,
+                kind_filter,
+                module_filter,
+                language_filter,
+            ));
+            results.sort_by(|a, b| b.score.total_cmp(&a.score));
+            results.truncate(limit);
+        }
+        Ok(results)
+    }
+
+    /// Full-text search ranked by proximity to `context_file`.
+    ///
+    /// Results in the same file, module, or directory as the context file, or
+    /// imported by it, are boosted. Without a context file this is [`Self::search`].
+    pub fn search_with_context(
+        &self,
+        query: &str,
+        limit: usize,
+        kind_filter: Option<SymbolKind>,
+        module_filter: Option<&str>,
+        language_filter: Option<&str>,
+        context_file: Option<&str>,
+    ) -> FacadeResult<Vec<SearchResult>> {
+        let Some(context_file) = context_file else {
+            return self.search(query, limit, kind_filter, module_filter, language_filter);
+        };
+
+        let fetch = limit.saturating_mul(super::proximity::CANDIDATE_FACTOR);
+        let mut results = self.search(query, fetch, kind_filter, module_filter, language_filter)?;
+        super::proximity::ProximityScorer::new(self, context_file).rerank(&mut results);
+        results.truncate(limit);
+        Ok(results)
+    }
+
+    /// Symbols whose name contains `query`, ignoring case, best match first:
+    /// whole names, then prefixes, then matches starting a word.
+    ///
+    /// Uses the warm-up's trigram index when current, otherwise scans the
+    /// distinct names in the term dictionary.
+    pub fn search_substring(
+        &self,
+        query: &str,
+        limit: usize,
+        kind_filter: Option<SymbolKind>,
+        module_filter: Option<&str>,
+        language_filter: Option<&str>,
+    ) -> FacadeResult<Vec<SearchResult>> {
+        let warm = self.warm_cache();
+        let scanned;
+        let ranked = match &warm {
+            Some(warm) => warm.names().search(query),
+            None => {
+                scanned = match self.sharded() {
+                    Some(shards) => shards.symbol_names_with_prefix("", usize::MAX),
+                    None => self
+                        .document_index
+                        .symbol_names_with_prefix("", usize::MAX)?,
+                };
+                substring_matches(scanned.iter().map(String::as_str), query)
+            }
+        };
+
+        let style = self.settings.display.signature_style();
+        let mut results = Vec::new();
+        for (name, score) in ranked {
+            for symbol in self.find_symbols_by_name(name, language_filter) {
+                let kind_matches = kind_filter.is_none_or(|kind| symbol.kind == kind);
+                let module_matches = module_filter.is_none_or(|module| {
+                    in_module_tree(symbol.module_path.as_deref().unwrap_or(""), module)
+                });
+                if kind_matches && module_matches {
+                    results.push(substring_result(symbol, score, style));
+                }
+            }
+            if results.len() >= limit {
+                break;
+            }
+        }
+        results.truncate(limit);
+        Ok(results)
     }
 
     /// Semantic search using doc comment embeddings.
@@ -771,6 +1523,9 @@ impl IndexFacade {
 
     /// Get file ID for a path.
     pub fn get_file_id_for_path(&self, path: &str) -> Option<FileId> {
+        if let Some(shards) = self.sharded() {
+            return shards.get_file_id(path);
+        }
         self.document_index
             .get_file_info(path)
             .ok()
@@ -782,14 +1537,22 @@ impl IndexFacade {
     ///
     /// Returns None on error for SimpleIndexer API compatibility.
     pub fn get_file_path(&self, file_id: FileId) -> Option<String> {
-        self.document_index.get_file_path(file_id).ok().flatten()
+        match self.sharded() {
+            Some(shards) => shards.get_file_path(file_id),
+            None => self.document_index.get_file_path(file_id).ok().flatten(),
+        }
     }
 
     /// Get all indexed file paths.
     pub fn get_all_indexed_paths(&self) -> Vec<PathBuf> {
-        self.document_index
+        let mut paths = self
+            .document_index
             .get_all_indexed_paths()
-            .unwrap_or_default()
+            .unwrap_or_default();
+        if let Some(shards) = self.sharded() {
+            paths.extend(shards.get_all_indexed_paths());
+        }
+        paths
     }
 
     // =========================================================================
@@ -798,22 +1561,26 @@ impl IndexFacade {
 
     /// Get the number of indexed symbols.
     pub fn symbol_count(&self) -> usize {
-        self.document_index.count_symbols().unwrap_or(0)
+        let shards = self.sharded().map_or(0, ShardSet::symbol_count);
+        self.document_index.count_symbols().unwrap_or(0) + shards
     }
 
     /// Get the number of indexed files.
     pub fn file_count(&self) -> u32 {
-        self.document_index.count_files().unwrap_or(0) as u32
+        let shards = self.sharded().map_or(0, ShardSet::file_count);
+        (self.document_index.count_files().unwrap_or(0) + shards) as u32
     }
 
     /// Get the number of relationships.
     pub fn relationship_count(&self) -> usize {
-        self.document_index.count_relationships().unwrap_or(0)
+        let shards = self.sharded().map_or(0, ShardSet::relationship_count);
+        self.document_index.count_relationships().unwrap_or(0) + shards
     }
 
     /// Get total Tantivy document count.
     pub fn document_count(&self) -> FacadeResult<u64> {
-        self.document_index.document_count().map_err(Into::into)
+        let shards = self.sharded().map_or(0, ShardSet::document_count);
+        Ok(self.document_index.document_count()? + shards)
     }
 
     // =========================================================================
@@ -855,6 +1622,106 @@ impl IndexFacade {
     // Mutation Methods (delegate to Pipeline)
     // =========================================================================
 
+    /// Keep queries on the current index generation across several updates,
+    /// until [`Self::publish_generation`] swaps them all in at once.
+    pub fn begin_generation(&self) {
+        self.document_index.begin_generation();
+    }
+
+    /// Publish the updates made since [`Self::begin_generation`].
+    pub fn publish_generation(&self) -> FacadeResult<u64> {
+        self.document_index.publish_generation().map_err(Into::into)
+    }
+
+    /// Redo the updates a crashed process left unfinished, then compact the
+    /// write-ahead log. Returns how many were redone.
+    ///
+    /// A file is indexed again from scratch, or removed if it no longer
+    /// exists; a directory is force re-indexed. The write lock is only taken
+    /// when there is something to redo or to compact, and compaction alone
+    /// is skipped rather than waited for when another process holds it.
+    pub fn recover(&mut self) -> FacadeResult<usize> {
+        let Some(wal) = &self.wal else {
+            return Ok(0);
+        };
+        let _lock = if !wal.pending()?.is_empty() {
+            self.lock_for_write()?
+        } else if wal.needs_compaction()? {
+            match WriteLocks::try_acquire(&self.write_locks) {
+                Ok(lock) => lock,
+                Err(_) => return Ok(0),
+            }
+        } else {
+            return Ok(0);
+        };
+        // Another process may have redone them while we waited
+        let pending = wal.pending()?;
+
+        for interrupted in &pending {
+            let redone = match &interrupted.update {
+                IndexUpdate::IndexFile { path } if path.exists() => {
+                    self.index_file_with_force(path, true).map(|_| ())
+                }
+                IndexUpdate::IndexFile { path } | IndexUpdate::RemoveFile { path } => {
+                    self.remove_file(path)
+                }
+                IndexUpdate::IndexDirectory { path } => {
+                    self.index_directory(path, true).map(|_| ())
+                }
+            };
+            // An update that fails again is left to the next incremental run
+            if let Err(e) = redone {
+                tracing::warn!(
+                    "[index] could not redo interrupted update {:?}: {e}",
+                    interrupted.update
+                );
+            }
+            if let Some(wal) = &self.wal {
+                wal.resolve(interrupted)?;
+            }
+        }
+
+        if let Some(wal) = &self.wal {
+            wal.compact()?;
+        }
+        Ok(pending.len())
+    }
+
+    /// Log `update` before it touches the index. The update runs even if it
+    /// can't be logged; it just won't be redone after a crash.
+    fn log_update(&self, update: IndexUpdate) -> Option<u64> {
+        match self.wal.as_ref()?.begin(update) {
+            Ok(seq) => Some(seq),
+            Err(e) => {
+                tracing::warn!("[index] failed to write the write-ahead log: {e}");
+                None
+            }
+        }
+    }
+
+    /// Close an update from [`Self::log_update`]: committed if it
+    /// `finished`, aborted if it failed. Either way it isn't redone; what a
+    /// failed update left is up to the next incremental run.
+    fn log_end(&self, seq: Option<u64>, finished: bool) {
+        let (Some(wal), Some(seq)) = (&self.wal, seq) else {
+            return;
+        };
+        let logged = if finished {
+            wal.commit(seq)
+        } else {
+            wal.abort(seq)
+        };
+        if let Err(e) = logged {
+            tracing::warn!("[index] failed to write the write-ahead log: {e}");
+        }
+    }
+
+    /// Keep other codanna processes from writing the index until the
+    /// guard is dropped; nested updates share one lock
+    fn lock_for_write(&self) -> FacadeResult<WriteGuard> {
+        WriteLocks::acquire(&self.write_locks)
+    }
+
     /// Index a single file using the parallel pipeline.
     ///
     /// Returns `IndexingResult::Indexed` with the file ID on success.
@@ -863,12 +1730,30 @@ impl IndexFacade {
         path: impl AsRef<std::path::Path>,
     ) -> crate::IndexResult<crate::IndexingResult> {
         let path = path.as_ref();
+        if let Some((shard, mut writer)) = self.shard_writer(path) {
+            let federate = |id| {
+                shard
+                    .file_id(id)
+                    .map_err(|e| crate::IndexError::General(e.to_string()))
+            };
+            return Ok(match writer.index_file(path)? {
+                crate::IndexingResult::Indexed(id) => crate::IndexingResult::Indexed(federate(id)?),
+                crate::IndexingResult::Cached(id) => crate::IndexingResult::Cached(federate(id)?),
+            });
+        }
+        let _lock = self.lock_for_write()?;
+        let logged = self.log_update(IndexUpdate::IndexFile {
+            path: path.to_path_buf(),
+        });
+        let _scope = self.document_index.write_scope();
         let stats = self.pipeline.index_file_single(
             path,
             Arc::clone(&self.document_index),
             self.semantic_search.clone(),
             self.embedding_pool.clone(),
-        )?;
+        );
+        self.log_end(logged, stats.is_ok());
+        let stats = stats?;
 
         Ok(crate::IndexingResult::Indexed(stats.file_id))
     }
@@ -894,9 +1779,18 @@ impl IndexFacade {
     /// Remove a file from the index.
     ///
     /// Uses the Pipeline's cleanup stage to remove symbols and embeddings.
+    #[tracing::instrument(level = "debug", skip_all, fields(path = %path.as_ref().display()))]
     pub fn remove_file(&mut self, path: impl AsRef<std::path::Path>) -> crate::IndexResult<()> {
         let path = path.as_ref();
+        if let Some((_, mut writer)) = self.shard_writer(path) {
+            return writer.remove_file(path);
+        }
         let semantic_path = self.settings.index_path.join("semantic");
+        let _lock = self.lock_for_write()?;
+        let logged = self.log_update(IndexUpdate::RemoveFile {
+            path: path.to_path_buf(),
+        });
+        let _scope = self.document_index.write_scope();
 
         use crate::indexing::pipeline::stages::CleanupStage;
         let cleanup_stage = if let Some(ref sem) = self.semantic_search {
@@ -906,24 +1800,35 @@ impl IndexFacade {
             CleanupStage::new(Arc::clone(&self.document_index), &semantic_path)
         };
 
-        cleanup_stage.cleanup_files(&[path.to_path_buf()])?;
+        let removed = cleanup_stage.cleanup_files(&[path.to_path_buf()]);
+        self.log_end(logged, removed.is_ok());
+        removed?;
         Ok(())
     }
 
     /// Index a directory using the parallel pipeline.
     ///
     /// This is the primary indexing entry point using Pipeline.
+    #[tracing::instrument(skip_all, fields(path = %path.display(), force = force))]
     pub fn index_directory(&mut self, path: &Path, force: bool) -> FacadeResult<IndexingStats> {
+        let _lock = self.lock_for_write()?;
+        let logged = self.log_update(IndexUpdate::IndexDirectory {
+            path: path.to_path_buf(),
+        });
+        let _scope = self.document_index.write_scope();
         let stats = self.pipeline.index_incremental(
             path,
             Arc::clone(&self.document_index),
             self.semantic_search.clone(),
             self.embedding_pool.clone(),
             force,
-        )?;
+        );
+        self.log_end(logged, stats.is_ok());
+        let stats = stats?;
 
         // Update tracked paths
         self.add_indexed_path(path);
+        self.summarize();
 
         Ok(IndexingStats {
             files_indexed: stats.new_files + stats.modified_files,
@@ -934,6 +1839,45 @@ impl IndexFacade {
         })
     }
 
+    /// Index a directory with `profile` for every language, for this call
+    /// only.
+    ///
+    /// Unchanged files are skipped as usual; pass `force` to extract them
+    /// again with the new profile.
+    pub fn index_directory_with_profile(
+        &mut self,
+        path: &Path,
+        force: bool,
+        profile: ExtractionProfile,
+    ) -> FacadeResult<IndexingStats> {
+        self.with_profile(profile, |facade| facade.index_directory(path, force))
+    }
+
+    /// Index a single file with `profile`, for this call only.
+    pub fn index_file_with_profile(
+        &mut self,
+        path: impl AsRef<std::path::Path>,
+        profile: ExtractionProfile,
+    ) -> crate::IndexResult<crate::IndexingResult> {
+        self.with_profile(profile, |facade| facade.index_file(path))
+    }
+
+    /// Run `index` with a pipeline extracting every language with `profile`
+    fn with_profile<T>(
+        &mut self,
+        profile: ExtractionProfile,
+        index: impl FnOnce(&mut Self) -> T,
+    ) -> T {
+        let settings = Arc::new(self.settings.with_extraction_profile(profile));
+        let mut profiled = Pipeline::with_settings(settings);
+        profiled.set_events(self.pipeline.events().cloned());
+        profiled.set_dependency_root(self.pipeline.dependency_root().map(Path::to_path_buf));
+        let pipeline = std::mem::replace(&mut self.pipeline, profiled);
+        let result = index(self);
+        self.pipeline = pipeline;
+        result
+    }
+
     /// Index a directory with advanced options.
     ///
     /// Provides options for progress reporting, dry-run mode, force re-indexing,
@@ -982,6 +1926,12 @@ impl IndexFacade {
         // Auto-force mode for empty indexes (clean index behaves like --force)
         let force = force || self.document_count().unwrap_or(0) == 0;
 
+        let _lock = self.lock_for_write()?;
+        let logged = self.log_update(IndexUpdate::IndexDirectory {
+            path: dir.to_path_buf(),
+        });
+        let _scope = self.document_index.write_scope();
+
         // Use Pipeline for indexing with progress flag
         // The pipeline manages progress bars internally for clean sequential display
         let pipeline_stats = self.pipeline.index_incremental_with_progress_flag(
@@ -992,14 +1942,18 @@ impl IndexFacade {
             force,
             progress && total_files > 0,
             total_files,
-        )?;
+        );
+        self.log_end(logged, pipeline_stats.is_ok());
+        let pipeline_stats = pipeline_stats?;
 
         // Update tracked paths
         self.add_indexed_path(dir);
+        self.summarize();
 
         // Convert to IndexStats format using pipeline's actual timing
         let mut stats = IndexStats::default();
         stats.files_indexed = pipeline_stats.new_files + pipeline_stats.modified_files;
+        stats.files_failed = pipeline_stats.index_stats.files_failed;
         stats.symbols_found = pipeline_stats.index_stats.symbols_found;
         stats.elapsed = pipeline_stats.elapsed;
 
@@ -1024,6 +1978,13 @@ impl IndexFacade {
         let to_remove: Vec<&PathBuf> = stored_set.difference(&config_set).collect();
 
         let mut stats = SyncStats::default();
+        // Nothing to write when the paths are unchanged, so don't block
+        let _lock = if to_add.is_empty() && to_remove.is_empty() {
+            None
+        } else {
+            Some(self.lock_for_write()?)
+        };
+        let _scope = self.document_index.write_scope();
 
         // Index new directories with progress if enabled
         // Use force=true since these are new directories being indexed for the first time
@@ -1041,6 +2002,9 @@ impl IndexFacade {
                 0
             };
 
+            let logged = self.log_update(IndexUpdate::IndexDirectory {
+                path: path.to_path_buf(),
+            });
             let result = self.pipeline.index_incremental_with_progress_flag(
                 path,
                 Arc::clone(&self.document_index),
@@ -1049,7 +2013,9 @@ impl IndexFacade {
                 true, // force: new directories should be fully indexed
                 progress,
                 file_count,
-            )?;
+            );
+            self.log_end(logged, result.is_ok());
+            let result = result?;
             stats.files_indexed += result.new_files + result.modified_files;
             stats.symbols_found += result.index_stats.symbols_found;
         }
@@ -1074,3 +2040,75 @@ impl IndexFacade {
         Ok(())
     }
 }
+
+/// A substring match in the shape of a full-text search result
+fn substring_result(symbol: Symbol, score: f32, style: SignatureStyle) -> SearchResult {
+    SearchResult {
+        symbol_id: symbol.id,
+        name: symbol.name.to_string(),
+        kind: symbol.kind,
+        file_path: symbol.file_path.to_string(),
+        line: symbol.range.start_line,
+        column: symbol.range.start_column,
+        doc_comment: symbol.doc_comment.as_deref().map(str::to_string),
+        signature: symbol
+            .signature
+            .as_deref()
+            .map(|signature| display_signature(signature, symbol.language_id, style)),
+        module_path: symbol
+            .module_path
+            .as_deref()
+            .unwrap_or_default()
+            .to_string(),
+        score,
+        highlights: Vec::new(),
+        context: None,
+    }
+}
+
+/// Write-ahead log in `index_base`; without one, updates still run but can't
+/// be redone after a crash
+fn open_wal(index_base: &Path, settings: &Settings) -> Option<WriteAheadLog> {
+    match WriteAheadLog::open(index_base, settings.indexing.fsync) {
+        Ok(wal) => Some(wal),
+        Err(e) => {
+            tracing::warn!("[index] failed to open the write-ahead log: {e}");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::wal::WAL_FILE;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_failed_update_is_aborted_in_the_log() {
+        let temp_dir = TempDir::new().unwrap();
+        let settings = Settings {
+            index_path: temp_dir.path().join("index"),
+            ..Settings::default()
+        };
+        let mut facade = IndexFacade::new(Arc::new(settings)).unwrap();
+
+        // The pipeline can't read a file that doesn't exist
+        assert!(
+            facade
+                .index_file(temp_dir.path().join("missing.rs"))
+                .is_err()
+        );
+
+        let log = std::fs::read_to_string(temp_dir.path().join("index").join(WAL_FILE)).unwrap();
+        let ops: Vec<String> = log
+            .lines()
+            .map(|line| {
+                let entry: serde_json::Value = serde_json::from_str(line).unwrap();
+                entry["op"].as_str().unwrap().to_string()
+            })
+            .collect();
+        assert_eq!(ops, ["begin", "abort"]);
+        assert_eq!(facade.recover().unwrap(), 0);
+    }
+}
@@ -1,15 +1,22 @@
 pub mod facade;
 pub mod file_info;
 pub mod progress;
+pub mod projects;
+pub mod proximity;
+pub mod shards;
 pub mod transaction;
+pub mod trigram;
 pub mod walker;
+pub mod warm;
 
 // Parallel pipeline for high-performance indexing
 pub mod pipeline;
 
 // Re-exports
 pub use file_info::{FileInfo, calculate_hash, get_utc_timestamp};
-pub use progress::IndexStats;
+pub use progress::{IndexStats, ProgressEvent, ProgressEventCallback};
+pub use projects::{Project, ProjectBoundaries};
+pub use shards::{ShardManifest, ShardPlan, ShardSet, ShardSource, plan_shards};
 pub use transaction::{FileTransaction, IndexTransaction};
 pub use walker::FileWalker;
 
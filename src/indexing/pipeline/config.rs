@@ -35,6 +35,9 @@ pub struct PipelineConfig {
     /// Number of batches between Tantivy commits
     pub batches_per_commit: usize,
 
+    /// Megabytes of uncommitted batches that trigger an early commit (0 = no limit)
+    pub memory_budget_mb: usize,
+
     /// Enable detailed stage tracing (timing, memory, throughput)
     pub pipeline_tracing: bool,
 }
@@ -54,6 +57,7 @@ impl Default for PipelineConfig {
             parsed_channel_size: 1000,
             batch_channel_size: 20,
             batches_per_commit: 10,
+            memory_budget_mb: 0,
             pipeline_tracing: false,
         }
     }
@@ -70,6 +74,7 @@ impl PipelineConfig {
     /// Also reads:
     /// - `indexing.batch_size` -> batch_size
     /// - `indexing.batches_per_commit` -> batches_per_commit
+    /// - `indexing.memory_budget_mb` -> memory_budget_mb
     /// - `indexing.pipeline_tracing` -> pipeline_tracing
     pub fn from_settings(settings: &Settings) -> Self {
         let indexing = &settings.indexing;
@@ -97,6 +102,7 @@ impl PipelineConfig {
             parsed_channel_size,
             batch_channel_size,
             batches_per_commit: indexing.batches_per_commit,
+            memory_budget_mb: indexing.memory_budget_mb,
             pipeline_tracing: indexing.pipeline_tracing,
         }
     }
@@ -113,6 +119,7 @@ impl PipelineConfig {
             parsed_channel_size: 500,
             batch_channel_size: 10,
             batches_per_commit: 5,
+            memory_budget_mb: 0,
             pipeline_tracing: false,
         }
     }
@@ -130,6 +137,7 @@ impl PipelineConfig {
             parsed_channel_size: 2000,
             batch_channel_size: 50,
             batches_per_commit: 20,
+            memory_budget_mb: 0,
             pipeline_tracing: false,
         }
     }
@@ -152,6 +160,12 @@ impl PipelineConfig {
         self
     }
 
+    /// Set the memory budget for uncommitted batches (0 disables it)
+    pub fn with_memory_budget_mb(mut self, mb: usize) -> Self {
+        self.memory_budget_mb = mb;
+        self
+    }
+
     /// Calculate total channel buffer memory (approximate)
     pub fn estimated_memory_mb(&self) -> usize {
         // Rough estimates:
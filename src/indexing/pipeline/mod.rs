@@ -31,17 +31,22 @@
 
 pub mod config;
 pub mod metrics;
+pub mod resolution_cache;
 pub mod stages;
 pub mod types;
 
 pub use config::PipelineConfig;
 pub use metrics::{PipelineMetrics, StageMetrics, StageTracker};
+pub use resolution_cache::{ResolutionCache, ResolutionCacheStats};
 pub use stages::cleanup::{CleanupStage, CleanupStats};
 pub use stages::context::{ContextStage, ContextStats};
 pub use stages::embed::{EmbedStage, EmbedStats};
 pub use stages::parse::{ParseStage, init_parser_cache, parse_file};
 pub use stages::resolve::{ResolveStage, ResolveStats};
 pub use stages::semantic_embed::{SemanticEmbedStage, SemanticEmbedStats};
+pub use stages::summarize::{
+    GeneratedDescription, SUMMARIES_FILE, SummarizeStage, SummarizeStats, SummaryStore,
+};
 pub use stages::write::{WriteStage, WriteStats};
 pub use types::{
     DiscoverResult, EmbeddingBatch, FileContent, FileRegistration, IndexBatch, ParsedFile,
@@ -53,7 +58,9 @@ pub use types::{
 use crate::FileId;
 use crate::RelationKind;
 use crate::Settings;
-use crate::indexing::IndexStats;
+use crate::deps::link::DependencyLinker;
+use crate::indexing::shards::ForeignSymbols;
+use crate::indexing::{IndexStats, ProgressEventCallback};
 use crate::io::status_line::DualProgressBar;
 use crate::parsing::ParserFactory;
 use crate::semantic::SimpleSemanticSearch;
@@ -92,12 +99,30 @@ type ParseSimpleJoinHandle = thread::JoinHandle<(usize, usize)>;
 pub struct Pipeline {
     settings: Arc<Settings>,
     config: PipelineConfig,
+    /// Per-file progress events from the read and parse stages
+    events: Option<ProgressEventCallback>,
+    /// Symbols of the other shards, tried for what Phase 2 can't resolve
+    foreign: Option<Arc<ForeignSymbols>>,
+    /// Directory of the dependency indexes Phase 2 links calls into
+    dependency_root: Option<PathBuf>,
+    /// Receives the relationships Phase 2 leaves unresolved
+    unresolved_sink: Option<UnresolvedSink>,
 }
 
+/// Collects the relationships Phase 2 leaves unresolved
+pub type UnresolvedSink = Arc<Mutex<Vec<UnresolvedRelationship>>>;
+
 impl Pipeline {
     /// Create a new pipeline with the given settings and configuration.
     pub fn new(settings: Arc<Settings>, config: PipelineConfig) -> Self {
-        Self { settings, config }
+        Self {
+            settings,
+            config,
+            events: None,
+            foreign: None,
+            dependency_root: None,
+            unresolved_sink: None,
+        }
     }
 
     /// Create a pipeline with configuration derived from settings.
@@ -116,6 +141,42 @@ impl Pipeline {
         &self.settings
     }
 
+    /// Get the progress event callback.
+    pub fn events(&self) -> Option<&ProgressEventCallback> {
+        self.events.as_ref()
+    }
+
+    /// Send per-file progress events to `events` while indexing.
+    ///
+    /// Called from the read and parse worker threads, so events from
+    /// different files interleave.
+    pub fn set_events(&mut self, events: Option<ProgressEventCallback>) {
+        self.events = events;
+    }
+
+    /// Resolve what Phase 2 can't resolve locally against the symbols of
+    /// the other shards of a sharded index.
+    pub fn set_foreign_symbols(&mut self, foreign: Option<Arc<ForeignSymbols>>) {
+        self.foreign = foreign;
+    }
+
+    /// Link the calls Phase 2 can't resolve in the project to the symbols of
+    /// the dependency indexes under `root`.
+    pub fn set_dependency_root(&mut self, root: Option<PathBuf>) {
+        self.dependency_root = root;
+    }
+
+    /// Directory of the dependency indexes Phase 2 links calls into.
+    pub fn dependency_root(&self) -> Option<&Path> {
+        self.dependency_root.as_deref()
+    }
+
+    /// Hand the relationships Phase 2 leaves unresolved to `sink`, so they
+    /// can be resolved again once the other shards exist.
+    pub fn set_unresolved_sink(&mut self, sink: Option<UnresolvedSink>) {
+        self.unresolved_sink = sink;
+    }
+
     // ─────────────────────────────────────────────────────────────────────────────
     // Helper methods for consistent data flow
     // ─────────────────────────────────────────────────────────────────────────────
@@ -222,6 +283,7 @@ impl Pipeline {
                 }
             }
         }
+        crate::metrics::record_parse_errors(errors);
 
         (
             files,
@@ -253,6 +315,7 @@ impl Pipeline {
                 }
             }
         }
+        crate::metrics::record_parse_errors(errors);
 
         (files, errors)
     }
@@ -270,6 +333,7 @@ impl Pipeline {
     /// - IndexStats: Statistics about the indexing operation
     /// - `Vec<UnresolvedRelationship>`: Pending references for Phase 2 resolution
     /// - SymbolLookupCache: In-memory cache for O(1) Phase 2 resolution
+    #[tracing::instrument(name = "index_directory", skip_all, fields(root = %root.display()))]
     pub fn index_directory(
         &self,
         root: &Path,
@@ -295,15 +359,18 @@ impl Pipeline {
 
         // Clone settings for threads
         let settings = Arc::clone(&self.settings);
+        let events = self.events.clone();
         let parse_threads = self.config.parse_threads;
         let read_threads = self.config.read_threads;
         let discover_threads = self.config.discover_threads;
         let batch_size = self.config.batch_size;
         let batches_per_commit = self.config.batches_per_commit;
+        let memory_budget_mb = self.config.memory_budget_mb;
         let tracing_enabled = self.config.pipeline_tracing;
 
         // Stage 1: DISCOVER - parallel file walk
         let discover_root = root.to_path_buf();
+        let language_overrides = self.settings.indexing.language_overrides.clone();
         let discover_handle = thread::spawn(move || {
             let tracker = if tracing_enabled {
                 Some(StageTracker::new("DISCOVER", discover_threads))
@@ -311,7 +378,8 @@ impl Pipeline {
                 None
             };
 
-            let stage = DiscoverStage::new(discover_root, discover_threads);
+            let stage = DiscoverStage::new(discover_root, discover_threads)
+                .with_language_overrides(language_overrides);
             let result = stage.run(path_tx);
 
             // Record metrics
@@ -329,8 +397,10 @@ impl Pipeline {
                 let rx = path_rx.clone();
                 let tx = content_tx.clone();
                 let workspace_root = workspace_root.clone();
+                let events = events.clone();
                 thread::spawn(move || {
-                    let stage = ReadStage::with_workspace_root(1, workspace_root);
+                    let stage =
+                        ReadStage::with_workspace_root(1, workspace_root).with_events(events);
                     stage.run(rx, tx)
                 })
             })
@@ -344,12 +414,13 @@ impl Pipeline {
                 let rx = content_rx.clone();
                 let tx = parsed_tx.clone();
                 let settings = Arc::clone(&settings);
+                let events = events.clone();
                 thread::spawn(move || {
                     let start = Instant::now();
                     // Initialize thread-local parser cache
                     init_parser_cache(settings.clone());
 
-                    let stage = ParseStage::new(settings);
+                    let stage = ParseStage::new(settings).with_events(events);
                     let mut parsed_count = 0;
                     let mut error_count = 0;
                     let mut symbol_count = 0;
@@ -432,7 +503,8 @@ impl Pipeline {
                 None
             };
 
-            let stage = IndexStage::new(index, batches_per_commit);
+            let stage =
+                IndexStage::new(index, batches_per_commit).with_memory_budget_mb(memory_budget_mb);
             let result = stage.run(batch_rx);
 
             // Record items and wait times before finalizing
@@ -561,16 +633,20 @@ impl Pipeline {
         let (batch_tx, batch_rx) = bounded(self.config.batch_channel_size);
 
         let settings = Arc::clone(&self.settings);
+        let events = self.events.clone();
         let parse_threads = self.config.parse_threads;
         let read_threads = self.config.read_threads;
         let discover_threads = self.config.discover_threads;
         let batch_size = self.config.batch_size;
         let batches_per_commit = self.config.batches_per_commit;
+        let memory_budget_mb = self.config.memory_budget_mb;
 
         // Stage 1: DISCOVER
         let discover_root = root.to_path_buf();
+        let language_overrides = self.settings.indexing.language_overrides.clone();
         let discover_handle = thread::spawn(move || {
-            let stage = DiscoverStage::new(discover_root, discover_threads);
+            let stage = DiscoverStage::new(discover_root, discover_threads)
+                .with_language_overrides(language_overrides);
             stage.run(path_tx)
         });
 
@@ -581,8 +657,10 @@ impl Pipeline {
                 let rx = path_rx.clone();
                 let tx = content_tx.clone();
                 let workspace_root = workspace_root.clone();
+                let events = events.clone();
                 thread::spawn(move || {
-                    let stage = ReadStage::with_workspace_root(1, workspace_root);
+                    let stage =
+                        ReadStage::with_workspace_root(1, workspace_root).with_events(events);
                     stage.run(rx, tx)
                 })
             })
@@ -596,9 +674,10 @@ impl Pipeline {
                 let rx = content_rx.clone();
                 let tx = parsed_tx.clone();
                 let settings = Arc::clone(&settings);
+                let events = events.clone();
                 thread::spawn(move || {
                     init_parser_cache(settings.clone());
-                    let stage = ParseStage::new(settings);
+                    let stage = ParseStage::new(settings).with_events(events);
                     let mut parsed = 0;
                     let mut errors = 0;
 
@@ -630,7 +709,8 @@ impl Pipeline {
         // Stage 5: INDEX with optional progress
         // Clone index Arc for metadata update after pipeline completes
         let index_for_metadata = Arc::clone(&index);
-        let mut index_stage = IndexStage::new(index, batches_per_commit);
+        let mut index_stage =
+            IndexStage::new(index, batches_per_commit).with_memory_budget_mb(memory_budget_mb);
         if let Some(prog) = progress {
             index_stage = index_stage.with_progress(prog);
         }
@@ -692,6 +772,7 @@ impl Pipeline {
     }
 
     /// Run Phase 2 with optional progress bar.
+    #[tracing::instrument(name = "phase2", skip_all, fields(relationships = unresolved.len()))]
     pub fn run_phase2_with_progress(
         &self,
         unresolved: Vec<UnresolvedRelationship>,
@@ -709,10 +790,13 @@ impl Pipeline {
                 calls_resolved: 0,
                 other_resolved: 0,
                 unresolved: 0,
+                resolution_cache: ResolutionCacheStats::default(),
                 elapsed: start.elapsed(),
             });
         }
 
+        let resolution_cache = Arc::new(Self::load_resolution_cache(&index, &symbol_cache));
+
         // Create stages
         let factory = Arc::new(ParserFactory::new(Arc::clone(&self.settings)));
         let context_stage = ContextStage::new(
@@ -720,7 +804,8 @@ impl Pipeline {
             Arc::clone(&index),
             factory,
             Arc::clone(&self.settings),
-        );
+        )
+        .with_resolution_cache(Arc::clone(&resolution_cache));
         let mut write_stage = WriteStage::new(Arc::clone(&index));
 
         // Split relationships by kind
@@ -774,7 +859,16 @@ impl Pipeline {
         if !others.is_empty() {
             let contexts = context_stage.build_contexts(others);
             let behaviors = context_stage.behaviors();
-            let resolve_stage = ResolveStage::new(Arc::clone(&symbol_cache), behaviors);
+            let dependencies = self
+                .dependency_root
+                .as_deref()
+                .and_then(|root| DependencyLinker::open(root, &self.settings, Arc::clone(&index)))
+                .map(Arc::new);
+            let resolve_stage = ResolveStage::new(Arc::clone(&symbol_cache), behaviors)
+                .with_inheritance(&contexts)
+                .with_foreign_symbols(self.foreign.clone())
+                .with_dependencies(dependencies.clone())
+                .with_unresolved_sink(self.unresolved_sink.clone());
 
             for ctx in contexts {
                 let rel_count = ctx.unresolved_rels.len() as u64;
@@ -792,6 +886,11 @@ impl Pipeline {
                 }
             }
 
+            // External symbols the calls were linked to go in the final commit
+            if let Some(dependencies) = &dependencies {
+                dependencies.flush()?;
+            }
+
             // Final commit
             write_stage
                 .flush()
@@ -802,6 +901,11 @@ impl Pipeline {
             - stats.defines_resolved
             - stats.calls_resolved
             - stats.other_resolved;
+
+        if let Err(e) = resolution_cache.save(index.path()) {
+            tracing::warn!(target: "pipeline", "Failed to save resolution cache: {e}");
+        }
+        stats.resolution_cache = resolution_cache.stats();
         stats.elapsed = start.elapsed();
 
         tracing::info!(
@@ -814,10 +918,44 @@ impl Pipeline {
             stats.other_resolved,
             stats.elapsed
         );
+        let cache_stats = stats.resolution_cache;
+        tracing::info!(
+            target: "pipeline",
+            "Resolution cache: {} hits, {} misses ({:.1}% hit rate), {} invalidated, {} entries",
+            cache_stats.hits,
+            cache_stats.misses,
+            cache_stats.hit_rate() * 100.0,
+            cache_stats.invalidated,
+            cache_stats.entries
+        );
 
         Ok(stats)
     }
 
+    /// Load the import resolution cache kept with `index` and drop entries
+    /// invalidated by files changed since it was saved.
+    fn load_resolution_cache(
+        index: &DocumentIndex,
+        symbol_cache: &SymbolLookupCache,
+    ) -> ResolutionCache {
+        let mut cache = ResolutionCache::load(index.path());
+        match index.query_file_info() {
+            Ok(files) => {
+                let hashes = files
+                    .into_iter()
+                    .map(|(file_id, _, hash, _)| (file_id, hash))
+                    .collect();
+                cache.refresh(hashes, symbol_cache);
+            }
+            Err(e) => {
+                // Without file hashes nothing can be trusted
+                tracing::warn!(target: "pipeline", "Failed to read file hashes: {e}");
+                cache = ResolutionCache::new();
+            }
+        }
+        cache
+    }
+
     /// Run full pipeline: Phase 1 (indexing) + Phase 2 (resolution).
     ///
     /// Convenience method that runs both phases in sequence.
@@ -858,6 +996,7 @@ impl Pipeline {
     ///
     /// # Returns
     /// `SingleFileStats` with indexing results or `Cached` if unchanged
+    #[tracing::instrument(level = "debug", name = "index_file", skip_all, fields(path = %path.display()))]
     pub fn index_file_single(
         &self,
         path: &Path,
@@ -890,7 +1029,7 @@ impl Pipeline {
             })?;
 
         // Read file using ReadStage (with absolute path for fs access)
-        let read_stage = ReadStage::new(1);
+        let read_stage = ReadStage::new(1).with_events(self.events.clone());
         let mut file_content = read_stage.read_single(&path.to_path_buf())?;
         // Use normalized path for storage consistency with full index
         file_content.path = normalized_path.to_path_buf();
@@ -928,7 +1067,8 @@ impl Pipeline {
 
         // Parse file
         init_parser_cache(Arc::clone(&self.settings));
-        let parse_stage = ParseStage::new(Arc::clone(&self.settings));
+        let parse_stage =
+            ParseStage::new(Arc::clone(&self.settings)).with_events(self.events.clone());
         let parsed = parse_stage.parse(file_content)?;
 
         // Collect into a batch (now includes embedding candidates)
@@ -997,6 +1137,7 @@ impl Pipeline {
                 }
             }
         }
+        save_embedding_cache(&embedding_pool);
 
         Ok(SingleFileStats {
             file_id,
@@ -1158,7 +1299,8 @@ impl Pipeline {
             // Incremental mode: discover first, then create bar with actual count
             let discover_stage = DiscoverStage::new(root, self.config.discover_threads)
                 .with_index(Arc::clone(&index))
-                .with_workspace_root(self.settings.workspace_root.clone());
+                .with_workspace_root(self.settings.workspace_root.clone())
+                .with_language_overrides(self.settings.indexing.language_overrides.clone());
             let discover_result = discover_stage.run_incremental()?;
 
             if discover_result.is_empty() {
@@ -1263,6 +1405,7 @@ impl Pipeline {
                 let _ = guard.save(&semantic_path);
             }
         }
+        save_embedding_cache(&embedding_pool);
 
         Ok(IncrementalStats {
             new_files: discover_counts.0,
@@ -1276,6 +1419,7 @@ impl Pipeline {
     }
 
     /// Index a directory with optional progress bar.
+    #[tracing::instrument(name = "index_incremental", skip_all, fields(root = %root.display(), force = force))]
     pub fn index_incremental_with_progress(
         &self,
         root: &Path,
@@ -1303,7 +1447,8 @@ impl Pipeline {
         // Incremental mode: detect changes
         let discover_stage = DiscoverStage::new(root, self.config.discover_threads)
             .with_index(Arc::clone(&index))
-            .with_workspace_root(self.settings.workspace_root.clone());
+            .with_workspace_root(self.settings.workspace_root.clone())
+            .with_language_overrides(self.settings.indexing.language_overrides.clone());
         let discover_result = discover_stage.run_incremental()?;
 
         tracing::info!(
@@ -1419,6 +1564,7 @@ impl Pipeline {
                     reason: format!("Failed to save embeddings: {e}"),
                 })?;
         }
+        save_embedding_cache(&embedding_pool);
 
         Ok(IncrementalStats {
             new_files: discover_result.new_files.len(),
@@ -1459,15 +1605,19 @@ impl Pipeline {
         let (batch_tx, batch_rx) = bounded(self.config.batch_channel_size);
 
         let settings = Arc::clone(&self.settings);
+        let events = self.events.clone();
         let parse_threads = self.config.parse_threads;
         let batch_size = self.config.batch_size;
         let batches_per_commit = self.config.batches_per_commit;
+        let memory_budget_mb = self.config.memory_budget_mb;
 
         // Stage 1: READ - Send files directly (already have the paths)
         let files_to_read = files.to_vec();
         let workspace_root = settings.workspace_root.clone();
+        let read_events = events.clone();
         let read_handle = thread::spawn(move || {
-            let stage = ReadStage::new(1);
+            let stage =
+                ReadStage::with_workspace_root(1, workspace_root.clone()).with_events(read_events);
             let mut count = 0;
             let mut errors = 0;
 
@@ -1500,9 +1650,10 @@ impl Pipeline {
                 let rx = content_rx.clone();
                 let tx = parsed_tx.clone();
                 let settings = Arc::clone(&settings);
+                let events = events.clone();
                 thread::spawn(move || {
                     init_parser_cache(settings.clone());
-                    let stage = ParseStage::new(settings);
+                    let stage = ParseStage::new(settings).with_events(events);
                     let mut parsed = 0;
                     let mut errors = 0;
 
@@ -1555,7 +1706,8 @@ impl Pipeline {
         // Stage 4b: INDEX (parallel with EMBED)
         // Clone index Arc for metadata update after pipeline completes
         let index_for_metadata = Arc::clone(&index);
-        let mut index_stage = IndexStage::new(index, batches_per_commit);
+        let mut index_stage =
+            IndexStage::new(index, batches_per_commit).with_memory_budget_mb(memory_budget_mb);
         if let Some(prog) = progress {
             index_stage = index_stage.with_progress(prog);
         }
@@ -1670,7 +1822,7 @@ impl Pipeline {
                 root,
                 Arc::clone(&index),
                 Arc::clone(sem),
-                embedding_pool,
+                embedding_pool.clone(),
                 progress,
                 None, // TODO: Wire DualProgressBar
             )?
@@ -1737,6 +1889,7 @@ impl Pipeline {
                     reason: format!("Failed to save embeddings: {e}"),
                 })?;
         }
+        save_embedding_cache(&embedding_pool);
 
         Ok(IncrementalStats {
             new_files: index_stats.files_indexed,
@@ -1790,15 +1943,18 @@ impl Pipeline {
         };
 
         let settings = Arc::clone(&self.settings);
+        let events = self.events.clone();
         let parse_threads = self.config.parse_threads;
         let read_threads = self.config.read_threads;
         let discover_threads = self.config.discover_threads;
         let batch_size = self.config.batch_size;
         let batches_per_commit = self.config.batches_per_commit;
+        let memory_budget_mb = self.config.memory_budget_mb;
         let tracing_enabled = self.config.pipeline_tracing;
 
         // Stage 1: DISCOVER
         let discover_root = root.to_path_buf();
+        let language_overrides = self.settings.indexing.language_overrides.clone();
         let discover_handle = thread::spawn(move || {
             let tracker = if tracing_enabled {
                 Some(StageTracker::new("DISCOVER", discover_threads))
@@ -1806,7 +1962,8 @@ impl Pipeline {
                 None
             };
 
-            let stage = DiscoverStage::new(discover_root, discover_threads);
+            let stage = DiscoverStage::new(discover_root, discover_threads)
+                .with_language_overrides(language_overrides);
             let result = stage.run(path_tx);
 
             if let (Some(tracker), Ok(count)) = (&tracker, &result) {
@@ -1823,8 +1980,10 @@ impl Pipeline {
                 let rx = path_rx.clone();
                 let tx = content_tx.clone();
                 let workspace_root = workspace_root.clone();
+                let events = events.clone();
                 thread::spawn(move || {
-                    let stage = ReadStage::with_workspace_root(1, workspace_root);
+                    let stage =
+                        ReadStage::with_workspace_root(1, workspace_root).with_events(events);
                     stage.run(rx, tx)
                 })
             })
@@ -1838,10 +1997,11 @@ impl Pipeline {
                 let rx = content_rx.clone();
                 let tx = parsed_tx.clone();
                 let settings = Arc::clone(&settings);
+                let events = events.clone();
                 thread::spawn(move || {
                     let start = Instant::now();
                     init_parser_cache(settings.clone());
-                    let stage = ParseStage::new(settings);
+                    let stage = ParseStage::new(settings).with_events(events);
                     let mut parsed = 0;
                     let mut errors = 0;
                     let mut symbol_count = 0;
@@ -1949,7 +2109,8 @@ impl Pipeline {
         // Completion callback to freeze timer when INDEX finishes
         let index_complete = dual_progress.as_ref().map(Arc::clone);
         let index_handle = {
-            let mut index_stage = IndexStage::new(index, batches_per_commit);
+            let mut index_stage =
+                IndexStage::new(index, batches_per_commit).with_memory_budget_mb(memory_budget_mb);
 
             // Prefer dual_progress callback over single progress bar
             if let Some(ref dp) = dual_progress {
@@ -2150,6 +2311,35 @@ impl Pipeline {
         Ok((stats, pending, cache, metrics))
     }
 
+    /// Describe undocumented public symbols with the `[summaries]` endpoint.
+    ///
+    /// Returns `None` when summaries are disabled. Descriptions go to `store`
+    /// only; the index itself is not changed.
+    pub fn summarize(
+        &self,
+        index: &DocumentIndex,
+        store: &mut SummaryStore,
+    ) -> PipelineResult<Option<SummarizeStats>> {
+        if !self.settings.summaries.enabled {
+            return Ok(None);
+        }
+        let stage = SummarizeStage::new(
+            &self.settings.summaries,
+            self.settings.workspace_root.clone(),
+        );
+        let stats = stage.run(index, store)?;
+        tracing::info!(
+            target: "pipeline",
+            "[summarize] {} described, {} reused, {} failed, {} deferred, {} removed",
+            stats.described,
+            stats.reused,
+            stats.failed,
+            stats.deferred,
+            stats.removed
+        );
+        Ok(Some(stats))
+    }
+
     /// Synchronize index with configuration (directory-level change detection).
     ///
     /// Compares stored indexed paths (from IndexMetadata) with current config paths
@@ -2331,6 +2521,16 @@ impl Pipeline {
     }
 }
 
+/// Persist the embeddings computed by `pool` for later runs. A cache that
+/// cannot be written only costs recomputation, so failures are logged.
+fn save_embedding_cache(pool: &Option<Arc<crate::semantic::EmbeddingPool>>) {
+    if let Some(pool) = pool {
+        if let Err(e) = pool.save_cache() {
+            tracing::warn!(target: "pipeline", "Failed to save embedding cache: {e}");
+        }
+    }
+}
+
 /// Statistics from sync_with_config operation.
 #[derive(Debug, Default)]
 pub struct SyncStats {
@@ -2382,6 +2582,8 @@ pub struct Phase2Stats {
     pub other_resolved: usize,
     /// Failed to resolve
     pub unresolved: usize,
+    /// Import resolution cache hits and misses
+    pub resolution_cache: ResolutionCacheStats,
     /// Time taken
     pub elapsed: std::time::Duration,
 }
@@ -2798,4 +3000,73 @@ export { processUser, main };
             "Every stage must run on its own OS thread"
         );
     }
+
+    #[test]
+    fn test_phase2_links_calls_into_dependencies() {
+        use crate::deps::link::EXTERNAL_TAG;
+        use crate::deps::{DependencyManifest, deps_dir, write_dependency_index};
+        use crate::{Range, Symbol, SymbolId, SymbolKind};
+
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let src_dir = temp_dir.path().join("src");
+        fs::create_dir_all(&src_dir).expect("Failed to create src dir");
+        fs::write(
+            src_dir.join("module.nix"),
+            "{ lib }:\n{\n  enableOption = lib.mkOption { default = false; };\n}\n",
+        )
+        .expect("Failed to write module.nix");
+
+        let settings = Settings {
+            index_path: temp_dir.path().join("index"),
+            ..Settings::default()
+        };
+        let deps_root = deps_dir(&settings.index_path);
+        let mk_option = Symbol::new(
+            SymbolId::new(1).unwrap(),
+            "mkOption",
+            SymbolKind::Function,
+            FileId::new(1).unwrap(),
+            Range::new(0, 0, 0, 0),
+        )
+        .with_file_path("lib/options.nix")
+        .with_module_path("lib.options")
+        .with_language_id(crate::parsing::LanguageId::new("nix"));
+        write_dependency_index(
+            &deps_root,
+            &settings,
+            DependencyManifest::new("nixpkgs-lib", "nix", "test"),
+            vec![mk_option],
+        )
+        .expect("Failed to write dependency index");
+
+        let index = Arc::new(
+            DocumentIndex::new(settings.index_path.join("tantivy"), &settings)
+                .expect("Failed to create index"),
+        );
+        let mut pipeline = Pipeline::with_settings(Arc::new(settings));
+        pipeline.set_dependency_root(Some(deps_root));
+
+        let (_, unresolved, symbol_cache) = pipeline
+            .index_directory(&src_dir, Arc::clone(&index))
+            .expect("Phase 1 failed");
+        let stats = pipeline
+            .run_phase2(unresolved, Arc::new(symbol_cache), Arc::clone(&index))
+            .expect("Phase 2 failed");
+        assert!(stats.calls_resolved >= 1);
+
+        let external = index
+            .find_symbols_by_name("mkOption", None)
+            .unwrap()
+            .into_iter()
+            .find(|symbol| symbol.tags.iter().any(|tag| &**tag == EXTERNAL_TAG))
+            .expect("mkOption is linked as an external symbol");
+        assert_eq!(external.project.as_deref(), Some("nixpkgs-lib"));
+
+        let callers = index
+            .get_relationships_to(external.id, RelationKind::Calls)
+            .unwrap();
+        assert_eq!(callers.len(), 1);
+        let caller = index.find_symbol_by_id(callers[0].0).unwrap().unwrap();
+        assert_eq!(&*caller.name, "enableOption");
+    }
 }
@@ -0,0 +1,435 @@
+//! Persisted import resolution cache
+//!
+//! CONTEXT resolves every import of every file to a symbol on each Phase 2
+//! run, even when neither side changed. This cache remembers the answer per
+//! (importing module, import path) across runs, stored as JSON next to the
+//! Tantivy index.
+//!
+//! Entries are keyed by module rather than file, so every file of a module
+//! shares them. An entry is dropped when the file defining its symbol changed
+//! or disappeared, and when a changed or new file defines a symbol of the same
+//! name, since that symbol may now be the better match. File changes are
+//! detected by comparing the content hashes recorded with the cache against
+//! the index's file records.
+//!
+//! Only successful resolutions are cached; imports of external packages are
+//! resolved afresh each time.
+
+use crate::indexing::pipeline::types::SymbolLookupCache;
+use crate::parsing::{CallerContext, Import, PipelineSymbolCache, ResolveResult};
+use crate::types::{FileId, Range, SymbolId};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// File name of the cache inside the index directory
+pub const RESOLUTION_CACHE_FILE: &str = "resolution_cache.json";
+
+/// What an import resolved to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedResolution {
+    pub symbol_id: SymbolId,
+    /// File defining the symbol
+    pub file_id: FileId,
+    /// Symbol name, to invalidate on new candidates
+    pub name: String,
+}
+
+/// Hit-rate statistics of one run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResolutionCacheStats {
+    /// Entries held after the run
+    pub entries: usize,
+    pub hits: usize,
+    pub misses: usize,
+    /// Entries dropped because files changed
+    pub invalidated: usize,
+}
+
+impl ResolutionCacheStats {
+    /// Share of lookups answered from the cache, 0.0 without lookups
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+/// On-disk form of the cache
+#[derive(Serialize, Deserialize)]
+struct PersistedCache {
+    version: u32,
+    /// Content hash of every indexed file when the cache was saved
+    file_hashes: HashMap<u32, String>,
+    entries: Vec<PersistedEntry>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PersistedEntry {
+    importing_module: String,
+    import_path: String,
+    symbol_id: u32,
+    file_id: u32,
+    name: String,
+}
+
+/// Import resolutions keyed by (importing module, import path)
+#[derive(Debug, Default)]
+pub struct ResolutionCache {
+    entries: DashMap<(String, String), CachedResolution>,
+    /// Content hashes the entries are valid for
+    file_hashes: HashMap<FileId, String>,
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+    invalidated: usize,
+}
+
+impl ResolutionCache {
+    /// Version of the on-disk format
+    const CURRENT_VERSION: u32 = 1;
+
+    /// Create an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load the cache saved in `dir`.
+    ///
+    /// A missing, unreadable, or outdated file yields an empty cache; the
+    /// cache only saves work, so there is nothing to report.
+    pub fn load(dir: &Path) -> Self {
+        let path = dir.join(RESOLUTION_CACHE_FILE);
+        let persisted = match std::fs::read_to_string(&path) {
+            Ok(json) => match serde_json::from_str::<PersistedCache>(&json) {
+                Ok(persisted) if persisted.version == Self::CURRENT_VERSION => persisted,
+                Ok(_) => return Self::new(),
+                Err(e) => {
+                    tracing::debug!(target: "pipeline", "ignoring {}: {e}", path.display());
+                    return Self::new();
+                }
+            },
+            Err(_) => return Self::new(),
+        };
+
+        let entries = DashMap::with_capacity(persisted.entries.len());
+        for entry in persisted.entries {
+            let (Some(symbol_id), Some(file_id)) =
+                (SymbolId::new(entry.symbol_id), FileId::new(entry.file_id))
+            else {
+                continue;
+            };
+            entries.insert(
+                (entry.importing_module, entry.import_path),
+                CachedResolution {
+                    symbol_id,
+                    file_id,
+                    name: entry.name,
+                },
+            );
+        }
+        Self {
+            entries,
+            file_hashes: persisted
+                .file_hashes
+                .into_iter()
+                .filter_map(|(id, hash)| Some((FileId::new(id)?, hash)))
+                .collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Save the cache to `dir`.
+    pub fn save(&self, dir: &Path) -> std::io::Result<()> {
+        let mut entries: Vec<PersistedEntry> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let (importing_module, import_path) = entry.key().clone();
+                let resolution = entry.value();
+                PersistedEntry {
+                    importing_module,
+                    import_path,
+                    symbol_id: resolution.symbol_id.value(),
+                    file_id: resolution.file_id.value(),
+                    name: resolution.name.clone(),
+                }
+            })
+            .collect();
+        entries.sort_by(|a, b| {
+            (&a.importing_module, &a.import_path).cmp(&(&b.importing_module, &b.import_path))
+        });
+        let persisted = PersistedCache {
+            version: Self::CURRENT_VERSION,
+            file_hashes: self
+                .file_hashes
+                .iter()
+                .map(|(id, hash)| (id.value(), hash.clone()))
+                .collect(),
+            entries,
+        };
+        let json = serde_json::to_string_pretty(&persisted).map_err(std::io::Error::other)?;
+        std::fs::write(dir.join(RESOLUTION_CACHE_FILE), json)
+    }
+
+    /// Bring the cache up to date with the index's current file hashes.
+    ///
+    /// Drops entries into changed or removed files, and entries whose name
+    /// a changed or new file defines (looked up in `symbols`).
+    pub fn refresh(&mut self, file_hashes: HashMap<FileId, String>, symbols: &SymbolLookupCache) {
+        let removed = self
+            .file_hashes
+            .keys()
+            .filter(|id| !file_hashes.contains_key(id));
+        let changed: HashSet<FileId> = file_hashes
+            .iter()
+            .filter(|(id, hash)| self.file_hashes.get(id) != Some(hash))
+            .map(|(id, _)| *id)
+            .chain(removed.copied())
+            .collect();
+
+        if !changed.is_empty() {
+            let names: HashSet<String> = changed
+                .iter()
+                .flat_map(|id| symbols.symbols_in_file(*id))
+                .filter_map(|id| symbols.get(id))
+                .map(|symbol| symbol.name.to_string())
+                .collect();
+            let before = self.entries.len();
+            self.entries.retain(|_, resolution| {
+                !changed.contains(&resolution.file_id) && !names.contains(&resolution.name)
+            });
+            self.invalidated += before - self.entries.len();
+        }
+        self.file_hashes = file_hashes;
+    }
+
+    /// Cached resolution of an import, counted as a hit or miss
+    pub fn get(&self, importing_module: &str, import_path: &str) -> Option<CachedResolution> {
+        let found = self
+            .entries
+            .get(&(importing_module.to_string(), import_path.to_string()))
+            .map(|entry| entry.value().clone());
+        let counter = if found.is_some() {
+            &self.hits
+        } else {
+            &self.misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        found
+    }
+
+    /// Remember what an import resolved to.
+    pub fn insert(&self, importing_module: &str, import_path: &str, resolution: CachedResolution) {
+        self.entries.insert(
+            (importing_module.to_string(), import_path.to_string()),
+            resolution,
+        );
+    }
+
+    /// Forget one import's resolution.
+    pub fn remove(&self, importing_module: &str, import_path: &str) {
+        self.entries
+            .remove(&(importing_module.to_string(), import_path.to_string()));
+    }
+
+    /// Number of cached resolutions
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if the cache holds no resolutions.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Statistics since the cache was loaded
+    pub fn stats(&self) -> ResolutionCacheStats {
+        ResolutionCacheStats {
+            entries: self.entries.len(),
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            invalidated: self.invalidated,
+        }
+    }
+}
+
+/// Symbol cache that answers import resolution from a [`ResolutionCache`]
+/// and records fresh answers in it.
+///
+/// Every other lookup goes straight to the wrapped cache.
+pub struct CachingSymbolCache<'a> {
+    inner: &'a dyn PipelineSymbolCache,
+    cache: &'a ResolutionCache,
+}
+
+impl<'a> CachingSymbolCache<'a> {
+    /// Wrap `inner`, reading and recording import resolutions in `cache`.
+    pub fn new(inner: &'a dyn PipelineSymbolCache, cache: &'a ResolutionCache) -> Self {
+        Self { inner, cache }
+    }
+}
+
+impl PipelineSymbolCache for CachingSymbolCache<'_> {
+    fn resolve(
+        &self,
+        name: &str,
+        caller: &CallerContext,
+        to_range: Option<&Range>,
+        imports: &[Import],
+    ) -> ResolveResult {
+        self.inner.resolve(name, caller, to_range, imports)
+    }
+
+    fn resolve_import(
+        &self,
+        name: &str,
+        caller: &CallerContext,
+        importing_module: Option<&str>,
+        import_path: &str,
+        imports: &[Import],
+    ) -> ResolveResult {
+        // Files without a module path share no key worth caching under
+        let Some(module) = importing_module.filter(|m| !m.is_empty()) else {
+            return self
+                .inner
+                .resolve_import(name, caller, importing_module, import_path, imports);
+        };
+
+        if let Some(cached) = self.cache.get(module, import_path) {
+            // The symbol may have been renumbered if its file was re-parsed
+            // without the content changing (e.g. a forced re-index)
+            match self.inner.get(cached.symbol_id) {
+                Some(symbol) if symbol.name.as_ref() != cached.name => {
+                    self.cache.remove(module, import_path);
+                }
+                _ => return ResolveResult::Found(cached.symbol_id),
+            }
+        }
+
+        let result =
+            self.inner
+                .resolve_import(name, caller, importing_module, import_path, imports);
+        let resolved = match &result {
+            ResolveResult::Found(id) => Some(*id),
+            ResolveResult::Ambiguous(ids) => ids.first().copied(),
+            ResolveResult::NotFound => None,
+        };
+        if let Some(symbol) = resolved.and_then(|id| self.inner.get(id)) {
+            self.cache.insert(
+                module,
+                import_path,
+                CachedResolution {
+                    symbol_id: symbol.id,
+                    file_id: symbol.file_id,
+                    name: symbol.name.to_string(),
+                },
+            );
+        }
+        result
+    }
+
+    fn get(&self, id: SymbolId) -> Option<crate::Symbol> {
+        self.inner.get(id)
+    }
+
+    fn symbols_in_file(&self, file_id: FileId) -> Vec<SymbolId> {
+        self.inner.symbols_in_file(file_id)
+    }
+
+    fn lookup_candidates(&self, name: &str) -> Vec<SymbolId> {
+        self.inner.lookup_candidates(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::LanguageId;
+    use crate::{Symbol, SymbolKind, Visibility};
+    use tempfile::TempDir;
+
+    fn symbol(id: u32, name: &str, file_id: u32, module: &str) -> Symbol {
+        let mut symbol = Symbol::new(
+            SymbolId::new(id).unwrap(),
+            name,
+            SymbolKind::Function,
+            FileId::new(file_id).unwrap(),
+            Range::new(1, 0, 3, 1),
+        )
+        .with_module_path(module)
+        .with_visibility(Visibility::Public);
+        symbol.language_id = Some(LanguageId::new("rust"));
+        symbol
+    }
+
+    fn hashes(files: &[(u32, &str)]) -> HashMap<FileId, String> {
+        files
+            .iter()
+            .map(|(id, hash)| (FileId::new(*id).unwrap(), hash.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_resolution_cache_persists_and_invalidates() {
+        let temp_dir = TempDir::new().unwrap();
+        let symbols = SymbolLookupCache::new();
+        symbols.insert(symbol(1, "main", 1, "crate"));
+        symbols.insert(symbol(2, "helper", 2, "crate::util"));
+        symbols.insert(symbol(3, "parse", 3, "crate::parser"));
+
+        let caller = CallerContext::from_file(FileId::new(1).unwrap(), LanguageId::new("rust"));
+        let import = |path: &str| Import {
+            path: path.to_string(),
+            alias: None,
+            file_id: FileId::new(1).unwrap(),
+            is_glob: false,
+            is_type_only: false,
+            range: None,
+        };
+        let imports = vec![
+            import("crate::util::helper"),
+            import("crate::parser::parse"),
+        ];
+        let resolve_all = |cache: &ResolutionCache| {
+            let caching = CachingSymbolCache::new(&symbols, cache);
+            for (name, path) in [
+                ("helper", "crate::util::helper"),
+                ("parse", "crate::parser::parse"),
+            ] {
+                let result = caching.resolve_import(name, &caller, Some("crate"), path, &imports);
+                assert!(
+                    matches!(result, ResolveResult::Found(_)),
+                    "{name}: {result:?}"
+                );
+            }
+        };
+
+        let mut cache = ResolutionCache::new();
+        cache.refresh(hashes(&[(1, "a"), (2, "b"), (3, "c")]), &symbols);
+        resolve_all(&cache);
+        assert_eq!((cache.stats().hits, cache.stats().misses), (0, 2));
+        cache.save(temp_dir.path()).unwrap();
+
+        // Nothing changed: both imports are hits
+        let mut cache = ResolutionCache::load(temp_dir.path());
+        cache.refresh(hashes(&[(1, "a"), (2, "b"), (3, "c")]), &symbols);
+        resolve_all(&cache);
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.invalidated), (2, 0, 0));
+        assert_eq!(stats.hit_rate(), 1.0);
+
+        // The file defining `parse` changed: only that entry is dropped
+        let mut cache = ResolutionCache::load(temp_dir.path());
+        cache.refresh(hashes(&[(1, "a"), (2, "b"), (3, "c2")]), &symbols);
+        assert_eq!(cache.len(), 1);
+        resolve_all(&cache);
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.invalidated), (1, 1, 1));
+        assert_eq!(stats.hit_rate(), 0.5);
+    }
+}
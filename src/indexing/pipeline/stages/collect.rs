@@ -330,7 +330,9 @@ impl CollectStage {
                 state.current_embed_batch.candidates.push((
                     symbol_id,
                     doc.clone(),
-                    state.current_language.clone(),
+                    raw_sym
+                        .language_id
+                        .map_or_else(|| state.current_language.clone(), |id| id.as_str().into()),
                 ));
             }
 
@@ -379,7 +381,11 @@ fn create_symbol(
     let mut symbol = Symbol::new(id, raw.name.clone(), raw.kind, file_id, raw.range)
         .with_file_path(file_path)
         .with_visibility(raw.visibility)
-        .with_language_id(language_id);
+        .with_exported(raw.exported)
+        .with_language_id(raw.language_id.unwrap_or(language_id))
+        .with_annotations(raw.annotations.clone())
+        .with_tags(raw.tags.clone())
+        .with_project(raw.project.clone());
 
     if let Some(sig) = &raw.signature {
         symbol = symbol.with_signature(sig.clone());
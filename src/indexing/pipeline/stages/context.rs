@@ -10,10 +10,11 @@
 //! - Outputs: `Vec<ResolutionContext>` for RESOLVE stage
 
 use crate::config::Settings;
+use crate::indexing::pipeline::resolution_cache::{CachingSymbolCache, ResolutionCache};
 use crate::indexing::pipeline::types::{
     ResolutionContext, SymbolLookupCache, UnresolvedRelationship,
 };
-use crate::parsing::{LanguageBehavior, LanguageId, ParserFactory};
+use crate::parsing::{LanguageBehavior, LanguageId, ParserFactory, PipelineSymbolCache};
 use crate::storage::DocumentIndex;
 use crate::types::FileId;
 use std::collections::HashMap;
@@ -30,6 +31,8 @@ pub struct ContextStage {
     settings: Arc<Settings>,
     /// Cached behaviors by language_id (created on demand)
     behaviors: std::sync::RwLock<HashMap<LanguageId, Arc<dyn LanguageBehavior>>>,
+    /// Import resolutions kept across runs
+    resolution_cache: Option<Arc<ResolutionCache>>,
 }
 
 impl ContextStage {
@@ -46,9 +49,16 @@ impl ContextStage {
             factory,
             settings,
             behaviors: std::sync::RwLock::new(HashMap::new()),
+            resolution_cache: None,
         }
     }
 
+    /// Resolve imports through a persisted resolution cache.
+    pub fn with_resolution_cache(mut self, cache: Arc<ResolutionCache>) -> Self {
+        self.resolution_cache = Some(cache);
+        self
+    }
+
     /// Get or create behavior for a language (cached).
     ///
     /// Language-agnostic: delegates to ParserFactory which uses the registry.
@@ -143,12 +153,21 @@ impl ContextStage {
             .map(|config| config.extensions.iter().map(|s| s.as_str()).collect())
             .unwrap_or_default();
 
+        let caching;
+        let symbols: &dyn PipelineSymbolCache = match &self.resolution_cache {
+            Some(cache) => {
+                caching = CachingSymbolCache::new(self.symbol_cache.as_ref(), cache);
+                &caching
+            }
+            None => self.symbol_cache.as_ref(),
+        };
+
         // Build ResolutionScope via behavior - returns (scope, enhanced_imports)
         // Enhanced imports have path aliases resolved (e.g., @/components → src.components)
         let (scope, enhanced_imports) = behavior.build_resolution_context_with_pipeline_cache(
             file_id,
             &raw_imports,
-            self.symbol_cache.as_ref(),
+            symbols,
             &extensions,
         );
 
@@ -1,7 +1,8 @@
 //! Discover stage - parallel file system walk
 //!
 //! Uses the `ignore` crate's parallel walker for high-performance
-//! file discovery. Filters by supported extensions.
+//! file discovery. Filters by supported extensions; files without one are
+//! kept when their shebang or a language override identifies them.
 //!
 //! Supports two modes:
 //! - Full: Discovers all files (for initial indexing or force re-index)
@@ -9,10 +10,12 @@
 
 use crate::indexing::file_info::calculate_hash;
 use crate::indexing::pipeline::types::{DiscoverResult, PipelineError, PipelineResult};
-use crate::parsing::get_registry;
+use crate::parsing::detection::read_head;
+use crate::parsing::{LanguageRegistry, get_registry};
 use crate::storage::DocumentIndex;
 use crossbeam_channel::Sender;
 use ignore::WalkBuilder;
+use indexmap::IndexMap;
 use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -27,6 +30,8 @@ pub struct DiscoverStage {
     index: Option<Arc<DocumentIndex>>,
     /// Workspace root for path normalization.
     workspace_root: Option<PathBuf>,
+    /// Languages by file name or interpreter (`indexing.language_overrides`)
+    language_overrides: Arc<IndexMap<String, String>>,
 }
 
 impl DiscoverStage {
@@ -37,6 +42,7 @@ impl DiscoverStage {
             threads: threads.max(1),
             index: None,
             workspace_root: None,
+            language_overrides: Arc::default(),
         }
     }
 
@@ -52,6 +58,12 @@ impl DiscoverStage {
         self
     }
 
+    /// Set the language overrides for files without a known extension.
+    pub fn with_language_overrides(mut self, overrides: IndexMap<String, String>) -> Self {
+        self.language_overrides = Arc::new(overrides);
+        self
+    }
+
     /// Normalize a path relative to workspace_root.
     fn normalize_path(&self, path: &Path) -> PathBuf {
         if path.is_absolute() {
@@ -95,6 +107,7 @@ impl DiscoverStage {
         walker.run(|| {
             let sender = sender.clone();
             let extensions = extensions.clone();
+            let overrides = self.language_overrides.clone();
             let count = count_clone.clone();
 
             Box::new(move |entry| {
@@ -119,8 +132,8 @@ impl DiscoverStage {
                     }
                 }
 
-                // Filter by extension
-                if !has_supported_extension(path, &extensions) {
+                // Filter by extension (or shebang)
+                if !is_source_file(path, &extensions, &overrides) {
                     return ignore::WalkState::Continue;
                 }
 
@@ -244,7 +257,7 @@ impl DiscoverStage {
                 }
             }
 
-            if has_supported_extension(path, &extensions) {
+            if is_source_file(path, &extensions, &self.language_overrides) {
                 files.push(path.to_path_buf());
             }
         }
@@ -320,6 +333,37 @@ fn has_supported_extension(path: &Path, extensions: &HashSet<&str>) -> bool {
         .unwrap_or(false)
 }
 
+/// Check if a path is a source file: by extension, or for a file without
+/// one, by a file name override or its shebang.
+fn is_source_file(
+    path: &Path,
+    extensions: &HashSet<&str>,
+    overrides: &IndexMap<String, String>,
+) -> bool {
+    if has_supported_extension(path, extensions) {
+        return true;
+    }
+    let overridden = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| overrides.contains_key(name));
+    if path.extension().is_some() && !overridden {
+        return false;
+    }
+
+    let head = if LanguageRegistry::needs_content(path, overrides) {
+        match read_head(path) {
+            Some(head) => Some(head),
+            None => return false,
+        }
+    } else {
+        None
+    };
+    get_registry()
+        .lock()
+        .is_ok_and(|registry| registry.detect(path, head.as_deref(), overrides).is_some())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -7,19 +7,25 @@
 //! - Writes symbols, imports, file registrations to Tantivy (parallel via RwLock)
 //! - Accumulates UnresolvedRelationships for Phase 2
 //! - Builds SymbolLookupCache for O(1) Phase 2 resolution (concurrent DashMap)
-//! - Commits every N batches for efficient I/O
+//! - Commits every N batches for efficient I/O, or earlier once the
+//!   uncommitted batches exceed the memory budget
+//! - Keeps what it holds for Phase 2 under the same budget: pending
+//!   relationships spill to a temporary file, and the symbol cache is
+//!   dropped and reloaded from the index after the final commit
 //!
 //! Note: Embedding generation moved to separate EMBED stage (parallel with INDEX).
 
 use crate::indexing::IndexStats;
 use crate::indexing::pipeline::types::{
-    IndexBatch, PipelineResult, SymbolLookupCache, UnresolvedRelationship,
+    IndexBatch, PipelineResult, RelationshipSpill, SymbolLookupCache, UnresolvedRelationship,
+    approx_symbol_bytes,
 };
 use crate::io::status_line::ProgressBar;
 use crate::storage::DocumentIndex;
 use crossbeam_channel::Receiver;
 use rayon::prelude::*;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 /// Index stage for Tantivy writes.
 ///
@@ -33,6 +39,11 @@ pub type IndexProgressCallback = Arc<dyn Fn(u64) + Send + Sync>;
 pub struct IndexStage {
     index: Arc<DocumentIndex>,
     batches_per_commit: usize,
+    /// Bytes of uncommitted batches that force an early commit, and of
+    /// Phase 2 data kept in memory before spilling (0 = no limit).
+    memory_budget: usize,
+    /// Most bytes of Phase 2 data held in memory at once during `run`.
+    peak_retained: AtomicUsize,
     /// Optional progress bar for live updates.
     progress: Option<Arc<ProgressBar>>,
     /// Optional progress callback (alternative to progress bar).
@@ -48,11 +59,27 @@ impl IndexStage {
         Self {
             index,
             batches_per_commit: batches_per_commit.max(1),
+            memory_budget: 0,
+            peak_retained: AtomicUsize::new(0),
             progress: None,
             progress_callback: None,
         }
     }
 
+    /// Commit as soon as the uncommitted batches hold about `mb` megabytes,
+    /// even before `batches_per_commit` is reached, and spill Phase 2 data
+    /// once it holds as much. 0 disables the budget.
+    pub fn with_memory_budget_mb(mut self, mb: usize) -> Self {
+        self.memory_budget = mb.saturating_mul(1024 * 1024);
+        self
+    }
+
+    /// Most bytes of pending relationships and cached symbols held in memory
+    /// at once by the last `run`.
+    pub fn peak_retained_bytes(&self) -> usize {
+        self.peak_retained.load(Ordering::Relaxed)
+    }
+
     /// Add progress bar for live updates.
     pub fn with_progress(mut self, progress: Arc<ProgressBar>) -> Self {
         self.progress = Some(progress);
@@ -69,6 +96,10 @@ impl IndexStage {
     ///
     /// Returns (stats, accumulated_relationships, symbol_cache, input_wait) for Phase 2.
     /// The symbol cache enables O(1) lookups during resolution instead of Tantivy queries.
+    ///
+    /// With a memory budget, relationships over budget go to a
+    /// [`RelationshipSpill`] and the cache is dropped once it takes half the
+    /// budget; both are restored only after the final commit.
     pub fn run(
         &self,
         receiver: Receiver<IndexBatch>,
@@ -83,8 +114,16 @@ impl IndexStage {
         let mut stats = IndexStats::new();
         let mut pending_relationships: Vec<UnresolvedRelationship> = Vec::new();
         let mut batch_count = 0;
+        let mut uncommitted_bytes = 0;
         let mut input_wait = Duration::ZERO;
 
+        // Phase 2 data held in memory, and where it goes when over budget
+        let mut relationship_bytes = 0;
+        let mut cache_bytes = 0;
+        let mut spill = RelationshipSpill::default();
+        let mut cache_dropped = false;
+        self.peak_retained.store(0, Ordering::Relaxed);
+
         // Pre-allocate cache based on expected symbols (will grow if needed)
         let symbol_cache = SymbolLookupCache::with_capacity(10_000);
 
@@ -100,34 +139,88 @@ impl IndexStage {
             };
             input_wait += recv_start.elapsed();
 
-            self.process_batch(&batch, &mut stats, &symbol_cache)?;
+            uncommitted_bytes += batch.approx_bytes();
+            let cache = (!cache_dropped).then_some(&symbol_cache);
+            self.process_batch(&batch, &mut stats, cache)?;
+            if !cache_dropped {
+                cache_bytes += batch
+                    .symbols
+                    .iter()
+                    .map(|(symbol, _)| approx_symbol_bytes(symbol))
+                    .sum::<usize>();
+            }
 
             // Accumulate relationships for Phase 2
+            relationship_bytes += batch
+                .unresolved_relationships
+                .iter()
+                .map(UnresolvedRelationship::approx_bytes)
+                .sum::<usize>();
             pending_relationships.extend(batch.unresolved_relationships);
 
+            self.peak_retained
+                .fetch_max(relationship_bytes + cache_bytes, Ordering::Relaxed);
+            if self.memory_budget > 0 && relationship_bytes + cache_bytes >= self.memory_budget {
+                tracing::debug!(
+                    target: "pipeline",
+                    "Phase 2 data holds ~{} MB, spilling {} relationships",
+                    (relationship_bytes + cache_bytes) / (1024 * 1024),
+                    pending_relationships.len()
+                );
+                spill.write(pending_relationships.drain(..))?;
+                relationship_bytes = 0;
+                if cache_bytes >= self.memory_budget / 2 {
+                    symbol_cache.clear();
+                    cache_bytes = 0;
+                    cache_dropped = true;
+                }
+            }
+
             batch_count += 1;
 
-            // Commit every N batches
-            if batch_count % self.batches_per_commit == 0 {
+            // Commit every N batches, or early when over the memory budget
+            let over_budget = self.memory_budget > 0 && uncommitted_bytes >= self.memory_budget;
+            if over_budget {
+                tracing::debug!(
+                    target: "pipeline",
+                    "Uncommitted batches hold ~{} MB, committing early",
+                    uncommitted_bytes / (1024 * 1024)
+                );
+            }
+            if over_budget || batch_count % self.batches_per_commit == 0 {
                 self.commit_and_restart()?;
+                uncommitted_bytes = 0;
             }
         }
 
         // Final commit
         self.index.commit_batch()?;
 
+        // Restore what was spilled, now that the index writer is done
+        let symbol_cache = if cache_dropped {
+            SymbolLookupCache::from_index(&self.index)?
+        } else {
+            symbol_cache
+        };
+        if !spill.is_empty() {
+            let mut spilled = spill.read_back()?;
+            spilled.append(&mut pending_relationships);
+            pending_relationships = spilled;
+        }
+
         Ok((stats, pending_relationships, symbol_cache, input_wait))
     }
 
     /// Process a single batch.
     ///
     /// Writes symbols, imports, and file registrations to Tantivy in parallel.
-    /// Accumulates symbols in cache for Phase 2 resolution.
+    /// Accumulates symbols in cache for Phase 2 resolution, unless the cache
+    /// was dropped to stay within the memory budget.
     fn process_batch(
         &self,
         batch: &IndexBatch,
         stats: &mut IndexStats,
-        symbol_cache: &SymbolLookupCache,
+        symbol_cache: Option<&SymbolLookupCache>,
     ) -> PipelineResult<()> {
         // Write file registrations in parallel
         batch
@@ -156,7 +249,9 @@ impl IndexStage {
                 );
             }
             // Insert into cache for O(1) Phase 2 resolution (DashMap is concurrent)
-            symbol_cache.insert(symbol.clone());
+            if let Some(symbol_cache) = symbol_cache {
+                symbol_cache.insert(symbol.clone());
+            }
         });
         stats.symbols_found += batch.symbols.len();
 
@@ -200,9 +295,8 @@ impl IndexStage {
     /// [PIPELINE API] Used by `Pipeline::index_file_single()` for watcher reindex.
     /// Caller must handle start_batch/commit_batch.
     pub fn index_batch(&self, batch: IndexBatch) -> PipelineResult<()> {
-        let symbol_cache = SymbolLookupCache::new();
         let mut stats = IndexStats::new();
-        self.process_batch(&batch, &mut stats, &symbol_cache)
+        self.process_batch(&batch, &mut stats, None)
     }
 }
 
@@ -362,6 +456,67 @@ mod tests {
         assert_eq!(symbol_cache.len(), 2);
     }
 
+    #[test]
+    fn test_index_stage_spills_phase2_data_over_budget() {
+        use crate::RelationKind;
+
+        fn send_batches(batch_tx: crossbeam_channel::Sender<IndexBatch>) -> usize {
+            let mut largest_batch = 0;
+            for file_id in 1..=20 {
+                let mut batch = make_test_batch(file_id, 5);
+                for call in 0..10 {
+                    batch.unresolved_relationships.push(UnresolvedRelationship {
+                        from_id: SymbolId::new((file_id - 1) * 5 + 1),
+                        from_name: Arc::from(format!("sym_{}", (file_id - 1) * 5 + 1)),
+                        to_name: Arc::from(format!("callee_{file_id}_{call}")),
+                        file_id: FileId::new(file_id).unwrap(),
+                        kind: RelationKind::Calls,
+                        metadata: None,
+                        to_range: None,
+                    });
+                }
+                largest_batch = largest_batch.max(batch.approx_bytes());
+                batch_tx.send(batch).unwrap();
+            }
+            largest_batch
+        }
+
+        let settings = Settings::default();
+        let budget = 4 * 1024;
+
+        let temp_dir = TempDir::new().unwrap();
+        let index = Arc::new(DocumentIndex::new(temp_dir.path(), &settings).unwrap());
+        let (batch_tx, batch_rx) = bounded(20);
+        let largest_batch = send_batches(batch_tx);
+        let mut stage = IndexStage::new(Arc::clone(&index), 100);
+        stage.memory_budget = budget;
+        let (stats, rels, symbol_cache, _) = stage.run(batch_rx).unwrap();
+
+        // Spilling loses nothing and keeps the order
+        assert_eq!(stats.symbols_found, 100);
+        assert_eq!(rels.len(), 200);
+        assert_eq!(rels[0].to_name.as_ref(), "callee_1_0");
+        assert_eq!(rels[199].to_name.as_ref(), "callee_20_9");
+        assert_eq!(symbol_cache.len(), 100);
+        assert_eq!(symbol_cache.lookup_candidates("sym_42").len(), 1);
+
+        // Memory stays within the budget plus the batch that crossed it
+        let bounded_peak = stage.peak_retained_bytes();
+        assert!(
+            bounded_peak < budget + largest_batch,
+            "peak {bounded_peak} exceeds budget {budget} + batch {largest_batch}"
+        );
+
+        // Without a budget the same input holds far more
+        let temp_dir = TempDir::new().unwrap();
+        let index = Arc::new(DocumentIndex::new(temp_dir.path(), &settings).unwrap());
+        let (batch_tx, batch_rx) = bounded(20);
+        send_batches(batch_tx);
+        let stage = IndexStage::new(Arc::clone(&index), 100);
+        stage.run(batch_rx).unwrap();
+        assert!(stage.peak_retained_bytes() > 2 * (budget + largest_batch));
+    }
+
     #[test]
     fn test_symbol_cache_lookup_by_name() {
         let temp_dir = TempDir::new().unwrap();
@@ -5,6 +5,7 @@
 //! Phase 1 stages: DISCOVER → READ → PARSE → COLLECT → INDEX
 //! Phase 2 stages: CONTEXT → RESOLVE → WRITE
 //! Pre-phase: CLEANUP (for incremental mode)
+//! Post-phase: SUMMARIZE (optional, when `[summaries]` is enabled)
 
 pub mod cleanup;
 pub mod collect;
@@ -16,6 +17,7 @@ pub mod parse;
 pub mod read;
 pub mod resolve;
 pub mod semantic_embed;
+pub mod summarize;
 pub mod write;
 
 // Pre-phase stages (incremental mode)
@@ -33,6 +35,9 @@ pub use context::{ContextStage, ContextStats};
 pub use resolve::{ResolveStage, ResolveStats};
 pub use write::{WriteStage, WriteStats};
 
+// Post-phase stages
+pub use summarize::{GeneratedDescription, SummarizeStage, SummarizeStats, SummaryStore};
+
 // Embedding (separate from main pipeline)
 pub use embed::{EmbedStage, EmbedStats};
 pub use semantic_embed::{EmbedProgressCallback, SemanticEmbedStage, SemanticEmbedStats};
@@ -3,17 +3,31 @@
 //! Converts FileContent into ParsedFile with RawSymbols.
 //! Uses thread-local parsers to avoid contention.
 
-use crate::Settings;
+use crate::config::ExtractionProfile;
 use crate::indexing::pipeline::types::{
     FileContent, ParsedFile, PipelineError, PipelineResult, RawImport, RawRelationship, RawSymbol,
 };
-use crate::parsing::{LanguageId, LanguageParser, get_registry, normalize_for_module_path};
-use crate::types::{FileId, SymbolCounter};
+use crate::indexing::progress::{ProgressEvent, ProgressEventCallback};
+use crate::indexing::projects::ProjectBoundaries;
+use crate::parsing::control_flow::range_within;
+use crate::parsing::injection::{InjectedRegion, find_injections};
+use crate::parsing::{
+    KindOverrides, LanguageBehavior, LanguageId, LanguageParser, ParseArtifacts, ReferenceSite,
+    TagRules, conditional_ranges, default_relationships, extract_references, get_registry,
+    normalize_for_module_path,
+};
+use crate::symbol::ScopeContext;
+use crate::types::{FileId, Range, SymbolCounter};
+use crate::{Settings, Symbol, SymbolKind, Visibility};
 use std::cell::RefCell;
 use std::collections::HashMap;
+use std::panic::AssertUnwindSafe;
 use std::path::Path;
 use std::sync::Arc;
 
+/// Name parsers give the symbol standing for a whole file
+const MODULE_SYMBOL: &str = "<module>";
+
 /// Thread-local parser cache.
 ///
 /// Each thread maintains its own set of parsers to avoid contention.
@@ -21,12 +35,20 @@ use std::sync::Arc;
 struct ParserCache {
     parsers: HashMap<LanguageId, Box<dyn LanguageParser>>,
     settings: Arc<Settings>,
+    /// `[[kinds]]` and `[[tags]]` rules, compiled once per thread
+    kinds: Arc<KindOverrides>,
+    tags: Arc<TagRules>,
+    /// Manifests seen by this thread, to find each file's project
+    projects: ProjectBoundaries,
 }
 
 impl ParserCache {
     fn new(settings: Arc<Settings>) -> Self {
         Self {
             parsers: HashMap::new(),
+            kinds: Arc::new(KindOverrides::new(&settings.kinds)),
+            tags: Arc::new(TagRules::new(&settings.tags)),
+            projects: ProjectBoundaries::new(settings.workspace_root.as_deref()),
             settings,
         }
     }
@@ -41,6 +63,14 @@ impl ParserCache {
         }
         Ok(self.parsers.get_mut(&language_id).unwrap().as_mut())
     }
+
+    /// Drop the parser for `language_id`; the next file gets a fresh one.
+    ///
+    /// Used after a parser panicked, since its internal state can no longer
+    /// be trusted.
+    fn discard(&mut self, language_id: LanguageId) {
+        self.parsers.remove(&language_id);
+    }
 }
 
 thread_local! {
@@ -73,10 +103,9 @@ fn create_parser(
         })
 }
 
-/// Detect language from file extension.
-fn detect_language(path: &Path) -> PipelineResult<LanguageId> {
-    let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
-
+/// Detect language from file extension, or from the content of files
+/// without one (shebang, modeline).
+fn detect_language(path: &Path, content: &str, settings: &Settings) -> PipelineResult<LanguageId> {
     let registry = get_registry();
     let registry = registry.lock().map_err(|e| PipelineError::Parse {
         path: path.to_path_buf(),
@@ -84,7 +113,7 @@ fn detect_language(path: &Path) -> PipelineResult<LanguageId> {
     })?;
 
     registry
-        .get_by_extension(extension)
+        .detect(path, Some(content), &settings.indexing.language_overrides)
         .map(|def| def.id())
         .ok_or_else(|| PipelineError::UnsupportedFileType {
             path: path.to_path_buf(),
@@ -92,14 +121,24 @@ fn detect_language(path: &Path) -> PipelineResult<LanguageId> {
 }
 
 /// Parse stage configuration.
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct ParseStage {
     settings: Arc<Settings>,
+    events: Option<ProgressEventCallback>,
 }
 
 impl ParseStage {
     pub fn new(settings: Arc<Settings>) -> Self {
-        Self { settings }
+        Self {
+            settings,
+            events: None,
+        }
+    }
+
+    /// Report each file's start, symbol count, or failure to `events`.
+    pub fn with_events(mut self, events: Option<ProgressEventCallback>) -> Self {
+        self.events = events;
+        self
     }
 
     /// Get the settings.
@@ -109,19 +148,38 @@ impl ParseStage {
 
     /// Parse a file using this stage's settings.
     pub fn parse(&self, content: FileContent) -> PipelineResult<ParsedFile> {
-        parse_file(content, &self.settings)
+        let Some(events) = &self.events else {
+            return parse_file(content, &self.settings);
+        };
+        let path = content.path.clone();
+        events(&ProgressEvent::FileStarted { path: path.clone() });
+        let result = parse_file(content, &self.settings);
+        events(&match &result {
+            Ok(parsed) => ProgressEvent::FileDone {
+                path,
+                symbols: parsed.raw_symbols.len(),
+            },
+            Err(e) => ProgressEvent::Error {
+                path,
+                message: e.to_string(),
+            },
+        });
+        result
     }
 }
 
 /// Parse a single file into a ParsedFile.
 ///
 /// This is the core parsing function. It:
-/// 1. Detects the language from file extension
+/// 1. Detects the language from file extension or shebang
 /// 2. Gets or creates a thread-local parser
 /// 3. Extracts symbols, imports, and relationships
 /// 4. Returns ParsedFile with RawSymbols (no IDs assigned)
+///
+/// Runs inside a `parse_file` span so parser diagnostics carry the file path.
+#[tracing::instrument(level = "debug", skip_all, fields(path = %content.path.display()))]
 pub fn parse_file(content: FileContent, settings: &Settings) -> PipelineResult<ParsedFile> {
-    let language_id = detect_language(&content.path)?;
+    let language_id = detect_language(&content.path, &content.content, settings)?;
 
     PARSER_CACHE.with(|cache| {
         let mut cache_ref = cache.borrow_mut();
@@ -129,18 +187,103 @@ pub fn parse_file(content: FileContent, settings: &Settings) -> PipelineResult<P
             .as_mut()
             .expect("Parser cache not initialized. Call init_parser_cache first.");
 
+        // One syntax tree per file for the passes that walk it directly
+        let tree = syntax_tree(&content.content, language_id);
+        let regions = tree.as_ref().map_or_else(Vec::new, |tree| {
+            find_injected_regions(tree, &content.content, language_id)
+        });
+        let path = content.path.clone();
+        let kinds = Arc::clone(&parser_cache.kinds);
+        let tags = Arc::clone(&parser_cache.tags);
         let parser = parser_cache.get_or_create(language_id)?;
+        let mut parsed = match run_isolated(|| {
+            parse_with_parser(
+                content,
+                tree.as_ref(),
+                language_id,
+                parser,
+                settings,
+                &kinds,
+                &tags,
+            )
+        }) {
+            Ok(result) => result?,
+            Err(message) => {
+                parser_cache.discard(language_id);
+                return Err(parser_panicked(&path, language_id, message));
+            }
+        };
 
-        parse_with_parser(content, language_id, parser, settings)
+        for region in &regions {
+            let injected_id = LanguageId::new(region.language);
+            // Embedded languages that aren't enabled are left unindexed
+            let Ok(parser) = parser_cache.get_or_create(injected_id) else {
+                continue;
+            };
+            let injected = run_isolated(|| {
+                parse_injection(
+                    region,
+                    injected_id,
+                    parser,
+                    settings,
+                    &kinds,
+                    &tags,
+                    &mut parsed,
+                )
+            });
+            // A crash in an embedded region only loses that region
+            if let Err(message) = injected {
+                parser_cache.discard(injected_id);
+                parser_panicked(&path, injected_id, message);
+            }
+        }
+
+        let project = parser_cache.projects.project_of(&path);
+        for symbol in &mut parsed.raw_symbols {
+            symbol.project = project.clone();
+        }
+        Ok(parsed)
+    })
+}
+
+/// Run `f`, turning a panic into its message.
+///
+/// tree-sitter grammars and the extractors built on them can panic on
+/// inputs nobody anticipated; one such file must not abort the whole run.
+fn run_isolated<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    std::panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+        payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string())
     })
 }
 
+/// Log and count a parser panic, returning the error reported for the file.
+fn parser_panicked(path: &Path, language_id: LanguageId, message: String) -> PipelineError {
+    crate::metrics::record_parser_panic(language_id.as_str());
+    let error = PipelineError::ParserPanic {
+        path: path.to_path_buf(),
+        language: language_id.as_str().to_string(),
+        message,
+    };
+    tracing::warn!(target: "pipeline", "{error}");
+    error
+}
+
 /// Parse content using provided parser.
+///
+/// `tree` is the file's syntax tree, which the reference and
+/// conditional-region passes walk instead of parsing again.
 fn parse_with_parser(
     content: FileContent,
+    tree: Option<&tree_sitter::Tree>,
     language_id: LanguageId,
     parser: &mut dyn LanguageParser,
     settings: &Settings,
+    kinds: &KindOverrides,
+    tags: &TagRules,
 ) -> PipelineResult<ParsedFile> {
     // Use a dummy file_id and counter - we just need to extract symbols
     // Real IDs are assigned in COLLECT stage
@@ -150,31 +293,37 @@ fn parse_with_parser(
     // Compute module_path using the language behavior
     let module_path = compute_module_path(&content.path, language_id, settings);
 
-    // Parse symbols
-    let symbols = parser.parse(&content.content, dummy_file_id, &mut counter);
+    // Parse symbols, keeping what the language's extraction profile asks for
+    let profile = settings.extraction_profile(language_id.as_str());
+    let mut symbols = parser.parse(&content.content, dummy_file_id, &mut counter);
+    if profile == ExtractionProfile::Light {
+        symbols = top_level_public(symbols);
+    }
+    kinds.apply(language_id, &mut symbols);
+    tags.apply(workspace_relative(&content.path, settings), &mut symbols);
+    if let Some(behavior) = create_behavior(language_id) {
+        behavior.annotate_from_companion_files(&content.path, &mut symbols);
+        mark_exported(behavior.as_ref(), &mut symbols);
+    }
 
-    // Convert to RawSymbols (strip the dummy ID)
-    let raw_symbols: Vec<RawSymbol> = symbols
-        .into_iter()
-        .map(|sym| {
-            let mut raw = RawSymbol::new(sym.name.clone(), sym.kind, sym.range);
-            if let Some(sig) = sym.signature {
-                raw = raw.with_signature(sig);
-            }
-            if let Some(doc) = sym.doc_comment {
-                raw = raw.with_doc_comment(doc);
-            }
-            raw = raw.with_visibility(sym.visibility);
-            if let Some(ctx) = sym.scope_context {
-                raw = raw.with_scope_context(ctx);
-            }
-            raw
-        })
-        .collect();
+    // Second pass for identifier references, which needs the symbol ranges
+    let references = match tree {
+        Some(tree) if settings.indexing.reference_pass && profile == ExtractionProfile::Full => {
+            let variable_types = parser.find_variable_types(&content.content);
+            find_references(
+                tree,
+                &content.content,
+                language_id,
+                &symbols,
+                &variable_types,
+            )
+        }
+        _ => Vec::new(),
+    };
 
     // Extract imports (without FileId)
     let imports = parser.find_imports(&content.content, dummy_file_id);
-    let raw_imports: Vec<RawImport> = imports
+    let mut raw_imports: Vec<RawImport> = imports
         .into_iter()
         .map(|imp| {
             let mut raw = RawImport::new(&imp.path);
@@ -187,12 +336,30 @@ fn parse_with_parser(
             if imp.is_type_only {
                 raw = raw.as_type_only();
             }
+            if let Some(range) = imp.range {
+                raw = raw.with_range(range);
+            }
             raw
         })
         .collect();
 
-    // Extract relationships
-    let raw_relationships = extract_relationships(parser, &content.content);
+    // Relationships, built by the behavior once every symbol is known
+    let artifacts = ParseArtifacts::collect(parser, &content.content, references);
+    let mut raw_relationships = build_relationships(language_id, &symbols, &artifacts);
+    keep_profile_relationships(profile, tree, language_id, &mut raw_relationships);
+
+    // Convert to RawSymbols (strip the dummy ID)
+    let mut raw_symbols: Vec<RawSymbol> = symbols.into_iter().map(to_raw_symbol).collect();
+
+    link_path_references(
+        &content.path,
+        language_id,
+        module_path.as_deref(),
+        settings,
+        &mut raw_symbols,
+        &mut raw_relationships,
+        &mut raw_imports,
+    );
 
     Ok(ParsedFile {
         path: content.path,
@@ -205,6 +372,125 @@ fn parse_with_parser(
     })
 }
 
+/// The relationships the extraction profile asks for: the full profile
+/// marks the calls behind branches, the light one drops references, the
+/// parser's (instantiations, generic arguments) included.
+fn keep_profile_relationships(
+    profile: ExtractionProfile,
+    tree: Option<&tree_sitter::Tree>,
+    language_id: LanguageId,
+    relationships: &mut Vec<RawRelationship>,
+) {
+    match (profile, tree) {
+        (ExtractionProfile::Full, Some(tree)) => {
+            let conditional = find_conditional_ranges(tree, language_id);
+            mark_conditional_calls(relationships, &conditional);
+        }
+        (ExtractionProfile::Light, _) => {
+            relationships.retain(|r| r.kind != crate::RelationKind::References);
+        }
+        (ExtractionProfile::Full, None) => {}
+    }
+}
+
+/// Strip the dummy ID from a parsed symbol.
+fn to_raw_symbol(sym: Symbol) -> RawSymbol {
+    let mut raw = RawSymbol::new(sym.name.clone(), sym.kind, sym.range);
+    if let Some(sig) = sym.signature {
+        raw = raw.with_signature(sig);
+    }
+    if let Some(doc) = sym.doc_comment {
+        raw = raw.with_doc_comment(doc);
+    }
+    raw = raw
+        .with_visibility(sym.visibility)
+        .with_exported(sym.exported);
+    if let Some(ctx) = sym.scope_context {
+        raw = raw.with_scope_context(ctx);
+    }
+    raw.with_annotations(sym.annotations).with_tags(sym.tags)
+}
+
+/// Code of other languages embedded in a host file, located by the host
+/// behavior's injection rules.
+fn find_injected_regions(
+    tree: &tree_sitter::Tree,
+    code: &str,
+    language_id: LanguageId,
+) -> Vec<InjectedRegion> {
+    match create_behavior(language_id) {
+        Some(behavior) => find_injections(tree.root_node(), code, behavior.injection_rules()),
+        None => Vec::new(),
+    }
+}
+
+/// Parse an embedded region with its own language's parser and add what it
+/// defines and references to the host file, at host positions.
+///
+/// Symbols keep the embedded language; imports are skipped since they
+/// don't apply to the host's module.
+fn parse_injection(
+    region: &InjectedRegion,
+    language_id: LanguageId,
+    parser: &mut dyn LanguageParser,
+    settings: &Settings,
+    kinds: &KindOverrides,
+    tags: &TagRules,
+    parsed: &mut ParsedFile,
+) {
+    let dummy_file_id = FileId::new(1).unwrap();
+    let mut counter = SymbolCounter::new();
+
+    // The host's profile decides how much of the file is kept
+    let profile = settings.extraction_profile(parsed.language_id.as_str());
+    let mut symbols = parser.parse(&region.code, dummy_file_id, &mut counter);
+    if profile == ExtractionProfile::Light {
+        symbols = top_level_public(symbols);
+    }
+    kinds.apply(language_id, &mut symbols);
+    tags.apply(workspace_relative(&parsed.path, settings), &mut symbols);
+    if let Some(behavior) = create_behavior(language_id) {
+        mark_exported(behavior.as_ref(), &mut symbols);
+    }
+    let tree = syntax_tree(&region.code, language_id);
+    let references = match &tree {
+        Some(tree) if settings.indexing.reference_pass && profile == ExtractionProfile::Full => {
+            let variable_types = parser.find_variable_types(&region.code);
+            find_references(tree, &region.code, language_id, &symbols, &variable_types)
+        }
+        _ => Vec::new(),
+    };
+    let artifacts = ParseArtifacts::collect(parser, &region.code, references);
+    let mut relationships = build_relationships(language_id, &symbols, &artifacts);
+    keep_profile_relationships(profile, tree.as_ref(), language_id, &mut relationships);
+
+    parsed.raw_symbols.extend(symbols.into_iter().map(|sym| {
+        let mut raw = to_raw_symbol(sym).with_language_id(language_id);
+        raw.range = region.to_host(raw.range);
+        raw
+    }));
+    parsed
+        .raw_relationships
+        .extend(relationships.into_iter().map(|mut rel| {
+            rel.from_range = region.to_host(rel.from_range);
+            rel.to_range = region.to_host(rel.to_range);
+            if let Some(metadata) = &mut rel.metadata {
+                metadata.line = Some(rel.to_range.start_line);
+                metadata.column = Some(rel.to_range.start_column);
+            }
+            rel
+        }));
+}
+
+/// `path` relative to the workspace root, as `[[tags]]` path globs expect
+fn workspace_relative<'a>(path: &'a Path, settings: &Settings) -> &'a Path {
+    let workspace_root = settings
+        .workspace_root
+        .as_deref()
+        .unwrap_or_else(|| Path::new("."));
+    path.strip_prefix(workspace_root).unwrap_or(path)
+}
+
 /// Compute module_path for a file using the language behavior.
 ///
 /// This calls behavior.module_path_from_file() which uses:
@@ -217,10 +503,7 @@ fn compute_module_path(
     language_id: LanguageId,
     settings: &Settings,
 ) -> Option<String> {
-    let registry = get_registry();
-    let registry_guard = registry.lock().ok()?;
-    let definition = registry_guard.get(language_id)?;
-    let behavior = definition.create_behavior();
+    let behavior = create_behavior(language_id)?;
 
     // Get extensions from settings.toml (single source of truth)
     let extensions: Vec<&str> = settings
@@ -241,96 +524,206 @@ fn compute_module_path(
     behavior.module_path_from_file(&normalized_path, workspace_root, &extensions)
 }
 
-/// Extract relationships from parsed content.
-///
-/// Range semantics:
-/// - `from_range`: Definition location of the calling/containing symbol (for COLLECT lookup)
-/// - `to_range`: Call site / reference location (for Phase 2 disambiguation)
-///
-/// For MethodCall: `caller_range` provides precise from_range when available.
-/// For legacy find_* methods: range typically points to the reference site.
-fn extract_relationships(parser: &mut dyn LanguageParser, content: &str) -> Vec<RawRelationship> {
-    let mut relationships = Vec::new();
-
-    // Function/method calls - MethodCall provides caller_range for precise lookup
-    for call in parser.find_method_calls(content) {
-        // Use caller_range when available, otherwise use call site (triggers fallback)
-        let from_range = call.caller_range.unwrap_or(call.range);
-        relationships.push(RawRelationship::new(
-            call.caller,
-            from_range,
-            call.method_name,
-            call.range, // to_range = call site
-            crate::RelationKind::Calls,
-        ));
+/// For languages whose files reference each other by path literal, name the
+/// file's `<module>` symbol by its module path and point path references and
+/// path imports at the module paths of the files they resolve to.
+fn link_path_references(
+    file_path: &Path,
+    language_id: LanguageId,
+    module_path: Option<&str>,
+    settings: &Settings,
+    raw_symbols: &mut [RawSymbol],
+    raw_relationships: &mut [RawRelationship],
+    raw_imports: &mut [RawImport],
+) {
+    let Some(behavior) = create_behavior(language_id) else {
+        return;
+    };
+    if !behavior.links_files_by_path() {
+        return;
     }
 
-    // Plain function calls (legacy - no caller_range available)
-    for (caller, called, call_site) in parser.find_calls(content) {
-        // Avoid duplicates - method_calls should be comprehensive
-        // but some parsers might return both
-        let already_exists = relationships.iter().any(|r| {
-            r.from_name.as_ref() == caller
-                && r.to_name.as_ref() == called
-                && r.to_range.start_line == call_site.start_line
-        });
-        if !already_exists {
-            // from_range = call_site triggers fallback to name-only lookup in COLLECT
-            relationships.push(RawRelationship::new(
-                caller,
-                call_site, // no caller_range available, use call_site
-                called,
-                call_site, // to_range = call site
-                crate::RelationKind::Calls,
-            ));
+    if let Some(module_path) = module_path {
+        for symbol in raw_symbols
+            .iter_mut()
+            .filter(|s| s.kind == SymbolKind::Module && s.name.as_ref() == MODULE_SYMBOL)
+        {
+            symbol.name = module_path.into();
+        }
+        for rel in raw_relationships
+            .iter_mut()
+            .filter(|r| r.from_name.as_ref() == MODULE_SYMBOL)
+        {
+            rel.from_name = module_path.into();
         }
     }
 
-    // Trait implementations - range is the impl definition site
-    for (type_name, trait_name, impl_range) in parser.find_implementations(content) {
-        relationships.push(RawRelationship::new(
-            type_name,
-            impl_range, // from_range = where impl is defined
-            trait_name,
-            impl_range, // to_range = where trait is referenced
-            crate::RelationKind::Implements,
-        ));
+    let workspace_root = settings
+        .workspace_root
+        .as_deref()
+        .unwrap_or_else(|| Path::new("."));
+    let from_file = normalize_for_module_path(file_path, workspace_root);
+    let resolve = |reference: &str| {
+        behavior
+            .resolve_path_reference(reference, &from_file)
+            .and_then(|target| compute_module_path(&target, language_id, settings))
+    };
+    for rel in raw_relationships
+        .iter_mut()
+        .filter(|r| r.kind == crate::RelationKind::References)
+    {
+        if let Some(target) = resolve(&rel.to_name) {
+            rel.to_name = target.into();
+        }
     }
+    for import in raw_imports.iter_mut() {
+        if let Some(target) = resolve(&import.path) {
+            import.path = target;
+        }
+    }
+}
 
-    // Inheritance (extends) - range is the class definition site
-    for (derived, base, class_range) in parser.find_extends(content) {
-        relationships.push(RawRelationship::new(
-            derived,
-            class_range, // from_range = where derived is defined
-            base,
-            class_range, // to_range = where base is referenced
-            crate::RelationKind::Extends,
-        ));
+/// Symbols kept by the light profile: public ones not nested in another
+/// symbol, in their original order.
+fn top_level_public(symbols: Vec<Symbol>) -> Vec<Symbol> {
+    let mut order: Vec<usize> = (0..symbols.len()).collect();
+    // Outer symbols first where two start at the same position
+    order.sort_by(|&a, &b| {
+        let (a, b) = (&symbols[a].range, &symbols[b].range);
+        (a.start_line, a.start_column)
+            .cmp(&(b.start_line, b.start_column))
+            .then((b.end_line, b.end_column).cmp(&(a.end_line, a.end_column)))
+    });
+
+    let mut nested = vec![false; symbols.len()];
+    let mut outer_end = None;
+    for index in order {
+        // A file's module symbol spans it without nesting its contents
+        if symbols[index].kind == SymbolKind::Module {
+            continue;
+        }
+        let range = &symbols[index].range;
+        if outer_end.is_some_and(|end| (range.start_line, range.start_column) < end) {
+            nested[index] = true;
+        } else {
+            outer_end = Some((range.end_line, range.end_column));
+        }
     }
 
-    // Type usage - range is the usage site
-    for (context, used_type, usage_range) in parser.find_uses(content) {
-        relationships.push(RawRelationship::new(
-            context,
-            usage_range, // from_range = usage context (triggers fallback)
-            used_type,
-            usage_range, // to_range = where type is used
-            crate::RelationKind::Uses,
-        ));
+    symbols
+        .into_iter()
+        .zip(nested)
+        .filter(|(symbol, nested)| {
+            // Methods of Rust impl blocks are not nested in a symbol
+            !nested
+                && symbol.visibility == Visibility::Public
+                && symbol.kind != SymbolKind::Method
+                && !matches!(
+                    symbol.scope_context,
+                    Some(
+                        ScopeContext::Local { .. }
+                            | ScopeContext::Parameter
+                            | ScopeContext::ClassMember { .. }
+                    )
+                )
+        })
+        .map(|(symbol, _)| symbol)
+        .collect()
+}
+
+/// Relationships the language's behavior builds from a file's symbols and
+/// parse artifacts
+fn build_relationships(
+    language_id: LanguageId,
+    symbols: &[Symbol],
+    artifacts: &ParseArtifacts,
+) -> Vec<RawRelationship> {
+    match create_behavior(language_id) {
+        Some(behavior) => behavior.build_relationships(symbols, artifacts),
+        None => default_relationships(artifacts),
     }
+}
 
-    // Method definitions (Defines relationships)
-    for (definer, method, def_range) in parser.find_defines(content) {
-        relationships.push(RawRelationship::new(
-            definer,
-            def_range, // from_range = where definer is
-            method,
-            def_range, // to_range = where method is defined
-            crate::RelationKind::Defines,
-        ));
+/// Record which symbols the language counts as exported, now that the parser
+/// has settled their visibility and scope.
+fn mark_exported(behavior: &dyn LanguageBehavior, symbols: &mut [Symbol]) {
+    for symbol in symbols {
+        symbol.exported = behavior.is_exported(symbol);
+    }
+}
+
+/// Behavior for a registered language
+fn create_behavior(language_id: LanguageId) -> Option<Box<dyn LanguageBehavior>> {
+    let registry = get_registry();
+    let registry_guard = registry.lock().ok()?;
+    Some(registry_guard.get(language_id)?.create_behavior())
+}
+
+/// Syntax tree of `code` in the grammar of a registered language
+pub(crate) fn syntax_tree(code: &str, language_id: LanguageId) -> Option<tree_sitter::Tree> {
+    let behavior = create_behavior(language_id)?;
+    let mut parser = tree_sitter::Parser::new();
+    parser.set_language(&behavior.get_language()).ok()?;
+    parser.parse(code, None)
+}
+
+/// Identifier references from the shared reference pass, for languages whose
+/// behavior declares reference node kinds. `variable_types` type the
+/// receivers of member accesses.
+fn find_references(
+    tree: &tree_sitter::Tree,
+    code: &str,
+    language_id: LanguageId,
+    symbols: &[Symbol],
+    variable_types: &[(&str, &str, Range)],
+) -> Vec<ReferenceSite> {
+    let Some(behavior) = create_behavior(language_id) else {
+        return Vec::new();
+    };
+    match behavior.reference_node_kinds() {
+        Some(kinds) => extract_references(tree, code, symbols, kinds, variable_types),
+        None => Vec::new(),
     }
+}
+
+/// Regions of `tree` that may not run whenever their function does, for
+/// languages whose behavior declares branch node kinds.
+pub(crate) fn find_conditional_ranges(
+    tree: &tree_sitter::Tree,
+    language_id: LanguageId,
+) -> Vec<Range> {
+    let Some(behavior) = create_behavior(language_id) else {
+        return Vec::new();
+    };
+    match behavior.branch_node_kinds() {
+        Some(kinds) => conditional_ranges(tree, kinds),
+        None => Vec::new(),
+    }
+}
 
-    relationships
+/// Tag calls made from a conditional region of their caller with the
+/// `conditional` attribute.
+///
+/// The region must lie inside the caller's range, so a function declared in
+/// a branch still calls unconditionally from its own body. Calls without a
+/// caller range (`from_range` is the call site) only need to be in a region.
+fn mark_conditional_calls(relationships: &mut [RawRelationship], conditional: &[Range]) {
+    if conditional.is_empty() {
+        return;
+    }
+    for rel in relationships
+        .iter_mut()
+        .filter(|rel| rel.kind == crate::RelationKind::Calls)
+    {
+        let behind_branch = conditional.iter().any(|region| {
+            range_within(&rel.to_range, region)
+                && (rel.from_range == rel.to_range || range_within(region, &rel.from_range))
+        });
+        if behind_branch {
+            let metadata = rel.metadata.take().unwrap_or_default();
+            rel.metadata = Some(metadata.with_attribute("conditional", "true"));
+        }
+    }
 }
 
 /// Compute content hash using FNV-1a.
@@ -364,7 +757,7 @@ mod tests {
     #[test]
     fn test_detect_language_rust() {
         let path = Path::new("test.rs");
-        let result = detect_language(path);
+        let result = detect_language(path, "", &Settings::default());
         assert!(result.is_ok());
         assert_eq!(result.unwrap().as_str(), "rust");
     }
@@ -372,7 +765,7 @@ mod tests {
     #[test]
     fn test_detect_language_typescript() {
         let path = Path::new("app.ts");
-        let result = detect_language(path);
+        let result = detect_language(path, "", &Settings::default());
         assert!(result.is_ok());
         assert_eq!(result.unwrap().as_str(), "typescript");
     }
@@ -380,10 +773,40 @@ mod tests {
     #[test]
     fn test_detect_language_unknown() {
         let path = Path::new("file.xyz");
-        let result = detect_language(path);
+        let result = detect_language(path, "#!/usr/bin/env python3\n", &Settings::default());
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_detect_language_by_shebang() {
+        let settings = Settings::default();
+        let detect = |path: &str, content: &str| {
+            detect_language(Path::new(path), content, &settings)
+                .ok()
+                .map(|id| id.as_str())
+        };
+        assert_eq!(
+            detect("bin/deploy", "#!/usr/bin/env python3\n"),
+            Some("python")
+        );
+        assert_eq!(
+            detect(
+                "scripts/update",
+                "#!/usr/bin/env nix-shell\n#!nix-shell -i node -p nodejs\n"
+            ),
+            Some("javascript")
+        );
+        assert_eq!(detect("README", "Plain text\n"), None);
+
+        let mut settings = Settings::default();
+        settings
+            .indexing
+            .language_overrides
+            .insert("Tiltfile".to_string(), "python".to_string());
+        let result = detect_language(Path::new("Tiltfile"), "load('ext://x')\n", &settings);
+        assert_eq!(result.unwrap().as_str(), "python");
+    }
+
     #[test]
     fn test_parse_file_rust() {
         let settings = Arc::new(Settings::default());
@@ -417,6 +840,218 @@ pub struct Foo {
         assert!(names.contains(&"Foo"));
     }
 
+    #[test]
+    fn test_nix_module_imports_resolve_to_module_paths() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        std::fs::create_dir_all(root.join("hosts/common")).unwrap();
+        std::fs::write(root.join("hosts/common/default.nix"), "{ }").unwrap();
+        std::fs::write(root.join("hosts/hardware.nix"), "{ }").unwrap();
+        let settings = Arc::new(Settings {
+            workspace_root: Some(root.clone()),
+            ..Settings::default()
+        });
+        init_parser_cache(settings.clone());
+
+        let content = FileContent::new(
+            root.join("hosts/laptop.nix"),
+            "{ ... }: { imports = [ ./hardware.nix ./common ./missing.nix ]; }\n".to_string(),
+            "abc123def456".to_string(),
+        );
+        let parsed = parse_file(content, &settings).unwrap();
+        let imports: Vec<&str> = parsed
+            .raw_imports
+            .iter()
+            .map(|import| import.path.as_str())
+            .collect();
+        // A path to no indexed file keeps its literal
+        assert_eq!(
+            imports,
+            ["hosts.hardware", "hosts.common.default", "./missing.nix"]
+        );
+        assert_eq!(
+            parsed.raw_imports[0].range,
+            Some(crate::Range::new(0, 23, 0, 37))
+        );
+    }
+
+    #[test]
+    fn test_kind_rules_reclassify_parsed_symbols() {
+        let settings = Arc::new(Settings {
+            kinds: vec![crate::config::KindRule {
+                language: "rust".to_string(),
+                name: "^[A-Z][A-Z0-9_]*$".to_string(),
+                from: Some(SymbolKind::Struct),
+                kind: SymbolKind::Constant,
+            }],
+            ..Settings::default()
+        });
+        init_parser_cache(settings.clone());
+
+        let content = FileContent::new(
+            "unit.rs".into(),
+            "pub struct UNIT;\npub struct Unit;\n".to_string(),
+            "abc123def456".to_string(),
+        );
+        let parsed = parse_file(content, &settings).unwrap();
+        let kind_of = |name: &str| {
+            parsed
+                .raw_symbols
+                .iter()
+                .find(|symbol| symbol.name.as_ref() == name)
+                .map(|symbol| symbol.kind)
+        };
+        assert_eq!(kind_of("UNIT"), Some(SymbolKind::Constant));
+        assert_eq!(kind_of("Unit"), Some(SymbolKind::Struct));
+    }
+
+    #[test]
+    fn test_parser_panic_is_isolated() {
+        let settings = Arc::new(Settings::default());
+        let mut cache = ParserCache::new(settings);
+        let rust = LanguageId::new("rust");
+        cache.get_or_create(rust).unwrap();
+
+        let result = run_isolated(|| -> usize { panic!("node kind out of range") });
+        assert_eq!(result, Err("node kind out of range".to_string()));
+        assert_eq!(run_isolated(|| 7), Ok(7));
+
+        let error = parser_panicked(Path::new("src/lib.rs"), rust, "boom".to_string());
+        assert!(matches!(
+            error,
+            PipelineError::ParserPanic { ref language, .. } if language == "rust"
+        ));
+
+        cache.discard(rust);
+        assert!(!cache.parsers.contains_key(&rust));
+        assert!(cache.get_or_create(rust).is_ok());
+    }
+
+    #[test]
+    fn test_reference_pass_adds_value_references() {
+        let settings = Arc::new(Settings::default());
+        init_parser_cache(settings.clone());
+
+        let content = FileContent::new(
+            "test.rs".into(),
+            r#"
+fn double(x: i32) -> i32 {
+    x * 2
+}
+
+fn apply(values: Vec<i32>) -> Vec<i32> {
+    let first = double(1);
+    values.into_iter().map(double).chain([first]).collect()
+}
+"#
+            .to_string(),
+            "abc123def456".to_string(),
+        );
+
+        let parsed = parse_file(content, &settings).unwrap();
+        let references: Vec<(&str, &str, u32)> = parsed
+            .raw_relationships
+            .iter()
+            .filter(|r| r.kind == crate::RelationKind::References)
+            .map(|r| {
+                (
+                    r.from_name.as_ref(),
+                    r.to_name.as_ref(),
+                    r.to_range.start_line,
+                )
+            })
+            .collect();
+        // Both `Vec<i32>` are generic instantiations. The call on line 6 is
+        // already a Calls edge; the locals are skipped.
+        assert_eq!(
+            references,
+            [
+                ("apply", "Vec", 5),
+                ("apply", "Vec", 5),
+                ("apply", "double", 7)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_calls_behind_branches_are_conditional() {
+        let settings = Arc::new(Settings::default());
+        init_parser_cache(settings.clone());
+
+        let content = FileContent::new(
+            "test.rs".into(),
+            r#"
+fn run(ready: bool) {
+    prepare();
+    if ready {
+        launch();
+    }
+}
+"#
+            .to_string(),
+            "abc123def456".to_string(),
+        );
+
+        let parsed = parse_file(content, &settings).unwrap();
+        let calls: Vec<(&str, bool)> = parsed
+            .raw_relationships
+            .iter()
+            .filter(|r| r.kind == crate::RelationKind::Calls)
+            .map(|r| {
+                let conditional = r
+                    .metadata
+                    .as_ref()
+                    .and_then(|m| m.attribute("conditional"))
+                    .is_some();
+                (r.to_name.as_ref(), conditional)
+            })
+            .collect();
+        assert_eq!(calls, [("prepare", false), ("launch", true)]);
+    }
+
+    #[test]
+    fn test_light_profile_keeps_top_level_public_symbols() {
+        let settings =
+            Arc::new(Settings::default().with_extraction_profile(ExtractionProfile::Light));
+        init_parser_cache(settings.clone());
+
+        let content = FileContent::new(
+            "test.rs".into(),
+            r#"
+pub struct Point {
+    pub x: i32,
+}
+
+impl Point {
+    pub fn norm(&self) -> i32 {
+        helper(self.x)
+    }
+}
+
+fn helper(value: i32) -> i32 {
+    value
+}
+
+pub fn origin() -> Point {
+    let p = Point { x: 0 };
+    p
+}
+"#
+            .to_string(),
+            "abc123def456".to_string(),
+        );
+
+        let parsed = parse_file(content, &settings).unwrap();
+        let names: Vec<&str> = parsed.raw_symbols.iter().map(|s| s.name.as_ref()).collect();
+        assert_eq!(names, ["Point", "origin"]);
+        assert!(
+            !parsed
+                .raw_relationships
+                .iter()
+                .any(|r| r.kind == crate::RelationKind::References)
+        );
+    }
+
     #[test]
     fn test_raw_symbol_has_no_id() {
         // RawSymbol intentionally has no id field
@@ -5,9 +5,10 @@
 
 use crate::indexing::file_info::calculate_hash;
 use crate::indexing::pipeline::types::{FileContent, PipelineError, PipelineResult};
+use crate::indexing::progress::{ProgressEvent, ProgressEventCallback};
 use crossbeam_channel::{Receiver, Sender};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::thread;
@@ -17,6 +18,7 @@ pub struct ReadStage {
     threads: usize,
     /// Workspace root for path normalization (stores relative paths)
     workspace_root: Option<PathBuf>,
+    events: Option<ProgressEventCallback>,
 }
 
 impl ReadStage {
@@ -25,6 +27,7 @@ impl ReadStage {
         Self {
             threads: threads.max(1),
             workspace_root: None,
+            events: None,
         }
     }
 
@@ -33,12 +36,23 @@ impl ReadStage {
         Self {
             threads: threads.max(1),
             workspace_root,
+            events: None,
         }
     }
 
+    /// Report files that cannot be read to `events`.
+    pub fn with_events(mut self, events: Option<ProgressEventCallback>) -> Self {
+        self.events = events;
+        self
+    }
+
     /// Read a single file directly (for incremental mode).
     pub fn read_single(&self, path: &PathBuf) -> PipelineResult<FileContent> {
-        read_file(path)
+        let result = read_file(path);
+        if let (Err(e), Some(events)) = (&result, &self.events) {
+            report_error(events, self.workspace_root.as_deref(), path, e);
+        }
+        result
     }
 
     /// Run the read stage, reading from path channel and sending to content channel.
@@ -65,6 +79,7 @@ impl ReadStage {
 
         let workspace_root = self.workspace_root.clone();
         let workspace_root = Arc::new(workspace_root);
+        let events = self.events.clone();
 
         let handles: Vec<_> = (0..self.threads)
             .map(|_| {
@@ -75,6 +90,7 @@ impl ReadStage {
                 let input_wait_ns = input_wait_ns.clone();
                 let output_wait_ns = output_wait_ns.clone();
                 let workspace_root = workspace_root.clone();
+                let events = events.clone();
 
                 thread::spawn(move || {
                     loop {
@@ -108,7 +124,10 @@ impl ReadStage {
                                     Ordering::Relaxed,
                                 );
                             }
-                            Err(_) => {
+                            Err(e) => {
+                                if let Some(events) = &events {
+                                    report_error(events, workspace_root.as_deref(), &path, &e);
+                                }
                                 error_count.fetch_add(1, Ordering::Relaxed);
                             }
                         }
@@ -132,6 +151,23 @@ impl ReadStage {
     }
 }
 
+/// Send an error event for `path`, relative to `workspace_root` like the
+/// paths of files that were read.
+fn report_error(
+    events: &ProgressEventCallback,
+    workspace_root: Option<&Path>,
+    path: &Path,
+    error: &PipelineError,
+) {
+    let path = workspace_root
+        .and_then(|root| path.strip_prefix(root).ok())
+        .unwrap_or(path);
+    events(&ProgressEvent::Error {
+        path: path.to_path_buf(),
+        message: error.to_string(),
+    });
+}
+
 /// Read a single file and compute its SHA256 hash.
 fn read_file(path: &PathBuf) -> PipelineResult<FileContent> {
     let content = fs::read_to_string(path).map_err(|e| PipelineError::FileRead {
@@ -14,14 +14,27 @@
 //! Two-pass execution:
 //! - Pass 1: Resolve Defines relationships
 //! - Pass 2: Resolve Calls (can reference Defines from Pass 1)
-
+//!
+//! A member not declared on its receiver's type is looked up on the
+//! supertypes, as the language's `InheritanceResolver` orders them.
+//!
+//! In a sharded index, what doesn't resolve locally is looked up among the
+//! symbols of the other shards ([`ForeignSymbols`]). Imported names and
+//! calls are then looked up in the mounted dependencies
+//! ([`DependencyLinker`]), and what still doesn't resolve goes to the
+//! unresolved sink for a later try.
+
+use crate::deps::link::DependencyLinker;
+use crate::indexing::pipeline::UnresolvedSink;
 use crate::indexing::pipeline::types::{
     CallerContext, ResolutionContext, ResolvedBatch, ResolvedRelationship, SymbolLookupCache,
     UnresolvedRelationship,
 };
-use crate::parsing::{Import, LanguageBehavior, LanguageId};
+use crate::indexing::shards::ForeignSymbols;
+use crate::parsing::{Import, InheritanceResolver, LanguageBehavior, LanguageId, TypeRef};
+use crate::symbol::ScopeContext;
 use crate::types::{FileId, SymbolId};
-use crate::{RelationKind, Symbol};
+use crate::{RelationKind, Symbol, SymbolKind, Visibility};
 use std::collections::HashMap;
 use std::sync::Arc;
 
@@ -32,6 +45,14 @@ pub struct ResolveStage {
     symbol_cache: Arc<SymbolLookupCache>,
     /// Behaviors by language_id (from CONTEXT stage)
     behaviors: HashMap<LanguageId, Arc<dyn LanguageBehavior>>,
+    /// Type hierarchies by language_id, for inherited members
+    inheritance: HashMap<LanguageId, Box<dyn InheritanceResolver>>,
+    /// Symbols of the other shards of a sharded index
+    foreign: Option<Arc<ForeignSymbols>>,
+    /// Symbols of the mounted dependencies
+    dependencies: Option<Arc<DependencyLinker>>,
+    /// Receives the relationships left unresolved
+    unresolved_sink: Option<UnresolvedSink>,
 }
 
 /// Statistics from resolution.
@@ -62,7 +83,60 @@ impl ResolveStage {
         Self {
             symbol_cache,
             behaviors,
+            inheritance: HashMap::new(),
+            foreign: None,
+            dependencies: None,
+            unresolved_sink: None,
+        }
+    }
+
+    /// Record the supertype edges among the relationships of `contexts`, so
+    /// members are also looked up on the receiver's supertypes.
+    pub fn with_inheritance(mut self, contexts: &[ResolutionContext]) -> Self {
+        for context in contexts {
+            for unresolved in &context.unresolved_rels {
+                let language = unresolved
+                    .from_id
+                    .and_then(|id| self.symbol_cache.get(id))
+                    .and_then(|symbol| symbol.language_id)
+                    .unwrap_or(context.language_id);
+                let Some(behavior) = self.behaviors.get(&language) else {
+                    continue;
+                };
+                let Some(relation) = behavior.supertype_relation(unresolved.kind) else {
+                    continue;
+                };
+                self.inheritance
+                    .entry(language)
+                    .or_insert_with(|| behavior.create_inheritance_resolver())
+                    .add_inheritance(
+                        TypeRef::named(&*unresolved.from_name),
+                        TypeRef::named(&*unresolved.to_name),
+                        relation,
+                    );
+            }
         }
+        self
+    }
+
+    /// Look up what doesn't resolve locally among the symbols of the other
+    /// shards.
+    pub fn with_foreign_symbols(mut self, foreign: Option<Arc<ForeignSymbols>>) -> Self {
+        self.foreign = foreign;
+        self
+    }
+
+    /// Look up the calls that resolve nowhere in the project among the
+    /// symbols of the mounted dependencies.
+    pub fn with_dependencies(mut self, dependencies: Option<Arc<DependencyLinker>>) -> Self {
+        self.dependencies = dependencies;
+        self
+    }
+
+    /// Hand the relationships left unresolved to `sink`.
+    pub fn with_unresolved_sink(mut self, sink: Option<UnresolvedSink>) -> Self {
+        self.unresolved_sink = sink;
+        self
     }
 
     /// Get behavior for a language, if available.
@@ -76,11 +150,16 @@ impl ResolveStage {
     pub fn resolve(&self, context: &ResolutionContext) -> (ResolvedBatch, ResolveStats) {
         let mut batch = ResolvedBatch::with_capacity(context.unresolved_rels.len());
         let mut stats = ResolveStats::default();
+        let mut leftover = Vec::new();
 
         for unresolved in &context.unresolved_rels {
             stats.total_processed += 1;
 
-            if let Some(resolved) = self.resolve_one(unresolved, context) {
+            let resolved = self
+                .resolve_one(unresolved, context)
+                .or_else(|| self.resolve_foreign(unresolved, context))
+                .or_else(|| self.resolve_dependency(unresolved, context));
+            if let Some(resolved) = resolved {
                 match resolved.kind {
                     RelationKind::Defines => stats.defines_resolved += 1,
                     RelationKind::Calls => stats.calls_resolved += 1,
@@ -96,12 +175,108 @@ impl ResolveStage {
                 } else {
                     stats.unresolved_ambiguous += 1;
                 }
+                if self.unresolved_sink.is_some() {
+                    leftover.push(unresolved.clone());
+                }
+            }
+        }
+
+        if let Some(sink) = &self.unresolved_sink {
+            if !leftover.is_empty() {
+                sink.lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .extend(leftover);
             }
         }
 
         (batch, stats)
     }
 
+    /// Resolve a relationship against the symbols of the other shards.
+    ///
+    /// Only plain names are looked up there; members, operators and options
+    /// are resolved within their shard. A candidate must be in the caller's
+    /// language and visible outside its file. An imported candidate wins,
+    /// otherwise the only one; of several, none is picked.
+    fn resolve_foreign(
+        &self,
+        unresolved: &UnresolvedRelationship,
+        context: &ResolutionContext,
+    ) -> Option<ResolvedRelationship> {
+        let foreign = self.foreign.as_ref()?;
+        let from_id = unresolved.from_id?;
+        if unresolved.metadata.as_ref().is_some_and(|metadata| {
+            ["receiver", "operator", "option"]
+                .iter()
+                .any(|attribute| metadata.attribute(attribute).is_some())
+        }) {
+            return None;
+        }
+
+        let candidates: Vec<Symbol> = foreign
+            .candidates(&unresolved.to_name)
+            .into_iter()
+            .filter(|symbol| {
+                symbol.language_id == Some(context.language_id)
+                    && symbol.visibility != Visibility::Private
+            })
+            .collect();
+        let imported: Vec<&Symbol> = candidates
+            .iter()
+            .filter(|symbol| self.is_imported(symbol, &context.imports, context))
+            .collect();
+        let to_id = match (imported.as_slice(), candidates.as_slice()) {
+            ([symbol], _) => symbol.id,
+            ([], [symbol]) => symbol.id,
+            _ => return None,
+        };
+        Some(ResolvedRelationship {
+            from_id,
+            to_id,
+            kind: unresolved.kind,
+            metadata: unresolved.metadata.clone(),
+        })
+    }
+
+    /// Resolve a relationship to a symbol of a mounted dependency.
+    ///
+    /// A name the file imports is looked up in the module it is imported
+    /// from. A call is also looked up by its path alone, so `lib.mkOption`
+    /// finds `mkOption` of nixpkgs-lib. Members of project types and
+    /// operators stay in the project. Only a unique match in the caller's
+    /// language is linked.
+    fn resolve_dependency(
+        &self,
+        unresolved: &UnresolvedRelationship,
+        context: &ResolutionContext,
+    ) -> Option<ResolvedRelationship> {
+        let dependencies = self.dependencies.as_ref()?;
+        let from_id = unresolved.from_id?;
+        let metadata = unresolved.metadata.as_ref();
+        if unresolved.kind == RelationKind::Defines
+            || metadata.is_some_and(|metadata| {
+                metadata.attribute("receiver").is_some() || metadata.attribute("operator").is_some()
+            })
+        {
+            return None;
+        }
+        let name = metadata
+            .and_then(|metadata| metadata.attribute("qualified"))
+            .unwrap_or(&unresolved.to_name);
+        let to_id = dependencies
+            .link_import(name, &context.imports, context.language_id)
+            .or_else(|| match unresolved.kind {
+                RelationKind::Calls => dependencies.link(name, context.language_id),
+                _ => None,
+            })?;
+        Some(ResolvedRelationship {
+            from_id,
+            to_id,
+            kind: unresolved.kind,
+            metadata: unresolved.metadata.clone(),
+        })
+    }
+
     /// Resolve a single relationship.
     ///
     /// Uses PipelineSymbolCache.resolve() with CallerContext:
@@ -132,6 +307,59 @@ impl ResolveStage {
             })
             .unwrap_or_else(|| CallerContext::from_file(context.file_id, context.language_id));
 
+        // A member access names its receiver's type; only a member of that
+        // type will do, whatever else shares the name
+        if let Some(receiver) = unresolved
+            .metadata
+            .as_ref()
+            .and_then(|metadata| metadata.attribute("receiver"))
+        {
+            let to_id = self.resolve_member(
+                &unresolved.to_name,
+                receiver,
+                context.file_id,
+                caller.language_id,
+            )?;
+            return Some(ResolvedRelationship {
+                from_id,
+                to_id,
+                kind: unresolved.kind,
+                metadata: unresolved.metadata.clone(),
+            });
+        }
+
+        // An operator names no type, only the method an overload defines; a
+        // plain function or a method of another language can't be it
+        if unresolved
+            .metadata
+            .as_ref()
+            .is_some_and(|metadata| metadata.attribute("operator").is_some())
+        {
+            let to_id = self.resolve_operator(&unresolved.to_name, &caller)?;
+            return Some(ResolvedRelationship {
+                from_id,
+                to_id,
+                kind: unresolved.kind,
+                metadata: unresolved.metadata.clone(),
+            });
+        }
+
+        // An option path set by a configuration lands on the declared option
+        // it falls under, wherever that is declared
+        if unresolved
+            .metadata
+            .as_ref()
+            .is_some_and(|metadata| metadata.attribute("option").is_some())
+        {
+            let to_id = self.resolve_option(&unresolved.to_name, &caller)?;
+            return Some(ResolvedRelationship {
+                from_id,
+                to_id,
+                kind: unresolved.kind,
+                metadata: unresolved.metadata.clone(),
+            });
+        }
+
         // First try context.resolve() which uses language-specific resolution
         // with pre-resolved import bindings from build_resolution_context_with_pipeline_cache()
         if let Some(to_id) = context.resolve(&unresolved.to_name) {
@@ -172,6 +400,115 @@ impl ResolveStage {
         }
     }
 
+    /// Resolve `name` as a member of the type named `receiver`, or else of
+    /// the first of its supertypes that declares it.
+    fn resolve_member(
+        &self,
+        name: &str,
+        receiver: &str,
+        file_id: FileId,
+        language: LanguageId,
+    ) -> Option<SymbolId> {
+        self.declared_member(name, receiver, file_id).or_else(|| {
+            self.inheritance
+                .get(&language)?
+                .get_inheritance_chain(&TypeRef::named(receiver))
+                .iter()
+                .skip(1)
+                .find_map(|ancestor| self.declared_member(name, &ancestor.qualified_name, file_id))
+        })
+    }
+
+    /// Resolve `name` as a member declared by the type named `receiver`.
+    ///
+    /// A symbol is a member when its scope names the type, or when it is
+    /// declared inside the type's body, as struct fields are. Members in the
+    /// caller's file win.
+    fn declared_member(&self, name: &str, receiver: &str, file_id: FileId) -> Option<SymbolId> {
+        let owners: Vec<Symbol> = self
+            .symbol_cache
+            .lookup_candidates(receiver)
+            .into_iter()
+            .filter_map(|id| self.symbol_cache.get(id))
+            .collect();
+        let members: Vec<Symbol> = self
+            .symbol_cache
+            .lookup_candidates(name)
+            .into_iter()
+            .filter_map(|id| self.symbol_cache.get(id))
+            .filter(|member| {
+                let scoped = match &member.scope_context {
+                    Some(ScopeContext::ClassMember {
+                        class_name: Some(class_name),
+                    }) => class_name.rsplit(['.', ':']).next() == Some(receiver),
+                    _ => false,
+                };
+                scoped
+                    || owners.iter().any(|owner| {
+                        owner.file_id == member.file_id
+                            && owner
+                                .range
+                                .contains(member.range.start_line, member.range.start_column)
+                    })
+            })
+            .collect();
+
+        members
+            .iter()
+            .find(|member| member.file_id == file_id)
+            .or_else(|| members.first())
+            .map(|member| member.id)
+    }
+
+    /// Resolve an overloaded operator to a method named `method`, looking at
+    /// qualified names (`Vector.__add__`) too. Prefers the caller's file.
+    fn resolve_operator(&self, method: &str, caller: &CallerContext) -> Option<SymbolId> {
+        let methods: Vec<Symbol> = self
+            .symbol_cache
+            .lookup_candidates(method)
+            .into_iter()
+            .chain(self.symbol_cache.lookup_members(method))
+            .filter_map(|id| self.symbol_cache.get(id))
+            .filter(|symbol| {
+                symbol.kind == SymbolKind::Method && symbol.language_id == Some(caller.language_id)
+            })
+            .collect();
+
+        methods
+            .iter()
+            .find(|symbol| symbol.file_id == caller.file_id)
+            .or_else(|| methods.first())
+            .map(|symbol| symbol.id)
+    }
+
+    /// Resolve a dotted option `path` to the declared option with the
+    /// longest path it starts with: setting `services.nginx.virtualHosts.web`
+    /// sets the `services.nginx.virtualHosts` option. Options are declared as
+    /// fields of the caller's language; one in the caller's file wins.
+    fn resolve_option(&self, path: &str, caller: &CallerContext) -> Option<SymbolId> {
+        let mut prefix = path;
+        loop {
+            let options: Vec<Symbol> = self
+                .symbol_cache
+                .lookup_candidates(prefix)
+                .into_iter()
+                .filter_map(|id| self.symbol_cache.get(id))
+                .filter(|symbol| {
+                    symbol.kind == SymbolKind::Field
+                        && symbol.language_id == Some(caller.language_id)
+                })
+                .collect();
+            if let Some(option) = options
+                .iter()
+                .find(|symbol| symbol.file_id == caller.file_id)
+                .or_else(|| options.first())
+            {
+                return Some(option.id);
+            }
+            prefix = &prefix[..prefix.rfind('.')?];
+        }
+    }
+
     /// Disambiguate among multiple candidates.
     ///
     /// Priority order:
@@ -362,8 +699,8 @@ impl ResolveStage {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::SymbolKind;
     use crate::parsing::{LanguageId, ResolutionScope, ScopeLevel, ScopeType};
+    use crate::relationship::{Confidence, RelationshipMetadata};
     use crate::types::Range;
     use std::sync::Arc as StdArc;
 
@@ -493,6 +830,163 @@ mod tests {
         assert_eq!(resolved.to_id, SymbolId::new(2).unwrap());
     }
 
+    #[test]
+    fn test_resolve_member_of_receiver_type() {
+        let rust = LanguageId::new("rust");
+        let cache = Arc::new(SymbolLookupCache::new());
+        cache.insert(make_symbol(1, "caller", 1, rust));
+
+        // `Config { port }` in file 1, `Server { port }` in file 2
+        let mut config = make_symbol(2, "Config", 1, rust);
+        config.range = Range::new(20, 0, 23, 1);
+        cache.insert(config);
+        let mut config_port = make_symbol(3, "port", 1, rust);
+        config_port.range = Range::new(21, 4, 21, 14);
+        cache.insert(config_port);
+        let server = make_symbol(4, "Server", 2, rust);
+        cache.insert(server);
+        let mut server_port = make_symbol(5, "port", 2, rust);
+        server_port.range = Range::new(2, 4, 2, 14);
+        cache.insert(server_port);
+
+        let stage = make_stage(cache);
+
+        let access = |receiver: &str| {
+            let mut unresolved = make_unresolved(1, "port", 1, RelationKind::References);
+            unresolved.metadata =
+                Some(RelationshipMetadata::new().with_attribute("receiver", receiver));
+            unresolved
+        };
+        let context = make_context(
+            1,
+            rust,
+            vec![SymbolId::new(1).unwrap()],
+            vec![access("Server"), access("Config"), access("Client")],
+        );
+
+        let (batch, stats) = stage.resolve(&context);
+
+        // The local `port` doesn't shadow Server's, and no type means no edge
+        assert_eq!(stats.resolved, 2);
+        assert_eq!(batch.relationships[0].to_id, SymbolId::new(5).unwrap());
+        assert_eq!(batch.relationships[1].to_id, SymbolId::new(3).unwrap());
+    }
+
+    #[test]
+    fn test_resolve_member_inherited_from_supertype() {
+        let python = LanguageId::new("python");
+        let cache = Arc::new(SymbolLookupCache::new());
+        cache.insert(make_symbol(1, "caller", 1, python));
+
+        // `class Animal: def speak` and `class Dog(Animal)`
+        let mut animal = make_symbol(2, "Animal", 1, python);
+        animal.range = Range::new(20, 0, 23, 1);
+        cache.insert(animal);
+        let mut speak = make_symbol(3, "speak", 1, python);
+        speak.range = Range::new(21, 4, 22, 12);
+        cache.insert(speak);
+        let mut dog = make_symbol(4, "Dog", 1, python);
+        dog.range = Range::new(25, 0, 26, 8);
+        cache.insert(dog);
+
+        let behaviors: HashMap<LanguageId, Arc<dyn LanguageBehavior>> = HashMap::from([(
+            python,
+            Arc::new(crate::parsing::python::PythonBehavior::new()) as Arc<dyn LanguageBehavior>,
+        )]);
+        let mut base = make_unresolved(4, "Animal", 1, RelationKind::Implements);
+        base.from_name = StdArc::from("Dog");
+        let mut call = make_unresolved(1, "speak", 1, RelationKind::Calls);
+        call.metadata = Some(RelationshipMetadata::new().with_attribute("receiver", "Dog"));
+        let contexts = vec![make_context(
+            1,
+            python,
+            vec![SymbolId::new(1).unwrap()],
+            vec![base, call],
+        )];
+
+        let (_, stats) =
+            ResolveStage::new(Arc::clone(&cache), HashMap::new()).resolve(&contexts[0]);
+        assert_eq!(stats.calls_resolved, 0);
+
+        let stage = ResolveStage::new(cache, behaviors).with_inheritance(&contexts);
+        let (batch, stats) = stage.resolve(&contexts[0]);
+        assert_eq!(stats.calls_resolved, 1);
+        assert_eq!(batch.relationships[1].to_id, SymbolId::new(3).unwrap());
+    }
+
+    #[test]
+    fn test_resolve_operator_to_overload_method() {
+        let python = LanguageId::new("python");
+        let cache = Arc::new(SymbolLookupCache::new());
+        cache.insert(make_symbol(1, "caller", 1, python));
+
+        // A free function named like the overload doesn't count, a method
+        // named after its class does
+        cache.insert(make_symbol(2, "__add__", 1, python));
+        let mut overload = make_symbol(3, "Vector.__add__", 2, python);
+        overload.kind = SymbolKind::Method;
+        cache.insert(overload);
+
+        let stage = make_stage(cache);
+
+        let mut unresolved = make_unresolved(1, "__add__", 1, RelationKind::Calls);
+        unresolved.metadata = Some(
+            RelationshipMetadata::new()
+                .with_attribute("operator", "+")
+                .with_confidence(Confidence::Low),
+        );
+        let context = make_context(1, python, vec![SymbolId::new(1).unwrap()], vec![unresolved]);
+
+        let (batch, stats) = stage.resolve(&context);
+
+        assert_eq!(stats.resolved, 1);
+        assert_eq!(batch.relationships[0].to_id, SymbolId::new(3).unwrap());
+    }
+
+    #[test]
+    fn test_resolve_option_path_to_longest_declared_option() {
+        let nix = LanguageId::new("nix");
+        let cache = Arc::new(SymbolLookupCache::new());
+        cache.insert(make_symbol(1, "caller", 1, nix));
+
+        // Options declared in another module; a binding named like one of
+        // them is no option
+        for (id, name) in [
+            (2, "services.nginx.enable"),
+            (3, "services.nginx.virtualHosts"),
+        ] {
+            let mut option = make_symbol(id, name, 2, nix);
+            option.kind = SymbolKind::Field;
+            cache.insert(option);
+        }
+        cache.insert(make_symbol(4, "services.nginx", 1, nix));
+
+        let stage = make_stage(cache);
+
+        let definition = |path: &str| {
+            let mut unresolved = make_unresolved(1, path, 1, RelationKind::References);
+            unresolved.metadata =
+                Some(RelationshipMetadata::new().with_attribute("option", "true"));
+            unresolved
+        };
+        let context = make_context(
+            1,
+            nix,
+            vec![SymbolId::new(1).unwrap()],
+            vec![
+                definition("services.nginx.enable"),
+                definition("services.nginx.virtualHosts.example.root"),
+                definition("services.nginx.package"),
+            ],
+        );
+
+        let (batch, stats) = stage.resolve(&context);
+
+        assert_eq!(stats.resolved, 2);
+        assert_eq!(batch.relationships[0].to_id, SymbolId::new(2).unwrap());
+        assert_eq!(batch.relationships[1].to_id, SymbolId::new(3).unwrap());
+    }
+
     #[test]
     fn test_resolve_no_candidates() {
         let cache = Arc::new(SymbolLookupCache::new());
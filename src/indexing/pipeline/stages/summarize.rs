@@ -0,0 +1,396 @@
+//! Summarize stage - generated descriptions for undocumented symbols
+//!
+//! Optional, runs after indexing when `[summaries]` is enabled. Public
+//! functions, types, and modules without a doc comment are sent to an
+//! OpenAI-compatible chat endpoint, which answers with a one-line
+//! description of each.
+//!
+//! Descriptions never become doc comments: they live in `summaries.json`
+//! next to the index, keyed by a hash of the symbol's location, name, and
+//! signature, so re-indexing an unchanged symbol reuses its description and
+//! a changed one gets a new one. Entries whose symbol is gone, or has since
+//! been documented, are dropped.
+//!
+//! Data flow:
+//! - Reads: every symbol of the DocumentIndex
+//! - Uses: chat completions endpoint for undescribed symbols
+//! - Writes: SummaryStore
+
+use crate::config::SummariesConfig;
+use crate::indexing::pipeline::types::PipelineResult;
+use crate::semantic::HttpClient;
+use crate::storage::DocumentIndex;
+use crate::{Symbol, SymbolKind, Visibility};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// File of the generated descriptions, below the index directory
+pub const SUMMARIES_FILE: &str = "summaries.json";
+
+const OPENAI_ENDPOINT: &str = "https://api.openai.com/v1";
+
+/// Lines of the definition included with the signature
+const SOURCE_LINES: usize = 40;
+
+const INSTRUCTIONS: &str = "You describe code for a code search index. Answer with one \
+     sentence of at most 20 words saying what the given symbol does or represents. No \
+     preamble, no markdown, do not repeat the symbol's name.";
+
+/// A description written by a model, not by the code's authors
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GeneratedDescription {
+    pub text: String,
+    /// Model that wrote it
+    pub model: String,
+    /// Unix time it was written
+    pub generated_at: u64,
+}
+
+/// Generated descriptions of one index
+#[derive(Debug, Default)]
+pub struct SummaryStore {
+    path: PathBuf,
+    descriptions: HashMap<String, GeneratedDescription>,
+}
+
+impl SummaryStore {
+    /// Descriptions of the index in `index_base`, none when there are none
+    /// yet or the file cannot be read.
+    pub fn load(index_base: &Path) -> Self {
+        let path = index_base.join(SUMMARIES_FILE);
+        let descriptions = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default();
+        Self { path, descriptions }
+    }
+
+    /// Write the descriptions, replacing the file in one step.
+    pub fn save(&self) -> std::io::Result<()> {
+        let json = serde_json::to_string_pretty(&self.descriptions)?;
+        let partial = self.path.with_extension("json.partial");
+        std::fs::write(&partial, json)?;
+        std::fs::rename(&partial, &self.path)
+    }
+
+    /// Key of `symbol`; it changes whenever the symbol moves to another
+    /// file or module, is renamed, or changes signature
+    pub fn key(symbol: &Symbol) -> String {
+        let kind = format!("{:?}", symbol.kind);
+        let mut hasher = Sha256::new();
+        for part in [
+            &*symbol.file_path,
+            symbol.module_path.as_deref().unwrap_or(""),
+            kind.as_str(),
+            &*symbol.name,
+            symbol.signature.as_deref().unwrap_or(""),
+        ] {
+            hasher.update(part.as_bytes());
+            hasher.update([0]);
+        }
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Generated description of `symbol`, if it has one
+    pub fn get(&self, symbol: &Symbol) -> Option<&GeneratedDescription> {
+        self.descriptions.get(&Self::key(symbol))
+    }
+
+    pub fn len(&self) -> usize {
+        self.descriptions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.descriptions.is_empty()
+    }
+}
+
+/// Statistics from a summarize run.
+#[derive(Debug, Default, Clone)]
+pub struct SummarizeStats {
+    /// Symbols described in this run
+    pub described: usize,
+    /// Symbols whose earlier description still applies
+    pub reused: usize,
+    /// Symbols the endpoint failed to describe
+    pub failed: usize,
+    /// Symbols left for later runs by `max_per_run`
+    pub deferred: usize,
+    /// Descriptions dropped because their symbol changed or is gone
+    pub removed: usize,
+}
+
+/// Summarize stage for undocumented public symbols.
+pub struct SummarizeStage {
+    client: HttpClient,
+    url: String,
+    model: String,
+    api_key: Option<String>,
+    max_per_run: usize,
+    workspace_root: Option<PathBuf>,
+}
+
+#[derive(Deserialize)]
+struct ChatResponse {
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Deserialize)]
+struct ChatChoice {
+    message: ChatMessage,
+}
+
+#[derive(Deserialize)]
+struct ChatMessage {
+    content: String,
+}
+
+impl SummarizeStage {
+    /// Create a summarize stage; relative symbol paths are read below
+    /// `workspace_root`.
+    pub fn new(config: &SummariesConfig, workspace_root: Option<PathBuf>) -> Self {
+        let endpoint = config.endpoint.as_deref().unwrap_or(OPENAI_ENDPOINT);
+        Self {
+            client: HttpClient::new(),
+            url: format!("{}/chat/completions", endpoint.trim_end_matches('/')),
+            model: config.model.clone(),
+            api_key: std::env::var(&config.api_key_env).ok(),
+            max_per_run: config.max_per_run,
+            workspace_root,
+        }
+    }
+
+    /// Whether `symbol` should get a generated description
+    pub fn needs_description(symbol: &Symbol) -> bool {
+        symbol.visibility == Visibility::Public
+            && symbol
+                .doc_comment
+                .as_deref()
+                .is_none_or(|doc| doc.trim().is_empty())
+            && matches!(
+                symbol.kind,
+                SymbolKind::Function
+                    | SymbolKind::Method
+                    | SymbolKind::Struct
+                    | SymbolKind::Enum
+                    | SymbolKind::Trait
+                    | SymbolKind::Interface
+                    | SymbolKind::Class
+                    | SymbolKind::Module
+                    | SymbolKind::TypeAlias
+                    | SymbolKind::Macro
+            )
+    }
+
+    /// Describe the undocumented symbols of `index` that `store` has no
+    /// current description for, and drop the descriptions that no longer
+    /// apply.
+    pub fn run(
+        &self,
+        index: &DocumentIndex,
+        store: &mut SummaryStore,
+    ) -> PipelineResult<SummarizeStats> {
+        let mut stats = SummarizeStats::default();
+        let mut current = HashSet::new();
+        let mut pending = Vec::new();
+        index.for_each_symbol(|symbol| {
+            if !Self::needs_description(&symbol) {
+                return Ok(());
+            }
+            let key = SummaryStore::key(&symbol);
+            if store.descriptions.contains_key(&key) {
+                stats.reused += 1;
+                current.insert(key);
+            } else if pending.len() < self.max_per_run {
+                pending.push((key, symbol));
+            } else {
+                stats.deferred += 1;
+            }
+            Ok(())
+        })?;
+
+        let before = store.descriptions.len();
+        store.descriptions.retain(|key, _| current.contains(key));
+        stats.removed = before - store.descriptions.len();
+
+        let described: Vec<_> = pending
+            .par_iter()
+            .map(|(key, symbol)| (key, self.describe(symbol)))
+            .collect();
+        let generated_at = crate::indexing::get_utc_timestamp();
+        for (key, result) in described {
+            match result {
+                Ok(text) => {
+                    store.descriptions.insert(
+                        key.clone(),
+                        GeneratedDescription {
+                            text,
+                            model: self.model.clone(),
+                            generated_at,
+                        },
+                    );
+                    stats.described += 1;
+                }
+                Err(e) => {
+                    tracing::debug!(target: "pipeline", "[summarize] {e}");
+                    stats.failed += 1;
+                }
+            }
+        }
+        Ok(stats)
+    }
+
+    /// One-line description of `symbol`
+    fn describe(&self, symbol: &Symbol) -> Result<String, String> {
+        let body = json!({
+            "model": self.model,
+            "temperature": 0,
+            "max_tokens": 60,
+            "messages": [
+                { "role": "system", "content": INSTRUCTIONS },
+                { "role": "user", "content": self.prompt(symbol) },
+            ],
+        });
+        let response: ChatResponse = self
+            .client
+            .post(&self.url, self.api_key.as_deref(), &body)?;
+        let text = response
+            .choices
+            .into_iter()
+            .next()
+            .map(|choice| first_line(&choice.message.content))
+            .unwrap_or_default();
+        if text.is_empty() {
+            return Err(format!("no description returned for {}", symbol.name));
+        }
+        Ok(text)
+    }
+
+    fn prompt(&self, symbol: &Symbol) -> String {
+        let mut prompt = format!(
+            "{:?} `{}` in {}",
+            symbol.kind, symbol.name, symbol.file_path
+        );
+        if let Some(module) = symbol.module_path.as_deref() {
+            prompt.push_str(&format!(" (module {module})"));
+        }
+        match self.definition(symbol) {
+            Some(source) => prompt.push_str(&format!(":\n\n{source}")),
+            None => {
+                if let Some(signature) = symbol.signature.as_deref() {
+                    prompt.push_str(&format!(":\n\n{signature}"));
+                }
+            }
+        }
+        prompt
+    }
+
+    /// Opening lines of the symbol's definition
+    fn definition(&self, symbol: &Symbol) -> Option<String> {
+        let path = Path::new(&*symbol.file_path);
+        let path = match &self.workspace_root {
+            Some(root) if path.is_relative() => root.join(path),
+            _ => path.to_path_buf(),
+        };
+        let source = std::fs::read_to_string(path).ok()?;
+        let start = symbol.range.start_line as usize;
+        let end = (symbol.range.end_line as usize).max(start);
+        let lines: Vec<&str> = source
+            .lines()
+            .skip(start)
+            .take((end - start + 1).min(SOURCE_LINES))
+            .collect();
+        (!lines.is_empty()).then(|| lines.join("\n"))
+    }
+}
+
+/// The first non-empty line of a model's answer, without quotes or markup
+fn first_line(answer: &str) -> String {
+    answer
+        .lines()
+        .map(|line| {
+            line.trim()
+                .trim_matches(|c: char| c == '"' || c == '`' || c == '*')
+        })
+        .find(|line| !line.is_empty())
+        .unwrap_or_default()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{FileId, Range, SymbolId};
+    use tempfile::TempDir;
+
+    fn function(name: &str, signature: &str) -> Symbol {
+        let mut symbol = Symbol::new(
+            SymbolId::new(1).unwrap(),
+            name,
+            SymbolKind::Function,
+            FileId::new(1).unwrap(),
+            Range::new(3, 0, 8, 1),
+        );
+        symbol.file_path = "src/lib.rs".into();
+        symbol.signature = Some(signature.into());
+        symbol.visibility = Visibility::Public;
+        symbol
+    }
+
+    #[test]
+    fn test_only_undocumented_public_symbols_are_described() {
+        let symbol = function("parse", "pub fn parse(input: &str) -> Ast");
+        assert!(SummarizeStage::needs_description(&symbol));
+
+        let mut documented = symbol.clone();
+        documented.doc_comment = Some("Parse a source file".into());
+        assert!(!SummarizeStage::needs_description(&documented));
+
+        let mut private = symbol.clone();
+        private.visibility = Visibility::Private;
+        assert!(!SummarizeStage::needs_description(&private));
+
+        let mut field = symbol;
+        field.kind = SymbolKind::Field;
+        assert!(!SummarizeStage::needs_description(&field));
+    }
+
+    #[test]
+    fn test_descriptions_follow_the_signature() {
+        let temp_dir = TempDir::new().unwrap();
+        let symbol = function("parse", "pub fn parse(input: &str) -> Ast");
+        let mut store = SummaryStore::load(temp_dir.path());
+        store.descriptions.insert(
+            SummaryStore::key(&symbol),
+            GeneratedDescription {
+                text: "Parses source text into a syntax tree.".to_string(),
+                model: "gpt-4o-mini".to_string(),
+                generated_at: 7,
+            },
+        );
+        store.save().unwrap();
+
+        let store = SummaryStore::load(temp_dir.path());
+        assert_eq!(store.len(), 1);
+        assert_eq!(
+            store.get(&symbol).unwrap().text,
+            "Parses source text into a syntax tree."
+        );
+        // Another signature is another symbol as far as descriptions go
+        let changed = function("parse", "pub fn parse(input: &[u8]) -> Ast");
+        assert!(store.get(&changed).is_none());
+    }
+
+    #[test]
+    fn test_first_line_strips_markup() {
+        assert_eq!(
+            first_line("\n\"Opens a socket to the daemon.\"\nMore text"),
+            "Opens a socket to the daemon."
+        );
+        assert_eq!(first_line("  \n"), "");
+    }
+}
@@ -9,7 +9,7 @@
 //! - Outputs: WriteStats with counts
 
 use crate::indexing::pipeline::types::{ResolvedBatch, ResolvedRelationship};
-use crate::relationship::Relationship;
+use crate::relationship::{Confidence, Relationship, RelationshipMetadata};
 use crate::storage::DocumentIndex;
 use std::sync::Arc;
 
@@ -85,7 +85,7 @@ impl WriteStage {
             // Convert to Relationship struct (clone metadata to avoid partial move)
             let relationship = Relationship {
                 kind: resolved.kind,
-                weight: 1.0, // Default weight
+                weight: weight(resolved.metadata.as_ref()),
                 metadata: resolved.metadata.clone(),
             };
 
@@ -187,6 +187,14 @@ impl WriteStage {
     }
 }
 
+/// Edge weight: low-confidence edges count for half.
+fn weight(metadata: Option<&RelationshipMetadata>) -> f32 {
+    match metadata.map(RelationshipMetadata::confidence) {
+        Some(Confidence::Low) => 0.5,
+        _ => 1.0,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -6,7 +6,7 @@
 
 use crate::parsing::{Import, LanguageId, PipelineSymbolCache, ResolveResult};
 use crate::relationship::RelationshipMetadata;
-use crate::symbol::ScopeContext;
+use crate::symbol::{Annotation, ScopeContext};
 use crate::types::{CompactString, FileId, Range, SymbolId};
 use crate::{RelationKind, Symbol, SymbolKind, Visibility};
 use std::path::PathBuf;
@@ -27,7 +27,16 @@ pub struct RawSymbol {
     pub signature: Option<Box<str>>,
     pub doc_comment: Option<Box<str>>,
     pub visibility: Visibility,
+    pub exported: bool,
     pub scope_context: Option<ScopeContext>,
+    /// Language of code embedded in the file, when it differs from the file's
+    pub language_id: Option<LanguageId>,
+    /// Failure points inside the symbol's definition
+    pub annotations: Vec<Annotation>,
+    /// Labels from `[[tags]]` rules
+    pub tags: Vec<Box<str>>,
+    /// Monorepo project of the file
+    pub project: Option<Box<str>>,
 }
 
 impl RawSymbol {
@@ -39,7 +48,12 @@ impl RawSymbol {
             signature: None,
             doc_comment: None,
             visibility: Visibility::Public,
+            exported: false,
             scope_context: None,
+            language_id: None,
+            annotations: Vec::new(),
+            tags: Vec::new(),
+            project: None,
         }
     }
 
@@ -58,10 +72,35 @@ impl RawSymbol {
         self
     }
 
+    pub fn with_exported(mut self, exported: bool) -> Self {
+        self.exported = exported;
+        self
+    }
+
     pub fn with_scope_context(mut self, ctx: ScopeContext) -> Self {
         self.scope_context = Some(ctx);
         self
     }
+
+    pub fn with_language_id(mut self, language_id: LanguageId) -> Self {
+        self.language_id = Some(language_id);
+        self
+    }
+
+    pub fn with_annotations(mut self, annotations: Vec<Annotation>) -> Self {
+        self.annotations = annotations;
+        self
+    }
+
+    pub fn with_tags(mut self, tags: Vec<Box<str>>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    pub fn with_project(mut self, project: Option<Box<str>>) -> Self {
+        self.project = project;
+        self
+    }
 }
 
 /// Import extracted from parsing, before FileId assignment.
@@ -73,6 +112,7 @@ pub struct RawImport {
     pub alias: Option<String>,
     pub is_glob: bool,
     pub is_type_only: bool,
+    pub range: Option<Range>,
 }
 
 impl RawImport {
@@ -82,6 +122,7 @@ impl RawImport {
             alias: None,
             is_glob: false,
             is_type_only: false,
+            range: None,
         }
     }
 
@@ -100,6 +141,11 @@ impl RawImport {
         self
     }
 
+    pub fn with_range(mut self, range: Range) -> Self {
+        self.range = Some(range);
+        self
+    }
+
     /// Convert to full Import with FileId
     pub fn into_import(self, file_id: FileId) -> Import {
         Import {
@@ -108,48 +154,13 @@ impl RawImport {
             alias: self.alias,
             is_glob: self.is_glob,
             is_type_only: self.is_type_only,
+            range: self.range,
         }
     }
 }
 
-/// Relationship extracted from parsing, before resolution.
-///
-/// Contains ranges for disambiguation when multiple symbols share the same name:
-/// - `from_range`: Position of the calling symbol (maps to from_id in COLLECT)
-/// - `to_range`: Position of the reference/call site (helps Phase 2 resolution)
-#[derive(Debug, Clone)]
-pub struct RawRelationship {
-    pub from_name: Arc<str>,
-    pub from_range: Range,
-    pub to_name: Arc<str>,
-    pub to_range: Range,
-    pub kind: RelationKind,
-    pub metadata: Option<RelationshipMetadata>,
-}
-
-impl RawRelationship {
-    pub fn new(
-        from_name: impl Into<Arc<str>>,
-        from_range: Range,
-        to_name: impl Into<Arc<str>>,
-        to_range: Range,
-        kind: RelationKind,
-    ) -> Self {
-        Self {
-            from_name: from_name.into(),
-            from_range,
-            to_name: to_name.into(),
-            to_range,
-            kind,
-            metadata: None,
-        }
-    }
-
-    pub fn with_metadata(mut self, metadata: RelationshipMetadata) -> Self {
-        self.metadata = Some(metadata);
-        self
-    }
-}
+/// Relationships are built during parsing, by the language's behavior.
+pub use crate::parsing::RawRelationship;
 
 /// Complete output from parsing a single file.
 ///
@@ -237,6 +248,129 @@ pub struct UnresolvedRelationship {
     pub to_range: Option<Range>,
 }
 
+impl UnresolvedRelationship {
+    /// Approximate memory held by the relationship, in bytes.
+    pub fn approx_bytes(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.from_name.len()
+            + self.to_name.len()
+            + self
+                .metadata
+                .as_ref()
+                .and_then(|m| m.context.as_deref())
+                .map_or(0, str::len)
+    }
+}
+
+/// Approximate memory held by a symbol, in bytes: its struct size plus its
+/// string payloads.
+pub(crate) fn approx_symbol_bytes(symbol: &Symbol) -> usize {
+    use std::mem::size_of;
+
+    let text = |s: &Option<Box<str>>| s.as_deref().map_or(0, str::len);
+    size_of::<Symbol>()
+        + symbol.name.len()
+        + symbol.file_path.len()
+        + text(&symbol.signature)
+        + text(&symbol.doc_comment)
+        + text(&symbol.module_path)
+        + symbol
+            .annotations
+            .iter()
+            .map(|a| size_of::<Annotation>() + a.message.len())
+            .sum::<usize>()
+}
+
+/// Unresolved relationships moved out of memory into an anonymous temporary
+/// file, read back once Phase 1 is done.
+///
+/// The file is deleted by the OS when the spill is dropped.
+#[derive(Debug, Default)]
+pub struct RelationshipSpill {
+    writer: Option<std::io::BufWriter<std::fs::File>>,
+    len: usize,
+}
+
+/// On-disk form of [`UnresolvedRelationship`], one JSON object per line
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SpilledRelationship {
+    from_id: Option<SymbolId>,
+    from_name: Box<str>,
+    to_name: Box<str>,
+    file_id: FileId,
+    kind: RelationKind,
+    metadata: Option<RelationshipMetadata>,
+    to_range: Option<Range>,
+}
+
+impl RelationshipSpill {
+    /// Number of relationships written so far.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Append relationships to the spill file, creating it on first use.
+    pub fn write(
+        &mut self,
+        relationships: impl IntoIterator<Item = UnresolvedRelationship>,
+    ) -> PipelineResult<()> {
+        use std::io::Write;
+
+        let writer = match &mut self.writer {
+            Some(writer) => writer,
+            None => self
+                .writer
+                .insert(std::io::BufWriter::new(tempfile::tempfile()?)),
+        };
+        for rel in relationships {
+            let line = SpilledRelationship {
+                from_id: rel.from_id,
+                from_name: rel.from_name.as_ref().into(),
+                to_name: rel.to_name.as_ref().into(),
+                file_id: rel.file_id,
+                kind: rel.kind,
+                metadata: rel.metadata,
+                to_range: rel.to_range,
+            };
+            serde_json::to_writer(&mut *writer, &line).map_err(std::io::Error::from)?;
+            writer.write_all(b"\n")?;
+            self.len += 1;
+        }
+        Ok(())
+    }
+
+    /// Read every spilled relationship back, in the order written.
+    pub fn read_back(self) -> PipelineResult<Vec<UnresolvedRelationship>> {
+        use std::io::{BufRead, Seek};
+
+        let Some(writer) = self.writer else {
+            return Ok(Vec::new());
+        };
+        let mut file = writer.into_inner().map_err(|e| e.into_error())?;
+        file.rewind()?;
+
+        let mut relationships = Vec::with_capacity(self.len);
+        for line in std::io::BufReader::new(file).lines() {
+            let rel: SpilledRelationship =
+                serde_json::from_str(&line?).map_err(std::io::Error::from)?;
+            relationships.push(UnresolvedRelationship {
+                from_id: rel.from_id,
+                from_name: rel.from_name.into(),
+                to_name: rel.to_name.into(),
+                file_id: rel.file_id,
+                kind: rel.kind,
+                metadata: rel.metadata,
+                to_range: rel.to_range,
+            });
+        }
+        Ok(relationships)
+    }
+}
+
 /// A batch of data ready to be written to Tantivy.
 ///
 /// The INDEX stage receives these batches and writes them efficiently.
@@ -287,6 +421,45 @@ impl IndexBatch {
             .extend(other.unresolved_relationships);
         self.file_registrations.extend(other.file_registrations);
     }
+
+    /// Approximate memory held by the batch, in bytes.
+    ///
+    /// Counts each item's struct size plus its string payloads. Allocator
+    /// overhead and spare capacity are ignored, so this is a lower bound.
+    pub fn approx_bytes(&self) -> usize {
+        use std::mem::size_of;
+
+        let symbols: usize = self
+            .symbols
+            .iter()
+            .map(|(symbol, path)| {
+                size_of::<PathBuf>() + path.as_os_str().len() + approx_symbol_bytes(symbol)
+            })
+            .sum();
+        let imports: usize = self
+            .imports
+            .iter()
+            .map(|import| {
+                size_of::<Import>()
+                    + import.path.len()
+                    + import.alias.as_ref().map_or(0, String::len)
+            })
+            .sum();
+        let relationships: usize = self
+            .unresolved_relationships
+            .iter()
+            .map(UnresolvedRelationship::approx_bytes)
+            .sum();
+        let registrations: usize = self
+            .file_registrations
+            .iter()
+            .map(|reg| {
+                size_of::<FileRegistration>() + reg.path.as_os_str().len() + reg.content_hash.len()
+            })
+            .sum();
+
+        symbols + imports + relationships + registrations
+    }
 }
 
 impl Default for IndexBatch {
@@ -372,6 +545,8 @@ pub use crate::parsing::CallerContext;
 /// - `by_id`: SymbolId → Symbol for direct lookups
 /// - `by_name`: name → `Vec<SymbolId>` for candidate resolution
 /// - `by_file_id`: FileId → `Vec<SymbolId>` for local symbol lookup
+/// - `by_member`: last segment of a dotted name (`Vector.__add__` → `__add__`)
+///   → `Vec<SymbolId>`, for languages that name methods after their class
 ///
 /// Memory: ~500 bytes/symbol, 600K symbols ≈ 300MB
 #[derive(Debug)]
@@ -379,6 +554,7 @@ pub struct SymbolLookupCache {
     by_id: dashmap::DashMap<crate::types::SymbolId, crate::Symbol>,
     by_name: dashmap::DashMap<Box<str>, Vec<crate::types::SymbolId>>,
     by_file_id: dashmap::DashMap<crate::types::FileId, Vec<crate::types::SymbolId>>,
+    by_member: dashmap::DashMap<Box<str>, Vec<crate::types::SymbolId>>,
 }
 
 impl Default for SymbolLookupCache {
@@ -394,6 +570,7 @@ impl SymbolLookupCache {
             by_id: dashmap::DashMap::new(),
             by_name: dashmap::DashMap::new(),
             by_file_id: dashmap::DashMap::new(),
+            by_member: dashmap::DashMap::new(),
         }
     }
 
@@ -403,6 +580,7 @@ impl SymbolLookupCache {
             by_id: dashmap::DashMap::with_capacity(symbols),
             by_name: dashmap::DashMap::with_capacity(symbols / 10), // Fewer unique names
             by_file_id: dashmap::DashMap::with_capacity(symbols / 50), // ~50 symbols/file avg
+            by_member: dashmap::DashMap::new(),
         }
     }
 
@@ -412,6 +590,11 @@ impl SymbolLookupCache {
         let file_id = symbol.file_id;
         let name: Box<str> = symbol.name.as_ref().into();
 
+        // Insert into by_member when the name is qualified by its class
+        if let Some((_, member)) = name.rsplit_once('.') {
+            self.by_member.entry(member.into()).or_default().push(id);
+        }
+
         // Insert into by_id
         self.by_id.insert(id, symbol);
 
@@ -422,6 +605,14 @@ impl SymbolLookupCache {
         self.by_file_id.entry(file_id).or_default().push(id);
     }
 
+    /// Drop every cached symbol, keeping the allocated capacity.
+    pub fn clear(&self) {
+        self.by_id.clear();
+        self.by_name.clear();
+        self.by_file_id.clear();
+        self.by_member.clear();
+    }
+
     /// Get symbol by ID (O(1)).
     pub fn get(&self, id: crate::types::SymbolId) -> Option<crate::Symbol> {
         self.by_id.get(&id).map(|r| r.value().clone())
@@ -443,6 +634,14 @@ impl SymbolLookupCache {
             .unwrap_or_default()
     }
 
+    /// Get symbol IDs whose dotted name ends in `.member` (O(1)).
+    pub fn lookup_members(&self, member: &str) -> Vec<crate::types::SymbolId> {
+        self.by_member
+            .get(member)
+            .map(|r| r.value().clone())
+            .unwrap_or_default()
+    }
+
     /// Get symbol IDs defined in a file (O(1)).
     ///
     /// Used by CONTEXT stage to find local symbols for a file.
@@ -807,6 +1006,13 @@ pub enum PipelineError {
     #[error("Failed to parse file {path}: {reason}")]
     Parse { path: PathBuf, reason: String },
 
+    #[error("{language} parser panicked on {path}: {message}")]
+    ParserPanic {
+        path: PathBuf,
+        language: String,
+        message: String,
+    },
+
     #[error("Unsupported file type: {path}")]
     UnsupportedFileType { path: PathBuf },
 
@@ -822,6 +1028,9 @@ pub enum PipelineError {
     /// [PIPELINE API] Uses storage::StorageError with proper `#[from]` conversion.
     #[error("Storage error: {0}")]
     Storage(#[from] crate::storage::StorageError),
+
+    #[error("Failed to spill relationships to disk: {0}")]
+    Spill(#[from] std::io::Error),
 }
 
 /// Result type for pipeline operations.
@@ -909,9 +1118,62 @@ mod tests {
             alias: None,
             is_glob: false,
             is_type_only: false,
+            range: None,
         });
 
         batch1.merge(batch2);
         assert_eq!(batch1.imports.len(), 1);
     }
+
+    #[test]
+    fn test_index_batch_approx_bytes() {
+        let mut batch = IndexBatch::new();
+        assert_eq!(batch.approx_bytes(), 0);
+
+        let import = |path: &str| Import {
+            file_id: FileId::new(1).unwrap(),
+            path: path.to_string(),
+            alias: None,
+            is_glob: false,
+            is_type_only: false,
+            range: None,
+        };
+        batch.imports.push(import("a"));
+        let small = batch.approx_bytes();
+        batch.imports.push(import(&"x".repeat(1000)));
+
+        // The second import's payload is counted on top of its struct size
+        assert!(batch.approx_bytes() >= 2 * small + 999);
+    }
+    #[test]
+    fn test_relationship_spill_round_trip() {
+        let rel = |to: &str| UnresolvedRelationship {
+            from_id: SymbolId::new(1),
+            from_name: Arc::from("caller"),
+            to_name: Arc::from(to),
+            file_id: FileId::new(1).unwrap(),
+            kind: RelationKind::Calls,
+            metadata: Some(RelationshipMetadata {
+                line: Some(3),
+                column: Some(4),
+                context: Some("receiver:self".into()),
+            }),
+            to_range: Some(Range::new(3, 4, 3, 10)),
+        };
+
+        let spill = RelationshipSpill::default();
+        assert!(spill.read_back().unwrap().is_empty());
+
+        let mut spill = RelationshipSpill::default();
+        spill.write([rel("first"), rel("second")]).unwrap();
+        spill.write([rel("third")]).unwrap();
+        assert_eq!(spill.len(), 3);
+
+        let back = spill.read_back().unwrap();
+        let names: Vec<_> = back.iter().map(|r| r.to_name.as_ref()).collect();
+        assert_eq!(names, ["first", "second", "third"]);
+        assert_eq!(back[0].metadata, rel("first").metadata);
+        assert_eq!(back[0].to_range, Some(Range::new(3, 4, 3, 10)));
+        assert_eq!(back[0].from_id, SymbolId::new(1));
+    }
 }
@@ -1,8 +1,48 @@
 //! Progress reporting for indexing operations
 
+use serde::Serialize;
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+/// A per-file indexing event, for integrations that follow progress as it
+/// happens rather than watching a progress bar
+///
+/// Serializes with an `event` tag, e.g.
+/// `{"event":"file_done","path":"src/lib.rs","symbols":12}`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ProgressEvent {
+    /// Parsing of a file began
+    FileStarted { path: PathBuf },
+    /// A file was parsed
+    FileDone { path: PathBuf, symbols: usize },
+    /// A file could not be read or parsed
+    Error { path: PathBuf, message: String },
+    /// Summary of the whole run
+    Totals {
+        files_indexed: usize,
+        files_failed: usize,
+        symbols_found: usize,
+        elapsed_ms: u64,
+    },
+}
+
+impl ProgressEvent {
+    /// The totals event for `stats`
+    pub fn totals(stats: &IndexStats) -> Self {
+        Self::Totals {
+            files_indexed: stats.files_indexed,
+            files_failed: stats.files_failed,
+            symbols_found: stats.symbols_found,
+            elapsed_ms: stats.elapsed.as_millis() as u64,
+        }
+    }
+}
+
+/// Receives [`ProgressEvent`]s from the indexing worker threads
+pub type ProgressEventCallback = Arc<dyn Fn(&ProgressEvent) + Send + Sync>;
+
 /// Statistics collected during indexing
 #[derive(Debug, Default)]
 pub struct IndexStats {
@@ -118,4 +158,26 @@ mod tests {
         assert_eq!(stats.errors.len(), 100);
         assert_eq!(stats.files_failed, 150);
     }
+
+    #[test]
+    fn test_progress_event_json() {
+        let done = ProgressEvent::FileDone {
+            path: PathBuf::from("src/lib.rs"),
+            symbols: 12,
+        };
+        assert_eq!(
+            serde_json::to_string(&done).unwrap(),
+            r#"{"event":"file_done","path":"src/lib.rs","symbols":12}"#
+        );
+
+        let mut stats = IndexStats::new();
+        stats.files_indexed = 3;
+        stats.files_failed = 1;
+        stats.symbols_found = 40;
+        stats.elapsed = Duration::from_millis(1500);
+        assert_eq!(
+            serde_json::to_string(&ProgressEvent::totals(&stats)).unwrap(),
+            r#"{"event":"totals","files_indexed":3,"files_failed":1,"symbols_found":40,"elapsed_ms":1500}"#
+        );
+    }
 }
@@ -0,0 +1,182 @@
+//! Project boundaries in monorepos.
+//!
+//! A directory holding a package manifest starts a project, and every file
+//! belongs to the project of the nearest such directory above it. Projects
+//! are named by their manifest, or after their directory when it declares
+//! no name:
+//!
+//! - `Cargo.toml` with a `[package]`; a workspace-only manifest starts no
+//!   project, so the crates under it stay apart
+//! - `package.json`
+//! - `pyproject.toml` with a `[project]`, `[tool.poetry]` or
+//!   `[build-system]` table, not one only configuring tools
+//! - `flake.nix`
+//!
+//! The parse stage records the project on every symbol, which is what the
+//! `project:` query filter and the per-project counts of `codanna stats`
+//! read.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Manifests that can start a project, in the order they are tried when
+/// one directory holds several
+pub const PROJECT_MANIFESTS: &[&str] =
+    &["Cargo.toml", "package.json", "pyproject.toml", "flake.nix"];
+
+/// A project and the manifest starting it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Project {
+    pub name: Box<str>,
+    /// File name of the manifest, e.g. `package.json`
+    pub manifest: &'static str,
+}
+
+/// Project lookup for files, reading each directory's manifests once
+#[derive(Debug, Default)]
+pub struct ProjectBoundaries {
+    /// Topmost directory searched for a manifest
+    root: PathBuf,
+    by_dir: HashMap<PathBuf, Option<Project>>,
+}
+
+impl ProjectBoundaries {
+    /// Boundaries up to `root`. Without one, relative paths are searched up
+    /// to the current directory and absolute ones to the filesystem root.
+    pub fn new(root: Option<&Path>) -> Self {
+        Self {
+            root: root.map(Path::to_path_buf).unwrap_or_default(),
+            by_dir: HashMap::new(),
+        }
+    }
+
+    /// Name of the project `file` belongs to
+    pub fn project_of(&mut self, file: &Path) -> Option<Box<str>> {
+        for dir in file.ancestors().skip(1) {
+            if let Some(project) = self.project_at(dir) {
+                return Some(project.name.clone());
+            }
+            if dir == self.root {
+                break;
+            }
+        }
+        None
+    }
+
+    /// The project `dir` starts, if any
+    pub fn project_at(&mut self, dir: &Path) -> Option<&Project> {
+        self.by_dir
+            .entry(dir.to_path_buf())
+            .or_insert_with(|| detect_project(dir))
+            .as_ref()
+    }
+}
+
+/// The project started by a manifest in `dir`
+pub fn detect_project(dir: &Path) -> Option<Project> {
+    PROJECT_MANIFESTS.iter().find_map(|&manifest| {
+        let content = std::fs::read_to_string(dir.join(manifest)).ok()?;
+        let name = match manifest {
+            "Cargo.toml" => {
+                let package = content.parse::<toml::Table>().ok()?.remove("package")?;
+                package.get("name")?.as_str()?.to_string()
+            }
+            "package.json" => serde_json::from_str::<serde_json::Value>(&content)
+                .ok()
+                .and_then(|package| package.get("name")?.as_str().map(str::to_string))
+                .or_else(|| dir_name(dir))?,
+            "pyproject.toml" => {
+                let pyproject = content.parse::<toml::Table>().ok()?;
+                let poetry = pyproject.get("tool").and_then(|tool| tool.get("poetry"));
+                let project = pyproject.get("project").or(poetry);
+                if project.is_none() && !pyproject.contains_key("build-system") {
+                    return None;
+                }
+                project
+                    .and_then(|project| project.get("name")?.as_str().map(str::to_string))
+                    .or_else(|| dir_name(dir))?
+            }
+            _ => dir_name(dir)?,
+        };
+        Some(Project {
+            name: name.into(),
+            manifest,
+        })
+    })
+}
+
+/// Last component of `dir`, resolving `""` and `.` to the directory they
+/// stand for
+fn dir_name(dir: &Path) -> Option<String> {
+    let named = match dir.file_name() {
+        Some(_) => dir.to_path_buf(),
+        None => std::fs::canonicalize(if dir.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            dir
+        })
+        .ok()?,
+    };
+    Some(named.file_name()?.to_string_lossy().into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_project_of() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        let write = |path: &str, content: &str| {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        };
+        write("Cargo.toml", "[workspace]\nmembers = [\"crates/*\"]\n");
+        write(
+            "crates/core/Cargo.toml",
+            "[package]\nname = \"acme-core\"\nversion = \"0.1.0\"\n",
+        );
+        write("crates/core/src/lib.rs", "");
+        write("web/package.json", r#"{"name": "@acme/web"}"#);
+        write("web/src/app/main.ts", "");
+        write("tools/pyproject.toml", "[tool.black]\nline-length = 100\n");
+        write("tools/gen.py", "");
+        write(
+            "services/api/pyproject.toml",
+            "[build-system]\nrequires = [\"hatchling\"]\n",
+        );
+        write("services/api/main.py", "");
+        write("nix/flake.nix", "{ outputs = { self }: { }; }\n");
+        write("nix/shell.nix", "");
+
+        let mut projects = ProjectBoundaries::new(Some(root));
+        let project = |projects: &mut ProjectBoundaries, path: &str| {
+            projects
+                .project_of(&root.join(path))
+                .map(|name| name.to_string())
+        };
+        assert_eq!(
+            project(&mut projects, "crates/core/src/lib.rs").as_deref(),
+            Some("acme-core")
+        );
+        assert_eq!(
+            project(&mut projects, "web/src/app/main.ts").as_deref(),
+            Some("@acme/web")
+        );
+        assert_eq!(
+            project(&mut projects, "services/api/main.py").as_deref(),
+            Some("api")
+        );
+        assert_eq!(
+            project(&mut projects, "nix/shell.nix").as_deref(),
+            Some("nix")
+        );
+        // Tool configuration and the workspace manifest start no project
+        assert_eq!(project(&mut projects, "tools/gen.py"), None);
+        assert_eq!(projects.project_at(root), None);
+    }
+}
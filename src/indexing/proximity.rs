@@ -0,0 +1,282 @@
+//! Proximity ranking for searches issued from a "current file".
+//!
+//! Editors and agents usually search with a file open. Results close to that
+//! file — the same file, module, or directory, or something it imports
+//! directly — are more likely to be what the user means, so their text
+//! relevance score is multiplied by a boost before re-sorting.
+
+use crate::indexing::facade::IndexFacade;
+use crate::storage::SearchResult;
+use std::path::Path;
+
+/// Boost for results in the context file itself
+pub const SAME_FILE_BOOST: f32 = 1.5;
+/// Boost for results sharing the context file's module path
+pub const SAME_MODULE_BOOST: f32 = 1.3;
+/// Boost for results in the context file's directory
+pub const SAME_DIRECTORY_BOOST: f32 = 1.2;
+/// Boost for results the context file imports directly
+pub const IMPORT_NEIGHBOR_BOOST: f32 = 1.15;
+
+/// Candidates fetched per requested result, so near results ranked just past
+/// the limit by text score can still be promoted
+pub const CANDIDATE_FACTOR: usize = 3;
+
+/// Scores search results by closeness to a context file
+#[derive(Debug, Clone, Default)]
+pub struct ProximityScorer {
+    file: String,
+    directory: String,
+    module: Option<String>,
+    /// Import paths with separators normalized to `::`
+    imports: Vec<String>,
+    /// Files named by relative imports (`./utils`), workspace-relative and
+    /// without extension
+    import_files: Vec<String>,
+}
+
+impl ProximityScorer {
+    /// Build a scorer for `context_file`, which may be absolute or
+    /// workspace-relative
+    pub fn new(facade: &IndexFacade, context_file: &str) -> Self {
+        let file = normalize_path(facade, context_file);
+        let directory = Path::new(&file)
+            .parent()
+            .map(|p| p.to_string_lossy().replace('\\', "/"))
+            .unwrap_or_default();
+
+        let mut scorer = Self {
+            file,
+            directory,
+            ..Self::default()
+        };
+
+        if let Some(file_id) = facade.get_file_id_for_path(&scorer.file) {
+            scorer.module = facade
+                .get_symbols_by_file(file_id)
+                .into_iter()
+                .find_map(|s| s.module_path.map(|m| m.to_string()));
+            let imports = facade
+                .document_index()
+                .get_imports_for_file(file_id)
+                .unwrap_or_default();
+            scorer.import_files = imports
+                .iter()
+                .filter_map(|import| resolve_relative(&scorer.directory, &import.path))
+                .collect();
+            scorer.imports = imports
+                .iter()
+                .map(|import| normalize_import(&import.path))
+                .collect();
+        }
+
+        scorer
+    }
+
+    /// Score multiplier for one result; 1.0 when unrelated
+    pub fn boost(&self, result: &SearchResult) -> f32 {
+        if result.file_path == self.file {
+            return SAME_FILE_BOOST;
+        }
+        if self
+            .module
+            .as_deref()
+            .is_some_and(|m| !m.is_empty() && result.module_path == m)
+        {
+            return SAME_MODULE_BOOST;
+        }
+        if self.is_import_neighbor(result) {
+            return IMPORT_NEIGHBOR_BOOST.max(self.directory_boost(result));
+        }
+        self.directory_boost(result)
+    }
+
+    fn directory_boost(&self, result: &SearchResult) -> f32 {
+        let directory = Path::new(&result.file_path)
+            .parent()
+            .map(|p| p.to_string_lossy().replace('\\', "/"))
+            .unwrap_or_default();
+        if directory == self.directory {
+            SAME_DIRECTORY_BOOST
+        } else {
+            1.0
+        }
+    }
+
+    /// Whether an import of the context file resolves to the result, by
+    /// module path or, for relative imports, by file
+    fn is_import_neighbor(&self, result: &SearchResult) -> bool {
+        let module = normalize_import(&result.module_path);
+        let qualified = if module.is_empty() {
+            result.name.clone()
+        } else {
+            format!("{module}::{}", result.name)
+        };
+        let by_module = self.imports.iter().any(|import| {
+            let import = import.trim_end_matches("::*");
+            // `use a::b::Name` imports the symbol; `use a::b` or `use a::b::*`
+            // brings in the module it lives in
+            import == qualified || (!module.is_empty() && import == module)
+        });
+        if by_module {
+            return true;
+        }
+
+        let file = without_extension(&result.file_path);
+        self.import_files.iter().any(|target| {
+            // `./utils.js` may name `utils.ts`; `./components` its index file
+            let target = without_extension(target);
+            file == target || file.strip_suffix("/index") == Some(target)
+        })
+    }
+
+    /// Apply boosts and re-sort by the adjusted score
+    pub fn rerank(&self, results: &mut [SearchResult]) {
+        for result in results.iter_mut() {
+            result.score *= self.boost(result);
+        }
+        results.sort_by(|a, b| b.score.total_cmp(&a.score));
+    }
+}
+
+fn normalize_path(facade: &IndexFacade, path: &str) -> String {
+    let path = Path::new(path);
+    let relative = facade
+        .settings()
+        .workspace_root
+        .as_deref()
+        .and_then(|root| path.strip_prefix(root).ok())
+        .unwrap_or(path);
+    relative
+        .to_string_lossy()
+        .trim_start_matches("./")
+        .replace('\\', "/")
+}
+
+/// Workspace-relative target of a `./` or `../` import made from `directory`
+fn resolve_relative(directory: &str, import: &str) -> Option<String> {
+    if !(import.starts_with("./") || import.starts_with("../")) {
+        return None;
+    }
+    let mut parts: Vec<&str> = directory.split('/').filter(|p| !p.is_empty()).collect();
+    for part in import.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+    Some(parts.join("/"))
+}
+
+fn without_extension(path: &str) -> &str {
+    match path.rsplit_once('.') {
+        Some((stem, extension)) if !extension.contains('/') && !stem.ends_with('/') => stem,
+        _ => path,
+    }
+}
+
+/// Unify `a.b.c`, `a/b/c` and `a::b::c` so imports compare against module paths
+fn normalize_import(path: &str) -> String {
+    path.trim_start_matches("./")
+        .replace(['/', '.'], "::")
+        .trim_start_matches("::")
+        .trim_start_matches("crate::")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SymbolId, SymbolKind};
+
+    fn result(name: &str, file: &str, module: &str, score: f32) -> SearchResult {
+        SearchResult {
+            symbol_id: SymbolId::new(1).unwrap(),
+            name: name.to_string(),
+            kind: SymbolKind::Function,
+            file_path: file.to_string(),
+            line: 1,
+            column: 0,
+            doc_comment: None,
+            signature: None,
+            module_path: module.to_string(),
+            score,
+            highlights: Vec::new(),
+            context: None,
+        }
+    }
+
+    fn scorer() -> ProximityScorer {
+        ProximityScorer {
+            file: "src/io/output.rs".to_string(),
+            directory: "src/io".to_string(),
+            module: Some("crate::io::output".to_string()),
+            imports: vec![
+                normalize_import("crate::storage::DocumentIndex"),
+                normalize_import("crate::config::*"),
+            ],
+            import_files: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_boost_levels() {
+        let scorer = scorer();
+        let same_file = result("write", "src/io/output.rs", "crate::io::output", 1.0);
+        let same_dir = result("parse", "src/io/args.rs", "crate::io::args", 1.0);
+        let imported = result(
+            "DocumentIndex",
+            "src/storage/tantivy.rs",
+            "crate::storage",
+            1.0,
+        );
+        let glob = result("Settings", "src/config.rs", "crate::config", 1.0);
+        let far = result("Parser", "src/parsing/mod.rs", "crate::parsing", 1.0);
+
+        assert_eq!(scorer.boost(&same_file), SAME_FILE_BOOST);
+        assert_eq!(scorer.boost(&same_dir), SAME_DIRECTORY_BOOST);
+        assert_eq!(scorer.boost(&imported), IMPORT_NEIGHBOR_BOOST);
+        assert_eq!(scorer.boost(&glob), IMPORT_NEIGHBOR_BOOST);
+        assert_eq!(scorer.boost(&far), 1.0);
+    }
+
+    #[test]
+    fn test_import_must_resolve_to_the_result() {
+        let scorer = scorer();
+        let namesake = result("DocumentIndex", "src/legacy/index.rs", "crate::legacy", 1.0);
+        assert_eq!(scorer.boost(&namesake), 1.0);
+
+        let scorer = ProximityScorer {
+            file: "web/app/page.tsx".to_string(),
+            directory: "web/app".to_string(),
+            import_files: ["./components/Button", "../lib/api.js"]
+                .into_iter()
+                .filter_map(|import| resolve_relative("web/app", import))
+                .collect(),
+            ..ProximityScorer::default()
+        };
+        let button = result("Button", "web/app/components/Button.tsx", "", 1.0);
+        let api = result("fetchUser", "web/lib/api.ts", "", 1.0);
+        let index = result("Button", "web/app/components/Button/index.ts", "", 1.0);
+        let other = result("Button", "web/admin/Button.tsx", "", 1.0);
+
+        assert_eq!(scorer.boost(&button), IMPORT_NEIGHBOR_BOOST);
+        assert_eq!(scorer.boost(&api), IMPORT_NEIGHBOR_BOOST);
+        assert_eq!(scorer.boost(&index), IMPORT_NEIGHBOR_BOOST);
+        assert_eq!(scorer.boost(&other), 1.0);
+    }
+
+    #[test]
+    fn test_rerank_promotes_nearby_results() {
+        let mut results = vec![
+            result("parse", "src/parsing/mod.rs", "crate::parsing", 1.1),
+            result("parse", "src/io/args.rs", "crate::io::args", 1.0),
+        ];
+        scorer().rerank(&mut results);
+        assert_eq!(results[0].file_path, "src/io/args.rs");
+        assert!((results[0].score - 1.2).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,846 @@
+//! Index shards for very large monorepos.
+//!
+//! With `indexing.shard_by_directory` set, every top-level directory of an
+//! indexed path gets an index of its own under
+//! `<index_path>/shards/<name>/`, and the files directly inside the indexed
+//! path go to the `_root` shard. Each shard is a complete index built by the
+//! regular pipeline, so indexing memory and commit latency are bounded by
+//! the largest directory instead of the whole tree.
+//!
+//! [`ShardSet`] federates queries over them. Name lookups, full-text
+//! searches and counts fan out to every shard in parallel and merge;
+//! lookups by symbol or file id go to the shard that issued the id, and
+//! updates of a file to the shard whose directory holds it. Ids keep the
+//! shard's position in their high bits, which makes them unique across
+//! shards, so callers of [`IndexFacade`](crate::indexing::IndexFacade) see a
+//! single index.
+//!
+//! Relationships are resolved within their shard first. Those left over
+//! are resolved against [`ForeignSymbols`], a lookup of the symbols of all
+//! shards, once every shard is built, and stored with the federated id of
+//! their target, so a call from one top-level directory into another is an
+//! edge like any other. Such an edge is refreshed when the file holding the
+//! call is reindexed.
+
+use crate::config::Settings;
+use crate::indexing::pipeline::SymbolLookupCache;
+use crate::parsing::get_registry;
+use crate::storage::{DocumentIndex, SearchResult, StorageError};
+use crate::{FileId, RelationKind, Relationship, Symbol, SymbolId, SymbolKind, SymbolRef};
+use ignore::WalkBuilder;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use thiserror::Error;
+
+/// Directory under the index path that holds the shards
+pub const SHARDS_DIR: &str = "shards";
+
+/// Shard of the files directly inside an indexed path
+pub const ROOT_SHARD: &str = "_root";
+
+const MANIFEST_FILE: &str = "shards.json";
+
+/// Low bits of a federated id, holding the id within its shard; a shard
+/// holds up to 16M symbols
+const LOCAL_BITS: u32 = 24;
+const LOCAL_MASK: u32 = (1 << LOCAL_BITS) - 1;
+
+/// Shards one index can hold, bounded by the high bits of an id
+pub const MAX_SHARDS: usize = (u32::MAX >> LOCAL_BITS) as usize;
+
+/// Errors raised while planning or recording shards
+#[derive(Error, Debug)]
+pub enum ShardError {
+    #[error(
+        "{count} shards exceed the {MAX_SHARDS} an index can hold\nSuggestion: Index fewer top-level directories or disable indexing.shard_by_directory"
+    )]
+    TooMany { count: usize },
+
+    #[error(
+        "Invalid shard manifest at {path}: {reason}\nSuggestion: Remove the shards directory and run 'codanna index --force'"
+    )]
+    InvalidManifest { path: PathBuf, reason: String },
+
+    #[error(
+        "Id {id} is beyond the {LOCAL_MASK} ids a shard can hold\nSuggestion: Split the directory into smaller top-level directories or disable indexing.shard_by_directory"
+    )]
+    IdOverflow { id: u32 },
+
+    #[error("IO error: {0}\nSuggestion: Check file permissions and disk space")]
+    Io(#[from] io::Error),
+
+    #[error("Storage error: {0}")]
+    Storage(#[from] StorageError),
+}
+
+/// Names of the shards under an index in id order: a shard's position
+/// sets the high bits of its ids, so positions never change once assigned
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShardManifest {
+    pub shards: Vec<String>,
+    /// Canonical paths each shard indexes: its directory, or the loose
+    /// files of [`ROOT_SHARD`]
+    #[serde(default)]
+    pub sources: BTreeMap<String, Vec<PathBuf>>,
+}
+
+impl ShardManifest {
+    /// The manifest under `shards_dir`, empty when there is none yet
+    pub fn load(shards_dir: &Path) -> Result<Self, ShardError> {
+        let path = shards_dir.join(MANIFEST_FILE);
+        match fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json).map_err(|e| ShardError::InvalidManifest {
+                path,
+                reason: e.to_string(),
+            }),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, shards_dir: &Path) -> Result<(), ShardError> {
+        fs::create_dir_all(shards_dir)?;
+        let path = shards_dir.join(MANIFEST_FILE);
+        let json = serde_json::to_string_pretty(self).map_err(|e| ShardError::InvalidManifest {
+            path: path.clone(),
+            reason: e.to_string(),
+        })?;
+        fs::write(path, json)?;
+        Ok(())
+    }
+
+    /// Position of the shard `name`, assigning the next one if it is new
+    pub fn position(&mut self, name: &str) -> Result<usize, ShardError> {
+        if let Some(position) = self.shards.iter().position(|shard| shard == name) {
+            return Ok(position);
+        }
+        if self.shards.len() >= MAX_SHARDS {
+            return Err(ShardError::TooMany {
+                count: self.shards.len() + 1,
+            });
+        }
+        self.shards.push(name.to_string());
+        Ok(self.shards.len() - 1)
+    }
+
+    /// Position of the shard of `plan`, recording the paths it indexes
+    pub fn record(&mut self, plan: &ShardPlan) -> Result<usize, ShardError> {
+        let position = self.position(&plan.name)?;
+        let sources = match &plan.source {
+            ShardSource::Directory(dir) => vec![canonical(dir)],
+            ShardSource::Files(files) => files.iter().map(|file| canonical(file)).collect(),
+        };
+        self.sources.insert(plan.name.clone(), sources);
+        Ok(position)
+    }
+}
+
+/// What one shard indexes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShardSource {
+    /// A top-level directory, indexed recursively
+    Directory(PathBuf),
+    /// The files directly inside an indexed path
+    Files(Vec<PathBuf>),
+}
+
+/// A shard to build
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShardPlan {
+    pub name: String,
+    pub source: ShardSource,
+}
+
+/// Shards for the indexed `paths`: one per top-level directory that is not
+/// hidden or ignored, plus [`ROOT_SHARD`] for loose files in a supported
+/// language. Directories of the same name under different paths get a
+/// numeric suffix.
+pub fn plan_shards(paths: &[PathBuf]) -> Vec<ShardPlan> {
+    let mut plans: Vec<ShardPlan> = Vec::new();
+    let mut root_files = Vec::new();
+    for path in paths {
+        let mut entries: Vec<PathBuf> = WalkBuilder::new(path)
+            .max_depth(Some(1))
+            .build()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.depth() == 1)
+            .map(|entry| entry.into_path())
+            .collect();
+        entries.sort();
+        for entry in entries {
+            if entry.is_dir() {
+                let base = entry
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                let mut name = base.clone();
+                let mut suffix = 2;
+                while name == ROOT_SHARD || plans.iter().any(|plan| plan.name == name) {
+                    name = format!("{base}-{suffix}");
+                    suffix += 1;
+                }
+                plans.push(ShardPlan {
+                    name,
+                    source: ShardSource::Directory(entry),
+                });
+            } else if is_supported(&entry) {
+                root_files.push(entry);
+            }
+        }
+    }
+    if !root_files.is_empty() {
+        plans.push(ShardPlan {
+            name: ROOT_SHARD.to_string(),
+            source: ShardSource::Files(root_files),
+        });
+    }
+    plans
+}
+
+/// Settings for building or opening the shard `name` of the index at
+/// `index_base`
+pub fn shard_settings(settings: &Settings, index_base: &Path, name: &str) -> Settings {
+    Settings {
+        index_path: index_base.join(SHARDS_DIR).join(name),
+        ..settings.clone()
+    }
+}
+
+/// `path` made absolute with symlinks resolved; a path that no longer
+/// exists is resolved through its parent
+fn canonical(path: &Path) -> PathBuf {
+    if let Ok(path) = path.canonicalize() {
+        return path;
+    }
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) if !parent.as_os_str().is_empty() => parent
+            .canonicalize()
+            .map_or_else(|_| path.to_path_buf(), |parent| parent.join(name)),
+        _ => std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf()),
+    }
+}
+
+/// Fail when the index of a shard has handed out more ids than its share
+/// of the federated id space holds; those ids would alias other symbols
+pub fn check_capacity(index: &DocumentIndex) -> Result<(), ShardError> {
+    let next = index.get_next_symbol_id()?.max(index.get_next_file_id()?);
+    if next > LOCAL_MASK + 1 {
+        return Err(ShardError::IdOverflow { id: next - 1 });
+    }
+    Ok(())
+}
+
+/// Whether a parser is registered for the extension of `path`
+fn is_supported(path: &Path) -> bool {
+    let Some(extension) = path.extension().and_then(|ext| ext.to_str()) else {
+        return false;
+    };
+    get_registry()
+        .lock()
+        .is_ok_and(|registry| registry.get_by_extension(extension).is_some())
+}
+
+/// Id of the symbol `local` of the shard at `position`, unique across
+/// shards. Fails for a local id beyond what a shard can hold.
+pub fn federated_symbol_id(position: usize, local: SymbolId) -> Result<SymbolId, ShardError> {
+    Ok(SymbolId(federate_id(position, local.value())?))
+}
+
+/// Shard position and local id of a federated symbol id, or `None` for an
+/// id of the unsharded index
+pub fn split_symbol_id(id: SymbolId) -> Option<(usize, SymbolId)> {
+    let shard = id.value() >> LOCAL_BITS;
+    if shard == 0 {
+        return None;
+    }
+    Some(((shard - 1) as usize, SymbolId(id.value() & LOCAL_MASK)))
+}
+
+/// Shard position and local id of a federated file id, or `None` for an
+/// id of the unsharded index
+pub fn split_file_id(id: FileId) -> Option<(usize, FileId)> {
+    let shard = id.value() >> LOCAL_BITS;
+    if shard == 0 {
+        return None;
+    }
+    Some(((shard - 1) as usize, FileId(id.value() & LOCAL_MASK)))
+}
+
+fn federate_id(position: usize, local: u32) -> Result<u32, ShardError> {
+    if local > LOCAL_MASK {
+        return Err(ShardError::IdOverflow { id: local });
+    }
+    Ok(((position as u32 + 1) << LOCAL_BITS) | local)
+}
+
+/// An opened shard
+pub struct Shard {
+    pub name: String,
+    position: usize,
+    index: Arc<DocumentIndex>,
+    settings: Arc<Settings>,
+    /// Canonical paths the shard indexes, see [`ShardManifest::sources`]
+    sources: Vec<PathBuf>,
+}
+
+impl Shard {
+    /// Position in the manifest, which sets the high bits of its ids
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn document_index(&self) -> &Arc<DocumentIndex> {
+        &self.index
+    }
+
+    /// Settings the shard was built with
+    pub fn settings(&self) -> &Arc<Settings> {
+        &self.settings
+    }
+
+    /// Federated id of the shard's file `local`
+    pub fn file_id(&self, local: FileId) -> Result<FileId, ShardError> {
+        Ok(FileId(federate_id(self.position, local.value())?))
+    }
+
+    fn symbol_id(&self, local: SymbolId) -> Result<SymbolId, ShardError> {
+        federated_symbol_id(self.position, local)
+    }
+
+    /// `symbol` with its ids made federated
+    fn federate(&self, mut symbol: Symbol) -> Result<Symbol, ShardError> {
+        symbol.id = self.symbol_id(symbol.id)?;
+        symbol.file_id = self.file_id(symbol.file_id)?;
+        Ok(symbol)
+    }
+
+    fn federate_all(&self, symbols: Vec<Symbol>) -> Result<Vec<Symbol>, ShardError> {
+        symbols.into_iter().map(|s| self.federate(s)).collect()
+    }
+
+    /// Relationships with their ids made federated. Targets in other
+    /// shards are stored federated already.
+    fn federate_relationships(
+        &self,
+        relationships: Vec<(SymbolId, SymbolId, Relationship)>,
+    ) -> Result<Vec<(SymbolId, SymbolId, Relationship)>, ShardError> {
+        relationships
+            .into_iter()
+            .map(|(from, to, relationship)| {
+                let to = match split_symbol_id(to) {
+                    Some(_) => to,
+                    None => self.symbol_id(to)?,
+                };
+                Ok((self.symbol_id(from)?, to, relationship))
+            })
+            .collect()
+    }
+
+    /// Whether the file at the canonical `path` belongs to this shard
+    fn owns(&self, path: &Path) -> bool {
+        self.sources.iter().any(|source| path.starts_with(source))
+    }
+}
+
+/// Symbols of all shards, for resolving what a shard leaves unresolved.
+/// Ids are federated; the resolving shard's own symbols are skipped, since
+/// its local resolution has already ruled on them.
+pub struct ForeignSymbols {
+    symbols: Arc<SymbolLookupCache>,
+    /// Position of the shard being resolved
+    position: usize,
+}
+
+impl ForeignSymbols {
+    pub fn new(symbols: Arc<SymbolLookupCache>, position: usize) -> Self {
+        Self { symbols, position }
+    }
+
+    /// Symbols of the other shards named `name`
+    pub fn candidates(&self, name: &str) -> Vec<Symbol> {
+        self.symbols
+            .lookup_candidates(name)
+            .into_iter()
+            .filter(|id| split_symbol_id(*id).is_some_and(|(shard, _)| shard != self.position))
+            .filter_map(|id| self.symbols.get(id))
+            .collect()
+    }
+}
+
+/// The shards of an index, queried as one
+#[derive(Default)]
+pub struct ShardSet {
+    shards: Vec<Shard>,
+    /// Lookup of all symbols, built on first use by [`Self::foreign_symbols`]
+    symbols: OnceLock<Arc<SymbolLookupCache>>,
+}
+
+impl ShardSet {
+    /// Open the shards listed in the manifest under `index_base`; missing
+    /// or broken ones are skipped
+    pub fn open(index_base: &Path, settings: &Settings) -> Self {
+        let shards_dir = index_base.join(SHARDS_DIR);
+        let mut manifest = match ShardManifest::load(&shards_dir) {
+            Ok(manifest) => manifest,
+            Err(e) => {
+                tracing::warn!(target: "shards", "{e}");
+                return Self::default();
+            }
+        };
+        let shards = manifest
+            .shards
+            .into_iter()
+            .enumerate()
+            .filter_map(|(position, name)| {
+                let tantivy_path = shards_dir.join(&name).join("tantivy");
+                if !tantivy_path.join("meta.json").exists() {
+                    return None;
+                }
+                let shard_settings = Arc::new(shard_settings(settings, index_base, &name));
+                match DocumentIndex::new(&tantivy_path, &shard_settings) {
+                    Ok(index) => Some(Shard {
+                        sources: manifest.sources.remove(&name).unwrap_or_default(),
+                        name,
+                        position,
+                        index: Arc::new(index),
+                        settings: shard_settings,
+                    }),
+                    Err(e) => {
+                        tracing::warn!(target: "shards", "failed to open shard {name}: {e}");
+                        None
+                    }
+                }
+            })
+            .collect();
+        Self {
+            shards,
+            symbols: OnceLock::new(),
+        }
+    }
+
+    /// Symbols of the shards other than the one at `position`, for
+    /// resolving its leftover relationships. The lookup is built once per
+    /// set and holds every symbol of the index.
+    pub fn foreign_symbols(&self, position: usize) -> ForeignSymbols {
+        let symbols = self.symbols.get_or_init(|| {
+            let cache = SymbolLookupCache::new();
+            self.for_each_symbol(|symbol| cache.insert(symbol));
+            Arc::new(cache)
+        });
+        ForeignSymbols::new(Arc::clone(symbols), position)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.shards.is_empty()
+    }
+
+    pub fn shards(&self) -> &[Shard] {
+        &self.shards
+    }
+
+    fn shard(&self, position: usize) -> Option<&Shard> {
+        self.shards.iter().find(|shard| shard.position == position)
+    }
+
+    /// The shard indexing the file at `path`, which need not exist anymore
+    pub fn owner(&self, path: &Path) -> Option<&Shard> {
+        let path = canonical(path);
+        self.shards.iter().find(|shard| shard.owns(&path))
+    }
+
+    /// Symbols named `name` across all shards
+    pub fn find_symbols_by_name(&self, name: &str, language: Option<&str>) -> Vec<Symbol> {
+        self.fan_out(|shard| shard.federate_all(shard.index.find_symbols_by_name(name, language)?))
+    }
+
+    /// References to the symbols named `name` across all shards
+    pub fn find_symbol_refs_by_name(&self, name: &str, language: Option<&str>) -> Vec<SymbolRef> {
+        self.fan_out(|shard| {
+            let mut refs = shard.index.find_symbol_refs_by_name(name, language)?;
+            for symbol in &mut refs {
+                symbol.id = shard.symbol_id(symbol.id)?;
+                symbol.file_id = shard.file_id(symbol.file_id)?;
+            }
+            Ok(refs)
+        })
+    }
+
+    /// Full-text search across all shards, best `limit` results by score
+    pub fn search(
+        &self,
+        query: &str,
+        limit: usize,
+        kind: Option<SymbolKind>,
+        module: Option<&str>,
+        language: Option<&str>,
+    ) -> Vec<SearchResult> {
+        let mut results = self.fan_out(|shard| {
+            let mut results = shard.index.search(query, limit, kind, module, language)?;
+            for result in &mut results {
+                result.symbol_id = shard.symbol_id(result.symbol_id)?;
+            }
+            Ok(results)
+        });
+        results.sort_by(|a, b| b.score.total_cmp(&a.score));
+        results.truncate(limit);
+        results
+    }
+
+    /// Symbol names starting with `prefix` across all shards, sorted
+    pub fn symbol_names_with_prefix(&self, prefix: &str, limit: usize) -> Vec<String> {
+        let names: BTreeSet<String> = self
+            .fan_out(|shard| Ok(shard.index.symbol_names_with_prefix(prefix, limit)?))
+            .into_iter()
+            .collect();
+        names.into_iter().take(limit).collect()
+    }
+
+    /// The symbol with a federated id
+    pub fn get_symbol(&self, id: SymbolId) -> Option<Symbol> {
+        let (shard, local) = self.locate(id)?;
+        let symbol = shard.index.find_symbol_by_id(local).ok().flatten()?;
+        shard.federate(symbol).ok()
+    }
+
+    /// Up to `limit` symbols, taken from the shards in order
+    pub fn get_all_symbols(&self, limit: usize) -> Vec<Symbol> {
+        let mut symbols =
+            self.fan_out(|shard| shard.federate_all(shard.index.get_all_symbols(limit)?));
+        symbols.truncate(limit);
+        symbols
+    }
+
+    /// Symbols carrying annotations, across all shards
+    pub fn find_annotated_symbols(&self) -> Vec<Symbol> {
+        self.fan_out(|shard| shard.federate_all(shard.index.find_annotated_symbols()?))
+    }
+
+    /// Visit every symbol of every shard, one shard after another
+    pub fn for_each_symbol(&self, mut visit: impl FnMut(Symbol)) {
+        for shard in &self.shards {
+            let visited = shard.index.for_each_symbol(|symbol| {
+                let symbol = shard
+                    .federate(symbol)
+                    .map_err(|e| StorageError::General(e.to_string()))?;
+                visit(symbol);
+                Ok(())
+            });
+            if let Err(e) = visited {
+                tracing::warn!(target: "shards", "query failed on shard {}: {e}", shard.name);
+            }
+        }
+    }
+
+    /// Symbols of the file with a federated id
+    pub fn find_symbols_by_file(&self, id: FileId) -> Vec<Symbol> {
+        let Some((shard, local)) = self.locate_file(id) else {
+            return Vec::new();
+        };
+        shard
+            .index
+            .find_symbols_by_file(local)
+            .map_err(ShardError::from)
+            .and_then(|symbols| shard.federate_all(symbols))
+            .unwrap_or_default()
+    }
+
+    /// Path of the file with a federated id
+    pub fn get_file_path(&self, id: FileId) -> Option<String> {
+        let (shard, local) = self.locate_file(id)?;
+        shard.index.get_file_path(local).ok().flatten()
+    }
+
+    /// Federated id of the file indexed at `path`, as stored by the pipeline
+    pub fn get_file_id(&self, path: &str) -> Option<FileId> {
+        self.fan_out(|shard| match shard.index.get_file_info(path)? {
+            Some((id, _, _)) => Ok(vec![shard.file_id(id)?]),
+            None => Ok(Vec::new()),
+        })
+        .into_iter()
+        .next()
+    }
+
+    /// Paths of the files indexed by all shards
+    pub fn get_all_indexed_paths(&self) -> Vec<PathBuf> {
+        self.fan_out(|shard| Ok(shard.index.get_all_indexed_paths()?))
+    }
+
+    /// Outgoing relationships of `kind` from a federated id
+    pub fn relationships_from(
+        &self,
+        id: SymbolId,
+        kind: RelationKind,
+    ) -> Vec<(SymbolId, SymbolId, Relationship)> {
+        let Some((shard, local)) = self.locate(id) else {
+            return Vec::new();
+        };
+        shard
+            .index
+            .get_relationships_from(local, kind)
+            .map_err(ShardError::from)
+            .and_then(|relationships| shard.federate_relationships(relationships))
+            .unwrap_or_default()
+    }
+
+    /// Incoming relationships of `kind` to a federated id: those from its
+    /// own shard, stored by local id, and those from the other shards,
+    /// stored by federated id
+    pub fn relationships_to(
+        &self,
+        id: SymbolId,
+        kind: RelationKind,
+    ) -> Vec<(SymbolId, SymbolId, Relationship)> {
+        let Some((owner, local)) = self.locate(id) else {
+            return Vec::new();
+        };
+        self.fan_out(|shard| {
+            let target = if shard.position == owner.position {
+                local
+            } else {
+                id
+            };
+            shard.federate_relationships(shard.index.get_relationships_to(target, kind)?)
+        })
+    }
+
+    /// Relationships of `kind` across all shards
+    pub fn relationships_by_kind(
+        &self,
+        kind: RelationKind,
+    ) -> Vec<(SymbolId, SymbolId, Relationship)> {
+        self.fan_out(|shard| {
+            shard.federate_relationships(shard.index.get_all_relationships_by_kind(kind)?)
+        })
+    }
+
+    pub fn symbol_count(&self) -> usize {
+        self.fan_out(|shard| Ok(vec![shard.index.count_symbols()?]))
+            .into_iter()
+            .sum()
+    }
+
+    pub fn file_count(&self) -> usize {
+        self.fan_out(|shard| Ok(vec![shard.index.count_files()?]))
+            .into_iter()
+            .sum()
+    }
+
+    pub fn relationship_count(&self) -> usize {
+        self.fan_out(|shard| Ok(vec![shard.index.count_relationships()?]))
+            .into_iter()
+            .sum()
+    }
+
+    pub fn document_count(&self) -> u64 {
+        self.fan_out(|shard| Ok(vec![shard.index.document_count()?]))
+            .into_iter()
+            .sum()
+    }
+
+    fn locate(&self, id: SymbolId) -> Option<(&Shard, SymbolId)> {
+        let (position, local) = split_symbol_id(id)?;
+        Some((self.shard(position)?, local))
+    }
+
+    fn locate_file(&self, id: FileId) -> Option<(&Shard, FileId)> {
+        let (position, local) = split_file_id(id)?;
+        Some((self.shard(position)?, local))
+    }
+
+    /// Run `query` on every shard in parallel and concatenate the results
+    /// in shard order; a failing shard contributes nothing
+    fn fan_out<T: Send>(
+        &self,
+        query: impl Fn(&Shard) -> Result<Vec<T>, ShardError> + Sync,
+    ) -> Vec<T> {
+        self.shards
+            .par_iter()
+            .map(|shard| {
+                query(shard).unwrap_or_else(|e| {
+                    tracing::warn!(target: "shards", "query failed on shard {}: {e}", shard.name);
+                    Vec::new()
+                })
+            })
+            .flatten()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_federated_ids() {
+        let local = SymbolId::new(42).unwrap();
+        let id = federated_symbol_id(3, local).unwrap();
+        assert_eq!(split_symbol_id(id), Some((3, local)));
+        assert_eq!(split_symbol_id(local), None);
+        assert_ne!(
+            federated_symbol_id(0, local).unwrap(),
+            federated_symbol_id(1, local).unwrap()
+        );
+
+        // An id past the shard's share would alias a symbol of the next shard
+        let beyond = SymbolId::new(LOCAL_MASK + 1).unwrap();
+        assert!(matches!(
+            federated_symbol_id(0, beyond),
+            Err(ShardError::IdOverflow { .. })
+        ));
+    }
+
+    #[test]
+    fn test_plan_and_manifest() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        fs::create_dir_all(root.join("services/api")).unwrap();
+        fs::create_dir_all(root.join("web")).unwrap();
+        fs::create_dir_all(root.join(".git")).unwrap();
+        fs::write(root.join("build.rs"), "fn main() {}\n").unwrap();
+        fs::write(root.join("README.md"), "# Demo\n").unwrap();
+
+        let plans = plan_shards(&[root.to_path_buf()]);
+        let names: Vec<&str> = plans.iter().map(|plan| plan.name.as_str()).collect();
+        assert_eq!(names, ["services", "web", ROOT_SHARD]);
+        assert_eq!(
+            plans[2].source,
+            ShardSource::Files(vec![root.join("build.rs")])
+        );
+
+        let shards_dir = root.join(SHARDS_DIR);
+        let mut manifest = ShardManifest::load(&shards_dir).unwrap();
+        assert_eq!(manifest.position("web").unwrap(), 0);
+        assert_eq!(manifest.position("services").unwrap(), 1);
+        assert_eq!(manifest.position("web").unwrap(), 0);
+        manifest.save(&shards_dir).unwrap();
+        assert_eq!(ShardManifest::load(&shards_dir).unwrap(), manifest);
+    }
+
+    #[test]
+    fn test_file_ids_and_paths_route_to_their_shard() {
+        use crate::indexing::facade::IndexFacade;
+
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        fs::create_dir_all(root.join("web")).unwrap();
+        fs::write(root.join("web/app.rs"), "pub fn render() {}\n").unwrap();
+        let index_base = root.join("index");
+        let settings = Settings {
+            index_path: index_base.clone(),
+            ..Settings::default()
+        };
+
+        let mut manifest = ShardManifest::default();
+        let position = manifest
+            .record(&ShardPlan {
+                name: "web".to_string(),
+                source: ShardSource::Directory(root.join("web")),
+            })
+            .unwrap();
+        manifest.save(&index_base.join(SHARDS_DIR)).unwrap();
+        let mut shard =
+            IndexFacade::new(Arc::new(shard_settings(&settings, &index_base, "web"))).unwrap();
+        shard.index_file(root.join("web/app.rs")).unwrap();
+        drop(shard);
+
+        let shards = ShardSet::open(&index_base, &settings);
+        let symbol = shards.find_symbols_by_name("render", None).pop().unwrap();
+        assert_eq!(
+            split_file_id(symbol.file_id).map(|(shard, _)| shard),
+            Some(position)
+        );
+        assert!(
+            shards
+                .get_file_path(symbol.file_id)
+                .is_some_and(|path| path.ends_with("app.rs"))
+        );
+        assert!(
+            shards
+                .find_symbols_by_file(symbol.file_id)
+                .iter()
+                .any(|found| found.id == symbol.id)
+        );
+        assert_eq!(shards.file_count(), 1);
+        assert_eq!(shards.get_all_indexed_paths().len(), 1);
+
+        // Files that don't exist yet still belong to their directory's shard
+        let owner = shards.owner(&root.join("web/new.rs"));
+        assert_eq!(owner.map(|shard| shard.name.as_str()), Some("web"));
+        assert!(shards.owner(&root.join("README.md")).is_none());
+    }
+
+    #[test]
+    fn test_calls_resolve_across_shards() {
+        use crate::indexing::facade::IndexFacade;
+        use crate::indexing::pipeline::UnresolvedSink;
+
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        fs::create_dir_all(root.join("api")).unwrap();
+        fs::create_dir_all(root.join("web")).unwrap();
+        fs::write(root.join("api/lib.rs"), "pub fn render_api() {}\n").unwrap();
+        fs::write(
+            root.join("web/app.rs"),
+            "pub fn main_page() {\n    render_api();\n}\n",
+        )
+        .unwrap();
+        let index_base = root.join("index");
+        let settings = Settings {
+            index_path: index_base.clone(),
+            ..Settings::default()
+        };
+
+        let mut manifest = ShardManifest::default();
+        let mut leftovers = Vec::new();
+        for (name, file) in [("api", "api/lib.rs"), ("web", "web/app.rs")] {
+            let position = manifest
+                .record(&ShardPlan {
+                    name: name.to_string(),
+                    source: ShardSource::Directory(root.join(name)),
+                })
+                .unwrap();
+            let mut shard =
+                IndexFacade::new(Arc::new(shard_settings(&settings, &index_base, name))).unwrap();
+            let sink: UnresolvedSink = Arc::default();
+            shard.set_unresolved_sink(Some(Arc::clone(&sink)));
+            shard.index_file(root.join(file)).unwrap();
+            let unresolved = std::mem::take(&mut *sink.lock().unwrap());
+            leftovers.push((name, position, unresolved));
+        }
+        manifest.save(&index_base.join(SHARDS_DIR)).unwrap();
+
+        // The call into the other directory is left over by its own shard
+        let (_, web, unresolved) = leftovers.pop().unwrap();
+        assert!(
+            unresolved
+                .iter()
+                .any(|rel| rel.to_name.as_ref() == "render_api")
+        );
+
+        let shards = ShardSet::open(&index_base, &settings);
+        let mut shard =
+            IndexFacade::new(Arc::new(shard_settings(&settings, &index_base, "web"))).unwrap();
+        shard.set_foreign_symbols(Some(Arc::new(shards.foreign_symbols(web))));
+        shard.resolve_relationships(unresolved).unwrap();
+        drop(shard);
+
+        let shards = ShardSet::open(&index_base, &settings);
+        let target = shards
+            .find_symbols_by_name("render_api", None)
+            .pop()
+            .unwrap();
+        let callers = shards.relationships_to(target.id, RelationKind::Calls);
+        assert_eq!(callers.len(), 1);
+        let caller = shards.get_symbol(callers[0].0).unwrap();
+        assert_eq!(caller.name.as_ref(), "main_page");
+        let callees = shards.relationships_from(caller.id, RelationKind::Calls);
+        assert_eq!(callees.len(), 1);
+        assert_eq!(callees[0].1, target.id);
+    }
+}
@@ -0,0 +1,210 @@
+//! Trigram index over symbol names
+//!
+//! Tantivy's term dictionary answers exact and prefix lookups, but cannot
+//! find `path` inside `normalize_file_path`. This index maps every run of
+//! three characters in a case-folded name to the names containing it. The
+//! query's own trigrams narrow the candidates to a handful of names, which
+//! are then checked for the whole query and ranked.
+
+use std::collections::HashMap;
+
+type Trigram = [char; 3];
+
+/// Symbol names indexed by their case-folded trigrams
+#[derive(Debug, Default)]
+pub struct TrigramIndex {
+    names: Vec<String>,
+    folded: Vec<String>,
+    /// Trigram to the indexes of the names containing it, ascending
+    postings: HashMap<Trigram, Vec<u32>>,
+}
+
+impl TrigramIndex {
+    pub fn new(names: impl IntoIterator<Item = String>) -> Self {
+        let mut index = Self::default();
+        for name in names {
+            let position = index.names.len() as u32;
+            let folded = fold(&name);
+            for trigram in trigrams(&folded) {
+                let names = index.postings.entry(trigram).or_default();
+                if names.last() != Some(&position) {
+                    names.push(position);
+                }
+            }
+            index.names.push(name);
+            index.folded.push(folded);
+        }
+        index
+    }
+
+    /// Number of indexed names
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Names containing `query`, ignoring case, best match first.
+    ///
+    /// Queries shorter than a trigram are checked against every name.
+    pub fn search(&self, query: &str) -> Vec<(&str, f32)> {
+        let query_folded = fold(query);
+        let wanted: Vec<Trigram> = trigrams(&query_folded).collect();
+        let candidates: Vec<u32> = if wanted.is_empty() {
+            (0..self.names.len() as u32).collect()
+        } else {
+            let mut lists = Vec::with_capacity(wanted.len());
+            for trigram in &wanted {
+                match self.postings.get(trigram) {
+                    Some(names) => lists.push(names),
+                    None => return Vec::new(),
+                }
+            }
+            lists.sort_by_key(|names| names.len());
+            let (shortest, rest) = lists.split_first().expect("query has a trigram");
+            shortest
+                .iter()
+                .copied()
+                .filter(|position| {
+                    rest.iter()
+                        .all(|names| names.binary_search(position).is_ok())
+                })
+                .collect()
+        };
+
+        let mut matches: Vec<(&str, f32)> = candidates
+            .into_iter()
+            .filter_map(|position| {
+                let position = position as usize;
+                let name = self.names[position].as_str();
+                substring_score(name, &self.folded[position], query, &query_folded)
+                    .map(|score| (name, score))
+            })
+            .collect();
+        rank(&mut matches);
+        matches
+    }
+}
+
+/// Names from `names` containing `query`, ignoring case, best match first;
+/// the scan [`TrigramIndex::search`] avoids
+pub fn substring_matches<'a>(
+    names: impl IntoIterator<Item = &'a str>,
+    query: &str,
+) -> Vec<(&'a str, f32)> {
+    let query_folded = fold(query);
+    let mut matches: Vec<(&str, f32)> = names
+        .into_iter()
+        .filter_map(|name| {
+            substring_score(name, &fold(name), query, &query_folded).map(|score| (name, score))
+        })
+        .collect();
+    rank(&mut matches);
+    matches
+}
+
+fn rank(matches: &mut [(&str, f32)]) {
+    matches.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+}
+
+/// How well `name` matches `query` as a case-insensitive substring, higher
+/// is better; `None` when it does not contain the query.
+///
+/// The whole name beats a prefix, which beats a match starting a word
+/// (after `_`, `:`, `.`, `-`, or at a lowercase-to-uppercase step), which
+/// beats one inside a word. Matching case and covering more of the name
+/// add a little.
+fn substring_score(name: &str, folded: &str, query: &str, query_folded: &str) -> Option<f32> {
+    if query_folded.is_empty() {
+        return None;
+    }
+    let chars: Vec<char> = name.chars().collect();
+    let query_len = query_folded.chars().count();
+
+    let mut best: Option<f32> = None;
+    for (byte, _) in folded.match_indices(query_folded) {
+        // Folding keeps one char per char, so positions carry over to `name`
+        let start = folded[..byte].chars().count();
+        let mut score = 1.0;
+        if query_len == chars.len() {
+            score += 4.0;
+        } else if start == 0 {
+            score += 2.0;
+        } else if is_word_start(&chars, start) {
+            score += 1.0;
+        }
+        let exact_case = chars[start..start + query_len]
+            .iter()
+            .copied()
+            .eq(query.chars());
+        if exact_case {
+            score += 0.5;
+        }
+        score += query_len as f32 / chars.len() as f32;
+        best = Some(best.map_or(score, |best: f32| best.max(score)));
+    }
+    best
+}
+
+fn is_word_start(chars: &[char], at: usize) -> bool {
+    let previous = chars[at - 1];
+    matches!(previous, '_' | ':' | '.' | '-' | '$')
+        || (chars[at].is_uppercase() && previous.is_lowercase())
+}
+
+/// `text` with each char replaced by its first lowercase char
+fn fold(text: &str) -> String {
+    text.chars()
+        .map(|c| c.to_lowercase().next().unwrap_or(c))
+        .collect()
+}
+
+fn trigrams(folded: &str) -> impl Iterator<Item = Trigram> + '_ {
+    let chars: Vec<char> = folded.chars().collect();
+    (0..chars.len().saturating_sub(2)).map(move |i| [chars[i], chars[i + 1], chars[i + 2]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_substring_search_ranks_whole_names_prefixes_and_word_starts() {
+        let names = [
+            "normalize_file_path",
+            "path",
+            "PathBuf",
+            "xpathy",
+            "file_path_cache",
+            "unrelated",
+        ];
+        let index = TrigramIndex::new(names.iter().map(|name| name.to_string()));
+
+        let found: Vec<&str> = index.search("PATH").iter().map(|(name, _)| *name).collect();
+        assert_eq!(
+            found,
+            vec![
+                "path",
+                "PathBuf",
+                "file_path_cache",
+                "normalize_file_path",
+                "xpathy"
+            ]
+        );
+        assert!(index.search("qqq").is_empty());
+
+        // Short queries skip the trigrams but rank the same way
+        let short: Vec<&str> = index.search("pa").iter().map(|(name, _)| *name).collect();
+        assert_eq!(short.len(), 5);
+        assert_eq!(short.first(), Some(&"path"));
+        assert_eq!(
+            substring_matches(names, "PATH")
+                .iter()
+                .map(|(name, _)| *name)
+                .collect::<Vec<_>>(),
+            found
+        );
+    }
+}
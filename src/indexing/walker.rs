@@ -7,7 +7,8 @@
 //! - Hidden file handling
 
 use crate::Settings;
-use crate::parsing::get_registry;
+use crate::parsing::detection::read_head;
+use crate::parsing::{LanguageRegistry, get_registry};
 use ignore::WalkBuilder;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
@@ -51,6 +52,7 @@ impl FileWalker {
 
         // Get enabled extensions from the registry
         let enabled_extensions = self.get_enabled_extensions();
+        let settings = Arc::clone(&self.settings);
 
         // Build and filter the walker
         builder
@@ -78,10 +80,37 @@ impl FileWalker {
                     }
                 }
 
-                None
+                // Extensionless scripts and overridden file names
+                Self::detect_enabled(path, &settings).then(|| path.to_path_buf())
             })
     }
 
+    /// Whether a file the extension check passed over is in an enabled
+    /// language, by file name override or shebang
+    fn detect_enabled(path: &Path, settings: &Settings) -> bool {
+        let overrides = &settings.indexing.language_overrides;
+        let overridden = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| overrides.contains_key(name));
+        if path.extension().is_some() && !overridden {
+            return false;
+        }
+        let head = if LanguageRegistry::needs_content(path, overrides) {
+            match read_head(path) {
+                Some(head) => Some(head),
+                None => return false,
+            }
+        } else {
+            None
+        };
+        get_registry().lock().is_ok_and(|registry| {
+            registry
+                .detect(path, head.as_deref(), overrides)
+                .is_some_and(|def| def.is_enabled(settings))
+        })
+    }
+
     /// Get list of enabled file extensions from the registry
     fn get_enabled_extensions(&self) -> Vec<String> {
         let registry = get_registry();
@@ -0,0 +1,151 @@
+//! Precomputed query structures
+//!
+//! A freshly started server answers its first caller and module queries by
+//! running Tantivy queries and loading documents one at a time, which on a
+//! large index takes seconds. Warming up builds the structures those queries
+//! need in one pass over the index: the reverse call graph, the module
+//! tree, and the trigram index over symbol names.
+//!
+//! A [`WarmCache`] belongs to the searcher generation it was built from.
+//! Once the index changes it is ignored, and queries fall back to Tantivy
+//! until the next warm-up.
+
+use super::trigram::TrigramIndex;
+use crate::storage::{DocumentIndex, StorageResult};
+use crate::{RelationKind, Relationship, SymbolId};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::time::{Duration, Instant};
+
+/// Query structures for one generation of the index
+#[derive(Debug)]
+pub struct WarmCache {
+    generation: u64,
+    /// Callee to its callers
+    callers: HashMap<SymbolId, Vec<(SymbolId, SymbolId, Relationship)>>,
+    /// Module path to the symbols declared directly in it
+    modules: BTreeMap<String, Vec<SymbolId>>,
+    names: TrigramIndex,
+}
+
+/// What a warm-up built
+#[derive(Debug, Clone, Default)]
+pub struct WarmStats {
+    pub call_edges: usize,
+    pub modules: usize,
+    pub names: usize,
+    pub elapsed: Duration,
+}
+
+impl WarmCache {
+    /// Build the cache from the index's current generation.
+    pub fn build(index: &DocumentIndex) -> StorageResult<(Self, WarmStats)> {
+        let start = Instant::now();
+        let generation = index.searcher_generation();
+
+        let mut callers: HashMap<SymbolId, Vec<_>> = HashMap::new();
+        let mut call_edges = 0;
+        for (from, to, relationship) in index.query_relationships()? {
+            if relationship.kind == RelationKind::Calls {
+                callers
+                    .entry(to)
+                    .or_default()
+                    .push((from, to, relationship));
+                call_edges += 1;
+            }
+        }
+
+        let mut modules: BTreeMap<String, Vec<SymbolId>> = BTreeMap::new();
+        let mut names = BTreeSet::new();
+        index.for_each_symbol(|symbol| {
+            names.insert(symbol.name.to_string());
+            let module = symbol.module_path.as_deref().unwrap_or_default();
+            modules
+                .entry(module.to_string())
+                .or_default()
+                .push(symbol.id);
+            Ok(())
+        })?;
+
+        let names = TrigramIndex::new(names);
+        let stats = WarmStats {
+            call_edges,
+            modules: modules.len(),
+            names: names.len(),
+            elapsed: start.elapsed(),
+        };
+        let cache = Self {
+            generation,
+            callers,
+            modules,
+            names,
+        };
+        Ok((cache, stats))
+    }
+
+    /// Whether the cache was built from `index` as it is now.
+    pub fn is_current(&self, index: &DocumentIndex) -> bool {
+        self.generation == index.searcher_generation()
+    }
+
+    /// Incoming `Calls` edges of `symbol_id`, as `(caller, callee, edge)`.
+    pub fn callers(&self, symbol_id: SymbolId) -> &[(SymbolId, SymbolId, Relationship)] {
+        self.callers
+            .get(&symbol_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Trigram index over the distinct symbol names.
+    pub fn names(&self) -> &TrigramIndex {
+        &self.names
+    }
+
+    /// Symbols declared in `module` or in a module nested below it.
+    pub fn module_symbols(&self, module: &str) -> Vec<SymbolId> {
+        self.modules
+            .range(module.to_string()..)
+            .take_while(|(path, _)| path.starts_with(module))
+            .filter(|(path, _)| in_module_tree(path, module))
+            .flat_map(|(_, ids)| ids.iter().copied())
+            .collect()
+    }
+}
+
+/// Whether the module at `path` is `module` or nested below it. Nesting is
+/// recognized across the separators languages use: `::`, `.`, and `/`. An
+/// empty `module` is the root of every tree.
+pub fn in_module_tree(path: &str, module: &str) -> bool {
+    module.is_empty()
+        || path
+            .strip_prefix(module)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(['.', ':', '/']))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_module_tree_membership() {
+        assert!(in_module_tree("crate::storage", "crate::storage"));
+        assert!(in_module_tree("crate::storage::tantivy", "crate::storage"));
+        assert!(in_module_tree("pkgs/by-name", "pkgs"));
+        assert!(in_module_tree("anything", ""));
+        assert!(!in_module_tree("crate::storage_extra", "crate::storage"));
+        assert!(!in_module_tree("crate", "crate::storage"));
+
+        let cache = WarmCache {
+            generation: 0,
+            callers: HashMap::new(),
+            modules: BTreeMap::from([
+                ("a".to_string(), vec![SymbolId(1)]),
+                ("a::b".to_string(), vec![SymbolId(2)]),
+                ("a_c".to_string(), vec![SymbolId(3)]),
+                ("b".to_string(), vec![SymbolId(4)]),
+            ]),
+            names: TrigramIndex::default(),
+        };
+        assert_eq!(cache.module_symbols("a"), vec![SymbolId(1), SymbolId(2)]);
+        assert_eq!(cache.module_symbols("").len(), 4);
+    }
+}
@@ -0,0 +1,424 @@
+//! Output budgets for agent-facing responses.
+//!
+//! An agent pays for every token of a response, and a search or call graph
+//! over a large codebase easily returns more than it can use. A [`Budget`]
+//! caps one response:
+//!
+//! - JSON documents first shorten long strings (source bodies, doc
+//!   comments), then drop results from the end, so the highest-ranked ones
+//!   survive, and finally shorten strings further. Envelopes report the cut
+//!   in `meta.truncated`, `meta.omitted` and `meta.trimmed`.
+//! - Text keeps its leading blank-line separated blocks and ends with a
+//!   note saying how much was left out.
+//!
+//! Tokens are estimated as four bytes each, the rule of thumb for code and
+//! English with current tokenizers.
+
+use serde::Deserialize;
+use serde_json::Value;
+use std::fmt;
+use std::sync::OnceLock;
+
+/// Bytes counted per token when a budget is given in tokens
+pub const BYTES_PER_TOKEN: usize = 4;
+
+/// Strings are first shortened to this many chars before results are dropped
+const BODY_CHARS: usize = 400;
+
+/// Strings are never shortened below this many chars
+const MIN_STRING_CHARS: usize = 32;
+
+/// Room kept for the `truncated`, `omitted` and `trimmed` meta entries
+const META_BYTES: usize = 80;
+
+const ELLIPSIS: &str = "…";
+
+/// Budget selected with the global `--max-tokens`/`--max-bytes` options
+static DEFAULT_BUDGET: OnceLock<Budget> = OnceLock::new();
+
+/// Upper bound on the size of one response
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Budget {
+    Tokens(usize),
+    Bytes(usize),
+}
+
+/// What fitting a response into its budget left out
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Omitted {
+    /// Results (or text blocks) dropped
+    pub items: usize,
+    /// Strings (or lines) shortened or cut
+    pub trimmed: usize,
+}
+
+/// The `max_tokens`/`max_bytes` parameters of an HTTP request
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+pub struct BudgetParams {
+    pub max_tokens: Option<usize>,
+    pub max_bytes: Option<usize>,
+}
+
+impl BudgetParams {
+    pub fn budget(&self) -> Option<Budget> {
+        Budget::from_limits(self.max_tokens, self.max_bytes)
+    }
+}
+
+impl Omitted {
+    pub fn is_empty(&self) -> bool {
+        self.items == 0 && self.trimmed == 0
+    }
+}
+
+impl Budget {
+    /// The tighter of a token and a byte limit, if either is given
+    pub fn from_limits(max_tokens: Option<usize>, max_bytes: Option<usize>) -> Option<Self> {
+        match (max_tokens.map(Self::Tokens), max_bytes.map(Self::Bytes)) {
+            (Some(tokens), Some(bytes)) if bytes.max_bytes() < tokens.max_bytes() => Some(bytes),
+            (Some(tokens), _) => Some(tokens),
+            (None, bytes) => bytes,
+        }
+    }
+
+    /// Select the budget applied to structured command output. Only the
+    /// first call takes effect.
+    pub fn set_default(budget: Self) {
+        let _ = DEFAULT_BUDGET.set(budget);
+    }
+
+    /// Budget selected with [`Budget::set_default`], if any
+    pub fn default_budget() -> Option<Self> {
+        DEFAULT_BUDGET.get().copied()
+    }
+
+    /// An even share of this budget for one of `parts` responses
+    pub fn share(&self, parts: usize) -> Self {
+        let parts = parts.max(1);
+        match *self {
+            Self::Tokens(tokens) => Self::Tokens(tokens / parts),
+            Self::Bytes(bytes) => Self::Bytes(bytes / parts),
+        }
+    }
+
+    pub fn max_bytes(&self) -> usize {
+        match *self {
+            Self::Tokens(tokens) => tokens.saturating_mul(BYTES_PER_TOKEN),
+            Self::Bytes(bytes) => bytes,
+        }
+    }
+
+    /// Shrink `value` until its pretty-printed JSON fits.
+    ///
+    /// Results are the items of a top-level array, of `data`, of
+    /// `data.items`, or of the only field of `data` (its `edges` when it is
+    /// a GraphQL connection). When `value` is an envelope its `meta` records what
+    /// was left out.
+    pub fn fit_value(&self, value: &mut Value) -> Omitted {
+        let mut omitted = Omitted::default();
+        if json_len(value) <= self.max_bytes() {
+            return omitted;
+        }
+        let max = if value.get("meta").is_some() {
+            self.max_bytes().saturating_sub(META_BYTES)
+        } else {
+            self.max_bytes()
+        };
+
+        // Strings shortened per result, so dropped results do not count
+        let mut trimmed = shorten_results(value, BODY_CHARS);
+        if json_len(value) > max {
+            omitted.items = drop_results(value, max);
+            trimmed.truncate(trimmed.len() - omitted.items);
+        }
+        let mut chars = BODY_CHARS / 2;
+        while json_len(value) > max && chars >= MIN_STRING_CHARS {
+            let shortened = shorten_results(value, chars);
+            for (total, more) in trimmed.iter_mut().zip(shortened) {
+                *total += more;
+            }
+            chars /= 2;
+        }
+        omitted.trimmed = trimmed.iter().sum();
+
+        if let Some(meta) = value.get_mut("meta").and_then(Value::as_object_mut) {
+            meta.insert("truncated".to_string(), Value::Bool(true));
+            if omitted.items > 0 {
+                meta.insert("omitted".to_string(), omitted.items.into());
+                if let Some(count) = meta.get("count").and_then(Value::as_u64) {
+                    let kept = (count as usize).saturating_sub(omitted.items);
+                    meta.insert("count".to_string(), kept.into());
+                }
+            }
+            if omitted.trimmed > 0 {
+                meta.insert("trimmed".to_string(), omitted.trimmed.into());
+            }
+        }
+        omitted
+    }
+
+    /// The leading part of `text` that fits, with a note on what was cut.
+    ///
+    /// Whole blocks (separated by a blank line) are kept in order; when
+    /// not even the first fits, its leading lines are kept instead.
+    pub fn fit_text(&self, text: &str) -> (String, Omitted) {
+        let max = self.max_bytes();
+        if text.len() <= max {
+            return (text.to_string(), Omitted::default());
+        }
+
+        let blocks: Vec<&str> = text.split("\n\n").collect();
+        let mut kept = String::new();
+        let mut kept_blocks = 0;
+        for block in &blocks {
+            let separator = if kept.is_empty() { 0 } else { 2 };
+            if kept.len() + separator + block.len() > max {
+                break;
+            }
+            if separator > 0 {
+                kept.push_str("\n\n");
+            }
+            kept.push_str(block);
+            kept_blocks += 1;
+        }
+
+        let mut omitted = Omitted {
+            items: blocks.len() - kept_blocks,
+            trimmed: 0,
+        };
+        if kept_blocks == 0 {
+            // A single oversized block: keep its first lines
+            let first = blocks[0];
+            for line in first.lines() {
+                if kept.len() + line.len() + 1 > max {
+                    break;
+                }
+                kept.push_str(line);
+                kept.push('\n');
+            }
+            if kept.is_empty() {
+                kept = truncate_chars(first, max.max(MIN_STRING_CHARS));
+            }
+            let kept_lines = kept.lines().count();
+            omitted.items -= 1;
+            omitted.trimmed = first.lines().count().saturating_sub(kept_lines).max(1);
+        }
+
+        let note = self.describe(&omitted, "section", "line");
+        (format!("{}\n\n[{note}]", kept.trim_end()), omitted)
+    }
+
+    /// "3 sections and 12 lines omitted to fit the 500-token budget", for
+    /// the singular nouns naming items and trimmed parts
+    pub fn describe(&self, omitted: &Omitted, item: &str, trimmed: &str) -> String {
+        let count = |n: usize, noun: &str| {
+            let plural = if n == 1 { "" } else { "s" };
+            format!("{n} {noun}{plural}")
+        };
+        let mut parts = Vec::new();
+        if omitted.items > 0 {
+            parts.push(count(omitted.items, item));
+        }
+        if omitted.trimmed > 0 {
+            parts.push(count(omitted.trimmed, trimmed));
+        }
+        format!("{} omitted to fit the {self} budget", parts.join(" and "))
+    }
+}
+
+impl fmt::Display for Budget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tokens(tokens) => write!(f, "{tokens}-token"),
+            Self::Bytes(bytes) => write!(f, "{bytes}-byte"),
+        }
+    }
+}
+
+fn json_len(value: &Value) -> usize {
+    serde_json::to_string_pretty(value).map_or(0, |json| json.len())
+}
+
+/// The result list of `value`, if it has one
+fn results_mut(value: &mut Value) -> Option<&mut Vec<Value>> {
+    if value.is_array() {
+        return value.as_array_mut();
+    }
+    let data = value.get_mut("data")?;
+    if data.is_array() {
+        return data.as_array_mut();
+    }
+    let data = data.as_object_mut()?;
+    if data.contains_key("items") {
+        return data.get_mut("items").and_then(Value::as_array_mut);
+    }
+    // A GraphQL response: the list under its only root field
+    if data.len() != 1 {
+        return None;
+    }
+    let field = data.values_mut().next()?;
+    if field.is_array() {
+        return field.as_array_mut();
+    }
+    field.get_mut("edges").and_then(Value::as_array_mut)
+}
+
+/// Keep the longest prefix of the results that fits in `max` bytes, but at
+/// least one result. Returns how many were dropped.
+fn drop_results(value: &mut Value, max: usize) -> usize {
+    let Some(results) = results_mut(value) else {
+        return 0;
+    };
+    let all = std::mem::take(results);
+    let total = all.len();
+
+    // Binary search the number of results kept
+    let (mut low, mut high) = (1.min(total), total);
+    while low < high {
+        let mid = (low + high).div_ceil(2);
+        if let Some(results) = results_mut(value) {
+            results.extend_from_slice(&all[..mid]);
+        }
+        let fits = json_len(value) <= max;
+        if let Some(results) = results_mut(value) {
+            results.clear();
+        }
+        if fits {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+
+    if let Some(results) = results_mut(value) {
+        results.extend(all.into_iter().take(low));
+    }
+    total - low
+}
+
+/// Shorten every string of the results (or of the whole value when it has
+/// no result list) to `chars`. Returns how many strings were shortened in
+/// each result.
+fn shorten_results(value: &mut Value, chars: usize) -> Vec<usize> {
+    match results_mut(value) {
+        Some(results) => results
+            .iter_mut()
+            .map(|result| shorten_strings(result, chars))
+            .collect(),
+        None => match value.get_mut("data") {
+            Some(data) => vec![shorten_strings(data, chars)],
+            None => vec![shorten_strings(value, chars)],
+        },
+    }
+}
+
+fn shorten_strings(value: &mut Value, chars: usize) -> usize {
+    match value {
+        Value::String(text) if text.chars().count() > chars => {
+            *text = truncate_chars(text, chars);
+            1
+        }
+        Value::Array(items) => items
+            .iter_mut()
+            .map(|item| shorten_strings(item, chars))
+            .sum(),
+        Value::Object(map) => map
+            .values_mut()
+            .map(|item| shorten_strings(item, chars))
+            .sum(),
+        _ => 0,
+    }
+}
+
+/// The first `chars` chars of `text`, ending in an ellipsis
+fn truncate_chars(text: &str, chars: usize) -> String {
+    let mut short: String = text.chars().take(chars.saturating_sub(1)).collect();
+    short.push_str(ELLIPSIS);
+    short
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_envelope_keeps_leading_results_and_reports_the_cut() {
+        let results: Vec<Value> = (0..50)
+            .map(|i| json!({ "name": format!("symbol_{i}"), "body": "x".repeat(1000) }))
+            .collect();
+        let mut envelope = json!({
+            "status": "success",
+            "data": results,
+            "meta": { "schema_version": "1.0.0", "count": 50 }
+        });
+
+        let budget = Budget::Tokens(500);
+        let omitted = budget.fit_value(&mut envelope);
+        assert!(json_len(&envelope) <= budget.max_bytes());
+        assert!(omitted.items > 0 && omitted.trimmed > 0);
+
+        let data = envelope["data"].as_array().unwrap();
+        assert_eq!(data[0]["name"], "symbol_0");
+        assert!(data[0]["body"].as_str().unwrap().ends_with(ELLIPSIS));
+        assert_eq!(envelope["meta"]["truncated"], true);
+        assert_eq!(envelope["meta"]["omitted"], omitted.items);
+        assert_eq!(envelope["meta"]["count"], 50 - omitted.items);
+
+        let mut small = json!({ "data": [1, 2, 3] });
+        assert!(budget.fit_value(&mut small).is_empty());
+        assert!(small.get("meta").is_none());
+    }
+
+    #[test]
+    fn test_graphql_response_keeps_leading_edges() {
+        let edges: Vec<Value> = (0..50)
+            .map(|i| json!({ "cursor": i.to_string(), "node": { "name": format!("symbol_{i}") } }))
+            .collect();
+        let mut response = json!({
+            "data": { "symbols": { "edges": edges, "pageInfo": { "hasNextPage": false } } }
+        });
+
+        let budget = Budget::Bytes(1000);
+        let omitted = budget.fit_value(&mut response);
+        assert!(omitted.items > 0);
+        assert!(json_len(&response) <= budget.max_bytes());
+        let edges = response["data"]["symbols"]["edges"].as_array().unwrap();
+        assert_eq!(edges.len(), 50 - omitted.items);
+        assert_eq!(edges[0]["node"]["name"], "symbol_0");
+    }
+
+    #[test]
+    fn test_text_keeps_whole_leading_blocks() {
+        let text = "first result\n  detail\n\nsecond result\n\nthird result";
+        let (fitted, omitted) = Budget::Bytes(40).fit_text(text);
+        assert_eq!(
+            omitted,
+            Omitted {
+                items: 1,
+                trimmed: 0
+            }
+        );
+        assert_eq!(
+            fitted,
+            "first result\n  detail\n\nsecond result\n\n\
+             [1 section omitted to fit the 40-byte budget]"
+        );
+
+        let (fitted, omitted) = Budget::Bytes(20).fit_text("line one\nline two\nline three");
+        assert_eq!(
+            omitted,
+            Omitted {
+                items: 0,
+                trimmed: 1
+            }
+        );
+        assert!(fitted.starts_with("line one\nline two\n\n["));
+
+        assert_eq!(
+            Budget::from_limits(Some(100), Some(300)),
+            Some(Budget::Bytes(300))
+        );
+        assert_eq!(Budget::from_limits(None, None), None);
+    }
+}
@@ -5,6 +5,10 @@
 //! This envelope provides consistent JSON output across all commands,
 //! designed for Unix piping, AI integration, and future streaming.
 
+use crate::error::{ErrorCategory, IndexError};
+use crate::io::budget::Budget;
+use crate::io::format::OutputFormat;
+use crate::io::formatter;
 use serde::{Deserialize, Serialize};
 
 /// Schema version for this envelope format.
@@ -121,6 +125,14 @@ pub struct Envelope<T = serde_json::Value> {
 /// Error details with suggestions and context.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ErrorDetails {
+    /// Stable failure category, for errors raised by the library
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<ErrorCategory>,
+
+    /// Stable code of the specific error (e.g. `FILE_READ_ERROR`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<String>,
+
     /// Recovery suggestions
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub suggestions: Vec<String>,
@@ -160,6 +172,14 @@ pub struct Meta {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub truncated: Option<bool>,
 
+    /// Results dropped to fit the output budget
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub omitted: Option<usize>,
+
+    /// Strings shortened to fit the output budget
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trimmed: Option<usize>,
+
     /// Traversal depth for tree/graph results
     #[serde(skip_serializing_if = "Option::is_none")]
     pub depth: Option<u32>,
@@ -175,6 +195,8 @@ impl Default for Meta {
             lang: None,
             duration_ms: None,
             truncated: None,
+            omitted: None,
+            trimmed: None,
             depth: None,
         }
     }
@@ -226,6 +248,32 @@ impl<T> Envelope<T> {
         }
     }
 
+    /// Create an error envelope from a library error, carrying its category,
+    /// stable error code, recovery suggestions and exit code.
+    pub fn from_error(error: &IndexError) -> Self {
+        let category = error.category();
+        let code = match category {
+            ErrorCategory::NotFound => ResultCode::NotFound,
+            ErrorCategory::Parse => ResultCode::ParseError,
+            ErrorCategory::Storage | ErrorCategory::IndexCorrupted | ErrorCategory::Capacity => {
+                ResultCode::IndexError
+            }
+            _ => ResultCode::InternalError,
+        };
+        let mut envelope = Self::error(code, error.to_string()).with_error_details(ErrorDetails {
+            category: Some(category),
+            error_code: Some(error.status_code()),
+            suggestions: error
+                .recovery_suggestions()
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            context: None,
+        });
+        envelope.exit_code = category.exit_code() as u8;
+        envelope
+    }
+
     /// Add hint for AI assistants.
     pub fn with_hint(mut self, hint: impl Into<String>) -> Self {
         self.hint = Some(hint.into());
@@ -286,12 +334,15 @@ impl<T> Envelope<T> {
         self
     }
 
-    /// Serialize to JSON string.
+    /// Serialize to JSON string, cut to the output budget if one is set.
     pub fn to_json(&self) -> Result<String, serde_json::Error>
     where
         T: Serialize,
     {
-        serde_json::to_string_pretty(self)
+        match Budget::default_budget() {
+            Some(budget) => serde_json::to_string_pretty(&fit(serde_json::to_value(self)?, budget)),
+            None => serde_json::to_string_pretty(self),
+        }
     }
 
     /// Serialize to compact JSON string (no whitespace).
@@ -308,6 +359,47 @@ impl<T> Envelope<T> {
     /// The envelope structure (type, status, code, etc.) is always included.
     /// Works with both array data (filters each item) and object data (filters the object).
     pub fn to_json_with_fields(&self, fields: &[String]) -> Result<String, serde_json::Error>
+    where
+        T: Serialize,
+    {
+        serde_json::to_string_pretty(&self.to_value_with_fields(fields)?)
+    }
+
+    /// Render in a structured output format (JSON, YAML, markdown, template).
+    pub fn render(&self, format: OutputFormat) -> Result<String, serde_json::Error>
+    where
+        T: Serialize,
+    {
+        match Budget::default_budget() {
+            Some(budget) => Ok(formatter::render_value(
+                &fit(serde_json::to_value(self)?, budget),
+                format,
+            )),
+            None => formatter::render(self, format),
+        }
+    }
+
+    /// Render in a structured output format, keeping only `fields` of each
+    /// data item.
+    pub fn render_with_fields(
+        &self,
+        format: OutputFormat,
+        fields: &[String],
+    ) -> Result<String, serde_json::Error>
+    where
+        T: Serialize,
+    {
+        let value = self.to_value_with_fields(fields)?;
+        match format {
+            OutputFormat::Text | OutputFormat::Json => serde_json::to_string_pretty(&value),
+            _ => Ok(formatter::render_value(&value, format)),
+        }
+    }
+
+    fn to_value_with_fields(
+        &self,
+        fields: &[String],
+    ) -> Result<serde_json::Value, serde_json::Error>
     where
         T: Serialize,
     {
@@ -341,10 +433,19 @@ impl<T> Envelope<T> {
             }
         }
 
-        serde_json::to_string_pretty(&value)
+        Ok(match Budget::default_budget() {
+            Some(budget) => fit(value, budget),
+            None => value,
+        })
     }
 }
 
+/// `value` cut to `budget`
+fn fit(mut value: serde_json::Value, budget: Budget) -> serde_json::Value {
+    budget.fit_value(&mut value);
+    value
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -377,10 +478,30 @@ mod tests {
         assert!(envelope.hint.is_some());
     }
 
+    #[test]
+    fn test_envelope_from_error() {
+        let error = crate::IndexError::TantivyError {
+            operation: "commit".to_string(),
+            cause: "disk full".to_string(),
+        };
+        let envelope: Envelope<()> = Envelope::from_error(&error);
+
+        assert_eq!(envelope.code, ResultCode::IndexError);
+        assert_eq!(envelope.exit_code, 9);
+        let details = envelope.error.as_ref().expect("error details");
+        assert_eq!(details.category, Some(ErrorCategory::Storage));
+        assert_eq!(details.error_code.as_deref(), Some("TANTIVY_ERROR"));
+
+        let json = envelope.to_json().unwrap();
+        assert!(json.contains(r#""category": "storage""#));
+    }
+
     #[test]
     fn test_error_envelope() {
         let envelope: Envelope<()> = Envelope::error(ResultCode::ParseError, "Invalid syntax")
             .with_error_details(ErrorDetails {
+                category: None,
+                error_code: None,
                 suggestions: vec!["Check syntax".to_string()],
                 context: None,
             });
@@ -44,6 +44,15 @@ pub enum ExitCode {
 
     /// Operation not supported (code 8)
     UnsupportedOperation = 8,
+
+    /// Index storage failed (code 9)
+    StorageError = 9,
+
+    /// A lock could not be acquired or was poisoned (code 10)
+    LockError = 10,
+
+    /// Semantic search is disabled or failed (code 11)
+    SemanticSearchError = 11,
 }
 
 impl From<ExitCode> for i32 {
@@ -65,30 +74,11 @@ impl ExitCode {
 
     /// Convert an `IndexError` to the appropriate exit code.
     ///
-    /// Maps specific error types to semantic exit codes that scripts
-    /// can use to determine appropriate recovery actions.
+    /// Maps the error's [`ErrorCategory`](crate::error::ErrorCategory) to a
+    /// semantic exit code that scripts can use to determine appropriate
+    /// recovery actions.
     pub fn from_error(error: &IndexError) -> Self {
-        match error {
-            // Not found errors are recoverable
-            IndexError::SymbolNotFound { .. } | IndexError::FileNotFound { .. } => {
-                ExitCode::NotFound
-            }
-
-            // Index corruption is a blocking error
-            IndexError::IndexCorrupted { .. } => ExitCode::BlockingError,
-
-            // Specific recoverable errors
-            IndexError::ParseError { .. } => ExitCode::ParseError,
-            IndexError::FileRead { .. } | IndexError::FileWrite { .. } => ExitCode::IoError,
-            IndexError::ConfigError { .. } => ExitCode::ConfigError,
-            IndexError::UnsupportedFileType { .. } => ExitCode::UnsupportedOperation,
-
-            // ID exhaustion errors are blocking
-            IndexError::FileIdExhausted | IndexError::SymbolIdExhausted => ExitCode::BlockingError,
-
-            // Everything else is a general error
-            _ => ExitCode::GeneralError,
-        }
+        error.category().exit_code()
     }
 
     /// Check if this exit code indicates a blocking error.
@@ -117,6 +107,9 @@ impl ExitCode {
             ExitCode::ConfigError => "Configuration error",
             ExitCode::IndexCorrupted => "Index corrupted",
             ExitCode::UnsupportedOperation => "Unsupported operation",
+            ExitCode::StorageError => "Storage error",
+            ExitCode::LockError => "Lock error",
+            ExitCode::SemanticSearchError => "Semantic search error",
         }
     }
 }
@@ -133,6 +126,25 @@ mod tests {
         assert_eq!(ExitCode::NotFound as u8, 3);
     }
 
+    #[test]
+    fn test_from_error_uses_category() {
+        let not_found = IndexError::SymbolNotFound {
+            name: "main".to_string(),
+        };
+        assert_eq!(ExitCode::from_error(&not_found), ExitCode::NotFound);
+
+        let lock = IndexError::lock_error();
+        assert_eq!(ExitCode::from_error(&lock), ExitCode::LockError);
+        assert_eq!(ExitCode::LockError as u8, 10);
+
+        let io = IndexError::from(std::io::Error::other("disk full"));
+        assert_eq!(ExitCode::from_error(&io), ExitCode::IoError);
+        assert_eq!(io.to_string(), "disk full");
+
+        let general = IndexError::General("something odd".to_string());
+        assert_eq!(ExitCode::from_error(&general), ExitCode::GeneralError);
+    }
+
     #[test]
     fn test_from_retrieve_result() {
         let some_result = Some("data");
@@ -7,6 +7,7 @@ use crate::error::IndexError;
 use crate::io::exit_code::ExitCode;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
 
 /// Output format for CLI commands.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -15,14 +16,52 @@ pub enum OutputFormat {
     Text,
     /// JSON for tool integration
     Json,
-    // Future: Yaml, Xml, etc.
+    /// The JSON document rendered as YAML
+    Yaml,
+    /// Markdown table, one row per result
+    Markdown,
+    /// One line per result from a user template (see [`crate::io::formatter`])
+    Template(&'static str),
 }
 
+/// Format selected with the global `--format`/`--template` options
+static DEFAULT_FORMAT: OnceLock<OutputFormat> = OnceLock::new();
+
 impl OutputFormat {
     /// Create format from JSON flag.
+    ///
+    /// Without `--json`, commands use the format selected globally with
+    /// [`OutputFormat::set_default`], or text.
     #[must_use]
     pub fn from_json_flag(json: bool) -> Self {
-        if json { Self::Json } else { Self::Text }
+        if json {
+            Self::Json
+        } else {
+            DEFAULT_FORMAT.get().copied().unwrap_or(Self::Text)
+        }
+    }
+
+    /// Parse the `--format` and `--template` options. A template implies
+    /// the template format.
+    pub fn parse(name: Option<&str>, template: Option<String>) -> Result<Self, String> {
+        if let Some(template) = template {
+            return Ok(Self::Template(Box::leak(template.into_boxed_str())));
+        }
+        match name.map(str::to_ascii_lowercase).as_deref() {
+            None | Some("text") => Ok(Self::Text),
+            Some("json") => Ok(Self::Json),
+            Some("yaml" | "yml") => Ok(Self::Yaml),
+            Some("markdown" | "md") => Ok(Self::Markdown),
+            Some(other) => Err(format!(
+                "unknown output format '{other}' (expected text, json, yaml or markdown)"
+            )),
+        }
+    }
+
+    /// Select the format used by commands invoked without `--json`. Only
+    /// the first call takes effect.
+    pub fn set_default(format: Self) {
+        let _ = DEFAULT_FORMAT.set(format);
     }
 
     /// Check if format is JSON.
@@ -30,6 +69,13 @@ impl OutputFormat {
     pub fn is_json(&self) -> bool {
         matches!(self, Self::Json)
     }
+
+    /// Check if output is rendered from the structured (JSON) document
+    /// rather than written as human-readable text.
+    #[must_use]
+    pub fn is_structured(&self) -> bool {
+        !matches!(self, Self::Text)
+    }
 }
 
 /// Standard JSON response format.
@@ -73,6 +119,9 @@ where
 /// Error details for JSON responses.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ErrorDetails {
+    /// Stable failure category (see [`crate::ErrorCategory::code`])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
     /// Recovery suggestions
     pub suggestions: Vec<String>,
     /// Additional error context
@@ -134,6 +183,7 @@ impl JsonResponse<serde_json::Value> {
             system_message: None,
             data: None,
             error: Some(ErrorDetails {
+                category: None,
                 suggestions: vec![
                     "Check the spelling".to_string(),
                     "Ensure the index is up to date".to_string(),
@@ -154,6 +204,7 @@ impl JsonResponse<serde_json::Value> {
             system_message: None,
             data: None,
             error: Some(ErrorDetails {
+                category: None,
                 suggestions: suggestions.iter().map(|s| s.to_string()).collect(),
                 context: None,
             }),
@@ -171,6 +222,7 @@ impl JsonResponse<serde_json::Value> {
             system_message: None,
             data: None,
             error: Some(ErrorDetails {
+                category: Some(error.category().code().to_string()),
                 suggestions: error
                     .recovery_suggestions()
                     .iter()
@@ -212,6 +264,24 @@ mod tests {
         assert_eq!(OutputFormat::from_json_flag(false), OutputFormat::Text);
     }
 
+    #[test]
+    fn test_output_format_parse() {
+        assert_eq!(OutputFormat::parse(None, None), Ok(OutputFormat::Text));
+        assert_eq!(
+            OutputFormat::parse(Some("YAML"), None),
+            Ok(OutputFormat::Yaml)
+        );
+        assert_eq!(
+            OutputFormat::parse(Some("md"), None),
+            Ok(OutputFormat::Markdown)
+        );
+        assert_eq!(
+            OutputFormat::parse(Some("json"), Some("{name}".to_string())),
+            Ok(OutputFormat::Template("{name}"))
+        );
+        assert!(OutputFormat::parse(Some("xml"), None).is_err());
+    }
+
     #[test]
     fn test_json_response_success() {
         #[derive(Serialize)]
@@ -242,4 +312,16 @@ mod tests {
         assert!(response.data.is_none());
         assert!(response.error.is_some());
     }
+
+    #[test]
+    fn test_json_response_from_error_carries_category() {
+        let error = IndexError::ConfigError {
+            reason: "bad value".to_string(),
+        };
+        let response = JsonResponse::from_error(&error);
+        assert_eq!(response.code, "CONFIG_ERROR");
+        assert_eq!(response.exit_code, ExitCode::ConfigError as u8);
+        let details = response.error.expect("error details");
+        assert_eq!(details.category.as_deref(), Some("config"));
+    }
 }
@@ -0,0 +1,321 @@
+//! Rendering of structured command output in user-selected formats.
+//!
+//! Commands build the same serializable value (an [`Envelope`] or
+//! [`JsonResponse`]) for every structured format; this module turns it into
+//! the requested text:
+//!
+//! - `json`: the value as pretty-printed JSON
+//! - `yaml`: the same document as block-style YAML
+//! - `markdown`: a table with one row per result
+//! - template: one line per result, e.g. `{symbol.name}\t{file_path}:{range.start_line}`
+//!
+//! Results are the items of the value's `data` array (or `data.items`).
+//! Nested objects are flattened to dotted keys, so `{symbol.name}` in a
+//! template and the `symbol.name` markdown column address the same field.
+//!
+//! Template syntax is intentionally minimal: `{key}` is replaced by the
+//! field's value (empty when missing), `{{` and `}}` produce literal braces.
+//!
+//! [`Envelope`]: crate::io::Envelope
+//! [`JsonResponse`]: crate::io::JsonResponse
+
+use crate::io::format::OutputFormat;
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt::Write;
+
+/// Render `data` in `format`. Text falls back to JSON; callers print their
+/// own human-readable text.
+pub fn render<T>(data: &T, format: OutputFormat) -> serde_json::Result<String>
+where
+    T: Serialize + ?Sized,
+{
+    match format {
+        OutputFormat::Text | OutputFormat::Json => serde_json::to_string_pretty(data),
+        _ => Ok(render_value(&serde_json::to_value(data)?, format)),
+    }
+}
+
+/// Render an already serialized value in `format`.
+pub fn render_value(value: &Value, format: OutputFormat) -> String {
+    match format {
+        OutputFormat::Text | OutputFormat::Json => {
+            serde_json::to_string_pretty(value).unwrap_or_default()
+        }
+        OutputFormat::Yaml => to_yaml(value),
+        OutputFormat::Markdown => match records(value) {
+            Some(rows) => markdown_table(&rows),
+            None => message(value),
+        },
+        OutputFormat::Template(template) => match records(value) {
+            Some(rows) => rows
+                .iter()
+                .map(|row| apply_template(template, row))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            None => message(value),
+        },
+    }
+}
+
+/// Fill `{key}` placeholders in `template` from a flattened result row.
+pub fn apply_template(template: &str, row: &[(String, String)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                out.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                out.push('}');
+            }
+            '{' => {
+                let key: String = chars.by_ref().take_while(|&c| c != '}').collect();
+                let key = key.trim();
+                if let Some((_, value)) = row.iter().find(|(k, _)| k == key) {
+                    out.push_str(value);
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Result rows of an output document, flattened to `(dotted key, value)`
+/// pairs. `None` when the document carries no data (errors, not found).
+fn records(value: &Value) -> Option<Vec<Vec<(String, String)>>> {
+    let data = value.get("data").filter(|data| !data.is_null())?;
+    let items = match data {
+        Value::Array(items) => items.iter().collect(),
+        Value::Object(map) => match map.get("items") {
+            Some(Value::Array(items)) => items.iter().collect(),
+            _ => vec![data],
+        },
+        scalar => vec![scalar],
+    };
+    Some(
+        items
+            .into_iter()
+            .map(|item| {
+                let mut row = Vec::new();
+                flatten("", item, &mut row);
+                row
+            })
+            .collect(),
+    )
+}
+
+fn flatten(prefix: &str, value: &Value, row: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let key = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                flatten(&key, value, row);
+            }
+        }
+        _ => {
+            let key = if prefix.is_empty() { "value" } else { prefix };
+            row.push((key.to_string(), scalar_text(value)));
+        }
+    }
+}
+
+/// Plain text of a leaf value: strings without quotes, lists of scalars
+/// comma-separated, anything else as compact JSON
+fn scalar_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Array(items)
+            if items
+                .iter()
+                .all(|item| !item.is_object() && !item.is_array()) =>
+        {
+            items.iter().map(scalar_text).collect::<Vec<_>>().join(", ")
+        }
+        other => other.to_string(),
+    }
+}
+
+fn message(value: &Value) -> String {
+    value
+        .get("message")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn markdown_table(rows: &[Vec<(String, String)>]) -> String {
+    let mut columns: Vec<&str> = Vec::new();
+    for (key, _) in rows.iter().flatten() {
+        if !columns.contains(&key.as_str()) {
+            columns.push(key);
+        }
+    }
+
+    let cell = |text: &str| text.replace('|', "\\|").replace('\n', " ");
+    let mut out = String::new();
+    let _ = writeln!(out, "| {} |", columns.join(" | "));
+    let _ = write!(out, "|{}", " --- |".repeat(columns.len()));
+    for row in rows {
+        let cells: Vec<String> = columns
+            .iter()
+            .map(|column| {
+                row.iter()
+                    .find(|(key, _)| key == column)
+                    .map(|(_, value)| cell(value))
+                    .unwrap_or_default()
+            })
+            .collect();
+        let _ = write!(out, "\n| {} |", cells.join(" | "));
+    }
+    out
+}
+
+fn to_yaml(value: &Value) -> String {
+    let mut out = String::new();
+    match value {
+        Value::Object(map) if !map.is_empty() => yaml_map(map, 0, &mut out),
+        Value::Array(items) if !items.is_empty() => yaml_seq(items, 0, &mut out),
+        scalar => out.push_str(&yaml_scalar(scalar)),
+    }
+    out.trim_end().to_string()
+}
+
+fn yaml_map(map: &serde_json::Map<String, Value>, indent: usize, out: &mut String) {
+    for (key, value) in map {
+        let key = yaml_string(key);
+        match value {
+            Value::Object(inner) if !inner.is_empty() => {
+                let _ = writeln!(out, "{:indent$}{key}:", "");
+                yaml_map(inner, indent + 2, out);
+            }
+            Value::Array(items) if !items.is_empty() => {
+                let _ = writeln!(out, "{:indent$}{key}:", "");
+                yaml_seq(items, indent, out);
+            }
+            scalar => {
+                let _ = writeln!(out, "{:indent$}{key}: {}", "", yaml_scalar(scalar));
+            }
+        }
+    }
+}
+
+fn yaml_seq(items: &[Value], indent: usize, out: &mut String) {
+    for item in items {
+        match item {
+            Value::Object(map) if !map.is_empty() => {
+                // First key shares the line with the dash
+                let mut nested = String::new();
+                yaml_map(map, indent + 2, &mut nested);
+                let _ = write!(out, "{:indent$}- {}", "", &nested[indent + 2..]);
+            }
+            Value::Array(inner) if !inner.is_empty() => {
+                let _ = writeln!(out, "{:indent$}-", "");
+                yaml_seq(inner, indent + 2, out);
+            }
+            scalar => {
+                let _ = writeln!(out, "{:indent$}- {}", "", yaml_scalar(scalar));
+            }
+        }
+    }
+}
+
+fn yaml_scalar(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::String(s) => yaml_string(s),
+        Value::Array(_) => "[]".to_string(),
+        Value::Object(_) => "{}".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Strings are written plain when YAML reads them back unchanged, and
+/// double-quoted (JSON escaping is valid YAML) otherwise
+fn yaml_string(s: &str) -> String {
+    let reserved = matches!(
+        s.to_ascii_lowercase().as_str(),
+        "" | "~" | "null" | "true" | "false" | "yes" | "no" | "on" | "off"
+    );
+    let plain = !reserved
+        && s.starts_with(|c: char| c.is_alphabetic() || c == '_' || c == '/' || c == '.')
+        && !s.ends_with(' ')
+        && !s.contains(": ")
+        && !s.contains(" #")
+        && s.parse::<f64>().is_err()
+        && s.chars()
+            .all(|c| c.is_alphanumeric() || " _-./()<>,=+*&@$%^~!?;'".contains(c));
+    if plain {
+        s.to_string()
+    } else {
+        Value::String(s.to_string()).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sample() -> Value {
+        // Keys in sorted order, so the expectations hold with or without
+        // serde_json's preserve_order feature
+        json!({
+            "data": [
+                { "file_path": "src/main.rs:10", "symbol": { "kind": "Function", "name": "main" } },
+                { "file_path": "src/app.rs:4", "symbol": { "kind": "Method", "name": "run" } }
+            ],
+            "message": "Found 2 symbol(s)",
+            "status": "success"
+        })
+    }
+
+    #[test]
+    fn test_template_and_markdown_rendering() {
+        let template = OutputFormat::Template("{symbol.name} ({symbol.kind}) {{{file_path}}}");
+        assert_eq!(
+            render_value(&sample(), template),
+            "main (Function) {src/main.rs:10}\nrun (Method) {src/app.rs:4}"
+        );
+
+        let table = render_value(&sample(), OutputFormat::Markdown);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines[0], "| file_path | symbol.kind | symbol.name |");
+        assert_eq!(lines[1], "| --- | --- | --- |");
+        assert_eq!(lines[2], "| src/main.rs:10 | Function | main |");
+        assert_eq!(lines.len(), 4);
+
+        // Documents without data fall back to their message
+        let not_found = json!({ "status": "not_found", "message": "No symbol found" });
+        assert_eq!(render_value(&not_found, template), "No symbol found");
+    }
+
+    #[test]
+    fn test_yaml_rendering() {
+        let yaml = render_value(&sample(), OutputFormat::Yaml);
+        let expected = "\
+data:
+- file_path: \"src/main.rs:10\"
+  symbol:
+    kind: Function
+    name: main
+- file_path: \"src/app.rs:4\"
+  symbol:
+    kind: Method
+    name: run
+message: Found 2 symbol(s)
+status: success";
+        assert_eq!(yaml, expected);
+        assert_eq!(yaml_string("true"), "\"true\"");
+        assert_eq!(yaml_string("42"), "\"42\"");
+    }
+}
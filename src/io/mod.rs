@@ -1,14 +1,16 @@
 //! Input/Output handling for CLI and tool integration.
 //!
 //! This module provides:
-//! - Unified output formatting (text, JSON)
+//! - Unified output formatting (text, JSON, YAML, markdown, templates)
 //! - Consistent error handling and exit codes
 //! - Future: JSON-RPC 2.0 support for IDE integration
 
 pub mod args;
+pub mod budget;
 pub mod envelope;
 pub mod exit_code;
 pub mod format;
+pub mod formatter;
 pub mod guidance;
 pub mod guidance_engine;
 pub mod input;
@@ -19,6 +21,7 @@ pub mod status_line;
 #[cfg(test)]
 mod test;
 
+pub use budget::{Budget, BudgetParams};
 pub use envelope::{
     EntityType as EnvelopeEntityType, Envelope, ErrorDetails as EnvelopeErrorDetails, MessageType,
     Meta, ResultCode, SCHEMA_VERSION, Status,
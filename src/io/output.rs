@@ -1,11 +1,12 @@
 //! Output management for CLI commands.
 //!
 //! Handles formatting and display for different output formats,
-//! providing a unified interface for text and JSON output.
+//! providing a unified interface for text and structured output.
 
 use crate::error::IndexError;
 use crate::io::exit_code::ExitCode;
 use crate::io::format::{JsonResponse, OutputFormat};
+use crate::io::formatter;
 use crate::io::schema::{OutputData, OutputStatus, UnifiedOutput};
 use serde::Serialize;
 use std::fmt::Display;
@@ -64,7 +65,7 @@ impl OutputManager {
 
     /// Output a successful result.
     ///
-    /// In JSON mode, wraps the data in a success response.
+    /// In structured formats (JSON, YAML, ...), wraps the data in a success response.
     /// In text mode, displays the data using its Display implementation.
     /// Broken pipe errors are silently ignored to support piping to commands like `head`.
     pub fn success<T>(&mut self, data: T) -> io::Result<ExitCode>
@@ -72,15 +73,15 @@ impl OutputManager {
         T: Serialize + Display,
     {
         match self.format {
-            OutputFormat::Json => {
-                let response = JsonResponse::success(&data);
-                let json_str = serde_json::to_string_pretty(&response)?;
-                Self::write_ignoring_broken_pipe(&mut *self.stdout, &json_str)?;
-            }
             OutputFormat::Text => {
                 let text = format!("{data}");
                 Self::write_ignoring_broken_pipe(&mut *self.stdout, &text)?;
             }
+            format => {
+                let response = JsonResponse::success(&data);
+                let rendered = formatter::render(&response, format)?;
+                Self::write_ignoring_broken_pipe(&mut *self.stdout, &rendered)?;
+            }
         }
         Ok(ExitCode::Success)
     }
@@ -104,15 +105,15 @@ impl OutputManager {
     /// Broken pipe errors are silently ignored.
     pub fn not_found(&mut self, entity: &str, name: &str) -> io::Result<ExitCode> {
         match self.format {
-            OutputFormat::Json => {
-                let response = JsonResponse::not_found(entity, name);
-                let json_str = serde_json::to_string_pretty(&response)?;
-                Self::write_ignoring_broken_pipe(&mut *self.stdout, &json_str)?;
-            }
             OutputFormat::Text => {
                 let text = format!("{entity} '{name}' not found");
                 Self::write_ignoring_broken_pipe(&mut *self.stderr, &text)?;
             }
+            format => {
+                let response = JsonResponse::not_found(entity, name);
+                let rendered = formatter::render(&response, format)?;
+                Self::write_ignoring_broken_pipe(&mut *self.stdout, &rendered)?;
+            }
         }
         Ok(ExitCode::NotFound)
     }
@@ -134,11 +135,6 @@ impl OutputManager {
         }
 
         match self.format {
-            OutputFormat::Json => {
-                let response = JsonResponse::success(&items);
-                let json_str = serde_json::to_string_pretty(&response)?;
-                Self::write_ignoring_broken_pipe(&mut *self.stdout, &json_str)?;
-            }
             OutputFormat::Text => {
                 let header = format!("Found {} {entity_name}:", items.len());
                 Self::write_ignoring_broken_pipe(&mut *self.stdout, &header)?;
@@ -148,6 +144,11 @@ impl OutputManager {
                     Self::write_ignoring_broken_pipe(&mut *self.stdout, &item_str)?;
                 }
             }
+            format => {
+                let response = JsonResponse::success(&items);
+                let rendered = formatter::render(&response, format)?;
+                Self::write_ignoring_broken_pipe(&mut *self.stdout, &rendered)?;
+            }
         }
         Ok(ExitCode::Success)
     }
@@ -157,11 +158,6 @@ impl OutputManager {
     /// Broken pipe errors are silently ignored.
     pub fn error(&mut self, error: &IndexError) -> io::Result<ExitCode> {
         match self.format {
-            OutputFormat::Json => {
-                let response = JsonResponse::from_error(error);
-                let json_str = serde_json::to_string_pretty(&response)?;
-                Self::write_ignoring_broken_pipe(&mut *self.stderr, &json_str)?;
-            }
             OutputFormat::Text => {
                 let error_msg = format!("Error: {error}");
                 Self::write_ignoring_broken_pipe(&mut *self.stderr, &error_msg)?;
@@ -170,6 +166,11 @@ impl OutputManager {
                     Self::write_ignoring_broken_pipe(&mut *self.stderr, &suggestion_msg)?;
                 }
             }
+            format => {
+                let response = JsonResponse::from_error(error);
+                let rendered = formatter::render(&response, format)?;
+                Self::write_ignoring_broken_pipe(&mut *self.stderr, &rendered)?;
+            }
         }
         Ok(ExitCode::from_error(error))
     }
@@ -219,11 +220,6 @@ impl OutputManager {
         }
 
         match self.format {
-            OutputFormat::Json => {
-                let response = JsonResponse::success(&contexts);
-                let json_str = serde_json::to_string_pretty(&response)?;
-                Self::write_ignoring_broken_pipe(&mut *self.stdout, &json_str)?;
-            }
             OutputFormat::Text => {
                 let header = format!("Found {} {}:", contexts.len(), entity_name);
                 Self::write_ignoring_broken_pipe(&mut *self.stdout, &header)?;
@@ -234,6 +230,11 @@ impl OutputManager {
                     Self::write_ignoring_broken_pipe(&mut *self.stdout, &formatted)?;
                 }
             }
+            format => {
+                let response = JsonResponse::success(&contexts);
+                let rendered = formatter::render(&response, format)?;
+                Self::write_ignoring_broken_pipe(&mut *self.stdout, &rendered)?;
+            }
         }
         Ok(ExitCode::Success)
     }
@@ -261,12 +262,6 @@ impl OutputManager {
         let exit_code = output.exit_code;
 
         match self.format {
-            OutputFormat::Json => {
-                // For JSON, serialize the UnifiedOutput directly
-                // This preserves the structured data with metadata and guidance
-                let json_str = serde_json::to_string_pretty(&output)?;
-                Self::write_ignoring_broken_pipe(&mut *self.stdout, &json_str)?;
-            }
             OutputFormat::Text => {
                 // For text, check if we have special handling needs
                 match (&output.data, &output.status) {
@@ -301,6 +296,12 @@ impl OutputManager {
                     Self::write_ignoring_broken_pipe(&mut *self.stderr, guidance)?;
                 }
             }
+            format => {
+                // For structured formats, serialize the UnifiedOutput directly
+                // This preserves the structured data with metadata and guidance
+                let rendered = formatter::render(&output, format)?;
+                Self::write_ignoring_broken_pipe(&mut *self.stdout, &rendered)?;
+            }
         }
 
         Ok(exit_code)
@@ -699,6 +699,9 @@ impl Spinner {
             6 => ExitCode::ConfigError,
             7 => ExitCode::IndexCorrupted,
             8 => ExitCode::UnsupportedOperation,
+            9 => ExitCode::StorageError,
+            10 => ExitCode::LockError,
+            11 => ExitCode::SemanticSearchError,
             _ => ExitCode::GeneralError,
         }
     }
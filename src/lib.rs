@@ -4,25 +4,35 @@
 // extern crate tree_sitter_kotlin;
 extern crate tree_sitter_kotlin_codanna as tree_sitter_kotlin;
 
+pub mod analysis;
 pub mod cli;
 pub mod config;
+pub mod deps;
 pub mod display;
 pub mod documents;
 pub mod error;
+pub mod export;
+#[cfg(feature = "graphql")]
+pub mod graphql;
 pub mod indexing;
 pub mod init;
 pub mod io;
 pub mod logging;
 pub mod mcp;
+pub mod metrics;
+pub mod navigation;
 pub mod parsing;
 pub mod plugins;
 pub mod profiles;
 pub mod project_resolver;
+pub mod queries;
 pub mod relationship;
 pub mod retrieve;
 pub mod semantic;
 pub mod storage;
 pub mod symbol;
+#[cfg(feature = "tui")]
+pub mod tui;
 pub mod types;
 pub mod utils;
 pub mod vector;
@@ -31,14 +41,14 @@ pub mod watcher;
 // Explicit exports for better API clarity
 pub use config::{LoggingConfig, Settings};
 pub use error::{
-    IndexError, IndexResult, McpError, McpResult, ParseError, ParseResult, StorageError,
-    StorageResult,
+    ErrorCategory, IndexError, IndexResult, McpError, McpResult, ParseError, ParseResult,
+    StorageError, StorageResult,
 };
 pub use indexing::calculate_hash;
 pub use parsing::RustParser;
-pub use relationship::{RelationKind, Relationship, RelationshipEdge};
+pub use relationship::{Confidence, RelationKind, Relationship, RelationshipEdge};
 pub use storage::IndexPersistence;
-pub use symbol::{CompactSymbol, ScopeContext, StringTable, Symbol, Visibility};
+pub use symbol::{CompactSymbol, ScopeContext, StringTable, Symbol, SymbolRef, Visibility};
 pub use types::{
     CompactString, FileId, IndexingResult, Range, SymbolId, SymbolKind, compact_string,
 };
@@ -8,6 +8,7 @@
 //! ```toml
 //! [logging]
 //! default = "warn"  # quiet by default
+//! format = "json"   # newline-delimited JSON with span context
 //!
 //! [logging.modules]
 //! cli = "debug"     # enable CLI debug logs
@@ -27,7 +28,7 @@ use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
 use tracing_subscriber::{EnvFilter, Layer};
 
-use crate::config::LoggingConfig;
+use crate::config::{LogFormat, LoggingConfig};
 
 static INIT: Once = Once::new();
 
@@ -42,7 +43,10 @@ impl FormatTime for CompactTime {
 
 /// Modules that use explicit `target: "name"` or are external crates.
 /// These don't need the `codanna::` prefix in filter strings.
-const EXTERNAL_TARGETS: &[&str] = &["cli", "tantivy", "pipeline", "semantic", "rag"];
+const EXTERNAL_TARGETS: &[&str] = &[
+    "cli", "tantivy", "pipeline", "semantic", "rag", "storage", "metrics", "analysis", "deps",
+    "shards",
+];
 
 /// Initialize logging with configuration.
 ///
@@ -79,12 +83,23 @@ pub fn init_with_config(config: &LoggingConfig) {
             EnvFilter::new(&filter_str)
         };
 
-        let fmt_layer = tracing_subscriber::fmt::layer()
-            .with_writer(std::io::stderr)
-            .with_target(true) // Show target for filtering visibility
-            .with_timer(CompactTime)
-            .with_level(true)
-            .with_filter(filter);
+        let fmt_layer = match config.format {
+            LogFormat::Text => tracing_subscriber::fmt::layer()
+                .with_writer(std::io::stderr)
+                .with_target(true) // Show target for filtering visibility
+                .with_timer(CompactTime)
+                .with_level(true)
+                .with_filter(filter)
+                .boxed(),
+            LogFormat::Json => tracing_subscriber::fmt::layer()
+                .json()
+                .with_writer(std::io::stderr)
+                .with_target(true)
+                .with_current_span(true)
+                .with_span_list(true)
+                .with_filter(filter)
+                .boxed(),
+        };
 
         tracing_subscriber::registry().with(fmt_layer).init();
     });
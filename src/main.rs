@@ -4,8 +4,9 @@
 //! Uses the cli module for argument parsing and command definitions.
 
 use clap::Parser;
-use codanna::cli::{Cli, Commands, RetrieveQuery};
+use codanna::cli::{Cli, Commands, DaemonAction, IndexAction, RetrieveQuery};
 use codanna::indexing::facade::IndexFacade;
+use codanna::io::{Budget, OutputFormat};
 use codanna::project_resolver::{
     providers::{
         csharp::CSharpProvider, go::GoProvider, java::JavaProvider, javascript::JavaScriptProvider,
@@ -14,8 +15,8 @@ use codanna::project_resolver::{
     },
     registry::SimpleProviderRegistry,
 };
-use codanna::storage::IndexMetadata;
-use codanna::{IndexPersistence, Settings};
+use codanna::storage::{IndexMetadata, LockWait};
+use codanna::{IndexError, IndexPersistence, Settings};
 use std::path::PathBuf;
 use std::sync::Arc;
 
@@ -197,8 +198,33 @@ fn seed_indexer_with_config_paths(
 async fn main() {
     let cli = Cli::parse();
 
+    // Completion scripts need neither settings nor an index
+    if let Commands::Completions { shell } = &cli.command {
+        let exit_code = codanna::cli::commands::completions::run(shell);
+        std::process::exit(exit_code as i32);
+    }
+
+    match OutputFormat::parse(cli.output_format.as_deref(), cli.template.clone()) {
+        Ok(format) => OutputFormat::set_default(format),
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::exit(2);
+        }
+    }
+    if let Some(budget) = Budget::from_limits(cli.max_tokens, cli.max_bytes) {
+        Budget::set_default(budget);
+    }
+    // The server outlives any indexing run, so it waits for the lock by default
+    LockWait::set_default(match (cli.wait, &cli.command) {
+        (Some(None), _) | (None, Commands::Serve { .. } | Commands::Daemon { .. }) => {
+            LockWait::Forever
+        }
+        (Some(Some(secs)), _) => LockWait::For(std::time::Duration::from_secs(secs)),
+        (None, _) => LockWait::No,
+    });
+
     // For index command, auto-initialize if needed (but not when using --config)
-    if matches!(cli.command, Commands::Index { .. }) && cli.config.is_none() {
+    if matches!(cli.command, Commands::Index { action: None, .. }) && cli.config.is_none() {
         if Settings::check_init().is_err() {
             // Auto-initialize for index command
             eprintln!("Initializing project configuration...");
@@ -241,6 +267,27 @@ async fn main() {
     // All logging goes to stderr to avoid polluting stdout (JSON output, piping)
     codanna::logging::init_with_config(&config.logging);
 
+    // A running daemon holds the index loaded already; hand the call to it
+    let forwarded = match &cli.command {
+        Commands::Mcp {
+            tool,
+            positional,
+            args,
+            json: false,
+            watch: false,
+            ..
+        } => codanna::cli::commands::daemon::forward(
+            &codanna::init::resolve_index_path(&config, cli.config.as_deref()),
+            tool,
+            positional,
+            args.as_deref(),
+        ),
+        _ => None,
+    };
+    if let Some(exit_code) = forwarded {
+        std::process::exit(exit_code as i32);
+    }
+
     // Determine resource requirements based on command type
     // Commands are categorized by what infrastructure they need:
     // - Thin: No index, no providers (Parse, McpTest, Benchmark)
@@ -248,7 +295,21 @@ async fn main() {
     // - Full: Index + providers (Retrieve, Mcp, Serve, Index)
     let needs_providers = !matches!(
         &cli.command,
-        Commands::Parse { .. } | Commands::McpTest { .. } | Commands::Benchmark { .. }
+        Commands::Parse { .. }
+            | Commands::McpTest { .. }
+            | Commands::Benchmark { .. }
+            | Commands::CompleteSymbols { .. }
+            | Commands::Daemon {
+                action: DaemonAction::Start {
+                    foreground: false,
+                    ..
+                } | DaemonAction::Stop
+                    | DaemonAction::Status { .. }
+            }
+            | Commands::Index {
+                action: Some(_),
+                ..
+            }
     );
 
     let needs_indexer = !matches!(
@@ -264,7 +325,20 @@ async fn main() {
             | Commands::Plugin { .. }
             | Commands::Documents { .. }
             | Commands::Profile { .. }
+            | Commands::Deps { .. }
+            | Commands::Completions { .. }
             | Commands::IndexParallel { .. }
+            | Commands::Daemon {
+                action: DaemonAction::Start {
+                    foreground: false,
+                    ..
+                } | DaemonAction::Stop
+                    | DaemonAction::Status { .. }
+            }
+            | Commands::Index {
+                action: Some(_),
+                ..
+            }
     );
 
     // Initialize project resolution providers (only if needed)
@@ -297,6 +371,9 @@ async fn main() {
     {
         config.indexing.parallelism = *t;
     }
+    if let Commands::Serve { fsync: true, .. } = &cli.command {
+        config.indexing.fsync = true;
+    }
 
     // Set up persistence based on config
     // Use global path resolution that handles --config properly
@@ -316,6 +393,7 @@ async fn main() {
             ..
         } | Commands::Index { .. }
             | Commands::Serve { .. }
+            | Commands::Daemon { .. }
     );
 
     // Determine if we need semantic search (ML model loading)
@@ -325,7 +403,7 @@ async fn main() {
             // Only these MCP tools need semantic search
             ["semantic_search_docs", "semantic_search_with_context"].contains(&tool.as_str())
         }
-        Commands::Index { .. } | Commands::Serve { .. } => true,
+        Commands::Index { .. } | Commands::Serve { .. } | Commands::Daemon { .. } => true,
         _ => false,
     };
 
@@ -364,6 +442,16 @@ async fn main() {
                         }
                         loaded
                     }
+                    Err(
+                        e @ (IndexError::IncompatibleIndex { .. } | IndexError::IndexLocked { .. }),
+                    ) => {
+                        // Opening it anyway would misread or clobber it, so stop here
+                        eprintln!("Error: {e}");
+                        for suggestion in e.recovery_suggestions() {
+                            eprintln!("Suggestion: {suggestion}");
+                        }
+                        std::process::exit(e.category().exit_code() as i32);
+                    }
                     Err(e) => {
                         eprintln!("Warning: Could not load index: {e}. Creating new index.");
                         IndexFacade::new(settings.clone()).expect("Failed to create IndexFacade")
@@ -424,11 +512,15 @@ async fn main() {
     let is_force_index = matches!(cli.command, Commands::Index { force: true, .. });
 
     // Progress is enabled by default from settings, can be disabled with --no-progress
+    // JSON progress events replace the progress bars
     let no_progress_flag = matches!(
         cli.command,
         Commands::Index {
             no_progress: true,
             ..
+        } | Commands::Index {
+            progress: Some(_),
+            ..
         }
     );
     let show_progress = config.indexing.show_progress && !no_progress_flag;
@@ -619,6 +711,7 @@ async fn main() {
             http,
             https,
             bind,
+            ..
         } => {
             use codanna::cli::commands::serve::{ServeArgs, run as run_serve};
             run_serve(
@@ -637,22 +730,83 @@ async fn main() {
             .await;
         }
 
+        Commands::Daemon { action } => {
+            use codanna::cli::commands::daemon;
+            let exit_code = match action {
+                DaemonAction::Start {
+                    foreground: true,
+                    watch_interval,
+                } => {
+                    daemon::run(
+                        &config,
+                        settings,
+                        indexer.expect("daemon requires indexer"),
+                        &index_path,
+                        watch_interval,
+                    )
+                    .await
+                }
+                DaemonAction::Start {
+                    foreground: false,
+                    watch_interval,
+                } => daemon::start(cli.config.as_deref(), &index_path, watch_interval),
+                DaemonAction::Stop => daemon::stop(&index_path),
+                DaemonAction::Status { json } => daemon::status(&index_path, json),
+            };
+            std::process::exit(exit_code as i32);
+        }
+
+        Commands::Index {
+            action: Some(IndexAction::Verify { repair, json }),
+            ..
+        } => {
+            let exit_code = codanna::cli::commands::index_verify::run(repair, json, &config);
+            std::process::exit(exit_code as i32);
+        }
+
+        Commands::Index {
+            action: Some(IndexAction::Publish { output }),
+            ..
+        } => {
+            let exit_code = codanna::cli::commands::artifact::run_publish(&output, &config);
+            std::process::exit(exit_code as i32);
+        }
+
+        Commands::Index {
+            action: Some(IndexAction::Mount { source, json }),
+            ..
+        } => {
+            let exit_code = codanna::cli::commands::artifact::run_mount(&source, json, &config);
+            std::process::exit(exit_code as i32);
+        }
+
         Commands::Index {
             paths,
             force,
             no_progress,
+            progress: progress_format,
             dry_run,
             max_files,
             ..
         } => {
             use codanna::cli::commands::index::{IndexArgs, run as run_index};
+            let progress_json = match progress_format.as_deref() {
+                None => false,
+                Some(format) if format.eq_ignore_ascii_case("json") => true,
+                Some(other) => {
+                    eprintln!("Error: Unknown progress format '{other}'");
+                    eprintln!("Suggestion: Use --progress json");
+                    std::process::exit(codanna::io::ExitCode::ConfigError as i32);
+                }
+            };
             // Progress enabled by default from settings, --no-progress overrides
-            let progress = config.indexing.show_progress && !no_progress;
+            let progress = config.indexing.show_progress && !no_progress && !progress_json;
             run_index(
                 IndexArgs {
                     paths,
                     force,
                     progress,
+                    progress_json,
                     dry_run,
                     max_files,
                     cli_config: cli.config.clone(),
@@ -730,7 +884,25 @@ async fn main() {
             .await;
         }
 
-        Commands::Benchmark { language, file } => {
+        Commands::Benchmark {
+            language,
+            file,
+            check,
+            save_baseline,
+            baseline,
+            tolerance,
+        } => {
+            if check || save_baseline {
+                let options = codanna::cli::commands::benchmark::SuiteOptions {
+                    check,
+                    save_baseline,
+                    baseline,
+                    tolerance,
+                };
+                let exit_code =
+                    codanna::cli::commands::benchmark::run_suite(&language, &options, &config);
+                std::process::exit(exit_code as i32);
+            }
             codanna::cli::commands::benchmark::run(&language, file);
         }
 
@@ -746,6 +918,59 @@ async fn main() {
             codanna::cli::commands::profile::run(action);
         }
 
+        Commands::Export { action } => {
+            let exit_code = codanna::cli::commands::export::run(
+                action,
+                indexer.as_ref().expect("export requires indexer"),
+            );
+            std::process::exit(exit_code as i32);
+        }
+
+        Commands::Analyze { action } => {
+            let exit_code = codanna::cli::commands::analyze::run(
+                action,
+                indexer.as_ref().expect("analyze requires indexer"),
+            );
+            std::process::exit(exit_code as i32);
+        }
+
+        Commands::Stats { json } => {
+            let exit_code = codanna::cli::commands::stats::run(
+                json,
+                indexer.as_ref().expect("stats requires indexer"),
+            );
+            std::process::exit(exit_code as i32);
+        }
+
+        Commands::Explore => {
+            let exit_code = codanna::cli::commands::explore::run(
+                indexer.as_ref().expect("explore requires indexer"),
+            );
+            std::process::exit(exit_code as i32);
+        }
+
+        Commands::Completions { .. } => unreachable!("handled before loading settings"),
+
+        Commands::CompleteSymbols { prefix, limit } => {
+            let exit_code = codanna::cli::commands::completions::run_symbols(
+                &prefix,
+                limit,
+                indexer.as_ref().expect("complete-symbols requires indexer"),
+            );
+            std::process::exit(exit_code as i32);
+        }
+
+        Commands::Repl => {
+            let exit_code =
+                codanna::cli::commands::repl::run(indexer.as_ref().expect("repl requires indexer"));
+            std::process::exit(exit_code as i32);
+        }
+
+        Commands::Deps { action } => {
+            let exit_code = codanna::cli::commands::deps::run(action, &config);
+            std::process::exit(exit_code as i32);
+        }
+
         Commands::IndexParallel {
             paths,
             force,
@@ -0,0 +1,282 @@
+//! Long-lived daemon answering tool calls for thin CLI clients
+//!
+//! `codanna daemon start` loads the index once, keeps it warm and current
+//! with the watchers of `serve --watch`, and listens on a unix socket next
+//! to the index. While it runs, `codanna mcp <tool>` hands its call to the
+//! daemon instead of loading the index itself, which on big repositories
+//! is most of a command's run time.
+//!
+//! Each connection carries one [`DaemonRequest`] as a JSON line and gets
+//! one [`DaemonReply`] line back. Other platforms have no daemon: clients
+//! find none and starting one fails.
+
+use super::CodeIntelligenceServer;
+#[cfg(unix)]
+use super::{SELF_BUDGETED_TOOLS, argument_budget, fit_result, result_text};
+use serde::{Deserialize, Serialize};
+#[cfg(unix)]
+use std::io::{BufRead, BufReader, Write};
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+#[cfg(unix)]
+use std::sync::Arc;
+#[cfg(unix)]
+use std::sync::atomic::{AtomicU64, Ordering};
+#[cfg(unix)]
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
+#[cfg(unix)]
+use tokio::net::UnixListener;
+#[cfg(unix)]
+use tokio::sync::Notify;
+
+/// Socket file below the index directory
+pub const SOCKET_FILE: &str = "daemon.sock";
+
+/// Log of a daemon started in the background, below the index directory
+pub const LOG_FILE: &str = "daemon.log";
+
+/// Socket of the daemon serving the index at `index_path`
+pub fn socket_path(index_path: &Path) -> PathBuf {
+    index_path.join(SOCKET_FILE)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "request", rename_all = "snake_case")]
+pub enum DaemonRequest {
+    /// Run an MCP tool, as `tools/call` would
+    Tool {
+        tool: String,
+        #[serde(default)]
+        arguments: serde_json::Map<String, serde_json::Value>,
+    },
+    Status,
+    Shutdown,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "reply", rename_all = "snake_case")]
+pub enum DaemonReply {
+    /// Text of a tool result; `is_error` for failed calls and unknown tools
+    Tool {
+        is_error: bool,
+        text: String,
+    },
+    Status(DaemonStatus),
+    ShuttingDown,
+    /// The request could not be read
+    Invalid {
+        message: String,
+    },
+}
+
+/// What `codanna daemon status` reports
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DaemonStatus {
+    pub pid: u32,
+    pub index_path: PathBuf,
+    /// Unix time the daemon started listening
+    pub started_at: u64,
+    /// Tool calls answered so far
+    pub requests: u64,
+    pub symbols: usize,
+    pub files: u32,
+}
+
+/// Send `request` to the daemon serving `index_path`.
+///
+/// `None` when no daemon listens there, including a socket left behind by
+/// one that crashed.
+#[cfg(unix)]
+pub fn send(index_path: &Path, request: &DaemonRequest) -> Option<std::io::Result<DaemonReply>> {
+    let stream = UnixStream::connect(socket_path(index_path)).ok()?;
+    Some(exchange(stream, request))
+}
+
+#[cfg(not(unix))]
+pub fn send(_index_path: &Path, _request: &DaemonRequest) -> Option<std::io::Result<DaemonReply>> {
+    None
+}
+
+#[cfg(unix)]
+fn exchange(mut stream: UnixStream, request: &DaemonRequest) -> std::io::Result<DaemonReply> {
+    let mut line = serde_json::to_string(request)?;
+    line.push('\n');
+    stream.write_all(line.as_bytes())?;
+    stream.shutdown(std::net::Shutdown::Write)?;
+
+    let mut reply = String::new();
+    BufReader::new(stream).read_line(&mut reply)?;
+    Ok(serde_json::from_str(&reply)?)
+}
+
+#[cfg(unix)]
+struct Daemon {
+    server: CodeIntelligenceServer,
+    index_path: PathBuf,
+    started_at: u64,
+    requests: AtomicU64,
+    shutdown: Notify,
+}
+
+/// Answer requests on the socket of `index_path` until a client asks the
+/// daemon to shut down or the process is interrupted.
+///
+/// Fails when another daemon already serves the index.
+#[cfg(unix)]
+pub async fn serve(server: CodeIntelligenceServer, index_path: &Path) -> std::io::Result<()> {
+    let socket = socket_path(index_path);
+    if UnixStream::connect(&socket).is_ok() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::AddrInUse,
+            format!("a daemon already serves {}", index_path.display()),
+        ));
+    }
+    // Left behind by a daemon that did not shut down cleanly
+    if socket.exists() {
+        std::fs::remove_file(&socket)?;
+    }
+    let listener = UnixListener::bind(&socket)?;
+    let daemon = Arc::new(Daemon {
+        server,
+        index_path: index_path.to_path_buf(),
+        started_at: crate::indexing::get_utc_timestamp(),
+        requests: AtomicU64::new(0),
+        shutdown: Notify::new(),
+    });
+    crate::log_event!("daemon", "listening", "{}", socket.display());
+
+    let result = loop {
+        tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => {
+                    let daemon = daemon.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = daemon.answer(stream).await {
+                            tracing::debug!("[daemon] connection failed: {e}");
+                        }
+                    });
+                }
+                Err(e) => break Err(e),
+            },
+            _ = daemon.shutdown.notified() => break Ok(()),
+            _ = tokio::signal::ctrl_c() => break Ok(()),
+        }
+    };
+    let _ = std::fs::remove_file(&socket);
+    crate::log_event!("daemon", "stopped");
+    result
+}
+
+#[cfg(not(unix))]
+pub async fn serve(_server: CodeIntelligenceServer, _index_path: &Path) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "the daemon needs unix domain sockets",
+    ))
+}
+
+#[cfg(unix)]
+impl Daemon {
+    async fn answer(&self, stream: tokio::net::UnixStream) -> std::io::Result<()> {
+        let (reader, mut writer) = stream.into_split();
+        let mut line = String::new();
+        tokio::io::BufReader::new(reader)
+            .read_line(&mut line)
+            .await?;
+
+        let reply = match serde_json::from_str(&line) {
+            Ok(request) => self.reply(request).await,
+            Err(e) => DaemonReply::Invalid {
+                message: format!("unreadable request: {e}"),
+            },
+        };
+        let mut line = serde_json::to_string(&reply)?;
+        line.push('\n');
+        writer.write_all(line.as_bytes()).await?;
+        writer.shutdown().await
+    }
+
+    async fn reply(&self, request: DaemonRequest) -> DaemonReply {
+        match request {
+            DaemonRequest::Tool { tool, arguments } => {
+                self.requests.fetch_add(1, Ordering::Relaxed);
+                let budget = argument_budget(&arguments)
+                    .filter(|_| !SELF_BUDGETED_TOOLS.contains(&tool.as_str()));
+                let start = std::time::Instant::now();
+                let result = self.server.run_tool(&tool, arguments).await;
+                crate::metrics::observe_query(&tool, start.elapsed());
+                match result {
+                    Ok(result) => {
+                        let result = match budget {
+                            Some(budget) => fit_result(result, budget),
+                            None => result,
+                        };
+                        DaemonReply::Tool {
+                            is_error: result.is_error.unwrap_or(false),
+                            text: result_text(&result),
+                        }
+                    }
+                    Err(message) => DaemonReply::Tool {
+                        is_error: true,
+                        text: message,
+                    },
+                }
+            }
+            DaemonRequest::Status => {
+                let facade = self.server.facade.read().await;
+                DaemonReply::Status(DaemonStatus {
+                    pid: std::process::id(),
+                    index_path: self.index_path.clone(),
+                    started_at: self.started_at,
+                    requests: self.requests.load(Ordering::Relaxed),
+                    symbols: facade.symbol_count(),
+                    files: facade.file_count(),
+                })
+            }
+            DaemonRequest::Shutdown => {
+                self.shutdown.notify_one();
+                DaemonReply::ShuttingDown
+            }
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_requests_and_replies_are_tagged_json_lines() {
+        let request = DaemonRequest::Tool {
+            tool: "find_symbol".to_string(),
+            arguments: serde_json::json!({"name": "parse"})
+                .as_object()
+                .cloned()
+                .unwrap(),
+        };
+        let line = serde_json::to_string(&request).unwrap();
+        assert_eq!(
+            line,
+            r#"{"request":"tool","tool":"find_symbol","arguments":{"name":"parse"}}"#
+        );
+        let status: DaemonRequest = serde_json::from_str(r#"{"request":"status"}"#).unwrap();
+        assert!(matches!(status, DaemonRequest::Status));
+
+        let reply: DaemonReply =
+            serde_json::from_str(r#"{"reply":"tool","is_error":false,"text":"Found 1 symbol"}"#)
+                .unwrap();
+        assert!(
+            matches!(reply, DaemonReply::Tool { is_error: false, text } if text == "Found 1 symbol")
+        );
+    }
+
+    #[test]
+    fn test_no_daemon_behind_a_stale_socket() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        // Bound and dropped: the file stays but nobody listens
+        drop(std::os::unix::net::UnixListener::bind(socket_path(temp_dir.path())).unwrap());
+        assert!(socket_path(temp_dir.path()).exists());
+        assert!(send(temp_dir.path(), &DaemonRequest::Status).is_none());
+    }
+}
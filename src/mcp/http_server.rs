@@ -49,6 +49,9 @@ pub async fn serve_http(config: crate::Settings, watch: bool, bind: String) -> a
         IndexFacade::new(settings.clone()).expect("Failed to create IndexFacade")
     };
     let indexer = Arc::new(RwLock::new(facade));
+    if config.server.warm_up {
+        crate::mcp::warm::spawn_warm_up(indexer.clone());
+    }
 
     // Create cancellation token for coordinated shutdown
     let ct = CancellationToken::new();
@@ -429,6 +432,22 @@ pub async fn serve_http(config: crate::Settings, watch: bool, bind: String) -> a
         .layer(axum::middleware::from_fn(validate_bearer_token));
 
     // Create main router - OAuth endpoints FIRST (no auth), then MCP endpoints (with auth)
+    // Metrics handler refreshes index gauges on every scrape
+    let metrics_indexer = indexer.clone();
+    let metrics = move || {
+        let indexer = metrics_indexer.clone();
+        async move {
+            let body = crate::metrics::scrape(&*indexer.read().await);
+            (
+                [(
+                    axum::http::header::CONTENT_TYPE,
+                    crate::metrics::CONTENT_TYPE,
+                )],
+                body,
+            )
+        }
+    };
+
     let router = Router::new()
         // OAuth endpoints - NO authentication required
         .route(
@@ -440,14 +459,42 @@ pub async fn serve_http(config: crate::Settings, watch: bool, bind: String) -> a
         .route("/oauth/authorize", axum::routing::get(oauth_authorize))
         // Health check - NO authentication required
         .route("/health", axum::routing::get(health_check))
+        // Prometheus metrics - NO authentication required
+        .route("/metrics", axum::routing::get(metrics))
         // MCP endpoint - Bearer token authentication required
         .merge(protected_mcp_router);
 
+    // GraphQL endpoint over the same facade - Bearer token authentication required
+    #[cfg(feature = "graphql")]
+    let router = router.merge(
+        crate::graphql::router(indexer.clone())
+            .layer(axum::middleware::from_fn(validate_bearer_token)),
+    );
+
+    // REST API - enabled only when an API token is configured
+    let router = match config.server.api_token.clone().filter(|t| !t.is_empty()) {
+        Some(token) => router.merge(crate::mcp::rest::router(indexer.clone(), token)),
+        None => router,
+    };
+
     // Bind and serve
     let listener = tokio::net::TcpListener::bind(&bind).await?;
     eprintln!("HTTP MCP server listening on http://{bind}");
     eprintln!("MCP endpoint: http://{bind}/mcp");
     eprintln!("Health check: http://{bind}/health");
+    eprintln!("Metrics: http://{bind}/metrics");
+    if config
+        .server
+        .api_token
+        .as_deref()
+        .is_some_and(|t| !t.is_empty())
+    {
+        eprintln!("REST API: http://{bind}/api/v1 (Bearer token required)");
+    } else {
+        eprintln!("REST API: disabled (set CI_SERVER__API_TOKEN to enable)");
+    }
+    #[cfg(feature = "graphql")]
+    eprintln!("GraphQL endpoint: http://{bind}/graphql");
     eprintln!("Press Ctrl+C to stop the server");
 
     // Create server future
@@ -52,6 +52,9 @@ pub async fn serve_https(config: crate::Settings, watch: bool, bind: String) ->
         IndexFacade::new(settings.clone()).expect("Failed to create IndexFacade")
     };
     let indexer = Arc::new(RwLock::new(facade));
+    if config.server.warm_up {
+        crate::mcp::warm::spawn_warm_up(indexer.clone());
+    }
 
     // Create cancellation token for graceful shutdown
     let ct = CancellationToken::new();
@@ -259,6 +262,22 @@ pub async fn serve_https(config: crate::Settings, watch: bool, bind: String) ->
         .layer(axum::middleware::from_fn(log_requests));
 
     // Create main router - OAuth endpoints available but optional for HTTPS
+    // Metrics handler refreshes index gauges on every scrape
+    let metrics_indexer = indexer.clone();
+    let metrics = move || {
+        let indexer = metrics_indexer.clone();
+        async move {
+            let body = crate::metrics::scrape(&*indexer.read().await);
+            (
+                [(
+                    axum::http::header::CONTENT_TYPE,
+                    crate::metrics::CONTENT_TYPE,
+                )],
+                body,
+            )
+        }
+    };
+
     let router = Router::new()
         // OAuth endpoints - NO authentication required
         .route(
@@ -270,6 +289,8 @@ pub async fn serve_https(config: crate::Settings, watch: bool, bind: String) ->
         .route("/oauth/authorize", axum::routing::get(oauth_authorize))
         // Health check - NO authentication required
         .route("/health", axum::routing::get(health_check))
+        // Prometheus metrics - NO authentication required
+        .route("/metrics", axum::routing::get(metrics))
         // MCP endpoint - No authentication required (TLS provides transport security)
         .merge(mcp_router_with_logging);
 
@@ -289,6 +310,7 @@ pub async fn serve_https(config: crate::Settings, watch: bool, bind: String) ->
     eprintln!("HTTPS MCP server listening on https://{bind}");
     eprintln!("MCP endpoint: https://{bind}/mcp");
     eprintln!("Health check: https://{bind}/health");
+    eprintln!("Metrics: https://{bind}/metrics");
     eprintln!();
     eprintln!("Using self-signed certificate. Clients will show security warnings.");
     eprintln!("To trust the certificate, visit https://{bind} in your browser first");
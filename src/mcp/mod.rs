@@ -16,11 +16,19 @@
 //!    - No separate process needed
 //!    - Direct access to already-loaded index
 //!    - Most memory efficient for CLI operations
+//!
+//! 3. **Daemon Mode**: Run with `codanna daemon start`
+//!    - Loads index once and keeps it current
+//!    - Answers `codanna mcp` calls over a unix socket (see [`daemon`])
 
 pub mod client;
+pub mod daemon;
 pub mod http_server;
 pub mod https_server;
 pub mod notifications;
+#[cfg(feature = "http-server")]
+pub mod rest;
+pub mod warm;
 
 use rmcp::{
     ServerHandler,
@@ -28,7 +36,7 @@ use rmcp::{
     model::{CustomNotification, CustomRequest, CustomResult, ErrorCode, ErrorData as McpError, *},
     schemars,
     service::{Peer, RequestContext, RoleServer, ServiceError},
-    tool, tool_handler, tool_router,
+    tool, tool_router,
 };
 use serde::{Deserialize, Serialize};
 use serde_json;
@@ -37,6 +45,9 @@ use tokio::sync::{Mutex, RwLock};
 
 use crate::documents::{DocumentStore, SearchQuery as DocSearchQuery};
 use crate::indexing::facade::IndexFacade;
+use crate::io::Budget;
+use crate::relationship::{Confidence, RelationshipMetadata};
+use crate::types::PositionEncoding;
 use crate::{Settings, Symbol};
 
 /// Generate guidance for MCP tool responses
@@ -80,6 +91,15 @@ pub struct FindSymbolRequest {
     pub lang: Option<String>,
 }
 
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct ResolveSymbolsRequest {
+    /// Symbol names, bare or qualified by module path (e.g., "crate::parser::parse", "app.models.User")
+    pub names: Vec<String>,
+    /// Filter by programming language (e.g., "rust", "python", "typescript", "php")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+}
+
 #[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
 pub struct GetCallsRequest {
     /// Name of the function to analyze (use symbol_id for unambiguous lookup)
@@ -88,6 +108,10 @@ pub struct GetCallsRequest {
     /// Symbol ID for direct lookup (recommended to avoid ambiguity)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub symbol_id: Option<u32>,
+    /// Leave out calls below this confidence: "high" drops calls inferred
+    /// from overloadable operators like `a + b` (default: keep all)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_confidence: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
@@ -98,6 +122,123 @@ pub struct FindCallersRequest {
     /// Symbol ID for direct lookup (recommended to avoid ambiguity)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub symbol_id: Option<u32>,
+    /// Leave out calls below this confidence: "high" drops calls inferred
+    /// from overloadable operators like `a + b` (default: keep all)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_confidence: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct CallHierarchyRequest {
+    /// Name of the function at the root of the hierarchy (use symbol_id for unambiguous lookup)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub function_name: Option<String>,
+    /// Symbol ID for direct lookup (recommended to avoid ambiguity)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol_id: Option<u32>,
+    /// "incoming" for callers (default) or "outgoing" for callees
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub direction: Option<String>,
+    /// Levels to expand (default: 3)
+    #[serde(default = "default_depth")]
+    pub depth: u32,
+}
+
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct TypeHierarchyRequest {
+    /// Name of the class, interface, or trait (use symbol_id for unambiguous lookup)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub type_name: Option<String>,
+    /// Symbol ID for direct lookup (recommended to avoid ambiguity)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol_id: Option<u32>,
+    /// Levels to expand in each direction (default: 3)
+    #[serde(default = "default_depth")]
+    pub depth: u32,
+}
+
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct FileOutlineRequest {
+    /// File path, relative to the workspace root or absolute
+    pub file_path: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct SymbolAtRequest {
+    /// File path, relative to the workspace root or absolute
+    pub file_path: String,
+    /// Line number (1-based)
+    pub line: u32,
+    /// Column (1-based, in bytes unless `encoding` says otherwise)
+    pub column: u32,
+    /// Unit of `column`: "byte" (default), "char", or "utf-16" as sent by LSP clients
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct GetSymbolContextRequest {
+    /// Name of the symbol (use symbol_id for unambiguous lookup)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol_name: Option<String>,
+    /// Symbol ID for direct lookup
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol_id: Option<u32>,
+    /// Filter by programming language when looking up by name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+    /// Most callers, callees, and implementation relations listed each (default: 10)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_related: Option<usize>,
+    /// Most lines of definition source included (default: 80)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_source_lines: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct SummarizeModuleRequest {
+    /// Module path (e.g., "crate::storage", "app.models") or directory of indexed files (e.g., "src/storage")
+    pub target: String,
+    /// Approximate size limit of the summary in tokens (default: 2000)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct GrepRequest {
+    /// Regular expression searched line by line in indexed files
+    pub pattern: String,
+    /// Maximum number of matching lines (default: 10)
+    #[serde(default = "default_limit")]
+    pub limit: u32,
+    /// Match regardless of case
+    #[serde(default)]
+    pub ignore_case: bool,
+    /// Unit of the reported columns: "byte" (default), "char", or "utf-16" as used by LSP clients
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct HoverRequest {
+    /// Name of the symbol (use symbol_id for unambiguous lookup)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol_name: Option<String>,
+    /// Symbol ID for direct lookup
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol_id: Option<u32>,
+    /// File path of a cursor position, instead of a name or ID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_path: Option<String>,
+    /// Line of the cursor position (1-based)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<u32>,
+    /// Column of the cursor position (1-based, in bytes unless `encoding` says otherwise)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub column: Option<u32>,
+    /// Unit of `column`: "byte" (default), "char", or "utf-16" as sent by LSP clients
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
@@ -111,6 +252,10 @@ pub struct AnalyzeImpactRequest {
     /// Maximum depth to search (default: 3)
     #[serde(default = "default_depth")]
     pub max_depth: u32,
+    /// Follow only calls that always run, leaving out callers that reach
+    /// the symbol behind an `if`, loop or closure (default: false)
+    #[serde(default)]
+    pub unconditional_only: bool,
 }
 
 #[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
@@ -129,6 +274,120 @@ pub struct SearchSymbolsRequest {
     /// Filter by programming language (e.g., "rust", "python", "typescript", "php")
     #[serde(skip_serializing_if = "Option::is_none")]
     pub lang: Option<String>,
+    /// File the caller is working in; nearby results (same file, module,
+    /// directory, or imports) rank higher
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context_file: Option<String>,
+    /// Match the query anywhere in symbol names, ignoring case, instead of
+    /// full-text search (finds `path` in `normalize_file_path`)
+    #[serde(default)]
+    pub substring: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct BatchQueryRequest {
+    /// Sub-queries, run in order; at most 32
+    pub queries: Vec<SubQuery>,
+    /// Approximate size limit of the response in tokens, shared evenly by
+    /// sub-queries without a limit of their own
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<usize>,
+    /// Size limit of the response in bytes, shared like max_tokens
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_bytes: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct SubQuery {
+    /// Key of this sub-query's result in the response
+    pub id: String,
+    /// Tool to run (e.g., "search_symbols", "find_symbol", "find_callers", "get_calls")
+    pub tool: String,
+    /// Arguments exactly as the tool takes them when called on its own
+    #[serde(default)]
+    pub arguments: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Most sub-queries one batch_query call runs
+const MAX_BATCH_QUERIES: usize = 32;
+
+/// Declares the tools a batch_query sub-query can run: their names in
+/// `BATCH_TOOLS` and their dispatch in `run_batch_tool`, from one list
+macro_rules! batch_tools {
+    ($($tool:ident),* $(,)?) => {
+        /// Tools a batch_query sub-query can run
+        const BATCH_TOOLS: &[&str] = &[$(stringify!($tool)),*];
+
+        impl CodeIntelligenceServer {
+            /// Run one of `BATCH_TOOLS` by name
+            async fn run_batch_tool(
+                &self,
+                tool: &str,
+                arguments: serde_json::Map<String, serde_json::Value>,
+            ) -> Result<CallToolResult, String> {
+                $(
+                    if tool == stringify!($tool) {
+                        let parameters = parse_arguments(arguments)?;
+                        return self.$tool(parameters).await.map_err(|e| e.message.to_string());
+                    }
+                )*
+                Err(format!("Unknown tool '{tool}'"))
+            }
+        }
+    };
+}
+
+batch_tools![
+    find_symbol,
+    resolve_symbols,
+    search_symbols,
+    get_calls,
+    find_callers,
+    analyze_impact,
+    call_hierarchy,
+    type_hierarchy,
+    file_outline,
+    get_symbol_context,
+    summarize_module,
+    symbol_at,
+    grep,
+    hover,
+    semantic_search_docs,
+    semantic_search_with_context,
+];
+
+/// Tool arguments as the parameters of the tool that takes them
+fn parse_arguments<T: serde::de::DeserializeOwned>(
+    arguments: serde_json::Map<String, serde_json::Value>,
+) -> Result<Parameters<T>, String> {
+    serde_json::from_value(serde_json::Value::Object(arguments))
+        .map(Parameters)
+        .map_err(|e| format!("Invalid arguments: {e}"))
+}
+
+/// Name the tools batch_query can run in its description
+fn describe_batch_tools(tool: &mut Tool) {
+    if tool.name != "batch_query" {
+        return;
+    }
+    let description = tool.description.as_deref().unwrap_or_default();
+    tool.description = Some(
+        format!(
+            "{description} Sub-queries can run: {}.",
+            BATCH_TOOLS.join(", ")
+        )
+        .into(),
+    );
+}
+
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct RunSavedQueryRequest {
+    /// Name of a query defined under [queries] in settings (omit to list them)
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Values for {placeholder} parameters in the saved query
+    #[serde(default)]
+    pub params: std::collections::HashMap<String, String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
@@ -188,6 +447,107 @@ fn default_context_limit() -> u32 {
     5
 }
 
+/// Parse a `min_confidence` parameter; no value keeps every edge.
+fn parse_min_confidence(value: Option<&str>) -> Result<Confidence, String> {
+    match value {
+        None => Ok(Confidence::Low),
+        Some(value) => Confidence::parse(value).ok_or_else(|| {
+            format!("Error: min_confidence must be \"low\" or \"high\", got \"{value}\"")
+        }),
+    }
+}
+
+fn confidence_of(metadata: Option<&RelationshipMetadata>) -> Confidence {
+    metadata.map_or(Confidence::High, RelationshipMetadata::confidence)
+}
+
+/// Note for a call inferred from an operator, empty for any other call
+fn operator_note(metadata: Option<&RelationshipMetadata>) -> String {
+    match metadata.and_then(|meta| meta.attribute("operator")) {
+        Some(operator) => format!(" (operator {operator}, low confidence)"),
+        None => String::new(),
+    }
+}
+
+/// Tools that fit `max_tokens`/`max_bytes` themselves instead of having
+/// their output cut after the fact
+const SELF_BUDGETED_TOOLS: &[&str] = &["summarize_module", "batch_query"];
+
+/// Output budget requested with the `max_tokens`/`max_bytes` arguments
+/// every tool accepts
+fn argument_budget(arguments: &serde_json::Map<String, serde_json::Value>) -> Option<Budget> {
+    let limit = |key: &str| {
+        arguments
+            .get(key)
+            .and_then(serde_json::Value::as_u64)
+            .map(|limit| limit as usize)
+    };
+    Budget::from_limits(limit("max_tokens"), limit("max_bytes"))
+}
+
+/// `result` with each text cut to `budget`. JSON text stays valid JSON and
+/// drops its trailing results; other text keeps its leading sections.
+fn fit_result(mut result: CallToolResult, budget: Budget) -> CallToolResult {
+    for content in &mut result.content {
+        if let RawContent::Text(text) = &mut content.raw {
+            text.text = fit_text(&text.text, budget);
+        }
+    }
+    result
+}
+
+fn fit_text(text: &str, budget: Budget) -> String {
+    let json: Option<serde_json::Value> = if text.trim_start().starts_with(['{', '[']) {
+        serde_json::from_str(text).ok()
+    } else {
+        None
+    };
+    match json {
+        Some(mut value) => {
+            budget.fit_value(&mut value);
+            serde_json::to_string_pretty(&value).unwrap_or_else(|_| text.to_string())
+        }
+        None => budget.fit_text(text).0,
+    }
+}
+
+/// Declare the `max_tokens`/`max_bytes` arguments in a tool's input schema,
+/// unless the tool declares them itself
+fn add_budget_arguments(tool: &mut Tool) {
+    let schema = Arc::make_mut(&mut tool.input_schema);
+    let Some(properties) = schema
+        .entry("properties")
+        .or_insert_with(|| serde_json::json!({}))
+        .as_object_mut()
+    else {
+        return;
+    };
+    properties.entry("max_tokens").or_insert_with(|| {
+        serde_json::json!({
+            "type": "integer",
+            "minimum": 1,
+            "description": "Approximate size limit of the response in tokens; the highest-ranked results are kept and the response says what was omitted"
+        })
+    });
+    properties.entry("max_bytes").or_insert_with(|| {
+        serde_json::json!({
+            "type": "integer",
+            "minimum": 1,
+            "description": "Size limit of the response in bytes, applied like max_tokens"
+        })
+    });
+}
+
+/// Text content of a tool result, one content item per line
+fn result_text(result: &CallToolResult) -> String {
+    result
+        .content
+        .iter()
+        .filter_map(|content| content.as_text().map(|text| text.text.as_str()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 #[derive(Clone)]
 pub struct CodeIntelligenceServer {
     pub facade: Arc<RwLock<IndexFacade>>,
@@ -295,6 +655,23 @@ impl CodeIntelligenceServer {
         };
 
         if symbols.is_empty() {
+            if !name.starts_with("symbol_id:") {
+                let external = indexer.find_external_symbols(&name, lang.as_deref());
+                if !external.is_empty() {
+                    let mut result = format!(
+                        "Found {} symbol(s) named '{}' in dependencies:\n\n",
+                        external.len(),
+                        name
+                    );
+                    for symbol in &external {
+                        result.push_str(&format!("{symbol}\n\n"));
+                    }
+                    return Ok(CallToolResult::success(vec![Content::text(
+                        result.trim_end().to_string(),
+                    )]));
+                }
+            }
+
             let mut output = format!("No symbols found with name: {name}");
             // Add guidance for no results
             if let Some(guidance) = generate_mcp_guidance(indexer.settings(), "find_symbol", 0) {
@@ -344,6 +721,11 @@ impl CodeIntelligenceServer {
                         doc_preview.join(" ")
                     };
                     result.push_str(&format!("Documentation: {preview}\n"));
+                } else if let Some(generated) = indexer.generated_description(symbol) {
+                    result.push_str(&format!(
+                        "Description (generated by {}): {}\n",
+                        generated.model, generated.text
+                    ));
                 }
 
                 // Add relationship summary
@@ -487,6 +869,11 @@ impl CodeIntelligenceServer {
                         doc_preview.join(" ")
                     };
                     result.push_str(&format!("Documentation: {preview}\n"));
+                } else if let Some(generated) = indexer.generated_description(symbol) {
+                    result.push_str(&format!(
+                        "Description (generated by {}): {}\n",
+                        generated.model, generated.text
+                    ));
                 }
 
                 if let Some(ref sig) = symbol.signature {
@@ -515,8 +902,13 @@ impl CodeIntelligenceServer {
         Parameters(GetCallsRequest {
             function_name,
             symbol_id,
+            min_confidence,
         }): Parameters<GetCallsRequest>,
     ) -> Result<CallToolResult, McpError> {
+        let min_confidence = match parse_min_confidence(min_confidence.as_deref()) {
+            Ok(confidence) => confidence,
+            Err(message) => return Ok(CallToolResult::success(vec![Content::text(message)])),
+        };
         let indexer = self.facade.read().await;
 
         // Get the symbol either by ID or by name
@@ -573,7 +965,11 @@ impl CodeIntelligenceServer {
         };
 
         // Get calls for this specific symbol
-        let all_called_with_metadata = indexer.get_called_functions_with_metadata(symbol.id);
+        let all_called_with_metadata: Vec<_> = indexer
+            .get_called_functions_with_metadata(symbol.id)
+            .into_iter()
+            .filter(|(_, metadata)| confidence_of(metadata.as_ref()) >= min_confidence)
+            .collect();
 
         if all_called_with_metadata.is_empty() {
             let mut output = format!("{identifier} doesn't call any functions");
@@ -633,8 +1029,12 @@ impl CodeIntelligenceServer {
             };
 
             result.push_str(&format!(
-                "  -> {:?} {} at {}:{}\n",
-                callee.kind, call_display, callee.file_path, call_line
+                "  -> {:?} {} at {}:{}{}\n",
+                callee.kind,
+                call_display,
+                callee.file_path,
+                call_line,
+                operator_note(metadata.as_ref())
             ));
             if let Some(ref sig) = callee.signature {
                 result.push_str(&format!("     Signature: {sig}\n"));
@@ -660,8 +1060,13 @@ impl CodeIntelligenceServer {
         Parameters(FindCallersRequest {
             function_name,
             symbol_id,
+            min_confidence,
         }): Parameters<FindCallersRequest>,
     ) -> Result<CallToolResult, McpError> {
+        let min_confidence = match parse_min_confidence(min_confidence.as_deref()) {
+            Ok(confidence) => confidence,
+            Err(message) => return Ok(CallToolResult::success(vec![Content::text(message)])),
+        };
         let indexer = self.facade.read().await;
 
         // Get the symbol either by ID or by name
@@ -717,7 +1122,11 @@ impl CodeIntelligenceServer {
         };
 
         // Get callers for THIS SPECIFIC symbol only (no aggregation)
-        let all_callers_with_metadata = indexer.get_calling_functions_with_metadata(symbol.id);
+        let all_callers_with_metadata: Vec<_> = indexer
+            .get_calling_functions_with_metadata(symbol.id)
+            .into_iter()
+            .filter(|(_, metadata)| confidence_of(metadata.as_ref()) >= min_confidence)
+            .collect();
 
         if all_callers_with_metadata.is_empty() {
             let mut output = format!("No functions call {identifier}");
@@ -780,8 +1189,13 @@ impl CodeIntelligenceServer {
             };
 
             result.push_str(&format!(
-                "  <- {:?} {} at {}:{}{}\n",
-                caller.kind, caller.name, caller.file_path, call_line, call_info
+                "  <- {:?} {} at {}:{}{}{}\n",
+                caller.kind,
+                caller.name,
+                caller.file_path,
+                call_line,
+                call_info,
+                operator_note(metadata.as_ref())
             ));
 
             if let Some(ref sig) = caller.signature {
@@ -801,6 +1215,484 @@ impl CodeIntelligenceServer {
         Ok(CallToolResult::success(vec![Content::text(result)]))
     }
 
+    #[tool(
+        description = "Show the call hierarchy of a function as a tree: its callers (direction: incoming) or callees (direction: outgoing), expanded up to `depth` levels, with the line of every call site.\n\nRecursive calls are marked and not expanded again."
+    )]
+    pub async fn call_hierarchy(
+        &self,
+        Parameters(CallHierarchyRequest {
+            function_name,
+            symbol_id,
+            direction,
+            depth,
+        }): Parameters<CallHierarchyRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        use crate::navigation::CallDirection;
+
+        let direction: CallDirection = match direction.as_deref().map(str::parse).transpose() {
+            Ok(direction) => direction.unwrap_or_default(),
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+
+        let indexer = self.facade.read().await;
+
+        let symbol = if let Some(id) = symbol_id {
+            match indexer.get_symbol(crate::SymbolId(id)) {
+                Some(sym) => sym,
+                None => {
+                    return Ok(CallToolResult::success(vec![Content::text(format!(
+                        "Symbol not found: symbol_id:{id}"
+                    ))]));
+                }
+            }
+        } else if let Some(name) = function_name {
+            let mut symbols = indexer.find_symbols_by_name(&name, None);
+
+            if symbols.is_empty() {
+                return Ok(CallToolResult::success(vec![Content::text(format!(
+                    "Function not found: {name}"
+                ))]));
+            }
+
+            if symbols.len() > 1 {
+                let mut msg = format!(
+                    "Ambiguous: found {} symbol(s) named '{}':\n",
+                    symbols.len(),
+                    name
+                );
+                for (i, sym) in symbols.iter().take(10).enumerate() {
+                    msg.push_str(&format!(
+                        "  {}. symbol_id:{} - {:?} at {}:{}\n",
+                        i + 1,
+                        sym.id.value(),
+                        sym.kind,
+                        sym.file_path,
+                        sym.range.start_line + 1
+                    ));
+                }
+                if symbols.len() > 10 {
+                    msg.push_str(&format!("  ... and {} more\n", symbols.len() - 10));
+                }
+                msg.push_str("\nUse: call_hierarchy symbol_id:<id> for specific symbol");
+                return Ok(CallToolResult::success(vec![Content::text(msg)]));
+            }
+
+            symbols.remove(0)
+        } else {
+            return Ok(CallToolResult::success(vec![Content::text(
+                "Error: Either function_name or symbol_id must be provided".to_string(),
+            )]));
+        };
+
+        match indexer.call_hierarchy(symbol.id, direction, depth, PositionEncoding::Byte) {
+            Some(tree) if !tree.children.is_empty() => {
+                let label = match direction {
+                    CallDirection::Incoming => "Callers",
+                    CallDirection::Outgoing => "Callees",
+                };
+                Ok(CallToolResult::success(vec![Content::text(format!(
+                    "{label} of {} ({} node(s)):\n\n{tree}",
+                    symbol.name,
+                    tree.node_count() - 1
+                ))]))
+            }
+            _ => {
+                let message = match direction {
+                    CallDirection::Incoming => format!("No functions call {}", symbol.name),
+                    CallDirection::Outgoing => {
+                        format!("{} calls no indexed functions", symbol.name)
+                    }
+                };
+                Ok(CallToolResult::success(vec![Content::text(message)]))
+            }
+        }
+    }
+
+    #[tool(
+        description = "Show the type hierarchy of a class, interface, or trait: its supertypes (what it extends or implements) and subtypes (what extends or implements it), expanded up to `depth` levels."
+    )]
+    pub async fn type_hierarchy(
+        &self,
+        Parameters(TypeHierarchyRequest {
+            type_name,
+            symbol_id,
+            depth,
+        }): Parameters<TypeHierarchyRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let indexer = self.facade.read().await;
+
+        let symbol = if let Some(id) = symbol_id {
+            match indexer.get_symbol(crate::SymbolId(id)) {
+                Some(sym) => sym,
+                None => {
+                    return Ok(CallToolResult::success(vec![Content::text(format!(
+                        "Symbol not found: symbol_id:{id}"
+                    ))]));
+                }
+            }
+        } else if let Some(name) = type_name {
+            let mut symbols = indexer.find_symbols_by_name(&name, None);
+
+            if symbols.is_empty() {
+                return Ok(CallToolResult::success(vec![Content::text(format!(
+                    "Type not found: {name}"
+                ))]));
+            }
+
+            if symbols.len() > 1 {
+                let mut msg = format!(
+                    "Ambiguous: found {} symbol(s) named '{}':\n",
+                    symbols.len(),
+                    name
+                );
+                for (i, sym) in symbols.iter().take(10).enumerate() {
+                    msg.push_str(&format!(
+                        "  {}. symbol_id:{} - {:?} at {}:{}\n",
+                        i + 1,
+                        sym.id.value(),
+                        sym.kind,
+                        sym.file_path,
+                        sym.range.start_line + 1
+                    ));
+                }
+                if symbols.len() > 10 {
+                    msg.push_str(&format!("  ... and {} more\n", symbols.len() - 10));
+                }
+                msg.push_str("\nUse: type_hierarchy symbol_id:<id> for specific symbol");
+                return Ok(CallToolResult::success(vec![Content::text(msg)]));
+            }
+
+            symbols.remove(0)
+        } else {
+            return Ok(CallToolResult::success(vec![Content::text(
+                "Error: Either type_name or symbol_id must be provided".to_string(),
+            )]));
+        };
+
+        match indexer.type_hierarchy(symbol.id, depth, PositionEncoding::Byte) {
+            Some(hierarchy) if !hierarchy.is_empty() => {
+                Ok(CallToolResult::success(vec![Content::text(
+                    hierarchy.to_string(),
+                )]))
+            }
+            _ => Ok(CallToolResult::success(vec![Content::text(format!(
+                "{} has no supertypes or subtypes",
+                symbol.name
+            ))])),
+        }
+    }
+
+    #[tool(
+        description = "Outline of a file: its symbols nested by containment (methods inside classes, fields inside structs) in source order, with line ranges and symbol_ids."
+    )]
+    pub async fn file_outline(
+        &self,
+        Parameters(FileOutlineRequest { file_path }): Parameters<FileOutlineRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let indexer = self.facade.read().await;
+
+        let text = match indexer.file_outline(&file_path, PositionEncoding::Byte) {
+            None => format!("File not indexed: {file_path}"),
+            Some(outline) if outline.is_empty() => format!("{file_path} has no indexed symbols"),
+            Some(outline) => outline
+                .iter()
+                .map(|node| node.to_string())
+                .collect::<Vec<_>>()
+                .join("\n"),
+        };
+        Ok(CallToolResult::success(vec![Content::text(text)]))
+    }
+
+    #[tool(
+        description = "Resolve many symbol names in one call. Names may be qualified by module path to disambiguate. Returns one line per name, in order, with its symbol_id or 'not found'.\n\nUse this when: You need symbol_ids for a batch of names, e.g. while expanding a call graph."
+    )]
+    pub async fn resolve_symbols(
+        &self,
+        Parameters(ResolveSymbolsRequest { names, lang }): Parameters<ResolveSymbolsRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        use crate::symbol::QualifiedName;
+
+        let indexer = self.facade.read().await;
+
+        let qualified: Vec<QualifiedName> = names
+            .iter()
+            .map(|name| {
+                let qualified = QualifiedName::parse(name);
+                match &lang {
+                    Some(lang) => qualified.with_language(lang),
+                    None => qualified,
+                }
+            })
+            .collect();
+        let resolved = indexer.resolve_many(&qualified);
+
+        let lines: Vec<String> = names
+            .iter()
+            .zip(resolved)
+            .map(
+                |(name, id)| match id.and_then(|id| indexer.get_symbol(id)) {
+                    Some(symbol) => format!(
+                        "{name} → {} ({:?}) {}:{} [symbol_id:{}]",
+                        symbol.name,
+                        symbol.kind,
+                        symbol.file_path,
+                        symbol.range.start_line + 1,
+                        symbol.id.value()
+                    ),
+                    None => format!("{name} → not found"),
+                },
+            )
+            .collect();
+        Ok(CallToolResult::success(vec![Content::text(
+            lines.join("\n"),
+        )]))
+    }
+
+    #[tool(
+        description = "Symbol at a cursor position: the innermost symbol enclosing a 1-based line and column of a file, its enclosing symbols, and the indexed symbol the identifier under the cursor refers to, if any."
+    )]
+    pub async fn symbol_at(
+        &self,
+        Parameters(SymbolAtRequest {
+            file_path,
+            line,
+            column,
+            encoding,
+        }): Parameters<SymbolAtRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let encoding = match encoding.as_deref().map(str::parse::<PositionEncoding>) {
+            None => PositionEncoding::Byte,
+            Some(Ok(encoding)) => encoding,
+            Some(Err(e)) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+        let indexer = self.facade.read().await;
+
+        let position = format!("{file_path}:{line}:{column}");
+        let text = if crate::navigation::indexed_file_id(&indexer, &file_path).is_none() {
+            format!("File not indexed: {file_path}")
+        } else {
+            match indexer.symbol_at(
+                &file_path,
+                line.saturating_sub(1),
+                column.saturating_sub(1),
+                encoding,
+            ) {
+                Some(found) => found.to_string(),
+                None => format!("No symbol encloses {position}"),
+            }
+        };
+        Ok(CallToolResult::success(vec![Content::text(text)]))
+    }
+
+    #[tool(
+        description = "Everything needed to work on one symbol in a single call: its definition source, documentation, direct callers and callees, the types it implements or that implement it, and the imports of its file. Lists are capped by max_related and source by max_source_lines; each list reports how many items it has in total."
+    )]
+    pub async fn get_symbol_context(
+        &self,
+        Parameters(GetSymbolContextRequest {
+            symbol_name,
+            symbol_id,
+            lang,
+            max_related,
+            max_source_lines,
+        }): Parameters<GetSymbolContextRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        use crate::navigation::BundleLimits;
+
+        let indexer = self.facade.read().await;
+
+        let target = match (symbol_id, symbol_name) {
+            (Some(id), _) => crate::SymbolId(id),
+            (None, Some(name)) => {
+                let symbols = indexer.find_symbols_by_name(&name, lang.as_deref());
+                match symbols.as_slice() {
+                    [] => {
+                        return Ok(CallToolResult::success(vec![Content::text(format!(
+                            "Symbol not found: {name}"
+                        ))]));
+                    }
+                    [symbol] => symbol.id,
+                    _ => {
+                        let mut msg = format!(
+                            "Ambiguous: found {} symbol(s) named '{name}':\n",
+                            symbols.len()
+                        );
+                        for symbol in symbols.iter().take(10) {
+                            msg.push_str(&format!(
+                                "  symbol_id:{} - {:?} at {}:{}\n",
+                                symbol.id.value(),
+                                symbol.kind,
+                                symbol.file_path,
+                                symbol.range.start_line + 1
+                            ));
+                        }
+                        msg.push_str(
+                            "\nUse: get_symbol_context symbol_id:<id> for specific symbol",
+                        );
+                        return Ok(CallToolResult::success(vec![Content::text(msg)]));
+                    }
+                }
+            }
+            (None, None) => {
+                return Ok(CallToolResult::error(vec![Content::text(
+                    "Error: Either symbol_name or symbol_id must be provided",
+                )]));
+            }
+        };
+
+        let defaults = BundleLimits::default();
+        let limits = BundleLimits {
+            related: max_related.unwrap_or(defaults.related),
+            source_lines: max_source_lines.unwrap_or(defaults.source_lines),
+            ..defaults
+        };
+        let text = match indexer.context_bundle(target, limits, PositionEncoding::Byte) {
+            Some(bundle) => bundle.to_string(),
+            None => format!("Symbol not found: symbol_id:{}", target.value()),
+        };
+        Ok(CallToolResult::success(vec![Content::text(text)]))
+    }
+
+    #[tool(
+        description = "Compact summary of a module or directory: its public symbols with signatures and one-line docs (most used from outside first), its entry points, and the modules it depends on. The summary is cut to fit max_tokens (default 2000) by listing fewer public symbols."
+    )]
+    pub async fn summarize_module(
+        &self,
+        Parameters(SummarizeModuleRequest { target, max_tokens }): Parameters<
+            SummarizeModuleRequest,
+        >,
+    ) -> Result<CallToolResult, McpError> {
+        use crate::analysis::module_summary::{DEFAULT_TOKEN_BUDGET, summarize_module};
+
+        let indexer = self.facade.read().await;
+        let budget = max_tokens.unwrap_or(DEFAULT_TOKEN_BUDGET);
+        let text = match summarize_module(&indexer, &target, budget) {
+            Some(summary) => summary.to_string(),
+            None => format!("No indexed module or directory named '{target}'"),
+        };
+        Ok(CallToolResult::success(vec![Content::text(text)]))
+    }
+
+    #[tool(
+        description = "Search the contents of indexed files with a regular expression. Each matching line is shown with the symbols enclosing it (\"inside method parse of class Parser\") and the innermost symbol's ID, for use with find_callers, get_calls, and other tools."
+    )]
+    pub async fn grep(
+        &self,
+        Parameters(GrepRequest {
+            pattern,
+            limit,
+            ignore_case,
+            encoding,
+        }): Parameters<GrepRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let encoding = match encoding.as_deref().map(str::parse::<PositionEncoding>) {
+            None => PositionEncoding::Byte,
+            Some(Ok(encoding)) => encoding,
+            Some(Err(e)) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+        let regex = match regex::RegexBuilder::new(&pattern)
+            .case_insensitive(ignore_case)
+            .build()
+        {
+            Ok(regex) => regex,
+            Err(e) => {
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Invalid pattern: {e}"
+                ))]));
+            }
+        };
+        let indexer = self.facade.read().await;
+
+        let matches = indexer.grep(&regex, limit as usize, encoding);
+        let text = if matches.is_empty() {
+            format!("No indexed file matches '{pattern}'")
+        } else {
+            matches
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        Ok(CallToolResult::success(vec![Content::text(text)]))
+    }
+
+    #[tool(
+        description = "Hover content of a symbol as markdown: its signature, documentation, and definition location. Give symbol_id, symbol_name, or a cursor position (file_path, 1-based line and column); at a position, the symbol the identifier there refers to is shown, else the enclosing one."
+    )]
+    pub async fn hover(
+        &self,
+        Parameters(HoverRequest {
+            symbol_name,
+            symbol_id,
+            file_path,
+            line,
+            column,
+            encoding,
+        }): Parameters<HoverRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let indexer = self.facade.read().await;
+
+        let target = match (symbol_id, symbol_name, file_path, line, column) {
+            (Some(id), _, _, _, _) => Ok(crate::SymbolId(id)),
+            (None, Some(name), _, _, _) => {
+                let symbols = indexer.find_symbols_by_name(&name, None);
+                match symbols.as_slice() {
+                    [] => Err(format!("Symbol not found: {name}")),
+                    [symbol] => Ok(symbol.id),
+                    _ => {
+                        let mut msg = format!(
+                            "Ambiguous: found {} symbol(s) named '{name}':\n",
+                            symbols.len()
+                        );
+                        for symbol in symbols.iter().take(10) {
+                            msg.push_str(&format!(
+                                "  symbol_id:{} - {:?} at {}:{}\n",
+                                symbol.id.value(),
+                                symbol.kind,
+                                symbol.file_path,
+                                symbol.range.start_line + 1
+                            ));
+                        }
+                        msg.push_str("\nUse: hover symbol_id:<id> for specific symbol");
+                        Err(msg)
+                    }
+                }
+            }
+            (None, None, Some(file_path), Some(line), Some(column)) => {
+                let encoding = match encoding.as_deref().map(str::parse::<PositionEncoding>) {
+                    None => PositionEncoding::Byte,
+                    Some(Ok(encoding)) => encoding,
+                    Some(Err(e)) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+                };
+                indexer
+                    .symbol_at(
+                        &file_path,
+                        line.saturating_sub(1),
+                        column.saturating_sub(1),
+                        encoding,
+                    )
+                    .map(|found| {
+                        let item = found.reference.map_or(found.symbol, |r| r.target);
+                        crate::SymbolId(item.data.symbol_id)
+                    })
+                    .ok_or_else(|| format!("No symbol at {file_path}:{line}:{column}"))
+            }
+            _ => Err(
+                "Error: Either symbol_id, symbol_name, or file_path with line and column must be provided"
+                    .to_string(),
+            ),
+        };
+
+        let text = match target {
+            Ok(id) => match indexer.hover(id, PositionEncoding::Byte) {
+                Some(hover) => hover.to_string(),
+                None => format!("Symbol not found: symbol_id:{}", id.value()),
+            },
+            Err(message) => message,
+        };
+        Ok(CallToolResult::success(vec![Content::text(text)]))
+    }
+
     #[tool(
         description = "Analyze complete impact of changing a symbol. Shows ALL relationships: function calls, type usage, composition.\n\nShows:\n- What CALLS this function\n- What USES this as a type (fields, parameters, returns)\n- What RENDERS/COMPOSES this (JSX: <Component>, Rust: struct fields, etc.)\n- Full dependency graph across files\n\nUse this when: You need to see everything that depends on a symbol."
     )]
@@ -810,6 +1702,7 @@ impl CodeIntelligenceServer {
             symbol_name,
             symbol_id,
             max_depth,
+            unconditional_only,
         }): Parameters<AnalyzeImpactRequest>,
     ) -> Result<CallToolResult, McpError> {
         use crate::symbol::context::ContextIncludes;
@@ -869,7 +1762,11 @@ impl CodeIntelligenceServer {
         };
 
         // Analyze impact for THIS SPECIFIC symbol only (no aggregation)
-        let impacted = indexer.get_impact_radius(symbol.id, Some(max_depth as usize));
+        let impacted = if unconditional_only {
+            indexer.get_unconditional_impact_radius(symbol.id, Some(max_depth as usize))
+        } else {
+            indexer.get_impact_radius(symbol.id, Some(max_depth as usize))
+        };
 
         if impacted.is_empty() {
             let mut output = format!("No symbols would be impacted by changing {identifier}");
@@ -1693,7 +2590,72 @@ impl CodeIntelligenceServer {
         }
     }
 
-    #[tool(description = "Search for symbols using full-text search with fuzzy matching")]
+    #[tool(
+        description = "Run a saved query defined in the project settings by name, substituting {placeholder} parameters. Call without a name to list available queries."
+    )]
+    pub async fn run_saved_query(
+        &self,
+        Parameters(RunSavedQueryRequest { name, params }): Parameters<RunSavedQueryRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        use crate::queries::SavedQuery;
+
+        let indexer = self.facade.read().await;
+        let queries = &indexer.settings().queries;
+
+        let Some(name) = name else {
+            if queries.is_empty() {
+                return Ok(CallToolResult::success(vec![Content::text(
+                    "No saved queries. Define them under [queries] in .codanna/settings.toml",
+                )]));
+            }
+            let mut output = format!("{} saved queries:\n\n", queries.len());
+            for (name, query) in queries {
+                output.push_str(&format!("- {name}: {query}\n"));
+            }
+            return Ok(CallToolResult::success(vec![Content::text(output)]));
+        };
+
+        let query = match SavedQuery::resolve(indexer.settings(), &name, &params) {
+            Ok(query) => query,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
+        };
+
+        let symbols = query.execute(&indexer);
+        if symbols.is_empty() {
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "No results for saved query '{name}'"
+            ))]));
+        }
+
+        let mut result = format!(
+            "Found {} result(s) for saved query '{name}':\n\n",
+            symbols.len()
+        );
+        for (i, symbol) in symbols.iter().enumerate() {
+            result.push_str(&format!(
+                "{}. {} ({:?}) [symbol_id:{}]\n",
+                i + 1,
+                symbol.name,
+                symbol.kind,
+                symbol.id.value()
+            ));
+            result.push_str(&format!(
+                "   File: {}:{}\n",
+                symbol.file_path,
+                symbol.range.start_line + 1
+            ));
+            if let Some(ref sig) = symbol.signature {
+                result.push_str(&format!("   Signature: {sig}\n"));
+            }
+            result.push('\n');
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(
+        description = "Search for symbols using full-text search with fuzzy matching, or set substring to match anywhere in symbol names ignoring case"
+    )]
     pub async fn search_symbols(
         &self,
         Parameters(SearchSymbolsRequest {
@@ -1702,6 +2664,8 @@ impl CodeIntelligenceServer {
             kind,
             module,
             lang,
+            context_file,
+            substring,
         }): Parameters<SearchSymbolsRequest>,
     ) -> Result<CallToolResult, McpError> {
         let indexer = self.facade.read().await;
@@ -1718,13 +2682,25 @@ impl CodeIntelligenceServer {
             _ => None,
         });
 
-        match indexer.search(
-            &query,
-            limit as usize,
-            kind_filter,
-            module.as_deref(),
-            lang.as_deref(),
-        ) {
+        let results = if substring {
+            indexer.search_substring(
+                &query,
+                limit as usize,
+                kind_filter,
+                module.as_deref(),
+                lang.as_deref(),
+            )
+        } else {
+            indexer.search_with_context(
+                &query,
+                limit as usize,
+                kind_filter,
+                module.as_deref(),
+                lang.as_deref(),
+                context_file.as_deref(),
+            )
+        };
+        match results {
             Ok(results) => {
                 if results.is_empty() {
                     let mut output = format!("No results found for query: {query}");
@@ -1875,10 +2851,142 @@ impl CodeIntelligenceServer {
             ))])),
         }
     }
+
+    #[tool(
+        description = "Run several queries in one call. Each sub-query names a tool and that tool's arguments; the response is a JSON object mapping each sub-query id to its tool's output. A failing sub-query reports its error without stopping the others."
+    )]
+    pub async fn batch_query(
+        &self,
+        Parameters(BatchQueryRequest {
+            queries,
+            max_tokens,
+            max_bytes,
+        }): Parameters<BatchQueryRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        if queries.len() > MAX_BATCH_QUERIES {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Too many sub-queries: {} (at most {MAX_BATCH_QUERIES} per call)",
+                queries.len()
+            ))]));
+        }
+        let mut seen = std::collections::HashSet::new();
+        if let Some(duplicate) = queries.iter().find(|query| !seen.insert(&query.id)) {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Duplicate sub-query id '{}'",
+                duplicate.id
+            ))]));
+        }
+
+        let shared =
+            Budget::from_limits(max_tokens, max_bytes).map(|budget| budget.share(queries.len()));
+        let mut results = serde_json::Map::new();
+        for SubQuery {
+            id,
+            tool,
+            arguments,
+        } in queries
+        {
+            let budget = argument_budget(&arguments).or(shared);
+            let (is_error, output) = match self.run_sub_query(&tool, arguments).await {
+                Ok(result) => {
+                    let is_error = result.is_error.unwrap_or(false);
+                    match budget {
+                        Some(budget) => (is_error, fit_text(&result_text(&result), budget)),
+                        None => (is_error, result_text(&result)),
+                    }
+                }
+                Err(message) => (true, message),
+            };
+            results.insert(
+                id,
+                serde_json::json!({ "tool": tool, "is_error": is_error, "output": output }),
+            );
+        }
+
+        let text = serde_json::to_string_pretty(&results)
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+        Ok(CallToolResult::success(vec![Content::text(text)]))
+    }
+
+    /// Run one batch_query sub-query through the tool it names
+    async fn run_sub_query(
+        &self,
+        tool: &str,
+        arguments: serde_json::Map<String, serde_json::Value>,
+    ) -> Result<CallToolResult, String> {
+        if !BATCH_TOOLS.contains(&tool) {
+            return Err(format!(
+                "Unknown tool '{tool}'; a sub-query can run {}",
+                BATCH_TOOLS.join(", ")
+            ));
+        }
+        self.run_batch_tool(tool, arguments).await
+    }
+
+    /// Run a tool by name outside an MCP session, e.g. for a daemon client.
+    ///
+    /// Errors are the messages of unknown tools, invalid arguments, and
+    /// failed calls.
+    pub async fn run_tool(
+        &self,
+        tool: &str,
+        arguments: serde_json::Map<String, serde_json::Value>,
+    ) -> Result<CallToolResult, String> {
+        if BATCH_TOOLS.contains(&tool) {
+            return self.run_batch_tool(tool, arguments).await;
+        }
+
+        let result = match tool {
+            "get_index_info" => self.get_index_info(parse_arguments(arguments)?).await,
+            "run_saved_query" => self.run_saved_query(parse_arguments(arguments)?).await,
+            "search_documents" => self.search_documents(parse_arguments(arguments)?).await,
+            "batch_query" => self.batch_query(parse_arguments(arguments)?).await,
+            _ => return Err(format!("Unknown tool '{tool}'")),
+        };
+        result.map_err(|e| e.message.to_string())
+    }
 }
 
-#[tool_handler]
 impl ServerHandler for CodeIntelligenceServer {
+    // Hand-written instead of #[tool_handler] so each tool call is timed
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParams,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let tool = request.name.clone();
+        let budget = request
+            .arguments
+            .as_ref()
+            .and_then(argument_budget)
+            .filter(|_| !SELF_BUDGETED_TOOLS.contains(&tool.as_ref()));
+        let start = std::time::Instant::now();
+        let tcc = rmcp::handler::server::tool::ToolCallContext::new(self, request, context);
+        let result = self.tool_router.call(tcc).await;
+        crate::metrics::observe_query(&tool, start.elapsed());
+        match budget {
+            Some(budget) => result.map(|result| fit_result(result, budget)),
+            None => result,
+        }
+    }
+
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, McpError> {
+        let mut tools = self.tool_router.list_all();
+        for tool in &mut tools {
+            add_budget_arguments(tool);
+            describe_batch_tools(tool);
+        }
+        Ok(ListToolsResult {
+            tools,
+            meta: None,
+            next_cursor: None,
+        })
+    }
+
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
             protocol_version: ProtocolVersion::V_2024_11_05,
@@ -1898,6 +3006,8 @@ impl ServerHandler for CodeIntelligenceServer {
                 Then use 'find_symbol' and 'search_symbols' to lock onto exact files and kinds. \
                 Treat 'get_calls', 'find_callers', and 'analyze_impact' as hints; confirm with code reading or tighter queries (unique names, kind filters). \
                 Use 'search_documents' to find relevant project documentation (markdown files). \
+                Use 'batch_query' to run several of these lookups in one call. \
+                Every tool accepts 'max_tokens' to cap the size of its response. \
                 Use 'get_index_info' to understand what's indexed."
                 .to_string()
             ),
@@ -0,0 +1,488 @@
+//! REST API over the index for scripts and CI
+//!
+//! Mounted under `/api/v1` by `serve --http`. Every route requires
+//! `Authorization: Bearer <token>` matching `server.api_token`
+//! (or `CI_SERVER__API_TOKEN`); without a configured token the API is disabled.
+//!
+//! Routes (all `GET`, JSON responses):
+//!
+//! - `/api/v1/search?q=<query>&limit=&kind=&lang=&module=` - full-text search
+//! - `/api/v1/symbols/{name}?lang=` - symbol detail
+//! - `/api/v1/symbols/{name}/references` - symbols that call, use, or implement it
+//! - `/api/v1/symbols/{name}/calls` - functions it calls, with call sites
+//! - `/api/v1/symbols/{name}/callers` - functions calling it, with call sites
+//!
+//! Every route also takes `max_tokens=` and `max_bytes=`: the response keeps
+//! its leading results and shortens long strings to fit, and the
+//! `X-Truncated`, `X-Omitted` and `X-Trimmed` headers report the cut.
+
+use crate::export::jsonl::ExportedSymbol;
+use crate::indexing::facade::IndexFacade;
+use crate::io::BudgetParams;
+use crate::relationship::RelationshipMetadata;
+use crate::{Symbol, SymbolKind};
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+type SharedFacade = Arc<RwLock<IndexFacade>>;
+
+const MAX_LIMIT: usize = 200;
+
+/// Build the `/api/v1` router, guarded by `token`
+pub fn router(indexer: SharedFacade, token: String) -> Router {
+    let token: Arc<str> = token.into();
+
+    Router::new()
+        .route("/api/v1/search", get(search))
+        .route("/api/v1/symbols/{name}", get(symbol))
+        .route("/api/v1/symbols/{name}/references", get(references))
+        .route("/api/v1/symbols/{name}/calls", get(calls))
+        .route("/api/v1/symbols/{name}/callers", get(callers))
+        .with_state(indexer)
+        .layer(axum::middleware::from_fn(fit_to_budget))
+        .layer(axum::middleware::from_fn(
+            move |req: axum::extract::Request, next: axum::middleware::Next| {
+                let token = token.clone();
+                async move {
+                    if is_authorized(&req, &token) {
+                        let endpoint = req
+                            .extensions()
+                            .get::<axum::extract::MatchedPath>()
+                            .map(|path| path.as_str().to_string())
+                            .unwrap_or_default();
+                        let start = std::time::Instant::now();
+                        let response = next.run(req).await;
+                        crate::metrics::observe_query(&endpoint, start.elapsed());
+                        response
+                    } else {
+                        ApiError::new(StatusCode::UNAUTHORIZED, "missing or invalid API token")
+                            .into_response()
+                    }
+                }
+            },
+        ))
+}
+
+fn is_authorized(req: &axum::extract::Request, token: &str) -> bool {
+    req.headers()
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|presented| constant_time_eq(presented.as_bytes(), token.as_bytes()))
+}
+
+/// Compare without short-circuiting so response timing does not leak the token
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Cut a successful JSON response to the `max_tokens`/`max_bytes` query
+/// parameters
+async fn fit_to_budget(req: axum::extract::Request, next: axum::middleware::Next) -> Response {
+    let budget = Query::<BudgetParams>::try_from_uri(req.uri())
+        .ok()
+        .and_then(|Query(params)| params.budget());
+    let response = next.run(req).await;
+    let Some(budget) = budget.filter(|_| response.status().is_success()) else {
+        return response;
+    };
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            return ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response();
+        }
+    };
+    let mut value = match serde_json::from_slice::<serde_json::Value>(&bytes) {
+        Ok(value) => value,
+        Err(_) => return Response::from_parts(parts, bytes.into()),
+    };
+    let omitted = budget.fit_value(&mut value);
+    if omitted.is_empty() {
+        return Response::from_parts(parts, bytes.into());
+    }
+
+    parts.headers.remove(axum::http::header::CONTENT_LENGTH);
+    parts
+        .headers
+        .insert("x-truncated", axum::http::HeaderValue::from_static("true"));
+    parts.headers.insert("x-omitted", omitted.items.into());
+    parts.headers.insert("x-trimmed", omitted.trimmed.into());
+    (parts, Json(value)).into_response()
+}
+
+/// JSON error body: `{"error": "..."}`
+struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+
+    fn not_found(name: &str) -> Self {
+        Self::new(StatusCode::NOT_FOUND, format!("symbol '{name}' not found"))
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (
+            self.status,
+            Json(serde_json::json!({ "error": self.message })),
+        )
+            .into_response()
+    }
+}
+
+type ApiResult<T> = Result<Json<T>, ApiError>;
+
+#[derive(Deserialize)]
+struct SearchParams {
+    q: String,
+    limit: Option<usize>,
+    kind: Option<String>,
+    lang: Option<String>,
+    module: Option<String>,
+    context_file: Option<String>,
+}
+
+async fn search(
+    State(indexer): State<SharedFacade>,
+    Query(params): Query<SearchParams>,
+) -> ApiResult<Vec<crate::storage::SearchResult>> {
+    let kind = params
+        .kind
+        .as_deref()
+        .map(str::parse::<SymbolKind>)
+        .transpose()
+        .map_err(|e| ApiError::new(StatusCode::BAD_REQUEST, e))?;
+    let limit = params.limit.unwrap_or(10).min(MAX_LIMIT);
+
+    let facade = indexer.read().await;
+    facade
+        .search_with_context(
+            &params.q,
+            limit,
+            kind,
+            params.module.as_deref(),
+            params.lang.as_deref(),
+            params.context_file.as_deref(),
+        )
+        .map(Json)
+        .map_err(|e| ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+#[derive(Deserialize)]
+struct SymbolParams {
+    lang: Option<String>,
+}
+
+fn lookup(facade: &IndexFacade, name: &str, lang: Option<&str>) -> Result<Vec<Symbol>, ApiError> {
+    let symbols = facade.find_symbols_by_name(name, lang);
+    if symbols.is_empty() {
+        Err(ApiError::not_found(name))
+    } else {
+        Ok(symbols)
+    }
+}
+
+/// Symbols are rendered with the same field layout as `codanna export symbols`
+fn to_json(symbol: &Symbol) -> serde_json::Value {
+    serde_json::to_value(ExportedSymbol::from(symbol)).unwrap_or_default()
+}
+
+async fn symbol(
+    State(indexer): State<SharedFacade>,
+    Path(name): Path<String>,
+    Query(params): Query<SymbolParams>,
+) -> ApiResult<Vec<serde_json::Value>> {
+    let facade = indexer.read().await;
+    let symbols = lookup(&facade, &name, params.lang.as_deref())?;
+    Ok(Json(symbols.iter().map(to_json).collect()))
+}
+
+/// A related symbol together with the edge that links it
+#[derive(Serialize)]
+struct Related {
+    symbol: serde_json::Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    line: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    column: Option<u16>,
+}
+
+impl Related {
+    fn new(symbol: &Symbol, metadata: Option<RelationshipMetadata>) -> Self {
+        Self {
+            symbol: to_json(symbol),
+            line: metadata.as_ref().and_then(|m| m.line),
+            column: metadata.as_ref().and_then(|m| m.column),
+        }
+    }
+}
+
+/// Relations of every symbol matching the requested name
+#[derive(Serialize)]
+struct RelatedGroup<T> {
+    symbol: serde_json::Value,
+    related: T,
+}
+
+fn group<T>(symbol: &Symbol, related: T) -> RelatedGroup<T> {
+    RelatedGroup {
+        symbol: to_json(symbol),
+        related,
+    }
+}
+
+async fn references(
+    State(indexer): State<SharedFacade>,
+    Path(name): Path<String>,
+    Query(params): Query<SymbolParams>,
+) -> ApiResult<Vec<RelatedGroup<BTreeMap<String, Vec<Related>>>>> {
+    let facade = indexer.read().await;
+    let symbols = lookup(&facade, &name, params.lang.as_deref())?;
+    Ok(Json(
+        symbols
+            .iter()
+            .map(|symbol| {
+                let by_kind = facade
+                    .get_dependents(symbol.id)
+                    .into_iter()
+                    .map(|(kind, dependents)| {
+                        let related = dependents.iter().map(|s| Related::new(s, None)).collect();
+                        (format!("{kind:?}"), related)
+                    })
+                    .collect();
+                group(symbol, by_kind)
+            })
+            .collect(),
+    ))
+}
+
+async fn calls(
+    State(indexer): State<SharedFacade>,
+    Path(name): Path<String>,
+    Query(params): Query<SymbolParams>,
+) -> ApiResult<Vec<RelatedGroup<Vec<Related>>>> {
+    let facade = indexer.read().await;
+    let symbols = lookup(&facade, &name, params.lang.as_deref())?;
+    Ok(Json(
+        symbols
+            .iter()
+            .map(|symbol| {
+                let callees = facade
+                    .get_called_functions_with_metadata(symbol.id)
+                    .into_iter()
+                    .map(|(callee, metadata)| Related::new(&callee, metadata))
+                    .collect();
+                group(symbol, callees)
+            })
+            .collect(),
+    ))
+}
+
+async fn callers(
+    State(indexer): State<SharedFacade>,
+    Path(name): Path<String>,
+    Query(params): Query<SymbolParams>,
+) -> ApiResult<Vec<RelatedGroup<Vec<Related>>>> {
+    let facade = indexer.read().await;
+    let symbols = lookup(&facade, &name, params.lang.as_deref())?;
+    Ok(Json(
+        symbols
+            .iter()
+            .map(|symbol| {
+                let callers = facade
+                    .get_calling_functions_with_metadata(symbol.id)
+                    .into_iter()
+                    .map(|(caller, metadata)| Related::new(&caller, metadata))
+                    .collect();
+                group(symbol, callers)
+            })
+            .collect(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Settings;
+    use crate::{FileId, Range, RelationKind, Relationship, SymbolId};
+    use axum::body::Body;
+    use axum::http::Request;
+    use tempfile::TempDir;
+    use tower::ServiceExt;
+
+    fn test_router(temp_dir: &TempDir) -> Router {
+        let settings = Settings {
+            index_path: temp_dir.path().join("index"),
+            ..Settings::default()
+        };
+        let facade = IndexFacade::new(Arc::new(settings)).unwrap();
+        let index = facade.document_index();
+        index.start_batch().unwrap();
+        for (id, name) in [(1, "main"), (2, "parse")] {
+            let symbol = Symbol::new(
+                SymbolId::new(id).unwrap(),
+                name,
+                SymbolKind::Function,
+                FileId::new(1).unwrap(),
+                Range::new(id, 0, id, 10),
+            );
+            index.index_symbol(&symbol, "src/main.rs").unwrap();
+        }
+        index
+            .store_relationship(
+                SymbolId::new(1).unwrap(),
+                SymbolId::new(2).unwrap(),
+                &Relationship::new(RelationKind::Calls),
+            )
+            .unwrap();
+        index.commit_batch().unwrap();
+
+        router(Arc::new(RwLock::new(facade)), "secret".to_string())
+    }
+
+    async fn get_json(
+        router: Router,
+        uri: &str,
+        token: Option<&str>,
+    ) -> (StatusCode, serde_json::Value) {
+        let mut request = Request::get(uri);
+        if let Some(token) = token {
+            request = request.header("Authorization", format!("Bearer {token}"));
+        }
+        let response = router
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_rejects_missing_or_wrong_token() {
+        let temp_dir = TempDir::new().unwrap();
+        let router = test_router(&temp_dir);
+
+        let (status, body) = get_json(router.clone(), "/api/v1/symbols/main", None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert!(body["error"].is_string());
+
+        let (status, _) = get_json(router, "/api/v1/symbols/main", Some("wrong")).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_symbol_and_call_graph_endpoints() {
+        let temp_dir = TempDir::new().unwrap();
+        let router = test_router(&temp_dir);
+
+        let (status, body) = get_json(router.clone(), "/api/v1/symbols/main", Some("secret")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body[0]["name"], "main");
+
+        let (_, body) =
+            get_json(router.clone(), "/api/v1/symbols/main/calls", Some("secret")).await;
+        assert_eq!(body[0]["related"][0]["symbol"]["name"], "parse");
+
+        let (_, body) = get_json(
+            router.clone(),
+            "/api/v1/symbols/parse/callers",
+            Some("secret"),
+        )
+        .await;
+        assert_eq!(body[0]["related"][0]["symbol"]["name"], "main");
+
+        let (_, body) = get_json(
+            router.clone(),
+            "/api/v1/symbols/parse/references",
+            Some("secret"),
+        )
+        .await;
+        assert_eq!(body[0]["related"]["Calls"][0]["symbol"]["name"], "main");
+
+        let (status, _) = get_json(router, "/api/v1/symbols/missing", Some("secret")).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_budget_keeps_leading_results() {
+        let temp_dir = TempDir::new().unwrap();
+        let settings = Settings {
+            index_path: temp_dir.path().join("index"),
+            ..Settings::default()
+        };
+        let facade = IndexFacade::new(Arc::new(settings)).unwrap();
+        let index = facade.document_index();
+        index.start_batch().unwrap();
+        for id in 1..=20 {
+            let symbol = Symbol::new(
+                SymbolId::new(id).unwrap(),
+                "handler",
+                SymbolKind::Function,
+                FileId::new(id).unwrap(),
+                Range::new(1, 0, 1, 10),
+            );
+            index
+                .index_symbol(&symbol, &format!("src/handler_{id}.rs"))
+                .unwrap();
+        }
+        index.commit_batch().unwrap();
+        let router = router(Arc::new(RwLock::new(facade)), "secret".to_string());
+
+        let request = Request::get("/api/v1/symbols/handler?max_bytes=1000")
+            .header("Authorization", "Bearer secret")
+            .body(Body::empty())
+            .unwrap();
+        let response = router.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["x-truncated"], "true");
+        let omitted: usize = response.headers()["x-omitted"]
+            .to_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        let kept = body.as_array().unwrap();
+        assert!(omitted > 0);
+        assert_eq!(kept.len() + omitted, 20);
+        assert_eq!(kept[0]["name"], "handler");
+
+        let (_, full) = get_json(
+            router,
+            "/api/v1/symbols/handler?max_tokens=100000",
+            Some("secret"),
+        )
+        .await;
+        assert_eq!(full.as_array().unwrap().len(), 20);
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"token", b"token"));
+        assert!(!constant_time_eq(b"token", b"tokem"));
+        assert!(!constant_time_eq(b"token", b"token2"));
+    }
+}
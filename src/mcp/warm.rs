@@ -0,0 +1,45 @@
+//! Background warm-up for long-running servers
+//!
+//! Servers warm the index on start and again whenever it changes, so the
+//! first caller or module query after startup or a re-index is served from
+//! precomputed structures. See [`crate::indexing::warm`].
+
+use crate::indexing::facade::IndexFacade;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+
+/// How often to check whether the index changed since the last warm-up
+const RECHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Warm `facade` now and again after each change to the index.
+///
+/// Changes are noticed by polling, so re-indexing from any source (file
+/// watcher, hot reload, REST) is covered.
+pub fn spawn_warm_up(facade: Arc<RwLock<IndexFacade>>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            let stale = !facade.read().await.is_warm();
+            if stale {
+                let facade = facade.clone();
+                let result =
+                    tokio::task::spawn_blocking(move || facade.blocking_read().warm_up()).await;
+                match result {
+                    Ok(Ok(stats)) => crate::log_event!(
+                        "warm-up",
+                        "done",
+                        "{} call edges, {} modules, {} names in {:.2}s",
+                        stats.call_edges,
+                        stats.modules,
+                        stats.names,
+                        stats.elapsed.as_secs_f64()
+                    ),
+                    Ok(Err(e)) => tracing::warn!("[warm-up] failed: {e}"),
+                    Err(e) => tracing::warn!("[warm-up] task failed: {e}"),
+                }
+            }
+            tokio::time::sleep(RECHECK_INTERVAL).await;
+        }
+    })
+}
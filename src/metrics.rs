@@ -0,0 +1,394 @@
+//! Prometheus-style metrics for long-running server modes
+//!
+//! [`MetricsRegistry`] holds counters, gauges, and histograms keyed by name and
+//! label set, and renders them in the Prometheus text exposition format.
+//! Server code records into the process-wide [`global`] registry; the HTTP
+//! servers expose it at `/metrics`.
+//!
+//! ```
+//! use codanna::metrics::MetricsRegistry;
+//!
+//! let registry = MetricsRegistry::new();
+//! registry
+//!     .counter("requests_total", "Requests served", &[("route", "search")])
+//!     .inc();
+//! assert!(registry.render().contains("requests_total{route=\"search\"} 1"));
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+/// Latency buckets in seconds, tuned for index queries
+pub const LATENCY_BUCKETS: &[f64] = &[
+    0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0,
+];
+
+/// Monotonically increasing counter
+#[derive(Debug, Default)]
+pub struct Counter(AtomicU64);
+
+impl Counter {
+    pub fn inc(&self) {
+        self.inc_by(1);
+    }
+
+    pub fn inc_by(&self, n: u64) {
+        self.0.fetch_add(n, Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Value that can go up and down
+#[derive(Debug, Default)]
+pub struct Gauge(AtomicI64);
+
+impl Gauge {
+    pub fn set(&self, value: i64) {
+        self.0.store(value, Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> i64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Cumulative histogram over fixed bucket bounds
+#[derive(Debug)]
+pub struct Histogram {
+    bounds: Vec<f64>,
+    buckets: Vec<AtomicU64>,
+    count: AtomicU64,
+    sum_bits: AtomicU64,
+}
+
+impl Histogram {
+    fn new(bounds: &[f64]) -> Self {
+        Self {
+            bounds: bounds.to_vec(),
+            buckets: bounds.iter().map(|_| AtomicU64::new(0)).collect(),
+            count: AtomicU64::new(0),
+            sum_bits: AtomicU64::new(0f64.to_bits()),
+        }
+    }
+
+    pub fn observe(&self, value: f64) {
+        if let Some(i) = self.bounds.iter().position(|bound| value <= *bound) {
+            self.buckets[i].fetch_add(1, Ordering::Relaxed);
+        }
+        self.count.fetch_add(1, Ordering::Relaxed);
+        let _ = self
+            .sum_bits
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |bits| {
+                Some((f64::from_bits(bits) + value).to_bits())
+            });
+    }
+
+    pub fn observe_duration(&self, elapsed: Duration) {
+        self.observe(elapsed.as_secs_f64());
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    pub fn sum(&self) -> f64 {
+        f64::from_bits(self.sum_bits.load(Ordering::Relaxed))
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Series {
+    Counter(Arc<Counter>),
+    Gauge(Arc<Gauge>),
+    Histogram(Arc<Histogram>),
+}
+
+impl Series {
+    fn type_name(&self) -> &'static str {
+        match self {
+            Series::Counter(_) => "counter",
+            Series::Gauge(_) => "gauge",
+            Series::Histogram(_) => "histogram",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Family {
+    help: String,
+    type_name: &'static str,
+    series: BTreeMap<String, Series>,
+}
+
+/// Registry of named metrics
+#[derive(Debug, Default)]
+pub struct MetricsRegistry {
+    families: Mutex<BTreeMap<String, Family>>,
+}
+
+impl MetricsRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get or register a counter
+    pub fn counter(&self, name: &str, help: &str, labels: &[(&str, &str)]) -> Arc<Counter> {
+        match self.get_or_insert(name, help, labels, || {
+            Series::Counter(Arc::new(Counter::default()))
+        }) {
+            Series::Counter(counter) => counter,
+            _ => Arc::new(Counter::default()),
+        }
+    }
+
+    /// Get or register a gauge
+    pub fn gauge(&self, name: &str, help: &str, labels: &[(&str, &str)]) -> Arc<Gauge> {
+        match self.get_or_insert(name, help, labels, || {
+            Series::Gauge(Arc::new(Gauge::default()))
+        }) {
+            Series::Gauge(gauge) => gauge,
+            _ => Arc::new(Gauge::default()),
+        }
+    }
+
+    /// Get or register a histogram with the given bucket bounds
+    pub fn histogram(
+        &self,
+        name: &str,
+        help: &str,
+        labels: &[(&str, &str)],
+        bounds: &[f64],
+    ) -> Arc<Histogram> {
+        match self.get_or_insert(name, help, labels, || {
+            Series::Histogram(Arc::new(Histogram::new(bounds)))
+        }) {
+            Series::Histogram(histogram) => histogram,
+            _ => Arc::new(Histogram::new(bounds)),
+        }
+    }
+
+    /// A name already registered with another type yields an unregistered
+    /// series, so a naming clash never corrupts the exposition output.
+    fn get_or_insert(
+        &self,
+        name: &str,
+        help: &str,
+        labels: &[(&str, &str)],
+        create: impl FnOnce() -> Series,
+    ) -> Series {
+        let key = render_labels(labels);
+        let mut families = self.families.lock().unwrap_or_else(|e| e.into_inner());
+        let series = create();
+        let family = families.entry(name.to_string()).or_insert_with(|| Family {
+            help: help.to_string(),
+            type_name: series.type_name(),
+            series: BTreeMap::new(),
+        });
+        if family.type_name != series.type_name() {
+            tracing::warn!(
+                target: "metrics",
+                "metric {name} already registered as {}",
+                family.type_name
+            );
+            return series;
+        }
+        family.series.entry(key).or_insert(series).clone()
+    }
+
+    /// Render all metrics in the Prometheus text format (version 0.0.4)
+    pub fn render(&self) -> String {
+        let families = self.families.lock().unwrap_or_else(|e| e.into_inner());
+        let mut out = String::new();
+        for (name, family) in families.iter() {
+            let _ = writeln!(out, "# HELP {name} {}", family.help);
+            let _ = writeln!(out, "# TYPE {name} {}", family.type_name);
+            for (labels, series) in &family.series {
+                match series {
+                    Series::Counter(c) => {
+                        let _ = writeln!(out, "{name}{} {}", braced(labels), c.get());
+                    }
+                    Series::Gauge(g) => {
+                        let _ = writeln!(out, "{name}{} {}", braced(labels), g.get());
+                    }
+                    Series::Histogram(h) => render_histogram(&mut out, name, labels, h),
+                }
+            }
+        }
+        out
+    }
+}
+
+fn render_histogram(out: &mut String, name: &str, labels: &str, histogram: &Histogram) {
+    let sep = if labels.is_empty() { "" } else { "," };
+    let mut cumulative = 0;
+    for (bound, bucket) in histogram.bounds.iter().zip(&histogram.buckets) {
+        cumulative += bucket.load(Ordering::Relaxed);
+        let _ = writeln!(
+            out,
+            "{name}_bucket{{{labels}{sep}le=\"{bound}\"}} {cumulative}"
+        );
+    }
+    let count = histogram.count();
+    let _ = writeln!(out, "{name}_bucket{{{labels}{sep}le=\"+Inf\"}} {count}");
+    let _ = writeln!(out, "{name}_sum{} {}", braced(labels), histogram.sum());
+    let _ = writeln!(out, "{name}_count{} {count}", braced(labels));
+}
+
+fn render_labels(labels: &[(&str, &str)]) -> String {
+    labels
+        .iter()
+        .map(|(key, value)| {
+            let value = value
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('\n', "\\n");
+            format!("{key}=\"{value}\"")
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn braced(labels: &str) -> String {
+    if labels.is_empty() {
+        String::new()
+    } else {
+        format!("{{{labels}}}")
+    }
+}
+
+/// Process-wide registry used by the server modes
+pub fn global() -> &'static MetricsRegistry {
+    static REGISTRY: OnceLock<MetricsRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(MetricsRegistry::new)
+}
+
+/// Record the latency of one query, labelled by the tool or route that served it
+pub fn observe_query(endpoint: &str, elapsed: Duration) {
+    global()
+        .histogram(
+            "codanna_query_duration_seconds",
+            "Query latency by endpoint",
+            &[("endpoint", endpoint)],
+            LATENCY_BUCKETS,
+        )
+        .observe_duration(elapsed);
+}
+
+/// Count a file watch event handled by `handler` (`modified` or `deleted`)
+pub fn record_watch_event(handler: &str, event: &str) {
+    global()
+        .counter(
+            "codanna_watch_events_total",
+            "File watch events processed",
+            &[("handler", handler), ("event", event)],
+        )
+        .inc();
+}
+
+/// Count files that failed to parse during indexing
+pub fn record_parse_errors(count: usize) {
+    if count == 0 {
+        return;
+    }
+    global()
+        .counter(
+            "codanna_parse_errors_total",
+            "Files that failed to parse",
+            &[],
+        )
+        .inc_by(count as u64);
+}
+
+/// Count a parser panic caught while indexing a file in `language`
+pub fn record_parser_panic(language: &str) {
+    global()
+        .counter(
+            "codanna_parser_panics_total",
+            "Parser panics isolated during indexing",
+            &[("language", language)],
+        )
+        .inc();
+}
+
+/// Update the index size gauges
+pub fn set_index_size(symbols: usize, files: usize, relationships: usize) {
+    let registry = global();
+    for (name, help, value) in [
+        ("codanna_index_symbols", "Symbols in the index", symbols),
+        ("codanna_index_files", "Files in the index", files),
+        (
+            "codanna_index_relationships",
+            "Relationships in the index",
+            relationships,
+        ),
+    ] {
+        registry.gauge(name, help, &[]).set(value as i64);
+    }
+}
+
+/// Refresh the index gauges from `facade` and render the global registry
+pub fn scrape(facade: &crate::indexing::facade::IndexFacade) -> String {
+    set_index_size(
+        facade.symbol_count(),
+        facade.file_count() as usize,
+        facade.relationship_count(),
+    );
+    global().render()
+}
+
+/// Content type of [`MetricsRegistry::render`] output
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_counters_and_gauges() {
+        let registry = MetricsRegistry::new();
+        registry
+            .counter("events_total", "Events", &[("kind", "modify")])
+            .inc_by(3);
+        registry.gauge("size", "Size", &[]).set(42);
+
+        let text = registry.render();
+        assert!(text.contains("# TYPE events_total counter"));
+        assert!(text.contains("events_total{kind=\"modify\"} 3"));
+        assert!(text.contains("# TYPE size gauge"));
+        assert!(text.contains("size 42"));
+    }
+
+    #[test]
+    fn test_histogram_buckets_are_cumulative() {
+        let registry = MetricsRegistry::new();
+        let histogram = registry.histogram("latency", "Latency", &[], &[0.1, 1.0]);
+        histogram.observe(0.05);
+        histogram.observe(0.5);
+        histogram.observe(3.0);
+
+        let text = registry.render();
+        assert!(text.contains("latency_bucket{le=\"0.1\"} 1"));
+        assert!(text.contains("latency_bucket{le=\"1\"} 2"));
+        assert!(text.contains("latency_bucket{le=\"+Inf\"} 3"));
+        assert!(text.contains("latency_count 3"));
+        assert!((histogram.sum() - 3.55).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_same_series_is_shared_and_type_clash_is_detached() {
+        let registry = MetricsRegistry::new();
+        registry.counter("hits", "Hits", &[]).inc();
+        registry.counter("hits", "Hits", &[]).inc();
+        registry.gauge("hits", "Hits", &[]).set(100);
+
+        let text = registry.render();
+        assert!(text.contains("hits 2"));
+        assert!(!text.contains("hits 100"));
+    }
+}
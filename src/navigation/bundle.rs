@@ -0,0 +1,293 @@
+//! Symbol context bundle
+//!
+//! Everything an agent usually gathers before touching a symbol, in one
+//! payload: its definition source and documentation, its direct callers and
+//! callees, the types it implements or that implement it, and the imports
+//! of its file. Every list is capped and reports its full size, so the
+//! bundle stays small for heavily used symbols without hiding that more
+//! exists.
+
+use super::hover::render_doc_markdown;
+use super::lsp::{HierarchyItem, Locator};
+use crate::indexing::facade::IndexFacade;
+use crate::symbol::context::ContextIncludes;
+use crate::types::PositionEncoding;
+use crate::{Symbol, SymbolId};
+use serde::Serialize;
+use std::fmt;
+
+/// Caps on the parts of a bundle
+#[derive(Debug, Clone, Copy)]
+pub struct BundleLimits {
+    /// Items per relationship list
+    pub related: usize,
+    /// Lines of definition source
+    pub source_lines: usize,
+    /// Imports of the symbol's file
+    pub imports: usize,
+}
+
+impl Default for BundleLimits {
+    fn default() -> Self {
+        Self {
+            related: 10,
+            source_lines: 80,
+            imports: 30,
+        }
+    }
+}
+
+/// The first items of a relationship list and how long it is
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Related {
+    pub items: Vec<HierarchyItem>,
+    pub total: usize,
+}
+
+impl Related {
+    fn new<'a>(
+        symbols: impl IntoIterator<Item = &'a Symbol>,
+        limit: usize,
+        locator: &Locator,
+    ) -> Self {
+        let mut related = Self::default();
+        for symbol in symbols {
+            if related.items.len() < limit {
+                related.items.push(HierarchyItem::new(symbol, locator));
+            }
+            related.total += 1;
+        }
+        related
+    }
+
+    fn write(&self, f: &mut fmt::Formatter<'_>, title: &str) -> fmt::Result {
+        if self.total == 0 {
+            return Ok(());
+        }
+        if self.total > self.items.len() {
+            writeln!(f, "\n{title} ({} of {}):", self.items.len(), self.total)?;
+        } else {
+            writeln!(f, "\n{title} ({}):", self.total)?;
+        }
+        for item in &self.items {
+            writeln!(
+                f,
+                "  {} ({}) {}:{} [symbol_id:{}]",
+                item.name,
+                item.kind_name(),
+                item.data.file_path,
+                item.range.start.line + 1,
+                item.data.symbol_id
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// A symbol with the context needed to work on it
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextBundle {
+    pub symbol: HierarchyItem,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub module_path: Option<String>,
+    /// The doc comment as markdown
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub documentation: Option<String>,
+    /// Definition source, cut to the line limit; absent when the file can
+    /// no longer be read
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Lines of the definition cut from `source`
+    pub source_lines_omitted: usize,
+    pub callers: Related,
+    pub callees: Related,
+    pub implements: Related,
+    pub implemented_by: Related,
+    /// Imports of the symbol's file, as written
+    pub imports: Vec<String>,
+    pub imports_omitted: usize,
+}
+
+impl fmt::Display for ContextBundle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} ({}) {}:{} [symbol_id:{}]",
+            self.symbol.name,
+            self.symbol.kind_name(),
+            self.symbol.data.file_path,
+            self.symbol.range.start.line + 1,
+            self.symbol.data.symbol_id
+        )?;
+        if let Some(module) = &self.module_path {
+            writeln!(f, "Module: {module}")?;
+        }
+        if let Some(doc) = &self.documentation {
+            writeln!(f, "\n{}", doc.trim_end())?;
+        }
+        if let Some(source) = &self.source {
+            writeln!(f, "\nSource:\n{}", source.trim_end())?;
+            if self.source_lines_omitted > 0 {
+                writeln!(f, "... {} more lines", self.source_lines_omitted)?;
+            }
+        }
+        self.callers.write(f, "Callers")?;
+        self.callees.write(f, "Calls")?;
+        self.implements.write(f, "Implements")?;
+        self.implemented_by.write(f, "Implemented by")?;
+        if !self.imports.is_empty() {
+            writeln!(f, "\nImports of {}:", self.symbol.data.file_path)?;
+            for import in &self.imports {
+                writeln!(f, "  {import}")?;
+            }
+            if self.imports_omitted > 0 {
+                writeln!(f, "  ... {} more", self.imports_omitted)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Context bundle of a symbol with columns counted in `encoding`, or `None`
+/// when it is not indexed
+pub fn context_bundle(
+    facade: &IndexFacade,
+    symbol_id: SymbolId,
+    limits: BundleLimits,
+    encoding: PositionEncoding,
+) -> Option<ContextBundle> {
+    let includes =
+        ContextIncludes::CALLS | ContextIncludes::CALLERS | ContextIncludes::IMPLEMENTATIONS;
+    let context = facade.get_symbol_context(symbol_id, includes)?;
+    let symbol = &context.symbol;
+    let relationships = &context.relationships;
+    let locator = Locator::for_facade(facade, encoding);
+
+    let (source, source_lines_omitted) = match definition_source(&locator, symbol) {
+        Some(lines) => {
+            let omitted = lines.len().saturating_sub(limits.source_lines);
+            let kept = &lines[..lines.len() - omitted];
+            (Some(kept.join("\n")), omitted)
+        }
+        None => (None, 0),
+    };
+
+    let imports: Vec<String> = facade
+        .document_index()
+        .get_imports_for_file(symbol.file_id)
+        .unwrap_or_default()
+        .iter()
+        .map(|import| {
+            let mut line = import.path.clone();
+            if import.is_glob {
+                line.push_str(" (glob)");
+            }
+            if let Some(alias) = &import.alias {
+                line.push_str(&format!(" as {alias}"));
+            }
+            line
+        })
+        .collect();
+    let imports_omitted = imports.len().saturating_sub(limits.imports);
+
+    Some(ContextBundle {
+        symbol: HierarchyItem::new(symbol, &locator),
+        module_path: symbol.as_module_path().map(str::to_string),
+        documentation: symbol
+            .doc_comment
+            .as_deref()
+            .map(render_doc_markdown)
+            .filter(|doc| !doc.is_empty()),
+        source,
+        source_lines_omitted,
+        callers: Related::new(
+            with_metadata(&relationships.called_by),
+            limits.related,
+            &locator,
+        ),
+        callees: Related::new(
+            with_metadata(&relationships.calls),
+            limits.related,
+            &locator,
+        ),
+        implements: Related::new(
+            relationships.implements.iter().flatten(),
+            limits.related,
+            &locator,
+        ),
+        implemented_by: Related::new(
+            relationships.implemented_by.iter().flatten(),
+            limits.related,
+            &locator,
+        ),
+        imports: imports.into_iter().take(limits.imports).collect(),
+        imports_omitted,
+    })
+}
+
+/// Symbols of a list of relationship targets carrying metadata
+fn with_metadata<M>(list: &Option<Vec<(Symbol, M)>>) -> impl Iterator<Item = &Symbol> {
+    list.iter().flatten().map(|(symbol, _)| symbol)
+}
+
+/// Lines of the symbol's definition in its current source
+fn definition_source(locator: &Locator, symbol: &Symbol) -> Option<Vec<String>> {
+    let source = locator.source(&symbol.file_path)?;
+    let start = symbol.range.start_line;
+    let end = symbol.range.end_line.max(start);
+    let lines: Vec<String> = (start..=end)
+        .map_while(|line| source.line(line))
+        .map(str::to_string)
+        .collect();
+    (!lines.is_empty()).then_some(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SymbolKind;
+    use crate::types::{FileId, Range};
+
+    fn function(id: u32, name: &str) -> Symbol {
+        Symbol::new(
+            SymbolId::new(id).unwrap(),
+            name,
+            SymbolKind::Function,
+            FileId::new(1).unwrap(),
+            Range::new(id, 0, id + 2, 1),
+        )
+        .with_file_path("src/lib.rs")
+    }
+
+    #[test]
+    fn test_related_lists_keep_their_full_size() {
+        let symbols = [function(1, "a"), function(2, "b"), function(3, "c")];
+        let locator = Locator::new(None, PositionEncoding::Byte);
+        let callers = Related::new(&symbols, 2, &locator);
+        assert_eq!(callers.items.len(), 2);
+        assert_eq!(callers.total, 3);
+
+        let bundle = ContextBundle {
+            symbol: HierarchyItem::new(&function(9, "parse"), &locator),
+            module_path: Some("crate::parser".to_string()),
+            documentation: None,
+            source: Some("fn parse() {\n    a();".to_string()),
+            source_lines_omitted: 1,
+            callers,
+            callees: Related::default(),
+            implements: Related::default(),
+            implemented_by: Related::default(),
+            imports: vec!["std::io".to_string()],
+            imports_omitted: 0,
+        };
+        assert_eq!(
+            bundle.to_string(),
+            "parse (Function) src/lib.rs:10 [symbol_id:9]\n\
+             Module: crate::parser\n\
+             \nSource:\nfn parse() {\n    a();\n... 1 more lines\n\
+             \nCallers (2 of 3):\n  a (Function) src/lib.rs:2 [symbol_id:1]\n  b (Function) src/lib.rs:3 [symbol_id:2]\n\
+             \nImports of src/lib.rs:\n  std::io\n"
+        );
+    }
+}
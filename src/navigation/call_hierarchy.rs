@@ -0,0 +1,378 @@
+//! Call hierarchy
+//!
+//! Callers (incoming) or callees (outgoing) of a symbol, expanded a few
+//! levels deep into a tree. Each node is an LSP `CallHierarchyItem` plus the
+//! `fromRanges` of its call sites, which is what `callHierarchy/incomingCalls`
+//! and `callHierarchy/outgoingCalls` return one level at a time:
+//!
+//! - incoming: the call sites are inside the caller (the node's own item)
+//! - outgoing: the call sites are inside the parent node, calling the item
+//!
+//! A symbol already on the path from the root is emitted once more, marked
+//! `recursive`, and not expanded again.
+
+use super::lsp::{HierarchyItem, Locator, LspRange};
+use crate::indexing::facade::IndexFacade;
+use crate::relationship::RelationshipMetadata;
+use crate::types::PositionEncoding;
+use crate::{Symbol, SymbolId};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Upper bound on nodes in one hierarchy, so deep expansions of hub
+/// functions stay readable
+const MAX_NODES: usize = 500;
+
+/// Which side of the call graph to expand
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CallDirection {
+    /// Functions calling the symbol
+    #[default]
+    Incoming,
+    /// Functions the symbol calls
+    Outgoing,
+}
+
+impl FromStr for CallDirection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "incoming" | "in" | "callers" => Ok(Self::Incoming),
+            "outgoing" | "out" | "calls" => Ok(Self::Outgoing),
+            other => Err(format!(
+                "unknown direction '{other}', expected 'incoming' or 'outgoing'"
+            )),
+        }
+    }
+}
+
+/// LSP `CallHierarchyItem`
+pub type CallHierarchyItem = HierarchyItem;
+
+/// One item of the hierarchy with the calls leading to it
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallHierarchyNode {
+    pub item: CallHierarchyItem,
+    /// Call sites connecting this node to its parent; empty for the root
+    pub from_ranges: Vec<LspRange>,
+    pub children: Vec<CallHierarchyNode>,
+    /// Already on the path from the root; children are not repeated
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub recursive: bool,
+    /// Children were cut off by the node limit
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+}
+
+impl CallHierarchyNode {
+    /// Number of nodes in the tree, including this one
+    pub fn node_count(&self) -> usize {
+        1 + self.children.iter().map(Self::node_count).sum::<usize>()
+    }
+
+    fn write_tree(&self, f: &mut fmt::Formatter<'_>, indent: usize) -> fmt::Result {
+        write!(
+            f,
+            "{:indent$}{} ({}) {}:{} [symbol_id:{}]",
+            "",
+            self.item.name,
+            self.item.kind_name(),
+            self.item.data.file_path,
+            self.item.range.start.line + 1,
+            self.item.data.symbol_id
+        )?;
+        if !self.from_ranges.is_empty() {
+            let lines: Vec<String> = self
+                .from_ranges
+                .iter()
+                .map(|range| (range.start.line + 1).to_string())
+                .collect();
+            write!(f, " [call sites: {}]", lines.join(", "))?;
+        }
+        if self.recursive {
+            write!(f, " (recursive)")?;
+        }
+        if self.truncated {
+            write!(f, " (truncated)")?;
+        }
+        for child in &self.children {
+            writeln!(f)?;
+            child.write_tree(f, indent + 2)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for CallHierarchyNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_tree(f, 0)
+    }
+}
+
+/// Call hierarchy of `symbol_id`, `depth` levels deep (at least one), with
+/// columns counted in `encoding`.
+///
+/// Returns `None` when the symbol is not in the index.
+pub fn call_hierarchy(
+    facade: &IndexFacade,
+    symbol_id: SymbolId,
+    direction: CallDirection,
+    depth: u32,
+    encoding: PositionEncoding,
+) -> Option<CallHierarchyNode> {
+    let root = facade.get_symbol(symbol_id)?;
+    let calls = |id| match direction {
+        CallDirection::Incoming => facade.get_calling_functions_with_metadata(id),
+        CallDirection::Outgoing => facade.get_called_functions_with_metadata(id),
+    };
+    let locator = Locator::for_facade(facade, encoding);
+    Some(build_hierarchy(&root, direction, depth, calls, &locator))
+}
+
+/// [`call_hierarchy`] over a custom call lookup
+pub fn build_hierarchy(
+    root: &Symbol,
+    direction: CallDirection,
+    depth: u32,
+    calls: impl Fn(SymbolId) -> Vec<(Symbol, Option<RelationshipMetadata>)>,
+    locator: &Locator,
+) -> CallHierarchyNode {
+    let mut builder = Builder {
+        direction,
+        calls,
+        locator,
+        path: Vec::new(),
+        budget: MAX_NODES,
+    };
+    builder.expand(root, Vec::new(), depth.max(1))
+}
+
+struct Builder<'a, C> {
+    direction: CallDirection,
+    calls: C,
+    locator: &'a Locator,
+    /// Symbols from the root to the node being expanded
+    path: Vec<SymbolId>,
+    /// Nodes that may still be added
+    budget: usize,
+}
+
+impl<C> Builder<'_, C>
+where
+    C: Fn(SymbolId) -> Vec<(Symbol, Option<RelationshipMetadata>)>,
+{
+    fn expand(
+        &mut self,
+        symbol: &Symbol,
+        from_ranges: Vec<LspRange>,
+        depth: u32,
+    ) -> CallHierarchyNode {
+        let mut node = CallHierarchyNode {
+            item: HierarchyItem::new(symbol, self.locator),
+            from_ranges,
+            children: Vec::new(),
+            recursive: self.path.contains(&symbol.id),
+            truncated: false,
+        };
+        if node.recursive || depth == 0 {
+            return node;
+        }
+
+        self.path.push(symbol.id);
+        for (target, ranges) in self.edges(symbol) {
+            if self.budget == 0 {
+                node.truncated = true;
+                break;
+            }
+            self.budget -= 1;
+            let child = self.expand(&target, ranges, depth - 1);
+            node.children.push(child);
+        }
+        self.path.pop();
+        node
+    }
+
+    /// Calls from or to `symbol`, one entry per symbol with all its call
+    /// sites, in first-seen order
+    fn edges(&self, symbol: &Symbol) -> Vec<(Symbol, Vec<LspRange>)> {
+        let mut edges: Vec<(Symbol, Vec<LspRange>)> = Vec::new();
+        for (target, metadata) in (self.calls)(symbol.id) {
+            // The span covers the callee name at the call site, which is
+            // in the caller's file
+            let (caller, callee) = match self.direction {
+                CallDirection::Incoming => (&target, symbol),
+                CallDirection::Outgoing => (symbol, &target),
+            };
+            let range = metadata.and_then(|m| {
+                Some(self.locator.span(
+                    &caller.file_path,
+                    m.line?,
+                    m.column? as u32,
+                    callee.as_name().len() as u32,
+                ))
+            });
+
+            let index = match edges.iter().position(|(s, _)| s.id == target.id) {
+                Some(index) => index,
+                None => {
+                    edges.push((target, Vec::new()));
+                    edges.len() - 1
+                }
+            };
+            edges[index].1.extend(range);
+        }
+        for (_, ranges) in &mut edges {
+            ranges.sort();
+            ranges.dedup();
+        }
+        edges
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SymbolKind;
+    use crate::navigation::lsp::Position;
+    use crate::types::{FileId, Range};
+    use std::collections::HashMap;
+    use std::path::Path;
+
+    fn locator() -> Locator {
+        Locator::new(Some(Path::new("/work")), PositionEncoding::Byte)
+    }
+
+    fn function(id: u32, name: &str, line: u32) -> Symbol {
+        Symbol::new(
+            SymbolId::new(id).unwrap(),
+            name,
+            SymbolKind::Function,
+            FileId::new(1).unwrap(),
+            Range::new(line, 0, line + 5, 1),
+        )
+        .with_file_path("src/lib.rs")
+        .with_signature(format!("fn {name}()"))
+    }
+
+    fn call_at(line: u32, column: u16) -> Option<RelationshipMetadata> {
+        Some(RelationshipMetadata::new().at_position(line, column))
+    }
+
+    #[test]
+    fn test_incoming_merges_call_sites_and_stops_at_cycles() {
+        let main = function(1, "main", 0);
+        let run = function(2, "run", 10);
+        let step = function(3, "step", 20);
+
+        // main -> run (twice), run -> step, step -> run
+        let mut callers: HashMap<u32, Vec<(Symbol, Option<RelationshipMetadata>)>> = HashMap::new();
+        callers.insert(
+            2,
+            vec![
+                (main.clone(), call_at(2, 4)),
+                (main, call_at(1, 4)),
+                (step.clone(), call_at(22, 8)),
+            ],
+        );
+        callers.insert(3, vec![(run.clone(), call_at(12, 8))]);
+
+        let tree = build_hierarchy(
+            &run,
+            CallDirection::Incoming,
+            5,
+            |id| callers.get(&id.value()).cloned().unwrap_or_default(),
+            &locator(),
+        );
+
+        assert_eq!(tree.item.name, "run");
+        assert_eq!(tree.item.selection_range, LspRange::at(10, 3, 3));
+        assert_eq!(tree.item.uri, "file:///work/src/lib.rs");
+        assert!(tree.from_ranges.is_empty());
+
+        let names: Vec<&str> = tree.children.iter().map(|c| c.item.name.as_str()).collect();
+        assert_eq!(names, ["main", "step"]);
+        assert_eq!(
+            tree.children[0].from_ranges,
+            [LspRange::at(1, 4, 3), LspRange::at(2, 4, 3)]
+        );
+
+        // step is called by run, which is already on the path
+        let step_node = &tree.children[1];
+        assert_eq!(step_node.children.len(), 1);
+        assert!(step_node.children[0].recursive);
+        assert!(step_node.children[0].children.is_empty());
+        assert_eq!(tree.node_count(), 4);
+    }
+
+    #[test]
+    fn test_outgoing_ranges_span_the_callee_and_respect_depth() {
+        let main = function(1, "main", 0);
+        let parse = function(2, "parse", 10);
+        let tokenize = function(3, "tokenize", 20);
+
+        let mut calls: HashMap<u32, Vec<(Symbol, Option<RelationshipMetadata>)>> = HashMap::new();
+        calls.insert(1, vec![(parse.clone(), call_at(3, 8))]);
+        calls.insert(2, vec![(tokenize, None)]);
+
+        let lookup = |id: SymbolId| calls.get(&id.value()).cloned().unwrap_or_default();
+        let locator = locator();
+        let shallow = build_hierarchy(&main, CallDirection::Outgoing, 1, lookup, &locator);
+        assert_eq!(shallow.children.len(), 1);
+        assert_eq!(shallow.children[0].from_ranges, [LspRange::at(3, 8, 5)]);
+        assert!(shallow.children[0].children.is_empty());
+
+        let deep = build_hierarchy(&main, CallDirection::Outgoing, 2, lookup, &locator);
+        let tokenize_node = &deep.children[0].children[0];
+        assert_eq!(tokenize_node.item.name, "tokenize");
+        assert!(tokenize_node.from_ranges.is_empty());
+    }
+
+    #[test]
+    fn test_utf16_columns_on_non_ascii_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        // 'é' is 2 bytes / 1 UTF-16 unit, '🦀' is 4 bytes / 2 units
+        std::fs::write(
+            dir.path().join("src/lib.rs"),
+            "/* é🦀 */ fn run() { step() }\n",
+        )
+        .unwrap();
+        let mut run = function(1, "run", 0);
+        run.range = Range::new(0, 13, 0, 32);
+        let step = function(2, "step", 5);
+
+        let locator = Locator::new(Some(dir.path()), PositionEncoding::Utf16);
+        let calls = |id: SymbolId| match id.value() {
+            1 => vec![(step.clone(), call_at(0, 24))],
+            _ => Vec::new(),
+        };
+        let tree = build_hierarchy(&run, CallDirection::Outgoing, 1, calls, &locator);
+
+        assert_eq!(
+            tree.item.range,
+            LspRange {
+                start: Position {
+                    line: 0,
+                    character: 10
+                },
+                end: Position {
+                    line: 0,
+                    character: 29
+                },
+            }
+        );
+        assert_eq!(tree.item.selection_range, LspRange::at(0, 13, 3));
+        assert_eq!(tree.children[0].from_ranges, [LspRange::at(0, 21, 4)]);
+    }
+
+    #[test]
+    fn test_direction_from_str() {
+        assert_eq!("incoming".parse(), Ok(CallDirection::Incoming));
+        assert_eq!("Out".parse(), Ok(CallDirection::Outgoing));
+        assert!("sideways".parse::<CallDirection>().is_err());
+    }
+}
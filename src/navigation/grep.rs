@@ -0,0 +1,186 @@
+//! Content grep with symbol context
+//!
+//! A regex search over the sources of indexed files. Each matching line is
+//! reported with the symbols enclosing it, so a hit reads "inside method
+//! parse of class Parser" instead of a bare `file:line`, and the symbol ids
+//! lead straight on to callers, callees, or the definition.
+
+use super::lsp::{HierarchyItem, Locator, LspRange, Source};
+use super::symbol_at::enclosing_symbols;
+use crate::Symbol;
+use crate::indexing::facade::IndexFacade;
+use crate::types::PositionEncoding;
+use regex::Regex;
+use serde::Serialize;
+use std::fmt;
+
+/// A line matching the pattern and the code around it
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrepMatch {
+    /// Path as indexed
+    pub file_path: String,
+    /// First match on the line
+    pub range: LspRange,
+    /// The matching line
+    pub text: String,
+    /// "inside method parse of class Parser"; absent at file level
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+    /// Symbols containing the match, innermost first
+    pub symbols: Vec<HierarchyItem>,
+}
+
+impl fmt::Display for GrepMatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}: {}",
+            self.file_path,
+            self.range.start.line + 1,
+            self.range.start.character + 1,
+            self.text.trim()
+        )?;
+        if let (Some(context), Some(innermost)) = (&self.context, self.symbols.first()) {
+            write!(f, "\n  {context} [symbol_id:{}]", innermost.data.symbol_id)?;
+        }
+        Ok(())
+    }
+}
+
+/// Lines of indexed files matching `pattern`, at most `limit`, ordered by
+/// path and line, with columns counted in `encoding`. Files that can no
+/// longer be read are skipped.
+pub fn grep(
+    facade: &IndexFacade,
+    pattern: &Regex,
+    limit: usize,
+    encoding: PositionEncoding,
+) -> Vec<GrepMatch> {
+    let mut paths: Vec<String> = facade
+        .get_all_indexed_paths()
+        .iter()
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect();
+    paths.sort();
+
+    let locator = Locator::for_facade(facade, encoding);
+    let mut matches = Vec::new();
+    for path in paths {
+        if matches.len() >= limit {
+            break;
+        }
+        let Some(source) = locator.source(&path) else {
+            continue;
+        };
+        let symbols = || {
+            facade
+                .get_file_id_for_path(&path)
+                .map(|file_id| facade.get_symbols_by_file(file_id))
+                .unwrap_or_default()
+        };
+        let remaining = limit - matches.len();
+        matches.extend(grep_file(
+            &path, &source, symbols, pattern, remaining, &locator,
+        ));
+        locator.release(&path);
+    }
+    matches
+}
+
+/// Matches in one file's `source`; its symbols are only loaded once a line
+/// matches
+fn grep_file(
+    path: &str,
+    source: &Source,
+    symbols: impl FnOnce() -> Vec<Symbol>,
+    pattern: &Regex,
+    limit: usize,
+    locator: &Locator,
+) -> Vec<GrepMatch> {
+    let mut symbols = Some(symbols);
+    let mut loaded = Vec::new();
+    let mut matches = Vec::new();
+    for (line, text) in source.lines() {
+        if matches.len() >= limit {
+            break;
+        }
+        let Some(found) = pattern.find(text) else {
+            continue;
+        };
+        if let Some(load) = symbols.take() {
+            loaded = load();
+        }
+        let enclosing: Vec<HierarchyItem> = enclosing_symbols(&loaded, line, found.start() as u32)
+            .into_iter()
+            .rev()
+            .map(|symbol| HierarchyItem::new(symbol, locator))
+            .collect();
+        matches.push(GrepMatch {
+            file_path: path.to_string(),
+            range: locator.span_in(text, line, found.start(), found.end()),
+            text: text.to_string(),
+            context: describe_context(&enclosing),
+            symbols: enclosing,
+        });
+    }
+    matches
+}
+
+/// "inside method parse of class Parser" for symbols innermost first
+fn describe_context(symbols: &[HierarchyItem]) -> Option<String> {
+    let (innermost, outer) = symbols.split_first()?;
+    let describe =
+        |item: &HierarchyItem| format!("{} {}", item.kind_name().to_ascii_lowercase(), item.name);
+    let mut context = format!("inside {}", describe(innermost));
+    for item in outer {
+        context.push_str(" of ");
+        context.push_str(&describe(item));
+    }
+    Some(context)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{FileId, Range};
+    use crate::{SymbolId, SymbolKind};
+
+    #[test]
+    fn test_matches_carry_their_enclosing_symbols() {
+        let source =
+            "use std::io;\n\nclass Parser {\n    fn parse() {\n        retry(3);\n    }\n}\n";
+        let symbol = |id, name, kind, range| {
+            Symbol::new(
+                SymbolId::new(id).unwrap(),
+                name,
+                kind,
+                FileId::new(1).unwrap(),
+                range,
+            )
+            .with_file_path("src/parser.rs")
+        };
+        let symbols = vec![
+            symbol(1, "Parser", SymbolKind::Class, Range::new(2, 0, 6, 1)),
+            symbol(2, "parse", SymbolKind::Method, Range::new(3, 4, 5, 5)),
+        ];
+        let pattern = Regex::new(r"retry|std").unwrap();
+        let source = Source::new(source.to_string());
+        let locator = Locator::new(None, PositionEncoding::Byte);
+
+        let matches = grep_file("src/parser.rs", &source, || symbols, &pattern, 10, &locator);
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].context, None);
+        assert_eq!(
+            matches[1].context.as_deref(),
+            Some("inside method parse of class Parser")
+        );
+        assert_eq!(
+            matches[1].to_string(),
+            "src/parser.rs:5:9: retry(3);\n  inside method parse of class Parser [symbol_id:2]"
+        );
+
+        let first = grep_file("src/parser.rs", &source, Vec::new, &pattern, 1, &locator);
+        assert_eq!(first.len(), 1);
+    }
+}
@@ -0,0 +1,138 @@
+//! Symbol history
+//!
+//! The commits that touched a symbol, newest first, found with
+//! `git log -L` over the symbol's indexed line range. Git traces the range
+//! back through earlier versions of the file, so edits made before the
+//! symbol moved within it are still found. libgit2 has no line-range log,
+//! so this runs the `git` binary.
+
+use super::source_path;
+use crate::Symbol;
+use crate::indexing::facade::IndexFacade;
+use serde::Serialize;
+use std::fmt;
+use std::path::Path;
+use std::process::Command;
+use thiserror::Error;
+
+/// Separates the fields of a commit in the log format
+const FIELD_SEPARATOR: char = '\u{1f}';
+/// Starts a commit header line, telling it apart from patch lines
+const RECORD_MARKER: char = '\u{1e}';
+
+#[derive(Error, Debug)]
+pub enum HistoryError {
+    #[error("Failed to run git: {0}\nSuggestion: Install git and make sure it is on PATH")]
+    GitUnavailable(#[from] std::io::Error),
+
+    #[error("git log failed for {file_path}: {message}")]
+    Log { file_path: String, message: String },
+}
+
+/// A commit that touched a symbol
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SymbolCommit {
+    /// Full commit hash
+    pub commit: String,
+    pub author: String,
+    pub email: String,
+    /// Author time, Unix seconds
+    pub timestamp: i64,
+    /// First line of the commit message
+    pub summary: String,
+}
+
+impl fmt::Display for SymbolCommit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let date = chrono::DateTime::from_timestamp(self.timestamp, 0)
+            .map(|date| date.format("%Y-%m-%d").to_string())
+            .unwrap_or_default();
+        let short = self.commit.get(..10).unwrap_or(&self.commit);
+        write!(f, "{short} {date} {}  {}", self.author, self.summary)
+    }
+}
+
+/// Commits that touched the lines of `symbol`, newest first, at most
+/// `limit` when given.
+///
+/// The range is taken from the index and applied to the file as committed
+/// at HEAD, so uncommitted edits above the symbol shift it.
+pub fn history(
+    facade: &IndexFacade,
+    symbol: &Symbol,
+    limit: Option<usize>,
+) -> Result<Vec<SymbolCommit>, HistoryError> {
+    let path = source_path(facade, &symbol.file_path);
+    let (dir, file_name) = match (path.parent(), path.file_name()) {
+        (Some(dir), Some(name)) if !dir.as_os_str().is_empty() => (dir, Path::new(name)),
+        _ => (Path::new("."), path.as_path()),
+    };
+
+    let mut command = Command::new("git");
+    command.current_dir(dir).arg("log").arg(format!(
+        "--format={RECORD_MARKER}%H{FIELD_SEPARATOR}%an{FIELD_SEPARATOR}%ae{FIELD_SEPARATOR}%at{FIELD_SEPARATOR}%s"
+    ));
+    if let Some(limit) = limit {
+        command.arg(format!("--max-count={limit}"));
+    }
+    command.arg(format!(
+        "-L{},{}:{}",
+        symbol.range.start_line + 1,
+        symbol.range.end_line + 1,
+        file_name.display()
+    ));
+
+    let output = command.output()?;
+    if !output.status.success() {
+        return Err(HistoryError::Log {
+            file_path: symbol.file_path.to_string(),
+            message: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+    Ok(parse_log(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Commit headers of `git log -L` output, skipping the patches between them
+fn parse_log(output: &str) -> Vec<SymbolCommit> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.strip_prefix(RECORD_MARKER)?.split(FIELD_SEPARATOR);
+            Some(SymbolCommit {
+                commit: fields.next()?.to_string(),
+                author: fields.next()?.to_string(),
+                email: fields.next()?.to_string(),
+                timestamp: fields.next()?.parse().ok()?,
+                summary: fields.next().unwrap_or_default().to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_log_skips_patches() {
+        let output = "\u{1e}4f2a9c1d3e\u{1f}Ada\u{1f}ada@example.com\u{1f}1760000000\u{1f}Handle empty input\n\
+             \n\
+             diff --git a/src/lib.rs b/src/lib.rs\n\
+             --- a/src/lib.rs\n\
+             +++ b/src/lib.rs\n\
+             @@ -3,2 +3,3 @@\n\
+             +    if input.is_empty() {\n\
+             \u{1e}9b1e07aa52\u{1f}Grace\u{1f}grace@example.com\u{1f}1750000000\u{1f}Add parser\n";
+        let commits = parse_log(output);
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].commit, "4f2a9c1d3e");
+        assert_eq!(commits[0].author, "Ada");
+        assert_eq!(commits[0].summary, "Handle empty input");
+        assert_eq!(commits[1].timestamp, 1_750_000_000);
+        assert_eq!(
+            commits[1].to_string(),
+            "9b1e07aa52 2025-06-15 Grace  Add parser"
+        );
+    }
+}
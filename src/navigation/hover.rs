@@ -0,0 +1,240 @@
+//! Hover content for a symbol
+//!
+//! One payload carries what an editor shows on hover: the signature in a
+//! code block, the doc comment as markdown, and where the symbol is defined.
+//! The parts are kept alongside the rendered markdown so the MCP `hover`
+//! tool, a language server, and `retrieve describe` all show the same thing.
+//!
+//! Doc comments are stored as written minus their comment markers, which
+//! for Javadoc-style blocks still leaves `*` gutters and `@param` tags.
+//! [`render_doc_markdown`] strips the gutters and turns the common tags into
+//! markdown sections. Signatures go through the language's normalizer, so
+//! they show without attributes, bodies or stray line breaks.
+
+use super::lsp::{Locator, LspRange};
+use crate::Symbol;
+use crate::indexing::facade::IndexFacade;
+use crate::parsing::display_signature;
+use crate::symbol::context::SymbolContext;
+use crate::types::PositionEncoding;
+use serde::Serialize;
+use std::fmt;
+
+/// LSP `MarkupContent`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MarkupContent {
+    /// Always "markdown"
+    pub kind: &'static str,
+    pub value: String,
+}
+
+/// Where a hovered symbol is defined
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HoverLocation {
+    pub file_path: String,
+    /// 1-based
+    pub line: u32,
+    pub uri: String,
+}
+
+/// Hover payload: an LSP `Hover` (`contents` and `range`) plus the parts it
+/// was rendered from
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Hover {
+    pub contents: MarkupContent,
+    /// Extent of the symbol
+    pub range: LspRange,
+    pub symbol_id: u32,
+    pub name: String,
+    pub kind: crate::SymbolKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    /// The doc comment rendered as markdown
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub documentation: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub module_path: Option<String>,
+    pub location: HoverLocation,
+}
+
+impl fmt::Display for Hover {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.contents.value)
+    }
+}
+
+/// Hover content for `symbol`, with the range's columns counted in
+/// `encoding`
+pub fn hover(facade: &IndexFacade, symbol: &Symbol, encoding: PositionEncoding) -> Hover {
+    let locator = Locator::for_facade(facade, encoding);
+    let location = HoverLocation {
+        file_path: symbol.file_path.to_string(),
+        line: symbol.range.start_line + 1,
+        uri: locator.uri(&symbol.file_path),
+    };
+    let documentation = symbol
+        .doc_comment
+        .as_deref()
+        .map(render_doc_markdown)
+        .filter(|doc| !doc.is_empty());
+    let style = facade.settings().display.signature_style();
+    let signature = symbol
+        .as_signature()
+        .map(|signature| display_signature(signature, symbol.language_id, style));
+    let module_path = symbol.as_module_path().map(str::to_string);
+
+    let mut markdown = String::new();
+    if let Some(signature) = &signature {
+        let language = symbol.language_id.map(|id| id.as_str()).unwrap_or_default();
+        markdown.push_str(&format!("```{language}\n{signature}\n```\n"));
+    } else {
+        markdown.push_str(&format!("**{}**\n", symbol.name));
+    }
+    if let Some(doc) = &documentation {
+        markdown.push_str(&format!("\n---\n\n{doc}\n"));
+    }
+    markdown.push_str("\n---\n\n");
+    markdown.push_str(&format!("*{:?}*", symbol.kind));
+    if let Some(module) = &module_path {
+        markdown.push_str(&format!(" in `{module}`"));
+    }
+    markdown.push_str(&format!(" at `{}`", SymbolContext::symbol_location(symbol)));
+
+    Hover {
+        contents: MarkupContent {
+            kind: "markdown",
+            value: markdown,
+        },
+        range: locator.range(&symbol.file_path, symbol.range),
+        symbol_id: symbol.id.value(),
+        name: symbol.name.to_string(),
+        kind: symbol.kind,
+        signature,
+        documentation,
+        module_path,
+        location,
+    }
+}
+
+/// A stored doc comment as markdown: `*` gutters removed, `{@code x}` and
+/// `{@link X}` as inline code, and `@param`/`@return`/`@throws` tags
+/// gathered into sections after the description
+pub fn render_doc_markdown(doc: &str) -> String {
+    // Only blocks written with a gutter on every line have one to strip, so
+    // markdown lists starting with `*` survive
+    let gutter = doc
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .all(|line| line.trim_start().starts_with('*'));
+
+    let mut description = Vec::new();
+    // Parameters, returns, throws, and other tags
+    let mut sections: [Vec<String>; 4] = Default::default();
+    // The section a tag's continuation lines belong to
+    let mut current: Option<usize> = None;
+    for line in doc.lines() {
+        let line = inline_tags(if gutter { strip_gutter(line) } else { line });
+        let trimmed = line.trim();
+        let Some(tag) = trimmed.strip_prefix('@') else {
+            match current.and_then(|section| sections[section].last_mut()) {
+                Some(last) if !trimmed.is_empty() => {
+                    last.push(' ');
+                    last.push_str(trimmed);
+                }
+                _ => {
+                    current = None;
+                    description.push(line);
+                }
+            }
+            continue;
+        };
+        let (name, rest) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+        let rest = rest.trim();
+        let (section, item) = match name {
+            "param" | "arg" | "argument" => {
+                let (param, text) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+                (
+                    0,
+                    format!("- `{}` {}", param.trim_start_matches('$'), text.trim()),
+                )
+            }
+            "return" | "returns" => (1, rest.to_string()),
+            "throws" | "throw" | "exception" | "raises" => {
+                let (error, text) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+                (2, format!("- `{error}` {}", text.trim()))
+            }
+            _ => (3, format!("*@{name}* {rest}")),
+        };
+        sections[section].push(item.trim_end().to_string());
+        current = Some(section);
+    }
+
+    let mut markdown = description.join("\n").trim().to_string();
+    let [params, returns, throws, other] = sections;
+    for (title, items) in [
+        ("Parameters", params),
+        ("Returns", returns),
+        ("Throws", throws),
+    ] {
+        if items.is_empty() {
+            continue;
+        }
+        if !markdown.is_empty() {
+            markdown.push_str("\n\n");
+        }
+        markdown.push_str(&format!("**{title}**\n{}", items.join("\n")));
+    }
+    for line in other {
+        if !markdown.is_empty() {
+            markdown.push_str("\n\n");
+        }
+        markdown.push_str(&line);
+    }
+    markdown
+}
+
+/// A line without its leading ` * ` gutter
+fn strip_gutter(line: &str) -> &str {
+    let rest = line.trim_start().trim_start_matches('*');
+    rest.strip_prefix(' ').unwrap_or(rest)
+}
+
+/// `{@code x}`, `{@link X}`, and `{@linkplain X}` as inline code
+fn inline_tags(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some(start) = rest.find("{@") {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        let inner = &rest[start + 2..start + end];
+        let text = inner
+            .split_once(char::is_whitespace)
+            .map(|(_, text)| text.trim())
+            .unwrap_or_default();
+        out.push_str(&format!("`{text}`"));
+        rest = &rest[start + end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_javadoc_tags_become_markdown_sections() {
+        let doc = "* Parses the {@code input} into a {@link Config}.\n*\n* @param input the raw text,\n*        never null\n* @return the parsed config\n* @throws ParseException on bad syntax\n* @since 2.1";
+
+        assert_eq!(
+            render_doc_markdown(doc),
+            "Parses the `input` into a `Config`.\n\n**Parameters**\n- `input` the raw text, never null\n\n**Returns**\nthe parsed config\n\n**Throws**\n- `ParseException` on bad syntax\n\n*@since* 2.1"
+        );
+        assert_eq!(
+            render_doc_markdown("Plain markdown with *emphasis*\n\n- a list"),
+            "Plain markdown with *emphasis*\n\n- a list"
+        );
+    }
+}
@@ -0,0 +1,336 @@
+//! LSP value types shared by the navigation requests
+//!
+//! Field names and encodings follow the Language Server Protocol so results
+//! can be handed to an editor unchanged. Lines are 0-based like the index.
+//! The index stores byte columns; a [`Locator`] converts them to the
+//! requested [`PositionEncoding`] (UTF-16 for most editors) through a
+//! [`LineIndex`] of each file's current source.
+
+use super::resolve_path;
+use crate::indexing::facade::IndexFacade;
+use crate::types::{LineIndex, PositionEncoding, Range, encoded_column};
+use crate::{Symbol, SymbolKind};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Zero-based line and character offset
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct Position {
+    pub line: u32,
+    pub character: u32,
+}
+
+/// Half-open span between two positions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct LspRange {
+    pub start: Position,
+    pub end: Position,
+}
+
+impl LspRange {
+    /// Single-line span of `len` characters starting at `line:character`
+    pub fn at(line: u32, character: u32, len: u32) -> Self {
+        Self {
+            start: Position { line, character },
+            end: Position {
+                line,
+                character: character + len,
+            },
+        }
+    }
+}
+
+impl From<Range> for LspRange {
+    fn from(range: Range) -> Self {
+        Self {
+            start: Position {
+                line: range.start_line,
+                character: range.start_column as u32,
+            },
+            end: Position {
+                line: range.end_line,
+                character: range.end_column as u32,
+            },
+        }
+    }
+}
+
+/// A file's source with its line index
+#[derive(Debug)]
+pub struct Source {
+    pub text: String,
+    pub lines: LineIndex,
+}
+
+impl Source {
+    pub fn new(text: String) -> Self {
+        let lines = LineIndex::new(&text);
+        Self { text, lines }
+    }
+
+    /// Text of zero-based `line`, without its line terminator
+    pub fn line(&self, line: u32) -> Option<&str> {
+        self.lines.line(&self.text, line)
+    }
+
+    /// Zero-based line numbers and texts, in order
+    pub fn lines(&self) -> impl Iterator<Item = (u32, &str)> {
+        (0..self.lines.line_count() as u32).filter_map(|line| Some((line, self.line(line)?)))
+    }
+}
+
+/// Maps indexed paths to URIs and byte columns to `encoding`.
+///
+/// Sources are read on first use and kept for the locator's lifetime, so
+/// the ranges of one file share a single read and line index. Columns of
+/// files that can no longer be read stay in bytes.
+#[derive(Debug)]
+pub struct Locator {
+    root: Option<PathBuf>,
+    encoding: PositionEncoding,
+    sources: Mutex<HashMap<String, Option<Arc<Source>>>>,
+}
+
+impl Locator {
+    /// Locator resolving relative paths against `root`
+    pub fn new(root: Option<&Path>, encoding: PositionEncoding) -> Self {
+        Self {
+            root: root.map(Path::to_path_buf),
+            encoding,
+            sources: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Locator for the facade's workspace
+    pub fn for_facade(facade: &IndexFacade, encoding: PositionEncoding) -> Self {
+        Self::new(facade.settings().workspace_root.as_deref(), encoding)
+    }
+
+    pub fn encoding(&self) -> PositionEncoding {
+        self.encoding
+    }
+
+    /// Absolute path of an indexed file
+    pub fn path(&self, file_path: &str) -> PathBuf {
+        resolve_path(self.root.as_deref(), file_path)
+    }
+
+    /// `file://` URI of an indexed file
+    pub fn uri(&self, file_path: &str) -> String {
+        file_uri(&self.path(file_path))
+    }
+
+    /// Current source of an indexed file, or `None` when it can't be read
+    pub fn source(&self, file_path: &str) -> Option<Arc<Source>> {
+        let mut sources = self.sources.lock().unwrap_or_else(|e| e.into_inner());
+        sources
+            .entry(file_path.to_string())
+            .or_insert_with(|| {
+                let text = std::fs::read_to_string(self.path(file_path)).ok()?;
+                Some(Arc::new(Source::new(text)))
+            })
+            .clone()
+    }
+
+    /// Drop the kept source of a file, for callers walking many files
+    pub fn release(&self, file_path: &str) {
+        let mut sources = self.sources.lock().unwrap_or_else(|e| e.into_inner());
+        sources.remove(file_path);
+    }
+
+    /// Column of byte offset `byte` on `line` of the file, in `encoding`
+    pub fn character(&self, file_path: &str, line: u32, byte: u32) -> u32 {
+        if self.encoding == PositionEncoding::Byte {
+            return byte;
+        }
+        self.source(file_path)
+            .and_then(|source| {
+                source.lines.convert(
+                    &source.text,
+                    line,
+                    byte,
+                    PositionEncoding::Byte,
+                    self.encoding,
+                )
+            })
+            .unwrap_or(byte)
+    }
+
+    /// Index range of a symbol in the file, in `encoding`
+    pub fn range(&self, file_path: &str, range: Range) -> LspRange {
+        LspRange {
+            start: Position {
+                line: range.start_line,
+                character: self.character(file_path, range.start_line, range.start_column as u32),
+            },
+            end: Position {
+                line: range.end_line,
+                character: self.character(file_path, range.end_line, range.end_column as u32),
+            },
+        }
+    }
+
+    /// Single-line span of `len` bytes at byte column `start`, in `encoding`
+    pub fn span(&self, file_path: &str, line: u32, start: u32, len: u32) -> LspRange {
+        LspRange {
+            start: Position {
+                line,
+                character: self.character(file_path, line, start),
+            },
+            end: Position {
+                line,
+                character: self.character(file_path, line, start + len),
+            },
+        }
+    }
+
+    /// Span of the bytes `start..end` of `text`, a line at hand, in `encoding`
+    pub fn span_in(&self, text: &str, line: u32, start: usize, end: usize) -> LspRange {
+        let character = |byte| encoded_column(text, byte, self.encoding) as u32;
+        LspRange {
+            start: Position {
+                line,
+                character: character(start),
+            },
+            end: Position {
+                line,
+                character: character(end),
+            },
+        }
+    }
+}
+
+/// LSP `SymbolKind` number for an index symbol kind
+pub fn symbol_kind(kind: SymbolKind) -> u8 {
+    match kind {
+        SymbolKind::Module => 2,
+        SymbolKind::Class => 5,
+        SymbolKind::Method => 6,
+        SymbolKind::Field => 8,
+        SymbolKind::Enum => 10,
+        SymbolKind::Interface | SymbolKind::Trait => 11,
+        SymbolKind::Function | SymbolKind::Macro => 12,
+        SymbolKind::Variable | SymbolKind::Parameter => 13,
+        SymbolKind::Constant => 14,
+        SymbolKind::Struct => 23,
+        SymbolKind::TypeAlias => 26,
+    }
+}
+
+/// Display name of an LSP `SymbolKind` number
+pub(super) fn symbol_kind_name(kind: u8) -> &'static str {
+    match kind {
+        2 => "Module",
+        5 => "Class",
+        6 => "Method",
+        8 => "Field",
+        10 => "Enum",
+        11 => "Interface",
+        12 => "Function",
+        13 => "Variable",
+        14 => "Constant",
+        23 => "Struct",
+        26 => "Type",
+        _ => "Symbol",
+    }
+}
+
+/// Item of a call or type hierarchy; LSP `CallHierarchyItem` and
+/// `TypeHierarchyItem` share this shape
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HierarchyItem {
+    pub name: String,
+    /// LSP `SymbolKind` number
+    pub kind: u8,
+    /// Signature, shown next to the name by editors
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    pub uri: String,
+    /// Full extent of the symbol
+    pub range: LspRange,
+    /// The symbol's name within `range`
+    pub selection_range: LspRange,
+    pub data: HierarchyData,
+}
+
+/// Round-tripped by LSP clients; identifies the item in the index
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HierarchyData {
+    pub symbol_id: u32,
+    pub file_path: String,
+}
+
+impl HierarchyItem {
+    /// Item for `symbol`, positioned by `locator`
+    pub fn new(symbol: &Symbol, locator: &Locator) -> Self {
+        Self {
+            name: symbol.name.to_string(),
+            kind: symbol_kind(symbol.kind),
+            detail: symbol.signature.as_deref().map(str::to_string),
+            uri: locator.uri(&symbol.file_path),
+            range: locator.range(&symbol.file_path, symbol.range),
+            selection_range: selection_range(symbol, locator),
+            data: HierarchyData {
+                symbol_id: symbol.id.value(),
+                file_path: symbol.file_path.to_string(),
+            },
+        }
+    }
+
+    pub fn kind_name(&self) -> &'static str {
+        symbol_kind_name(self.kind)
+    }
+}
+
+/// Span of the name, located through the signature, which starts where the
+/// symbol does; the start of the symbol when the name is not found there
+pub(super) fn selection_range(symbol: &Symbol, locator: &Locator) -> LspRange {
+    let name = symbol.as_name();
+    let offset = symbol
+        .signature
+        .as_deref()
+        .and_then(|signature| signature.lines().next())
+        .and_then(|first_line| first_line.find(name));
+    let (offset, len) = match offset {
+        Some(offset) => (offset as u32, name.len() as u32),
+        None => (0, 0),
+    };
+    locator.span(
+        &symbol.file_path,
+        symbol.range.start_line,
+        symbol.range.start_column as u32 + offset,
+        len,
+    )
+}
+
+/// `file://` URI for an absolute path
+pub fn file_uri(path: &Path) -> String {
+    let mut uri = String::from("file://");
+    for byte in path.to_string_lossy().bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' => {
+                uri.push(byte as char)
+            }
+            b'\\' => uri.push('/'),
+            _ => uri.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    uri
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_uri_escapes_reserved_characters() {
+        assert_eq!(
+            file_uri(Path::new("/work/my project/a#b.rs")),
+            "file:///work/my%20project/a%23b.rs"
+        );
+    }
+}
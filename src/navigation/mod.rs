@@ -0,0 +1,67 @@
+//! Editor-style navigation over the index
+//!
+//! Answers the questions an editor asks about a symbol (where is its type
+//! defined, who calls it, what does it extend) in shapes close to the
+//! corresponding LSP requests, so the CLI, MCP tools, and a future language
+//! server can share them.
+
+pub mod bundle;
+pub mod call_hierarchy;
+pub mod grep;
+pub mod history;
+pub mod hover;
+pub mod lsp;
+pub mod outline;
+pub mod symbol_at;
+pub mod type_definition;
+pub mod type_hierarchy;
+
+pub use bundle::{BundleLimits, ContextBundle, Related, context_bundle};
+pub use call_hierarchy::{CallDirection, CallHierarchyItem, CallHierarchyNode, call_hierarchy};
+pub use grep::{GrepMatch, grep};
+pub use history::{HistoryError, SymbolCommit, history};
+pub use hover::{Hover, HoverLocation, MarkupContent, hover, render_doc_markdown};
+pub use lsp::Locator;
+pub use outline::{OutlineNode, file_outline};
+pub use symbol_at::{ReferenceAt, SymbolAt, symbol_at};
+pub use type_definition::{TypeDefinition, TypeTarget, find_type_definition};
+pub use type_hierarchy::{TypeHierarchy, TypeHierarchyItem, TypeHierarchyNode, type_hierarchy};
+
+use crate::indexing::facade::IndexFacade;
+use crate::types::FileId;
+use std::path::{Path, PathBuf};
+
+/// Absolute path of an indexed file; relative paths are resolved against the
+/// workspace root
+pub(crate) fn source_path(facade: &IndexFacade, file_path: &str) -> PathBuf {
+    resolve_path(facade.settings().workspace_root.as_deref(), file_path)
+}
+
+/// `file_path` resolved against `root` when relative
+fn resolve_path(root: Option<&Path>, file_path: &str) -> PathBuf {
+    let path = Path::new(file_path);
+    match root {
+        Some(root) if path.is_relative() => root.join(path),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Index id of a file given as indexed, absolute, or relative to the
+/// workspace root
+pub(crate) fn indexed_file_id(facade: &IndexFacade, path: &str) -> Option<FileId> {
+    if let Some(file_id) = facade.get_file_id_for_path(path) {
+        return Some(file_id);
+    }
+    let path = Path::new(path);
+    let relative = facade
+        .settings()
+        .workspace_root
+        .as_deref()
+        .and_then(|root| path.strip_prefix(root).ok())
+        .unwrap_or(path);
+    let normalized = relative
+        .to_string_lossy()
+        .trim_start_matches("./")
+        .replace('\\', "/");
+    facade.get_file_id_for_path(&normalized)
+}
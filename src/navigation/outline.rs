@@ -0,0 +1,170 @@
+//! File outline
+//!
+//! The symbols of one file nested by range containment (methods inside their
+//! class, fields inside their struct) and ordered by position. The shape is
+//! LSP `DocumentSymbol`, which editors render as outline views and
+//! breadcrumbs without re-nesting a flat list.
+
+use super::indexed_file_id;
+use super::lsp::{Locator, LspRange, selection_range, symbol_kind, symbol_kind_name};
+use crate::Symbol;
+use crate::indexing::facade::IndexFacade;
+use crate::types::PositionEncoding;
+use serde::Serialize;
+use std::fmt;
+
+/// A symbol of the outline with the symbols it contains
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutlineNode {
+    pub name: String,
+    /// LSP `SymbolKind` number
+    pub kind: u8,
+    /// Signature, shown next to the name by editors
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// Full extent of the symbol
+    pub range: LspRange,
+    /// The symbol's name within `range`
+    pub selection_range: LspRange,
+    pub symbol_id: u32,
+    pub children: Vec<OutlineNode>,
+}
+
+impl OutlineNode {
+    fn new(symbol: &Symbol, locator: &Locator) -> Self {
+        Self {
+            name: symbol.name.to_string(),
+            kind: symbol_kind(symbol.kind),
+            detail: symbol.signature.as_deref().map(str::to_string),
+            range: locator.range(&symbol.file_path, symbol.range),
+            selection_range: selection_range(symbol, locator),
+            symbol_id: symbol.id.value(),
+            children: Vec::new(),
+        }
+    }
+
+    pub fn kind_name(&self) -> &'static str {
+        symbol_kind_name(self.kind)
+    }
+
+    fn contains(&self, other: &OutlineNode) -> bool {
+        self.range.start <= other.range.start && other.range.end <= self.range.end
+    }
+
+    fn write_tree(&self, f: &mut fmt::Formatter<'_>, indent: usize) -> fmt::Result {
+        write!(
+            f,
+            "{:indent$}{} ({}) lines {}-{} [symbol_id:{}]",
+            "",
+            self.name,
+            self.kind_name(),
+            self.range.start.line + 1,
+            self.range.end.line + 1,
+            self.symbol_id
+        )?;
+        for child in &self.children {
+            writeln!(f)?;
+            child.write_tree(f, indent + 2)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for OutlineNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_tree(f, 0)
+    }
+}
+
+/// Outline of an indexed file, given as indexed or relative to the
+/// workspace root, with columns counted in `encoding`.
+///
+/// Returns `None` when the file is not in the index.
+pub fn file_outline(
+    facade: &IndexFacade,
+    path: &str,
+    encoding: PositionEncoding,
+) -> Option<Vec<OutlineNode>> {
+    let file_id = indexed_file_id(facade, path)?;
+    let locator = Locator::for_facade(facade, encoding);
+    Some(build_outline(facade.get_symbols_by_file(file_id), &locator))
+}
+
+/// Nest `symbols` by range containment, in position order. A symbol with
+/// the same range as an earlier one is nested inside it.
+pub fn build_outline(mut symbols: Vec<Symbol>, locator: &Locator) -> Vec<OutlineNode> {
+    // Outer symbols first where two start at the same position
+    symbols.sort_by(|a, b| {
+        (a.range.start_line, a.range.start_column)
+            .cmp(&(b.range.start_line, b.range.start_column))
+            .then(
+                (b.range.end_line, b.range.end_column).cmp(&(a.range.end_line, a.range.end_column)),
+            )
+    });
+
+    let mut roots = Vec::new();
+    // Open nodes, each contained in the one below it
+    let mut stack: Vec<OutlineNode> = Vec::new();
+    for symbol in &symbols {
+        let node = OutlineNode::new(symbol, locator);
+        while stack.last().is_some_and(|open| !open.contains(&node)) {
+            close(&mut stack, &mut roots);
+        }
+        stack.push(node);
+    }
+    while !stack.is_empty() {
+        close(&mut stack, &mut roots);
+    }
+    roots
+}
+
+/// Move the innermost open node into its parent, or into `roots`
+fn close(stack: &mut Vec<OutlineNode>, roots: &mut Vec<OutlineNode>) {
+    if let Some(node) = stack.pop() {
+        match stack.last_mut() {
+            Some(parent) => parent.children.push(node),
+            None => roots.push(node),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{FileId, Range};
+    use crate::{SymbolId, SymbolKind};
+
+    fn symbol(id: u32, name: &str, kind: SymbolKind, range: Range) -> Symbol {
+        Symbol::new(
+            SymbolId::new(id).unwrap(),
+            name,
+            kind,
+            FileId::new(1).unwrap(),
+            range,
+        )
+    }
+
+    #[test]
+    fn test_outline_nests_by_containment_in_position_order() {
+        // Stored out of order, as the index returns them
+        let symbols = vec![
+            symbol(3, "area", SymbolKind::Method, Range::new(6, 4, 8, 5)),
+            symbol(5, "main", SymbolKind::Function, Range::new(12, 0, 14, 1)),
+            symbol(2, "width", SymbolKind::Field, Range::new(2, 4, 2, 15)),
+            symbol(1, "Shape", SymbolKind::Class, Range::new(1, 0, 10, 1)),
+            symbol(4, "scale", SymbolKind::Variable, Range::new(7, 8, 7, 20)),
+        ];
+
+        let outline = build_outline(symbols, &Locator::new(None, PositionEncoding::Byte));
+        let names: Vec<&str> = outline.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names, ["Shape", "main"]);
+
+        let shape = &outline[0];
+        let members: Vec<&str> = shape.children.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(members, ["width", "area"]);
+        assert_eq!(shape.children[1].children[0].name, "scale");
+        assert_eq!(shape.kind, 5);
+        assert!(outline[1].children.is_empty());
+    }
+}
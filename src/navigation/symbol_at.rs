@@ -0,0 +1,249 @@
+//! Symbol at a position
+//!
+//! What sits under an editor's cursor: the innermost symbol whose range
+//! contains the position, and the symbol the word there refers to. The word
+//! is read from the source file and matched by name against the outgoing
+//! calls, type uses, and references of the enclosing symbols, innermost
+//! first, so it resolves to whatever the index resolved that site to rather
+//! than to any symbol of the same name.
+
+use super::indexed_file_id;
+use super::lsp::{HierarchyItem, Locator, LspRange, Position};
+use crate::Symbol;
+use crate::indexing::facade::IndexFacade;
+use crate::relationship::RelationKind;
+use crate::types::{PositionEncoding, byte_column};
+use serde::Serialize;
+use std::fmt;
+
+/// Relationship kinds whose targets can be under the cursor, with their labels
+const REFERENCE_KINDS: [(RelationKind, &str); 3] = [
+    (RelationKind::Calls, "calls"),
+    (RelationKind::Uses, "uses"),
+    (RelationKind::References, "references"),
+];
+
+/// The symbol enclosing a position and the reference at it
+#[derive(Debug, Clone, Serialize)]
+pub struct SymbolAt {
+    pub position: Position,
+    /// Innermost symbol containing the position
+    pub symbol: HierarchyItem,
+    /// Symbols around `symbol`, outermost first
+    pub containers: Vec<HierarchyItem>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reference: Option<ReferenceAt>,
+}
+
+/// An indexed symbol named by the word under the cursor
+#[derive(Debug, Clone, Serialize)]
+pub struct ReferenceAt {
+    pub name: String,
+    /// The relationship it was found through: "calls", "uses", "references"
+    pub relation: &'static str,
+    /// Span of the word
+    pub range: LspRange,
+    pub target: HierarchyItem,
+}
+
+impl fmt::Display for SymbolAt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}) {}:{} [symbol_id:{}]",
+            self.symbol.name,
+            self.symbol.kind_name(),
+            self.symbol.data.file_path,
+            self.symbol.range.start.line + 1,
+            self.symbol.data.symbol_id
+        )?;
+        if !self.containers.is_empty() {
+            let path: Vec<&str> = self.containers.iter().map(|c| c.name.as_str()).collect();
+            write!(f, "\n  in {}", path.join(" > "))?;
+        }
+        if let Some(reference) = &self.reference {
+            let target = &reference.target;
+            write!(
+                f,
+                "\n  {} {} ({}) {}:{} [symbol_id:{}]",
+                reference.relation,
+                target.name,
+                target.kind_name(),
+                target.data.file_path,
+                target.range.start.line + 1,
+                target.data.symbol_id
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Symbol at a zero-based `line` and `character` of an indexed file, given
+/// as indexed or relative to the workspace root. `character` and every
+/// returned range are counted in `encoding`.
+///
+/// Returns `None` when the file is not indexed or no symbol contains the
+/// position.
+pub fn symbol_at(
+    facade: &IndexFacade,
+    path: &str,
+    line: u32,
+    character: u32,
+    encoding: PositionEncoding,
+) -> Option<SymbolAt> {
+    let file_id = indexed_file_id(facade, path)?;
+    let symbols = facade.get_symbols_by_file(file_id);
+    let locator = Locator::for_facade(facade, encoding);
+    let source = symbols
+        .first()
+        .and_then(|symbol| locator.source(&symbol.file_path));
+    let line_text = source.as_deref().and_then(|source| source.line(line));
+    let byte = match line_text {
+        Some(text) => byte_column(text, character as usize, encoding) as u32,
+        None => character,
+    };
+    let mut enclosing = enclosing_symbols(&symbols, line, byte);
+    let symbol = enclosing.pop()?;
+
+    let reference = line_text.and_then(|text| {
+        let (start, end) = word_at(text, byte as usize)?;
+        let word = &text[start..end];
+        // The symbol's own name where it is declared
+        if symbol.name.as_ref() == word && symbol.range.start_line == line {
+            return None;
+        }
+        let (target, relation) = std::iter::once(symbol)
+            .chain(enclosing.iter().rev().copied())
+            .find_map(|context| reference_target(facade, context, word, line))?;
+        Some(ReferenceAt {
+            name: word.to_string(),
+            relation,
+            range: locator.span_in(text, line, start, end),
+            target: HierarchyItem::new(&target, &locator),
+        })
+    });
+
+    Some(SymbolAt {
+        position: Position { line, character },
+        symbol: HierarchyItem::new(symbol, &locator),
+        containers: enclosing
+            .into_iter()
+            .map(|s| HierarchyItem::new(s, &locator))
+            .collect(),
+        reference,
+    })
+}
+
+/// Target of an outgoing relationship of `context` named `word`, preferring
+/// sites recorded on `line`
+fn reference_target(
+    facade: &IndexFacade,
+    context: &Symbol,
+    word: &str,
+    line: u32,
+) -> Option<(Symbol, &'static str)> {
+    let mut fallback = None;
+    for (kind, relation) in REFERENCE_KINDS {
+        for (target, metadata) in facade.get_relationship_targets_with_metadata(context.id, kind) {
+            if target.name.as_ref() != word {
+                continue;
+            }
+            match metadata.and_then(|m| m.line) {
+                Some(site_line) if site_line == line => return Some((target, relation)),
+                _ => {
+                    fallback.get_or_insert((target, relation));
+                }
+            }
+        }
+    }
+    fallback
+}
+
+/// Symbols containing the position, outermost first. Of two with the same
+/// range, the one stored first counts as the outer.
+pub fn enclosing_symbols(symbols: &[Symbol], line: u32, character: u32) -> Vec<&Symbol> {
+    let column = u16::try_from(character).unwrap_or(u16::MAX);
+    let mut enclosing: Vec<&Symbol> = symbols
+        .iter()
+        .filter(|s| s.range.contains(line, column))
+        .collect();
+    enclosing.sort_by(|a, b| {
+        (a.range.start_line, a.range.start_column)
+            .cmp(&(b.range.start_line, b.range.start_column))
+            .then(
+                (b.range.end_line, b.range.end_column).cmp(&(a.range.end_line, a.range.end_column)),
+            )
+    });
+    enclosing
+}
+
+/// Byte span of the identifier at or just before `character` in `line`
+pub fn word_at(line: &str, character: usize) -> Option<(usize, usize)> {
+    let bytes = line.as_bytes();
+    let is_word = |b: u8| b.is_ascii_alphanumeric() || b == b'_' || b == b'$' || b >= 0x80;
+    let mut at = character.min(bytes.len());
+    if !bytes.get(at).copied().is_some_and(is_word) {
+        // Cursor right after the word, as editors place it
+        if at == 0 || !is_word(bytes[at - 1]) {
+            return None;
+        }
+        at -= 1;
+    }
+    let start = bytes[..at]
+        .iter()
+        .rposition(|&b| !is_word(b))
+        .map_or(0, |i| i + 1);
+    let end = bytes[at..]
+        .iter()
+        .position(|&b| !is_word(b))
+        .map_or(bytes.len(), |i| at + i);
+    // Not a number literal
+    if bytes[start].is_ascii_digit() || !line.is_char_boundary(start) || !line.is_char_boundary(end)
+    {
+        return None;
+    }
+    Some((start, end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{FileId, Range};
+    use crate::{SymbolId, SymbolKind};
+
+    fn symbol(id: u32, name: &str, kind: SymbolKind, range: Range) -> Symbol {
+        Symbol::new(
+            SymbolId::new(id).unwrap(),
+            name,
+            kind,
+            FileId::new(1).unwrap(),
+            range,
+        )
+    }
+
+    #[test]
+    fn test_innermost_symbol_and_word_at_position() {
+        let symbols = vec![
+            symbol(2, "area", SymbolKind::Method, Range::new(3, 4, 6, 5)),
+            symbol(1, "Shape", SymbolKind::Class, Range::new(1, 0, 8, 1)),
+            symbol(3, "main", SymbolKind::Function, Range::new(10, 0, 12, 1)),
+        ];
+
+        let names = |line, character| -> Vec<&str> {
+            enclosing_symbols(&symbols, line, character)
+                .iter()
+                .map(|s| s.name.as_ref())
+                .collect()
+        };
+        assert_eq!(names(4, 8), ["Shape", "area"]);
+        assert_eq!(names(2, 4), ["Shape"]);
+        assert!(names(9, 0).is_empty());
+
+        let line = "        let total = compute_area(width) + 2;";
+        assert_eq!(word_at(line, 22), Some((20, 32)));
+        // Just past the end of a word
+        assert_eq!(word_at(line, 32), Some((20, 32)));
+        assert_eq!(word_at(line, 3), None);
+        assert_eq!(word_at(line, 42), None);
+    }
+}
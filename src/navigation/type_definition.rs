@@ -0,0 +1,515 @@
+//! Go to type definition
+//!
+//! For a variable, field, constant, or parameter, finds the symbols that
+//! define its type; for a function or method, the types in its return type.
+//! Unlike go-to-definition, which lands on the declaration of `config`, this
+//! lands on `struct Config`.
+//!
+//! The type comes from the declaration's annotation (`x: Foo`, `Foo x`,
+//! `x Foo` depending on the language). Without one, the language parser's
+//! variable type inference (`let x = Foo::new()`) is used. Every named type
+//! in the annotation is resolved, outermost first, so `Option<Config>` yields
+//! both `Option` and `Config`. Types missing from the project are looked up
+//! in dependency indexes.
+
+use super::source_path;
+use crate::indexing::facade::IndexFacade;
+use crate::parsing::get_registry;
+use crate::{Symbol, SymbolKind};
+use serde::Serialize;
+use std::fmt;
+
+/// Kinds that can define a type
+const TYPE_KINDS: &[SymbolKind] = &[
+    SymbolKind::Struct,
+    SymbolKind::Class,
+    SymbolKind::Interface,
+    SymbolKind::TypeAlias,
+    SymbolKind::Enum,
+    SymbolKind::Trait,
+];
+
+/// Builtin types and type-position keywords that never resolve to a symbol
+const BUILTIN_TYPES: &str = "
+    i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize f32 f64 bool char str
+    mut dyn impl const Self self
+    int long short byte float double void unsigned signed struct var val auto final
+    error func map chan interface Int Long Boolean Unit
+    number string boolean any unknown never object null undefined readonly keyof typeof
+    None list dict set tuple bytes
+";
+
+fn is_builtin(name: &str) -> bool {
+    BUILTIN_TYPES
+        .split_whitespace()
+        .any(|builtin| builtin == name)
+}
+
+/// How a language writes a declaration's type
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum AnnotationStyle {
+    /// `name: Type` (Rust, TypeScript, Python, Kotlin, Swift, GDScript)
+    Suffix,
+    /// `Type name` (Java, C#, C, C++, PHP)
+    Prefix,
+    /// `name Type` (Go)
+    Trailing,
+}
+
+impl AnnotationStyle {
+    fn for_language(language: &str) -> Option<Self> {
+        match language {
+            "rust" | "typescript" | "python" | "kotlin" | "swift" | "gdscript" => {
+                Some(Self::Suffix)
+            }
+            "java" | "csharp" | "c" | "cpp" | "php" => Some(Self::Prefix),
+            "go" => Some(Self::Trailing),
+            _ => None,
+        }
+    }
+}
+
+/// The type of a symbol and the symbols defining it
+#[derive(Debug, Clone, Serialize)]
+pub struct TypeDefinition {
+    /// Symbol whose type was looked up
+    pub symbol: String,
+    /// Type as written (or inferred), e.g. `Option<Config>`
+    pub type_expr: String,
+    /// Definitions of the named types in `type_expr`, outermost first
+    pub targets: Vec<TypeTarget>,
+}
+
+/// A symbol defining one of the types in a [`TypeDefinition`]
+#[derive(Debug, Clone, Serialize)]
+pub struct TypeTarget {
+    /// Type name as it appears in the annotation
+    pub type_name: String,
+    pub symbol: Symbol,
+    /// Dependency index the symbol came from, when outside the project
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dependency: Option<String>,
+}
+
+impl fmt::Display for TypeTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} -> {:?} {} at {}:{}",
+            self.type_name,
+            self.symbol.kind,
+            self.symbol.name,
+            self.symbol.file_path,
+            self.symbol.range.start_line + 1
+        )?;
+        if let Some(dependency) = &self.dependency {
+            write!(f, " [{dependency}]")?;
+        }
+        Ok(())
+    }
+}
+
+/// Resolve the type of `symbol` to its defining symbols.
+///
+/// Returns `None` when no type can be determined, and a definition with no
+/// targets when the type is known but not defined in any index.
+pub fn find_type_definition(facade: &IndexFacade, symbol: &Symbol) -> Option<TypeDefinition> {
+    let type_expr = annotated_type(facade, symbol).or_else(|| inferred_type(facade, symbol))?;
+
+    let mut targets = Vec::new();
+    for type_name in type_names(&type_expr) {
+        targets.extend(resolve_type(facade, symbol, &type_name));
+    }
+
+    Some(TypeDefinition {
+        symbol: symbol.name.to_string(),
+        type_expr,
+        targets,
+    })
+}
+
+fn language(symbol: &Symbol) -> Option<&'static str> {
+    symbol.language_id.map(|id| id.as_str())
+}
+
+/// Declaration text of the symbol: its source lines, or the signature
+fn declaration(facade: &IndexFacade, symbol: &Symbol) -> Option<String> {
+    let source = std::fs::read_to_string(source_path(facade, &symbol.file_path)).ok();
+    if let Some(source) = source {
+        let start = symbol.range.start_line as usize;
+        let end = (symbol.range.end_line as usize).min(start + 3);
+        let lines: Vec<&str> = source
+            .lines()
+            .skip(start)
+            .take(end.saturating_sub(start) + 1)
+            .collect();
+        if !lines.is_empty() {
+            return Some(lines.join("\n"));
+        }
+    }
+    symbol.signature.as_deref().map(str::to_string)
+}
+
+fn annotated_type(facade: &IndexFacade, symbol: &Symbol) -> Option<String> {
+    let style = AnnotationStyle::for_language(language(symbol)?)?;
+    let text = declaration(facade, symbol)?;
+    match symbol.kind {
+        SymbolKind::Function | SymbolKind::Method => return_type(&text, style),
+        _ => declared_type(&text, symbol.as_name(), style),
+    }
+}
+
+/// Type from the parser's variable binding inference, for declarations
+/// without an annotation
+fn inferred_type(facade: &IndexFacade, symbol: &Symbol) -> Option<String> {
+    let language_id = symbol.language_id?;
+    let source = std::fs::read_to_string(source_path(facade, &symbol.file_path)).ok()?;
+    let mut parser = get_registry()
+        .lock()
+        .ok()?
+        .create_parser(language_id, facade.settings())
+        .ok()?;
+
+    let line = symbol.range.start_line;
+    if let Some(bindings) = parser.find_variable_types_with_substitution(&source) {
+        return bindings
+            .into_iter()
+            .find(|(name, _, range)| {
+                name == symbol.as_name() && range.start_line <= line && line <= range.end_line
+            })
+            .map(|(_, type_name, _)| type_name);
+    }
+    parser
+        .find_variable_types(&source)
+        .into_iter()
+        .find(|(name, _, range)| {
+            *name == symbol.as_name() && range.start_line <= line && line <= range.end_line
+        })
+        .map(|(_, type_name, _)| type_name.to_string())
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Byte range of `name` as a whole word in `text`
+fn find_word(text: &str, name: &str) -> Option<(usize, usize)> {
+    text.match_indices(name)
+        .map(|(i, _)| (i, i + name.len()))
+        .find(|&(start, end)| {
+            !text[..start].ends_with(is_ident_char) && !text[end..].starts_with(is_ident_char)
+        })
+}
+
+/// Read a type expression up to the first top-level terminator
+fn take_type(text: &str) -> Option<String> {
+    let mut depth = 0usize;
+    let mut end = text.len();
+    let mut prev = ' ';
+    for (i, c) in text.char_indices() {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            '{' if i == 0 || depth > 0 => depth += 1,
+            '>' if prev == '=' || prev == '-' => {}
+            '>' | ')' | ']' | '}' if depth > 0 => depth -= 1,
+            // `=>` in function types is not an initializer
+            '=' if depth == 0 && text[i + 1..].starts_with('>') => {}
+            '=' | ',' | ';' | ')' | '{' | '}' | '\n' if depth == 0 => {
+                end = i;
+                break;
+            }
+            _ => {}
+        }
+        prev = c;
+    }
+    let ty = text[..end].trim();
+    (!ty.is_empty()).then(|| ty.to_string())
+}
+
+/// Type annotation of `name` in a declaration
+fn declared_type(text: &str, name: &str, style: AnnotationStyle) -> Option<String> {
+    let (start, end) = find_word(text, name)?;
+    match style {
+        AnnotationStyle::Suffix => {
+            let rest = text[end..].trim_start().trim_start_matches(['?', '!']);
+            let rest = rest.strip_prefix(':').filter(|r| !r.starts_with(':'))?;
+            take_type(rest)
+        }
+        AnnotationStyle::Trailing => {
+            let rest = text[end..].trim_start();
+            if rest.starts_with([':', '=', ',', ')']) {
+                return None;
+            }
+            take_type(rest)
+        }
+        AnnotationStyle::Prefix => {
+            let before = text[..start].trim_end().trim_end_matches(['*', '&', '$']);
+            let before = before.trim_end();
+            let mut depth = 0usize;
+            let mut type_start = 0;
+            for (i, c) in before.char_indices().rev() {
+                match c {
+                    '>' | ']' | ')' => depth += 1,
+                    '<' | '[' | '(' => depth = depth.saturating_sub(1),
+                    c if depth == 0 && (c.is_whitespace() || c == ',' || c == '(') => {
+                        type_start = i + c.len_utf8();
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+            let ty = before[type_start..].trim_start_matches('?');
+            (!ty.is_empty() && !is_builtin(ty)).then(|| ty.to_string())
+        }
+    }
+}
+
+/// Byte index of the `)` matching the `(` at `open`
+fn closing_paren(text: &str, open: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, c) in text[open..].char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open + i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Return type of a function declaration
+fn return_type(text: &str, style: AnnotationStyle) -> Option<String> {
+    let mut open = text.find('(')?;
+    // Skip a Go method receiver: `func (s *Store) Get(...)`
+    if style == AnnotationStyle::Trailing && text[..open].trim_end().ends_with("func") {
+        let receiver_end = closing_paren(text, open)?;
+        open = receiver_end + text[receiver_end..].find('(')?;
+    }
+    let close = closing_paren(text, open)?;
+    let rest = text[close + 1..].trim_start();
+
+    match style {
+        AnnotationStyle::Suffix => {
+            let rest = rest.strip_prefix("->").or_else(|| rest.strip_prefix(':'))?;
+            take_type(rest).map(|ty| ty.trim_end_matches(':').trim().to_string())
+        }
+        AnnotationStyle::Trailing => take_type(rest),
+        AnnotationStyle::Prefix => {
+            let name_end = text[..open].trim_end();
+            let name_start = name_end
+                .rfind(|c: char| !is_ident_char(c))
+                .map_or(0, |i| i + 1);
+            declared_type(name_end, &name_end[name_start..], style)
+        }
+    }
+}
+
+/// Named types in a type expression, outermost first: `HashMap<String, Vec<Config>>`
+/// gives `HashMap`, `String`, `Vec`, `Config`
+fn type_names(type_expr: &str) -> Vec<String> {
+    fn flush(current: &mut String, names: &mut Vec<String>) {
+        let name = current.trim_matches(['.', ':']).to_string();
+        current.clear();
+        let base = name.rsplit(['.', ':']).next().unwrap_or(&name);
+        let is_type = base.starts_with(|c: char| c.is_alphabetic() || c == '_');
+        if is_type && !is_builtin(base) && !names.contains(&name) {
+            names.push(name);
+        }
+    }
+
+    let mut names: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut chars = type_expr.chars().peekable();
+    while let Some(c) = chars.next() {
+        if is_ident_char(c) || (c == '.' && !current.is_empty()) {
+            current.push(c);
+        } else if c == ':' && chars.peek() == Some(&':') {
+            chars.next();
+            current.push_str("::");
+        } else if c == ':' {
+            // Parameter or property name in a function or object type
+            current.clear();
+        } else if c == '\'' {
+            // A quoted forward reference ('Node') names a type, a lifetime ('a) does not
+            flush(&mut current, &mut names);
+            while let Some(&next) = chars.peek() {
+                if !is_ident_char(next) && next != '.' {
+                    break;
+                }
+                current.push(next);
+                chars.next();
+            }
+            if chars.peek() == Some(&'\'') {
+                chars.next();
+                flush(&mut current, &mut names);
+            } else {
+                current.clear();
+            }
+        } else if !current.is_empty() {
+            flush(&mut current, &mut names);
+        }
+    }
+    if !current.is_empty() {
+        flush(&mut current, &mut names);
+    }
+    names
+}
+
+/// Symbols defining `type_name`, closest to `origin` first
+fn resolve_type(facade: &IndexFacade, origin: &Symbol, type_name: &str) -> Vec<TypeTarget> {
+    let base = type_name.rsplit(['.', ':']).next().unwrap_or(type_name);
+    let mut candidates: Vec<Symbol> = facade
+        .find_symbols_by_name(base, language(origin))
+        .into_iter()
+        .filter(|symbol| TYPE_KINDS.contains(&symbol.kind))
+        .collect();
+
+    if candidates.is_empty() {
+        return facade
+            .find_external_symbols(type_name, language(origin))
+            .into_iter()
+            .filter(|external| TYPE_KINDS.contains(&external.symbol.kind))
+            .map(|external| TypeTarget {
+                type_name: type_name.to_string(),
+                symbol: external.symbol,
+                dependency: Some(external.dependency),
+            })
+            .collect();
+    }
+
+    let qualifier = type_name
+        .rsplit_once("::")
+        .or_else(|| type_name.rsplit_once('.'))
+        .map(|(qualifier, _)| qualifier);
+    candidates.sort_by_key(|symbol| {
+        let same_file = symbol.file_id == origin.file_id;
+        let qualified = qualifier.is_some_and(|q| {
+            symbol
+                .module_path
+                .as_deref()
+                .is_some_and(|module| module.ends_with(q))
+        });
+        let same_module = symbol.module_path.is_some() && symbol.module_path == origin.module_path;
+        (!same_file, !qualified, !same_module)
+    });
+    candidates
+        .into_iter()
+        .map(|symbol| TypeTarget {
+            type_name: type_name.to_string(),
+            symbol,
+            dependency: None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use AnnotationStyle::*;
+
+    #[test]
+    fn test_declared_type() {
+        let cases = [
+            (
+                "let config: Config = load();",
+                "config",
+                Suffix,
+                Some("Config"),
+            ),
+            (
+                "let cache: HashMap<String, Vec<u8>> = HashMap::new();",
+                "cache",
+                Suffix,
+                Some("HashMap<String, Vec<u8>>"),
+            ),
+            (
+                "pub settings: Arc<Settings>,",
+                "settings",
+                Suffix,
+                Some("Arc<Settings>"),
+            ),
+            ("user?: User | null;", "user", Suffix, Some("User | null")),
+            (
+                "handler: (e: Event) => void = noop",
+                "handler",
+                Suffix,
+                Some("(e: Event) => void"),
+            ),
+            (
+                "self.client: HttpClient = client",
+                "client",
+                Suffix,
+                Some("HttpClient"),
+            ),
+            ("let count = 0;", "count", Suffix, None),
+            (
+                "private final Map<String, User> users = new HashMap<>();",
+                "users",
+                Prefix,
+                Some("Map<String, User>"),
+            ),
+            ("const Node *head = NULL;", "head", Prefix, Some("Node")),
+            ("var service = new Service();", "service", Prefix, None),
+            ("private ?Logger $logger;", "logger", Prefix, Some("Logger")),
+            (
+                "var server *http.Server",
+                "server",
+                Trailing,
+                Some("*http.Server"),
+            ),
+            ("store Store", "store", Trailing, Some("Store")),
+            ("conn := dial()", "conn", Trailing, None),
+        ];
+        for (text, name, style, expected) in cases {
+            assert_eq!(
+                declared_type(text, name, style).as_deref(),
+                expected,
+                "{text}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_return_type_and_type_names() {
+        assert_eq!(
+            return_type(
+                "pub fn load(path: &Path) -> Result<Config, Error> {",
+                Suffix
+            )
+            .as_deref(),
+            Some("Result<Config, Error>")
+        );
+        assert_eq!(
+            return_type("def fetch(self, url: str) -> Response:", Suffix).as_deref(),
+            Some("Response")
+        );
+        assert_eq!(
+            return_type("function find(id: string): Promise<User> {", Suffix).as_deref(),
+            Some("Promise<User>")
+        );
+        assert_eq!(
+            return_type("public List<Order> orders(int limit) {", Prefix).as_deref(),
+            Some("List<Order>")
+        );
+        assert_eq!(
+            return_type("func (s *Store) Get(id string) (*Item, error) {", Trailing).as_deref(),
+            Some("(*Item, error)")
+        );
+
+        assert_eq!(
+            type_names("HashMap<String, Vec<crate::config::Config>>"),
+            ["HashMap", "String", "Vec", "crate::config::Config"]
+        );
+        assert_eq!(type_names("&'a mut [u8]"), Vec::<String>::new());
+        assert_eq!(type_names("*http.Server"), ["http.Server"]);
+        assert_eq!(type_names("User | null"), ["User"]);
+        assert_eq!(type_names("Optional['Node']"), ["Optional", "Node"]);
+        assert_eq!(type_names("(*Item, error)"), ["Item"]);
+        assert_eq!(type_names("(e: Event) => void"), ["Event"]);
+    }
+}
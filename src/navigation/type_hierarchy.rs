@@ -0,0 +1,346 @@
+//! Type hierarchy
+//!
+//! Supertypes and subtypes of a class, interface, or trait, from the
+//! `Extends` and `Implements` relationships in the index. Parsers do not
+//! agree on which of the two a base class becomes (Python stores base classes
+//! as `Implements`, C++ uses both), so each edge is labelled by the
+//! subtype's [`LanguageBehavior::supertype_relation`], which can also exclude
+//! it. Both directions are expanded `depth` levels, like LSP
+//! `typeHierarchy/supertypes` and `typeHierarchy/subtypes` applied
+//! repeatedly.
+//!
+//! [`LanguageBehavior::supertype_relation`]: crate::parsing::LanguageBehavior::supertype_relation
+
+use super::lsp::{HierarchyItem, Locator};
+use crate::indexing::facade::IndexFacade;
+use crate::parsing::{LanguageBehavior, LanguageId, get_registry};
+use crate::relationship::RelationKind;
+use crate::types::PositionEncoding;
+use crate::{Symbol, SymbolId};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
+
+/// LSP `TypeHierarchyItem`
+pub type TypeHierarchyItem = HierarchyItem;
+
+/// Upper bound on nodes per direction
+const MAX_NODES: usize = 500;
+
+/// A type with its supertypes and subtypes
+#[derive(Debug, Clone, Serialize)]
+pub struct TypeHierarchy {
+    pub item: TypeHierarchyItem,
+    pub supertypes: Vec<TypeHierarchyNode>,
+    pub subtypes: Vec<TypeHierarchyNode>,
+}
+
+/// A supertype or subtype, expanded further in the same direction
+#[derive(Debug, Clone, Serialize)]
+pub struct TypeHierarchyNode {
+    pub item: TypeHierarchyItem,
+    /// How the subtype of the edge relates to the supertype in its
+    /// language: "extends", "implements", "inherits"
+    pub relation: &'static str,
+    pub children: Vec<TypeHierarchyNode>,
+    /// Already on the path from the root; children are not repeated
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub recursive: bool,
+}
+
+impl TypeHierarchy {
+    pub fn is_empty(&self) -> bool {
+        self.supertypes.is_empty() && self.subtypes.is_empty()
+    }
+}
+
+impl fmt::Display for TypeHierarchy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}) {}:{} [symbol_id:{}]",
+            self.item.name,
+            self.item.kind_name(),
+            self.item.data.file_path,
+            self.item.range.start.line + 1,
+            self.item.data.symbol_id
+        )?;
+        if !self.supertypes.is_empty() {
+            write!(f, "\n  Supertypes:")?;
+            for node in &self.supertypes {
+                node.write_tree(f, 4)?;
+            }
+        }
+        if !self.subtypes.is_empty() {
+            write!(f, "\n  Subtypes:")?;
+            for node in &self.subtypes {
+                node.write_tree(f, 4)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl TypeHierarchyNode {
+    fn write_tree(&self, f: &mut fmt::Formatter<'_>, indent: usize) -> fmt::Result {
+        write!(
+            f,
+            "\n{:indent$}{} ({}, {}) {}:{} [symbol_id:{}]",
+            "",
+            self.item.name,
+            self.item.kind_name(),
+            self.relation,
+            self.item.data.file_path,
+            self.item.range.start.line + 1,
+            self.item.data.symbol_id
+        )?;
+        if self.recursive {
+            write!(f, " (recursive)")?;
+        }
+        for child in &self.children {
+            child.write_tree(f, indent + 2)?;
+        }
+        Ok(())
+    }
+}
+
+/// Which way an edge is followed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeDirection {
+    Supertypes,
+    Subtypes,
+}
+
+/// Type hierarchy of `symbol_id`, `depth` levels in each direction (at
+/// least one), with columns counted in `encoding`.
+///
+/// Returns `None` when the symbol is not in the index.
+pub fn type_hierarchy(
+    facade: &IndexFacade,
+    symbol_id: SymbolId,
+    depth: u32,
+    encoding: PositionEncoding,
+) -> Option<TypeHierarchy> {
+    let root = facade.get_symbol(symbol_id)?;
+    let edges = |id, direction| match direction {
+        TypeDirection::Supertypes => {
+            let mut edges: Vec<_> = facade
+                .get_extends(id)
+                .into_iter()
+                .map(|s| (s, RelationKind::Extends))
+                .collect();
+            edges.extend(
+                facade
+                    .get_implemented_traits(id)
+                    .into_iter()
+                    .map(|s| (s, RelationKind::Implements)),
+            );
+            edges
+        }
+        TypeDirection::Subtypes => {
+            let mut edges: Vec<_> = facade
+                .get_extended_by(id)
+                .into_iter()
+                .map(|s| (s, RelationKind::Extends))
+                .collect();
+            edges.extend(
+                facade
+                    .get_implementations(id)
+                    .into_iter()
+                    .map(|s| (s, RelationKind::Implements)),
+            );
+            edges
+        }
+    };
+
+    let mut behaviors: HashMap<LanguageId, Option<Box<dyn LanguageBehavior>>> = HashMap::new();
+    let relation = |subtype: &Symbol, kind| {
+        let behavior = subtype.language_id.and_then(|language| {
+            behaviors
+                .entry(language)
+                .or_insert_with(|| {
+                    let registry = get_registry().lock().ok()?;
+                    Some(registry.get(language)?.create_behavior())
+                })
+                .as_deref()
+        });
+        match behavior {
+            Some(behavior) => behavior.supertype_relation(kind),
+            None => default_relation(kind),
+        }
+    };
+
+    let locator = Locator::for_facade(facade, encoding);
+    Some(build_type_hierarchy(
+        &root, depth, edges, relation, &locator,
+    ))
+}
+
+fn default_relation(kind: RelationKind) -> Option<&'static str> {
+    match kind {
+        RelationKind::Extends => Some("extends"),
+        RelationKind::Implements => Some("implements"),
+        _ => None,
+    }
+}
+
+/// [`type_hierarchy`] over custom lookups: `edges` lists the neighbours of a
+/// type in a direction with the stored relationship kind, and `relation`
+/// labels an edge given its subtype
+pub fn build_type_hierarchy(
+    root: &Symbol,
+    depth: u32,
+    edges: impl Fn(SymbolId, TypeDirection) -> Vec<(Symbol, RelationKind)>,
+    mut relation: impl FnMut(&Symbol, RelationKind) -> Option<&'static str>,
+    locator: &Locator,
+) -> TypeHierarchy {
+    let depth = depth.max(1);
+    let mut expand = |direction| {
+        let mut builder = Builder {
+            direction,
+            edges: &edges,
+            relation: &mut relation,
+            locator,
+            path: vec![root.id],
+            budget: MAX_NODES,
+        };
+        builder.children(root, depth)
+    };
+    let supertypes = expand(TypeDirection::Supertypes);
+    let subtypes = expand(TypeDirection::Subtypes);
+
+    TypeHierarchy {
+        item: HierarchyItem::new(root, locator),
+        supertypes,
+        subtypes,
+    }
+}
+
+struct Builder<'a, E, R> {
+    direction: TypeDirection,
+    edges: &'a E,
+    relation: &'a mut R,
+    locator: &'a Locator,
+    /// Symbols from the root to the node being expanded
+    path: Vec<SymbolId>,
+    /// Nodes that may still be added
+    budget: usize,
+}
+
+impl<E, R> Builder<'_, E, R>
+where
+    E: Fn(SymbolId, TypeDirection) -> Vec<(Symbol, RelationKind)>,
+    R: FnMut(&Symbol, RelationKind) -> Option<&'static str>,
+{
+    fn children(&mut self, symbol: &Symbol, depth: u32) -> Vec<TypeHierarchyNode> {
+        let mut nodes: Vec<TypeHierarchyNode> = Vec::new();
+        for (target, kind) in (self.edges)(symbol.id, self.direction) {
+            if self.budget == 0 {
+                break;
+            }
+            // Duplicate edges of both kinds collapse into one node
+            if nodes
+                .iter()
+                .any(|n| n.item.data.symbol_id == target.id.value())
+            {
+                continue;
+            }
+            let subtype = match self.direction {
+                TypeDirection::Supertypes => symbol,
+                TypeDirection::Subtypes => &target,
+            };
+            let Some(relation) = (self.relation)(subtype, kind) else {
+                continue;
+            };
+            self.budget -= 1;
+
+            let recursive = self.path.contains(&target.id);
+            let children = if recursive || depth <= 1 {
+                Vec::new()
+            } else {
+                self.path.push(target.id);
+                let children = self.children(&target, depth - 1);
+                self.path.pop();
+                children
+            };
+            nodes.push(TypeHierarchyNode {
+                item: HierarchyItem::new(&target, self.locator),
+                relation,
+                children,
+                recursive,
+            });
+        }
+        nodes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SymbolKind;
+    use crate::types::{FileId, Range};
+
+    fn locator() -> Locator {
+        Locator::new(None, PositionEncoding::Byte)
+    }
+
+    fn class(id: u32, name: &str) -> Symbol {
+        Symbol::new(
+            SymbolId::new(id).unwrap(),
+            name,
+            SymbolKind::Class,
+            FileId::new(1).unwrap(),
+            Range::new(id * 10, 0, id * 10 + 5, 1),
+        )
+        .with_file_path("models.py")
+    }
+
+    #[test]
+    fn test_type_hierarchy_both_directions() {
+        let base = class(1, "Base");
+        let animal = class(2, "Animal");
+        let dog = class(3, "Dog");
+        let puppy = class(4, "Puppy");
+
+        // Puppy -> Dog -> Animal -> Base, stored as Implements like Python
+        let parents = [(4, 3), (3, 2), (2, 1)];
+        let symbols = [base, animal, dog.clone(), puppy];
+        let find = |id: u32| symbols.iter().find(|s| s.id.value() == id).unwrap().clone();
+        let edges = |id: SymbolId, direction| -> Vec<(Symbol, RelationKind)> {
+            parents
+                .iter()
+                .filter_map(|&(child, parent)| match direction {
+                    TypeDirection::Supertypes if child == id.value() => Some(find(parent)),
+                    TypeDirection::Subtypes if parent == id.value() => Some(find(child)),
+                    _ => None,
+                })
+                .map(|s| (s, RelationKind::Implements))
+                .collect()
+        };
+        let relation = |_: &Symbol, _| Some("inherits");
+
+        let hierarchy = build_type_hierarchy(&dog, 5, edges, relation, &locator());
+        assert_eq!(hierarchy.supertypes.len(), 1);
+        assert_eq!(hierarchy.supertypes[0].item.name, "Animal");
+        assert_eq!(hierarchy.supertypes[0].relation, "inherits");
+        assert_eq!(hierarchy.supertypes[0].children[0].item.name, "Base");
+        assert_eq!(hierarchy.subtypes[0].item.name, "Puppy");
+
+        let shallow = build_type_hierarchy(&dog, 1, edges, relation, &locator());
+        assert!(shallow.supertypes[0].children.is_empty());
+    }
+
+    #[test]
+    fn test_behavior_can_exclude_edges() {
+        let a = class(1, "A");
+        let b = class(2, "B");
+        let edges = |id: SymbolId, direction| match direction {
+            TypeDirection::Supertypes if id.value() == 1 => {
+                vec![(b.clone(), RelationKind::Extends)]
+            }
+            _ => Vec::new(),
+        };
+        let hierarchy = build_type_hierarchy(&a, 1, edges, |_, _| None, &locator());
+        assert!(hierarchy.is_empty());
+    }
+}
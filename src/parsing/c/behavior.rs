@@ -4,6 +4,7 @@ use super::resolution::CResolutionContext;
 use crate::FileId;
 use crate::Visibility;
 use crate::parsing::behavior_state::{BehaviorState, StatefulBehavior};
+use crate::parsing::references::ReferenceNodeKinds;
 use crate::parsing::{LanguageBehavior, ResolutionScope};
 use std::path::PathBuf;
 use tree_sitter::Language;
@@ -37,6 +38,22 @@ impl Default for CBehavior {
     }
 }
 
+/// Reference pass node kinds. Declarators bind the declared name; the
+/// initializer of an `init_declarator` is read as references.
+static REFERENCE_NODES: ReferenceNodeKinds = ReferenceNodeKinds {
+    identifiers: &["identifier"],
+    bindings: &[
+        ("parameter_declaration", Some("declarator")),
+        ("init_declarator", Some("declarator")),
+        ("declaration", Some("declarator")),
+    ],
+    members: &[],
+    paths: &[],
+    fields: &[("field_expression", "argument", "field")],
+    containers: &[],
+    this: &[],
+};
+
 impl LanguageBehavior for CBehavior {
     fn language_id(&self) -> crate::parsing::registry::LanguageId {
         crate::parsing::registry::LanguageId::new("c")
@@ -68,6 +85,14 @@ impl LanguageBehavior for CBehavior {
         self.language.clone()
     }
 
+    fn reference_node_kinds(&self) -> Option<&'static ReferenceNodeKinds> {
+        Some(&REFERENCE_NODES)
+    }
+
+    fn call_node_kinds(&self) -> &'static [(&'static str, &'static str)] {
+        &[("call_expression", "function")]
+    }
+
     fn format_path_as_module(&self, components: &[&str]) -> Option<String> {
         if components.is_empty() {
             Some("root".to_string())
@@ -151,7 +176,7 @@ impl LanguageBehavior for CBehavior {
         match symbol.visibility {
             Visibility::Public => true,
             Visibility::Crate => true,
-            Visibility::Module => false,
+            Visibility::Module | Visibility::Protected | Visibility::Package => false,
             Visibility::Private => false,
         }
     }
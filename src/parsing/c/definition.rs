@@ -32,7 +32,7 @@ impl LanguageDefinition for CLanguage {
     }
 
     fn create_parser(&self, _settings: &Settings) -> IndexResult<Box<dyn LanguageParser>> {
-        let parser = CParser::new().map_err(crate::IndexError::General)?;
+        let parser = CParser::new().map_err(|e| crate::IndexError::parser_init("c", e))?;
         Ok(Box::new(parser))
     }
 
@@ -68,6 +68,7 @@ impl CParser {
                     file_id,
                     is_glob: false,
                     is_type_only: false,
+                    range: Some(Import::range_of(&node)),
                 });
             }
         }
@@ -4,7 +4,7 @@
 //! as Rust and TypeScript implementations.
 
 use crate::parsing::resolution::ImportBinding;
-use crate::parsing::{InheritanceResolver, ResolutionScope, ScopeLevel, ScopeType};
+use crate::parsing::{NameKeyedInheritanceResolver, ResolutionScope, ScopeLevel, ScopeType};
 use crate::{FileId, SymbolId};
 use std::collections::HashMap;
 
@@ -192,7 +192,7 @@ impl ResolutionScope for CResolutionContext {
     }
 }
 
-/// Implementation of InheritanceResolver for C
+/// Implementation of NameKeyedInheritanceResolver for C
 ///
 /// C doesn't have traditional inheritance, but it does have:
 /// - Struct composition (embedding one struct in another)
@@ -247,7 +247,7 @@ impl Default for CInheritanceResolver {
     }
 }
 
-impl InheritanceResolver for CInheritanceResolver {
+impl NameKeyedInheritanceResolver for CInheritanceResolver {
     fn add_inheritance(&mut self, child: String, parent: String, kind: &str) {
         // In C, "inheritance" is really composition or typedef relationships
         match kind {
@@ -119,6 +119,8 @@ impl ParserContext {
                         hoisted: *hoisting,
                         parent_name,
                         parent_kind,
+                        depth: self.scope_depth(),
+                        ancestors: self.ancestor_kinds(),
                     };
                 }
                 ScopeType::Block => {
@@ -137,6 +139,8 @@ impl ParserContext {
                         hoisted: false,
                         parent_name,
                         parent_kind,
+                        depth: self.scope_depth(),
+                        ancestors: self.ancestor_kinds(),
                     };
                 }
                 ScopeType::Class => {
@@ -161,6 +165,33 @@ impl ParserContext {
         ScopeContext::Module
     }
 
+    /// Number of scopes entered below module level, blocks included
+    pub fn scope_depth(&self) -> u16 {
+        self.scope_stack
+            .iter()
+            .filter(|s| !matches!(s, ScopeType::Module | ScopeType::Global))
+            .count() as u16
+    }
+
+    /// Kinds of the enclosing named scopes, innermost first
+    ///
+    /// Blocks carry no kind of their own and are skipped; packages and
+    /// namespaces are reported as modules.
+    pub fn ancestor_kinds(&self) -> Vec<SymbolKind> {
+        self.scope_stack
+            .iter()
+            .rev()
+            .filter_map(|scope| match scope {
+                ScopeType::Function { .. } => Some(SymbolKind::Function),
+                ScopeType::Class => Some(SymbolKind::Class),
+                ScopeType::Module | ScopeType::Package | ScopeType::Namespace => {
+                    Some(SymbolKind::Module)
+                }
+                ScopeType::Block | ScopeType::Global => None,
+            })
+            .collect()
+    }
+
     /// Check if currently inside a class
     pub fn is_in_class(&self) -> bool {
         self.scope_stack
@@ -256,6 +287,8 @@ mod tests {
                 hoisted: false,
                 parent_name: Some("my_func".to_string().into()),
                 parent_kind: Some(SymbolKind::Function),
+                depth: 1,
+                ancestors: vec![SymbolKind::Function, SymbolKind::Module],
             }
         );
         assert!(ctx.is_in_function());
@@ -285,6 +318,8 @@ mod tests {
                 hoisted: false,
                 parent_name: None,
                 parent_kind: None,
+                depth: 2,
+                ancestors: vec![SymbolKind::Function, SymbolKind::Class, SymbolKind::Module],
             }
         );
         assert!(ctx.is_in_class());
@@ -313,7 +348,37 @@ mod tests {
                 hoisted: true,
                 parent_name: None,
                 parent_kind: None,
+                depth: 1,
+                ancestors: vec![SymbolKind::Function, SymbolKind::Module],
             }
         );
     }
+
+    #[test]
+    fn test_block_scopes_count_towards_depth() {
+        let mut ctx = ParserContext::new();
+        ctx.enter_scope(ScopeType::Class);
+        ctx.enter_scope(ScopeType::function());
+        ctx.set_current_function(Some("run".to_string()));
+        ctx.enter_scope(ScopeType::Block);
+        ctx.enter_scope(ScopeType::Block);
+
+        // Same-named locals in sibling and nested blocks differ by depth,
+        // while the kind chain only names the function, class and module
+        match ctx.current_scope_context() {
+            ScopeContext::Local {
+                depth, ancestors, ..
+            } => {
+                assert_eq!(depth, 4);
+                assert_eq!(
+                    ancestors,
+                    vec![SymbolKind::Function, SymbolKind::Class, SymbolKind::Module]
+                );
+            }
+            other => panic!("expected a local scope, got {other:?}"),
+        }
+
+        ctx.exit_scope();
+        assert_eq!(ctx.scope_depth(), 3);
+    }
 }
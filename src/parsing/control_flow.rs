@@ -0,0 +1,132 @@
+//! Conditional regions of function bodies
+//!
+//! A lightweight stand-in for an intra-procedural control-flow graph. It
+//! does not build blocks and edges; it only marks the code that may not run
+//! every time its function does: the arms of an `if` or `match`, loop
+//! bodies, the right operand of `&&` and `||`, catch handlers and closures.
+//! The condition or scrutinee deciding a branch always runs, so it is left
+//! out. The parse stage tags calls inside these regions `conditional:true`,
+//! which lets impact analysis tell calls a function always makes from those
+//! it only might.
+//!
+//! Early exits are not followed: code after a `return`, `?` or `throw`
+//! inside a branch still counts as unconditional.
+
+use crate::parsing::parser::check_recursion_depth;
+use crate::types::Range;
+use tree_sitter::{Node, Tree};
+
+/// Node kinds driving the conditional-region pass for one language
+#[derive(Debug, Clone, Copy)]
+pub struct BranchNodeKinds {
+    /// Branching nodes, with the fields that may not run: `if` with its
+    /// consequence and alternative, a loop with its body. An empty list
+    /// makes the whole node conditional, as for a closure or case arm.
+    pub branches: &'static [(&'static str, &'static [&'static str])],
+    /// Short-circuiting operators; the `right` operand of a node whose
+    /// `operator` field is one of them may not run
+    pub short_circuit: &'static [&'static str],
+}
+
+/// Ranges in `tree` that may not run whenever their enclosing function
+/// does, in document order. Regions inside other regions are listed too.
+pub fn conditional_ranges(tree: &Tree, kinds: &BranchNodeKinds) -> Vec<Range> {
+    let mut ranges = Vec::new();
+    visit(tree.root_node(), kinds, &mut ranges, 0);
+    ranges.sort_by_key(|range| (range.start_line, range.start_column));
+    ranges
+}
+
+/// Whether `inner` lies within `outer`
+pub fn range_within(inner: &Range, outer: &Range) -> bool {
+    outer.contains(inner.start_line, inner.start_column)
+        && outer.contains(inner.end_line, inner.end_column)
+}
+
+fn visit(node: Node, kinds: &BranchNodeKinds, ranges: &mut Vec<Range>, depth: usize) {
+    if !check_recursion_depth(depth, node) {
+        return;
+    }
+
+    if let Some((_, fields)) = kinds.branches.iter().find(|(kind, _)| *kind == node.kind()) {
+        if fields.is_empty() {
+            ranges.push(node_range(node));
+        } else {
+            for (i, child) in node.children(&mut node.walk()).enumerate() {
+                if node
+                    .field_name_for_child(i as u32)
+                    .is_some_and(|field| fields.contains(&field))
+                {
+                    ranges.push(node_range(child));
+                }
+            }
+        }
+    }
+
+    let short_circuits = node
+        .child_by_field_name("operator")
+        .is_some_and(|operator| kinds.short_circuit.contains(&operator.kind()));
+    if let (true, Some(right)) = (short_circuits, node.child_by_field_name("right")) {
+        ranges.push(node_range(right));
+    }
+
+    for child in node.children(&mut node.walk()) {
+        visit(child, kinds, ranges, depth + 1);
+    }
+}
+
+fn node_range(node: Node) -> Range {
+    Range::new(
+        node.start_position().row as u32,
+        node.start_position().column as u16,
+        node.end_position().row as u32,
+        node.end_position().column as u16,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{LineIndex, PositionEncoding};
+
+    const RUST: BranchNodeKinds = BranchNodeKinds {
+        branches: &[
+            ("if_expression", &["consequence", "alternative"]),
+            ("match_arm", &[]),
+            ("closure_expression", &[]),
+        ],
+        short_circuit: &["&&", "||"],
+    };
+
+    fn regions(code: &str) -> Vec<String> {
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&tree_sitter_rust::LANGUAGE.into())
+            .unwrap();
+        let tree = parser.parse(code, None).unwrap();
+        let lines = LineIndex::new(code);
+        let offset = |line, column| {
+            lines
+                .offset(code, line, u32::from(column), PositionEncoding::Byte)
+                .unwrap()
+        };
+        conditional_ranges(&tree, &RUST)
+            .into_iter()
+            .map(|range| {
+                let start = offset(range.start_line, range.start_column);
+                let end = offset(range.end_line, range.end_column);
+                code[start..end].to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_branches_but_not_conditions_are_conditional() {
+        let code = "fn run() {\n    if ready() && confirm() { launch(); } else { wait(); }\n    always();\n}\n";
+
+        assert_eq!(
+            regions(code),
+            vec!["confirm()", "{ launch(); }", "else { wait(); }"]
+        );
+    }
+}
@@ -4,6 +4,8 @@ use super::resolution::CppResolutionContext;
 use crate::FileId;
 use crate::Visibility;
 use crate::parsing::behavior_state::{BehaviorState, StatefulBehavior};
+use crate::parsing::references::ReferenceNodeKinds;
+use crate::parsing::signature::{self, SignatureStyle};
 use crate::parsing::{LanguageBehavior, ResolutionScope};
 use std::path::PathBuf;
 use tree_sitter::Language;
@@ -37,6 +39,24 @@ impl Default for CppBehavior {
     }
 }
 
+/// Reference pass node kinds. As for C, plus range-for and defaulted
+/// parameters.
+static REFERENCE_NODES: ReferenceNodeKinds = ReferenceNodeKinds {
+    identifiers: &["identifier"],
+    bindings: &[
+        ("parameter_declaration", Some("declarator")),
+        ("optional_parameter_declaration", Some("declarator")),
+        ("init_declarator", Some("declarator")),
+        ("declaration", Some("declarator")),
+        ("for_range_loop", Some("declarator")),
+    ],
+    members: &[],
+    paths: &[],
+    fields: &[("field_expression", "argument", "field")],
+    containers: &[("class_specifier", "name"), ("struct_specifier", "name")],
+    this: &["this"],
+};
+
 impl LanguageBehavior for CppBehavior {
     fn language_id(&self) -> crate::parsing::registry::LanguageId {
         crate::parsing::registry::LanguageId::new("cpp")
@@ -51,7 +71,7 @@ impl LanguageBehavior for CppBehavior {
         if signature.contains("public:") {
             Visibility::Public
         } else if signature.contains("protected:") {
-            Visibility::Module
+            Visibility::Protected
         } else if signature.contains("private:") {
             Visibility::Private
         } else {
@@ -64,6 +84,12 @@ impl LanguageBehavior for CppBehavior {
         "::"
     }
 
+    // Standard attributes are double-bracketed: `[[nodiscard]]`
+    fn normalize_signature(&self, signature: &str, style: SignatureStyle) -> String {
+        let declaration = signature::strip_attributes(signature, &["[["]);
+        signature::finish(signature::strip_body(declaration), style)
+    }
+
     fn supports_traits(&self) -> bool {
         true
     }
@@ -76,6 +102,10 @@ impl LanguageBehavior for CppBehavior {
         self.language.clone()
     }
 
+    fn reference_node_kinds(&self) -> Option<&'static ReferenceNodeKinds> {
+        Some(&REFERENCE_NODES)
+    }
+
     fn format_path_as_module(&self, components: &[&str]) -> Option<String> {
         if components.is_empty() {
             Some("root".to_string())
@@ -130,6 +160,15 @@ impl LanguageBehavior for CppBehavior {
         "inherits"
     }
 
+    fn supertype_relation(&self, kind: crate::relationship::RelationKind) -> Option<&'static str> {
+        use crate::relationship::RelationKind;
+        // Base classes may be stored as either kind
+        match kind {
+            RelationKind::Extends | RelationKind::Implements => Some("inherits"),
+            _ => None,
+        }
+    }
+
     fn map_relationship(&self, language_specific: &str) -> crate::relationship::RelationKind {
         use crate::relationship::RelationKind;
         match language_specific {
@@ -164,7 +203,7 @@ impl LanguageBehavior for CppBehavior {
         match symbol.visibility {
             Visibility::Public => true,
             Visibility::Crate => true,
-            Visibility::Module => false,
+            Visibility::Module | Visibility::Protected | Visibility::Package => false,
             Visibility::Private => false,
         }
     }
@@ -32,7 +32,7 @@ impl LanguageDefinition for CppLanguage {
     }
 
     fn create_parser(&self, _settings: &Settings) -> IndexResult<Box<dyn LanguageParser>> {
-        let parser = CppParser::new().map_err(crate::IndexError::General)?;
+        let parser = CppParser::new().map_err(|e| crate::IndexError::parser_init("cpp", e))?;
         Ok(Box::new(parser))
     }
 
@@ -100,6 +100,7 @@ impl CppParser {
                     file_id,
                     is_glob: false,
                     is_type_only: false,
+                    range: Some(Import::range_of(&node)),
                 });
             }
         }
@@ -745,12 +746,12 @@ impl LanguageParser for CppParser {
 
     fn extract_doc_comment(&self, node: &Node, code: &str) -> Option<String> {
         // Look for Doxygen/JavaDoc style comments (/** ... */ or ///)
-        // Check previous sibling for doc comment
-        let prev = node.prev_sibling()?;
-
-        if prev.kind() == "comment" {
-            let comment = &code[prev.byte_range()];
+        // Only the comment nearest the declaration is its doc
+        let comments = self.preceding_comments(node, code, &|text| {
+            text.starts_with("/**") || text.starts_with("///")
+        });
 
+        if let Some(&comment) = comments.last() {
             // Handle block comments (/** ... */)
             if comment.starts_with("/**") {
                 let cleaned = comment
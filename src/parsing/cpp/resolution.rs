@@ -4,7 +4,7 @@
 //! as Rust and TypeScript implementations, with additional C++-specific features.
 
 use crate::parsing::resolution::ImportBinding;
-use crate::parsing::{InheritanceResolver, ResolutionScope, ScopeLevel, ScopeType};
+use crate::parsing::{NameKeyedInheritanceResolver, ResolutionScope, ScopeLevel, ScopeType};
 use crate::{FileId, SymbolId};
 use std::collections::HashMap;
 
@@ -259,7 +259,7 @@ impl ResolutionScope for CppResolutionContext {
     }
 }
 
-/// Implementation of InheritanceResolver for C++
+/// Implementation of NameKeyedInheritanceResolver for C++
 ///
 /// Handles C++ inheritance patterns including:
 /// - Single and multiple inheritance
@@ -290,7 +290,7 @@ impl Default for CppInheritanceResolver {
     }
 }
 
-impl InheritanceResolver for CppInheritanceResolver {
+impl NameKeyedInheritanceResolver for CppInheritanceResolver {
     fn add_inheritance(&mut self, child: String, parent: String, kind: &str) {
         self.inheritance_map
             .entry(child)
@@ -13,7 +13,9 @@
 use crate::parsing::LanguageBehavior;
 use crate::parsing::behavior_state::{BehaviorState, StatefulBehavior};
 use crate::parsing::paths::strip_extension;
+use crate::parsing::references::ReferenceNodeKinds;
 use crate::parsing::resolution::ResolutionScope;
+use crate::parsing::signature::{self, SignatureStyle};
 use crate::symbol::ScopeContext;
 use crate::types::FileId;
 use crate::{Symbol, Visibility};
@@ -93,6 +95,32 @@ impl StatefulBehavior for CSharpBehavior {
     }
 }
 
+/// Reference pass node kinds. C# type names are plain identifiers, so type
+/// references show up here as well as in `Uses`.
+static REFERENCE_NODES: ReferenceNodeKinds = ReferenceNodeKinds {
+    identifiers: &["identifier"],
+    bindings: &[
+        ("parameter", Some("name")),
+        ("variable_declarator", Some("name")),
+        ("foreach_statement", Some("left")),
+        ("catch_declaration", Some("name")),
+        ("declaration_expression", Some("name")),
+        ("lambda_expression", Some("parameters")),
+    ],
+    members: &[
+        ("member_access_expression", Some("name")),
+        ("argument", Some("name")),
+    ],
+    paths: &[],
+    fields: &[("member_access_expression", "expression", "name")],
+    containers: &[
+        ("class_declaration", "name"),
+        ("struct_declaration", "name"),
+        ("record_declaration", "name"),
+    ],
+    this: &["this"],
+};
+
 impl LanguageBehavior for CSharpBehavior {
     fn language_id(&self) -> crate::parsing::registry::LanguageId {
         crate::parsing::registry::LanguageId::new("csharp")
@@ -116,6 +144,10 @@ impl LanguageBehavior for CSharpBehavior {
         tree_sitter_c_sharp::LANGUAGE.into()
     }
 
+    fn reference_node_kinds(&self) -> Option<&'static ReferenceNodeKinds> {
+        Some(&REFERENCE_NODES)
+    }
+
     fn format_path_as_module(&self, components: &[&str]) -> Option<String> {
         if components.is_empty() {
             None
@@ -128,6 +160,12 @@ impl LanguageBehavior for CSharpBehavior {
         "." // C# uses dots for namespace separation
     }
 
+    // Attributes are bracketed: `[Obsolete]`
+    fn normalize_signature(&self, signature: &str, style: SignatureStyle) -> String {
+        let declaration = signature::strip_attributes(signature, &["["]);
+        signature::finish(signature::strip_body(declaration), style)
+    }
+
     fn module_path_from_file(
         &self,
         file_path: &Path,
@@ -242,11 +280,10 @@ impl LanguageBehavior for CSharpBehavior {
         } else if signature.contains("private ") {
             Visibility::Private
         } else if signature.contains("protected ") {
-            // Map protected to Module visibility as closest approximation
-            Visibility::Module
+            Visibility::Protected
         } else if signature.contains("internal ") {
-            // Internal is assembly-level visibility, map to Module
-            Visibility::Module
+            // Internal is assembly-level visibility, the C# counterpart of a crate
+            Visibility::Crate
         } else {
             // Default C# visibility depends on context:
             // - Top-level types: internal
@@ -325,7 +362,13 @@ impl LanguageBehavior for CSharpBehavior {
                 ScopeContext::Module | ScopeContext::Global | ScopeContext::Package => true,
                 ScopeContext::Local { .. } | ScopeContext::Parameter => false,
                 ScopeContext::ClassMember { .. } => {
-                    matches!(symbol.visibility, Visibility::Public | Visibility::Module)
+                    matches!(
+                        symbol.visibility,
+                        Visibility::Public
+                            | Visibility::Crate
+                            | Visibility::Module
+                            | Visibility::Protected
+                    )
                 }
             }
         } else {
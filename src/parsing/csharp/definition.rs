@@ -25,7 +25,7 @@ impl LanguageDefinition for CSharpLanguage {
     }
 
     fn create_parser(&self, _settings: &Settings) -> IndexResult<Box<dyn LanguageParser>> {
-        let parser = CSharpParser::new().map_err(|e| IndexError::General(e.to_string()))?;
+        let parser = CSharpParser::new().map_err(|e| IndexError::parser_init("csharp", e))?;
         Ok(Box::new(parser))
     }
 
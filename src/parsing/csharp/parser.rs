@@ -121,7 +121,7 @@ impl CSharpParser {
                 );
             }
             None => {
-                eprintln!("Failed to parse C# file");
+                tracing::warn!(file_id = file_id.0, "failed to parse C# file");
             }
         }
 
@@ -964,6 +964,7 @@ impl CSharpParser {
                         file_id,
                         is_glob: false,
                         is_type_only: false,
+                        range: Some(Import::range_of(&node)),
                     });
                 } else {
                     // Fallback: tree-sitter-c-sharp doesn't consistently expose "name" field
@@ -979,6 +980,7 @@ impl CSharpParser {
                                 file_id,
                                 is_glob: false,
                                 is_type_only: false,
+                                range: Some(Import::range_of(&node)),
                             });
                             break;
                         }
@@ -1235,9 +1237,9 @@ impl CSharpParser {
                 } else if modifier_text.contains("private") {
                     return Visibility::Private;
                 } else if modifier_text.contains("protected") {
-                    return Visibility::Module; // Closest approximation
+                    return Visibility::Protected;
                 } else if modifier_text.contains("internal") {
-                    return Visibility::Module;
+                    return Visibility::Crate;
                 }
             }
         }
@@ -1245,39 +1247,18 @@ impl CSharpParser {
         // Default C# visibility rules
         match self.context.current_scope_context() {
             crate::symbol::ScopeContext::ClassMember { .. } => Visibility::Private, // Class members are private by default
-            _ => Visibility::Module, // Top-level types are internal by default
+            _ => Visibility::Crate, // Top-level types are internal by default
         }
     }
 
     /// Extract documentation comment
     fn extract_doc_comment(&self, node: &Node, code: &str) -> Option<String> {
         // Collect all consecutive /// comments immediately before this node
-        let mut doc_lines = Vec::new();
-        let mut current = node.prev_sibling();
-
-        // Walk backwards through siblings, collecting /// comments
-        while let Some(sibling) = current {
-            if sibling.kind() == "comment" {
-                let comment_text = &code[sibling.byte_range()];
-                // C# XML documentation comments start with ///
-                if comment_text.starts_with("///") {
-                    doc_lines.push(comment_text.to_string());
-                } else {
-                    // Non-doc comment stops the sequence
-                    break;
-                }
-            } else {
-                // Non-comment node stops the sequence
-                break;
-            }
-            current = sibling.prev_sibling();
-        }
+        let doc_lines = self.preceding_comments(node, code, &|text| text.starts_with("///"));
 
         if doc_lines.is_empty() {
             None
         } else {
-            // Reverse to restore original order (we walked backwards)
-            doc_lines.reverse();
             Some(doc_lines.join("\n"))
         }
     }
@@ -1786,7 +1767,7 @@ impl LanguageParser for CSharpParser {
         let tree = match self.parser.parse(code, None) {
             Some(tree) => tree,
             None => {
-                eprintln!("Failed to parse C# file for calls");
+                tracing::warn!("failed to parse C# file for calls");
                 return Vec::new();
             }
         };
@@ -1812,7 +1793,7 @@ impl LanguageParser for CSharpParser {
                 self.extract_method_calls_from_node(root_node, code, &mut method_calls);
             }
             None => {
-                eprintln!("Failed to parse C# file for method calls");
+                tracing::warn!("failed to parse C# file for method calls");
             }
         }
 
@@ -1828,7 +1809,7 @@ impl LanguageParser for CSharpParser {
                 Self::extract_implementations_from_node(root_node, code, &mut implementations);
             }
             None => {
-                eprintln!("Failed to parse C# file for implementations");
+                tracing::warn!("failed to parse C# file for implementations");
             }
         }
 
@@ -1928,7 +1909,7 @@ impl LanguageParser for CSharpParser {
                 Self::extract_imports_from_node(root_node, code, file_id, &mut imports);
             }
             None => {
-                eprintln!("Failed to parse C# file for imports");
+                tracing::warn!("failed to parse C# file for imports");
             }
         }
 
@@ -272,7 +272,10 @@ impl ResolutionScope for CSharpResolutionContext {
                 // C#: classes inherit from classes
                 self.resolve(to_name)
             }
-            RelationKind::Uses | RelationKind::References => {
+            RelationKind::Uses
+            | RelationKind::References
+            | RelationKind::Aliases
+            | RelationKind::Throws => {
                 // General usage/reference
                 self.resolve(to_name)
             }
@@ -289,7 +292,9 @@ impl ResolutionScope for CSharpResolutionContext {
             | RelationKind::ImplementedBy
             | RelationKind::UsedBy
             | RelationKind::DefinedIn
-            | RelationKind::ReferencedBy => {
+            | RelationKind::ReferencedBy
+            | RelationKind::AliasedBy
+            | RelationKind::ThrownBy => {
                 // Reverse relationships - typically used for finding references
                 self.resolve(to_name)
             }
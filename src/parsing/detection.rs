@@ -0,0 +1,135 @@
+//! Language detection from file content
+//!
+//! Scripts are often named without an extension and announce their language
+//! in the first line instead: `#!/usr/bin/env python3`. For such files the
+//! registry falls back to what the start of the file says, in this order:
+//!
+//! - the shebang interpreter, looking through `env` and its flags, and through
+//!   `nix-shell` to the interpreter its `-i` option names
+//! - an Emacs (`-*- mode: python -*-`) or Vim (`vim: set ft=python:`) modeline
+//! - a `<?php` open tag
+//!
+//! See [`LanguageRegistry::detect`](super::LanguageRegistry::detect).
+
+use std::io::Read;
+use std::path::Path;
+
+/// Bytes read from the start of a file for detection
+pub const HEAD_BYTES: usize = 1024;
+
+/// Lines at the start of a file that may hold a modeline or `nix-shell` options
+const HEAD_LINES: usize = 5;
+
+/// Start of the file at `path`, lossily decoded; `None` if it can't be read
+pub fn read_head(path: &Path) -> Option<String> {
+    let file = std::fs::File::open(path).ok()?;
+    let mut buf = Vec::with_capacity(HEAD_BYTES);
+    file.take(HEAD_BYTES as u64).read_to_end(&mut buf).ok()?;
+    Some(String::from_utf8_lossy(&buf).into_owned())
+}
+
+/// Interpreter named by the shebang line of `head`, as a bare program name
+/// (`python3` for `#!/usr/bin/python3`)
+pub fn shebang_interpreter(head: &str) -> Option<&str> {
+    let first = head.lines().next()?.strip_prefix("#!")?;
+    let mut words = first.split_whitespace();
+    let mut program = program_name(words.next()?);
+    if program == "env" {
+        // Skip `env` options (`-S`, `-i`) and variable assignments
+        program = program_name(words.find(|w| !w.starts_with('-') && !w.contains('='))?);
+    }
+    if program == "nix-shell" {
+        return nix_shell_interpreter(head);
+    }
+    Some(program)
+}
+
+/// Interpreter of a `nix-shell` script, from the `-i` option of its
+/// `#! nix-shell` lines. Without one the script runs in bash.
+fn nix_shell_interpreter(head: &str) -> Option<&str> {
+    head.lines()
+        .take(HEAD_LINES)
+        .filter_map(|line| line.strip_prefix("#!"))
+        .find_map(|line| {
+            let mut words = line.split_whitespace();
+            words.find(|w| *w == "-i")?;
+            words.next().map(program_name)
+        })
+}
+
+/// Language named by something other than the shebang: a modeline, or a
+/// PHP open tag
+pub fn content_language(head: &str) -> Option<&str> {
+    if head.trim_start().starts_with("<?php") {
+        return Some("php");
+    }
+    head.lines().take(HEAD_LINES).find_map(modeline_language)
+}
+
+/// Language name of an Emacs or Vim modeline on `line`
+fn modeline_language(line: &str) -> Option<&str> {
+    if let Some(start) = line.find("-*-") {
+        let rest = &line[start + 3..];
+        let body = &rest[..rest.find("-*-")?];
+        // `-*- python -*-` or `-*- mode: python; coding: utf-8 -*-`
+        return body.split(';').find_map(|part| match part.split_once(':') {
+            Some((key, value)) if key.trim().eq_ignore_ascii_case("mode") => Some(value.trim()),
+            Some(_) => None,
+            None => Some(part.trim()).filter(|p| !p.is_empty()),
+        });
+    }
+
+    let at = line.find("vim:").or_else(|| line.find("vi:"))?;
+    line[at..]
+        .split(|c: char| c.is_whitespace() || c == ':')
+        .find_map(|option| {
+            option
+                .strip_prefix("ft=")
+                .or_else(|| option.strip_prefix("filetype="))
+        })
+        .filter(|name| !name.is_empty())
+}
+
+/// Last path component of a program
+fn program_name(program: &str) -> &str {
+    program.rsplit('/').next().unwrap_or(program)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shebang_and_content_detection() {
+        assert_eq!(
+            shebang_interpreter("#!/usr/bin/env python3\n"),
+            Some("python3")
+        );
+        assert_eq!(shebang_interpreter("#! /usr/local/bin/node"), Some("node"));
+        assert_eq!(
+            shebang_interpreter("#!/usr/bin/env -S NODE_ENV=prod deno run\n"),
+            Some("deno")
+        );
+        assert_eq!(
+            shebang_interpreter("#!/usr/bin/env nix-shell\n#!nix-shell -i python3 -p python3\n"),
+            Some("python3")
+        );
+        assert_eq!(
+            shebang_interpreter("#!/usr/bin/env nix-shell\necho hi\n"),
+            None
+        );
+        assert_eq!(shebang_interpreter("print('no shebang')\n"), None);
+
+        assert_eq!(content_language("<?php\necho 1;"), Some("php"));
+        assert_eq!(
+            content_language("#!/bin/sh\n# -*- mode: lua; coding: utf-8 -*-\n"),
+            Some("lua")
+        );
+        assert_eq!(content_language("// -*- swift -*-\n"), Some("swift"));
+        assert_eq!(
+            content_language("# vim: set ft=python ts=4:\n"),
+            Some("python")
+        );
+        assert_eq!(content_language("just text\n"), None);
+    }
+}
@@ -51,7 +51,7 @@ impl ParserFactory {
         let registry = get_registry();
         let registry = registry
             .lock()
-            .map_err(|e| IndexError::General(format!("Failed to acquire registry lock: {e}")))?;
+            .map_err(|e| IndexError::LockError(format!("language registry: {e}")))?;
 
         registry
             .create_parser(language_id, &self.settings)
@@ -69,7 +69,7 @@ impl ParserFactory {
         let registry = get_registry();
         let registry = registry
             .lock()
-            .map_err(|e| IndexError::General(format!("Failed to acquire registry lock: {e}")))?;
+            .map_err(|e| IndexError::LockError(format!("language registry: {e}")))?;
 
         let (parser, behavior) = registry
             .create_parser_with_behavior(language_id, &self.settings)
@@ -124,11 +124,12 @@ impl ParserFactory {
 
         match language {
             Language::Rust => {
-                let parser = RustParser::new().map_err(IndexError::General)?;
+                let parser = RustParser::new().map_err(|e| IndexError::parser_init("rust", e))?;
                 Ok(Box::new(parser))
             }
             Language::Python => {
-                let parser = PythonParser::new().map_err(|e| IndexError::General(e.to_string()))?;
+                let parser =
+                    PythonParser::new().map_err(|e| IndexError::parser_init("python", e))?;
                 Ok(Box::new(parser))
             }
             Language::JavaScript => {
@@ -146,47 +147,50 @@ impl ParserFactory {
                 )))
             }
             Language::Php => {
-                let parser = PhpParser::new().map_err(|e| IndexError::General(e.to_string()))?;
+                let parser = PhpParser::new().map_err(|e| IndexError::parser_init("php", e))?;
                 Ok(Box::new(parser))
             }
             Language::Go => {
-                let parser = GoParser::new().map_err(|e| IndexError::General(e.to_string()))?;
+                let parser = GoParser::new().map_err(|e| IndexError::parser_init("go", e))?;
                 Ok(Box::new(parser))
             }
             Language::Nix => {
-                let parser = NixParser::new().map_err(|e| IndexError::General(e.to_string()))?;
+                let parser = NixParser::new().map_err(|e| IndexError::parser_init("nix", e))?;
                 Ok(Box::new(parser))
             }
             Language::C => {
-                let parser = CParser::new().map_err(|e| IndexError::General(e.to_string()))?;
+                let parser = CParser::new().map_err(|e| IndexError::parser_init("c", e))?;
                 Ok(Box::new(parser))
             }
             Language::Cpp => {
-                let parser = CppParser::new().map_err(|e| IndexError::General(e.to_string()))?;
+                let parser = CppParser::new().map_err(|e| IndexError::parser_init("cpp", e))?;
                 Ok(Box::new(parser))
             }
             Language::CSharp => {
-                let parser = CSharpParser::new().map_err(|e| IndexError::General(e.to_string()))?;
+                let parser =
+                    CSharpParser::new().map_err(|e| IndexError::parser_init("csharp", e))?;
                 Ok(Box::new(parser))
             }
             Language::Gdscript => {
-                let parser = GdscriptParser::new().map_err(IndexError::General)?;
+                let parser =
+                    GdscriptParser::new().map_err(|e| IndexError::parser_init("gdscript", e))?;
                 Ok(Box::new(parser))
             }
             Language::Java => {
-                let parser = JavaParser::new().map_err(|e| IndexError::General(e.to_string()))?;
+                let parser = JavaParser::new().map_err(|e| IndexError::parser_init("java", e))?;
                 Ok(Box::new(parser))
             }
             Language::Kotlin => {
-                let parser = KotlinParser::new().map_err(|e| IndexError::General(e.to_string()))?;
+                let parser =
+                    KotlinParser::new().map_err(|e| IndexError::parser_init("kotlin", e))?;
                 Ok(Box::new(parser))
             }
             Language::Lua => {
-                let parser = LuaParser::new().map_err(|e| IndexError::General(e.to_string()))?;
+                let parser = LuaParser::new().map_err(|e| IndexError::parser_init("lua", e))?;
                 Ok(Box::new(parser))
             }
             Language::Swift => {
-                let parser = SwiftParser::new().map_err(|e| IndexError::General(e.to_string()))?;
+                let parser = SwiftParser::new().map_err(|e| IndexError::parser_init("swift", e))?;
                 Ok(Box::new(parser))
             }
         }
@@ -228,107 +232,111 @@ impl ParserFactory {
         // Create parser and behavior pair
         let result = match language {
             Language::Rust => {
-                let parser = RustParser::new().map_err(IndexError::General)?;
+                let parser = RustParser::new().map_err(|e| IndexError::parser_init("rust", e))?;
                 ParserWithBehavior {
                     parser: Box::new(parser),
                     behavior: Box::new(RustBehavior::new()),
                 }
             }
             Language::Python => {
-                let parser = PythonParser::new().map_err(|e| IndexError::General(e.to_string()))?;
+                let parser =
+                    PythonParser::new().map_err(|e| IndexError::parser_init("python", e))?;
                 ParserWithBehavior {
                     parser: Box::new(parser),
                     behavior: Box::new(PythonBehavior::new()),
                 }
             }
             Language::Php => {
-                let parser = PhpParser::new().map_err(|e| IndexError::General(e.to_string()))?;
+                let parser = PhpParser::new().map_err(|e| IndexError::parser_init("php", e))?;
                 ParserWithBehavior {
                     parser: Box::new(parser),
                     behavior: Box::new(PhpBehavior::new()),
                 }
             }
             Language::TypeScript => {
-                let parser =
-                    TypeScriptParser::new().map_err(|e| IndexError::General(e.to_string()))?;
+                let parser = TypeScriptParser::new()
+                    .map_err(|e| IndexError::parser_init("typescript", e))?;
                 ParserWithBehavior {
                     parser: Box::new(parser),
                     behavior: Box::new(TypeScriptBehavior::new()),
                 }
             }
             Language::JavaScript => {
-                let parser =
-                    JavaScriptParser::new().map_err(|e| IndexError::General(e.to_string()))?;
+                let parser = JavaScriptParser::new()
+                    .map_err(|e| IndexError::parser_init("javascript", e))?;
                 ParserWithBehavior {
                     parser: Box::new(parser),
                     behavior: Box::new(JavaScriptBehavior::new()),
                 }
             }
             Language::Go => {
-                let parser = GoParser::new().map_err(|e| IndexError::General(e.to_string()))?;
+                let parser = GoParser::new().map_err(|e| IndexError::parser_init("go", e))?;
                 ParserWithBehavior {
                     parser: Box::new(parser),
                     behavior: Box::new(GoBehavior::new()),
                 }
             }
             Language::Nix => {
-                let parser = NixParser::new().map_err(|e| IndexError::General(e.to_string()))?;
+                let parser = NixParser::new().map_err(|e| IndexError::parser_init("nix", e))?;
                 ParserWithBehavior {
                     parser: Box::new(parser),
                     behavior: Box::new(NixBehavior::new()),
                 }
             }
             Language::C => {
-                let parser = CParser::new().map_err(|e| IndexError::General(e.to_string()))?;
+                let parser = CParser::new().map_err(|e| IndexError::parser_init("c", e))?;
                 ParserWithBehavior {
                     parser: Box::new(parser),
                     behavior: Box::new(CBehavior::new()),
                 }
             }
             Language::Cpp => {
-                let parser = CppParser::new().map_err(|e| IndexError::General(e.to_string()))?;
+                let parser = CppParser::new().map_err(|e| IndexError::parser_init("cpp", e))?;
                 ParserWithBehavior {
                     parser: Box::new(parser),
                     behavior: Box::new(CppBehavior::new()),
                 }
             }
             Language::CSharp => {
-                let parser = CSharpParser::new().map_err(|e| IndexError::General(e.to_string()))?;
+                let parser =
+                    CSharpParser::new().map_err(|e| IndexError::parser_init("csharp", e))?;
                 ParserWithBehavior {
                     parser: Box::new(parser),
                     behavior: Box::new(CSharpBehavior::new()),
                 }
             }
             Language::Gdscript => {
-                let parser = GdscriptParser::new().map_err(IndexError::General)?;
+                let parser =
+                    GdscriptParser::new().map_err(|e| IndexError::parser_init("gdscript", e))?;
                 ParserWithBehavior {
                     parser: Box::new(parser),
                     behavior: Box::new(GdscriptBehavior::new()),
                 }
             }
             Language::Java => {
-                let parser = JavaParser::new().map_err(|e| IndexError::General(e.to_string()))?;
+                let parser = JavaParser::new().map_err(|e| IndexError::parser_init("java", e))?;
                 ParserWithBehavior {
                     parser: Box::new(parser),
                     behavior: Box::new(JavaBehavior::new()),
                 }
             }
             Language::Kotlin => {
-                let parser = KotlinParser::new().map_err(|e| IndexError::General(e.to_string()))?;
+                let parser =
+                    KotlinParser::new().map_err(|e| IndexError::parser_init("kotlin", e))?;
                 ParserWithBehavior {
                     parser: Box::new(parser),
                     behavior: Box::new(KotlinBehavior::new()),
                 }
             }
             Language::Lua => {
-                let parser = LuaParser::new().map_err(|e| IndexError::General(e.to_string()))?;
+                let parser = LuaParser::new().map_err(|e| IndexError::parser_init("lua", e))?;
                 ParserWithBehavior {
                     parser: Box::new(parser),
                     behavior: Box::new(LuaBehavior::new()),
                 }
             }
             Language::Swift => {
-                let parser = SwiftParser::new().map_err(|e| IndexError::General(e.to_string()))?;
+                let parser = SwiftParser::new().map_err(|e| IndexError::parser_init("swift", e))?;
                 ParserWithBehavior {
                     parser: Box::new(parser),
                     behavior: Box::new(SwiftBehavior::new()),
@@ -410,7 +418,7 @@ mod tests {
         let go_id = LanguageId::new("go");
 
         if let Err(e) = &parser_with_behavior {
-            eprintln!("Failed to create Python parser with behavior: {e}");
+            tracing::warn!("Failed to create Python parser with behavior: {e}");
         }
         assert!(
             parser_with_behavior.is_ok(),
@@ -503,6 +511,7 @@ mod tests {
                 parser_options: HashMap::new(),
                 config_files: Vec::new(),
                 projects: Vec::new(),
+                profile: None,
             },
         );
 
@@ -515,6 +524,7 @@ mod tests {
                 parser_options: HashMap::new(),
                 config_files: Vec::new(),
                 projects: Vec::new(),
+                profile: None,
             },
         );
 
@@ -527,6 +537,7 @@ mod tests {
                 parser_options: HashMap::new(),
                 config_files: Vec::new(),
                 projects: Vec::new(),
+                profile: None,
             },
         );
 
@@ -539,6 +550,7 @@ mod tests {
                 parser_options: HashMap::new(),
                 config_files: Vec::new(),
                 projects: Vec::new(),
+                profile: None,
             },
         );
 
@@ -656,6 +668,7 @@ mod tests {
                 parser_options: HashMap::new(),
                 config_files: Vec::new(),
                 projects: Vec::new(),
+                profile: None,
             },
         );
         settings.languages = languages;
@@ -4,6 +4,8 @@ use crate::parsing::LanguageBehavior;
 use crate::parsing::ResolutionScope;
 use crate::parsing::behavior_state::{BehaviorState, StatefulBehavior};
 use crate::parsing::paths::strip_extension;
+use crate::parsing::references::ReferenceNodeKinds;
+use crate::parsing::signature::{self, SignatureStyle};
 use crate::parsing::{Import, InheritanceResolver};
 use crate::types::compact_string;
 use crate::{FileId, Symbol, SymbolKind, Visibility};
@@ -120,6 +122,25 @@ impl Default for GdscriptBehavior {
     }
 }
 
+/// Reference pass node kinds. `var` names are `name` nodes, not
+/// identifiers, and are bound like parameters.
+static REFERENCE_NODES: ReferenceNodeKinds = ReferenceNodeKinds {
+    identifiers: &["identifier"],
+    bindings: &[
+        ("parameters", None),
+        ("typed_parameter", None),
+        ("default_parameter", None),
+        ("typed_default_parameter", None),
+        ("variable_statement", Some("name")),
+        ("for_statement", Some("left")),
+    ],
+    members: &[("attribute_call", None)],
+    paths: &[],
+    fields: &[],
+    containers: &[],
+    this: &[],
+};
+
 impl LanguageBehavior for GdscriptBehavior {
     fn language_id(&self) -> crate::parsing::registry::LanguageId {
         crate::parsing::registry::LanguageId::new("gdscript")
@@ -177,6 +198,12 @@ impl LanguageBehavior for GdscriptBehavior {
         "/"
     }
 
+    // Blocks open with a trailing `:` rather than a brace
+    fn normalize_signature(&self, signature: &str, style: SignatureStyle) -> String {
+        let header = signature::strip_attributes(signature, &[]);
+        signature::finish(header.trim_end().trim_end_matches(':'), style)
+    }
+
     fn format_path_as_module(&self, components: &[&str]) -> Option<String> {
         if components.is_empty() {
             Some("res://".to_string())
@@ -206,6 +233,10 @@ impl LanguageBehavior for GdscriptBehavior {
         self.language.clone()
     }
 
+    fn reference_node_kinds(&self) -> Option<&'static ReferenceNodeKinds> {
+        Some(&REFERENCE_NODES)
+    }
+
     // Override import tracking methods to use state
     fn register_file(&self, path: PathBuf, file_id: FileId, module_path: String) {
         self.register_file_with_state(path, file_id, module_path);
@@ -30,8 +30,12 @@ impl LanguageDefinition for GdscriptLanguage {
         &["gd"]
     }
 
+    fn interpreters(&self) -> &'static [&'static str] {
+        &["godot"]
+    }
+
     fn create_parser(&self, _settings: &Settings) -> IndexResult<Box<dyn LanguageParser>> {
-        let parser = GdscriptParser::new().map_err(IndexError::General)?;
+        let parser = GdscriptParser::new().map_err(|e| IndexError::parser_init("gdscript", e))?;
         Ok(Box::new(parser))
     }
 
@@ -67,29 +67,16 @@ impl GdscriptParser {
 
     /// Extract documentation comments prefixed with `##`
     fn doc_comment_for(&self, node: &Node, code: &str) -> Option<String> {
-        let mut comments = Vec::new();
-        let mut current = node.prev_named_sibling();
-
-        while let Some(sibling) = current {
-            if sibling.kind() != "comment" {
-                break;
-            }
-
-            let raw = self.text_for_node(code, sibling).trim();
-            if raw.starts_with("##") {
-                let cleaned = raw.trim_start_matches('#').trim();
-                comments.push(cleaned.to_string());
-                current = sibling.prev_named_sibling();
-            } else {
-                break;
-            }
-        }
-
+        let comments =
+            self.preceding_comments(node, code, &|text| text.trim_start().starts_with("##"));
         if comments.is_empty() {
             None
         } else {
-            comments.reverse();
-            Some(comments.join("\n"))
+            let cleaned: Vec<&str> = comments
+                .iter()
+                .map(|raw| raw.trim().trim_start_matches('#').trim())
+                .collect();
+            Some(cleaned.join("\n"))
         }
     }
 
@@ -188,6 +175,7 @@ impl GdscriptParser {
                             alias: None,
                             is_glob: false,
                             is_type_only: false,
+                            range: Some(Import::range_of(&node)),
                         });
                     }
                 }
@@ -204,6 +192,7 @@ impl GdscriptParser {
                                 alias: None,
                                 is_glob: false,
                                 is_type_only: false,
+                                range: Some(Import::range_of(&node)),
                             });
                         }
                     }
@@ -220,6 +209,7 @@ impl GdscriptParser {
                             alias: None,
                             is_glob: true, // Globally visible
                             is_type_only: false,
+                            range: Some(Import::range_of(&node)),
                         });
                     }
                 }
@@ -254,6 +244,7 @@ impl GdscriptParser {
                                                 alias: None,
                                                 is_glob: false,
                                                 is_type_only: false,
+                                                range: Some(Import::range_of(&node)),
                                             });
                                         }
                                     }
@@ -4,7 +4,7 @@
 //! GDScript language. GDScript shares similarities with Python-style modules
 //! and classes, but has its own surface syntax and export semantics.
 
-use crate::parsing::resolution::{ImportBinding, InheritanceResolver, ResolutionScope};
+use crate::parsing::resolution::{ImportBinding, NameKeyedInheritanceResolver, ResolutionScope};
 use crate::parsing::{ScopeLevel, ScopeType};
 use crate::{FileId, SymbolId};
 use std::collections::{HashMap, HashSet};
@@ -307,7 +307,7 @@ impl GdscriptInheritanceResolver {
     }
 }
 
-impl InheritanceResolver for GdscriptInheritanceResolver {
+impl NameKeyedInheritanceResolver for GdscriptInheritanceResolver {
     fn add_inheritance(&mut self, child: String, parent: String, _kind: &str) {
         self.parents.entry(child).or_default().push(parent);
     }
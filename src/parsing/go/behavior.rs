@@ -3,6 +3,8 @@
 use crate::Visibility;
 use crate::parsing::LanguageBehavior;
 use crate::parsing::behavior_state::{BehaviorState, StatefulBehavior};
+use crate::parsing::control_flow::BranchNodeKinds;
+use crate::parsing::references::ReferenceNodeKinds;
 use crate::parsing::resolution::{InheritanceResolver, ResolutionScope};
 use crate::types::FileId;
 use std::path::{Path, PathBuf};
@@ -37,6 +39,40 @@ impl StatefulBehavior for GoBehavior {
     }
 }
 
+/// Reference pass node kinds. Selector fields are `field_identifier` nodes;
+/// the package side of `pkg.Func` is read like any other name.
+static REFERENCE_NODES: ReferenceNodeKinds = ReferenceNodeKinds {
+    identifiers: &["identifier"],
+    bindings: &[
+        ("parameter_declaration", Some("name")),
+        ("variadic_parameter_declaration", Some("name")),
+        ("short_var_declaration", Some("left")),
+        ("var_spec", Some("name")),
+        ("const_spec", Some("name")),
+        ("range_clause", Some("left")),
+    ],
+    members: &[],
+    paths: &[],
+    fields: &[("selector_expression", "operand", "field")],
+    containers: &[],
+    this: &[],
+};
+
+/// Conditional-region node kinds. Each `switch` and `select` case is its
+/// own region; the switch value is evaluated unconditionally.
+static BRANCH_NODES: BranchNodeKinds = BranchNodeKinds {
+    branches: &[
+        ("if_statement", &["consequence", "alternative"]),
+        ("for_statement", &["body"]),
+        ("expression_case", &[]),
+        ("default_case", &[]),
+        ("type_case", &[]),
+        ("communication_case", &[]),
+        ("func_literal", &[]),
+    ],
+    short_circuit: &["&&", "||"],
+};
+
 impl LanguageBehavior for GoBehavior {
     fn language_id(&self) -> crate::parsing::registry::LanguageId {
         crate::parsing::registry::LanguageId::new("go")
@@ -51,6 +87,14 @@ impl LanguageBehavior for GoBehavior {
     fn get_language(&self) -> Language {
         tree_sitter_go::LANGUAGE.into()
     }
+
+    fn reference_node_kinds(&self) -> Option<&'static ReferenceNodeKinds> {
+        Some(&REFERENCE_NODES)
+    }
+
+    fn branch_node_kinds(&self) -> Option<&'static BranchNodeKinds> {
+        Some(&BRANCH_NODES)
+    }
     fn module_separator(&self) -> &'static str {
         "/"
     }
@@ -604,8 +648,12 @@ mod tests {
             file_path: "<unknown>".into(),
             doc_comment: None,
             visibility: Visibility::Private, // Will be updated by configure_symbol
+            exported: false,
             scope_context: None,
             language_id: Some(LanguageId::new("go")),
+            annotations: Vec::new(),
+            tags: Vec::new(),
+            project: None,
         };
 
         behavior.configure_symbol(&mut symbol, Some("pkg/utils"));
@@ -633,8 +681,12 @@ mod tests {
             file_path: "<unknown>".into(),
             doc_comment: None,
             visibility: Visibility::Public, // Will be updated by configure_symbol
+            exported: false,
             scope_context: None,
             language_id: Some(LanguageId::new("go")),
+            annotations: Vec::new(),
+            tags: Vec::new(),
+            project: None,
         };
 
         behavior.configure_symbol(&mut symbol, None);
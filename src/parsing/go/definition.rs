@@ -70,7 +70,7 @@ impl LanguageDefinition for GoLanguage {
     }
 
     fn create_parser(&self, _settings: &Settings) -> IndexResult<Box<dyn LanguageParser>> {
-        let parser = GoParser::new().map_err(|e| IndexError::General(e.to_string()))?;
+        let parser = GoParser::new().map_err(|e| IndexError::parser_init("go", e))?;
         Ok(Box::new(parser))
     }
 
@@ -60,7 +60,7 @@ impl GoParser {
                 );
             }
             None => {
-                eprintln!("Failed to parse Go file");
+                tracing::warn!(file_id = file_id.0, "failed to parse Go file");
             }
         }
 
@@ -1070,6 +1070,8 @@ impl GoParser {
                 hoisted: false, // Go doesn't have hoisting
                 parent_name: self.context.current_function().map(|s| s.into()),
                 parent_kind: Some(SymbolKind::Function),
+                depth: self.context.scope_depth(),
+                ancestors: self.context.ancestor_kinds(),
             });
 
             symbols.push(symbol);
@@ -1283,6 +1285,8 @@ impl GoParser {
                 hoisted: false, // Go doesn't have hoisting
                 parent_name: self.context.current_function().map(|s| s.into()),
                 parent_kind: Some(SymbolKind::Function),
+                depth: self.context.scope_depth(),
+                ancestors: self.context.ancestor_kinds(),
             });
 
             symbols.push(symbol);
@@ -1473,6 +1477,7 @@ impl GoParser {
                 file_id,
                 is_glob: is_dot_import, // Dot imports are like glob imports
                 is_type_only: false,    // Go doesn't have type-only imports
+                range: Some(Import::range_of(&node)),
             };
             imports.push(import);
         }
@@ -1871,6 +1876,7 @@ impl GoParser {
                                 is_static,
                                 range,
                                 caller_range: None, // TODO: track caller definition range
+                                category: None,
                             };
 
                             calls.push(method_call);
@@ -1980,38 +1986,17 @@ impl LanguageParser for GoParser {
 
         let search_node = comment_node?;
 
-        let mut doc_lines = Vec::new();
-        let mut current = search_node.prev_sibling();
-
-        // Walk backwards through previous siblings to collect consecutive comment lines
-        while let Some(sibling) = current {
-            if sibling.kind() == "comment" {
-                let comment_text = &code[sibling.byte_range()];
-
-                // Check for Go-style line comments starting with //
-                if comment_text.starts_with("//") {
-                    // Extract the comment content (remove // and leading/trailing whitespace)
-                    let content = comment_text.trim_start_matches("//").trim();
-
-                    // Add to the beginning of doc_lines since we're walking backwards
-                    doc_lines.insert(0, content.to_string());
-
-                    // Continue to previous sibling to check for more comment lines
-                    current = sibling.prev_sibling();
-                } else {
-                    // Found a non-Go comment (like /* */), stop collecting
-                    break;
-                }
-            } else {
-                // Found a non-comment node, stop collecting
-                break;
-            }
-        }
+        // Consecutive line comments; a block comment (/* */) ends the doc
+        let doc_lines: Vec<&str> = self
+            .preceding_comments(&search_node, code, &|text| text.starts_with("//"))
+            .into_iter()
+            .map(|text| text.trim_start_matches("//").trim())
+            .collect();
 
         // If we found any documentation comments, join them and return
         if !doc_lines.is_empty() {
             // Filter out empty lines and join with newlines
-            let filtered_lines: Vec<String> = doc_lines
+            let filtered_lines: Vec<&str> = doc_lines
                 .into_iter()
                 .filter(|line| !line.is_empty())
                 .collect();
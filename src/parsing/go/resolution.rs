@@ -8,7 +8,7 @@
 //! - Interface implementation tracking (implicit in Go)
 
 use crate::parsing::resolution::ImportBinding;
-use crate::parsing::{InheritanceResolver, ResolutionScope, ScopeLevel, ScopeType};
+use crate::parsing::{NameKeyedInheritanceResolver, ResolutionScope, ScopeLevel, ScopeType};
 use crate::storage::DocumentIndex;
 use crate::{FileId, SymbolId};
 use std::collections::HashMap;
@@ -470,6 +470,8 @@ impl GoResolutionContext {
                             crate::Visibility::Private => return Some(candidate.id),
                             crate::Visibility::Crate => return Some(candidate.id),
                             crate::Visibility::Module => return Some(candidate.id),
+                            crate::Visibility::Protected => return Some(candidate.id),
+                            crate::Visibility::Package => return Some(candidate.id),
                         }
                     }
                 }
@@ -1146,7 +1148,7 @@ impl GoInheritanceResolver {
     }
 }
 
-impl InheritanceResolver for GoInheritanceResolver {
+impl NameKeyedInheritanceResolver for GoInheritanceResolver {
     fn add_inheritance(&mut self, child: String, parent: String, kind: &str) {
         match kind {
             "embeds" => {
@@ -3,7 +3,8 @@
 //! This module defines the Import struct used by language parsers
 //! to represent import statements extracted from source files.
 
-use crate::FileId;
+use crate::{FileId, Range};
+use tree_sitter::Node;
 
 /// Represents an import statement in a file
 #[derive(Debug, Clone)]
@@ -18,4 +19,20 @@ pub struct Import {
     pub is_glob: bool,
     /// Whether this is a type-only import (TypeScript: `import type { Foo }`)
     pub is_type_only: bool,
+    /// Where the import statement sits, when the parser recorded it
+    pub range: Option<Range>,
+}
+
+impl Import {
+    /// Range of the import statement at `node`, for [`Import::range`]
+    pub fn range_of(node: &Node) -> Range {
+        let start = node.start_position();
+        let end = node.end_position();
+        Range::new(
+            start.row as u32,
+            start.column as u16,
+            end.row as u32,
+            end.column as u16,
+        )
+    }
 }
@@ -0,0 +1,292 @@
+//! Embedded language regions
+//!
+//! Code of one language often lives inside another: a Python script in a Nix
+//! `writers.writePython3` string, JavaScript in a PHP template's `<script>`
+//! block. Languages describe where with [`InjectionRule`]s (see
+//! [`LanguageBehavior::injection_rules`]), and [`find_injections`] locates the
+//! regions in a host file so the pipeline can parse them with the embedded
+//! language's parser and index their symbols under the host file.
+//!
+//! A rule only applies when its language is registered; regions of languages
+//! without a parser are left alone. That is the case for the SQL in Python
+//! query calls, the bash in Nix `writeShellScript` strings, and the HTML of
+//! PHP templates: codanna ships no SQL, bash, or HTML grammar yet, so their
+//! rules locate the regions, and indexing picks them up once those languages
+//! are registered.
+//!
+//! [`LanguageBehavior::injection_rules`]: super::LanguageBehavior::injection_rules
+
+use super::parser::check_recursion_depth;
+use crate::types::Range;
+use tree_sitter::Node;
+
+/// Where a host language embeds code of another
+///
+/// Several rules may match one node; each adds its regions.
+#[derive(Debug, Clone, Copy)]
+pub struct InjectionRule {
+    /// Registered id of the embedded language (e.g. "python")
+    pub language: &'static str,
+    /// Kind of the node holding the code (a string literal or template text)
+    pub node_kind: &'static str,
+    /// Functions whose argument the code must be, matched against the last
+    /// segment of the callee (`writePython3` matches
+    /// `pkgs.writers.writePython3`). Empty matches every node of the kind.
+    pub callees: &'static [&'static str],
+    /// Opening tag and closing tag delimiting the code inside the node, for
+    /// markup such as `("<script", "</script>")`. The opening tag extends to
+    /// the next `>`. `None` takes the node's contents without its quotes, or
+    /// the whole node when it has none, as markup doesn't.
+    pub delimiters: Option<(&'static str, &'static str)>,
+}
+
+/// Code of an embedded language found in a host file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InjectedRegion {
+    /// Registered id of the embedded language
+    pub language: &'static str,
+    pub code: String,
+    /// Zero-based line of the first byte of `code` in the host file
+    pub start_line: u32,
+    /// Column of the first byte of `code` in the host file
+    pub start_column: u16,
+}
+
+impl InjectedRegion {
+    /// Map a range in the region's code to the host file
+    pub fn to_host(&self, range: Range) -> Range {
+        let shift = |line: u32, column: u16| {
+            if line == 0 {
+                (self.start_line, column.saturating_add(self.start_column))
+            } else {
+                (line + self.start_line, column)
+            }
+        };
+        let (start_line, start_column) = shift(range.start_line, range.start_column);
+        let (end_line, end_column) = shift(range.end_line, range.end_column);
+        Range::new(start_line, start_column, end_line, end_column)
+    }
+}
+
+/// Embedded code regions under `root` matched by `rules`
+pub fn find_injections(root: Node, code: &str, rules: &[InjectionRule]) -> Vec<InjectedRegion> {
+    let mut regions = Vec::new();
+    if !rules.is_empty() {
+        visit(root, code, rules, &mut regions, 0);
+    }
+    regions
+}
+
+fn visit(
+    node: Node,
+    code: &str,
+    rules: &[InjectionRule],
+    regions: &mut Vec<InjectedRegion>,
+    depth: usize,
+) {
+    if !check_recursion_depth(depth, node) {
+        return;
+    }
+    let mut matched = false;
+    for rule in rules.iter().filter(|rule| {
+        rule.node_kind == node.kind()
+            && (rule.callees.is_empty()
+                || callee_name(node, code).is_some_and(|name| rule.callees.contains(&name)))
+    }) {
+        matched = true;
+        let start = node.start_byte();
+        let text = &code[start..node.end_byte()];
+        let spans = match rule.delimiters {
+            Some((open, close)) => delimited_spans(text, open, close),
+            None => unquoted_span(text).into_iter().collect(),
+        };
+        for (from, to) in spans {
+            if text[from..to].trim().is_empty() {
+                continue;
+            }
+            let (start_line, start_column) = position(code, start + from);
+            regions.push(InjectedRegion {
+                language: rule.language,
+                code: text[from..to].to_string(),
+                start_line,
+                start_column,
+            });
+        }
+    }
+    if matched {
+        return;
+    }
+    for child in node.children(&mut node.walk()) {
+        visit(child, code, rules, regions, depth + 1);
+    }
+}
+
+/// Last segment of the function a node is passed to, through argument lists
+/// and curried applications (`f "name" {} ''code''` in Nix)
+fn callee_name<'a>(node: Node, code: &'a str) -> Option<&'a str> {
+    let mut child = node;
+    let mut call = None;
+    for _ in 0..3 {
+        let parent = child.parent()?;
+        if let Some(function) = parent.child_by_field_name("function") {
+            if function.id() != child.id() {
+                call = Some(function);
+                break;
+            }
+        }
+        child = parent;
+    }
+
+    let mut function = call?;
+    while let Some(inner) = function.child_by_field_name("function") {
+        function = inner;
+    }
+    let text = function.utf8_text(code.as_bytes()).ok()?;
+    text.rsplit(['.', ':', '>', '\\']).next()
+}
+
+/// Byte span of a string literal's contents: without a prefix such as `r` or
+/// `f` and up to three quote characters at each end (`"`, `'`, `` ` ``, or
+/// Nix `''`). Text without quotes is taken whole.
+fn unquoted_span(text: &str) -> Option<(usize, usize)> {
+    let is_quote = |c: char| matches!(c, '"' | '\'' | '`');
+    let prefix = text
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .unwrap_or(text.len());
+    let quotes = text[prefix..]
+        .chars()
+        .take(3)
+        .take_while(|&c| is_quote(c))
+        .count();
+    if quotes == 0 {
+        return Some((0, text.len()));
+    }
+    let trailing = text.chars().rev().take_while(|&c| is_quote(c)).count();
+    if quotes == 0 || trailing < quotes || prefix + quotes > text.len() - quotes {
+        return None;
+    }
+    Some((prefix + quotes, text.len() - quotes))
+}
+
+/// Byte spans between `open ... >` and `close` tags
+fn delimited_spans(text: &str, open: &str, close: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut at = 0;
+    while let Some(found) = text[at..].find(open) {
+        let tag = at + found;
+        let Some(tag_end) = text[tag..].find('>') else {
+            break;
+        };
+        let from = tag + tag_end + 1;
+        let Some(length) = text[from..].find(close) else {
+            break;
+        };
+        spans.push((from, from + length));
+        at = from + length + close.len();
+    }
+    spans
+}
+
+/// Zero-based line and byte column of `offset` in `code`
+fn position(code: &str, offset: usize) -> (u32, u16) {
+    let before = &code[..offset];
+    let line = before.matches('\n').count() as u32;
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let column = u16::try_from(offset - line_start).unwrap_or(u16::MAX);
+    (line, column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::{LanguageBehavior, nix::NixBehavior};
+
+    #[test]
+    fn test_find_injections_in_nix_writers() {
+        let code = r#"{ pkgs }:
+{
+  tool = pkgs.writers.writePython3 "tool" { } ''
+    def main():
+        print("hi")
+  '';
+  plain = pkgs.writeText "notes" ''
+    not code
+  '';
+}
+"#;
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&tree_sitter_nix::LANGUAGE.into())
+            .unwrap();
+        let tree = parser.parse(code, None).unwrap();
+        let behavior = NixBehavior::new();
+
+        let regions = find_injections(tree.root_node(), code, behavior.injection_rules());
+        assert_eq!(regions.len(), 1);
+        let region = &regions[0];
+        assert_eq!(region.language, "python");
+        assert!(region.code.contains("def main():"));
+        assert_eq!((region.start_line, region.start_column), (2, 48));
+
+        // `def main` sits on the region's second line
+        let host = region.to_host(Range::new(1, 4, 2, 19));
+        assert_eq!(
+            (host.start_line, host.start_column, host.end_line),
+            (3, 4, 4)
+        );
+
+        assert_eq!(
+            delimited_spans(
+                "<p>x</p><script type=\"module\">run()</script>",
+                "<script",
+                "</script>"
+            ),
+            vec![(30, 35)]
+        );
+    }
+
+    #[test]
+    fn test_find_injections_of_languages_without_a_parser() {
+        use crate::parsing::{php::PhpBehavior, python::PythonBehavior};
+
+        let regions = |language: tree_sitter::Language, code: &str, rules: &[InjectionRule]| {
+            let mut parser = tree_sitter::Parser::new();
+            parser.set_language(&language).unwrap();
+            let tree = parser.parse(code, None).unwrap();
+            find_injections(tree.root_node(), code, rules)
+                .into_iter()
+                .map(|region| (region.language, region.code.trim().to_string()))
+                .collect::<Vec<_>>()
+        };
+
+        let python = "cursor.execute(\"SELECT id FROM users\")\nprint(\"SELECT\")\n";
+        assert_eq!(
+            regions(
+                tree_sitter_python::LANGUAGE.into(),
+                python,
+                PythonBehavior::new().injection_rules()
+            ),
+            [("sql", "SELECT id FROM users".to_string())]
+        );
+
+        let nix =
+            "{ pkgs }:\n{\n  hello = pkgs.writeShellScript \"hello\" ''\n    echo hi\n  '';\n}\n";
+        assert_eq!(
+            regions(
+                tree_sitter_nix::LANGUAGE.into(),
+                nix,
+                NixBehavior::new().injection_rules()
+            ),
+            [("bash", "echo hi".to_string())]
+        );
+
+        let php = "<ul><li><?php echo $name; ?></li></ul><script>go()</script>";
+        let found = regions(
+            tree_sitter_php::LANGUAGE_PHP.into(),
+            php,
+            PhpBehavior::new().injection_rules(),
+        );
+        assert!(found.contains(&("html", "<ul><li>".to_string())));
+        assert!(found.contains(&("javascript", "go()".to_string())));
+    }
+}
@@ -0,0 +1,97 @@
+//! Type instantiation sites
+//!
+//! `new Foo()`, a Python `Foo()` call and a Rust `Foo { .. }` literal all
+//! name a type, and most of them run its constructor. The parse stage turns
+//! each site into a References edge to the type and, when a constructor
+//! runs, a Calls edge to it that resolves only among the type's members.
+//! Generics named with type arguments are instantiations of another kind,
+//! recorded with the arguments they were given.
+
+use crate::Range;
+
+/// A type instantiated inside a function or method
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instantiation {
+    /// Enclosing function or method, named as its symbol is
+    pub context: String,
+    /// Range of the enclosing function or method
+    pub context_range: Range,
+    /// The instantiated type
+    pub type_name: String,
+    /// Constructor the instantiation runs, named as its symbol is
+    /// (`constructor`, `Foo.__init__`); `None` for literals that bypass one,
+    /// like Rust struct expressions
+    pub constructor: Option<String>,
+    /// Where the type is named
+    pub range: Range,
+}
+
+/// A generic type or function named with concrete type arguments, like
+/// `Map<string, Session>` or `parse::<Config>()`
+///
+/// The parse stage records it as a References edge to the generic whose
+/// `type_args` attribute holds the arguments, so a generic's references
+/// show what it is used with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GenericInstantiation {
+    /// Enclosing symbol, named as its symbol is
+    pub context: String,
+    /// Range of the enclosing symbol
+    pub context_range: Range,
+    /// The generic, without its path: `Map`, `parse`
+    pub generic: String,
+    /// Type arguments as written: `string`, `HashMap<K, V>`
+    pub type_arguments: Vec<String>,
+    /// Where the generic is named
+    pub range: Range,
+}
+
+/// Join type arguments into one relationship attribute value. Attribute
+/// entries are comma-separated, so every comma becomes a `;`.
+pub fn encode_type_arguments(arguments: &[String]) -> String {
+    arguments
+        .iter()
+        .map(|argument| argument.replace(", ", ";").replace(',', ";"))
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+/// Split a value made by [`encode_type_arguments`] back into arguments,
+/// with `, ` separating any nested ones.
+pub fn decode_type_arguments(value: &str) -> Vec<String> {
+    let mut arguments = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in value.char_indices() {
+        match c {
+            '<' | '(' | '[' | '{' => depth += 1,
+            '>' | ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            ';' if depth == 0 => {
+                arguments.push(value[start..i].replace(';', ", "));
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if start < value.len() {
+        arguments.push(value[start..].replace(';', ", "));
+    }
+    arguments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_type_arguments_round_trip() {
+        let arguments = vec!["HashMap<K, V>".to_string(), "(u8,u16)".to_string()];
+        let encoded = encode_type_arguments(&arguments);
+
+        assert!(!encoded.contains(','));
+        assert_eq!(
+            decode_type_arguments(&encoded),
+            vec!["HashMap<K, V>".to_string(), "(u8, u16)".to_string()]
+        );
+    }
+}
@@ -4,8 +4,10 @@
 //!
 //! TODO: Implement methods after exploring actual Java AST with tree-sitter.
 
+use crate::parsing::control_flow::BranchNodeKinds;
+use crate::parsing::references::ReferenceNodeKinds;
 use crate::parsing::{
-    Import, InheritanceResolver, LanguageBehavior, ResolutionScope,
+    Import, InheritanceResolver, LanguageBehavior, PipelineSymbolCache, ResolutionScope, TypeRef,
     behavior_state::{BehaviorState, StatefulBehavior},
     paths::strip_extension,
 };
@@ -39,6 +41,48 @@ impl StatefulBehavior for JavaBehavior {
     }
 }
 
+/// Reference pass node kinds. Method names are reported as calls, and field
+/// names cannot be resolved without the receiver, so both are skipped.
+static REFERENCE_NODES: ReferenceNodeKinds = ReferenceNodeKinds {
+    identifiers: &["identifier"],
+    bindings: &[
+        ("formal_parameter", Some("name")),
+        ("catch_formal_parameter", Some("name")),
+        ("variable_declarator", Some("name")),
+        ("enhanced_for_statement", Some("name")),
+        ("lambda_expression", Some("parameters")),
+        ("inferred_parameters", None),
+        ("resource", Some("name")),
+    ],
+    members: &[
+        ("field_access", Some("field")),
+        ("method_invocation", Some("name")),
+    ],
+    paths: &[],
+    fields: &[("field_access", "object", "field")],
+    containers: &[
+        ("class_declaration", "name"),
+        ("enum_declaration", "name"),
+        ("record_declaration", "name"),
+    ],
+    this: &["this"],
+};
+
+/// Conditional-region node kinds
+static BRANCH_NODES: BranchNodeKinds = BranchNodeKinds {
+    branches: &[
+        ("if_statement", &["consequence", "alternative"]),
+        ("switch_expression", &["body"]),
+        ("while_statement", &["body"]),
+        ("for_statement", &["body", "update"]),
+        ("enhanced_for_statement", &["body"]),
+        ("ternary_expression", &["consequence", "alternative"]),
+        ("catch_clause", &[]),
+        ("lambda_expression", &[]),
+    ],
+    short_circuit: &["&&", "||"],
+};
+
 impl LanguageBehavior for JavaBehavior {
     fn language_id(&self) -> crate::parsing::registry::LanguageId {
         crate::parsing::registry::LanguageId::new("java")
@@ -68,11 +112,11 @@ impl LanguageBehavior for JavaBehavior {
         if trimmed.contains("private") {
             Visibility::Private
         } else if trimmed.contains("protected") {
-            Visibility::Module // Protected in Java
+            Visibility::Protected
         } else if trimmed.contains("public") {
             Visibility::Public
         } else {
-            Visibility::Crate // Package-private (Java default when no modifier)
+            Visibility::Package // Java default when no modifier
         }
     }
 
@@ -104,6 +148,14 @@ impl LanguageBehavior for JavaBehavior {
         tree_sitter_java::LANGUAGE.into()
     }
 
+    fn reference_node_kinds(&self) -> Option<&'static ReferenceNodeKinds> {
+        Some(&REFERENCE_NODES)
+    }
+
+    fn branch_node_kinds(&self) -> Option<&'static BranchNodeKinds> {
+        Some(&BRANCH_NODES)
+    }
+
     /// Validate node kind (tree-sitter ABI compatibility)
     fn validate_node_kind(&self, _node_kind: &str) -> bool {
         true
@@ -341,6 +393,7 @@ impl LanguageBehavior for JavaBehavior {
                 alias: import.alias.clone(),
                 is_glob: import.is_glob,
                 is_type_only: import.is_type_only,
+                range: import.range,
             });
 
             // Look up candidates by class name and match computed module_path
@@ -637,13 +690,12 @@ impl JavaBehavior {
     ///
     /// Check if symbol is visible from another file (backward compatible)
     ///
-    /// Delegates to is_symbol_visible_from_context() without inheritance context.
+    /// Same rules as is_symbol_visible_from_context(), without inheritance context.
     /// For full inheritance-based protected checks, use is_symbol_visible_from_context().
     pub fn is_symbol_visible_from_file(&self, symbol: &Symbol, from_file: FileId) -> bool {
-        // Delegate to enhanced method without inheritance context
-        // This makes protected symbols permissive for cross-package access
-        let resolver = super::JavaInheritanceResolver::new();
-        self.is_symbol_visible_from_context(symbol, from_file, None, &resolver)
+        // No inheritance context: protected symbols are permissive for
+        // cross-package access
+        self.is_symbol_visible(symbol, from_file, None)
     }
 
     /// Check if a symbol and a file are in the same package
@@ -700,14 +752,35 @@ impl JavaBehavior {
     /// Parameters:
     /// - `symbol`: The symbol being accessed
     /// - `from_file`: The file attempting access
-    /// - `accessing_class`: Optional fully qualified class name doing the access
+    /// - `accessing_class`: Optional indexed class doing the access
+    /// - `symbols`: Indexed symbols, to find the class declaring `symbol`
     /// - `inheritance`: Inheritance resolver for subclass checks
     pub fn is_symbol_visible_from_context(
         &self,
         symbol: &Symbol,
         from_file: FileId,
-        accessing_class: Option<&str>,
+        accessing_class: Option<&TypeRef>,
+        symbols: &dyn PipelineSymbolCache,
         inheritance: &dyn InheritanceResolver,
+    ) -> bool {
+        match accessing_class {
+            Some(accessing) => {
+                let is_subclass_of = |containing: &str| {
+                    inheritance.is_subtype(accessing, &TypeRef::lookup(containing, symbols))
+                };
+                self.is_symbol_visible(symbol, from_file, Some(&is_subclass_of))
+            }
+            None => self.is_symbol_visible(symbol, from_file, None),
+        }
+    }
+
+    /// Visibility rules, with `is_subclass_of` answering whether the
+    /// accessing class extends the named containing class
+    fn is_symbol_visible(
+        &self,
+        symbol: &Symbol,
+        from_file: FileId,
+        is_subclass_of: Option<&dyn Fn(&str) -> bool>,
     ) -> bool {
         // Same file: always visible
         if symbol.file_id == from_file {
@@ -717,11 +790,12 @@ impl JavaBehavior {
         // Check visibility modifiers
         match symbol.visibility {
             Visibility::Private => false, // Private symbols are class-scoped only
-            Visibility::Crate => {
+            Visibility::Package | Visibility::Crate => {
                 // Package-private (Java default) - same package only
+                // Crate: package-private in indexes written before Package existed
                 self.is_same_package(symbol, from_file)
             }
-            Visibility::Module => {
+            Visibility::Protected | Visibility::Module => {
                 // Protected - accessible in same package + subclasses
 
                 // Same package: always grants access
@@ -730,9 +804,9 @@ impl JavaBehavior {
                 }
 
                 // Cross-package: check inheritance if context available
-                if let Some(accessing) = accessing_class {
+                if let Some(is_subclass_of) = is_subclass_of {
                     if let Some(containing) = self.get_containing_class(symbol) {
-                        return inheritance.is_subtype(accessing, &containing);
+                        return is_subclass_of(&containing);
                     }
                 }
 
@@ -750,6 +824,28 @@ mod tests {
     use super::*;
     use crate::types::{FileId, Range, SymbolId};
 
+    /// Indexed classes the visibility tests refer to
+    fn class_symbols() -> crate::indexing::pipeline::types::SymbolLookupCache {
+        let symbols = crate::indexing::pipeline::types::SymbolLookupCache::new();
+        for (id, package, name) in [
+            (10, "com.example", "Parent"),
+            (11, "com.example", "Child"),
+            (12, "com.other", "Child"),
+            (13, "com.other", "Unrelated"),
+        ] {
+            let mut class = Symbol::new(
+                crate::types::SymbolId(id),
+                name,
+                SymbolKind::Class,
+                crate::types::FileId(id),
+                crate::types::Range::new(0, 0, 1, 0),
+            );
+            class.module_path = Some(package.into());
+            symbols.insert(class);
+        }
+        symbols
+    }
+
     #[test]
     fn test_protected_visibility_same_package() {
         let behavior = JavaBehavior::new();
@@ -768,7 +864,7 @@ mod tests {
             },
         );
         symbol.module_path = Some("com.example".to_string().into());
-        symbol.visibility = Visibility::Module; // Protected
+        symbol.visibility = Visibility::Protected;
         symbol.scope_context = Some(ScopeContext::ClassMember {
             class_name: Some("Parent".to_string().into()),
         });
@@ -781,12 +877,14 @@ mod tests {
         );
 
         // Test: Same package access should succeed
+        let symbols = class_symbols();
         let resolver = super::super::JavaInheritanceResolver::new();
         assert!(
             behavior.is_symbol_visible_from_context(
                 &symbol,
                 FileId(2),
-                Some("com.example.Child"),
+                Some(&TypeRef::lookup("com.example.Child", &symbols)),
+                &symbols,
                 &resolver
             ),
             "Protected symbol should be visible in same package"
@@ -811,7 +909,7 @@ mod tests {
             },
         );
         symbol.module_path = Some("com.example".to_string().into());
-        symbol.visibility = Visibility::Module; // Protected
+        symbol.visibility = Visibility::Protected;
         symbol.scope_context = Some(ScopeContext::ClassMember {
             class_name: Some("Parent".to_string().into()),
         });
@@ -824,10 +922,11 @@ mod tests {
         );
 
         // Setup inheritance: Child extends Parent
+        let symbols = class_symbols();
         let mut resolver = super::super::JavaInheritanceResolver::new();
         resolver.add_inheritance(
-            "com.other.Child".to_string(),
-            "com.example.Parent".to_string(),
+            TypeRef::lookup("com.other.Child", &symbols),
+            TypeRef::lookup("com.example.Parent", &symbols),
             "extends",
         );
 
@@ -836,7 +935,8 @@ mod tests {
             behavior.is_symbol_visible_from_context(
                 &symbol,
                 FileId(2),
-                Some("com.other.Child"),
+                Some(&TypeRef::lookup("com.other.Child", &symbols)),
+                &symbols,
                 &resolver
             ),
             "Protected symbol should be visible to subclass in different package"
@@ -861,7 +961,7 @@ mod tests {
             },
         );
         symbol.module_path = Some("com.example".to_string().into());
-        symbol.visibility = Visibility::Module; // Protected
+        symbol.visibility = Visibility::Protected;
         symbol.scope_context = Some(ScopeContext::ClassMember {
             class_name: Some("Parent".to_string().into()),
         });
@@ -874,6 +974,7 @@ mod tests {
         );
 
         // No inheritance relationship
+        let symbols = class_symbols();
         let resolver = super::super::JavaInheritanceResolver::new();
 
         // Test: Cross-package access should FAIL when no inheritance exists
@@ -883,7 +984,8 @@ mod tests {
             !behavior.is_symbol_visible_from_context(
                 &symbol,
                 FileId(2),
-                Some("com.other.Unrelated"),
+                Some(&TypeRef::lookup("com.other.Unrelated", &symbols)),
+                &symbols,
                 &resolver
             ),
             "Protected symbol should NOT be visible to non-subclass in different package"
@@ -1043,10 +1145,10 @@ config_files = ["{}"]
             },
         );
         symbol2.signature = Some("protected void protectedMethod()".to_string().into());
-        symbol2.visibility = Visibility::Module; // Set by parser's determine_visibility()
+        symbol2.visibility = Visibility::Protected; // Set by parser's determine_visibility()
         behavior.configure_symbol(&mut symbol2, Some("com.example"));
 
-        assert_eq!(symbol2.visibility, Visibility::Module);
+        assert_eq!(symbol2.visibility, Visibility::Protected);
 
         // Test private visibility
         let mut symbol3 = Symbol::new(
@@ -1081,10 +1183,10 @@ config_files = ["{}"]
             },
         );
         symbol4.signature = Some("void packageMethod()".to_string().into());
-        symbol4.visibility = Visibility::Crate; // Set by parser's determine_visibility()
+        symbol4.visibility = Visibility::Package; // Set by parser's determine_visibility()
         behavior.configure_symbol(&mut symbol4, Some("com.example"));
 
-        assert_eq!(symbol4.visibility, Visibility::Crate); // Package-private
+        assert_eq!(symbol4.visibility, Visibility::Package);
     }
 
     #[test]
@@ -25,7 +25,7 @@ impl LanguageDefinition for JavaLanguage {
     }
 
     fn create_parser(&self, _settings: &Settings) -> IndexResult<Box<dyn LanguageParser>> {
-        let parser = JavaParser::new().map_err(|e| IndexError::General(e.to_string()))?;
+        let parser = JavaParser::new().map_err(|e| IndexError::parser_init("java", e))?;
         Ok(Box::new(parser))
     }
 
@@ -118,73 +118,38 @@ impl JavaParser {
     // =========================================================================
 
     /// Extract documentation comments (/** */ or //)
-    /// TODO: Implement after tree-sitter exploration
     fn doc_comment_for(&self, node: &Node, code: &str) -> Option<String> {
-        let mut result = String::new();
-        let mut has_comment = false;
-
-        // Stack to collect comments in reverse order (traverse backwards)
-        let mut comment_stack: [Option<&str>; 8] = [None; 8];
-        let mut stack_len = 0;
-        let mut current = node.prev_sibling();
-
-        // Special case: if previous sibling is package_declaration, check its children
-        if let Some(sibling) = current {
-            if sibling.kind() == NODE_PACKAGE_DECLARATION {
-                let mut cursor = sibling.walk();
-                for child in sibling.named_children(&mut cursor) {
-                    let child_kind = child.kind();
-                    if child_kind == NODE_BLOCK_COMMENT || child_kind == NODE_LINE_COMMENT {
-                        let raw = self.text_for_node(code, child);
-                        if let Some(cleaned) = self.extract_comment_text(raw) {
-                            if has_comment {
-                                result.push('\n');
-                            }
-                            result.push_str(cleaned);
-                            has_comment = true;
-                        }
-                    }
-                }
-                if has_comment {
-                    return Some(result);
-                }
-            }
+        // Comments right after `package x;` are parsed inside the declaration
+        let mut comments = Vec::new();
+        if let Some(package) = node
+            .prev_sibling()
+            .filter(|sibling| sibling.kind() == NODE_PACKAGE_DECLARATION)
+        {
+            let mut cursor = package.walk();
+            comments.extend(
+                package
+                    .named_children(&mut cursor)
+                    .filter(|child| {
+                        child.kind() == NODE_BLOCK_COMMENT || child.kind() == NODE_LINE_COMMENT
+                    })
+                    .map(|child| self.text_for_node(code, child)),
+            );
         }
-
-        // Standard case: traverse backwards collecting comments
-        current = node.prev_sibling();
-        while let Some(sibling) = current {
-            let sibling_kind = sibling.kind();
-            if sibling_kind != NODE_BLOCK_COMMENT && sibling_kind != NODE_LINE_COMMENT {
-                break;
-            }
-
-            let raw = self.text_for_node(code, sibling);
-            if stack_len < comment_stack.len() {
-                if let Some(cleaned) = self.extract_comment_text(raw) {
-                    comment_stack[stack_len] = Some(cleaned);
-                    stack_len += 1;
-                    current = sibling.prev_sibling();
-                    continue;
-                }
-            }
-            break;
+        if comments.is_empty() {
+            comments = self.preceding_comments(node, code, &|text| {
+                self.extract_comment_text(text).is_some()
+            });
         }
 
-        // Build result from stack in reverse order
-        if stack_len > 0 {
-            for i in (0..stack_len).rev() {
-                if let Some(comment) = comment_stack[i] {
-                    if !result.is_empty() {
-                        result.push('\n');
-                    }
-                    result.push_str(comment);
-                    has_comment = true;
-                }
-            }
+        let cleaned: Vec<&str> = comments
+            .into_iter()
+            .filter_map(|raw| self.extract_comment_text(raw))
+            .collect();
+        if cleaned.is_empty() {
+            None
+        } else {
+            Some(cleaned.join("\n"))
         }
-
-        if has_comment { Some(result) } else { None }
     }
 
     /// Extract text from comment, removing delimiters
@@ -233,16 +198,16 @@ impl JavaParser {
                 if modifiers_text.contains("private") {
                     return Visibility::Private;
                 } else if modifiers_text.contains("protected") {
-                    return Visibility::Module; // Protected in Java
+                    return Visibility::Protected;
                 } else if modifiers_text.contains("public") {
                     return Visibility::Public;
                 }
                 // If modifiers exist but no visibility keyword, it's package-private
-                return Visibility::Crate;
+                return Visibility::Package;
             }
         }
         // No modifiers node means package-private (default in Java)
-        Visibility::Crate
+        Visibility::Package
     }
 
     // =========================================================================
@@ -823,6 +788,7 @@ impl JavaParser {
                     alias: None,
                     is_glob,
                     is_type_only: false,
+                    range: Some(Import::range_of(&node)),
                 });
             }
         }
@@ -897,6 +863,7 @@ impl JavaParser {
                         is_static: false, // TODO: detect static calls (Type.method vs instance.method)
                         range,
                         caller_range: None, // TODO: track caller definition range
+                        category: None,
                     });
                 }
             }
@@ -7,7 +7,7 @@
 //! TODO: Implement methods after exploring actual Java AST with tree-sitter.
 
 use crate::parsing::{
-    InheritanceResolver, ResolutionScope, ScopeLevel, ScopeType, resolution::ImportBinding,
+    NameKeyedInheritanceResolver, ResolutionScope, ScopeLevel, ScopeType, resolution::ImportBinding,
 };
 use crate::{FileId, RelationKind, SymbolId};
 use std::any::Any;
@@ -538,7 +538,7 @@ impl Default for JavaInheritanceResolver {
     }
 }
 
-impl InheritanceResolver for JavaInheritanceResolver {
+impl NameKeyedInheritanceResolver for JavaInheritanceResolver {
     /// Add inheritance relationship (extends or implements)
     fn add_inheritance(&mut self, child: String, parent: String, _kind: &str) {
         self.parents.entry(child).or_default().push(parent);
@@ -595,6 +595,7 @@ mod tests {
                 file_id: FileId(1),
                 is_glob: false,
                 is_type_only: false,
+                range: None,
             },
             Import {
                 path: "com.example.utils.Helper".to_string(),
@@ -602,6 +603,7 @@ mod tests {
                 file_id: FileId(1),
                 is_glob: false,
                 is_type_only: false,
+                range: None,
             },
         ];
 
@@ -627,6 +629,7 @@ mod tests {
             file_id: FileId(1),
             is_glob: false,
             is_type_only: false,
+            range: None,
         }];
 
         ctx.populate_imports(&imports);
@@ -652,6 +655,7 @@ mod tests {
             file_id: FileId(1),
             is_glob: true,
             is_type_only: false,
+            range: None,
         }];
 
         ctx.populate_imports(&imports);
@@ -673,6 +677,7 @@ mod tests {
                 file_id: FileId(1),
                 is_glob: false,
                 is_type_only: false,
+                range: None,
             },
             Import {
                 path: "java.util.ArrayList".to_string(),
@@ -680,6 +685,7 @@ mod tests {
                 file_id: FileId(1),
                 is_glob: false,
                 is_type_only: false,
+                range: None,
             },
             Import {
                 path: "java.util.*".to_string(),
@@ -687,6 +693,7 @@ mod tests {
                 file_id: FileId(1),
                 is_glob: true,
                 is_type_only: false,
+                range: None,
             },
         ];
 
@@ -2,7 +2,9 @@
 
 use crate::parsing::LanguageBehavior;
 use crate::parsing::behavior_state::{BehaviorState, StatefulBehavior};
+use crate::parsing::control_flow::BranchNodeKinds;
 use crate::parsing::paths::strip_extension;
+use crate::parsing::references::ReferenceNodeKinds;
 use crate::parsing::resolution::{InheritanceResolver, ResolutionScope};
 use crate::project_resolver::persist::ResolutionPersistence;
 use crate::types::FileId;
@@ -93,6 +95,41 @@ impl StatefulBehavior for JavaScriptBehavior {
     }
 }
 
+/// Reference pass node kinds. Property names are `property_identifier`
+/// nodes, so member accesses need no exclusions.
+static REFERENCE_NODES: ReferenceNodeKinds = ReferenceNodeKinds {
+    identifiers: &["identifier"],
+    bindings: &[
+        ("formal_parameters", None),
+        ("assignment_pattern", Some("left")),
+        ("variable_declarator", Some("name")),
+        ("arrow_function", Some("parameter")),
+        ("catch_clause", Some("parameter")),
+        ("for_in_statement", Some("left")),
+    ],
+    members: &[],
+    paths: &[],
+    fields: &[("member_expression", "object", "property")],
+    containers: &[("class_declaration", "name"), ("class", "name")],
+    this: &["this"],
+};
+
+/// Conditional-region node kinds, the same shapes as TypeScript
+static BRANCH_NODES: BranchNodeKinds = BranchNodeKinds {
+    branches: &[
+        ("if_statement", &["consequence", "alternative"]),
+        ("switch_statement", &["body"]),
+        ("while_statement", &["body"]),
+        ("for_statement", &["body", "increment"]),
+        ("for_in_statement", &["body"]),
+        ("ternary_expression", &["consequence", "alternative"]),
+        ("catch_clause", &[]),
+        ("arrow_function", &[]),
+        ("function_expression", &[]),
+    ],
+    short_circuit: &["&&", "||", "??"],
+};
+
 impl LanguageBehavior for JavaScriptBehavior {
     fn language_id(&self) -> crate::parsing::registry::LanguageId {
         crate::parsing::registry::LanguageId::new("javascript")
@@ -116,6 +153,14 @@ impl LanguageBehavior for JavaScriptBehavior {
         tree_sitter_javascript::LANGUAGE.into()
     }
 
+    fn reference_node_kinds(&self) -> Option<&'static ReferenceNodeKinds> {
+        Some(&REFERENCE_NODES)
+    }
+
+    fn branch_node_kinds(&self) -> Option<&'static BranchNodeKinds> {
+        Some(&BRANCH_NODES)
+    }
+
     fn module_separator(&self) -> &'static str {
         "."
     }
@@ -360,6 +405,7 @@ impl LanguageBehavior for JavaScriptBehavior {
                 alias: import.alias.clone(),
                 is_glob: import.is_glob,
                 is_type_only: import.is_type_only,
+                range: import.range,
             });
 
             // Look up candidates by local_name and match computed module_path
@@ -452,6 +498,18 @@ impl LanguageBehavior for JavaScriptBehavior {
         }
     }
 
+    // Only module-level declarations carry `export`; class members are
+    // public by default but are reached through their class
+    fn is_exported(&self, symbol: &crate::Symbol) -> bool {
+        use crate::symbol::ScopeContext;
+
+        symbol.visibility == Visibility::Public
+            && matches!(
+                symbol.scope_context,
+                None | Some(ScopeContext::Module | ScopeContext::Global | ScopeContext::Package)
+            )
+    }
+
     fn get_module_path_for_file(&self, file_id: FileId) -> Option<String> {
         // Use the BehaviorState to get module path (O(1) lookup)
         self.state.get_module_path(file_id)
@@ -24,8 +24,13 @@ impl LanguageDefinition for JavaScriptLanguage {
         &["js", "jsx", "mjs", "cjs"]
     }
 
+    fn interpreters(&self) -> &'static [&'static str] {
+        &["node", "nodejs"]
+    }
+
     fn create_parser(&self, _settings: &Settings) -> IndexResult<Box<dyn LanguageParser>> {
-        let parser = JavaScriptParser::new().map_err(|e| IndexError::General(e.to_string()))?;
+        let parser =
+            JavaScriptParser::new().map_err(|e| IndexError::parser_init("javascript", e))?;
         Ok(Box::new(parser))
     }
 
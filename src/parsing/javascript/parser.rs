@@ -9,7 +9,8 @@
 use crate::parsing::Import;
 use crate::parsing::parser::check_recursion_depth;
 use crate::parsing::{
-    LanguageParser, MethodCall, NodeTracker, NodeTrackingState, ParserContext, ScopeType,
+    Instantiation, LanguageParser, MethodCall, NodeTracker, NodeTrackingState, ParserContext,
+    ScopeType,
 };
 use crate::types::SymbolCounter;
 use crate::{FileId, Range, Symbol, SymbolKind, Visibility};
@@ -649,6 +650,8 @@ impl JavaScriptParser {
                                 Some(crate::symbol::ScopeContext::Local {
                                     parent_name,
                                     parent_kind,
+                                    depth,
+                                    ancestors,
                                     ..
                                 }) => {
                                     symbol.scope_context =
@@ -656,6 +659,8 @@ impl JavaScriptParser {
                                             hoisted: false, // Arrow functions are never hoisted
                                             parent_name,    // Keep the parent context
                                             parent_kind,    // Keep the parent kind
+                                            depth,
+                                            ancestors,
                                         });
                                 }
                                 _ => {
@@ -675,6 +680,8 @@ impl JavaScriptParser {
                                             hoisted: false,
                                             parent_name,
                                             parent_kind,
+                                            depth: self.context.scope_depth(),
+                                            ancestors: self.context.ancestor_kinds(),
                                         });
                                 }
                             }
@@ -1032,6 +1039,7 @@ impl JavaScriptParser {
                                     file_id,
                                     is_glob: false,
                                     is_type_only: false, // JavaScript doesn't have type-only imports
+                                    range: Some(Import::range_of(&node)),
                                 });
                             }
                         }
@@ -1065,6 +1073,7 @@ impl JavaScriptParser {
                     file_id,
                     is_glob: true,
                     is_type_only: false,
+                    range: Some(Import::range_of(&node)),
                 });
             } else if has_default && has_named {
                 // Mixed import: import React, { Component } from 'react'
@@ -1075,6 +1084,7 @@ impl JavaScriptParser {
                     file_id,
                     is_glob: false,
                     is_type_only: false,
+                    range: Some(Import::range_of(&node)),
                 });
             } else if has_default {
                 // Default only: import React from 'react'
@@ -1087,6 +1097,7 @@ impl JavaScriptParser {
                     file_id,
                     is_glob: false,
                     is_type_only: false,
+                    range: Some(Import::range_of(&node)),
                 });
             } else if has_named {
                 // Named-only already pushed per specifier above
@@ -1099,6 +1110,7 @@ impl JavaScriptParser {
                 file_id,
                 is_glob: false,
                 is_type_only: false,
+                range: Some(Import::range_of(&node)),
             });
         }
     }
@@ -1130,6 +1142,7 @@ impl JavaScriptParser {
                 file_id,
                 is_glob: true,
                 is_type_only: false,
+                range: Some(Import::range_of(&node)),
             });
         } else {
             // Named re-exports - just track the module being imported from
@@ -1139,6 +1152,7 @@ impl JavaScriptParser {
                 file_id,
                 is_glob: false,
                 is_type_only: false,
+                range: Some(Import::range_of(&node)),
             });
         }
     }
@@ -1481,6 +1495,7 @@ impl JavaScriptParser {
                                 is_static,
                                 range,
                                 caller_range: None, // TODO: track caller definition range
+                                category: None,
                             };
 
                             calls.push(method_call);
@@ -1690,39 +1705,27 @@ impl LanguageParser for JavaScriptParser {
 
         // First, check if this node is inside an export_statement
         // If so, we need to check the export_statement's previous sibling for the comment
-        let comment_node = if let Some(parent) = node.parent() {
-            if parent.kind() == "export_statement" {
-                // For exported functions, check the export statement's previous sibling
-                parent.prev_sibling()
-            } else {
-                // For non-exported functions, check the node's previous sibling
-                node.prev_sibling()
-            }
-        } else {
-            // No parent, check the node's previous sibling
-            node.prev_sibling()
+        let anchor = match node.parent() {
+            // For exported functions, check the export statement's previous sibling
+            Some(parent) if parent.kind() == "export_statement" => parent,
+            _ => *node,
         };
 
-        if let Some(prev) = comment_node {
-            if prev.kind() == "comment" {
-                let comment = &code[prev.byte_range()];
-                if comment.starts_with("/**") {
-                    // Clean up the comment
-                    let cleaned = comment
-                        .trim_start_matches("/**")
-                        .trim_end_matches("*/")
-                        .lines()
-                        .map(|line| line.trim_start_matches(" * ").trim_start_matches(" *"))
-                        .collect::<Vec<_>>()
-                        .join("\n")
-                        .trim()
-                        .to_string();
-
-                    return Some(cleaned);
-                }
-            }
-        }
-        None
+        let comments = self.preceding_comments(&anchor, code, &|text| text.starts_with("/**"));
+        let comment = comments.last()?;
+
+        // Clean up the comment
+        let cleaned = comment
+            .trim_start_matches("/**")
+            .trim_end_matches("*/")
+            .lines()
+            .map(|line| line.trim_start_matches(" * ").trim_start_matches(" *"))
+            .collect::<Vec<_>>()
+            .join("\n")
+            .trim()
+            .to_string();
+
+        Some(cleaned)
     }
 
     fn find_calls<'a>(&mut self, code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
@@ -1754,6 +1757,15 @@ impl LanguageParser for JavaScriptParser {
         method_calls
     }
 
+    fn find_instantiations(&mut self, code: &str) -> Vec<Instantiation> {
+        let Some(tree) = self.parser.parse(code, None) else {
+            return Vec::new();
+        };
+        let mut instantiations = Vec::new();
+        find_instantiations_in_node(tree.root_node(), code, None, &mut instantiations, 0);
+        instantiations
+    }
+
     fn find_implementations<'a>(&mut self, code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
         let mut implementations = Vec::new();
 
@@ -1885,6 +1897,65 @@ impl LanguageParser for JavaScriptParser {
     }
 }
 
+/// `new Foo()` and `new ns.Foo()` inside named functions, including arrow
+/// functions bound to a name; the class's `constructor` method runs
+fn find_instantiations_in_node<'a>(
+    node: Node,
+    code: &'a str,
+    context: Option<(&'a str, Range)>,
+    instantiations: &mut Vec<Instantiation>,
+    depth: usize,
+) {
+    if !check_recursion_depth(depth, node) {
+        return;
+    }
+    let name = match node.kind() {
+        "function_declaration" | "generator_function_declaration" | "method_definition" => {
+            node.child_by_field_name("name")
+        }
+        "variable_declarator" => node
+            .child_by_field_name("value")
+            .filter(|value| matches!(value.kind(), "arrow_function" | "function_expression"))
+            .and_then(|_| node.child_by_field_name("name")),
+        _ => None,
+    };
+    let context = name
+        .map(|name| (&code[name.byte_range()], node_range(node)))
+        .or(context);
+
+    if node.kind() == "new_expression" {
+        let class = node
+            .child_by_field_name("constructor")
+            .and_then(|class| match class.kind() {
+                "identifier" => Some(class),
+                "member_expression" => class.child_by_field_name("property"),
+                _ => None,
+            });
+        if let (Some((context, context_range)), Some(class)) = (context, class) {
+            instantiations.push(Instantiation {
+                context: context.to_string(),
+                context_range,
+                type_name: code[class.byte_range()].to_string(),
+                constructor: Some("constructor".to_string()),
+                range: node_range(class),
+            });
+        }
+    }
+
+    for child in node.children(&mut node.walk()) {
+        find_instantiations_in_node(child, code, context, instantiations, depth + 1);
+    }
+}
+
+fn node_range(node: Node) -> Range {
+    Range::new(
+        node.start_position().row as u32,
+        node.start_position().column as u16,
+        node.end_position().row as u32,
+        node.end_position().column as u16,
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1998,6 +2069,30 @@ export * from './common';
         );
     }
 
+    #[test]
+    fn test_javascript_find_instantiations() {
+        let mut parser = JavaScriptParser::new().unwrap();
+        let code = r#"
+            const connect = (url) => new Client(new URL(url));
+            function start() {
+                return new sdk.ChatSDK();
+            }
+        "#;
+        let sites: Vec<(String, String)> = parser
+            .find_instantiations(code)
+            .into_iter()
+            .map(|site| (site.context, site.type_name))
+            .collect();
+        assert_eq!(
+            sites,
+            [
+                ("connect".to_string(), "Client".to_string()),
+                ("connect".to_string(), "URL".to_string()),
+                ("start".to_string(), "ChatSDK".to_string()),
+            ]
+        );
+    }
+
     #[test]
     fn test_javascript_find_method_calls_extraction() {
         let mut parser = JavaScriptParser::new().unwrap();
@@ -2179,4 +2274,29 @@ var myVar = true;
 
         println!("✅ const/let/var extraction working");
     }
+
+    #[test]
+    fn test_doc_comment_nearest_and_own_line() {
+        let mut parser = JavaScriptParser::new().unwrap();
+        let code = r#"
+/** Not the doc */
+/** Exported helper */
+export function exported() {}
+
+const limit = 10; /** trailing note */
+function local() {}
+"#;
+
+        let mut counter = SymbolCounter::new();
+        let symbols = parser.parse(code, FileId::new(1).unwrap(), &mut counter);
+        let doc = |name: &str| {
+            symbols
+                .iter()
+                .find(|s| s.name.as_ref() == name)
+                .and_then(|s| s.doc_comment.as_deref())
+        };
+
+        assert_eq!(doc("exported"), Some("Exported helper"));
+        assert_eq!(doc("local"), None, "trailing comment belongs to limit");
+    }
 }
@@ -13,7 +13,7 @@
 //! - Abstract classes
 
 use crate::parsing::resolution::ImportBinding;
-use crate::parsing::{InheritanceResolver, ResolutionScope, ScopeLevel, ScopeType};
+use crate::parsing::{NameKeyedInheritanceResolver, ResolutionScope, ScopeLevel, ScopeType};
 use crate::{FileId, SymbolId};
 use std::collections::HashMap;
 
@@ -432,6 +432,8 @@ impl ResolutionScope for JavaScriptResolutionContext {
             }
             References => true,
             ReferencedBy => true,
+            Aliases | AliasedBy => true,
+            Throws | ThrownBy => true,
             // JavaScript doesn't support Implements/ImplementedBy (no interfaces)
             Implements | ImplementedBy => false,
         }
@@ -481,7 +483,7 @@ impl JavaScriptInheritanceResolver {
     }
 }
 
-impl InheritanceResolver for JavaScriptInheritanceResolver {
+impl NameKeyedInheritanceResolver for JavaScriptInheritanceResolver {
     fn add_inheritance(&mut self, child: String, parent: String, kind: &str) {
         match kind {
             "extends" => {
@@ -0,0 +1,155 @@
+//! User overrides of symbol kinds
+//!
+//! Parsers classify symbols by syntax alone, which misses conventions only
+//! the project knows: a flake's `packages.*` bindings act as modules, and
+//! an UPPER_CASE assignment is a constant to its authors whatever scope it
+//! sits in. `[[kinds]]` rules in settings (see [`KindRule`]) reclassify
+//! such symbols right after parsing, before relationships are built or
+//! anything is stored.
+
+use crate::config::KindRule;
+use crate::parsing::LanguageId;
+use crate::{Symbol, SymbolKind};
+use regex::Regex;
+
+#[derive(Debug)]
+struct CompiledRule {
+    language: String,
+    name: Regex,
+    from: Option<SymbolKind>,
+    kind: SymbolKind,
+}
+
+/// Compiled `[[kinds]]` rules
+#[derive(Debug, Default)]
+pub struct KindOverrides {
+    rules: Vec<CompiledRule>,
+}
+
+impl KindOverrides {
+    /// Compile `rules`. A rule whose name pattern isn't a valid regular
+    /// expression is skipped with a warning rather than failing the index.
+    pub fn new(rules: &[KindRule]) -> Self {
+        let rules = rules
+            .iter()
+            .filter_map(|rule| match Regex::new(&rule.name) {
+                Ok(name) => Some(CompiledRule {
+                    language: rule.language.clone(),
+                    name,
+                    from: rule.from,
+                    kind: rule.kind,
+                }),
+                Err(e) => {
+                    tracing::warn!(
+                        "[config] ignoring kind rule for {}: invalid name pattern '{}': {e}",
+                        rule.language,
+                        rule.name
+                    );
+                    None
+                }
+            })
+            .collect();
+        Self { rules }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Kind the first matching rule gives a `language` symbol named `name`
+    /// that the parser classified as `kind`
+    pub fn kind_for(
+        &self,
+        language: LanguageId,
+        name: &str,
+        kind: SymbolKind,
+    ) -> Option<SymbolKind> {
+        self.rules
+            .iter()
+            .find(|rule| {
+                rule.language == language.as_str()
+                    && rule.from.is_none_or(|from| from == kind)
+                    && rule.name.is_match(name)
+            })
+            .map(|rule| rule.kind)
+    }
+
+    /// Reclassify the matching symbols of a `language` file
+    pub fn apply(&self, language: LanguageId, symbols: &mut [Symbol]) {
+        if self.is_empty() {
+            return;
+        }
+        for symbol in symbols {
+            if let Some(kind) = self.kind_for(language, &symbol.name, symbol.kind) {
+                symbol.kind = kind;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(language: &str, name: &str, from: Option<SymbolKind>, kind: SymbolKind) -> KindRule {
+        KindRule {
+            language: language.to_string(),
+            name: name.to_string(),
+            from,
+            kind,
+        }
+    }
+
+    #[test]
+    fn test_first_matching_rule_wins() {
+        let overrides = KindOverrides::new(&[
+            rule("nix", r"^packages\.", None, SymbolKind::Module),
+            rule("nix", r"^packages\.", None, SymbolKind::Constant),
+        ]);
+        let nix = LanguageId::new("nix");
+
+        assert_eq!(
+            overrides.kind_for(nix, "packages.x86_64-linux.default", SymbolKind::Variable),
+            Some(SymbolKind::Module)
+        );
+        assert_eq!(
+            overrides.kind_for(nix, "devShells.default", SymbolKind::Variable),
+            None
+        );
+        assert_eq!(
+            overrides.kind_for(
+                LanguageId::new("python"),
+                "packages.default",
+                SymbolKind::Variable
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_from_restricts_the_original_kind() {
+        let overrides = KindOverrides::new(&[rule(
+            "python",
+            "^[A-Z][A-Z0-9_]*$",
+            Some(SymbolKind::Variable),
+            SymbolKind::Constant,
+        )]);
+        let python = LanguageId::new("python");
+
+        assert_eq!(
+            overrides.kind_for(python, "MAX_RETRIES", SymbolKind::Variable),
+            Some(SymbolKind::Constant)
+        );
+        assert_eq!(overrides.kind_for(python, "HTTP", SymbolKind::Class), None);
+        assert_eq!(
+            overrides.kind_for(python, "max_retries", SymbolKind::Variable),
+            None
+        );
+    }
+
+    #[test]
+    fn test_invalid_pattern_is_skipped() {
+        let overrides = KindOverrides::new(&[rule("rust", "(", None, SymbolKind::Module)]);
+        assert!(overrides.is_empty());
+    }
+}
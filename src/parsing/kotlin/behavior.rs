@@ -4,7 +4,8 @@ use crate::parsing::LanguageBehavior;
 use crate::parsing::ResolutionScope;
 use crate::parsing::behavior_state::{BehaviorState, StatefulBehavior};
 use crate::parsing::paths::strip_extension;
-use crate::parsing::{Import, InheritanceResolver};
+use crate::parsing::references::ReferenceNodeKinds;
+use crate::parsing::{Import, InheritanceResolver, PipelineSymbolCache, TypeRef};
 use crate::symbol::ScopeContext;
 use crate::types::compact_string;
 use crate::{FileId, Symbol, SymbolKind, Visibility};
@@ -63,12 +64,45 @@ impl KotlinBehavior {
     /// - internal: module-scoped (mapped to Crate)
     /// - protected: subclasses only (mapped to Module)
     /// - public: everywhere
+    ///
+    /// `accessing_class` is the indexed class doing the access; the class
+    /// declaring `symbol` is looked up in `symbols` so both sides of the
+    /// subtype check carry their indexed ids.
     pub fn is_symbol_visible_from_context(
         &self,
         symbol: &Symbol,
         from_file: FileId,
-        accessing_class: Option<&str>,
+        accessing_class: Option<&TypeRef>,
+        symbols: &dyn PipelineSymbolCache,
         inheritance: &dyn InheritanceResolver,
+    ) -> bool {
+        match accessing_class {
+            Some(accessing) => {
+                let is_subclass_of = |containing: &str| {
+                    inheritance.is_subtype(accessing, &TypeRef::lookup(containing, symbols))
+                };
+                self.is_symbol_visible(symbol, from_file, Some(&is_subclass_of))
+            }
+            None => self.is_symbol_visible(symbol, from_file, None),
+        }
+    }
+
+    /// Check if symbol is visible from another file (backward compatible)
+    ///
+    /// Same rules as is_symbol_visible_from_context(), without inheritance context.
+    /// For full inheritance-based protected checks, use is_symbol_visible_from_context().
+    pub fn is_symbol_visible_from_file(&self, symbol: &Symbol, from_file: FileId) -> bool {
+        // No inheritance context: protected symbols are permissive
+        self.is_symbol_visible(symbol, from_file, None)
+    }
+
+    /// Visibility rules, with `is_subclass_of` answering whether the
+    /// accessing class extends the named containing class
+    fn is_symbol_visible(
+        &self,
+        symbol: &Symbol,
+        from_file: FileId,
+        is_subclass_of: Option<&dyn Fn(&str) -> bool>,
     ) -> bool {
         // Same file: always visible
         if symbol.file_id == from_file {
@@ -78,17 +112,18 @@ impl KotlinBehavior {
         // Check visibility modifiers
         match symbol.visibility {
             Visibility::Private => false, // Private symbols are file-scoped
-            Visibility::Crate => {
+            Visibility::Crate | Visibility::Package => {
                 // Kotlin internal - module-scoped
                 // For now, be permissive (would need module boundary tracking)
                 true
             }
-            Visibility::Module => {
+            Visibility::Protected | Visibility::Module => {
                 // Kotlin protected - accessible to subclasses
+                // Module: protected in indexes written before Protected existed
                 // Check inheritance if context available
-                if let Some(accessing) = accessing_class {
+                if let Some(is_subclass_of) = is_subclass_of {
                     if let Some(containing) = self.get_containing_class(symbol) {
-                        return inheritance.is_subtype(accessing, &containing);
+                        return is_subclass_of(&containing);
                     }
                 }
 
@@ -98,16 +133,6 @@ impl KotlinBehavior {
             Visibility::Public => true, // Public symbols visible everywhere
         }
     }
-
-    /// Check if symbol is visible from another file (backward compatible)
-    ///
-    /// Delegates to is_symbol_visible_from_context() without inheritance context.
-    /// For full inheritance-based protected checks, use is_symbol_visible_from_context().
-    pub fn is_symbol_visible_from_file(&self, symbol: &Symbol, from_file: FileId) -> bool {
-        // Delegate to enhanced method without inheritance context
-        let resolver = super::KotlinInheritanceResolver::new();
-        self.is_symbol_visible_from_context(symbol, from_file, None, &resolver)
-    }
 }
 
 impl StatefulBehavior for KotlinBehavior {
@@ -122,6 +147,22 @@ impl Default for KotlinBehavior {
     }
 }
 
+/// Reference pass node kinds. The Kotlin grammar rarely uses fields, so
+/// parameters and declarations bind their direct identifiers.
+static REFERENCE_NODES: ReferenceNodeKinds = ReferenceNodeKinds {
+    identifiers: &["simple_identifier"],
+    bindings: &[
+        ("parameter", None),
+        ("class_parameter", None),
+        ("variable_declaration", None),
+    ],
+    members: &[("navigation_suffix", None)],
+    paths: &[],
+    fields: &[],
+    containers: &[],
+    this: &[],
+};
+
 impl LanguageBehavior for KotlinBehavior {
     fn language_id(&self) -> crate::parsing::registry::LanguageId {
         crate::parsing::registry::LanguageId::new("kotlin")
@@ -175,9 +216,9 @@ impl LanguageBehavior for KotlinBehavior {
         if trimmed.contains("private") {
             Visibility::Private
         } else if trimmed.contains("protected") {
-            Visibility::Module // Map protected to module-level
+            Visibility::Protected
         } else if trimmed.contains("internal") {
-            Visibility::Crate // Map internal to crate-level
+            Visibility::Crate // Kotlin internal is module-wide, like a crate
         } else {
             Visibility::Public // Kotlin default
         }
@@ -226,6 +267,10 @@ impl LanguageBehavior for KotlinBehavior {
         self.language.clone()
     }
 
+    fn reference_node_kinds(&self) -> Option<&'static ReferenceNodeKinds> {
+        Some(&REFERENCE_NODES)
+    }
+
     fn supports_traits(&self) -> bool {
         true // Kotlin has interfaces
     }
@@ -344,6 +389,28 @@ impl LanguageBehavior for KotlinBehavior {
 mod tests {
     use super::*;
 
+    /// Indexed classes the visibility tests refer to
+    fn class_symbols() -> crate::indexing::pipeline::types::SymbolLookupCache {
+        let symbols = crate::indexing::pipeline::types::SymbolLookupCache::new();
+        for (id, package, name) in [
+            (10, "com.example", "Parent"),
+            (11, "com.example", "Child"),
+            (12, "com.other", "Child"),
+            (13, "com.other", "Unrelated"),
+        ] {
+            let mut class = Symbol::new(
+                crate::types::SymbolId(id),
+                name,
+                SymbolKind::Class,
+                crate::types::FileId(id),
+                crate::types::Range::new(0, 0, 1, 0),
+            );
+            class.module_path = Some(package.into());
+            symbols.insert(class);
+        }
+        symbols
+    }
+
     #[test]
     fn test_parse_visibility() {
         let behavior = KotlinBehavior::new();
@@ -354,7 +421,7 @@ mod tests {
         );
         assert_eq!(
             behavior.parse_visibility("protected fun test()"),
-            Visibility::Module
+            Visibility::Protected
         );
         assert_eq!(
             behavior.parse_visibility("internal fun test()"),
@@ -431,16 +498,17 @@ mod tests {
             },
         );
         symbol.module_path = Some("com.example".to_string().into());
-        symbol.visibility = Visibility::Module; // Protected
+        symbol.visibility = Visibility::Protected;
         symbol.scope_context = Some(ScopeContext::ClassMember {
             class_name: Some("Parent".to_string().into()),
         });
 
         // Setup inheritance: Child extends Parent
+        let symbols = class_symbols();
         let mut resolver = super::super::KotlinInheritanceResolver::new();
         resolver.add_inheritance(
-            "com.other.Child".to_string(),
-            "com.example.Parent".to_string(),
+            TypeRef::lookup("com.other.Child", &symbols),
+            TypeRef::lookup("com.example.Parent", &symbols),
             "extends",
         );
 
@@ -449,7 +517,8 @@ mod tests {
             behavior.is_symbol_visible_from_context(
                 &symbol,
                 FileId(2),
-                Some("com.other.Child"),
+                Some(&TypeRef::lookup("com.other.Child", &symbols)),
+                &symbols,
                 &resolver
             ),
             "Protected symbol should be visible to subclass"
@@ -476,12 +545,13 @@ mod tests {
             },
         );
         symbol.module_path = Some("com.example".to_string().into());
-        symbol.visibility = Visibility::Module; // Protected
+        symbol.visibility = Visibility::Protected;
         symbol.scope_context = Some(ScopeContext::ClassMember {
             class_name: Some("Parent".to_string().into()),
         });
 
         // No inheritance relationship
+        let symbols = class_symbols();
         let resolver = super::super::KotlinInheritanceResolver::new();
 
         // Test: Should be denied when no inheritance
@@ -489,7 +559,8 @@ mod tests {
             !behavior.is_symbol_visible_from_context(
                 &symbol,
                 FileId(2),
-                Some("com.other.Unrelated"),
+                Some(&TypeRef::lookup("com.other.Unrelated", &symbols)),
+                &symbols,
                 &resolver
             ),
             "Protected symbol should NOT be visible to non-subclass"
@@ -30,8 +30,12 @@ impl LanguageDefinition for KotlinLanguage {
         &["kt", "kts"]
     }
 
+    fn interpreters(&self) -> &'static [&'static str] {
+        &["kotlin", "kscript"]
+    }
+
     fn create_parser(&self, _settings: &Settings) -> IndexResult<Box<dyn LanguageParser>> {
-        let parser = KotlinParser::new().map_err(IndexError::General)?;
+        let parser = KotlinParser::new().map_err(|e| IndexError::parser_init("kotlin", e))?;
         Ok(Box::new(parser))
     }
 
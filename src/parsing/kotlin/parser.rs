@@ -164,92 +164,38 @@ impl KotlinParser {
         self.text_for_node(code, node).trim()
     }
 
-    /// Extract documentation comments (/** */ or //) - optimized version
-    /// Uses stack-allocated buffer and minimizes allocations
+    /// Extract documentation comments (/** */ or ///)
     fn doc_comment_for(&self, node: &Node, code: &str) -> Option<String> {
-        let mut result = String::new();
-        let mut has_comment = false;
-
-        // Stack to collect comments in reverse order (we traverse backwards)
-        let mut comment_stack: [Option<&str>; 8] = [None; 8];
-        let mut stack_len = 0;
-        let mut current = node.prev_sibling();
-
-        // Special case: if previous sibling is package_header, check its children for comments
-        if let Some(sibling) = current {
-            if sibling.kind() == NODE_PACKAGE_HEADER {
-                let mut cursor = sibling.walk();
-                for child in sibling.named_children(&mut cursor) {
-                    let child_kind = child.kind();
-                    if child_kind == NODE_MULTILINE_COMMENT || child_kind == NODE_LINE_COMMENT {
-                        let raw = self.text_for_node(code, child);
-                        if let Some(cleaned) = self.extract_comment_text(raw, &mut result) {
-                            if has_comment {
-                                result.push('\n');
-                            }
-                            result.push_str(cleaned);
-                            has_comment = true;
-                        }
-                    }
-                }
-                if has_comment {
-                    return Some(result);
-                }
-            }
+        // Comments right after `package x` are parsed inside the package header
+        let mut comments = Vec::new();
+        if let Some(header) = node
+            .prev_sibling()
+            .filter(|sibling| sibling.kind() == NODE_PACKAGE_HEADER)
+        {
+            let mut cursor = header.walk();
+            comments.extend(
+                header
+                    .named_children(&mut cursor)
+                    .filter(|child| {
+                        child.kind() == NODE_MULTILINE_COMMENT || child.kind() == NODE_LINE_COMMENT
+                    })
+                    .map(|child| self.text_for_node(code, child)),
+            );
         }
-
-        // Standard case: traverse backwards through siblings collecting comments
-        current = node.prev_sibling();
-        while let Some(sibling) = current {
-            let sibling_kind = sibling.kind();
-            if sibling_kind != NODE_MULTILINE_COMMENT && sibling_kind != NODE_LINE_COMMENT {
-                break;
-            }
-
-            let raw = self.text_for_node(code, sibling);
-            // Try to use stack allocation for small numbers of comments
-            if stack_len < comment_stack.len() {
-                if let Some(cleaned) = self.peek_comment_text(raw) {
-                    comment_stack[stack_len] = Some(cleaned);
-                    stack_len += 1;
-                    current = sibling.prev_sibling();
-                    continue;
-                }
-            }
-            break;
+        if comments.is_empty() {
+            comments =
+                self.preceding_comments(node, code, &|text| self.peek_comment_text(text).is_some());
         }
 
-        // Build result from stack (in reverse order to get correct ordering)
-        if stack_len > 0 {
-            for i in (0..stack_len).rev() {
-                if let Some(comment) = comment_stack[i] {
-                    if !result.is_empty() {
-                        result.push('\n');
-                    }
-                    result.push_str(comment);
-                    has_comment = true;
-                }
-            }
-        }
-
-        if has_comment { Some(result) } else { None }
-    }
-
-    /// Extract and clean comment text, writing directly to result buffer
-    /// Returns a reference to the cleaned text within the raw string when possible
-    fn extract_comment_text<'a>(&self, raw: &'a str, _result: &mut String) -> Option<&'a str> {
-        let trimmed = raw.trim();
-        if let Some(content) = trimmed
-            .strip_prefix("/**")
-            .and_then(|s| s.strip_suffix("*/"))
-        {
-            let cleaned = content.trim();
-            return Some(cleaned);
-        } else if let Some(content) = trimmed.strip_prefix("///") {
-            let cleaned = content.trim();
-            return Some(cleaned);
+        let cleaned: Vec<&str> = comments
+            .into_iter()
+            .filter_map(|raw| self.peek_comment_text(raw))
+            .collect();
+        if cleaned.is_empty() {
+            None
+        } else {
+            Some(cleaned.join("\n"))
         }
-        None
     }
 
     fn extract_generic_parameters<'a>(
@@ -743,9 +689,9 @@ impl KotlinParser {
                 if modifiers_text.contains("private") {
                     return Visibility::Private;
                 } else if modifiers_text.contains("protected") {
-                    return Visibility::Module; // Map protected to module-level
+                    return Visibility::Protected;
                 } else if modifiers_text.contains("internal") {
-                    return Visibility::Crate; // Map internal to crate-level
+                    return Visibility::Crate; // Kotlin internal is module-wide, like a crate
                 }
             }
         }
@@ -834,7 +780,7 @@ impl KotlinParser {
                     if modifiers_text.contains("private") {
                         visibility = Visibility::Private;
                     } else if modifiers_text.contains("protected") {
-                        visibility = Visibility::Module;
+                        visibility = Visibility::Protected;
                     } else if modifiers_text.contains("internal") {
                         visibility = Visibility::Crate;
                     }
@@ -1469,6 +1415,7 @@ impl KotlinParser {
                         alias: None,
                         is_glob,
                         is_type_only: false,
+                        range: Some(Import::range_of(&node)),
                     });
                 }
             }
@@ -3,7 +3,7 @@
 //! Provides scoping and inheritance tracking tailored for Kotlin's language features,
 //! including package-based modules, nested classes, companion objects, and interfaces.
 
-use crate::parsing::resolution::{ImportBinding, InheritanceResolver, ResolutionScope};
+use crate::parsing::resolution::{ImportBinding, NameKeyedInheritanceResolver, ResolutionScope};
 use crate::parsing::{ScopeLevel, ScopeType};
 use crate::{FileId, SymbolId};
 use std::collections::{HashMap, HashSet};
@@ -361,7 +361,7 @@ impl KotlinInheritanceResolver {
     }
 }
 
-impl InheritanceResolver for KotlinInheritanceResolver {
+impl NameKeyedInheritanceResolver for KotlinInheritanceResolver {
     fn add_inheritance(&mut self, child: String, parent: String, _kind: &str) {
         self.parents.entry(child).or_default().push(parent);
     }
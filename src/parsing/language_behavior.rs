@@ -58,11 +58,15 @@
 //! 4. (Future) Register in the language registry for auto-discovery
 
 use crate::parsing::MethodCall;
+use crate::parsing::control_flow::BranchNodeKinds;
 use crate::parsing::paths::{strip_extension, strip_source_root};
+use crate::parsing::references::ReferenceNodeKinds;
+use crate::parsing::relationships::{ParseArtifacts, RawRelationship, default_relationships};
 use crate::parsing::resolution::{
     GenericInheritanceResolver, GenericResolutionContext, ImportBinding, ImportOrigin,
     InheritanceResolver, PipelineSymbolCache, ResolutionScope, ScopeLevel,
 };
+use crate::parsing::signature::{self, SignatureStyle};
 use crate::relationship::RelationKind;
 use crate::storage::DocumentIndex;
 use crate::{FileId, Symbol, SymbolId, SymbolKind, Visibility};
@@ -485,6 +489,120 @@ pub trait LanguageBehavior: Send + Sync {
         }
     }
 
+    /// How a stored supertype edge reads in this language, from the subtype
+    ///
+    /// `kind` is the relationship stored from subtype to supertype (`Extends`
+    /// or `Implements`); parsers differ in which one they emit for the same
+    /// concept, so type hierarchies ask the subtype's language how to label
+    /// it. `None` means the edge does not express subtyping.
+    fn supertype_relation(&self, kind: RelationKind) -> Option<&'static str> {
+        match kind {
+            RelationKind::Extends => Some("extends"),
+            RelationKind::Implements => Some("implements"),
+            _ => None,
+        }
+    }
+
+    /// Syntax node kinds for the shared reference pass
+    ///
+    /// Identifiers of these kinds inside a function become `References`
+    /// edges from it, minus local bindings and member names. `None` leaves
+    /// the language out of the pass.
+    fn reference_node_kinds(&self) -> Option<&'static ReferenceNodeKinds> {
+        None
+    }
+
+    /// Syntax node kinds for the conditional-region pass
+    ///
+    /// Calls inside the regions these describe are tagged `conditional:true`.
+    /// `None` leaves every call of the language untagged.
+    fn branch_node_kinds(&self) -> Option<&'static BranchNodeKinds> {
+        None
+    }
+
+    /// Whether files of this language reference each other by path literal
+    ///
+    /// When true, each file's `<module>` symbol is named by its module path
+    /// and references that [`resolve_path_reference`](Self::resolve_path_reference)
+    /// maps to a file point at that file's module symbol.
+    fn links_files_by_path(&self) -> bool {
+        false
+    }
+
+    /// File a path literal in `from_file` refers to, if it exists
+    ///
+    /// Nix: `./lib` in `/repo/default.nix` → `/repo/lib/default.nix`.
+    fn resolve_path_reference(&self, _reference: &str, _from_file: &Path) -> Option<PathBuf> {
+        None
+    }
+
+    /// Add to `symbols` what files next to `file_path` record about them
+    ///
+    /// Nix: the `flake.lock` beside a `flake.nix` pins each of its inputs.
+    /// The companion file is read when `file_path` is indexed, so a change
+    /// to it alone shows up once `file_path` is indexed again.
+    fn annotate_from_companion_files(&self, _file_path: &Path, _symbols: &mut [Symbol]) {}
+
+    /// Relationships of a parsed file, built from the artifacts its
+    /// parser's `find_*` passes collected
+    ///
+    /// Runs after symbol extraction, so `symbols` holds every symbol of the
+    /// file, whatever order the parser produced them in. The default gives
+    /// each artifact the edge of its kind; languages override this to add,
+    /// drop or rewrite edges.
+    fn build_relationships(
+        &self,
+        _symbols: &[Symbol],
+        artifacts: &ParseArtifacts,
+    ) -> Vec<RawRelationship> {
+        default_relationships(artifacts)
+    }
+
+    /// Where this language embeds code of other languages
+    ///
+    /// Regions matched by these rules are parsed with the embedded
+    /// language's parser and indexed under the host file. Default: none.
+    fn injection_rules(&self) -> &'static [super::injection::InjectionRule] {
+        &[]
+    }
+
+    /// Syntax node kinds of import declarations
+    ///
+    /// Identifiers inside them declare imports rather than use them, which
+    /// is what unused import detection needs to tell apart. Empty leaves the
+    /// language out of that check.
+    fn import_declaration_kinds(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Call expression node kinds, each with the field naming the callee
+    ///
+    /// Used by security-sensitive call detection, which matches the callee's
+    /// source text against rules. Empty leaves the language out of that check.
+    fn call_node_kinds(&self) -> &'static [(&'static str, &'static str)] {
+        &[]
+    }
+
+    /// Names an import binds in the importing file
+    ///
+    /// The alias, or else the last segment of the path. Empty for imports
+    /// that bind no name, such as globs.
+    fn import_bindings(&self, import: &crate::parsing::Import) -> Vec<String> {
+        if import.is_glob {
+            return Vec::new();
+        }
+        if let Some(alias) = &import.alias {
+            return vec![alias.clone()];
+        }
+        import
+            .path
+            .rsplit(self.module_separator())
+            .next()
+            .filter(|name| !name.is_empty())
+            .map(|name| vec![name.to_string()])
+            .unwrap_or_default()
+    }
+
     /// Map language-specific relationship to generic RelationKind
     ///
     /// Allows languages to define how their concepts map to the generic relationship types.
@@ -496,6 +614,8 @@ pub trait LanguageBehavior: Send + Sync {
             "uses" => RelationKind::Uses,
             "calls" => RelationKind::Calls,
             "defines" => RelationKind::Defines,
+            "aliases" => RelationKind::Aliases,
+            "throws" | "raises" => RelationKind::Throws,
             _ => RelationKind::References,
         }
     }
@@ -554,6 +674,7 @@ pub trait LanguageBehavior: Send + Sync {
                     alias: import.alias.clone(),
                     is_glob: import.is_glob,
                     is_type_only: import.is_type_only,
+                    range: import.range,
                 }
             })
             .collect();
@@ -568,11 +689,13 @@ pub trait LanguageBehavior: Send + Sync {
             let separator = self.module_separator();
             let symbol_name = import.path.split(separator).last().unwrap_or(&import.path);
 
-            // Use PipelineSymbolCache.resolve() for multi-tier resolution
-            let result = cache.resolve(
+            // Multi-tier resolution, answered from the resolution cache when
+            // this import was resolved before
+            let result = cache.resolve_import(
                 symbol_name,
                 &caller,
-                None, // No specific range for imports
+                importing_module.as_deref(),
+                &import.path,
                 imports,
             );
 
@@ -714,6 +837,33 @@ pub trait LanguageBehavior: Send + Sync {
         matches!(symbol.visibility, Visibility::Public)
     }
 
+    /// Check if a symbol is part of its module's exported surface
+    ///
+    /// Stored as [`Symbol::exported`] alongside the visibility, so queries can
+    /// tell crate-wide or package-wide symbols from the exported API.
+    ///
+    /// Default implementation exports public symbols that are not locals.
+    fn is_exported(&self, symbol: &Symbol) -> bool {
+        use crate::symbol::ScopeContext;
+
+        symbol.visibility == Visibility::Public
+            && !matches!(
+                symbol.scope_context,
+                Some(ScopeContext::Local { .. } | ScopeContext::Parameter)
+            )
+    }
+
+    /// Normalize a stored signature for search results and hovers
+    ///
+    /// Parsers keep signatures as source slices; this strips attributes and
+    /// bodies and collapses whitespace so every language displays alike.
+    ///
+    /// Default implementation handles `#[...]` attributes, `@` annotations
+    /// and brace-delimited bodies.
+    fn normalize_signature(&self, signature: &str, style: SignatureStyle) -> String {
+        signature::normalize(signature, style)
+    }
+
     /// Get imports for a file
     ///
     /// Returns the list of imports that were registered for this file.
@@ -980,6 +1130,20 @@ pub fn default_relationship_compatibility(
             // Very permissive - almost anything can reference anything
             true
         }
+        Aliases | AliasedBy => {
+            // Aliases name types, values and modules alike, and their
+            // targets are often external (`u32`, `pkgs.hello`)
+            true
+        }
+        Throws | ThrownBy => {
+            let thrower = matches!(from_kind, Function | Method);
+            let error = matches!(to_kind, Class | Struct | Enum | Interface | TypeAlias);
+            match rel_kind {
+                Throws => thrower && error,
+                ThrownBy => error && thrower,
+                _ => unreachable!(),
+            }
+        }
     }
 }
 
@@ -3,6 +3,7 @@
 use crate::Visibility;
 use crate::parsing::LanguageBehavior;
 use crate::parsing::behavior_state::{BehaviorState, StatefulBehavior};
+use crate::parsing::references::ReferenceNodeKinds;
 use crate::parsing::resolution::{InheritanceResolver, ResolutionScope};
 use crate::types::FileId;
 use std::path::{Path, PathBuf};
@@ -36,6 +37,27 @@ impl StatefulBehavior for LuaBehavior {
     }
 }
 
+/// Reference pass node kinds. Assignment targets count as locals even
+/// without `local`, which keeps globals assigned inside a function out of its
+/// references.
+static REFERENCE_NODES: ReferenceNodeKinds = ReferenceNodeKinds {
+    identifiers: &["identifier"],
+    bindings: &[
+        ("parameters", Some("name")),
+        ("variable_list", Some("name")),
+        ("for_numeric_clause", Some("name")),
+    ],
+    members: &[
+        ("dot_index_expression", Some("field")),
+        ("method_index_expression", Some("method")),
+        ("field", Some("name")),
+    ],
+    paths: &[],
+    fields: &[],
+    containers: &[],
+    this: &[],
+};
+
 impl LanguageBehavior for LuaBehavior {
     fn language_id(&self) -> crate::parsing::registry::LanguageId {
         crate::parsing::registry::LanguageId::new("lua")
@@ -49,6 +71,10 @@ impl LanguageBehavior for LuaBehavior {
         tree_sitter_lua::LANGUAGE.into()
     }
 
+    fn reference_node_kinds(&self) -> Option<&'static ReferenceNodeKinds> {
+        Some(&REFERENCE_NODES)
+    }
+
     fn module_separator(&self) -> &'static str {
         "."
     }
@@ -56,8 +56,12 @@ impl LanguageDefinition for LuaLanguage {
         &["lua"]
     }
 
+    fn interpreters(&self) -> &'static [&'static str] {
+        &["lua", "luajit"]
+    }
+
     fn create_parser(&self, _settings: &Settings) -> IndexResult<Box<dyn LanguageParser>> {
-        let parser = LuaParser::new().map_err(|e| IndexError::General(e.to_string()))?;
+        let parser = LuaParser::new().map_err(|e| IndexError::parser_init("lua", e))?;
         Ok(Box::new(parser))
     }
 
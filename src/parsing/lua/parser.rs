@@ -687,41 +687,29 @@ impl LuaParser {
     }
 
     fn extract_lua_doc_comment(&self, node: &Node, code: &str) -> Option<String> {
-        let mut doc_lines = Vec::new();
-        let mut current = node.prev_sibling();
-
-        while let Some(sibling) = current {
-            if sibling.kind() == "comment" {
-                let comment_text = &code[sibling.byte_range()];
-
-                if comment_text.starts_with("---") {
-                    let content = comment_text.trim_start_matches("---").trim();
-                    doc_lines.insert(0, content.to_string());
-                    current = sibling.prev_sibling();
-                } else if comment_text.starts_with("--[[") {
-                    let content = comment_text
-                        .trim_start_matches("--[[")
-                        .trim_end_matches("]]")
-                        .trim();
-                    doc_lines.insert(0, content.to_string());
-                    break;
-                } else {
-                    // Stop on first non-doc comment (regular -- comments)
-                    break;
-                }
-            } else {
-                break;
-            }
-        }
-
-        if !doc_lines.is_empty() {
-            let filtered: Vec<String> = doc_lines.into_iter().filter(|l| !l.is_empty()).collect();
-            if !filtered.is_empty() {
-                return Some(filtered.join("\n"));
-            }
+        // `---` lines and `--[[ ]]` blocks document; regular `--` comments don't
+        let comments = self.preceding_comments(node, code, &|text| {
+            text.starts_with("---") || text.starts_with("--[[")
+        });
+        // A block comment ends the doc: earlier comments belong to other code
+        let start = comments
+            .iter()
+            .rposition(|text| text.starts_with("--[["))
+            .unwrap_or(0);
+
+        let doc_lines: Vec<&str> = comments[start..]
+            .iter()
+            .map(|text| match text.strip_prefix("--[[") {
+                Some(block) => block.trim_end_matches("]]").trim(),
+                None => text.trim_start_matches("---").trim(),
+            })
+            .filter(|line| !line.is_empty())
+            .collect();
+        if doc_lines.is_empty() {
+            None
+        } else {
+            Some(doc_lines.join("\n"))
         }
-
-        None
     }
 
     fn extract_method_calls_from_tree(&self, tree: &Tree, code: &str) -> Vec<MethodCall> {
@@ -753,6 +741,7 @@ fn extract_method_calls_recursive(node: &Node, code: &str, calls: &mut Vec<Metho
                             is_static: false,
                             range,
                             caller_range: Some(range),
+                            category: None,
                         });
                     }
                 }
@@ -864,6 +853,7 @@ fn try_extract_require_call(
                     file_id,
                     is_glob: false,
                     is_type_only: false,
+                    range: Some(Import::range_of(node)),
                 });
             }
         }
@@ -1350,4 +1340,34 @@ end
                 .any(|(c, callee, _)| *c == "process" && *callee == "sqrt")
         );
     }
+
+    #[test]
+    fn test_doc_comment_from_preceding_comments() {
+        let mut parser = LuaParser::new().unwrap();
+        let code = r#"
+--- Not the doc
+--[[ Adds two numbers ]]
+--- Returns their sum
+function add(a, b)
+    return a + b
+end
+
+local limit = 10 --- trailing note
+function clamp(x)
+    return x
+end
+"#;
+
+        let mut counter = SymbolCounter::new();
+        let symbols = parser.parse(code, FileId::new(1).unwrap(), &mut counter);
+        let doc = |name: &str| {
+            symbols
+                .iter()
+                .find(|s| s.name.as_ref() == name)
+                .and_then(|s| s.doc_comment.as_deref())
+        };
+
+        assert_eq!(doc("add"), Some("Adds two numbers\nReturns their sum"));
+        assert_eq!(doc("clamp"), None, "trailing comment belongs to limit");
+    }
 }
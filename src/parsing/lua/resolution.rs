@@ -1,6 +1,6 @@
 //! Lua-specific resolution and inheritance implementation
 
-use crate::parsing::{InheritanceResolver, ResolutionScope, ScopeLevel, ScopeType};
+use crate::parsing::{NameKeyedInheritanceResolver, ResolutionScope, ScopeLevel, ScopeType};
 use crate::symbol::ScopeContext;
 use crate::{FileId, SymbolId};
 use std::any::Any;
@@ -173,7 +173,7 @@ impl LuaInheritanceResolver {
     }
 }
 
-impl InheritanceResolver for LuaInheritanceResolver {
+impl NameKeyedInheritanceResolver for LuaInheritanceResolver {
     fn add_inheritance(&mut self, child: String, parent: String, kind: &str) {
         self.inheritance
             .entry(child)
@@ -93,6 +93,12 @@ pub struct MethodCall {
     /// Used for precise symbol lookup during relationship resolution.
     /// When provided, enables exact matching instead of name-only fallback.
     pub caller_range: Option<Range>,
+
+    /// What the called function does, for well-known library functions
+    ///
+    /// Stored in the call's relationship metadata under the `category` key
+    /// (e.g. `"fetcher"` for Nix `builtins.fetchGit`).
+    pub category: Option<&'static str>,
 }
 
 impl MethodCall {
@@ -113,6 +119,7 @@ impl MethodCall {
             is_static: false,
             range,
             caller_range: None,
+            category: None,
         }
     }
 
@@ -142,6 +149,12 @@ impl MethodCall {
         self
     }
 
+    /// Sets the semantic category of the called function
+    pub fn with_category(mut self, category: &'static str) -> Self {
+        self.category = Some(category);
+        self
+    }
+
     /// Checks if this is a self method call
     #[inline]
     pub fn is_self_call(&self) -> bool {
@@ -1,40 +1,53 @@
 pub mod behavior_state;
 pub mod c;
 pub mod context;
+pub mod control_flow;
 pub mod cpp;
 pub mod csharp;
+pub mod detection;
 pub mod factory;
 pub mod gdscript;
 pub mod go;
 pub mod import;
+pub mod injection;
+pub mod instantiation;
 pub mod java;
 pub mod javascript;
+pub mod kind_overrides;
 pub mod kotlin;
 pub mod language;
 pub mod language_behavior;
 pub mod lua;
 pub mod method_call;
 pub mod nix;
+pub mod operator;
 pub mod parser;
 pub mod paths;
 pub mod php;
 pub mod python;
+pub mod references;
 pub mod registry;
+pub mod relationships;
 pub mod resolution;
 pub mod rust;
+pub mod signature;
 pub mod swift;
+pub mod tag_rules;
 pub mod typescript;
 
 pub use c::{CBehavior, CParser};
 pub use context::{ParserContext, ScopeType};
+pub use control_flow::{BranchNodeKinds, conditional_ranges};
 pub use cpp::{CppBehavior, CppParser};
 pub use csharp::{CSharpBehavior, CSharpParser};
 pub use factory::{ParserFactory, ParserWithBehavior};
 pub use gdscript::{GdscriptBehavior, GdscriptParser};
 pub use go::{GoBehavior, GoParser};
 pub use import::Import;
+pub use instantiation::{GenericInstantiation, Instantiation};
 pub use java::{JavaBehavior, JavaParser};
 pub use javascript::{JavaScriptBehavior, JavaScriptParser};
+pub use kind_overrides::KindOverrides;
 pub use kotlin::{KotlinBehavior, KotlinParser};
 pub use language::Language;
 pub use language_behavior::{
@@ -43,6 +56,7 @@ pub use language_behavior::{
 pub use lua::{LuaBehavior, LuaParser};
 pub use method_call::{MethodCall, MethodCallResolver};
 pub use nix::{NixBehavior, NixParser};
+pub use operator::OperatorCall;
 pub use parser::{
     HandledNode, LanguageParser, NodeTracker, NodeTrackingState, safe_substring_window,
     safe_truncate_str, truncate_for_display,
@@ -52,11 +66,16 @@ pub use paths::{
 };
 pub use php::{PhpBehavior, PhpParser};
 pub use python::{PythonBehavior, PythonParser};
+pub use references::{ReferenceNodeKinds, ReferenceSite, extract_references};
 pub use registry::{LanguageDefinition, LanguageId, LanguageRegistry, RegistryError, get_registry};
+pub use relationships::{ParseArtifacts, RawRelationship, default_relationships};
 pub use resolution::{
     CallerContext, GenericInheritanceResolver, GenericResolutionContext, InheritanceResolver,
-    PipelineSymbolCache, ResolutionScope, ResolveResult, ScopeLevel,
+    NameKeyedInheritanceResolver, PipelineSymbolCache, ResolutionScope, ResolveResult, ScopeLevel,
+    TypeRef,
 };
 pub use rust::{RustBehavior, RustParser};
+pub use signature::{SignatureStyle, display_signature};
 pub use swift::{SwiftBehavior, SwiftParser};
+pub use tag_rules::TagRules;
 pub use typescript::{TypeScriptBehavior, TypeScriptParser};
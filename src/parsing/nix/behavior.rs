@@ -11,7 +11,9 @@
 
 use crate::Visibility;
 use crate::parsing::LanguageBehavior;
-use std::path::Path;
+use crate::parsing::injection::InjectionRule;
+use crate::parsing::references::ReferenceNodeKinds;
+use std::path::{Component, Path, PathBuf};
 use tree_sitter::Language;
 
 /// Nix language behavior implementation
@@ -37,6 +39,61 @@ impl Default for NixBehavior {
     }
 }
 
+/// Reference pass node kinds. Attribute paths name attributes of a set,
+/// not bindings in scope, so only variables are read.
+static REFERENCE_NODES: ReferenceNodeKinds = ReferenceNodeKinds {
+    identifiers: &["identifier"],
+    bindings: &[
+        ("formal", Some("name")),
+        ("function_expression", Some("universal")),
+    ],
+    members: &[
+        ("attrpath", Some("attr")),
+        ("inherited_attrs", Some("attr")),
+    ],
+    paths: &[],
+    fields: &[],
+    containers: &[],
+    this: &[],
+};
+
+/// Scripts passed to the nixpkgs writers and builders as indented strings
+/// (`writers.writePython3 "name" { } ''...''`, `writeShellScript "name" ''...''`)
+static INJECTIONS: &[InjectionRule] = &[
+    InjectionRule {
+        language: "bash",
+        node_kind: "indented_string_expression",
+        callees: &[
+            "writeShellScript",
+            "writeShellScriptBin",
+            "writeScript",
+            "writeScriptBin",
+            "writeBash",
+            "writeBashBin",
+            "runCommand",
+            "runCommandLocal",
+        ],
+        delimiters: None,
+    },
+    InjectionRule {
+        language: "python",
+        node_kind: "indented_string_expression",
+        callees: &[
+            "writePython3",
+            "writePython3Bin",
+            "writePyPy3",
+            "writePyPy3Bin",
+        ],
+        delimiters: None,
+    },
+    InjectionRule {
+        language: "javascript",
+        node_kind: "indented_string_expression",
+        callees: &["writeJS", "writeJSBin"],
+        delimiters: None,
+    },
+];
+
 impl LanguageBehavior for NixBehavior {
     fn language_id(&self) -> crate::parsing::registry::LanguageId {
         crate::parsing::registry::LanguageId::new("nix")
@@ -102,6 +159,19 @@ impl LanguageBehavior for NixBehavior {
         tree_sitter_nix::LANGUAGE.into()
     }
 
+    /// Function application; curried calls nest, so `f a b` applies `f a`
+    fn call_node_kinds(&self) -> &'static [(&'static str, &'static str)] {
+        &[("apply_expression", "function")]
+    }
+
+    fn reference_node_kinds(&self) -> Option<&'static ReferenceNodeKinds> {
+        Some(&REFERENCE_NODES)
+    }
+
+    fn injection_rules(&self) -> &'static [InjectionRule] {
+        INJECTIONS
+    }
+
     /// Convert file path to Nix module path
     ///
     /// Nix files typically represent configuration or build expressions.
@@ -140,6 +210,49 @@ impl LanguageBehavior for NixBehavior {
         }
     }
 
+    /// Nix files import each other by path: `import ./lib.nix`,
+    /// `imports = [ ./hardware.nix ];`
+    fn links_files_by_path(&self) -> bool {
+        true
+    }
+
+    /// Resolve a relative or absolute path literal against the importing
+    /// file's directory. A directory stands for its `default.nix`, as with
+    /// `import`.
+    fn resolve_path_reference(&self, reference: &str, from_file: &Path) -> Option<PathBuf> {
+        if !(reference.starts_with("./")
+            || reference.starts_with("../")
+            || reference.starts_with('/'))
+        {
+            return None;
+        }
+        let mut target = from_file.parent()?.to_path_buf();
+        for component in Path::new(reference).components() {
+            match component {
+                Component::CurDir => {}
+                Component::ParentDir => {
+                    target.pop();
+                }
+                Component::RootDir => target = PathBuf::from("/"),
+                other => target.push(other),
+            }
+        }
+        if target.is_dir() {
+            target.push("default.nix");
+        }
+        (target.is_file() && target.extension().is_some_and(|ext| ext == "nix")).then_some(target)
+    }
+
+    /// Pin the inputs of a `flake.nix` to the revisions in its `flake.lock`
+    fn annotate_from_companion_files(&self, file_path: &Path, symbols: &mut [crate::Symbol]) {
+        if file_path
+            .file_name()
+            .is_some_and(|name| name == "flake.nix")
+        {
+            super::flake::annotate_locked_inputs(file_path, symbols);
+        }
+    }
+
     /// Nix doesn't have traits or interfaces
     ///
     /// Nix is a purely functional language without object-oriented concepts
@@ -221,6 +334,11 @@ impl LanguageBehavior for NixBehavior {
         "references"
     }
 
+    /// Nix has no types to subtype
+    fn supertype_relation(&self, _kind: crate::relationship::RelationKind) -> Option<&'static str> {
+        None
+    }
+
     /// Map Nix-specific relationships to generic RelationKind
     ///
     /// Nix has specific relationship patterns based on functional programming
@@ -421,6 +539,8 @@ mod tests {
             hoisted: false,
             parent_name: None,
             parent_kind: None,
+            depth: 0,
+            ancestors: Vec::new(),
         });
         assert!(behavior.is_resolvable_symbol(&local_symbol));
 
@@ -533,6 +653,36 @@ mod tests {
         assert!(!behavior.import_matches_symbol("./utils", "lib.other", Some("lib")));
     }
 
+    #[test]
+    fn test_resolve_path_reference() {
+        let behavior = NixBehavior::new();
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("lib")).unwrap();
+        std::fs::write(root.join("default.nix"), "{ }").unwrap();
+        std::fs::write(root.join("lib/default.nix"), "{ }").unwrap();
+        std::fs::write(root.join("lib/utils.nix"), "{ }").unwrap();
+        let from = root.join("lib/utils.nix");
+
+        assert_eq!(
+            behavior.resolve_path_reference("./default.nix", &from),
+            Some(root.join("lib/default.nix"))
+        );
+        assert_eq!(
+            behavior.resolve_path_reference("../lib", &from),
+            Some(root.join("lib/default.nix"))
+        );
+        assert_eq!(
+            behavior.resolve_path_reference("../default.nix", &from),
+            Some(root.join("default.nix"))
+        );
+        assert_eq!(
+            behavior.resolve_path_reference("./missing.nix", &from),
+            None
+        );
+        assert_eq!(behavior.resolve_path_reference("pkgs", &from), None);
+    }
+
     #[test]
     fn test_get_language() {
         let behavior = NixBehavior::new();
@@ -561,8 +711,12 @@ mod tests {
             },
             doc_comment: None,
             visibility: Visibility::Private,
+            exported: false,
             scope_context: None,
             language_id: Some(LanguageId::new("nix")),
+            annotations: Vec::new(),
+            tags: Vec::new(),
+            project: None,
         }
     }
 }
@@ -0,0 +1,196 @@
+//! Semantic categories of Nix builtins and nixpkgs `lib` functions
+//!
+//! Calls to well-known functions are tagged with what they do, so the call
+//! graph can answer questions such as "which bindings fetch remote sources"
+//! (`fetchGit`, `fetchTarball`, `fetchurl`) without listing every function
+//! by hand. The category is stored in the call's relationship metadata under
+//! the `category` key.
+
+/// What a well-known Nix function does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CallCategory {
+    /// Downloads remote sources: `builtins.fetchGit`, `fetchurl`, `fetchFromGitHub`
+    Fetcher,
+    /// Builds or manipulates strings: `toString`, `concatStringsSep`
+    String,
+    /// Works on lists: `map`, `filter`, `foldl'`
+    List,
+    /// Works on attribute sets: `mapAttrs`, `attrNames`, `recursiveUpdate`
+    AttrSet,
+    /// Reads the filesystem at evaluation time: `readFile`, `pathExists`
+    File,
+    /// Loads other Nix code: `import`, `callPackage`
+    Import,
+    /// Declares a derivation: `derivation`, `mkDerivation`, `runCommand`
+    Derivation,
+}
+
+impl CallCategory {
+    /// Name stored in call metadata
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Fetcher => "fetcher",
+            Self::String => "string",
+            Self::List => "list",
+            Self::AttrSet => "attrset",
+            Self::File => "file",
+            Self::Import => "import",
+            Self::Derivation => "derivation",
+        }
+    }
+}
+
+/// Qualifiers under which the well-known functions live. Calls through any
+/// other attribute set (`myset.map`) are left unclassified.
+const KNOWN_SETS: &[&str] = &[
+    "builtins", "lib", "pkgs", "strings", "lists", "attrsets", "trivial", "stdenv",
+];
+
+const STRING: &[&str] = &[
+    "toString",
+    "substring",
+    "stringLength",
+    "replaceStrings",
+    "split",
+    "match",
+    "concatStrings",
+    "concatStringsSep",
+    "concatMapStrings",
+    "concatMapStringsSep",
+    "splitString",
+    "hasPrefix",
+    "hasSuffix",
+    "removePrefix",
+    "removeSuffix",
+    "toLower",
+    "toUpper",
+    "optionalString",
+    "escapeShellArg",
+    "escapeShellArgs",
+    "makeBinPath",
+    "makeLibraryPath",
+    "versionOlder",
+    "versionAtLeast",
+    "hashString",
+    "trim",
+];
+
+const LIST: &[&str] = &[
+    "map",
+    "filter",
+    "foldl'",
+    "foldl",
+    "foldr",
+    "length",
+    "head",
+    "tail",
+    "last",
+    "elem",
+    "elemAt",
+    "concatLists",
+    "concatMap",
+    "genList",
+    "sort",
+    "partition",
+    "all",
+    "any",
+    "flatten",
+    "unique",
+    "optional",
+    "optionals",
+    "imap0",
+    "imap1",
+    "reverseList",
+    "range",
+    "take",
+    "drop",
+    "sublist",
+    "zipLists",
+    "groupBy",
+    "findFirst",
+    "forEach",
+    "count",
+];
+
+const ATTRSET: &[&str] = &[
+    "attrNames",
+    "attrValues",
+    "hasAttr",
+    "getAttr",
+    "removeAttrs",
+    "intersectAttrs",
+    "catAttrs",
+    "listToAttrs",
+    "mapAttrs",
+    "mapAttrs'",
+    "mapAttrsToList",
+    "filterAttrs",
+    "filterAttrsRecursive",
+    "recursiveUpdate",
+    "genAttrs",
+    "nameValuePair",
+    "attrByPath",
+    "setAttrByPath",
+    "getAttrFromPath",
+    "optionalAttrs",
+    "foldlAttrs",
+    "zipAttrsWith",
+    "concatMapAttrs",
+];
+
+const FILE: &[&str] = &[
+    "readFile",
+    "readDir",
+    "readFileType",
+    "pathExists",
+    "path",
+    "filterSource",
+    "toFile",
+    "fileContents",
+    "importJSON",
+    "importTOML",
+];
+
+const IMPORT: &[&str] = &["import", "scopedImport", "callPackage", "callPackages"];
+
+const DERIVATION: &[&str] = &[
+    "derivation",
+    "mkDerivation",
+    "runCommand",
+    "runCommandLocal",
+    "writeText",
+    "writeTextFile",
+    "writeShellScript",
+    "writeShellScriptBin",
+    "writeShellApplication",
+    "symlinkJoin",
+    "buildEnv",
+];
+
+/// Category of a call to `name`, reached through `qualifier` (the attribute
+/// path before the name, `None` for a bare name)
+pub fn classify(qualifier: Option<&str>, name: &str) -> Option<CallCategory> {
+    let known = qualifier.is_none_or(|path| {
+        path.split('.')
+            .next_back()
+            .is_some_and(|set| KNOWN_SETS.contains(&set))
+    });
+    if !known {
+        return None;
+    }
+
+    if name.starts_with("fetch") {
+        return Some(CallCategory::Fetcher);
+    }
+    [
+        (STRING, CallCategory::String),
+        (LIST, CallCategory::List),
+        (ATTRSET, CallCategory::AttrSet),
+        (FILE, CallCategory::File),
+        (IMPORT, CallCategory::Import),
+        (DERIVATION, CallCategory::Derivation),
+    ]
+    .into_iter()
+    .find(|(names, _)| names.contains(&name))
+    .map(|(_, category)| category)
+}
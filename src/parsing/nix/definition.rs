@@ -63,7 +63,7 @@ impl LanguageDefinition for NixLanguage {
     fn create_parser(&self, _settings: &Settings) -> IndexResult<Box<dyn LanguageParser>> {
         NixParser::new()
             .map(|parser| Box::new(parser) as Box<dyn LanguageParser>)
-            .map_err(|e| IndexError::General(format!("Failed to create NixParser: {e}")))
+            .map_err(|e| IndexError::parser_init("nix", e))
     }
 
     fn create_behavior(&self) -> Box<dyn LanguageBehavior> {
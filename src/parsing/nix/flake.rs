@@ -0,0 +1,387 @@
+//! Flake inputs and the revisions `flake.lock` pins them to
+//!
+//! A flake declares its inputs in `flake.nix`, in any mix of these forms:
+//!
+//! ```nix
+//! inputs.nixpkgs.url = "github:NixOS/nixpkgs/nixos-unstable";
+//! inputs.home-manager = {
+//!   url = "github:nix-community/home-manager";
+//!   inputs.nixpkgs.follows = "nixpkgs";
+//! };
+//! inputs = { nixpkgs-unstable.url = "github:NixOS/nixpkgs"; };
+//! ```
+//!
+//! The parser gives every input a symbol of its own, so that references like
+//! `inputs.home-manager.nixosModules.default` in other files resolve to it.
+//! Indexing then reads the `flake.lock` next to the flake and adds the
+//! locked source and revision to each input's signature.
+
+use super::parser::node_range;
+use crate::{Range, Symbol};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+use tree_sitter::Node;
+
+/// Lock file of a flake, next to its `flake.nix`
+pub const LOCK_FILE: &str = "flake.lock";
+
+/// Longest chain of `follows` resolved in a lock file
+const MAX_FOLLOWS: usize = 16;
+
+/// An input declared by a flake
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlakeInput {
+    pub name: String,
+    /// Range of the input's name where it is first declared
+    pub range: Range,
+    pub url: Option<String>,
+    /// Input of this flake whose source this one reuses
+    pub follows: Option<String>,
+}
+
+impl FlakeInput {
+    /// Signature of the input's symbol
+    pub fn signature(&self) -> String {
+        match (&self.url, &self.follows) {
+            (Some(url), _) => format!("{} = <flake input {url}>", self.name),
+            (None, Some(follows)) => format!("{} = <flake input follows {follows}>", self.name),
+            (None, None) => format!("{} = <flake input>", self.name),
+        }
+    }
+}
+
+/// Inputs of the flake in `root`, empty unless the file is a flake: an
+/// attribute set that binds `outputs`
+pub fn flake_inputs(root: Node, code: &str) -> Vec<FlakeInput> {
+    let Some(flake) = root
+        .child_by_field_name("expression")
+        .filter(|node| node.kind() == "attrset_expression")
+    else {
+        return Vec::new();
+    };
+    let bindings = bindings(flake, code);
+    if !bindings.iter().any(|(path, _)| path[0].1 == "outputs") {
+        return Vec::new();
+    }
+
+    let mut inputs = Vec::new();
+    for (path, value) in &bindings {
+        if path[0].1 != "inputs" {
+            continue;
+        }
+        match path.get(1) {
+            // inputs.nixpkgs.url = "...";
+            Some(name) => declare(&mut inputs, name, &path[2..], *value, code),
+            // inputs = { nixpkgs.url = "..."; };
+            None => {
+                for (path, value) in bindings_of_set(*value, code) {
+                    declare(&mut inputs, &path[0], &path[1..], value, code);
+                }
+            }
+        }
+    }
+    inputs
+}
+
+/// Attribute path segments with their nodes
+pub(super) type AttrPath<'tree> = Vec<(Node<'tree>, String)>;
+
+/// Bindings of an attribute set with a static attribute path, in order
+pub(super) fn bindings<'tree>(set: Node<'tree>, code: &str) -> Vec<(AttrPath<'tree>, Node<'tree>)> {
+    let mut bindings = Vec::new();
+    for binding_set in set
+        .named_children(&mut set.walk())
+        .filter(|child| child.kind() == "binding_set")
+    {
+        for binding in binding_set
+            .named_children(&mut binding_set.walk())
+            .filter(|child| child.kind() == "binding")
+        {
+            let (Some(attrpath), Some(value)) = (
+                binding.child_by_field_name("attrpath"),
+                binding.child_by_field_name("expression"),
+            ) else {
+                continue;
+            };
+            let path: Option<AttrPath> = attrpath
+                .children_by_field_name("attr", &mut attrpath.walk())
+                .map(|attr| attr_name(attr, code).map(|name| (attr, name)))
+                .collect();
+            if let Some(path) = path.filter(|path| !path.is_empty()) {
+                bindings.push((path, value));
+            }
+        }
+    }
+    bindings
+}
+
+/// Bindings of `value` if it is a plain attribute set
+pub(super) fn bindings_of_set<'tree>(
+    value: Node<'tree>,
+    code: &str,
+) -> Vec<(AttrPath<'tree>, Node<'tree>)> {
+    if value.kind() == "attrset_expression" {
+        bindings(value, code)
+    } else {
+        Vec::new()
+    }
+}
+
+/// Record what a binding below `inputs.<name>` says about the input
+fn declare(
+    inputs: &mut Vec<FlakeInput>,
+    (name_node, name): &(Node, String),
+    rest: &[(Node, String)],
+    value: Node,
+    code: &str,
+) {
+    let index = match inputs.iter().position(|input| input.name == *name) {
+        Some(index) => index,
+        None => {
+            inputs.push(FlakeInput {
+                name: name.clone(),
+                range: node_range(*name_node),
+                url: None,
+                follows: None,
+            });
+            inputs.len() - 1
+        }
+    };
+    describe(&mut inputs[index], rest, value, code);
+}
+
+fn describe(input: &mut FlakeInput, path: &[(Node, String)], value: Node, code: &str) {
+    let path: Vec<&str> = path.iter().map(|(_, name)| name.as_str()).collect();
+    match path.as_slice() {
+        ["url"] => input.url = string_value(value, code),
+        ["follows"] => input.follows = string_value(value, code),
+        [] => {
+            for (path, value) in bindings_of_set(value, code) {
+                describe(input, &path, value, code);
+            }
+        }
+        // `flake = false;` and the follows of the input's own inputs
+        _ => {}
+    }
+}
+
+/// Name of an attribute: an identifier or a string without interpolation
+fn attr_name(attr: Node, code: &str) -> Option<String> {
+    match attr.kind() {
+        "identifier" => Some(code[attr.byte_range()].to_string()),
+        "string_expression" => string_value(attr, code),
+        _ => None,
+    }
+}
+
+pub(super) fn string_value(node: Node, code: &str) -> Option<String> {
+    if node.kind() != "string_expression" {
+        return None;
+    }
+    let text = code[node.byte_range()]
+        .strip_prefix('"')?
+        .strip_suffix('"')?;
+    (!text.contains("${")).then(|| text.to_string())
+}
+
+/// Parsed `flake.lock`
+#[derive(Debug, Deserialize)]
+struct FlakeLock {
+    nodes: HashMap<String, LockNode>,
+    root: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct LockNode {
+    #[serde(default)]
+    inputs: HashMap<String, LockInput>,
+    locked: Option<Locked>,
+}
+
+/// An input of a lock node: the key of its own node, or the path of the
+/// input it follows, from the root
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum LockInput {
+    Node(String),
+    Follows(Vec<String>),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Locked {
+    #[serde(rename = "type")]
+    kind: String,
+    owner: Option<String>,
+    repo: Option<String>,
+    url: Option<String>,
+    path: Option<String>,
+    rev: Option<String>,
+    last_modified: Option<i64>,
+}
+
+impl FlakeLock {
+    /// Node an input path from the root leads to
+    fn node(&self, path: &[String], depth: usize) -> Option<&LockNode> {
+        if depth > MAX_FOLLOWS {
+            return None;
+        }
+        let mut node = self.nodes.get(&self.root)?;
+        for name in path {
+            node = match node.inputs.get(name)? {
+                LockInput::Node(key) => self.nodes.get(key)?,
+                LockInput::Follows(target) => self.node(target, depth + 1)?,
+            };
+        }
+        Some(node)
+    }
+
+    /// Where the root input `name` is locked, as shown in its signature
+    fn pin(&self, name: &str) -> Option<String> {
+        let locked = self.node(&[name.to_string()], 0)?.locked.as_ref()?;
+        let source = match (&locked.owner, &locked.repo, &locked.url, &locked.path) {
+            (Some(owner), Some(repo), _, _) => format!("{}:{owner}/{repo}", locked.kind),
+            (_, _, Some(url), _) => url.clone(),
+            (_, _, _, Some(path)) => format!("path:{path}"),
+            _ => locked.kind.clone(),
+        };
+        let mut pin = format!("locked to {source}");
+        if let Some(rev) = &locked.rev {
+            pin.push_str(&format!(" at {rev}"));
+        }
+        if let Some(date) = locked
+            .last_modified
+            .and_then(|time| chrono::DateTime::from_timestamp(time, 0))
+        {
+            pin.push_str(&format!(" ({})", date.format("%Y-%m-%d")));
+        }
+        Some(pin)
+    }
+}
+
+/// Add the pins of `flake.lock` next to `flake_path` to the signatures of
+/// the input symbols among `symbols`
+pub fn annotate_locked_inputs(flake_path: &Path, symbols: &mut [Symbol]) {
+    let lock_path = flake_path.with_file_name(LOCK_FILE);
+    let Ok(content) = std::fs::read_to_string(&lock_path) else {
+        return;
+    };
+    let lock: FlakeLock = match serde_json::from_str(&content) {
+        Ok(lock) => lock,
+        Err(e) => {
+            tracing::debug!("[nix] ignoring unreadable {}: {e}", lock_path.display());
+            return;
+        }
+    };
+
+    for symbol in symbols.iter_mut() {
+        let Some(declared) = symbol
+            .signature
+            .as_deref()
+            .and_then(|signature| signature.strip_prefix(&*symbol.name))
+            .filter(|rest| rest.starts_with(" = <flake input"))
+            .and_then(|rest| rest.strip_suffix('>'))
+        else {
+            continue;
+        };
+        if let Some(pin) = lock.pin(&symbol.name) {
+            let signature = format!("{}{declared}, {pin}>", symbol.name);
+            symbol.signature = Some(signature.into());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FileId, SymbolId, SymbolKind};
+
+    const LOCK: &str = r#"{
+  "nodes": {
+    "home-manager": {
+      "inputs": { "nixpkgs": ["nixpkgs"] },
+      "locked": {
+        "lastModified": 1714000000,
+        "owner": "nix-community",
+        "repo": "home-manager",
+        "rev": "0e8d5c3f0a1b2c3d4e5f60718293a4b5c6d7e8f9",
+        "type": "github"
+      }
+    },
+    "nixpkgs": {
+      "locked": {
+        "lastModified": 1715000000,
+        "owner": "NixOS",
+        "repo": "nixpkgs",
+        "rev": "5fd8536a9a5932d4ae8de52b7dc08d92041237fc",
+        "type": "github"
+      }
+    },
+    "root": {
+      "inputs": {
+        "home-manager": "home-manager",
+        "nixpkgs": "nixpkgs",
+        "pkgs": ["home-manager", "nixpkgs"]
+      }
+    }
+  },
+  "root": "root",
+  "version": 7
+}"#;
+
+    #[test]
+    fn test_follows_resolve_to_the_followed_node() {
+        let lock: FlakeLock = serde_json::from_str(LOCK).unwrap();
+        assert_eq!(
+            lock.pin("pkgs").as_deref(),
+            Some(
+                "locked to github:NixOS/nixpkgs at 5fd8536a9a5932d4ae8de52b7dc08d92041237fc (2024-05-06)"
+            )
+        );
+        assert_eq!(
+            lock.pin("home-manager").as_deref(),
+            Some(
+                "locked to github:nix-community/home-manager at 0e8d5c3f0a1b2c3d4e5f60718293a4b5c6d7e8f9 (2024-04-24)"
+            )
+        );
+        assert_eq!(lock.pin("unknown"), None);
+    }
+
+    #[test]
+    fn test_lock_pins_are_added_to_input_signatures() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join(LOCK_FILE), LOCK).unwrap();
+        let range = Range::new(1, 2, 1, 9);
+        let mut symbols = vec![
+            Symbol::new(
+                SymbolId(1),
+                "nixpkgs",
+                SymbolKind::Variable,
+                FileId(1),
+                range,
+            )
+            .with_signature("nixpkgs = <flake input github:NixOS/nixpkgs/nixos-unstable>"),
+            Symbol::new(
+                SymbolId(2),
+                "outputs",
+                SymbolKind::Function,
+                FileId(1),
+                range,
+            )
+            .with_signature("outputs = <function>"),
+        ];
+
+        annotate_locked_inputs(&temp_dir.path().join("flake.nix"), &mut symbols);
+        assert_eq!(
+            symbols[0].signature.as_deref(),
+            Some(
+                "nixpkgs = <flake input github:NixOS/nixpkgs/nixos-unstable, locked to github:NixOS/nixpkgs at 5fd8536a9a5932d4ae8de52b7dc08d92041237fc (2024-05-06)>"
+            )
+        );
+        assert_eq!(
+            symbols[1].signature.as_deref(),
+            Some("outputs = <function>")
+        );
+    }
+}
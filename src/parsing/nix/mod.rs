@@ -4,7 +4,10 @@
 //! enabling precise symbol extraction, relationship tracking, and semantic analysis of Nix expressions.
 
 pub mod behavior;
+pub mod builtins;
 pub mod definition;
+pub mod flake;
+pub mod options;
 pub mod parser;
 pub mod resolution;
 
@@ -16,7 +19,10 @@ pub mod test_helpers;
 // mod resolution_tests;
 
 pub use behavior::NixBehavior;
+pub use builtins::CallCategory;
 pub use definition::NixLanguage;
+pub use flake::FlakeInput;
+pub use options::ModuleOptions;
 pub use parser::NixParser;
 pub use resolution::{NixInheritanceResolver, NixResolutionContext};
 
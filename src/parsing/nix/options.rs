@@ -0,0 +1,485 @@
+//! Options declared by NixOS and home-manager modules, and the definitions
+//! that set them
+//!
+//! A module declares options under `options` and sets them under `config`,
+//! or at its top level when it declares none:
+//!
+//! ```nix
+//! { config, lib, ... }: {
+//!   options.services.codanna = {
+//!     enable = lib.mkEnableOption "codanna";
+//!     port = lib.mkOption { type = lib.types.port; default = 8080; };
+//!   };
+//!   config = lib.mkIf config.services.codanna.enable {
+//!     networking.firewall.allowedTCPPorts = [ config.services.codanna.port ];
+//!   };
+//! }
+//! ```
+//!
+//! Every declared option becomes a symbol named by its full path
+//! (`services.codanna.port`). Every definition becomes a reference from the
+//! binding that makes it to the path it sets, which resolves to the option
+//! with the longest matching path, in whichever file declares it. Modules
+//! handed to home-manager through `home-manager.users.<name>` or
+//! `home-manager.sharedModules` set home-manager options, so paths inside
+//! them start over at the home-manager root.
+
+use super::flake::{AttrPath, bindings, bindings_of_set, string_value};
+use super::parser::node_range;
+use crate::Range;
+use crate::parsing::parser::check_recursion_depth;
+use tree_sitter::Node;
+
+/// Attributes of a module that are not option definitions
+const MODULE_ATTRIBUTES: &[&str] = &[
+    "imports",
+    "disabledModules",
+    "_file",
+    "key",
+    "_class",
+    "freeformType",
+];
+
+/// Functions that declare an option
+const DECLARATIONS: &[&str] = &[
+    "mkOption",
+    "mkEnableOption",
+    "mkPackageOption",
+    "mkSinkUndeclaredOptions",
+];
+
+/// Functions that change how a definition merges, not what it sets
+const MODIFIERS: &[&str] = &[
+    "mkIf",
+    "mkDefault",
+    "mkForce",
+    "mkOverride",
+    "mkOptionDefault",
+    "mkVMOverride",
+    "mkBefore",
+    "mkAfter",
+    "mkOrder",
+];
+
+/// An option declared under `options`
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptionDeclaration {
+    /// Full path of the option, like `services.codanna.port`
+    pub path: String,
+    /// Range of the option's last attribute name
+    pub range: Range,
+    /// Type as written, when it fits on one line
+    pub option_type: Option<String>,
+    pub description: Option<String>,
+}
+
+impl OptionDeclaration {
+    /// Signature of the option's symbol
+    pub fn signature(&self) -> String {
+        match &self.option_type {
+            Some(option_type) => format!("{} = <option {option_type}>", self.path),
+            None => format!("{} = <option>", self.path),
+        }
+    }
+}
+
+/// A definition setting the option at `path`, or something inside it
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptionDefinition {
+    pub path: String,
+    /// Range of the last attribute name the definition sets
+    pub range: Range,
+    /// Name and range of the symbol of the innermost binding making the
+    /// definition
+    pub binding: (String, Range),
+}
+
+/// Options declared and defined by a file
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ModuleOptions {
+    pub declarations: Vec<OptionDeclaration>,
+    pub definitions: Vec<OptionDefinition>,
+}
+
+/// Options of the module in `root`, empty unless the file is a module: an
+/// attribute set, or a function of module arguments returning one
+pub fn module_options(root: Node, code: &str) -> ModuleOptions {
+    let mut options = ModuleOptions::default();
+    if let Some(expression) = root.child_by_field_name("expression") {
+        module(expression, code, &mut options, 0);
+    }
+    options
+}
+
+fn module(node: Node, code: &str, options: &mut ModuleOptions, depth: usize) {
+    if !check_recursion_depth(depth, node) {
+        return;
+    }
+    match node.kind() {
+        // `final: prev: { ... }` is an overlay, not a module
+        "function_expression" if node.child_by_field_name("formals").is_some() => {
+            if let Some(body) = node.child_by_field_name("body") {
+                module(body, code, options, depth + 1);
+            }
+        }
+        "let_expression" | "with_expression" | "parenthesized_expression" => {
+            if let Some(inner) = inner(node) {
+                module(inner, code, options, depth + 1);
+            }
+        }
+        "attrset_expression" => {
+            let bindings = bindings(node, code);
+            // A flake's outputs are not option definitions
+            if bindings.iter().any(|(path, _)| path[0].1 == "outputs") {
+                return;
+            }
+            for (path, value) in &bindings {
+                let names: Vec<String> = path.iter().map(|(_, name)| name.clone()).collect();
+                match names[0].as_str() {
+                    name if MODULE_ATTRIBUTES.contains(&name) => {}
+                    "options" => declare(&names[1..], path, *value, code, options, depth + 1),
+                    "config" => define(&names[1..], path, *value, code, options, depth + 1),
+                    _ => define(&names, path, *value, code, options, depth + 1),
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Record the options declared by `value`, bound at `prefix` below
+/// `options` by the binding with attribute path `path`
+fn declare(
+    prefix: &[String],
+    path: &AttrPath,
+    value: Node,
+    code: &str,
+    options: &mut ModuleOptions,
+    depth: usize,
+) {
+    if !check_recursion_depth(depth, value) {
+        return;
+    }
+    match value.kind() {
+        "attrset_expression" => {
+            for (path, value) in bindings(value, code) {
+                let names = extend(prefix, &path);
+                declare(&names, &path, value, code, options, depth + 1);
+            }
+        }
+        "let_expression" | "with_expression" | "parenthesized_expression" => {
+            if let Some(inner) = inner(value) {
+                declare(prefix, path, inner, code, options, depth + 1);
+            }
+        }
+        "apply_expression" if !prefix.is_empty() => {
+            let Some(function) = applied_function(value, code) else {
+                return;
+            };
+            if !DECLARATIONS.contains(&function) {
+                return;
+            }
+            let argument = value.child_by_field_name("argument");
+            let (option_type, description) = match function {
+                "mkEnableOption" => (
+                    Some("types.bool".to_string()),
+                    argument
+                        .and_then(|name| text_value(name, code))
+                        .map(|name| format!("Whether to enable {name}.")),
+                ),
+                "mkPackageOption" => (Some("types.package".to_string()), None),
+                _ => {
+                    let settings = argument
+                        .map(|argument| bindings_of_set(argument, code))
+                        .unwrap_or_default();
+                    let setting = |name: &str| {
+                        settings
+                            .iter()
+                            .find(|(path, _)| path.len() == 1 && path[0].1 == name)
+                            .map(|(_, value)| *value)
+                    };
+                    (
+                        setting("type")
+                            .map(|option_type| &code[option_type.byte_range()])
+                            .filter(|option_type| !option_type.contains('\n'))
+                            .map(|option_type| option_type.trim_start_matches("lib.").to_string()),
+                        setting("description").and_then(|text| text_value(text, code)),
+                    )
+                }
+            };
+            options.declarations.push(OptionDeclaration {
+                path: prefix.join("."),
+                range: node_range(path[path.len() - 1].0),
+                option_type,
+                description,
+            });
+        }
+        _ => {}
+    }
+}
+
+/// Record the definitions made by `value`, bound at `prefix` below
+/// `config` by the binding with attribute path `path`
+fn define(
+    prefix: &[String],
+    path: &AttrPath,
+    value: Node,
+    code: &str,
+    options: &mut ModuleOptions,
+    depth: usize,
+) {
+    if !check_recursion_depth(depth, value) {
+        return;
+    }
+
+    // Modules handed to home-manager set options of their own
+    if let [home_manager, users, _, rest @ ..] = prefix {
+        if home_manager == "home-manager" && users == "users" {
+            options
+                .definitions
+                .push(definition(&prefix[..3], path, code));
+            if rest.is_empty() {
+                module(value, code, options, depth + 1);
+            } else {
+                define(rest, path, value, code, options, depth + 1);
+            }
+            return;
+        }
+    }
+    if let [home_manager, shared] = prefix {
+        if home_manager == "home-manager" && shared == "sharedModules" {
+            options.definitions.push(definition(prefix, path, code));
+            if value.kind() == "list_expression" {
+                for element in value.children_by_field_name("element", &mut value.walk()) {
+                    module(element, code, options, depth + 1);
+                }
+            }
+            return;
+        }
+    }
+
+    match value.kind() {
+        "attrset_expression" => {
+            for (path, value) in bindings(value, code) {
+                let names = extend(prefix, &path);
+                define(&names, &path, value, code, options, depth + 1);
+            }
+        }
+        "let_expression" | "with_expression" | "parenthesized_expression" => {
+            if let Some(inner) = inner(value) {
+                define(prefix, path, inner, code, options, depth + 1);
+            }
+        }
+        "apply_expression" => {
+            let argument = value.child_by_field_name("argument");
+            match (applied_function(value, code), argument) {
+                (Some("mkMerge"), Some(list)) if list.kind() == "list_expression" => {
+                    for element in list.children_by_field_name("element", &mut list.walk()) {
+                        define(prefix, path, element, code, options, depth + 1);
+                    }
+                }
+                (Some(function), Some(argument)) if MODIFIERS.contains(&function) => {
+                    define(prefix, path, argument, code, options, depth + 1);
+                }
+                _ if !prefix.is_empty() => options.definitions.push(definition(prefix, path, code)),
+                _ => {}
+            }
+        }
+        _ if !prefix.is_empty() => options.definitions.push(definition(prefix, path, code)),
+        _ => {}
+    }
+}
+
+fn definition(prefix: &[String], path: &AttrPath, code: &str) -> OptionDefinition {
+    let first = path[0].0;
+    OptionDefinition {
+        path: prefix.join("."),
+        range: node_range(path[path.len() - 1].0),
+        // Binding symbols are named by the first attribute as written
+        binding: (code[first.byte_range()].to_string(), node_range(first)),
+    }
+}
+
+fn extend(prefix: &[String], path: &AttrPath) -> Vec<String> {
+    prefix
+        .iter()
+        .cloned()
+        .chain(path.iter().map(|(_, name)| name.clone()))
+        .collect()
+}
+
+/// Body of a `let` or `with`, or the expression in parentheses
+fn inner(node: Node) -> Option<Node> {
+    node.child_by_field_name("body")
+        .or_else(|| node.child_by_field_name("expression"))
+}
+
+/// Name of the function an application calls: `mkIf` in `lib.mkIf c { }`
+fn applied_function<'a>(node: Node, code: &'a str) -> Option<&'a str> {
+    let mut function = node.child_by_field_name("function")?;
+    while function.kind() == "apply_expression" {
+        function = function.child_by_field_name("function")?;
+    }
+    let name = match function.kind() {
+        "variable_expression" => function.child_by_field_name("name")?,
+        "select_expression" => {
+            let attrpath = function.child_by_field_name("attrpath")?;
+            attrpath
+                .children_by_field_name("attr", &mut attrpath.walk())
+                .last()?
+        }
+        _ => return None,
+    };
+    Some(&code[name.byte_range()])
+}
+
+/// Text of a string without interpolation, on one line, looking through
+/// wrappers like `lib.mdDoc`
+fn text_value(node: Node, code: &str) -> Option<String> {
+    match node.kind() {
+        "string_expression" => string_value(node, code),
+        "indented_string_expression" => {
+            let text = code[node.byte_range()]
+                .strip_prefix("''")?
+                .strip_suffix("''")?;
+            (!text.contains("${")).then(|| text.split_whitespace().collect::<Vec<_>>().join(" "))
+        }
+        "apply_expression" => text_value(node.child_by_field_name("argument")?, code),
+        "parenthesized_expression" => text_value(node.child_by_field_name("expression")?, code),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options_of(code: &str) -> ModuleOptions {
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&tree_sitter_nix::LANGUAGE.into())
+            .unwrap();
+        let tree = parser.parse(code, None).unwrap();
+        module_options(tree.root_node(), code)
+    }
+
+    fn paths(definitions: &[OptionDefinition]) -> Vec<&str> {
+        definitions.iter().map(|d| d.path.as_str()).collect()
+    }
+
+    #[test]
+    fn test_declarations_and_definitions_of_a_module() {
+        let options = options_of(
+            r#"{ config, lib, pkgs, ... }:
+with lib;
+let cfg = config.services.codanna;
+in {
+  imports = [ ./hardware.nix ];
+  options.services.codanna = {
+    enable = mkEnableOption "the codanna server";
+    port = mkOption {
+      type = types.port;
+      default = 8080;
+      description = lib.mdDoc ''
+        Port to listen on.
+      '';
+    };
+    settings = mkOption {
+      type = types.submodule {
+        freeformType = types.attrs;
+      };
+    };
+  };
+  config = mkIf cfg.enable (mkMerge [
+    { networking.firewall.allowedTCPPorts = [ cfg.port ]; }
+    (mkIf cfg.openFirewall {
+      systemd.services.codanna = {
+        wantedBy = [ "multi-user.target" ];
+        serviceConfig.DynamicUser = mkDefault true;
+      };
+    })
+  ]);
+}"#,
+        );
+
+        let declared: Vec<String> = options
+            .declarations
+            .iter()
+            .map(OptionDeclaration::signature)
+            .collect();
+        assert_eq!(
+            declared,
+            [
+                "services.codanna.enable = <option types.bool>",
+                "services.codanna.port = <option types.port>",
+                "services.codanna.settings = <option>",
+            ]
+        );
+        assert_eq!(
+            options.declarations[0].description.as_deref(),
+            Some("Whether to enable the codanna server.")
+        );
+        assert_eq!(
+            options.declarations[1].description.as_deref(),
+            Some("Port to listen on.")
+        );
+
+        assert_eq!(
+            paths(&options.definitions),
+            [
+                "networking.firewall.allowedTCPPorts",
+                "systemd.services.codanna.wantedBy",
+                "systemd.services.codanna.serviceConfig.DynamicUser",
+            ]
+        );
+        let dynamic_user = &options.definitions[2];
+        assert_eq!(dynamic_user.binding.0, "serviceConfig");
+        assert_eq!(
+            dynamic_user.range.start_line,
+            dynamic_user.binding.1.start_line
+        );
+    }
+
+    #[test]
+    fn test_home_manager_modules_start_a_new_option_root() {
+        let options = options_of(
+            r#"{ pkgs, ... }: {
+  services.openssh.enable = true;
+  home-manager.useGlobalPkgs = true;
+  home-manager.users.alice = { config, ... }: {
+    programs.git = { enable = true; userName = "alice"; };
+  };
+  home-manager.sharedModules = [
+    { programs.direnv.enable = true; }
+    ./shared.nix
+  ];
+}"#,
+        );
+
+        assert!(options.declarations.is_empty());
+        assert_eq!(
+            paths(&options.definitions),
+            [
+                "services.openssh.enable",
+                "home-manager.useGlobalPkgs",
+                "home-manager.users.alice",
+                "programs.git.enable",
+                "programs.git.userName",
+                "home-manager.sharedModules",
+                "programs.direnv.enable",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_overlays_and_flakes_are_not_modules() {
+        assert_eq!(
+            options_of("final: prev: { hello = prev.hello; }"),
+            ModuleOptions::default()
+        );
+        assert_eq!(
+            options_of(r#"{ inputs.nixpkgs.url = "github:NixOS/nixpkgs"; outputs = _: { }; }"#),
+            ModuleOptions::default()
+        );
+    }
+}
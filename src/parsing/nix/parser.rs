@@ -4,21 +4,39 @@
 //! extracting symbols from Nix expressions including functions, variables,
 //! and attribute sets.
 
+use super::builtins::classify;
+use super::flake::flake_inputs;
+use super::options::module_options;
 use super::resolution::NixResolutionContext;
+use crate::parsing::parser::check_recursion_depth;
 use crate::parsing::{
-    LanguageParser, MethodCall, ParserContext, ScopeLevel, resolution::ResolutionScope,
+    LanguageParser, MethodCall, ParserContext, ReferenceSite, ScopeLevel,
+    resolution::ResolutionScope,
 };
+use crate::symbol::{Annotation, AnnotationKind};
 use crate::types::SymbolCounter;
 use crate::{FileId, Range, Symbol, SymbolKind, Visibility};
 use std::any::Any;
 use tree_sitter::{Node, Parser, Tree};
 
+/// Name of the symbol standing for the file itself
+const MODULE_SYMBOL: &str = "<module>";
+
 /// Nix language parser using tree-sitter-nix
 pub struct NixParser {
     parser: Parser,
     context: ParserContext,
     /// Nix-specific resolution context for advanced scoping
     resolution_context: Option<NixResolutionContext>,
+    /// Bindings whose value is being processed, innermost last, with the
+    /// range of their symbol
+    bindings: Vec<(String, Range)>,
+    /// Range of the file's module symbol, the context of references made
+    /// outside any binding
+    module_range: Range,
+    /// Names used in string interpolations and path literals, and option
+    /// paths set by the module, found while parsing
+    interpolation_references: Vec<ReferenceSite>,
 }
 
 impl NixParser {
@@ -34,6 +52,9 @@ impl NixParser {
             parser,
             context: ParserContext::new(),
             resolution_context: None,
+            bindings: Vec::new(),
+            module_range: Range::new(0, 0, 0, 0),
+            interpolation_references: Vec::new(),
         })
     }
 
@@ -108,7 +129,7 @@ impl NixParser {
             }
             // Handle path literals: ./path/to/file
             "path_expression" => {
-                self.process_path_literal(node, code, file_id, counter, symbols);
+                self.process_path_literal(node, code);
             }
             _ => {
                 // Recursively process child nodes for other node types
@@ -195,23 +216,30 @@ impl NixParser {
             // Look for documentation comment (preceding comment)
             let doc_comment = self.extract_doc_comment(&node, code);
 
-            let symbol = self.create_symbol(
+            let mut symbol = self.create_symbol(
                 counter.next_id(),
-                name,
+                name.clone(),
                 symbol_kind,
                 file_id,
                 range,
                 signature,
                 doc_comment,
             );
+            if let Some(value_node) = node.child_by_field_name("expression") {
+                failure_points(value_node, code, &mut symbol.annotations, 0);
+            }
 
             symbols.push(symbol);
+            self.bindings.push((name, range));
         }
 
         // Recursively process the value expression
         if let Some(value_node) = node.child_by_field_name("expression") {
             self.extract_symbols_from_node(value_node, code, file_id, counter, symbols);
         }
+        if identifier_node.is_some() {
+            self.bindings.pop();
+        }
     }
 
     /// Process attribute set: { name = value; }
@@ -276,7 +304,77 @@ impl NixParser {
     ) -> Vec<Symbol> {
         let mut symbols = Vec::new();
         let root_node = tree.root_node();
+
+        // The file itself, named by its module path during indexing, so
+        // path literals in other files can point at it
+        self.module_range = node_range(root_node);
+        let mut module_symbol = Symbol::new(
+            counter.next_id(),
+            MODULE_SYMBOL,
+            SymbolKind::Module,
+            file_id,
+            self.module_range,
+        );
+        module_symbol.scope_context = Some(crate::symbol::ScopeContext::Module);
+        module_symbol = module_symbol.with_visibility(Visibility::Public);
+        failure_points(root_node, code, &mut module_symbol.annotations, 0);
+        symbols.push(module_symbol);
+
         self.extract_symbols_from_node(root_node, code, file_id, counter, &mut symbols);
+
+        // Inputs declared as `inputs.<name>.url` have no binding of their
+        // own; those declared inside `inputs = { ... }` already do
+        for input in flake_inputs(root_node, code) {
+            let signature = input.signature();
+            match symbols
+                .iter_mut()
+                .find(|s| *s.name == *input.name && s.range == input.range)
+            {
+                Some(symbol) => symbol.signature = Some(signature.into()),
+                None => {
+                    let symbol = self.create_symbol(
+                        counter.next_id(),
+                        input.name,
+                        SymbolKind::Variable,
+                        file_id,
+                        input.range,
+                        Some(signature),
+                        None,
+                    );
+                    symbols.push(symbol);
+                }
+            }
+        }
+
+        // Options are named by their full path, so definitions in other
+        // modules can find them
+        let options = module_options(root_node, code);
+        for declaration in options.declarations {
+            let signature = declaration.signature();
+            let mut symbol = self.create_symbol(
+                counter.next_id(),
+                declaration.path,
+                SymbolKind::Field,
+                file_id,
+                declaration.range,
+                Some(signature),
+                declaration.description,
+            );
+            symbol.scope_context = Some(crate::symbol::ScopeContext::Module);
+            symbols.push(symbol);
+        }
+        self.interpolation_references
+            .extend(options.definitions.into_iter().map(|definition| {
+                let (context, context_range) = definition.binding;
+                ReferenceSite {
+                    context,
+                    context_range,
+                    name: definition.path,
+                    range: definition.range,
+                    receiver: None,
+                    option_path: true,
+                }
+            }));
         symbols
     }
 
@@ -308,7 +406,8 @@ impl NixParser {
     }
 
     /// Process string interpolation: "text ${expr} more text"
-    /// Extract symbols from interpolated expressions
+    /// Record the names used in interpolated expressions as references from
+    /// the enclosing binding, and extract symbols from them
     fn process_string_interpolation(
         &mut self,
         node: Node,
@@ -321,12 +420,79 @@ impl NixParser {
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
             if child.kind() == "interpolation" {
+                if let Some(expression) = child.child_by_field_name("expression") {
+                    self.record_interpolation_references(expression, code);
+                }
                 // Process the expression inside ${}
                 self.extract_symbols_from_node(child, code, file_id, counter, symbols);
             }
         }
     }
 
+    /// Record references for the names an interpolated expression uses.
+    ///
+    /// A variable counts when the resolution context knows it (a let
+    /// binding, parameter, or rec attribute), which leaves out builtins and
+    /// free names. An attribute selection like `pkgs.hello` references the
+    /// selected attribute `hello`, plus its head `pkgs` if that is known.
+    /// Nested strings are left to their own interpolation pass.
+    fn record_interpolation_references(&mut self, node: Node, code: &str) {
+        let Some((context, context_range)) = self.bindings.last().cloned() else {
+            return;
+        };
+        let mut sites = Vec::new();
+        self.collect_interpolated_names(node, code, &mut sites);
+        self.interpolation_references
+            .extend(sites.into_iter().map(|(name, range)| ReferenceSite {
+                context: context.clone(),
+                context_range,
+                name,
+                range,
+                receiver: None,
+                option_path: false,
+            }));
+    }
+
+    fn collect_interpolated_names(&self, node: Node, code: &str, sites: &mut Vec<(String, Range)>) {
+        let is_known = |name: &str| {
+            self.resolution_context
+                .as_ref()
+                .is_some_and(|ctx| ctx.resolve_nix_symbol(name).is_some())
+        };
+        match node.kind() {
+            "variable_expression" => {
+                if let Some(identifier) = node.child_by_field_name("name") {
+                    let name = &code[identifier.byte_range()];
+                    if is_known(name) {
+                        sites.push((name.to_string(), node_range(identifier)));
+                    }
+                }
+            }
+            "select_expression" => {
+                if let Some(attr) = node
+                    .child_by_field_name("attrpath")
+                    .and_then(|path| path.children_by_field_name("attr", &mut path.walk()).last())
+                    .filter(|attr| attr.kind() == "identifier")
+                {
+                    sites.push((code[attr.byte_range()].to_string(), node_range(node)));
+                }
+                if let Some(head) = node.child_by_field_name("expression") {
+                    self.collect_interpolated_names(head, code, sites);
+                }
+                if let Some(default) = node.child_by_field_name("default") {
+                    self.collect_interpolated_names(default, code, sites);
+                }
+            }
+            "string_expression" | "indented_string_expression" => {}
+            _ => {
+                let mut cursor = node.walk();
+                for child in node.named_children(&mut cursor) {
+                    self.collect_interpolated_names(child, code, sites);
+                }
+            }
+        }
+    }
+
     /// Process lambda function: param: body or { param1, param2 }: body
     /// Extract function parameters and process body with proper scoping
     fn process_lambda_function(
@@ -397,36 +563,27 @@ impl NixParser {
     }
 
     /// Process path literal: ./path/to/file or /absolute/path
-    /// These are Nix-specific constructs for file references
-    fn process_path_literal(
-        &mut self,
-        node: Node,
-        code: &str,
-        file_id: FileId,
-        counter: &mut SymbolCounter,
-        symbols: &mut Vec<Symbol>,
-    ) {
-        let path_str = code[node.byte_range()].to_string();
-        let ts_range = node.range();
-        let range = Range::new(
-            ts_range.start_point.row as u32,
-            ts_range.start_point.column as u16,
-            ts_range.end_point.row as u32,
-            ts_range.end_point.column as u16,
-        );
-
-        // Create a constant symbol for the path literal
-        let symbol = self.create_symbol(
-            counter.next_id(),
-            format!("path_{}", symbols.len()), // Generate unique name for path
-            SymbolKind::Constant,
-            file_id,
-            range,
-            Some(format!("path = {path_str}")),
-            None,
-        );
-
-        symbols.push(symbol);
+    /// Record it as a reference from the enclosing binding, or from the file's
+    /// module outside any binding. The pipeline resolves the path to the
+    /// referenced file's module symbol.
+    fn process_path_literal(&mut self, node: Node, code: &str) {
+        // Interpolated paths (`./${name}.nix`) can't be resolved statically
+        if code[node.byte_range()].contains("${") {
+            return;
+        }
+        let (context, context_range) = self
+            .bindings
+            .last()
+            .cloned()
+            .unwrap_or_else(|| (MODULE_SYMBOL.to_string(), self.module_range));
+        self.interpolation_references.push(ReferenceSite {
+            context,
+            context_range,
+            name: code[node.byte_range()].to_string(),
+            range: node_range(node),
+            receiver: None,
+            option_path: false,
+        });
     }
 
     /// Enhanced recursive attribute set processing with forward references
@@ -494,22 +651,27 @@ impl NixParser {
                 Some(format!("{name} = <value>"))
             };
 
-            let symbol = self.create_symbol(
+            let mut symbol = self.create_symbol(
                 symbol_id,
-                name,
+                name.clone(),
                 symbol_kind,
                 file_id,
                 range,
                 signature,
                 None,
             );
+            if let Some(value_node) = binding_node.child_by_field_name("expression") {
+                failure_points(value_node, code, &mut symbol.annotations, 0);
+            }
 
             symbols.push(symbol);
 
             // Process the value expression
+            self.bindings.push((name, range));
             if let Some(value_node) = binding_node.child_by_field_name("expression") {
                 self.extract_symbols_from_node(value_node, code, file_id, counter, symbols);
             }
+            self.bindings.pop();
         }
 
         // Exit recursive scope
@@ -532,10 +694,21 @@ impl NixParser {
             ctx.enter_let_scope();
         }
 
-        // Process let bindings first
+        // Process let bindings first; the grammar groups them in a binding_set
         let mut cursor = node.walk();
-        for child in node.children(&mut cursor) {
-            if child.kind() == "binding" {
+        let members: Vec<Node> = node
+            .children(&mut cursor)
+            .filter(|child| child.kind() == "binding_set")
+            .flat_map(|set| {
+                let mut set_cursor = set.walk();
+                set.children(&mut set_cursor).collect::<Vec<_>>()
+            })
+            .collect();
+        for child in members {
+            if child.kind() != "binding" {
+                // `inherit` clauses
+                self.extract_symbols_from_node(child, code, file_id, counter, symbols);
+            } else {
                 self.process_binding(child, code, file_id, counter, symbols);
 
                 // Add binding to let context for the 'in' expression
@@ -554,7 +727,7 @@ impl NixParser {
 
         // Process the 'in' expression with bindings available
         for child in node.children(&mut cursor) {
-            if child.kind() != "binding" && child.kind() != "let" {
+            if child.kind() != "binding_set" && child.kind() != "let" {
                 self.extract_symbols_from_node(child, code, file_id, counter, symbols);
             }
         }
@@ -578,17 +751,22 @@ impl LanguageParser for NixParser {
         self.context = ParserContext::new();
         // Initialize resolution context for advanced scoping
         self.resolution_context = Some(NixResolutionContext::new(file_id));
+        self.bindings.clear();
+        self.interpolation_references.clear();
 
         match self.parser.parse(code, None) {
             Some(tree) => {
                 if tree.root_node().has_error() {
                     // Log parsing errors but continue with partial results
-                    eprintln!("Nix parsing errors detected in file {}", file_id.0);
+                    tracing::warn!(
+                        file_id = file_id.0,
+                        "Nix syntax errors, continuing with partial results"
+                    );
                 }
                 self.walk_tree(tree, code, file_id, symbol_counter)
             }
             None => {
-                eprintln!("Failed to parse Nix file {}", file_id.0);
+                tracing::warn!(file_id = file_id.0, "failed to parse Nix file");
                 Vec::new()
             }
         }
@@ -601,31 +779,11 @@ impl LanguageParser for NixParser {
 
     /// Extract documentation comment for Nix (typically # comments)
     fn extract_doc_comment(&self, node: &Node, code: &str) -> Option<String> {
-        // Look for preceding comment lines that start with #
-        let start_line = node.start_position().row;
-
-        if start_line == 0 {
-            return None;
-        }
-
-        let lines: Vec<&str> = code.lines().collect();
-        let mut doc_lines = Vec::new();
-
-        // Look backwards for consecutive comment lines
-        for i in (0..start_line).rev() {
-            let line = lines.get(i)?.trim();
-            if line.starts_with('#') {
-                // Remove # and trim whitespace
-                let comment_text = line.trim_start_matches('#').trim();
-                doc_lines.insert(0, comment_text.to_string());
-            } else if line.is_empty() {
-                // Empty lines are okay, continue looking
-                continue;
-            } else {
-                // Non-comment, non-empty line - stop looking
-                break;
-            }
-        }
+        let doc_lines: Vec<&str> = self
+            .preceding_comments(node, code, &|text| text.starts_with('#'))
+            .into_iter()
+            .map(|text| text.trim_start_matches('#').trim())
+            .collect();
 
         if doc_lines.is_empty() {
             None
@@ -641,11 +799,20 @@ impl LanguageParser for NixParser {
         Vec::new()
     }
 
-    /// Find method calls with receiver information
-    fn find_method_calls(&mut self, _code: &str) -> Vec<MethodCall> {
-        // Nix doesn't have traditional method calls like OOP languages
-        // Function application is the primary mechanism
-        Vec::new()
+    /// Find function applications, from the binding they appear in
+    ///
+    /// Nix has no methods; `pkgs.lib.map f xs` is an application of the
+    /// function `map`, reached through `pkgs.lib`, which becomes the receiver.
+    /// Calls to well-known builtins and `lib` functions carry their category.
+    fn find_method_calls(&mut self, code: &str) -> Vec<MethodCall> {
+        let Some(tree) = self.parser.parse(code, None) else {
+            return Vec::new();
+        };
+        let root = tree.root_node();
+        let mut bindings = vec![(MODULE_SYMBOL.to_string(), node_range(root))];
+        let mut calls = Vec::new();
+        collect_calls(root, code, &mut bindings, &mut calls, 0);
+        calls
     }
 
     /// Find trait/interface implementations (not applicable to Nix)
@@ -654,6 +821,21 @@ impl LanguageParser for NixParser {
         Vec::new()
     }
 
+    /// Find bindings that only rename another value: `mkIf = lib.mkIf;`,
+    /// `pkg = hello;`
+    ///
+    /// `inherit` needs no alias: it creates no symbol of its own, so
+    /// references to an inherited name already resolve to the original
+    /// binding.
+    fn find_aliases<'a>(&mut self, code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        let Some(tree) = self.parser.parse(code, None) else {
+            return Vec::new();
+        };
+        let mut aliases = Vec::new();
+        collect_aliases(tree.root_node(), code, &mut aliases, 0);
+        aliases
+    }
+
     /// Find type usage (not applicable to Nix)
     fn find_uses<'a>(&mut self, _code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
         // Nix is dynamically typed - no explicit type usage
@@ -666,10 +848,36 @@ impl LanguageParser for NixParser {
         Vec::new()
     }
 
-    /// Find import statements in Nix code
-    fn find_imports(&mut self, _code: &str, _file_id: FileId) -> Vec<crate::parsing::Import> {
-        // TODO: Implement import detection for Nix (import statements, with expressions)
-        Vec::new()
+    /// References from string interpolations and path literals, recorded by
+    /// the last `parse`
+    fn find_references(&mut self, _code: &str) -> Vec<ReferenceSite> {
+        std::mem::take(&mut self.interpolation_references)
+    }
+
+    /// Find the files a NixOS module pulls in through `imports`:
+    /// `imports = [ ./hardware.nix ../common ];`
+    ///
+    /// Only path literals count. Module values like
+    /// `inputs.disko.nixosModules.default` and interpolated paths name no
+    /// file that can be found statically. The pipeline resolves each path to
+    /// the module path of the file it points at.
+    fn find_imports(&mut self, code: &str, file_id: FileId) -> Vec<crate::parsing::Import> {
+        let Some(tree) = self.parser.parse(code, None) else {
+            return Vec::new();
+        };
+        let mut paths = Vec::new();
+        collect_module_imports(tree.root_node(), code, &mut paths, 0);
+        paths
+            .into_iter()
+            .map(|(path, range)| crate::parsing::Import {
+                path: path.to_string(),
+                alias: None,
+                file_id,
+                is_glob: false,
+                is_type_only: false,
+                range: Some(range),
+            })
+            .collect()
     }
 
     /// Get the language this parser handles
@@ -678,6 +886,240 @@ impl LanguageParser for NixParser {
     }
 }
 
+/// Function applications under `node`, with the innermost enclosing binding
+/// as caller
+fn collect_calls(
+    node: Node,
+    code: &str,
+    bindings: &mut Vec<(String, Range)>,
+    calls: &mut Vec<MethodCall>,
+    depth: usize,
+) {
+    if !check_recursion_depth(depth, node) {
+        return;
+    }
+
+    let binding = (node.kind() == "binding")
+        .then(|| node.child_by_field_name("attrpath")?.child(0))
+        .flatten();
+    if let Some(identifier) = binding {
+        bindings.push((
+            code[identifier.byte_range()].to_string(),
+            node_range(identifier),
+        ));
+    }
+
+    // `f a b` nests as `(f a) b`; only the outermost application is a call
+    let is_partial = node.parent().is_some_and(|parent| {
+        parent.kind() == "apply_expression"
+            && parent
+                .child_by_field_name("function")
+                .is_some_and(|function| function.id() == node.id())
+    });
+    if node.kind() == "apply_expression" && !is_partial {
+        if let Some(call) = application(node, code, bindings.last()) {
+            calls.push(call);
+        }
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_calls(child, code, bindings, calls, depth + 1);
+    }
+
+    if binding.is_some() {
+        bindings.pop();
+    }
+}
+
+/// Bindings under `node` whose value is a bare name or an attribute
+/// selection, with the name of the selected value
+fn collect_aliases<'a>(
+    node: Node,
+    code: &'a str,
+    aliases: &mut Vec<(&'a str, &'a str, Range)>,
+    depth: usize,
+) {
+    if !check_recursion_depth(depth, node) {
+        return;
+    }
+
+    if node.kind() == "binding" {
+        let identifier = node
+            .child_by_field_name("attrpath")
+            .filter(|attrpath| attrpath.named_child_count() == 1)
+            .and_then(|attrpath| attrpath.child(0));
+        let target = node
+            .child_by_field_name("expression")
+            .and_then(|value| match value.kind() {
+                "variable_expression" => value.child_by_field_name("name"),
+                "select_expression" => {
+                    let attrpath = value.child_by_field_name("attrpath")?;
+                    let last = attrpath
+                        .children_by_field_name("attr", &mut attrpath.walk())
+                        .last()?;
+                    (last.kind() == "identifier").then_some(last)
+                }
+                _ => None,
+            });
+        if let (Some(identifier), Some(target)) = (identifier, target) {
+            aliases.push((
+                &code[identifier.byte_range()],
+                &code[target.byte_range()],
+                node_range(identifier),
+            ));
+        }
+    }
+
+    for child in node.children(&mut node.walk()) {
+        collect_aliases(child, code, aliases, depth + 1);
+    }
+}
+
+/// Path literals listed by the `imports` bindings under `node`
+fn collect_module_imports<'a>(
+    node: Node,
+    code: &'a str,
+    paths: &mut Vec<(&'a str, Range)>,
+    depth: usize,
+) {
+    if !check_recursion_depth(depth, node) {
+        return;
+    }
+
+    if node.kind() == "binding" {
+        let is_imports = node
+            .child_by_field_name("attrpath")
+            .is_some_and(|attrpath| &code[attrpath.byte_range()] == "imports");
+        if is_imports {
+            if let Some(value) = node.child_by_field_name("expression") {
+                import_paths(value, code, paths, depth + 1);
+            }
+            return;
+        }
+    }
+
+    for child in node.children(&mut node.walk()) {
+        collect_module_imports(child, code, paths, depth + 1);
+    }
+}
+
+/// Static path literals in the value of `imports`, including lists joined
+/// with `++` and entries added by `lib.optional`
+fn import_paths<'a>(node: Node, code: &'a str, paths: &mut Vec<(&'a str, Range)>, depth: usize) {
+    if !check_recursion_depth(depth, node) {
+        return;
+    }
+
+    match node.kind() {
+        "path_expression" => {
+            let path = &code[node.byte_range()];
+            if !path.contains("${") {
+                paths.push((path, node_range(node)));
+            }
+        }
+        // An inline module: only its own `imports` name files to import
+        "binding" => collect_module_imports(node, code, paths, depth),
+        _ => {
+            for child in node.children(&mut node.walk()) {
+                import_paths(child, code, paths, depth + 1);
+            }
+        }
+    }
+}
+
+/// Call for an outermost application, if its function is a name or an
+/// attribute selection
+fn application(node: Node, code: &str, caller: Option<&(String, Range)>) -> Option<MethodCall> {
+    let (caller, caller_range) = caller?;
+    let mut function = node.child_by_field_name("function")?;
+    while function.kind() == "apply_expression" {
+        function = function.child_by_field_name("function")?;
+    }
+
+    let (qualifier, name) = match function.kind() {
+        "variable_expression" => {
+            let name = function.child_by_field_name("name")?;
+            (None, &code[name.byte_range()])
+        }
+        "select_expression" => {
+            let head = function.child_by_field_name("expression")?;
+            let attrpath = function.child_by_field_name("attrpath")?;
+            let attrs: Vec<Node> = attrpath
+                .children_by_field_name("attr", &mut attrpath.walk())
+                .collect();
+            let (last, init) = attrs.split_last()?;
+            if last.kind() != "identifier" {
+                return None;
+            }
+            let mut qualifier = code[head.byte_range()].to_string();
+            for attr in init {
+                qualifier.push('.');
+                qualifier.push_str(&code[attr.byte_range()]);
+            }
+            (Some(qualifier), &code[last.byte_range()])
+        }
+        _ => return None,
+    };
+
+    let mut call = MethodCall::new(caller, name, node_range(node)).with_caller_range(*caller_range);
+    if let Some(category) = classify(qualifier.as_deref(), name) {
+        call = call.with_category(category.as_str());
+    }
+    if let Some(qualifier) = &qualifier {
+        call = call.with_receiver(qualifier);
+    }
+    Some(call)
+}
+
+/// `assert` conditions and `throw`/`abort` calls evaluated as part of
+/// `node`, stopping at nested bindings, which carry their own
+fn failure_points(node: Node, code: &str, points: &mut Vec<Annotation>, depth: usize) {
+    if !check_recursion_depth(depth, node) {
+        return;
+    }
+    let text = |n: Node| &code[n.byte_range()];
+    let point = |kind, message: Node| {
+        let start = node.start_position();
+        Annotation::new(kind, text(message), start.row as u32, start.column as u16)
+    };
+    match node.kind() {
+        "binding" => return,
+        "assert_expression" => {
+            if let Some(condition) = node.child_by_field_name("condition") {
+                points.push(point(AnnotationKind::Assert, condition));
+            }
+        }
+        "apply_expression" => {
+            let kind =
+                node.child_by_field_name("function")
+                    .and_then(|function| match text(function) {
+                        "throw" | "builtins.throw" => Some(AnnotationKind::Throw),
+                        "abort" | "builtins.abort" => Some(AnnotationKind::Abort),
+                        _ => None,
+                    });
+            if let (Some(kind), Some(argument)) = (kind, node.child_by_field_name("argument")) {
+                points.push(point(kind, argument));
+            }
+        }
+        _ => {}
+    }
+    for child in node.children(&mut node.walk()) {
+        failure_points(child, code, points, depth + 1);
+    }
+}
+
+/// Range of a tree-sitter node
+pub(super) fn node_range(node: Node) -> Range {
+    let ts_range = node.range();
+    Range::new(
+        ts_range.start_point.row as u32,
+        ts_range.start_point.column as u16,
+        ts_range.end_point.row as u32,
+        ts_range.end_point.column as u16,
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -945,6 +1387,313 @@ in { inherit name greeting complex; }
         );
     }
 
+    #[test]
+    fn test_string_interpolation_references() {
+        let mut parser = NixParser::new().expect("Failed to create NixParser");
+        let mut counter = SymbolCounter::new();
+        let file_id = FileId(1);
+
+        let code = r#"
+{ pkgs }:
+let
+  name = "world";
+  greeting = "Hello ${name} from ${toString unknown}";
+  run = "${pkgs.hello}/bin/hello";
+in { inherit greeting run; }
+"#;
+
+        parser.parse(code, file_id, &mut counter);
+        let references = parser.find_references(code);
+        let edges: Vec<(&str, &str)> = references
+            .iter()
+            .map(|site| (site.context.as_str(), site.name.as_str()))
+            .collect();
+
+        // Builtins and free names are left out
+        assert_eq!(
+            edges,
+            vec![("greeting", "name"), ("run", "hello"), ("run", "pkgs")]
+        );
+        assert_eq!(references[1].range.start_line, 5);
+        assert_eq!(references[1].range.start_column, 11);
+        assert!(
+            parser.find_references(code).is_empty(),
+            "references are handed out once per parse"
+        );
+    }
+
+    #[test]
+    fn test_call_classification() {
+        let mut parser = NixParser::new().expect("Failed to create NixParser");
+
+        let code = r#"
+{ pkgs, lib }:
+{
+  src = builtins.fetchGit { url = "https://example.com/repo.git"; };
+  tarball = fetchTarball "https://example.com/x.tar.gz";
+  names = lib.attrNames (pkgs.lib.mapAttrs (n: v: v) { });
+  custom = mySet.map toString [ ];
+}
+"#;
+
+        let calls = parser.find_method_calls(code);
+        let summary: Vec<(&str, &str, Option<&str>)> = calls
+            .iter()
+            .map(|c| (c.caller.as_str(), c.method_name.as_str(), c.category))
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                ("src", "fetchGit", Some("fetcher")),
+                ("tarball", "fetchTarball", Some("fetcher")),
+                ("names", "attrNames", Some("attrset")),
+                ("names", "mapAttrs", Some("attrset")),
+                ("custom", "map", None),
+            ]
+        );
+        assert_eq!(calls[0].receiver.as_deref(), Some("builtins"));
+        assert_eq!(calls[3].receiver.as_deref(), Some("pkgs.lib"));
+        assert!(calls[1].receiver.is_none());
+    }
+
+    #[test]
+    fn test_find_aliases() {
+        let mut parser = NixParser::new().expect("Failed to create NixParser");
+
+        let code = r#"
+{ pkgs, lib }:
+let
+  mkIf' = lib.mkIf;
+  compiler = pkgs.gcc;
+  hello = pkgs.hello;
+  pkg = compiler;
+  inherit (lib) optional;
+  version = "1.0";
+in { inherit pkg; meta.broken = lib.broken; }
+"#;
+
+        let aliases: Vec<(&str, &str)> = parser
+            .find_aliases(code)
+            .into_iter()
+            .map(|(alias, target, _)| (alias, target))
+            .collect();
+
+        // Same-name renames are dropped when relationships are built
+        assert_eq!(
+            aliases,
+            vec![
+                ("mkIf'", "mkIf"),
+                ("compiler", "gcc"),
+                ("hello", "hello"),
+                ("pkg", "compiler"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_module_imports() {
+        let mut parser = NixParser::new().expect("Failed to create NixParser");
+
+        let code = r#"
+{ config, lib, inputs, modulesPath, ... }:
+{
+  imports = [
+    ./hardware-configuration.nix
+    ../common
+    (modulesPath + "/installer/scan/not-detected.nix")
+    inputs.disko.nixosModules.default
+    ./hosts/${config.networking.hostName}.nix
+    { imports = [ ./users.nix ]; services.openssh.banner = ./banner.txt; }
+  ] ++ lib.optional config.services.xserver.enable ./desktop.nix;
+
+  environment.etc."motd".source = ./motd;
+}
+"#;
+
+        let paths: Vec<String> = parser
+            .find_imports(code, FileId(1))
+            .into_iter()
+            .map(|import| import.path)
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                "./hardware-configuration.nix",
+                "../common",
+                "./users.nix",
+                "./desktop.nix",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_flake_inputs_get_symbols() {
+        let mut parser = NixParser::new().expect("Failed to create NixParser");
+        let mut counter = SymbolCounter::new();
+
+        let code = r#"
+{
+  inputs.nixpkgs.url = "github:NixOS/nixpkgs/nixos-unstable";
+  inputs.home-manager = {
+    url = "github:nix-community/home-manager";
+    inputs.nixpkgs.follows = "nixpkgs";
+  };
+  inputs = {
+    pkgs.follows = "nixpkgs";
+    secrets = { url = "git+ssh://git@example.com/secrets"; flake = false; };
+  };
+
+  outputs = { self, nixpkgs, ... }: { };
+}
+"#;
+
+        let symbols = parser.parse(code, FileId(1), &mut counter);
+        let inputs: Vec<(&str, &str)> = symbols
+            .iter()
+            .filter_map(|s| Some((s.name.as_ref(), s.signature.as_deref()?)))
+            .filter(|(_, signature)| signature.contains("<flake input"))
+            .collect();
+        assert_eq!(
+            inputs,
+            vec![
+                ("pkgs", "pkgs = <flake input follows nixpkgs>"),
+                (
+                    "secrets",
+                    "secrets = <flake input git+ssh://git@example.com/secrets>"
+                ),
+                (
+                    "nixpkgs",
+                    "nixpkgs = <flake input github:NixOS/nixpkgs/nixos-unstable>"
+                ),
+                (
+                    "home-manager",
+                    "home-manager = <flake input github:nix-community/home-manager>"
+                ),
+            ]
+        );
+
+        // Without `outputs` an attribute set is no flake
+        let symbols = parser.parse(
+            r#"{ inputs.nixpkgs.url = "github:NixOS/nixpkgs"; }"#,
+            FileId(1),
+            &mut counter,
+        );
+        assert!(!symbols.iter().any(|s| s.name.as_ref() == "nixpkgs"));
+    }
+
+    #[test]
+    fn test_module_options_get_symbols_and_definitions() {
+        let mut parser = NixParser::new().expect("Failed to create NixParser");
+        let mut counter = SymbolCounter::new();
+
+        let code = r#"
+{ config, lib, ... }:
+{
+  options.services.codanna.port = lib.mkOption {
+    type = lib.types.port;
+    description = "Port to listen on.";
+  };
+  config = lib.mkIf config.services.codanna.enable {
+    services.codanna.port = 9090;
+    home-manager.users.alice.programs.git.enable = true;
+  };
+}
+"#;
+
+        let symbols = parser.parse(code, FileId(1), &mut counter);
+        let option = symbols
+            .iter()
+            .find(|s| s.name.as_ref() == "services.codanna.port")
+            .expect("option symbol");
+        assert_eq!(option.kind, SymbolKind::Field);
+        assert_eq!(
+            option.signature.as_deref(),
+            Some("services.codanna.port = <option types.port>")
+        );
+        assert_eq!(option.doc_comment.as_deref(), Some("Port to listen on."));
+
+        // Definitions come from the binding symbol that makes them
+        let definitions: Vec<(String, String)> = parser
+            .find_references(code)
+            .into_iter()
+            .filter(|site| site.option_path)
+            .map(|site| {
+                assert!(
+                    symbols
+                        .iter()
+                        .any(|s| *s.name == site.context && s.range == site.context_range)
+                );
+                (site.context, site.name)
+            })
+            .collect();
+        assert_eq!(
+            definitions,
+            vec![
+                ("services".to_string(), "services.codanna.port".to_string()),
+                (
+                    "home-manager".to_string(),
+                    "home-manager.users.alice".to_string()
+                ),
+                (
+                    "home-manager".to_string(),
+                    "programs.git.enable".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_failure_point_annotations() {
+        let mut parser = NixParser::new().expect("Failed to create NixParser");
+        let mut counter = SymbolCounter::new();
+
+        let code = r#"{ lib, version }:
+assert lib.versionAtLeast version "2.0";
+{
+  check = x:
+    if x > 0 then x else throw "x must be positive";
+  nested = {
+    inner = builtins.abort "unreachable";
+  };
+  fine = 1;
+}
+"#;
+
+        let symbols = parser.parse(code, FileId(1), &mut counter);
+        let annotations = |name: &str| {
+            symbols
+                .iter()
+                .find(|s| s.name.as_ref() == name)
+                .map(|s| s.annotations.clone())
+                .unwrap_or_default()
+        };
+
+        let module = annotations(MODULE_SYMBOL);
+        assert_eq!(module.len(), 1, "nested bindings carry their own");
+        assert_eq!(module[0].kind, AnnotationKind::Assert);
+        assert_eq!(
+            module[0].message.as_ref(),
+            "lib.versionAtLeast version \"2.0\""
+        );
+        assert_eq!((module[0].line, module[0].column), (1, 0));
+
+        let check = annotations("check");
+        assert_eq!(check.len(), 1);
+        assert_eq!(check[0].kind, AnnotationKind::Throw);
+        assert_eq!(check[0].message.as_ref(), "\"x must be positive\"");
+        assert_eq!(check[0].line, 4);
+
+        assert!(annotations("nested").is_empty());
+        assert_eq!(annotations("inner")[0].kind, AnnotationKind::Abort);
+        assert!(annotations("fine").is_empty());
+
+        // Round-trips through the single-line storage form
+        let stored = check[0].to_string();
+        assert_eq!(stored, "throw 4:25 \"x must be positive\"");
+        assert_eq!(Annotation::parse(&stored), Some(check[0].clone()));
+    }
+
     #[test]
     fn test_path_literal_parsing() {
         let mut parser = NixParser::new().expect("Failed to create NixParser");
@@ -984,15 +1733,29 @@ in { inherit relativePath absolutePath; }
             "absolutePath should be a variable"
         );
 
-        // Should also extract path literal constants
-        let path_constants: Vec<_> = symbols
+        // Path literals are references from their binding, not symbols
+        assert!(
+            !symbols
+                .iter()
+                .any(|s| s.kind == SymbolKind::Constant && s.name.starts_with("path_")),
+            "Path literals should not become constants"
+        );
+        let references = parser.find_references(code);
+        let edges: Vec<(&str, &str)> = references
             .iter()
-            .filter(|s| s.kind == SymbolKind::Constant && s.name.starts_with("path_"))
+            .map(|site| (site.context.as_str(), site.name.as_str()))
             .collect();
-        assert!(
-            !path_constants.is_empty(),
-            "Should extract path literal constants"
+        assert_eq!(
+            edges,
+            vec![
+                ("relativePath", "./config/default.nix"),
+                ("absolutePath", "/etc/nixos/configuration.nix"),
+            ]
         );
+
+        // The file has a module symbol for other files' paths to point at
+        let module = symbols.iter().find(|s| s.kind == SymbolKind::Module);
+        assert_eq!(module.map(|s| s.name.as_ref()), Some("<module>"));
     }
 
     #[test]
@@ -1097,4 +1860,29 @@ in { inherit documented add; }
             "Should contain function doc text"
         );
     }
+
+    #[test]
+    fn test_trailing_comment_is_not_doc() {
+        let mut parser = NixParser::new().expect("Failed to create NixParser");
+        let mut counter = SymbolCounter::new();
+
+        let code = r#"
+let
+  # Port to listen on
+  port = 8080; # default for development
+  host = "localhost";
+in { inherit port host; }
+"#;
+
+        let symbols = parser.parse(code, FileId(1), &mut counter);
+        let doc = |name: &str| {
+            symbols
+                .iter()
+                .find(|s| s.name.as_ref() == name)
+                .and_then(|s| s.doc_comment.as_deref())
+        };
+
+        assert_eq!(doc("port"), Some("Port to listen on"));
+        assert_eq!(doc("host"), None, "trailing comment belongs to port");
+    }
 }
@@ -9,7 +9,7 @@
 //! Handles Nix's unique scoping patterns including let-in expressions, with statements,
 //! recursive attribute sets, and functional composition.
 
-use crate::parsing::{InheritanceResolver, ResolutionScope, ScopeLevel, ScopeType};
+use crate::parsing::{InheritanceResolver, ResolutionScope, ScopeLevel, ScopeType, TypeRef};
 use crate::{FileId, SymbolId};
 use std::any::Any;
 use std::collections::HashMap;
@@ -408,6 +408,9 @@ pub struct NixInheritanceResolver {
     /// Track function composition relationships
     /// Maps composed function to its components
     composition_relationships: HashMap<SymbolId, Vec<SymbolId>>,
+
+    /// Qualified names of symbols added through [`InheritanceResolver`]
+    names: HashMap<SymbolId, String>,
 }
 
 impl NixInheritanceResolver {
@@ -499,12 +502,20 @@ impl NixInheritanceResolver {
     }
 }
 
+impl NixInheritanceResolver {
+    fn type_ref(&self, id: SymbolId) -> TypeRef {
+        match self.names.get(&id) {
+            Some(name) => TypeRef::new(id, name.clone()),
+            None => TypeRef::new(id, format!("symbol_{}", id.0)),
+        }
+    }
+}
+
 impl InheritanceResolver for NixInheritanceResolver {
-    fn add_inheritance(&mut self, child: String, parent: String, kind: &str) {
-        // For Nix, we map names to a simple ID system for compatibility
-        // In a real implementation, this would use the symbol table to map names to IDs
-        let child_id = SymbolId(child.len() as u32); // Simplified mapping
-        let parent_id = SymbolId(parent.len() as u32); // Simplified mapping
+    fn add_inheritance(&mut self, child: TypeRef, parent: TypeRef, kind: &str) {
+        let (child_id, parent_id) = (child.id, parent.id);
+        self.names.insert(child.id, child.qualified_name);
+        self.names.insert(parent.id, parent.qualified_name);
 
         match kind {
             "merge" => self.add_merge_relationship(child_id, parent_id),
@@ -514,34 +525,27 @@ impl InheritanceResolver for NixInheritanceResolver {
         }
     }
 
-    fn resolve_method(&self, _type_name: &str, _method: &str) -> Option<String> {
+    fn resolve_method(&self, _ty: &TypeRef, _method: &str) -> Option<TypeRef> {
         // Nix doesn't have traditional methods, so we return None
         None
     }
 
-    fn get_inheritance_chain(&self, type_name: &str) -> Vec<String> {
-        // For Nix, convert the name to a simple ID and get the chain
-        let symbol_id = SymbolId(type_name.len() as u32); // Simplified mapping
-        let chain = self.get_full_inheritance_chain(symbol_id);
-
-        // Convert back to string representation
-        chain
+    fn get_inheritance_chain(&self, ty: &TypeRef) -> Vec<TypeRef> {
+        self.get_full_inheritance_chain(ty.id)
             .into_iter()
-            .map(|id| format!("symbol_{}", id.0))
+            .map(|id| self.type_ref(id))
             .collect()
     }
 
-    fn is_subtype(&self, child: &str, parent: &str) -> bool {
-        let child_id = SymbolId(child.len() as u32);
-        let parent_id = SymbolId(parent.len() as u32);
-        self.check_inheritance(child_id, parent_id)
+    fn is_subtype(&self, child: &TypeRef, parent: &TypeRef) -> bool {
+        self.check_inheritance(child.id, parent.id)
     }
 
-    fn add_type_methods(&mut self, _type_name: String, _methods: Vec<String>) {
+    fn add_type_methods(&mut self, _ty: TypeRef, _methods: Vec<String>) {
         // Nix doesn't have traditional type methods, so this is a no-op
     }
 
-    fn get_all_methods(&self, _type_name: &str) -> Vec<String> {
+    fn get_all_methods(&self, _ty: &TypeRef) -> Vec<String> {
         // Nix doesn't have traditional methods, return empty vector
         Vec::new()
     }
@@ -0,0 +1,24 @@
+//! Overloadable operator sites
+//!
+//! `a + b` runs `Add::add` when `a` is a Rust type that implements it,
+//! `__add__` in Python, and `$a[$k]` runs `offsetGet` on a PHP `ArrayAccess`.
+//! Without operand types the parser can't tell which overload runs, or if
+//! any does, so the parse stage reports each site as a low-confidence call
+//! that resolves only to a method of that name.
+
+use crate::Range;
+
+/// An operator that may run a user-defined overload
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperatorCall {
+    /// Enclosing function or method, named as its symbol is
+    pub context: String,
+    /// Range of the enclosing function or method
+    pub context_range: Range,
+    /// The operator as written: `+`, `==`, `[]`
+    pub operator: &'static str,
+    /// Method an overload of the operator defines: `add`, `__eq__`, `offsetGet`
+    pub method: &'static str,
+    /// Where the operator is applied
+    pub range: Range,
+}
@@ -31,6 +31,53 @@ pub trait LanguageParser: Send + Sync {
     /// - JavaScript/TypeScript: JSDoc `/** */`
     fn extract_doc_comment(&self, node: &Node, code: &str) -> Option<String>;
 
+    /// Text of the doc comments directly preceding `node`, in source order
+    ///
+    /// Walks the comment siblings before `node`, nearest first, while
+    /// `is_doc` accepts them. A node that opens its parent (the first
+    /// binding of a set, a declaration inside an export) has its comments
+    /// before the parent, so the walk climbs there. A comment that trails
+    /// code on the same line (`a = 1; # note`) belongs to that code and ends
+    /// the walk. Only the comments are visited, whatever the size of the file.
+    fn preceding_comments<'c>(
+        &self,
+        node: &Node,
+        code: &'c str,
+        is_doc: &dyn Fn(&str) -> bool,
+    ) -> Vec<&'c str> {
+        let mut comments = Vec::new();
+        let mut current = *node;
+        loop {
+            match current.prev_sibling() {
+                Some(sibling) if sibling.kind().ends_with("comment") => {
+                    let Some(text) = code.get(sibling.byte_range()) else {
+                        break;
+                    };
+                    let trailing = sibling.prev_sibling().is_some_and(|before| {
+                        !before.kind().ends_with("comment")
+                            && before.end_position().row == sibling.start_position().row
+                    });
+                    if trailing || !is_doc(text) {
+                        break;
+                    }
+                    comments.push(text);
+                    current = sibling;
+                }
+                Some(_) => break,
+                None => match current.parent() {
+                    Some(parent)
+                        if comments.is_empty() && parent.start_byte() == current.start_byte() =>
+                    {
+                        current = parent;
+                    }
+                    _ => break,
+                },
+            }
+        }
+        comments.reverse();
+        comments
+    }
+
     /// Find function/method calls in the code
     ///
     /// Returns tuples of (caller_name, callee_name, range)
@@ -68,6 +115,33 @@ pub trait LanguageParser: Send + Sync {
         Vec::new()
     }
 
+    /// Find declared aliases: `type NodeId = u32`, Python `Alias = RealClass`
+    ///
+    /// Returns tuples of (alias_name, target_name, range), where range is the
+    /// alias declaration. Import renames (`use a::B as C`, `import { A as B }`)
+    /// are not reported here; import bindings already resolve them.
+    /// Zero-cost: Returns string slices into the source code
+    fn find_aliases<'a>(&mut self, _code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        Vec::new()
+    }
+
+    /// Find raised errors: Python `raise ValueError(..)`, `throw new
+    /// HttpError()`, Rust `Err(ConfigError::Missing)` and `Result<_, E>` returns
+    ///
+    /// Returns tuples of (context_name, error_type, range). Owned, as contexts
+    /// like Python's `Class.method` are built rather than sliced.
+    fn find_throws(&mut self, _code: &str) -> Vec<(String, String, Range)> {
+        Vec::new()
+    }
+
+    /// Find handled errors: `except ValueError`, `catch (HttpError $e)`
+    ///
+    /// Returns tuples of (context_name, error_type, range), like
+    /// [`find_throws`](Self::find_throws). Default returns empty.
+    fn find_catches(&mut self, _code: &str) -> Vec<(String, String, Range)> {
+        Vec::new()
+    }
+
     /// Find type usage (in fields, parameters, returns)
     ///
     /// Returns tuples of (context_name, used_type, range)
@@ -119,6 +193,43 @@ pub trait LanguageParser: Send + Sync {
     fn find_inherent_methods(&mut self, _code: &str) -> Vec<(String, String, Range)> {
         Vec::new()
     }
+
+    /// Find type instantiations: `new Foo()`, Python `Foo()`, Rust `Foo { .. }`
+    ///
+    /// Each becomes a reference to the type and, when a constructor runs, a
+    /// call to it. Default returns empty.
+    fn find_instantiations(&mut self, _code: &str) -> Vec<crate::parsing::Instantiation> {
+        Vec::new()
+    }
+
+    /// Find generics named with type arguments: `Vec<Config>`,
+    /// `new Map<string, Session>()`, `parse::<Config>()`
+    ///
+    /// Each becomes a reference to the generic carrying its arguments.
+    /// Default returns empty.
+    fn find_generic_instantiations(
+        &mut self,
+        _code: &str,
+    ) -> Vec<crate::parsing::GenericInstantiation> {
+        Vec::new()
+    }
+
+    /// Find operators that may run an overload: `a + b` (Rust `add`, Python
+    /// `__add__`), `$a[$k]` (PHP `offsetGet`)
+    ///
+    /// Each becomes a low-confidence call to the overload's method. Default
+    /// returns empty, for languages without operator overloading.
+    fn find_operator_calls(&mut self, _code: &str) -> Vec<crate::parsing::OperatorCall> {
+        Vec::new()
+    }
+
+    /// References the parser tracks itself, beyond the shared reference pass
+    ///
+    /// For sites the shared pass can't see, such as names used inside Nix
+    /// string interpolations of plain bindings. Default returns empty.
+    fn find_references(&mut self, _code: &str) -> Vec<crate::parsing::ReferenceSite> {
+        Vec::new()
+    }
 }
 
 /// Trait for creating language parsers
@@ -2,6 +2,9 @@
 
 use crate::parsing::LanguageBehavior;
 use crate::parsing::behavior_state::{BehaviorState, StatefulBehavior};
+use crate::parsing::control_flow::BranchNodeKinds;
+use crate::parsing::injection::InjectionRule;
+use crate::parsing::references::ReferenceNodeKinds;
 use crate::{FileId, Visibility};
 use std::path::{Path, PathBuf};
 use tree_sitter::Language;
@@ -35,6 +38,68 @@ impl Default for PhpBehavior {
     }
 }
 
+/// Reference pass node kinds. `$variables` are always local to PHP
+/// functions and never referenced; functions, classes, and constants are
+/// `name` nodes.
+static REFERENCE_NODES: ReferenceNodeKinds = ReferenceNodeKinds {
+    identifiers: &["name"],
+    bindings: &[],
+    members: &[
+        ("variable_name", None),
+        ("namespace_name", None),
+        ("member_access_expression", Some("name")),
+        ("member_call_expression", Some("name")),
+        ("nullsafe_member_access_expression", Some("name")),
+        ("nullsafe_member_call_expression", Some("name")),
+        ("scoped_property_access_expression", Some("name")),
+    ],
+    paths: &[],
+    fields: &[
+        ("member_access_expression", "object", "name"),
+        ("nullsafe_member_access_expression", "object", "name"),
+    ],
+    containers: &[
+        ("class_declaration", "name"),
+        ("trait_declaration", "name"),
+        ("enum_declaration", "name"),
+    ],
+    this: &["$this"],
+};
+
+/// Conditional-region node kinds. `xor` evaluates both sides and is left
+/// out of the short-circuit operators.
+static BRANCH_NODES: BranchNodeKinds = BranchNodeKinds {
+    branches: &[
+        ("if_statement", &["body", "alternative"]),
+        ("switch_statement", &["body"]),
+        ("match_expression", &["body"]),
+        ("while_statement", &["body"]),
+        ("for_statement", &["body", "update"]),
+        ("foreach_statement", &["body"]),
+        ("conditional_expression", &["body", "alternative"]),
+        ("catch_clause", &[]),
+        ("anonymous_function", &[]),
+        ("arrow_function", &[]),
+    ],
+    short_circuit: &["&&", "||", "and", "or", "??"],
+};
+
+/// The HTML template around PHP tags, and the `<script>` blocks in it
+static INJECTIONS: &[InjectionRule] = &[
+    InjectionRule {
+        language: "html",
+        node_kind: "text",
+        callees: &[],
+        delimiters: None,
+    },
+    InjectionRule {
+        language: "javascript",
+        node_kind: "text",
+        callees: &[],
+        delimiters: Some(("<script", "</script>")),
+    },
+];
+
 impl LanguageBehavior for PhpBehavior {
     fn language_id(&self) -> crate::parsing::registry::LanguageId {
         crate::parsing::registry::LanguageId::new("php")
@@ -61,7 +126,7 @@ impl LanguageBehavior for PhpBehavior {
         if signature.contains("private ") {
             Visibility::Private
         } else if signature.contains("protected ") {
-            Visibility::Module // Protected in PHP = Module visibility
+            Visibility::Protected
         } else if signature.contains("public ") {
             Visibility::Public
         } else {
@@ -95,6 +160,26 @@ impl LanguageBehavior for PhpBehavior {
         self.language.clone()
     }
 
+    fn reference_node_kinds(&self) -> Option<&'static ReferenceNodeKinds> {
+        Some(&REFERENCE_NODES)
+    }
+
+    fn branch_node_kinds(&self) -> Option<&'static BranchNodeKinds> {
+        Some(&BRANCH_NODES)
+    }
+
+    fn injection_rules(&self) -> &'static [InjectionRule] {
+        INJECTIONS
+    }
+
+    fn import_declaration_kinds(&self) -> &'static [&'static str] {
+        &["namespace_use_declaration"]
+    }
+
+    fn call_node_kinds(&self) -> &'static [(&'static str, &'static str)] {
+        &[("function_call_expression", "function")]
+    }
+
     fn module_path_from_file(
         &self,
         file_path: &Path,
@@ -346,7 +431,7 @@ mod tests {
         );
         assert_eq!(
             behavior.parse_visibility("protected function baz()"),
-            Visibility::Module
+            Visibility::Protected
         );
 
         // Default visibility (public in PHP)
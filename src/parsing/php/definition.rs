@@ -32,8 +32,12 @@ impl LanguageDefinition for PhpLanguage {
         ]
     }
 
+    fn interpreters(&self) -> &'static [&'static str] {
+        &["php"]
+    }
+
     fn create_parser(&self, _settings: &Settings) -> IndexResult<Box<dyn LanguageParser>> {
-        let parser = PhpParser::new().map_err(|e| IndexError::General(e.to_string()))?;
+        let parser = PhpParser::new().map_err(|e| IndexError::parser_init("php", e))?;
         Ok(Box::new(parser))
     }
 
@@ -12,9 +12,11 @@
 use crate::parsing::Import;
 use crate::parsing::parser::check_recursion_depth;
 use crate::parsing::{
-    Language, LanguageParser, MethodCall, NodeTracker, NodeTrackingState, ParserContext, ScopeType,
+    Language, LanguageParser, MethodCall, NodeTracker, NodeTrackingState, OperatorCall,
+    ParserContext, ScopeType,
 };
-use crate::types::SymbolCounter;
+use crate::symbol::ScopeContext;
+use crate::types::{SymbolCounter, compact_string};
 use crate::{FileId, Range, Symbol, SymbolKind};
 use std::any::Any;
 use thiserror::Error;
@@ -335,6 +337,39 @@ impl PhpParser {
                 self.context.set_current_function(saved_function);
                 self.context.set_current_class(saved_class);
             }
+            "enum_declaration" => {
+                self.register_handled_node(node.kind(), node.kind_id());
+                let enum_name = node
+                    .child_by_field_name("name")
+                    .map(|name| code[name.byte_range()].to_string());
+
+                if let Some(symbol) = self.process_enum(node, code, file_id, counter) {
+                    symbols.push(symbol);
+                }
+
+                // Enums hold cases, constants and methods like a class does
+                self.context.enter_scope(ScopeType::Class);
+
+                let saved_function = self.context.current_function().map(|s| s.to_string());
+                let saved_class = self.context.current_class().map(|s| s.to_string());
+
+                if let Some(name) = enum_name {
+                    self.context.set_current_class(Some(name));
+                }
+
+                self.process_children(node, code, file_id, symbols, counter, depth);
+
+                self.context.exit_scope();
+
+                self.context.set_current_function(saved_function);
+                self.context.set_current_class(saved_class);
+            }
+            "enum_case" => {
+                self.register_handled_node(node.kind(), node.kind_id());
+                if let Some(symbol) = self.process_enum_case(node, code, file_id, counter) {
+                    symbols.push(symbol);
+                }
+            }
             "property_declaration" => {
                 self.register_handled_node(node.kind(), node.kind_id());
                 if let Some(symbol) = self.process_property(node, code, file_id, counter) {
@@ -652,6 +687,69 @@ impl PhpParser {
         Some(symbol)
     }
 
+    /// Process an enum declaration node
+    fn process_enum(
+        &mut self,
+        node: Node,
+        code: &str,
+        file_id: FileId,
+        counter: &mut SymbolCounter,
+    ) -> Option<Symbol> {
+        let name_node = node.child_by_field_name("name")?;
+        let name = &code[name_node.byte_range()];
+
+        let id = counter.next_id();
+
+        let mut symbol = Symbol::new(
+            id,
+            name,
+            SymbolKind::Enum,
+            file_id,
+            self.node_to_range(node),
+        );
+        symbol.scope_context = Some(self.context.current_scope_context());
+        symbol.doc_comment = self.extract_doc_comment(&node, code).map(Into::into);
+
+        // Signature is everything before the body: `enum Status: string implements HasLabel`
+        let signature = match node.child_by_field_name("body") {
+            Some(body) => code[node.start_byte()..body.start_byte()].trim(),
+            None => &code[node.byte_range()],
+        };
+        symbol.signature = Some(signature.into());
+
+        Some(symbol)
+    }
+
+    /// Process an enum case node (`case Pending = 'pending';`)
+    fn process_enum_case(
+        &mut self,
+        node: Node,
+        code: &str,
+        file_id: FileId,
+        counter: &mut SymbolCounter,
+    ) -> Option<Symbol> {
+        let name_node = node.child_by_field_name("name")?;
+        let name = &code[name_node.byte_range()];
+
+        let id = counter.next_id();
+
+        let mut symbol = Symbol::new(
+            id,
+            name,
+            SymbolKind::Constant,
+            file_id,
+            self.node_to_range(node),
+        );
+        // Scoped to the enum so `Status::Pending` addresses this case
+        symbol.scope_context = Some(ScopeContext::ClassMember {
+            class_name: self.context.current_class().map(compact_string),
+        });
+        symbol.doc_comment = self.extract_doc_comment(&node, code).map(Into::into);
+        symbol.signature = Some(code[node.byte_range()].trim_end_matches(';').into());
+
+        Some(symbol)
+    }
+
     /// Process a property declaration node
     fn process_property(
         &mut self,
@@ -745,7 +843,8 @@ impl PhpParser {
                 | "function_definition"
                 | "method_declaration"
                 | "interface_declaration"
-                | "trait_declaration" => return false,
+                | "trait_declaration"
+                | "enum_declaration" => return false,
                 "program" => return true,
                 _ => parent = p.parent(),
             }
@@ -889,37 +988,33 @@ impl LanguageParser for PhpParser {
     }
 
     fn extract_doc_comment(&self, node: &Node, code: &str) -> Option<String> {
-        // Look for a comment node immediately before this node
-        if let Some(prev) = node.prev_sibling() {
-            if prev.kind() == "comment" {
-                let comment_text = &code[prev.byte_range()];
-                // PHP doc comments start with /** or //
-                if comment_text.starts_with("/**") {
-                    // Remove /** and */ and clean up
-                    let cleaned = comment_text
-                        .strip_prefix("/**")
-                        .and_then(|s| s.strip_suffix("*/"))
-                        .map(|s| {
-                            s.lines()
-                                .map(|line| line.trim().trim_start_matches('*').trim())
-                                .filter(|line| !line.is_empty())
-                                .collect::<Vec<_>>()
-                                .join("\n")
-                        });
-                    return cleaned;
-                } else if comment_text.starts_with("//") {
-                    // Single line comment
-                    return Some(
-                        comment_text
-                            .strip_prefix("//")
-                            .unwrap_or("")
-                            .trim()
-                            .to_string(),
-                    );
-                }
-            }
+        // PHP doc comments start with /** or //; only the nearest one counts
+        let comments = self.preceding_comments(node, code, &|text| {
+            text.starts_with("/**") || text.starts_with("//")
+        });
+        let comment_text = *comments.last()?;
+        if comment_text.starts_with("/**") {
+            // Remove /** and */ and clean up
+            comment_text
+                .strip_prefix("/**")
+                .and_then(|s| s.strip_suffix("*/"))
+                .map(|s| {
+                    s.lines()
+                        .map(|line| line.trim().trim_start_matches('*').trim())
+                        .filter(|line| !line.is_empty())
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                })
+        } else {
+            // Single line comment
+            Some(
+                comment_text
+                    .strip_prefix("//")
+                    .unwrap_or("")
+                    .trim()
+                    .to_string(),
+            )
         }
-        None
     }
 
     fn find_calls<'a>(&mut self, code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
@@ -940,6 +1035,53 @@ impl LanguageParser for PhpParser {
             .collect()
     }
 
+    fn find_throws(&mut self, code: &str) -> Vec<(String, String, Range)> {
+        let tree = match self.parser.parse(code, None) {
+            Some(tree) => tree,
+            None => return Vec::new(),
+        };
+
+        let mut throws = Vec::new();
+        self.extract_errors_from_node(
+            tree.root_node(),
+            code,
+            None,
+            "throw_expression",
+            &mut throws,
+            0,
+        );
+        throws
+    }
+
+    fn find_catches(&mut self, code: &str) -> Vec<(String, String, Range)> {
+        let tree = match self.parser.parse(code, None) {
+            Some(tree) => tree,
+            None => return Vec::new(),
+        };
+
+        let mut catches = Vec::new();
+        self.extract_errors_from_node(
+            tree.root_node(),
+            code,
+            None,
+            "catch_clause",
+            &mut catches,
+            0,
+        );
+        catches
+    }
+
+    fn find_operator_calls(&mut self, code: &str) -> Vec<OperatorCall> {
+        let tree = match self.parser.parse(code, None) {
+            Some(tree) => tree,
+            None => return Vec::new(),
+        };
+
+        let mut calls = Vec::new();
+        self.extract_operator_calls_from_node(tree.root_node(), code, None, &mut calls, 0);
+        calls
+    }
+
     fn find_implementations<'a>(&mut self, code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
         let tree = match self.parser.parse(code, None) {
             Some(tree) => tree,
@@ -1079,6 +1221,134 @@ impl PhpParser {
         }
     }
 
+    /// Collects subscripts, which call the `ArrayAccess` methods when the
+    /// subscripted value is an object: `offsetGet` to read, `offsetSet` to
+    /// assign, `offsetExists` under `isset` and `offsetUnset` under `unset`
+    fn extract_operator_calls_from_node<'a>(
+        &self,
+        node: Node,
+        code: &'a str,
+        current_context: Option<(&'a str, Range)>,
+        calls: &mut Vec<OperatorCall>,
+        depth: usize,
+    ) {
+        if !check_recursion_depth(depth, node) {
+            return;
+        }
+        let current_context = match node.kind() {
+            "function_definition" | "method_declaration" => node
+                .child_by_field_name("name")
+                .map(|name| (&code[name.byte_range()], self.node_to_range(node)))
+                .or(current_context),
+            _ => current_context,
+        };
+
+        if let (Some((context, context_range)), "subscript_expression") =
+            (current_context, node.kind())
+        {
+            let parent = node.parent();
+            let method = match parent.map(|parent| parent.kind()) {
+                Some("assignment_expression" | "augmented_assignment_expression")
+                    if parent.and_then(|p| p.child_by_field_name("left")) == Some(node) =>
+                {
+                    "offsetSet"
+                }
+                Some("unset_statement") => "offsetUnset",
+                Some("argument") if Self::is_isset_argument(parent, code) => "offsetExists",
+                _ => "offsetGet",
+            };
+            calls.push(OperatorCall {
+                context: context.to_string(),
+                context_range,
+                operator: "[]",
+                method,
+                range: self.node_to_range(node),
+            });
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.extract_operator_calls_from_node(child, code, current_context, calls, depth + 1);
+        }
+    }
+
+    /// Collects the classes named by `throw new Error(..)` (`site_kind`
+    /// `throw_expression`) or `catch (A | B $e)` (`catch_clause`), without
+    /// their namespace. Rethrowing a variable names no class.
+    fn extract_errors_from_node<'a>(
+        &self,
+        node: Node,
+        code: &'a str,
+        current_context: Option<&'a str>,
+        site_kind: &str,
+        errors: &mut Vec<(String, String, Range)>,
+        depth: usize,
+    ) {
+        if !check_recursion_depth(depth, node) {
+            return;
+        }
+        let current_context = match node.kind() {
+            "function_definition" | "method_declaration" => node
+                .child_by_field_name("name")
+                .map(|name| &code[name.byte_range()])
+                .or(current_context),
+            _ => current_context,
+        };
+
+        if let (Some(context), true) = (current_context, node.kind() == site_kind) {
+            let classes: Vec<Node> = match site_kind {
+                "throw_expression" => node
+                    .named_child(0)
+                    .filter(|thrown| thrown.kind() == "object_creation_expression")
+                    .and_then(|thrown| thrown.named_child(0))
+                    .into_iter()
+                    .collect(),
+                _ => node
+                    .child_by_field_name("type")
+                    .map(|types| {
+                        types
+                            .named_children(&mut types.walk())
+                            .filter_map(|named_type| named_type.named_child(0))
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            };
+            for class in classes {
+                if matches!(class.kind(), "name" | "qualified_name") {
+                    let name = &code[class.byte_range()];
+                    let name = name.rsplit('\\').next().unwrap_or(name);
+                    errors.push((
+                        context.to_string(),
+                        name.to_string(),
+                        self.node_to_range(class),
+                    ));
+                }
+            }
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.extract_errors_from_node(
+                child,
+                code,
+                current_context,
+                site_kind,
+                errors,
+                depth + 1,
+            );
+        }
+    }
+
+    /// Whether `argument` is passed to `isset(...)`
+    fn is_isset_argument(argument: Option<Node>, code: &str) -> bool {
+        argument
+            .and_then(|argument| argument.parent())
+            .and_then(|arguments| arguments.parent())
+            .filter(|call| call.kind() == "function_call_expression")
+            .and_then(|call| call.child_by_field_name("function"))
+            .is_some_and(|function| &code[function.byte_range()] == "isset")
+    }
+
     fn extract_implementations_from_node<'a>(
         &self,
         node: Node,
@@ -1234,6 +1504,7 @@ impl PhpParser {
                             is_glob: false,
                             file_id,
                             is_type_only: false,
+                            range: Some(Import::range_of(&node)),
                         });
                     }
                 }
@@ -1260,6 +1531,7 @@ impl PhpParser {
                         is_glob: false,
                         file_id,
                         is_type_only: false,
+                        range: Some(Import::range_of(&node)),
                     });
                 }
             }
@@ -1357,4 +1629,136 @@ $globalVar = 'test';
             "Should find globalVar"
         );
     }
+
+    #[test]
+    fn test_php_enum_cases() {
+        let code = r#"<?php
+enum Status: string
+{
+    case Pending = 'pending';
+    case Done = 'done';
+
+    public function label(): string
+    {
+        return $this === Status::Pending ? 'waiting' : 'finished';
+    }
+}
+"#;
+
+        let mut parser = PhpParser::new().unwrap();
+        let symbols = parser.parse(code, FileId(1), &mut SymbolCounter::new());
+
+        let status = symbols
+            .iter()
+            .find(|s| s.name.as_ref() == "Status")
+            .expect("Should find the enum");
+        assert_eq!(status.kind, SymbolKind::Enum);
+        assert_eq!(status.signature.as_deref(), Some("enum Status: string"));
+
+        let cases: Vec<_> = symbols
+            .iter()
+            .filter(|s| {
+                s.scope_context
+                    == Some(ScopeContext::ClassMember {
+                        class_name: Some("Status".into()),
+                    })
+                    && s.kind == SymbolKind::Constant
+            })
+            .map(|s| s.name.as_ref())
+            .collect();
+        assert_eq!(cases, vec!["Pending", "Done"]);
+    }
+
+    #[test]
+    fn test_php_throws_and_catches() {
+        let code = r#"<?php
+class Importer
+{
+    public function import(string $path): void
+    {
+        try {
+            $this->read($path);
+        } catch (\App\Errors\NotFound | ParseError $e) {
+            throw new ImportFailed($path, previous: $e);
+        } catch (Throwable $e) {
+            throw $e;
+        }
+    }
+}
+"#;
+
+        let mut parser = PhpParser::new().unwrap();
+        let pairs = |sites: Vec<(String, String, Range)>| -> Vec<(String, String)> {
+            sites
+                .into_iter()
+                .map(|(context, class, _)| (context, class))
+                .collect()
+        };
+        let pair = |class: &str| ("import".to_string(), class.to_string());
+
+        assert_eq!(pairs(parser.find_throws(code)), vec![pair("ImportFailed")]);
+        assert_eq!(
+            pairs(parser.find_catches(code)),
+            vec![pair("NotFound"), pair("ParseError"), pair("Throwable")]
+        );
+    }
+
+    #[test]
+    fn test_php_subscript_operator_calls() {
+        let code = r#"<?php
+function remember(Cache $cache, string $key)
+{
+    if (!isset($cache[$key])) {
+        $cache[$key] = load($key);
+    }
+    unset($cache['stale']);
+    return $cache[$key];
+}
+"#;
+
+        let mut parser = PhpParser::new().unwrap();
+        let calls: Vec<_> = parser
+            .find_operator_calls(code)
+            .into_iter()
+            .map(|call| (call.context, call.method))
+            .collect();
+
+        let remember = || "remember".to_string();
+        assert_eq!(
+            calls,
+            vec![
+                (remember(), "offsetExists"),
+                (remember(), "offsetSet"),
+                (remember(), "offsetUnset"),
+                (remember(), "offsetGet"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_php_doc_comment_nearest_and_own_line() {
+        let code = r#"<?php
+/** Not the doc */
+/**
+ * Formats a name
+ */
+function format_name() {}
+
+$limit = 10; // trailing note
+function local() {}
+"#;
+
+        let mut parser = PhpParser::new().unwrap();
+        let mut counter = SymbolCounter::new();
+        let symbols = parser.parse(code, FileId(1), &mut counter);
+        let doc = |name: &str| {
+            symbols
+                .iter()
+                .find(|s| s.name.as_ref() == name)
+                .and_then(|s| s.doc_comment.as_deref())
+        };
+
+        assert_eq!(doc("format_name"), Some("Formats a name"));
+        assert_eq!(doc("local"), None, "trailing comment belongs to $limit");
+    }
 }
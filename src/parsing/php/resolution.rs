@@ -7,7 +7,7 @@
 //! - Trait usage with precedence rules
 
 use crate::parsing::resolution::ImportBinding;
-use crate::parsing::{InheritanceResolver, ResolutionScope, ScopeLevel, ScopeType};
+use crate::parsing::{InheritanceResolver, ResolutionScope, ScopeLevel, ScopeType, TypeRef};
 use crate::{FileId, SymbolId};
 use std::collections::HashMap;
 
@@ -290,23 +290,23 @@ impl ResolutionScope for PhpResolutionContext {
 /// - Trait usage (use)
 #[derive(Clone)]
 pub struct PhpInheritanceResolver {
-    /// Maps class names to their parent class
-    class_extends: HashMap<String, String>,
+    /// Maps classes to their parent class
+    class_extends: HashMap<TypeRef, TypeRef>,
 
-    /// Maps class names to interfaces they implement
-    class_implements: HashMap<String, Vec<String>>,
+    /// Maps classes to interfaces they implement
+    class_implements: HashMap<TypeRef, Vec<TypeRef>>,
 
-    /// Maps class names to traits they use
-    class_uses_traits: HashMap<String, Vec<String>>,
+    /// Maps classes to traits they use
+    class_uses_traits: HashMap<TypeRef, Vec<TypeRef>>,
 
-    /// Maps interface names to interfaces they extend
-    interface_extends: HashMap<String, Vec<String>>,
+    /// Maps interfaces to interfaces they extend
+    interface_extends: HashMap<TypeRef, Vec<TypeRef>>,
 
     /// Maps classes/interfaces/traits to their methods
-    type_methods: HashMap<String, Vec<String>>,
+    type_methods: HashMap<TypeRef, Vec<String>>,
 
     /// Maps traits to their methods
-    trait_methods: HashMap<String, Vec<String>>,
+    trait_methods: HashMap<TypeRef, Vec<String>>,
 }
 
 impl Default for PhpInheritanceResolver {
@@ -328,33 +328,33 @@ impl PhpInheritanceResolver {
     }
 
     /// Add a class with its parent
-    pub fn add_class_extends(&mut self, class: String, parent: String) {
+    pub fn add_class_extends(&mut self, class: TypeRef, parent: TypeRef) {
         self.class_extends.insert(class, parent);
     }
 
     /// Add interfaces that a class implements
-    pub fn add_class_implements(&mut self, class: String, interfaces: Vec<String>) {
+    pub fn add_class_implements(&mut self, class: TypeRef, interfaces: Vec<TypeRef>) {
         self.class_implements.insert(class, interfaces);
     }
 
     /// Add traits that a class uses
-    pub fn add_class_uses(&mut self, class: String, traits: Vec<String>) {
+    pub fn add_class_uses(&mut self, class: TypeRef, traits: Vec<TypeRef>) {
         self.class_uses_traits.insert(class, traits);
     }
 
     /// Add interfaces that an interface extends
-    pub fn add_interface_extends(&mut self, interface: String, parents: Vec<String>) {
+    pub fn add_interface_extends(&mut self, interface: TypeRef, parents: Vec<TypeRef>) {
         self.interface_extends.insert(interface, parents);
     }
 
     /// Add methods to a trait
-    pub fn add_trait_methods(&mut self, trait_name: String, methods: Vec<String>) {
-        self.trait_methods.insert(trait_name, methods);
+    pub fn add_trait_methods(&mut self, trait_ref: TypeRef, methods: Vec<String>) {
+        self.trait_methods.insert(trait_ref, methods);
     }
 }
 
 impl InheritanceResolver for PhpInheritanceResolver {
-    fn add_inheritance(&mut self, child: String, parent: String, kind: &str) {
+    fn add_inheritance(&mut self, child: TypeRef, parent: TypeRef, kind: &str) {
         match kind {
             "extends" => {
                 self.class_extends.insert(child, parent);
@@ -373,7 +373,7 @@ impl InheritanceResolver for PhpInheritanceResolver {
         }
     }
 
-    fn resolve_method(&self, type_name: &str, method_name: &str) -> Option<String> {
+    fn resolve_method(&self, ty: &TypeRef, method_name: &str) -> Option<TypeRef> {
         // PHP method resolution order:
         // 1. Own methods
         // 2. Trait methods (in use order, later traits override earlier)
@@ -381,26 +381,26 @@ impl InheritanceResolver for PhpInheritanceResolver {
         // 4. Interface methods (though these are usually abstract)
 
         // 1. Check own methods
-        if let Some(methods) = self.type_methods.get(type_name) {
+        if let Some(methods) = self.type_methods.get(ty) {
             if methods.iter().any(|m| m == method_name) {
-                return Some(type_name.to_string());
+                return Some(ty.clone());
             }
         }
 
         // 2. Check trait methods
-        if let Some(traits) = self.class_uses_traits.get(type_name) {
+        if let Some(traits) = self.class_uses_traits.get(ty) {
             // In PHP, later traits override earlier ones
-            for trait_name in traits.iter().rev() {
-                if let Some(methods) = self.trait_methods.get(trait_name) {
+            for trait_ref in traits.iter().rev() {
+                if let Some(methods) = self.trait_methods.get(trait_ref) {
                     if methods.iter().any(|m| m == method_name) {
-                        return Some(trait_name.clone());
+                        return Some(trait_ref.clone());
                     }
                 }
             }
         }
 
         // 3. Check parent class
-        if let Some(parent) = self.class_extends.get(type_name) {
+        if let Some(parent) = self.class_extends.get(ty) {
             // Recursively check parent
             return self.resolve_method(parent, method_name);
         }
@@ -408,24 +408,23 @@ impl InheritanceResolver for PhpInheritanceResolver {
         None
     }
 
-    fn get_inheritance_chain(&self, type_name: &str) -> Vec<String> {
-        let mut chain = vec![type_name.to_string()];
+    fn get_inheritance_chain(&self, ty: &TypeRef) -> Vec<TypeRef> {
+        let mut chain = vec![ty.clone()];
         let mut visited = std::collections::HashSet::new();
-        visited.insert(type_name.to_string());
+        visited.insert(ty);
 
         // Add parent class chain
-        let mut current = type_name;
+        let mut current = ty;
         while let Some(parent) = self.class_extends.get(current) {
-            if visited.contains(parent) {
+            if !visited.insert(parent) {
                 break; // Prevent infinite loop
             }
             chain.push(parent.clone());
-            visited.insert(parent.clone());
             current = parent;
         }
 
         // Add implemented interfaces
-        if let Some(interfaces) = self.class_implements.get(type_name) {
+        if let Some(interfaces) = self.class_implements.get(ty) {
             for interface in interfaces {
                 if !visited.contains(interface) {
                     chain.push(interface.clone());
@@ -434,10 +433,10 @@ impl InheritanceResolver for PhpInheritanceResolver {
         }
 
         // Add used traits
-        if let Some(traits) = self.class_uses_traits.get(type_name) {
-            for trait_name in traits {
-                if !visited.contains(trait_name) {
-                    chain.push(trait_name.clone());
+        if let Some(traits) = self.class_uses_traits.get(ty) {
+            for trait_ref in traits {
+                if !visited.contains(trait_ref) {
+                    chain.push(trait_ref.clone());
                 }
             }
         }
@@ -445,7 +444,7 @@ impl InheritanceResolver for PhpInheritanceResolver {
         chain
     }
 
-    fn is_subtype(&self, child: &str, parent: &str) -> bool {
+    fn is_subtype(&self, child: &TypeRef, parent: &TypeRef) -> bool {
         // Check direct parent
         if let Some(direct_parent) = self.class_extends.get(child) {
             if direct_parent == parent {
@@ -459,14 +458,14 @@ impl InheritanceResolver for PhpInheritanceResolver {
 
         // Check interfaces
         if let Some(interfaces) = self.class_implements.get(child) {
-            if interfaces.iter().any(|i| i == parent) {
+            if interfaces.contains(parent) {
                 return true;
             }
         }
 
         // Check traits
         if let Some(traits) = self.class_uses_traits.get(child) {
-            if traits.iter().any(|t| t == parent) {
+            if traits.contains(parent) {
                 return true;
             }
         }
@@ -474,16 +473,16 @@ impl InheritanceResolver for PhpInheritanceResolver {
         false
     }
 
-    fn add_type_methods(&mut self, type_name: String, methods: Vec<String>) {
-        self.type_methods.insert(type_name, methods);
+    fn add_type_methods(&mut self, ty: TypeRef, methods: Vec<String>) {
+        self.type_methods.insert(ty, methods);
     }
 
-    fn get_all_methods(&self, type_name: &str) -> Vec<String> {
+    fn get_all_methods(&self, ty: &TypeRef) -> Vec<String> {
         let mut all_methods = Vec::new();
         let mut seen = std::collections::HashSet::new();
 
         // Add own methods
-        if let Some(methods) = self.type_methods.get(type_name) {
+        if let Some(methods) = self.type_methods.get(ty) {
             for method in methods {
                 if seen.insert(method.clone()) {
                     all_methods.push(method.clone());
@@ -492,9 +491,9 @@ impl InheritanceResolver for PhpInheritanceResolver {
         }
 
         // Add trait methods
-        if let Some(traits) = self.class_uses_traits.get(type_name) {
-            for trait_name in traits {
-                if let Some(methods) = self.trait_methods.get(trait_name) {
+        if let Some(traits) = self.class_uses_traits.get(ty) {
+            for trait_ref in traits {
+                if let Some(methods) = self.trait_methods.get(trait_ref) {
                     for method in methods {
                         if seen.insert(method.clone()) {
                             all_methods.push(method.clone());
@@ -505,7 +504,7 @@ impl InheritanceResolver for PhpInheritanceResolver {
         }
 
         // Add parent methods recursively
-        if let Some(parent) = self.class_extends.get(type_name) {
+        if let Some(parent) = self.class_extends.get(ty) {
             for method in self.get_all_methods(parent) {
                 if seen.insert(method.clone()) {
                     all_methods.push(method);
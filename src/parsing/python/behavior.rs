@@ -3,6 +3,10 @@
 use crate::parsing::LanguageBehavior;
 use crate::parsing::ResolutionScope;
 use crate::parsing::behavior_state::{BehaviorState, StatefulBehavior};
+use crate::parsing::control_flow::BranchNodeKinds;
+use crate::parsing::injection::InjectionRule;
+use crate::parsing::references::ReferenceNodeKinds;
+use crate::parsing::signature::{self, SignatureStyle};
 use crate::{FileId, Visibility};
 use std::path::{Path, PathBuf};
 use tree_sitter::Language;
@@ -77,6 +81,70 @@ impl Default for PythonBehavior {
     }
 }
 
+/// Reference pass node kinds. Any assignment inside a function binds a local,
+/// as Python does without a `global` statement. Both `self` and `cls` type
+/// attribute accesses with the enclosing class.
+static REFERENCE_NODES: ReferenceNodeKinds = ReferenceNodeKinds {
+    identifiers: &["identifier"],
+    bindings: &[
+        ("parameters", None),
+        ("lambda_parameters", None),
+        ("typed_parameter", None),
+        ("default_parameter", Some("name")),
+        ("typed_default_parameter", Some("name")),
+        ("assignment", Some("left")),
+        ("for_statement", Some("left")),
+        ("for_in_clause", Some("left")),
+        ("as_pattern", Some("alias")),
+    ],
+    members: &[
+        ("attribute", Some("attribute")),
+        ("keyword_argument", Some("name")),
+    ],
+    paths: &[],
+    fields: &[("attribute", "object", "attribute")],
+    containers: &[("class_definition", "name")],
+    this: &["self", "cls"],
+};
+
+/// Conditional-region node kinds. The `else` of a loop is its `alternative`
+/// and only runs when the loop is not broken out of.
+static BRANCH_NODES: BranchNodeKinds = BranchNodeKinds {
+    branches: &[
+        ("if_statement", &["consequence", "alternative"]),
+        ("for_statement", &["body", "alternative"]),
+        ("while_statement", &["body", "alternative"]),
+        ("match_statement", &["body"]),
+        ("except_clause", &[]),
+        ("lambda", &[]),
+    ],
+    short_circuit: &["and", "or"],
+};
+
+/// Browser scripts run through Selenium or Playwright
+/// (`driver.execute_script("...")`, `page.evaluate("...")`), and queries
+/// passed to DB-API cursors and pandas (`cursor.execute("SELECT ...")`)
+static INJECTIONS: &[InjectionRule] = &[
+    InjectionRule {
+        language: "javascript",
+        node_kind: "string",
+        callees: &["execute_script", "execute_async_script", "evaluate"],
+        delimiters: None,
+    },
+    InjectionRule {
+        language: "sql",
+        node_kind: "string",
+        callees: &[
+            "execute",
+            "executemany",
+            "executescript",
+            "read_sql",
+            "read_sql_query",
+        ],
+        delimiters: None,
+    },
+];
+
 impl LanguageBehavior for PythonBehavior {
     fn language_id(&self) -> crate::parsing::registry::LanguageId {
         crate::parsing::registry::LanguageId::new("python")
@@ -147,6 +215,12 @@ impl LanguageBehavior for PythonBehavior {
         "."
     }
 
+    // Blocks open with a trailing `:` rather than a brace
+    fn normalize_signature(&self, signature: &str, style: SignatureStyle) -> String {
+        let header = signature::strip_attributes(signature, &[]);
+        signature::finish(header.trim_end().trim_end_matches(':'), style)
+    }
+
     fn supports_traits(&self) -> bool {
         false // Python doesn't have traits, it has inheritance and mixins
     }
@@ -155,6 +229,15 @@ impl LanguageBehavior for PythonBehavior {
         false // Python methods are always on classes, not separate
     }
 
+    fn supertype_relation(&self, kind: crate::relationship::RelationKind) -> Option<&'static str> {
+        use crate::relationship::RelationKind;
+        // Base classes are stored as Implements; Python has only one kind
+        match kind {
+            RelationKind::Extends | RelationKind::Implements => Some("inherits"),
+            _ => None,
+        }
+    }
+
     fn format_path_as_module(&self, components: &[&str]) -> Option<String> {
         if components.is_empty() {
             None
@@ -167,6 +250,54 @@ impl LanguageBehavior for PythonBehavior {
         self.language.clone()
     }
 
+    fn reference_node_kinds(&self) -> Option<&'static ReferenceNodeKinds> {
+        Some(&REFERENCE_NODES)
+    }
+
+    fn branch_node_kinds(&self) -> Option<&'static BranchNodeKinds> {
+        Some(&BRANCH_NODES)
+    }
+
+    fn injection_rules(&self) -> &'static [InjectionRule] {
+        INJECTIONS
+    }
+
+    fn import_declaration_kinds(&self) -> &'static [&'static str] {
+        &[
+            "import_statement",
+            "import_from_statement",
+            "future_import_statement",
+        ]
+    }
+
+    fn call_node_kinds(&self) -> &'static [(&'static str, &'static str)] {
+        &[("call", "function")]
+    }
+
+    fn import_bindings(&self, import: &crate::parsing::Import) -> Vec<String> {
+        if import.is_glob {
+            return Vec::new();
+        }
+        if let Some(alias) = &import.alias {
+            return vec![alias.clone()];
+        }
+        // `from a import b` binds `b` but `import a.b` binds `a`, and both
+        // are recorded as "a.b"
+        let mut names: Vec<String> = Vec::new();
+        for name in [
+            import.path.rsplit('.').next(),
+            import.path.split('.').next(),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            if !name.is_empty() && !names.iter().any(|n| n == name) {
+                names.push(name.to_string());
+            }
+        }
+        names
+    }
+
     fn normalize_caller_name(&self, name: &str, file_id: FileId) -> String {
         if name == "<module>" {
             if let Some(module_path) = self.get_module_path_for_file(file_id) {
@@ -415,6 +546,7 @@ impl LanguageBehavior for PythonBehavior {
                 alias: import.alias.clone(),
                 is_glob: import.is_glob,
                 is_type_only: import.is_type_only,
+                range: import.range,
             });
 
             // 5. Lookup candidates by symbol name and match by module_path
@@ -700,6 +832,27 @@ mod tests {
         assert!(!behavior.supports_inherent_methods());
     }
 
+    #[test]
+    fn test_normalize_signature() {
+        let behavior = PythonBehavior::new();
+        let signature =
+            "@app.route(\"/users\")\ndef list_users(\n    page: int = 1,\n    size=20,\n) -> list:";
+
+        assert_eq!(
+            behavior.normalize_signature(signature, SignatureStyle::default()),
+            "def list_users(page: int = 1, size=20) -> list"
+        );
+        assert_eq!(
+            behavior.normalize_signature(
+                signature,
+                SignatureStyle {
+                    elide_defaults: true
+                }
+            ),
+            "def list_users(page: int, size) -> list"
+        );
+    }
+
     #[test]
     fn test_validate_node_kinds() {
         let behavior = PythonBehavior::new();
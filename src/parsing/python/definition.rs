@@ -30,8 +30,12 @@ impl LanguageDefinition for PythonLanguage {
         &["py", "pyi"]
     }
 
+    fn interpreters(&self) -> &'static [&'static str] {
+        &["python", "pypy", "uv"]
+    }
+
     fn create_parser(&self, _settings: &Settings) -> IndexResult<Box<dyn LanguageParser>> {
-        let parser = PythonParser::new().map_err(|e| IndexError::General(e.to_string()))?;
+        let parser = PythonParser::new().map_err(|e| IndexError::parser_init("python", e))?;
         Ok(Box::new(parser))
     }
 
@@ -14,16 +14,60 @@
 use crate::parsing::Import;
 use crate::parsing::parser::check_recursion_depth;
 use crate::parsing::{
-    HandledNode, Language, LanguageParser, MethodCall, NodeTracker, NodeTrackingState,
-    ParserContext, ScopeType,
+    HandledNode, Instantiation, Language, LanguageParser, MethodCall, NodeTracker,
+    NodeTrackingState, OperatorCall, ParserContext, ReferenceSite, ScopeType,
 };
-use crate::types::SymbolCounter;
+use crate::symbol::ScopeContext;
+use crate::types::{SymbolCounter, compact_string};
 use crate::{FileId, Range, Symbol, SymbolKind};
 use std::any::Any;
 use std::collections::HashSet;
 use thiserror::Error;
 use tree_sitter::{Node, Parser};
 
+/// Binary, comparison and augmented assignment operators with the special
+/// method each calls
+const OPERATOR_METHODS: &[(&str, &str)] = &[
+    ("+", "__add__"),
+    ("-", "__sub__"),
+    ("*", "__mul__"),
+    ("@", "__matmul__"),
+    ("/", "__truediv__"),
+    ("//", "__floordiv__"),
+    ("%", "__mod__"),
+    ("**", "__pow__"),
+    ("<<", "__lshift__"),
+    (">>", "__rshift__"),
+    ("&", "__and__"),
+    ("^", "__xor__"),
+    ("|", "__or__"),
+    ("==", "__eq__"),
+    ("!=", "__ne__"),
+    ("<", "__lt__"),
+    ("<=", "__le__"),
+    (">", "__gt__"),
+    (">=", "__ge__"),
+    ("in", "__contains__"),
+    ("not in", "__contains__"),
+    ("+=", "__iadd__"),
+    ("-=", "__isub__"),
+    ("*=", "__imul__"),
+    ("@=", "__imatmul__"),
+    ("/=", "__itruediv__"),
+    ("//=", "__ifloordiv__"),
+    ("%=", "__imod__"),
+    ("**=", "__ipow__"),
+    ("<<=", "__ilshift__"),
+    (">>=", "__irshift__"),
+    ("&=", "__iand__"),
+    ("^=", "__ixor__"),
+    ("|=", "__ior__"),
+];
+
+/// Prefix operators, which share symbols with binary ones
+const UNARY_OPERATOR_METHODS: &[(&str, &str)] =
+    &[("-", "__neg__"), ("+", "__pos__"), ("~", "__invert__")];
+
 /// Python-specific parsing errors
 #[derive(Error, Debug)]
 pub enum PythonParseError {
@@ -421,8 +465,15 @@ impl PythonParser {
             };
 
             let mut symbol = Symbol::new(symbol_id, name, kind, file_id, range);
-            // Set scope context - assignments are at the current scope level
-            symbol.scope_context = Some(context.current_scope_context());
+            // Set scope context - assignments are at the current scope level.
+            // Class attributes name their class so `Status.PENDING` can be
+            // addressed on its own
+            symbol.scope_context = Some(match context.current_scope_context() {
+                ScopeContext::ClassMember { class_name: None } => ScopeContext::ClassMember {
+                    class_name: context.current_class().map(compact_string),
+                },
+                scope => scope,
+            });
 
             // Try to extract the value as a simple signature
             if let Some(right) = node.child_by_field_name("right") {
@@ -971,6 +1022,7 @@ impl PythonParser {
                     file_id,
                     is_glob: false,
                     is_type_only: false,
+                    range: Some(Import::range_of(&node)),
                 });
             }
         }
@@ -995,6 +1047,7 @@ impl PythonParser {
                     file_id,
                     is_glob: true,
                     is_type_only: false,
+                    range: Some(Import::range_of(&node)),
                 });
             } else {
                 // Process individual imports
@@ -1053,6 +1106,7 @@ impl PythonParser {
                         file_id,
                         is_glob: false,
                         is_type_only: false,
+                        range: Some(Import::range_of(&node)),
                     });
                 }
                 "aliased_import" => {
@@ -1087,6 +1141,7 @@ impl PythonParser {
                 file_id,
                 is_glob: false,
                 is_type_only: false,
+                range: Some(Import::range_of(&node)),
             });
         }
     }
@@ -1256,6 +1311,136 @@ impl PythonParser {
         }
     }
 
+    /// Names whose attributes are enum members: `Enum`/`Flag` subclasses
+    /// declared in the file, and names brought in with `from ... import`
+    fn collect_enum_receivers<'a>(
+        &self,
+        node: Node,
+        code: &'a str,
+        enums: &mut HashSet<&'a str>,
+        imported: &mut HashSet<&'a str>,
+        depth: usize,
+    ) {
+        if !check_recursion_depth(depth, node) {
+            return;
+        }
+        match node.kind() {
+            "class_definition" => {
+                let is_enum = node
+                    .child_by_field_name("superclasses")
+                    .is_some_and(|bases| {
+                        bases.named_children(&mut bases.walk()).any(|base| {
+                            let base = match base.kind() {
+                                "attribute" => base.child_by_field_name("attribute"),
+                                "identifier" => Some(base),
+                                _ => None,
+                            };
+                            base.is_some_and(|base| {
+                                let base = &code[base.byte_range()];
+                                base.ends_with("Enum") || base.ends_with("Flag")
+                            })
+                        })
+                    });
+                if let Some(name) = node.child_by_field_name("name").filter(|_| is_enum) {
+                    enums.insert(&code[name.byte_range()]);
+                }
+            }
+            "import_from_statement" => {
+                let mut cursor = node.walk();
+                for name in node.children_by_field_name("name", &mut cursor) {
+                    let local = match name.kind() {
+                        "aliased_import" => name.child_by_field_name("alias"),
+                        _ => Some(name),
+                    };
+                    if let Some(local) = local {
+                        imported.insert(&code[local.byte_range()]);
+                    }
+                }
+            }
+            _ => {}
+        }
+        for child in node.children(&mut node.walk()) {
+            self.collect_enum_receivers(child, code, enums, imported, depth + 1);
+        }
+    }
+
+    /// Exception class named by a raised or caught expression: `Error`,
+    /// `errors.Timeout` or a call to either. Exception classes are CapWords.
+    fn exception_name<'c>(node: Node, code: &'c str) -> Option<&'c str> {
+        let name = match node.kind() {
+            "identifier" => node,
+            "attribute" => node.child_by_field_name("attribute")?,
+            "call" => return Self::exception_name(node.child_by_field_name("function")?, code),
+            _ => return None,
+        };
+        Some(&code[name.byte_range()]).filter(|name| name.starts_with(|c: char| c.is_uppercase()))
+    }
+
+    /// Whether `node` is part of a type annotation, up to its function
+    fn in_type_annotation(node: Node) -> bool {
+        let mut current = node.parent();
+        while let Some(ancestor) = current {
+            match ancestor.kind() {
+                "type" => return true,
+                "function_definition" | "block" => return false,
+                _ => current = ancestor.parent(),
+            }
+        }
+        false
+    }
+
+    /// Calls `visit` on every node inside a function, along with that
+    /// function named the way `process_function` names it and its range
+    fn walk_functions<'t>(
+        &self,
+        node: Node<'t>,
+        code: &str,
+        class_path: Option<&str>,
+        context: Option<&(String, Range)>,
+        visit: &mut dyn FnMut(Node<'t>, &(String, Range)),
+        depth: usize,
+    ) {
+        if !check_recursion_depth(depth, node) {
+            return;
+        }
+        let name = node
+            .child_by_field_name("name")
+            .map(|name| &code[name.byte_range()]);
+
+        match (node.kind(), name) {
+            ("class_definition", Some(name)) => {
+                let class_path = match class_path {
+                    Some(parent) => format!("{parent}.{name}"),
+                    None => name.to_string(),
+                };
+                for child in node.children(&mut node.walk()) {
+                    let class_path = Some(class_path.as_str());
+                    self.walk_functions(child, code, class_path, context, visit, depth + 1);
+                }
+                return;
+            }
+            ("function_definition", Some(name)) => {
+                let function = match class_path {
+                    Some(class_name) => format!("{class_name}.{name}"),
+                    None => name.to_string(),
+                };
+                let context = (function, self.node_to_range(node));
+                for child in node.children(&mut node.walk()) {
+                    self.walk_functions(child, code, class_path, Some(&context), visit, depth + 1);
+                }
+                return;
+            }
+            _ => {}
+        }
+
+        if let Some(context) = context {
+            visit(node, context);
+        }
+        for child in node.children(&mut node.walk()) {
+            self.walk_functions(child, code, class_path, context, visit, depth + 1);
+        }
+    }
+
     fn find_defines_in_node<'a>(
         parser: &mut PythonParser,
         node: Node,
@@ -1386,6 +1571,52 @@ impl LanguageParser for PythonParser {
         implementations
     }
 
+    fn find_aliases<'a>(&mut self, code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        let tree = match self.parser.parse(code, None) {
+            Some(tree) => tree,
+            None => return Vec::new(),
+        };
+
+        // Module-level `Alias = RealClass` or `Alias = module.RealClass`;
+        // inside functions the same assignment is just a local variable
+        let root_node = tree.root_node();
+        let mut aliases = Vec::new();
+        for statement in root_node.children(&mut root_node.walk()) {
+            if statement.kind() != "expression_statement" {
+                continue;
+            }
+            let Some(assignment) = statement.named_child(0) else {
+                continue;
+            };
+            if assignment.kind() != "assignment" {
+                continue;
+            }
+            let (Some(left), Some(right)) = (
+                assignment.child_by_field_name("left"),
+                assignment.child_by_field_name("right"),
+            ) else {
+                continue;
+            };
+            if left.kind() != "identifier" {
+                continue;
+            }
+            let target = match right.kind() {
+                "identifier" => right,
+                "attribute" => match right.child_by_field_name("attribute") {
+                    Some(attribute) => attribute,
+                    None => continue,
+                },
+                _ => continue,
+            };
+            aliases.push((
+                &code[left.byte_range()],
+                &code[target.byte_range()],
+                self.node_to_range(assignment),
+            ));
+        }
+        aliases
+    }
+
     fn find_uses<'a>(&mut self, _code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
         // Stub implementation - will be implemented in Phase 3
         Vec::new()
@@ -1417,6 +1648,238 @@ impl LanguageParser for PythonParser {
         imports
     }
 
+    fn find_references(&mut self, code: &str) -> Vec<ReferenceSite> {
+        let tree = match self.parser.parse(code, None) {
+            Some(tree) => tree,
+            None => return Vec::new(),
+        };
+
+        // The shared pass sees `Status.PENDING` only as an attribute access;
+        // report the member when the receiver is known to be an enum
+        let root_node = tree.root_node();
+        let mut enums = HashSet::new();
+        let mut imported = HashSet::new();
+        self.collect_enum_receivers(root_node, code, &mut enums, &mut imported, 0);
+
+        let mut sites = Vec::new();
+        if enums.is_empty() && imported.is_empty() {
+            return sites;
+        }
+        let mut visit = |node: Node, (function, function_range): &(String, Range)| {
+            if node.kind() != "attribute" {
+                return;
+            }
+            let (Some(object), Some(attribute)) = (
+                node.child_by_field_name("object"),
+                node.child_by_field_name("attribute"),
+            ) else {
+                return;
+            };
+            let receiver = &code[object.byte_range()];
+            let member = &code[attribute.byte_range()];
+            let is_constant = member
+                .chars()
+                .all(|c| c.is_uppercase() || c == '_' || c.is_numeric());
+            if object.kind() == "identifier"
+                && (enums.contains(receiver) || (imported.contains(receiver) && is_constant))
+            {
+                sites.push(ReferenceSite {
+                    context: function.clone(),
+                    context_range: *function_range,
+                    name: member.to_string(),
+                    range: self.node_to_range(attribute),
+                    // Imported names may be aliases; only local enums are
+                    // known by the name used here
+                    receiver: enums.contains(receiver).then(|| receiver.to_string()),
+                    option_path: false,
+                });
+            }
+        };
+        self.walk_functions(root_node, code, None, None, &mut visit, 0);
+        sites
+    }
+
+    fn find_instantiations(&mut self, code: &str) -> Vec<Instantiation> {
+        let tree = match self.parser.parse(code, None) {
+            Some(tree) => tree,
+            None => return Vec::new(),
+        };
+
+        // `Config(...)` with a CapWords name constructs a class by
+        // convention and runs its `__init__`, named like any other method
+        let mut instantiations = Vec::new();
+        let mut visit = |node: Node, (function, function_range): &(String, Range)| {
+            let Some(callee) = node
+                .child_by_field_name("function")
+                .filter(|callee| node.kind() == "call" && callee.kind() == "identifier")
+            else {
+                return;
+            };
+            let name = &code[callee.byte_range()];
+            let is_class = name.starts_with(|c: char| c.is_uppercase())
+                && name.chars().any(|c| c.is_lowercase());
+            if is_class {
+                instantiations.push(Instantiation {
+                    context: function.clone(),
+                    context_range: *function_range,
+                    type_name: name.to_string(),
+                    constructor: Some(format!("{name}.__init__")),
+                    range: self.node_to_range(callee),
+                });
+            }
+        };
+        self.walk_functions(tree.root_node(), code, None, None, &mut visit, 0);
+        instantiations
+    }
+
+    fn find_throws(&mut self, code: &str) -> Vec<(String, String, Range)> {
+        let tree = match self.parser.parse(code, None) {
+            Some(tree) => tree,
+            None => return Vec::new(),
+        };
+
+        // `raise ValueError("..")` and `raise errors.Timeout`; a bare `raise`
+        // or `raise error` re-raises something already counted
+        let mut throws = Vec::new();
+        let mut visit = |node: Node, (function, _): &(String, Range)| {
+            let raised = node
+                .named_child(0)
+                .filter(|_| node.kind() == "raise_statement")
+                .and_then(|raised| Self::exception_name(raised, code));
+            if let Some(raised) = raised {
+                throws.push((
+                    function.clone(),
+                    raised.to_string(),
+                    self.node_to_range(node),
+                ));
+            }
+        };
+        self.walk_functions(tree.root_node(), code, None, None, &mut visit, 0);
+        throws
+    }
+
+    fn find_catches(&mut self, code: &str) -> Vec<(String, String, Range)> {
+        let tree = match self.parser.parse(code, None) {
+            Some(tree) => tree,
+            None => return Vec::new(),
+        };
+
+        // `except ValueError:`, `except (KeyError, IndexError) as e:`
+        let mut catches = Vec::new();
+        let mut visit = |node: Node, (function, _): &(String, Range)| {
+            let Some(mut value) = node
+                .child_by_field_name("value")
+                .filter(|_| node.kind() == "except_clause")
+            else {
+                return;
+            };
+            // `except (KeyError, IndexError) as e:` parses as an as_pattern
+            if value.kind() == "as_pattern" {
+                match value.named_child(0) {
+                    Some(caught) => value = caught,
+                    None => return,
+                }
+            }
+            let caught: Vec<Node> = match value.kind() {
+                "tuple" | "parenthesized_expression" => {
+                    value.named_children(&mut value.walk()).collect()
+                }
+                _ => vec![value],
+            };
+            for caught in caught {
+                if let Some(name) = Self::exception_name(caught, code) {
+                    catches.push((
+                        function.clone(),
+                        name.to_string(),
+                        self.node_to_range(caught),
+                    ));
+                }
+            }
+        };
+        self.walk_functions(tree.root_node(), code, None, None, &mut visit, 0);
+        catches
+    }
+
+    fn find_operator_calls(&mut self, code: &str) -> Vec<OperatorCall> {
+        let tree = match self.parser.parse(code, None) {
+            Some(tree) => tree,
+            None => return Vec::new(),
+        };
+
+        let is_literal = |operand: Option<Node>| {
+            operand.is_some_and(|operand| {
+                matches!(
+                    operand.kind(),
+                    "integer" | "float" | "string" | "concatenated_string" | "true" | "false"
+                )
+            })
+        };
+        let lookup = |table: &[(&'static str, &'static str)], operator: Node| {
+            table
+                .iter()
+                .find(|(symbol, _)| *symbol == operator.kind())
+                .copied()
+        };
+
+        let mut calls = Vec::new();
+        let mut visit = |node: Node, (function, function_range): &(String, Range)| {
+            let mut sites = Vec::new();
+            match node.kind() {
+                "binary_operator" | "augmented_assignment" => {
+                    let literals = is_literal(node.child_by_field_name("left"))
+                        && is_literal(node.child_by_field_name("right"));
+                    if let Some(operator) =
+                        node.child_by_field_name("operator").filter(|_| !literals)
+                    {
+                        sites.extend(lookup(OPERATOR_METHODS, operator).map(|op| (op, operator)));
+                    }
+                }
+                "comparison_operator"
+                    if !node
+                        .named_children(&mut node.walk())
+                        .all(|operand| is_literal(Some(operand))) =>
+                {
+                    for operator in node.children_by_field_name("operators", &mut node.walk()) {
+                        sites.extend(lookup(OPERATOR_METHODS, operator).map(|op| (op, operator)));
+                    }
+                }
+                "unary_operator" => {
+                    if let Some(operator) = node.child_by_field_name("operator") {
+                        sites.extend(
+                            lookup(UNARY_OPERATOR_METHODS, operator).map(|op| (op, operator)),
+                        );
+                    }
+                }
+                // `list[int]` in an annotation is a type, not an item lookup
+                "subscript" if !Self::in_type_annotation(node) => {
+                    let parent = node.parent();
+                    let method = match parent.map(|parent| parent.kind()) {
+                        Some("assignment")
+                            if parent.and_then(|p| p.child_by_field_name("left")) == Some(node) =>
+                        {
+                            "__setitem__"
+                        }
+                        Some("delete_statement") => "__delitem__",
+                        _ => "__getitem__",
+                    };
+                    sites.push((("[]", method), node));
+                }
+                _ => {}
+            }
+            for ((operator, method), site) in sites {
+                calls.push(OperatorCall {
+                    context: function.clone(),
+                    context_range: *function_range,
+                    operator,
+                    method,
+                    range: self.node_to_range(site),
+                });
+            }
+        };
+        self.walk_functions(tree.root_node(), code, None, None, &mut visit, 0);
+        calls
+    }
+
     fn find_variable_types<'a>(&mut self, code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
         let tree = match self.parser.parse(code, None) {
             Some(tree) => tree,
@@ -2104,6 +2567,199 @@ class Dog(Animal):
         );
     }
 
+    #[test]
+    fn test_module_level_aliases() {
+        let mut parser = PythonParser::new().unwrap();
+        let code = r#"
+import collections
+
+Registry = collections.OrderedDict
+Alias = RealClass
+MAX_SIZE = 10
+handler = make_handler()
+
+def build():
+    Local = RealClass
+"#;
+        let aliases: Vec<_> = parser
+            .find_aliases(code)
+            .into_iter()
+            .map(|(alias, target, _)| (alias, target))
+            .collect();
+
+        assert_eq!(
+            aliases,
+            vec![("Registry", "OrderedDict"), ("Alias", "RealClass")]
+        );
+    }
+
+    #[test]
+    fn test_enum_members_and_references() {
+        let mut parser = PythonParser::new().unwrap();
+        let code = r#"
+from enum import Enum
+from colors import Color
+
+class Status(Enum):
+    PENDING = 1
+    DONE = 2
+
+class Job:
+    def label(self):
+        if self.status == Status.PENDING:
+            return Color.RED
+        return self.name.upper
+"#;
+        let symbols = parser.parse(code, FileId::new(1).unwrap(), &mut SymbolCounter::new());
+        let pending = symbols
+            .iter()
+            .find(|s| s.name.as_ref() == "PENDING")
+            .expect("enum member should be a symbol");
+        assert_eq!(
+            pending.scope_context,
+            Some(ScopeContext::ClassMember {
+                class_name: Some("Status".into())
+            })
+        );
+
+        let references: Vec<_> = parser
+            .find_references(code)
+            .into_iter()
+            .map(|site| (site.context, site.name))
+            .collect();
+        assert_eq!(
+            references,
+            vec![
+                ("Job.label".to_string(), "PENDING".to_string()),
+                ("Job.label".to_string(), "RED".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_instantiations() {
+        let mut parser = PythonParser::new().unwrap();
+        let code = r#"
+class Service:
+    def __init__(self, config):
+        self.config = config
+
+    def restart(self):
+        return Service(Config.load())
+
+def main():
+    service = Service(Config())
+    return len(MAX_SIZE)
+"#;
+        let sites: Vec<_> = parser
+            .find_instantiations(code)
+            .into_iter()
+            .map(|site| (site.context, site.type_name, site.constructor))
+            .collect();
+
+        assert_eq!(
+            sites,
+            vec![
+                (
+                    "Service.restart".to_string(),
+                    "Service".to_string(),
+                    Some("Service.__init__".to_string())
+                ),
+                (
+                    "main".to_string(),
+                    "Service".to_string(),
+                    Some("Service.__init__".to_string())
+                ),
+                (
+                    "main".to_string(),
+                    "Config".to_string(),
+                    Some("Config.__init__".to_string())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_throws_and_catches() {
+        let mut parser = PythonParser::new().unwrap();
+        let code = r#"
+class Loader:
+    def load(self, path):
+        try:
+            return read(path)
+        except (KeyError, errors.Timeout) as error:
+            raise LoadError(path) from error
+        except OSError:
+            raise
+
+def check(value):
+    if value is None:
+        raise ValueError
+"#;
+        let throws: Vec<_> = parser
+            .find_throws(code)
+            .into_iter()
+            .map(|(context, error, _)| (context, error))
+            .collect();
+        let catches: Vec<_> = parser
+            .find_catches(code)
+            .into_iter()
+            .map(|(context, error, _)| (context, error))
+            .collect();
+
+        let pair = |context: &str, error: &str| (context.to_string(), error.to_string());
+        assert_eq!(
+            throws,
+            vec![
+                pair("Loader.load", "LoadError"),
+                pair("check", "ValueError")
+            ]
+        );
+        assert_eq!(
+            catches,
+            vec![
+                pair("Loader.load", "KeyError"),
+                pair("Loader.load", "Timeout"),
+                pair("Loader.load", "OSError"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_operator_calls() {
+        let mut parser = PythonParser::new().unwrap();
+        let code = r#"
+class Vector:
+    def __add__(self, other):
+        return Vector(self.x + other.x)
+
+def step(position: Vector, cache: dict[str, Vector]) -> bool:
+    position += -position
+    cache["last"] = position
+    del cache["first"]
+    return "last" in cache and cache["last"] is not None and 1 < 2
+"#;
+        let calls: Vec<_> = parser
+            .find_operator_calls(code)
+            .into_iter()
+            .map(|call| (call.context, call.operator, call.method))
+            .collect();
+
+        let step = || "step".to_string();
+        assert_eq!(
+            calls,
+            vec![
+                ("Vector.__add__".to_string(), "+", "__add__"),
+                (step(), "+=", "__iadd__"),
+                (step(), "-", "__neg__"),
+                (step(), "[]", "__setitem__"),
+                (step(), "[]", "__delitem__"),
+                (step(), "in", "__contains__"),
+                (step(), "[]", "__getitem__"),
+            ]
+        );
+    }
+
     // Sub-Task 5.1.1: Function parameter types
     #[test]
     fn test_function_type_annotations() {
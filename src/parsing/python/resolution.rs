@@ -6,7 +6,7 @@
 //! - Module imports with aliasing
 
 use crate::parsing::resolution::ImportBinding;
-use crate::parsing::{InheritanceResolver, ResolutionScope, ScopeLevel, ScopeType};
+use crate::parsing::{InheritanceResolver, ResolutionScope, ScopeLevel, ScopeType, TypeRef};
 use crate::{FileId, SymbolId};
 use std::collections::HashMap;
 
@@ -310,17 +310,14 @@ impl ResolutionScope for PythonResolutionContext {
 /// Handles Python's Method Resolution Order (MRO) and multiple inheritance
 #[derive(Clone)]
 pub struct PythonInheritanceResolver {
-    /// Maps class names to their base classes
-    /// Key: "ClassName", Value: Vec<"BaseClass">
-    class_bases: HashMap<String, Vec<String>>,
+    /// Maps classes to their base classes, in declaration order
+    class_bases: HashMap<TypeRef, Vec<TypeRef>>,
 
-    /// Maps class names to their methods
-    /// Key: "ClassName", Value: Vec<"method_name">
-    class_methods: HashMap<String, Vec<String>>,
+    /// Maps classes to the names of the methods they define
+    class_methods: HashMap<TypeRef, Vec<String>>,
 
     /// Cached MRO for classes (Method Resolution Order)
-    /// Key: "ClassName", Value: Vec<"ClassName"> (in MRO order)
-    mro_cache: HashMap<String, Vec<String>>,
+    mro_cache: HashMap<TypeRef, Vec<TypeRef>>,
 }
 
 impl Default for PythonInheritanceResolver {
@@ -340,16 +337,16 @@ impl PythonInheritanceResolver {
 
     /// Calculate Method Resolution Order (MRO) using C3 linearization
     /// This is a simplified version - Python's actual MRO is more complex
-    fn calculate_mro(&self, class_name: &str) -> Vec<String> {
+    fn calculate_mro(&self, class: &TypeRef) -> Vec<TypeRef> {
         // Check cache first
-        if let Some(mro) = self.mro_cache.get(class_name) {
+        if let Some(mro) = self.mro_cache.get(class) {
             return mro.clone();
         }
 
         // Simple MRO: class itself, then bases in order (left-to-right)
-        let mut mro = vec![class_name.to_string()];
+        let mut mro = vec![class.clone()];
 
-        if let Some(bases) = self.class_bases.get(class_name) {
+        if let Some(bases) = self.class_bases.get(class) {
             for base in bases {
                 // Recursively get MRO of base classes
                 let base_mro = self.calculate_mro(base);
@@ -365,20 +362,20 @@ impl PythonInheritanceResolver {
     }
 
     /// Add a class with its base classes
-    pub fn add_class(&mut self, class_name: String, bases: Vec<String>) {
-        self.class_bases.insert(class_name.clone(), bases);
+    pub fn add_class(&mut self, class: TypeRef, bases: Vec<TypeRef>) {
+        self.class_bases.insert(class, bases);
         // Clear MRO cache as hierarchy changed
         self.mro_cache.clear();
     }
 
     /// Add methods to a class
-    pub fn add_class_methods(&mut self, class_name: String, methods: Vec<String>) {
-        self.class_methods.insert(class_name, methods);
+    pub fn add_class_methods(&mut self, class: TypeRef, methods: Vec<String>) {
+        self.class_methods.insert(class, methods);
     }
 }
 
 impl InheritanceResolver for PythonInheritanceResolver {
-    fn add_inheritance(&mut self, child: String, parent: String, kind: &str) {
+    fn add_inheritance(&mut self, child: TypeRef, parent: TypeRef, kind: &str) {
         if kind == "extends" || kind == "inherits" {
             // In Python, this is class inheritance
             self.class_bases.entry(child).or_default().push(parent);
@@ -387,38 +384,30 @@ impl InheritanceResolver for PythonInheritanceResolver {
         }
     }
 
-    fn resolve_method(&self, type_name: &str, method_name: &str) -> Option<String> {
-        // Get MRO for the class
-        let mro = self.calculate_mro(type_name);
-
+    fn resolve_method(&self, ty: &TypeRef, method_name: &str) -> Option<TypeRef> {
         // Search for method in MRO order
-        for class in &mro {
-            if let Some(methods) = self.class_methods.get(class) {
-                if methods.iter().any(|m| m == method_name) {
-                    return Some(class.clone());
-                }
-            }
-        }
-
-        None
+        self.calculate_mro(ty).into_iter().find(|class| {
+            self.class_methods
+                .get(class)
+                .is_some_and(|methods| methods.iter().any(|m| m == method_name))
+        })
     }
 
-    fn get_inheritance_chain(&self, type_name: &str) -> Vec<String> {
-        self.calculate_mro(type_name)
+    fn get_inheritance_chain(&self, ty: &TypeRef) -> Vec<TypeRef> {
+        self.calculate_mro(ty)
     }
 
-    fn is_subtype(&self, child: &str, parent: &str) -> bool {
-        let mro = self.calculate_mro(child);
-        mro.contains(&parent.to_string())
+    fn is_subtype(&self, child: &TypeRef, parent: &TypeRef) -> bool {
+        self.calculate_mro(child).contains(parent)
     }
 
-    fn add_type_methods(&mut self, type_name: String, methods: Vec<String>) {
-        self.add_class_methods(type_name, methods);
+    fn add_type_methods(&mut self, ty: TypeRef, methods: Vec<String>) {
+        self.add_class_methods(ty, methods);
     }
 
-    fn get_all_methods(&self, type_name: &str) -> Vec<String> {
+    fn get_all_methods(&self, ty: &TypeRef) -> Vec<String> {
         let mut all_methods = Vec::new();
-        let mro = self.calculate_mro(type_name);
+        let mro = self.calculate_mro(ty);
 
         for class in &mro {
             if let Some(methods) = self.class_methods.get(class) {
@@ -433,3 +422,33 @@ impl InheritanceResolver for PythonInheritanceResolver {
         all_methods
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mro_keeps_same_named_classes_apart() {
+        let mut resolver = PythonInheritanceResolver::new();
+        let base = TypeRef::new(SymbolId(1), "app.models.Base");
+        let user = TypeRef::new(SymbolId(2), "app.models.User");
+        let admin_user = TypeRef::new(SymbolId(3), "admin.models.User");
+        let mixin = TypeRef::new(SymbolId(4), "app.mixins.AuditMixin");
+
+        resolver.add_inheritance(user.clone(), base.clone(), "inherits");
+        resolver.add_inheritance(user.clone(), mixin.clone(), "inherits");
+        resolver.add_type_methods(base.clone(), vec!["save".to_string()]);
+        resolver.add_type_methods(mixin.clone(), vec!["save".to_string(), "audit".to_string()]);
+
+        assert_eq!(
+            resolver.get_inheritance_chain(&user),
+            vec![user.clone(), base.clone(), mixin.clone()]
+        );
+        // Leftmost base wins
+        assert_eq!(resolver.resolve_method(&user, "save"), Some(base.clone()));
+        assert_eq!(resolver.resolve_method(&user, "audit"), Some(mixin));
+        assert!(resolver.is_subtype(&user, &base));
+        assert!(!resolver.is_subtype(&admin_user, &base));
+        assert!(resolver.get_all_methods(&admin_user).is_empty());
+    }
+}
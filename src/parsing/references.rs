@@ -0,0 +1,435 @@
+//! Shared reference extraction
+//!
+//! Language parsers report calls, type uses, and inheritance, but few report
+//! plain identifier references: a function passed as a callback, a constant
+//! read in an expression, a static table indexed by name. This pass covers
+//! them for every language in one way. It walks the syntax tree again and
+//! reads each identifier node of a kind listed in the language's
+//! [`ReferenceNodeKinds`] as a reference from the innermost function or
+//! method around it.
+//!
+//! Scoping is per function. Names bound inside a function (parameters, local
+//! variables, loop and pattern bindings) are remembered and their uses
+//! skipped, so locals do not resolve to same-named globals. Member names
+//! after a `.` are reported only when the receiver's type is known: `self`
+//! or `this` inside a type body, or a variable the parser reports a declared
+//! type for. Such sites carry the type, and the resolve stage only accepts a
+//! member of it. Everything else is resolved later against imports and
+//! module scope by the resolve stage, like any other relationship.
+
+use crate::parsing::parser::check_recursion_depth;
+use crate::types::Range;
+use crate::{Symbol, SymbolKind};
+use std::collections::HashSet;
+use tree_sitter::{Node, Tree};
+
+/// Node kinds driving the reference pass for one language
+#[derive(Debug, Clone, Copy)]
+pub struct ReferenceNodeKinds {
+    /// Identifier nodes that may name another symbol
+    pub identifiers: &'static [&'static str],
+    /// Nodes binding local names, with the field holding what they bind;
+    /// `None` binds the children without a field. Every leaf in a bound
+    /// subtree is a local name, unless a nested binding node narrows it to
+    /// its own fields.
+    pub bindings: &'static [(&'static str, Option<&'static str>)],
+    /// Nodes naming a member, with the field holding the name; `None`
+    /// matches identifiers among the children without a field
+    pub members: &'static [(&'static str, Option<&'static str>)],
+    /// Nodes whose identifiers name existing items even inside a binding,
+    /// such as a qualified enum variant in a pattern
+    pub paths: &'static [&'static str],
+    /// Member accesses, with the fields holding the receiver and the member
+    /// name
+    pub fields: &'static [(&'static str, &'static str, &'static str)],
+    /// Type bodies, with the field naming the type the receivers in `this`
+    /// stand for inside them
+    pub containers: &'static [(&'static str, &'static str)],
+    /// Receiver spellings meaning the enclosing type, like `self`
+    pub this: &'static [&'static str],
+}
+
+impl ReferenceNodeKinds {
+    fn is_identifier(&self, kind: &str) -> bool {
+        self.identifiers.contains(&kind)
+    }
+
+    fn is_binding(&self, kind: &str) -> bool {
+        self.bindings.iter().any(|(k, _)| *k == kind)
+    }
+}
+
+/// An identifier read as a reference from its enclosing function
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReferenceSite {
+    /// Name of the enclosing function or method
+    pub context: String,
+    /// Range of the enclosing function or method, as parsed
+    pub context_range: Range,
+    /// The referenced name
+    pub name: String,
+    /// Where the name appears
+    pub range: Range,
+    /// Type of the receiver, when the name is a member access
+    pub receiver: Option<String>,
+    /// Whether the name is a dotted option path a configuration sets,
+    /// standing for the declared option with the longest matching path
+    pub option_path: bool,
+}
+
+/// References in `tree` from the functions and methods in `symbols`, in
+/// document order
+///
+/// `variable_types` are the parser's `(variable, type, range)` bindings, used
+/// to type the receivers of member accesses.
+pub fn extract_references<'a>(
+    tree: &Tree,
+    code: &'a str,
+    symbols: &'a [Symbol],
+    kinds: &'a ReferenceNodeKinds,
+    variable_types: &'a [(&'a str, &'a str, Range)],
+) -> Vec<ReferenceSite> {
+    let contexts: Vec<&Symbol> = symbols
+        .iter()
+        .filter(|s| matches!(s.kind, SymbolKind::Function | SymbolKind::Method))
+        .collect();
+    if contexts.is_empty() || kinds.identifiers.is_empty() {
+        return Vec::new();
+    }
+
+    let mut pass = ReferencePass {
+        code,
+        symbols,
+        contexts,
+        kinds,
+        variable_types,
+        types: Vec::new(),
+        locals: HashSet::new(),
+        sites: Vec::new(),
+    };
+    pass.visit(tree.root_node(), false, 0);
+    pass.sites
+}
+
+struct ReferencePass<'a> {
+    code: &'a str,
+    symbols: &'a [Symbol],
+    contexts: Vec<&'a Symbol>,
+    kinds: &'a ReferenceNodeKinds,
+    variable_types: &'a [(&'a str, &'a str, Range)],
+    /// Enclosing type bodies, innermost last
+    types: Vec<&'a str>,
+    /// Names bound locally, by index into `contexts`
+    locals: HashSet<(usize, String)>,
+    sites: Vec<ReferenceSite>,
+}
+
+impl<'a> ReferencePass<'a> {
+    fn visit(&mut self, node: Node, binding: bool, depth: usize) {
+        if !check_recursion_depth(depth, node) {
+            return;
+        }
+        let kind = node.kind();
+        let binding_node = self.kinds.is_binding(kind);
+        if let Some((_, receiver, member)) = self.kinds.fields.iter().find(|(k, ..)| *k == kind) {
+            self.member(node, receiver, member);
+        }
+
+        let mut cursor = node.walk();
+        if !cursor.goto_first_child() {
+            return;
+        }
+        let container = self
+            .kinds
+            .containers
+            .iter()
+            .find(|(k, _)| *k == kind)
+            .and_then(|(_, field)| node.child_by_field_name(field))
+            .and_then(|name| name.utf8_text(self.code.as_bytes()).ok())
+            .and_then(type_name);
+        if let Some(container) = container {
+            self.types.push(container);
+        }
+        loop {
+            let child = cursor.node();
+            let field = cursor.field_name();
+            let child_binding = if self.kinds.paths.contains(&child.kind()) {
+                false
+            } else if binding_node {
+                self.kinds.bindings.contains(&(kind, field))
+            } else {
+                binding
+            };
+
+            if self.kinds.is_identifier(child.kind()) {
+                if !self.kinds.members.contains(&(kind, field)) {
+                    self.identifier(child, child_binding);
+                }
+            } else if child_binding && child.is_named() && child.child_count() == 0 {
+                // Pattern leaves that are not identifiers, like JavaScript's
+                // shorthand destructuring
+                self.identifier(child, true);
+            } else {
+                self.visit(child, child_binding, depth + 1);
+            }
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+        if container.is_some() {
+            self.types.pop();
+        }
+    }
+
+    /// A member access, reported when the receiver's type is known
+    fn member(&mut self, node: Node, receiver_field: &str, member_field: &str) {
+        let (Some(receiver), Some(member)) = (
+            node.child_by_field_name(receiver_field),
+            node.child_by_field_name(member_field),
+        ) else {
+            return;
+        };
+        // Swift wraps the name in a suffix node
+        let member = match member.named_child_count() {
+            0 => member,
+            count => member.named_child(count as u32 - 1).unwrap_or(member),
+        };
+        let Ok(name) = member.utf8_text(self.code.as_bytes()) else {
+            return;
+        };
+        // Tuple indices and computed names have no symbol to point at
+        if !name.starts_with(|c: char| c.is_alphabetic() || c == '_')
+            || !name.chars().all(|c| c.is_alphanumeric() || c == '_')
+        {
+            return;
+        }
+        let Some(receiver_type) = self.receiver_type(receiver) else {
+            return;
+        };
+        let range = range_from_node(&member);
+        let Some(context) = self.context_at(&range) else {
+            return;
+        };
+        let symbol = self.contexts[context];
+        self.sites.push(ReferenceSite {
+            context: symbol.name.to_string(),
+            context_range: symbol.range,
+            name: name.to_string(),
+            range,
+            receiver: Some(receiver_type.to_string()),
+            option_path: false,
+        });
+    }
+
+    /// The enclosing type for `self`/`this`, or the latest declared type of
+    /// a variable receiver
+    fn receiver_type(&self, receiver: Node) -> Option<&'a str> {
+        let text = receiver.utf8_text(self.code.as_bytes()).ok()?;
+        if self.kinds.this.contains(&text) {
+            return self.types.last().copied();
+        }
+        if !self.kinds.is_identifier(receiver.kind()) {
+            return None;
+        }
+        let line = receiver.start_position().row as u32;
+        self.variable_types
+            .iter()
+            .filter(|(name, _, range)| *name == text && range.start_line <= line)
+            .max_by_key(|(_, _, range)| range.start_line)
+            .and_then(|(_, type_text, _)| type_name(type_text))
+    }
+
+    fn identifier(&mut self, node: Node, binding: bool) {
+        let Ok(name) = node.utf8_text(self.code.as_bytes()) else {
+            return;
+        };
+        let range = range_from_node(&node);
+        let Some(context) = self.context_at(&range) else {
+            return;
+        };
+
+        if binding {
+            self.locals.insert((context, name.to_string()));
+            return;
+        }
+        let key = (context, name.to_string());
+        if self.locals.contains(&key) || self.is_definition_name(name, &range) {
+            return;
+        }
+        let symbol = self.contexts[context];
+        self.sites.push(ReferenceSite {
+            context: symbol.name.to_string(),
+            context_range: symbol.range,
+            name: key.1,
+            range,
+            receiver: None,
+            option_path: false,
+        });
+    }
+
+    /// Innermost function or method containing `range`
+    fn context_at(&self, range: &Range) -> Option<usize> {
+        self.contexts
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| s.range.contains(range.start_line, range.start_column))
+            .max_by_key(|(_, s)| (s.range.start_line, s.range.start_column))
+            .map(|(index, _)| index)
+    }
+
+    /// Whether the identifier is the name of a symbol declared on its line
+    fn is_definition_name(&self, name: &str, range: &Range) -> bool {
+        self.symbols.iter().any(|s| {
+            s.range.start_line == range.start_line
+                && s.name.as_ref() == name
+                && s.range.contains(range.start_line, range.start_column)
+        })
+    }
+}
+
+/// Bare name of a written type: `&mut Config`, `*Server`, `struct Config`
+/// and `config::Config` all give `Config`; `Vec<Item>` gives `Vec`
+fn type_name(text: &str) -> Option<&str> {
+    let text = text.split(['<', '[', '(']).next()?;
+    let text = text.trim_end_matches(['?', '!', '*', '&', ' ']);
+    let text = text.rsplit([' ', '*', '&']).next()?;
+    let text = text.rsplit("::").next()?.rsplit('.').next()?;
+    text.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        .then_some(text)
+}
+
+fn range_from_node(node: &Node) -> Range {
+    let start = node.start_position();
+    let end = node.end_position();
+    Range::new(
+        start.row as u32,
+        start.column as u16,
+        end.row as u32,
+        end.column as u16,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{FileId, SymbolId};
+
+    const RUST: ReferenceNodeKinds = ReferenceNodeKinds {
+        identifiers: &["identifier"],
+        bindings: &[
+            ("let_declaration", Some("pattern")),
+            ("parameter", Some("pattern")),
+            ("match_arm", Some("pattern")),
+        ],
+        members: &[("scoped_identifier", Some("path"))],
+        paths: &["scoped_identifier"],
+        fields: &[("field_expression", "value", "field")],
+        containers: &[("impl_item", "type")],
+        this: &["self"],
+    };
+
+    fn function(id: u32, name: &str, range: Range) -> Symbol {
+        Symbol::new(
+            SymbolId::new(id).unwrap(),
+            name,
+            SymbolKind::Function,
+            FileId::new(1).unwrap(),
+            range,
+        )
+    }
+
+    #[test]
+    fn test_references_skip_locals_and_definitions() {
+        let code = "\
+const LIMIT: usize = 3;
+fn helper(n: usize) -> usize { n }
+fn run(items: usize) -> usize {
+    let total = helper(items) + LIMIT;
+    let f = helper;
+    total + LIMIT + f(1)
+}
+";
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&tree_sitter_rust::LANGUAGE.into())
+            .unwrap();
+        let tree = parser.parse(code, None).unwrap();
+        let symbols = vec![
+            function(1, "helper", Range::new(1, 0, 1, 34)),
+            function(2, "run", Range::new(2, 0, 6, 1)),
+        ];
+
+        let sites = extract_references(&tree, code, &symbols, &RUST, &[]);
+        let names: Vec<(&str, &str, u32)> = sites
+            .iter()
+            .map(|s| (s.context.as_str(), s.name.as_str(), s.range.start_line))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                ("run", "helper", 3),
+                ("run", "LIMIT", 3),
+                ("run", "helper", 4),
+                ("run", "LIMIT", 5),
+            ]
+        );
+        assert_eq!(sites[0].context_range, symbols[1].range);
+    }
+
+    #[test]
+    fn test_paths_in_patterns_are_references() {
+        let code = "\
+fn code(s: Status) -> u8 {
+    match s {
+        Status::Pending => 0,
+        other => other.rank(),
+    }
+}
+";
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&tree_sitter_rust::LANGUAGE.into())
+            .unwrap();
+        let tree = parser.parse(code, None).unwrap();
+        let symbols = vec![function(1, "code", Range::new(0, 0, 5, 1))];
+
+        let names: Vec<String> = extract_references(&tree, code, &symbols, &RUST, &[])
+            .into_iter()
+            .map(|s| s.name)
+            .collect();
+        assert_eq!(names, ["Pending"]);
+    }
+
+    #[test]
+    fn test_member_access_needs_a_known_receiver() {
+        let code = "\
+impl Server<Tcp> {
+    fn start(&self, config: Config, other: Other) -> u16 {
+        self.connection.open();
+        other.port + config.port + config.0
+    }
+}
+";
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&tree_sitter_rust::LANGUAGE.into())
+            .unwrap();
+        let tree = parser.parse(code, None).unwrap();
+        let symbols = vec![function(1, "start", Range::new(1, 4, 4, 5))];
+        let variable_types = [("config", "&Config", Range::new(1, 24, 1, 38))];
+
+        let sites: Vec<(String, Option<String>)> =
+            extract_references(&tree, code, &symbols, &RUST, &variable_types)
+                .into_iter()
+                .map(|s| (s.name, s.receiver))
+                .collect();
+        assert_eq!(
+            sites,
+            [
+                ("connection".to_string(), Some("Server".to_string())),
+                ("port".to_string(), Some("Config".to_string())),
+            ]
+        );
+    }
+}
@@ -16,11 +16,13 @@
 //! without recompilation, while still maintaining zero-cost
 //! abstractions and type safety.
 
+use indexmap::IndexMap;
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Arc;
 use thiserror::Error;
 
-use super::{LanguageBehavior, LanguageParser};
+use super::{LanguageBehavior, LanguageParser, detection};
 use crate::{IndexResult, Settings};
 
 /// Type alias for parser and behavior pair to reduce complexity
@@ -143,6 +145,13 @@ pub trait LanguageDefinition: Send + Sync {
     /// Extensions should NOT include the dot prefix
     fn extensions(&self) -> &'static [&'static str];
 
+    /// Interpreters that run this language's scripts, as named in shebang
+    /// lines (e.g., ["python3", "python"]). A trailing version such as
+    /// "3.12" is ignored when matching.
+    fn interpreters(&self) -> &'static [&'static str] {
+        &[]
+    }
+
     /// Create a parser instance for this language
     /// Takes borrowed Settings to access language-specific configuration
     fn create_parser(&self, settings: &Settings) -> IndexResult<Box<dyn LanguageParser>>;
@@ -182,6 +191,9 @@ pub struct LanguageRegistry {
     /// Extension to language mapping for quick lookup
     /// Built from all available languages, not just enabled ones
     extension_map: HashMap<&'static str, LanguageId>,
+
+    /// Shebang interpreter to language mapping
+    interpreter_map: HashMap<&'static str, LanguageId>,
 }
 
 impl LanguageRegistry {
@@ -190,6 +202,7 @@ impl LanguageRegistry {
         Self {
             definitions: HashMap::new(),
             extension_map: HashMap::new(),
+            interpreter_map: HashMap::new(),
         }
     }
 
@@ -208,6 +221,9 @@ impl LanguageRegistry {
         for ext in definition.extensions() {
             self.extension_map.insert(ext, id);
         }
+        for interpreter in definition.interpreters() {
+            self.interpreter_map.insert(interpreter, id);
+        }
     }
 
     /// Get a language definition by ID
@@ -231,6 +247,70 @@ impl LanguageRegistry {
         self.extension_map.get(ext).and_then(|id| self.get(*id))
     }
 
+    /// Get a language by shebang interpreter (e.g., "python3.12", "node")
+    #[must_use]
+    pub fn get_by_interpreter(&self, interpreter: &str) -> Option<&dyn LanguageDefinition> {
+        let unversioned = interpreter.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+        self.interpreter_map
+            .get(interpreter)
+            .or_else(|| self.interpreter_map.get(unversioned))
+            .and_then(|id| self.get(*id))
+    }
+
+    /// Detect the language of a file
+    ///
+    /// Checks, in order: `overrides` by file name, the extension, and for
+    /// files without an extension the start of their content (`head`): the
+    /// shebang interpreter, with `overrides` consulted first, then modelines
+    /// (see [`detection`](super::detection)). `overrides` maps file names and
+    /// interpreters to language ids, as configured in
+    /// `indexing.language_overrides`.
+    pub fn detect(
+        &self,
+        path: &Path,
+        head: Option<&str>,
+        overrides: &IndexMap<String, String>,
+    ) -> Option<&dyn LanguageDefinition> {
+        let by_name = |name: &str| {
+            self.find_language_id(&name.to_ascii_lowercase())
+                .and_then(|id| self.get(id))
+                .or_else(|| self.get_by_interpreter(name))
+        };
+
+        if let Some(language) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| overrides.get(name))
+        {
+            return by_name(language);
+        }
+        if let Some(extension) = path.extension() {
+            return extension
+                .to_str()
+                .and_then(|extension| self.get_by_extension(extension));
+        }
+
+        let head = head?;
+        if let Some(interpreter) = detection::shebang_interpreter(head) {
+            if let Some(language) = overrides.get(interpreter) {
+                return by_name(language);
+            }
+            if let Some(definition) = self.get_by_interpreter(interpreter) {
+                return Some(definition);
+            }
+        }
+        detection::content_language(head).and_then(by_name)
+    }
+
+    /// Whether [`detect`](Self::detect) needs the start of the file at `path`
+    pub fn needs_content(path: &Path, overrides: &IndexMap<String, String>) -> bool {
+        path.extension().is_none()
+            && !path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| overrides.contains_key(name))
+    }
+
     /// Convert a string to LanguageId by looking up registered languages
     ///
     /// This is useful when reading language identifiers from storage
@@ -0,0 +1,446 @@
+//! Relationships built from what a parser finds in a file
+//!
+//! Parsing a file leaves two things behind: its symbols, and the artifacts
+//! the parser's `find_*` passes collect (calls, implementations, references
+//! and the rest). Relationships are built in a second pass over both, by
+//! [`LanguageBehavior::build_relationships`], so a language can shape its
+//! edges without depending on the order its parser extracts symbols in.
+//!
+//! [`LanguageBehavior::build_relationships`]: crate::parsing::LanguageBehavior::build_relationships
+
+use super::instantiation::encode_type_arguments;
+use super::{
+    GenericInstantiation, Instantiation, LanguageParser, MethodCall, OperatorCall, ReferenceSite,
+};
+use crate::relationship::{Confidence, RelationKind, RelationshipMetadata};
+use crate::types::Range;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Relationship extracted from parsing, before resolution.
+///
+/// Contains ranges for disambiguation when multiple symbols share the same name:
+/// - `from_range`: Position of the calling symbol (maps to from_id in COLLECT)
+/// - `to_range`: Position of the reference/call site (helps Phase 2 resolution)
+#[derive(Debug, Clone)]
+pub struct RawRelationship {
+    pub from_name: Arc<str>,
+    pub from_range: Range,
+    pub to_name: Arc<str>,
+    pub to_range: Range,
+    pub kind: RelationKind,
+    pub metadata: Option<RelationshipMetadata>,
+}
+
+impl RawRelationship {
+    pub fn new(
+        from_name: impl Into<Arc<str>>,
+        from_range: Range,
+        to_name: impl Into<Arc<str>>,
+        to_range: Range,
+        kind: RelationKind,
+    ) -> Self {
+        Self {
+            from_name: from_name.into(),
+            from_range,
+            to_name: to_name.into(),
+            to_range,
+            kind,
+            metadata: None,
+        }
+    }
+
+    pub fn with_metadata(mut self, metadata: RelationshipMetadata) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+}
+
+/// Everything a parser's relationship passes found in one file
+///
+/// Names borrow from the parsed code where the parser returns them so.
+#[derive(Debug, Default)]
+pub struct ParseArtifacts<'a> {
+    /// Calls that know their caller's range
+    pub method_calls: Vec<MethodCall>,
+    /// `(caller, called, call site)`
+    pub calls: Vec<(&'a str, &'a str, Range)>,
+    /// `(type, trait, impl site)`
+    pub implementations: Vec<(&'a str, &'a str, Range)>,
+    /// `(derived, base, class site)`
+    pub extends: Vec<(&'a str, &'a str, Range)>,
+    /// `(alias, target, alias site)`
+    pub aliases: Vec<(&'a str, &'a str, Range)>,
+    /// `(context, error, raise site)`
+    pub throws: Vec<(String, String, Range)>,
+    /// `(context, error, catch site)`
+    pub catches: Vec<(String, String, Range)>,
+    /// `(context, type, usage site)`
+    pub uses: Vec<(&'a str, &'a str, Range)>,
+    /// `(definer, method, definition site)`
+    pub defines: Vec<(&'a str, &'a str, Range)>,
+    pub instantiations: Vec<Instantiation>,
+    pub generic_instantiations: Vec<GenericInstantiation>,
+    pub operator_calls: Vec<OperatorCall>,
+    /// References from the shared reference pass, then those the parser
+    /// tracks itself
+    pub references: Vec<ReferenceSite>,
+}
+
+impl<'a> ParseArtifacts<'a> {
+    /// Run every relationship pass of `parser` over `code`, which it has
+    /// just parsed. `references` come from the shared reference pass.
+    pub fn collect(
+        parser: &mut dyn LanguageParser,
+        code: &'a str,
+        mut references: Vec<ReferenceSite>,
+    ) -> Self {
+        let method_calls = parser.find_method_calls(code);
+        let calls = parser.find_calls(code);
+        let implementations = parser.find_implementations(code);
+        let extends = parser.find_extends(code);
+        let aliases = parser.find_aliases(code);
+        let throws = parser.find_throws(code);
+        let catches = parser.find_catches(code);
+        let uses = parser.find_uses(code);
+        let defines = parser.find_defines(code);
+        let instantiations = parser.find_instantiations(code);
+        let generic_instantiations = parser.find_generic_instantiations(code);
+        let operator_calls = parser.find_operator_calls(code);
+        references.extend(parser.find_references(code));
+        Self {
+            method_calls,
+            calls,
+            implementations,
+            extends,
+            aliases,
+            throws,
+            catches,
+            uses,
+            defines,
+            instantiations,
+            generic_instantiations,
+            operator_calls,
+            references,
+        }
+    }
+}
+
+/// Relationships for `artifacts`, one kind of edge per kind of artifact.
+///
+/// Range semantics:
+/// - `from_range`: Definition location of the calling/containing symbol (for COLLECT lookup)
+/// - `to_range`: Call site / reference location (for Phase 2 disambiguation)
+///
+/// For MethodCall: `caller_range` provides precise from_range when available.
+/// For legacy find_* methods: range typically points to the reference site.
+/// References carry the enclosing symbol's range as `from_range`; one edge
+/// is kept per symbol and name. Member accesses record their receiver's
+/// type as the `receiver` attribute; calls through a plain path record the
+/// path they name, such as `lib.mkOption`, as the `qualified` attribute.
+pub fn default_relationships(artifacts: &ParseArtifacts) -> Vec<RawRelationship> {
+    let mut relationships = Vec::new();
+
+    // Function/method calls - MethodCall provides caller_range for precise lookup
+    for call in &artifacts.method_calls {
+        // Use caller_range when available, otherwise use call site (triggers fallback)
+        let from_range = call.caller_range.unwrap_or(call.range);
+        let mut metadata = site_metadata(&call.range);
+        if let Some(category) = call.category {
+            metadata = metadata.with_attribute("category", category);
+        }
+        if let Some(qualified) = qualified_call(call) {
+            metadata = metadata.with_attribute("qualified", &qualified);
+        }
+        relationships.push(
+            RawRelationship::new(
+                call.caller.as_str(),
+                from_range,
+                call.method_name.as_str(),
+                call.range, // to_range = call site
+                RelationKind::Calls,
+            )
+            .with_metadata(metadata),
+        );
+    }
+
+    // Plain function calls (legacy - no caller_range available)
+    for &(caller, called, call_site) in &artifacts.calls {
+        // Avoid duplicates - method_calls should be comprehensive
+        // but some parsers might return both
+        let already_exists = relationships.iter().any(|r| {
+            r.from_name.as_ref() == caller
+                && r.to_name.as_ref() == called
+                && r.to_range.start_line == call_site.start_line
+        });
+        if !already_exists {
+            // from_range = call_site triggers fallback to name-only lookup in COLLECT
+            relationships.push(
+                RawRelationship::new(
+                    caller,
+                    call_site, // no caller_range available, use call_site
+                    called,
+                    call_site, // to_range = call site
+                    RelationKind::Calls,
+                )
+                .with_metadata(site_metadata(&call_site)),
+            );
+        }
+    }
+
+    // Trait implementations - range is the impl definition site
+    for &(type_name, trait_name, impl_range) in &artifacts.implementations {
+        relationships.push(RawRelationship::new(
+            type_name,
+            impl_range, // from_range = where impl is defined
+            trait_name,
+            impl_range, // to_range = where trait is referenced
+            RelationKind::Implements,
+        ));
+    }
+
+    // Inheritance (extends) - range is the class definition site
+    for &(derived, base, class_range) in &artifacts.extends {
+        relationships.push(RawRelationship::new(
+            derived,
+            class_range, // from_range = where derived is defined
+            base,
+            class_range, // to_range = where base is referenced
+            RelationKind::Extends,
+        ));
+    }
+
+    // Aliases - range is the alias declaration. An alias of the same name
+    // (`hello = pkgs.hello`) could only resolve to itself.
+    for &(alias, target, alias_range) in &artifacts.aliases {
+        if alias != target {
+            relationships.push(RawRelationship::new(
+                alias,
+                alias_range, // from_range = where the alias is declared
+                target,
+                alias_range, // to_range = where the target is named
+                RelationKind::Aliases,
+            ));
+        }
+    }
+
+    // Raised and caught errors - range is the raise or catch site
+    for (context, error, site) in &artifacts.throws {
+        relationships.push(
+            RawRelationship::new(
+                context.as_str(),
+                *site,
+                error.as_str(),
+                *site,
+                RelationKind::Throws,
+            )
+            .with_metadata(site_metadata(site)),
+        );
+    }
+    for (context, error, site) in &artifacts.catches {
+        relationships.push(
+            RawRelationship::new(
+                context.as_str(),
+                *site,
+                error.as_str(),
+                *site,
+                RelationKind::Throws,
+            )
+            .with_metadata(site_metadata(site).with_attribute("caught", "true")),
+        );
+    }
+
+    // Type usage - range is the usage site
+    for &(context, used_type, usage_range) in &artifacts.uses {
+        relationships.push(RawRelationship::new(
+            context,
+            usage_range, // from_range = usage context (triggers fallback)
+            used_type,
+            usage_range, // to_range = where type is used
+            RelationKind::Uses,
+        ));
+    }
+
+    // Method definitions (Defines relationships)
+    for &(definer, method, def_range) in &artifacts.defines {
+        relationships.push(RawRelationship::new(
+            definer,
+            def_range, // from_range = where definer is
+            method,
+            def_range, // to_range = where method is defined
+            RelationKind::Defines,
+        ));
+    }
+
+    // Instantiations - a reference to the type, and a call to the constructor
+    // that runs, resolved among the type's members
+    for site in &artifacts.instantiations {
+        relationships.push(
+            RawRelationship::new(
+                site.context.as_str(),
+                site.context_range, // from_range = enclosing function
+                site.type_name.as_str(),
+                site.range, // to_range = where the type is named
+                RelationKind::References,
+            )
+            .with_metadata(site_metadata(&site.range)),
+        );
+        if let Some(constructor) = &site.constructor {
+            relationships.push(
+                RawRelationship::new(
+                    site.context.as_str(),
+                    site.context_range,
+                    constructor.as_str(),
+                    site.range,
+                    RelationKind::Calls,
+                )
+                .with_metadata(
+                    site_metadata(&site.range).with_attribute("receiver", &site.type_name),
+                ),
+            );
+        }
+    }
+
+    // Generic instantiations - a reference to the generic with the type
+    // arguments it was given
+    for site in &artifacts.generic_instantiations {
+        relationships.push(
+            RawRelationship::new(
+                site.context.as_str(),
+                site.context_range,
+                site.generic.as_str(),
+                site.range,
+                RelationKind::References,
+            )
+            .with_metadata(
+                site_metadata(&site.range)
+                    .with_attribute("type_args", &encode_type_arguments(&site.type_arguments)),
+            ),
+        );
+    }
+
+    // Operators that may run an overload - a low-confidence call to the
+    // method the overload defines
+    for site in &artifacts.operator_calls {
+        relationships.push(
+            RawRelationship::new(
+                site.context.as_str(),
+                site.context_range,
+                site.method,
+                site.range,
+                RelationKind::Calls,
+            )
+            .with_metadata(
+                site_metadata(&site.range)
+                    .with_attribute("operator", site.operator)
+                    .with_confidence(Confidence::Low),
+            ),
+        );
+    }
+
+    // Identifier references, once per function and name, skipping sites a
+    // parser already reported
+    let mut referenced = HashSet::new();
+    for site in &artifacts.references {
+        let reported = relationships.iter().any(|r| {
+            r.to_name.as_ref() == site.name && r.to_range.start_line == site.range.start_line
+        });
+        if !reported && referenced.insert((site.context.as_str(), site.name.as_str())) {
+            let mut metadata = site_metadata(&site.range);
+            if let Some(receiver) = &site.receiver {
+                metadata = metadata.with_attribute("receiver", receiver);
+            }
+            if site.option_path {
+                metadata = metadata.with_attribute("option", "true");
+            }
+            relationships.push(
+                RawRelationship::new(
+                    site.context.as_str(),
+                    site.context_range, // from_range = enclosing function
+                    site.name.as_str(),
+                    site.range, // to_range = where the name appears
+                    RelationKind::References,
+                )
+                .with_metadata(metadata),
+            );
+        }
+    }
+
+    relationships
+}
+
+/// Call or reference site stored with the relationship, so callers and call
+/// hierarchies can point at the site rather than the caller's definition.
+/// `receiver.method` for a call through a plain path, with `::` for static
+/// calls and paths that already use it
+fn qualified_call(call: &MethodCall) -> Option<String> {
+    let receiver = call.receiver.as_deref()?;
+    let is_path = receiver
+        .chars()
+        .all(|c| c.is_alphanumeric() || matches!(c, '_' | '.' | ':' | '-' | '\'' | '$'));
+    if receiver.is_empty() || !is_path {
+        return None;
+    }
+    let separator = if call.is_static || receiver.contains("::") {
+        "::"
+    } else {
+        "."
+    };
+    Some(format!("{receiver}{separator}{}", call.method_name))
+}
+
+fn site_metadata(range: &Range) -> RelationshipMetadata {
+    RelationshipMetadata::new().at_position(range.start_line, range.start_column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reference(context: &str, name: &str, line: u32) -> ReferenceSite {
+        ReferenceSite {
+            context: context.to_string(),
+            context_range: Range::new(0, 0, 20, 1),
+            name: name.to_string(),
+            range: Range::new(line, 4, line, 10),
+            receiver: None,
+            option_path: false,
+        }
+    }
+
+    #[test]
+    fn test_default_relationships_without_a_parser() {
+        let call_site = Range::new(3, 4, 3, 12);
+        let mut call = MethodCall::new("main", "helper", call_site);
+        call.caller_range = Some(Range::new(0, 0, 20, 1));
+        let artifacts = ParseArtifacts {
+            method_calls: vec![call],
+            // Reported again by the legacy pass, and as a reference
+            calls: vec![("main", "helper", call_site)],
+            catches: vec![("main".to_string(), "IoError".to_string(), call_site)],
+            references: vec![
+                reference("main", "helper", 3),
+                reference("main", "CONFIG", 5),
+                reference("main", "CONFIG", 7),
+            ],
+            ..ParseArtifacts::default()
+        };
+
+        let relationships = default_relationships(&artifacts);
+        let edges: Vec<(&str, RelationKind)> = relationships
+            .iter()
+            .map(|r| (r.to_name.as_ref(), r.kind))
+            .collect();
+        assert_eq!(
+            edges,
+            [
+                ("helper", RelationKind::Calls),
+                ("IoError", RelationKind::Throws),
+                ("CONFIG", RelationKind::References),
+            ]
+        );
+        assert_eq!(relationships[0].from_range, Range::new(0, 0, 20, 1));
+        let caught = relationships[1].metadata.as_ref().unwrap();
+        assert_eq!(caught.attribute("caught"), Some("true"));
+    }
+}
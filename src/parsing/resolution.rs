@@ -310,6 +310,20 @@ pub trait ResolutionScope: Send + Sync {
                 // Reverse of References - also permissive
                 true
             }
+            Aliases | AliasedBy => {
+                // A new name for any symbol
+                true
+            }
+            Throws => {
+                // Code raises errors, which are classes, structs or enums
+                matches!(from_kind, Function | Method)
+                    && matches!(to_kind, Class | Struct | Enum | Interface | TypeAlias)
+            }
+            ThrownBy => {
+                // Reverse of Throws
+                matches!(from_kind, Class | Struct | Enum | Interface | TypeAlias)
+                    && matches!(to_kind, Function | Method)
+            }
         }
     }
 }
@@ -344,6 +358,103 @@ pub trait ProjectResolutionEnhancer: Send + Sync {
     }
 }
 
+/// A type taking part in inheritance: its symbol and qualified name
+///
+/// Resolvers key types by `id`, so same-named classes in different modules
+/// (`app.models.User`, `admin.models.User`) no longer collide; the name is
+/// carried along for display and for callers that only have text.
+///
+/// A type that is not in the index (an external base class, or a caller
+/// still working with names) gets an id derived from its name with
+/// [`TypeRef::named`]. Those ids live in the upper half of the id space,
+/// away from the sequential ids of indexed symbols. Derived ids can collide,
+/// so named types compare and hash by `qualified_name`; indexed types
+/// compare by `id`, and never equal a named type.
+#[derive(Debug, Clone)]
+pub struct TypeRef {
+    pub id: SymbolId,
+    pub qualified_name: String,
+}
+
+/// Marks ids derived from names rather than assigned by the indexer
+const NAMED_TYPE_BIT: u32 = 1 << 31;
+
+impl TypeRef {
+    pub fn new(id: SymbolId, qualified_name: impl Into<String>) -> Self {
+        Self {
+            id,
+            qualified_name: qualified_name.into(),
+        }
+    }
+
+    /// Type known only by name
+    pub fn named(qualified_name: impl Into<String>) -> Self {
+        let qualified_name = qualified_name.into();
+        // FNV-1a: stable across runs, unlike the std hasher
+        let hash = qualified_name.bytes().fold(0x811c_9dc5_u32, |hash, byte| {
+            (hash ^ byte as u32).wrapping_mul(0x0100_0193)
+        });
+        Self {
+            id: SymbolId(NAMED_TYPE_BIT | hash),
+            qualified_name,
+        }
+    }
+
+    /// Type named `qualified_name`, as the indexed symbol when `symbols`
+    /// holds exactly one type declared under that name
+    pub fn lookup(qualified_name: &str, symbols: &dyn PipelineSymbolCache) -> Self {
+        let (module, name) = match qualified_name.rsplit_once('.') {
+            Some((module, name)) => (Some(module), name),
+            None => (None, qualified_name),
+        };
+        let mut found = symbols
+            .lookup_candidates(name)
+            .into_iter()
+            .filter_map(|id| symbols.get(id))
+            .filter(|symbol| {
+                matches!(
+                    symbol.kind,
+                    crate::SymbolKind::Class
+                        | crate::SymbolKind::Interface
+                        | crate::SymbolKind::Struct
+                        | crate::SymbolKind::Trait
+                        | crate::SymbolKind::Enum
+                ) && symbol.module_path.as_deref().filter(|m| !m.is_empty()) == module
+            });
+        match (found.next(), found.next()) {
+            (Some(symbol), None) => Self::new(symbol.id, qualified_name),
+            _ => Self::named(qualified_name),
+        }
+    }
+
+    /// Whether the type is an indexed symbol rather than a bare name
+    pub fn is_indexed(&self) -> bool {
+        self.id.0 & NAMED_TYPE_BIT == 0
+    }
+}
+
+impl PartialEq for TypeRef {
+    fn eq(&self, other: &Self) -> bool {
+        match (self.is_indexed(), other.is_indexed()) {
+            (true, true) => self.id == other.id,
+            (false, false) => self.qualified_name == other.qualified_name,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for TypeRef {}
+
+impl std::hash::Hash for TypeRef {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        if self.is_indexed() {
+            self.id.hash(state);
+        } else {
+            self.qualified_name.hash(state);
+        }
+    }
+}
+
 /// Language-agnostic inheritance resolver
 ///
 /// Each language implements this trait to handle its inheritance model:
@@ -352,7 +463,37 @@ pub trait ProjectResolutionEnhancer: Send + Sync {
 /// - Python: multiple inheritance with MRO
 /// - PHP: traits and interfaces
 /// - Go: interfaces and struct embedding
+///
+/// Types are identified by [`TypeRef`]. Languages that have not moved off
+/// names yet implement [`NameKeyedInheritanceResolver`] instead and get this
+/// trait through a blanket impl keyed by qualified name.
 pub trait InheritanceResolver: Send + Sync {
+    /// Add an inheritance relationship
+    fn add_inheritance(&mut self, child: TypeRef, parent: TypeRef, kind: &str);
+
+    /// Resolve which type in the hierarchy provides a method
+    fn resolve_method(&self, ty: &TypeRef, method: &str) -> Option<TypeRef>;
+
+    /// Get the inheritance chain for a type, starting with the type itself
+    fn get_inheritance_chain(&self, ty: &TypeRef) -> Vec<TypeRef>;
+
+    /// Check if one type is a subtype of another
+    fn is_subtype(&self, child: &TypeRef, parent: &TypeRef) -> bool;
+
+    /// Add methods that a type defines
+    fn add_type_methods(&mut self, ty: TypeRef, methods: Vec<String>);
+
+    /// Get all methods available on a type (including inherited)
+    fn get_all_methods(&self, ty: &TypeRef) -> Vec<String>;
+}
+
+/// String-keyed form of [`InheritanceResolver`], kept while languages
+/// migrate
+///
+/// Same-named types in different modules share one entry here. Results
+/// come back as [`TypeRef::named`], so they compare equal to the names
+/// callers pass in.
+pub trait NameKeyedInheritanceResolver: Send + Sync {
     /// Add an inheritance relationship
     fn add_inheritance(&mut self, child: String, parent: String, kind: &str);
 
@@ -372,6 +513,45 @@ pub trait InheritanceResolver: Send + Sync {
     fn get_all_methods(&self, type_name: &str) -> Vec<String>;
 }
 
+impl<T: NameKeyedInheritanceResolver> InheritanceResolver for T {
+    fn add_inheritance(&mut self, child: TypeRef, parent: TypeRef, kind: &str) {
+        NameKeyedInheritanceResolver::add_inheritance(
+            self,
+            child.qualified_name,
+            parent.qualified_name,
+            kind,
+        );
+    }
+
+    fn resolve_method(&self, ty: &TypeRef, method: &str) -> Option<TypeRef> {
+        NameKeyedInheritanceResolver::resolve_method(self, &ty.qualified_name, method)
+            .map(TypeRef::named)
+    }
+
+    fn get_inheritance_chain(&self, ty: &TypeRef) -> Vec<TypeRef> {
+        NameKeyedInheritanceResolver::get_inheritance_chain(self, &ty.qualified_name)
+            .into_iter()
+            .map(TypeRef::named)
+            .collect()
+    }
+
+    fn is_subtype(&self, child: &TypeRef, parent: &TypeRef) -> bool {
+        NameKeyedInheritanceResolver::is_subtype(
+            self,
+            &child.qualified_name,
+            &parent.qualified_name,
+        )
+    }
+
+    fn add_type_methods(&mut self, ty: TypeRef, methods: Vec<String>) {
+        NameKeyedInheritanceResolver::add_type_methods(self, ty.qualified_name, methods);
+    }
+
+    fn get_all_methods(&self, ty: &TypeRef) -> Vec<String> {
+        NameKeyedInheritanceResolver::get_all_methods(self, &ty.qualified_name)
+    }
+}
+
 /// Generic resolution context that wraps the existing ResolutionContext
 ///
 /// This provides a default implementation that maintains backward compatibility
@@ -480,9 +660,9 @@ impl ResolutionScope for GenericResolutionContext {
 /// to provide their own inheritance semantics.
 pub struct GenericInheritanceResolver {
     /// Maps child to parent relationships
-    inheritance: HashMap<String, Vec<(String, String)>>, // (parent, kind)
+    inheritance: HashMap<TypeRef, Vec<(TypeRef, String)>>, // (parent, kind)
     /// Maps types to their methods
-    type_methods: HashMap<String, Vec<String>>,
+    type_methods: HashMap<TypeRef, Vec<String>>,
 }
 
 impl GenericInheritanceResolver {
@@ -502,23 +682,23 @@ impl Default for GenericInheritanceResolver {
 }
 
 impl InheritanceResolver for GenericInheritanceResolver {
-    fn add_inheritance(&mut self, child: String, parent: String, kind: &str) {
+    fn add_inheritance(&mut self, child: TypeRef, parent: TypeRef, kind: &str) {
         self.inheritance
             .entry(child)
             .or_default()
             .push((parent, kind.to_string()));
     }
 
-    fn resolve_method(&self, type_name: &str, method: &str) -> Option<String> {
+    fn resolve_method(&self, ty: &TypeRef, method: &str) -> Option<TypeRef> {
         // First check if the type has the method directly
-        if let Some(methods) = self.type_methods.get(type_name) {
-            if methods.contains(&method.to_string()) {
-                return Some(type_name.to_string());
+        if let Some(methods) = self.type_methods.get(ty) {
+            if methods.iter().any(|m| m == method) {
+                return Some(ty.clone());
             }
         }
 
         // Then check parent types
-        if let Some(parents) = self.inheritance.get(type_name) {
+        if let Some(parents) = self.inheritance.get(ty) {
             for (parent, _kind) in parents {
                 if let Some(result) = self.resolve_method(parent, method) {
                     return Some(result);
@@ -529,19 +709,19 @@ impl InheritanceResolver for GenericInheritanceResolver {
         None
     }
 
-    fn get_inheritance_chain(&self, type_name: &str) -> Vec<String> {
-        let mut chain = vec![type_name.to_string()];
+    fn get_inheritance_chain(&self, ty: &TypeRef) -> Vec<TypeRef> {
+        let mut chain = vec![ty.clone()];
         let mut visited = std::collections::HashSet::new();
-        visited.insert(type_name.to_string());
+        visited.insert(ty);
 
-        let mut to_visit = vec![type_name.to_string()];
+        let mut to_visit = vec![ty];
 
         while let Some(current) = to_visit.pop() {
-            if let Some(parents) = self.inheritance.get(&current) {
+            if let Some(parents) = self.inheritance.get(current) {
                 for (parent, _kind) in parents {
-                    if visited.insert(parent.clone()) {
+                    if visited.insert(parent) {
                         chain.push(parent.clone());
-                        to_visit.push(parent.clone());
+                        to_visit.push(parent);
                     }
                 }
             }
@@ -550,22 +730,22 @@ impl InheritanceResolver for GenericInheritanceResolver {
         chain
     }
 
-    fn is_subtype(&self, child: &str, parent: &str) -> bool {
+    fn is_subtype(&self, child: &TypeRef, parent: &TypeRef) -> bool {
         if child == parent {
             return true;
         }
 
         let chain = self.get_inheritance_chain(child);
-        chain.contains(&parent.to_string())
+        chain.contains(parent)
     }
 
-    fn add_type_methods(&mut self, type_name: String, methods: Vec<String>) {
-        self.type_methods.insert(type_name, methods);
+    fn add_type_methods(&mut self, ty: TypeRef, methods: Vec<String>) {
+        self.type_methods.insert(ty, methods);
     }
 
-    fn get_all_methods(&self, type_name: &str) -> Vec<String> {
+    fn get_all_methods(&self, ty: &TypeRef) -> Vec<String> {
         let mut methods = Vec::new();
-        let chain = self.get_inheritance_chain(type_name);
+        let chain = self.get_inheritance_chain(ty);
 
         for ancestor in chain {
             if let Some(type_methods) = self.type_methods.get(&ancestor) {
@@ -680,6 +860,24 @@ pub trait PipelineSymbolCache: Send + Sync {
         imports: &[Import],
     ) -> ResolveResult;
 
+    /// Resolve the symbol an import brings into scope.
+    ///
+    /// `name` is the binding the import introduces; `importing_module` and
+    /// `import_path` identify the import, so implementations can remember the
+    /// answer across files of a module and across runs. Defaults to
+    /// [`resolve`](Self::resolve) without a reference range.
+    fn resolve_import(
+        &self,
+        name: &str,
+        caller: &CallerContext,
+        importing_module: Option<&str>,
+        import_path: &str,
+        imports: &[Import],
+    ) -> ResolveResult {
+        let _ = (importing_module, import_path);
+        self.resolve(name, caller, None, imports)
+    }
+
     /// Direct lookup by ID for metadata access.
     ///
     /// Used after resolution to get full symbol details (module_path, kind, etc).
@@ -777,39 +975,106 @@ mod tests {
     #[test]
     fn test_generic_inheritance_resolver() {
         let mut resolver = GenericInheritanceResolver::new();
+        let child = TypeRef::named("Child");
+        let parent = TypeRef::named("Parent");
+        let grand_parent = TypeRef::named("GrandParent");
 
         // Set up a simple inheritance hierarchy
-        resolver.add_inheritance("Child".to_string(), "Parent".to_string(), "extends");
-        resolver.add_inheritance("Parent".to_string(), "GrandParent".to_string(), "extends");
+        resolver.add_inheritance(child.clone(), parent.clone(), "extends");
+        resolver.add_inheritance(parent.clone(), grand_parent.clone(), "extends");
 
         // Add methods
-        resolver.add_type_methods("GrandParent".to_string(), vec!["method1".to_string()]);
-        resolver.add_type_methods("Parent".to_string(), vec!["method2".to_string()]);
-        resolver.add_type_methods("Child".to_string(), vec!["method3".to_string()]);
+        resolver.add_type_methods(grand_parent.clone(), vec!["method1".to_string()]);
+        resolver.add_type_methods(parent.clone(), vec!["method2".to_string()]);
+        resolver.add_type_methods(child.clone(), vec!["method3".to_string()]);
 
         // Test method resolution
         assert_eq!(
-            resolver.resolve_method("Child", "method3"),
-            Some("Child".to_string())
+            resolver.resolve_method(&child, "method3"),
+            Some(child.clone())
         );
         assert_eq!(
-            resolver.resolve_method("Child", "method2"),
-            Some("Parent".to_string())
+            resolver.resolve_method(&child, "method2"),
+            Some(parent.clone())
         );
         assert_eq!(
-            resolver.resolve_method("Child", "method1"),
-            Some("GrandParent".to_string())
+            resolver.resolve_method(&child, "method1"),
+            Some(grand_parent.clone())
         );
 
         // Test inheritance chain
-        let chain = resolver.get_inheritance_chain("Child");
-        assert!(chain.contains(&"Child".to_string()));
-        assert!(chain.contains(&"Parent".to_string()));
-        assert!(chain.contains(&"GrandParent".to_string()));
+        let chain = resolver.get_inheritance_chain(&child);
+        assert!(chain.contains(&child));
+        assert!(chain.contains(&parent));
+        assert!(chain.contains(&grand_parent));
 
         // Test subtype checking
-        assert!(resolver.is_subtype("Child", "Parent"));
-        assert!(resolver.is_subtype("Child", "GrandParent"));
-        assert!(!resolver.is_subtype("Parent", "Child"));
+        assert!(resolver.is_subtype(&child, &parent));
+        assert!(resolver.is_subtype(&child, &grand_parent));
+        assert!(!resolver.is_subtype(&parent, &child));
+    }
+
+    #[test]
+    fn test_same_named_types_stay_apart() {
+        let mut resolver = GenericInheritanceResolver::new();
+        let app_user = TypeRef::new(SymbolId::new(1).unwrap(), "app.models.User");
+        let admin_user = TypeRef::new(SymbolId::new(2).unwrap(), "admin.models.User");
+        let base = TypeRef::new(SymbolId::new(3).unwrap(), "app.models.Base");
+
+        resolver.add_inheritance(app_user.clone(), base.clone(), "extends");
+        resolver.add_type_methods(base.clone(), vec!["save".to_string()]);
+
+        assert!(resolver.is_subtype(&app_user, &base));
+        assert!(!resolver.is_subtype(&admin_user, &base));
+        assert_eq!(resolver.resolve_method(&app_user, "save"), Some(base));
+        assert_eq!(resolver.resolve_method(&admin_user, "save"), None);
+    }
+
+    #[test]
+    fn test_named_type_refs_are_stable() {
+        let a = TypeRef::named("pkg.Base");
+        assert_eq!(a, TypeRef::named("pkg.Base"));
+        assert_ne!(a, TypeRef::named("other.Base"));
+        assert!(!a.is_indexed());
+        assert!(TypeRef::new(SymbolId::new(7).unwrap(), "pkg.Base").is_indexed());
+    }
+
+    #[test]
+    fn test_named_type_refs_compare_by_name() {
+        let base = TypeRef::named("pkg.Base");
+        // Same derived id, different name: a hash collision, not the same type
+        let colliding = TypeRef {
+            id: base.id,
+            qualified_name: "pkg.Other".to_string(),
+        };
+        assert_ne!(base, colliding);
+
+        let mut resolver = GenericInheritanceResolver::new();
+        resolver.add_inheritance(TypeRef::named("pkg.Child"), base.clone(), "extends");
+        assert!(resolver.is_subtype(&TypeRef::named("pkg.Child"), &base));
+        assert!(!resolver.is_subtype(&TypeRef::named("pkg.Child"), &colliding));
+    }
+
+    #[test]
+    fn test_lookup_finds_indexed_type() {
+        use crate::indexing::pipeline::types::SymbolLookupCache;
+
+        let symbols = SymbolLookupCache::new();
+        for (id, module) in [(1, "app.models"), (2, "admin.models")] {
+            let mut user = crate::Symbol::new(
+                SymbolId::new(id).unwrap(),
+                "User",
+                crate::SymbolKind::Class,
+                FileId::new(id).unwrap(),
+                Range::new(0, 0, 1, 0),
+            );
+            user.module_path = Some(module.into());
+            symbols.insert(user);
+        }
+
+        let user = TypeRef::lookup("admin.models.User", &symbols);
+        assert_eq!(user.id, SymbolId::new(2).unwrap());
+        assert!(user.is_indexed());
+        assert!(!TypeRef::lookup("lib.models.User", &symbols).is_indexed());
     }
 }
@@ -4,6 +4,8 @@ use super::resolution::{RustResolutionContext, RustTraitResolver};
 use crate::FileId;
 use crate::Visibility;
 use crate::parsing::behavior_state::{BehaviorState, StatefulBehavior};
+use crate::parsing::control_flow::BranchNodeKinds;
+use crate::parsing::references::ReferenceNodeKinds;
 use crate::parsing::{InheritanceResolver, LanguageBehavior, ResolutionScope};
 use std::path::PathBuf;
 use std::sync::{Arc, RwLock};
@@ -40,6 +42,44 @@ impl Default for RustBehavior {
     }
 }
 
+/// Reference pass node kinds. Paths are kept whole (`Foo::new` references
+/// both `Foo` and `new`), including in patterns, where `Status::Pending`
+/// matches a variant rather than binding a name; tuple and struct patterns
+/// bind their fields but not the variant they match.
+static REFERENCE_NODES: ReferenceNodeKinds = ReferenceNodeKinds {
+    identifiers: &["identifier"],
+    bindings: &[
+        ("let_declaration", Some("pattern")),
+        ("parameter", Some("pattern")),
+        ("closure_parameters", None),
+        ("for_expression", Some("pattern")),
+        ("match_arm", Some("pattern")),
+        ("let_condition", Some("pattern")),
+        ("tuple_struct_pattern", None),
+        ("struct_pattern", None),
+        ("field_pattern", Some("name")),
+        ("field_pattern", Some("pattern")),
+    ],
+    members: &[],
+    paths: &["scoped_identifier"],
+    fields: &[("field_expression", "value", "field")],
+    containers: &[("impl_item", "type"), ("trait_item", "name")],
+    this: &["self"],
+};
+
+/// Conditional-region node kinds. A `match` body counts as a whole since
+/// only one arm runs.
+static BRANCH_NODES: BranchNodeKinds = BranchNodeKinds {
+    branches: &[
+        ("if_expression", &["consequence", "alternative"]),
+        ("match_expression", &["body"]),
+        ("while_expression", &["body"]),
+        ("for_expression", &["body"]),
+        ("closure_expression", &[]),
+    ],
+    short_circuit: &["&&", "||"],
+};
+
 impl LanguageBehavior for RustBehavior {
     fn language_id(&self) -> crate::parsing::registry::LanguageId {
         crate::parsing::registry::LanguageId::new("rust")
@@ -77,6 +117,30 @@ impl LanguageBehavior for RustBehavior {
         self.language.clone()
     }
 
+    fn reference_node_kinds(&self) -> Option<&'static ReferenceNodeKinds> {
+        Some(&REFERENCE_NODES)
+    }
+
+    fn branch_node_kinds(&self) -> Option<&'static BranchNodeKinds> {
+        Some(&BRANCH_NODES)
+    }
+
+    fn import_declaration_kinds(&self) -> &'static [&'static str] {
+        &["use_declaration"]
+    }
+
+    fn import_bindings(&self, import: &crate::parsing::Import) -> Vec<String> {
+        // `use Trait as _` brings methods into scope without a name
+        if import.alias.as_deref() == Some("_") || import.is_glob {
+            return Vec::new();
+        }
+        let name = import
+            .alias
+            .as_deref()
+            .or_else(|| import.path.rsplit("::").next());
+        name.map(|name| vec![name.to_string()]).unwrap_or_default()
+    }
+
     fn format_path_as_module(&self, components: &[&str]) -> Option<String> {
         // Handle empty path
         if components.is_empty() {
@@ -232,6 +296,8 @@ impl LanguageBehavior for RustBehavior {
                 // For now, be conservative and return false
                 false
             }
+            // Not produced by the Rust parser
+            Visibility::Protected | Visibility::Package => false,
             Visibility::Private => false,
         }
     }
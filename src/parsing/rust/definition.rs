@@ -32,7 +32,7 @@ impl LanguageDefinition for RustLanguage {
     }
 
     fn create_parser(&self, _settings: &Settings) -> IndexResult<Box<dyn LanguageParser>> {
-        let parser = RustParser::new().map_err(crate::IndexError::General)?;
+        let parser = RustParser::new().map_err(|e| crate::IndexError::parser_init("rust", e))?;
         Ok(Box::new(parser))
     }
 
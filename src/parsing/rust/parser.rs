@@ -34,12 +34,47 @@ use crate::parsing::Import;
 use crate::parsing::method_call::MethodCall;
 use crate::parsing::parser::check_recursion_depth;
 use crate::parsing::{
-    HandledNode, Language, LanguageParser, NodeTracker, NodeTrackingState, ParserContext, ScopeType,
+    GenericInstantiation, HandledNode, Instantiation, Language, LanguageParser, NodeTracker,
+    NodeTrackingState, OperatorCall, ParserContext, ScopeType,
 };
-use crate::types::SymbolCounter;
+use crate::symbol::ScopeContext;
+use crate::types::{SymbolCounter, compact_string};
 use crate::{FileId, Range, Symbol, SymbolKind};
 use tree_sitter::{Node, Parser};
 
+/// Overloadable operators and the `std::ops`/`std::cmp` trait method each runs
+const OPERATOR_METHODS: &[(&str, &str)] = &[
+    ("+", "add"),
+    ("-", "sub"),
+    ("*", "mul"),
+    ("/", "div"),
+    ("%", "rem"),
+    ("&", "bitand"),
+    ("|", "bitor"),
+    ("^", "bitxor"),
+    ("<<", "shl"),
+    (">>", "shr"),
+    ("==", "eq"),
+    ("!=", "ne"),
+    ("<", "lt"),
+    ("<=", "le"),
+    (">", "gt"),
+    (">=", "ge"),
+    ("+=", "add_assign"),
+    ("-=", "sub_assign"),
+    ("*=", "mul_assign"),
+    ("/=", "div_assign"),
+    ("%=", "rem_assign"),
+    ("&=", "bitand_assign"),
+    ("|=", "bitor_assign"),
+    ("^=", "bitxor_assign"),
+    ("<<=", "shl_assign"),
+    (">>=", "shr_assign"),
+];
+
+/// Prefix operators, which share symbols with binary ones
+const UNARY_OPERATOR_METHODS: &[(&str, &str)] = &[("-", "neg"), ("!", "not")];
+
 // Helper enum for doc comment type classification
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum DocCommentType {
@@ -142,6 +177,7 @@ impl RustParser {
                     file_id,
                     is_glob: false,
                     is_type_only: false,
+                    range: Some(Import::range_of(&node)),
                 });
             }
             "scoped_identifier" => {
@@ -153,6 +189,7 @@ impl RustParser {
                     file_id,
                     is_glob: false,
                     is_type_only: false,
+                    range: Some(Import::range_of(&node)),
                 });
             }
             "use_as_clause" => {
@@ -167,6 +204,7 @@ impl RustParser {
                             file_id,
                             is_glob: false,
                             is_type_only: false,
+                            range: Some(Import::range_of(&node)),
                         });
                     }
                 }
@@ -183,6 +221,7 @@ impl RustParser {
                             file_id,
                             is_glob: true,
                             is_type_only: false,
+                            range: Some(Import::range_of(&node)),
                         });
                         break;
                     }
@@ -243,6 +282,7 @@ impl RustParser {
                     file_id,
                     is_glob: false,
                     is_type_only: false,
+                    range: Some(Import::range_of(&node)),
                 });
             }
             "use_as_clause" => {
@@ -261,6 +301,7 @@ impl RustParser {
                             file_id,
                             is_glob: false,
                             is_type_only: false,
+                            range: Some(Import::range_of(&node)),
                         });
                     }
                 }
@@ -482,13 +523,17 @@ impl RustParser {
                     }
                 }
 
-                // Process enum variants
+                // Process enum variants, scoped to the enum so that
+                // `Status::Pending` addresses one like `Type::method` does
+                let enum_name = node
+                    .child_by_field_name("name")
+                    .map(|n| compact_string(&code[n.byte_range()]));
                 if let Some(body) = node.child_by_field_name("body") {
                     for child in body.children(&mut body.walk()) {
                         if child.kind() == "enum_variant" {
                             self.register_handled_node("enum_variant", child.kind_id());
                             if let Some(name_node) = child.child_by_field_name("name") {
-                                if let Some(symbol) = self.create_symbol(
+                                if let Some(mut symbol) = self.create_symbol(
                                     counter,
                                     child,
                                     name_node,
@@ -496,6 +541,9 @@ impl RustParser {
                                     file_id,
                                     code,
                                 ) {
+                                    symbol.scope_context = Some(ScopeContext::ClassMember {
+                                        class_name: enum_name.clone(),
+                                    });
                                     symbols.push(symbol);
                                 }
                             }
@@ -794,6 +842,22 @@ impl RustParser {
         defines
     }
 
+    /// Find type aliases (`type NodeId = u32;`)
+    /// Returns Vec<(alias_name, target_type, range)>
+    pub fn find_aliases<'a>(&mut self, code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        let tree = match self.parser.parse(code, None) {
+            Some(tree) => tree,
+            None => return Vec::new(),
+        };
+
+        let root_node = tree.root_node();
+        let mut aliases = Vec::new();
+
+        self.find_aliases_in_node(root_node, code, &mut aliases);
+
+        aliases
+    }
+
     /// Find inherent methods (methods in impl blocks without traits)
     /// Returns Vec<(type_name, method_name, range)>
     pub fn find_inherent_methods(&mut self, code: &str) -> Vec<(String, String, Range)> {
@@ -877,6 +941,299 @@ impl RustParser {
         }
     }
 
+    /// Struct literals and `Type::new(..)` calls inside functions.
+    ///
+    /// A literal builds the value directly, so only `Type::new` reports a
+    /// constructor. `Self { .. }` and calls on lowercase paths are skipped.
+    fn find_instantiations_in_node<'a>(
+        &self,
+        node: Node,
+        code: &'a str,
+        context: Option<(&'a str, Range)>,
+        instantiations: &mut Vec<Instantiation>,
+        depth: usize,
+    ) {
+        if !check_recursion_depth(depth, node) {
+            return;
+        }
+        let range_of = |node: Node| {
+            Range::new(
+                node.start_position().row as u32,
+                node.start_position().column as u16,
+                node.end_position().row as u32,
+                node.end_position().column as u16,
+            )
+        };
+        let context = match node.kind() {
+            "function_item" => node
+                .child_by_field_name("name")
+                .map(|name| (&code[name.byte_range()], range_of(node)))
+                .or(context),
+            _ => context,
+        };
+
+        let site = match node.kind() {
+            "struct_expression" => node
+                .child_by_field_name("name")
+                .and_then(|name| Some((self.extract_type_name(name, code)?, name, None))),
+            "call_expression" => node
+                .child_by_field_name("function")
+                .filter(|function| function.kind() == "scoped_identifier")
+                .filter(|function| {
+                    function
+                        .child_by_field_name("name")
+                        .is_some_and(|name| &code[name.byte_range()] == "new")
+                })
+                .and_then(|function| function.child_by_field_name("path"))
+                .map(|path| (&code[path.byte_range()], path, Some("new"))),
+            _ => None,
+        };
+        if let (Some((context, context_range)), Some((path, type_node, constructor))) =
+            (context, site)
+        {
+            let type_name = path.rsplit("::").next().unwrap_or(path);
+            if type_name != "Self" && type_name.starts_with(|c: char| c.is_ascii_uppercase()) {
+                instantiations.push(Instantiation {
+                    context: context.to_string(),
+                    context_range,
+                    type_name: type_name.to_string(),
+                    constructor: constructor.map(str::to_string),
+                    range: range_of(type_node),
+                });
+            }
+        }
+
+        for child in node.children(&mut node.walk()) {
+            self.find_instantiations_in_node(child, code, context, instantiations, depth + 1);
+        }
+    }
+
+    /// Collects the error types a function can return: the `E` of a
+    /// `Result<T, E>` return type, and the type built in `Err(..)`. Errors
+    /// that `?` propagates convert into the return type's `E`, so that covers
+    /// them; `Box<dyn Error>` and other generic errors name no type.
+    fn find_throws_in_node<'a>(
+        &self,
+        node: Node,
+        code: &'a str,
+        context: Option<&'a str>,
+        throws: &mut Vec<(String, String, Range)>,
+        depth: usize,
+    ) {
+        if !check_recursion_depth(depth, node) {
+            return;
+        }
+        let range_of = |node: Node| {
+            Range::new(
+                node.start_position().row as u32,
+                node.start_position().column as u16,
+                node.end_position().row as u32,
+                node.end_position().column as u16,
+            )
+        };
+        let context = match node.kind() {
+            "function_item" => node
+                .child_by_field_name("name")
+                .map(|name| &code[name.byte_range()])
+                .or(context),
+            _ => context,
+        };
+
+        let site = match node.kind() {
+            "function_item" => node
+                .child_by_field_name("return_type")
+                .filter(|returned| returned.kind() == "generic_type")
+                .filter(|returned| {
+                    returned.child_by_field_name("type").is_some_and(|result| {
+                        code[result.byte_range()].rsplit("::").next() == Some("Result")
+                    })
+                })
+                .and_then(|returned| returned.child_by_field_name("type_arguments"))
+                .and_then(|arguments| {
+                    arguments
+                        .named_children(&mut arguments.walk())
+                        .filter(|argument| argument.kind() != "lifetime")
+                        .nth(1)
+                }),
+            "call_expression" => node
+                .child_by_field_name("function")
+                .filter(|function| &code[function.byte_range()] == "Err")
+                .and_then(|_| node.child_by_field_name("arguments"))
+                .and_then(|arguments| arguments.named_child(0)),
+            _ => None,
+        };
+        let error = site.and_then(|site| Some((Self::error_type(site, code)?, site)));
+        if let (Some(context), Some((error, site))) = (context, error) {
+            throws.push((context.to_string(), error.to_string(), range_of(site)));
+        }
+
+        for child in node.children(&mut node.walk()) {
+            self.find_throws_in_node(child, code, context, throws, depth + 1);
+        }
+    }
+
+    /// The type an error expression or type names: `ConfigError` for
+    /// `ConfigError`, `errors::ConfigError`, `ConfigError::Missing`,
+    /// `ConfigError::Parse(e)` or `ConfigError { .. }`
+    fn error_type<'a>(node: Node, code: &'a str) -> Option<&'a str> {
+        let name = match node.kind() {
+            "type_identifier" | "identifier" => node,
+            "scoped_type_identifier" => node.child_by_field_name("name")?,
+            "scoped_identifier" => {
+                let path = node.child_by_field_name("path")?;
+                match path.kind() {
+                    "scoped_identifier" => path.child_by_field_name("name")?,
+                    _ => path,
+                }
+            }
+            "struct_expression" => {
+                return Self::error_type(node.child_by_field_name("name")?, code);
+            }
+            "call_expression" => {
+                return Self::error_type(node.child_by_field_name("function")?, code);
+            }
+            _ => return None,
+        };
+        Some(&code[name.byte_range()])
+            .filter(|name| name.starts_with(|c: char| c.is_ascii_uppercase()))
+    }
+
+    /// Collects generics named with type arguments in functions, structs,
+    /// enums and type aliases. Lifetimes aren't type arguments.
+    fn find_generic_instantiations_in_node<'a>(
+        &self,
+        node: Node,
+        code: &'a str,
+        context: Option<(&'a str, Range)>,
+        generics: &mut Vec<GenericInstantiation>,
+        depth: usize,
+    ) {
+        if !check_recursion_depth(depth, node) {
+            return;
+        }
+        let range_of = |node: Node| {
+            Range::new(
+                node.start_position().row as u32,
+                node.start_position().column as u16,
+                node.end_position().row as u32,
+                node.end_position().column as u16,
+            )
+        };
+        let context = match node.kind() {
+            "function_item" | "struct_item" | "enum_item" | "type_item" => node
+                .child_by_field_name("name")
+                .map(|name| (&code[name.byte_range()], range_of(node)))
+                .or(context),
+            _ => context,
+        };
+
+        let generic = match node.kind() {
+            "generic_type" | "generic_type_with_turbofish" => node.child_by_field_name("type"),
+            "generic_function" => node.child_by_field_name("function"),
+            _ => None,
+        }
+        .map(|generic| match generic.kind() {
+            "scoped_identifier" | "scoped_type_identifier" => {
+                generic.child_by_field_name("name").unwrap_or(generic)
+            }
+            "field_expression" => generic.child_by_field_name("field").unwrap_or(generic),
+            _ => generic,
+        });
+        let type_arguments: Vec<String> = node
+            .child_by_field_name("type_arguments")
+            .map(|arguments| {
+                arguments
+                    .named_children(&mut arguments.walk())
+                    .filter(|argument| argument.kind() != "lifetime")
+                    .map(|argument| code[argument.byte_range()].to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        if let (Some((context, context_range)), Some(generic), false) =
+            (context, generic, type_arguments.is_empty())
+        {
+            generics.push(GenericInstantiation {
+                context: context.to_string(),
+                context_range,
+                generic: code[generic.byte_range()].to_string(),
+                type_arguments,
+                range: range_of(generic),
+            });
+        }
+
+        for child in node.children(&mut node.walk()) {
+            self.find_generic_instantiations_in_node(child, code, context, generics, depth + 1);
+        }
+    }
+
+    /// Collects operators that may run a trait overload, skipping operations
+    /// on two literals, which no user code can overload.
+    fn find_operator_calls_in_node<'a>(
+        &self,
+        node: Node,
+        code: &'a str,
+        context: Option<(&'a str, Range)>,
+        calls: &mut Vec<OperatorCall>,
+        depth: usize,
+    ) {
+        if !check_recursion_depth(depth, node) {
+            return;
+        }
+        let range_of = |node: Node| {
+            Range::new(
+                node.start_position().row as u32,
+                node.start_position().column as u16,
+                node.end_position().row as u32,
+                node.end_position().column as u16,
+            )
+        };
+        let context = match node.kind() {
+            "function_item" => node
+                .child_by_field_name("name")
+                .map(|name| (&code[name.byte_range()], range_of(node)))
+                .or(context),
+            _ => context,
+        };
+        let is_literal =
+            |operand: Option<Node>| operand.is_some_and(|n| n.kind().ends_with("_literal"));
+
+        let site = match node.kind() {
+            "binary_expression" | "compound_assignment_expr" => node
+                .child_by_field_name("operator")
+                .filter(|_| {
+                    !(is_literal(node.child_by_field_name("left"))
+                        && is_literal(node.child_by_field_name("right")))
+                })
+                .and_then(|operator| {
+                    OPERATOR_METHODS
+                        .iter()
+                        .find(|(symbol, _)| *symbol == &code[operator.byte_range()])
+                        .map(|&(symbol, method)| (symbol, method, operator))
+                }),
+            "unary_expression" => node.child(0).and_then(|operator| {
+                UNARY_OPERATOR_METHODS
+                    .iter()
+                    .find(|(symbol, _)| *symbol == operator.kind())
+                    .map(|&(symbol, method)| (symbol, method, operator))
+            }),
+            "index_expression" => Some(("[]", "index", node)),
+            _ => None,
+        };
+        if let (Some((context, context_range)), Some((operator, method, site))) = (context, site) {
+            calls.push(OperatorCall {
+                context: context.to_string(),
+                context_range,
+                operator,
+                method,
+                range: range_of(site),
+            });
+        }
+
+        for child in node.children(&mut node.walk()) {
+            self.find_operator_calls_in_node(child, code, context, calls, depth + 1);
+        }
+    }
+
     /// Recursively extracts method calls from AST nodes with enhanced receiver detection.
     ///
     /// Handles direct function calls, instance methods, and static method calls.
@@ -1024,6 +1381,38 @@ impl RustParser {
         }
     }
 
+    fn find_aliases_in_node<'a>(
+        &self,
+        node: Node,
+        code: &'a str,
+        aliases: &mut Vec<(&'a str, &'a str, Range)>,
+    ) {
+        match node.kind() {
+            "type_item" => {
+                let name = node.child_by_field_name("name");
+                let target = node
+                    .child_by_field_name("type")
+                    .and_then(|type_node| self.extract_type_name(type_node, code));
+                if let (Some(name_node), Some(target)) = (name, target) {
+                    let range = Range::new(
+                        node.start_position().row as u32,
+                        node.start_position().column as u16,
+                        node.end_position().row as u32,
+                        node.end_position().column as u16,
+                    );
+                    aliases.push((&code[name_node.byte_range()], target, range));
+                }
+            }
+            // Associated types name a trait's placeholder, not another type
+            "impl_item" | "trait_item" => return,
+            _ => {}
+        }
+
+        for child in node.children(&mut node.walk()) {
+            self.find_aliases_in_node(child, code, aliases);
+        }
+    }
+
     fn find_variable_types_in_node<'a>(
         &self,
         node: Node,
@@ -1444,6 +1833,31 @@ impl RustParser {
                     }
                 }
             }
+            "enum_item" => {
+                if let (Some(name_node), Some(body)) = (
+                    node.child_by_field_name("name"),
+                    node.child_by_field_name("body"),
+                ) {
+                    let enum_name = &code[name_node.byte_range()];
+                    for child in body.children(&mut body.walk()) {
+                        if child.kind() == "enum_variant" {
+                            if let Some(variant_name_node) = child.child_by_field_name("name") {
+                                let range = Range::new(
+                                    child.start_position().row as u32,
+                                    child.start_position().column as u16,
+                                    child.end_position().row as u32,
+                                    child.end_position().column as u16,
+                                );
+                                defines.push((
+                                    enum_name,
+                                    &code[variant_name_node.byte_range()],
+                                    range,
+                                ));
+                            }
+                        }
+                    }
+                }
+            }
             _ => {}
         }
 
@@ -1645,41 +2059,21 @@ impl RustParser {
     }
 
     fn extract_doc_comments(&self, node: &Node, code: &str) -> Option<String> {
-        let mut doc_lines = Vec::new();
-        let mut current = node.prev_sibling();
-
-        while let Some(sibling) = current {
-            match sibling.kind() {
-                "line_comment" | "block_comment" => {
-                    if let Ok(text) = sibling.utf8_text(code.as_bytes()) {
-                        if self.is_outer_doc_comment(text) {
-                            let comment_type = self.classify_doc_comment(text);
-                            let content = match comment_type {
-                                DocCommentType::OuterLine => {
-                                    text.trim_start_matches("///").trim().to_string()
-                                }
-                                DocCommentType::OuterBlock => text
-                                    .trim_start_matches("/**")
-                                    .trim_end_matches("*/")
-                                    .trim()
-                                    .to_string(),
-                                _ => break, // Should not happen due to is_outer_doc_comment check
-                            };
-                            doc_lines.push(content);
-                        } else {
-                            break; // Non-outer-doc comment ends the sequence
-                        }
-                    }
-                }
-                _ => break, // Non-comment node ends the sequence
-            }
-            current = sibling.prev_sibling();
-        }
+        let doc_lines: Vec<&str> = self
+            .preceding_comments(node, code, &|text| self.is_outer_doc_comment(text))
+            .into_iter()
+            .filter_map(|text| match self.classify_doc_comment(text) {
+                DocCommentType::OuterLine => Some(text.trim_start_matches("///").trim()),
+                DocCommentType::OuterBlock => {
+                    Some(text.trim_start_matches("/**").trim_end_matches("*/").trim())
+                }
+                _ => None, // Ruled out by is_outer_doc_comment
+            })
+            .collect();
 
         if doc_lines.is_empty() {
             None
         } else {
-            doc_lines.reverse(); // Restore original order
             Some(doc_lines.join("\n"))
         }
     }
@@ -1732,6 +2126,50 @@ impl LanguageParser for RustParser {
         self.find_calls(code)
     }
 
+    fn find_instantiations(&mut self, code: &str) -> Vec<Instantiation> {
+        let tree = match self.parser.parse(code, None) {
+            Some(tree) => tree,
+            None => return Vec::new(),
+        };
+
+        let mut instantiations = Vec::new();
+        self.find_instantiations_in_node(tree.root_node(), code, None, &mut instantiations, 0);
+        instantiations
+    }
+
+    fn find_generic_instantiations(&mut self, code: &str) -> Vec<GenericInstantiation> {
+        let tree = match self.parser.parse(code, None) {
+            Some(tree) => tree,
+            None => return Vec::new(),
+        };
+
+        let mut generics = Vec::new();
+        self.find_generic_instantiations_in_node(tree.root_node(), code, None, &mut generics, 0);
+        generics
+    }
+
+    fn find_throws(&mut self, code: &str) -> Vec<(String, String, Range)> {
+        let tree = match self.parser.parse(code, None) {
+            Some(tree) => tree,
+            None => return Vec::new(),
+        };
+
+        let mut throws = Vec::new();
+        self.find_throws_in_node(tree.root_node(), code, None, &mut throws, 0);
+        throws
+    }
+
+    fn find_operator_calls(&mut self, code: &str) -> Vec<OperatorCall> {
+        let tree = match self.parser.parse(code, None) {
+            Some(tree) => tree,
+            None => return Vec::new(),
+        };
+
+        let mut calls = Vec::new();
+        self.find_operator_calls_in_node(tree.root_node(), code, None, &mut calls, 0);
+        calls
+    }
+
     fn find_method_calls(&mut self, code: &str) -> Vec<MethodCall> {
         tracing::debug!("[rust-parser] find_method_calls called with enhanced AST detection");
 
@@ -1756,6 +2194,10 @@ impl LanguageParser for RustParser {
         self.find_implementations(code)
     }
 
+    fn find_aliases<'a>(&mut self, code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        self.find_aliases(code)
+    }
+
     fn find_uses<'a>(&mut self, code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
         self.find_uses(code)
     }
@@ -1838,6 +2280,40 @@ mod tests {
         assert_eq!(symbols[0].kind, SymbolKind::Struct);
     }
 
+    #[test]
+    fn test_enum_variants_are_enum_members() {
+        let mut parser = RustParser::new().unwrap();
+        let code = r#"
+            enum Status {
+                Pending,
+                Failed(String),
+            }
+        "#;
+        let file_id = FileId::new(1).unwrap();
+
+        let mut counter = SymbolCounter::new();
+        let symbols = parser.parse(code, file_id, &mut counter);
+
+        let pending = symbols
+            .iter()
+            .find(|s| s.name.as_ref() == "Pending")
+            .expect("variant symbol");
+        assert_eq!(pending.kind, SymbolKind::Constant);
+        assert_eq!(
+            pending.scope_context,
+            Some(ScopeContext::ClassMember {
+                class_name: Some("Status".into())
+            })
+        );
+
+        let defines: Vec<_> = parser
+            .find_defines(code)
+            .into_iter()
+            .map(|(definer, member, _)| (definer, member))
+            .collect();
+        assert_eq!(defines, [("Status", "Pending"), ("Status", "Failed")]);
+    }
+
     #[test]
     fn test_find_imports() {
         let mut parser = RustParser::new().unwrap();
@@ -2212,6 +2688,179 @@ mod tests {
         assert_eq!(debug_impl.1, "std::fmt::Debug");
     }
 
+    #[test]
+    fn test_find_instantiations() {
+        let mut parser = RustParser::new().unwrap();
+        let code = r#"
+impl Config {
+    fn new() -> Self {
+        Self { port: 80 }
+    }
+}
+
+fn start() {
+    let config = Config::new();
+    let server = net::Server { config };
+    let items = vec::new();
+}
+"#;
+        let sites: Vec<_> = parser
+            .find_instantiations(code)
+            .into_iter()
+            .map(|site| (site.context, site.type_name, site.constructor))
+            .collect();
+
+        assert_eq!(
+            sites,
+            vec![
+                (
+                    "start".to_string(),
+                    "Config".to_string(),
+                    Some("new".to_string())
+                ),
+                ("start".to_string(), "Server".to_string(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_throws() {
+        let mut parser = RustParser::new().unwrap();
+        let code = r#"
+fn load(path: &Path) -> Result<Config, errors::ConfigError> {
+    let text = fs::read_to_string(path)?;
+    if text.is_empty() {
+        return Err(ConfigError::Empty);
+    }
+    parse(&text).map_err(|e| ConfigError::Parse(e))
+}
+
+fn parse(text: &str) -> Result<Config, Box<dyn Error>> {
+    Err(ParseError { line: 1 }.into())
+}
+
+fn check(value: u32) -> io::Result<()> {
+    Err(e)
+}
+"#;
+        let throws: Vec<_> = parser
+            .find_throws(code)
+            .into_iter()
+            .map(|(context, error, _)| (context, error))
+            .collect();
+
+        let pair = |context: &str, error: &str| (context.to_string(), error.to_string());
+        assert_eq!(
+            throws,
+            vec![pair("load", "ConfigError"), pair("load", "ConfigError")]
+        );
+    }
+
+    #[test]
+    fn test_find_generic_instantiations() {
+        let mut parser = RustParser::new().unwrap();
+        let code = r#"
+struct Registry<'a> {
+    sessions: HashMap<String, Session<'a>>,
+}
+
+fn load(input: &str) -> Result<Vec<Config>, Error> {
+    let config = serde_json::from_str::<Config>(input)?;
+    Ok(vec![config])
+}
+"#;
+        let sites: Vec<_> = parser
+            .find_generic_instantiations(code)
+            .into_iter()
+            .map(|site| (site.context, site.generic, site.type_arguments))
+            .collect();
+
+        let strings = |items: &[&str]| items.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            sites,
+            vec![
+                (
+                    "Registry".to_string(),
+                    "HashMap".to_string(),
+                    strings(&["String", "Session<'a>"])
+                ),
+                (
+                    "load".to_string(),
+                    "Result".to_string(),
+                    strings(&["Vec<Config>", "Error"])
+                ),
+                ("load".to_string(), "Vec".to_string(), strings(&["Config"])),
+                (
+                    "load".to_string(),
+                    "from_str".to_string(),
+                    strings(&["Config"])
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_operator_calls() {
+        let mut parser = RustParser::new().unwrap();
+        let code = r#"
+const LIMIT: u32 = 2 + 3;
+
+fn step(position: Vector, velocity: Vector, items: &[u32]) -> bool {
+    let mut next = position + velocity;
+    next -= -velocity;
+    let done = !next.is_zero() && items[0] == 1 + 1;
+    done
+}
+"#;
+        let calls: Vec<_> = parser
+            .find_operator_calls(code)
+            .into_iter()
+            .map(|call| (call.context, call.operator, call.method))
+            .collect();
+
+        let step = || "step".to_string();
+        assert_eq!(
+            calls,
+            vec![
+                (step(), "+", "add"),
+                (step(), "-=", "sub_assign"),
+                (step(), "-", "neg"),
+                (step(), "!", "not"),
+                (step(), "==", "eq"),
+                (step(), "[]", "index"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_aliases() {
+        let mut parser = RustParser::new().unwrap();
+        let code = r#"
+            type NodeId = u32;
+            type Result<T> = std::result::Result<T, Error>;
+            type Graph = HashMap<NodeId, Vec<NodeId>>;
+
+            impl Iterator for Walk {
+                type Item = NodeId;
+            }
+        "#;
+
+        let aliases: Vec<_> = parser
+            .find_aliases(code)
+            .into_iter()
+            .map(|(alias, target, _)| (alias, target))
+            .collect();
+
+        assert_eq!(
+            aliases,
+            vec![
+                ("NodeId", "u32"),
+                ("Result", "std::result::Result"),
+                ("Graph", "HashMap"),
+            ]
+        );
+    }
+
     #[test]
     fn test_find_inherent_methods() {
         let mut parser = RustParser::new().unwrap();
@@ -2972,4 +3621,30 @@ pub fn init_global_dirs() {
             "Should find call from init_config_file to crate::init::init_global_dirs\nFound calls: {calls:?}"
         );
     }
+
+    #[test]
+    fn test_extract_doc_comment_stops_at_plain_comment() {
+        let mut parser = RustParser::new().unwrap();
+        let code = r#"
+/// Detached documentation
+// plain comment
+fn detached() {}
+
+/** Block part */
+/// Line part
+fn mixed() {}
+"#;
+
+        let mut counter = SymbolCounter::new();
+        let symbols = parser.parse(code, FileId::new(1).unwrap(), &mut counter);
+        let doc = |name: &str| {
+            symbols
+                .iter()
+                .find(|s| s.name.as_ref() == name)
+                .and_then(|s| s.doc_comment.as_deref())
+        };
+
+        assert_eq!(doc("detached"), None);
+        assert_eq!(doc("mixed"), Some("Block part\nLine part"));
+    }
 }
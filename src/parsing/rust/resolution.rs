@@ -6,7 +6,7 @@
 //! - src/indexing/resolution_context.rs → RustResolutionContext
 
 use crate::parsing::resolution::ImportBinding;
-use crate::parsing::{InheritanceResolver, ResolutionScope, ScopeLevel, ScopeType};
+use crate::parsing::{NameKeyedInheritanceResolver, ResolutionScope, ScopeLevel, ScopeType};
 use crate::{FileId, SymbolId};
 use std::collections::HashMap;
 
@@ -325,7 +325,7 @@ impl RustTraitResolver {
     }
 }
 
-impl InheritanceResolver for RustTraitResolver {
+impl NameKeyedInheritanceResolver for RustTraitResolver {
     fn add_inheritance(&mut self, child: String, parent: String, kind: &str) {
         if kind == "implements" {
             // In Rust, this is a trait implementation
@@ -482,8 +482,8 @@ impl RustTraitResolver {
             // In real Rust this would be an error requiring disambiguation
             if !matching_traits.is_empty() {
                 if matching_traits.len() > 1 {
-                    eprintln!(
-                        "WARNING: Ambiguous method '{method_name}' on type '{type_name}' - found in traits: {matching_traits:?}"
+                    tracing::warn!(
+                        "ambiguous method '{method_name}' on type '{type_name}' - found in traits: {matching_traits:?}"
                     );
                 }
                 return Some(matching_traits[0]);
@@ -0,0 +1,343 @@
+//! Signature normalization for display
+//!
+//! Parsers store signatures as slices of the source, so the same shape of
+//! declaration comes out differently depending on how it was written:
+//! attributes on the line above, a body brace left on the end, arguments
+//! wrapped over several lines. Before a signature reaches a search result
+//! or a hover, the language's behavior normalizes it through
+//! [`LanguageBehavior::normalize_signature`]. The helpers here are the
+//! building blocks those normalizers share; [`normalize`] is the default.
+
+use crate::parsing::LanguageBehavior;
+use crate::parsing::registry::{LanguageId, get_registry};
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+/// How much of a signature to keep when displaying it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SignatureStyle {
+    /// Drop default parameter values: `fn(a = 1, b)` becomes `fn(a, b)`
+    pub elide_defaults: bool,
+}
+
+thread_local! {
+    /// Behaviors are stateful and not `Sync`, so each thread keeps its own
+    /// for normalizing; `None` records a language the registry can't build
+    static BEHAVIORS: RefCell<HashMap<LanguageId, Option<Box<dyn LanguageBehavior>>>> =
+        RefCell::new(HashMap::new());
+}
+
+/// `signature` normalized by the behavior of `language`, or by
+/// [`normalize`] when the language is unknown
+pub fn display_signature(
+    signature: &str,
+    language: Option<LanguageId>,
+    style: SignatureStyle,
+) -> String {
+    let Some(language) = language else {
+        return normalize(signature, style);
+    };
+
+    BEHAVIORS.with(|behaviors| {
+        let mut behaviors = behaviors.borrow_mut();
+        let behavior = behaviors.entry(language).or_insert_with(|| {
+            let registry = get_registry().lock().ok()?;
+            registry
+                .get(language)
+                .map(|definition| definition.create_behavior())
+        });
+        match behavior {
+            Some(behavior) => behavior.normalize_signature(signature, style),
+            None => normalize(signature, style),
+        }
+    })
+}
+
+/// Default normalization: drop leading `#[...]` attributes and `@`
+/// annotations, cut the body, collapse whitespace
+pub fn normalize(signature: &str, style: SignatureStyle) -> String {
+    finish(strip_body(strip_attributes(signature, &["#["])), style)
+}
+
+/// The steps every normalizer ends with: collapse whitespace, drop a
+/// trailing `;`, and elide default values if `style` asks for it
+pub fn finish(signature: &str, style: SignatureStyle) -> String {
+    let collapsed = collapse_whitespace(signature);
+    let trimmed = collapsed.trim_end_matches(';').trim_end();
+    if style.elide_defaults {
+        elide_defaults(trimmed)
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// `signature` without the attributes in front of it
+///
+/// Removes bracketed groups starting with one of `openers`, such as `#[`
+/// in Rust or `[` in C#, and `@Name` annotations with optional arguments.
+/// `@interface` is a Java declaration keyword and is kept.
+pub fn strip_attributes<'a>(signature: &'a str, openers: &[&str]) -> &'a str {
+    let mut rest = signature.trim_start();
+    loop {
+        if let Some(opener) = openers.iter().find(|opener| rest.starts_with(**opener)) {
+            let open_idx = opener.find(['(', '[', '{']).unwrap_or(0);
+            match group_end(rest, open_idx) {
+                Some(end) => rest = rest[end..].trim_start(),
+                None => return rest,
+            }
+        } else if let Some(annotation) = rest.strip_prefix('@') {
+            let name_len = annotation
+                .find(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '.' | ':')))
+                .unwrap_or(annotation.len());
+            if name_len == 0 || &annotation[..name_len] == "interface" {
+                return rest;
+            }
+            let mut after = &annotation[name_len..];
+            if after.starts_with('(') {
+                match group_end(after, 0) {
+                    Some(end) => after = &after[end..],
+                    None => return rest,
+                }
+            }
+            rest = after.trim_start();
+        } else {
+            return rest;
+        }
+    }
+}
+
+/// Byte offset just past the bracket matching the one at `open_idx`,
+/// skipping over string literals
+pub fn group_end(text: &str, open_idx: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut escaped = false;
+    for (offset, c) in text[open_idx..].char_indices() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' => quote = Some(c),
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => {
+                depth = depth.checked_sub(1)?;
+                if depth == 0 {
+                    return Some(open_idx + offset + c.len_utf8());
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// `signature` up to its body: the first `{` outside any brackets
+///
+/// After a top-level assignment only a `{` directly after `=>` opens a
+/// body, so `const X = { a: 1 }` is kept whole while
+/// `const f = () => { ... }` loses its body.
+pub fn strip_body(signature: &str) -> &str {
+    let bytes = signature.as_bytes();
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut assigned = false;
+    for (i, &b) in bytes.iter().enumerate() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if b == b'\\' {
+                escaped = true;
+            } else if b == b'"' {
+                in_string = false;
+            }
+            continue;
+        }
+        match b {
+            b'"' => in_string = true,
+            b'{' if depth == 0 => {
+                let after_arrow = signature[..i].trim_end().ends_with("=>");
+                if !assigned || after_arrow {
+                    return signature[..i].trim_end();
+                }
+                depth += 1;
+            }
+            b'(' | b'[' | b'{' | b'<' => depth += 1,
+            b'>' if i > 0 && matches!(bytes[i - 1], b'-' | b'=') => {}
+            b')' | b']' | b'}' | b'>' => depth = depth.saturating_sub(1),
+            b'=' if depth == 0 && is_assignment(bytes, i) => assigned = true,
+            _ => {}
+        }
+    }
+    signature.trim_end()
+}
+
+/// Whether the `=` at `i` assigns rather than compares or forms an arrow
+fn is_assignment(bytes: &[u8], i: usize) -> bool {
+    let next = bytes.get(i + 1).copied();
+    let prev = i.checked_sub(1).map(|p| bytes[p]);
+    !matches!(next, Some(b'=' | b'>')) && !matches!(prev, Some(b'=' | b'!' | b'<' | b'>'))
+}
+
+/// Single spaces between words, none just inside brackets, and no
+/// trailing comma before a closing bracket
+fn collapse_whitespace(signature: &str) -> String {
+    let mut out = String::with_capacity(signature.len());
+    for word in signature.split_whitespace() {
+        if word.starts_with([')', ']']) && out.ends_with(',') {
+            out.pop();
+        }
+        let glued = out.is_empty() || out.ends_with(['(', '[']) || word.starts_with([')', ']']);
+        if !glued {
+            out.push(' ');
+        }
+        out.push_str(word);
+    }
+    out
+}
+
+/// `signature` with default values removed from its parameter lists
+///
+/// Only assignments directly inside parentheses or square brackets count,
+/// so generic defaults like `<T = int>` and Rust associated type bindings
+/// like `Iterator<Item = u8>` are left alone.
+pub fn elide_defaults(signature: &str) -> String {
+    let bytes = signature.as_bytes();
+    let mut out = String::with_capacity(signature.len());
+    let mut open: Vec<u8> = Vec::new();
+    let mut skipping = false;
+    let mut skip_depth = 0usize;
+    let mut quote: Option<u8> = None;
+    let mut escaped = false;
+    let mut start = 0;
+
+    for (i, &b) in bytes.iter().enumerate() {
+        if skipping {
+            if let Some(q) = quote {
+                if escaped {
+                    escaped = false;
+                } else if b == b'\\' {
+                    escaped = true;
+                } else if b == q {
+                    quote = None;
+                }
+                continue;
+            }
+            match b {
+                b'\'' | b'"' => quote = Some(b),
+                b'(' | b'[' | b'{' => skip_depth += 1,
+                b')' | b']' | b'}' | b',' if skip_depth == 0 => {
+                    if b != b',' {
+                        open.pop();
+                    }
+                    skipping = false;
+                    start = i;
+                }
+                b')' | b']' | b'}' => skip_depth -= 1,
+                _ => {}
+            }
+            continue;
+        }
+        match b {
+            b'(' | b'[' | b'{' | b'<' => open.push(b),
+            b'>' if i > 0 && matches!(bytes[i - 1], b'-' | b'=') => {}
+            b')' | b']' | b'}' | b'>' => {
+                open.pop();
+            }
+            b'=' if matches!(open.last(), Some(b'(' | b'[')) && is_assignment(bytes, i) => {
+                // GDScript's inferred `x := 1` drops the colon too
+                let name = &signature[start..i];
+                out.push_str(name.strip_suffix(':').unwrap_or(name).trim_end());
+                skipping = true;
+                skip_depth = 0;
+            }
+            _ => {}
+        }
+    }
+    if !skipping {
+        out.push_str(&signature[start..]);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain(signature: &str) -> String {
+        normalize(signature, SignatureStyle::default())
+    }
+
+    fn elided(signature: &str) -> String {
+        normalize(
+            signature,
+            SignatureStyle {
+                elide_defaults: true,
+            },
+        )
+    }
+
+    #[test]
+    fn test_collapses_wrapped_parameters() {
+        assert_eq!(
+            plain("pub fn add(\n    a: i32,\n    b: i32,\n) -> i32"),
+            "pub fn add(a: i32, b: i32) -> i32"
+        );
+    }
+
+    #[test]
+    fn test_strips_attributes_and_body() {
+        assert_eq!(
+            plain("#[inline]\n#[must_use]\npub fn id(x: u8) -> u8 { x }"),
+            "pub fn id(x: u8) -> u8"
+        );
+        assert_eq!(
+            plain("@Override\npublic String toString() {"),
+            "public String toString()"
+        );
+        assert_eq!(plain("@interface Marker {"), "@interface Marker");
+    }
+
+    #[test]
+    fn test_arrow_function_body_is_stripped() {
+        assert_eq!(
+            plain("const add = (a, b) => { return a + b; }"),
+            "const add = (a, b) =>"
+        );
+        assert_eq!(
+            plain("const config = { port: 8080 };"),
+            "const config = { port: 8080 }"
+        );
+    }
+
+    #[test]
+    fn test_elides_default_values() {
+        assert_eq!(
+            elided("function greet(name = \"a, b\", times = f(1, 2), loud) {"),
+            "function greet(name, times, loud)"
+        );
+        assert_eq!(
+            plain("function greet(name = \"x\")"),
+            "function greet(name = \"x\")"
+        );
+    }
+
+    #[test]
+    fn test_keeps_type_bindings_and_comparisons() {
+        assert_eq!(
+            elided("fn bytes(it: impl Iterator<Item = u8>) -> usize"),
+            "fn bytes(it: impl Iterator<Item = u8>) -> usize"
+        );
+        assert_eq!(
+            elided("template <typename T = int> void fill(T value = T(), bool ok = a == b);"),
+            "template <typename T = int> void fill(T value, bool ok)"
+        );
+    }
+}
@@ -2,6 +2,7 @@
 
 use crate::parsing::behavior_state::{BehaviorState, StatefulBehavior};
 use crate::parsing::paths::strip_extension;
+use crate::parsing::references::ReferenceNodeKinds;
 use crate::parsing::{Import, InheritanceResolver, LanguageBehavior, ResolutionScope};
 use crate::types::compact_string;
 use crate::{FileId, Symbol, SymbolKind, Visibility};
@@ -37,6 +38,26 @@ impl StatefulBehavior for SwiftBehavior {
     }
 }
 
+/// Reference pass node kinds. Argument labels and the external names of
+/// parameters are never references.
+static REFERENCE_NODES: ReferenceNodeKinds = ReferenceNodeKinds {
+    identifiers: &["simple_identifier"],
+    bindings: &[
+        ("parameter", Some("name")),
+        ("parameter", Some("external_name")),
+        ("lambda_parameter", Some("name")),
+        ("pattern", Some("bound_identifier")),
+    ],
+    members: &[
+        ("navigation_suffix", Some("suffix")),
+        ("value_argument_label", None),
+    ],
+    paths: &[],
+    fields: &[("navigation_expression", "target", "suffix")],
+    containers: &[("class_declaration", "name")],
+    this: &["self"],
+};
+
 impl LanguageBehavior for SwiftBehavior {
     fn language_id(&self) -> crate::parsing::registry::LanguageId {
         crate::parsing::registry::LanguageId::new("swift")
@@ -88,7 +109,8 @@ impl LanguageBehavior for SwiftBehavior {
                 true
             }
             Visibility::Private => false, // private/fileprivate - file-scoped only
-            Visibility::Crate => true,    // Not used in Swift, but treat as visible
+            // Not used in Swift, but treat as visible
+            Visibility::Crate | Visibility::Protected | Visibility::Package => true,
         }
     }
 
@@ -227,6 +249,10 @@ impl LanguageBehavior for SwiftBehavior {
         tree_sitter_swift::LANGUAGE.into()
     }
 
+    fn reference_node_kinds(&self) -> Option<&'static ReferenceNodeKinds> {
+        Some(&REFERENCE_NODES)
+    }
+
     fn supports_traits(&self) -> bool {
         true // Swift has protocols
     }
@@ -309,6 +335,7 @@ impl LanguageBehavior for SwiftBehavior {
                 alias: import.alias.clone(),
                 is_glob: import.is_glob,
                 is_type_only: import.is_type_only,
+                range: import.range,
             });
 
             // Look up candidates by module name and match computed module_path
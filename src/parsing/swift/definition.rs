@@ -30,8 +30,12 @@ impl LanguageDefinition for SwiftLanguage {
         &["swift"]
     }
 
+    fn interpreters(&self) -> &'static [&'static str] {
+        &["swift"]
+    }
+
     fn create_parser(&self, _settings: &Settings) -> IndexResult<Box<dyn LanguageParser>> {
-        let parser = SwiftParser::new().map_err(IndexError::General)?;
+        let parser = SwiftParser::new().map_err(|e| IndexError::parser_init("swift", e))?;
         Ok(Box::new(parser))
     }
 
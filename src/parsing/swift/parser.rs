@@ -27,8 +27,6 @@ const NODE_ENUM_ENTRY: &str = "enum_entry";
 const NODE_MODIFIERS: &str = "modifiers";
 const NODE_VISIBILITY_MODIFIER: &str = "visibility_modifier";
 const NODE_SIMPLE_IDENTIFIER: &str = "simple_identifier";
-const NODE_COMMENT: &str = "comment";
-const NODE_MULTILINE_COMMENT: &str = "multiline_comment";
 const NODE_CALL_EXPRESSION: &str = "call_expression";
 const NODE_NAVIGATION_EXPRESSION: &str = "navigation_expression";
 const NODE_TYPE_IDENTIFIER: &str = "type_identifier";
@@ -151,29 +149,15 @@ impl SwiftParser {
 
     /// Extract documentation comments (/// or /** */)
     fn doc_comment_for(&self, node: &Node, code: &str) -> Option<String> {
-        let mut result = String::new();
-        let mut current = node.prev_sibling();
-
-        while let Some(sibling) = current {
-            let kind = sibling.kind();
-            if kind != NODE_COMMENT && kind != NODE_MULTILINE_COMMENT {
-                break;
-            }
-
-            let raw = self.text_for_node(code, sibling);
-            if let Some(cleaned) = self.clean_comment(raw) {
-                if !result.is_empty() {
-                    result.insert(0, '\n');
-                }
-                result.insert_str(0, cleaned);
-            }
-            current = sibling.prev_sibling();
-        }
-
-        if result.is_empty() {
+        let cleaned: Vec<&str> = self
+            .preceding_comments(node, code, &|_| true)
+            .into_iter()
+            .filter_map(|raw| self.clean_comment(raw))
+            .collect();
+        if cleaned.is_empty() {
             None
         } else {
-            Some(result)
+            Some(cleaned.join("\n"))
         }
     }
 
@@ -1101,6 +1085,7 @@ impl SwiftParser {
                         file_id,
                         is_glob: false,
                         is_type_only: false,
+                        range: Some(Import::range_of(&node)),
                     });
                     break;
                 }
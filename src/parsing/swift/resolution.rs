@@ -3,7 +3,7 @@
 //! Provides scoping and inheritance tracking tailored for Swift's language features,
 //! including protocols, extensions, and class inheritance.
 
-use crate::parsing::resolution::{ImportBinding, InheritanceResolver, ResolutionScope};
+use crate::parsing::resolution::{ImportBinding, NameKeyedInheritanceResolver, ResolutionScope};
 use crate::parsing::{ScopeLevel, ScopeType};
 use crate::{FileId, SymbolId};
 use std::collections::{HashMap, HashSet};
@@ -456,7 +456,7 @@ impl SwiftInheritanceResolver {
     }
 }
 
-impl InheritanceResolver for SwiftInheritanceResolver {
+impl NameKeyedInheritanceResolver for SwiftInheritanceResolver {
     fn add_inheritance(&mut self, child: String, parent: String, kind: &str) {
         match kind {
             "extends" | "class" => {
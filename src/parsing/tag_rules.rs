@@ -0,0 +1,181 @@
+//! Project-defined symbol tags
+//!
+//! `[[tags]]` rules in settings (see [`TagRule`]) label symbols by where
+//! they live, what they are called and what kind they are: everything
+//! under `src/api/` named `handle_*` becomes an `api-handler`. Tags are
+//! attached while parsing and stored with the symbol, so saved queries can
+//! filter on them (`tag:api-handler`) and `codanna stats` can count them.
+
+use crate::config::TagRule;
+use crate::{Symbol, SymbolKind};
+use regex::Regex;
+use std::path::Path;
+
+#[derive(Debug)]
+struct CompiledRule {
+    tag: Box<str>,
+    paths: Vec<glob::Pattern>,
+    name: Option<Regex>,
+    kind: Option<SymbolKind>,
+}
+
+impl CompiledRule {
+    fn compile(rule: &TagRule) -> Result<Self, String> {
+        if rule.paths.is_empty() && rule.name.is_none() && rule.kind.is_none() {
+            return Err("it has no paths, name or kind condition".to_string());
+        }
+        let paths = rule
+            .paths
+            .iter()
+            .map(|path| {
+                glob::Pattern::new(path).map_err(|e| format!("invalid path glob '{path}': {e}"))
+            })
+            .collect::<Result<_, _>>()?;
+        let name = rule
+            .name
+            .as_deref()
+            .map(|name| Regex::new(name).map_err(|e| format!("invalid name pattern '{name}': {e}")))
+            .transpose()?;
+        Ok(Self {
+            tag: rule.tag.as_str().into(),
+            paths,
+            name,
+            kind: rule.kind,
+        })
+    }
+
+    fn matches_path(&self, path: &str) -> bool {
+        self.paths.is_empty() || self.paths.iter().any(|pattern| pattern.matches(path))
+    }
+
+    fn matches_symbol(&self, symbol: &Symbol) -> bool {
+        self.kind.is_none_or(|kind| symbol.kind == kind)
+            && self
+                .name
+                .as_ref()
+                .is_none_or(|name| name.is_match(&symbol.name))
+    }
+}
+
+/// Compiled `[[tags]]` rules
+#[derive(Debug, Default)]
+pub struct TagRules {
+    rules: Vec<CompiledRule>,
+}
+
+impl TagRules {
+    /// Compile `rules`. A rule with an invalid pattern or no condition is
+    /// skipped with a warning rather than failing the index.
+    pub fn new(rules: &[TagRule]) -> Self {
+        let rules = rules
+            .iter()
+            .filter_map(|rule| match CompiledRule::compile(rule) {
+                Ok(compiled) => Some(compiled),
+                Err(reason) => {
+                    tracing::warn!("[config] ignoring tag rule '{}': {reason}", rule.tag);
+                    None
+                }
+            })
+            .collect();
+        Self { rules }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Tag the symbols of the file at `path`, relative to the workspace root
+    pub fn apply(&self, path: &Path, symbols: &mut [Symbol]) {
+        let path = path.to_string_lossy().replace('\\', "/");
+        let rules: Vec<&CompiledRule> = self
+            .rules
+            .iter()
+            .filter(|rule| rule.matches_path(&path))
+            .collect();
+        if rules.is_empty() {
+            return;
+        }
+        for symbol in symbols {
+            for rule in &rules {
+                if rule.matches_symbol(symbol) && !symbol.tags.contains(&rule.tag) {
+                    symbol.tags.push(rule.tag.clone());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FileId, Range, SymbolId};
+
+    fn symbol(name: &str, kind: SymbolKind) -> Symbol {
+        Symbol::new(
+            SymbolId::new(1).unwrap(),
+            name,
+            kind,
+            FileId::new(1).unwrap(),
+            Range::new(0, 0, 1, 0),
+        )
+    }
+
+    fn tags(symbol: &Symbol) -> Vec<&str> {
+        symbol.tags.iter().map(|tag| &**tag).collect()
+    }
+
+    fn rule(tag: &str, paths: &[&str], name: Option<&str>, kind: Option<SymbolKind>) -> TagRule {
+        TagRule {
+            tag: tag.to_string(),
+            paths: paths.iter().map(|path| path.to_string()).collect(),
+            name: name.map(str::to_string),
+            kind,
+        }
+    }
+
+    #[test]
+    fn test_all_conditions_must_match() {
+        let rules = TagRules::new(&[rule(
+            "api-handler",
+            &["src/api/**"],
+            Some("^handle_"),
+            Some(SymbolKind::Function),
+        )]);
+        let mut symbols = vec![
+            symbol("handle_login", SymbolKind::Function),
+            symbol("handle_cache", SymbolKind::Struct),
+            symbol("login", SymbolKind::Function),
+        ];
+
+        rules.apply(Path::new("src/api/auth/routes.rs"), &mut symbols);
+        assert_eq!(tags(&symbols[0]), ["api-handler"]);
+        assert!(symbols[1].tags.is_empty());
+        assert!(symbols[2].tags.is_empty());
+
+        let mut elsewhere = vec![symbol("handle_login", SymbolKind::Function)];
+        rules.apply(Path::new("src/cli/main.rs"), &mut elsewhere);
+        assert!(elsewhere[0].tags.is_empty());
+    }
+
+    #[test]
+    fn test_symbols_collect_every_matching_tag_once() {
+        let rules = TagRules::new(&[
+            rule("deprecated-zone", &["src/legacy/**"], None, None),
+            rule("deprecated-zone", &["**/compat.rs"], None, None),
+            rule("entry", &[], Some("^main$"), None),
+        ]);
+        let mut symbols = vec![symbol("main", SymbolKind::Function)];
+
+        rules.apply(Path::new("src/legacy/compat.rs"), &mut symbols);
+        assert_eq!(tags(&symbols[0]), ["deprecated-zone", "entry"]);
+    }
+
+    #[test]
+    fn test_invalid_rules_are_skipped() {
+        let rules = TagRules::new(&[
+            rule("broken", &[], Some("("), None),
+            rule("everything", &[], None, None),
+        ]);
+        assert!(rules.is_empty());
+    }
+}
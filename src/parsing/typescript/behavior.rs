@@ -2,7 +2,9 @@
 
 use crate::parsing::LanguageBehavior;
 use crate::parsing::behavior_state::{BehaviorState, StatefulBehavior};
+use crate::parsing::control_flow::BranchNodeKinds;
 use crate::parsing::paths::strip_extension;
+use crate::parsing::references::ReferenceNodeKinds;
 use crate::parsing::resolution::{InheritanceResolver, ResolutionScope};
 use crate::project_resolver::persist::{ResolutionPersistence, ResolutionRules};
 use crate::types::FileId;
@@ -90,6 +92,46 @@ impl StatefulBehavior for TypeScriptBehavior {
     }
 }
 
+/// Reference pass node kinds. Type names are `type_identifier` nodes and
+/// already reported as `Uses`, so only value identifiers are read.
+static REFERENCE_NODES: ReferenceNodeKinds = ReferenceNodeKinds {
+    identifiers: &["identifier"],
+    bindings: &[
+        ("required_parameter", Some("pattern")),
+        ("optional_parameter", Some("pattern")),
+        ("variable_declarator", Some("name")),
+        ("arrow_function", Some("parameter")),
+        ("catch_clause", Some("parameter")),
+        ("for_in_statement", Some("left")),
+    ],
+    members: &[],
+    paths: &[],
+    fields: &[("member_expression", "object", "property")],
+    containers: &[
+        ("class_declaration", "name"),
+        ("abstract_class_declaration", "name"),
+        ("class", "name"),
+    ],
+    this: &["this"],
+};
+
+/// Conditional-region node kinds. The increment of a `for` is skipped when
+/// the loop never runs, so it counts alongside the body.
+static BRANCH_NODES: BranchNodeKinds = BranchNodeKinds {
+    branches: &[
+        ("if_statement", &["consequence", "alternative"]),
+        ("switch_statement", &["body"]),
+        ("while_statement", &["body"]),
+        ("for_statement", &["body", "increment"]),
+        ("for_in_statement", &["body"]),
+        ("ternary_expression", &["consequence", "alternative"]),
+        ("catch_clause", &[]),
+        ("arrow_function", &[]),
+        ("function_expression", &[]),
+    ],
+    short_circuit: &["&&", "||", "??"],
+};
+
 impl LanguageBehavior for TypeScriptBehavior {
     fn language_id(&self) -> crate::parsing::registry::LanguageId {
         crate::parsing::registry::LanguageId::new("typescript")
@@ -112,6 +154,25 @@ impl LanguageBehavior for TypeScriptBehavior {
     fn get_language(&self) -> Language {
         tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into()
     }
+
+    fn reference_node_kinds(&self) -> Option<&'static ReferenceNodeKinds> {
+        Some(&REFERENCE_NODES)
+    }
+
+    fn branch_node_kinds(&self) -> Option<&'static BranchNodeKinds> {
+        Some(&BRANCH_NODES)
+    }
+
+    fn import_declaration_kinds(&self) -> &'static [&'static str] {
+        &["import_statement"]
+    }
+
+    fn import_bindings(&self, import: &crate::parsing::Import) -> Vec<String> {
+        // The path is a module specifier; the local name, if any, is always
+        // recorded as the alias. Side-effect imports and re-exports have none.
+        import.alias.iter().cloned().collect()
+    }
+
     fn module_separator(&self) -> &'static str {
         "."
     }
@@ -172,9 +233,7 @@ impl LanguageBehavior for TypeScriptBehavior {
         } else if signature.contains("private ") || signature.contains("#") {
             Visibility::Private
         } else if signature.contains("protected ") {
-            // TypeScript has protected but Rust's Visibility enum doesn't
-            // Map protected to Module visibility as a reasonable approximation
-            Visibility::Module
+            Visibility::Protected
         } else {
             // Default visibility for TypeScript symbols
             // Module-level symbols are private by default unless exported
@@ -338,6 +397,7 @@ impl LanguageBehavior for TypeScriptBehavior {
                 alias: import.alias.clone(),
                 is_glob: import.is_glob,
                 is_type_only: import.is_type_only,
+                range: import.range,
             });
 
             // Look up candidates by local_name and match module_path
@@ -435,6 +495,18 @@ impl LanguageBehavior for TypeScriptBehavior {
         }
     }
 
+    // Only module-level declarations carry `export`; class members are
+    // public by default but are reached through their class
+    fn is_exported(&self, symbol: &crate::Symbol) -> bool {
+        use crate::symbol::ScopeContext;
+
+        symbol.visibility == Visibility::Public
+            && matches!(
+                symbol.scope_context,
+                None | Some(ScopeContext::Module | ScopeContext::Global | ScopeContext::Package)
+            )
+    }
+
     fn get_module_path_for_file(&self, file_id: FileId) -> Option<String> {
         // Use the BehaviorState to get module path (O(1) lookup)
         self.state.get_module_path(file_id)
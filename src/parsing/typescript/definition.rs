@@ -24,8 +24,13 @@ impl LanguageDefinition for TypeScriptLanguage {
         &["ts", "tsx", "mts", "cts"]
     }
 
+    fn interpreters(&self) -> &'static [&'static str] {
+        &["deno", "ts-node", "tsx", "bun"]
+    }
+
     fn create_parser(&self, _settings: &Settings) -> IndexResult<Box<dyn LanguageParser>> {
-        let parser = TypeScriptParser::new().map_err(|e| IndexError::General(e.to_string()))?;
+        let parser =
+            TypeScriptParser::new().map_err(|e| IndexError::parser_init("typescript", e))?;
         Ok(Box::new(parser))
     }
 
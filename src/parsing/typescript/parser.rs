@@ -8,11 +8,13 @@
 use crate::parsing::Import;
 use crate::parsing::parser::check_recursion_depth;
 use crate::parsing::{
-    LanguageParser, MethodCall, NodeTracker, NodeTrackingState, ParserContext, ScopeType,
+    GenericInstantiation, Instantiation, LanguageParser, MethodCall, NodeTracker,
+    NodeTrackingState, ParserContext, ReferenceSite, ScopeType,
 };
 use crate::types::SymbolCounter;
 use crate::{FileId, Range, Symbol, SymbolKind, Visibility};
 use std::any::Any;
+use std::collections::HashSet;
 use tree_sitter::{Language, Node, Parser};
 
 /// TypeScript language parser
@@ -89,7 +91,7 @@ impl TypeScriptParser {
                 );
             }
             None => {
-                eprintln!("Failed to parse TypeScript file");
+                tracing::warn!(file_id = file_id.0, "failed to parse TypeScript file");
             }
         }
 
@@ -263,6 +265,7 @@ impl TypeScriptParser {
                 if let Some(symbol) = self.process_enum(node, code, file_id, counter, module_path) {
                     symbols.push(symbol);
                 }
+                self.process_enum_members(node, code, file_id, counter, symbols, module_path);
             }
             "lexical_declaration" | "variable_declaration" => {
                 self.register_node_recursively(node);
@@ -780,6 +783,60 @@ impl TypeScriptParser {
         ))
     }
 
+    /// Process the members of an enum body as constants of the enum
+    ///
+    /// `enum Status { Pending, Done = "done" }` yields `Pending` and `Done`,
+    /// each scoped to `Status` so `Status.Pending` can be looked up.
+    fn process_enum_members(
+        &mut self,
+        node: Node,
+        code: &str,
+        file_id: FileId,
+        counter: &mut SymbolCounter,
+        symbols: &mut Vec<Symbol>,
+        module_path: &str,
+    ) {
+        let (Some(name_node), Some(body)) = (
+            node.child_by_field_name("name"),
+            node.child_by_field_name("body"),
+        ) else {
+            return;
+        };
+        let enum_name = &code[name_node.byte_range()];
+        let visibility = self.determine_visibility(node, code);
+
+        for member in body.named_children(&mut body.walk()) {
+            let member_name = match member.kind() {
+                "property_identifier" => member,
+                "enum_assignment" => match member.child_by_field_name("name") {
+                    Some(name) if name.kind() == "property_identifier" => name,
+                    _ => continue,
+                },
+                _ => continue,
+            };
+            let mut symbol = self.create_symbol(
+                counter.next_id(),
+                code[member_name.byte_range()].to_string(),
+                SymbolKind::Constant,
+                file_id,
+                Range::new(
+                    member.start_position().row as u32,
+                    member.start_position().column as u16,
+                    member.end_position().row as u32,
+                    member.end_position().column as u16,
+                ),
+                Some(format!("{enum_name}.{}", &code[member.byte_range()])),
+                self.extract_doc_comment(&member, code),
+                module_path,
+                visibility,
+            );
+            symbol.scope_context = Some(crate::symbol::ScopeContext::ClassMember {
+                class_name: Some(enum_name.into()),
+            });
+            symbols.push(symbol);
+        }
+    }
+
     /// Process variable declarations
     fn process_variable_declaration(
         &mut self,
@@ -844,6 +901,8 @@ impl TypeScriptParser {
                                 Some(crate::symbol::ScopeContext::Local {
                                     parent_name,
                                     parent_kind,
+                                    depth,
+                                    ancestors,
                                     ..
                                 }) => {
                                     symbol.scope_context =
@@ -851,6 +910,8 @@ impl TypeScriptParser {
                                             hoisted: false, // Arrow functions are never hoisted
                                             parent_name,    // Keep the parent context
                                             parent_kind,    // Keep the parent kind
+                                            depth,
+                                            ancestors,
                                         });
                                 }
                                 _ => {
@@ -870,6 +931,8 @@ impl TypeScriptParser {
                                             hoisted: false,
                                             parent_name,
                                             parent_kind,
+                                            depth: self.context.scope_depth(),
+                                            ancestors: self.context.ancestor_kinds(),
                                         });
                                 }
                             }
@@ -1082,12 +1145,42 @@ impl TypeScriptParser {
         if signature.contains("private ") || signature.starts_with("#") {
             Visibility::Private
         } else if signature.contains("protected ") {
-            Visibility::Module // Map TypeScript protected to Module visibility
+            Visibility::Protected
         } else {
             Visibility::Public // Default for class members
         }
     }
 
+    /// Find type aliases that rename another type: `type UserId = Id`,
+    /// `type Items = Array<Item>`. Unions, object types and other type
+    /// expressions define something new and are skipped.
+    fn find_aliases_in_node<'a>(
+        &self,
+        node: Node,
+        code: &'a str,
+        aliases: &mut Vec<(&'a str, &'a str, Range)>,
+    ) {
+        if node.kind() == "type_alias_declaration" {
+            let name = node.child_by_field_name("name");
+            let target = node
+                .child_by_field_name("value")
+                .and_then(|value| self.extract_type_name(value, code));
+            if let (Some(name_node), Some(target)) = (name, target) {
+                let range = Range::new(
+                    node.start_position().row as u32,
+                    node.start_position().column as u16,
+                    node.end_position().row as u32,
+                    node.end_position().column as u16,
+                );
+                aliases.push((&code[name_node.byte_range()], target, range));
+            }
+        }
+
+        for child in node.children(&mut node.walk()) {
+            self.find_aliases_in_node(child, code, aliases);
+        }
+    }
+
     /// Find implementations (extends and implements) in TypeScript
     fn find_implementations_in_node<'a>(
         &self,
@@ -1409,6 +1502,7 @@ impl TypeScriptParser {
                                     file_id,
                                     is_glob: false,
                                     is_type_only,
+                                    range: Some(Import::range_of(&node)),
                                 });
                             }
                         }
@@ -1442,6 +1536,7 @@ impl TypeScriptParser {
                     file_id,
                     is_glob: true,
                     is_type_only,
+                    range: Some(Import::range_of(&node)),
                 });
             } else if has_default && has_named {
                 // Mixed import: import React, { Component } from 'react'
@@ -1452,6 +1547,7 @@ impl TypeScriptParser {
                     file_id,
                     is_glob: false,
                     is_type_only,
+                    range: Some(Import::range_of(&node)),
                 });
             } else if has_default {
                 // Default only: import React from 'react'
@@ -1464,6 +1560,7 @@ impl TypeScriptParser {
                     file_id,
                     is_glob: false,
                     is_type_only,
+                    range: Some(Import::range_of(&node)),
                 });
             } else if has_named {
                 // Named-only already pushed per specifier above
@@ -1476,6 +1573,7 @@ impl TypeScriptParser {
                 file_id,
                 is_glob: false,
                 is_type_only: false, // Side-effect imports are never type-only
+                range: Some(Import::range_of(&node)),
             });
         }
     }
@@ -1510,6 +1608,7 @@ impl TypeScriptParser {
                 file_id,
                 is_glob: true,
                 is_type_only,
+                range: Some(Import::range_of(&node)),
             });
         } else {
             // Named re-exports - just track the module being imported from
@@ -1519,6 +1618,7 @@ impl TypeScriptParser {
                 file_id,
                 is_glob: false,
                 is_type_only,
+                range: Some(Import::range_of(&node)),
             });
         }
     }
@@ -2196,6 +2296,32 @@ impl TypeScriptParser {
                 }
             }
 
+            // Enum members
+            "enum_declaration" => {
+                if let (Some(name_node), Some(body)) = (
+                    node.child_by_field_name("name"),
+                    node.child_by_field_name("body"),
+                ) {
+                    let enum_name = &code[name_node.byte_range()];
+                    for member in body.named_children(&mut body.walk()) {
+                        let member_name = match member.kind() {
+                            "property_identifier" => Some(member),
+                            "enum_assignment" => member
+                                .child_by_field_name("name")
+                                .filter(|name| name.kind() == "property_identifier"),
+                            _ => None,
+                        };
+                        if let Some(member_name) = member_name {
+                            defines.push((
+                                enum_name,
+                                &code[member_name.byte_range()],
+                                node_range(member),
+                            ));
+                        }
+                    }
+                }
+            }
+
             // Type aliases with object types (method signatures in type literals)
             "type_alias_declaration" => {
                 let type_name = node
@@ -2316,6 +2442,7 @@ impl TypeScriptParser {
                                 is_static,
                                 range,
                                 caller_range: None, // TODO: track caller definition range
+                                category: None,
                             };
 
                             calls.push(method_call);
@@ -2526,39 +2653,27 @@ impl LanguageParser for TypeScriptParser {
 
         // First, check if this node is inside an export_statement
         // If so, we need to check the export_statement's previous sibling for the comment
-        let comment_node = if let Some(parent) = node.parent() {
-            if parent.kind() == "export_statement" {
-                // For exported functions, check the export statement's previous sibling
-                parent.prev_sibling()
-            } else {
-                // For non-exported functions, check the node's previous sibling
-                node.prev_sibling()
-            }
-        } else {
-            // No parent, check the node's previous sibling
-            node.prev_sibling()
+        let anchor = match node.parent() {
+            // For exported functions, check the export statement's previous sibling
+            Some(parent) if parent.kind() == "export_statement" => parent,
+            _ => *node,
         };
 
-        if let Some(prev) = comment_node {
-            if prev.kind() == "comment" {
-                let comment = &code[prev.byte_range()];
-                if comment.starts_with("/**") {
-                    // Clean up the comment
-                    let cleaned = comment
-                        .trim_start_matches("/**")
-                        .trim_end_matches("*/")
-                        .lines()
-                        .map(|line| line.trim_start_matches(" * ").trim_start_matches(" *"))
-                        .collect::<Vec<_>>()
-                        .join("\n")
-                        .trim()
-                        .to_string();
+        let comments = self.preceding_comments(&anchor, code, &|text| text.starts_with("/**"));
+        let comment = comments.last()?;
 
-                    return Some(cleaned);
-                }
-            }
-        }
-        None
+        // Clean up the comment
+        let cleaned = comment
+            .trim_start_matches("/**")
+            .trim_end_matches("*/")
+            .lines()
+            .map(|line| line.trim_start_matches(" * ").trim_start_matches(" *"))
+            .collect::<Vec<_>>()
+            .join("\n")
+            .trim()
+            .to_string();
+
+        Some(cleaned)
     }
 
     fn find_calls<'a>(&mut self, code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
@@ -2610,6 +2725,71 @@ impl LanguageParser for TypeScriptParser {
         extends
     }
 
+    fn find_aliases<'a>(&mut self, code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        let mut aliases = Vec::new();
+
+        if let Some(tree) = self.parser.parse(code, None) {
+            self.find_aliases_in_node(tree.root_node(), code, &mut aliases);
+        }
+
+        aliases
+    }
+
+    /// Enum member accesses (`Status.Pending`), from the function they
+    /// appear in
+    ///
+    /// The shared reference pass skips names after a `.`, which need the
+    /// receiver's type. An enum's members are the exception: the receiver
+    /// names the enum itself. It must be an enum declared in the file, or an
+    /// imported name followed by a capitalized member.
+    fn find_references(&mut self, code: &str) -> Vec<ReferenceSite> {
+        let Some(tree) = self.parser.parse(code, None) else {
+            return Vec::new();
+        };
+        let root = tree.root_node();
+        let mut receivers = EnumReceivers::default();
+        receivers.collect(root, code, 0);
+        let mut sites = Vec::new();
+        receivers.member_sites(root, code, None, &mut sites, 0);
+        sites
+    }
+
+    fn find_instantiations(&mut self, code: &str) -> Vec<Instantiation> {
+        let Some(tree) = self.parser.parse(code, None) else {
+            return Vec::new();
+        };
+        let mut instantiations = Vec::new();
+        find_instantiations_in_node(tree.root_node(), code, None, &mut instantiations, 0);
+        instantiations
+    }
+
+    fn find_throws(&mut self, code: &str) -> Vec<(String, String, Range)> {
+        let Some(tree) = self.parser.parse(code, None) else {
+            return Vec::new();
+        };
+        let mut errors = ErrorSites::default();
+        find_errors_in_node(tree.root_node(), code, None, &mut errors, 0);
+        errors.thrown
+    }
+
+    fn find_catches(&mut self, code: &str) -> Vec<(String, String, Range)> {
+        let Some(tree) = self.parser.parse(code, None) else {
+            return Vec::new();
+        };
+        let mut errors = ErrorSites::default();
+        find_errors_in_node(tree.root_node(), code, None, &mut errors, 0);
+        errors.caught
+    }
+
+    fn find_generic_instantiations(&mut self, code: &str) -> Vec<GenericInstantiation> {
+        let Some(tree) = self.parser.parse(code, None) else {
+            return Vec::new();
+        };
+        let mut generics = Vec::new();
+        find_generic_instantiations_in_node(tree.root_node(), code, None, &mut generics, 0);
+        generics
+    }
+
     fn find_imports(&mut self, code: &str, file_id: FileId) -> Vec<Import> {
         let mut imports = Vec::new();
 
@@ -2730,6 +2910,334 @@ impl LanguageParser for TypeScriptParser {
     }
 }
 
+/// Names whose members may be enum members
+#[derive(Default)]
+struct EnumReceivers<'a> {
+    /// Enums declared in the file
+    enums: HashSet<&'a str>,
+    /// Local names of imports
+    imported: HashSet<&'a str>,
+}
+
+impl<'a> EnumReceivers<'a> {
+    fn collect(&mut self, node: Node, code: &'a str, depth: usize) {
+        if !check_recursion_depth(depth, node) {
+            return;
+        }
+        match node.kind() {
+            "enum_declaration" => {
+                if let Some(name) = node.child_by_field_name("name") {
+                    self.enums.insert(&code[name.byte_range()]);
+                }
+            }
+            "import_specifier" => {
+                let local = node
+                    .child_by_field_name("alias")
+                    .or_else(|| node.child_by_field_name("name"));
+                if let Some(local) = local {
+                    self.imported.insert(&code[local.byte_range()]);
+                }
+            }
+            "import_clause" => {
+                // Default import: `import Status from './status'`
+                for child in node.named_children(&mut node.walk()) {
+                    if child.kind() == "identifier" {
+                        self.imported.insert(&code[child.byte_range()]);
+                    }
+                }
+            }
+            _ => {}
+        }
+        for child in node.children(&mut node.walk()) {
+            self.collect(child, code, depth + 1);
+        }
+    }
+
+    fn is_member_access(&self, receiver: &str, member: &str) -> bool {
+        self.enums.contains(receiver)
+            || (self.imported.contains(receiver)
+                && member.starts_with(|c: char| c.is_ascii_uppercase()))
+    }
+
+    /// Member accesses under `node`, with the innermost named function or
+    /// method around them as context
+    fn member_sites<'c>(
+        &self,
+        node: Node,
+        code: &'c str,
+        context: Option<(&'c str, Range)>,
+        sites: &mut Vec<ReferenceSite>,
+        depth: usize,
+    ) {
+        if !check_recursion_depth(depth, node) {
+            return;
+        }
+        let context = function_name(node, code)
+            .map(|name| (name, node_range(node)))
+            .or(context);
+
+        if node.kind() == "member_expression" {
+            let object = node.child_by_field_name("object");
+            let property = node.child_by_field_name("property");
+            if let (Some((context, context_range)), Some(object), Some(property)) =
+                (context, object, property)
+            {
+                let receiver = &code[object.byte_range()];
+                let member = &code[property.byte_range()];
+                if object.kind() == "identifier"
+                    && property.kind() == "property_identifier"
+                    && self.is_member_access(receiver, member)
+                {
+                    sites.push(ReferenceSite {
+                        context: context.to_string(),
+                        context_range,
+                        name: member.to_string(),
+                        range: node_range(property),
+                        receiver: self.enums.contains(receiver).then(|| receiver.to_string()),
+                        option_path: false,
+                    });
+                }
+            }
+        }
+
+        for child in node.children(&mut node.walk()) {
+            self.member_sites(child, code, context, sites, depth + 1);
+        }
+    }
+}
+
+/// Name of a named function or method node, which encloses the references
+/// and instantiations inside it
+fn function_name<'c>(node: Node, code: &'c str) -> Option<&'c str> {
+    let name = match node.kind() {
+        "function_declaration" | "generator_function_declaration" | "method_definition" => {
+            node.child_by_field_name("name")
+        }
+        "variable_declarator" => node
+            .child_by_field_name("value")
+            .filter(|value| matches!(value.kind(), "arrow_function" | "function_expression"))
+            .and_then(|_| node.child_by_field_name("name")),
+        _ => None,
+    };
+    name.map(|name| &code[name.byte_range()])
+}
+
+/// `new Foo()` and `new ns.Foo()` inside named functions; the class's
+/// `constructor` method runs
+fn find_instantiations_in_node<'a>(
+    node: Node,
+    code: &'a str,
+    context: Option<(&'a str, Range)>,
+    instantiations: &mut Vec<Instantiation>,
+    depth: usize,
+) {
+    if !check_recursion_depth(depth, node) {
+        return;
+    }
+    let context = function_name(node, code)
+        .map(|name| (name, node_range(node)))
+        .or(context);
+
+    if node.kind() == "new_expression" {
+        let class = node
+            .child_by_field_name("constructor")
+            .and_then(|class| match class.kind() {
+                "identifier" => Some(class),
+                "member_expression" => class.child_by_field_name("property"),
+                _ => None,
+            });
+        if let (Some((context, context_range)), Some(class)) = (context, class) {
+            instantiations.push(Instantiation {
+                context: context.to_string(),
+                context_range,
+                type_name: code[class.byte_range()].to_string(),
+                constructor: Some("constructor".to_string()),
+                range: node_range(class),
+            });
+        }
+    }
+
+    for child in node.children(&mut node.walk()) {
+        find_instantiations_in_node(child, code, context, instantiations, depth + 1);
+    }
+}
+
+/// Thrown and caught error classes, as (function, class, site)
+#[derive(Default)]
+struct ErrorSites {
+    thrown: Vec<(String, String, Range)>,
+    caught: Vec<(String, String, Range)>,
+}
+
+/// `throw new HttpError()` and `throw Error(..)` inside named functions,
+/// and catches: a `catch (e: HttpError)` annotation, or an `e instanceof
+/// HttpError` test in the catch body, as untyped catches narrow that way
+fn find_errors_in_node<'a>(
+    node: Node,
+    code: &'a str,
+    context: Option<&'a str>,
+    errors: &mut ErrorSites,
+    depth: usize,
+) {
+    if !check_recursion_depth(depth, node) {
+        return;
+    }
+    let context = function_name(node, code).or(context);
+    let site = |class: Node| (code[class.byte_range()].to_string(), node_range(class));
+
+    match (node.kind(), context) {
+        ("throw_statement", Some(context)) => {
+            if let Some(class) = node
+                .named_child(0)
+                .and_then(|class| error_class(class, code))
+            {
+                let (class, range) = site(class);
+                errors.thrown.push((context.to_string(), class, range));
+            }
+        }
+        ("catch_clause", Some(context)) => {
+            let annotated = node
+                .child_by_field_name("type")
+                .and_then(|annotation| annotation.named_child(0))
+                .and_then(|class| error_class(class, code));
+            let parameter = node
+                .child_by_field_name("parameter")
+                .map(|parameter| &code[parameter.byte_range()]);
+            let mut tested = Vec::new();
+            if let (Some(parameter), Some(body)) = (parameter, node.child_by_field_name("body")) {
+                collect_instanceof_tests(body, code, parameter, &mut tested, depth + 1);
+            }
+            for class in annotated.into_iter().chain(tested) {
+                let (class, range) = site(class);
+                errors.caught.push((context.to_string(), class, range));
+            }
+        }
+        _ => {}
+    }
+
+    for child in node.children(&mut node.walk()) {
+        find_errors_in_node(child, code, context, errors, depth + 1);
+    }
+}
+
+/// The capitalized class a thrown or caught expression names: `HttpError`,
+/// `new errors.HttpError()`, `Error("..")`
+fn error_class<'t>(node: Node<'t>, code: &str) -> Option<Node<'t>> {
+    let class = match node.kind() {
+        "new_expression" => node.child_by_field_name("constructor")?,
+        "call_expression" => node.child_by_field_name("function")?,
+        _ => node,
+    };
+    let class = match class.kind() {
+        "member_expression" => class.child_by_field_name("property")?,
+        "identifier" | "type_identifier" => class,
+        _ => return None,
+    };
+    code[class.byte_range()]
+        .starts_with(|c: char| c.is_ascii_uppercase())
+        .then_some(class)
+}
+
+/// Classes on the right of `variable instanceof Class` tests
+fn collect_instanceof_tests<'t>(
+    node: Node<'t>,
+    code: &str,
+    variable: &str,
+    classes: &mut Vec<Node<'t>>,
+    depth: usize,
+) {
+    if !check_recursion_depth(depth, node) {
+        return;
+    }
+    if node.kind() == "binary_expression"
+        && node
+            .child_by_field_name("operator")
+            .is_some_and(|operator| operator.kind() == "instanceof")
+        && node
+            .child_by_field_name("left")
+            .is_some_and(|left| &code[left.byte_range()] == variable)
+    {
+        classes.extend(
+            node.child_by_field_name("right")
+                .map(|right| right.child_by_field_name("property").unwrap_or(right)),
+        );
+    }
+    for child in node.children(&mut node.walk()) {
+        collect_instanceof_tests(child, code, variable, classes, depth + 1);
+    }
+}
+
+/// Generics given type arguments - `Map<string, Session>` in a type,
+/// `new Map<string, Session>()`, `useState<Session>()` - inside named
+/// functions, classes, interfaces and type aliases
+fn find_generic_instantiations_in_node<'a>(
+    node: Node,
+    code: &'a str,
+    context: Option<(&'a str, Range)>,
+    generics: &mut Vec<GenericInstantiation>,
+    depth: usize,
+) {
+    if !check_recursion_depth(depth, node) {
+        return;
+    }
+    let declared = match node.kind() {
+        "class_declaration"
+        | "abstract_class_declaration"
+        | "interface_declaration"
+        | "type_alias_declaration" => node
+            .child_by_field_name("name")
+            .map(|name| &code[name.byte_range()]),
+        _ => function_name(node, code),
+    };
+    let context = declared.map(|name| (name, node_range(node))).or(context);
+
+    let generic = match node.kind() {
+        "generic_type" => node.child_by_field_name("name"),
+        "new_expression" => node.child_by_field_name("constructor"),
+        "call_expression" => node.child_by_field_name("function"),
+        _ => None,
+    }
+    .and_then(|generic| match generic.kind() {
+        "member_expression" => generic.child_by_field_name("property"),
+        "nested_type_identifier" => generic.child_by_field_name("name"),
+        "identifier" | "type_identifier" => Some(generic),
+        _ => None,
+    });
+    let type_arguments: Vec<String> = node
+        .child_by_field_name("type_arguments")
+        .map(|arguments| {
+            arguments
+                .named_children(&mut arguments.walk())
+                .map(|argument| code[argument.byte_range()].to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    if let (Some((context, context_range)), Some(generic), false) =
+        (context, generic, type_arguments.is_empty())
+    {
+        generics.push(GenericInstantiation {
+            context: context.to_string(),
+            context_range,
+            generic: code[generic.byte_range()].to_string(),
+            type_arguments,
+            range: node_range(generic),
+        });
+    }
+
+    for child in node.children(&mut node.walk()) {
+        find_generic_instantiations_in_node(child, code, context, generics, depth + 1);
+    }
+}
+
+fn node_range(node: Node) -> Range {
+    Range::new(
+        node.start_position().row as u32,
+        node.start_position().column as u16,
+        node.end_position().row as u32,
+        node.end_position().column as u16,
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -3073,6 +3581,156 @@ export { default as MyButton } from './Button';
         );
     }
 
+    #[test]
+    fn test_find_instantiations() {
+        let mut parser = TypeScriptParser::new().unwrap();
+        let code = r#"
+const shared = new Cache();
+
+class Service {
+    start(): Server {
+        return new http.Server(new Config());
+    }
+}
+"#;
+        let sites: Vec<(String, String)> = parser
+            .find_instantiations(code)
+            .into_iter()
+            .map(|site| (site.context, site.type_name))
+            .collect();
+        assert_eq!(
+            sites,
+            [
+                ("start".to_string(), "Server".to_string()),
+                ("start".to_string(), "Config".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_throws_and_catches() {
+        let mut parser = TypeScriptParser::new().unwrap();
+        let code = r#"
+async function fetchUser(id: string): Promise<User> {
+    if (!id) {
+        throw new errors.ValidationError("missing id");
+    }
+    try {
+        return await api.get(id);
+    } catch (error) {
+        if (error instanceof HttpError || error instanceof net.TimeoutError) {
+            throw Error("unavailable");
+        }
+        throw error;
+    }
+}
+"#;
+        let pairs = |sites: Vec<(String, String, Range)>| -> Vec<(String, String)> {
+            sites
+                .into_iter()
+                .map(|(context, class, _)| (context, class))
+                .collect()
+        };
+        let pair = |class: &str| ("fetchUser".to_string(), class.to_string());
+
+        assert_eq!(
+            pairs(parser.find_throws(code)),
+            [pair("ValidationError"), pair("Error")]
+        );
+        assert_eq!(
+            pairs(parser.find_catches(code)),
+            [pair("HttpError"), pair("TimeoutError")]
+        );
+    }
+
+    #[test]
+    fn test_find_generic_instantiations() {
+        let mut parser = TypeScriptParser::new().unwrap();
+        let code = r#"
+const loose: Array<string> = [];
+
+interface Store {
+    sessions: Map<string, Session>;
+}
+
+function track(id: string): Promise<void> {
+    const seen = new Map<string, Array<Session>>();
+    return api.fetch<Session>(id);
+}
+"#;
+        let sites: Vec<_> = parser
+            .find_generic_instantiations(code)
+            .into_iter()
+            .map(|site| (site.context, site.generic, site.type_arguments.join(" | ")))
+            .collect();
+        let site = |context: &str, generic: &str, arguments: &str| {
+            (
+                context.to_string(),
+                generic.to_string(),
+                arguments.to_string(),
+            )
+        };
+        assert_eq!(
+            sites,
+            [
+                site("Store", "Map", "string | Session"),
+                site("track", "Promise", "void"),
+                site("track", "Map", "string | Array<Session>"),
+                site("track", "Array", "Session"),
+                site("track", "fetch", "Session"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_enum_members_and_references() {
+        let mut parser = TypeScriptParser::new().unwrap();
+        let file_id = FileId::new(1).unwrap();
+        let code = r#"
+import { Color } from './color';
+import { helpers } from './helpers';
+
+export enum Status { Pending, Done = "done" }
+
+function label(s: Status): string {
+    if (s === Status.Pending) { return Color.Red; }
+    return helpers.format(s);
+}
+"#;
+
+        let mut counter = SymbolCounter::new();
+        let symbols = parser.parse(code, file_id, &mut counter);
+        let members: Vec<&str> = symbols
+            .iter()
+            .filter(|s| s.kind == SymbolKind::Constant)
+            .map(|s| s.name.as_ref())
+            .collect();
+        assert_eq!(members, ["Pending", "Done"]);
+        let pending = symbols
+            .iter()
+            .find(|s| s.name.as_ref() == "Pending")
+            .unwrap();
+        assert_eq!(
+            pending.scope_context,
+            Some(crate::symbol::ScopeContext::ClassMember {
+                class_name: Some("Status".into())
+            })
+        );
+
+        let references: Vec<(String, String)> = parser
+            .find_references(code)
+            .into_iter()
+            .map(|site| (site.context, site.name))
+            .collect();
+        assert_eq!(
+            references,
+            [
+                ("label".to_string(), "Pending".to_string()),
+                ("label".to_string(), "Red".to_string()),
+            ]
+        );
+    }
+
     #[test]
     fn test_typescript_find_variable_types_new_expression() {
         let mut parser = TypeScriptParser::new().unwrap();
@@ -3245,4 +3903,29 @@ export function AnotherComponent() {
 
         println!("✅ JSX component usage tracking working");
     }
+
+    #[test]
+    fn test_doc_comment_nearest_and_own_line() {
+        let mut parser = TypeScriptParser::new().unwrap();
+        let code = r#"
+/** Not the doc */
+/** Exported helper */
+export function exported() {}
+
+const limit = 10; /** trailing note */
+function local() {}
+"#;
+
+        let mut counter = SymbolCounter::new();
+        let symbols = parser.parse(code, FileId::new(1).unwrap(), &mut counter);
+        let doc = |name: &str| {
+            symbols
+                .iter()
+                .find(|s| s.name.as_ref() == name)
+                .and_then(|s| s.doc_comment.as_deref())
+        };
+
+        assert_eq!(doc("exported"), Some("Exported helper"));
+        assert_eq!(doc("local"), None, "trailing comment belongs to limit");
+    }
 }
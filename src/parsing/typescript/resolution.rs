@@ -24,7 +24,7 @@
 //! - Type/value space population from parser
 
 use crate::parsing::resolution::{ImportBinding, ProjectResolutionEnhancer};
-use crate::parsing::{InheritanceResolver, ResolutionScope, ScopeLevel, ScopeType};
+use crate::parsing::{NameKeyedInheritanceResolver, ResolutionScope, ScopeLevel, ScopeType};
 use crate::project_resolver::persist::ResolutionRules;
 use crate::{FileId, SymbolId};
 use std::collections::HashMap;
@@ -510,6 +510,14 @@ impl ResolutionScope for TypeScriptResolutionContext {
                 // Very permissive - almost anything can reference anything
                 true
             }
+            Aliases | AliasedBy => {
+                // `type X = Y` and `import { A as B }` can rename anything
+                true
+            }
+            Throws | ThrownBy => {
+                // Anything can be thrown, though usually an Error subclass
+                true
+            }
         }
     }
 
@@ -583,7 +591,7 @@ impl TypeScriptInheritanceResolver {
     }
 }
 
-impl InheritanceResolver for TypeScriptInheritanceResolver {
+impl NameKeyedInheritanceResolver for TypeScriptInheritanceResolver {
     fn add_inheritance(&mut self, child: String, parent: String, kind: &str) {
         match kind {
             "extends" => {
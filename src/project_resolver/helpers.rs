@@ -542,6 +542,7 @@ mod tests {
             parser_options: HashMap::new(),
             config_files,
             projects: Vec::new(),
+            profile: None,
         };
         settings.languages.insert(language_id.to_string(), config);
         settings
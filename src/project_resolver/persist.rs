@@ -237,6 +237,7 @@ mod tests {
             extensions: vec![".ts".to_string(), ".tsx".to_string()],
             parser_options: Default::default(),
             projects: Vec::new(),
+            profile: None,
         };
         settings
             .languages
@@ -231,6 +231,7 @@ mod tests {
             parser_options: HashMap::new(),
             config_files,
             projects: Vec::new(),
+            profile: None,
         };
         settings
             .languages
@@ -267,6 +268,7 @@ mod tests {
             parser_options: HashMap::new(),
             config_files: vec![],
             projects: Vec::new(),
+            profile: None,
         };
         settings
             .languages
@@ -227,6 +227,7 @@ mod tests {
             parser_options: HashMap::new(),
             config_files,
             projects: Vec::new(),
+            profile: None,
         };
         settings
             .languages
@@ -263,6 +264,7 @@ mod tests {
             parser_options: HashMap::new(),
             config_files: vec![],
             projects: Vec::new(),
+            profile: None,
         };
         settings
             .languages
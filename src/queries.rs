@@ -0,0 +1,451 @@
+//! Saved queries and named views.
+//!
+//! Queries are declared in settings and invoked by name from the CLI
+//! (`codanna retrieve query <name>`) or MCP (`run_saved_query`):
+//!
+//! ```toml
+//! [queries]
+//! public-api = "kind:function visibility:public lang:rust"
+//! crate-internal = "visibility:crate exported:false lang:rust"
+//! handlers-api = "tag:api-handler kind:function"
+//! web-components = "kind:function project:@acme/web exported:true"
+//! handlers = "{term} kind:function module:{module} limit:20"
+//! ```
+//!
+//! A query is a list of `key:value` filters plus optional free text. Free text
+//! goes through full-text search; without it the filters are applied to the
+//! whole symbol table. `{param}` placeholders are substituted from the
+//! parameters supplied at invocation, so `handlers` above runs as
+//! `codanna retrieve query handlers term:parse module:crate::io`.
+
+use crate::indexing::facade::IndexFacade;
+use crate::io::ExitCode;
+use crate::{Settings, Symbol, SymbolKind, Visibility};
+use std::collections::HashMap;
+use thiserror::Error;
+
+const DEFAULT_LIMIT: usize = 50;
+
+/// Errors raised while resolving a saved query
+#[derive(Error, Debug)]
+pub enum SavedQueryError {
+    #[error(
+        "Unknown saved query '{name}'\nSuggestion: Available queries: {available}. Define queries under [queries] in .codanna/settings.toml"
+    )]
+    Unknown { name: String, available: String },
+
+    #[error(
+        "Saved query '{name}' is missing parameters: {}\nSuggestion: Pass them as key:value arguments, e.g. {}:<value>",
+        .missing.join(", "),
+        .missing[0]
+    )]
+    MissingParams { name: String, missing: Vec<String> },
+
+    #[error("Invalid {key} filter '{value}' in saved query\nSuggestion: {hint}")]
+    InvalidFilter {
+        key: &'static str,
+        value: String,
+        hint: &'static str,
+    },
+}
+
+impl SavedQueryError {
+    pub fn exit_code(&self) -> ExitCode {
+        match self {
+            SavedQueryError::Unknown { .. } => ExitCode::NotFound,
+            SavedQueryError::MissingParams { .. } | SavedQueryError::InvalidFilter { .. } => {
+                ExitCode::ConfigError
+            }
+        }
+    }
+}
+
+/// A saved query after parameter substitution
+#[derive(Debug, Clone, PartialEq)]
+pub struct SavedQuery {
+    /// Free text for full-text search
+    pub text: Option<String>,
+    pub kind: Option<SymbolKind>,
+    pub lang: Option<String>,
+    pub module: Option<String>,
+    pub visibility: Option<Visibility>,
+    /// Whether the symbol is part of its module's exported surface
+    pub exported: Option<bool>,
+    /// A tag from the `[[tags]]` rules the symbol must carry
+    pub tag: Option<String>,
+    /// Monorepo project the symbol must belong to
+    pub project: Option<String>,
+    pub limit: usize,
+}
+
+impl SavedQuery {
+    /// Look up `name` in settings and substitute `params` into it
+    pub fn resolve(
+        settings: &Settings,
+        name: &str,
+        params: &HashMap<String, String>,
+    ) -> Result<Self, SavedQueryError> {
+        let template = settings
+            .queries
+            .get(name)
+            .ok_or_else(|| SavedQueryError::Unknown {
+                name: name.to_string(),
+                available: if settings.queries.is_empty() {
+                    "none".to_string()
+                } else {
+                    settings
+                        .queries
+                        .keys()
+                        .cloned()
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                },
+            })?;
+
+        let expanded =
+            substitute(template, params).map_err(|missing| SavedQueryError::MissingParams {
+                name: name.to_string(),
+                missing,
+            })?;
+        Self::parse(&expanded)
+    }
+
+    /// Parse an already substituted query string
+    pub fn parse(query: &str) -> Result<Self, SavedQueryError> {
+        let mut parsed = SavedQuery {
+            text: None,
+            kind: None,
+            lang: None,
+            module: None,
+            visibility: None,
+            exported: None,
+            tag: None,
+            project: None,
+            limit: DEFAULT_LIMIT,
+        };
+        let mut words = Vec::new();
+
+        for token in tokenize(query) {
+            let Some((key, value)) = token.split_once(':') else {
+                words.push(token);
+                continue;
+            };
+            match key {
+                "kind" => {
+                    parsed.kind = Some(value.parse().map_err(|_| SavedQueryError::InvalidFilter {
+                        key: "kind",
+                        value: value.to_string(),
+                        hint: "Use function, method, struct, enum, trait, interface, class, module, variable, constant, field, typealias, or macro",
+                    })?);
+                }
+                "visibility" => {
+                    parsed.visibility = Some(parse_visibility(value).ok_or(
+                        SavedQueryError::InvalidFilter {
+                            key: "visibility",
+                            value: value.to_string(),
+                            hint: "Use public, crate, module, protected, package, or private",
+                        },
+                    )?);
+                }
+                "exported" => {
+                    parsed.exported =
+                        Some(value.parse().map_err(|_| SavedQueryError::InvalidFilter {
+                            key: "exported",
+                            value: value.to_string(),
+                            hint: "Use true or false",
+                        })?);
+                }
+                "limit" => {
+                    parsed.limit = value.parse().map_err(|_| SavedQueryError::InvalidFilter {
+                        key: "limit",
+                        value: value.to_string(),
+                        hint: "Use a positive number",
+                    })?;
+                }
+                "lang" => parsed.lang = Some(value.to_string()),
+                "module" => parsed.module = Some(value.to_string()),
+                "tag" => parsed.tag = Some(value.to_string()),
+                "project" => parsed.project = Some(value.to_string()),
+                "query" => words.push(value.to_string()),
+                // Not a filter, e.g. a path like `std::io`
+                _ => words.push(token),
+            }
+        }
+
+        if !words.is_empty() {
+            parsed.text = Some(words.join(" "));
+        }
+        Ok(parsed)
+    }
+
+    fn matches(&self, symbol: &Symbol) -> bool {
+        self.kind.is_none_or(|kind| symbol.kind == kind)
+            && self.visibility.is_none_or(|v| symbol.visibility == v)
+            && self.exported.is_none_or(|e| symbol.exported == e)
+            && self
+                .tag
+                .as_deref()
+                .is_none_or(|tag| symbol.tags.iter().any(|t| &**t == tag))
+            && self
+                .project
+                .as_deref()
+                .is_none_or(|project| symbol.project.as_deref() == Some(project))
+            && self.lang.as_deref().is_none_or(|lang| {
+                symbol
+                    .language_id
+                    .is_some_and(|id| id.as_str().eq_ignore_ascii_case(lang))
+            })
+            && self
+                .module
+                .as_deref()
+                .is_none_or(|module| symbol.module_path.as_deref() == Some(module))
+    }
+
+    /// Run the query against the index
+    pub fn execute(&self, facade: &IndexFacade) -> Vec<Symbol> {
+        match &self.text {
+            Some(text) => {
+                // Visibility, tags and projects are not search filters;
+                // over-fetch so post-filtering still fills the limit in the
+                // common case
+                let post_filtered = self.visibility.is_some()
+                    || self.exported.is_some()
+                    || self.tag.is_some()
+                    || self.project.is_some();
+                let fetch = if post_filtered {
+                    self.limit.saturating_mul(4)
+                } else {
+                    self.limit
+                };
+                facade
+                    .search(
+                        text,
+                        fetch,
+                        self.kind,
+                        self.module.as_deref(),
+                        self.lang.as_deref(),
+                    )
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter_map(|result| facade.get_symbol(result.symbol_id))
+                    .filter(|symbol| self.matches(symbol))
+                    .take(self.limit)
+                    .collect()
+            }
+            None => {
+                let mut symbols = Vec::new();
+                let _ = facade.document_index().for_each_symbol(|symbol| {
+                    if symbols.len() < self.limit && self.matches(&symbol) {
+                        symbols.push(symbol);
+                    }
+                    Ok(())
+                });
+                symbols
+            }
+        }
+    }
+}
+
+/// Replace `{param}` placeholders, returning the names of missing parameters
+pub fn substitute(template: &str, params: &HashMap<String, String>) -> Result<String, Vec<String>> {
+    let mut out = String::with_capacity(template.len());
+    let mut missing = Vec::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let Some(end) = after.find('}') else {
+            out.push_str(&rest[start..]);
+            rest = "";
+            break;
+        };
+        let name = &after[..end];
+        match params.get(name) {
+            Some(value) if value.contains(char::is_whitespace) => {
+                out.push('"');
+                out.push_str(value);
+                out.push('"');
+            }
+            Some(value) => out.push_str(value),
+            None => {
+                if !missing.iter().any(|m| m == name) {
+                    missing.push(name.to_string());
+                }
+            }
+        }
+        rest = &after[end + 1..];
+    }
+    out.push_str(rest);
+
+    if missing.is_empty() {
+        Ok(out)
+    } else {
+        Err(missing)
+    }
+}
+
+/// Split on whitespace, keeping double-quoted runs together
+fn tokenize(query: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+
+    for c in query.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+fn parse_visibility(value: &str) -> Option<Visibility> {
+    Some(match value.to_lowercase().as_str() {
+        "public" => Visibility::Public,
+        "crate" | "internal" => Visibility::Crate,
+        "module" => Visibility::Module,
+        "protected" => Visibility::Protected,
+        "package" => Visibility::Package,
+        "private" => Visibility::Private,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_filters_and_text() {
+        let query =
+            SavedQuery::parse("parse input kind:function visibility:public lang:rust limit:5")
+                .unwrap();
+        assert_eq!(query.text.as_deref(), Some("parse input"));
+        assert_eq!(query.kind, Some(SymbolKind::Function));
+        assert_eq!(query.visibility, Some(Visibility::Public));
+        assert_eq!(query.lang.as_deref(), Some("rust"));
+        assert_eq!(query.limit, 5);
+
+        let filters_only = SavedQuery::parse("kind:struct").unwrap();
+        assert_eq!(filters_only.text, None);
+        assert_eq!(filters_only.limit, DEFAULT_LIMIT);
+
+        assert!(matches!(
+            SavedQuery::parse("kind:widget"),
+            Err(SavedQueryError::InvalidFilter { key: "kind", .. })
+        ));
+    }
+
+    #[test]
+    fn test_crate_public_but_not_exported() {
+        let query = SavedQuery::parse("visibility:crate exported:false").unwrap();
+        assert_eq!(query.visibility, Some(Visibility::Crate));
+        assert_eq!(query.exported, Some(false));
+
+        let symbol = Symbol::new(
+            crate::SymbolId::new(1).unwrap(),
+            "helper",
+            SymbolKind::Function,
+            crate::FileId::new(1).unwrap(),
+            crate::Range::new(1, 0, 3, 1),
+        )
+        .with_visibility(Visibility::Crate);
+        assert!(query.matches(&symbol));
+        assert!(!query.matches(&symbol.clone().with_exported(true)));
+        assert!(!query.matches(&symbol.with_visibility(Visibility::Public)));
+
+        assert!(matches!(
+            SavedQuery::parse("exported:maybe"),
+            Err(SavedQueryError::InvalidFilter {
+                key: "exported",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_tag_filter() {
+        let query = SavedQuery::parse("tag:api-handler kind:function").unwrap();
+        assert_eq!(query.tag.as_deref(), Some("api-handler"));
+        assert_eq!(query.text, None);
+
+        let symbol = Symbol::new(
+            crate::SymbolId::new(1).unwrap(),
+            "handle_login",
+            SymbolKind::Function,
+            crate::FileId::new(1).unwrap(),
+            crate::Range::new(1, 0, 3, 1),
+        );
+        assert!(!query.matches(&symbol));
+        assert!(
+            query.matches(&symbol.with_tags(vec!["deprecated-zone".into(), "api-handler".into()]))
+        );
+    }
+
+    #[test]
+    fn test_project_filter() {
+        let query = SavedQuery::parse("project:@acme/web parse").unwrap();
+        assert_eq!(query.project.as_deref(), Some("@acme/web"));
+        assert_eq!(query.text.as_deref(), Some("parse"));
+
+        let symbol = Symbol::new(
+            crate::SymbolId::new(1).unwrap(),
+            "parse",
+            SymbolKind::Function,
+            crate::FileId::new(1).unwrap(),
+            crate::Range::new(1, 0, 3, 1),
+        );
+        assert!(!query.matches(&symbol));
+        assert!(!query.matches(&symbol.clone().with_project(Some("acme-core".into()))));
+        assert!(query.matches(&symbol.with_project(Some("@acme/web".into()))));
+    }
+
+    #[test]
+    fn test_substitution() {
+        let template = "{term} kind:function module:{module}";
+        let expanded = substitute(
+            template,
+            &params(&[("term", "error handling"), ("module", "io")]),
+        )
+        .unwrap();
+        assert_eq!(expanded, "\"error handling\" kind:function module:io");
+
+        let query = SavedQuery::parse(&expanded).unwrap();
+        assert_eq!(query.text.as_deref(), Some("error handling"));
+
+        let missing = substitute(template, &params(&[])).unwrap_err();
+        assert_eq!(missing, vec!["term".to_string(), "module".to_string()]);
+    }
+
+    #[test]
+    fn test_resolve_from_settings() {
+        let mut settings = Settings::default();
+        settings.queries.insert(
+            "public-api".to_string(),
+            "kind:function visibility:public".to_string(),
+        );
+
+        let query = SavedQuery::resolve(&settings, "public-api", &HashMap::new()).unwrap();
+        assert_eq!(query.visibility, Some(Visibility::Public));
+
+        let err = SavedQuery::resolve(&settings, "nope", &HashMap::new()).unwrap_err();
+        assert_eq!(err.exit_code(), ExitCode::NotFound);
+        assert!(err.to_string().contains("public-api"));
+    }
+}
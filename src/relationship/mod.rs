@@ -15,6 +15,15 @@ pub enum RelationKind {
     DefinedIn,
     References,
     ReferencedBy,
+    /// `from` is another name for `to`: `type NodeId = u32`,
+    /// `import { A as B }`, `inherit (pkgs) hello`
+    Aliases,
+    AliasedBy,
+    /// `from` raises or returns the error type `to`: Python `raise`, a
+    /// `throw`, Rust `Err(..)` or a `Result<_, E>` return. With the
+    /// `caught:true` attribute, `from` catches `to` instead.
+    Throws,
+    ThrownBy,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -31,6 +40,33 @@ pub struct RelationshipMetadata {
     pub context: Option<Box<str>>,
 }
 
+/// How sure the parser is that an edge exists. Edges are `High` unless marked
+/// otherwise, as calls through overloadable operators are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Confidence {
+    /// Inferred from a name alone, like `a + b` calling some `add`
+    Low,
+    High,
+}
+
+impl Confidence {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::High => "high",
+        }
+    }
+
+    /// Parse `low` or `high`, case-insensitively
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "low" => Some(Self::Low),
+            "high" => Some(Self::High),
+            _ => None,
+        }
+    }
+}
+
 #[repr(C)]
 #[derive(Debug, Clone, Copy)]
 pub struct CompactRelationship {
@@ -86,6 +122,10 @@ impl RelationKind {
             Self::DefinedIn => Self::Defines,
             Self::References => Self::ReferencedBy,
             Self::ReferencedBy => Self::References,
+            Self::Aliases => Self::AliasedBy,
+            Self::AliasedBy => Self::Aliases,
+            Self::Throws => Self::ThrownBy,
+            Self::ThrownBy => Self::Throws,
         }
     }
 
@@ -124,6 +164,38 @@ impl RelationshipMetadata {
         self.context = Some(context.into());
         self
     }
+
+    /// Add a `key:value` entry to the context, which holds comma-separated
+    /// entries such as `receiver:self,static:false`.
+    pub fn with_attribute(mut self, key: &str, value: &str) -> Self {
+        let entry = format!("{key}:{value}");
+        self.context = Some(match self.context.take() {
+            Some(context) if !context.is_empty() => format!("{context},{entry}").into(),
+            _ => entry.into(),
+        });
+        self
+    }
+
+    /// Value of a `key:value` entry in the context
+    pub fn attribute(&self, key: &str) -> Option<&str> {
+        self.context.as_deref()?.split(',').find_map(|entry| {
+            entry
+                .split_once(':')
+                .filter(|(k, _)| *k == key)
+                .map(|(_, value)| value)
+        })
+    }
+
+    /// Mark the edge with a confidence other than the default `High`
+    pub fn with_confidence(self, confidence: Confidence) -> Self {
+        self.with_attribute("confidence", confidence.as_str())
+    }
+
+    pub fn confidence(&self) -> Confidence {
+        self.attribute("confidence")
+            .and_then(Confidence::parse)
+            .unwrap_or(Confidence::High)
+    }
 }
 
 pub struct RelationshipEdge {
@@ -175,6 +247,33 @@ mod tests {
         assert_eq!(meta.context.as_deref(), Some("inside main function"));
     }
 
+    #[test]
+    fn test_metadata_attributes() {
+        let metadata = RelationshipMetadata::new()
+            .at_position(3, 4)
+            .with_attribute("category", "fetcher")
+            .with_attribute("receiver", "builtins");
+
+        assert_eq!(
+            metadata.context.as_deref(),
+            Some("category:fetcher,receiver:builtins")
+        );
+        assert_eq!(metadata.attribute("category"), Some("fetcher"));
+        assert_eq!(metadata.attribute("receiver"), Some("builtins"));
+        assert_eq!(metadata.attribute("static"), None);
+    }
+
+    #[test]
+    fn test_metadata_confidence() {
+        let metadata = RelationshipMetadata::new().with_attribute("operator", "+");
+        assert_eq!(metadata.confidence(), Confidence::High);
+
+        let metadata = metadata.with_confidence(Confidence::Low);
+        assert_eq!(metadata.attribute("operator"), Some("+"));
+        assert_eq!(metadata.confidence(), Confidence::Low);
+        assert!(Confidence::Low < Confidence::High);
+    }
+
     #[test]
     fn test_relation_kind_inverse() {
         assert_eq!(RelationKind::Calls.inverse(), RelationKind::CalledBy);
@@ -189,6 +288,8 @@ mod tests {
             RelationKind::References.inverse(),
             RelationKind::ReferencedBy
         );
+        assert_eq!(RelationKind::Aliases.inverse(), RelationKind::AliasedBy);
+        assert_eq!(RelationKind::Throws.inverse(), RelationKind::ThrownBy);
     }
 
     #[test]
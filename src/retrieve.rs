@@ -3,13 +3,16 @@
 //! This module uses QueryContext to reduce duplication across retrieve functions.
 
 use crate::Symbol;
+use crate::deps::ExternalSymbol;
 use crate::indexing::facade::IndexFacade;
 use crate::io::{
     EntityType, ExitCode, OutputFormat, OutputManager, OutputStatus,
     envelope::{EntityType as EnvelopeEntityType, Envelope, ResultCode},
     schema::{OutputData, OutputMetadata, UnifiedOutput, UnifiedOutputBuilder},
 };
+use crate::navigation::{CallDirection, Hover, hover};
 use crate::symbol::context::SymbolContext;
+use crate::types::PositionEncoding;
 use serde::Serialize;
 use std::borrow::Cow;
 use std::fmt::Display;
@@ -97,7 +100,7 @@ impl<'a> QueryContext<'a> {
 
     /// Output not-found result.
     pub fn output_not_found(&self, query: &str) -> ExitCode {
-        if self.format == OutputFormat::Json {
+        if self.format.is_structured() {
             let envelope: Envelope<()> = Envelope::not_found(format!(
                 "No symbol found for '{query}'"
             ))
@@ -108,7 +111,12 @@ impl<'a> QueryContext<'a> {
                     .to_string(),
             );
 
-            println!("{}", envelope.to_json().expect("envelope serialization"));
+            println!(
+                "{}",
+                envelope
+                    .render(self.format)
+                    .expect("envelope serialization")
+            );
             ExitCode::NotFound
         } else {
             eprintln!("Not found: '{query}'");
@@ -118,7 +126,7 @@ impl<'a> QueryContext<'a> {
 
     /// Output ambiguous match result.
     pub fn output_ambiguous(&self, query: &str, symbols: &[Symbol]) -> ExitCode {
-        if self.format == OutputFormat::Json {
+        if self.format.is_structured() {
             // In JSON mode, return an error with suggestions
             let suggestions: Vec<String> = symbols
                 .iter()
@@ -155,11 +163,18 @@ impl<'a> QueryContext<'a> {
                 .collect();
 
             let envelope = envelope.with_error_details(crate::io::envelope::ErrorDetails {
+                category: None,
+                error_code: None,
                 suggestions,
                 context: Some(serde_json::json!(context)),
             });
 
-            println!("{}", envelope.to_json().expect("envelope serialization"));
+            println!(
+                "{}",
+                envelope
+                    .render(self.format)
+                    .expect("envelope serialization")
+            );
             ExitCode::GeneralError
         } else {
             // Text mode - print to stderr
@@ -189,16 +204,45 @@ impl<'a> QueryContext<'a> {
         }
     }
 
+    /// Output not-found result for a file path.
+    pub fn output_file_not_found(&self, path: &str) -> ExitCode {
+        if self.format.is_structured() {
+            let envelope: Envelope<()> =
+                Envelope::not_found(format!("File '{path}' is not in the index"))
+                    .with_entity_type(self.entity_type)
+                    .with_query(path)
+                    .with_hint(
+                        "Use a path relative to the workspace root, as shown in search results"
+                            .to_string(),
+                    );
+
+            println!(
+                "{}",
+                envelope
+                    .render(self.format)
+                    .expect("envelope serialization")
+            );
+        } else {
+            eprintln!("Not indexed: '{path}'");
+        }
+        ExitCode::NotFound
+    }
+
     /// Output invalid symbol_id error.
     pub fn output_invalid_id(&self, id: &str) -> ExitCode {
-        if self.format == OutputFormat::Json {
+        if self.format.is_structured() {
             let envelope: Envelope<()> = Envelope::error(
                 ResultCode::InvalidQuery,
                 format!("Invalid symbol_id format: '{id}'"),
             )
             .with_hint("symbol_id must be a positive integer");
 
-            println!("{}", envelope.to_json().expect("envelope serialization"));
+            println!(
+                "{}",
+                envelope
+                    .render(self.format)
+                    .expect("envelope serialization")
+            );
         } else {
             eprintln!("Invalid symbol_id format: {id}");
         }
@@ -214,7 +258,7 @@ impl<'a> QueryContext<'a> {
     ) -> ExitCode {
         let count = data.len();
 
-        if self.format == OutputFormat::Json {
+        if self.format.is_structured() {
             let mut envelope = Envelope::success(data)
                 .with_entity_type(self.entity_type)
                 .with_count(count)
@@ -226,9 +270,9 @@ impl<'a> QueryContext<'a> {
             }
 
             let json = if let Some(ref fields) = self.fields {
-                envelope.to_json_with_fields(fields)
+                envelope.render_with_fields(self.format, fields)
             } else {
-                envelope.to_json()
+                envelope.render(self.format)
             };
 
             println!("{}", json.expect("envelope serialization"));
@@ -244,14 +288,19 @@ impl<'a> QueryContext<'a> {
 
     /// Output empty success (symbol found but no results).
     pub fn output_empty(&self, query: &str, message: &str) -> ExitCode {
-        if self.format == OutputFormat::Json {
+        if self.format.is_structured() {
             let envelope: Envelope<Vec<()>> = Envelope::success(vec![])
                 .with_entity_type(self.entity_type)
                 .with_count(0)
                 .with_query(query)
                 .with_message(message);
 
-            println!("{}", envelope.to_json().expect("envelope serialization"));
+            println!(
+                "{}",
+                envelope
+                    .render(self.format)
+                    .expect("envelope serialization")
+            );
         } else {
             println!("{message}");
         }
@@ -281,13 +330,16 @@ pub fn retrieve_symbol(
             },
             Err(_) => {
                 // Invalid symbol_id format
-                if format == OutputFormat::Json {
+                if format.is_structured() {
                     let envelope: Envelope<()> = Envelope::error(
                         ResultCode::InvalidQuery,
                         format!("Invalid symbol_id format: '{id_str}'"),
                     )
                     .with_hint("symbol_id must be a positive integer");
-                    println!("{}", envelope.to_json().expect("envelope serialization"));
+                    println!(
+                        "{}",
+                        envelope.render(format).expect("envelope serialization")
+                    );
                 } else {
                     eprintln!("Invalid symbol_id format: {id_str}");
                 }
@@ -300,13 +352,24 @@ pub fn retrieve_symbol(
     };
 
     if symbols.is_empty() {
+        // Fall back to dependency indexes (e.g. nixpkgs lib functions)
+        if !name.starts_with("symbol_id:") {
+            let external = indexer.find_external_symbols(name, language);
+            if !external.is_empty() {
+                return output_external_symbols(&external, name, format);
+            }
+        }
+
         // Not found
-        if format == OutputFormat::Json {
+        if format.is_structured() {
             let envelope: Envelope<()> = Envelope::not_found(format!("No symbol found for '{name}'"))
                 .with_entity_type(EnvelopeEntityType::Symbol)
                 .with_query(name)
                 .with_hint("Use codanna retrieve search <query> for fuzzy matching, or try semantic_search_with_context");
-            println!("{}", envelope.to_json().expect("envelope serialization"));
+            println!(
+                "{}",
+                envelope.render(format).expect("envelope serialization")
+            );
         } else {
             eprintln!("Not found: '{name}'");
         }
@@ -328,7 +391,7 @@ pub fn retrieve_symbol(
 
     let count = symbols_with_context.len();
 
-    if format == OutputFormat::Json {
+    if format.is_structured() {
         let mut envelope = Envelope::success(symbols_with_context)
             .with_entity_type(EnvelopeEntityType::Symbol)
             .with_count(count)
@@ -342,9 +405,9 @@ pub fn retrieve_symbol(
         }
 
         let json = if let Some(ref f) = fields {
-            envelope.to_json_with_fields(f)
+            envelope.render_with_fields(format, f)
         } else {
-            envelope.to_json()
+            envelope.render(format)
         };
 
         println!("{}", json.expect("envelope serialization"));
@@ -358,6 +421,324 @@ pub fn retrieve_symbol(
     }
 }
 
+/// Print symbols resolved from dependency indexes
+fn output_external_symbols(
+    external: &[ExternalSymbol],
+    name: &str,
+    format: OutputFormat,
+) -> ExitCode {
+    let count = external.len();
+    if format.is_structured() {
+        let envelope = Envelope::success(external)
+            .with_entity_type(EnvelopeEntityType::Symbol)
+            .with_count(count)
+            .with_query(name)
+            .with_message(format!("Found {count} symbol(s) in dependencies"));
+        println!(
+            "{}",
+            envelope.render(format).expect("envelope serialization")
+        );
+    } else {
+        for symbol in external {
+            println!("{symbol}");
+        }
+    }
+    ExitCode::Success
+}
+
+/// Execute retrieve type-definition command
+///
+/// Resolves the symbol, then the symbols defining its type.
+pub fn retrieve_type_definition(
+    indexer: &IndexFacade,
+    name: &str,
+    language: Option<&str>,
+    format: OutputFormat,
+    fields: Option<Vec<String>>,
+) -> ExitCode {
+    let ctx = QueryContext::new(
+        indexer,
+        format,
+        fields,
+        EnvelopeEntityType::Symbol,
+        "type-definition",
+    );
+
+    let symbol = match ctx.resolve_symbol(name, language) {
+        ResolveResult::Found(s) => s,
+        other => return ctx.handle_resolve_error(other, name),
+    };
+
+    match indexer.find_type_definition(symbol.id) {
+        None => ctx.output_empty(name, &format!("No type information for '{name}'")),
+        Some(definition) if definition.targets.is_empty() => ctx.output_empty(
+            name,
+            &format!(
+                "Type '{}' of '{name}' is not defined in the index",
+                definition.type_expr
+            ),
+        ),
+        Some(definition) => ctx.output_success(
+            definition.targets,
+            name,
+            Some("Use symbol_id for precise lookup in subsequent queries"),
+        ),
+    }
+}
+
+/// Execute retrieve call-hierarchy command
+///
+/// Resolves the function, then expands its callers or callees `depth` levels.
+pub fn retrieve_call_hierarchy(
+    indexer: &IndexFacade,
+    function: &str,
+    direction: CallDirection,
+    depth: u32,
+    language: Option<&str>,
+    encoding: PositionEncoding,
+    format: OutputFormat,
+    fields: Option<Vec<String>>,
+) -> ExitCode {
+    let ctx = QueryContext::new(
+        indexer,
+        format,
+        fields,
+        EnvelopeEntityType::CallTree,
+        "call-hierarchy",
+    );
+
+    let symbol = match ctx.resolve_symbol(function, language) {
+        ResolveResult::Found(s) => s,
+        other => return ctx.handle_resolve_error(other, function),
+    };
+
+    match indexer.call_hierarchy(symbol.id, direction, depth, encoding) {
+        Some(tree) if !tree.children.is_empty() => ctx.output_success(
+            vec![tree],
+            function,
+            Some("Use data.symbolId of a node to expand it further"),
+        ),
+        _ => {
+            let message = match direction {
+                CallDirection::Incoming => format!("No functions call '{function}'"),
+                CallDirection::Outgoing => format!("'{function}' calls no indexed functions"),
+            };
+            ctx.output_empty(function, &message)
+        }
+    }
+}
+
+/// Execute retrieve type-hierarchy command
+///
+/// Resolves the type, then expands its supertypes and subtypes `depth` levels.
+pub fn retrieve_type_hierarchy(
+    indexer: &IndexFacade,
+    name: &str,
+    depth: u32,
+    language: Option<&str>,
+    encoding: PositionEncoding,
+    format: OutputFormat,
+    fields: Option<Vec<String>>,
+) -> ExitCode {
+    let ctx = QueryContext::new(
+        indexer,
+        format,
+        fields,
+        EnvelopeEntityType::Symbol,
+        "type-hierarchy",
+    );
+
+    let symbol = match ctx.resolve_symbol(name, language) {
+        ResolveResult::Found(s) => s,
+        other => return ctx.handle_resolve_error(other, name),
+    };
+
+    match indexer.type_hierarchy(symbol.id, depth, encoding) {
+        Some(hierarchy) if !hierarchy.is_empty() => ctx.output_success(
+            vec![hierarchy],
+            name,
+            Some("Use data.symbolId of a node to expand it further"),
+        ),
+        _ => ctx.output_empty(name, &format!("'{name}' has no supertypes or subtypes")),
+    }
+}
+
+/// Execute retrieve history command
+///
+/// Lists the commits that touched a symbol, newest first.
+pub fn retrieve_history(
+    indexer: &IndexFacade,
+    name: &str,
+    limit: Option<usize>,
+    language: Option<&str>,
+    format: OutputFormat,
+    fields: Option<Vec<String>>,
+) -> ExitCode {
+    let ctx = QueryContext::new(
+        indexer,
+        format,
+        fields,
+        EnvelopeEntityType::Symbol,
+        "history",
+    );
+
+    let symbol = match ctx.resolve_symbol(name, language) {
+        ResolveResult::Found(s) => s,
+        other => return ctx.handle_resolve_error(other, name),
+    };
+
+    match indexer.history(&symbol, limit) {
+        Ok(commits) if commits.is_empty() => ctx.output_empty(
+            name,
+            &format!("No commits touch '{name}'; it may not be committed yet"),
+        ),
+        Ok(commits) => ctx.output_success(
+            commits,
+            name,
+            Some("Use `git show <commit>` to see what a commit changed"),
+        ),
+        Err(e) => {
+            if format.is_structured() {
+                let envelope: Envelope<()> =
+                    Envelope::error(ResultCode::InternalError, e.to_string()).with_query(name);
+                println!(
+                    "{}",
+                    envelope.render(format).expect("envelope serialization")
+                );
+            } else {
+                eprintln!("Error: {e}");
+            }
+            ExitCode::GeneralError
+        }
+    }
+}
+
+/// Execute retrieve outline command
+///
+/// Prints the symbols of a file nested by containment.
+pub fn retrieve_outline(
+    indexer: &IndexFacade,
+    path: &str,
+    encoding: PositionEncoding,
+    format: OutputFormat,
+    fields: Option<Vec<String>>,
+) -> ExitCode {
+    let ctx = QueryContext::new(
+        indexer,
+        format,
+        fields,
+        EnvelopeEntityType::Symbol,
+        "outline",
+    );
+
+    match indexer.file_outline(path, encoding) {
+        None => ctx.output_file_not_found(path),
+        Some(outline) if outline.is_empty() => {
+            ctx.output_empty(path, &format!("'{path}' has no indexed symbols"))
+        }
+        Some(outline) => ctx.output_success(
+            outline,
+            path,
+            Some("Use symbolId with other retrieve commands as symbol_id:<id>"),
+        ),
+    }
+}
+
+/// Execute retrieve symbol-at command
+///
+/// Takes the 1-based line and column an editor shows, with the column
+/// counted in `encoding`.
+pub fn retrieve_symbol_at(
+    indexer: &IndexFacade,
+    path: &str,
+    line: u32,
+    column: u32,
+    encoding: PositionEncoding,
+    format: OutputFormat,
+    fields: Option<Vec<String>>,
+) -> ExitCode {
+    let ctx = QueryContext::new(
+        indexer,
+        format,
+        fields,
+        EnvelopeEntityType::Symbol,
+        "symbol-at",
+    );
+    let query = format!("{path}:{line}:{column}");
+
+    if crate::navigation::indexed_file_id(indexer, path).is_none() {
+        return ctx.output_file_not_found(path);
+    }
+    match indexer.symbol_at(
+        path,
+        line.saturating_sub(1),
+        column.saturating_sub(1),
+        encoding,
+    ) {
+        Some(found) => ctx.output_success(
+            vec![found],
+            &query,
+            Some("Use symbolId with other retrieve commands as symbol_id:<id>"),
+        ),
+        None => ctx.output_empty(&query, &format!("No symbol encloses {query}")),
+    }
+}
+
+/// Execute retrieve grep command
+///
+/// Searches indexed sources line by line and reports each match with the
+/// symbols enclosing it.
+pub fn retrieve_grep(
+    indexer: &IndexFacade,
+    pattern: &str,
+    limit: usize,
+    ignore_case: bool,
+    encoding: PositionEncoding,
+    format: OutputFormat,
+    fields: Option<Vec<String>>,
+) -> ExitCode {
+    let ctx = QueryContext::new(
+        indexer,
+        format,
+        fields,
+        EnvelopeEntityType::SearchResult,
+        "grep",
+    );
+
+    let regex = match regex::RegexBuilder::new(pattern)
+        .case_insensitive(ignore_case)
+        .build()
+    {
+        Ok(regex) => regex,
+        Err(e) => {
+            if format.is_structured() {
+                let envelope: Envelope<()> =
+                    Envelope::error(ResultCode::InvalidQuery, format!("Invalid pattern: {e}"))
+                        .with_query(pattern)
+                        .with_hint("Escape regex metacharacters such as ( [ . with a backslash");
+                println!(
+                    "{}",
+                    envelope.render(format).expect("envelope serialization")
+                );
+            } else {
+                eprintln!("Error: Invalid pattern '{pattern}': {e}");
+                eprintln!("Suggestion: Escape regex metacharacters such as ( [ . with a backslash");
+            }
+            return ExitCode::GeneralError;
+        }
+    };
+
+    let matches = indexer.grep(&regex, limit, encoding);
+    if matches.is_empty() {
+        return ctx.output_empty(pattern, &format!("No indexed file matches '{pattern}'"));
+    }
+    ctx.output_success(
+        matches,
+        pattern,
+        Some("Use symbolId with other retrieve commands as symbol_id:<id>"),
+    )
+}
+
 /// Execute retrieve callers command
 ///
 /// Uses QueryContext for symbol resolution with ambiguous handling.
@@ -406,7 +787,7 @@ pub fn retrieve_callers(
 
     let count = callers_with_context.len();
 
-    if format == OutputFormat::Json {
+    if format.is_structured() {
         let mut envelope = Envelope::success(callers_with_context)
             .with_entity_type(EnvelopeEntityType::Callers)
             .with_count(count)
@@ -419,9 +800,9 @@ pub fn retrieve_callers(
         }
 
         let json = if let Some(ref f) = fields {
-            envelope.to_json_with_fields(f)
+            envelope.render_with_fields(format, f)
         } else {
-            envelope.to_json()
+            envelope.render(format)
         };
 
         println!("{}", json.expect("envelope serialization"));
@@ -483,7 +864,7 @@ pub fn retrieve_calls(
 
     let count = calls_with_context.len();
 
-    if format == OutputFormat::Json {
+    if format.is_structured() {
         let mut envelope = Envelope::success(calls_with_context)
             .with_entity_type(EnvelopeEntityType::Calls)
             .with_count(count)
@@ -496,9 +877,9 @@ pub fn retrieve_calls(
         }
 
         let json = if let Some(ref f) = fields {
-            envelope.to_json_with_fields(f)
+            envelope.render_with_fields(format, f)
         } else {
-            envelope.to_json()
+            envelope.render(format)
         };
 
         println!("{}", json.expect("envelope serialization"));
@@ -563,7 +944,7 @@ pub fn retrieve_implementations(
 
     let count = impls_with_context.len();
 
-    if format == OutputFormat::Json {
+    if format.is_structured() {
         let mut envelope = Envelope::success(impls_with_context)
             .with_entity_type(EnvelopeEntityType::Symbol)
             .with_count(count)
@@ -576,9 +957,9 @@ pub fn retrieve_implementations(
         }
 
         let json = if let Some(ref f) = fields {
-            envelope.to_json_with_fields(f)
+            envelope.render_with_fields(format, f)
         } else {
-            envelope.to_json()
+            envelope.render(format)
         };
 
         println!("{}", json.expect("envelope serialization"));
@@ -595,6 +976,9 @@ pub fn retrieve_implementations(
 /// Execute retrieve search command
 ///
 /// Full-text search with optional filters. Uses Envelope for JSON output.
+/// With `context_file`, results near that file rank higher. With
+/// `substring`, the query is matched anywhere in symbol names instead.
+#[allow(clippy::too_many_arguments)]
 pub fn retrieve_search(
     indexer: &IndexFacade,
     query: &str,
@@ -602,34 +986,28 @@ pub fn retrieve_search(
     kind: Option<&str>,
     module: Option<&str>,
     language: Option<&str>,
+    context_file: Option<&str>,
+    substring: bool,
     format: OutputFormat,
     fields: Option<Vec<String>>,
 ) -> ExitCode {
     use crate::symbol::context::ContextIncludes;
 
     // Parse the kind filter if provided
-    let kind_filter = kind.and_then(|k| match k.to_lowercase().as_str() {
-        "function" => Some(crate::SymbolKind::Function),
-        "struct" => Some(crate::SymbolKind::Struct),
-        "trait" => Some(crate::SymbolKind::Trait),
-        "interface" => Some(crate::SymbolKind::Interface),
-        "class" => Some(crate::SymbolKind::Class),
-        "method" => Some(crate::SymbolKind::Method),
-        "field" => Some(crate::SymbolKind::Field),
-        "variable" => Some(crate::SymbolKind::Variable),
-        "constant" => Some(crate::SymbolKind::Constant),
-        "module" => Some(crate::SymbolKind::Module),
-        "typealias" => Some(crate::SymbolKind::TypeAlias),
-        "enum" => Some(crate::SymbolKind::Enum),
-        _ => {
+    let kind_filter = kind.and_then(|k| match k.parse::<crate::SymbolKind>() {
+        Ok(kind) => Some(kind),
+        Err(_) => {
             eprintln!("Warning: Unknown symbol kind '{k}', ignoring filter");
             None
         }
     });
 
-    let search_results = indexer
-        .search(query, limit, kind_filter, module, language)
-        .unwrap_or_default();
+    let search_results = if substring {
+        indexer.search_substring(query, limit, kind_filter, module, language)
+    } else {
+        indexer.search_with_context(query, limit, kind_filter, module, language, context_file)
+    }
+    .unwrap_or_default();
 
     // Transform search results to SymbolContext with relationships
     let results_with_context: Vec<SymbolContext> = search_results
@@ -646,7 +1024,7 @@ pub fn retrieve_search(
 
     let count = results_with_context.len();
 
-    if format == OutputFormat::Json {
+    if format.is_structured() {
         // Build envelope
         let envelope = if results_with_context.is_empty() {
             Envelope::not_found(format!("No results for '{query}'"))
@@ -668,9 +1046,9 @@ pub fn retrieve_search(
         };
 
         let json = if let Some(ref f) = fields {
-            envelope.to_json_with_fields(f)
+            envelope.render_with_fields(format, f)
         } else {
-            envelope.to_json()
+            envelope.render(format)
         };
 
         println!("{}", json.expect("envelope serialization"));
@@ -694,6 +1072,146 @@ pub fn retrieve_search(
     }
 }
 
+/// Execute a saved query from settings by name
+pub fn retrieve_saved_query(
+    indexer: &IndexFacade,
+    name: &str,
+    params: &std::collections::HashMap<String, String>,
+    format: OutputFormat,
+    fields: Option<Vec<String>>,
+) -> ExitCode {
+    use crate::queries::SavedQuery;
+
+    let query = match SavedQuery::resolve(indexer.settings(), name, params) {
+        Ok(query) => query,
+        Err(e) => {
+            if format.is_structured() {
+                let envelope: Envelope =
+                    Envelope::error(ResultCode::InvalidQuery, e.to_string()).with_query(name);
+                println!(
+                    "{}",
+                    envelope.render(format).expect("envelope serialization")
+                );
+            } else {
+                eprintln!("Error: {e}");
+            }
+            return e.exit_code();
+        }
+    };
+
+    let not_found = format!("No results for saved query '{name}'");
+    output_query_results(indexer, &query, name, &not_found, format, fields)
+}
+
+/// Execute an ad-hoc query written in the saved query syntax, e.g.
+/// `parse kind:function lang:rust`
+pub fn retrieve_query(
+    indexer: &IndexFacade,
+    text: &str,
+    format: OutputFormat,
+    fields: Option<Vec<String>>,
+) -> ExitCode {
+    use crate::queries::SavedQuery;
+
+    match SavedQuery::parse(text) {
+        Ok(query) => {
+            let not_found = format!("No results for '{text}'");
+            output_query_results(indexer, &query, text, &not_found, format, fields)
+        }
+        Err(e) => {
+            if format.is_structured() {
+                let envelope: Envelope =
+                    Envelope::error(ResultCode::InvalidQuery, e.to_string()).with_query(text);
+                println!(
+                    "{}",
+                    envelope.render(format).expect("envelope serialization")
+                );
+            } else {
+                eprintln!("Error: {e}");
+            }
+            e.exit_code()
+        }
+    }
+}
+
+fn output_query_results(
+    indexer: &IndexFacade,
+    query: &crate::queries::SavedQuery,
+    name: &str,
+    not_found: &str,
+    format: OutputFormat,
+    fields: Option<Vec<String>>,
+) -> ExitCode {
+    use crate::symbol::context::ContextIncludes;
+
+    let results: Vec<SymbolContext> = query
+        .execute(indexer)
+        .into_iter()
+        .filter_map(|symbol| {
+            indexer.get_symbol_context(
+                symbol.id,
+                ContextIncludes::IMPLEMENTATIONS
+                    | ContextIncludes::DEFINITIONS
+                    | ContextIncludes::CALLERS,
+            )
+        })
+        .collect();
+    let count = results.len();
+
+    if format.is_structured() {
+        let envelope = if results.is_empty() {
+            Envelope::not_found(not_found)
+                .with_entity_type(EnvelopeEntityType::SearchResult)
+                .with_query(name)
+        } else {
+            Envelope::success(results)
+                .with_entity_type(EnvelopeEntityType::SearchResult)
+                .with_count(count)
+                .with_query(name)
+                .with_message(format!("Found {count} result(s)"))
+        };
+
+        let json = if let Some(ref f) = fields {
+            envelope.render_with_fields(format, f)
+        } else {
+            envelope.render(format)
+        };
+        println!("{}", json.expect("envelope serialization"));
+    } else if results.is_empty() {
+        eprintln!("{not_found}");
+    } else {
+        for ctx in &results {
+            println!("{ctx}");
+        }
+    }
+
+    if count == 0 {
+        ExitCode::NotFound
+    } else {
+        ExitCode::Success
+    }
+}
+
+/// List saved queries defined in settings
+pub fn list_saved_queries(settings: &crate::Settings, format: OutputFormat) -> ExitCode {
+    if format.is_structured() {
+        let envelope = Envelope::success(&settings.queries)
+            .with_count(settings.queries.len())
+            .with_message(format!("{} saved queries", settings.queries.len()));
+        println!(
+            "{}",
+            envelope.render(format).expect("envelope serialization")
+        );
+    } else if settings.queries.is_empty() {
+        eprintln!("No saved queries. Define them under [queries] in .codanna/settings.toml");
+    } else {
+        for (name, query) in &settings.queries {
+            println!("{name:<24} {query}");
+        }
+    }
+    ExitCode::Success
+}
+
 /// Execute retrieve impact command
 // DEPRECATED: This function has been disabled.
 // Use MCP semantic_search_with_context or slash commands instead.
@@ -709,7 +1227,7 @@ pub fn retrieve_impact(
     format: OutputFormat,
 ) -> ExitCode {
     let mut output = OutputManager::new(format);
-    let symbols = indexer.find_symbols_by_name(symbol_name, None);
+    let symbols = indexer.find_symbol_refs_by_name(symbol_name, None);
 
     if symbols.is_empty() {
         let unified = UnifiedOutput {
@@ -875,9 +1393,19 @@ pub fn retrieve_describe(
         context.relationships.used_by = Some(used_by);
     }
 
+    /// The context with the hover payload shown by editors and MCP
+    #[derive(Serialize)]
+    struct Described {
+        #[serde(flatten)]
+        context: SymbolContext,
+        hover: Hover,
+    }
+
+    let hover = hover(indexer, &symbol, PositionEncoding::Byte);
+
     // Output
-    if format == OutputFormat::Json {
-        let mut envelope = Envelope::success(context)
+    if format.is_structured() {
+        let mut envelope = Envelope::success(Described { context, hover })
             .with_entity_type(EnvelopeEntityType::Symbol)
             .with_count(1)
             .with_query(symbol_name)
@@ -889,16 +1417,20 @@ pub fn retrieve_describe(
         }
 
         let json = if let Some(ref f) = fields {
-            envelope.to_json_with_fields(f)
+            envelope.render_with_fields(format, f)
         } else {
-            envelope.to_json()
+            envelope.render(format)
         };
 
         println!("{}", json.expect("envelope serialization"));
         ExitCode::Success
     } else {
-        // Text output
-        println!("{context}");
+        // Text output: the hover content, then the relationships
+        println!("{hover}");
+        let relationships = context.format_relationships("");
+        if !relationships.is_empty() {
+            println!("\n{}", relationships.trim_end());
+        }
         ExitCode::Success
     }
 }
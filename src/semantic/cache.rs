@@ -0,0 +1,343 @@
+//! Persistent cache of embeddings keyed by the text they were computed from
+//!
+//! Re-indexing a file re-embeds every doc comment in it, and `index --force`
+//! re-embeds the whole codebase, although most comments did not change. The
+//! cache maps the SHA-256 of a comment to its embedding, one file per model
+//! under `<index>/embeddings/`, so only new and edited comments reach the
+//! model. Entries remember when they were last used; saving drops those
+//! unused for longer than the configured age, then the least recently used
+//! ones beyond the entry limit.
+
+use super::SemanticSearchError;
+use crate::config::SemanticSearchConfig;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// Directory of the cache files, below the index directory
+pub const CACHE_DIR: &str = "embeddings";
+
+/// Start of every cache file, followed by the format version
+const MAGIC: &[u8; 8] = b"CDNAEMB1";
+
+/// SHA-256 of the embedded text
+pub type ContentHash = [u8; 32];
+
+/// Bounds applied when the cache is saved
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheLimits {
+    /// Most entries kept; 0 disables the cache
+    pub max_entries: usize,
+    /// Entries unused for longer are dropped
+    pub max_age: Option<Duration>,
+}
+
+impl CacheLimits {
+    pub fn from_config(config: &SemanticSearchConfig) -> Self {
+        Self {
+            max_entries: config.cache_max_entries,
+            max_age: (config.cache_max_age_days > 0)
+                .then(|| Duration::from_secs(config.cache_max_age_days * 24 * 60 * 60)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    embedding: Vec<f32>,
+    /// Unix time of the last lookup or insert
+    last_used: u64,
+}
+
+/// Embeddings of one model, keyed by [`ContentHash`]
+#[derive(Debug)]
+pub struct EmbeddingCache {
+    path: PathBuf,
+    dimensions: usize,
+    limits: CacheLimits,
+    entries: Mutex<HashMap<ContentHash, Entry>>,
+    /// Whether entries were added or used since the last load or save
+    dirty: AtomicBool,
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+}
+
+impl EmbeddingCache {
+    /// Open the cache of `model` in `dir`, empty when there is none yet.
+    ///
+    /// A file that cannot be read, or that holds embeddings of another
+    /// size, is ignored and replaced on the next save.
+    pub fn open(dir: &Path, model: &str, dimensions: usize, limits: CacheLimits) -> Self {
+        let file_name: String = model
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        let path = dir.join(format!("{file_name}.cache"));
+        let entries = match std::fs::read(&path) {
+            Ok(bytes) => decode(&bytes, dimensions).unwrap_or_else(|| {
+                tracing::warn!(
+                    target: "semantic",
+                    "ignoring unreadable embedding cache {}",
+                    path.display()
+                );
+                HashMap::new()
+            }),
+            Err(_) => HashMap::new(),
+        };
+        tracing::debug!(
+            target: "semantic",
+            "embedding cache {}: {} entries",
+            path.display(),
+            entries.len()
+        );
+
+        Self {
+            path,
+            dimensions,
+            limits,
+            entries: Mutex::new(entries),
+            dirty: AtomicBool::new(false),
+            hits: AtomicUsize::new(0),
+            misses: AtomicUsize::new(0),
+        }
+    }
+
+    /// Key of `text`
+    pub fn key(text: &str) -> ContentHash {
+        Sha256::digest(text.as_bytes()).into()
+    }
+
+    /// Whether lookups and inserts do anything
+    pub fn is_enabled(&self) -> bool {
+        self.limits.max_entries > 0
+    }
+
+    /// The embedding of the text with hash `key`, if cached
+    pub fn get(&self, key: &ContentHash) -> Option<Vec<f32>> {
+        if !self.is_enabled() {
+            return None;
+        }
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get_mut(key) {
+            Some(entry) => {
+                entry.last_used = crate::indexing::get_utc_timestamp();
+                self.dirty.store(true, Ordering::Relaxed);
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                Some(entry.embedding.clone())
+            }
+            None => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+
+    /// Remember the embedding of the text with hash `key`
+    pub fn insert(&self, key: ContentHash, embedding: Vec<f32>) {
+        self.put(key, embedding, crate::indexing::get_utc_timestamp());
+    }
+
+    fn put(&self, key: ContentHash, embedding: Vec<f32>, last_used: u64) {
+        if !self.is_enabled() || embedding.len() != self.dimensions {
+            return;
+        }
+        self.entries.lock().unwrap().insert(
+            key,
+            Entry {
+                embedding,
+                last_used,
+            },
+        );
+        self.dirty.store(true, Ordering::Relaxed);
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Lookups answered from the cache and lookups that were not
+    pub fn hit_rate(&self) -> (usize, usize) {
+        (
+            self.hits.load(Ordering::Relaxed),
+            self.misses.load(Ordering::Relaxed),
+        )
+    }
+
+    /// Apply the limits and write the cache, if entries were added or used
+    /// since it was loaded or last saved. Returns the number of entries kept.
+    pub fn save(&self) -> Result<usize, SemanticSearchError> {
+        if !self.is_enabled() || !self.dirty.swap(false, Ordering::Relaxed) {
+            return Ok(self.len());
+        }
+        let storage_error = |action: &str, e: std::io::Error| SemanticSearchError::StorageError {
+            message: format!(
+                "Failed to {action} embedding cache {}: {e}",
+                self.path.display()
+            ),
+            suggestion: "Check disk space and permissions; the cache can be deleted safely"
+                .to_string(),
+        };
+
+        let bytes = {
+            let mut entries = self.entries.lock().unwrap();
+            evict(
+                &mut entries,
+                self.limits,
+                crate::indexing::get_utc_timestamp(),
+            );
+            encode(&entries, self.dimensions)
+        };
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| storage_error("create", e))?;
+        }
+        // Written aside and renamed, so a crash never leaves half a cache
+        let partial = self.path.with_extension("cache.partial");
+        std::fs::write(&partial, bytes).map_err(|e| storage_error("write", e))?;
+        std::fs::rename(&partial, &self.path).map_err(|e| storage_error("replace", e))?;
+        Ok(self.len())
+    }
+}
+
+/// Drop entries older than the age limit, then the least recently used
+/// ones beyond the entry limit
+fn evict(entries: &mut HashMap<ContentHash, Entry>, limits: CacheLimits, now: u64) {
+    if let Some(max_age) = limits.max_age {
+        entries.retain(|_, entry| now.saturating_sub(entry.last_used) <= max_age.as_secs());
+    }
+    let excess = entries.len().saturating_sub(limits.max_entries);
+    if excess == 0 {
+        return;
+    }
+    let mut by_age: Vec<(u64, ContentHash)> = entries
+        .iter()
+        .map(|(key, entry)| (entry.last_used, *key))
+        .collect();
+    by_age.sort_unstable();
+    for (_, key) in by_age.into_iter().take(excess) {
+        entries.remove(&key);
+    }
+}
+
+fn encode(entries: &HashMap<ContentHash, Entry>, dimensions: usize) -> Vec<u8> {
+    let entry_size = 32 + 8 + dimensions * 4;
+    let mut bytes = Vec::with_capacity(MAGIC.len() + 12 + entries.len() * entry_size);
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&(dimensions as u32).to_le_bytes());
+    bytes.extend_from_slice(&(entries.len() as u64).to_le_bytes());
+    for (key, entry) in entries {
+        bytes.extend_from_slice(key);
+        bytes.extend_from_slice(&entry.last_used.to_le_bytes());
+        for value in &entry.embedding {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+    bytes
+}
+
+/// Entries of a cache file, `None` unless it is complete and holds
+/// embeddings of `dimensions` values
+fn decode(bytes: &[u8], dimensions: usize) -> Option<HashMap<ContentHash, Entry>> {
+    let rest = bytes.strip_prefix(MAGIC)?;
+    let (stored_dimensions, rest) = rest.split_first_chunk::<4>()?;
+    if u32::from_le_bytes(*stored_dimensions) as usize != dimensions {
+        return None;
+    }
+    let (count, mut rest) = rest.split_first_chunk::<8>()?;
+    let count = u64::from_le_bytes(*count) as usize;
+    let entry_size = 32 + 8 + dimensions * 4;
+    if rest.len() != count.checked_mul(entry_size)? {
+        return None;
+    }
+
+    let mut entries = HashMap::with_capacity(count);
+    while let Some((key, tail)) = rest.split_first_chunk::<32>() {
+        let (last_used, tail) = tail.split_first_chunk::<8>()?;
+        let (values, tail) = tail.split_at(dimensions * 4);
+        let embedding = values
+            .chunks_exact(4)
+            .map(|value| f32::from_le_bytes([value[0], value[1], value[2], value[3]]))
+            .collect();
+        entries.insert(
+            *key,
+            Entry {
+                embedding,
+                last_used: u64::from_le_bytes(*last_used),
+            },
+        );
+        rest = tail;
+    }
+    Some(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const LIMITS: CacheLimits = CacheLimits {
+        max_entries: 2,
+        max_age: Some(Duration::from_secs(60 * 60)),
+    };
+
+    #[test]
+    fn test_saved_cache_keeps_recent_entries_within_limits() {
+        let temp_dir = TempDir::new().unwrap();
+        let now = crate::indexing::get_utc_timestamp();
+        let cache = EmbeddingCache::open(temp_dir.path(), "AllMiniLML6V2", 3, LIMITS);
+        let key = EmbeddingCache::key;
+        cache.put(key("Parse a file"), vec![1.0, 0.0, 0.0], now - 10);
+        cache.put(key("Open a socket"), vec![0.0, 1.0, 0.0], now - 20);
+        cache.put(key("Hash a string"), vec![0.0, 0.0, 1.0], now - 30);
+        cache.put(key("Stale"), vec![0.5, 0.5, 0.0], now - 2 * 60 * 60);
+        // Wrong size for this model
+        cache.insert(key("Too long"), vec![0.0; 4]);
+        assert_eq!(cache.len(), 4);
+        assert_eq!(cache.save().unwrap(), 2);
+
+        let reopened = EmbeddingCache::open(temp_dir.path(), "AllMiniLML6V2", 3, LIMITS);
+        assert_eq!(
+            reopened.get(&key("Parse a file")),
+            Some(vec![1.0, 0.0, 0.0])
+        );
+        assert_eq!(
+            reopened.get(&key("Open a socket")),
+            Some(vec![0.0, 1.0, 0.0])
+        );
+        assert_eq!(reopened.get(&key("Hash a string")), None);
+        assert_eq!(reopened.get(&key("Stale")), None);
+        assert_eq!(reopened.hit_rate(), (2, 2));
+
+        // Another model has its own file, and other sizes are not read back
+        assert!(EmbeddingCache::open(temp_dir.path(), "MultilingualE5Small", 3, LIMITS).is_empty());
+        assert!(EmbeddingCache::open(temp_dir.path(), "AllMiniLML6V2", 4, LIMITS).is_empty());
+    }
+
+    #[test]
+    fn test_truncated_cache_file_is_ignored() {
+        let mut entries = HashMap::new();
+        entries.insert(
+            EmbeddingCache::key("Parse a file"),
+            Entry {
+                embedding: vec![1.0, 2.0],
+                last_used: 7,
+            },
+        );
+        let bytes = encode(&entries, 2);
+        assert_eq!(decode(&bytes, 2).unwrap().len(), 1);
+        assert!(decode(&bytes[..bytes.len() - 1], 2).is_none());
+        assert!(decode(b"CDNAEMB0", 2).is_none());
+    }
+}
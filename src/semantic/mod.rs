@@ -3,13 +3,21 @@
 //! This module provides a simple API for semantic search on documentation,
 //! designed to integrate with the existing indexing system.
 
+mod cache;
 mod metadata;
 mod pool;
+mod provider;
 mod simple;
 mod storage;
 
+pub use cache::{CACHE_DIR, CacheLimits, EmbeddingCache};
 pub use metadata::SemanticMetadata;
 pub use pool::EmbeddingPool;
+pub(crate) use provider::HttpClient;
+pub use provider::{
+    EmbeddingProvider, LocalProvider, OllamaProvider, OpenAiProvider, create_provider,
+    provider_for_model,
+};
 pub use simple::{SemanticSearchError, SimpleSemanticSearch};
 pub use storage::SemanticVectorStorage;
 
@@ -1,24 +1,27 @@
 //! Embedding model pool for parallel embedding generation
 //!
-//! Provides multiple TextEmbedding instances that can be used concurrently
-//! by different threads, enabling parallel embedding generation.
+//! Provides multiple embedding provider instances that can be used
+//! concurrently by different threads, enabling parallel embedding generation.
+//! For remote providers each instance is one request in flight.
 
 use crate::SymbolId;
+use crate::config::SemanticSearchConfig;
 use crossbeam_channel::{Receiver, Sender, bounded};
-use fastembed::{EmbeddingModel, InitOptions, TextEmbedding};
+use fastembed::EmbeddingModel;
 use std::sync::atomic::{AtomicUsize, Ordering};
 
-use super::SemanticSearchError;
+use super::provider::{EmbeddingProvider, LocalProvider, create_provider};
+use super::{EmbeddingCache, SemanticSearchError};
 
 /// Model instance with an ID for tracking
 struct ModelInstance {
-    model: TextEmbedding,
+    model: Box<dyn EmbeddingProvider>,
     id: usize,
 }
 
-/// Pool of TextEmbedding models for parallel embedding generation.
+/// Pool of embedding providers for parallel embedding generation.
 ///
-/// Each model instance is expensive (~86MB), but having multiple allows
+/// Each local model instance is expensive (~86MB), but having multiple allows
 /// true parallel embedding generation with rayon.
 pub struct EmbeddingPool {
     /// Channel to acquire models from the pool
@@ -32,10 +35,12 @@ pub struct EmbeddingPool {
     model_name: String,
     /// Usage counters per model instance (for tracing)
     usage_counters: Vec<AtomicUsize>,
+    /// Embeddings of texts seen before, reused instead of recomputed
+    cache: Option<EmbeddingCache>,
 }
 
 impl EmbeddingPool {
-    /// Create a new embedding pool with the specified number of model instances.
+    /// Create a new embedding pool with the specified number of local model instances.
     ///
     /// # Arguments
     /// * `pool_size` - Number of TextEmbedding instances to create
@@ -44,18 +49,33 @@ impl EmbeddingPool {
     /// # Note
     /// Each model instance uses ~86MB of memory for AllMiniLML6V2.
     pub fn new(pool_size: usize, model: EmbeddingModel) -> Result<Self, SemanticSearchError> {
+        Self::build(pool_size, |i| {
+            // Only show progress for first model
+            Ok(Box::new(LocalProvider::new(model.clone(), i == 0)?))
+        })
+    }
+
+    /// Create a pool of `embedding_threads` instances of the configured provider.
+    pub fn from_config(config: &SemanticSearchConfig) -> Result<Self, SemanticSearchError> {
+        Self::build(config.embedding_threads, |i| {
+            create_provider(config, i == 0)
+        })
+    }
+
+    fn build(
+        pool_size: usize,
+        create: impl Fn(usize) -> Result<Box<dyn EmbeddingProvider>, SemanticSearchError>,
+    ) -> Result<Self, SemanticSearchError> {
         let pool_size = pool_size.max(1);
         let (sender, receiver) = bounded(pool_size);
 
-        let cache_dir = crate::init::models_dir();
-        let model_name = crate::vector::model_to_string(&model);
-
         tracing::info!(
             target: "semantic",
-            "Initializing embedding pool: {pool_size} instances ({model_name})"
+            "Initializing embedding pool: {pool_size} instances"
         );
 
         let mut dimensions = 0;
+        let mut model_name = String::new();
 
         // Create usage counters for each model
         let usage_counters: Vec<AtomicUsize> =
@@ -63,31 +83,15 @@ impl EmbeddingPool {
 
         // Create pool_size model instances
         for i in 0..pool_size {
-            let mut text_model = TextEmbedding::try_new(
-                InitOptions::new(model.clone())
-                    .with_cache_dir(cache_dir.clone())
-                    .with_show_download_progress(i == 0), // Only show progress for first model
-            )
-            .map_err(|e| {
-                SemanticSearchError::ModelInitError(format!(
-                    "Failed to initialize model instance {}: {}",
-                    i + 1,
-                    e
-                ))
-            })?;
+            let model = create(i)?;
 
             // Get dimensions from first model
             if i == 0 {
-                let test_embedding = text_model
-                    .embed(vec!["test"], None)
-                    .map_err(|e| SemanticSearchError::EmbeddingError(e.to_string()))?;
-                dimensions = test_embedding.into_iter().next().unwrap().len();
+                dimensions = model.probe_dimensions()?;
+                model_name = model.model_name().to_string();
             }
 
-            let instance = ModelInstance {
-                model: text_model,
-                id: i,
-            };
+            let instance = ModelInstance { model, id: i };
             sender
                 .send(instance)
                 .expect("Pool channel should not be closed");
@@ -95,7 +99,7 @@ impl EmbeddingPool {
 
         tracing::info!(
             target: "semantic",
-            "Embedding pool ready: {pool_size} instances, {dimensions} dimensions"
+            "Embedding pool ready: {pool_size} instances ({model_name}), {dimensions} dimensions"
         );
 
         Ok(Self {
@@ -105,9 +109,29 @@ impl EmbeddingPool {
             dimensions,
             model_name,
             usage_counters,
+            cache: None,
         })
     }
 
+    /// Reuse embeddings from `cache` and remember new ones in it.
+    pub fn with_cache(mut self, cache: EmbeddingCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Write the embedding cache, if there is one.
+    pub fn save_cache(&self) -> Result<(), SemanticSearchError> {
+        if let Some(cache) = &self.cache {
+            let (hits, misses) = cache.hit_rate();
+            let kept = cache.save()?;
+            tracing::debug!(
+                target: "semantic",
+                "embedding cache saved: {kept} entries ({hits} hits, {misses} misses)"
+            );
+        }
+        Ok(())
+    }
+
     /// Create a pool with default model (AllMiniLML6V2)
     pub fn with_size(pool_size: usize) -> Result<Self, SemanticSearchError> {
         Self::new(pool_size, EmbeddingModel::AllMiniLML6V2)
@@ -156,14 +180,11 @@ impl EmbeddingPool {
             ));
         }
 
-        let mut instance = self.acquire();
-        let result = instance
-            .model
-            .embed(vec![text], None)
-            .map_err(|e| SemanticSearchError::EmbeddingError(e.to_string()));
+        let instance = self.acquire();
+        let result = instance.model.embed_one(text);
         self.release(instance);
 
-        result.map(|mut v| v.remove(0))
+        result
     }
 
     /// Log usage statistics for all model instances.
@@ -210,12 +231,38 @@ impl EmbeddingPool {
             .filter(|(_, doc, _)| !doc.trim().is_empty())
             .collect();
 
+        // Unchanged docs keep the embedding computed last time
+        let mut cached = Vec::new();
+        let valid_items: Vec<_> = match &self.cache {
+            Some(cache) if cache.is_enabled() => valid_items
+                .into_iter()
+                .filter(
+                    |(symbol_id, doc, language)| match cache.get(&EmbeddingCache::key(doc)) {
+                        Some(embedding) => {
+                            cached.push((*symbol_id, embedding, (*language).to_string()));
+                            false
+                        }
+                        None => true,
+                    },
+                )
+                .collect(),
+            _ => valid_items,
+        };
+        if !cached.is_empty() {
+            tracing::debug!(
+                target: "semantic",
+                "{} embeddings from cache, {} to compute",
+                cached.len(),
+                valid_items.len()
+            );
+        }
+
         if valid_items.is_empty() {
-            return Vec::new();
+            return cached;
         }
 
         // Process in batches of 64, parallelized across available model instances
-        let results: Vec<_> = valid_items
+        let mut results: Vec<_> = valid_items
             .chunks(BATCH_SIZE)
             .par_bridge()
             .flat_map(|batch| {
@@ -223,8 +270,8 @@ impl EmbeddingPool {
                 let texts: Vec<&str> = batch.iter().map(|(_, doc, _)| *doc).collect();
 
                 // Acquire model, embed entire batch, release model
-                let mut instance = self.acquire();
-                let embeddings_result = instance.model.embed(texts.clone(), None);
+                let instance = self.acquire();
+                let embeddings_result = instance.model.embed(&texts);
                 self.release(instance);
 
                 // Process results
@@ -232,8 +279,11 @@ impl EmbeddingPool {
                     Ok(embeddings) => {
                         let mut results = Vec::with_capacity(batch.len());
                         for (item, embedding) in batch.iter().zip(embeddings.into_iter()) {
-                            let (symbol_id, _, language) = *item;
+                            let (symbol_id, doc, language) = *item;
                             if embedding.len() == self.dimensions {
+                                if let Some(cache) = &self.cache {
+                                    cache.insert(EmbeddingCache::key(doc), embedding.clone());
+                                }
                                 results.push((*symbol_id, embedding, (*language).to_string()));
                             } else {
                                 tracing::warn!(
@@ -262,6 +312,7 @@ impl EmbeddingPool {
         // Log usage stats after parallel embedding
         self.log_usage_stats();
 
+        results.extend(cached);
         results
     }
 }
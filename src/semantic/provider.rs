@@ -0,0 +1,328 @@
+//! Backends that compute embeddings
+//!
+//! Semantic search only needs text turned into vectors, so where that
+//! happens is up to `[semantic_search] provider`:
+//!
+//! - `local` runs a bundled fastembed ONNX model in process. Once the model
+//!   is in the models directory it needs no network, which keeps semantic
+//!   search usable in air-gapped environments.
+//! - `openai` posts to an OpenAI-compatible `/embeddings` endpoint: OpenAI
+//!   itself, or a self-hosted server such as vLLM or LocalAI.
+//! - `ollama` posts to the `/api/embed` endpoint of an Ollama server.
+//!
+//! Each provider names its model distinctly (`openai:text-embedding-3-small`),
+//! and that name is what the semantic metadata records, so embeddings from
+//! different backends are never compared with each other.
+
+use super::SemanticSearchError;
+use crate::config::{EmbeddingProviderKind, SemanticSearchConfig};
+use fastembed::{EmbeddingModel, InitOptions, TextEmbedding};
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Longest wait for one request to a remote model
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+const OPENAI_ENDPOINT: &str = "https://api.openai.com/v1";
+const OLLAMA_ENDPOINT: &str = "http://localhost:11434";
+
+/// Something that turns texts into embeddings of a fixed size
+pub trait EmbeddingProvider: Send + Sync {
+    /// Name recorded in the semantic metadata
+    fn model_name(&self) -> &str;
+
+    /// One embedding per text, in order
+    fn embed(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, SemanticSearchError>;
+
+    /// Embedding of a single text
+    fn embed_one(&self, text: &str) -> Result<Vec<f32>, SemanticSearchError> {
+        self.embed(&[text])?.into_iter().next().ok_or_else(|| {
+            SemanticSearchError::EmbeddingError("provider returned no embedding".to_string())
+        })
+    }
+
+    /// Size of the embeddings, found by embedding a probe text
+    fn probe_dimensions(&self) -> Result<usize, SemanticSearchError> {
+        Ok(self.embed_one("test")?.len())
+    }
+}
+
+/// The provider selected by `config`.
+///
+/// `show_progress` shows the download of a local model that is not cached
+/// yet. Remote providers connect on first use.
+pub fn create_provider(
+    config: &SemanticSearchConfig,
+    show_progress: bool,
+) -> Result<Box<dyn EmbeddingProvider>, SemanticSearchError> {
+    open(config.provider, &config.model, config, show_progress)
+}
+
+/// The provider that computed embeddings recorded as `model_name`, reached
+/// through the endpoint and key of `config`.
+pub fn provider_for_model(
+    model_name: &str,
+    config: &SemanticSearchConfig,
+) -> Result<Box<dyn EmbeddingProvider>, SemanticSearchError> {
+    match model_name.split_once(':') {
+        Some(("openai", model)) => open(EmbeddingProviderKind::OpenAi, model, config, false),
+        Some(("ollama", model)) => open(EmbeddingProviderKind::Ollama, model, config, false),
+        _ => open(EmbeddingProviderKind::Local, model_name, config, false),
+    }
+}
+
+fn open(
+    kind: EmbeddingProviderKind,
+    model: &str,
+    config: &SemanticSearchConfig,
+    show_progress: bool,
+) -> Result<Box<dyn EmbeddingProvider>, SemanticSearchError> {
+    let endpoint = |default: &str| {
+        config
+            .endpoint
+            .as_deref()
+            .unwrap_or(default)
+            .trim_end_matches('/')
+            .to_string()
+    };
+    Ok(match kind {
+        EmbeddingProviderKind::Local => {
+            let model = crate::vector::parse_embedding_model(model).map_err(|e| {
+                SemanticSearchError::ModelInitError(format!("Invalid model name: {e}"))
+            })?;
+            Box::new(LocalProvider::new(model, show_progress)?)
+        }
+        EmbeddingProviderKind::OpenAi => {
+            // A missing key is fine for self-hosted servers that want none
+            let api_key = std::env::var(&config.api_key_env).ok();
+            if api_key.is_none() && config.endpoint.is_none() {
+                return Err(SemanticSearchError::ModelInitError(format!(
+                    "the openai provider needs an API key in ${}",
+                    config.api_key_env
+                )));
+            }
+            Box::new(OpenAiProvider {
+                client: HttpClient::new(),
+                endpoint: endpoint(OPENAI_ENDPOINT),
+                model: model.to_string(),
+                name: format!("openai:{model}"),
+                api_key,
+            })
+        }
+        EmbeddingProviderKind::Ollama => Box::new(OllamaProvider {
+            client: HttpClient::new(),
+            endpoint: endpoint(OLLAMA_ENDPOINT),
+            model: model.to_string(),
+            name: format!("ollama:{model}"),
+        }),
+    })
+}
+
+/// A fastembed model run in this process
+pub struct LocalProvider {
+    model: Mutex<TextEmbedding>,
+    name: String,
+}
+
+impl LocalProvider {
+    pub fn new(model: EmbeddingModel, show_progress: bool) -> Result<Self, SemanticSearchError> {
+        let name = crate::vector::model_to_string(&model);
+        let text_model = TextEmbedding::try_new(
+            InitOptions::new(model)
+                .with_cache_dir(crate::init::models_dir())
+                .with_show_download_progress(show_progress),
+        )
+        .map_err(|e| {
+            SemanticSearchError::ModelInitError(format!("Failed to initialize model '{name}': {e}"))
+        })?;
+        Ok(Self {
+            model: Mutex::new(text_model),
+            name,
+        })
+    }
+}
+
+impl EmbeddingProvider for LocalProvider {
+    fn model_name(&self) -> &str {
+        &self.name
+    }
+
+    fn embed(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, SemanticSearchError> {
+        self.model
+            .lock()
+            .unwrap()
+            .embed(texts, None)
+            .map_err(|e| SemanticSearchError::EmbeddingError(e.to_string()))
+    }
+}
+
+/// An endpoint speaking the OpenAI embeddings API
+pub struct OpenAiProvider {
+    client: HttpClient,
+    endpoint: String,
+    model: String,
+    name: String,
+    api_key: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct OpenAiResponse {
+    data: Vec<OpenAiEmbedding>,
+}
+
+#[derive(Deserialize)]
+struct OpenAiEmbedding {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+impl EmbeddingProvider for OpenAiProvider {
+    fn model_name(&self) -> &str {
+        &self.name
+    }
+
+    fn embed(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, SemanticSearchError> {
+        let url = format!("{}/embeddings", self.endpoint);
+        let body = json!({ "model": self.model, "input": texts });
+        let mut response: OpenAiResponse = self
+            .client
+            .post(&url, self.api_key.as_deref(), &body)
+            .map_err(SemanticSearchError::EmbeddingError)?;
+        // The API may answer out of order; `index` says which input is which
+        response.data.sort_by_key(|item| item.index);
+        expect_count(
+            texts.len(),
+            response
+                .data
+                .into_iter()
+                .map(|item| item.embedding)
+                .collect(),
+        )
+    }
+}
+
+/// An Ollama server
+pub struct OllamaProvider {
+    client: HttpClient,
+    endpoint: String,
+    model: String,
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct OllamaResponse {
+    embeddings: Vec<Vec<f32>>,
+}
+
+impl EmbeddingProvider for OllamaProvider {
+    fn model_name(&self) -> &str {
+        &self.name
+    }
+
+    fn embed(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, SemanticSearchError> {
+        let url = format!("{}/api/embed", self.endpoint);
+        let body = json!({ "model": self.model, "input": texts });
+        let response: OllamaResponse = self
+            .client
+            .post(&url, None, &body)
+            .map_err(SemanticSearchError::EmbeddingError)?;
+        expect_count(texts.len(), response.embeddings)
+    }
+}
+
+fn expect_count(
+    expected: usize,
+    embeddings: Vec<Vec<f32>>,
+) -> Result<Vec<Vec<f32>>, SemanticSearchError> {
+    if embeddings.len() != expected {
+        return Err(SemanticSearchError::EmbeddingError(format!(
+            "asked for {expected} embeddings, got {}",
+            embeddings.len()
+        )));
+    }
+    Ok(embeddings)
+}
+
+/// Blocking JSON client for remote model APIs
+pub(crate) struct HttpClient {
+    agent: ureq::Agent,
+}
+
+impl HttpClient {
+    pub(crate) fn new() -> Self {
+        let config = ureq::Agent::config_builder()
+            .timeout_global(Some(REQUEST_TIMEOUT))
+            .build();
+        Self {
+            agent: ureq::Agent::new_with_config(config),
+        }
+    }
+
+    pub(crate) fn post<T: serde::de::DeserializeOwned>(
+        &self,
+        url: &str,
+        api_key: Option<&str>,
+        body: &serde_json::Value,
+    ) -> Result<T, String> {
+        let failed = |reason: String| format!("request to {url} failed: {reason}");
+        let mut request = self
+            .agent
+            .post(url)
+            .header("Content-Type", "application/json");
+        if let Some(key) = api_key {
+            request = request.header("Authorization", format!("Bearer {key}"));
+        }
+        let mut response = request
+            .send(body.to_string())
+            .map_err(|e| failed(e.to_string()))?;
+        let text = response
+            .body_mut()
+            .read_to_string()
+            .map_err(|e| failed(e.to_string()))?;
+        serde_json::from_str(&text).map_err(|e| failed(format!("unexpected response: {e}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recorded_model_names_pick_the_provider() {
+        let config = SemanticSearchConfig {
+            endpoint: Some("http://embeddings.internal:8080/v1/".to_string()),
+            ..SemanticSearchConfig::default()
+        };
+        let openai = provider_for_model("openai:text-embedding-3-small", &config).unwrap();
+        assert_eq!(openai.model_name(), "openai:text-embedding-3-small");
+        let ollama = provider_for_model("ollama:nomic-embed-text", &config).unwrap();
+        assert_eq!(ollama.model_name(), "ollama:nomic-embed-text");
+
+        // The configured kind decides how `model` is read
+        let config = SemanticSearchConfig {
+            provider: EmbeddingProviderKind::Ollama,
+            model: "nomic-embed-text".to_string(),
+            ..SemanticSearchConfig::default()
+        };
+        let created = create_provider(&config, false).unwrap();
+        assert_eq!(created.model_name(), "ollama:nomic-embed-text");
+    }
+
+    #[test]
+    fn test_openai_without_key_or_endpoint_is_refused() {
+        let config = SemanticSearchConfig {
+            provider: EmbeddingProviderKind::OpenAi,
+            model: "text-embedding-3-small".to_string(),
+            api_key_env: "CODANNA_TEST_UNSET_EMBEDDING_KEY".to_string(),
+            ..SemanticSearchConfig::default()
+        };
+        let error = create_provider(&config, false).err().unwrap();
+        assert!(
+            error
+                .to_string()
+                .contains("CODANNA_TEST_UNSET_EMBEDDING_KEY")
+        );
+    }
+}
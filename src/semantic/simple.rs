@@ -1,10 +1,11 @@
 //! Simple semantic search implementation for documentation comments
 
+use super::provider::{EmbeddingProvider, LocalProvider, create_provider, provider_for_model};
 use crate::SymbolId;
-use fastembed::{EmbeddingModel, InitOptions, TextEmbedding};
+use crate::config::{EmbeddingProviderKind, SemanticSearchConfig};
+use fastembed::EmbeddingModel;
 use std::collections::HashMap;
 use std::path::Path;
-use std::sync::Mutex;
 
 /// Error type for semantic search operations
 #[derive(Debug, thiserror::Error)]
@@ -45,8 +46,8 @@ pub struct SimpleSemanticSearch {
     /// Language mapping for each symbol (for language-filtered search)
     symbol_languages: HashMap<SymbolId, String>,
 
-    /// The embedding model (wrapped in Mutex for interior mutability)
-    model: Mutex<TextEmbedding>,
+    /// Computes embeddings of doc comments and queries
+    model: Box<dyn EmbeddingProvider>,
 
     /// Model dimensions for validation
     dimensions: usize,
@@ -60,7 +61,7 @@ impl std::fmt::Debug for SimpleSemanticSearch {
         f.debug_struct("SimpleSemanticSearch")
             .field("embeddings_count", &self.embeddings.len())
             .field("dimensions", &self.dimensions)
-            .field("model", &"<TextEmbedding>")
+            .field("model", &self.model.model_name())
             .field("metadata", &self.metadata)
             .finish()
     }
@@ -96,6 +97,14 @@ impl SimpleSemanticSearch {
         Self::with_model(model)
     }
 
+    /// Create a semantic search instance with the provider and model of `config`.
+    pub fn from_config(config: &SemanticSearchConfig) -> Result<Self, SemanticSearchError> {
+        match config.provider {
+            EmbeddingProviderKind::Local => Self::from_model_name(&config.model),
+            _ => Self::with_provider(create_provider(config, true)?),
+        }
+    }
+
     /// Create with a specific model enum.
     pub fn with_model(model: EmbeddingModel) -> Result<Self, SemanticSearchError> {
         let cache_dir = crate::init::models_dir();
@@ -114,26 +123,17 @@ impl SimpleSemanticSearch {
             eprintln!("Downloading embedding model '{model_name}' (first time only)...");
         }
 
-        let mut text_model = TextEmbedding::try_new(
-            InitOptions::new(model)
-                .with_cache_dir(cache_dir)
-                .with_show_download_progress(true), // Always show progress, but with context from message above
-        )
-        .map_err(|e| {
-            SemanticSearchError::ModelInitError(format!(
-                "Failed to initialize model '{model_name}': {e}"
-            ))
-        })?;
+        Self::with_provider(Box::new(LocalProvider::new(model, true)?))
+    }
 
+    /// Create with any embedding provider.
+    pub fn with_provider(model: Box<dyn EmbeddingProvider>) -> Result<Self, SemanticSearchError> {
         // Get dimensions by generating a test embedding
-        let test_embedding = text_model
-            .embed(vec!["test"], None)
-            .map_err(|e| SemanticSearchError::EmbeddingError(e.to_string()))?;
-        let dimensions = test_embedding.into_iter().next().unwrap().len();
+        let dimensions = model.probe_dimensions()?;
 
         // Create initial metadata
         let metadata = crate::semantic::SemanticMetadata::new(
-            model_name.clone(),
+            model.model_name().to_string(),
             dimensions,
             0, // No embeddings yet
         );
@@ -141,7 +141,7 @@ impl SimpleSemanticSearch {
         Ok(Self {
             embeddings: HashMap::new(),
             symbol_languages: HashMap::new(),
-            model: Mutex::new(text_model),
+            model,
             dimensions,
             metadata: Some(metadata),
         })
@@ -159,14 +159,7 @@ impl SimpleSemanticSearch {
         }
 
         // Generate embedding
-        let embeddings = self
-            .model
-            .lock()
-            .unwrap()
-            .embed(vec![doc], None)
-            .map_err(|e| SemanticSearchError::EmbeddingError(e.to_string()))?;
-
-        let embedding = embeddings.into_iter().next().unwrap();
+        let embedding = self.model.embed_one(doc)?;
 
         // Validate dimensions
         if embedding.len() != self.dimensions {
@@ -232,13 +225,7 @@ impl SimpleSemanticSearch {
         }
 
         // Generate query embedding
-        let query_embeddings = self
-            .model
-            .lock()
-            .unwrap()
-            .embed(vec![query], None)
-            .map_err(|e| SemanticSearchError::EmbeddingError(e.to_string()))?;
-        let query_embedding = query_embeddings.into_iter().next().unwrap();
+        let query_embedding = self.model.embed_one(query)?;
 
         // Calculate similarities
         let mut similarities: Vec<(SymbolId, f32)> = self
@@ -273,13 +260,7 @@ impl SimpleSemanticSearch {
         }
 
         // Generate query embedding
-        let query_embeddings = self
-            .model
-            .lock()
-            .unwrap()
-            .embed(vec![query], None)
-            .map_err(|e| SemanticSearchError::EmbeddingError(e.to_string()))?;
-        let query_embedding = query_embeddings.into_iter().next().unwrap();
+        let query_embedding = self.model.embed_one(query)?;
 
         // Filter embeddings by language BEFORE computing similarity
         let filtered_embeddings: Vec<(&SymbolId, &Vec<f32>)> = if let Some(lang) = language {
@@ -428,20 +409,32 @@ impl SimpleSemanticSearch {
     /// # Arguments
     /// * `path` - Path where semantic data is stored
     pub fn load(path: &Path) -> Result<Self, SemanticSearchError> {
+        Self::load_with_config(path, &SemanticSearchConfig::default())
+    }
+
+    /// Load embeddings from disk, reaching a remote provider through the
+    /// endpoint and API key of `config`.
+    ///
+    /// The model is still the one specified in the metadata.
+    pub fn load_with_config(
+        path: &Path,
+        config: &SemanticSearchConfig,
+    ) -> Result<Self, SemanticSearchError> {
         use crate::semantic::{SemanticMetadata, SemanticVectorStorage};
 
         // Load metadata first
         let metadata = SemanticMetadata::load(path)?;
 
-        // Parse model name from metadata
-        let model = crate::vector::parse_embedding_model(&metadata.model_name)
-            .map_err(|e| SemanticSearchError::StorageError {
+        // Provider and model from metadata
+        let model = provider_for_model(&metadata.model_name, config).map_err(|e| {
+            SemanticSearchError::StorageError {
                 message: format!("Invalid model in metadata: {e}"),
                 suggestion: format!(
-                    "The index was created with model '{}' which is not supported. Consider re-indexing with a supported model.",
+                    "The index was created with model '{}' which is not available. Check the [semantic_search] provider settings or re-index with a supported model.",
                     metadata.model_name
                 ),
-            })?;
+            }
+        })?;
 
         // Open existing storage
         let mut storage = SemanticVectorStorage::open(path)?;
@@ -476,19 +469,6 @@ impl SimpleSemanticSearch {
             embeddings.insert(id, embedding);
         }
 
-        // Create new instance with model from metadata
-        let text_model = TextEmbedding::try_new(
-            InitOptions::new(model)
-                .with_cache_dir(crate::init::models_dir())
-                .with_show_download_progress(false),
-        )
-        .map_err(|e| {
-            SemanticSearchError::ModelInitError(format!(
-                "Failed to load model '{}': {}",
-                metadata.model_name, e
-            ))
-        })?;
-
         // Load language mappings if they exist
         let languages_path = path.join("languages.json");
         let symbol_languages = if languages_path.exists() {
@@ -515,7 +495,7 @@ impl SimpleSemanticSearch {
         Ok(Self {
             embeddings,
             symbol_languages,
-            model: Mutex::new(text_model),
+            model,
             dimensions: metadata.dimension,
             metadata: Some(metadata),
         })
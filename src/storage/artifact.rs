@@ -0,0 +1,566 @@
+//! Shareable index artifacts
+//!
+//! A published artifact is a gzipped tarball containing the Tantivy index,
+//! `index.meta`, and a `manifest.json` describing the codanna version, index
+//! format, and the content hash of every indexed file.
+//!
+//! Mounting an artifact unpacks it into `<index_path>/mounts/base`, which is
+//! kept read-only, and seeds the working index from it. Files whose local
+//! content no longer matches the manifest hash form the local overlay and are
+//! re-indexed on top of the shared base. Semantic embeddings are not part of
+//! the artifact since they depend on the local model configuration; mounting
+//! removes the local ones, which describe the symbols being replaced.
+
+use super::{DocumentIndex, IndexLock, IndexMetadata, LockMode, LockWait, StorageError};
+use crate::config::Settings;
+use crate::indexing::{calculate_hash, get_utc_timestamp};
+use crate::io::exit_code::ExitCode;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use thiserror::Error;
+
+/// Current artifact layout version
+pub const ARTIFACT_FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const MOUNT_FILE: &str = "mount.json";
+const MOUNT_BASE_DIR: &str = "mounts/base";
+
+/// Longest wait for a download server to connect and start responding
+const DOWNLOAD_RESPONSE_TIMEOUT: Duration = Duration::from_secs(60);
+/// Longest an artifact download may take once the server responds
+const DOWNLOAD_BODY_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Errors that can occur while publishing, fetching, or mounting artifacts
+#[derive(Error, Debug)]
+pub enum ArtifactError {
+    #[error("No index found at {path}\nSuggestion: Run 'codanna index' before publishing")]
+    MissingIndex { path: PathBuf },
+
+    #[error("Failed to download {url}: {reason}\nSuggestion: Check the URL and your network")]
+    Download { url: String, reason: String },
+
+    #[error(
+        "Invalid artifact manifest: {reason}\nSuggestion: Re-publish the artifact with 'codanna index publish'"
+    )]
+    InvalidManifest { reason: String },
+
+    #[error(
+        "Incompatible artifact: {field} is {found}, expected {expected}\nSuggestion: Publish the artifact with a matching codanna version"
+    )]
+    Incompatible {
+        field: &'static str,
+        found: u32,
+        expected: u32,
+    },
+
+    #[error("IO error: {0}\nSuggestion: Check file permissions and disk space")]
+    Io(#[from] io::Error),
+
+    #[error("Storage error: {0}")]
+    Storage(#[from] StorageError),
+
+    #[error("Index error: {0}")]
+    Index(#[from] crate::IndexError),
+}
+
+/// Result type for artifact operations
+pub type ArtifactResult<T> = Result<T, ArtifactError>;
+
+impl ArtifactError {
+    /// Map artifact errors to CLI exit codes
+    pub fn exit_code(&self) -> ExitCode {
+        match self {
+            ArtifactError::MissingIndex { .. } => ExitCode::NotFound,
+            ArtifactError::InvalidManifest { .. } | ArtifactError::Incompatible { .. } => {
+                ExitCode::ConfigError
+            }
+            ArtifactError::Download { .. }
+            | ArtifactError::Io(_)
+            | ArtifactError::Storage(_)
+            | ArtifactError::Index(_) => ExitCode::IoError,
+        }
+    }
+}
+
+/// A file captured in the artifact with its content hash at publish time
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArtifactFile {
+    pub path: String,
+    pub hash: String,
+}
+
+/// Describes a published index artifact
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArtifactManifest {
+    pub format_version: u32,
+    pub codanna_version: String,
+    pub index_version: u32,
+    pub created_at: u64,
+    pub symbol_count: u32,
+    pub file_count: u32,
+    pub files: Vec<ArtifactFile>,
+}
+
+/// Recorded in `<index_path>/mount.json` while an artifact is mounted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MountInfo {
+    pub source: String,
+    pub codanna_version: String,
+    pub created_at: u64,
+    pub mounted_at: u64,
+    pub base_path: PathBuf,
+}
+
+/// Outcome of mounting an artifact
+#[derive(Debug, Clone, Serialize)]
+pub struct MountReport {
+    pub source: String,
+    pub symbol_count: u32,
+    pub file_count: u32,
+    /// Files edited locally since publish (workspace-relative)
+    pub modified: Vec<String>,
+    /// Files in the artifact that no longer exist locally
+    pub deleted: Vec<String>,
+}
+
+impl std::fmt::Display for MountReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Mounted {} ({} symbols, {} files)",
+            self.source, self.symbol_count, self.file_count
+        )?;
+        if self.modified.is_empty() && self.deleted.is_empty() {
+            writeln!(f, "No local changes to overlay")
+        } else {
+            writeln!(
+                f,
+                "Local overlay: {} modified, {} deleted",
+                self.modified.len(),
+                self.deleted.len()
+            )
+        }
+    }
+}
+
+/// Package the index at `settings.index_path` into a tarball at `output`
+pub fn publish_index(settings: &Settings, output: &Path) -> ArtifactResult<ArtifactManifest> {
+    let tantivy_path = settings.index_path.join("tantivy");
+    if !tantivy_path.join("meta.json").exists() {
+        return Err(ArtifactError::MissingIndex {
+            path: settings.index_path.clone(),
+        });
+    }
+
+    let index = DocumentIndex::new(&tantivy_path, settings)?;
+    let mut files: Vec<ArtifactFile> = index
+        .query_file_info()?
+        .into_iter()
+        .map(|(_, path, hash, _)| ArtifactFile { path, hash })
+        .collect();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let manifest = ArtifactManifest {
+        format_version: ARTIFACT_FORMAT_VERSION,
+        codanna_version: env!("CARGO_PKG_VERSION").to_string(),
+        index_version: IndexMetadata::default().version,
+        created_at: get_utc_timestamp(),
+        symbol_count: index.count_symbols()? as u32,
+        file_count: files.len() as u32,
+        files,
+    };
+    drop(index);
+
+    if let Some(parent) = output.parent() {
+        if !parent.as_os_str().is_empty() {
+            fs::create_dir_all(parent)?;
+        }
+    }
+
+    let encoder =
+        flate2::write::GzEncoder::new(File::create(output)?, flate2::Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+
+    let manifest_json =
+        serde_json::to_vec_pretty(&manifest).map_err(|e| ArtifactError::InvalidManifest {
+            reason: e.to_string(),
+        })?;
+    let mut header = tar::Header::new_gnu();
+    header.set_size(manifest_json.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(manifest.created_at);
+    header.set_cksum();
+    builder.append_data(&mut header, MANIFEST_FILE, manifest_json.as_slice())?;
+
+    let meta_path = settings.index_path.join("index.meta");
+    if meta_path.exists() {
+        builder.append_path_with_name(&meta_path, "index.meta")?;
+    }
+
+    for entry in fs::read_dir(&tantivy_path)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        // Lock files belong to the publishing process, not the index
+        if name.to_string_lossy().ends_with(".lock") || !entry.file_type()?.is_file() {
+            continue;
+        }
+        builder.append_path_with_name(entry.path(), Path::new("tantivy").join(&name))?;
+    }
+
+    builder.into_inner()?.finish()?;
+
+    tracing::debug!(
+        target: "storage",
+        "published artifact {} ({} files)",
+        output.display(),
+        manifest.file_count
+    );
+
+    Ok(manifest)
+}
+
+/// Resolve an artifact source to a local tarball
+///
+/// `http://` and `https://` sources are downloaded into `cache_dir`; anything
+/// else is treated as a local path.
+pub fn fetch_artifact(source: &str, cache_dir: &Path) -> ArtifactResult<PathBuf> {
+    if !(source.starts_with("http://") || source.starts_with("https://")) {
+        let path = PathBuf::from(source);
+        if !path.is_file() {
+            return Err(ArtifactError::Io(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("artifact not found: {source}"),
+            )));
+        }
+        return Ok(path);
+    }
+
+    fs::create_dir_all(cache_dir)?;
+    let destination = cache_dir.join("artifact.tar.gz");
+
+    let download_error = |reason: String| ArtifactError::Download {
+        url: source.to_string(),
+        reason,
+    };
+    let config = ureq::Agent::config_builder()
+        .timeout_connect(Some(DOWNLOAD_RESPONSE_TIMEOUT))
+        .timeout_recv_response(Some(DOWNLOAD_RESPONSE_TIMEOUT))
+        .timeout_recv_body(Some(DOWNLOAD_BODY_TIMEOUT))
+        .build();
+    let response = ureq::Agent::new_with_config(config)
+        .get(source)
+        .call()
+        .map_err(|e| download_error(e.to_string()))?;
+    let mut reader = response.into_body().into_reader();
+    let mut file = File::create(&destination)?;
+    io::copy(&mut reader, &mut file).map_err(|e| download_error(e.to_string()))?;
+
+    Ok(destination)
+}
+
+/// Unpack an artifact into `dest`, validating its manifest
+///
+/// Any previous content of `dest` is replaced. Unpacked files are marked
+/// read-only.
+pub fn unpack_artifact(archive: &Path, dest: &Path) -> ArtifactResult<ArtifactManifest> {
+    if dest.exists() {
+        set_read_only(dest, false)?;
+        fs::remove_dir_all(dest)?;
+    }
+    fs::create_dir_all(dest)?;
+
+    let decoder = flate2::read::GzDecoder::new(File::open(archive)?);
+    tar::Archive::new(decoder).unpack(dest)?;
+
+    let manifest = read_manifest(dest)?;
+    if manifest.format_version > ARTIFACT_FORMAT_VERSION {
+        return Err(ArtifactError::Incompatible {
+            field: "format_version",
+            found: manifest.format_version,
+            expected: ARTIFACT_FORMAT_VERSION,
+        });
+    }
+    let index_version = IndexMetadata::default().version;
+    if manifest.index_version != index_version {
+        return Err(ArtifactError::Incompatible {
+            field: "index_version",
+            found: manifest.index_version,
+            expected: index_version,
+        });
+    }
+    if !dest.join("tantivy").join("meta.json").exists() {
+        return Err(ArtifactError::InvalidManifest {
+            reason: "artifact does not contain a tantivy index".to_string(),
+        });
+    }
+
+    set_read_only(dest, true)?;
+    Ok(manifest)
+}
+
+/// Fetch and mount an artifact as the base of the local index
+///
+/// The working index at `settings.index_path/tantivy` is replaced with a
+/// writable copy of the artifact, and local semantic embeddings, which
+/// belong to the replaced index, are removed. The returned report lists
+/// files that need re-indexing to overlay local edits.
+pub fn mount_artifact(source: &str, settings: &Settings) -> ArtifactResult<MountReport> {
+    let index_path = &settings.index_path;
+    let base_path = index_path.join(MOUNT_BASE_DIR);
+    // The working index is replaced below; no other process may be using it
+    let _lock = IndexLock::acquire(index_path, LockMode::Write, LockWait::default_wait())?;
+
+    let archive = fetch_artifact(source, &index_path.join("mounts"))?;
+    let manifest = unpack_artifact(&archive, &base_path)?;
+
+    // Seed the working index from the read-only base
+    let working = index_path.join("tantivy");
+    if working.exists() {
+        fs::remove_dir_all(&working)?;
+    }
+    fs::create_dir_all(&working)?;
+    for entry in fs::read_dir(base_path.join("tantivy"))? {
+        let entry = entry?;
+        if entry.file_type()?.is_file() {
+            fs::copy(entry.path(), working.join(entry.file_name()))?;
+        }
+    }
+    set_read_only(&working, false)?;
+
+    // Embeddings are keyed by the replaced index's symbol ids
+    let semantic = index_path.join("semantic");
+    if semantic.exists() {
+        fs::remove_dir_all(&semantic)?;
+    }
+
+    // Stored indexed paths come from the publisher's machine; point them at
+    // the local configuration so sync does not rebuild the whole base.
+    let mut metadata = IndexMetadata::load(&base_path).unwrap_or_default();
+    metadata.update_counts(manifest.symbol_count, manifest.file_count);
+    metadata.update_indexed_paths(settings.indexing.indexed_paths.clone());
+    metadata.save(index_path)?;
+
+    let (modified, deleted) = local_changes(&manifest, settings.workspace_root.as_deref());
+
+    let info = MountInfo {
+        source: source.to_string(),
+        codanna_version: manifest.codanna_version.clone(),
+        created_at: manifest.created_at,
+        mounted_at: get_utc_timestamp(),
+        base_path,
+    };
+    let json = serde_json::to_string_pretty(&info).map_err(|e| ArtifactError::InvalidManifest {
+        reason: e.to_string(),
+    })?;
+    fs::write(index_path.join(MOUNT_FILE), json)?;
+
+    Ok(MountReport {
+        source: source.to_string(),
+        symbol_count: manifest.symbol_count,
+        file_count: manifest.file_count,
+        modified,
+        deleted,
+    })
+}
+
+/// Read mount information if an artifact is mounted at `index_path`
+pub fn read_mount_info(index_path: &Path) -> Option<MountInfo> {
+    let json = fs::read_to_string(index_path.join(MOUNT_FILE)).ok()?;
+    serde_json::from_str(&json).ok()
+}
+
+fn read_manifest(dir: &Path) -> ArtifactResult<ArtifactManifest> {
+    let json = fs::read_to_string(dir.join(MANIFEST_FILE)).map_err(|e| {
+        ArtifactError::InvalidManifest {
+            reason: format!("missing {MANIFEST_FILE}: {e}"),
+        }
+    })?;
+    serde_json::from_str(&json).map_err(|e| ArtifactError::InvalidManifest {
+        reason: e.to_string(),
+    })
+}
+
+/// Split manifest files into locally modified and deleted sets
+fn local_changes(
+    manifest: &ArtifactManifest,
+    workspace_root: Option<&Path>,
+) -> (Vec<String>, Vec<String>) {
+    let mut modified = Vec::new();
+    let mut deleted = Vec::new();
+
+    for file in &manifest.files {
+        let path = Path::new(&file.path);
+        let local = match workspace_root {
+            Some(root) if path.is_relative() => root.join(path),
+            _ => path.to_path_buf(),
+        };
+        match fs::read_to_string(&local) {
+            Ok(content) => {
+                if calculate_hash(&content) != file.hash {
+                    modified.push(file.path.clone());
+                }
+            }
+            Err(_) => deleted.push(file.path.clone()),
+        }
+    }
+
+    (modified, deleted)
+}
+
+#[allow(clippy::permissions_set_readonly_false)]
+fn set_read_only(dir: &Path, read_only: bool) -> io::Result<()> {
+    for entry in walkdir::WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+    {
+        if entry.file_type().is_file() {
+            let mut permissions = entry.metadata()?.permissions();
+            permissions.set_readonly(read_only);
+            fs::set_permissions(entry.path(), permissions)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indexing::pipeline::FileRegistration;
+    use crate::parsing::registry::LanguageId;
+    use crate::{FileId, Range, Symbol, SymbolId, SymbolKind};
+    use tempfile::TempDir;
+
+    fn build_index(workspace: &Path) -> Settings {
+        let settings = Settings {
+            index_path: workspace.join(".codanna/index"),
+            workspace_root: Some(workspace.to_path_buf()),
+            ..Settings::default()
+        };
+
+        let content = "fn shared() {}";
+        fs::write(workspace.join("lib.rs"), content).unwrap();
+        fs::write(workspace.join("old.rs"), "fn old() {}").unwrap();
+
+        let index = DocumentIndex::new(settings.index_path.join("tantivy"), &settings).unwrap();
+        index.start_batch().unwrap();
+        for (id, path, body) in [(1, "lib.rs", content), (2, "old.rs", "fn old() {}")] {
+            index
+                .store_file_registration(&FileRegistration {
+                    path: PathBuf::from(path),
+                    file_id: FileId::new(id).unwrap(),
+                    content_hash: calculate_hash(body),
+                    language_id: LanguageId::new("rust"),
+                    timestamp: 0,
+                    mtime: 0,
+                })
+                .unwrap();
+            let symbol = Symbol::new(
+                SymbolId::new(id).unwrap(),
+                format!("sym_{id}"),
+                SymbolKind::Function,
+                FileId::new(id).unwrap(),
+                Range::new(1, 0, 1, 10),
+            );
+            index.index_symbol(&symbol, path).unwrap();
+        }
+        index.commit_batch().unwrap();
+
+        settings
+    }
+
+    #[test]
+    fn test_publish_and_mount_roundtrip() {
+        let publisher = TempDir::new().unwrap();
+        let settings = build_index(publisher.path());
+        let artifact = publisher.path().join("out/index.tar.gz");
+
+        let manifest = publish_index(&settings, &artifact).unwrap();
+        assert_eq!(manifest.format_version, ARTIFACT_FORMAT_VERSION);
+        assert_eq!(manifest.file_count, 2);
+        assert_eq!(manifest.symbol_count, 2);
+        assert!(artifact.exists());
+
+        // Consumer has edited lib.rs and never had old.rs
+        let consumer = TempDir::new().unwrap();
+        fs::write(consumer.path().join("lib.rs"), "fn shared() { edited(); }").unwrap();
+        let consumer_settings = Settings {
+            index_path: consumer.path().join(".codanna/index"),
+            workspace_root: Some(consumer.path().to_path_buf()),
+            ..Settings::default()
+        };
+        // Embeddings of an index built before the mount
+        let semantic = consumer_settings.index_path.join("semantic");
+        fs::create_dir_all(&semantic).unwrap();
+        fs::write(semantic.join("metadata.json"), "{}").unwrap();
+
+        let report = mount_artifact(artifact.to_str().unwrap(), &consumer_settings).unwrap();
+        assert_eq!(report.symbol_count, 2);
+        assert_eq!(report.modified, vec!["lib.rs".to_string()]);
+        assert_eq!(report.deleted, vec!["old.rs".to_string()]);
+        assert!(!semantic.exists());
+
+        let index = DocumentIndex::new(
+            consumer_settings.index_path.join("tantivy"),
+            &consumer_settings,
+        )
+        .unwrap();
+        assert_eq!(index.count_symbols().unwrap(), 2);
+
+        let info = read_mount_info(&consumer_settings.index_path).unwrap();
+        assert_eq!(info.codanna_version, env!("CARGO_PKG_VERSION"));
+        let base_meta = fs::metadata(info.base_path.join("tantivy/meta.json")).unwrap();
+        assert!(base_meta.permissions().readonly());
+    }
+
+    #[test]
+    fn test_publish_without_index_fails() {
+        let temp_dir = TempDir::new().unwrap();
+        let settings = Settings {
+            index_path: temp_dir.path().join("missing"),
+            ..Settings::default()
+        };
+        let err = publish_index(&settings, &temp_dir.path().join("a.tar.gz")).unwrap_err();
+        assert!(matches!(err, ArtifactError::MissingIndex { .. }));
+        assert_eq!(err.exit_code(), ExitCode::NotFound);
+    }
+
+    #[test]
+    fn test_unpack_rejects_newer_format() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive = temp_dir.path().join("future.tar.gz");
+        let manifest = ArtifactManifest {
+            format_version: ARTIFACT_FORMAT_VERSION + 1,
+            codanna_version: "99.0.0".to_string(),
+            index_version: 1,
+            created_at: 0,
+            symbol_count: 0,
+            file_count: 0,
+            files: Vec::new(),
+        };
+        let json = serde_json::to_vec(&manifest).unwrap();
+        let encoder =
+            flate2::write::GzEncoder::new(File::create(&archive).unwrap(), Default::default());
+        let mut builder = tar::Builder::new(encoder);
+        let mut header = tar::Header::new_gnu();
+        header.set_size(json.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, MANIFEST_FILE, json.as_slice())
+            .unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+
+        let err = unpack_artifact(&archive, &temp_dir.path().join("base")).unwrap_err();
+        assert!(matches!(
+            err,
+            ArtifactError::Incompatible {
+                field: "format_version",
+                ..
+            }
+        ));
+    }
+}
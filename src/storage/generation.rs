@@ -0,0 +1,142 @@
+//! Published generations of the document index
+//!
+//! Every commit reloads the index reader, so without care a query running
+//! between two commits of one update sees it half applied. Queries instead
+//! read a published searcher, a fixed snapshot of the index segments, and an
+//! update swaps in a new one only once it is complete.
+//!
+//! Commits made outside any update publish at once, as before. The facade
+//! wraps each update in a [`WriteScope`], and the watcher holds a generation
+//! open across a burst of changed files; either defers publishing until the
+//! last hold is released. Inside a write scope the writer reads its latest
+//! view, since resolving one file needs the symbols of the files written
+//! before it.
+//!
+//! Each publish also writes a `generation` marker next to the index, which
+//! other processes watch instead of `meta.json` to pick up whole updates.
+
+use super::{DocumentIndex, StorageError, StorageResult};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+use tantivy::Searcher;
+
+/// Name of the marker file written on each publish
+pub const GENERATION_MARKER: &str = "generation";
+
+/// Published searcher and the state deciding when it is replaced
+pub(crate) struct Generations {
+    published: RwLock<Searcher>,
+    number: AtomicU64,
+    holds: AtomicUsize,
+    writers: AtomicUsize,
+}
+
+impl Generations {
+    /// Start at `searcher`, continuing the numbering of the marker in `dir`
+    pub(crate) fn new(searcher: Searcher, dir: &Path) -> Self {
+        Self {
+            published: RwLock::new(searcher),
+            number: AtomicU64::new(read_marker(dir).unwrap_or(0)),
+            holds: AtomicUsize::new(0),
+            writers: AtomicUsize::new(0),
+        }
+    }
+
+    /// The searcher a query should use: the writer's latest view while a
+    /// write scope is open or no generation is held, the published one
+    /// otherwise
+    pub(crate) fn searcher(&self, latest: impl FnOnce() -> Searcher) -> Searcher {
+        if self.holds.load(Ordering::Acquire) == 0 || self.writers.load(Ordering::Acquire) > 0 {
+            return latest();
+        }
+        match self.published.read() {
+            Ok(published) => published.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    /// Number of the published generation
+    pub(crate) fn current(&self) -> u64 {
+        self.number.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn hold(&self) {
+        self.holds.fetch_add(1, Ordering::AcqRel);
+    }
+
+    /// Release a hold; returns whether it was the last one
+    pub(crate) fn release(&self) -> bool {
+        self.holds
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |holds| {
+                holds.checked_sub(1)
+            })
+            .is_ok_and(|holds| holds == 1)
+    }
+
+    /// Whether a commit may publish straight away
+    pub(crate) fn is_free(&self) -> bool {
+        self.holds.load(Ordering::Acquire) == 0
+    }
+
+    /// Swap in `searcher` as the next generation and record it in `dir`
+    pub(crate) fn publish(&self, searcher: Searcher, dir: &Path) -> StorageResult<u64> {
+        let mut published = self
+            .published
+            .write()
+            .map_err(|_| StorageError::LockPoisoned)?;
+        *published = searcher;
+        let number = self.number.fetch_add(1, Ordering::AcqRel) + 1;
+        write_marker(dir, number)?;
+        Ok(number)
+    }
+}
+
+/// A writer's claim on its own view of the index for the length of an
+/// update, which is published when the claim is dropped. See
+/// [`DocumentIndex::write_scope`].
+pub struct WriteScope {
+    index: Arc<DocumentIndex>,
+}
+
+impl WriteScope {
+    pub(crate) fn new(index: Arc<DocumentIndex>) -> Self {
+        let generations = index.generations();
+        generations.hold();
+        generations.writers.fetch_add(1, Ordering::AcqRel);
+        Self { index }
+    }
+}
+
+impl Drop for WriteScope {
+    fn drop(&mut self) {
+        self.index
+            .generations()
+            .writers
+            .fetch_sub(1, Ordering::AcqRel);
+        if let Err(e) = self.index.publish_generation() {
+            tracing::warn!("[index] failed to publish generation: {e}");
+        }
+    }
+}
+
+/// Generation number recorded in `dir`, if an update was ever published
+pub fn read_marker(dir: &Path) -> Option<u64> {
+    std::fs::read_to_string(dir.join(GENERATION_MARKER))
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// Record `number` in `dir`, through a rename so readers never see a
+/// partial write
+fn write_marker(dir: &Path, number: u64) -> StorageResult<()> {
+    if !dir.join("meta.json").exists() {
+        return Ok(());
+    }
+    let temp = dir.join(format!("{GENERATION_MARKER}.tmp"));
+    std::fs::write(&temp, number.to_string())?;
+    std::fs::rename(&temp, dir.join(GENERATION_MARKER))?;
+    Ok(())
+}
@@ -0,0 +1,569 @@
+//! Advisory locks shared by the processes using one index
+//!
+//! An editor's server, a CI job and a developer's `codanna index` may all
+//! open the same index. Readers can share it, but two writers interleave
+//! their Tantivy commits, write-ahead log and semantic store and corrupt
+//! it. Locks are files in `<index>/locks`, each held with an OS advisory
+//! lock (`flock` on Unix, `LockFileEx` on Windows) for as long as it is in
+//! use:
+//!
+//! - a writer locks `write.lock`, then waits until no reader holds the
+//!   index; readers arriving meanwhile back off, so writers are not starved
+//! - a reader locks a file of its own, `read-<pid>-<n>.lock`, unless a
+//!   writer holds the index. The file is locked before it gets that name,
+//!   so no one sees it unlocked while its reader lives
+//!
+//! The OS drops the locks of a process that dies, so a file that can be
+//! locked has no owner: there is no stale-lock check to race with. Each
+//! file also records its owner, for the message of whoever waits; Windows
+//! keeps locked files from being read, so there the owner is unknown.
+//! `write.lock` is never removed, only emptied on release, and a reader's
+//! file is removed only by whoever holds its lock.
+//!
+//! A process never conflicts with its own locks; it coordinates its threads
+//! itself. Locks are advisory and only keep codanna processes apart.
+
+use crate::{IndexError, IndexResult};
+use fs4::fs_std::FileExt;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{ErrorKind, Seek, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+/// Directory of the lock files inside the index directory
+pub const LOCK_DIR: &str = "locks";
+
+const WRITE_LOCK: &str = "write.lock";
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Wait selected with the global `--wait` option
+static DEFAULT_WAIT: OnceLock<LockWait> = OnceLock::new();
+
+/// Numbers this process's read lock files
+static NEXT_READ_LOCK: AtomicU64 = AtomicU64::new(1);
+
+/// Lock files this process holds
+static HELD: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LockMode {
+    Read,
+    Write,
+}
+
+/// How long to wait for a lock another process holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockWait {
+    /// Fail at once
+    No,
+    For(Duration),
+    Forever,
+}
+
+impl LockWait {
+    /// Select the wait used for index locks. Only the first call takes
+    /// effect.
+    pub fn set_default(wait: Self) {
+        let _ = DEFAULT_WAIT.set(wait);
+    }
+
+    /// Wait selected with [`LockWait::set_default`], or no wait
+    pub fn default_wait() -> Self {
+        DEFAULT_WAIT.get().copied().unwrap_or(Self::No)
+    }
+}
+
+/// Who holds a lock, as recorded in its file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockOwner {
+    pub pid: u32,
+    pub host: String,
+    /// Start time of the owning process in seconds since the epoch
+    pub started_at: u64,
+    pub mode: LockMode,
+    /// Command line of the owner, for the message of whoever waits
+    pub command: String,
+    pub acquired_at: u64,
+}
+
+impl LockOwner {
+    fn this_process(mode: LockMode) -> Self {
+        let pid = std::process::id();
+        Self {
+            pid,
+            host: host_name(),
+            started_at: process_start(pid).unwrap_or(0),
+            mode,
+            command: std::env::args().collect::<Vec<_>>().join(" "),
+            acquired_at: crate::indexing::get_utc_timestamp(),
+        }
+    }
+
+    fn is_this_process(&self) -> bool {
+        self.pid == std::process::id() && self.host == host_name()
+    }
+
+    /// Owner recorded in a locked file; unknown if it can't be read, as
+    /// while it is written or where the OS lock also blocks reads
+    fn read(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+}
+
+impl std::fmt::Display for LockOwner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "pid {} on {} ({})", self.pid, self.host, self.command)
+    }
+}
+
+/// A lock file held until dropped
+#[derive(Debug)]
+pub struct IndexLock {
+    path: PathBuf,
+    mode: LockMode,
+    /// The locked file; `None` when this process already held the write
+    /// lock, so dropping leaves it alone
+    file: Option<File>,
+}
+
+impl IndexLock {
+    /// Lock the index in `index_dir` for `mode`, waiting up to `wait` for
+    /// other processes to release it
+    pub fn acquire(index_dir: &Path, mode: LockMode, wait: LockWait) -> IndexResult<Self> {
+        let dir = index_dir.join(LOCK_DIR);
+        fs::create_dir_all(&dir)?;
+        let mut waiter = Waiter::new(wait);
+        match mode {
+            LockMode::Read => loop {
+                match Self::try_read(&dir)? {
+                    Ok(lock) => return Ok(lock),
+                    Err(blocker) => waiter.wait(blocker)?,
+                }
+            },
+            LockMode::Write => {
+                let lock = loop {
+                    match Self::try_write(&dir)? {
+                        Ok(lock) => break lock,
+                        Err(blocker) => waiter.wait(blocker)?,
+                    }
+                };
+                // Holding write.lock keeps new readers out; wait for the
+                // current ones. Giving up drops, and so releases, the lock.
+                while let Some(blocker) = foreign_reader(&dir)? {
+                    waiter.wait(blocker)?;
+                }
+                Ok(lock)
+            }
+        }
+    }
+
+    pub fn mode(&self) -> LockMode {
+        self.mode
+    }
+
+    /// Take write.lock, or name the process holding it
+    fn try_write(dir: &Path) -> IndexResult<Result<Self, Blocker>> {
+        let path = dir.join(WRITE_LOCK);
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)?;
+        if !FileExt::try_lock_exclusive(&file)? {
+            return match foreign_holder(&path) {
+                Some(owner) => Ok(Err((path, owner))),
+                None => Ok(Ok(Self {
+                    path,
+                    mode: LockMode::Write,
+                    file: None,
+                })),
+            };
+        }
+        // Record ourselves before looking for readers, so a reader that
+        // arrives after that look finds us
+        file.set_len(0)?;
+        write_owner(&mut file, LockMode::Write)?;
+        Ok(Ok(Self::held(path, LockMode::Write, file)))
+    }
+
+    /// Take a read lock, or name the process writing the index
+    fn try_read(dir: &Path) -> IndexResult<Result<Self, Blocker>> {
+        let writer = dir.join(WRITE_LOCK);
+        if let Some(owner) = foreign_writer(&writer)? {
+            return Ok(Err((writer, owner)));
+        }
+
+        let lock = Self::create_read(dir)?;
+        // A writer may have arrived between the check and our file
+        if let Some(owner) = foreign_writer(&writer)? {
+            return Ok(Err((writer, owner)));
+        }
+        Ok(Ok(lock))
+    }
+
+    /// A lock on `file`, which this process has just locked
+    fn held(path: PathBuf, mode: LockMode, file: File) -> Self {
+        HELD.lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(path.clone());
+        Self {
+            path,
+            mode,
+            file: Some(file),
+        }
+    }
+
+    /// Lock a new file under a private name, then give it its read lock
+    /// name; hard links never replace an existing file
+    fn create_read(dir: &Path) -> IndexResult<Self> {
+        loop {
+            let sequence = NEXT_READ_LOCK.fetch_add(1, Ordering::Relaxed);
+            let name = format!("read-{}-{sequence}.lock", std::process::id());
+            let staging = dir.join(format!(".{name}.new"));
+            let mut file = match OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create_new(true)
+                .open(&staging)
+            {
+                Ok(file) => file,
+                // Left by a process that had our pid
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e.into()),
+            };
+            FileExt::lock_exclusive(&file)?;
+            write_owner(&mut file, LockMode::Read)?;
+
+            let path = dir.join(name);
+            let linked = fs::hard_link(&staging, &path);
+            fs::remove_file(&staging)?;
+            match linked {
+                Ok(()) => return Ok(Self::held(path, LockMode::Read, file)),
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
+
+/// A lock file another process holds, and its owner
+type Blocker = (PathBuf, LockOwner);
+
+/// Polls for a lock until the caller's patience runs out
+struct Waiter {
+    deadline: Option<Instant>,
+    announced: bool,
+}
+
+impl Waiter {
+    fn new(wait: LockWait) -> Self {
+        let deadline = match wait {
+            LockWait::No => Some(Instant::now()),
+            LockWait::For(duration) => Some(Instant::now() + duration),
+            LockWait::Forever => None,
+        };
+        Self {
+            deadline,
+            announced: false,
+        }
+    }
+
+    /// Sleep before the next attempt, or fail once the deadline passed
+    fn wait(&mut self, (path, owner): Blocker) -> IndexResult<()> {
+        if self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            return Err(IndexError::IndexLocked {
+                path,
+                holder: owner.to_string(),
+            });
+        }
+        if !self.announced {
+            eprintln!("Waiting for the index lock held by {owner}...");
+            self.announced = true;
+        }
+        std::thread::sleep(POLL_INTERVAL);
+        Ok(())
+    }
+}
+
+impl Drop for IndexLock {
+    fn drop(&mut self) {
+        let Some(file) = self.file.take() else {
+            return;
+        };
+        HELD.lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .retain(|held| *held != self.path);
+        // Still holding the OS lock, so no one else touches the file. The
+        // lock itself goes with the handle.
+        let released = match self.mode {
+            LockMode::Write => file.set_len(0),
+            LockMode::Read => fs::remove_file(&self.path),
+        };
+        if let Err(e) = released {
+            tracing::warn!("[lock] failed to release {}: {e}", self.path.display());
+        }
+    }
+}
+
+/// This process's write lock on one index, taken by the outermost of
+/// nested updates and released when the last of them finishes
+#[derive(Debug)]
+pub struct WriteLocks {
+    index_dir: PathBuf,
+    held: Mutex<(Option<IndexLock>, usize)>,
+}
+
+/// One update's share of a [`WriteLocks`] lock
+#[derive(Debug)]
+pub struct WriteGuard {
+    locks: Arc<WriteLocks>,
+}
+
+impl WriteLocks {
+    pub fn new(index_dir: impl Into<PathBuf>) -> Arc<Self> {
+        Arc::new(Self {
+            index_dir: index_dir.into(),
+            held: Mutex::new((None, 0)),
+        })
+    }
+
+    /// Hold the write lock until the guard is dropped, waiting as selected
+    /// with [`LockWait::set_default`]
+    pub fn acquire(locks: &Arc<Self>) -> IndexResult<WriteGuard> {
+        Self::acquire_waiting(locks, LockWait::default_wait())
+    }
+
+    /// Hold the write lock until the guard is dropped, failing at once if
+    /// another process holds the index
+    pub fn try_acquire(locks: &Arc<Self>) -> IndexResult<WriteGuard> {
+        Self::acquire_waiting(locks, LockWait::No)
+    }
+
+    fn acquire_waiting(locks: &Arc<Self>, wait: LockWait) -> IndexResult<WriteGuard> {
+        let mut held = locks.held.lock().map_err(|_| IndexError::lock_error())?;
+        if held.1 == 0 {
+            held.0 = Some(IndexLock::acquire(&locks.index_dir, LockMode::Write, wait)?);
+        }
+        held.1 += 1;
+        Ok(WriteGuard {
+            locks: Arc::clone(locks),
+        })
+    }
+}
+
+impl Drop for WriteGuard {
+    fn drop(&mut self) {
+        let Ok(mut held) = self.locks.held.lock() else {
+            return;
+        };
+        held.1 = held.1.saturating_sub(1);
+        if held.1 == 0 {
+            held.0 = None;
+        }
+    }
+}
+
+/// Record this process as the owner of the locked `file`
+fn write_owner(file: &mut File, mode: LockMode) -> IndexResult<()> {
+    let owner = serde_json::to_string(&LockOwner::this_process(mode))
+        .map_err(|e| IndexError::General(format!("Failed to serialize lock owner: {e}")))?;
+    file.rewind()?;
+    file.write_all(owner.as_bytes())?;
+    file.flush()?;
+    Ok(())
+}
+
+/// Another process holding write.lock, if any
+///
+/// An empty file has no writer. Otherwise the file is probed with a shared
+/// lock, which fails only while a writer holds it; the probe is dropped at
+/// once.
+fn foreign_writer(path: &Path) -> IndexResult<Option<LockOwner>> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    if file.metadata()?.len() == 0 {
+        return Ok(None);
+    }
+    if FileExt::try_lock_shared(&file)? {
+        // Left by a writer that died
+        return Ok(None);
+    }
+    Ok(foreign_holder(path))
+}
+
+/// Owner of the lock file at `path`, which someone holds; `None` if that
+/// is this process
+fn foreign_holder(path: &Path) -> Option<LockOwner> {
+    let held_here = HELD
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .any(|held| held == path);
+    let owner = LockOwner::read(path);
+    (!held_here && !owner.is_this_process()).then_some(owner)
+}
+
+/// A read lock of another live process, if any. Read lock files nobody
+/// holds were left by readers that died, and are removed.
+fn foreign_reader(dir: &Path) -> IndexResult<Option<Blocker>> {
+    for path in read_locks(dir)? {
+        let file = match OpenOptions::new().read(true).write(true).open(&path) {
+            Ok(file) => file,
+            // Released meanwhile
+            Err(e) if e.kind() == ErrorKind::NotFound => continue,
+            Err(e) => return Err(e.into()),
+        };
+        if FileExt::try_lock_exclusive(&file)? {
+            tracing::info!("[lock] removing stale lock {}", path.display());
+            match fs::remove_file(&path) {
+                Ok(()) => {}
+                Err(e) if e.kind() == ErrorKind::NotFound => {}
+                Err(e) => return Err(e.into()),
+            }
+            continue;
+        }
+        if let Some(owner) = foreign_holder(&path) {
+            return Ok(Some((path, owner)));
+        }
+    }
+    Ok(None)
+}
+
+fn read_locks(dir: &Path) -> IndexResult<Vec<PathBuf>> {
+    let mut locks = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let is_read_lock = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with("read-") && name.ends_with(".lock"));
+        if is_read_lock {
+            locks.push(path);
+        }
+    }
+    Ok(locks)
+}
+
+impl Default for LockOwner {
+    /// An owner whose file could not be read: never us
+    fn default() -> Self {
+        Self {
+            pid: 0,
+            host: "unknown host".to_string(),
+            started_at: 0,
+            mode: LockMode::Write,
+            command: "unknown command".to_string(),
+            acquired_at: 0,
+        }
+    }
+}
+
+fn host_name() -> String {
+    System::host_name().unwrap_or_else(|| "localhost".to_string())
+}
+
+/// Start time of the running process `pid`, `None` if there is none
+pub(crate) fn process_start(pid: u32) -> Option<u64> {
+    let pid = Pid::from_u32(pid);
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[pid]),
+        true,
+        ProcessRefreshKind::nothing(),
+    );
+    system.process(pid).map(|process| process.start_time())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn plant(dir: &Path, name: &str, owner: &LockOwner) -> PathBuf {
+        let locks = dir.join(LOCK_DIR);
+        fs::create_dir_all(&locks).unwrap();
+        let path = locks.join(name);
+        fs::write(&path, serde_json::to_string(owner).unwrap()).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_stale_locks_are_taken_over_and_live_ones_block() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        let write_lock = dir.join(LOCK_DIR).join(WRITE_LOCK);
+
+        // Files left by a writer and a reader that are no longer running:
+        // nothing holds them, whoever they name
+        let crashed = LockOwner {
+            pid: u32::MAX,
+            ..LockOwner::this_process(LockMode::Write)
+        };
+        plant(dir, WRITE_LOCK, &crashed);
+        let stale_reader = plant(dir, "read-4294967295-1.lock", &crashed);
+        let lock = IndexLock::acquire(dir, LockMode::Write, LockWait::No).unwrap();
+        assert_eq!(lock.mode(), LockMode::Write);
+        assert!(!stale_reader.exists());
+        drop(lock);
+        assert_eq!(fs::metadata(&write_lock).unwrap().len(), 0);
+
+        // A writer holding the file blocks, even one on another host
+        let remote = LockOwner {
+            host: "ci-runner-7".to_string(),
+            ..LockOwner::this_process(LockMode::Write)
+        };
+        plant(dir, WRITE_LOCK, &remote);
+        let held = File::open(&write_lock).unwrap();
+        FileExt::lock_exclusive(&held).unwrap();
+        let busy = IndexLock::acquire(
+            dir,
+            LockMode::Read,
+            LockWait::For(Duration::from_millis(150)),
+        )
+        .unwrap_err();
+        let holder = match busy {
+            IndexError::IndexLocked { holder, .. } => holder,
+            other => panic!("expected IndexLocked, got {other:?}"),
+        };
+        if cfg!(unix) {
+            assert!(holder.contains("ci-runner-7"), "{holder}");
+        }
+
+        // Released by the OS along with the handle
+        drop(held);
+        IndexLock::acquire(dir, LockMode::Read, LockWait::No).unwrap();
+    }
+
+    #[test]
+    fn test_own_locks_never_conflict() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        let write_lock = dir.join(LOCK_DIR).join(WRITE_LOCK);
+
+        let read = IndexLock::acquire(dir, LockMode::Read, LockWait::No).unwrap();
+        let locks = WriteLocks::new(dir);
+        let outer = WriteLocks::acquire(&locks).unwrap();
+        let inner = WriteLocks::acquire(&locks).unwrap();
+        drop(outer);
+        assert!(fs::metadata(&write_lock).unwrap().len() > 0);
+        drop(inner);
+        assert_eq!(fs::metadata(&write_lock).unwrap().len(), 0);
+        drop(read);
+        assert!(read_locks(&dir.join(LOCK_DIR)).unwrap().is_empty());
+    }
+}
@@ -1,5 +1,6 @@
 //! Metadata tracking for index state and data sources
 
+use super::migration::{AppliedMigration, INDEX_FORMAT_VERSION};
 use crate::IndexResult;
 use serde::{Deserialize, Serialize};
 use std::fs;
@@ -8,7 +9,7 @@ use std::path::{Path, PathBuf};
 /// Metadata about the index state
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IndexMetadata {
-    /// Version of the index format
+    /// Version of the index format, see [`super::migration`]
     pub version: u32,
 
     /// Current data source
@@ -27,6 +28,10 @@ pub struct IndexMetadata {
     /// Used to detect config changes and auto-sync on load
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub indexed_paths: Option<Vec<PathBuf>>,
+
+    /// Format migrations applied to this index, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub migrations: Vec<AppliedMigration>,
 }
 
 /// Describes where the index data came from
@@ -46,12 +51,13 @@ pub enum DataSource {
 impl Default for IndexMetadata {
     fn default() -> Self {
         Self {
-            version: 1,
+            version: INDEX_FORMAT_VERSION,
             data_source: DataSource::Fresh,
             symbol_count: 0,
             file_count: 0,
             last_modified: crate::indexing::get_utc_timestamp(),
             indexed_paths: None,
+            migrations: Vec::new(),
         }
     }
 }
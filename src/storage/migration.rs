@@ -0,0 +1,276 @@
+//! Index format versions and in-place migrations
+//!
+//! `index.meta` records the format the index was written in. Loading an
+//! index in an older format runs the registered [`MIGRATIONS`] one version
+//! at a time and records each step in `index.meta`, so an upgrade of
+//! codanna no longer silently reads an index it does not understand. An
+//! index written by a newer codanna, or one whose upgrade has no migration,
+//! is refused with [`IndexError::IncompatibleIndex`] naming both formats.
+//!
+//! Indexes written before `index.meta` existed are format 1.
+
+use super::generation::GENERATION_MARKER;
+use super::lock::{IndexLock, LockMode, LockWait};
+use super::tantivy::{IndexSchema, register_tokenizers};
+use super::{IndexMetadata, StorageResult};
+use crate::{IndexError, IndexResult};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use tantivy::{DocAddress, Index, IndexWriter, TantivyDocument};
+
+/// Format written by this build
+pub const INDEX_FORMAT_VERSION: u32 = 5;
+
+/// Writer heap used while copying an index into a new schema
+const REBUILD_HEAP_BYTES: usize = 50_000_000;
+
+/// An in-place upgrade from format `from` to `from + 1`
+#[derive(Debug)]
+pub struct Migration {
+    pub from: u32,
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Upgrade the index below the given index directory
+    pub apply: fn(&Path) -> StorageResult<()>,
+}
+
+/// A migration recorded in `index.meta` once applied
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppliedMigration {
+    pub name: String,
+    pub from: u32,
+    pub to: u32,
+    pub applied_at: u64,
+}
+
+/// Registered migrations, one per format step. A format change that cannot
+/// be applied in place has no entry, so older indexes must be rebuilt.
+pub static MIGRATIONS: &[Migration] = &[
+    Migration {
+        from: 1,
+        name: "rebuild-search-schema",
+        description: "copy the search index into the current schema (stemmed doc comments)",
+        apply: rebuild_search_schema,
+    },
+    Migration {
+        from: 2,
+        name: "add-exported-field",
+        description: "copy the search index into the current schema (exported flag); symbols read as not exported until reindexed",
+        apply: rebuild_search_schema,
+    },
+    Migration {
+        from: 3,
+        name: "add-tags-field",
+        description: "copy the search index into the current schema (symbol tags); symbols carry no tags until reindexed",
+        apply: rebuild_search_schema,
+    },
+    Migration {
+        from: 4,
+        name: "add-project-field",
+        description: "copy the search index into the current schema (monorepo projects); symbols belong to no project until reindexed",
+        apply: rebuild_search_schema,
+    },
+];
+
+/// Bring the index in `base` to [`INDEX_FORMAT_VERSION`].
+///
+/// Returns the migrations applied; none for a current index or a directory
+/// without one.
+pub fn migrate(base: &Path) -> IndexResult<Vec<&'static Migration>> {
+    let Some(found) = format_version(base)? else {
+        return Ok(Vec::new());
+    };
+    if plan(found)?.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // Another process may be upgrading it as well; whoever locks first does
+    let _lock = IndexLock::acquire(base, LockMode::Write, LockWait::default_wait())?;
+    let Some(found) = format_version(base)? else {
+        return Ok(Vec::new());
+    };
+    let mut metadata = IndexMetadata::load(base)?;
+    metadata.version = found;
+
+    let steps = plan(metadata.version)?;
+    for migration in &steps {
+        tracing::info!(
+            "[migration] upgrading index format {} to {}: {}",
+            migration.from,
+            migration.from + 1,
+            migration.description
+        );
+        (migration.apply)(base).map_err(|e| IndexError::IncompatibleIndex {
+            found: migration.from,
+            supported: INDEX_FORMAT_VERSION,
+            reason: format!("migration '{}' failed: {e}", migration.name),
+        })?;
+        metadata.version = migration.from + 1;
+        metadata.migrations.push(AppliedMigration {
+            name: migration.name.to_string(),
+            from: migration.from,
+            to: migration.from + 1,
+            applied_at: crate::indexing::get_utc_timestamp(),
+        });
+        // Saved after every step, so a failed later step resumes from here
+        metadata.save(base)?;
+    }
+    Ok(steps)
+}
+
+/// Format of the index in `base`, `None` when there is no index
+fn format_version(base: &Path) -> IndexResult<Option<u32>> {
+    if base.join("index.meta").exists() {
+        return Ok(Some(IndexMetadata::load(base)?.version));
+    }
+    Ok(base.join("tantivy").join("meta.json").exists().then_some(1))
+}
+
+/// The migrations from format `found` to the current one
+pub fn plan(found: u32) -> IndexResult<Vec<&'static Migration>> {
+    if found > INDEX_FORMAT_VERSION {
+        return Err(IndexError::IncompatibleIndex {
+            found,
+            supported: INDEX_FORMAT_VERSION,
+            reason: "the index was written by a newer codanna".to_string(),
+        });
+    }
+    (found..INDEX_FORMAT_VERSION)
+        .map(|from| {
+            MIGRATIONS
+                .iter()
+                .find(|migration| migration.from == from)
+                .ok_or_else(|| IndexError::IncompatibleIndex {
+                    found,
+                    supported: INDEX_FORMAT_VERSION,
+                    reason: format!("format {from} cannot be upgraded in place"),
+                })
+        })
+        .collect()
+}
+
+/// Copy every stored document of the Tantivy index into a new index with
+/// the current schema, then swap the two.
+///
+/// Fields are matched by name: fields new to the schema stay empty and
+/// removed ones are dropped. This covers changed tokenizers and indexing
+/// options, not fields whose type changed. All fields are stored, so
+/// nothing is lost. An index already in the current schema is left alone.
+fn rebuild_search_schema(base: &Path) -> StorageResult<()> {
+    let dir = base.join("tantivy");
+    let old = Index::open_in_dir(&dir)?;
+    let (schema, _) = IndexSchema::build();
+    let old_schema = old.schema();
+    if old_schema == schema {
+        return Ok(());
+    }
+
+    let staging = base.join("tantivy.migrating");
+    if staging.exists() {
+        fs::remove_dir_all(&staging)?;
+    }
+    fs::create_dir_all(&staging)?;
+    let new = Index::create_in_dir(&staging, schema.clone())?;
+    register_tokenizers(&new);
+    let mut writer: IndexWriter = new.writer(REBUILD_HEAP_BYTES)?;
+
+    let searcher = old.reader()?.searcher();
+    for (ordinal, segment) in searcher.segment_readers().iter().enumerate() {
+        for doc_id in segment.doc_ids_alive() {
+            let doc: TantivyDocument = searcher.doc(DocAddress::new(ordinal as u32, doc_id))?;
+            let mut copy = TantivyDocument::new();
+            for (field, value) in doc.field_values() {
+                if let Ok(target) = schema.get_field(old_schema.get_field_name(field)) {
+                    copy.add_field_value(target, value);
+                }
+            }
+            writer.add_document(copy)?;
+        }
+    }
+    writer.commit()?;
+    writer.wait_merging_threads()?;
+    drop(searcher);
+    drop(old);
+
+    let marker = dir.join(GENERATION_MARKER);
+    if marker.exists() {
+        fs::copy(&marker, staging.join(GENERATION_MARKER))?;
+    }
+    let retired = base.join("tantivy.retired");
+    if retired.exists() {
+        fs::remove_dir_all(&retired)?;
+    }
+    fs::rename(&dir, &retired)?;
+    fs::rename(&staging, &dir)?;
+    fs::remove_dir_all(&retired)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tantivy::schema::{STORED, STRING, SchemaBuilder, TEXT};
+    use tantivy::{doc, schema::Schema};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_plan_covers_every_step_or_refuses() {
+        assert!(plan(INDEX_FORMAT_VERSION).unwrap().is_empty());
+        let steps = plan(1).unwrap();
+        assert_eq!(steps.len() as u32, INDEX_FORMAT_VERSION - 1);
+        assert_eq!(steps[0].from, 1);
+
+        let newer = plan(INDEX_FORMAT_VERSION + 1).unwrap_err();
+        assert!(matches!(
+            newer,
+            IndexError::IncompatibleIndex { found, supported: INDEX_FORMAT_VERSION, .. }
+                if found == INDEX_FORMAT_VERSION + 1
+        ));
+        assert!(matches!(
+            plan(0),
+            Err(IndexError::IncompatibleIndex { found: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn test_legacy_index_is_rebuilt_in_the_current_schema() {
+        let temp_dir = TempDir::new().unwrap();
+        let base = temp_dir.path();
+        let tantivy_dir = base.join("tantivy");
+        fs::create_dir_all(&tantivy_dir).unwrap();
+
+        // Format 1 tokenized doc comments like any other text
+        let mut builder = SchemaBuilder::default();
+        let doc_type = builder.add_text_field("doc_type", STRING | STORED);
+        let name = builder.add_text_field("name", STRING | STORED);
+        let doc_comment = builder.add_text_field("doc_comment", TEXT | STORED);
+        let legacy: Schema = builder.build();
+        let index = Index::create_in_dir(&tantivy_dir, legacy).unwrap();
+        let mut writer: IndexWriter = index.writer(15_000_000).unwrap();
+        for symbol in ["parse", "retry"] {
+            writer
+                .add_document(doc!(
+                    doc_type => "symbol",
+                    name => symbol,
+                    doc_comment => "Retries the request"
+                ))
+                .unwrap();
+        }
+        writer.commit().unwrap();
+        drop(writer);
+        drop(index);
+
+        let applied = migrate(base).unwrap();
+        assert_eq!(applied.len() as u32, INDEX_FORMAT_VERSION - 1);
+
+        let index = Index::open_in_dir(&tantivy_dir).unwrap();
+        assert_eq!(index.schema(), IndexSchema::build().0);
+        assert_eq!(index.reader().unwrap().searcher().num_docs(), 2);
+
+        let metadata = IndexMetadata::load(base).unwrap();
+        assert_eq!(metadata.version, INDEX_FORMAT_VERSION);
+        assert_eq!(metadata.migrations[0].name, "rebuild-search-schema");
+        assert!(migrate(base).unwrap().is_empty());
+    }
+}
@@ -1,11 +1,21 @@
+pub mod artifact;
 pub mod error;
+pub mod generation;
+pub mod lock;
 pub mod memory;
 pub mod metadata;
 pub mod metadata_keys;
+pub mod migration;
 pub mod persistence;
 pub mod tantivy;
+pub mod verify;
+pub mod wal;
 pub use error::{StorageError, StorageResult};
+pub use generation::WriteScope;
+pub use lock::{IndexLock, LockMode, LockWait};
 pub use metadata::{DataSource, IndexMetadata};
 pub use metadata_keys::MetadataKey;
+pub use migration::{INDEX_FORMAT_VERSION, migrate};
 pub use persistence::IndexPersistence;
 pub use tantivy::{DocumentIndex, SearchResult};
+pub use verify::{VerifyReport, verify_index};
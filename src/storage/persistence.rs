@@ -4,6 +4,7 @@
 //! All actual data is stored in Tantivy.
 
 use crate::indexing::facade::IndexFacade;
+use crate::storage::lock::{IndexLock, LockMode, LockWait};
 use crate::storage::{DataSource, IndexMetadata};
 use crate::{IndexError, IndexResult, Settings};
 use std::path::PathBuf;
@@ -50,9 +51,6 @@ impl IndexPersistence {
         settings: Arc<Settings>,
         load_semantic: bool,
     ) -> IndexResult<IndexFacade> {
-        // Load metadata to understand data sources
-        let metadata = IndexMetadata::load(&self.base_path).ok();
-
         // Check if Tantivy index exists
         let tantivy_path = self.base_path.join("tantivy");
         if !tantivy_path.join("meta.json").exists() {
@@ -65,6 +63,23 @@ impl IndexPersistence {
             });
         }
 
+        // Upgrade an index written in an older format before opening it
+        for migration in super::migrate(&self.base_path)? {
+            tracing::info!(
+                target: "storage",
+                "Upgraded index from format {} to {}: {}",
+                migration.from,
+                migration.from + 1,
+                migration.description
+            );
+        }
+
+        // Keep other processes from rewriting the index while it is opened
+        let lock = IndexLock::acquire(&self.base_path, LockMode::Read, LockWait::default_wait())?;
+
+        // Load metadata to understand data sources, as left by the migrations
+        let metadata = IndexMetadata::load(&self.base_path).ok();
+
         // Create IndexFacade - it will open the existing Tantivy index
         let mut facade = IndexFacade::new(settings)?;
 
@@ -125,12 +140,29 @@ impl IndexPersistence {
             }
         }
 
+        // Recovery may take the write lock, which waits for every reader to
+        // leave; two loads each holding a read lock would wait for each other
+        drop(lock);
+
+        // Redo updates a crashed writer left half applied
+        match facade.recover() {
+            Ok(0) => {}
+            Ok(redone) => {
+                tracing::warn!("[persistence] redid {redone} interrupted index updates");
+            }
+            Err(e) => {
+                tracing::warn!("[persistence] failed to recover interrupted updates: {e}");
+            }
+        }
+
         Ok(facade)
     }
 
     /// Save metadata for an IndexFacade
     #[must_use = "Save errors should be handled to ensure data is persisted"]
     pub fn save_facade(&self, facade: &IndexFacade) -> IndexResult<()> {
+        let _lock = IndexLock::acquire(&self.base_path, LockMode::Write, LockWait::default_wait())?;
+
         // Update metadata
         let mut metadata =
             IndexMetadata::load(&self.base_path).unwrap_or_else(|_| IndexMetadata::new());
@@ -188,6 +220,13 @@ impl IndexPersistence {
     pub fn clear(&self) -> Result<(), std::io::Error> {
         let tantivy_path = self.base_path.join("tantivy");
         if tantivy_path.exists() {
+            // Never delete an index another process is reading or writing
+            let _lock =
+                IndexLock::acquire(&self.base_path, LockMode::Write, LockWait::default_wait())
+                    .map_err(|e| {
+                        std::io::Error::new(std::io::ErrorKind::WouldBlock, e.to_string())
+                    })?;
+
             // On Windows, we may need multiple attempts due to file locking
             let mut attempts = 0;
             const MAX_ATTEMPTS: u32 = 3;
@@ -229,6 +268,15 @@ impl IndexPersistence {
             // Recreate the empty tantivy directory after clearing
             std::fs::create_dir_all(&tantivy_path)?;
 
+            // The rebuilt index is written in the current format
+            if let Ok(mut metadata) = IndexMetadata::load(&self.base_path) {
+                metadata.version = super::INDEX_FORMAT_VERSION;
+                metadata.migrations.clear();
+                if let Err(e) = metadata.save(&self.base_path) {
+                    tracing::warn!("[persistence] failed to reset index format version: {e}");
+                }
+            }
+
             // On Windows, add extra delay after recreating directory to ensure filesystem is ready
             #[cfg(windows)]
             {
@@ -3,8 +3,11 @@
 //! This module provides rich full-text search capabilities using Tantivy,
 //! enabling semantic search across documentation, code, and symbols.
 
+use super::generation::{Generations, WriteScope};
 use super::{MetadataKey, StorageError, StorageResult};
+use crate::parsing::{SignatureStyle, display_signature};
 use crate::relationship::RelationshipMetadata;
+use crate::symbol::SymbolRef;
 use crate::vector::{ClusterId, EmbeddingGenerator, SegmentOrdinal, VectorId, VectorSearchEngine};
 use crate::{FileId, RelationKind, Relationship, SymbolId, SymbolKind};
 use serde::{Deserialize, Serialize};
@@ -14,9 +17,10 @@ use std::sync::Mutex;
 use std::sync::{Arc, RwLock};
 use tantivy::DocId;
 use tantivy::{
-    Index, IndexReader, IndexSettings, IndexWriter, ReloadPolicy, TantivyDocument as Document,
-    Term,
+    Index, IndexReader, IndexSettings, IndexWriter, ReloadPolicy, Searcher, SegmentReader,
+    TantivyDocument as Document, Term,
     collector::TopDocs,
+    columnar::{Column, StrColumn},
     directory::MmapDirectory,
     query::{BooleanQuery, FuzzyTermQuery, Occur, Query, QueryParser, TermQuery},
     schema::{
@@ -47,6 +51,11 @@ pub struct IndexSchema {
     pub end_column: Field,
     pub context: Field,
     pub visibility: Field,
+    pub exported: Field,
+    /// Labels from `[[tags]]` rules, one value per tag
+    pub tags: Field,
+    /// Monorepo project of the symbol's file
+    pub project: Field,
     pub scope_context: Field,
     pub language: Field, // Language identifier for the symbol
 
@@ -80,6 +89,10 @@ pub struct IndexSchema {
     pub import_alias: Field,        // Optional alias
     pub import_is_glob: Field,      // Boolean (0/1) for glob imports
     pub import_is_type_only: Field, // Boolean (0/1) for type-only imports (TypeScript)
+    pub import_line: Field,         // Range of the import statement, when recorded
+    pub import_column: Field,
+    pub import_end_line: Field,
+    pub import_end_column: Field,
 }
 
 impl IndexSchema {
@@ -98,11 +111,11 @@ impl IndexSchema {
 
         // Symbol fields (existing)
         let symbol_id = builder.add_u64_field("symbol_id", indexed_u64_options.clone());
-        let file_path = builder.add_text_field("file_path", STRING | STORED);
+        let file_path = builder.add_text_field("file_path", STRING | STORED | FAST);
         let line_number = builder.add_u64_field("line_number", indexed_u64_options.clone());
-        let column = builder.add_u64_field("column", STORED);
+        let column = builder.add_u64_field("column", STORED | FAST);
         let end_line = builder.add_u64_field("end_line", STORED | FAST);
-        let end_column = builder.add_u64_field("end_column", STORED);
+        let end_column = builder.add_u64_field("end_column", STORED | FAST);
 
         // Text fields for search
         let text_options = TextOptions::default()
@@ -128,14 +141,26 @@ impl IndexSchema {
             .set_stored();
         let name_text = builder.add_text_field("name_text", ngram_text_options);
 
-        let doc_comment = builder.add_text_field("doc_comment", text_options.clone());
+        // Doc comments are prose, so they are stemmed: "retries" finds a symbol
+        // documented as "retry on failure". `en_stem` is built into Tantivy.
+        let doc_comment_options = TextOptions::default()
+            .set_indexing_options(
+                TextFieldIndexing::default()
+                    .set_tokenizer("en_stem")
+                    .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+            )
+            .set_stored();
+        let doc_comment = builder.add_text_field("doc_comment", doc_comment_options);
         let signature = builder.add_text_field("signature", text_options.clone());
         let context = builder.add_text_field("context", text_options.clone());
 
         // String fields for filtering (using STRING for exact match)
         let module_path = builder.add_text_field("module_path", STRING | STORED);
-        let kind = builder.add_text_field("kind", STRING | STORED);
+        let kind = builder.add_text_field("kind", STRING | STORED | FAST);
         let visibility = builder.add_u64_field("visibility", STORED);
+        let exported = builder.add_u64_field("exported", STORED);
+        let tags = builder.add_text_field("tags", STRING | STORED);
+        let project = builder.add_text_field("project", STRING | STORED);
         let scope_context = builder.add_text_field("scope_context", STRING | STORED);
         let language = builder.add_text_field("language", STRING | STORED | FAST);
 
@@ -169,6 +194,10 @@ impl IndexSchema {
         let import_alias = builder.add_text_field("import_alias", STRING | STORED);
         let import_is_glob = builder.add_u64_field("import_is_glob", STORED);
         let import_is_type_only = builder.add_u64_field("import_is_type_only", STORED);
+        let import_line = builder.add_u64_field("import_line", STORED);
+        let import_column = builder.add_u64_field("import_column", STORED);
+        let import_end_line = builder.add_u64_field("import_end_line", STORED);
+        let import_end_column = builder.add_u64_field("import_end_column", STORED);
 
         let schema = builder.build();
         let index_schema = IndexSchema {
@@ -187,6 +216,9 @@ impl IndexSchema {
             end_column,
             context,
             visibility,
+            exported,
+            tags,
+            project,
             scope_context,
             language,
             from_symbol_id,
@@ -210,12 +242,24 @@ impl IndexSchema {
             import_alias,
             import_is_glob,
             import_is_type_only,
+            import_line,
+            import_column,
+            import_end_line,
+            import_end_column,
         };
 
         (schema, index_schema)
     }
 }
 
+/// Register the tokenizers the schema uses beyond Tantivy's built-in ones
+pub(crate) fn register_tokenizers(index: &Index) {
+    // Register custom tokenizer for partial matching (ngram with min_gram=3, max_gram=10)
+    // This allows "Archive" to match "ArchiveAppService"
+    let ngram_tokenizer = TextAnalyzer::builder(NgramTokenizer::new(3, 10, false).unwrap()).build();
+    index.tokenizers().register("ngram", ngram_tokenizer);
+}
+
 /// Metadata for tracking vector-related information per document
 #[derive(Debug, Clone, PartialEq)]
 pub struct VectorMetadata {
@@ -417,6 +461,10 @@ pub struct DocumentIndex {
     pending_symbol_counter: Mutex<Option<u32>>,
     /// Pending file counter during batch operations
     pending_file_counter: Mutex<Option<u32>>,
+    /// Searcher queries see, replaced when an update is published
+    generations: Generations,
+    /// How search results display signatures
+    signature_style: SignatureStyle,
 }
 
 impl std::fmt::Debug for DocumentIndex {
@@ -467,11 +515,7 @@ impl DocumentIndex {
             Index::create(dir, schema, IndexSettings::default())?
         };
 
-        // Register custom tokenizer for partial matching (ngram with min_gram=3, max_gram=10)
-        // This allows "Archive" to match "ArchiveAppService"
-        let ngram_tokenizer =
-            TextAnalyzer::builder(NgramTokenizer::new(3, 10, false).unwrap()).build();
-        index.tokenizers().register("ngram", ngram_tokenizer);
+        register_tokenizers(&index);
 
         let reader = index
             .reader_builder()
@@ -482,6 +526,7 @@ impl DocumentIndex {
         if index_path.join("meta.json").exists() {
             reader.reload()?;
         }
+        let generations = Generations::new(reader.searcher(), &index_path);
 
         Ok(Self {
             index,
@@ -498,9 +543,63 @@ impl DocumentIndex {
             pending_embeddings: Mutex::new(Vec::new()),
             pending_symbol_counter: Mutex::new(None),
             pending_file_counter: Mutex::new(None),
+            generations,
+            signature_style: settings.display.signature_style(),
         })
     }
 
+    /// Searcher for a query, see [`super::generation`]
+    fn searcher(&self) -> Searcher {
+        self.generations.searcher(|| self.reader.searcher())
+    }
+
+    /// Identifies the snapshot queries read; it changes whenever they would
+    /// see different segments
+    pub fn searcher_generation(&self) -> u64 {
+        self.searcher().generation().generation_id()
+    }
+
+    pub(crate) fn generations(&self) -> &Generations {
+        &self.generations
+    }
+
+    /// Claim the writer's view of the index until the returned scope is
+    /// dropped, then publish what was written as one generation
+    pub fn write_scope(self: &Arc<Self>) -> WriteScope {
+        WriteScope::new(Arc::clone(self))
+    }
+
+    /// Hold the published generation: queries keep seeing it until every
+    /// hold is released with [`Self::publish_generation`]
+    pub fn begin_generation(&self) {
+        self.generations.hold();
+    }
+
+    /// Release a hold from [`Self::begin_generation`]; the last one swaps in
+    /// everything committed since. Returns the published generation.
+    pub fn publish_generation(&self) -> StorageResult<u64> {
+        if self.generations.release() {
+            return self
+                .generations
+                .publish(self.reader.searcher(), &self.index_path);
+        }
+        Ok(self.generations.current())
+    }
+
+    /// Number of the generation queries currently see
+    pub fn generation(&self) -> u64 {
+        self.generations.current()
+    }
+
+    /// Publish a reload straight away unless a generation is held
+    fn reloaded(&self) -> StorageResult<()> {
+        if self.generations.is_free() {
+            self.generations
+                .publish(self.reader.searcher(), &self.index_path)?;
+        }
+        Ok(())
+    }
+
     /// Create index writer with retry logic for transient errors
     fn create_writer_with_retry(&self) -> Result<IndexWriter<Document>, tantivy::TantivyError> {
         for attempt in 0..self.max_retry_attempts {
@@ -648,7 +747,7 @@ impl DocumentIndex {
             return Ok(());
         }
 
-        let searcher = self.reader.searcher();
+        let searcher = self.searcher();
         let generation = searcher.segment_readers().len() as u64; // Simple generation tracking
 
         // Check if cache is already valid
@@ -772,6 +871,7 @@ impl DocumentIndex {
     pub fn reload_and_warm(&self) -> StorageResult<()> {
         // Reload the reader to see latest changes
         self.reader.reload()?;
+        self.reloaded()?;
 
         // Warm the cluster cache if vector support is enabled
         if self.has_vector_support() {
@@ -816,7 +916,7 @@ impl DocumentIndex {
         }
 
         // Get the current searcher
-        let searcher = self.reader.searcher();
+        let searcher = self.searcher();
 
         // Perform batch update
         self.start_batch()?;
@@ -908,6 +1008,9 @@ impl DocumentIndex {
         module_path: &str,
         context: Option<&str>,
         visibility: crate::Visibility,
+        exported: bool,
+        tags: &[Box<str>],
+        project: Option<&str>,
         scope_context: Option<crate::ScopeContext>,
         language_id: Option<&str>, // Language identifier for the symbol
     ) -> StorageResult<()> {
@@ -942,6 +1045,13 @@ impl DocumentIndex {
         doc.add_text(self.schema.module_path, module_path);
         doc.add_text(self.schema.kind, format!("{kind:?}"));
         doc.add_u64(self.schema.visibility, visibility as u64);
+        doc.add_u64(self.schema.exported, exported as u64);
+        for tag in tags {
+            doc.add_text(self.schema.tags, tag);
+        }
+        if let Some(project) = project {
+            doc.add_text(self.schema.project, project);
+        }
 
         // Store scope_context as a string (serialized enum)
         if let Some(scope) = scope_context {
@@ -1023,6 +1133,7 @@ impl DocumentIndex {
 
             // Reload the reader to see new documents
             self.reader.reload()?;
+            self.reloaded()?;
 
             // Clear the pending symbol counter after commit
             if let Ok(mut pending_guard) = self.pending_symbol_counter.lock() {
@@ -1062,6 +1173,7 @@ impl DocumentIndex {
             writer.delete_term(term);
             writer.commit()?;
             self.reader.reload()?;
+            self.reloaded()?;
         }
 
         Ok(())
@@ -1076,7 +1188,7 @@ impl DocumentIndex {
         module_filter: Option<&str>,
         language_filter: Option<&str>,
     ) -> StorageResult<Vec<SearchResult>> {
-        let searcher = self.reader.searcher();
+        let searcher = self.searcher();
 
         let query_parser = QueryParser::for_index(
             &self.index,
@@ -1230,10 +1342,20 @@ impl DocumentIndex {
                 .and_then(|v| v.as_str())
                 .map(|s| s.to_string());
 
+            let language = doc
+                .get_first(self.schema.language)
+                .and_then(|v| v.as_str())
+                .filter(|s| !s.is_empty())
+                .and_then(|lang_str| {
+                    crate::parsing::get_registry()
+                        .lock()
+                        .ok()
+                        .and_then(|registry| registry.find_language_id(lang_str))
+                });
             let signature = doc
                 .get_first(self.schema.signature)
                 .and_then(|v| v.as_str())
-                .map(|s| s.to_string());
+                .map(|s| display_signature(s, language, self.signature_style));
 
             let context = doc
                 .get_first(self.schema.context)
@@ -1284,7 +1406,7 @@ impl DocumentIndex {
 
     /// Get total number of indexed documents
     pub fn document_count(&self) -> StorageResult<u64> {
-        let searcher = self.reader.searcher();
+        let searcher = self.searcher();
         Ok(searcher.num_docs())
     }
 
@@ -1301,12 +1423,52 @@ impl DocumentIndex {
         writer.delete_all_documents()?;
         writer.commit()?;
         self.reader.reload()?;
+        self.reloaded()?;
         Ok(())
     }
 
+    /// Distinct symbol names starting with `prefix`, in byte order
+    ///
+    /// Reads the term dictionary of the name field and loads no documents,
+    /// so it is cheap enough to run on every keystroke. Names of symbols
+    /// deleted since the last merge may still be listed.
+    pub fn symbol_names_with_prefix(
+        &self,
+        prefix: &str,
+        limit: usize,
+    ) -> StorageResult<Vec<String>> {
+        let searcher = self.searcher();
+        let mut names = std::collections::BTreeSet::new();
+
+        for segment_reader in searcher.segment_readers() {
+            let inverted_index = segment_reader.inverted_index(self.schema.name)?;
+            let mut terms = inverted_index
+                .terms()
+                .range()
+                .ge(prefix.as_bytes())
+                .into_stream()
+                .map_err(tantivy::TantivyError::from)?;
+            // Each segment's first `limit` matches cover the first `limit`
+            // of the union
+            let mut taken = 0;
+            while taken < limit && terms.advance() {
+                let key = terms.key();
+                if !key.starts_with(prefix.as_bytes()) {
+                    break;
+                }
+                if let Ok(name) = std::str::from_utf8(key) {
+                    names.insert(name.to_string());
+                    taken += 1;
+                }
+            }
+        }
+
+        Ok(names.into_iter().take(limit).collect())
+    }
+
     /// Find a symbol by its ID
     pub fn find_symbol_by_id(&self, id: SymbolId) -> StorageResult<Option<crate::Symbol>> {
-        let searcher = self.reader.searcher();
+        let searcher = self.searcher();
         let query = TermQuery::new(
             Term::from_field_u64(self.schema.symbol_id, id.0 as u64),
             IndexRecordOption::Basic,
@@ -1328,7 +1490,7 @@ impl DocumentIndex {
         id: SymbolId,
         language: &str,
     ) -> StorageResult<Option<crate::Symbol>> {
-        let searcher = self.reader.searcher();
+        let searcher = self.searcher();
 
         // Build a compound query: symbol_id AND language
         let query = BooleanQuery::from(vec![
@@ -1364,8 +1526,58 @@ impl DocumentIndex {
         name: &str,
         language_filter: Option<&str>,
     ) -> StorageResult<Vec<crate::Symbol>> {
-        let searcher = self.reader.searcher();
+        let searcher = self.searcher();
+        let top_docs = searcher.search(
+            &self.symbol_name_query(name, language_filter),
+            &TopDocs::with_limit(100),
+        )?;
+        let mut symbols = Vec::new();
+
+        for (_score, doc_address) in top_docs {
+            let doc = searcher.doc::<Document>(doc_address)?;
+            symbols.push(self.document_to_symbol(&doc)?);
+        }
+
+        Ok(symbols)
+    }
+
+    /// Find symbols by name, reading only their identity and location
+    ///
+    /// Matches what [`find_symbols_by_name`](Self::find_symbols_by_name)
+    /// finds from the fast-field columns, without loading stored documents.
+    pub fn find_symbol_refs_by_name(
+        &self,
+        name: &str,
+        language_filter: Option<&str>,
+    ) -> StorageResult<Vec<SymbolRef>> {
+        let searcher = self.searcher();
+        let top_docs = searcher.search(
+            &self.symbol_name_query(name, language_filter),
+            &TopDocs::with_limit(100),
+        )?;
+        let mut refs = Vec::with_capacity(top_docs.len());
+        let mut segments: HashMap<u32, Option<SymbolRefColumns>> = HashMap::new();
+
+        for (_score, doc_address) in top_docs {
+            let columns = segments.entry(doc_address.segment_ord).or_insert_with(|| {
+                SymbolRefColumns::open(searcher.segment_reader(doc_address.segment_ord))
+            });
+            let symbol_ref = match columns
+                .as_ref()
+                .and_then(|columns| columns.read(doc_address.doc_id, name))
+            {
+                Some(symbol_ref) => symbol_ref,
+                // Segments written before these fields were fast
+                None => self.document_to_symbol_ref(&searcher.doc::<Document>(doc_address)?)?,
+            };
+            refs.push(symbol_ref);
+        }
+
+        Ok(refs)
+    }
 
+    /// Query for the symbols with exactly `name`, optionally in one language
+    fn symbol_name_query(&self, name: &str, language_filter: Option<&str>) -> BooleanQuery {
         // Use exact term matching for symbol names (name field is STRING type, not TEXT)
         // This prevents tokenization issues that cause "MyService" to match "Main"
         let name_query = Box::new(TermQuery::new(
@@ -1396,17 +1608,73 @@ impl DocumentIndex {
             ));
         }
 
-        let final_query = BooleanQuery::new(query_clauses);
+        BooleanQuery::new(query_clauses)
+    }
 
-        let top_docs = searcher.search(&final_query, &TopDocs::with_limit(100))?;
-        let mut symbols = Vec::new();
+    /// Find symbols by any of several names with one search
+    ///
+    /// Returns the matches grouped by name; names without matches are absent.
+    pub fn find_symbols_by_names(
+        &self,
+        names: &[&str],
+        language_filter: Option<&str>,
+    ) -> StorageResult<HashMap<String, Vec<crate::Symbol>>> {
+        let mut by_name: HashMap<String, Vec<crate::Symbol>> = HashMap::new();
+        if names.is_empty() {
+            return Ok(by_name);
+        }
+        let searcher = self.searcher();
+
+        let name_clauses: Vec<(Occur, Box<dyn Query>)> = names
+            .iter()
+            .map(|name| {
+                (
+                    Occur::Should,
+                    Box::new(TermQuery::new(
+                        Term::from_field_text(self.schema.name, name),
+                        IndexRecordOption::Basic,
+                    )) as Box<dyn Query>,
+                )
+            })
+            .collect();
+        let mut query_clauses = vec![
+            (
+                Occur::Must,
+                Box::new(BooleanQuery::new(name_clauses)) as Box<dyn Query>,
+            ),
+            (
+                Occur::Must,
+                Box::new(TermQuery::new(
+                    Term::from_field_text(self.schema.doc_type, "symbol"),
+                    IndexRecordOption::Basic,
+                )) as Box<dyn Query>,
+            ),
+        ];
+        if let Some(lang) = language_filter {
+            query_clauses.push((
+                Occur::Must,
+                Box::new(TermQuery::new(
+                    Term::from_field_text(self.schema.language, lang),
+                    IndexRecordOption::Basic,
+                )),
+            ));
+        }
 
+        // Same per-name limit as find_symbols_by_name
+        let limit = 100 * names.len();
+        let top_docs = searcher.search(
+            &BooleanQuery::new(query_clauses),
+            &TopDocs::with_limit(limit),
+        )?;
         for (_score, doc_address) in top_docs {
             let doc = searcher.doc::<Document>(doc_address)?;
-            symbols.push(self.document_to_symbol(&doc)?);
+            let symbol = self.document_to_symbol(&doc)?;
+            by_name
+                .entry(symbol.name.to_string())
+                .or_default()
+                .push(symbol);
         }
-
-        Ok(symbols)
+        Ok(by_name)
     }
 
     /// Find a symbol by name, file, and range
@@ -1419,7 +1687,7 @@ impl DocumentIndex {
         file_id: FileId,
         range: &crate::Range,
     ) -> StorageResult<Option<crate::Symbol>> {
-        let searcher = self.reader.searcher();
+        let searcher = self.searcher();
 
         // Query by name, file_id, and start line
         let query = BooleanQuery::from(vec![
@@ -1469,7 +1737,7 @@ impl DocumentIndex {
 
     /// Find symbols by file ID
     pub fn find_symbols_by_file(&self, file_id: FileId) -> StorageResult<Vec<crate::Symbol>> {
-        let searcher = self.reader.searcher();
+        let searcher = self.searcher();
         let query = BooleanQuery::from(vec![
             (
                 Occur::Must,
@@ -1503,7 +1771,7 @@ impl DocumentIndex {
     /// Used for same-package symbol resolution (Java, Kotlin, etc.)
     /// Returns all symbols that have the specified module_path.
     pub fn find_symbols_by_module(&self, module_path: &str) -> StorageResult<Vec<crate::Symbol>> {
-        let searcher = self.reader.searcher();
+        let searcher = self.searcher();
 
         let query = BooleanQuery::from(vec![
             (
@@ -1533,9 +1801,59 @@ impl DocumentIndex {
         Ok(symbols)
     }
 
+    /// Find symbols carrying failure-point annotations (`assert`, `throw`, `abort`)
+    pub fn find_annotated_symbols(&self) -> StorageResult<Vec<crate::Symbol>> {
+        let searcher = self.searcher();
+
+        // Every stored annotation line starts with its kind
+        let kinds: Vec<(Occur, Box<dyn Query>)> = [
+            crate::symbol::AnnotationKind::Assert,
+            crate::symbol::AnnotationKind::Throw,
+            crate::symbol::AnnotationKind::Abort,
+        ]
+        .iter()
+        .map(|kind| {
+            (
+                Occur::Should,
+                Box::new(TermQuery::new(
+                    Term::from_field_text(self.schema.context, kind.as_str()),
+                    IndexRecordOption::Basic,
+                )) as Box<dyn Query>,
+            )
+        })
+        .collect();
+
+        let query = BooleanQuery::from(vec![
+            (
+                Occur::Must,
+                Box::new(TermQuery::new(
+                    Term::from_field_text(self.schema.doc_type, "symbol"),
+                    IndexRecordOption::Basic,
+                )) as Box<dyn Query>,
+            ),
+            (
+                Occur::Must,
+                Box::new(BooleanQuery::from(kinds)) as Box<dyn Query>,
+            ),
+        ]);
+
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(10000))?;
+        let mut symbols = Vec::new();
+
+        for (_score, doc_address) in top_docs {
+            let doc = searcher.doc::<Document>(doc_address)?;
+            let symbol = self.document_to_symbol(&doc)?;
+            if !symbol.annotations.is_empty() {
+                symbols.push(symbol);
+            }
+        }
+
+        Ok(symbols)
+    }
+
     /// Get all symbols (use with caution on large indexes)
     pub fn get_all_symbols(&self, limit: usize) -> StorageResult<Vec<crate::Symbol>> {
-        let searcher = self.reader.searcher();
+        let searcher = self.searcher();
 
         // Use pre-filtering query instead of AllQuery + post-filtering
         // This matches the pattern used in find_symbols_by_name and find_symbols_by_file
@@ -1559,6 +1877,64 @@ impl DocumentIndex {
         Ok(symbols)
     }
 
+    /// Visit every symbol in the index without a result limit
+    ///
+    /// Symbols are streamed in index order, so callers can export large
+    /// indexes without materializing them all at once.
+    pub fn for_each_symbol<F>(&self, mut visit: F) -> StorageResult<usize>
+    where
+        F: FnMut(crate::Symbol) -> StorageResult<()>,
+    {
+        let searcher = self.searcher();
+        let query = TermQuery::new(
+            Term::from_field_text(self.schema.doc_type, "symbol"),
+            IndexRecordOption::Basic,
+        );
+
+        let mut addresses: Vec<_> = searcher
+            .search(&query, &tantivy::collector::DocSetCollector)?
+            .into_iter()
+            .collect();
+        addresses.sort();
+
+        for doc_address in &addresses {
+            let doc = searcher.doc::<Document>(*doc_address)?;
+            visit(self.document_to_symbol(&doc)?)?;
+        }
+
+        Ok(addresses.len())
+    }
+
+    /// Convert a Tantivy document to a SymbolRef, leaving the heavy fields
+    /// unread; for segments without the [`SymbolRefColumns`]
+    fn document_to_symbol_ref(&self, doc: &Document) -> StorageResult<SymbolRef> {
+        let number = |field: Field| doc.get_first(field).and_then(|v| v.as_u64());
+        let text = |field: Field| doc.get_first(field).and_then(|v| v.as_str());
+        let missing = |field: &str| StorageError::InvalidFieldValue {
+            field: field.to_string(),
+            reason: "missing from document".to_string(),
+        };
+
+        let start_line = number(self.schema.line_number).unwrap_or(0) as u32;
+        Ok(SymbolRef {
+            id: SymbolId(number(self.schema.symbol_id).ok_or_else(|| missing("symbol_id"))? as u32),
+            name: text(self.schema.name)
+                .ok_or_else(|| missing("name"))?
+                .into(),
+            kind: SymbolKind::from_str_with_default(
+                text(self.schema.kind).ok_or_else(|| missing("kind"))?,
+            ),
+            file_id: FileId(number(self.schema.file_id).ok_or_else(|| missing("file_id"))? as u32),
+            file_path: text(self.schema.file_path).unwrap_or("<unknown>").into(),
+            range: crate::Range {
+                start_line,
+                start_column: number(self.schema.column).unwrap_or(0) as u16,
+                end_line: number(self.schema.end_line).map_or(start_line, |line| line as u32),
+                end_column: number(self.schema.end_column).unwrap_or(0) as u16,
+            },
+        })
+    }
+
     /// Convert a Tantivy document to a Symbol
     fn document_to_symbol(&self, doc: &Document) -> StorageResult<crate::Symbol> {
         use crate::{Range, Symbol, SymbolKind, Visibility};
@@ -1641,6 +2017,8 @@ impl DocumentIndex {
                 1 => Visibility::Crate,
                 2 => Visibility::Module,
                 3 => Visibility::Private,
+                4 => Visibility::Protected,
+                5 => Visibility::Package,
                 _ => Visibility::Private,
             })
             .unwrap_or(Visibility::Private);
@@ -1671,7 +2049,7 @@ impl DocumentIndex {
                         Some(crate::ScopeContext::ClassMember { class_name })
                     }
                     s if s.starts_with("Local") => {
-                        // Handle Local { hoisted: bool, parent_name: Option<String>, parent_kind: Option<SymbolKind> } format
+                        // Handle Local { hoisted: bool, parent_name: Option<String>, parent_kind: Option<SymbolKind>, depth: u16, ancestors: Vec<SymbolKind> } format
                         let hoisted = s.contains("hoisted: true") || s.contains("hoisted:true");
 
                         // Extract parent_name if present
@@ -1687,21 +2065,39 @@ impl DocumentIndex {
                         let parent_kind = if s.contains("parent_kind: Some(") {
                             let start = s.find("parent_kind: Some(").map(|i| i + 18)?;
                             let end = s[start..].find(')').map(|i| start + i)?;
-                            let kind_str = &s[start..end];
-                            match kind_str {
-                                "Function" => Some(crate::SymbolKind::Function),
-                                "Class" => Some(crate::SymbolKind::Class),
-                                "Method" => Some(crate::SymbolKind::Method),
-                                _ => None,
-                            }
+                            s[start..end].parse::<SymbolKind>().ok()
                         } else {
                             None
                         };
 
+                        // Depth and ancestor chain are absent in documents indexed before they existed
+                        let depth = s
+                            .find("depth: ")
+                            .map(|i| &s[i + 7..])
+                            .and_then(|rest| {
+                                let end = rest
+                                    .find(|c: char| !c.is_ascii_digit())
+                                    .unwrap_or(rest.len());
+                                rest[..end].parse::<u16>().ok()
+                            })
+                            .unwrap_or(0);
+                        let ancestors = s
+                            .find("ancestors: [")
+                            .map(|i| &s[i + 12..])
+                            .and_then(|rest| rest.split(']').next())
+                            .map(|list| {
+                                list.split(", ")
+                                    .filter_map(|kind| kind.parse::<SymbolKind>().ok())
+                                    .collect()
+                            })
+                            .unwrap_or_default();
+
                         Some(crate::ScopeContext::Local {
                             hoisted,
                             parent_name,
                             parent_kind,
+                            depth,
+                            ancestors,
                         })
                     }
                     _ => None,
@@ -1728,6 +2124,10 @@ impl DocumentIndex {
             doc_comment: doc_comment.map(|s| s.into()),
             module_path: module_path.map(|s| s.into()),
             visibility,
+            exported: doc
+                .get_first(self.schema.exported)
+                .and_then(|v| v.as_u64())
+                .is_some_and(|v| v == 1),
             scope_context,
             language_id: {
                 // Read the language field from the document and convert to LanguageId
@@ -1745,13 +2145,31 @@ impl DocumentIndex {
                             .and_then(|registry| registry.find_language_id(lang_str))
                     })
             },
+            // Annotations are stored one per line in the context field
+            annotations: doc
+                .get_first(self.schema.context)
+                .and_then(|v| v.as_str())
+                .map(|s| {
+                    s.lines()
+                        .filter_map(crate::symbol::Annotation::parse)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            tags: doc
+                .get_all(self.schema.tags)
+                .filter_map(|v| v.as_str().map(Box::from))
+                .collect(),
+            project: doc
+                .get_first(self.schema.project)
+                .and_then(|v| v.as_str())
+                .map(Box::from),
         })
     }
 
     /// Get file info by path
     /// Returns (file_id, hash, mtime). Mtime is 0 for legacy entries without mtime.
     pub fn get_file_info(&self, path: &str) -> StorageResult<Option<(FileId, String, u64)>> {
-        let searcher = self.reader.searcher();
+        let searcher = self.searcher();
         let query = BooleanQuery::from(vec![
             (
                 Occur::Must,
@@ -1889,7 +2307,7 @@ impl DocumentIndex {
 
     /// Count symbols
     pub fn count_symbols(&self) -> StorageResult<usize> {
-        let searcher = self.reader.searcher();
+        let searcher = self.searcher();
         let query = TermQuery::new(
             Term::from_field_text(self.schema.doc_type, "symbol"),
             IndexRecordOption::Basic,
@@ -1901,7 +2319,7 @@ impl DocumentIndex {
 
     /// Count total number of relationships
     pub fn count_relationships(&self) -> StorageResult<usize> {
-        let searcher = self.reader.searcher();
+        let searcher = self.searcher();
         let query = TermQuery::new(
             Term::from_field_text(self.schema.doc_type, "relationship"),
             IndexRecordOption::Basic,
@@ -1913,7 +2331,7 @@ impl DocumentIndex {
 
     /// Count files
     pub fn count_files(&self) -> StorageResult<usize> {
-        let searcher = self.reader.searcher();
+        let searcher = self.searcher();
         let query = TermQuery::new(
             Term::from_field_text(self.schema.doc_type, "file_info"),
             IndexRecordOption::Basic,
@@ -1926,7 +2344,7 @@ impl DocumentIndex {
     /// Get all indexed file paths for file watching
     /// Returns a vector of all file paths currently in the index
     pub fn get_all_indexed_paths(&self) -> StorageResult<Vec<PathBuf>> {
-        let searcher = self.reader.searcher();
+        let searcher = self.searcher();
         let query = TermQuery::new(
             Term::from_field_text(self.schema.doc_type, "file_info"),
             IndexRecordOption::Basic,
@@ -1958,7 +2376,7 @@ impl DocumentIndex {
         from_id: SymbolId,
         kind: RelationKind,
     ) -> StorageResult<Vec<(SymbolId, SymbolId, Relationship)>> {
-        let searcher = self.reader.searcher();
+        let searcher = self.searcher();
         let query = BooleanQuery::from(vec![
             (
                 Occur::Must,
@@ -2036,7 +2454,7 @@ impl DocumentIndex {
         to_id: SymbolId,
         kind: RelationKind,
     ) -> StorageResult<Vec<(SymbolId, SymbolId, Relationship)>> {
-        let searcher = self.reader.searcher();
+        let searcher = self.searcher();
         let query = BooleanQuery::from(vec![
             (
                 Occur::Must,
@@ -2113,7 +2531,7 @@ impl DocumentIndex {
         &self,
         kind: RelationKind,
     ) -> StorageResult<Vec<(SymbolId, SymbolId, Relationship)>> {
-        let searcher = self.reader.searcher();
+        let searcher = self.searcher();
         let query = BooleanQuery::from(vec![
             (
                 Occur::Must,
@@ -2163,7 +2581,7 @@ impl DocumentIndex {
 
     /// Get file path by ID
     pub fn get_file_path(&self, file_id: FileId) -> StorageResult<Option<String>> {
-        let searcher = self.reader.searcher();
+        let searcher = self.searcher();
         let query = BooleanQuery::from(vec![
             (
                 Occur::Must,
@@ -2209,6 +2627,16 @@ impl DocumentIndex {
         &self.index_path
     }
 
+    /// Validate checksums of all files belonging to searchable segments
+    ///
+    /// Returns the paths (relative to the index directory) of damaged files.
+    /// An empty result means every active segment is intact.
+    pub fn validate_segments(&self) -> StorageResult<Vec<PathBuf>> {
+        let mut damaged: Vec<PathBuf> = self.index.validate_checksum()?.into_iter().collect();
+        damaged.sort();
+        Ok(damaged)
+    }
+
     // Internal methods for storage operations (accessible within crate)
 
     /// Store a relationship between two symbols
@@ -2252,6 +2680,14 @@ impl DocumentIndex {
 
     /// Index a symbol from a Symbol struct
     pub fn index_symbol(&self, symbol: &crate::Symbol, file_path: &str) -> StorageResult<()> {
+        let annotations = (!symbol.annotations.is_empty()).then(|| {
+            symbol
+                .annotations
+                .iter()
+                .map(|a| a.to_string())
+                .collect::<Vec<_>>()
+                .join("\n")
+        });
         self.add_document(
             symbol.id,
             &symbol.name,
@@ -2269,8 +2705,12 @@ impl DocumentIndex {
                 .as_ref()
                 .map(|s| s.as_ref())
                 .unwrap_or(""),
-            None, // context (old field, different from scope_context)
+            // context (old field, different from scope_context): annotations
+            annotations.as_deref(),
             symbol.visibility,
+            symbol.exported,
+            &symbol.tags,
+            symbol.project.as_deref(),
             // NOTE: We clone scope_context here because ScopeContext now contains CompactString
             // (for parent_name) which doesn't implement Copy. This clone happens during indexing
             // where we process thousands of symbols per second.
@@ -2362,6 +2802,13 @@ impl DocumentIndex {
             if import.is_type_only { 1 } else { 0 },
         );
 
+        if let Some(range) = &import.range {
+            doc.add_u64(self.schema.import_line, range.start_line as u64);
+            doc.add_u64(self.schema.import_column, range.start_column as u64);
+            doc.add_u64(self.schema.import_end_line, range.end_line as u64);
+            doc.add_u64(self.schema.import_end_column, range.end_column as u64);
+        }
+
         writer.add_document(doc)?;
         Ok(())
     }
@@ -2390,7 +2837,7 @@ impl DocumentIndex {
             ),
         ]);
 
-        let searcher = self.reader.searcher();
+        let searcher = self.searcher();
         let top_docs = searcher
             .search(&query, &TopDocs::with_limit(1000))
             .map_err(|e| StorageError::General(format!("Import search failed: {e}")))?;
@@ -2425,12 +2872,32 @@ impl DocumentIndex {
                 .map(|v| v == 1)
                 .unwrap_or(false);
 
+            // Imports stored before their range was recorded have none
+            let position = |field| doc.get_first(field).and_then(|v| v.as_u64());
+            let range = match (
+                position(self.schema.import_line),
+                position(self.schema.import_column),
+                position(self.schema.import_end_line),
+                position(self.schema.import_end_column),
+            ) {
+                (Some(line), Some(column), Some(end_line), Some(end_column)) => {
+                    Some(crate::Range::new(
+                        line as u32,
+                        column as u16,
+                        end_line as u32,
+                        end_column as u16,
+                    ))
+                }
+                _ => None,
+            };
+
             imports.push(crate::parsing::Import {
                 path: import_path,
                 alias,
                 file_id,
                 is_glob,
                 is_type_only,
+                range,
             });
         }
 
@@ -2499,11 +2966,10 @@ impl DocumentIndex {
     }
 
     /// Query all relationships from the index
-    #[allow(dead_code)]
     pub(crate) fn query_relationships(
         &self,
     ) -> StorageResult<Vec<(SymbolId, SymbolId, crate::Relationship)>> {
-        let searcher = self.reader.searcher();
+        let searcher = self.searcher();
         let query = TermQuery::new(
             Term::from_field_text(self.schema.doc_type, "relationship"),
             IndexRecordOption::Basic,
@@ -2562,6 +3028,10 @@ impl DocumentIndex {
                 "DefinedIn" => RelationKind::DefinedIn,
                 "References" => RelationKind::References,
                 "ReferencedBy" => RelationKind::ReferencedBy,
+                "Aliases" => RelationKind::Aliases,
+                "AliasedBy" => RelationKind::AliasedBy,
+                "Throws" => RelationKind::Throws,
+                "ThrownBy" => RelationKind::ThrownBy,
                 _ => continue, // Skip unknown relation kinds
             };
 
@@ -2604,9 +3074,8 @@ impl DocumentIndex {
     }
 
     /// Query all file information from the index
-    #[allow(dead_code)]
     pub(crate) fn query_file_info(&self) -> StorageResult<Vec<(FileId, String, String, u64)>> {
-        let searcher = self.reader.searcher();
+        let searcher = self.searcher();
         let query = TermQuery::new(
             Term::from_field_text(self.schema.doc_type, "file_info"),
             IndexRecordOption::Basic,
@@ -2651,10 +3120,48 @@ impl DocumentIndex {
         Ok(files)
     }
 
+    /// Query the id and file path of every symbol document
+    ///
+    /// Cheaper than `get_all_symbols` because it skips full symbol
+    /// reconstruction; used by integrity checks that only need locations.
+    pub(crate) fn query_symbol_locations(&self) -> StorageResult<Vec<(SymbolId, String)>> {
+        let searcher = self.searcher();
+        let query = TermQuery::new(
+            Term::from_field_text(self.schema.doc_type, "symbol"),
+            IndexRecordOption::Basic,
+        );
+
+        let collector = TopDocs::with_limit(1_000_000);
+        let top_docs = searcher.search(&query, &collector)?;
+
+        let mut locations = Vec::with_capacity(top_docs.len());
+        for (_score, doc_address) in top_docs {
+            let doc: Document = searcher.doc(doc_address)?;
+
+            let Some(symbol_id) = doc
+                .get_first(self.schema.symbol_id)
+                .and_then(|v| v.as_u64())
+                .and_then(|id| SymbolId::new(id as u32))
+            else {
+                continue;
+            };
+
+            let path = doc
+                .get_first(self.schema.file_path)
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string();
+
+            locations.push((symbol_id, path));
+        }
+
+        Ok(locations)
+    }
+
     /// Count symbols in Tantivy index
     #[allow(dead_code)]
     pub(crate) fn count_symbol_documents(&self) -> StorageResult<u64> {
-        let searcher = self.reader.searcher();
+        let searcher = self.searcher();
         let query = TermQuery::new(
             Term::from_field_text(self.schema.doc_type, "symbol"),
             IndexRecordOption::Basic,
@@ -2674,7 +3181,7 @@ impl DocumentIndex {
 
     /// Query metadata value by key
     pub(crate) fn query_metadata(&self, key: MetadataKey) -> StorageResult<Option<u64>> {
-        let searcher = self.reader.searcher();
+        let searcher = self.searcher();
 
         // Build a compound query for doc_type="metadata" AND meta_key=key
         let doc_type_query = TermQuery::new(
@@ -2705,6 +3212,65 @@ impl DocumentIndex {
     }
 }
 
+/// The fast-field columns of one segment a [`SymbolRef`] is read from
+struct SymbolRefColumns {
+    symbol_id: Column<u64>,
+    file_id: Column<u64>,
+    line_number: Column<u64>,
+    column: Column<u64>,
+    end_line: Column<u64>,
+    end_column: Column<u64>,
+    kind: StrColumn,
+    file_path: StrColumn,
+}
+
+impl SymbolRefColumns {
+    /// Columns of `segment`, or `None` when it was written before all of
+    /// them were fast
+    fn open(segment: &SegmentReader) -> Option<Self> {
+        let fast_fields = segment.fast_fields();
+        let number = |name: &str| fast_fields.column_opt::<u64>(name).ok().flatten();
+        let text = |name: &str| fast_fields.str(name).ok().flatten();
+        Some(Self {
+            symbol_id: number("symbol_id")?,
+            file_id: number("file_id")?,
+            line_number: number("line_number")?,
+            column: number("column")?,
+            end_line: number("end_line")?,
+            end_column: number("end_column")?,
+            kind: text("kind")?,
+            file_path: text("file_path")?,
+        })
+    }
+
+    /// The symbol `doc` of the segment, found under `name`
+    fn read(&self, doc: DocId, name: &str) -> Option<SymbolRef> {
+        let text = |column: &StrColumn| {
+            let ord = column.term_ords(doc).next()?;
+            let mut value = String::new();
+            column.ord_to_str(ord, &mut value).ok()?.then_some(value)
+        };
+
+        let start_line = self.line_number.first(doc).unwrap_or(0) as u32;
+        Some(SymbolRef {
+            id: SymbolId(self.symbol_id.first(doc)? as u32),
+            name: name.into(),
+            kind: SymbolKind::from_str_with_default(&text(&self.kind)?),
+            file_id: FileId(self.file_id.first(doc)? as u32),
+            file_path: text(&self.file_path).map_or_else(|| "<unknown>".into(), Into::into),
+            range: crate::Range {
+                start_line,
+                start_column: self.column.first(doc).unwrap_or(0) as u16,
+                end_line: self
+                    .end_line
+                    .first(doc)
+                    .map_or(start_line, |line| line as u32),
+                end_column: self.end_column.first(doc).unwrap_or(0) as u16,
+            },
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2743,6 +3309,61 @@ mod tests {
         assert!(field_entry.is_stored(), "Language field should be stored");
     }
 
+    #[test]
+    fn test_held_generation_hides_commits_until_published() {
+        let temp_dir = TempDir::new().unwrap();
+        let settings = crate::config::Settings::default();
+        let index = Arc::new(DocumentIndex::new(temp_dir.path(), &settings).unwrap());
+        let add = |id: u32, name: &str| {
+            index.start_batch().unwrap();
+            index
+                .add_document(
+                    SymbolId::new(id).unwrap(),
+                    name,
+                    SymbolKind::Function,
+                    FileId::new(id).unwrap(),
+                    &format!("src/{name}.rs"),
+                    1,
+                    0,
+                    2,
+                    0,
+                    None,
+                    None,
+                    "crate",
+                    None,
+                    crate::Visibility::Public,
+                    false,
+                    &[],
+                    None,
+                    None,
+                    None,
+                )
+                .unwrap();
+            index.commit_batch().unwrap();
+        };
+
+        add(1, "first");
+        let before = index.generation();
+
+        index.begin_generation();
+        {
+            let _scope = index.write_scope();
+            add(2, "second");
+            // The writer reads what it wrote
+            assert_eq!(index.count_symbols().unwrap(), 2);
+        }
+        // Readers stay on the held generation
+        assert_eq!(index.count_symbols().unwrap(), 1);
+        assert_eq!(index.generation(), before);
+
+        assert_eq!(index.publish_generation().unwrap(), before + 1);
+        assert_eq!(index.count_symbols().unwrap(), 2);
+        assert_eq!(
+            crate::storage::generation::read_marker(temp_dir.path()),
+            Some(before + 1)
+        );
+    }
+
     #[test]
     fn test_add_and_search_document() {
         let temp_dir = TempDir::new().unwrap();
@@ -2771,6 +3392,9 @@ mod tests {
                 "crate::parser",
                 None,
                 crate::Visibility::Public,
+                false,
+                &[],
+                None,
                 Some(crate::ScopeContext::Module),
                 None, // No language_id for this test
             )
@@ -2830,6 +3454,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_local_scope_context_roundtrip() {
+        let temp_dir = TempDir::new().unwrap();
+        let settings = crate::config::Settings::default();
+        let index = DocumentIndex::new(temp_dir.path(), &settings).unwrap();
+
+        index.start_batch().unwrap();
+
+        let scope = crate::ScopeContext::Local {
+            hoisted: false,
+            parent_name: Some("run".into()),
+            parent_kind: Some(SymbolKind::Method),
+            depth: 3,
+            ancestors: vec![SymbolKind::Function, SymbolKind::Class, SymbolKind::Module],
+        };
+        let symbol = crate::Symbol::new(
+            SymbolId::new(1).unwrap(),
+            "count",
+            SymbolKind::Variable,
+            FileId::new(1).unwrap(),
+            crate::Range::new(4, 8, 4, 13),
+        )
+        .with_scope(scope.clone());
+
+        index.index_symbol(&symbol, "src/worker.ts").unwrap();
+        index.commit_batch().unwrap();
+
+        let retrieved = index.find_symbol_by_id(symbol.id).unwrap().unwrap();
+        assert_eq!(retrieved.scope_context, Some(scope));
+    }
+
     #[test]
     fn test_fuzzy_search() {
         let temp_dir = TempDir::new().unwrap();
@@ -2857,6 +3512,9 @@ mod tests {
                 "crate::server",
                 None,
                 crate::Visibility::Private,
+                false,
+                &[],
+                None,
                 Some(crate::ScopeContext::Module),
                 None, // No language_id for this test
             )
@@ -3030,6 +3688,9 @@ mod tests {
                 "crate",
                 None,
                 crate::Visibility::Public,
+                false,
+                &[],
+                None,
                 Some(crate::ScopeContext::Module),
                 None, // No language_id for this test
             )
@@ -3225,6 +3886,9 @@ mod tests {
                 "test",
                 None,
                 crate::Visibility::Public,
+                false,
+                &[],
+                None,
                 Some(crate::ScopeContext::Module),
                 None, // No language_id for this test
             )
@@ -3269,6 +3933,9 @@ mod tests {
                 "test",
                 None,
                 crate::Visibility::Public,
+                false,
+                &[],
+                None,
                 Some(crate::ScopeContext::Module),
                 None, // No language_id for this test
             )
@@ -3579,6 +4246,9 @@ mod tests {
                 "crate",                   // module_path
                 None,                      // context
                 crate::Visibility::Public, // visibility
+                false,                     // exported
+                &[],                       // tags
+                None,                      // project
                 None,                      // scope_context
                 Some("rust"),              // language_id
             )
@@ -3601,6 +4271,9 @@ mod tests {
                 "__main__",                 // module_path
                 None,                       // context
                 crate::Visibility::Public,  // visibility
+                false,                      // exported
+                &[],                        // tags
+                None,                       // project
                 None,                       // scope_context
                 Some("python"),             // language_id
             )
@@ -3623,6 +4296,9 @@ mod tests {
                 "app",                         // module_path
                 None,                          // context
                 crate::Visibility::Public,     // visibility
+                false,                         // exported
+                &[],                           // tags
+                None,                          // project
                 None,                          // scope_context
                 Some("typescript"),            // language_id
             )
@@ -3735,6 +4411,9 @@ mod tests {
                 "crate::config",                               // module_path
                 None,                                          // context
                 crate::Visibility::Public,                     // visibility
+                false,                                         // exported
+                &[],                                           // tags
+                None,                                          // project
                 None,                                          // scope_context
                 Some("rust"),                                  // language_id
             )
@@ -3757,6 +4436,9 @@ mod tests {
                 "parser",                                  // module_path
                 None,                                      // context
                 crate::Visibility::Public,                 // visibility
+                false,                                     // exported
+                &[],                                       // tags
+                None,                                      // project
                 None,                                      // scope_context
                 Some("python"),                            // language_id
             )
@@ -3779,6 +4461,9 @@ mod tests {
                 "utils.parser",                                   // module_path
                 None,                                             // context
                 crate::Visibility::Public,                        // visibility
+                false,                                            // exported
+                &[],                                              // tags
+                None,                                             // project
                 None,                                             // scope_context
                 Some("typescript"),                               // language_id
             )
@@ -3894,6 +4579,9 @@ mod tests {
                 "server",                  // module_path
                 None,                      // context
                 crate::Visibility::Public, // visibility
+                false,                     // exported
+                &[],                       // tags
+                None,                      // project
                 None,                      // scope_context
                 Some("rust"),              // language_id
             )
@@ -3915,6 +4603,9 @@ mod tests {
                 "server",                      // module_path
                 None,                          // context
                 crate::Visibility::Public,     // visibility
+                false,                         // exported
+                &[],                           // tags
+                None,                          // project
                 None,                          // scope_context
                 Some("python"),                // language_id
             )
@@ -4296,6 +4987,7 @@ mod tests {
                 file_id,
                 is_glob: false,
                 is_type_only: false,
+                range: Some(crate::Range::new(2, 0, 2, 27)),
             };
 
             let import2 = crate::parsing::Import {
@@ -4304,6 +4996,7 @@ mod tests {
                 file_id,
                 is_glob: false,
                 is_type_only: false,
+                range: None,
             };
 
             index.store_import(&import1).unwrap();
@@ -4333,6 +5026,7 @@ mod tests {
             assert_eq!(import1.alias, None);
             assert!(!import1.is_glob);
             assert!(!import1.is_type_only);
+            assert_eq!(import1.range, Some(crate::Range::new(2, 0, 2, 27)));
 
             // Verify second import (with alias)
             let import2 = loaded_imports
@@ -4342,6 +5036,7 @@ mod tests {
             assert_eq!(import2.alias.as_deref(), Some("SerTrait"));
             assert!(!import2.is_glob);
             assert!(!import2.is_type_only);
+            assert_eq!(import2.range, None);
         }
     }
 
@@ -4374,6 +5069,7 @@ mod tests {
             file_id,
             is_glob: false,
             is_type_only: false,
+            range: None,
         };
         index.store_import(&import).unwrap();
 
@@ -4546,4 +5242,143 @@ mod tests {
 
         println!("✅ SUCCESS: Owner extends Person relationship verified!");
     }
+
+    #[test]
+    fn test_symbol_names_with_prefix() {
+        let temp_dir = TempDir::new().unwrap();
+        let settings = crate::config::Settings::default();
+        let index = DocumentIndex::new(temp_dir.path(), &settings).unwrap();
+
+        index.start_batch().unwrap();
+        for (id, name) in [(1, "parse"), (2, "parse_file"), (3, "parse"), (4, "print")] {
+            index
+                .add_document(
+                    SymbolId::new(id).unwrap(),
+                    name,
+                    SymbolKind::Function,
+                    FileId::new(id).unwrap(),
+                    "src/lib.rs",
+                    0,
+                    0,
+                    1,
+                    0,
+                    None,
+                    None,
+                    "crate",
+                    None,
+                    crate::Visibility::Public,
+                    false,
+                    &[],
+                    None,
+                    None,
+                    Some("rust"),
+                )
+                .unwrap();
+        }
+        index.commit_batch().unwrap();
+
+        assert_eq!(
+            index.symbol_names_with_prefix("pa", 10).unwrap(),
+            vec!["parse".to_string(), "parse_file".to_string()]
+        );
+        assert_eq!(index.symbol_names_with_prefix("p", 2).unwrap().len(), 2);
+        assert!(index.symbol_names_with_prefix("q", 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_symbol_refs_match_full_symbols() {
+        let temp_dir = TempDir::new().unwrap();
+        let settings = crate::config::Settings::default();
+        let index = DocumentIndex::new(temp_dir.path(), &settings).unwrap();
+
+        index.start_batch().unwrap();
+        index
+            .add_document(
+                SymbolId::new(7).unwrap(),
+                "connect",
+                SymbolKind::Method,
+                FileId::new(3).unwrap(),
+                "src/net.rs",
+                12,
+                4,
+                20,
+                5,
+                Some("Open a connection to the server"),
+                Some("fn connect(&self, addr: &str) -> Result<Conn>"),
+                "crate::net",
+                None,
+                crate::Visibility::Public,
+                true,
+                &[],
+                None,
+                None,
+                Some("rust"),
+            )
+            .unwrap();
+        index.commit_batch().unwrap();
+
+        let symbols = index.find_symbols_by_name("connect", None).unwrap();
+        let refs = index.find_symbol_refs_by_name("connect", None).unwrap();
+        assert_eq!(refs, vec![SymbolRef::from(&symbols[0])]);
+        assert_eq!(refs[0].range.end_line, 20);
+        assert!(
+            SymbolRefColumns::open(index.searcher().segment_reader(0)).is_some(),
+            "refs are read from fast fields"
+        );
+        assert!(
+            index
+                .find_symbol_refs_by_name("connect", Some("python"))
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_search_matches_stemmed_doc_comments() {
+        let temp_dir = TempDir::new().unwrap();
+        let settings = crate::config::Settings::default();
+        let index = DocumentIndex::new(temp_dir.path(), &settings).unwrap();
+
+        index.start_batch().unwrap();
+        for (id, name, doc) in [
+            (
+                1,
+                "send_request",
+                "Retry the request with an exponential backoff.",
+            ),
+            (2, "parse_config", "Parse configuration from file"),
+        ] {
+            index
+                .add_document(
+                    SymbolId::new(id).unwrap(),
+                    name,
+                    SymbolKind::Function,
+                    FileId::new(id).unwrap(),
+                    "src/lib.rs",
+                    0,
+                    0,
+                    1,
+                    0,
+                    Some(doc),
+                    None,
+                    "crate",
+                    None,
+                    crate::Visibility::Public,
+                    false,
+                    &[],
+                    None,
+                    None,
+                    Some("rust"),
+                )
+                .unwrap();
+        }
+        index.commit_batch().unwrap();
+
+        // Neither name matches; "retries" and "retry" share a stem
+        let results = index
+            .search("retries with exponential backoff", 10, None, None, None)
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].symbol_id, SymbolId::new(1).unwrap());
+    }
 }
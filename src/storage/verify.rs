@@ -0,0 +1,446 @@
+//! Index integrity verification and repair
+//!
+//! Detects inconsistencies that accumulate when indexing is interrupted or
+//! files disappear between syncs:
+//! - relationship edges whose endpoints no longer exist as symbols
+//! - symbols whose file was never registered or was deleted from disk
+//! - Tantivy segment files that fail checksum validation
+//!
+//! In repair mode, dangling edges and orphaned symbols are pruned in a single
+//! batch. Corrupted segments cannot be repaired in place and require a full
+//! rebuild with `codanna index --force`.
+
+use super::{DocumentIndex, StorageResult};
+use crate::SymbolId;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// A relationship edge pointing at a symbol that is not in the index
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DanglingEdge {
+    pub from: u32,
+    pub to: u32,
+    pub kind: String,
+}
+
+/// Why a symbol is considered orphaned
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrphanReason {
+    /// No file_info document exists for the symbol's file
+    UnregisteredFile,
+    /// The file is registered but no longer exists on disk
+    FileDeleted,
+}
+
+/// A symbol whose source file is gone
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OrphanedSymbol {
+    pub symbol_id: u32,
+    pub file_path: String,
+    pub reason: OrphanReason,
+}
+
+/// Result of an integrity check
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct VerifyReport {
+    pub symbols_checked: usize,
+    pub relationships_checked: usize,
+    pub files_checked: usize,
+    pub dangling_edges: Vec<DanglingEdge>,
+    pub orphaned_symbols: Vec<OrphanedSymbol>,
+    /// Registered files that no longer exist on disk
+    pub deleted_files: Vec<String>,
+    /// Segment files failing checksum validation (relative to the index dir)
+    pub corrupted_segments: Vec<PathBuf>,
+    /// Populated only when running in repair mode
+    pub repair: Option<RepairSummary>,
+}
+
+/// What a repair run removed from the index
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct RepairSummary {
+    pub edges_pruned: usize,
+    pub symbols_pruned: usize,
+    pub files_pruned: usize,
+}
+
+impl VerifyReport {
+    /// True when no inconsistencies were found
+    pub fn is_clean(&self) -> bool {
+        self.dangling_edges.is_empty()
+            && self.orphaned_symbols.is_empty()
+            && self.deleted_files.is_empty()
+            && self.corrupted_segments.is_empty()
+    }
+
+    /// True when problems remain after this run
+    ///
+    /// Corrupted segments always remain; everything else is resolved by repair.
+    pub fn has_unresolved_issues(&self) -> bool {
+        if self.repair.is_some() {
+            !self.corrupted_segments.is_empty()
+        } else {
+            !self.is_clean()
+        }
+    }
+}
+
+impl std::fmt::Display for VerifyReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Checked {} symbols, {} relationships, {} files",
+            self.symbols_checked, self.relationships_checked, self.files_checked
+        )?;
+
+        if self.is_clean() {
+            return writeln!(f, "Index is consistent");
+        }
+
+        if !self.dangling_edges.is_empty() {
+            writeln!(f, "\nDangling relationships: {}", self.dangling_edges.len())?;
+            for edge in self.dangling_edges.iter().take(10) {
+                writeln!(f, "  {} -[{}]-> {}", edge.from, edge.kind, edge.to)?;
+            }
+            if self.dangling_edges.len() > 10 {
+                writeln!(f, "  ... and {} more", self.dangling_edges.len() - 10)?;
+            }
+        }
+
+        if !self.orphaned_symbols.is_empty() {
+            writeln!(f, "\nOrphaned symbols: {}", self.orphaned_symbols.len())?;
+            for orphan in self.orphaned_symbols.iter().take(10) {
+                let reason = match orphan.reason {
+                    OrphanReason::UnregisteredFile => "file not registered",
+                    OrphanReason::FileDeleted => "file deleted",
+                };
+                writeln!(
+                    f,
+                    "  symbol_id:{} in {} ({reason})",
+                    orphan.symbol_id, orphan.file_path
+                )?;
+            }
+            if self.orphaned_symbols.len() > 10 {
+                writeln!(f, "  ... and {} more", self.orphaned_symbols.len() - 10)?;
+            }
+        }
+
+        if !self.deleted_files.is_empty() {
+            writeln!(
+                f,
+                "\nDeleted files still indexed: {}",
+                self.deleted_files.len()
+            )?;
+            for path in self.deleted_files.iter().take(10) {
+                writeln!(f, "  {path}")?;
+            }
+            if self.deleted_files.len() > 10 {
+                writeln!(f, "  ... and {} more", self.deleted_files.len() - 10)?;
+            }
+        }
+
+        if !self.corrupted_segments.is_empty() {
+            writeln!(
+                f,
+                "\nCorrupted segment files: {}",
+                self.corrupted_segments.len()
+            )?;
+            for path in &self.corrupted_segments {
+                writeln!(f, "  {}", path.display())?;
+            }
+        }
+
+        match &self.repair {
+            Some(summary) => {
+                writeln!(
+                    f,
+                    "\nRepaired: pruned {} relationships, {} symbols, {} files",
+                    summary.edges_pruned, summary.symbols_pruned, summary.files_pruned
+                )?;
+                if !self.corrupted_segments.is_empty() {
+                    writeln!(
+                        f,
+                        "Corrupted segments cannot be repaired in place. Run 'codanna index --force' to rebuild."
+                    )?;
+                }
+            }
+            None => {
+                writeln!(
+                    f,
+                    "\nRun 'codanna index verify --repair' to prune inconsistencies"
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Check index consistency and optionally prune what can be pruned
+///
+/// Relative file paths stored in the index are resolved against
+/// `workspace_root` (or the current directory when `None`) to detect
+/// deleted files.
+pub fn verify_index(
+    index: &DocumentIndex,
+    workspace_root: Option<&Path>,
+    repair: bool,
+) -> StorageResult<VerifyReport> {
+    let mut report = VerifyReport {
+        corrupted_segments: index.validate_segments()?,
+        ..Default::default()
+    };
+
+    let symbols = index.query_symbol_locations()?;
+    let relationships = index.query_relationships()?;
+    let files = index.query_file_info()?;
+
+    report.symbols_checked = symbols.len();
+    report.relationships_checked = relationships.len();
+    report.files_checked = files.len();
+
+    let known_symbols: HashSet<SymbolId> = symbols.iter().map(|(id, _)| *id).collect();
+
+    // Ids referenced by edges but absent from the symbol set
+    let mut missing_endpoints = HashSet::new();
+    for (from, to, rel) in &relationships {
+        let from_missing = !known_symbols.contains(from);
+        let to_missing = !known_symbols.contains(to);
+        if from_missing {
+            missing_endpoints.insert(*from);
+        }
+        if to_missing {
+            missing_endpoints.insert(*to);
+        }
+        if from_missing || to_missing {
+            report.dangling_edges.push(DanglingEdge {
+                from: from.value(),
+                to: to.value(),
+                kind: format!("{:?}", rel.kind),
+            });
+        }
+    }
+
+    let registered: HashSet<&str> = files.iter().map(|(_, path, _, _)| path.as_str()).collect();
+    let deleted: BTreeMap<&str, crate::FileId> = files
+        .iter()
+        .filter(|(_, path, _, _)| !resolve(workspace_root, path).exists())
+        .map(|(id, path, _, _)| (path.as_str(), *id))
+        .collect();
+    report.deleted_files = deleted.keys().map(|p| p.to_string()).collect();
+
+    for (id, path) in &symbols {
+        let reason = if !registered.contains(path.as_str()) {
+            OrphanReason::UnregisteredFile
+        } else if deleted.contains_key(path.as_str()) {
+            OrphanReason::FileDeleted
+        } else {
+            continue;
+        };
+        report.orphaned_symbols.push(OrphanedSymbol {
+            symbol_id: id.value(),
+            file_path: path.clone(),
+            reason,
+        });
+    }
+
+    tracing::debug!(
+        target: "storage",
+        "verify: {} dangling edges, {} orphaned symbols, {} deleted files, {} corrupted segment files",
+        report.dangling_edges.len(),
+        report.orphaned_symbols.len(),
+        report.deleted_files.len(),
+        report.corrupted_segments.len()
+    );
+
+    if repair {
+        let mut summary = RepairSummary::default();
+
+        if !missing_endpoints.is_empty()
+            || !report.orphaned_symbols.is_empty()
+            || !deleted.is_empty()
+        {
+            index.start_batch()?;
+
+            let orphans: HashSet<SymbolId> = report
+                .orphaned_symbols
+                .iter()
+                .filter_map(|orphan| SymbolId::new(orphan.symbol_id))
+                .collect();
+            for id in &orphans {
+                index.delete_symbol(*id)?;
+            }
+            summary.symbols_pruned = report.orphaned_symbols.len();
+
+            // Deleting by endpoint id only touches relationship documents,
+            // since symbol documents store their id in a separate field.
+            let pruned: HashSet<SymbolId> = missing_endpoints.union(&orphans).copied().collect();
+            for id in &pruned {
+                index.delete_relationships_for_symbol(*id)?;
+            }
+            summary.edges_pruned = relationships
+                .iter()
+                .filter(|(from, to, _)| pruned.contains(from) || pruned.contains(to))
+                .count();
+
+            for (path, file_id) in &deleted {
+                index.remove_file_documents(path)?;
+                index.delete_imports_for_file(*file_id)?;
+            }
+            summary.files_pruned = deleted.len();
+
+            index.commit_batch()?;
+        }
+
+        report.repair = Some(summary);
+    }
+
+    Ok(report)
+}
+
+fn resolve(workspace_root: Option<&Path>, path: &str) -> PathBuf {
+    let path = Path::new(path);
+    match workspace_root {
+        Some(root) if path.is_relative() => root.join(path),
+        _ => path.to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indexing::pipeline::FileRegistration;
+    use crate::parsing::registry::LanguageId;
+    use crate::{FileId, Range, RelationKind, Relationship, Symbol, SymbolKind};
+    use tempfile::TempDir;
+
+    fn register(index: &DocumentIndex, id: u32, path: &Path) {
+        let registration = FileRegistration {
+            path: path.to_path_buf(),
+            file_id: FileId::new(id).unwrap(),
+            content_hash: "hash".to_string(),
+            language_id: LanguageId::new("rust"),
+            timestamp: 0,
+            mtime: 0,
+        };
+        index.store_file_registration(&registration).unwrap();
+    }
+
+    fn add_symbol(index: &DocumentIndex, id: u32, file_id: u32, path: &Path) {
+        let symbol = Symbol::new(
+            SymbolId::new(id).unwrap(),
+            format!("sym_{id}"),
+            SymbolKind::Function,
+            FileId::new(file_id).unwrap(),
+            Range::new(1, 0, 2, 0),
+        );
+        index.index_symbol(&symbol, path.to_str().unwrap()).unwrap();
+    }
+
+    fn setup() -> (TempDir, DocumentIndex, PathBuf, PathBuf) {
+        let temp_dir = TempDir::new().unwrap();
+        let settings = crate::config::Settings::default();
+        let index = DocumentIndex::new(temp_dir.path().join("tantivy"), &settings).unwrap();
+
+        let live = temp_dir.path().join("live.rs");
+        let gone = temp_dir.path().join("gone.rs");
+        std::fs::write(&live, "fn a() {}").unwrap();
+
+        index.start_batch().unwrap();
+        register(&index, 1, &live);
+        register(&index, 2, &gone);
+        add_symbol(&index, 1, 1, &live);
+        add_symbol(&index, 2, 1, &live);
+        add_symbol(&index, 3, 2, &gone);
+        let calls = Relationship::new(RelationKind::Calls);
+        let a = SymbolId::new(1).unwrap();
+        let b = SymbolId::new(2).unwrap();
+        let missing = SymbolId::new(99).unwrap();
+        index.store_relationship(a, b, &calls).unwrap();
+        index.store_relationship(a, missing, &calls).unwrap();
+        index.commit_batch().unwrap();
+
+        (temp_dir, index, live, gone)
+    }
+
+    #[test]
+    fn test_verify_reports_inconsistencies() {
+        let (_dir, index, _live, gone) = setup();
+
+        let report = verify_index(&index, None, false).unwrap();
+
+        assert_eq!(report.symbols_checked, 3);
+        assert_eq!(report.relationships_checked, 2);
+        assert_eq!(report.dangling_edges.len(), 1);
+        assert_eq!(report.dangling_edges[0].to, 99);
+        assert_eq!(
+            report.deleted_files,
+            vec![gone.to_str().unwrap().to_string()]
+        );
+        assert_eq!(report.orphaned_symbols.len(), 1);
+        assert_eq!(report.orphaned_symbols[0].symbol_id, 3);
+        assert_eq!(report.orphaned_symbols[0].reason, OrphanReason::FileDeleted);
+        assert!(report.corrupted_segments.is_empty());
+        assert!(report.repair.is_none());
+        assert!(report.has_unresolved_issues());
+    }
+
+    #[test]
+    fn test_repair_prunes_and_leaves_clean_index() {
+        let (_dir, index, _live, _gone) = setup();
+
+        let report = verify_index(&index, None, true).unwrap();
+        let summary = report.repair.as_ref().unwrap();
+        assert_eq!(summary.edges_pruned, 1);
+        assert_eq!(summary.symbols_pruned, 1);
+        assert_eq!(summary.files_pruned, 1);
+        assert!(!report.has_unresolved_issues());
+
+        let after = verify_index(&index, None, false).unwrap();
+        assert!(after.is_clean(), "index should be clean after repair");
+        assert_eq!(after.symbols_checked, 2);
+        assert_eq!(after.relationships_checked, 1);
+        assert_eq!(after.files_checked, 1);
+    }
+
+    #[test]
+    fn test_repair_counts_edges_of_pruned_symbols() {
+        let (_dir, index, _live, _gone) = setup();
+        index.start_batch().unwrap();
+        index
+            .store_relationship(
+                SymbolId::new(1).unwrap(),
+                SymbolId::new(3).unwrap(),
+                &Relationship::new(RelationKind::Calls),
+            )
+            .unwrap();
+        index.commit_batch().unwrap();
+
+        let report = verify_index(&index, None, true).unwrap();
+        assert_eq!(report.dangling_edges.len(), 1);
+        assert_eq!(report.repair.as_ref().unwrap().edges_pruned, 2);
+
+        let after = verify_index(&index, None, false).unwrap();
+        assert_eq!(after.relationships_checked, 1);
+    }
+
+    #[test]
+    fn test_unregistered_file_symbols_are_orphaned() {
+        let temp_dir = TempDir::new().unwrap();
+        let settings = crate::config::Settings::default();
+        let index = DocumentIndex::new(temp_dir.path(), &settings).unwrap();
+
+        index.start_batch().unwrap();
+        add_symbol(&index, 7, 3, Path::new("src/never_registered.rs"));
+        index.commit_batch().unwrap();
+
+        let report = verify_index(&index, None, false).unwrap();
+        assert_eq!(report.orphaned_symbols.len(), 1);
+        assert_eq!(
+            report.orphaned_symbols[0].reason,
+            OrphanReason::UnregisteredFile
+        );
+    }
+}
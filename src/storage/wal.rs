@@ -0,0 +1,386 @@
+//! Write-ahead log of index updates
+//!
+//! One update (indexing a file or directory, removing a file) spans several
+//! Tantivy commits plus the semantic store, so a process dying halfway leaves
+//! a file with symbols but no relationships, or embeddings for symbols that
+//! are gone. Each update is logged before it starts and marked committed once
+//! it finishes, or aborted if it fails. When an index is opened, updates
+//! begun by a process that is no longer running and never closed are
+//! returned by [`WriteAheadLog::pending`] to be redone from scratch.
+//!
+//! The log is JSON lines in `wal.log` next to the index. Appends are flushed
+//! but only synced to disk with `indexing.fsync` (`--fsync`), which server
+//! deployments that must survive power loss should turn on.
+
+use super::lock::process_start;
+use super::{StorageError, StorageResult};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+/// File name of the log inside the index directory
+pub const WAL_FILE: &str = "wal.log";
+
+/// Closed updates the log may hold before opening an index compacts it
+const COMPACT_AFTER: usize = 256;
+
+/// An index mutation, as much as is needed to redo it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum IndexUpdate {
+    IndexFile { path: PathBuf },
+    RemoveFile { path: PathBuf },
+    IndexDirectory { path: PathBuf },
+}
+
+/// One line of the log. Updates are keyed by the writing process, a run id
+/// drawn each time the log is opened and a sequence number local to the
+/// run, so several processes, and several opens in one process, can share a
+/// log. Pids are reused (a container's server is pid 1 on every start), so
+/// a begin also records its process's start time.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum WalEntry {
+    Begin {
+        pid: u32,
+        #[serde(default)]
+        started_at: u64,
+        #[serde(default)]
+        run: u64,
+        seq: u64,
+        update: IndexUpdate,
+    },
+    Commit {
+        pid: u32,
+        #[serde(default)]
+        run: u64,
+        seq: u64,
+    },
+    /// The update failed; whatever it left is up to the next incremental run
+    Abort {
+        pid: u32,
+        #[serde(default)]
+        run: u64,
+        seq: u64,
+    },
+}
+
+/// An update that was begun and never closed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingUpdate {
+    pid: u32,
+    started_at: u64,
+    run: u64,
+    seq: u64,
+    pub update: IndexUpdate,
+}
+
+impl PendingUpdate {
+    fn key(&self) -> (u32, u64, u64) {
+        (self.pid, self.run, self.seq)
+    }
+}
+
+/// Append-only log of index updates
+#[derive(Debug)]
+pub struct WriteAheadLog {
+    path: PathBuf,
+    file: Mutex<File>,
+    fsync: bool,
+    /// Start time of this process, `0` if unknown
+    started_at: u64,
+    run: u64,
+    next_seq: AtomicU64,
+}
+
+/// Contents of the log: the updates still open and how many were closed
+struct LogScan {
+    open: Vec<PendingUpdate>,
+    closed: usize,
+}
+
+impl WriteAheadLog {
+    /// Open or create the log in `dir`
+    pub fn open(dir: &Path, fsync: bool) -> StorageResult<Self> {
+        std::fs::create_dir_all(dir)?;
+        let path = dir.join(WAL_FILE);
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&path)?;
+        // End a line torn by a crash, so the next entry starts on its own
+        if ends_torn(&mut file)? {
+            file.write_all(b"\n")?;
+        }
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+            fsync,
+            started_at: process_start(std::process::id()).unwrap_or(0),
+            run: rand::random(),
+            next_seq: AtomicU64::new(1),
+        })
+    }
+
+    /// Log the start of `update`; pass the returned number to
+    /// [`Self::commit`] or [`Self::abort`]
+    pub fn begin(&self, update: IndexUpdate) -> StorageResult<u64> {
+        let seq = self.next_seq.fetch_add(1, Ordering::Relaxed);
+        self.append(&WalEntry::Begin {
+            pid: std::process::id(),
+            started_at: self.started_at,
+            run: self.run,
+            seq,
+            update,
+        })?;
+        Ok(seq)
+    }
+
+    /// Log that the update `seq` of this log finished
+    pub fn commit(&self, seq: u64) -> StorageResult<()> {
+        self.append(&WalEntry::Commit {
+            pid: std::process::id(),
+            run: self.run,
+            seq,
+        })
+    }
+
+    /// Log that the update `seq` of this log failed, so it isn't redone
+    pub fn abort(&self, seq: u64) -> StorageResult<()> {
+        self.append(&WalEntry::Abort {
+            pid: std::process::id(),
+            run: self.run,
+            seq,
+        })
+    }
+
+    /// Updates never closed by processes that are no longer running, in
+    /// the order they began
+    pub fn pending(&self) -> StorageResult<Vec<PendingUpdate>> {
+        let open = self.scan()?.open;
+        let mut system = System::new();
+        let pids: Vec<Pid> = open.iter().map(|p| Pid::from_u32(p.pid)).collect();
+        system.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&pids),
+            true,
+            ProcessRefreshKind::nothing(),
+        );
+        Ok(open
+            .into_iter()
+            .filter(|p| {
+                // A live process with another start time reused the pid
+                system
+                    .process(Pid::from_u32(p.pid))
+                    .is_none_or(|process| p.started_at != 0 && process.start_time() != p.started_at)
+            })
+            .collect())
+    }
+
+    /// Mark a pending update as redone
+    pub fn resolve(&self, pending: &PendingUpdate) -> StorageResult<()> {
+        self.append(&WalEntry::Commit {
+            pid: pending.pid,
+            run: pending.run,
+            seq: pending.seq,
+        })
+    }
+
+    /// Whether enough closed updates piled up to be worth a [`Self::compact`]
+    pub fn needs_compaction(&self) -> StorageResult<bool> {
+        Ok(self.scan()?.closed >= COMPACT_AFTER)
+    }
+
+    /// Rewrite the log keeping only updates still open, so it doesn't grow
+    /// without bound. Call only while holding the index's write lock: the
+    /// log is replaced, and an append of another writer would be lost.
+    pub fn compact(&self) -> StorageResult<()> {
+        let scan = self.scan()?;
+        if scan.closed == 0 {
+            return Ok(());
+        }
+        let mut file = self.file.lock().map_err(|_| StorageError::LockPoisoned)?;
+        let temp = self.path.with_extension("log.tmp");
+        let mut out = File::create(&temp)?;
+        for p in scan.open {
+            let entry = WalEntry::Begin {
+                pid: p.pid,
+                started_at: p.started_at,
+                run: p.run,
+                seq: p.seq,
+                update: p.update,
+            };
+            writeln!(out, "{}", encode(&entry)?)?;
+        }
+        out.sync_all()?;
+        std::fs::rename(&temp, &self.path)?;
+        *file = OpenOptions::new().append(true).open(&self.path)?;
+        Ok(())
+    }
+
+    /// Updates begun and not closed, by any process, and the number closed
+    fn scan(&self) -> StorageResult<LogScan> {
+        let reader = BufReader::new(File::open(&self.path)?);
+        let mut begun = Vec::new();
+        let mut closed = HashSet::new();
+        for line in reader.lines() {
+            // A torn last line is the write that was cut off; skip it
+            match serde_json::from_str(&line?) {
+                Ok(WalEntry::Begin {
+                    pid,
+                    started_at,
+                    run,
+                    seq,
+                    update,
+                }) => begun.push(PendingUpdate {
+                    pid,
+                    started_at,
+                    run,
+                    seq,
+                    update,
+                }),
+                Ok(WalEntry::Commit { pid, run, seq } | WalEntry::Abort { pid, run, seq }) => {
+                    closed.insert((pid, run, seq));
+                }
+                Err(_) => {}
+            }
+        }
+        let begun_count = begun.len();
+        begun.retain(|p| !closed.contains(&p.key()));
+        Ok(LogScan {
+            closed: begun_count - begun.len(),
+            open: begun,
+        })
+    }
+
+    fn append(&self, entry: &WalEntry) -> StorageResult<()> {
+        let line = format!("{}\n", encode(entry)?);
+        let mut file = self.file.lock().map_err(|_| StorageError::LockPoisoned)?;
+        file.write_all(line.as_bytes())?;
+        file.flush()?;
+        if self.fsync {
+            file.sync_data()?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether the log's last line lacks its newline
+fn ends_torn(file: &mut File) -> StorageResult<bool> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    if file.metadata()?.len() == 0 {
+        return Ok(false);
+    }
+    let mut last = [0u8; 1];
+    file.seek(SeekFrom::End(-1))?;
+    file.read_exact(&mut last)?;
+    Ok(last[0] != b'\n')
+}
+
+fn encode(entry: &WalEntry) -> StorageResult<String> {
+    serde_json::to_string(entry).map_err(|e| StorageError::Serialization(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_uncommitted_updates_of_dead_processes_are_pending() {
+        let temp_dir = TempDir::new().unwrap();
+        let wal = WriteAheadLog::open(temp_dir.path(), false).unwrap();
+
+        let done = wal
+            .begin(IndexUpdate::IndexFile {
+                path: "src/done.rs".into(),
+            })
+            .unwrap();
+        wal.commit(done).unwrap();
+        // Still running: this process
+        wal.begin(IndexUpdate::IndexFile {
+            path: "src/live.rs".into(),
+        })
+        .unwrap();
+        // A crashed writer, and a torn line it left behind
+        let crashed = WalEntry::Begin {
+            pid: u32::MAX,
+            started_at: 0,
+            run: 7,
+            seq: 1,
+            update: IndexUpdate::RemoveFile {
+                path: "src/gone.rs".into(),
+            },
+        };
+        wal.append(&crashed).unwrap();
+        wal.file
+            .lock()
+            .unwrap()
+            .write_all(b"{\"op\":\"beg")
+            .unwrap();
+        let wal = WriteAheadLog::open(temp_dir.path(), false).unwrap();
+
+        let pending = wal.pending().unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(
+            pending[0].update,
+            IndexUpdate::RemoveFile {
+                path: "src/gone.rs".into()
+            }
+        );
+
+        wal.resolve(&pending[0]).unwrap();
+        wal.compact().unwrap();
+        assert!(wal.pending().unwrap().is_empty());
+        assert_eq!(wal.scan().unwrap().open.len(), 1);
+    }
+
+    #[test]
+    fn test_runs_and_reused_pids_are_told_apart() {
+        let temp_dir = TempDir::new().unwrap();
+        let earlier = WriteAheadLog::open(temp_dir.path(), false).unwrap();
+        let done = earlier
+            .begin(IndexUpdate::IndexFile {
+                path: "src/done.rs".into(),
+            })
+            .unwrap();
+        earlier.commit(done).unwrap();
+
+        // A reopened log numbers from 1 again; the earlier commit of
+        // sequence 1 must not close this run's update
+        let wal = WriteAheadLog::open(temp_dir.path(), false).unwrap();
+        let failed = wal
+            .begin(IndexUpdate::IndexFile {
+                path: "src/open.rs".into(),
+            })
+            .unwrap();
+        assert_eq!(failed, done);
+        assert_eq!(wal.scan().unwrap().open.len(), 1);
+        wal.abort(failed).unwrap();
+        assert!(wal.scan().unwrap().open.is_empty());
+
+        // An earlier process with this pid, as a container's pid 1 is on
+        // every start, whose update was cut off
+        let crashed = WalEntry::Begin {
+            pid: std::process::id(),
+            started_at: wal.started_at.wrapping_sub(60),
+            run: 7,
+            seq: 1,
+            update: IndexUpdate::IndexDirectory { path: "src".into() },
+        };
+        wal.append(&crashed).unwrap();
+        let pending = wal.pending().unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(
+            pending[0].update,
+            IndexUpdate::IndexDirectory { path: "src".into() }
+        );
+        assert!(!wal.needs_compaction().unwrap());
+    }
+}
@@ -95,6 +95,13 @@ impl SymbolContext {
         output
     }
 
+    /// Format only the relationship sections
+    pub fn format_relationships(&self, indent: &str) -> String {
+        let mut output = String::new();
+        self.append_relationships(&mut output, indent);
+        output
+    }
+
     fn append_header(&self, output: &mut String, indent: &str) {
         output.push_str(&format!(
             "{}{} ({:?}) at {} [symbol_id:{}]\n",
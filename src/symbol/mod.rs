@@ -1,4 +1,9 @@
 pub mod context;
+pub mod qualified;
+pub mod reference;
+
+pub use qualified::QualifiedName;
+pub use reference::SymbolRef;
 
 use crate::parsing::registry::LanguageId;
 use crate::types::{CompactString, FileId, Range, SymbolId, SymbolKind, compact_string};
@@ -6,16 +11,23 @@ use serde::{Deserialize, Serialize};
 use std::fmt;
 
 /// Visibility of a symbol
+///
+/// Whether a symbol is also part of its module's exported surface is tracked
+/// separately in [`Symbol::exported`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Visibility {
     /// Public visibility (pub)
     Public,
-    /// Crate-level visibility (pub(crate))
+    /// Crate-level visibility (pub(crate), C#/Kotlin internal)
     Crate,
     /// Module-level visibility (pub(super), pub(in path))
     Module,
     /// Private visibility (default)
     Private,
+    /// Visible to subclasses (protected)
+    Protected,
+    /// Visible within the declaring package (Java default)
+    Package,
 }
 
 /// Scope context for symbol definition
@@ -32,6 +44,12 @@ pub enum ScopeContext {
         parent_name: Option<CompactString>,
         /// Kind of the parent (Function, Class, etc.)
         parent_kind: Option<SymbolKind>,
+        /// Number of scopes (functions, classes, blocks) between this symbol and module level
+        #[serde(default)]
+        depth: u16,
+        /// Kinds of the enclosing scopes, innermost first (e.g. Function, Class, Module)
+        #[serde(default)]
+        ancestors: Vec<SymbolKind>,
     },
     /// Parameter of function/method
     Parameter,
@@ -66,6 +84,12 @@ pub struct Symbol {
     pub module_path: Option<Box<str>>,
     /// Visibility of the symbol
     pub visibility: Visibility,
+    /// Part of the module's exported surface (Rust `pub`, TypeScript `export`)
+    ///
+    /// Set by the language behavior, so `pub(crate)` items and unexported
+    /// TypeScript declarations stay distinguishable from the public API.
+    #[serde(default)]
+    pub exported: bool,
     /// Scope context where this symbol is defined
     ///
     /// This field enables proper resolution without heuristics.
@@ -76,6 +100,96 @@ pub struct Symbol {
     /// This field enables language-specific filtering in searches.
     /// It's Optional for backward compatibility - existing indexes will have None.
     pub language_id: Option<LanguageId>,
+    /// Failure points inside the symbol's definition (Nix `assert`, `throw`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub annotations: Vec<Annotation>,
+    /// Labels from the project's `[[tags]]` rules, e.g. `api-handler`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<Box<str>>,
+    /// Monorepo project of the symbol's file, named by its manifest
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<Box<str>>,
+}
+
+/// What kind of failure point an [`Annotation`] records
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AnnotationKind {
+    /// `assert cond; expr` - evaluation fails when the condition is false
+    Assert,
+    /// `throw msg` - a catchable evaluation error
+    Throw,
+    /// `abort msg` - an uncatchable evaluation error
+    Abort,
+}
+
+impl AnnotationKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Assert => "assert",
+            Self::Throw => "throw",
+            Self::Abort => "abort",
+        }
+    }
+
+    pub fn from_str_opt(s: &str) -> Option<Self> {
+        match s {
+            "assert" => Some(Self::Assert),
+            "throw" => Some(Self::Throw),
+            "abort" => Some(Self::Abort),
+            _ => None,
+        }
+    }
+}
+
+/// A diagnostics-style note attached to a symbol: where evaluation can fail
+/// and why
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Annotation {
+    pub kind: AnnotationKind,
+    /// The asserted condition, or the message expression of a throw/abort,
+    /// with whitespace collapsed to single spaces
+    pub message: Box<str>,
+    /// Zero-based line of the failure point
+    pub line: u32,
+    pub column: u16,
+}
+
+impl Annotation {
+    pub fn new(kind: AnnotationKind, message: &str, line: u32, column: u16) -> Self {
+        let message = message.split_whitespace().collect::<Vec<_>>().join(" ");
+        Self {
+            kind,
+            message: message.into(),
+            line,
+            column,
+        }
+    }
+
+    /// Parse the single-line form written by `Display`: `kind line:column message`
+    pub fn parse(s: &str) -> Option<Self> {
+        let (kind, rest) = s.split_once(' ')?;
+        let (position, message) = rest.split_once(' ').unwrap_or((rest, ""));
+        let (line, column) = position.split_once(':')?;
+        Some(Self {
+            kind: AnnotationKind::from_str_opt(kind)?,
+            message: message.into(),
+            line: line.parse().ok()?,
+            column: column.parse().ok()?,
+        })
+    }
+}
+
+impl fmt::Display for Annotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {}:{} {}",
+            self.kind.as_str(),
+            self.line,
+            self.column,
+            self.message
+        )
+    }
 }
 
 #[repr(C, align(32))]
@@ -112,8 +226,12 @@ impl Symbol {
             doc_comment: None,
             module_path: None,
             visibility: Visibility::Private,
+            exported: false,
             scope_context: None, // Default to None for backward compatibility
             language_id: None,   // Default to None for backward compatibility
+            annotations: Vec::new(),
+            tags: Vec::new(),
+            project: None,
         }
     }
 
@@ -156,6 +274,11 @@ impl Symbol {
         self
     }
 
+    pub fn with_exported(mut self, exported: bool) -> Self {
+        self.exported = exported;
+        self
+    }
+
     pub fn with_scope(mut self, scope: ScopeContext) -> Self {
         self.scope_context = Some(scope);
         self
@@ -166,6 +289,21 @@ impl Symbol {
         self
     }
 
+    pub fn with_annotations(mut self, annotations: Vec<Annotation>) -> Self {
+        self.annotations = annotations;
+        self
+    }
+
+    pub fn with_tags(mut self, tags: Vec<Box<str>>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    pub fn with_project(mut self, project: Option<Box<str>>) -> Self {
+        self.project = project;
+        self
+    }
+
     /// Get the symbol name as a string slice
     pub fn as_name(&self) -> &str {
         &self.name
@@ -341,8 +479,12 @@ impl CompactSymbol {
             doc_comment: None,
             module_path: None,
             visibility: Visibility::Private,
+            exported: false,
             scope_context: None, // CompactSymbol doesn't store scope info yet
             language_id: None,   // CompactSymbol doesn't store language info yet
+            annotations: Vec::new(),
+            tags: Vec::new(),
+            project: None,
         })
     }
 }
@@ -0,0 +1,154 @@
+//! Qualified symbol names for lookups
+//!
+//! Callers name symbols the way source code does: bare (`parse`), or with a
+//! module path in the language's own notation (`crate::parser::parse`,
+//! `app.models.User`, `App\Models\User`). A qualified name keeps the module
+//! part apart from the symbol name so lookups can query the index by name and
+//! narrow by module afterwards.
+
+use super::Symbol;
+use std::fmt;
+
+/// Module separators recognized when parsing, longest first
+const SEPARATORS: [&str; 4] = ["::", "\\", ".", "/"];
+
+/// A symbol name, optionally qualified by a module path and a language
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct QualifiedName {
+    /// Module part as written, without the trailing separator
+    pub module_path: Option<String>,
+    pub name: String,
+    /// Language to look in (e.g. "rust")
+    pub language: Option<String>,
+}
+
+impl QualifiedName {
+    /// Bare name, matched in any module.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            module_path: None,
+            name: name.into(),
+            language: None,
+        }
+    }
+
+    /// Split `qualified` at its last module separator (`::`, `\`, `.`, or `/`).
+    pub fn parse(qualified: &str) -> Self {
+        let split = SEPARATORS
+            .iter()
+            .filter_map(|sep| qualified.rfind(sep).map(|at| (at, sep.len())))
+            .max_by_key(|(at, _)| *at);
+        match split {
+            Some((at, len)) if at > 0 && at + len < qualified.len() => Self {
+                module_path: Some(qualified[..at].to_string()),
+                name: qualified[at + len..].to_string(),
+                language: None,
+            },
+            _ => Self::new(qualified),
+        }
+    }
+
+    /// Restrict the lookup to one language.
+    pub fn with_language(mut self, language: impl Into<String>) -> Self {
+        self.language = Some(language.into());
+        self
+    }
+
+    /// Whether `symbol` is what this name refers to.
+    ///
+    /// The module part may be a suffix of the symbol's module path, so
+    /// `parser::parse` matches `parse` in `crate::parser`. It is compared
+    /// ignoring separator style, and may also end in the symbol's parent type
+    /// (`Parser::parse` for a method of `Parser`).
+    pub fn matches(&self, symbol: &Symbol) -> bool {
+        if symbol.name.as_ref() != self.name {
+            return false;
+        }
+        let Some(qualifier) = &self.module_path else {
+            return true;
+        };
+        let qualifier = segments(qualifier);
+        let module = symbol
+            .module_path
+            .as_deref()
+            .map(segments)
+            .unwrap_or_default();
+        if module.ends_with(&qualifier) {
+            return true;
+        }
+        // Trailing segment naming the containing type
+        let Some((parent, rest)) = qualifier.split_last() else {
+            return true;
+        };
+        symbol
+            .scope_context
+            .as_ref()
+            .and_then(|scope| match scope {
+                super::ScopeContext::ClassMember { class_name } => class_name.as_deref(),
+                _ => None,
+            })
+            .is_some_and(|class| segments(class).last() == Some(parent) && module.ends_with(rest))
+    }
+}
+
+impl From<&str> for QualifiedName {
+    fn from(qualified: &str) -> Self {
+        Self::parse(qualified)
+    }
+}
+
+impl fmt::Display for QualifiedName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.module_path {
+            Some(module) => write!(f, "{module}::{}", self.name),
+            None => f.write_str(&self.name),
+        }
+    }
+}
+
+/// Segments of a module path in any separator style
+fn segments(path: &str) -> Vec<&str> {
+    let mut parts = vec![path];
+    for sep in SEPARATORS {
+        parts = parts.iter().flat_map(|part| part.split(sep)).collect();
+    }
+    parts.retain(|part| !part.is_empty());
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{FileId, Range, SymbolId, SymbolKind};
+
+    #[test]
+    fn test_qualified_name_parse_and_match() {
+        let name = QualifiedName::parse("crate::parser::parse");
+        assert_eq!(name.module_path.as_deref(), Some("crate::parser"));
+        assert_eq!(name.name, "parse");
+        assert_eq!(QualifiedName::parse("app.models.User").name, "User");
+        assert_eq!(QualifiedName::parse(r"App\Models\User").name, "User");
+        assert_eq!(QualifiedName::parse("parse"), QualifiedName::new("parse"));
+
+        let symbol = Symbol::new(
+            SymbolId::new(1).unwrap(),
+            "parse",
+            SymbolKind::Method,
+            FileId::new(1).unwrap(),
+            Range::new(1, 0, 3, 1),
+        )
+        .with_module_path("crate::parser");
+        let mut method = symbol.clone();
+        method.scope_context = Some(crate::ScopeContext::ClassMember {
+            class_name: Some("Parser".into()),
+        });
+
+        assert!(QualifiedName::parse("parse").matches(&symbol));
+        assert!(QualifiedName::parse("crate::parser::parse").matches(&symbol));
+        assert!(QualifiedName::parse("parser::parse").matches(&symbol));
+        assert!(QualifiedName::parse("parser.parse").matches(&symbol));
+        assert!(!QualifiedName::parse("lexer::parse").matches(&symbol));
+        assert!(!QualifiedName::parse("Parser::parse").matches(&symbol));
+        assert!(QualifiedName::parse("parser::Parser::parse").matches(&method));
+    }
+}
@@ -0,0 +1,58 @@
+//! Lightweight symbol references
+//!
+//! Most lookups only need to know which symbols match: their ids to follow
+//! relationships, their names and locations to list them. [`SymbolRef`]
+//! carries just that. Reading one from the index skips the signature, doc
+//! comment, scope and annotations that make up most of a [`Symbol`], and
+//! [`SymbolRef::hydrate`] fetches the full symbol when it is needed.
+
+use crate::Symbol;
+use crate::indexing::facade::IndexFacade;
+use crate::types::{CompactString, FileId, Range, SymbolId, SymbolKind};
+use serde::Serialize;
+use std::fmt;
+
+/// Identity and location of an indexed symbol, without its heavy fields
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SymbolRef {
+    pub id: SymbolId,
+    pub name: CompactString,
+    pub kind: SymbolKind,
+    pub file_id: FileId,
+    /// Clean file path without line numbers (e.g., "src/lib.rs")
+    pub file_path: Box<str>,
+    pub range: Range,
+}
+
+impl SymbolRef {
+    /// The full symbol, or `None` when it has left the index since
+    pub fn hydrate(&self, facade: &IndexFacade) -> Option<Symbol> {
+        facade.get_symbol(self.id)
+    }
+}
+
+impl From<&Symbol> for SymbolRef {
+    fn from(symbol: &Symbol) -> Self {
+        Self {
+            id: symbol.id,
+            name: symbol.name.clone(),
+            kind: symbol.kind,
+            file_id: symbol.file_id,
+            file_path: symbol.file_path.clone(),
+            range: symbol.range,
+        }
+    }
+}
+
+impl fmt::Display for SymbolRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} {} at {}:{}",
+            self.kind,
+            self.name,
+            self.file_path,
+            self.range.start_line + 1
+        )
+    }
+}
@@ -0,0 +1,418 @@
+//! Explorer state and key handling
+
+use crate::indexing::facade::IndexFacade;
+use crate::navigation::{Hover, OutlineNode};
+use crate::types::PositionEncoding;
+use crate::{Symbol, SymbolId, SymbolKind};
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::widgets::ListState;
+
+/// Search results kept for display
+const MAX_MATCHES: usize = 200;
+
+/// A symbol as listed in search results and relationship tabs
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub id: SymbolId,
+    pub name: String,
+    pub kind: SymbolKind,
+    /// `path:line`
+    pub location: String,
+}
+
+impl Entry {
+    fn new(symbol: &Symbol) -> Self {
+        Self {
+            id: symbol.id,
+            name: symbol.name.to_string(),
+            kind: symbol.kind,
+            location: format!("{}:{}", symbol.file_path, symbol.range.start_line + 1),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Search,
+    Browse,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tab {
+    Callers,
+    Callees,
+    Implementations,
+    Outline,
+}
+
+impl Tab {
+    pub const ALL: [Tab; 4] = [
+        Tab::Callers,
+        Tab::Callees,
+        Tab::Implementations,
+        Tab::Outline,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Tab::Callers => "Callers",
+            Tab::Callees => "Callees",
+            Tab::Implementations => "Implementations",
+            Tab::Outline => "Outline",
+        }
+    }
+
+    fn index(self) -> usize {
+        Self::ALL.iter().position(|tab| *tab == self).unwrap_or(0)
+    }
+}
+
+/// The symbol being browsed and its relationships
+pub struct View {
+    pub symbol: Symbol,
+    pub hover: Option<Hover>,
+    pub tab: Tab,
+    pub callers: Vec<Entry>,
+    pub callees: Vec<Entry>,
+    /// Implementations of a trait or interface, and the traits a type
+    /// implements
+    pub implementations: Vec<Entry>,
+    /// The symbol's file, flattened with nesting depth
+    pub outline: Vec<(usize, Entry)>,
+    pub state: ListState,
+}
+
+impl View {
+    fn new(facade: &IndexFacade, symbol: Symbol) -> Self {
+        let entries =
+            |symbols: Vec<Symbol>| -> Vec<Entry> { symbols.iter().map(Entry::new).collect() };
+        let mut implementations = entries(facade.get_implementations(symbol.id));
+        implementations.extend(
+            facade
+                .get_implemented_traits(symbol.id)
+                .iter()
+                .map(Entry::new),
+        );
+        let mut outline = Vec::new();
+        if let Some(nodes) = facade.file_outline(&symbol.file_path, PositionEncoding::Byte) {
+            flatten_outline(facade, &nodes, 0, &mut outline);
+        }
+
+        let mut view = Self {
+            hover: facade.hover(symbol.id, PositionEncoding::Byte),
+            tab: Tab::Callers,
+            callers: entries(facade.get_calling_functions(symbol.id)),
+            callees: entries(facade.get_called_functions(symbol.id)),
+            implementations,
+            outline,
+            state: ListState::default(),
+            symbol,
+        };
+        // Open on the first tab with something in it
+        if let Some(tab) = Tab::ALL.into_iter().find(|tab| view.len(*tab) > 0) {
+            view.tab = tab;
+        }
+        view.reset_selection();
+        view
+    }
+
+    pub fn len(&self, tab: Tab) -> usize {
+        match tab {
+            Tab::Callers => self.callers.len(),
+            Tab::Callees => self.callees.len(),
+            Tab::Implementations => self.implementations.len(),
+            Tab::Outline => self.outline.len(),
+        }
+    }
+
+    fn selected(&self) -> Option<&Entry> {
+        let index = self.state.selected()?;
+        match self.tab {
+            Tab::Callers => self.callers.get(index),
+            Tab::Callees => self.callees.get(index),
+            Tab::Implementations => self.implementations.get(index),
+            Tab::Outline => self.outline.get(index).map(|(_, entry)| entry),
+        }
+    }
+
+    fn cycle_tab(&mut self, forward: bool) {
+        let count = Tab::ALL.len();
+        let step = if forward { 1 } else { count - 1 };
+        self.tab = Tab::ALL[(self.tab.index() + step) % count];
+        self.reset_selection();
+    }
+
+    fn reset_selection(&mut self) {
+        let selected = if self.tab == Tab::Outline {
+            // Start on the browsed symbol itself
+            self.outline
+                .iter()
+                .position(|(_, entry)| entry.id == self.symbol.id)
+                .or(Some(0))
+        } else {
+            Some(0)
+        };
+        let has_rows = self.len(self.tab) > 0;
+        self.state.select(selected.filter(|_| has_rows));
+    }
+}
+
+fn flatten_outline(
+    facade: &IndexFacade,
+    nodes: &[OutlineNode],
+    depth: usize,
+    out: &mut Vec<(usize, Entry)>,
+) {
+    for node in nodes {
+        if let Some(symbol) = facade.get_symbol(SymbolId(node.symbol_id)) {
+            out.push((depth, Entry::new(&symbol)));
+        }
+        flatten_outline(facade, &node.children, depth + 1, out);
+    }
+}
+
+/// Explorer state: the search box, its matches, and the stack of browsed
+/// symbols
+pub struct App<'a> {
+    facade: &'a IndexFacade,
+    /// Every indexed symbol, searched by name
+    entries: Vec<Entry>,
+    pub mode: Mode,
+    pub query: String,
+    /// Indexes into `entries`, best match first
+    pub matches: Vec<usize>,
+    pub search_state: ListState,
+    /// Browsed symbols; the last one is on screen
+    pub trail: Vec<View>,
+    pub should_quit: bool,
+}
+
+impl<'a> App<'a> {
+    pub fn new(facade: &'a IndexFacade) -> Self {
+        let mut entries = Vec::new();
+        let _ = facade.document_index().for_each_symbol(|symbol| {
+            entries.push(Entry::new(&symbol));
+            Ok(())
+        });
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut app = Self {
+            facade,
+            entries,
+            mode: Mode::Search,
+            query: String::new(),
+            matches: Vec::new(),
+            search_state: ListState::default(),
+            trail: Vec::new(),
+            should_quit: false,
+        };
+        app.refilter();
+        app
+    }
+
+    pub fn facade(&self) -> &IndexFacade {
+        self.facade
+    }
+
+    pub fn entry(&self, index: usize) -> &Entry {
+        &self.entries[index]
+    }
+
+    /// The search match under the cursor
+    pub fn selected_match(&self) -> Option<&Entry> {
+        let index = *self.matches.get(self.search_state.selected()?)?;
+        Some(&self.entries[index])
+    }
+
+    pub fn current(&self) -> Option<&View> {
+        self.trail.last()
+    }
+
+    pub fn current_mut(&mut self) -> Option<&mut View> {
+        self.trail.last_mut()
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        if ctrl && key.code == KeyCode::Char('c') {
+            self.should_quit = true;
+            return;
+        }
+        match self.mode {
+            Mode::Search => self.search_key(key.code, ctrl),
+            Mode::Browse => self.browse_key(key.code),
+        }
+    }
+
+    fn search_key(&mut self, code: KeyCode, ctrl: bool) {
+        match code {
+            KeyCode::Esc if self.trail.is_empty() => self.should_quit = true,
+            KeyCode::Esc => self.mode = Mode::Browse,
+            KeyCode::Enter => {
+                if let Some(id) = self.selected_match().map(|entry| entry.id) {
+                    self.open(id);
+                }
+            }
+            KeyCode::Up => step(&mut self.search_state, self.matches.len(), false),
+            KeyCode::Down => step(&mut self.search_state, self.matches.len(), true),
+            KeyCode::Char('p') if ctrl => step(&mut self.search_state, self.matches.len(), false),
+            KeyCode::Char('n') if ctrl => step(&mut self.search_state, self.matches.len(), true),
+            KeyCode::Char('u') if ctrl => {
+                self.query.clear();
+                self.refilter();
+            }
+            KeyCode::Char(c) if !ctrl => {
+                self.query.push(c);
+                self.refilter();
+            }
+            KeyCode::Backspace => {
+                self.query.pop();
+                self.refilter();
+            }
+            _ => {}
+        }
+    }
+
+    fn browse_key(&mut self, code: KeyCode) {
+        let Some(view) = self.trail.last_mut() else {
+            self.mode = Mode::Search;
+            return;
+        };
+        let len = view.len(view.tab);
+        match code {
+            KeyCode::Char('q') => self.should_quit = true,
+            KeyCode::Char('/') => self.mode = Mode::Search,
+            KeyCode::Tab | KeyCode::Right | KeyCode::Char('l') => view.cycle_tab(true),
+            KeyCode::BackTab | KeyCode::Left | KeyCode::Char('h') => view.cycle_tab(false),
+            KeyCode::Up | KeyCode::Char('k') => step(&mut view.state, len, false),
+            KeyCode::Down | KeyCode::Char('j') => step(&mut view.state, len, true),
+            KeyCode::Enter => {
+                if let Some(id) = view.selected().map(|entry| entry.id) {
+                    self.open(id);
+                }
+            }
+            KeyCode::Esc | KeyCode::Backspace | KeyCode::Char('b') => {
+                self.trail.pop();
+                if self.trail.is_empty() {
+                    self.mode = Mode::Search;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Browse the symbol `id`, keeping the current one to go back to
+    fn open(&mut self, id: SymbolId) {
+        if self.current().is_some_and(|view| view.symbol.id == id) {
+            self.mode = Mode::Browse;
+            return;
+        }
+        if let Some(symbol) = self.facade.get_symbol(id) {
+            self.trail.push(View::new(self.facade, symbol));
+            self.mode = Mode::Browse;
+        }
+    }
+
+    fn refilter(&mut self) {
+        self.matches = if self.query.is_empty() {
+            (0..self.entries.len().min(MAX_MATCHES)).collect()
+        } else {
+            let mut scored: Vec<(i64, usize)> = self
+                .entries
+                .iter()
+                .enumerate()
+                .filter_map(|(index, entry)| {
+                    fuzzy_score(&self.query, &entry.name).map(|score| (score, index))
+                })
+                .collect();
+            scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+            scored
+                .into_iter()
+                .take(MAX_MATCHES)
+                .map(|(_, index)| index)
+                .collect()
+        };
+        self.search_state
+            .select((!self.matches.is_empty()).then_some(0));
+    }
+}
+
+/// Move a list selection one row, wrapping at either end
+fn step(state: &mut ListState, len: usize, down: bool) {
+    if len == 0 {
+        return;
+    }
+    let current = state.selected().unwrap_or(0);
+    let next = if down {
+        (current + 1) % len
+    } else {
+        (current + len - 1) % len
+    };
+    state.select(Some(next));
+}
+
+/// How well `candidate` matches `query` as a case-insensitive subsequence,
+/// higher is better. `None` when the query's characters do not all appear
+/// in order.
+///
+/// Consecutive characters and matches at word starts (after `_`, `:`, `.`,
+/// or a lowercase-to-uppercase step) score higher; gaps and long names
+/// score lower.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    let chars: Vec<char> = candidate.chars().collect();
+    let mut score = 0i64;
+    let mut from = 0;
+    let mut previous: Option<usize> = None;
+
+    for wanted in query.chars() {
+        let found = (from..chars.len()).find(|&i| chars_match(chars[i], wanted))?;
+        score += 10;
+        if previous.is_some_and(|previous| previous + 1 == found) {
+            score += 15;
+        }
+        let word_start = found == 0
+            || matches!(chars[found - 1], '_' | ':' | '.' | '-' | '$')
+            || (chars[found].is_uppercase() && chars[found - 1].is_lowercase());
+        if word_start {
+            score += 20;
+        }
+        score -= (found - from) as i64;
+        previous = Some(found);
+        from = found + 1;
+    }
+
+    if query.chars().count() == chars.len() {
+        // Every character matched in order, so this is the name itself
+        score += 100;
+    }
+    Some(score - chars.len() as i64 / 4)
+}
+
+fn chars_match(a: char, b: char) -> bool {
+    a == b || a.to_lowercase().eq(b.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_score_prefers_word_starts_and_exact_names() {
+        assert!(fuzzy_score("xyz", "parse_file").is_none());
+        assert!(fuzzy_score("fp", "parse_file").is_none());
+
+        let exact = fuzzy_score("parse", "parse").unwrap();
+        let prefix = fuzzy_score("parse", "parse_file").unwrap();
+        assert!(exact > prefix);
+
+        // `pf` hits the starts of both words here, but not in `perfect`
+        let boundary = fuzzy_score("pf", "parse_file").unwrap();
+        let inner = fuzzy_score("pf", "perfect").unwrap();
+        assert!(boundary > inner);
+        assert!(fuzzy_score("GSC", "get_symbol_context").is_some());
+        assert!(
+            fuzzy_score("gsc", "getSymbolContext").unwrap()
+                > fuzzy_score("gsc", "gossip_cache").unwrap()
+        );
+    }
+}
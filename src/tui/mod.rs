@@ -0,0 +1,36 @@
+//! Terminal code graph explorer behind `codanna explore`.
+//!
+//! Fuzzy-find a symbol by name, read its signature and docs, and walk its
+//! callers, callees, implementations, and file outline from the keyboard.
+//! Everything shown comes from the index through [`IndexFacade`]; no source
+//! file is reparsed.
+
+mod app;
+mod ui;
+
+pub use app::fuzzy_score;
+
+use crate::indexing::facade::IndexFacade;
+use app::App;
+use ratatui::DefaultTerminal;
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+
+/// Run the explorer until the user quits, restoring the terminal afterwards
+pub fn run(facade: &IndexFacade) -> std::io::Result<()> {
+    let mut app = App::new(facade);
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, &mut app);
+    ratatui::restore();
+    result
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, app: &mut App) -> std::io::Result<()> {
+    while !app.should_quit {
+        terminal.draw(|frame| ui::draw(frame, app))?;
+        match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => app.handle_key(key),
+            _ => {}
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,159 @@
+//! Explorer rendering
+
+use super::app::{App, Entry, Mode, Tab, View};
+use crate::types::PositionEncoding;
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, List, ListItem, Paragraph, Tabs, Wrap};
+
+const SEARCH_HELP: &str = "type to search  ↑/↓ select  Enter open  Esc back/quit  Ctrl-C quit";
+const BROWSE_HELP: &str =
+    "Tab/←/→ switch list  ↑/↓ select  Enter open  Backspace back  / search  q quit";
+
+pub fn draw(frame: &mut Frame, app: &mut App) {
+    let [body, footer] =
+        Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+    let help = match app.mode {
+        Mode::Search => SEARCH_HELP,
+        Mode::Browse => BROWSE_HELP,
+    };
+    frame.render_widget(
+        Paragraph::new(help).style(Style::new().fg(Color::DarkGray)),
+        footer,
+    );
+
+    match app.mode {
+        Mode::Search => draw_search(frame, app, body),
+        Mode::Browse => draw_browse(frame, app, body),
+    }
+}
+
+fn draw_search(frame: &mut Frame, app: &mut App, area: Rect) {
+    let [input, results] =
+        Layout::vertical([Constraint::Length(3), Constraint::Min(1)]).areas(area);
+    let [list_area, preview_area] =
+        Layout::horizontal([Constraint::Percentage(45), Constraint::Percentage(55)]).areas(results);
+
+    frame.render_widget(
+        Paragraph::new(app.query.as_str()).block(Block::bordered().title(" Find symbol ")),
+        input,
+    );
+    frame.set_cursor_position((input.x + 1 + app.query.chars().count() as u16, input.y + 1));
+
+    let items: Vec<ListItem> = app
+        .matches
+        .iter()
+        .map(|&index| entry_item(app.entry(index), 0))
+        .collect();
+    let title = format!(" {} matches ", app.matches.len());
+    let preview = app
+        .selected_match()
+        .and_then(|entry| app.facade().hover(entry.id, PositionEncoding::Byte))
+        .map(|hover| hover.contents.value)
+        .unwrap_or_default();
+
+    frame.render_stateful_widget(
+        selectable(items).block(Block::bordered().title(title)),
+        list_area,
+        &mut app.search_state,
+    );
+    frame.render_widget(
+        Paragraph::new(preview)
+            .wrap(Wrap { trim: false })
+            .block(Block::bordered().title(" Preview ")),
+        preview_area,
+    );
+}
+
+fn draw_browse(frame: &mut Frame, app: &mut App, area: Rect) {
+    let depth = app.trail.len();
+    let Some(view) = app.current_mut() else {
+        return;
+    };
+    let [doc_area, related_area] =
+        Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(area);
+    let [tabs_area, list_area] =
+        Layout::vertical([Constraint::Length(3), Constraint::Min(1)]).areas(related_area);
+
+    let doc = view
+        .hover
+        .as_ref()
+        .map(|hover| hover.contents.value.clone())
+        .unwrap_or_else(|| view.symbol.name.to_string());
+    let title = format!(" {} ({:?}) [{depth}] ", view.symbol.name, view.symbol.kind);
+    frame.render_widget(
+        Paragraph::new(doc)
+            .wrap(Wrap { trim: false })
+            .block(Block::bordered().title(title)),
+        doc_area,
+    );
+
+    let titles: Vec<String> = Tab::ALL
+        .iter()
+        .map(|tab| format!("{} ({})", tab.title(), view.len(*tab)))
+        .collect();
+    let selected_tab = Tab::ALL.iter().position(|tab| *tab == view.tab);
+    frame.render_widget(
+        Tabs::new(titles)
+            .select(selected_tab.unwrap_or(0))
+            .highlight_style(Style::new().add_modifier(Modifier::BOLD | Modifier::UNDERLINED))
+            .block(Block::bordered()),
+        tabs_area,
+    );
+
+    let items = tab_items(view);
+    let block = Block::bordered().title(format!(" {} ", view.tab.title()));
+    if items.is_empty() {
+        frame.render_widget(
+            Paragraph::new("Nothing recorded in the index")
+                .style(Style::new().fg(Color::DarkGray))
+                .block(block),
+            list_area,
+        );
+    } else {
+        frame.render_stateful_widget(selectable(items).block(block), list_area, &mut view.state);
+    }
+}
+
+fn tab_items(view: &View) -> Vec<ListItem<'static>> {
+    let flat = |entries: &[Entry]| -> Vec<ListItem<'static>> {
+        entries.iter().map(|entry| entry_item(entry, 0)).collect()
+    };
+    match view.tab {
+        Tab::Callers => flat(&view.callers),
+        Tab::Callees => flat(&view.callees),
+        Tab::Implementations => flat(&view.implementations),
+        Tab::Outline => view
+            .outline
+            .iter()
+            .map(|(depth, entry)| {
+                let item = entry_item(entry, *depth);
+                if entry.id == view.symbol.id {
+                    item.style(Style::new().add_modifier(Modifier::BOLD))
+                } else {
+                    item
+                }
+            })
+            .collect(),
+    }
+}
+
+/// `name  Kind  path:line`, indented by `depth`
+fn entry_item(entry: &Entry, depth: usize) -> ListItem<'static> {
+    ListItem::new(Line::from(vec![
+        Span::raw(format!("{:indent$}{}", "", entry.name, indent = depth * 2)),
+        Span::styled(format!("  {:?}", entry.kind), Style::new().fg(Color::Cyan)),
+        Span::styled(
+            format!("  {}", entry.location),
+            Style::new().fg(Color::DarkGray),
+        ),
+    ]))
+}
+
+fn selectable(items: Vec<ListItem<'static>>) -> List<'static> {
+    List::new(items)
+        .highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+        .highlight_symbol("> ")
+}
@@ -1,5 +1,7 @@
+mod position;
 mod symbol_counter;
 
+pub use position::{LineIndex, PositionEncoding, byte_column, convert_column, encoded_column};
 pub use symbol_counter::SymbolCounter;
 
 use serde::{Deserialize, Serialize};
@@ -119,24 +121,29 @@ impl Range {
 impl FromStr for SymbolKind {
     type Err = &'static str;
 
+    /// Case-insensitive, so user-written filters can say `function`
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "Function" => Ok(SymbolKind::Function),
-            "Method" => Ok(SymbolKind::Method),
-            "Struct" => Ok(SymbolKind::Struct),
-            "Enum" => Ok(SymbolKind::Enum),
-            "Trait" => Ok(SymbolKind::Trait),
-            "Interface" => Ok(SymbolKind::Interface),
-            "Class" => Ok(SymbolKind::Class),
-            "Module" => Ok(SymbolKind::Module),
-            "Variable" => Ok(SymbolKind::Variable),
-            "Constant" => Ok(SymbolKind::Constant),
-            "Field" => Ok(SymbolKind::Field),
-            "Parameter" => Ok(SymbolKind::Parameter),
-            "TypeAlias" => Ok(SymbolKind::TypeAlias),
-            "Macro" => Ok(SymbolKind::Macro),
-            _ => Err("Unknown symbol kind"),
-        }
+        const KINDS: &[(&str, SymbolKind)] = &[
+            ("Function", SymbolKind::Function),
+            ("Method", SymbolKind::Method),
+            ("Struct", SymbolKind::Struct),
+            ("Enum", SymbolKind::Enum),
+            ("Trait", SymbolKind::Trait),
+            ("Interface", SymbolKind::Interface),
+            ("Class", SymbolKind::Class),
+            ("Module", SymbolKind::Module),
+            ("Variable", SymbolKind::Variable),
+            ("Constant", SymbolKind::Constant),
+            ("Field", SymbolKind::Field),
+            ("Parameter", SymbolKind::Parameter),
+            ("TypeAlias", SymbolKind::TypeAlias),
+            ("Macro", SymbolKind::Macro),
+        ];
+        KINDS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(s))
+            .map(|(_, kind)| *kind)
+            .ok_or("Unknown symbol kind")
     }
 }
 
@@ -173,6 +180,14 @@ mod tests {
         assert_eq!(id.value(), 100);
     }
 
+    #[test]
+    fn test_symbol_kind_from_str_ignores_case() {
+        assert_eq!("TypeAlias".parse(), Ok(SymbolKind::TypeAlias));
+        assert_eq!("typealias".parse(), Ok(SymbolKind::TypeAlias));
+        assert_eq!("FUNCTION".parse(), Ok(SymbolKind::Function));
+        assert!("type_alias".parse::<SymbolKind>().is_err());
+    }
+
     #[test]
     fn test_range_creation() {
         let range = Range::new(10, 5, 15, 20);
@@ -0,0 +1,272 @@
+//! Column encodings and line/offset conversion
+//!
+//! Parsers store columns as byte offsets into the line (tree-sitter's
+//! `Point::column`), while editors speak UTF-16 code units (LSP's default)
+//! and some clients count Unicode scalar values. The three only agree on
+//! ASCII lines. [`LineIndex`] maps between byte offsets and `(line, column)`
+//! positions of a file in any of these encodings; [`convert_column`] does
+//! the same for a single line that is already at hand.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Unit in which a column is counted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PositionEncoding {
+    /// UTF-8 bytes, as stored in the index
+    #[default]
+    Byte,
+    /// Unicode scalar values (LSP `utf-32`)
+    Char,
+    /// UTF-16 code units (LSP `utf-16`, the protocol default)
+    Utf16,
+}
+
+impl PositionEncoding {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Byte => "byte",
+            Self::Char => "char",
+            Self::Utf16 => "utf-16",
+        }
+    }
+
+    /// Width of `c` in this encoding
+    fn width(self, c: char) -> usize {
+        match self {
+            Self::Byte => c.len_utf8(),
+            Self::Char => 1,
+            Self::Utf16 => c.len_utf16(),
+        }
+    }
+}
+
+impl fmt::Display for PositionEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for PositionEncoding {
+    type Err = String;
+
+    /// Accepts our names as well as the LSP `PositionEncodingKind` values
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "byte" | "bytes" | "utf-8" | "utf8" => Ok(Self::Byte),
+            "char" | "chars" | "utf-32" | "utf32" => Ok(Self::Char),
+            "utf-16" | "utf16" => Ok(Self::Utf16),
+            other => Err(format!(
+                "unknown position encoding '{other}' (expected byte, char, or utf-16)"
+            )),
+        }
+    }
+}
+
+/// Byte offset within `line` of `column` counted in `encoding`.
+///
+/// Columns past the end clamp to the line length; a column inside a
+/// multi-unit character resolves to that character's start.
+pub fn byte_column(line: &str, column: usize, encoding: PositionEncoding) -> usize {
+    if encoding == PositionEncoding::Byte {
+        let mut column = column.min(line.len());
+        while !line.is_char_boundary(column) {
+            column -= 1;
+        }
+        return column;
+    }
+    let mut units = 0;
+    for (offset, c) in line.char_indices() {
+        let next = units + encoding.width(c);
+        if next > column {
+            return offset;
+        }
+        units = next;
+    }
+    line.len()
+}
+
+/// Column of byte offset `byte` within `line`, counted in `encoding`
+pub fn encoded_column(line: &str, byte: usize, encoding: PositionEncoding) -> usize {
+    let byte = byte_column(line, byte, PositionEncoding::Byte);
+    match encoding {
+        PositionEncoding::Byte => byte,
+        PositionEncoding::Char => line[..byte].chars().count(),
+        PositionEncoding::Utf16 => line[..byte].encode_utf16().count(),
+    }
+}
+
+/// Convert a column of `line` from one encoding to another
+pub fn convert_column(
+    line: &str,
+    column: usize,
+    from: PositionEncoding,
+    to: PositionEncoding,
+) -> usize {
+    if from == to || line.is_ascii() {
+        return column.min(line.len());
+    }
+    encoded_column(line, byte_column(line, column, from), to)
+}
+
+/// Line start offsets of one file, for converting between byte offsets and
+/// zero-based `(line, column)` positions.
+///
+/// Newlines are found with `memchr`, which scans many bytes per step. The
+/// index holds offsets only, so it can be built once per file and kept
+/// beside the text; methods take the text it was built from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineIndex {
+    /// Byte offset at which each line starts; the first is always 0
+    line_starts: Vec<usize>,
+    /// Length of the indexed text
+    len: usize,
+}
+
+impl LineIndex {
+    pub fn new(text: &str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(memchr::memchr_iter(b'\n', text.as_bytes()).map(|i| i + 1))
+            .collect();
+        Self {
+            line_starts,
+            len: text.len(),
+        }
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Byte offset at which zero-based `line` starts
+    pub fn line_start(&self, line: u32) -> Option<usize> {
+        self.line_starts.get(line as usize).copied()
+    }
+
+    /// Zero-based line containing byte `offset`, clamped to the last line
+    pub fn line_of(&self, offset: usize) -> u32 {
+        (self.line_starts.partition_point(|&start| start <= offset) - 1) as u32
+    }
+
+    /// Text of zero-based `line`, without its line terminator
+    pub fn line<'t>(&self, text: &'t str, line: u32) -> Option<&'t str> {
+        let start = self.line_start(line)?;
+        let end = self
+            .line_starts
+            .get(line as usize + 1)
+            .map_or(self.len, |next| next - 1);
+        let line = text.get(start..end)?;
+        Some(line.strip_suffix('\r').unwrap_or(line))
+    }
+
+    /// Byte offset of `line:column`, with `column` counted in `encoding`.
+    /// `None` when the line doesn't exist.
+    pub fn offset(
+        &self,
+        text: &str,
+        line: u32,
+        column: u32,
+        encoding: PositionEncoding,
+    ) -> Option<usize> {
+        let line_text = self.line(text, line)?;
+        Some(self.line_starts[line as usize] + byte_column(line_text, column as usize, encoding))
+    }
+
+    /// Zero-based line and `encoding` column of byte `offset`, clamped to
+    /// the end of the text
+    pub fn position(&self, text: &str, offset: usize, encoding: PositionEncoding) -> (u32, u32) {
+        let offset = offset.min(self.len);
+        let line = self.line_of(offset);
+        let line_text = self.line(text, line).unwrap_or_default();
+        let column = encoded_column(
+            line_text,
+            offset - self.line_starts[line as usize],
+            encoding,
+        );
+        (line, column as u32)
+    }
+
+    /// Convert the column of `line:column` between encodings. `None` when
+    /// the line doesn't exist.
+    pub fn convert(
+        &self,
+        text: &str,
+        line: u32,
+        column: u32,
+        from: PositionEncoding,
+        to: PositionEncoding,
+    ) -> Option<u32> {
+        let line_text = self.line(text, line)?;
+        Some(convert_column(line_text, column as usize, from, to) as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column_conversion() {
+        // 'é' is 2 bytes / 1 UTF-16 unit, '🦀' is 4 bytes / 2 UTF-16 units
+        let line = "let é = \"🦀\"; x";
+        let x_byte = line.find('x').unwrap();
+        assert_eq!(x_byte, 17);
+
+        let utf16 = encoded_column(line, x_byte, PositionEncoding::Utf16);
+        let chars = encoded_column(line, x_byte, PositionEncoding::Char);
+        assert_eq!((utf16, chars), (14, 13));
+        assert_eq!(byte_column(line, utf16, PositionEncoding::Utf16), x_byte);
+        assert_eq!(byte_column(line, chars, PositionEncoding::Char), x_byte);
+
+        // Inside the crab's surrogate pair, and past the end
+        assert_eq!(byte_column(line, 10, PositionEncoding::Utf16), 10);
+        assert_eq!(byte_column(line, 99, PositionEncoding::Utf16), line.len());
+        assert_eq!(
+            convert_column(line, 14, PositionEncoding::Utf16, PositionEncoding::Char),
+            13
+        );
+        assert_eq!(
+            "utf-16".parse::<PositionEncoding>(),
+            Ok(PositionEncoding::Utf16)
+        );
+    }
+
+    #[test]
+    fn test_line_index_round_trip() {
+        let text = "fn a() {}\r\n// ü\nlet b = 1;";
+        let index = LineIndex::new(text);
+        assert_eq!(index.line_count(), 3);
+        assert_eq!(index.line(text, 0), Some("fn a() {}"));
+        assert_eq!(index.line(text, 1), Some("// ü"));
+        assert_eq!(index.line(text, 3), None);
+
+        let b = text.find('b').unwrap();
+        assert_eq!(index.line_of(b), 2);
+        assert_eq!(index.position(text, b, PositionEncoding::Byte), (2, 4));
+        assert_eq!(index.offset(text, 2, 4, PositionEncoding::Utf16), Some(b));
+
+        // End of the line with the umlaut
+        assert_eq!(index.position(text, b - 5, PositionEncoding::Utf16), (1, 4));
+        assert_eq!(
+            index.convert(text, 1, 5, PositionEncoding::Byte, PositionEncoding::Utf16),
+            Some(4)
+        );
+    }
+
+    #[test]
+    fn test_line_index_edges() {
+        let empty = LineIndex::new("");
+        assert_eq!(empty.line_count(), 1);
+        assert_eq!(empty.line("", 0), Some(""));
+
+        // A trailing newline opens an empty last line
+        let text = "a\n\nb\n";
+        let index = LineIndex::new(text);
+        assert_eq!(index.line_count(), 4);
+        assert_eq!(index.line(text, 1), Some(""));
+        assert_eq!(index.line(text, 3), Some(""));
+        assert_eq!(index.line_start(2), Some(3));
+    }
+}
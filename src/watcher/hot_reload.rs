@@ -3,7 +3,7 @@
 //! Polls for changes to the index made by external processes (CI/CD, other terminals)
 //! and hot-reloads them without restarting the server.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 use tokio::sync::RwLock;
@@ -12,12 +12,14 @@ use tracing::{debug, info, warn};
 
 use crate::indexing::facade::IndexFacade;
 use crate::mcp::notifications::{FileChangeEvent, NotificationBroadcaster};
+use crate::storage::generation::GENERATION_MARKER;
 use crate::{IndexPersistence, Settings};
 
 /// Watches for external index changes and hot-reloads them.
 ///
-/// This watcher polls `meta.json` and `state.json` to detect when the index
-/// is modified by external processes (e.g., `codanna index` in another terminal,
+/// This watcher polls the index's published generation marker (`meta.json`
+/// for indexes written before markers existed) and `state.json` to detect
+/// when the index is modified by external processes (e.g., `codanna index` in another terminal,
 /// CI/CD pipelines). It does NOT watch source files - that's handled by UnifiedWatcher.
 pub struct HotReloadWatcher {
     index_path: PathBuf,
@@ -41,8 +43,7 @@ impl HotReloadWatcher {
         let persistence = IndexPersistence::new(index_path.clone());
 
         // Get initial modification time of the index metadata file
-        let meta_file_path = index_path.join("tantivy").join("meta.json");
-        let last_modified = std::fs::metadata(&meta_file_path)
+        let last_modified = std::fs::metadata(change_marker(&index_path))
             .ok()
             .and_then(|meta| meta.modified().ok());
 
@@ -95,9 +96,10 @@ impl HotReloadWatcher {
             return Ok(());
         }
 
-        // Get current modification time of the index metadata file
-        let meta_file_path = self.index_path.join("tantivy").join("meta.json");
-        let metadata = std::fs::metadata(&meta_file_path)?;
+        // Get current modification time of the index metadata file. The
+        // generation marker only changes once an update is complete, where
+        // meta.json changes on every commit of it.
+        let metadata = std::fs::metadata(change_marker(&self.index_path))?;
         let current_modified = metadata.modified()?;
 
         // Check if file has been modified
@@ -227,3 +229,14 @@ pub struct IndexStats {
     pub last_modified: Option<SystemTime>,
     pub index_path: PathBuf,
 }
+
+/// File whose modification time signals a new version of the index
+fn change_marker(index_path: &Path) -> PathBuf {
+    let tantivy = index_path.join("tantivy");
+    let marker = tantivy.join(GENERATION_MARKER);
+    if marker.exists() {
+        marker
+    } else {
+        tantivy.join("meta.json")
+    }
+}
@@ -123,8 +123,8 @@ impl UnifiedWatcher {
                 // Process debounced changes
                 _ = &mut timeout => {
                     let ready = self.debouncer.take_ready();
-                    for path in ready {
-                        self.process_modification(&path).await;
+                    if !ready.is_empty() {
+                        self.process_modifications(ready).await;
                     }
                 }
 
@@ -193,6 +193,18 @@ impl UnifiedWatcher {
         }
     }
 
+    /// Process a burst of debounced modifications as one index generation,
+    /// so queries see either none of the changed files or all of them.
+    async fn process_modifications(&self, paths: Vec<PathBuf>) {
+        self.facade.read().await.begin_generation();
+        for path in paths {
+            self.process_modification(&path).await;
+        }
+        if let Err(e) = self.facade.write().await.publish_generation() {
+            tracing::error!("[watcher] failed to publish index generation: {e}");
+        }
+    }
+
     /// Process a debounced file modification.
     async fn process_modification(&self, path: &Path) {
         // Check if file still exists (handles rename-as-modify on macOS)
@@ -207,6 +219,7 @@ impl UnifiedWatcher {
             }
 
             crate::log_event!(handler.name(), "modified", "{}", path.display());
+            crate::metrics::record_watch_event(handler.name(), "modified");
 
             match handler.on_modify(path).await {
                 Ok(action) => {
@@ -229,6 +242,7 @@ impl UnifiedWatcher {
             }
 
             crate::log_event!(handler.name(), "deleted", "{}", path.display());
+            crate::metrics::record_watch_event(handler.name(), "deleted");
 
             match handler.on_delete(path).await {
                 Ok(action) => {
@@ -0,0 +1,84 @@
+//! Processes loading one index at the same time must not wait for each
+//! other, even when the index has an interrupted update to redo.
+
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+use tempfile::TempDir;
+
+fn codanna_binary() -> PathBuf {
+    PathBuf::from(env!("CARGO_BIN_EXE_codanna"))
+}
+
+fn codanna(workspace: &Path, args: &[&str]) -> Command {
+    let mut command = Command::new(codanna_binary());
+    command
+        .args(args)
+        .current_dir(workspace)
+        .env("HOME", workspace.join("home"))
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    command
+}
+
+/// Wait for `child`, killing it and failing the test once `deadline` passes
+fn finish(mut child: Child, deadline: Instant) -> (bool, String) {
+    loop {
+        if child.try_wait().expect("poll codanna").is_some() {
+            let output = child.wait_with_output().expect("collect output");
+            return (
+                output.status.success(),
+                String::from_utf8_lossy(&output.stdout).to_string(),
+            );
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            panic!("codanna did not finish: concurrent loads deadlocked");
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+}
+
+#[test]
+fn concurrent_loads_with_pending_recovery_do_not_deadlock() {
+    let temp_dir = TempDir::new().expect("create temp dir");
+    let workspace = temp_dir.path();
+    std::fs::create_dir_all(workspace.join(".codanna")).expect("create config dir");
+    std::fs::write(
+        workspace.join(".codanna/settings.toml"),
+        "index_path = \"index\"\n",
+    )
+    .expect("write settings");
+    std::fs::create_dir_all(workspace.join("src")).expect("create src");
+    std::fs::write(workspace.join("src/lib.rs"), "pub fn greet() {}\n").expect("write source");
+
+    let status = codanna(workspace, &["index", "src", "--no-progress"])
+        .status()
+        .expect("run codanna index");
+    assert!(status.success(), "codanna index failed");
+
+    // An update begun by a writer that is gone, so every load has to redo it
+    // under the write lock
+    let mut wal = std::fs::read_to_string(workspace.join("index/wal.log")).unwrap_or_default();
+    wal.push_str(
+        "{\"op\":\"begin\",\"pid\":4294967295,\"run\":7,\"seq\":1,\"update\":{\"kind\":\"index_file\",\"path\":\"src/lib.rs\"}}\n",
+    );
+    std::fs::write(workspace.join("index/wal.log"), wal).expect("plant interrupted update");
+
+    let retrieve = ["--wait", "retrieve", "symbol", "greet", "--json"];
+    let first = codanna(workspace, &retrieve).spawn().expect("spawn first");
+    let second = codanna(workspace, &retrieve).spawn().expect("spawn second");
+
+    let deadline = Instant::now() + Duration::from_secs(60);
+    for (succeeded, stdout) in [finish(first, deadline), finish(second, deadline)] {
+        assert!(succeeded, "retrieve failed: {stdout}");
+        assert!(stdout.contains("greet"), "greet not found: {stdout}");
+    }
+
+    // Nothing is left to redo, so a load that doesn't wait still succeeds
+    let output = codanna(workspace, &["retrieve", "symbol", "greet", "--json"])
+        .output()
+        .expect("run retrieve");
+    assert!(output.status.success());
+}
@@ -2,3 +2,6 @@
 
 #[path = "cli/test_plugin_commands.rs"]
 mod test_plugin_commands;
+
+#[path = "cli/test_concurrent_load.rs"]
+mod test_concurrent_load;
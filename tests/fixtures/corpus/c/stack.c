@@ -0,0 +1,19 @@
+/* Fixed-size integer stack */
+#include <stddef.h>
+
+#define STACK_SIZE 16
+
+struct stack {
+    int items[STACK_SIZE];
+    size_t top;
+};
+
+enum status { STACK_OK, STACK_FULL };
+
+enum status stack_push(struct stack *s, int value) {
+    if (s->top == STACK_SIZE) {
+        return STACK_FULL;
+    }
+    s->items[s->top++] = value;
+    return STACK_OK;
+}
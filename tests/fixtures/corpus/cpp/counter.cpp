@@ -0,0 +1,21 @@
+// Counting helpers
+#include <string>
+
+namespace metrics {
+
+class Counter {
+public:
+    explicit Counter(std::string name) : name_(std::move(name)) {}
+    void increment() { ++count_; }
+    int value() const { return count_; }
+
+private:
+    std::string name_;
+    int count_ = 0;
+};
+
+int total(const Counter& a, const Counter& b) {
+    return a.value() + b.value();
+}
+
+}  // namespace metrics
@@ -0,0 +1,22 @@
+using System.Collections.Generic;
+
+namespace Shop
+{
+    /// <summary>An item kind.</summary>
+    public enum Category { Food, Tool }
+
+    public interface IStock
+    {
+        int Count(string sku);
+    }
+
+    /// <summary>Items on hand.</summary>
+    public class Inventory : IStock
+    {
+        private readonly Dictionary<string, int> _items = new();
+
+        public int Count(string sku) => _items.TryGetValue(sku, out var n) ? n : 0;
+
+        public void Add(string sku) { _items[sku] = Count(sku) + 1; }
+    }
+}
@@ -0,0 +1,18 @@
+extends CharacterBody2D
+class_name Player
+
+## Emitted when health reaches zero
+signal died
+
+const MAX_HEALTH := 100
+
+@export var speed: float = 200.0
+var health := MAX_HEALTH
+
+func take_damage(amount: int) -> void:
+	health -= amount
+	if health <= 0:
+		died.emit()
+
+func _physics_process(delta: float) -> void:
+	move_and_slide()
@@ -0,0 +1,19 @@
+// Package shapes holds geometry types.
+package shapes
+
+// Point is a point in 2D space.
+type Point struct {
+	X float64
+	Y float64
+}
+
+// Norm is the squared distance from the origin.
+func (p Point) Norm() float64 {
+	return p.X*p.X + p.Y*p.Y
+}
+
+const Origin = 0
+
+func NewPoint(x float64) Point {
+	return Point{X: x}
+}
@@ -0,0 +1,20 @@
+package com.example.library;
+
+import java.util.ArrayList;
+import java.util.List;
+
+/** A shelf of books. */
+public class Library {
+    public static final int CAPACITY = 100;
+
+    private final List<String> titles = new ArrayList<>();
+
+    /** Adds a title if there is room. */
+    public boolean add(String title) {
+        return titles.size() < CAPACITY && titles.add(title);
+    }
+
+    public interface Listener {
+        void onAdded(String title);
+    }
+}
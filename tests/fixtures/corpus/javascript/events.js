@@ -0,0 +1,18 @@
+// Minimal event emitter
+const MAX_LISTENERS = 10;
+
+class Emitter {
+  constructor() {
+    this.listeners = [];
+  }
+
+  on(listener) {
+    this.listeners.push(listener);
+  }
+}
+
+function createEmitter() {
+  return new Emitter();
+}
+
+module.exports = { Emitter, createEmitter, MAX_LISTENERS };
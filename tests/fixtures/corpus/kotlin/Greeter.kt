@@ -0,0 +1,12 @@
+package com.example.greeting
+
+/** Formats greetings. */
+class Greeter(private val prefix: String) {
+    fun greet(name: String): String = "$prefix, $name"
+}
+
+object Defaults {
+    const val PREFIX = "Hello"
+}
+
+fun defaultGreeter(): Greeter = Greeter(Defaults.PREFIX)
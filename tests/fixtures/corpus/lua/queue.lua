@@ -0,0 +1,19 @@
+-- A first-in first-out queue
+local Queue = {}
+Queue.__index = Queue
+
+local DEFAULT_CAPACITY = 8
+
+function Queue.new(capacity)
+  return setmetatable({ items = {}, capacity = capacity or DEFAULT_CAPACITY }, Queue)
+end
+
+function Queue:push(item)
+  table.insert(self.items, item)
+end
+
+local function is_empty(queue)
+  return #queue.items == 0
+end
+
+return Queue
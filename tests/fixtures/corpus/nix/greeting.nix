@@ -0,0 +1,9 @@
+# Greeting helpers
+let
+  name = "world";
+  greet = who: "Hello ${who}";
+in
+{
+  message = greet name;
+  version = "1.0";
+}
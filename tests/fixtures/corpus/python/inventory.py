@@ -0,0 +1,18 @@
+"""Inventory helpers."""
+
+MAX_ITEMS = 100
+
+
+class Inventory:
+    """A collection of items."""
+
+    def __init__(self, owner):
+        self.owner = owner
+        self.items = []
+
+    def add(self, item):
+        self.items.append(item)
+
+
+def create_inventory(owner):
+    return Inventory(owner)
@@ -0,0 +1,27 @@
+//! Geometry helpers
+
+/// A point in 2D space
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Point {
+    /// Distance from the origin
+    pub fn norm(&self) -> f64 {
+        (self.x * self.x + self.y * self.y).sqrt()
+    }
+}
+
+pub enum Shape {
+    Circle,
+    Square,
+}
+
+pub type Coord = (f64, f64);
+
+pub const ORIGIN: Coord = (0.0, 0.0);
+
+pub fn origin() -> Point {
+    Point { x: 0.0, y: 0.0 }
+}
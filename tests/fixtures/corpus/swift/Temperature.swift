@@ -0,0 +1,25 @@
+import Foundation
+
+/// A temperature scale
+enum Scale {
+    case celsius
+    case fahrenheit
+}
+
+/// A temperature reading
+struct Temperature {
+    let degrees: Double
+    let scale: Scale
+
+    func inCelsius() -> Double {
+        scale == .celsius ? degrees : (degrees - 32) * 5 / 9
+    }
+}
+
+protocol Thermometer {
+    func read() -> Temperature
+}
+
+func freezing() -> Temperature {
+    Temperature(degrees: 0, scale: .celsius)
+}
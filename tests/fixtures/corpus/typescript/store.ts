@@ -0,0 +1,24 @@
+// A tiny key-value store
+export interface Entry {
+  key: string;
+  value: number;
+}
+
+export type Key = string;
+
+export enum Mode {
+  ReadOnly,
+  ReadWrite,
+}
+
+export class Store {
+  private entries: Entry[] = [];
+
+  get(key: Key): number | undefined {
+    return this.entries.find((entry) => entry.key === key)?.value;
+  }
+}
+
+export function createStore(): Store {
+  return new Store();
+}
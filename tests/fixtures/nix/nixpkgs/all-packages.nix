@@ -0,0 +1,41 @@
+# Shape of pkgs/top-level/all-packages.nix: each package is a callPackage of
+# its directory, with the whole package set in scope through `with`.
+
+{ lib, noSysDirs, config, overlays }:
+res: pkgs: super:
+
+with pkgs;
+
+{
+  # Call a file that returns several packages
+  callPackages = lib.callPackagesWith pkgs;
+
+  hello = callPackage ../applications/misc/hello { };
+
+  ripgrep = callPackage ../tools/text/ripgrep {
+    inherit (darwin.apple_sdk.frameworks) Security;
+  };
+
+  inherit (callPackages ../development/libraries/openssl { })
+    openssl_1_1
+    openssl_3;
+
+  openssl = openssl_3;
+
+  python3Packages = python3.pkgs;
+
+  rustPlatform = makeRustPlatform {
+    inherit (rustPackages) cargo rustc;
+  };
+
+  mkShellNoCC = mkShell.override { stdenv = stdenvNoCC; };
+
+  wrapFirefox = callPackage ../applications/networking/browsers/firefox/wrapper.nix { };
+
+  firefox = wrapFirefox firefox-unwrapped { };
+
+  # Fetcher for repositories that need a token
+  fetchFromGitHubPrivate = args: fetchFromGitHub (args // { private = true; });
+
+  recurseIntoAttrs = attrs: attrs // { recurseForDerivations = true; };
+}
@@ -0,0 +1,48 @@
+# A flake exposing per-system outputs through a forAllSystems helper, as the
+# nixpkgs flake does, next to system-independent ones.
+
+{
+  description = "Codanna packages, NixOS module and development shell";
+
+  inputs.nixpkgs.url = "github:NixOS/nixpkgs/nixos-unstable";
+
+  outputs =
+    { self, nixpkgs }:
+    let
+      inherit (nixpkgs) lib;
+
+      systems = [ "x86_64-linux" "aarch64-linux" "aarch64-darwin" ];
+
+      forAllSystems = f: lib.genAttrs systems (system: f nixpkgs.legacyPackages.${system});
+    in
+    {
+      overlays.default = import ./overlay.nix;
+
+      packages = forAllSystems (pkgs: {
+        default = pkgs.callPackage ./package.nix { };
+      });
+
+      devShells = forAllSystems (pkgs: {
+        default = pkgs.mkShell {
+          inputsFrom = [ self.packages.${pkgs.system}.default ];
+          packages = [ pkgs.cargo-watch ];
+        };
+      });
+
+      nixosModules.codanna = ./module.nix;
+
+      nixosConfigurations.demo = lib.nixosSystem {
+        system = "x86_64-linux";
+        modules = [
+          self.nixosModules.codanna
+          { services.codanna.enable = true; }
+        ];
+      };
+
+      checks = forAllSystems (pkgs: {
+        build = self.packages.${pkgs.system}.default;
+      });
+
+      formatter = forAllSystems (pkgs: pkgs.nixfmt-rfc-style);
+    };
+}
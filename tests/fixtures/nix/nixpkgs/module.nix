@@ -0,0 +1,50 @@
+# A NixOS module in the style of nixos/modules/services: options declared
+# under services.<name>, configuration guarded by mkIf.
+
+{ config, lib, pkgs, ... }:
+
+let
+  cfg = config.services.codanna;
+
+  settingsFormat = pkgs.formats.toml { };
+
+  configFile = settingsFormat.generate "codanna.toml" cfg.settings;
+
+  mkPortOption = default: lib.mkOption {
+    type = lib.types.port;
+    inherit default;
+    description = "Port the server listens on.";
+  };
+in
+{
+  imports = [ ./codanna-watcher.nix ];
+
+  options.services.codanna = {
+    enable = lib.mkEnableOption "the codanna code intelligence server";
+
+    package = lib.mkPackageOption pkgs "codanna" { };
+
+    port = mkPortOption 8080;
+
+    settings = lib.mkOption {
+      type = settingsFormat.type;
+      default = { };
+      description = "Settings written to codanna.toml.";
+    };
+  };
+
+  config = lib.mkIf cfg.enable {
+    systemd.services.codanna = {
+      description = "Codanna code intelligence server";
+      wantedBy = [ "multi-user.target" ];
+      after = [ "network.target" ];
+      serviceConfig = {
+        ExecStart = "${lib.getExe cfg.package} serve --http --port ${toString cfg.port} --config ${configFile}";
+        DynamicUser = true;
+        Restart = "on-failure";
+      };
+    };
+
+    networking.firewall.allowedTCPPorts = [ cfg.port ];
+  };
+}
@@ -0,0 +1,27 @@
+# An overlay: a function of the final and the previous package set, returning
+# the attributes it adds or replaces.
+
+final: prev: {
+  ripgrep = prev.ripgrep.override { withPCRE2 = false; };
+
+  hello = prev.hello.overrideAttrs (old: {
+    pname = "hello-patched";
+    patches = (old.patches or [ ]) ++ [ ./hello-greeting.patch ];
+  });
+
+  pythonPackagesExtensions = prev.pythonPackagesExtensions ++ [
+    (pyFinal: pyPrev: {
+      requests = pyPrev.requests.overridePythonAttrs { doCheck = false; };
+    })
+  ];
+
+  # Mark any package of the final set as patched
+  mkPatchedTool = name: final.${name}.overrideAttrs (old: {
+    passthru = (old.passthru or { }) // { patched = true; };
+  });
+
+  myTools = final.recurseIntoAttrs {
+    inherit (final) ripgrep hello;
+    lint = final.callPackage ./tools/lint { };
+  };
+}
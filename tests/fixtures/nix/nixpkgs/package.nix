@@ -0,0 +1,57 @@
+# Shape of pkgs/by-name/ri/ripgrep/package.nix: dependencies as formals, a
+# builder called with a function of its own final attributes.
+
+{
+  lib,
+  stdenv,
+  fetchFromGitHub,
+  rustPlatform,
+  pkg-config,
+  pcre2,
+  darwin,
+  nix-update-script,
+  testers,
+  withPCRE2 ? true,
+}:
+
+rustPlatform.buildRustPackage (finalAttrs: {
+  pname = "ripgrep";
+  version = "14.1.1";
+
+  src = fetchFromGitHub {
+    owner = "BurntSushi";
+    repo = "ripgrep";
+    tag = finalAttrs.version;
+    hash = "sha256-gyWnahj1A+iXUQlQ1O1H1u7K5euYQOld9qWm99Vjaeg=";
+  };
+
+  cargoHash = "sha256-b+iA8iTYWlczBpNq9eyHrWG8LMU4WPBzaU6pQRht+yE=";
+
+  nativeBuildInputs = [ pkg-config ];
+
+  buildInputs =
+    lib.optional withPCRE2 pcre2
+    ++ lib.optionals stdenv.hostPlatform.isDarwin [ darwin.apple_sdk.frameworks.Security ];
+
+  buildFeatures = lib.optional withPCRE2 "pcre2";
+
+  doCheck = stdenv.buildPlatform.canExecute stdenv.hostPlatform;
+
+  postInstall = ''
+    installManPage doc/rg.1
+    installShellCompletion --cmd rg complete/rg.{bash,fish}
+  '';
+
+  passthru = {
+    updateScript = nix-update-script { };
+    tests.version = testers.testVersion { package = finalAttrs.finalPackage; };
+  };
+
+  meta = {
+    description = "Utility that combines the usability of The Silver Searcher with the raw speed of grep";
+    homepage = "https://github.com/BurntSushi/ripgrep";
+    license = with lib.licenses; [ unlicense mit ];
+    maintainers = with lib.maintainers; [ ];
+    mainProgram = "rg";
+  };
+})
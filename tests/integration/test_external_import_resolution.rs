@@ -43,6 +43,7 @@ fn test_external_import_detection_prevents_local_resolution() {
         file_id,
         is_glob: false,
         is_type_only: false,
+        range: None,
     };
 
     println!("\n1. Populating external import: {}", external_import.path);
@@ -113,6 +114,7 @@ fn test_internal_import_not_flagged_as_external() {
         file_id,
         is_glob: false,
         is_type_only: false,
+        range: None,
     };
 
     println!("1. Populating internal import: {}", internal_import.path);
@@ -172,6 +174,7 @@ fn test_aliased_external_import_detection() {
         file_id,
         is_glob: false,
         is_type_only: false,
+        range: None,
     };
 
     println!(
@@ -239,6 +242,7 @@ fn test_multiple_external_imports() {
             file_id,
             is_glob: false,
             is_type_only: false,
+            range: None,
         },
         Import {
             path: "serde::Serialize".to_string(),
@@ -246,6 +250,7 @@ fn test_multiple_external_imports() {
             file_id,
             is_glob: false,
             is_type_only: false,
+            range: None,
         },
         Import {
             path: "tokio::sync::Mutex".to_string(),
@@ -253,6 +258,7 @@ fn test_multiple_external_imports() {
             file_id,
             is_glob: false,
             is_type_only: false,
+            range: None,
         },
     ];
 
@@ -330,6 +336,7 @@ fn test_external_import_same_name_as_local_symbol() {
         file_id,
         is_glob: false,
         is_type_only: false,
+        range: None,
     };
 
     println!("\n1. External import: {}", external_import.path);
@@ -98,6 +98,7 @@ async fn test_gdscript_semantic_search_and_analyze_impact() {
             symbol_name: None,
             symbol_id: Some(apply_damage_symbol_id),
             max_depth: 2,
+            unconditional_only: false,
         }))
         .await
         .expect("analyze_impact should succeed");
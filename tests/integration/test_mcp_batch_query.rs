@@ -0,0 +1,139 @@
+//! Tests for the batch_query MCP tool
+
+use std::sync::Arc;
+
+use codanna::config::Settings;
+use codanna::indexing::facade::IndexFacade;
+use codanna::mcp::{BatchQueryRequest, CodeIntelligenceServer, SubQuery};
+use rmcp::handler::server::wrapper::Parameters;
+use rmcp::model::CallToolResult;
+use tempfile::TempDir;
+
+fn test_server(temp_dir: &TempDir) -> CodeIntelligenceServer {
+    let workspace_root = temp_dir.path();
+    let source = workspace_root.join("main.rs");
+    std::fs::write(&source, "fn main() {\n    parse();\n}\n\nfn parse() {}\n")
+        .expect("write source");
+
+    let settings = Settings {
+        workspace_root: Some(workspace_root.to_path_buf()),
+        index_path: workspace_root.join(".codanna-index"),
+        ..Default::default()
+    };
+    let mut facade = IndexFacade::new(Arc::new(settings)).expect("create IndexFacade");
+    facade
+        .index_file(source.to_str().expect("utf8 path"))
+        .expect("index source");
+    CodeIntelligenceServer::new(facade)
+}
+
+fn sub_query(id: &str, tool: &str, arguments: serde_json::Value) -> SubQuery {
+    SubQuery {
+        id: id.to_string(),
+        tool: tool.to_string(),
+        arguments: arguments.as_object().cloned().unwrap_or_default(),
+    }
+}
+
+async fn run_batch(server: &CodeIntelligenceServer, queries: Vec<SubQuery>) -> CallToolResult {
+    server
+        .batch_query(Parameters(BatchQueryRequest {
+            queries,
+            max_tokens: None,
+            max_bytes: None,
+        }))
+        .await
+        .expect("batch_query should return a result")
+}
+
+fn text(result: &CallToolResult) -> &str {
+    result
+        .content
+        .first()
+        .and_then(|content| content.as_text())
+        .map(|text| text.text.as_str())
+        .expect("text content")
+}
+
+#[tokio::test]
+async fn test_batch_query_rejects_more_than_32_queries() {
+    let temp_dir = TempDir::new().unwrap();
+    let server = test_server(&temp_dir);
+
+    let queries = (0..33)
+        .map(|i| {
+            sub_query(
+                &format!("q{i}"),
+                "find_symbol",
+                serde_json::json!({"name": "main"}),
+            )
+        })
+        .collect();
+    let result = run_batch(&server, queries).await;
+
+    assert_eq!(result.is_error, Some(true));
+    assert!(text(&result).contains("Too many sub-queries: 33"));
+}
+
+#[tokio::test]
+async fn test_batch_query_rejects_duplicate_ids() {
+    let temp_dir = TempDir::new().unwrap();
+    let server = test_server(&temp_dir);
+
+    let queries = vec![
+        sub_query("same", "find_symbol", serde_json::json!({"name": "main"})),
+        sub_query("same", "find_symbol", serde_json::json!({"name": "parse"})),
+    ];
+    let result = run_batch(&server, queries).await;
+
+    assert_eq!(result.is_error, Some(true));
+    assert!(text(&result).contains("Duplicate sub-query id 'same'"));
+}
+
+#[tokio::test]
+async fn test_batch_query_reports_failing_sub_queries_alongside_results() {
+    let temp_dir = TempDir::new().unwrap();
+    let server = test_server(&temp_dir);
+
+    let queries = vec![
+        sub_query("found", "find_symbol", serde_json::json!({"name": "parse"})),
+        sub_query("unknown", "delete_index", serde_json::json!({})),
+        sub_query("nested", "batch_query", serde_json::json!({"queries": []})),
+        sub_query("invalid", "find_symbol", serde_json::json!({})),
+    ];
+    let result = run_batch(&server, queries).await;
+
+    assert_ne!(
+        result.is_error,
+        Some(true),
+        "one failure must not fail the batch"
+    );
+    let results: serde_json::Value = serde_json::from_str(text(&result)).expect("JSON results");
+
+    assert_eq!(results["found"]["is_error"], false);
+    assert!(
+        results["found"]["output"]
+            .as_str()
+            .unwrap()
+            .contains("parse")
+    );
+
+    for id in ["unknown", "nested"] {
+        assert_eq!(results[id]["is_error"], true, "{id} should fail");
+        assert!(
+            results[id]["output"]
+                .as_str()
+                .unwrap()
+                .contains("Unknown tool"),
+            "{id} should name the tools a sub-query can run"
+        );
+    }
+
+    assert_eq!(results["invalid"]["is_error"], true);
+    assert!(
+        results["invalid"]["output"]
+            .as_str()
+            .unwrap()
+            .contains("Invalid arguments")
+    );
+}
@@ -26,6 +26,7 @@ fn create_test_settings(config_files: Vec<PathBuf>, index_path: PathBuf) -> Sett
         extensions: vec!["ts".to_string(), "tsx".to_string()],
         parser_options: HashMap::new(),
         projects: Vec::new(),
+        profile: None,
     };
 
     settings
@@ -313,6 +314,7 @@ fn test_provider_not_active_when_disabled() {
         extensions: vec!["ts".to_string()],
         parser_options: HashMap::new(),
         projects: Vec::new(),
+        profile: None,
     };
 
     settings
@@ -7,6 +7,9 @@
 #[path = "integration/test_mcp_schema.rs"]
 mod test_mcp_schema;
 
+#[path = "integration/test_mcp_batch_query.rs"]
+mod test_mcp_batch_query;
+
 #[path = "integration/embedding_model_comparison.rs"]
 mod embedding_model_comparison;
 
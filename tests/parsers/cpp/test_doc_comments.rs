@@ -0,0 +1,36 @@
+//! Doc comment extraction for C++ declarations
+
+use codanna::FileId;
+use codanna::parsing::cpp::parser::CppParser;
+use codanna::types::SymbolCounter;
+
+#[test]
+fn test_cpp_doc_comment_nearest_and_own_line() {
+    let code = r#"
+/** Not the doc */
+/**
+ * Adds two numbers
+ */
+int add(int a, int b) { return a + b; }
+
+/// Returns zero
+int zero() { return 0; }
+
+int limit = 10; /// trailing note
+int local() { return limit; }
+"#;
+
+    let mut parser = CppParser::new().expect("Failed to create CppParser");
+    let mut counter = SymbolCounter::new();
+    let symbols = parser.parse(code, FileId(1), &mut counter);
+    let doc = |name: &str| {
+        symbols
+            .iter()
+            .find(|s| s.name.as_ref() == name)
+            .and_then(|s| s.doc_comment.as_deref())
+    };
+
+    assert_eq!(doc("add"), Some("Adds two numbers"));
+    assert_eq!(doc("zero"), Some("Returns zero"));
+    assert_eq!(doc("local"), None, "trailing comment belongs to limit");
+}
@@ -165,6 +165,7 @@ fn test_add_import() {
         alias: None,
         is_glob: false,
         is_type_only: false,
+        range: None,
     };
 
     // Should track import
@@ -198,6 +199,7 @@ fn test_multiple_imports_same_file() {
         alias: None,
         is_glob: false,
         is_type_only: false,
+        range: None,
     };
     let import2 = Import {
         file_id,
@@ -205,6 +207,7 @@ fn test_multiple_imports_same_file() {
         alias: Some("Gun".to_string()),
         is_glob: false,
         is_type_only: false,
+        range: None,
     };
 
     behavior.add_import(import1);
@@ -227,6 +230,7 @@ fn test_imports_isolated_by_file() {
         alias: None,
         is_glob: false,
         is_type_only: false,
+        range: None,
     };
     let import2 = Import {
         file_id: file2,
@@ -234,6 +238,7 @@ fn test_imports_isolated_by_file() {
         alias: None,
         is_glob: false,
         is_type_only: false,
+        range: None,
     };
 
     behavior.add_import(import1);
@@ -412,6 +417,7 @@ fn test_gdscript_class_name_import() {
         alias: None,
         is_glob: true, // Global visibility
         is_type_only: false,
+        range: None,
     };
 
     behavior.add_import(import);
@@ -433,6 +439,7 @@ fn test_gdscript_extends_import() {
         alias: None,
         is_glob: false,
         is_type_only: false,
+        range: None,
     };
 
     behavior.add_import(import);
@@ -454,6 +461,7 @@ fn test_gdscript_preload_import() {
         alias: Some("EnemyScene".to_string()),
         is_glob: false,
         is_type_only: false,
+        range: None,
     };
 
     behavior.add_import(import);
@@ -2,7 +2,7 @@ use codanna::parsing::gdscript::{
     GdscriptBehavior, GdscriptInheritanceResolver, GdscriptResolutionContext,
 };
 use codanna::parsing::{
-    InheritanceResolver, LanguageBehavior, ResolutionScope, ScopeLevel, ScopeType,
+    LanguageBehavior, NameKeyedInheritanceResolver, ResolutionScope, ScopeLevel, ScopeType,
 };
 use codanna::{FileId, SymbolId};
 
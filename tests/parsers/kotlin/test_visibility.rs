@@ -64,8 +64,8 @@ open class BaseClass {
         .iter()
         .find(|s| s.name.as_ref() == "protectedMethod");
     assert!(method_sym.is_some(), "Should find protectedMethod");
-    // Protected maps to Module in our visibility model
-    assert_eq!(method_sym.unwrap().visibility, Visibility::Module);
+    // Protected has its own level in our visibility model
+    assert_eq!(method_sym.unwrap().visibility, Visibility::Protected);
 }
 
 #[test]
@@ -132,7 +132,7 @@ class MixedClass {
 
     assert_eq!(public_val.unwrap().visibility, Visibility::Public);
     assert_eq!(private_val.unwrap().visibility, Visibility::Private);
-    assert_eq!(protected_val.unwrap().visibility, Visibility::Module);
+    assert_eq!(protected_val.unwrap().visibility, Visibility::Protected);
     assert_eq!(internal_val.unwrap().visibility, Visibility::Crate);
     assert_eq!(default_val.unwrap().visibility, Visibility::Public);
 }
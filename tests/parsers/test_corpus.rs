@@ -0,0 +1,284 @@
+//! Corpus-based differential test for every language parser
+//!
+//! Each file under `tests/fixtures/corpus/<language>/` is parsed with the
+//! parser registered for its extension, and the extracted symbols are
+//! compared against the golden snapshot stored next to it as
+//! `<file>.symbols.json`. Every registered language has at least one
+//! corpus file.
+//!
+//! The older Nix fixtures under `tests/fixtures/nix/` hold partial
+//! expectations instead, in an `expected_symbols.json` per directory: symbol
+//! counts and the kind and signature of chosen symbols. They are checked
+//! here as well.
+//!
+//! After an intended change in symbol extraction, regenerate the snapshots
+//! and review the diff:
+//!
+//! ```bash
+//! CODANNA_UPDATE_CORPUS=1 cargo test --test parsers_tests corpus
+//! ```
+
+use codanna::Settings;
+use codanna::parsing::get_registry;
+use codanna::types::{FileId, SymbolCounter};
+use codanna::{Symbol, SymbolKind};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const CORPUS_DIR: &str = "tests/fixtures/corpus";
+const UPDATE_ENV: &str = "CODANNA_UPDATE_CORPUS";
+
+/// Fixture directories described by an `expected_symbols.json`
+const EXPECTATION_DIRS: &[&str] = &["tests/fixtures/nix", "tests/fixtures/nix/nixpkgs"];
+
+/// What the snapshot records about each symbol
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct CorpusSymbol {
+    name: String,
+    kind: String,
+    /// 1-based line where the symbol's node starts
+    line: u32,
+}
+
+fn corpus_files() -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(CORPUS_DIR)
+        .expect("Failed to read corpus directory")
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .flat_map(|dir| fs::read_dir(dir.path()).expect("Failed to read corpus language dir"))
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| !path.to_string_lossy().ends_with(".symbols.json"))
+        .collect();
+    files.sort();
+    files
+}
+
+fn snapshot_path(source: &Path) -> PathBuf {
+    let mut name = source.file_name().unwrap().to_os_string();
+    name.push(".symbols.json");
+    source.with_file_name(name)
+}
+
+/// Partial expectations for one fixture in an `expected_symbols.json`
+#[derive(Debug, Deserialize)]
+struct Expectations {
+    #[serde(default)]
+    min_total_symbols: usize,
+    #[serde(default)]
+    expected_functions: usize,
+    #[serde(default)]
+    expected_variables: usize,
+    #[serde(default)]
+    expected_symbols: Vec<ExpectedSymbol>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExpectedSymbol {
+    name: String,
+    kind: String,
+    #[serde(default)]
+    has_signature: bool,
+}
+
+fn extract(source: &Path) -> Vec<CorpusSymbol> {
+    parse(source)
+        .into_iter()
+        .map(|symbol| CorpusSymbol {
+            name: symbol.name.to_string(),
+            kind: format!("{:?}", symbol.kind),
+            line: symbol.range.start_line + 1,
+        })
+        .collect()
+}
+
+/// Symbols of `source`, from the parser registered for its extension
+fn parse(source: &Path) -> Vec<Symbol> {
+    let extension = source.extension().and_then(|e| e.to_str()).unwrap_or("");
+    // Settings read the registry, so they are built before locking it
+    let settings = Settings::default();
+    let mut parser = {
+        let registry = get_registry().lock().unwrap();
+        let definition = registry
+            .get_by_extension(extension)
+            .unwrap_or_else(|| panic!("No parser for corpus file {}", source.display()));
+        definition
+            .create_parser(&settings)
+            .expect("Failed to create parser")
+    };
+
+    let code = fs::read_to_string(source).unwrap();
+    let file_id = FileId::new(1).unwrap();
+    parser.parse(&code, file_id, &mut SymbolCounter::new())
+}
+
+/// How the symbols of `source` fall short of `expected`
+fn unmet_expectations(source: &Path, expected: &Expectations) -> Vec<String> {
+    let symbols = parse(source);
+    let count = |kind: SymbolKind| symbols.iter().filter(|s| s.kind == kind).count();
+    let mut unmet = Vec::new();
+    if symbols.len() < expected.min_total_symbols {
+        unmet.push(format!(
+            "  at least {} symbols expected, found {}",
+            expected.min_total_symbols,
+            symbols.len()
+        ));
+    }
+    for (kind, wanted) in [
+        (SymbolKind::Function, expected.expected_functions),
+        (SymbolKind::Variable, expected.expected_variables),
+    ] {
+        if count(kind) < wanted {
+            unmet.push(format!(
+                "  at least {wanted} {kind:?} symbols expected, found {}",
+                count(kind)
+            ));
+        }
+    }
+    for wanted in &expected.expected_symbols {
+        match symbols.iter().find(|s| *s.name == *wanted.name) {
+            None => unmet.push(format!("  {} {} not found", wanted.kind, wanted.name)),
+            Some(symbol) if format!("{:?}", symbol.kind) != wanted.kind => unmet.push(format!(
+                "  {} is {:?}, expected {}",
+                wanted.name, symbol.kind, wanted.kind
+            )),
+            Some(symbol) if symbol.signature.is_some() != wanted.has_signature => {
+                unmet.push(format!(
+                    "  {} {} a signature",
+                    wanted.name,
+                    if wanted.has_signature { "lacks" } else { "has" }
+                ))
+            }
+            Some(_) => {}
+        }
+    }
+    unmet
+}
+
+/// Human-readable difference between the golden and the actual symbols
+fn describe_diff(expected: &[CorpusSymbol], actual: &[CorpusSymbol]) -> String {
+    let mut lines = Vec::new();
+    for symbol in expected.iter().filter(|s| !actual.contains(s)) {
+        lines.push(format!(
+            "  - {} {} (line {})",
+            symbol.kind, symbol.name, symbol.line
+        ));
+    }
+    for symbol in actual.iter().filter(|s| !expected.contains(s)) {
+        lines.push(format!(
+            "  + {} {} (line {})",
+            symbol.kind, symbol.name, symbol.line
+        ));
+    }
+    if lines.is_empty() {
+        lines.push("  (same symbols, different order)".to_string());
+    }
+    lines.join("\n")
+}
+
+#[test]
+fn test_corpus_matches_golden_snapshots() {
+    let update = std::env::var_os(UPDATE_ENV).is_some();
+    let files = corpus_files();
+    assert!(!files.is_empty(), "Corpus directory {CORPUS_DIR} is empty");
+
+    let mut failures = Vec::new();
+    for source in &files {
+        let actual = extract(source);
+        let golden = snapshot_path(source);
+
+        if update {
+            let json = serde_json::to_string_pretty(&actual).unwrap();
+            fs::write(&golden, json + "\n").unwrap();
+            continue;
+        }
+
+        let Ok(content) = fs::read_to_string(&golden) else {
+            failures.push(format!(
+                "{}: missing snapshot {}",
+                source.display(),
+                golden.display()
+            ));
+            continue;
+        };
+        let expected: Vec<CorpusSymbol> =
+            serde_json::from_str(&content).expect("Failed to parse golden snapshot");
+        if expected != actual {
+            failures.push(format!(
+                "{}:\n{}",
+                source.display(),
+                describe_diff(&expected, &actual)
+            ));
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "Corpus snapshots differ (rerun with {UPDATE_ENV}=1 to accept):\n{}",
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn test_corpus_covers_every_language() {
+    let extensions: Vec<String> = corpus_files()
+        .iter()
+        .filter_map(|path| path.extension()?.to_str().map(str::to_string))
+        .collect();
+    let registry = get_registry().lock().unwrap();
+    let missing: Vec<&str> = registry
+        .iter_all()
+        .filter(|definition| {
+            !definition
+                .extensions()
+                .iter()
+                .any(|extension| extensions.iter().any(|covered| covered == extension))
+        })
+        .map(|definition| definition.name())
+        .collect();
+
+    assert!(
+        missing.is_empty(),
+        "Languages without a corpus file in {CORPUS_DIR}: {missing:?}"
+    );
+}
+
+#[test]
+fn test_fixtures_meet_expected_symbols() {
+    let mut failures = Vec::new();
+    for dir in EXPECTATION_DIRS.iter().map(Path::new) {
+        let content = fs::read_to_string(dir.join("expected_symbols.json"))
+            .expect("Failed to read expected symbols file");
+        let expectations: HashMap<String, Expectations> =
+            serde_json::from_str(&content).expect("Failed to parse expected symbols JSON");
+
+        let mut sources: Vec<PathBuf> = fs::read_dir(dir)
+            .expect("Failed to read fixtures directory")
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file() && !path.ends_with("expected_symbols.json"))
+            .collect();
+        sources.sort();
+        assert!(!sources.is_empty(), "No fixtures in {}", dir.display());
+
+        for source in &sources {
+            let name = source.file_name().unwrap().to_string_lossy();
+            let Some(expected) = expectations.get(name.as_ref()) else {
+                failures.push(format!("{}: no expected symbols", source.display()));
+                continue;
+            };
+            let unmet = unmet_expectations(source, expected);
+            if !unmet.is_empty() {
+                failures.push(format!("{}:\n{}", source.display(), unmet.join("\n")));
+            }
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "Fixtures miss their expected symbols:\n{}",
+        failures.join("\n")
+    );
+}
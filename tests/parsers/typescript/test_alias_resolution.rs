@@ -203,6 +203,7 @@ fn test_typescript_behavior_add_import() {
         is_glob: false,
         is_type_only: false,
         file_id,
+        range: None,
     };
 
     // Add the import - this should enhance it using the rules we set up
@@ -223,6 +223,7 @@ fn test_pipeline_cache_import_resolution() {
         alias: None,
         is_glob: false,
         is_type_only: false,
+        range: None,
     }];
 
     // Resolve "Button" with import context
@@ -353,6 +354,7 @@ fn test_behavior_pipeline_cache_isolated() {
         alias: None,
         is_glob: false,
         is_type_only: false,
+        range: None,
     }];
 
     let extensions = &["ts", "tsx", "js", "jsx"];
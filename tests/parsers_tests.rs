@@ -25,6 +25,9 @@ mod test_javascript_nested_functions;
 #[path = "parsers/c/test_resolution.rs"]
 mod test_c_resolution;
 
+#[path = "parsers/cpp/test_doc_comments.rs"]
+mod test_cpp_doc_comments;
+
 #[path = "parsers/cpp/test_resolution.rs"]
 mod test_cpp_resolution;
 
@@ -114,3 +117,6 @@ mod test_lua_relationships;
 
 #[path = "parsers/swift/test_nested_types.rs"]
 mod test_swift_nested_types;
+
+#[path = "parsers/test_corpus.rs"]
+mod test_corpus;
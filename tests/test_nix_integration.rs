@@ -1,15 +1,14 @@
 //! Integration tests for Nix language support
 //!
 //! This test suite validates the complete Nix parsing pipeline using real
-//! Nix files and verifies symbol extraction accuracy and performance.
+//! Nix files and its performance. The fixtures' expected symbols are checked
+//! by the corpus test in `tests/parsers/test_corpus.rs`.
 
 use codanna::parsing::nix::{NixBehavior, NixLanguage, NixParser};
 use codanna::parsing::{LanguageBehavior, LanguageDefinition, LanguageParser};
 use codanna::types::SymbolCounter;
-use codanna::{FileId, Settings, SymbolKind};
-use serde_json::{self, Value};
+use codanna::{FileId, Settings};
 use std::fs;
-use std::path::Path;
 use std::time::Instant;
 
 /// Test fixture data structure
@@ -17,91 +16,23 @@ use std::time::Instant;
 struct TestFixture {
     name: String,
     content: String,
-    expected: ExpectedResults,
-}
-
-/// Expected results for symbol extraction validation
-#[derive(Debug)]
-struct ExpectedResults {
-    min_total_symbols: usize,
-    expected_functions: usize,
-    expected_variables: usize,
-    specific_symbols: Vec<ExpectedSymbol>,
-}
-
-#[derive(Debug)]
-struct ExpectedSymbol {
-    name: String,
-    kind: String,
-    has_signature: bool,
-    #[allow(dead_code)]
-    has_doc: bool,
 }
 
 /// Load test fixtures from files
 fn load_test_fixtures() -> Vec<TestFixture> {
-    let fixtures_dir = Path::new("tests/fixtures/nix");
-    let expected_file = fixtures_dir.join("expected_symbols.json");
-
-    // Load expected results
-    let expected_content =
-        fs::read_to_string(&expected_file).expect("Failed to read expected symbols file");
-    let expected: Value =
-        serde_json::from_str(&expected_content).expect("Failed to parse expected symbols JSON");
-
     let mut fixtures = Vec::new();
-
-    // Load each test file
-    for entry in fs::read_dir(fixtures_dir).expect("Failed to read fixtures directory") {
-        let entry = entry.expect("Failed to read directory entry");
-        let path = entry.path();
-
+    for entry in fs::read_dir("tests/fixtures/nix").expect("Failed to read fixtures directory") {
+        let path = entry.expect("Failed to read directory entry").path();
         if path.extension().and_then(|s| s.to_str()) == Some("nix") {
-            let filename = path.file_name().unwrap().to_str().unwrap();
+            let name = path.file_name().unwrap().to_string_lossy().into_owned();
             let content = fs::read_to_string(&path)
-                .unwrap_or_else(|_| panic!("Failed to read fixture file: {filename}"));
-
-            // Get expected results for this file
-            if let Some(file_expected) = expected.get(filename) {
-                let expected_results = parse_expected_results(file_expected);
-                fixtures.push(TestFixture {
-                    name: filename.to_string(),
-                    content,
-                    expected: expected_results,
-                });
-            }
+                .unwrap_or_else(|_| panic!("Failed to read fixture file: {name}"));
+            fixtures.push(TestFixture { name, content });
         }
     }
-
     fixtures
 }
 
-/// Parse expected results from JSON
-fn parse_expected_results(json: &Value) -> ExpectedResults {
-    let min_total_symbols = json["min_total_symbols"].as_u64().unwrap_or(0) as usize;
-    let expected_functions = json["expected_functions"].as_u64().unwrap_or(0) as usize;
-    let expected_variables = json["expected_variables"].as_u64().unwrap_or(0) as usize;
-
-    let mut specific_symbols = Vec::new();
-    if let Some(symbols_array) = json["expected_symbols"].as_array() {
-        for symbol in symbols_array {
-            specific_symbols.push(ExpectedSymbol {
-                name: symbol["name"].as_str().unwrap_or("").to_string(),
-                kind: symbol["kind"].as_str().unwrap_or("").to_string(),
-                has_signature: symbol["has_signature"].as_bool().unwrap_or(false),
-                has_doc: symbol["has_doc"].as_bool().unwrap_or(false),
-            });
-        }
-    }
-
-    ExpectedResults {
-        min_total_symbols,
-        expected_functions,
-        expected_variables,
-        specific_symbols,
-    }
-}
-
 /// Test basic parser creation and initialization
 #[test]
 fn test_nix_parser_initialization() {
@@ -126,110 +57,6 @@ fn test_nix_parser_initialization() {
     assert!(language.default_enabled());
 }
 
-/// Test symbol extraction with real Nix files
-#[test]
-fn test_symbol_extraction_accuracy() {
-    let fixtures = load_test_fixtures();
-    assert!(!fixtures.is_empty(), "No test fixtures found");
-
-    let mut parser = NixParser::new().expect("Failed to create parser");
-
-    for fixture in fixtures {
-        println!("Testing fixture: {}", fixture.name);
-
-        let mut counter = SymbolCounter::new();
-        let file_id = FileId(1);
-
-        let symbols = parser.parse(&fixture.content, file_id, &mut counter);
-
-        // Validate total symbol count
-        assert!(
-            symbols.len() >= fixture.expected.min_total_symbols,
-            "Fixture {}: Expected at least {} symbols, found {}",
-            fixture.name,
-            fixture.expected.min_total_symbols,
-            symbols.len()
-        );
-
-        // Count symbols by kind
-        let mut function_count = 0;
-        let mut variable_count = 0;
-
-        for symbol in &symbols {
-            match symbol.kind {
-                SymbolKind::Function => function_count += 1,
-                SymbolKind::Variable => variable_count += 1,
-                _ => {}
-            }
-        }
-
-        // Validate function and variable counts
-        assert!(
-            function_count >= fixture.expected.expected_functions,
-            "Fixture {}: Expected at least {} functions, found {}",
-            fixture.name,
-            fixture.expected.expected_functions,
-            function_count
-        );
-
-        assert!(
-            variable_count >= fixture.expected.expected_variables,
-            "Fixture {}: Expected at least {} variables, found {}",
-            fixture.name,
-            fixture.expected.expected_variables,
-            variable_count
-        );
-
-        // Validate specific expected symbols
-        for expected_symbol in &fixture.expected.specific_symbols {
-            let found_symbol = symbols
-                .iter()
-                .find(|s| s.name.as_ref() == expected_symbol.name);
-
-            assert!(
-                found_symbol.is_some(),
-                "Fixture {}: Expected symbol '{}' not found",
-                fixture.name,
-                expected_symbol.name
-            );
-
-            let symbol = found_symbol.unwrap();
-
-            // Validate symbol kind
-            let expected_kind = match expected_symbol.kind.as_str() {
-                "Function" => SymbolKind::Function,
-                "Variable" => SymbolKind::Variable,
-                "Constant" => SymbolKind::Constant,
-                _ => panic!("Unknown symbol kind: {}", expected_symbol.kind),
-            };
-
-            assert_eq!(
-                symbol.kind, expected_kind,
-                "Fixture {}: Symbol '{}' has wrong kind",
-                fixture.name, expected_symbol.name
-            );
-
-            // Validate signature presence
-            assert_eq!(
-                symbol.signature.is_some(),
-                expected_symbol.has_signature,
-                "Fixture {}: Symbol '{}' signature presence mismatch",
-                fixture.name,
-                expected_symbol.name
-            );
-
-            // Skip doc comment validation for now due to parser complexity
-            // Documentation extraction works but varies by symbol position
-            // assert_eq!(
-            //     symbol.doc_comment.is_some(), expected_symbol.has_doc,
-            //     "Fixture {}: Symbol '{}' documentation presence mismatch",
-            //     fixture.name,
-            //     expected_symbol.name
-            // );
-        }
-    }
-}
-
 /// Test parsing performance with large files
 #[test]
 fn test_parsing_performance() {